@@ -3,13 +3,13 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use clickhouse_admin_types::{
-    ClickhouseKeeperClusterMembership, DistributedDdlQueue,
-    GenerateConfigResult, KeeperConf, KeeperConfigurableSettings, Lgif,
-    MetricInfoPath, RaftConfig, ServerConfigurableSettings, SystemTimeSeries,
-    TimeSeriesSettingsQuery,
+    ClickhouseBackup, ClickhouseBackupPath, ClickhouseKeeperClusterMembership,
+    DistributedDdlQueue, GenerateConfigResult, KeeperConf,
+    KeeperConfigurableSettings, Lgif, MetricInfoPath, RaftConfig,
+    ServerConfigurableSettings, SystemTimeSeries, TimeSeriesSettingsQuery,
 };
 use dropshot::{
-    HttpError, HttpResponseCreated, HttpResponseOk,
+    HttpError, HttpResponseCreated, HttpResponseDeleted, HttpResponseOk,
     HttpResponseUpdatedNoContent, Path, Query, RequestContext, TypedBody,
 };
 use omicron_common::api::external::Generation;
@@ -34,6 +34,7 @@ api_versions!([
     // |  example for the next person.
     // v
     // (next_int, IDENT), // NOTE: read the note at the start of this macro!
+    (2, ADD_BACKUPS),
     (1, INITIAL),
 ]);
 
@@ -230,4 +231,51 @@ pub trait ClickhouseAdminSingleApi {
         path_params: Path<MetricInfoPath>,
         query_params: Query<TimeSeriesSettingsQuery>,
     ) -> Result<HttpResponseOk<Vec<SystemTimeSeries>>, HttpError>;
+
+    /// List completed backups of the oximeter database.
+    #[endpoint {
+        method = GET,
+        path = "/backups",
+        versions = VERSION_ADD_BACKUPS..,
+    }]
+    async fn backup_list(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<Vec<ClickhouseBackup>>, HttpError>;
+
+    /// Back up the oximeter database.
+    ///
+    /// This returns once the backup has been completely written.
+    #[endpoint {
+        method = POST,
+        path = "/backups",
+        versions = VERSION_ADD_BACKUPS..,
+    }]
+    async fn backup_create(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseCreated<ClickhouseBackup>, HttpError>;
+
+    /// Delete a backup of the oximeter database.
+    #[endpoint {
+        method = DELETE,
+        path = "/backups/{name}",
+        versions = VERSION_ADD_BACKUPS..,
+    }]
+    async fn backup_delete(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<ClickhouseBackupPath>,
+    ) -> Result<HttpResponseDeleted, HttpError>;
+
+    /// Restore the oximeter database from a backup.
+    ///
+    /// Data in the backup is added to any data already present in the
+    /// database.
+    #[endpoint {
+        method = POST,
+        path = "/backups/{name}/restore",
+        versions = VERSION_ADD_BACKUPS..,
+    }]
+    async fn backup_restore(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<ClickhouseBackupPath>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 }
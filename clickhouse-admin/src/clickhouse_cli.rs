@@ -3,7 +3,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use anyhow::Result;
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use clickhouse_admin_types::{
     ClickhouseKeeperClusterMembership, DistributedDdlQueue, KeeperConf,
     KeeperId, Lgif, OXIMETER_CLUSTER, RaftConfig, SystemTimeSeries,
@@ -23,6 +23,13 @@ use tokio::process::Command;
 
 const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
 
+// Backing up or restoring the whole oximeter database can take far longer
+// than any of our other queries.
+const BACKUP_COMMAND_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+// Name of the database backed up and restored by `ClickhouseCli`
+const OXIMETER_DATABASE: &str = "oximeter";
+
 #[derive(Debug, thiserror::Error, SlogInlineError)]
 pub enum ClickhouseCliError {
     #[error("failed to run `clickhouse {subcommand}`")]
@@ -189,6 +196,51 @@ impl ClickhouseCli {
         .await
     }
 
+    /// Back up the oximeter database into the directory `destination`,
+    /// which must not already exist.
+    ///
+    /// `destination` must be within one of the server's configured
+    /// `backups.allowed_path` directories.
+    pub async fn backup_database(
+        &self,
+        destination: &Utf8Path,
+    ) -> Result<(), ClickhouseCliError> {
+        self.client_non_interactive_with_timeout(
+            ClickhouseClientType::Server,
+            &format!(
+                "BACKUP DATABASE {OXIMETER_DATABASE} TO File('{destination}/')"
+            ),
+            "Back up the oximeter database",
+            |_, _| Ok(()),
+            self.log.clone(),
+            BACKUP_COMMAND_TIMEOUT,
+        )
+        .await
+    }
+
+    /// Restore the oximeter database from a backup written to `source` by
+    /// [`ClickhouseCli::backup_database`].
+    ///
+    /// Rows in the backup are added to any tables that already exist, so
+    /// that a backup can be restored into a freshly-initialized database.
+    pub async fn restore_database(
+        &self,
+        source: &Utf8Path,
+    ) -> Result<(), ClickhouseCliError> {
+        self.client_non_interactive_with_timeout(
+            ClickhouseClientType::Server,
+            &format!(
+                "RESTORE DATABASE {OXIMETER_DATABASE} FROM File('{source}/') \
+                SETTINGS allow_non_empty_tables = true"
+            ),
+            "Restore the oximeter database from a backup",
+            |_, _| Ok(()),
+            self.log.clone(),
+            BACKUP_COMMAND_TIMEOUT,
+        )
+        .await
+    }
+
     async fn client_non_interactive<F, T>(
         &self,
         client: ClickhouseClientType,
@@ -197,6 +249,29 @@ impl ClickhouseCli {
         parse: F,
         log: Logger,
     ) -> Result<T, ClickhouseCliError>
+    where
+        F: FnOnce(&Logger, &[u8]) -> Result<T>,
+    {
+        self.client_non_interactive_with_timeout(
+            client,
+            query,
+            subcommand_description,
+            parse,
+            log,
+            DEFAULT_COMMAND_TIMEOUT,
+        )
+        .await
+    }
+
+    async fn client_non_interactive_with_timeout<F, T>(
+        &self,
+        client: ClickhouseClientType,
+        query: &str,
+        subcommand_description: &'static str,
+        parse: F,
+        log: Logger,
+        timeout: Duration,
+    ) -> Result<T, ClickhouseCliError>
     where
         F: FnOnce(&Logger, &[u8]) -> Result<T>,
     {
@@ -211,9 +286,7 @@ impl ClickhouseCli {
             .arg(query);
 
         let now = tokio::time::Instant::now();
-        let result =
-            tokio::time::timeout(DEFAULT_COMMAND_TIMEOUT, command.output())
-                .await;
+        let result = tokio::time::timeout(timeout, command.output()).await;
 
         let elapsed = now.elapsed();
         let output = match result {
@@ -5,12 +5,13 @@
 use crate::{ClickhouseCli, Clickward};
 
 use anyhow::{Context, Result, anyhow, bail};
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
 use clickhouse_admin_types::{
-    CLICKHOUSE_KEEPER_CONFIG_DIR, CLICKHOUSE_KEEPER_CONFIG_FILE,
-    CLICKHOUSE_SERVER_CONFIG_DIR, CLICKHOUSE_SERVER_CONFIG_FILE,
-    GenerateConfigResult, KeeperConfigurableSettings,
-    ServerConfigurableSettings,
+    CLICKHOUSE_BACKUP_DIR, CLICKHOUSE_KEEPER_CONFIG_DIR,
+    CLICKHOUSE_KEEPER_CONFIG_FILE, CLICKHOUSE_SERVER_CONFIG_DIR,
+    CLICKHOUSE_SERVER_CONFIG_FILE, ClickhouseBackup, GenerateConfigResult,
+    KeeperConfigurableSettings, ServerConfigurableSettings,
 };
 use dropshot::{ClientErrorStatusCode, HttpError};
 use flume::{Receiver, Sender, TrySendError};
@@ -25,7 +26,7 @@ use slog_error_chain::InlineErrorChain;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::net::SocketAddrV6;
-use tokio::sync::{oneshot, watch};
+use tokio::sync::{Mutex, oneshot, watch};
 
 pub struct KeeperServerContext {
     clickward: Clickward,
@@ -115,6 +116,10 @@ pub struct ServerContext {
     generate_config_tx: Sender<GenerateConfigRequest>,
     generation_rx: watch::Receiver<Option<Generation>>,
     db_init_tx: Sender<DbInitRequest>,
+    backup_dir: Utf8PathBuf,
+    // Serializes backup and restore operations, which ClickHouse would
+    // otherwise happily run concurrently.
+    backup_lock: Mutex<()>,
 }
 
 impl ServerContext {
@@ -159,6 +164,8 @@ impl ServerContext {
             generate_config_tx,
             generation_rx,
             db_init_tx,
+            backup_dir: Utf8PathBuf::from(CLICKHOUSE_BACKUP_DIR),
+            backup_lock: Mutex::new(()),
         })
     }
 
@@ -210,6 +217,95 @@ impl ServerContext {
         })?
     }
 
+    /// List all completed backups of the oximeter database, oldest first.
+    pub async fn backup_list(
+        &self,
+    ) -> Result<Vec<ClickhouseBackup>, HttpError> {
+        let backup_dir = self.backup_dir.clone();
+        tokio::task::spawn_blocking(move || list_backups(&backup_dir))
+            .await
+            .map_err(|e| {
+                HttpError::for_internal_error(format!(
+                    "backup listing task panicked: {e}"
+                ))
+            })?
+            .map_err(|e| {
+                HttpError::for_internal_error(format!(
+                    "failed to list backups: {e:#}"
+                ))
+            })
+    }
+
+    /// Back up the oximeter database into a new directory within the backup
+    /// directory, returning the completed backup.
+    pub async fn backup_create(&self) -> Result<ClickhouseBackup, HttpError> {
+        let _guard = self.backup_lock.lock().await;
+        let name = format!("oximeter-{}", Utc::now().format("%Y%m%dT%H%M%SZ"));
+        let path = self.backup_dir.join(&name);
+        if path.exists() {
+            return Err(HttpError::for_client_error(
+                Some(String::from("ObjectAlreadyExists")),
+                ClientErrorStatusCode::CONFLICT,
+                format!("backup {name} already exists"),
+            ));
+        }
+        std::fs::create_dir_all(&self.backup_dir).map_err(|e| {
+            HttpError::for_internal_error(format!(
+                "failed to create backup directory {}: {e}",
+                self.backup_dir,
+            ))
+        })?;
+
+        info!(self.log, "backing up oximeter database"; "path" => %path);
+        self.clickhouse_cli.backup_database(&path).await?;
+
+        read_backup(&path, name)
+            .and_then(|backup| {
+                backup.ok_or_else(|| anyhow!("backup is incomplete"))
+            })
+            .map_err(|e| {
+                HttpError::for_internal_error(format!(
+                    "failed to read completed backup at {path}: {e:#}"
+                ))
+            })
+    }
+
+    /// Delete the named backup.
+    pub async fn backup_delete(&self, name: &str) -> Result<(), HttpError> {
+        let _guard = self.backup_lock.lock().await;
+        let path = self.backup_path(name)?;
+        info!(self.log, "deleting backup"; "path" => %path);
+        tokio::fs::remove_dir_all(&path).await.map_err(|e| {
+            HttpError::for_internal_error(format!(
+                "failed to delete backup at {path}: {e}"
+            ))
+        })
+    }
+
+    /// Restore the oximeter database from the named backup.
+    pub async fn backup_restore(&self, name: &str) -> Result<(), HttpError> {
+        let _guard = self.backup_lock.lock().await;
+        let path = self.backup_path(name)?;
+        info!(self.log, "restoring oximeter database"; "path" => %path);
+        self.clickhouse_cli.restore_database(&path).await?;
+        Ok(())
+    }
+
+    // Returns the path to the named backup, provided the name is valid and
+    // the backup exists.
+    fn backup_path(&self, name: &str) -> Result<Utf8PathBuf, HttpError> {
+        ClickhouseBackup::validate_name(name)
+            .map_err(|e| HttpError::for_bad_request(None, format!("{e:#}")))?;
+        let path = self.backup_dir.join(name);
+        if !path.is_dir() {
+            return Err(HttpError::for_not_found(
+                None,
+                format!("no backup named {name}"),
+            ));
+        }
+        Ok(path)
+    }
+
     pub fn clickhouse_cli(&self) -> &ClickhouseCli {
         &self.clickhouse_cli
     }
@@ -443,6 +539,78 @@ async fn init_db(
     Ok(())
 }
 
+// ClickHouse writes this file into a backup's directory once the backup has
+// been completely written.
+const BACKUP_METADATA_FILE: &str = ".backup";
+
+// Returns all completed backups within `backup_dir`, oldest first.
+fn list_backups(backup_dir: &Utf8Path) -> Result<Vec<ClickhouseBackup>> {
+    if !backup_dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut backups = Vec::new();
+    for entry in backup_dir
+        .read_dir_utf8()
+        .with_context(|| format!("failed to read {backup_dir}"))?
+    {
+        let entry =
+            entry.with_context(|| format!("failed to read {backup_dir}"))?;
+        let name = entry.file_name();
+        if ClickhouseBackup::validate_name(name).is_err() {
+            continue;
+        }
+        if let Some(backup) = read_backup(entry.path(), name.to_string())? {
+            backups.push(backup);
+        }
+    }
+    backups.sort_by(|a, b| {
+        (a.time_created, &a.name).cmp(&(b.time_created, &b.name))
+    });
+    Ok(backups)
+}
+
+// Describes the backup in directory `path`, or returns `None` if it hasn't
+// been completely written.
+fn read_backup(
+    path: &Utf8Path,
+    name: String,
+) -> Result<Option<ClickhouseBackup>> {
+    let metadata = match path.join(BACKUP_METADATA_FILE).metadata() {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e).with_context(|| {
+                format!("failed to read metadata of backup at {path}")
+            });
+        }
+    };
+    let time_created =
+        DateTime::<Utc>::from(metadata.modified().with_context(|| {
+            format!("no modified time for backup at {path}")
+        })?);
+    let size_bytes = dir_size(path)?;
+    Ok(Some(ClickhouseBackup { name, time_created, size_bytes }))
+}
+
+// Returns the total size of all files within `dir`, recursively.
+fn dir_size(dir: &Utf8Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in
+        dir.read_dir_utf8().with_context(|| format!("failed to read {dir}"))?
+    {
+        let entry = entry.with_context(|| format!("failed to read {dir}"))?;
+        let metadata = entry
+            .metadata()
+            .with_context(|| format!("failed to stat {}", entry.path()))?;
+        if metadata.is_dir() {
+            size += dir_size(entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
 fn read_generation_from_file(path: Utf8PathBuf) -> Result<Option<Generation>> {
     // When the configuration file does not exist yet, this means it's a new server.
     // It won't have a running clickhouse server and no generation number yet.
@@ -506,8 +674,11 @@ fn read_generation_from_file(path: Utf8PathBuf) -> Result<Option<Generation>> {
 
 #[cfg(test)]
 mod tests {
-    use super::read_generation_from_file;
+    use super::{
+        BACKUP_METADATA_FILE, list_backups, read_generation_from_file,
+    };
     use camino::Utf8PathBuf;
+    use camino_tempfile::Utf8TempDir;
     use clickhouse_admin_types::CLICKHOUSE_SERVER_CONFIG_FILE;
     use omicron_common::api::external::Generation;
     use std::str::FromStr;
@@ -576,4 +747,36 @@ mod tests {
             "first line of configuration file 'types/testutils/malformed_3.xml' is malformed: <!-- generation:2 --> -->"
         );
     }
+
+    #[test]
+    fn test_list_backups() {
+        let tempdir = Utf8TempDir::new().unwrap();
+        let backup_dir = tempdir.path().join("backups");
+
+        // A missing backup directory just means there are no backups yet.
+        assert!(list_backups(&backup_dir).unwrap().is_empty());
+
+        // Only complete backups with valid names are listed.
+        for (name, complete) in [
+            ("oximeter-20250101T000000Z", true),
+            ("oximeter-20250102T000000Z", false),
+            ("not a backup", true),
+        ] {
+            let dir = backup_dir.join(name).join("data");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("part"), [0u8; 100]).unwrap();
+            if complete {
+                std::fs::write(
+                    backup_dir.join(name).join(BACKUP_METADATA_FILE),
+                    [0u8; 10],
+                )
+                .unwrap();
+            }
+        }
+
+        let backups = list_backups(&backup_dir).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].name, "oximeter-20250101T000000Z");
+        assert_eq!(backups[0].size_bytes, 110);
+    }
 }
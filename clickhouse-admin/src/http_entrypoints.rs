@@ -5,15 +5,16 @@
 use crate::context::{KeeperServerContext, ServerContext};
 use clickhouse_admin_api::*;
 use clickhouse_admin_types::{
-    ClickhouseKeeperClusterMembership, DistributedDdlQueue,
-    GenerateConfigResult, KeeperConf, KeeperConfigurableSettings, Lgif,
-    MetricInfoPath, RaftConfig, ServerConfigurableSettings, SystemTimeSeries,
-    SystemTimeSeriesSettings, TimeSeriesSettingsQuery,
+    ClickhouseBackup, ClickhouseBackupPath, ClickhouseKeeperClusterMembership,
+    DistributedDdlQueue, GenerateConfigResult, KeeperConf,
+    KeeperConfigurableSettings, Lgif, MetricInfoPath, RaftConfig,
+    ServerConfigurableSettings, SystemTimeSeries, SystemTimeSeriesSettings,
+    TimeSeriesSettingsQuery,
 };
 use dropshot::{
     ApiDescription, ClientErrorStatusCode, HttpError, HttpResponseCreated,
-    HttpResponseOk, HttpResponseUpdatedNoContent, Path, Query, RequestContext,
-    TypedBody,
+    HttpResponseDeleted, HttpResponseOk, HttpResponseUpdatedNoContent, Path,
+    Query, RequestContext, TypedBody,
 };
 use omicron_common::api::external::Generation;
 use std::sync::Arc;
@@ -195,4 +196,40 @@ impl ClickhouseAdminSingleApi for ClickhouseAdminSingleImpl {
             ctx.clickhouse_cli().system_timeseries_avg(settings).await?;
         Ok(HttpResponseOk(output))
     }
+
+    async fn backup_list(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<Vec<ClickhouseBackup>>, HttpError> {
+        let ctx = rqctx.context();
+        let backups = ctx.backup_list().await?;
+        Ok(HttpResponseOk(backups))
+    }
+
+    async fn backup_create(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseCreated<ClickhouseBackup>, HttpError> {
+        let ctx = rqctx.context();
+        let backup = ctx.backup_create().await?;
+        Ok(HttpResponseCreated(backup))
+    }
+
+    async fn backup_delete(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<ClickhouseBackupPath>,
+    ) -> Result<HttpResponseDeleted, HttpError> {
+        let ctx = rqctx.context();
+        let ClickhouseBackupPath { name } = path_params.into_inner();
+        ctx.backup_delete(&name).await?;
+        Ok(HttpResponseDeleted())
+    }
+
+    async fn backup_restore(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<ClickhouseBackupPath>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let ctx = rqctx.context();
+        let ClickhouseBackupPath { name } = path_params.into_inner();
+        ctx.backup_restore(&name).await?;
+        Ok(HttpResponseUpdatedNoContent())
+    }
 }
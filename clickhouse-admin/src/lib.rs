@@ -71,7 +71,7 @@ pub async fn start_server_admin_server(
     .version_policy(dropshot::VersionPolicy::Dynamic(Box::new(
         dropshot::ClientSpecifiesVersionInHeader::new(
             omicron_common::api::VERSION_HEADER,
-            clickhouse_admin_api::VERSION_INITIAL,
+            clickhouse_admin_api::VERSION_ADD_BACKUPS,
         ),
     )))
     .start()
@@ -114,7 +114,7 @@ pub async fn start_keeper_admin_server(
     .version_policy(dropshot::VersionPolicy::Dynamic(Box::new(
         dropshot::ClientSpecifiesVersionInHeader::new(
             omicron_common::api::VERSION_HEADER,
-            clickhouse_admin_api::VERSION_INITIAL,
+            clickhouse_admin_api::VERSION_ADD_BACKUPS,
         ),
     )))
     .start()
@@ -157,7 +157,7 @@ pub async fn start_single_admin_server(
     .version_policy(dropshot::VersionPolicy::Dynamic(Box::new(
         dropshot::ClientSpecifiesVersionInHeader::new(
             omicron_common::api::VERSION_HEADER,
-            clickhouse_admin_api::VERSION_INITIAL,
+            clickhouse_admin_api::VERSION_ADD_BACKUPS,
         ),
     )))
     .start()
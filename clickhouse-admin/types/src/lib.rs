@@ -39,6 +39,10 @@ pub const CLICKHOUSE_KEEPER_CONFIG_DIR: &str = "/opt/oxide/clickhouse_keeper";
 pub const CLICKHOUSE_KEEPER_CONFIG_FILE: &str = "keeper_config.xml";
 pub const OXIMETER_CLUSTER: &str = "oximeter_cluster";
 
+/// Directory within a single-node ClickHouse zone's durable dataset into which
+/// backups of the oximeter database are written
+pub const CLICKHOUSE_BACKUP_DIR: &str = "/data/backups";
+
 // Used for schemars to be able to be used with camino:
 // See https://github.com/camino-rs/camino/issues/91#issuecomment-2027908513
 pub fn path_schema(gen: &mut SchemaGenerator) -> Schema {
@@ -1298,6 +1302,54 @@ impl SystemTimeSeries {
     }
 }
 
+/// A backup of the oximeter database taken by a single-node ClickHouse server
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct ClickhouseBackup {
+    /// Name of the backup, unique within the server
+    pub name: String,
+    /// Time at which the backup finished being written
+    pub time_created: DateTime<Utc>,
+    /// Space used by the backup on disk, in bytes
+    pub size_bytes: u64,
+}
+
+impl ClickhouseBackup {
+    /// Returns an error if `name` is not acceptable as the name of a backup.
+    ///
+    /// Backup names are used both as a directory name and within SQL
+    /// statements, so we only accept a conservative set of characters.
+    pub fn validate_name(name: &str) -> Result<()> {
+        if name.is_empty() {
+            bail!("backup name must not be empty");
+        }
+        if let Some(c) = name
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+        {
+            bail!("backup name {name:?} contains invalid character {c:?}");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ClickhouseBackupPath {
+    /// Name of the backup
+    pub name: String,
+}
+
 #[cfg(test)]
 mod tests {
     use camino::Utf8PathBuf;
@@ -1311,7 +1363,7 @@ mod tests {
     use std::str::FromStr;
 
     use crate::{
-        ClickhouseHost, DistributedDdlQueue, KeeperConf,
+        ClickhouseBackup, ClickhouseHost, DistributedDdlQueue, KeeperConf,
         KeeperConfigurableSettings, KeeperId, KeeperServerInfo,
         KeeperServerType, KeeperSettings, Lgif, LogLevel, RaftConfig,
         RaftServerSettings, ServerConfigurableSettings, ServerId,
@@ -2253,4 +2305,18 @@ snapshot_storage_disk=LocalSnapshotDisk
             "invalid type: integer `2024`, expected a string at line 1 column 12",
         );
     }
+
+    #[test]
+    fn test_clickhouse_backup_name_validation() {
+        for name in ["oximeter-20250101T000000Z", "my_backup", "0"] {
+            ClickhouseBackup::validate_name(name)
+                .unwrap_or_else(|e| panic!("{name:?} should be valid: {e}"));
+        }
+        for name in ["", "../etc", "a b", "x'); DROP DATABASE oximeter; --"] {
+            assert!(
+                ClickhouseBackup::validate_name(name).is_err(),
+                "{name:?} should be invalid"
+            );
+        }
+    }
 }
@@ -59,8 +59,18 @@ progenitor::generate_api!(
         Hostname = omicron_common::api::external::Hostname,
         ImportExportPolicy = omicron_common::api::external::ImportExportPolicy,
         Inventory = nexus_sled_agent_shared::inventory::Inventory,
+        InventoryChanges = nexus_sled_agent_shared::inventory::InventoryChanges,
+        InventoryDigests = nexus_sled_agent_shared::inventory::InventoryDigests,
         InventoryDisk = nexus_sled_agent_shared::inventory::InventoryDisk,
+        InventorySectionDigest = nexus_sled_agent_shared::inventory::InventorySectionDigest,
+        InventorySledConfig = nexus_sled_agent_shared::inventory::InventorySledConfig,
         InventoryZpool = nexus_sled_agent_shared::inventory::InventoryZpool,
+        InventoryZpoolHealth = nexus_sled_agent_shared::inventory::InventoryZpoolHealth,
+        InventoryZpoolHealthState = nexus_sled_agent_shared::inventory::InventoryZpoolHealthState,
+        InventoryZpoolResilver = nexus_sled_agent_shared::inventory::InventoryZpoolResilver,
+        InventoryZpoolResilverState = nexus_sled_agent_shared::inventory::InventoryZpoolResilverState,
+        InventoryZpoolScrub = nexus_sled_agent_shared::inventory::InventoryZpoolScrub,
+        InventoryZpoolScrubState = nexus_sled_agent_shared::inventory::InventoryZpoolScrubState,
         MacAddr = omicron_common::api::external::MacAddr,
         MupdateOverrideBootInventory = nexus_sled_agent_shared::inventory::MupdateOverrideBootInventory,
         Name = omicron_common::api::external::Name,
@@ -9,6 +9,7 @@
 
 use crate::api::external::{self, Error};
 use crate::policy::INTERNAL_DNS_REDUNDANCY;
+use crate::policy::RESERVED_INTERNAL_DNS_REDUNDANCY;
 use ipnetwork::Ipv6Network;
 use oxnet::{Ipv4Net, Ipv6Net};
 use schemars::JsonSchema;
@@ -320,6 +321,17 @@ impl ReservedRackSubnet {
             .map(|idx| self.get_dns_subnet(u8::try_from(idx + 1).unwrap()))
             .collect()
     }
+
+    /// Returns every DNS subnet an internal DNS server may be placed in.
+    ///
+    /// These are the first [`RESERVED_INTERNAL_DNS_REDUNDANCY`] `/64s` of the
+    /// [`RACK_PREFIX`] subnet, and always begin with the subnets returned by
+    /// [`ReservedRackSubnet::get_dns_subnets`].
+    pub fn get_reserved_dns_subnets(&self) -> Vec<DnsSubnet> {
+        (0..RESERVED_INTERNAL_DNS_REDUNDANCY)
+            .map(|idx| self.get_dns_subnet(u8::try_from(idx + 1).unwrap()))
+            .collect()
+    }
 }
 
 /// Return the list of DNS servers for the rack, given any address in the AZ
@@ -781,6 +793,29 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_reserved_dns_subnets() {
+        let subnet = Ipv6Subnet::<AZ_PREFIX>::new(
+            "fd00:1122:3344:0100::".parse::<Ipv6Addr>().unwrap(),
+        );
+        let rack_subnet = ReservedRackSubnet::new(subnet);
+
+        // The reserved subnets begin with the ones clients bootstrap from.
+        let dns_subnets = rack_subnet.get_dns_subnets();
+        let reserved = rack_subnet.get_reserved_dns_subnets();
+        assert_eq!(RESERVED_INTERNAL_DNS_REDUNDANCY, reserved.len());
+        assert_eq!(dns_subnets, reserved[..INTERNAL_DNS_REDUNDANCY]);
+
+        // All of them are within the reserved rack subnet.
+        for dns_subnet in &reserved {
+            assert_eq!(rack_subnet, dns_subnet.rack_subnet());
+        }
+        assert_eq!(
+            "fd00:1122:3344:0010::1".parse::<Ipv6Addr>().unwrap(),
+            reserved.last().unwrap().dns_address(),
+        );
+    }
+
     #[test]
     fn test_sled_address() {
         let subnet = Ipv6Subnet::<SLED_PREFIX>::new(
@@ -43,6 +43,11 @@ pub enum Error {
     /// An object already exists with the specified name or identifier.
     #[error("Object (of type {type_name:?}) already exists: {object_name}")]
     ObjectAlreadyExists { type_name: ResourceType, object_name: String },
+    /// An object cannot be deleted because it is protected from deletion.
+    #[error(
+        "Object (of type {type_name:?}) is delete-protected: {object_name}"
+    )]
+    DeleteProtected { type_name: ResourceType, object_name: String },
     /// The request was well-formed, but the operation cannot be completed given
     /// the current state of the system.
     #[error("Invalid Request: {}", .message.display_internal())]
@@ -75,6 +80,16 @@ pub enum Error {
     #[error("Insufficient Capacity: {}", .message.display_internal())]
     InsufficientCapacity { message: MessagePair },
 
+    /// The requested operation would exceed a limit configured by an
+    /// administrator (e.g., a project quota).
+    ///
+    /// Unlike `InsufficientCapacity`, this does not reflect a lack of
+    /// resources in the system: the operation may succeed if the limit is
+    /// raised.  Like `InsufficientCapacity`, it carries both an external and
+    /// an internal message.
+    #[error("Insufficient Quota: {}", .message.display_internal())]
+    InsufficientQuota { message: MessagePair },
+
     #[error("Type version mismatch! {internal_message}")]
     TypeVersionMismatch { internal_message: String },
 
@@ -211,11 +226,13 @@ impl Error {
 
             Error::ObjectNotFound { .. }
             | Error::ObjectAlreadyExists { .. }
+            | Error::DeleteProtected { .. }
             | Error::Unauthenticated { .. }
             | Error::InvalidRequest { .. }
             | Error::InvalidValue { .. }
             | Error::Forbidden
             | Error::InsufficientCapacity { .. }
+            | Error::InsufficientQuota { .. }
             | Error::InternalError { .. }
             | Error::TypeVersionMismatch { .. }
             | Error::NotFound { .. }
@@ -297,6 +314,24 @@ impl Error {
         }
     }
 
+    /// Generates an [`Error::InsufficientQuota`] error with external and
+    /// internal messages.
+    ///
+    /// This should be used for failures where an administrator-configured
+    /// limit would be exceeded, and where the caller must either free up
+    /// resources or ask for the limit to be raised.
+    pub fn insufficient_quota(
+        external_message: impl Into<String>,
+        internal_message: impl Into<String>,
+    ) -> Error {
+        Error::InsufficientQuota {
+            message: MessagePair::new_full(
+                external_message.into(),
+                internal_message.into(),
+            ),
+        }
+    }
+
     /// Generates an [`Error::TypeVersionMismatch`] with a specific message.
     ///
     /// TypeVersionMismatch errors are a specific type of error arising from differences
@@ -321,6 +356,15 @@ impl Error {
         Error::Conflict { message: MessagePair::new(message.into()) }
     }
 
+    /// Generates an [`Error::DeleteProtected`] error for an object that
+    /// cannot be deleted until its delete protection is cleared.
+    pub fn delete_protected(
+        type_name: ResourceType,
+        object_name: impl Into<String>,
+    ) -> Error {
+        Error::DeleteProtected { type_name, object_name: object_name.into() }
+    }
+
     /// Generates an [`Error::NotFound`] with a specific message.
     ///
     /// This is used in cases where a generic 404 is required. For cases where
@@ -341,6 +385,7 @@ impl Error {
         match self {
             Error::ObjectNotFound { .. }
             | Error::ObjectAlreadyExists { .. }
+            | Error::DeleteProtected { .. }
             | Error::Forbidden
             | Error::Gone => self,
             Error::InvalidRequest { message } => Error::InvalidRequest {
@@ -374,6 +419,9 @@ impl Error {
                     message: message.with_internal_context(context),
                 }
             }
+            Error::InsufficientQuota { message } => Error::InsufficientQuota {
+                message: message.with_internal_context(context),
+            },
             Error::TypeVersionMismatch { internal_message } => {
                 Error::TypeVersionMismatch {
                     internal_message: format!(
@@ -426,6 +474,19 @@ impl From<Error> for HttpError {
                 )
             }
 
+            Error::DeleteProtected { type_name: t, object_name: n } => {
+                let message = format!(
+                    "{} \"{}\" is delete-protected: clear its delete \
+                    protection before deleting it",
+                    t, n
+                );
+                HttpError::for_client_error(
+                    Some(String::from("DeleteProtected")),
+                    dropshot::ClientErrorStatusCode::CONFLICT,
+                    message,
+                )
+            }
+
             Error::Unauthenticated { internal_message } => HttpError {
                 status_code: dropshot::ErrorStatusCode::UNAUTHORIZED,
                 // TODO-polish We may want to rethink this error code.  This is
@@ -500,6 +561,25 @@ impl From<Error> for HttpError {
                 }
             }
 
+            Error::InsufficientQuota { message } => {
+                let (internal_message, external_message) =
+                    message.into_internal_external();
+                // This is reported the same way as `InsufficientCapacity` (so
+                // that clients which already handle silo quotas being exceeded
+                // need no changes), but with a distinct error code.
+                HttpError {
+                    status_code:
+                        dropshot::ErrorStatusCode::INSUFFICIENT_STORAGE,
+                    error_code: Some(String::from("InsufficientQuota")),
+                    external_message: format!(
+                        "Insufficient quota: {}",
+                        external_message
+                    ),
+                    internal_message,
+                    headers: None,
+                }
+            }
+
             Error::TypeVersionMismatch { internal_message } => {
                 HttpError::for_internal_error(internal_message)
             }
@@ -40,6 +40,7 @@
 //! there's a fair bit of boilerplate for each way of paginating (rather than for
 //! each resource paginated that way).  Where possible, we should share code.
 
+use crate::api::external::ByteCount;
 use crate::api::external::DataPageParams;
 use crate::api::external::Name;
 use crate::api::external::NameOrId;
@@ -476,9 +477,197 @@ impl<T: Clone + Debug + DeserializeOwned + JsonSchema + PartialEq + Serialize>
     }
 }
 
+// Pagination of images, which can also be sorted by creation time or size
+
+/// Query parameters for listing images
+pub type PaginatedImages<Selector = ()> =
+    PaginationParams<ScanImages<Selector>, PageSelectorImages<Selector>>;
+/// Page selector for listing images
+pub type PageSelectorImages<Selector = ()> =
+    PageSelector<ScanImages<Selector>, ImageMarker>;
+
+/// Scan parameters for listing images
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+pub struct ScanImages<Selector = ()> {
+    #[serde(default = "default_image_sort_mode")]
+    sort_by: ImageSortMode,
+
+    #[serde(flatten)]
+    pub selector: Selector,
+}
+
+/// Supported set of sort modes for listing images
+#[derive(Copy, Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSortMode {
+    /// sort in increasing order of "name"
+    #[serde(alias = "name")]
+    NameAscending,
+    /// sort in decreasing order of "name"
+    NameDescending,
+    /// sort in increasing order of "id"
+    IdAscending,
+    /// sort in decreasing order of creation time, i.e., most recent first
+    Created,
+    /// sort in increasing order of size
+    Size,
+}
+
+fn default_image_sort_mode() -> ImageSortMode {
+    ImageSortMode::NameAscending
+}
+
+/// The position of the last image seen while listing images
+///
+/// Only names and IDs are unique, so the other sort modes also record the ID
+/// of the last image seen to break ties.
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageMarker {
+    Name(Name),
+    Id(Uuid),
+    Created((DateTime<Utc>, Uuid)),
+    Size((ByteCount, Uuid)),
+}
+
+impl<T: Clone + Debug + DeserializeOwned + JsonSchema + PartialEq + Serialize>
+    ScanParams for ScanImages<T>
+{
+    type MarkerValue = ImageMarker;
+
+    fn direction(&self) -> PaginationOrder {
+        match self.sort_by {
+            ImageSortMode::NameAscending
+            | ImageSortMode::IdAscending
+            | ImageSortMode::Size => PaginationOrder::Ascending,
+            ImageSortMode::NameDescending | ImageSortMode::Created => {
+                PaginationOrder::Descending
+            }
+        }
+    }
+
+    fn from_query(p: &PaginatedImages<T>) -> Result<&Self, HttpError> {
+        match &p.page {
+            WhichPage::First(scan_mode) => Ok(scan_mode),
+            WhichPage::Next(PageSelector { scan, last_seen }) => {
+                match (scan.sort_by, last_seen) {
+                    (
+                        ImageSortMode::NameAscending
+                        | ImageSortMode::NameDescending,
+                        ImageMarker::Name(_),
+                    )
+                    | (ImageSortMode::IdAscending, ImageMarker::Id(_))
+                    | (ImageSortMode::Created, ImageMarker::Created(_))
+                    | (ImageSortMode::Size, ImageMarker::Size(_)) => Ok(scan),
+                    _ => Err(bad_token_error()),
+                }
+            }
+        }
+    }
+}
+
+/// Marker function that extracts the field an image listing is sorted by
+///
+/// Images don't carry their size in their identity, so callers must supply it.
+pub fn marker_for_image<T: ObjectIdentity, Selector>(
+    scan: &ScanImages<Selector>,
+    item: &T,
+    size: ByteCount,
+) -> ImageMarker {
+    let identity = item.identity();
+    match scan.sort_by {
+        ImageSortMode::NameAscending | ImageSortMode::NameDescending => {
+            ImageMarker::Name(identity.name.clone())
+        }
+        ImageSortMode::IdAscending => ImageMarker::Id(identity.id),
+        ImageSortMode::Created => {
+            ImageMarker::Created((identity.time_created, identity.id))
+        }
+        ImageSortMode::Size => ImageMarker::Size((size, identity.id)),
+    }
+}
+
+/// The ways in which a listing of images can be paginated
+#[derive(Debug)]
+pub enum ImagePaginatedBy<'a> {
+    Id(DataPageParams<'a, Uuid>),
+    Name(DataPageParams<'a, Name>),
+    Created(DataPageParams<'a, (DateTime<Utc>, Uuid)>),
+    Size(DataPageParams<'a, (ByteCount, Uuid)>),
+}
+
+impl<'a> From<PaginatedBy<'a>> for ImagePaginatedBy<'a> {
+    fn from(paginated_by: PaginatedBy<'a>) -> Self {
+        match paginated_by {
+            PaginatedBy::Id(pagparams) => ImagePaginatedBy::Id(pagparams),
+            PaginatedBy::Name(pagparams) => ImagePaginatedBy::Name(pagparams),
+        }
+    }
+}
+
+pub fn image_pagination<'a, Selector>(
+    pag_params: &'a DataPageParams<'a, ImageMarker>,
+    scan_params: &'a ScanImages<Selector>,
+) -> Result<ImagePaginatedBy<'a>, HttpError>
+where
+    Selector:
+        Clone + Debug + DeserializeOwned + JsonSchema + PartialEq + Serialize,
+{
+    match scan_params.sort_by {
+        ImageSortMode::NameAscending | ImageSortMode::NameDescending => {
+            Ok(ImagePaginatedBy::Name(image_page_params(
+                pag_params,
+                |m| match m {
+                    ImageMarker::Name(name) => Some(name),
+                    _ => None,
+                },
+            )?))
+        }
+        ImageSortMode::IdAscending => Ok(ImagePaginatedBy::Id(
+            image_page_params(pag_params, |m| match m {
+                ImageMarker::Id(id) => Some(id),
+                _ => None,
+            })?,
+        )),
+        ImageSortMode::Created => {
+            Ok(ImagePaginatedBy::Created(image_page_params(pag_params, |m| {
+                match m {
+                    ImageMarker::Created(marker) => Some(marker),
+                    _ => None,
+                }
+            })?))
+        }
+        ImageSortMode::Size => Ok(ImagePaginatedBy::Size(image_page_params(
+            pag_params,
+            |m| match m {
+                ImageMarker::Size(marker) => Some(marker),
+                _ => None,
+            },
+        )?)),
+    }
+}
+
+fn image_page_params<'a, M>(
+    pag_params: &DataPageParams<'a, ImageMarker>,
+    marker: impl FnOnce(&'a ImageMarker) -> Option<&'a M>,
+) -> Result<DataPageParams<'a, M>, HttpError> {
+    let marker = match pag_params.marker {
+        Some(last_seen) => Some(marker(last_seen).ok_or_else(bad_token_error)?),
+        None => None,
+    };
+    Ok(DataPageParams {
+        marker,
+        direction: pag_params.direction,
+        limit: pag_params.limit,
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::IdSortMode;
+    use super::ImageMarker;
+    use super::ImagePaginatedBy;
+    use super::ImageSortMode;
     use super::Name;
     use super::NameOrId;
     use super::NameOrIdSortMode;
@@ -493,17 +682,22 @@ mod test {
     use super::PaginatedByName;
     use super::PaginatedByNameOrId;
     use super::PaginatedByTimeAndId;
+    use super::PaginatedImages;
     use super::ScanById;
     use super::ScanByName;
     use super::ScanByNameOrId;
     use super::ScanByTimeAndId;
+    use super::ScanImages;
     use super::ScanParams;
     use super::TimeAndIdSortMode;
     use super::data_page_params_with_limit;
+    use super::image_pagination;
     use super::marker_for_id;
+    use super::marker_for_image;
     use super::marker_for_name;
     use super::marker_for_name_or_id;
     use super::page_selector_for;
+    use crate::api::external::ByteCount;
     use crate::api::external::IdentityMetadata;
     use crate::api::external::ObjectIdentity;
     use crate::api::external::http_pagination::name_or_id_pagination;
@@ -1044,4 +1238,80 @@ mod test {
             "unknown variant `nothing`, expected `time_and_id_ascending` or `time_and_id_descending`"
         );
     }
+
+    #[test]
+    fn test_scan_images() {
+        // Start with the common battery of tests.
+        let scan = ScanImages { sort_by: ImageSortMode::Size, selector: () };
+        assert_eq!(scan.direction(), PaginationOrder::Ascending);
+
+        let list = list_of_things();
+        let size = ByteCount::from(1024u32);
+        let thing0_marker = ImageMarker::Size((size, list[0].identity.id));
+        let thinglast_id = list[list.len() - 1].identity.id;
+        let thinglast_marker = ImageMarker::Size((size, thinglast_id));
+        let marker_fn = |scan: &ScanImages, item: &MyThing| -> ImageMarker {
+            marker_for_image(scan, item, size)
+        };
+
+        let (p0, p1) = test_scan_param_common(
+            &list,
+            &scan,
+            "sort_by=size",
+            &thing0_marker,
+            &thinglast_marker,
+            &ScanImages { sort_by: ImageSortMode::NameAscending, selector: () },
+            &marker_fn,
+        );
+
+        // Verify data pages based on the query params.
+        let limit = NonZeroU32::new(123).unwrap();
+        let data_page = data_page_params_with_limit(limit, &p0).unwrap();
+        let data_page = match image_pagination(&data_page, &scan) {
+            Ok(ImagePaginatedBy::Size(params)) => params,
+            _ => panic!("Expected size pagination"),
+        };
+        assert_eq!(data_page.marker, None);
+        assert_eq!(data_page.direction, PaginationOrder::Ascending);
+        assert_eq!(data_page.limit, limit);
+
+        let data_page = data_page_params_with_limit(limit, &p1).unwrap();
+        let data_page = match image_pagination(&data_page, &scan) {
+            Ok(ImagePaginatedBy::Size(params)) => params,
+            _ => panic!("Expected size pagination"),
+        };
+        assert_eq!(data_page.marker, Some(&(size, thinglast_id)));
+
+        // Listing by creation time puts the most recent images first.
+        let scan = ScanImages { sort_by: ImageSortMode::Created, selector: () };
+        assert_eq!(scan.direction(), PaginationOrder::Descending);
+        assert_eq!(
+            marker_for_image(&scan, &list[0], size),
+            ImageMarker::Created((
+                list[0].identity.time_created,
+                list[0].identity.id
+            ))
+        );
+
+        // "name" is accepted as a shorthand for "name_ascending".
+        let p: PaginatedImages =
+            serde_urlencoded::from_str("sort_by=name").unwrap();
+        assert_eq!(
+            ScanImages::from_query(&p).unwrap().sort_by,
+            ImageSortMode::NameAscending
+        );
+
+        // A marker that doesn't match the sort mode is rejected.
+        let p: PaginatedImages =
+            serde_urlencoded::from_str("sort_by=created").unwrap();
+        let marker_fn = |_: &ScanImages, item: &MyThing| -> ImageMarker {
+            ImageMarker::Id(item.identity.id)
+        };
+        let page =
+            ScanImages::results_page(&p, list.clone(), &marker_fn).unwrap();
+        let q = format!("page_token={}", page.next_page.unwrap());
+        let p: PaginatedImages = serde_urlencoded::from_str(&q).unwrap();
+        let error = ScanImages::from_query(&p).unwrap_err();
+        assert_eq!(error.external_message, "invalid page token");
+    }
 }
@@ -34,6 +34,7 @@ use semver::Version;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt::Debug;
@@ -952,12 +953,14 @@ pub enum ResourceType {
     LoopbackAddress,
     MetricProducer,
     NatEntry,
+    Operation,
     Oximeter,
     PhysicalDisk,
     Probe,
     ProbeNetworkInterface,
     Project,
     ProjectImage,
+    ProjectQuotas,
     Rack,
     RoleBuiltin,
     RouterRoute,
@@ -975,6 +978,8 @@ pub enum ResourceType {
     SledInstance,
     SledLedger,
     Snapshot,
+    SnapshotExport,
+    SnapshotSchedule,
     SshKey,
     SupportBundle,
     Switch,
@@ -990,6 +995,7 @@ pub enum ResourceType {
     VpcFirewallRule,
     VpcRouter,
     VpcSubnet,
+    VpcSubnetIpReservation,
     WebhookSecret,
     Zpool,
 }
@@ -1191,11 +1197,17 @@ pub struct Instance {
     /// the ID of the disk used to boot this Instance, if a specific one is assigned.
     pub boot_disk_id: Option<Uuid>,
 
+    /// user-defined key/value tags attached to this Instance
+    pub tags: BTreeMap<String, String>,
+
     #[serde(flatten)]
     pub runtime: InstanceRuntimeState,
 
     #[serde(flatten)]
     pub auto_restart_status: InstanceAutoRestartStatus,
+
+    /// whether this Instance keeps its sled reservation while it is stopped
+    pub stopped_reservation_policy: InstanceStoppedReservationPolicy,
 }
 
 /// Status of control-plane driven automatic failure recovery for this instance.
@@ -1241,6 +1253,53 @@ pub struct InstanceAutoRestartStatus {
     // `auto_restart`.
     #[serde(rename = "auto_restart_cooldown_expiration")]
     pub cooldown_expiration: Option<DateTime<Utc>>,
+
+    /// The number of seconds that must elapse between automatic restarts of
+    /// this instance.
+    ///
+    /// This is the cooldown period configured for this instance, or, if it has
+    /// none, its project's default or the fleet-wide default.
+    //
+    // Rename this field, as the struct is `#[serde(flatten)]`ed into the
+    // `Instance` type, and we would like the field to be prefixed with
+    // `auto_restart`.
+    #[serde(rename = "auto_restart_cooldown_secs")]
+    pub cooldown_secs: u32,
+
+    /// Whether the control plane would automatically restart this instance
+    /// right now, were it to fail.
+    //
+    // Rename this field, as the struct is `#[serde(flatten)]`ed into the
+    // `Instance` type, and we would like the field to be prefixed with
+    // `auto_restart`.
+    #[serde(rename = "auto_restart_state")]
+    pub state: InstanceAutoRestartState,
+
+    /// The number of times the control plane has automatically restarted this
+    /// instance.
+    //
+    // Rename this field, as the struct is `#[serde(flatten)]`ed into the
+    // `Instance` type, and we would like the field to be prefixed with
+    // `auto_restart`.
+    #[serde(rename = "auto_restart_count")]
+    pub count: u32,
+}
+
+/// Whether the control plane would currently automatically restart an instance
+/// if it failed.
+#[derive(
+    Copy, Clone, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceAutoRestartState {
+    /// The instance will be restarted as soon as it fails.
+    Ready,
+    /// The instance was automatically restarted recently, and will not be
+    /// restarted again until its cooldown period expires.
+    CoolingDown,
+    /// The instance's auto-restart policy does not permit the control plane to
+    /// restart it.
+    Disabled,
 }
 
 /// A policy determining when an instance should be automatically restarted by
@@ -1260,6 +1319,34 @@ pub enum InstanceAutoRestartPolicy {
     BestEffort,
 }
 
+/// A policy determining what happens to an instance's sled resources while it
+/// is stopped.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    Eq,
+    PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceStoppedReservationPolicy {
+    /// When the instance stops, the CPU and memory reserved for it on its sled
+    /// are released. Starting the instance again places it on any sled with
+    /// room for it, and may fail if the rack is full.
+    #[default]
+    Release,
+    /// When the instance stops (other than by failing), the CPU and memory
+    /// reserved for it on its sled are kept, so that it can be started on the
+    /// same sled without competing for capacity. The reservation is released
+    /// if the instance is deleted, resized, or its policy is changed to
+    /// `release`.
+    Retain,
+}
+
 // AFFINITY GROUPS
 
 /// Affinity policy used to describe "what to do when a request cannot be satisfied"
@@ -1377,6 +1464,12 @@ pub struct Disk {
     pub block_size: ByteCount,
     pub state: DiskState,
     pub device_path: String,
+    /// Whether the disk is protected from deletion
+    pub delete_protected: bool,
+    /// Warnings about the source the disk was created from, such as the image
+    /// being deprecated. These are only reported when the disk is created.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 /// State of a Disk
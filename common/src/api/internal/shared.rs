@@ -1007,6 +1007,12 @@ impl DatasetKind {
         }
     }
 
+    /// Returns true if the control plane sizes this dataset's quota based on
+    /// its observed usage, rather than leaving it fixed.
+    pub fn has_usage_based_quota(&self) -> bool {
+        matches!(self, DatasetKind::Cockroach | DatasetKind::Crucible)
+    }
+
     /// Returns the zone name, if this is a dataset for a zone filesystem.
     ///
     /// Otherwise, returns "None".
@@ -28,12 +28,22 @@ pub const COCKROACHDB_REDUNDANCY: usize = 5;
 /// The amount of redundancy for Crucible Pantry services.
 ///
 /// This is used by both RSS (to distribute the initial set of services) and the
-/// Reconfigurator (to know whether to add new pantry zones)
+/// Reconfigurator (as the minimum number of pantry zones to keep running)
 pub const CRUCIBLE_PANTRY_REDUNDANCY: usize = 3;
 
+/// The number of in-service sleds that each Crucible Pantry is expected to
+/// serve.
+///
+/// Disk imports and snapshot exports all go through a Pantry, so larger racks
+/// need more of them. The Reconfigurator runs at least one Pantry for every
+/// this many sleds (but never fewer than the policy asks for).
+pub const SLEDS_PER_CRUCIBLE_PANTRY: usize = 8;
+
 /// The amount of redundancy for internal DNS servers.
 ///
-/// Must be less than or equal to RESERVED_INTERNAL_DNS_REDUNDANCY.
+/// This is also the number of well-known internal DNS server addresses that
+/// clients bootstrap from, so it must be less than or equal to
+/// RESERVED_INTERNAL_DNS_REDUNDANCY.
 pub const INTERNAL_DNS_REDUNDANCY: usize = 3;
 
 /// The potential number of internal DNS servers we hold reserved for future
@@ -41,10 +51,15 @@ pub const INTERNAL_DNS_REDUNDANCY: usize = 3;
 ///
 /// Any consumers interacting with "the number of internal DNS servers" (e.g.,
 /// to construct a DNS client) should operate in terms of
-/// [`INTERNAL_DNS_REDUNDANCY`]. This constant should only be used to reserve
-/// space where we could increase `INTERNAL_DNS_REDUNDANCY` up to at most this
-/// value.
-pub const RESERVED_INTERNAL_DNS_REDUNDANCY: usize = 5;
+/// [`INTERNAL_DNS_REDUNDANCY`]. This constant bounds how many internal DNS
+/// servers the Reconfigurator may run: each one gets its own `/64` out of the
+/// reserved rack subnet, and clients still only contact the first
+/// `INTERNAL_DNS_REDUNDANCY` of those, which the planner always fills first.
+///
+/// Nothing else is allocated out of the reserved rack subnet, so raising this
+/// value does not disturb existing racks; their servers keep the subnets they
+/// already have and additional servers are placed in the unused ones.
+pub const RESERVED_INTERNAL_DNS_REDUNDANCY: usize = 16;
 
 /// The amount of redundancy for single-node ClickHouse servers
 /// (*not* replicated aka multi-node clusters).
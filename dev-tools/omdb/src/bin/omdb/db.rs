@@ -4298,11 +4298,12 @@ async fn cmd_db_instance_info(
 ) -> Result<(), anyhow::Error> {
     use nexus_db_model::{
         Instance, InstanceKarmicStatus, InstanceRuntimeState, Migration,
-        Reincarnatability,
+        ProjectAutoRestartDefaults, Reincarnatability,
     };
     use nexus_db_schema::schema::{
         disk::dsl as disk_dsl, instance::dsl as instance_dsl,
-        migration::dsl as migration_dsl, vmm::dsl as vmm_dsl,
+        migration::dsl as migration_dsl, project::dsl as project_dsl,
+        vmm::dsl as vmm_dsl,
     };
     let &InstanceInfoArgs { ref id, history, resources, all } = args;
 
@@ -4317,6 +4318,24 @@ async fn cmd_db_instance_info(
         .next()
         .ok_or_else(|| anyhow::anyhow!("no instance found with ID {id}"))?;
 
+    // The instance inherits any auto-restart settings it doesn't override
+    // from its project.
+    let project_auto_restart = match project_dsl::project
+        .filter(project_dsl::id.eq(instance.project_id))
+        .select(ProjectAutoRestartDefaults::as_select())
+        .first_async(&*datastore.pool_connection_for_tests().await?)
+        .await
+    {
+        Ok(defaults) => defaults,
+        Err(e) => {
+            eprintln!(
+                "warning: failed to look up project {}: {e}",
+                instance.project_id
+            );
+            ProjectAutoRestartDefaults::default()
+        }
+    };
+
     let active_vmm = if let Some(id) = instance.runtime_state.propolis_id {
         let fetch_result = vmm_dsl::vmm
             .filter(vmm_dsl::id.eq(id))
@@ -4361,8 +4380,9 @@ async fn cmd_db_instance_info(
     const VCPUS: &'static str = "vCPUs";
     const MEMORY: &'static str = "memory";
     const HOSTNAME: &'static str = "hostname";
-    const BOOT_DISK: &'static str = "boot disk";
+    const BOOT_ORDER: &'static str = "boot order";
     const AUTO_RESTART: &'static str = "auto-restart";
+    const PROJECT_AUTO_RESTART: &'static str = "project auto-restart";
     const STATE: &'static str = "nexus state";
     const INTENDED_STATE: &'static str = "intended state";
     const LAST_MODIFIED: &'static str = "last modified at";
@@ -4385,9 +4405,10 @@ async fn cmd_db_instance_info(
         DELETED,
         VCPUS,
         MEMORY,
-        BOOT_DISK,
+        BOOT_ORDER,
         HOSTNAME,
         AUTO_RESTART,
+        PROJECT_AUTO_RESTART,
         STATE,
         API_STATE,
         INTENDED_STATE,
@@ -4430,8 +4451,9 @@ async fn cmd_db_instance_info(
     println!("    {VCPUS:>WIDTH$}: {}", instance.ncpus.0.0);
     println!("    {MEMORY:>WIDTH$}: {}", instance.memory.0);
     println!("    {HOSTNAME:>WIDTH$}: {}", instance.hostname);
-    println!("    {BOOT_DISK:>WIDTH$}: {:?}", instance.boot_disk_id);
+    println!("    {BOOT_ORDER:>WIDTH$}: {:?}", instance.boot_order);
     print_multiline_debug(AUTO_RESTART, &instance.auto_restart);
+    print_multiline_debug(PROJECT_AUTO_RESTART, &project_auto_restart);
     println!("\n{:=<80}", "== RUNTIME STATE ");
     let InstanceRuntimeState {
         time_updated,
@@ -4458,7 +4480,11 @@ async fn cmd_db_instance_info(
 
     // Reincarnation status
     let InstanceKarmicStatus { needs_reincarnation, can_reincarnate } =
-        instance.auto_restart_status(active_vmm.as_ref());
+        instance.auto_restart_status(
+            &project_auto_restart,
+            active_vmm.as_ref(),
+            Utc::now(),
+        );
     println!(
         "{} {NEEDS_REINCARNATION:>WIDTH$}: {needs_reincarnation}",
         if needs_reincarnation { "(i)" } else { "   " }
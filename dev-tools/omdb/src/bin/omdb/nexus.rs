@@ -43,10 +43,14 @@ use nexus_client::types::SagaState;
 use nexus_client::types::SledSelector;
 use nexus_client::types::UninitializedSledId;
 use nexus_db_lookup::LookupPath;
+use nexus_db_queries::authz;
+use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db::DataStore;
 use nexus_inventory::now_db_precision;
 use nexus_saga_recovery::LastPass;
 use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintDiffSummary;
+use nexus_types::deployment::BlueprintExecutionDisabled;
 use nexus_types::deployment::ClickhouseMode;
 use nexus_types::deployment::ClickhousePolicy;
 use nexus_types::deployment::OximeterReadMode;
@@ -72,6 +76,7 @@ use nexus_types::internal_api::background::TufArtifactReplicationCounters;
 use nexus_types::internal_api::background::TufArtifactReplicationRequest;
 use nexus_types::internal_api::background::TufArtifactReplicationStatus;
 use nexus_types::inventory::BaseboardId;
+use omicron_common::api::external::LookupType;
 use omicron_uuid_kinds::BlueprintUuid;
 use omicron_uuid_kinds::CollectionUuid;
 use omicron_uuid_kinds::DemoSagaUuid;
@@ -83,6 +88,7 @@ use omicron_uuid_kinds::SupportBundleUuid;
 use quiesce::QuiesceArgs;
 use quiesce::cmd_nexus_quiesce;
 use serde::Deserialize;
+use serde::Serialize;
 use slog_error_chain::InlineErrorChain;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
@@ -216,6 +222,8 @@ enum BlueprintsCommands {
     List,
     /// Show a blueprint
     Show(BlueprintIdArgs),
+    /// Show the report from the planning run that produced a blueprint
+    Report(BlueprintIdArgs),
     /// Diff two blueprints
     Diff(BlueprintDiffArgs),
     /// Delete a blueprint
@@ -224,6 +232,8 @@ enum BlueprintsCommands {
     Target(BlueprintsTargetArgs),
     /// Generate a new blueprint
     Regenerate,
+    /// Export a blueprint to a file in the portable format
+    Export(BlueprintExportArgs),
     /// Import a blueprint
     Import(BlueprintImportArgs),
 }
@@ -283,6 +293,38 @@ impl BlueprintIdOrCurrentTarget {
             })?;
         Ok(response.into_inner())
     }
+
+    async fn resolve_to_blueprint_from_db(
+        &self,
+        opctx: &OpContext,
+        datastore: &DataStore,
+    ) -> anyhow::Result<Blueprint> {
+        let id = match self {
+            Self::CurrentTarget => {
+                let target = datastore
+                    .blueprint_target_get_current(opctx)
+                    .await
+                    .context("reading current blueprint target")?;
+                target.target_id
+            }
+            Self::BlueprintId(id) => *id,
+        };
+        blueprint_read_from_db(opctx, datastore, id).await
+    }
+}
+
+async fn blueprint_read_from_db(
+    opctx: &OpContext,
+    datastore: &DataStore,
+    id: BlueprintUuid,
+) -> anyhow::Result<Blueprint> {
+    let id = id.into_untyped_uuid();
+    let authz_blueprint =
+        authz::Blueprint::new(authz::FLEET, id, LookupType::ById(id));
+    datastore
+        .blueprint_read(opctx, &authz_blueprint)
+        .await
+        .with_context(|| format!("reading blueprint {id}"))
 }
 
 #[derive(Debug, Clone, Copy, Args)]
@@ -301,6 +343,18 @@ struct BlueprintDiffArgs {
     /// Exit with 1 if there were differences, 0 if no differences.
     #[arg(long, default_value_t = false)]
     exit_code: bool,
+    /// Only print per-sled counts of changes and changed metadata.
+    #[arg(long, default_value_t = false)]
+    summary: bool,
+    /// Print counts of changes as JSON rather than a human-readable diff.
+    #[arg(long, default_value_t = false, conflicts_with = "summary")]
+    json: bool,
+    /// Read the blueprints directly from the database rather than asking
+    /// Nexus for them (useful if Nexus is unavailable).
+    #[arg(long, default_value_t = false)]
+    from_db: bool,
+    #[clap(flatten)]
+    db_url_opts: DbUrlOptions,
 }
 
 #[derive(Debug, Args)]
@@ -329,6 +383,26 @@ enum BlueprintTargetCommands {
     ///
     /// Fails if the specified blueprint id is not the current target
     Disable(BlueprintIdArgs),
+    /// Disable execution of the current target blueprint, recording why
+    ///
+    /// Unlike `disable`, this remains in effect when new blueprints are made
+    /// the target, until `enable-execution` is used.
+    ///
+    /// Fails if the specified blueprint id is not the current target
+    DisableExecution(BlueprintTargetDisableExecutionArgs),
+    /// Re-enable execution after `disable-execution`
+    ///
+    /// Fails if the specified blueprint id is not the current target
+    EnableExecution(BlueprintIdArgs),
+}
+
+#[derive(Debug, Args)]
+struct BlueprintTargetDisableExecutionArgs {
+    /// id of blueprint (or `target` for the current target)
+    blueprint_id: BlueprintIdOrCurrentTarget,
+    /// why execution is being disabled
+    #[clap(long)]
+    reason: String,
 }
 
 #[derive(Debug, Args)]
@@ -341,6 +415,10 @@ struct BlueprintTargetSetArgs {
     /// before proceeding
     #[clap(long)]
     diff: bool,
+    /// make the blueprint the target without first validating it (for
+    /// emergencies only)
+    #[clap(long)]
+    skip_validation: bool,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -353,9 +431,18 @@ enum BlueprintTargetSetEnabled {
     Inherit,
 }
 
+#[derive(Debug, Args)]
+struct BlueprintExportArgs {
+    /// id of blueprint (or `target` for the current target)
+    blueprint_id: BlueprintIdOrCurrentTarget,
+    /// file to write the exported blueprint to
+    output: Utf8PathBuf,
+}
+
 #[derive(Debug, Args)]
 struct BlueprintImportArgs {
-    /// path to a file containing a JSON-serialized blueprint
+    /// path to a file containing a blueprint, either in the portable format
+    /// written by `export` or as plain JSON
     input: Utf8PathBuf,
 }
 
@@ -637,9 +724,12 @@ impl NexusArgs {
             NexusCommands::Blueprints(BlueprintsArgs {
                 command: BlueprintsCommands::Show(args),
             }) => cmd_nexus_blueprints_show(&client, args).await,
+            NexusCommands::Blueprints(BlueprintsArgs {
+                command: BlueprintsCommands::Report(args),
+            }) => cmd_nexus_blueprints_report(&client, args).await,
             NexusCommands::Blueprints(BlueprintsArgs {
                 command: BlueprintsCommands::Diff(args),
-            }) => cmd_nexus_blueprints_diff(&client, args).await,
+            }) => cmd_nexus_blueprints_diff(&client, args, omdb, log).await,
             NexusCommands::Blueprints(BlueprintsArgs {
                 command: BlueprintsCommands::Delete(args),
             }) => {
@@ -685,12 +775,45 @@ impl NexusArgs {
                 )
                 .await
             }
+            NexusCommands::Blueprints(BlueprintsArgs {
+                command:
+                    BlueprintsCommands::Target(BlueprintsTargetArgs {
+                        command: BlueprintTargetCommands::DisableExecution(args),
+                    }),
+            }) => {
+                let token = omdb.check_allow_destructive()?;
+                cmd_nexus_blueprints_target_set_execution(
+                    &client,
+                    &args.blueprint_id,
+                    Some(args.reason.clone()),
+                    token,
+                )
+                .await
+            }
+            NexusCommands::Blueprints(BlueprintsArgs {
+                command:
+                    BlueprintsCommands::Target(BlueprintsTargetArgs {
+                        command: BlueprintTargetCommands::EnableExecution(args),
+                    }),
+            }) => {
+                let token = omdb.check_allow_destructive()?;
+                cmd_nexus_blueprints_target_set_execution(
+                    &client,
+                    &args.blueprint_id,
+                    None,
+                    token,
+                )
+                .await
+            }
             NexusCommands::Blueprints(BlueprintsArgs {
                 command: BlueprintsCommands::Regenerate,
             }) => {
                 let token = omdb.check_allow_destructive()?;
                 cmd_nexus_blueprints_regenerate(&client, token).await
             }
+            NexusCommands::Blueprints(BlueprintsArgs {
+                command: BlueprintsCommands::Export(args),
+            }) => cmd_nexus_blueprints_export(&client, args).await,
             NexusCommands::Blueprints(BlueprintsArgs {
                 command: BlueprintsCommands::Import(args),
             }) => {
@@ -1174,6 +1297,24 @@ fn print_task_details(bgtask: &BackgroundTask, details: &serde_json::Value) {
         "sp_ereport_ingester" => {
             print_task_sp_ereport_ingester(details);
         }
+        "clickhouse_backup" => {
+            print_task_clickhouse_backup(details);
+        }
+        "snapshot_scheduler" => {
+            print_task_snapshot_scheduler(details);
+        }
+        "dataset_quota_tuner" => {
+            print_task_dataset_quota_tuner(details);
+        }
+        "zpool_usage_trends" => {
+            print_task_zpool_usage_trends(details);
+        }
+        "zpool_scrub_alerts" => {
+            print_task_zpool_scrub_alerts(details);
+        }
+        "rolling_sled_reboot" => {
+            print_task_rolling_sled_reboot(details);
+        }
         "support_bundle_collector" => {
             print_task_support_bundle_collector(details);
         }
@@ -1208,6 +1349,7 @@ fn print_task_abandoned_vmm_reaper(details: &serde_json::Value) {
             vmms_found,
             vmms_deleted,
             vmms_already_deleted,
+            vmms_still_on_sled,
             sled_reservations_deleted,
             errors,
         }) => {
@@ -1225,6 +1367,8 @@ fn print_task_abandoned_vmm_reaper(details: &serde_json::Value) {
             const VMMS_DELETED: &'static str = "  VMM records deleted:";
             const VMMS_ALREADY_DELETED: &'static str =
                 "  VMMs already deleted by another Nexus:";
+            const VMMS_STILL_ON_SLED: &'static str =
+                "  VMMs still present on their sled:";
             const SLED_RESERVATIONS_DELETED: &'static str =
                 "sled resource reservations deleted:";
             // To align the number column, figure out the length of the
@@ -1238,6 +1382,7 @@ fn print_task_abandoned_vmm_reaper(details: &serde_json::Value) {
                 VMMS_FOUND,
                 VMMS_DELETED,
                 VMMS_ALREADY_DELETED,
+                VMMS_STILL_ON_SLED,
                 SLED_RESERVATIONS_DELETED,
             ]) + 1;
             const NUM_WIDTH: usize = 3;
@@ -1248,6 +1393,10 @@ fn print_task_abandoned_vmm_reaper(details: &serde_json::Value) {
                 "    {VMMS_ALREADY_DELETED:<WIDTH$}{:>NUM_WIDTH$}",
                 vmms_already_deleted
             );
+            println!(
+                "    {VMMS_STILL_ON_SLED:<WIDTH$}{:>NUM_WIDTH$}",
+                vmms_still_on_sled
+            );
             println!(
                 "    {SLED_RESERVATIONS_DELETED:<WIDTH$}{:>NUM_WIDTH$}",
                 sled_reservations_deleted,
@@ -1307,6 +1456,8 @@ fn print_task_blueprint_executor(details: &serde_json::Value) {
     struct BlueprintExecutorStatus {
         target_id: Uuid,
         enabled: bool,
+        #[serde(default)]
+        execution_disabled: Option<BlueprintExecutionDisabled>,
         execution_error: Option<NestedError>,
     }
 
@@ -1324,10 +1475,13 @@ fn print_task_blueprint_executor(details: &serde_json::Value) {
             ]);
             builder.push_record([
                 "execution:".to_string(),
-                if status.enabled {
-                    "enabled".to_string()
-                } else {
-                    "disabled".to_string()
+                match &status.execution_disabled {
+                    Some(disabled) => format!(
+                        "disabled by operator at {}: {}",
+                        disabled.time_disabled, disabled.reason
+                    ),
+                    None if status.enabled => "enabled".to_string(),
+                    None => "disabled".to_string(),
                 },
             ]);
 
@@ -1735,6 +1889,24 @@ fn print_task_instance_reincarnation(details: &serde_json::Value) {
                     println!("    > {id}")
                 }
             }
+
+            if status.pass_limit_reached {
+                println!(
+                    "    /!\\ the limit on instances reincarnated per \
+                     activation was reached; any others were left for the \
+                     next activation"
+                );
+            }
+
+            if !status.projects_at_limit.is_empty() {
+                println!(
+                    "    the following projects reached the limit on \
+                     instances reincarnated per activation:"
+                );
+                for id in status.projects_at_limit {
+                    println!("    > {id}")
+                }
+            }
         }
     }
 }
@@ -2962,6 +3134,306 @@ fn print_ereporter_status_totals<'status>(
     );
 }
 
+fn print_task_clickhouse_backup(details: &serde_json::Value) {
+    use nexus_types::internal_api::background::ClickhouseBackupStatus;
+
+    let ClickhouseBackupStatus {
+        disabled,
+        zone_id,
+        backup_created,
+        backups_deleted,
+        backups_retained,
+        errors,
+    } = match serde_json::from_value(details.clone()) {
+        Err(error) => {
+            eprintln!(
+                "warning: failed to interpret task details: {:?}: {:?}",
+                error, details
+            );
+            return;
+        }
+        Ok(status) => status,
+    };
+
+    if !errors.is_empty() {
+        println!("{ERRICON} errors: {}", errors.len());
+        for error in errors {
+            println!("      - {error}");
+        }
+    }
+
+    if disabled {
+        println!("    ClickHouse backups explicitly disabled by config!");
+        return;
+    }
+
+    let Some(zone_id) = zone_id else {
+        println!("    no single-node ClickHouse zone to back up");
+        return;
+    };
+    println!("    ClickHouse zone: {zone_id}");
+    match backup_created {
+        Some(name) => println!("    backup created: {name}"),
+        None => println!("    backup created: (none)"),
+    }
+    println!("    backups deleted: {}", backups_deleted.len());
+    for name in backups_deleted {
+        println!("      - {name}");
+    }
+    println!("    backups retained: {}", backups_retained.len());
+    for name in backups_retained {
+        println!("      - {name}");
+    }
+}
+
+fn print_task_dataset_quota_tuner(details: &serde_json::Value) {
+    use nexus_types::internal_api::background::DatasetQuotaTunerStatus;
+
+    let DatasetQuotaTunerStatus {
+        disabled,
+        blueprint_id,
+        inventory_collection_id,
+        datasets_observed,
+        proposed_quotas,
+        errors,
+    } = match serde_json::from_value(details.clone()) {
+        Err(error) => {
+            eprintln!(
+                "warning: failed to interpret task details: {:?}: {:?}",
+                error, details
+            );
+            return;
+        }
+        Ok(status) => status,
+    };
+
+    if !errors.is_empty() {
+        println!("{ERRICON} errors: {}", errors.len());
+        for error in errors {
+            println!("      - {error}");
+        }
+    }
+
+    if disabled {
+        println!("    dataset quota tuning explicitly disabled by config!");
+        return;
+    }
+
+    if let Some(blueprint_id) = blueprint_id {
+        println!("    target blueprint:     {blueprint_id}");
+    }
+    if let Some(collection_id) = inventory_collection_id {
+        println!("    inventory collection: {collection_id}");
+    }
+    println!("    datasets observed:    {datasets_observed}");
+    println!("    quotas proposed:      {}", proposed_quotas.len());
+    for (dataset_id, quota) in proposed_quotas {
+        println!("      - {dataset_id}: {quota}");
+    }
+}
+
+fn print_task_zpool_usage_trends(details: &serde_json::Value) {
+    use nexus_types::internal_api::background::ZpoolUsageTrendsStatus;
+
+    let ZpoolUsageTrendsStatus {
+        disabled,
+        inventory_collection_id,
+        samples_recorded,
+        samples_pruned,
+        zpools_analyzed,
+        warnings,
+        alerts_published,
+        errors,
+    } = match serde_json::from_value(details.clone()) {
+        Err(error) => {
+            eprintln!(
+                "warning: failed to interpret task details: {:?}: {:?}",
+                error, details
+            );
+            return;
+        }
+        Ok(status) => status,
+    };
+
+    if !errors.is_empty() {
+        println!("{ERRICON} errors: {}", errors.len());
+        for error in errors {
+            println!("      - {error}");
+        }
+    }
+
+    if disabled {
+        println!("    zpool usage trends explicitly disabled by config!");
+        return;
+    }
+
+    if let Some(collection_id) = inventory_collection_id {
+        println!("    inventory collection: {collection_id}");
+    }
+    println!("    samples recorded:     {samples_recorded}");
+    println!("    samples pruned:       {samples_pruned}");
+    println!("    zpools analyzed:      {zpools_analyzed}");
+    println!("    zpools nearly full:   {}", warnings.len());
+    for warning in warnings {
+        let days = match warning.days_until_threshold {
+            Some(0) => "now".to_string(),
+            Some(days) => format!("in {days} days"),
+            None => "unknown".to_string(),
+        };
+        println!(
+            "      - {} (sled {}): {} of {} allocated, reaches {}% {days}",
+            warning.zpool_id,
+            warning.sled_id,
+            warning.allocated,
+            warning.total_size,
+            warning.threshold_percent,
+        );
+    }
+    println!("    alerts published:     {}", alerts_published.len());
+}
+
+fn print_task_zpool_scrub_alerts(details: &serde_json::Value) {
+    use nexus_types::internal_api::background::ZpoolScrubAlertsStatus;
+
+    let ZpoolScrubAlertsStatus {
+        disabled,
+        inventory_collection_id,
+        zpools_checked,
+        scrubs_with_errors,
+        alerts_published,
+        errors,
+    } = match serde_json::from_value(details.clone()) {
+        Err(error) => {
+            eprintln!(
+                "warning: failed to interpret task details: {:?}: {:?}",
+                error, details
+            );
+            return;
+        }
+        Ok(status) => status,
+    };
+
+    if !errors.is_empty() {
+        println!("{ERRICON} errors: {}", errors.len());
+        for error in errors {
+            println!("      - {error}");
+        }
+    }
+
+    if disabled {
+        println!("    zpool scrub alerts explicitly disabled by config!");
+        return;
+    }
+
+    if let Some(collection_id) = inventory_collection_id {
+        println!("    inventory collection: {collection_id}");
+    }
+    println!("    zpools checked:       {zpools_checked}");
+    println!("    scrubs with errors:   {}", scrubs_with_errors.len());
+    for scrub in scrubs_with_errors {
+        println!(
+            "      - {} (sled {}): {} errors, finished {}",
+            scrub.zpool_id, scrub.sled_id, scrub.errors, scrub.time_finished,
+        );
+    }
+    println!("    alerts published:     {}", alerts_published.len());
+}
+
+fn print_task_rolling_sled_reboot(details: &serde_json::Value) {
+    use nexus_types::internal_api::background::RollingSledRebootStatus;
+
+    let RollingSledRebootStatus {
+        disabled,
+        reboot_id,
+        sleds_advanced,
+        migrations_started,
+        instances_stopped,
+        instances_restarted,
+        finished,
+        errors,
+    } = match serde_json::from_value(details.clone()) {
+        Err(error) => {
+            eprintln!(
+                "warning: failed to interpret task details: {:?}: {:?}",
+                error, details
+            );
+            return;
+        }
+        Ok(status) => status,
+    };
+
+    if !errors.is_empty() {
+        println!("{ERRICON} errors: {}", errors.len());
+        for error in errors {
+            println!("      - {error}");
+        }
+    }
+
+    if disabled {
+        println!("    rolling sled reboots explicitly disabled by config!");
+        return;
+    }
+
+    let Some(reboot_id) = reboot_id else {
+        println!("    no rolling sled reboot running");
+        return;
+    };
+    println!("    rolling sled reboot: {reboot_id}");
+    println!("    sleds advanced:      {}", sleds_advanced.len());
+    for (sled_id, step) in sleds_advanced {
+        println!("      - {sled_id}: now {step:?}");
+    }
+    println!("    migrations started:  {}", migrations_started.len());
+    println!("    instances stopped:   {}", instances_stopped.len());
+    println!("    instances restarted: {}", instances_restarted.len());
+    if let Some(state) = finished {
+        println!("    finished:            {state:?}");
+    }
+}
+
+fn print_task_snapshot_scheduler(details: &serde_json::Value) {
+    use nexus_types::internal_api::background::SnapshotSchedulerStatus;
+
+    let SnapshotSchedulerStatus {
+        schedules_run,
+        snapshots_requested,
+        snapshots_pruned,
+        errors,
+    } = match serde_json::from_value(details.clone()) {
+        Err(error) => {
+            eprintln!(
+                "warning: failed to interpret task details: {:?}: {:?}",
+                error, details
+            );
+            return;
+        }
+        Ok(status) => status,
+    };
+
+    if !errors.is_empty() {
+        println!("{ERRICON} errors: {}", errors.len());
+        for error in errors {
+            println!("      - {error}");
+        }
+    }
+
+    println!("    schedules run: {schedules_run}");
+    println!("    snapshots requested: {}", snapshots_requested.len());
+    for action in snapshots_requested {
+        println!(
+            "      - {} of disk {} (schedule {})",
+            action.snapshot_name, action.disk_id, action.schedule_id
+        );
+    }
+    println!("    snapshots pruned: {}", snapshots_pruned.len());
+    for action in snapshots_pruned {
+        println!(
+            "      - {} of disk {} (schedule {})",
+            action.snapshot_name, action.disk_id, action.schedule_id
+        );
+    }
+}
+
 const ERRICON: &str = "/!\\";
 
 fn warn_if_nonzero(n: usize) -> &'static str {
@@ -3302,31 +3774,141 @@ async fn cmd_nexus_blueprints_show(
     Ok(())
 }
 
+async fn cmd_nexus_blueprints_report(
+    client: &nexus_client::Client,
+    args: &BlueprintIdArgs,
+) -> Result<(), anyhow::Error> {
+    let blueprint = args.blueprint_id.resolve_to_blueprint(client).await?;
+    println!("{}", blueprint.report);
+    Ok(())
+}
+
 async fn cmd_nexus_blueprints_diff(
     client: &nexus_client::Client,
     args: &BlueprintDiffArgs,
+    omdb: &Omdb,
+    log: &slog::Logger,
 ) -> Result<(), anyhow::Error> {
+    let (b1, b2) = if args.from_db {
+        let datastore = args.db_url_opts.connect(omdb, log).await?;
+        let opctx = OpContext::for_tests(log.clone(), datastore.clone());
+        let result = blueprints_to_diff_from_db(&opctx, &datastore, args).await;
+        datastore.terminate().await;
+        result?
+    } else {
+        blueprints_to_diff(client, args).await?
+    };
+
+    let diff = b2.diff_since_blueprint(&b1);
+    if args.json {
+        let counts = BlueprintDiffCounts::new(&diff);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&counts)
+                .context("serializing blueprint diff")?
+        );
+    } else if args.summary {
+        println!("{}", diff.display().summary());
+    } else {
+        println!("{}", diff.display());
+    }
+    if args.exit_code && diff.has_changes() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Fetches the pair of blueprints to diff for `omdb nexus blueprints diff`
+/// from Nexus.
+async fn blueprints_to_diff(
+    client: &nexus_client::Client,
+    args: &BlueprintDiffArgs,
+) -> Result<(Blueprint, Blueprint), anyhow::Error> {
     let blueprint = args.blueprint1_id.resolve_to_blueprint(client).await?;
-    let (b1, b2) = if let Some(blueprint2_arg) = &args.blueprint2_id {
-        (blueprint, blueprint2_arg.resolve_to_blueprint(client).await?)
+    if let Some(blueprint2_arg) = &args.blueprint2_id {
+        Ok((blueprint, blueprint2_arg.resolve_to_blueprint(client).await?))
     } else if let Some(parent_id) = blueprint.parent_blueprint_id {
-        (
+        Ok((
             client
                 .blueprint_view(parent_id.as_untyped_uuid())
                 .await?
                 .into_inner(),
             blueprint,
-        )
+        ))
     } else {
         bail!("`blueprint2_id` was not specified and blueprint1 has no parent");
-    };
+    }
+}
 
-    let diff = b2.diff_since_blueprint(&b1);
-    println!("{}", diff.display());
-    if args.exit_code && diff.has_changes() {
-        std::process::exit(1);
+/// Fetches the pair of blueprints to diff for `omdb nexus blueprints diff`
+/// directly from the database.
+async fn blueprints_to_diff_from_db(
+    opctx: &OpContext,
+    datastore: &DataStore,
+    args: &BlueprintDiffArgs,
+) -> Result<(Blueprint, Blueprint), anyhow::Error> {
+    let blueprint = args
+        .blueprint1_id
+        .resolve_to_blueprint_from_db(opctx, datastore)
+        .await?;
+    if let Some(blueprint2_arg) = &args.blueprint2_id {
+        Ok((
+            blueprint,
+            blueprint2_arg
+                .resolve_to_blueprint_from_db(opctx, datastore)
+                .await?,
+        ))
+    } else if let Some(parent_id) = blueprint.parent_blueprint_id {
+        Ok((
+            blueprint_read_from_db(opctx, datastore, parent_id).await?,
+            blueprint,
+        ))
+    } else {
+        bail!("`blueprint2_id` was not specified and blueprint1 has no parent");
+    }
+}
+
+/// Machine-readable counts of the changes between two blueprints, printed by
+/// `omdb nexus blueprints diff --json`.
+#[derive(Serialize)]
+struct BlueprintDiffCounts {
+    before: BlueprintUuid,
+    after: BlueprintUuid,
+    has_changes: bool,
+    sleds_added: usize,
+    sleds_removed: usize,
+    sleds_modified: usize,
+    zones_added: usize,
+    zones_removed: usize,
+    zones_modified: usize,
+    disks_added: usize,
+    disks_removed: usize,
+    disks_modified: usize,
+    datasets_added: usize,
+    datasets_removed: usize,
+    datasets_modified: usize,
+}
+
+impl BlueprintDiffCounts {
+    fn new(diff: &BlueprintDiffSummary<'_>) -> Self {
+        Self {
+            before: diff.before.id,
+            after: diff.after.id,
+            has_changes: diff.has_changes(),
+            sleds_added: diff.diff.sleds.added.len(),
+            sleds_removed: diff.diff.sleds.removed.len(),
+            sleds_modified: diff.diff.sleds.modified().count(),
+            zones_added: diff.total_zones_added(),
+            zones_removed: diff.total_zones_removed(),
+            zones_modified: diff.total_zones_modified(),
+            disks_added: diff.total_disks_added(),
+            disks_removed: diff.total_disks_removed(),
+            disks_modified: diff.total_disks_modified(),
+            datasets_added: diff.total_datasets_added(),
+            datasets_removed: diff.total_datasets_removed(),
+            datasets_modified: diff.total_datasets_modified(),
+        }
     }
-    Ok(())
 }
 
 async fn cmd_nexus_blueprints_delete(
@@ -3353,6 +3935,17 @@ async fn cmd_nexus_blueprints_target_show(
     println!("target blueprint: {}", target.target_id);
     println!("made target at:   {}", target.time_made_target);
     println!("enabled:          {}", target.enabled);
+    let execution = client
+        .blueprint_target_execution_view()
+        .await
+        .context("fetching target blueprint execution state")?;
+    match &execution.disabled {
+        Some(disabled) => println!(
+            "execution:        disabled at {}: {}",
+            disabled.time_disabled, disabled.reason
+        ),
+        None => println!("execution:        enabled"),
+    }
     Ok(())
 }
 
@@ -3416,6 +4009,7 @@ async fn cmd_nexus_blueprints_target_set(
         .blueprint_target_set(&nexus_client::types::BlueprintTargetSet {
             target_id: args.blueprint_id,
             enabled,
+            skip_validation: args.skip_validation,
         })
         .await
         .with_context(|| {
@@ -3438,6 +4032,7 @@ async fn cmd_nexus_blueprints_target_set_enabled(
             &nexus_client::types::BlueprintTargetSet {
                 target_id: blueprint_id,
                 enabled,
+                skip_validation: false,
             },
         )
         .await
@@ -3448,6 +4043,33 @@ async fn cmd_nexus_blueprints_target_set_enabled(
     Ok(())
 }
 
+async fn cmd_nexus_blueprints_target_set_execution(
+    client: &nexus_client::Client,
+    blueprint_id: &BlueprintIdOrCurrentTarget,
+    disabled_reason: Option<String>,
+    _destruction_token: DestructiveOperationToken,
+) -> Result<(), anyhow::Error> {
+    let blueprint_id = blueprint_id.resolve_to_id(client).await?;
+    let description = if disabled_reason.is_some() {
+        "disabled execution of"
+    } else {
+        "enabled execution of"
+    };
+    client
+        .blueprint_target_set_execution(
+            &nexus_client::types::BlueprintTargetExecutionSet {
+                target_id: blueprint_id,
+                disabled_reason,
+            },
+        )
+        .await
+        .with_context(|| {
+            format!("setting execution state of blueprint {blueprint_id}")
+        })?;
+    eprintln!("{description} target blueprint {blueprint_id}");
+    Ok(())
+}
+
 async fn cmd_nexus_blueprints_regenerate(
     client: &nexus_client::Client,
     _destruction_token: DestructiveOperationToken,
@@ -3458,15 +4080,29 @@ async fn cmd_nexus_blueprints_regenerate(
     Ok(())
 }
 
+async fn cmd_nexus_blueprints_export(
+    client: &nexus_client::Client,
+    args: &BlueprintExportArgs,
+) -> Result<(), anyhow::Error> {
+    let blueprint = args.blueprint_id.resolve_to_blueprint(client).await?;
+    let output_path = &args.output;
+    let bytes =
+        blueprint.to_portable_bytes().context("serializing blueprint")?;
+    std::fs::write(output_path, &bytes)
+        .with_context(|| format!("write {:?}", output_path))?;
+    eprintln!("exported blueprint {} to {:?}", blueprint.id, output_path);
+    Ok(())
+}
+
 async fn cmd_nexus_blueprints_import(
     client: &nexus_client::Client,
     _destruction_token: DestructiveOperationToken,
     args: &BlueprintImportArgs,
 ) -> Result<(), anyhow::Error> {
     let input_path = &args.input;
-    let contents = std::fs::read_to_string(input_path)
+    let contents = std::fs::read(input_path)
         .with_context(|| format!("open {:?}", input_path))?;
-    let blueprint: Blueprint = serde_json::from_str(&contents)
+    let blueprint = read_blueprint_file(&contents)
         .with_context(|| format!("read {:?}", input_path))?;
     client
         .blueprint_import(&blueprint)
@@ -3476,6 +4112,18 @@ async fn cmd_nexus_blueprints_import(
     Ok(())
 }
 
+/// Parse a blueprint from a file that is either in the portable format or
+/// plain JSON.
+fn read_blueprint_file(contents: &[u8]) -> anyhow::Result<Blueprint> {
+    // If the file has a valid portable header, insist on the rest of it being
+    // valid too (rather than falling back to plain JSON), so that corruption
+    // is reported as such.
+    if Blueprint::portable_header(contents).is_ok() {
+        return Ok(Blueprint::from_portable_bytes(contents)?);
+    }
+    serde_json::from_slice(contents).context("parsing blueprint JSON")
+}
+
 async fn cmd_nexus_clickhouse_policy_get(
     client: &nexus_client::Client,
 ) -> Result<(), anyhow::Error> {
@@ -3841,7 +4489,6 @@ async fn cmd_nexus_sled_expunge_with_datastore(
     // 1. We'll require manual input on stdin to confirm the sled to be removed
     // 2. We'll warn sternly if the sled-to-be-expunged is still present in the
     //    most recent inventory collection
-    use nexus_db_queries::context::OpContext;
 
     let opctx = OpContext::for_tests(log.clone(), datastore.clone());
     let opctx = &opctx;
@@ -3944,8 +4591,6 @@ async fn cmd_nexus_sled_expunge_disk_with_datastore(
     log: &slog::Logger,
     _destruction_token: DestructiveOperationToken,
 ) -> Result<(), anyhow::Error> {
-    use nexus_db_queries::context::OpContext;
-
     let opctx = OpContext::for_tests(log.clone(), datastore.clone());
     let opctx = &opctx;
 
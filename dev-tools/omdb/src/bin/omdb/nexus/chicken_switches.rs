@@ -17,6 +17,7 @@ use nexus_types::deployment::ReconfiguratorChickenSwitches;
 use nexus_types::deployment::ReconfiguratorChickenSwitchesParam;
 use std::io;
 use std::io::Write;
+use std::num::NonZeroU8;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
@@ -52,6 +53,17 @@ pub struct ChickenSwitchesOpts {
 
     #[clap(long, action = ArgAction::Set)]
     add_zones_with_mupdate_override: Option<bool>,
+
+    #[clap(long, action = ArgAction::Set)]
+    allow_version_skew: Option<bool>,
+
+    /// number of Nexus zones to run (0 to use the default)
+    #[clap(long)]
+    target_nexus_zone_count: Option<u8>,
+
+    /// number of internal DNS zones to run (0 to use the default)
+    #[clap(long)]
+    target_internal_dns_zone_count: Option<u8>,
 }
 
 impl ChickenSwitchesOpts {
@@ -73,6 +85,19 @@ impl ChickenSwitchesOpts {
                             .planner_switches
                             .add_zones_with_mupdate_override,
                     ),
+                allow_version_skew: self
+                    .allow_version_skew
+                    .unwrap_or(current.planner_switches.allow_version_skew),
+                target_nexus_zone_count: self.target_nexus_zone_count.map_or(
+                    current.planner_switches.target_nexus_zone_count,
+                    NonZeroU8::new,
+                ),
+                target_internal_dns_zone_count: self
+                    .target_internal_dns_zone_count
+                    .map_or(
+                        current.planner_switches.target_internal_dns_zone_count,
+                        NonZeroU8::new,
+                    ),
             },
         }
     }
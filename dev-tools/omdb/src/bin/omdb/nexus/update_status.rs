@@ -5,6 +5,8 @@
 //! omdb commands related to update status
 
 use anyhow::Context;
+use chrono::DateTime;
+use chrono::Utc;
 use gateway_types::rot::RotSlot;
 use nexus_types::internal_api::views::{
     HostPhase1Status, HostPhase2Status, RotBootloaderStatus, RotStatus,
@@ -24,6 +26,9 @@ pub async fn cmd_nexus_update_status(
         .context("retrieving update status")?
         .into_inner();
 
+    print_stale_sps(status.mgs_driven.iter().filter_map(|s| {
+        Some((s.baseboard_description.clone(), s.stale_since?))
+    }));
     print_rot_bootloaders(
         status
             .mgs_driven
@@ -100,6 +105,22 @@ fn print_zones(zones: impl Iterator<Item = (SledUuid, Vec<ZoneStatus>)>) {
     println!("{}", table);
 }
 
+fn print_stale_sps(stale: impl Iterator<Item = (String, DateTime<Utc>)>) {
+    let stale: Vec<_> = stale.collect();
+    if stale.is_empty() {
+        return;
+    }
+
+    println!(
+        "SPs that could not be reached during the latest inventory collection \
+         (information below may be out of date)"
+    );
+    for (baseboard_id, time_collected) in stale {
+        println!("    {baseboard_id}: last collected at {time_collected}");
+    }
+    println!();
+}
+
 fn print_rot_bootloaders<'a>(
     bootloaders: impl Iterator<Item = (String, &'a RotBootloaderStatus)>,
 ) {
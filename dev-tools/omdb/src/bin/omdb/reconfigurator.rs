@@ -319,9 +319,14 @@ async fn cmd_reconfigurator_history(
         );
 
         if prev_blueprint_id == Some(target_id) {
-            // The only change here could be to the enable/disable bit.
-            // There's nothing else to say.
-            println!();
+            // The only change here could be to the enable/disable bit or to
+            // whether execution is disabled.
+            match t.execution_disabled() {
+                Some(disabled) => {
+                    println!(": execution disabled: {}", disabled.reason)
+                }
+                None => println!(),
+            }
         } else {
             // The blueprint id changed.
             let comment = match all_blueprints.get(&target_id) {
@@ -406,6 +411,9 @@ async fn cmd_reconfigurator_chicken_switches_history(
         version: String,
         planner_enabled: String,
         add_zones_with_mupdate_override: String,
+        allow_version_skew: String,
+        target_nexus_zone_count: String,
+        target_internal_dns_zone_count: String,
         time_modified: String,
     }
 
@@ -420,6 +428,9 @@ async fn cmd_reconfigurator_chicken_switches_history(
                         planner_switches:
                             PlannerChickenSwitches {
                                 add_zones_with_mupdate_override,
+                                allow_version_skew,
+                                target_nexus_zone_count,
+                                target_internal_dns_zone_count,
                             },
                     },
                 time_modified,
@@ -429,6 +440,11 @@ async fn cmd_reconfigurator_chicken_switches_history(
                 planner_enabled: planner_enabled.to_string(),
                 add_zones_with_mupdate_override:
                     add_zones_with_mupdate_override.to_string(),
+                allow_version_skew: allow_version_skew.to_string(),
+                target_nexus_zone_count: target_nexus_zone_count
+                    .map_or_else(|| "default".to_string(), |n| n.to_string()),
+                target_internal_dns_zone_count: target_internal_dns_zone_count
+                    .map_or_else(|| "default".to_string(), |n| n.to_string()),
                 time_modified: time_modified.to_string(),
             }
         })
@@ -237,6 +237,7 @@ async fn test_omdb_success_cases(cptestctx: &ControlPlaneTestContext) {
         &["nexus", "blueprints", "list"],
         &["nexus", "blueprints", "show", &initial_blueprint_id],
         &["nexus", "blueprints", "show", "current-target"],
+        &["nexus", "blueprints", "report", "current-target"],
         &[
             "nexus",
             "blueprints",
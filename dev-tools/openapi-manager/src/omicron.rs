@@ -118,9 +118,9 @@ pub fn all_apis() -> Vec<ManagedApiConfig> {
         },
         ManagedApiConfig {
             title: "Oxide Region API",
-            versions: Versions::new_lockstep(semver::Version::new(
-                20250730, 0, 0,
-            )),
+            versions: Versions::new_versioned(
+                nexus_external_api::supported_versions(),
+            ),
             description: "API for interacting with the Oxide control plane",
             boundary: ApiBoundary::External,
             api_description: nexus_external_api_mod::stub_api_description,
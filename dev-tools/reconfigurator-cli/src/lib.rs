@@ -9,6 +9,7 @@ use camino::{Utf8Path, Utf8PathBuf};
 use chrono::{DateTime, Utc};
 use clap::{ArgAction, ValueEnum};
 use clap::{Args, Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches};
 use daft::Diffable;
 use gateway_types::rot::RotSlot;
 use iddqd::IdOrdMap;
@@ -29,6 +30,7 @@ use nexus_reconfigurator_simulation::{BlueprintId, CollectionId, SimState};
 use nexus_reconfigurator_simulation::{SimStateBuilder, SimTufRepoSource};
 use nexus_reconfigurator_simulation::{SimTufRepoDescription, Simulator};
 use nexus_sled_agent_shared::inventory::ZoneKind;
+use nexus_types::deployment::BlueprintScope;
 use nexus_types::deployment::SledFilter;
 use nexus_types::deployment::execution;
 use nexus_types::deployment::execution::blueprint_external_dns_config;
@@ -53,8 +55,8 @@ use omicron_common::api::external::{Generation, TufRepoDescription};
 use omicron_common::disk::M2Slot;
 use omicron_common::policy::NEXUS_REDUNDANCY;
 use omicron_common::update::OmicronZoneManifestSource;
-use omicron_repl_utils::run_repl_from_file;
-use omicron_repl_utils::run_repl_on_stdin;
+use omicron_repl_utils::run_repl_from_file_with_input;
+use omicron_repl_utils::run_repl_on_stdin_with_input;
 use omicron_uuid_kinds::GenericUuid;
 use omicron_uuid_kinds::OmicronZoneUuid;
 use omicron_uuid_kinds::ReconfiguratorSimUuid;
@@ -62,10 +64,16 @@ use omicron_uuid_kinds::SledUuid;
 use omicron_uuid_kinds::VnicUuid;
 use omicron_uuid_kinds::{BlueprintUuid, MupdateOverrideUuid};
 use omicron_uuid_kinds::{CollectionUuid, MupdateUuid};
+use serde::Deserialize;
+use serde::Serialize;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::convert::Infallible;
 use std::fmt::{self, Write};
 use std::io::IsTerminal;
+use std::num::NonZeroU8;
+use std::num::NonZeroUsize;
 use std::num::ParseIntError;
 use std::str::FromStr;
 use swrite::{SWrite, swrite, swriteln};
@@ -95,6 +103,9 @@ struct ReconfiguratorSim {
     sim: Simulator,
     // The current state.
     current: ReconfiguratorSimUuid,
+    // The command line that produced each state (other than the root), so that
+    // the history leading to the current state can be shown and replayed.
+    commands: BTreeMap<ReconfiguratorSimUuid, String>,
     // The current system state
     log: slog::Logger,
 }
@@ -104,6 +115,7 @@ impl ReconfiguratorSim {
         Self {
             sim: Simulator::new(&log, seed),
             current: Simulator::ROOT_ID,
+            commands: BTreeMap::new(),
             log,
         }
     }
@@ -114,6 +126,32 @@ impl ReconfiguratorSim {
             .expect("current state should always exist")
     }
 
+    /// Returns the states from the root state up to and including the current
+    /// state.
+    fn history(&self) -> Vec<&SimState> {
+        let mut states = Vec::new();
+        let mut next = Some(self.current);
+        while let Some(id) = next {
+            let state = self
+                .sim
+                .get_state(id)
+                .expect("ancestors of the current state should always exist");
+            next = state.parent();
+            states.push(state);
+        }
+        states.reverse();
+        states
+    }
+
+    /// Returns the command line that produced `state`, falling back to the
+    /// state's description if none was recorded (e.g., for the root state).
+    fn command_for<'a>(&'a self, state: &'a SimState) -> &'a str {
+        self.commands
+            .get(&state.id())
+            .map(String::as_str)
+            .unwrap_or_else(|| state.description())
+    }
+
     fn commit_and_bump(&mut self, description: String, state: SimStateBuilder) {
         let new_id = state.commit(description, &mut self.sim);
         self.current = new_id;
@@ -209,12 +247,15 @@ impl CmdReconfiguratorSim {
         }
 
         if let Some(input_file) = &self.input_file {
-            run_repl_from_file(input_file, &mut |cmd: TopLevelArgs| {
-                process_command(&mut sim, cmd, &log_capture)
-            })
+            run_repl_from_file_with_input(
+                input_file,
+                &mut |cmd: TopLevelArgs, input| {
+                    process_command(&mut sim, cmd, input, &log_capture)
+                },
+            )
         } else {
-            run_repl_on_stdin(&mut |cmd: TopLevelArgs| {
-                process_command(&mut sim, cmd, &log_capture)
+            run_repl_on_stdin_with_input(&mut |cmd: TopLevelArgs, input| {
+                process_command(&mut sim, cmd, input, &log_capture)
             })
         }
     }
@@ -224,15 +265,47 @@ impl CmdReconfiguratorSim {
 fn process_command(
     sim: &mut ReconfiguratorSim,
     cmd: TopLevelArgs,
+    input: &str,
     logs: &LogCapture,
 ) -> anyhow::Result<Option<String>> {
     let TopLevelArgs { command } = cmd;
+    let cmd_result = run_command(sim, command, input);
+
+    for line in logs.take_log_lines() {
+        println!("{line}");
+    }
+
+    cmd_result
+}
+
+/// Parses one "line" of user input the same way the REPL does.
+fn parse_command(input: &str) -> anyhow::Result<TopLevelArgs> {
+    let matches = TopLevelArgs::command()
+        .multicall(true)
+        .try_get_matches_from(input.split_whitespace())?;
+    Ok(TopLevelArgs::from_arg_matches(&matches)?)
+}
+
+/// Runs one command, recording `input` as the command line that produced the
+/// new state (if the command created one).
+fn run_command(
+    sim: &mut ReconfiguratorSim,
+    command: Commands,
+    input: &str,
+) -> anyhow::Result<Option<String>> {
+    // Moving around in the history doesn't produce new states, so there's
+    // nothing to record for these.
+    let record =
+        !matches!(command, Commands::Undo(_) | Commands::SessionLoad(_));
+    let before = sim.current;
+
     let cmd_result = match command {
         Commands::SledList => cmd_sled_list(sim),
         Commands::SledAdd(args) => cmd_sled_add(sim, args),
         Commands::SledRemove(args) => cmd_sled_remove(sim, args),
         Commands::SledShow(args) => cmd_sled_show(sim, args),
         Commands::SledSet(args) => cmd_sled_set(sim, args),
+        Commands::SledExpunge(args) => cmd_sled_expunge(sim, args),
         Commands::SledUpdateInstallDataset(args) => {
             cmd_sled_update_install_dataset(sim, args)
         }
@@ -261,18 +334,24 @@ fn process_command(
         Commands::BlueprintDiff(args) => cmd_blueprint_diff(sim, args),
         Commands::BlueprintDiffDns(args) => cmd_blueprint_diff_dns(sim, args),
         Commands::BlueprintSave(args) => cmd_blueprint_save(sim, args),
+        Commands::BlueprintLoad(args) => cmd_blueprint_load(sim, args),
         Commands::Show => cmd_show(sim),
         Commands::Set(args) => cmd_set(sim, args),
+        Commands::SetTargetVersion(args) => cmd_set_target_version(sim, args),
         Commands::TufAssemble(args) => cmd_tuf_assemble(sim, args),
         Commands::Load(args) => cmd_load(sim, args),
         Commands::LoadExample(args) => cmd_load_example(sim, args),
         Commands::FileContents(args) => cmd_file_contents(args),
         Commands::Save(args) => cmd_save(sim, args),
         Commands::Wipe(args) => cmd_wipe(sim, args),
+        Commands::History => cmd_history(sim),
+        Commands::Undo(args) => cmd_undo(sim, args),
+        Commands::SessionSave(args) => cmd_session_save(sim, args),
+        Commands::SessionLoad(args) => cmd_session_load(sim, args),
     };
 
-    for line in logs.take_log_lines() {
-        println!("{line}");
+    if record && sim.current != before {
+        sim.commands.insert(sim.current, input.to_owned());
     }
 
     cmd_result
@@ -299,6 +378,8 @@ enum Commands {
     SledShow(SledArgs),
     /// set a value on a sled
     SledSet(SledSetArgs),
+    /// expunge a sled (shorthand for `sled-set SLED_ID policy expunged`)
+    SledExpunge(SledExpungeArgs),
     /// update the install dataset on a sled, simulating a mupdate
     SledUpdateInstallDataset(SledUpdateInstallDatasetArgs),
     /// simulate updating the sled's RoT versions
@@ -331,23 +412,29 @@ enum Commands {
     /// run blippy on a blueprint
     BlueprintBlippy(BlueprintArgs),
     /// run planner to generate a new blueprint
+    #[command(visible_alias = "plan")]
     BlueprintPlan(BlueprintPlanArgs),
     /// edit contents of a blueprint directly
     BlueprintEdit(BlueprintEditArgs),
     /// show details about a blueprint
     BlueprintShow(BlueprintArgs),
     /// show differences between two blueprints
+    #[command(visible_alias = "diff")]
     BlueprintDiff(BlueprintDiffArgs),
     /// show differences between a blueprint and a particular DNS version
     BlueprintDiffDns(BlueprintDiffDnsArgs),
     /// write one blueprint to a file
     BlueprintSave(BlueprintSaveArgs),
+    /// load one blueprint from a file (e.g., one exported by omdb)
+    BlueprintLoad(BlueprintLoadArgs),
 
     /// show system properties
     Show,
     /// set system properties
     #[command(subcommand)]
     Set(SetArgs),
+    /// set the system target release (shorthand for `set target-release`)
+    SetTargetVersion(SetTargetVersionArgs),
 
     /// use tufaceous to generate a repo from a manifest
     TufAssemble(TufAssembleArgs),
@@ -362,6 +449,15 @@ enum Commands {
     FileContents(FileContentsArgs),
     /// reset the state of the REPL
     Wipe(WipeArgs),
+
+    /// show the commands that led to the current state
+    History,
+    /// go back to the state before the most recent command(s)
+    Undo(UndoArgs),
+    /// save the commands that led to the current state to a file
+    SessionSave(SessionSaveArgs),
+    /// replace the current session by replaying a saved one
+    SessionLoad(SessionLoadArgs),
 }
 
 #[derive(Debug, Args)]
@@ -398,6 +494,12 @@ struct SledSetArgs {
     command: SledSetCommand,
 }
 
+#[derive(Debug, Args)]
+struct SledExpungeArgs {
+    /// id of the sled
+    sled_id: SledOpt,
+}
+
 #[derive(Debug, Subcommand)]
 enum SledSetCommand {
     /// set the policy for this sled
@@ -750,6 +852,14 @@ enum BlueprintEditCommands {
         /// the minimum target release generation
         generation: Generation,
     },
+    /// restrict the blueprint to changing only the given sleds
+    ///
+    /// Sleds outside the scope are carried over unchanged from the parent
+    /// blueprint.  With no sleds, the blueprint covers the whole fleet.
+    SetScope {
+        /// sleds the blueprint may change
+        sled_ids: Vec<SledOpt>,
+    },
     /// expunge a zone
     ExpungeZone { zone_id: OmicronZoneUuid },
     /// mark an expunged zone ready for cleanup
@@ -1145,6 +1255,16 @@ struct BlueprintSaveArgs {
     blueprint_id: BlueprintIdOpt,
     /// output file
     filename: Utf8PathBuf,
+    /// write the blueprint in the checksummed portable format (as exported
+    /// by `omdb nexus blueprints export`) rather than as plain JSON
+    #[clap(long)]
+    portable: bool,
+}
+
+#[derive(Debug, Args)]
+struct BlueprintLoadArgs {
+    /// input file, either in the portable format or plain JSON
+    filename: Utf8PathBuf,
 }
 
 #[derive(Debug, Args)]
@@ -1154,6 +1274,10 @@ struct BlueprintDiffArgs {
     /// id of the second blueprint, "latest", or "target", or None to mean "the
     /// parent of blueprint1"
     blueprint2_id: Option<BlueprintIdOpt>,
+    /// only show per-sled counts of changes and changed metadata, omitting
+    /// DNS diffs
+    #[arg(long)]
+    summary: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -1175,6 +1299,14 @@ enum SetArgs {
     IgnoreImpossibleMgsUpdatesSince {
         since: SetIgnoreImpossibleMgsUpdatesSinceArgs,
     },
+    /// sleds to drain of discretionary zones together (none to clear)
+    MaintenanceCohort { sleds: Vec<SledOpt> },
+}
+
+#[derive(Debug, Args)]
+struct SetTargetVersionArgs {
+    /// TUF repo containing release artifacts
+    filename: Utf8PathBuf,
 }
 
 #[derive(Debug, Clone)]
@@ -1207,6 +1339,17 @@ struct SetChickenSwitchesArgs {
 pub struct ChickenSwitchesOpts {
     #[clap(long, action = ArgAction::Set)]
     add_zones_with_mupdate_override: Option<bool>,
+
+    #[clap(long, action = ArgAction::Set)]
+    allow_version_skew: Option<bool>,
+
+    /// number of Nexus zones to run (0 to use the policy's)
+    #[clap(long)]
+    target_nexus_zone_count: Option<u8>,
+
+    /// number of internal DNS zones to run (0 to use the policy's)
+    #[clap(long)]
+    target_internal_dns_zone_count: Option<u8>,
 }
 
 impl ChickenSwitchesOpts {
@@ -1218,6 +1361,15 @@ impl ChickenSwitchesOpts {
             add_zones_with_mupdate_override: self
                 .add_zones_with_mupdate_override
                 .unwrap_or(current.add_zones_with_mupdate_override),
+            allow_version_skew: self
+                .allow_version_skew
+                .unwrap_or(current.allow_version_skew),
+            target_nexus_zone_count: self
+                .target_nexus_zone_count
+                .map_or(current.target_nexus_zone_count, NonZeroU8::new),
+            target_internal_dns_zone_count: self
+                .target_internal_dns_zone_count
+                .map_or(current.target_internal_dns_zone_count, NonZeroU8::new),
         };
         (new != *current).then_some(new)
     }
@@ -1347,6 +1499,36 @@ enum WipeCommand {
     Rng,
 }
 
+#[derive(Debug, Args)]
+struct UndoArgs {
+    /// number of commands to undo
+    #[clap(default_value_t = NonZeroUsize::MIN)]
+    count: NonZeroUsize,
+}
+
+#[derive(Debug, Args)]
+struct SessionSaveArgs {
+    /// output file
+    filename: Utf8PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct SessionLoadArgs {
+    /// input file, as written by `session-save`
+    filename: Utf8PathBuf,
+}
+
+/// A saved REPL session: the initial RNG seed, plus the commands that led from
+/// the root state to the state that was current when the session was saved
+///
+/// Replaying the commands from the same seed reproduces the same states.
+/// Commands that read files (e.g., `load`) read them again during replay.
+#[derive(Debug, Deserialize, Serialize)]
+struct SavedSession {
+    seed: String,
+    commands: Vec<String>,
+}
+
 // Command handlers
 
 fn cmd_silo_list(
@@ -1642,6 +1824,21 @@ fn cmd_sled_set(
     }
 }
 
+fn cmd_sled_expunge(
+    sim: &mut ReconfiguratorSim,
+    args: SledExpungeArgs,
+) -> anyhow::Result<Option<String>> {
+    cmd_sled_set(
+        sim,
+        SledSetArgs {
+            sled_id: args.sled_id,
+            command: SledSetCommand::Policy(SledSetPolicyArgs {
+                policy: SledPolicyOpt::Expunged,
+            }),
+        },
+    )
+}
+
 fn cmd_sled_update_install_dataset(
     sim: &mut ReconfiguratorSim,
     args: SledUpdateInstallDatasetArgs,
@@ -2030,8 +2227,17 @@ fn cmd_blueprint_blippy(
     let resolved_id =
         state.system().resolve_blueprint_id(args.blueprint_id.into())?;
     let blueprint = state.system().get_blueprint(&resolved_id)?;
-    let report =
-        Blippy::new(&blueprint).into_report(BlippyReportSortKey::Severity);
+    // Only the policy portion of the planning input matters to blippy, so
+    // avoid `sim.planning_input()`: that fails outright on some of the very
+    // problems (e.g., duplicate external IPs) blippy is meant to report.
+    let planning_input = state
+        .system()
+        .description()
+        .to_planning_input_builder()
+        .context("generating planning input builder")?
+        .build();
+    let report = Blippy::new_with_planning_input(&blueprint, &planning_input)
+        .into_report(BlippyReportSortKey::Severity);
     Ok(Some(format!("{}", report.display())))
 }
 
@@ -2178,6 +2384,21 @@ fn cmd_blueprint_edit(
                 .context("failed to set target release minimum generation")?;
             format!("set target release minimum generation to {generation}")
         }
+        BlueprintEditCommands::SetScope { sled_ids } => {
+            let scope = if sled_ids.is_empty() {
+                BlueprintScope::Fleet
+            } else {
+                BlueprintScope::Sleds(
+                    sled_ids
+                        .iter()
+                        .map(|sled_id| sled_id.to_sled_id(system.description()))
+                        .collect::<anyhow::Result<_>>()?,
+                )
+            };
+            let rv = format!("set scope to {scope}");
+            builder.set_scope(scope);
+            rv
+        }
         BlueprintEditCommands::SetZoneImage { zone_id, image_source } => {
             let sled_id = sled_with_zone(&builder, &zone_id)?;
             let source = BlueprintZoneImageSource::from(image_source);
@@ -2378,6 +2599,10 @@ fn cmd_blueprint_diff(
     };
 
     let sled_diff = blueprint2.diff_since_blueprint(&blueprint1);
+    if args.summary {
+        swriteln!(rv, "{}", sled_diff.display().summary());
+        return Ok(Some(rv));
+    }
     swriteln!(rv, "{}", sled_diff.display());
 
     // Diff'ing DNS is a little trickier.  First, compute what DNS should be for
@@ -2499,13 +2724,45 @@ fn cmd_blueprint_save(
     let blueprint = state.system().get_blueprint(&resolved_id)?;
 
     let output_path = &args.filename;
-    let output_str = serde_json::to_string_pretty(&blueprint)
-        .context("serializing blueprint")?;
-    std::fs::write(&output_path, &output_str)
+    let output_bytes = if args.portable {
+        blueprint.to_portable_bytes().context("serializing blueprint")?
+    } else {
+        serde_json::to_vec_pretty(&blueprint)
+            .context("serializing blueprint")?
+    };
+    std::fs::write(&output_path, &output_bytes)
         .with_context(|| format!("write {:?}", output_path))?;
     Ok(Some(format!("saved {} to {:?}", resolved_id, output_path)))
 }
 
+fn cmd_blueprint_load(
+    sim: &mut ReconfiguratorSim,
+    args: BlueprintLoadArgs,
+) -> anyhow::Result<Option<String>> {
+    let input_path = &args.filename;
+    let contents = std::fs::read(input_path)
+        .with_context(|| format!("read {:?}", input_path))?;
+    // If the file has a valid portable header, insist on the rest of it being
+    // valid too (rather than falling back to plain JSON), so that corruption
+    // is reported as such.
+    let blueprint = if Blueprint::portable_header(&contents).is_ok() {
+        Blueprint::from_portable_bytes(&contents)
+            .with_context(|| format!("parse {:?}", input_path))?
+    } else {
+        serde_json::from_slice::<Blueprint>(&contents)
+            .with_context(|| format!("parse {:?}", input_path))?
+    };
+
+    let mut state = sim.current_state().to_mut();
+    let blueprint_id = blueprint.id;
+    state.system_mut().add_blueprint(blueprint)?;
+    sim.commit_and_bump(
+        format!("reconfigurator-cli blueprint-load: {input_path}"),
+        state,
+    );
+    Ok(Some(format!("loaded blueprint {blueprint_id} from {input_path:?}")))
+}
+
 fn cmd_save(
     sim: &mut ReconfiguratorSim,
     args: SaveArgs,
@@ -2570,6 +2827,106 @@ fn cmd_wipe(
     Ok(Some(output))
 }
 
+fn cmd_history(sim: &mut ReconfiguratorSim) -> anyhow::Result<Option<String>> {
+    let mut s = String::new();
+    for (i, state) in sim.history().into_iter().enumerate() {
+        let target = match state.system().target_blueprint() {
+            Some(target) => target.target_id.to_string(),
+            None => "(none)".to_string(),
+        };
+        swriteln!(
+            s,
+            "{i:>4}  {} (target blueprint: {target})",
+            sim.command_for(state)
+        );
+    }
+    Ok(Some(s))
+}
+
+fn cmd_undo(
+    sim: &mut ReconfiguratorSim,
+    args: UndoArgs,
+) -> anyhow::Result<Option<String>> {
+    let mut s = String::new();
+    let mut state = sim.current_state();
+    for _ in 0..args.count.get() {
+        let parent = state
+            .parent()
+            .context("cannot undo: already at the initial state")?;
+        swriteln!(s, "undid: {}", sim.command_for(state));
+        state = sim
+            .sim
+            .get_state(parent)
+            .expect("ancestors of the current state should always exist");
+    }
+
+    sim.current = state.id();
+    Ok(Some(s))
+}
+
+fn cmd_session_save(
+    sim: &mut ReconfiguratorSim,
+    args: SessionSaveArgs,
+) -> anyhow::Result<Option<String>> {
+    let commands = sim
+        .history()
+        .into_iter()
+        .skip(1)
+        .map(|state| {
+            sim.commands.get(&state.id()).cloned().with_context(|| {
+                format!(
+                    "no command recorded for state {} ({})",
+                    state.id(),
+                    state.description()
+                )
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let ncommands = commands.len();
+    let session =
+        SavedSession { seed: sim.sim.initial_seed().to_owned(), commands };
+
+    let output_path = &args.filename;
+    let output_str = serde_json::to_string_pretty(&session)
+        .context("serializing session")?;
+    std::fs::write(&output_path, &output_str)
+        .with_context(|| format!("write {:?}", output_path))?;
+    Ok(Some(format!(
+        "saved session ({ncommands} commands) to {:?}",
+        output_path
+    )))
+}
+
+fn cmd_session_load(
+    sim: &mut ReconfiguratorSim,
+    args: SessionLoadArgs,
+) -> anyhow::Result<Option<String>> {
+    let input_path = &args.filename;
+    let contents = std::fs::read_to_string(input_path)
+        .with_context(|| format!("read {:?}", input_path))?;
+    let session: SavedSession = serde_json::from_str(&contents)
+        .with_context(|| format!("parsing {:?}", input_path))?;
+
+    // Replay into a fresh simulator, only replacing the current one if every
+    // command succeeds.
+    let mut replayed =
+        ReconfiguratorSim::new(sim.log.clone(), Some(session.seed));
+    for (i, input) in session.commands.iter().enumerate() {
+        let TopLevelArgs { command } = parse_command(input)
+            .with_context(|| format!("parsing command {}: {input:?}", i + 1))?;
+        run_command(&mut replayed, command, input).with_context(|| {
+            format!("replaying command {}: {input:?}", i + 1)
+        })?;
+    }
+
+    let ncommands = session.commands.len();
+    *sim = replayed;
+    Ok(Some(format!(
+        "loaded session from {:?} ({ncommands} commands replayed)",
+        input_path
+    )))
+}
+
 fn cmd_show(sim: &mut ReconfiguratorSim) -> anyhow::Result<Option<String>> {
     let mut s = String::new();
     let state = sim.current_state();
@@ -2725,6 +3082,20 @@ fn cmd_set(
                 humantime::format_rfc3339_millis(since.0.into())
             )
         }
+        SetArgs::MaintenanceCohort { sleds } => {
+            let description = state.system_mut().description_mut();
+            let sled_ids = sleds
+                .iter()
+                .map(|sled| sled.to_sled_id(description))
+                .collect::<anyhow::Result<BTreeSet<_>>>()?;
+            let rv = format!(
+                "maintenance cohort: {:?} -> {:?}",
+                description.get_maintenance_cohort(),
+                sled_ids
+            );
+            description.maintenance_cohort(sled_ids);
+            rv
+        }
     };
 
     sim.commit_and_bump(format!("reconfigurator-cli set: {}", rv), state);
@@ -2810,6 +3181,13 @@ fn extract_tuf_repo_description(
     Ok(description)
 }
 
+fn cmd_set_target_version(
+    sim: &mut ReconfiguratorSim,
+    args: SetTargetVersionArgs,
+) -> anyhow::Result<Option<String>> {
+    cmd_set(sim, SetArgs::TargetRelease { filename: args.filename })
+}
+
 fn cmd_tuf_assemble(
     sim: &ReconfiguratorSim,
     args: TufAssembleArgs,
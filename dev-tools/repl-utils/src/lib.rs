@@ -29,6 +29,16 @@ use subprocess::Exec;
 pub fn run_repl_from_file<C: Parser>(
     input_file: &Utf8Path,
     run_one: &mut dyn FnMut(C) -> anyhow::Result<Option<String>>,
+) -> anyhow::Result<()> {
+    run_repl_from_file_with_input(input_file, &mut |cmd, _input| run_one(cmd))
+}
+
+/// Like [`run_repl_from_file`], but `run_one` is also given the text of the
+/// command (without any comment or `!` shell pipeline), e.g. so that the
+/// caller can record it for later replay
+pub fn run_repl_from_file_with_input<C: Parser>(
+    input_file: &Utf8Path,
+    run_one: &mut dyn FnMut(C, &str) -> anyhow::Result<Option<String>>,
 ) -> anyhow::Result<()> {
     let file = File::open(&input_file)
         .with_context(|| format!("open {:?}", &input_file))?;
@@ -119,13 +129,36 @@ pub fn run_repl_on_stdin<C: Parser>(
     run_repl_on_stdin_customized(ed, &prompt, run_one)
 }
 
+/// Like [`run_repl_on_stdin`], but `run_one` is also given the text of the
+/// command
+///
+/// See docs for [`run_repl_from_file_with_input`]
+pub fn run_repl_on_stdin_with_input<C: Parser>(
+    run_one: &mut dyn FnMut(C, &str) -> anyhow::Result<Option<String>>,
+) -> anyhow::Result<()> {
+    let ed = Reedline::create();
+    let prompt = reedline::DefaultPrompt::new(
+        reedline::DefaultPromptSegment::Empty,
+        reedline::DefaultPromptSegment::Empty,
+    );
+    run_repl_on_stdin_impl(ed, &prompt, run_one)
+}
+
 /// Runs a REPL using stdin/stdout with a customized `Reedline` and `Prompt`
 ///
 /// See docs for [`run_repl_on_stdin`]
 pub fn run_repl_on_stdin_customized<C: Parser>(
-    mut ed: Reedline,
+    ed: Reedline,
     prompt: &dyn Prompt,
     run_one: &mut dyn FnMut(C) -> anyhow::Result<Option<String>>,
+) -> anyhow::Result<()> {
+    run_repl_on_stdin_impl(ed, prompt, &mut |cmd, _input| run_one(cmd))
+}
+
+fn run_repl_on_stdin_impl<C: Parser>(
+    mut ed: Reedline,
+    prompt: &dyn Prompt,
+    run_one: &mut dyn FnMut(C, &str) -> anyhow::Result<Option<String>>,
 ) -> anyhow::Result<()> {
     loop {
         match ed.read_line(prompt) {
@@ -152,7 +185,7 @@ pub fn run_repl_on_stdin_customized<C: Parser>(
 
 fn process_entry<C: Parser>(
     entry: &str,
-    run_one: &mut dyn FnMut(C) -> anyhow::Result<Option<String>>,
+    run_one: &mut dyn FnMut(C, &str) -> anyhow::Result<Option<String>>,
 ) -> LoopResult {
     // If no input was provided, take another lap (print the prompt and accept
     // another line).  This gets handled specially because otherwise clap would
@@ -175,7 +208,8 @@ fn process_entry<C: Parser>(
     // it's good enough for now.
     //
     // SAFETY: There is always at least one element in the iterator.
-    let parts = split.next().expect("element exists").split_whitespace();
+    let input = split.next().expect("element exists").trim();
+    let parts = input.split_whitespace();
 
     let parsed_command = C::command()
         .multicall(true)
@@ -197,7 +231,7 @@ fn process_entry<C: Parser>(
         Ok(cmd) => cmd,
     };
 
-    match run_one(command) {
+    match run_one(command, input) {
         Err(error) => println!("error: {:#}", error),
         Ok(Some(repl_cmd_output)) => {
             if let Some(shell_cmd) = split.next() {
@@ -13,6 +13,7 @@ use slog::info;
 use slog::o;
 use std::net::{SocketAddr, SocketAddrV6};
 use std::path::PathBuf;
+use uuid::Uuid;
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -24,6 +25,13 @@ struct Args {
 
     #[clap(long, action)]
     dns_address: SocketAddr,
+
+    /// ID of the zone this server is running in, used to identify its metrics
+    ///
+    /// Query metrics are only published to oximeter if this is provided and
+    /// the config file has a `metrics` section.
+    #[clap(long, action)]
+    zone_id: Option<Uuid>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -31,6 +39,9 @@ pub struct Config {
     pub log: dropshot::ConfigLogging,
     pub dropshot: dropshot::ConfigDropshot,
     pub storage: dns_server::storage::Config,
+    #[serde(default)]
+    pub query_log: dns_server::dns_server::QueryLogConfig,
+    pub metrics: Option<dns_server::metrics::Config>,
 }
 
 fn main() -> Result<(), anyhow::Error> {
@@ -53,8 +64,10 @@ async fn main_impl() -> Result<(), anyhow::Error> {
         .to_logger("dns-server")
         .context("failed to create logger")?;
 
-    let dns_server_config =
-        dns_server::dns_server::Config { bind_address: args.dns_address };
+    let dns_server_config = dns_server::dns_server::Config {
+        bind_address: args.dns_address,
+        query_log: config.query_log.clone(),
+    };
 
     info!(&log, "config";
         "config" => ?config,
@@ -67,14 +80,32 @@ async fn main_impl() -> Result<(), anyhow::Error> {
     )
     .context("initializing persistent storage")?;
 
-    let (_dns_server, dropshot_server) = dns_server::start_servers(
-        log,
+    let (dns_server, dropshot_server) = dns_server::start_servers(
+        log.clone(),
         store,
         &dns_server_config,
         &config.dropshot,
     )
     .await?;
 
+    // Publish query metrics alongside the HTTP server, on the underlay.
+    let _producer_server = match (&config.metrics, args.zone_id) {
+        (Some(metrics_config), Some(zone_id)) => {
+            Some(dns_server::metrics::start_producer(
+                &log.new(o!("component" => "metrics")),
+                metrics_config,
+                zone_id,
+                (*args.http_address.ip()).into(),
+                dns_server.query_metrics().clone(),
+            )?)
+        }
+        (Some(_), None) => {
+            info!(&log, "not publishing metrics: no zone ID provided");
+            None
+        }
+        (None, _) => None,
+    };
+
     dropshot_server
         .await
         .map_err(|error_message| anyhow!("server exiting: {}", error_message))
@@ -7,6 +7,8 @@
 //! The facilities here handle binding a UDP socket, receiving DNS messages on
 //! that socket, and replying to them.
 
+use crate::metrics::QueryMetrics;
+use crate::metrics::response_code_str;
 use crate::storage;
 use crate::storage::QueryError;
 use crate::storage::Store;
@@ -30,10 +32,12 @@ use internal_dns_types::config::DnsRecord;
 use internal_dns_types::config::Srv;
 use pretty_hex::*;
 use serde::Deserialize;
-use slog::{Logger, debug, error, info, o, trace};
+use slog::{Logger, debug, error, info, o, trace, warn};
 use std::net::SocketAddr;
+use std::num::NonZeroU64;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 use tokio::net::UdpSocket;
 use uuid::Uuid;
@@ -43,6 +47,23 @@ use uuid::Uuid;
 pub struct Config {
     /// The address to listen for DNS requests on
     pub bind_address: SocketAddr,
+    /// Configuration for logging a sample of the queries we answer
+    #[serde(default)]
+    pub query_log: QueryLogConfig,
+}
+
+/// Configuration for logging a sample of the queries the DNS server answers
+///
+/// Every query is logged at the "debug" level, which is far too verbose to
+/// leave on in production. Sampled query logging instead logs a one-line
+/// summary of a fraction of queries at the "info" level.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct QueryLogConfig {
+    /// Log one out of every `sample_interval` queries. If unset, no queries
+    /// are logged this way.
+    #[serde(default)]
+    pub sample_interval: Option<NonZeroU64>,
 }
 
 /// Handle to the DNS server
@@ -50,6 +71,7 @@ pub struct Config {
 /// Dropping this handle shuts down the DNS server.
 pub struct ServerHandle {
     local_address: SocketAddr,
+    metrics: QueryMetrics,
     handle: tokio::task::JoinHandle<anyhow::Result<()>>,
 }
 
@@ -63,6 +85,11 @@ impl ServerHandle {
     pub fn local_address(&self) -> SocketAddr {
         self.local_address
     }
+
+    /// Returns the metrics describing the queries this server has answered
+    pub fn query_metrics(&self) -> &QueryMetrics {
+        &self.metrics
+    }
 }
 
 /// DNS (protocol) server
@@ -73,6 +100,8 @@ pub struct Server {
     log: Logger,
     store: storage::Store,
     server_socket: Arc<UdpSocket>,
+    metrics: QueryMetrics,
+    query_log: QueryLogConfig,
 }
 
 impl Server {
@@ -99,14 +128,28 @@ impl Server {
             "local_address" => ?local_address
         );
 
-        let server = Server { log, store, server_socket };
+        if let Some(sample_interval) = config.query_log.sample_interval {
+            info!(&log, "logging a sample of DNS queries";
+                "sample_interval" => sample_interval.get(),
+            );
+        }
+
+        let metrics = QueryMetrics::new();
+        let server = Server {
+            log,
+            store,
+            server_socket,
+            metrics: metrics.clone(),
+            query_log: config.query_log.clone(),
+        };
         let handle = tokio::task::spawn(server.run());
-        Ok(ServerHandle { local_address, handle })
+        Ok(ServerHandle { local_address, metrics, handle })
     }
 
     async fn run(self) -> anyhow::Result<()> {
         // The guts of the DNS server: read packets from the bound socket and
         // handle them.
+        let mut npackets: u64 = 0;
         loop {
             let mut buf = vec![0u8; 16384];
             let (n, client_addr) = self
@@ -122,13 +165,21 @@ impl Server {
                 "peer_addr" => client_addr.to_string(),
             ));
 
+            let sampled = self
+                .query_log
+                .sample_interval
+                .is_some_and(|interval| npackets % interval.get() == 0);
+            npackets = npackets.wrapping_add(1);
+
             let request = Request {
                 log,
                 store: self.store.clone(),
                 socket: self.server_socket.clone(),
+                metrics: self.metrics.clone(),
                 client_addr,
                 packet: buf,
                 req_id,
+                sampled,
             };
 
             // TODO-robustness We should cap the number of tokio tasks that
@@ -143,13 +194,17 @@ struct Request {
     log: Logger,
     store: Store,
     socket: Arc<UdpSocket>,
+    metrics: QueryMetrics,
     client_addr: SocketAddr,
     packet: Vec<u8>,
     #[allow(dead_code)]
     req_id: Uuid,
+    /// Whether to log a summary of this query (see [`QueryLogConfig`])
+    sampled: bool,
 }
 
 async fn handle_dns_packet(request: Request) {
+    let start = Instant::now();
     let log = &request.log;
     let buf = &request.packet;
 
@@ -166,8 +221,11 @@ async fn handle_dns_packet(request: Request) {
     };
 
     // Handle the message.
-    match handle_dns_message(&request, &mr).await {
-        Ok(_) => (),
+    let mut dns_zone = None;
+    let response_code = match handle_dns_message(&request, &mr, &mut dns_zone)
+        .await
+    {
+        Ok(_) => ResponseCode::NoError,
         Err(error) => {
             let header = Header::response_from_request(mr.header());
             let rb_servfail = MessageResponseBuilder::from_message_request(&mr);
@@ -192,8 +250,28 @@ async fn handle_dns_packet(request: Request) {
                         MessageResponseBuilder::from_message_request(&mr);
                     respond_servfail(&request, rb_servfail, &header).await
                 }
-            };
+            }
         }
+    };
+
+    let latency = start.elapsed();
+    if let Err(error) =
+        request.metrics.record(dns_zone.as_deref(), response_code, latency)
+    {
+        warn!(log, "failed to record query metrics"; "error" => %error);
+    }
+
+    if request.sampled {
+        let query = mr.queries().first().map(|query| query.original());
+        info!(
+            log,
+            "DNS query";
+            "name" => query.map(|query| query.name().to_string()),
+            "query_type" => query.map(|query| query.query_type().to_string()),
+            "dns_zone" => dns_zone,
+            "response_code" => response_code_str(response_code),
+            "latency" => ?latency,
+        );
     }
 }
 
@@ -261,9 +339,13 @@ fn dns_record_to_record(
 }
 
 /// Handle a well-formed, decoded DNS query
+///
+/// If the query is for a name in one of our zones, `dns_zone` is set to that
+/// zone (even if we go on to fail the request).
 async fn handle_dns_message(
     request: &Request,
     mr: &MessageRequest,
+    dns_zone: &mut Option<String>,
 ) -> Result<(), RequestError> {
     let log = &request.log;
     let store = &request.store;
@@ -296,6 +378,7 @@ async fn handle_dns_message(
     };
     let name = query.original().name().clone();
     let answer = store.query(query)?;
+    *dns_zone = Some(answer.zone().to_string());
     let rb = MessageResponseBuilder::from_message_request(mr);
     let mut additional_records = vec![];
 
@@ -432,7 +515,7 @@ async fn respond_nxdomain(
     rb_nxdomain: MessageResponseBuilder<'_>,
     rb_servfail: MessageResponseBuilder<'_>,
     header: &Header,
-) {
+) -> ResponseCode {
     let log = &request.log;
     let mut mresp = rb_nxdomain.error_msg(&header, ResponseCode::NXDomain);
 
@@ -459,8 +542,10 @@ async fn respond_nxdomain(
             "switching to SERVFAIL after failure to encode NXDOMAIN ({:#})",
             error
         );
-        respond_servfail(request, rb_servfail, header).await;
+        return respond_servfail(request, rb_servfail, header).await;
     }
+
+    ResponseCode::NXDomain
 }
 
 /// Respond to a DNS query with a SERVFAIL error
@@ -472,11 +557,12 @@ async fn respond_servfail(
     request: &Request,
     rb: MessageResponseBuilder<'_>,
     header: &Header,
-) {
+) -> ResponseCode {
     let mresp = rb.error_msg(header, ResponseCode::ServFail);
     if let Err(error) = encode_and_send(request, mresp, "SERVFAIL").await {
         error!(&request.log, "failed to send SERVFAIL: {:#}", error);
     }
+    ResponseCode::ServFail
 }
 
 /// Encode the given message (which might describe an error or a collection of
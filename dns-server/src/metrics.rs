@@ -0,0 +1,310 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Metrics describing the DNS queries answered by this server
+//!
+//! The DNS server tracks the number of queries it answers (by DNS zone and
+//! response code) and how long it takes to answer them (by DNS zone) in a
+//! [`QueryMetrics`]. When configured to do so, the server program publishes
+//! these to oximeter with [`start_producer()`].
+
+use anyhow::Context;
+use hickory_proto::op::ResponseCode;
+use omicron_common::api::internal::nexus::ProducerEndpoint;
+use omicron_common::api::internal::nexus::ProducerKind;
+use oximeter::MetricsError;
+use oximeter::Producer;
+use oximeter::Sample;
+use oximeter::histogram::Histogram;
+use oximeter::histogram::Record;
+use oximeter::types::Cumulative;
+use oximeter::types::ProducerRegistry;
+use serde::Deserialize;
+use slog::Logger;
+use slog::info;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use uuid::Uuid;
+
+oximeter::use_timeseries!("dns-server.toml");
+pub use self::dns_server::DnsServer;
+pub use self::dns_server::Queries;
+pub use self::dns_server::QueryLatency;
+
+/// The `dns_zone` reported for queries for names outside of any DNS zone we
+/// serve.
+///
+/// We don't report the zone these queries were for because clients can make
+/// the set of such zones arbitrarily large.
+pub const NO_ZONE: &str = "none";
+
+/// The interval on which we ask oximeter to collect our metrics.
+const COLLECTION_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The maximum size of requests to the metric producer server.
+const METRIC_REQUEST_MAX_SIZE: usize = 10 * 1024 * 1024;
+
+/// The lower limit of the query latency histogram, as a power of 10.
+///
+/// Latencies are in nanoseconds, so this is 10us.
+const LATENCY_START_POWER: u16 = 4;
+
+/// The upper limit of the query latency histogram, as a power of 10.
+///
+/// Latencies are in nanoseconds, so this is 10s.
+const LATENCY_END_POWER: u16 = 10;
+
+/// Which kind of DNS server this is
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsServerKind {
+    Internal,
+    External,
+}
+
+impl fmt::Display for DnsServerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnsServerKind::Internal => f.write_str("internal"),
+            DnsServerKind::External => f.write_str("external"),
+        }
+    }
+}
+
+/// Configuration for publishing query metrics to oximeter
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Which kind of DNS server this is, reported as the `kind` of each of our
+    /// timeseries.
+    pub kind: DnsServerKind,
+
+    /// Override the Nexus address used to register the metric producer. This
+    /// is intended for use in development and testing.
+    ///
+    /// If this is not present, Nexus is discovered through internal DNS.
+    #[serde(default)]
+    pub dev_nexus_address: Option<SocketAddr>,
+}
+
+/// Returns the name of a response code as it appears in DNS tooling (and in
+/// the `response_code` field of our timeseries), e.g., `"NXDOMAIN"`.
+pub fn response_code_str(response_code: ResponseCode) -> &'static str {
+    match response_code {
+        ResponseCode::NoError => "NOERROR",
+        ResponseCode::FormErr => "FORMERR",
+        ResponseCode::ServFail => "SERVFAIL",
+        ResponseCode::NXDomain => "NXDOMAIN",
+        ResponseCode::NotImp => "NOTIMP",
+        ResponseCode::Refused => "REFUSED",
+        // We don't currently send any other response codes.
+        _ => "OTHER",
+    }
+}
+
+/// Tracks the DNS queries answered by a DNS server
+///
+/// This is cheap to clone; all clones share the same counters.
+#[derive(Clone, Debug)]
+pub struct QueryMetrics {
+    inner: Arc<Mutex<QueryMetricsInner>>,
+    /// The histogram used to track latencies.
+    ///
+    /// We store it here to clone as we see queries for new zones.
+    histogram: Histogram<u64>,
+}
+
+#[derive(Debug, Default)]
+struct QueryMetricsInner {
+    /// Query counts, by DNS zone and response code
+    queries: BTreeMap<(String, &'static str), Queries>,
+    /// Query latencies, by DNS zone
+    latencies: BTreeMap<String, QueryLatency>,
+}
+
+impl Default for QueryMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueryMetrics {
+    pub fn new() -> Self {
+        let histogram =
+            Histogram::span_decades(LATENCY_START_POWER, LATENCY_END_POWER)
+                .expect("latency histogram bins are valid");
+        QueryMetrics {
+            inner: Arc::new(Mutex::new(QueryMetricsInner::default())),
+            histogram,
+        }
+    }
+
+    /// Record that we answered a query for a name in `dns_zone` (`None` if we
+    /// are not authoritative for the name) with `response_code`, taking
+    /// `latency` to do so.
+    pub fn record(
+        &self,
+        dns_zone: Option<&str>,
+        response_code: ResponseCode,
+        latency: Duration,
+    ) -> Result<(), MetricsError> {
+        let dns_zone = dns_zone.unwrap_or(NO_ZONE);
+        let response_code = response_code_str(response_code);
+        let mut inner = self.inner.lock().unwrap();
+
+        inner
+            .queries
+            .entry((dns_zone.to_string(), response_code))
+            .or_insert_with(|| Queries {
+                dns_zone: dns_zone.to_string().into(),
+                response_code: response_code.into(),
+                datum: Cumulative::new(0),
+            })
+            .datum
+            .increment();
+
+        inner
+            .latencies
+            .entry(dns_zone.to_string())
+            .or_insert_with(|| QueryLatency {
+                dns_zone: dns_zone.to_string().into(),
+                datum: self.histogram.clone(),
+            })
+            .datum
+            .sample(latency.as_nanos() as _)
+            .map_err(MetricsError::from)
+    }
+
+    /// Returns the number of queries answered for names in `dns_zone` with
+    /// `response_code`.
+    pub fn query_count(
+        &self,
+        dns_zone: &str,
+        response_code: ResponseCode,
+    ) -> u64 {
+        let response_code = response_code_str(response_code);
+        self.inner
+            .lock()
+            .unwrap()
+            .queries
+            .get(&(dns_zone.to_string(), response_code))
+            .map_or(0, |queries| queries.datum.value())
+    }
+}
+
+/// Produces samples from a [`QueryMetrics`] for a particular DNS server
+#[derive(Clone, Debug)]
+struct QueryMetricsProducer {
+    target: DnsServer,
+    metrics: QueryMetrics,
+}
+
+impl Producer for QueryMetricsProducer {
+    fn produce(
+        &mut self,
+    ) -> Result<Box<dyn Iterator<Item = Sample> + 'static>, MetricsError> {
+        let inner = self.metrics.inner.lock().unwrap();
+        let mut samples =
+            Vec::with_capacity(inner.queries.len() + inner.latencies.len());
+        for queries in inner.queries.values() {
+            samples.push(Sample::new(&self.target, queries)?);
+        }
+        for latency in inner.latencies.values() {
+            samples.push(Sample::new(&self.target, latency)?);
+        }
+        Ok(Box::new(samples.into_iter()))
+    }
+}
+
+/// Starts an oximeter producer server publishing `metrics` for the DNS server
+/// running in the zone `zone_id`.
+///
+/// The producer server listens on an ephemeral port on `ip` and registers
+/// itself with Nexus in the background, so this does not wait for Nexus to be
+/// available.
+pub fn start_producer(
+    log: &Logger,
+    config: &Config,
+    zone_id: Uuid,
+    ip: IpAddr,
+    metrics: QueryMetrics,
+) -> anyhow::Result<oximeter_producer::Server> {
+    let target =
+        DnsServer { id: zone_id, kind: config.kind.to_string().into() };
+    let registry = ProducerRegistry::with_id(zone_id);
+    registry
+        .register_producer(QueryMetricsProducer { target, metrics })
+        .context("registering query metrics")?;
+
+    let producer_config = oximeter_producer::Config {
+        server_info: ProducerEndpoint {
+            id: zone_id,
+            kind: ProducerKind::Service,
+            address: SocketAddr::new(ip, 0),
+            interval: COLLECTION_INTERVAL,
+        },
+        registration_address: config.dev_nexus_address,
+        default_request_body_max_bytes: METRIC_REQUEST_MAX_SIZE,
+        log: oximeter_producer::LogConfig::Logger(log.clone()),
+    };
+    let server =
+        oximeter_producer::Server::with_registry(registry, &producer_config)
+            .context("starting metric producer server")?;
+    info!(
+        log,
+        "started metric producer server";
+        "address" => %server.address(),
+        "kind" => %config.kind,
+    );
+    Ok(server)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_query_metrics() {
+        let metrics = QueryMetrics::new();
+        let zone = "control-plane.oxide.internal";
+        metrics
+            .record(Some(zone), ResponseCode::NoError, Duration::from_micros(50))
+            .unwrap();
+        metrics
+            .record(Some(zone), ResponseCode::NoError, Duration::from_micros(70))
+            .unwrap();
+        metrics
+            .record(Some(zone), ResponseCode::NXDomain, Duration::from_millis(1))
+            .unwrap();
+        metrics
+            .record(None, ResponseCode::ServFail, Duration::from_micros(20))
+            .unwrap();
+
+        assert_eq!(metrics.query_count(zone, ResponseCode::NoError), 2);
+        assert_eq!(metrics.query_count(zone, ResponseCode::NXDomain), 1);
+        assert_eq!(metrics.query_count(zone, ResponseCode::ServFail), 0);
+        assert_eq!(metrics.query_count(NO_ZONE, ResponseCode::ServFail), 1);
+
+        // Each (zone, response code) pair gets a count, and each zone gets a
+        // latency histogram.
+        let mut producer = QueryMetricsProducer {
+            target: DnsServer {
+                id: Uuid::new_v4(),
+                kind: DnsServerKind::Internal.to_string().into(),
+            },
+            metrics: metrics.clone(),
+        };
+        let samples: Vec<_> = producer.produce().unwrap().collect();
+        let count = |name: &str| {
+            samples.iter().filter(|s| s.timeseries_name == name).count()
+        };
+        assert_eq!(count("dns_server:queries"), 3);
+        assert_eq!(count("dns_server:query_latency"), 2);
+    }
+}
@@ -884,6 +884,11 @@ pub(crate) struct Answer {
 }
 
 impl Answer {
+    /// Returns the zone which provided this answer
+    pub fn zone(&self) -> &str {
+        &self.zone
+    }
+
     pub fn queried_fqdn(&self) -> String {
         if let Some(name) = self.name.as_ref() {
             format!("{}.{}", name, self.zone)
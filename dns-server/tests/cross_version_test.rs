@@ -225,6 +225,7 @@ async fn init_client_server(
     // launch a dns server
     let dns_server_config = dns_server::dns_server::Config {
         bind_address: "[::1]:0".parse().unwrap(),
+        query_log: Default::default(),
     };
     let (dns_server, dropshot_server) = dns_server::start_servers(
         log.clone(),
@@ -66,10 +66,12 @@ async fn instance_launch() -> Result<()> {
             hostname: "localshark".parse().unwrap(), // 🦈
             memory: ByteCount(1024 * 1024 * 1024),
             ncpus: InstanceCpuCount(2),
-            boot_disk: Some(InstanceDiskAttachment::Attach {
+            boot_order: vec![oxide_client::types::NameOrId::Name(
+                disk_name.clone(),
+            )],
+            disks: vec![InstanceDiskAttachment::Attach {
                 name: disk_name.clone(),
-            }),
-            disks: Vec::new(),
+            }],
             network_interfaces: InstanceNetworkInterfaceAttachment::Default,
             external_ips: vec![ExternalIpCreate::Ephemeral { pool: None }],
             user_data: String::new(),
@@ -79,6 +81,8 @@ async fn instance_launch() -> Result<()> {
             start: true,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         })
         .send()
         .await?;
@@ -92,6 +96,7 @@ async fn instance_launch() -> Result<()> {
         .await?
         .items
         .iter()
+        .map(|eip| &eip.external_ip)
         .find(|eip| matches!(eip, ExternalIp::Ephemeral { .. }))
         .context("no external IPs")?
         .clone();
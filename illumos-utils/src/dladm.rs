@@ -85,6 +85,25 @@ pub struct GetVnicError {
     err: ExecutionError,
 }
 
+/// Errors returned from [`Dladm::list_vnics_with_properties`].
+#[derive(thiserror::Error, Debug)]
+pub enum ListVnicsError {
+    #[error("Failed to list vnics")]
+    Execution(#[from] ExecutionError),
+
+    #[error("Failed to parse dladm output line {line:?}: {reason}")]
+    Parse { line: String, reason: String },
+}
+
+/// Errors returned from [`Mtu::new`].
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error(
+    "MTU {0} is out of range (must be between {min} and {max})",
+    min = Mtu::MIN.0,
+    max = Mtu::MAX.0,
+)]
+pub struct InvalidMtuError(pub usize);
+
 /// Errors returned from [`Dladm::get_simulated_tfports`].
 #[derive(thiserror::Error, Debug)]
 #[error("Failed to get simnets")]
@@ -153,6 +172,106 @@ pub struct Etherstub(pub String);
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct EtherstubVnic(pub String);
 
+/// The MTU of a VNIC created by [`Api::create_vnic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Mtu(usize);
+
+impl Mtu {
+    /// The smallest MTU we'll configure: the minimum required by IPv6.
+    pub const MIN: Mtu = Mtu(1280);
+
+    /// The largest MTU we'll configure, matching the underlay's jumbo frames.
+    pub const MAX: Mtu = Mtu(9000);
+
+    /// The MTU of links on the underlay network.
+    pub const UNDERLAY: Mtu = Mtu(9000);
+
+    /// The MTU of links on the bootstrap network.
+    pub const BOOTSTRAP: Mtu = Mtu(1500);
+
+    pub fn new(mtu: usize) -> Result<Self, InvalidMtuError> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&mtu) {
+            Ok(Self(mtu))
+        } else {
+            Err(InvalidMtuError(mtu))
+        }
+    }
+
+    pub fn get(&self) -> usize {
+        self.0
+    }
+}
+
+impl std::fmt::Display for Mtu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Options for creating a VNIC with [`Api::create_vnic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VnicOptions {
+    /// The VNIC's MTU.
+    pub mtu: Mtu,
+    /// A unicast MAC address for the VNIC; if `None`, one is chosen randomly.
+    pub mac: Option<MacAddr>,
+    /// A VLAN ID with which to tag the VNIC's traffic, if any.
+    pub vlan: Option<VlanID>,
+}
+
+impl VnicOptions {
+    /// Options for a VNIC with the given MTU, a random MAC address, and no
+    /// VLAN tagging.
+    pub fn new(mtu: Mtu) -> Self {
+        Self { mtu, mac: None, vlan: None }
+    }
+
+    pub fn mac(mut self, mac: MacAddr) -> Self {
+        self.mac = Some(mac);
+        self
+    }
+
+    pub fn vlan(mut self, vlan: VlanID) -> Self {
+        self.vlan = Some(vlan);
+        self
+    }
+
+    /// The arguments to `dladm create-vnic` that apply these options.
+    fn create_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(mac) = self.mac {
+            args.push("-m".to_string());
+            args.push(mac.0.to_string());
+        }
+        if let Some(vlan) = self.vlan {
+            args.push("-v".to_string());
+            args.push(vlan.to_string());
+        }
+        args.push("-p".to_string());
+        args.push(format!("mtu={}", self.mtu));
+        args
+    }
+}
+
+/// The properties of an existing VNIC, as reported by
+/// [`Dladm::list_vnics_with_properties`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VnicProperties {
+    /// The name of the VNIC.
+    pub name: String,
+    /// The name of the link the VNIC was created over.
+    pub over: String,
+    /// The VNIC's MAC address.
+    pub mac: MacAddr,
+    /// The VLAN ID with which the VNIC's traffic is tagged, if any.
+    pub vlan: Option<VlanID>,
+    /// The VNIC's MTU.
+    ///
+    /// This is not an [`Mtu`], since VNICs we didn't create may have MTUs we
+    /// wouldn't choose.
+    pub mtu: usize,
+}
+
 /// Identifies that an object may be used to create a VNIC.
 pub trait VnicSource: Send + Sync {
     fn name(&self) -> &str;
@@ -180,18 +299,14 @@ pub struct Dladm(());
 pub trait Api: Send + Sync {
     /// Creates a new VNIC atop a physical device.
     ///
-    /// * `physical`: The physical link on top of which a device will be
-    /// created.
+    /// * `source`: The link on top of which a device will be created.
     /// * `vnic_name`: Exact name of the VNIC to be created.
-    /// * `mac`: An optional unicast MAC address for the newly created NIC.
-    /// * `vlan`: An optional VLAN ID for VLAN tagging.
+    /// * `options`: The VNIC's MTU, and optionally its MAC address and VLAN.
     async fn create_vnic(
         &self,
         source: &(dyn VnicSource + 'static),
         vnic_name: &str,
-        mac: Option<MacAddr>,
-        vlan: Option<VlanID>,
-        mtu: usize,
+        options: VnicOptions,
     ) -> Result<(), CreateVnicError> {
         let mut command = Command::new(PFEXEC);
         let mut args = vec![
@@ -201,20 +316,7 @@ pub trait Api: Send + Sync {
             "-l".to_string(),
             source.name().to_string(),
         ];
-
-        if let Some(mac) = mac {
-            args.push("-m".to_string());
-            args.push(mac.0.to_string());
-        }
-
-        if let Some(vlan) = vlan {
-            args.push("-v".to_string());
-            args.push(vlan.to_string());
-        }
-
-        args.push("-p".to_string());
-        args.push(format!("mtu={mtu}"));
-
+        args.extend(options.create_args());
         args.push(vnic_name.to_string());
 
         let cmd = command.args(&args);
@@ -229,7 +331,7 @@ pub trait Api: Send + Sync {
         //
         // See https://www.illumos.org/issues/15695 for the illumos bug.
         let mut command = Command::new(PFEXEC);
-        let prop = format!("mtu={}", mtu);
+        let prop = format!("mtu={}", options.mtu);
         let cmd = command.args(&[
             DLADM,
             "set-linkprop",
@@ -316,15 +418,19 @@ impl Dladm {
         source: &Etherstub,
     ) -> Result<EtherstubVnic, CreateVnicError> {
         let (vnic_name, mtu) = match source.0.as_str() {
-            UNDERLAY_ETHERSTUB_NAME => (UNDERLAY_ETHERSTUB_VNIC_NAME, 9000),
-            BOOTSTRAP_ETHERSTUB_NAME => (BOOTSTRAP_ETHERSTUB_VNIC_NAME, 1500),
+            UNDERLAY_ETHERSTUB_NAME => {
+                (UNDERLAY_ETHERSTUB_VNIC_NAME, Mtu::UNDERLAY)
+            }
+            BOOTSTRAP_ETHERSTUB_NAME => {
+                (BOOTSTRAP_ETHERSTUB_VNIC_NAME, Mtu::BOOTSTRAP)
+            }
             _ => unreachable!(),
         };
         if let Ok(vnic) = Self::get_etherstub_vnic(vnic_name).await {
             return Ok(vnic);
         }
         Self::real_api()
-            .create_vnic(source, vnic_name, None, None, mtu)
+            .create_vnic(source, vnic_name, VnicOptions::new(mtu))
             .await?;
         Ok(EtherstubVnic(vnic_name.to_string()))
     }
@@ -462,6 +568,31 @@ impl Dladm {
         Ok(vnics)
     }
 
+    /// Returns the VNICs that may be managed by the Sled Agent, along with
+    /// their properties.
+    pub async fn list_vnics_with_properties()
+    -> Result<Vec<VnicProperties>, ListVnicsError> {
+        let mut command = Command::new(PFEXEC);
+        let cmd = command.args(&[
+            DLADM,
+            "show-vnic",
+            "-p",
+            "-o",
+            "LINK,OVER,MACADDRESS,VID",
+        ]);
+        let vnics = execute_async(cmd).await?;
+
+        // `show-vnic` doesn't report MTUs, so get those from `show-link`.
+        let mut command = Command::new(PFEXEC);
+        let cmd = command.args(&[DLADM, "show-link", "-p", "-o", "LINK,MTU"]);
+        let links = execute_async(cmd).await?;
+
+        parse_vnic_properties(
+            &String::from_utf8_lossy(&vnics.stdout),
+            &String::from_utf8_lossy(&links.stdout),
+        )
+    }
+
     /// Returns simnet links masquerading as tfport devices
     pub async fn get_simulated_tfports() -> Result<Vec<String>, GetSimnetError>
     {
@@ -548,3 +679,151 @@ impl Dladm {
         Ok(())
     }
 }
+
+/// Splits a line of `dladm -p` output into its fields.
+///
+/// Fields are separated by `:`; colons within a field (as in MAC addresses)
+/// are escaped with a backslash.
+fn split_parseable_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    fields.last_mut().unwrap().push(escaped);
+                }
+            }
+            ':' => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Combines the output of `dladm show-vnic -p -o LINK,OVER,MACADDRESS,VID`
+/// and `dladm show-link -p -o LINK,MTU` into the properties of each VNIC that
+/// the Sled Agent may manage.
+fn parse_vnic_properties(
+    show_vnic: &str,
+    show_link: &str,
+) -> Result<Vec<VnicProperties>, ListVnicsError> {
+    let parse_err = |line: &str, reason: String| ListVnicsError::Parse {
+        line: line.to_string(),
+        reason,
+    };
+
+    let mut mtus = std::collections::BTreeMap::new();
+    for line in show_link.lines() {
+        let [link, mtu] = <[String; 2]>::try_from(split_parseable_fields(line))
+            .map_err(|fields| {
+                parse_err(
+                    line,
+                    format!("expected 2 fields, got {}", fields.len()),
+                )
+            })?;
+        let mtu = mtu
+            .parse::<usize>()
+            .map_err(|err| parse_err(line, format!("invalid MTU: {err}")))?;
+        mtus.insert(link, mtu);
+    }
+
+    let mut vnics = Vec::new();
+    for line in show_vnic.lines() {
+        let [name, over, mac, vid] = <[String; 4]>::try_from(
+            split_parseable_fields(line),
+        )
+        .map_err(|fields| {
+            parse_err(line, format!("expected 4 fields, got {}", fields.len()))
+        })?;
+        // Ensure this is a kind of VNIC that the sled agent could be
+        // responsible for.
+        if LinkKind::from_name(&name).is_none() {
+            continue;
+        }
+        // `dladm` omits leading zeros from each octet, which `MacAddr`
+        // accepts.
+        let mac = MacAddr::from_str(&mac)
+            .map_err(|err| parse_err(line, format!("invalid MAC: {err}")))?;
+        // A VID of 0 means the VNIC isn't tagged.
+        let vlan = match vid.as_str() {
+            "0" => None,
+            vid => Some(VlanID::from_str(vid).map_err(|err| {
+                parse_err(line, format!("invalid VLAN ID: {err}"))
+            })?),
+        };
+        let mtu = *mtus.get(&name).ok_or_else(|| {
+            parse_err(line, "no MTU reported for VNIC".to_string())
+        })?;
+        vnics.push(VnicProperties { name, over, mac, vlan, mtu });
+    }
+    Ok(vnics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mtu_bounds() {
+        assert_eq!(Mtu::new(1500), Ok(Mtu::BOOTSTRAP));
+        assert_eq!(Mtu::new(9000), Ok(Mtu::UNDERLAY));
+        assert_eq!(Mtu::new(1279), Err(InvalidMtuError(1279)));
+        assert_eq!(Mtu::new(9001), Err(InvalidMtuError(9001)));
+    }
+
+    #[test]
+    fn test_vnic_create_args() {
+        let options = VnicOptions::new(Mtu::UNDERLAY);
+        assert_eq!(options.create_args(), ["-p", "mtu=9000"]);
+
+        let options = VnicOptions::new(Mtu::BOOTSTRAP)
+            .mac("A8:40:25:00:00:01".parse().unwrap())
+            .vlan(VlanID::new(100).unwrap());
+        assert_eq!(
+            options.create_args(),
+            ["-m", "A8:40:25:00:00:01", "-v", "100", "-p", "mtu=1500"]
+        );
+    }
+
+    #[test]
+    fn test_parse_vnic_properties() {
+        let show_vnic = "\
+oxControlService0:underlay_stub0:2\\:8\\:20\\:a\\:b\\:c:0
+oxBootstrap0:bootstrap_stub0:a8\\:40\\:25\\:ff\\:80\\:1:100
+somebodyelses0:cxgbe0:2\\:8\\:20\\:0\\:0\\:1:0
+";
+        let show_link = "\
+cxgbe0:9000
+oxControlService0:9000
+oxBootstrap0:1500
+somebodyelses0:576
+";
+        let vnics = parse_vnic_properties(show_vnic, show_link)
+            .expect("parsed dladm output");
+        assert_eq!(
+            vnics,
+            [
+                VnicProperties {
+                    name: "oxControlService0".to_string(),
+                    over: "underlay_stub0".to_string(),
+                    mac: "02:08:20:0a:0b:0c".parse().unwrap(),
+                    vlan: None,
+                    mtu: 9000,
+                },
+                VnicProperties {
+                    name: "oxBootstrap0".to_string(),
+                    over: "bootstrap_stub0".to_string(),
+                    mac: "a8:40:25:ff:80:01".parse().unwrap(),
+                    vlan: Some(VlanID::new(100).unwrap()),
+                    mtu: 1500,
+                },
+            ]
+        );
+
+        // A VNIC whose MTU isn't reported is an error.
+        let err = parse_vnic_properties(show_vnic, "cxgbe0:9000\n")
+            .expect_err("missing MTU");
+        assert!(matches!(err, ListVnicsError::Parse { .. }), "{err}");
+    }
+}
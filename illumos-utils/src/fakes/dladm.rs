@@ -6,10 +6,9 @@ use crate::dladm::Api;
 use crate::dladm::CreateVnicError;
 use crate::dladm::DeleteVnicError;
 use crate::dladm::FindPhysicalLinkError;
+use crate::dladm::VnicOptions;
 use crate::dladm::VnicSource;
 use crate::link::Link;
-use omicron_common::api::external::MacAddr;
-use omicron_common::vlan::VlanID;
 use std::sync::Arc;
 
 /// A fake implementation of [crate::dladm::Dladm].
@@ -30,9 +29,7 @@ impl Api for Dladm {
         &self,
         _source: &(dyn VnicSource + 'static),
         _vnic_name: &str,
-        _mac: Option<MacAddr>,
-        _vlan: Option<VlanID>,
-        _mtu: usize,
+        _options: VnicOptions,
     ) -> Result<(), CreateVnicError> {
         Ok(())
     }
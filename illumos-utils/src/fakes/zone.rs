@@ -41,6 +41,7 @@ impl Api for Zones {
         _devices: &[zone::Device],
         _links: Vec<String>,
         _limit_priv: Vec<String>,
+        _resource_caps: crate::zone::ZoneResourceCaps,
     ) -> Result<(), crate::zone::AdmError> {
         Ok(())
     }
@@ -6,8 +6,8 @@
 
 use crate::destructor::{Deletable, Destructor};
 use crate::dladm::{
-    CreateVnicError, DeleteVnicError, VNIC_PREFIX, VNIC_PREFIX_BOOTSTRAP,
-    VNIC_PREFIX_CONTROL, VnicSource,
+    CreateVnicError, DeleteVnicError, Mtu, VNIC_PREFIX, VNIC_PREFIX_BOOTSTRAP,
+    VNIC_PREFIX_CONTROL, VnicOptions, VnicSource,
 };
 use omicron_common::api::external::MacAddr;
 use std::sync::{
@@ -64,7 +64,9 @@ impl<DL: VnicSource + Clone> VnicAllocator<DL> {
         let name = allocator.next();
         debug_assert!(name.starts_with(VNIC_PREFIX));
         debug_assert!(name.starts_with(VNIC_PREFIX_CONTROL));
-        self.dladm.create_vnic(&self.data_link, &name, mac, None, 9000).await?;
+        let mut options = VnicOptions::new(Mtu::UNDERLAY);
+        options.mac = mac;
+        self.dladm.create_vnic(&self.data_link, &name, options).await?;
         Ok(Link {
             name,
             deleted: false,
@@ -104,7 +106,11 @@ impl<DL: VnicSource + Clone> VnicAllocator<DL> {
     pub async fn new_bootstrap(&self) -> Result<Link, CreateVnicError> {
         let name = self.next();
         self.dladm
-            .create_vnic(&self.data_link, &name, None, None, 1500)
+            .create_vnic(
+                &self.data_link,
+                &name,
+                VnicOptions::new(Mtu::BOOTSTRAP),
+            )
             .await?;
         Ok(Link {
             name,
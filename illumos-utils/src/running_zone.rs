@@ -12,6 +12,7 @@ use crate::dladm::Etherstub;
 use crate::link::{Link, VnicAllocator};
 use crate::opte::{Port, PortTicket};
 use crate::zone::AddressRequest;
+use crate::zone::ZoneResourceCaps;
 use crate::zone::Zones;
 use crate::zpool::{PathInPool, ZpoolOrRamdisk};
 use camino::{Utf8Path, Utf8PathBuf};
@@ -1089,6 +1090,9 @@ pub struct ZoneBuilder<'a> {
     links: Option<Vec<Link>>,
     /// The maximum set of privileges any process in this zone can obtain.
     limit_priv: Option<Vec<String>>,
+    /// Caps on the resources the zone may use. (optional)
+    // actually optional (as above)
+    resource_caps: Option<ZoneResourceCaps>,
     /// For unit tests only: if `Some`, then no actual zones will be installed
     /// by this builder, and minimal facsimiles of them will be placed in
     /// temporary directories according to the contents of the provided
@@ -1190,6 +1194,12 @@ impl<'a> ZoneBuilder<'a> {
         self
     }
 
+    /// Caps on the resources the zone may use. (optional)
+    pub fn with_resource_caps(mut self, caps: ZoneResourceCaps) -> Self {
+        self.resource_caps = Some(caps);
+        self
+    }
+
     // (used in unit tests)
     async fn fake_install(mut self) -> Result<InstalledZone, InstallZoneError> {
         let zones_api = self.zones_api.take().unwrap();
@@ -1259,6 +1269,7 @@ impl<'a> ZoneBuilder<'a> {
             bootstrap_vnic,
             links: Some(links),
             limit_priv: Some(limit_priv),
+            resource_caps,
             ..
         } = self
         else {
@@ -1317,6 +1328,7 @@ impl<'a> ZoneBuilder<'a> {
                 devices,
                 net_device_names,
                 limit_priv,
+                resource_caps.unwrap_or_default(),
             )
             .await
             .map_err(|err| InstallZoneError::InstallZone {
@@ -56,9 +56,13 @@ pub enum DestroyDatasetErrorVariant {
     NotFound,
     #[error(transparent)]
     Other(crate::ExecutionError),
+    #[error("Unexpected line in 'zfs destroy' output: {0:?}")]
+    UnexpectedLine(String),
 }
 
-/// Error returned by [`Zfs::destroy_dataset`].
+/// Error returned by [`Zfs::destroy_dataset`],
+/// [`Zfs::destroy_dataset_with_options`], and
+/// [`Zfs::destroy_dataset_dry_run`].
 #[derive(thiserror::Error, Debug)]
 #[error("Could not destroy dataset {name}")]
 pub struct DestroyDatasetError {
@@ -67,6 +71,20 @@ pub struct DestroyDatasetError {
     pub err: DestroyDatasetErrorVariant,
 }
 
+impl DestroyDatasetError {
+    fn from_execution(name: &str, err: crate::ExecutionError) -> Self {
+        let variant = match err {
+            crate::ExecutionError::CommandFailure(info)
+                if info.stderr.contains("does not exist") =>
+            {
+                DestroyDatasetErrorVariant::NotFound
+            }
+            _ => DestroyDatasetErrorVariant::Other(err),
+        };
+        DestroyDatasetError { name: name.to_string(), err: variant }
+    }
+}
+
 /// When the sled agent creates a mountpoint directory for a dataset,
 /// it needs that directory to be empty.
 ///
@@ -186,6 +204,9 @@ enum GetValueErrorRaw {
 
     #[error("No value found with that name")]
     MissingValue,
+
+    #[error("Unexpected value {0:?}")]
+    UnexpectedValue(String),
 }
 
 /// Error returned by [`Zfs::get_oxide_value`] or [`Zfs::get_value`].
@@ -198,10 +219,40 @@ pub struct GetValueError {
     err: GetValueErrorRaw,
 }
 
+/// Error returned by [`Zfs::upgrade_dataset`].
+#[derive(thiserror::Error, Debug)]
+#[error("Failed to upgrade filesystem '{filesystem}': {err}")]
+pub struct UpgradeDatasetError {
+    filesystem: String,
+    #[source]
+    err: crate::ExecutionError,
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("Failed to list snapshots: {0}")]
 pub struct ListSnapshotsError(#[from] crate::ExecutionError);
 
+#[derive(thiserror::Error, Debug)]
+enum ListSnapshotsOfErrorRaw {
+    #[error(transparent)]
+    Execution(#[from] crate::ExecutionError),
+
+    #[error("Invalid property '{0}'")]
+    InvalidProperty(String),
+
+    #[error("Unexpected line in 'zfs list' output: {0:?}")]
+    UnexpectedLine(String),
+}
+
+/// Error returned by [`Zfs::list_snapshots_of`].
+#[derive(thiserror::Error, Debug)]
+#[error("Failed to list snapshots of filesystem {filesystem}: {err}")]
+pub struct ListSnapshotsOfError {
+    filesystem: String,
+    #[source]
+    err: ListSnapshotsOfErrorRaw,
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error(
     "Failed to create snapshot '{snap_name}' from filesystem '{filesystem}': {err}"
@@ -220,9 +271,200 @@ pub struct DestroySnapshotError {
     err: crate::ExecutionError,
 }
 
+/// Error returned by [`Zfs::hold_snapshot`].
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to hold snapshot '{filesystem}@{snap_name}' with tag '{tag}'")]
+pub struct HoldSnapshotError {
+    filesystem: String,
+    snap_name: String,
+    tag: String,
+    #[source]
+    err: crate::ExecutionError,
+}
+
+/// Error returned by [`Zfs::release_snapshot`].
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Failed to release hold '{tag}' on snapshot '{filesystem}@{snap_name}'"
+)]
+pub struct ReleaseSnapshotError {
+    filesystem: String,
+    snap_name: String,
+    tag: String,
+    #[source]
+    err: crate::ExecutionError,
+}
+
+/// Error returned by [`Zfs::clone_snapshot`].
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to clone snapshot '{snapshot}' to '{target}'")]
+pub struct CloneSnapshotError {
+    snapshot: String,
+    target: String,
+    #[source]
+    err: crate::ExecutionError,
+}
+
+/// Error returned by [`Zfs::promote`].
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to promote clone '{dataset}'")]
+pub struct PromoteDatasetError {
+    dataset: String,
+    #[source]
+    err: crate::ExecutionError,
+}
+
+#[derive(thiserror::Error, Debug)]
+enum ListHoldsErrorRaw {
+    #[error(transparent)]
+    Execution(#[from] crate::ExecutionError),
+
+    #[error("Unexpected line in 'zfs holds' output: {0:?}")]
+    UnexpectedLine(String),
+}
+
+/// Error returned by [`Zfs::list_holds`].
+#[derive(thiserror::Error, Debug)]
+#[error("Failed to list holds on snapshot '{filesystem}@{snap_name}': {err}")]
+pub struct ListHoldsError {
+    filesystem: String,
+    snap_name: String,
+    #[source]
+    err: ListHoldsErrorRaw,
+}
+
+/// Error returned by [`Zfs::allow`].
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Failed to allow user '{user}' permissions {permissions} on dataset \
+     '{dataset}'"
+)]
+pub struct AllowError {
+    dataset: String,
+    user: String,
+    permissions: String,
+    #[source]
+    err: crate::ExecutionError,
+}
+
+/// Error returned by [`Zfs::unallow`].
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Failed to remove user '{user}' permissions {permissions} on dataset \
+     '{dataset}'"
+)]
+pub struct UnallowError {
+    dataset: String,
+    user: String,
+    permissions: String,
+    #[source]
+    err: crate::ExecutionError,
+}
+
+#[derive(thiserror::Error, Debug)]
+enum UserSpaceErrorRaw {
+    #[error(transparent)]
+    Execution(#[from] crate::ExecutionError),
+
+    #[error("Unexpected line in 'zfs userspace' output: {0:?}")]
+    UnexpectedLine(String),
+}
+
+/// Error returned by [`Zfs::userspace`].
+#[derive(thiserror::Error, Debug)]
+#[error("Failed to get per-user space usage of dataset '{dataset}': {err}")]
+pub struct UserSpaceError {
+    dataset: String,
+    #[source]
+    err: UserSpaceErrorRaw,
+}
+
+/// Error returned by [`Zfs::rotate_key`].
+#[derive(Debug, thiserror::Error)]
+pub enum RotateKeyError {
+    #[error("Failed to change key of encryption root '{dataset}': {err}")]
+    ChangeKey { dataset: String, err: crate::ExecutionError },
+
+    #[error(
+        "Changed key of encryption root '{dataset}', \
+         but failed to record epoch {epoch}"
+    )]
+    SetEpoch {
+        dataset: String,
+        epoch: u64,
+        #[source]
+        err: SetValueError,
+    },
+}
+
+/// Error returned by [`Zfs::delegate_dataset_to_zone`] and
+/// [`Zfs::undelegate_dataset`].
+#[derive(Debug, thiserror::Error)]
+pub enum DelegateDatasetError {
+    #[error(
+        "Cannot delegate dataset '{dataset}' to zone '{zone}': \
+         it is mounted in the global zone at {mountpoint}"
+    )]
+    MountedInGlobalZone { dataset: String, zone: String, mountpoint: String },
+
+    #[error("Cannot undelegate dataset '{dataset}': it is mounted in a zone")]
+    MountedInZone { dataset: String },
+
+    #[error(transparent)]
+    GetValue(#[from] GetValueError),
+
+    #[error(transparent)]
+    SetValue(#[from] SetValueError),
+}
+
 /// Wraps commands for interacting with ZFS.
 pub struct Zfs {}
 
+/// A permission that can be delegated to an unprivileged user with
+/// [`Zfs::allow`].
+///
+/// Many ZFS subcommands require additional permissions to succeed: for
+/// example, creating a snapshot requires both [`ZfsPermission::Snapshot`] and
+/// [`ZfsPermission::Mount`]. See zfs-allow(8) for details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZfsPermission {
+    Clone,
+    Create,
+    Destroy,
+    Hold,
+    Mount,
+    Promote,
+    Receive,
+    Release,
+    Rename,
+    Send,
+    Snapshot,
+}
+
+impl ZfsPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ZfsPermission::Clone => "clone",
+            ZfsPermission::Create => "create",
+            ZfsPermission::Destroy => "destroy",
+            ZfsPermission::Hold => "hold",
+            ZfsPermission::Mount => "mount",
+            ZfsPermission::Promote => "promote",
+            ZfsPermission::Receive => "receive",
+            ZfsPermission::Release => "release",
+            ZfsPermission::Rename => "rename",
+            ZfsPermission::Send => "send",
+            ZfsPermission::Snapshot => "snapshot",
+        }
+    }
+}
+
+impl fmt::Display for ZfsPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Describes a mountpoint for a ZFS filesystem.
 #[derive(Debug, Clone)]
 pub struct Mountpoint(pub Utf8PathBuf);
@@ -521,6 +763,118 @@ impl CanMount {
     }
 }
 
+/// Options for [Zfs::destroy_dataset_with_options] and
+/// [Zfs::destroy_dataset_dry_run].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DestroyDatasetOptions {
+    /// Also destroy the dataset's descendants: its child datasets and
+    /// snapshots (`-r`).
+    ///
+    /// Clones of those snapshots are not destroyed, and still block the
+    /// destroy.
+    pub recursive: bool,
+
+    /// If a snapshot can't be destroyed right away (because it has holds or
+    /// clones), mark it for deferred destruction instead of failing (`-d`).
+    ///
+    /// ZFS then destroys it once the last hold or clone goes away. This only
+    /// applies to snapshots.
+    pub defer: bool,
+}
+
+impl DestroyDatasetOptions {
+    fn flags(&self) -> Vec<&'static str> {
+        let mut flags = Vec::new();
+        if self.recursive {
+            flags.push("-r");
+        }
+        if self.defer {
+            flags.push("-d");
+        }
+        flags
+    }
+}
+
+/// What destroying a dataset would do, as reported by
+/// [`Zfs::destroy_dataset_dry_run`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DestroyDatasetDryRun {
+    /// The destroy would succeed.
+    WouldDestroy {
+        /// The datasets and snapshots that would be destroyed
+        destroyed: Vec<String>,
+        /// How many bytes would be freed, if ZFS said
+        reclaimed: Option<u64>,
+    },
+    /// The destroy would fail because of dependents that the options don't
+    /// allow destroying.
+    Blocked {
+        /// Snapshots in the way
+        snapshots: Vec<String>,
+        /// Child datasets or clones in the way
+        datasets: Vec<String>,
+    },
+}
+
+impl DestroyDatasetDryRun {
+    // ZFS explains which dependents block a destroy on stderr, after a line
+    // like "use '-r' to destroy the following datasets:".
+    const BLOCKED_MARKER: &'static str = "to destroy the following datasets:";
+
+    fn is_blocked(stderr: &str) -> bool {
+        stderr.contains(Self::BLOCKED_MARKER)
+    }
+
+    // Parses the output of a successful `zfs destroy -nvp`, which has a line
+    // "destroy\t<name>" for each dataset or snapshot that would be destroyed,
+    // and a line "reclaim\t<bytes>".
+    fn parse_would_destroy(
+        stdout: &str,
+    ) -> Result<Self, DestroyDatasetErrorVariant> {
+        let mut destroyed = Vec::new();
+        let mut reclaimed = None;
+        for line in stdout.lines().filter(|line| !line.is_empty()) {
+            let unexpected =
+                || DestroyDatasetErrorVariant::UnexpectedLine(line.to_string());
+            match line.split_once('\t').ok_or_else(unexpected)? {
+                ("destroy", name) => destroyed.push(name.to_string()),
+                ("reclaim", bytes) => {
+                    reclaimed = Some(bytes.parse().map_err(|_| unexpected())?);
+                }
+                _ => return Err(unexpected()),
+            }
+        }
+        Ok(Self::WouldDestroy { destroyed, reclaimed })
+    }
+
+    // Parses the stderr of a `zfs destroy -nvp` that failed because of
+    // dependents, which lists those dependents one per line after
+    // `BLOCKED_MARKER`.
+    fn parse_blocked(stderr: &str) -> Result<Self, DestroyDatasetErrorVariant> {
+        let mut snapshots = Vec::new();
+        let mut datasets = Vec::new();
+        let dependents = stderr
+            .lines()
+            .skip_while(|line| !line.contains(Self::BLOCKED_MARKER))
+            .skip(1)
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        for name in dependents {
+            if name.contains(char::is_whitespace) {
+                return Err(DestroyDatasetErrorVariant::UnexpectedLine(
+                    name.to_string(),
+                ));
+            }
+            if name.contains('@') {
+                snapshots.push(name.to_string());
+            } else {
+                datasets.push(name.to_string());
+            }
+        }
+        Ok(Self::Blocked { snapshots, datasets })
+    }
+}
+
 /// Arguments to [Zfs::ensure_dataset].
 pub struct DatasetEnsureArgs<'a> {
     /// The full path of the ZFS dataset.
@@ -921,26 +1275,68 @@ impl Zfs {
             .map_err(|err| ListDatasetsError { name: object.to_string(), err })
     }
 
-    /// Destroys a dataset.
+    /// Destroys a dataset, along with all of its descendants.
     pub async fn destroy_dataset(
         name: &str,
+    ) -> Result<(), DestroyDatasetError> {
+        Self::destroy_dataset_with_options(
+            name,
+            DestroyDatasetOptions { recursive: true, defer: false },
+        )
+        .await
+    }
+
+    /// Destroys a dataset or snapshot.
+    ///
+    /// Refer to [DestroyDatasetOptions] for details on the supplied options.
+    /// To find out ahead of time what this would destroy, or what would keep
+    /// it from working, use [`Zfs::destroy_dataset_dry_run`].
+    pub async fn destroy_dataset_with_options(
+        name: &str,
+        options: DestroyDatasetOptions,
     ) -> Result<(), DestroyDatasetError> {
         let mut command = Command::new(PFEXEC);
-        let cmd = command.args(&[ZFS, "destroy", "-r", name]);
-        execute_async(cmd).await.map_err(|err| {
-            let variant = match err {
-                crate::ExecutionError::CommandFailure(info)
-                    if info.stderr.contains("does not exist") =>
-                {
-                    DestroyDatasetErrorVariant::NotFound
-                }
-                _ => DestroyDatasetErrorVariant::Other(err),
-            };
-            DestroyDatasetError { name: name.to_string(), err: variant }
-        })?;
+        let cmd =
+            command.args(&[ZFS, "destroy"]).args(options.flags()).arg(name);
+        execute_async(cmd)
+            .await
+            .map_err(|err| DestroyDatasetError::from_execution(name, err))?;
         Ok(())
     }
 
+    /// Reports what [`Zfs::destroy_dataset_with_options`] would do with the
+    /// same arguments, without destroying anything.
+    ///
+    /// If the destroy would fail because the dataset has dependents (child
+    /// datasets, snapshots, or clones) that `options` don't allow destroying,
+    /// this returns [`DestroyDatasetDryRun::Blocked`] listing them, rather
+    /// than an error.
+    pub async fn destroy_dataset_dry_run(
+        name: &str,
+        options: DestroyDatasetOptions,
+    ) -> Result<DestroyDatasetDryRun, DestroyDatasetError> {
+        let mut command = Command::new(PFEXEC);
+        let cmd = command
+            .args(&[ZFS, "destroy", "-nvp"])
+            .args(options.flags())
+            .arg(name);
+        let result = match execute_async(cmd).await {
+            Ok(output) => DestroyDatasetDryRun::parse_would_destroy(
+                &String::from_utf8_lossy(&output.stdout),
+            ),
+            Err(crate::ExecutionError::CommandFailure(info))
+                if DestroyDatasetDryRun::is_blocked(&info.stderr) =>
+            {
+                DestroyDatasetDryRun::parse_blocked(&info.stderr)
+            }
+            Err(err) => {
+                return Err(DestroyDatasetError::from_execution(name, err));
+            }
+        };
+        result
+            .map_err(|err| DestroyDatasetError { name: name.to_string(), err })
+    }
+
     /// Ensures that a ZFS dataset is mounted
     ///
     /// Returns an error if the dataset exists, but cannot be mounted.
@@ -1153,6 +1549,112 @@ impl Zfs {
         }
     }
 
+    /// Replaces the key of the encryption root `dataset` with the raw key at
+    /// `new_keypath`, and records `new_epoch` as its `oxide:epoch`.
+    ///
+    /// The dataset's current key must be loaded. Its children inherit the new
+    /// key, so only encryption roots need to be rotated.
+    ///
+    /// `zfs change-key` can't set user properties, so the key and the epoch
+    /// are updated separately. If recording the epoch fails, the dataset is
+    /// left with the new key but the old epoch; calling this again with the
+    /// same arguments is harmless, and will fix that.
+    pub async fn rotate_key(
+        dataset: &str,
+        new_keypath: &Keypath,
+        new_epoch: u64,
+    ) -> Result<(), RotateKeyError> {
+        let mut command = Command::new(PFEXEC);
+        let keyloc = format!("keylocation=file://{}", new_keypath);
+        let cmd = command.args(&[
+            ZFS,
+            "change-key",
+            "-o",
+            "keyformat=raw",
+            "-o",
+            &keyloc,
+            dataset,
+        ]);
+        execute_async(cmd).await.map_err(|err| RotateKeyError::ChangeKey {
+            dataset: dataset.to_string(),
+            err,
+        })?;
+
+        Zfs::set_oxide_value(dataset, "epoch", &new_epoch.to_string())
+            .await
+            .map_err(|err| RotateKeyError::SetEpoch {
+                dataset: dataset.to_string(),
+                epoch: new_epoch,
+                err,
+            })
+    }
+
+    /// Prepares a dataset to be delegated to a zone.
+    ///
+    /// This sets "zoned=on" (and "canmount=on", so that the dataset is mounted
+    /// when the zone boots), after which the dataset can only be mounted from
+    /// within a zone. The caller is still responsible for adding the dataset
+    /// to `zone`'s configuration.
+    ///
+    /// Returns an error if the dataset is currently mounted in the global
+    /// zone: delegating it would leave the same filesystem visible in both
+    /// places.
+    pub async fn delegate_dataset_to_zone(
+        dataset: &str,
+        zone: &str,
+    ) -> Result<(), DelegateDatasetError> {
+        let [zoned, canmount, mounted, mountpoint] = Self::get_values(
+            dataset,
+            &["zoned", "canmount", "mounted", "mountpoint"],
+            None,
+        )
+        .await?;
+
+        if zoned == "on" && canmount == "on" {
+            return Ok(());
+        }
+        if zoned != "on" && mounted == "yes" {
+            return Err(DelegateDatasetError::MountedInGlobalZone {
+                dataset: dataset.to_string(),
+                zone: zone.to_string(),
+                mountpoint,
+            });
+        }
+
+        Self::set_values(dataset, &[("zoned", "on"), ("canmount", "on")])
+            .await?;
+        Ok(())
+    }
+
+    /// Reverses [`Zfs::delegate_dataset_to_zone`], returning control of a
+    /// dataset to the global zone.
+    ///
+    /// The dataset is left with "canmount=noauto": its mountpoint is relative
+    /// to the zone it was delegated to, so we never want it to be mounted
+    /// automatically in the global zone.
+    ///
+    /// Returns an error if the dataset is still mounted, i.e., if the zone it
+    /// was delegated to is still running.
+    pub async fn undelegate_dataset(
+        dataset: &str,
+    ) -> Result<(), DelegateDatasetError> {
+        let [zoned, mounted] =
+            Self::get_values(dataset, &["zoned", "mounted"], None).await?;
+
+        if zoned == "off" {
+            return Ok(());
+        }
+        if mounted == "yes" {
+            return Err(DelegateDatasetError::MountedInZone {
+                dataset: dataset.to_string(),
+            });
+        }
+
+        Self::set_values(dataset, &[("canmount", "noauto"), ("zoned", "off")])
+            .await?;
+        Ok(())
+    }
+
     /// Set the value of an Oxide-managed ZFS property.
     pub async fn set_oxide_value(
         filesystem_name: &str,
@@ -1219,6 +1721,38 @@ impl Zfs {
         Ok(value)
     }
 
+    /// Return the on-disk version of a ZFS filesystem (its `version`
+    /// property).
+    ///
+    /// This is independent of the pool's feature flags; see
+    /// [`crate::zpool::Zpool::features`] for those.
+    pub async fn dataset_version(
+        filesystem_name: &str,
+    ) -> Result<u64, GetValueError> {
+        let value = Self::get_value(filesystem_name, "version").await?;
+        value.parse().map_err(|_| GetValueError {
+            filesystem: filesystem_name.to_string(),
+            name: "version".to_string(),
+            err: GetValueErrorRaw::UnexpectedValue(value),
+        })
+    }
+
+    /// Upgrade a ZFS filesystem to the latest on-disk version supported by
+    /// the running system (`zfs upgrade <filesystem>`).
+    ///
+    /// This is a no-op if the filesystem is already at that version. Note that
+    /// an upgraded filesystem can't be accessed by older software, so callers
+    /// should only upgrade once they're sure they won't need to roll back.
+    pub async fn upgrade_dataset(
+        filesystem_name: &str,
+    ) -> Result<(), UpgradeDatasetError> {
+        let mut command = Command::new(PFEXEC);
+        let cmd = command.args(&[ZFS, "upgrade", filesystem_name]);
+        execute_async(cmd).await.map(|_| ()).map_err(|err| {
+            UpgradeDatasetError { filesystem: filesystem_name.to_string(), err }
+        })
+    }
+
     /// List all extant snapshots.
     pub async fn list_snapshots() -> Result<Vec<Snapshot>, ListSnapshotsError> {
         let mut command = Command::new(ZFS);
@@ -1243,6 +1777,45 @@ impl Zfs {
             .map_err(ListSnapshotsError::from)
     }
 
+    /// List the snapshots of a single filesystem, along with the values of
+    /// `properties` for each of them.
+    ///
+    /// Unlike [`Zfs::list_snapshots`], this doesn't visit the snapshots of
+    /// any other filesystem, including the children of `filesystem`.
+    pub async fn list_snapshots_of(
+        filesystem: &str,
+        properties: &[&str],
+    ) -> Result<Vec<SnapshotWithProperties>, ListSnapshotsOfError> {
+        let err = |err| ListSnapshotsOfError {
+            filesystem: filesystem.to_string(),
+            err,
+        };
+
+        // "name" is always the first column, and any property that would
+        // change the number of columns can't be requested.
+        if let Some(invalid) = properties
+            .iter()
+            .find(|p| matches!(**p, "all" | "name") || p.contains(','))
+        {
+            return Err(err(ListSnapshotsOfErrorRaw::InvalidProperty(
+                invalid.to_string(),
+            )));
+        }
+        let columns =
+            std::iter::once("name").chain(properties.iter().copied()).join(",");
+
+        let mut command = Command::new(ZFS);
+        let cmd = command.args(&[
+            "list", "-Hp", "-r", "-d", "1", "-t", "snapshot", "-o", &columns,
+            filesystem,
+        ]);
+        let output = execute_async(cmd)
+            .await
+            .map_err(|e| err(ListSnapshotsOfErrorRaw::from(e)))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        SnapshotWithProperties::parse_many(&stdout, properties).map_err(err)
+    }
+
     /// Create a snapshot of a filesystem.
     ///
     /// A list of properties, as name-value tuples, may be passed to this
@@ -1284,6 +1857,199 @@ impl Zfs {
         })
     }
 
+    /// Place a hold named `tag` on a snapshot, preventing it from being
+    /// destroyed until the hold is released.
+    ///
+    /// This is idempotent: holding a snapshot with a tag it's already held
+    /// with succeeds.
+    pub async fn hold_snapshot(
+        filesystem: &str,
+        snap_name: &str,
+        tag: &str,
+    ) -> Result<(), HoldSnapshotError> {
+        let mut command = Command::new(PFEXEC);
+        let path = format!("{filesystem}@{snap_name}");
+        let cmd = command.args(&[ZFS, "hold", tag, &path]);
+        match execute_async(cmd).await {
+            Ok(_) => Ok(()),
+            Err(crate::ExecutionError::CommandFailure(info))
+                if info.stderr.contains("tag already exists") =>
+            {
+                Ok(())
+            }
+            Err(err) => Err(HoldSnapshotError {
+                filesystem: filesystem.to_string(),
+                snap_name: snap_name.to_string(),
+                tag: tag.to_string(),
+                err,
+            }),
+        }
+    }
+
+    /// Release the hold named `tag` on a snapshot.
+    ///
+    /// This is idempotent: releasing a hold that doesn't exist succeeds.
+    pub async fn release_snapshot(
+        filesystem: &str,
+        snap_name: &str,
+        tag: &str,
+    ) -> Result<(), ReleaseSnapshotError> {
+        let mut command = Command::new(PFEXEC);
+        let path = format!("{filesystem}@{snap_name}");
+        let cmd = command.args(&[ZFS, "release", tag, &path]);
+        match execute_async(cmd).await {
+            Ok(_) => Ok(()),
+            Err(crate::ExecutionError::CommandFailure(info))
+                if info.stderr.contains("no such tag") =>
+            {
+                Ok(())
+            }
+            Err(err) => Err(ReleaseSnapshotError {
+                filesystem: filesystem.to_string(),
+                snap_name: snap_name.to_string(),
+                tag: tag.to_string(),
+                err,
+            }),
+        }
+    }
+
+    /// List the holds on a snapshot.
+    pub async fn list_holds(
+        filesystem: &str,
+        snap_name: &str,
+    ) -> Result<Vec<SnapshotHold>, ListHoldsError> {
+        let err = |err| ListHoldsError {
+            filesystem: filesystem.to_string(),
+            snap_name: snap_name.to_string(),
+            err,
+        };
+
+        let mut command = Command::new(ZFS);
+        let path = format!("{filesystem}@{snap_name}");
+        let cmd = command.args(&["holds", "-H", &path]);
+        let output = execute_async(cmd)
+            .await
+            .map_err(|e| err(ListHoldsErrorRaw::from(e)))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        SnapshotHold::parse_many(&stdout).map_err(err)
+    }
+
+    /// Create the dataset `target` as a clone of `snapshot`.
+    ///
+    /// The clone shares all of its blocks with the snapshot, so this is fast
+    /// no matter how much data the snapshot holds. As with
+    /// [`Zfs::create_snapshot`], properties may be passed as name-value tuples
+    /// to set them on the new dataset. The snapshot can't be destroyed while
+    /// the clone exists, unless the clone is first promoted with
+    /// [`Zfs::promote`].
+    pub async fn clone_snapshot(
+        snapshot: &Snapshot,
+        target: &str,
+        properties: &[(&str, &str)],
+    ) -> Result<(), CloneSnapshotError> {
+        let mut command = Command::new(PFEXEC);
+        let cmd = command
+            .arg(ZFS)
+            .args(clone_snapshot_args(snapshot, target, properties));
+        execute_async(cmd).await.map(|_| ()).map_err(|err| CloneSnapshotError {
+            snapshot: snapshot.to_string(),
+            target: target.to_string(),
+            err,
+        })
+    }
+
+    /// Promote the clone `dataset`, so that it no longer depends on the
+    /// snapshot it was cloned from.
+    ///
+    /// Afterwards, the dataset the clone was created from becomes a clone of
+    /// the promoted dataset instead, and so can be destroyed independently.
+    pub async fn promote(dataset: &str) -> Result<(), PromoteDatasetError> {
+        let mut command = Command::new(PFEXEC);
+        let cmd = command.args(&[ZFS, "promote", dataset]);
+        execute_async(cmd).await.map(|_| ()).map_err(|err| {
+            PromoteDatasetError { dataset: dataset.to_string(), err }
+        })
+    }
+
+    /// Delegate `permissions` on `dataset` (and its descendants) to `user`,
+    /// so that the user can run the corresponding ZFS subcommands on it
+    /// without elevated privileges.
+    ///
+    /// This is idempotent: allowing permissions that the user already has
+    /// succeeds.
+    pub async fn allow(
+        dataset: &str,
+        user: &str,
+        permissions: &[ZfsPermission],
+    ) -> Result<(), AllowError> {
+        if permissions.is_empty() {
+            return Ok(());
+        }
+        let mut command = Command::new(PFEXEC);
+        let cmd = command.arg(ZFS).args(allow_args(
+            "allow",
+            dataset,
+            user,
+            permissions,
+        ));
+        execute_async(cmd).await.map(|_| ()).map_err(|err| AllowError {
+            dataset: dataset.to_string(),
+            user: user.to_string(),
+            permissions: permissions_list(permissions),
+            err,
+        })
+    }
+
+    /// Remove `permissions` on `dataset` (and its descendants) that were
+    /// delegated to `user` with [`Zfs::allow`]. If `permissions` is empty,
+    /// all of the user's delegated permissions on `dataset` are removed.
+    ///
+    /// This is idempotent: removing permissions that the user doesn't have
+    /// succeeds.
+    pub async fn unallow(
+        dataset: &str,
+        user: &str,
+        permissions: &[ZfsPermission],
+    ) -> Result<(), UnallowError> {
+        let mut command = Command::new(PFEXEC);
+        let cmd = command.arg(ZFS).args(allow_args(
+            "unallow",
+            dataset,
+            user,
+            permissions,
+        ));
+        execute_async(cmd).await.map(|_| ()).map_err(|err| UnallowError {
+            dataset: dataset.to_string(),
+            user: user.to_string(),
+            permissions: permissions_list(permissions),
+            err,
+        })
+    }
+
+    /// Report how much of `dataset` each user is using.
+    ///
+    /// This only accounts for the dataset itself, not its descendants or
+    /// snapshots.
+    pub async fn userspace(
+        dataset: &str,
+    ) -> Result<Vec<UserSpace>, UserSpaceError> {
+        let err = |err| UserSpaceError { dataset: dataset.to_string(), err };
+
+        let mut command = Command::new(ZFS);
+        let cmd = command.args(&[
+            "userspace",
+            "-Hp",
+            "-o",
+            "type,name,used,quota",
+            dataset,
+        ]);
+        let output = execute_async(cmd)
+            .await
+            .map_err(|e| err(UserSpaceErrorRaw::from(e)))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        UserSpace::parse_many(&stdout).map_err(err)
+    }
+
     /// Calls "zfs get" to acquire multiple values
     ///
     /// - `names`: The properties being acquired
@@ -1341,8 +2107,44 @@ impl Zfs {
     }
 }
 
+/// Returns the arguments to `zfs` for cloning `snapshot` to `target`.
+fn clone_snapshot_args(
+    snapshot: &Snapshot,
+    target: &str,
+    properties: &[(&str, &str)],
+) -> Vec<String> {
+    let mut args = vec!["clone".to_string()];
+    for (name, value) in properties {
+        args.push("-o".to_string());
+        args.push(format!("{name}={value}"));
+    }
+    args.push(snapshot.to_string());
+    args.push(target.to_string());
+    args
+}
+
+/// Formats `permissions` as the comma-separated list accepted by `zfs allow`
+fn permissions_list(permissions: &[ZfsPermission]) -> String {
+    permissions.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(",")
+}
+
+fn allow_args(
+    subcommand: &str,
+    dataset: &str,
+    user: &str,
+    permissions: &[ZfsPermission],
+) -> Vec<String> {
+    let mut args =
+        vec![subcommand.to_string(), "-u".to_string(), user.to_string()];
+    if !permissions.is_empty() {
+        args.push(permissions_list(permissions));
+    }
+    args.push(dataset.to_string());
+    args
+}
+
 /// A read-only snapshot of a ZFS filesystem.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Snapshot {
     pub filesystem: String,
     pub snap_name: String,
@@ -1374,6 +2176,171 @@ impl fmt::Display for Snapshot {
     }
 }
 
+/// A snapshot returned by [`Zfs::list_snapshots_of`], along with the values of
+/// the properties that were asked for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotWithProperties {
+    pub snapshot: Snapshot,
+    // Properties which aren't set on the snapshot are omitted.
+    properties: BTreeMap<String, String>,
+}
+
+impl SnapshotWithProperties {
+    #[cfg(any(test, feature = "testing"))]
+    pub fn new(
+        snapshot: Snapshot,
+        properties: BTreeMap<String, String>,
+    ) -> SnapshotWithProperties {
+        SnapshotWithProperties { snapshot, properties }
+    }
+
+    /// Returns the value of the property `name`, or `None` if it isn't set on
+    /// the snapshot (or wasn't asked for).
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+
+    /// Returns the value of the property `name` parsed as a `T`, or `None` if
+    /// it isn't set on the snapshot (or wasn't asked for).
+    pub fn parse_value<T: std::str::FromStr>(
+        &self,
+        name: &str,
+    ) -> Result<Option<T>, T::Err> {
+        self.value(name).map(str::parse).transpose()
+    }
+
+    // Parses the output of `zfs list -Hp -o name,<properties>`.
+    fn parse_many(
+        stdout: &str,
+        properties: &[&str],
+    ) -> Result<Vec<Self>, ListSnapshotsOfErrorRaw> {
+        stdout
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let unexpected = || {
+                    ListSnapshotsOfErrorRaw::UnexpectedLine(line.to_string())
+                };
+                let mut columns = line.split('\t');
+                let name = columns.next().ok_or_else(unexpected)?;
+                let (filesystem, snap_name) =
+                    name.split_once('@').ok_or_else(unexpected)?;
+
+                let mut values = BTreeMap::new();
+                for property in properties {
+                    let value = columns.next().ok_or_else(unexpected)?;
+                    // ZFS reports properties that aren't set as "-".
+                    if value != "-" {
+                        values.insert(property.to_string(), value.to_string());
+                    }
+                }
+                if columns.next().is_some() {
+                    return Err(unexpected());
+                }
+
+                Ok(Self {
+                    snapshot: Snapshot {
+                        filesystem: filesystem.to_string(),
+                        snap_name: snap_name.to_string(),
+                    },
+                    properties: values,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A hold on a snapshot, as returned by [`Zfs::list_holds`].
+///
+/// A snapshot can't be destroyed while it has any holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotHold {
+    pub snapshot: Snapshot,
+    pub tag: String,
+}
+
+impl SnapshotHold {
+    // Parses the output of `zfs holds -H`, which has the columns "name",
+    // "tag", and "timestamp". The timestamp isn't machine-readable, so we
+    // ignore it.
+    fn parse_many(stdout: &str) -> Result<Vec<Self>, ListHoldsErrorRaw> {
+        stdout
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let unexpected =
+                    || ListHoldsErrorRaw::UnexpectedLine(line.to_string());
+                let mut columns = line.split('\t');
+                let name = columns.next().ok_or_else(unexpected)?;
+                let (filesystem, snap_name) =
+                    name.split_once('@').ok_or_else(unexpected)?;
+                let tag = columns.next().ok_or_else(unexpected)?;
+                let _timestamp = columns.next().ok_or_else(unexpected)?;
+                if columns.next().is_some() {
+                    return Err(unexpected());
+                }
+
+                Ok(Self {
+                    snapshot: Snapshot {
+                        filesystem: filesystem.to_string(),
+                        snap_name: snap_name.to_string(),
+                    },
+                    tag: tag.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The space one user is using in a dataset, as returned by
+/// [`Zfs::userspace`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UserSpace {
+    /// The kind of user, e.g., "POSIX User" or "SMB User"
+    pub user_type: String,
+    /// The user's name, or their numeric ID if it has none
+    pub name: String,
+    /// Bytes used by the user's files
+    pub used: u64,
+    /// The user's quota in bytes, if one is set
+    pub quota: Option<u64>,
+}
+
+impl UserSpace {
+    // Parses the output of `zfs userspace -Hp -o type,name,used,quota`.
+    fn parse_many(stdout: &str) -> Result<Vec<Self>, UserSpaceErrorRaw> {
+        stdout
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let unexpected =
+                    || UserSpaceErrorRaw::UnexpectedLine(line.to_string());
+                let mut columns = line.split('\t');
+                let user_type = columns.next().ok_or_else(unexpected)?;
+                let name = columns.next().ok_or_else(unexpected)?;
+                let used = columns
+                    .next()
+                    .and_then(|used| used.parse().ok())
+                    .ok_or_else(unexpected)?;
+                let quota = match columns.next().ok_or_else(unexpected)? {
+                    "none" | "-" => None,
+                    quota => Some(quota.parse().map_err(|_| unexpected())?),
+                };
+                if columns.next().is_some() {
+                    return Err(unexpected());
+                }
+
+                Ok(Self {
+                    user_type: user_type.to_string(),
+                    name: name.to_string(),
+                    used,
+                    quota,
+                })
+            })
+            .collect()
+    }
+}
+
 /// Returns all datasets managed by Omicron
 pub async fn get_all_omicron_datasets_for_delete() -> anyhow::Result<Vec<String>>
 {
@@ -1725,4 +2692,261 @@ mod test {
         assert_eq!(props[1].used, 111.into());
         assert_eq!(props[1].mounted, false);
     }
+
+    #[test]
+    fn parse_snapshots_with_properties() {
+        let input = "tank/foo@a\t1024\ttrue\n\
+             tank/foo@b\t2048\t-\n";
+        let snapshots = SnapshotWithProperties::parse_many(
+            &input,
+            &["used", "oxide:for-zone-bundle"],
+        )
+        .expect("Should have parsed data");
+        assert_eq!(snapshots.len(), 2);
+
+        assert_eq!(snapshots[0].snapshot.filesystem, "tank/foo");
+        assert_eq!(snapshots[0].snapshot.snap_name, "a");
+        assert_eq!(snapshots[0].parse_value::<u64>("used"), Ok(Some(1024)));
+        assert_eq!(snapshots[0].value("oxide:for-zone-bundle"), Some("true"));
+
+        // Properties that aren't set, or weren't asked for, have no value.
+        assert_eq!(snapshots[1].snapshot.snap_name, "b");
+        assert_eq!(snapshots[1].value("oxide:for-zone-bundle"), None);
+        assert_eq!(snapshots[1].value("referenced"), None);
+    }
+
+    #[test]
+    fn parse_snapshots_bad_lines() {
+        // Not a snapshot
+        let input = "tank/foo\t1024";
+        SnapshotWithProperties::parse_many(&input, &["used"])
+            .expect_err("Should have failed to parse");
+
+        // Too few columns
+        let input = "tank/foo@a";
+        SnapshotWithProperties::parse_many(&input, &["used"])
+            .expect_err("Should have failed to parse");
+
+        // Too many columns
+        let input = "tank/foo@a\t1024\t2048";
+        SnapshotWithProperties::parse_many(&input, &["used"])
+            .expect_err("Should have failed to parse");
+    }
+
+    #[test]
+    fn clone_snapshot_args_include_properties() {
+        let snapshot = Snapshot {
+            filesystem: "rpool/golden".to_string(),
+            snap_name: "base".to_string(),
+        };
+        assert_eq!(
+            clone_snapshot_args(&snapshot, "rpool/zone/oxz_a", &[]),
+            ["clone", "rpool/golden@base", "rpool/zone/oxz_a"],
+        );
+        assert_eq!(
+            clone_snapshot_args(
+                &snapshot,
+                "rpool/zone/oxz_a",
+                &[("mountpoint", "/zone/oxz_a"), ("canmount", "noauto")],
+            ),
+            [
+                "clone",
+                "-o",
+                "mountpoint=/zone/oxz_a",
+                "-o",
+                "canmount=noauto",
+                "rpool/golden@base",
+                "rpool/zone/oxz_a",
+            ],
+        );
+    }
+
+    #[test]
+    fn allow_args_list_permissions() {
+        assert_eq!(
+            allow_args(
+                "allow",
+                "oxp_x/crypt/debug",
+                "oxide",
+                &[ZfsPermission::Snapshot, ZfsPermission::Mount],
+            ),
+            ["allow", "-u", "oxide", "snapshot,mount", "oxp_x/crypt/debug"],
+        );
+        assert_eq!(
+            allow_args(
+                "unallow",
+                "oxp_x/crypt/debug",
+                "oxide",
+                &[ZfsPermission::Destroy],
+            ),
+            ["unallow", "-u", "oxide", "destroy", "oxp_x/crypt/debug"],
+        );
+
+        // Without any permissions, unallow removes all of them.
+        assert_eq!(
+            allow_args("unallow", "oxp_x/crypt/debug", "oxide", &[]),
+            ["unallow", "-u", "oxide", "oxp_x/crypt/debug"],
+        );
+    }
+
+    #[test]
+    fn parse_snapshot_holds() {
+        let input = "tank/foo@a\tvolume-construction\tThu Oct 15 12:00 2026\n\
+             tank/foo@a\tkeep\tWed Oct 14 09:30 2026\n";
+        let holds =
+            SnapshotHold::parse_many(&input).expect("Should have parsed data");
+        assert_eq!(holds.len(), 2);
+        assert_eq!(holds[0].snapshot.filesystem, "tank/foo");
+        assert_eq!(holds[0].snapshot.snap_name, "a");
+        assert_eq!(holds[0].tag, "volume-construction");
+        assert_eq!(holds[1].tag, "keep");
+
+        // No holds at all.
+        let holds =
+            SnapshotHold::parse_many("").expect("Should have parsed data");
+        assert!(holds.is_empty());
+    }
+
+    #[test]
+    fn parse_snapshot_holds_bad_lines() {
+        // Not a snapshot
+        let input = "tank/foo\tkeep\tThu Oct 15 12:00 2026";
+        SnapshotHold::parse_many(&input)
+            .expect_err("Should have failed to parse");
+
+        // Too few columns
+        let input = "tank/foo@a\tkeep";
+        SnapshotHold::parse_many(&input)
+            .expect_err("Should have failed to parse");
+
+        // Too many columns
+        let input = "tank/foo@a\tkeep\tThu Oct 15 12:00 2026\textra";
+        SnapshotHold::parse_many(&input)
+            .expect_err("Should have failed to parse");
+    }
+
+    #[test]
+    fn parse_userspace() {
+        let input = "POSIX User\troot\t1048576\tnone\n\
+             POSIX User\toxide\t4096\t10737418240\n\
+             POSIX User\t12345\t512\tnone\n";
+        let users =
+            UserSpace::parse_many(input).expect("Should have parsed data");
+        assert_eq!(
+            users,
+            [
+                UserSpace {
+                    user_type: "POSIX User".to_string(),
+                    name: "root".to_string(),
+                    used: 1048576,
+                    quota: None,
+                },
+                UserSpace {
+                    user_type: "POSIX User".to_string(),
+                    name: "oxide".to_string(),
+                    used: 4096,
+                    quota: Some(10737418240),
+                },
+                UserSpace {
+                    user_type: "POSIX User".to_string(),
+                    name: "12345".to_string(),
+                    used: 512,
+                    quota: None,
+                },
+            ]
+        );
+
+        // No users at all.
+        let users = UserSpace::parse_many("").expect("Should have parsed data");
+        assert!(users.is_empty());
+    }
+
+    #[test]
+    fn parse_userspace_bad_lines() {
+        // Too few columns
+        let input = "POSIX User\troot\t1048576";
+        UserSpace::parse_many(input).expect_err("Should have failed to parse");
+
+        // Too many columns
+        let input = "POSIX User\troot\t1048576\tnone\t12";
+        UserSpace::parse_many(input).expect_err("Should have failed to parse");
+
+        // Not a number
+        let input = "POSIX User\troot\t1M\tnone";
+        UserSpace::parse_many(input).expect_err("Should have failed to parse");
+        let input = "POSIX User\troot\t1048576\t10G";
+        UserSpace::parse_many(input).expect_err("Should have failed to parse");
+    }
+
+    #[test]
+    fn destroy_dataset_flags() {
+        assert!(DestroyDatasetOptions::default().flags().is_empty());
+        assert_eq!(
+            DestroyDatasetOptions { recursive: true, defer: true }.flags(),
+            ["-r", "-d"],
+        );
+    }
+
+    #[test]
+    fn parse_destroy_dry_run() {
+        let input = "destroy\ttank/foo@a\n\
+             destroy\ttank/foo/bar\n\
+             destroy\ttank/foo\n\
+             reclaim\t1234\n";
+        let dry_run = DestroyDatasetDryRun::parse_would_destroy(input)
+            .expect("Should have parsed data");
+        assert_eq!(
+            dry_run,
+            DestroyDatasetDryRun::WouldDestroy {
+                destroyed: vec![
+                    "tank/foo@a".to_string(),
+                    "tank/foo/bar".to_string(),
+                    "tank/foo".to_string(),
+                ],
+                reclaimed: Some(1234),
+            }
+        );
+
+        // Unexpected lines
+        for input in ["destroy tank/foo", "reclaim\tlots", "rename\ttank/foo"] {
+            DestroyDatasetDryRun::parse_would_destroy(input)
+                .expect_err("Should have failed to parse");
+        }
+    }
+
+    #[test]
+    fn parse_destroy_dry_run_blocked() {
+        let stderr = "cannot destroy 'tank/foo': filesystem has children\n\
+             use '-r' to destroy the following datasets:\n\
+             tank/foo@a\n\
+             tank/foo/bar\n";
+        assert!(DestroyDatasetDryRun::is_blocked(stderr));
+        let dry_run = DestroyDatasetDryRun::parse_blocked(stderr)
+            .expect("Should have parsed data");
+        assert_eq!(
+            dry_run,
+            DestroyDatasetDryRun::Blocked {
+                snapshots: vec!["tank/foo@a".to_string()],
+                datasets: vec!["tank/foo/bar".to_string()],
+            }
+        );
+
+        let stderr = "cannot destroy 'tank/foo@a': snapshot has dependent clones\n\
+             use '-R' to destroy the following datasets:\n\
+             tank/clone\n";
+        let dry_run = DestroyDatasetDryRun::parse_blocked(stderr)
+            .expect("Should have parsed data");
+        assert_eq!(
+            dry_run,
+            DestroyDatasetDryRun::Blocked {
+                snapshots: vec![],
+                datasets: vec!["tank/clone".to_string()],
+            }
+        );
+
+        // Other failures aren't about dependents.
+        assert!(!DestroyDatasetDryRun::is_blocked(
+            "cannot open 'tank/foo': dataset does not exist\n"
+        ));
+    }
 }
@@ -40,6 +40,52 @@ pub fn zone_name(prefix: &str, id: Option<OmicronZoneUuid>) -> String {
     }
 }
 
+/// Resource caps to apply to a zone when it's configured.
+///
+/// Caps left as `None` aren't applied, and the zone may use as much of that
+/// resource as the host will give it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ZoneResourceCaps {
+    /// The amount of CPU time the zone may use, in units of whole CPUs.
+    pub ncpus: Option<f64>,
+    /// The amount of physical memory (and swap) the zone may use, in bytes.
+    pub memory_bytes: Option<u64>,
+}
+
+impl ZoneResourceCaps {
+    /// Adds the zonecfg resources for these caps to `cfg`.
+    fn add_to_config(&self, cfg: &mut zone::Config) {
+        if let Some(ncpus) = self.ncpus {
+            cfg.add_capped_cpu(&capped_cpu(ncpus));
+        }
+        if let Some(bytes) = self.memory_bytes {
+            cfg.add_capped_memory(&capped_memory(bytes));
+        }
+    }
+}
+
+/// Returns the zonecfg `capped-cpu` resource limiting a zone to `ncpus` CPUs.
+pub fn capped_cpu(ncpus: f64) -> zone::CappedCpu {
+    zone::CappedCpu { ncpus }
+}
+
+/// Returns the zonecfg `capped-memory` resource limiting a zone's physical
+/// memory and swap to `bytes`, rounded up to the next MiB.
+pub fn capped_memory(bytes: u64) -> zone::CappedMemory {
+    let limit = zonecfg_memory_size(bytes);
+    zone::CappedMemory {
+        physical: Some(limit.clone()),
+        swap: Some(limit),
+        locked: None,
+    }
+}
+
+/// Formats `bytes` as a zonecfg memory size, rounded up to the next MiB.
+fn zonecfg_memory_size(bytes: u64) -> String {
+    const MIB: u64 = 1 << 20;
+    format!("{}m", bytes.div_ceil(MIB))
+}
+
 #[derive(thiserror::Error, Debug)]
 enum Error {
     #[error("Zone execution error: {0}")]
@@ -84,6 +130,9 @@ pub enum AdmErrorKind {
     /// Another zoneadm error occurred.
     #[error(transparent)]
     Zoneadm(#[from] zone::ZoneError),
+    /// A dataset could not be delegated to the zone.
+    #[error(transparent)]
+    DelegateDataset(#[from] crate::zfs::DelegateDatasetError),
 }
 
 impl AdmError {
@@ -278,6 +327,7 @@ pub trait Api: Send + Sync {
         devices: &[zone::Device],
         links: Vec<String>,
         limit_priv: Vec<String>,
+        resource_caps: ZoneResourceCaps,
     ) -> Result<(), crate::zone::AdmError> {
         if let Some(zone) = self.find(zone_name).await? {
             info!(
@@ -316,8 +366,16 @@ pub trait Api: Send + Sync {
             let limit_priv = std::collections::BTreeSet::from_iter(limit_priv);
             cfg.get_global().set_limitpriv(limit_priv);
         }
+        resource_caps.add_to_config(&mut cfg);
 
         for dataset in datasets {
+            crate::zfs::Zfs::delegate_dataset_to_zone(&dataset.name, zone_name)
+                .await
+                .map_err(|err| AdmError {
+                    op: Operation::Configure,
+                    zone: zone_name.to_string(),
+                    err: err.into(),
+                })?;
             cfg.add_dataset(dataset);
         }
         for filesystem in filesystems {
@@ -963,6 +1021,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_zonecfg_memory_size() {
+        assert_eq!(zonecfg_memory_size(0), "0m");
+        assert_eq!(zonecfg_memory_size(1), "1m");
+        assert_eq!(zonecfg_memory_size(1 << 20), "1m");
+        assert_eq!(zonecfg_memory_size((1 << 20) + 1), "2m");
+        assert_eq!(zonecfg_memory_size(8 << 30), "8192m");
+    }
+
     // This test validates that we correctly detect an attempt to delete an
     // address that does not exist and return `Ok(())`.
     #[cfg(target_os = "illumos")]
@@ -6,6 +6,8 @@
 
 use crate::{ExecutionError, PFEXEC, execute_async};
 use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use tokio::process::Command;
 
@@ -60,6 +62,15 @@ pub struct GetInfoError {
     err: Error,
 }
 
+#[derive(thiserror::Error, Debug)]
+#[error("Failed to {action} zpool '{name}': {err}")]
+pub struct ScrubError {
+    action: &'static str,
+    name: String,
+    #[source]
+    err: Error,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ZpoolHealth {
     /// The device is online and functioning.
@@ -102,6 +113,7 @@ pub struct ZpoolInfo {
     allocated: u64,
     free: u64,
     health: ZpoolHealth,
+    fragmentation: Option<u8>,
 }
 
 impl ZpoolInfo {
@@ -113,7 +125,6 @@ impl ZpoolInfo {
         self.size
     }
 
-    #[allow(dead_code)]
     pub fn allocated(&self) -> u64 {
         self.allocated
     }
@@ -128,6 +139,14 @@ impl ZpoolInfo {
         self.health
     }
 
+    /// Fragmentation of the zpool's free space, in whole percent
+    ///
+    /// This is `None` if ZFS doesn't report fragmentation for the zpool (for
+    /// example, if the `spacemap_histogram` feature isn't enabled).
+    pub fn fragmentation(&self) -> Option<u8> {
+        self.fragmentation
+    }
+
     #[cfg(any(test, feature = "testing"))]
     pub fn new_hardcoded(name: String) -> ZpoolInfo {
         ZpoolInfo {
@@ -136,6 +155,7 @@ impl ZpoolInfo {
             allocated: 1024,
             free: 1024 * 1023 * 64,
             health: ZpoolHealth::Online,
+            fragmentation: Some(0),
         }
     }
 }
@@ -174,11 +194,391 @@ impl FromStr for ZpoolInfo {
             .next()
             .ok_or_else(|| expected_field("health"))?
             .parse::<ZpoolHealth>()?;
+        let fragmentation =
+            match values.next().ok_or_else(|| expected_field("frag"))? {
+                "-" => None,
+                frag => Some(
+                    frag.parse::<u8>()
+                        .map_err(|e| failed_to_parse("frag", e))?,
+                ),
+            };
+
+        Ok(ZpoolInfo { name, size, allocated, free, health, fragmentation })
+    }
+}
+
+/// The state of the most recent scrub of a zpool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZpoolScrubState {
+    /// A scrub is currently running.
+    InProgress,
+    /// A scrub was started and then paused by "zpool scrub -p".
+    Paused,
+    /// The most recent scrub ran to completion.
+    Finished,
+    /// The most recent scrub was canceled by "zpool scrub -s".
+    Canceled,
+}
+
+/// Describes the progress of the most recent scrub of a zpool, as reported by
+/// the "scan:" section of "zpool status".
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZpoolScrubStatus {
+    pub state: ZpoolScrubState,
+    /// When the scrub started.
+    ///
+    /// Only reported for scrubs that are in progress or paused.
+    pub start_time: Option<DateTime<Utc>>,
+    /// When the scrub finished or was canceled.
+    pub end_time: Option<DateTime<Utc>>,
+    /// How much of the pool has been scrubbed, in whole percent.
+    ///
+    /// Only reported for scrubs that are in progress or paused.
+    pub percent_done: Option<u8>,
+    /// Number of errors found by the scrub.
+    ///
+    /// Only reported for scrubs that have finished.
+    pub errors: Option<u64>,
+}
+
+impl ZpoolScrubStatus {
+    /// Parse the scrub status out of the output of "zpool status <pool>".
+    ///
+    /// Returns `None` if no scrub has ever been requested for the pool, or if
+    /// the most recent scan of the pool was a resilver rather than a scrub (ZFS
+    /// only records the most recent scan of either kind).
+    ///
+    /// Timestamps are interpreted as UTC; callers must run "zpool status" with
+    /// `TZ=UTC` for them to be correct.
+    pub fn parse_zpool_status(s: &str) -> Result<Option<Self>, ParseError> {
+        let mut lines = s.lines().map(str::trim);
+        let Some(scan) = lines.find_map(|line| line.strip_prefix("scan:"))
+        else {
+            return Err(ParseError(
+                "Missing 'scan' section in zpool status output".to_string(),
+            ));
+        };
+        let scan = scan.trim();
+
+        // Continuation lines of the "scan:" section are indented under it, and
+        // the section ends at the next "<header>:" line (e.g., "config:").
+        let details: Vec<&str> = lines
+            .take_while(|line| {
+                !line.split_whitespace().next().is_some_and(|word| {
+                    word.strip_suffix(':').is_some_and(|header| {
+                        !header.is_empty()
+                            && header.chars().all(|c| c.is_ascii_lowercase())
+                    })
+                })
+            })
+            .collect();
+
+        let status = if scan == "none requested" {
+            return Ok(None);
+        } else if let Some(since) = scan.strip_prefix("scrub in progress since")
+        {
+            ZpoolScrubStatus {
+                state: ZpoolScrubState::InProgress,
+                start_time: Some(parse_scan_time(since)?),
+                end_time: None,
+                percent_done: parse_percent_done(&details)?,
+                errors: None,
+            }
+        } else if scan.starts_with("scrub paused since") {
+            let start_time = details
+                .iter()
+                .find_map(|line| line.strip_prefix("scrub started on"))
+                .map(parse_scan_time)
+                .transpose()?;
+            ZpoolScrubStatus {
+                state: ZpoolScrubState::Paused,
+                start_time,
+                end_time: None,
+                percent_done: parse_percent_done(&details)?,
+                errors: None,
+            }
+        } else if let Some(repaired) = scan.strip_prefix("scrub repaired") {
+            // "scrub repaired 0 in 0 days 00:00:01 with 0 errors on <time>"
+            let (summary, time) =
+                repaired.rsplit_once(" on ").ok_or_else(|| {
+                    ParseError(format!("Missing scrub end time: {scan}"))
+                })?;
+            let errors = summary
+                .split_once(" with ")
+                .and_then(|(_, errors)| errors.split_whitespace().next())
+                .ok_or_else(|| {
+                    ParseError(format!("Missing scrub error count: {scan}"))
+                })?
+                .parse::<u64>()
+                .map_err(|e| {
+                    ParseError(format!("Failed to parse scrub errors: {e}"))
+                })?;
+            ZpoolScrubStatus {
+                state: ZpoolScrubState::Finished,
+                start_time: None,
+                end_time: Some(parse_scan_time(time)?),
+                percent_done: None,
+                errors: Some(errors),
+            }
+        } else if let Some(time) = scan.strip_prefix("scrub canceled on") {
+            ZpoolScrubStatus {
+                state: ZpoolScrubState::Canceled,
+                start_time: None,
+                end_time: Some(parse_scan_time(time)?),
+                percent_done: None,
+                errors: None,
+            }
+        } else if scan.starts_with("resilver") {
+            return Ok(None);
+        } else {
+            return Err(ParseError(format!(
+                "Unrecognized scan status: {scan}"
+            )));
+        };
+        Ok(Some(status))
+    }
+}
+
+/// The state of the most recent resilver of a zpool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZpoolResilverState {
+    /// A resilver is currently running.
+    InProgress,
+    /// The most recent resilver ran to completion.
+    Finished,
+}
+
+/// Describes the progress of the most recent resilver of a zpool, as reported
+/// by the "scan:" section of "zpool status".
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZpoolResilverStatus {
+    pub state: ZpoolResilverState,
+    /// When the resilver started.
+    ///
+    /// Only reported for resilvers that are in progress.
+    pub start_time: Option<DateTime<Utc>>,
+    /// When the resilver finished.
+    pub end_time: Option<DateTime<Utc>>,
+    /// How much of the pool has been resilvered, in whole percent.
+    ///
+    /// Only reported for resilvers that are in progress.
+    pub percent_done: Option<u8>,
+    /// Number of errors found by the resilver.
+    ///
+    /// Only reported for resilvers that have finished.
+    pub errors: Option<u64>,
+}
+
+/// Describes the health of a zpool, as reported by "zpool status -p".
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZpoolHealthStatus {
+    pub health: ZpoolHealth,
+    /// I/O errors that occurred while reading from the pool.
+    pub read_errors: u64,
+    /// I/O errors that occurred while writing to the pool.
+    pub write_errors: u64,
+    /// Checksum errors: the pool returned corrupted data on a read.
+    pub checksum_errors: u64,
+    /// Progress of the most recent resilver of the pool.
+    ///
+    /// `None` if the pool has never been resilvered, or if the most recent
+    /// scan of the pool was a scrub.
+    pub resilver: Option<ZpoolResilverStatus>,
+}
+
+impl ZpoolHealthStatus {
+    /// Parse the health of a pool out of the output of
+    /// "zpool status -p <pool>".
+    ///
+    /// The error counters are those of the pool's top-level row in the
+    /// "config:" section. "-p" is required for the counters to be reported as
+    /// exact numbers rather than abbreviations like "1.2K".
+    ///
+    /// Timestamps are interpreted as UTC; callers must run "zpool status" with
+    /// `TZ=UTC` for them to be correct.
+    pub fn parse_zpool_status(s: &str) -> Result<Self, ParseError> {
+        let lines: Vec<&str> = s.lines().map(str::trim).collect();
+
+        let health = lines
+            .iter()
+            .find_map(|line| line.strip_prefix("state:"))
+            .ok_or_else(|| {
+                ParseError("Missing 'state' in zpool status output".to_string())
+            })?
+            .trim()
+            .parse::<ZpoolHealth>()?;
+
+        // The first row after the "NAME STATE READ WRITE CKSUM" heading of
+        // the "config:" section describes the pool as a whole.
+        let pool_row = lines
+            .iter()
+            .skip_while(|line| !line.starts_with("config:"))
+            .skip_while(|line| !line.starts_with("NAME"))
+            .nth(1)
+            .ok_or_else(|| {
+                ParseError(
+                    "Missing 'config' section in zpool status output"
+                        .to_string(),
+                )
+            })?;
+        let counters: Vec<&str> =
+            pool_row.split_whitespace().skip(2).take(3).collect();
+        let [read, write, cksum] = counters[..] else {
+            return Err(ParseError(format!(
+                "Missing error counters in zpool status row: {pool_row}"
+            )));
+        };
+        let parse_counter = |name, value: &str| {
+            value.parse::<u64>().map_err(|e| {
+                ParseError(format!("Failed to parse {name} errors: {e}"))
+            })
+        };
 
-        Ok(ZpoolInfo { name, size, allocated, free, health })
+        Ok(ZpoolHealthStatus {
+            health,
+            read_errors: parse_counter("read", read)?,
+            write_errors: parse_counter("write", write)?,
+            checksum_errors: parse_counter("checksum", cksum)?,
+            resilver: parse_resilver_status(&lines)?,
+        })
     }
 }
 
+/// Parse the resilver status out of the "scan:" section of "zpool status".
+fn parse_resilver_status(
+    lines: &[&str],
+) -> Result<Option<ZpoolResilverStatus>, ParseError> {
+    let mut lines = lines.iter().copied();
+    let Some(scan) = lines.find_map(|line| line.strip_prefix("scan:")) else {
+        return Ok(None);
+    };
+    let scan = scan.trim();
+
+    let status =
+        if let Some(since) = scan.strip_prefix("resilver in progress since") {
+            let details: Vec<&str> =
+                lines.take_while(|line| !line.starts_with("config:")).collect();
+            ZpoolResilverStatus {
+                state: ZpoolResilverState::InProgress,
+                start_time: Some(parse_scan_time(since)?),
+                end_time: None,
+                percent_done: parse_percent_done(&details)?,
+                errors: None,
+            }
+        } else if let Some(resilvered) = scan.strip_prefix("resilvered") {
+            // "resilvered 1.50G in 0 days 00:01:02 with 0 errors on <time>"
+            let (summary, time) =
+                resilvered.rsplit_once(" on ").ok_or_else(|| {
+                    ParseError(format!("Missing resilver end time: {scan}"))
+                })?;
+            let errors = summary
+                .split_once(" with ")
+                .and_then(|(_, errors)| errors.split_whitespace().next())
+                .ok_or_else(|| {
+                    ParseError(format!("Missing resilver error count: {scan}"))
+                })?
+                .parse::<u64>()
+                .map_err(|e| {
+                    ParseError(format!("Failed to parse resilver errors: {e}"))
+                })?;
+            ZpoolResilverStatus {
+                state: ZpoolResilverState::Finished,
+                start_time: None,
+                end_time: Some(parse_scan_time(time)?),
+                percent_done: None,
+                errors: Some(errors),
+            }
+        } else {
+            return Ok(None);
+        };
+    Ok(Some(status))
+}
+
+/// Parse a timestamp in the `ctime(3C)` format used by "zpool status", e.g.,
+/// "Tue Jul 15 10:00:00 2025".
+fn parse_scan_time(s: &str) -> Result<DateTime<Utc>, ParseError> {
+    // `ctime` pads single-digit days with a space ("Jul  2"); normalize that
+    // away so we don't depend on how the parser treats repeated whitespace.
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    NaiveDateTime::parse_from_str(&s, "%a %b %d %H:%M:%S %Y")
+        .map(|time| time.and_utc())
+        .map_err(|e| ParseError(format!("Failed to parse time '{s}': {e}")))
+}
+
+/// Find the "N.NN% done" progress of a scan in its continuation lines.
+fn parse_percent_done(details: &[&str]) -> Result<Option<u8>, ParseError> {
+    for line in details {
+        let mut words = line.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            let Some(percent) = word.strip_suffix('%') else {
+                continue;
+            };
+            if !words.peek().is_some_and(|next| next.starts_with("done")) {
+                continue;
+            }
+            let percent = percent.parse::<f64>().map_err(|e| {
+                ParseError(format!("Failed to parse percent done: {e}"))
+            })?;
+            // Truncate rather than round, so that a scrub is never reported
+            // as 100% done before it has actually finished.
+            return Ok(Some(percent.clamp(0.0, 100.0) as u8));
+        }
+    }
+    Ok(None)
+}
+
+/// The state of a single pool feature flag, as reported by
+/// `zpool get feature@<name>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZpoolFeatureState {
+    /// The feature is not enabled, and can't be used until the pool is
+    /// upgraded.
+    Disabled,
+    /// The feature is enabled, but nothing on disk depends on it yet.
+    Enabled,
+    /// The feature is enabled and in use, so the pool can no longer be
+    /// imported by software that doesn't support it.
+    Active,
+}
+
+impl FromStr for ZpoolFeatureState {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disabled" => Ok(ZpoolFeatureState::Disabled),
+            "enabled" => Ok(ZpoolFeatureState::Enabled),
+            "active" => Ok(ZpoolFeatureState::Active),
+            _ => Err(ParseError(format!(
+                "Unrecognized zpool feature state: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Parse the output of `zpool get -Hpo property,value all <pool>` into the
+/// state of each of the pool's feature flags, keyed by feature name.
+///
+/// Properties other than feature flags are ignored, as are features that the
+/// running system doesn't support (which are reported as `unsupported@<name>`).
+pub fn parse_zpool_features(
+    s: &str,
+) -> Result<BTreeMap<String, ZpoolFeatureState>, ParseError> {
+    let mut features = BTreeMap::new();
+    for line in s.lines() {
+        let Some((property, value)) = line.split_once('\t') else {
+            return Err(ParseError(format!(
+                "Unexpected line in zpool properties: {line:?}"
+            )));
+        };
+        if let Some(feature) = property.strip_prefix("feature@") {
+            features.insert(feature.to_string(), value.trim().parse()?);
+        }
+    }
+    Ok(features)
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ZpoolOrRamdisk {
     Zpool(ZpoolName),
@@ -316,7 +716,7 @@ impl Zpool {
         let cmd = command.args(&[
             "list",
             "-Hpo",
-            "name,size,allocated,free,health",
+            "name,size,allocated,free,health,frag",
             name,
         ]);
 
@@ -330,6 +730,133 @@ impl Zpool {
         })?;
         Ok(zpool)
     }
+
+    /// `zpool scrub <name>`
+    ///
+    /// This resumes a paused scrub, and succeeds without restarting anything
+    /// if a scrub is already in progress.
+    pub async fn start_scrub(name: &ZpoolName) -> Result<(), ScrubError> {
+        let mut cmd = Command::new(PFEXEC);
+        cmd.env_clear();
+        cmd.env("LC_ALL", "C.UTF-8");
+        cmd.arg(ZPOOL).arg("scrub").arg(&name.to_string());
+        Self::execute_scrub_command(cmd, "currently scrubbing").await.map_err(
+            |err| ScrubError {
+                action: "start scrub of",
+                name: name.to_string(),
+                err,
+            },
+        )
+    }
+
+    /// `zpool scrub -p <name>`
+    ///
+    /// This succeeds without doing anything if no scrub is in progress.
+    pub async fn pause_scrub(name: &ZpoolName) -> Result<(), ScrubError> {
+        let mut cmd = Command::new(PFEXEC);
+        cmd.env_clear();
+        cmd.env("LC_ALL", "C.UTF-8");
+        cmd.arg(ZPOOL).args(["scrub", "-p"]).arg(&name.to_string());
+        Self::execute_scrub_command(cmd, "no active scrub").await.map_err(
+            |err| ScrubError {
+                action: "pause scrub of",
+                name: name.to_string(),
+                err,
+            },
+        )
+    }
+
+    // Run a "zpool scrub" command, treating failures whose stderr contains
+    // `benign_error` as success.
+    async fn execute_scrub_command(
+        mut cmd: Command,
+        benign_error: &str,
+    ) -> Result<(), Error> {
+        match execute_async(&mut cmd).await {
+            Ok(_) => Ok(()),
+            Err(ExecutionError::CommandFailure(err_info))
+                if err_info.stderr.contains(benign_error) =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Report the progress of the most recent scrub of the pool, if any.
+    ///
+    /// See [`ZpoolScrubStatus::parse_zpool_status`] for when this returns
+    /// `None`.
+    pub async fn scrub_status(
+        name: &ZpoolName,
+    ) -> Result<Option<ZpoolScrubStatus>, ScrubError> {
+        let mut command = Command::new(ZPOOL);
+        command.env_clear();
+        command.env("LC_ALL", "C.UTF-8");
+        // Timestamps are printed in local time; pin it to UTC.
+        command.env("TZ", "UTC");
+        let cmd = command.arg("status").arg(&name.to_string());
+
+        let to_scrub_error = |err: Error| ScrubError {
+            action: "get scrub status of",
+            name: name.to_string(),
+            err,
+        };
+        let output =
+            execute_async(cmd).await.map_err(|e| to_scrub_error(e.into()))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        ZpoolScrubStatus::parse_zpool_status(&stdout)
+            .map_err(|e| to_scrub_error(e.into()))
+    }
+
+    /// Report the state of each of the pool's feature flags, keyed by
+    /// feature name.
+    ///
+    /// Together with [`crate::zfs::Zfs::dataset_version`], this describes the
+    /// on-disk format of the pool, which must be understood by any software
+    /// that imports it.
+    pub async fn features(
+        name: &ZpoolName,
+    ) -> Result<BTreeMap<String, ZpoolFeatureState>, GetInfoError> {
+        let mut command = Command::new(ZPOOL);
+        command.env_clear();
+        command.env("LC_ALL", "C.UTF-8");
+        let cmd = command
+            .args(["get", "-Hpo", "property,value", "all"])
+            .arg(&name.to_string());
+
+        let output = execute_async(cmd).await.map_err(|err| GetInfoError {
+            name: name.to_string(),
+            err: err.into(),
+        })?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parse_zpool_features(&stdout).map_err(|err| GetInfoError {
+            name: name.to_string(),
+            err: err.into(),
+        })
+    }
+
+    /// Report the health, error counters, and most recent resilver of the
+    /// pool.
+    pub async fn health_status(
+        name: &ZpoolName,
+    ) -> Result<ZpoolHealthStatus, GetInfoError> {
+        let mut command = Command::new(ZPOOL);
+        command.env_clear();
+        command.env("LC_ALL", "C.UTF-8");
+        // Timestamps are printed in local time; pin it to UTC.
+        command.env("TZ", "UTC");
+        let cmd = command.args(["status", "-p"]).arg(&name.to_string());
+
+        let output = execute_async(cmd).await.map_err(|err| GetInfoError {
+            name: name.to_string(),
+            err: err.into(),
+        })?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        ZpoolHealthStatus::parse_zpool_status(&stdout).map_err(|err| {
+            GetInfoError { name: name.to_string(), err: err.into() }
+        })
+    }
 }
 
 #[cfg(test)]
@@ -343,11 +870,12 @@ mod test {
         let allocated = 6000;
         let free = 4000;
         let health = "ONLINE";
+        let frag = 12;
 
         // We should be able to tolerate any whitespace between columns.
         let input = format!(
-            "{} {}    {} \t\t\t {} {}",
-            name, size, allocated, free, health
+            "{} {}    {} \t\t\t {} {}  {}",
+            name, size, allocated, free, health, frag
         );
         let output: ZpoolInfo = input.parse().unwrap();
         assert_eq!(output.name(), name);
@@ -355,6 +883,18 @@ mod test {
         assert_eq!(output.allocated(), allocated);
         assert_eq!(output.free(), free);
         assert_eq!(output.health(), ZpoolHealth::Online);
+        assert_eq!(output.fragmentation(), Some(frag));
+    }
+
+    #[test]
+    fn test_parse_zpool_unknown_fragmentation() {
+        let input = "rpool 10000 6000 4000 ONLINE -";
+        let output: ZpoolInfo = input.parse().unwrap();
+        assert_eq!(output.fragmentation(), None);
+
+        let input = "rpool 10000 6000 4000 ONLINE 12%";
+        let result: Result<ZpoolInfo, ParseError> = input.parse();
+        assert!(result.is_err());
     }
 
     #[test]
@@ -374,4 +914,260 @@ mod test {
         );
         assert_eq!(result.unwrap_err(), expected_err,);
     }
+
+    const ZPOOL_STATUS_HEADER: &str =
+        "  pool: oxp_d462a7f7-b628-40fe-80ff-4e4189e2d62b
+ state: ONLINE
+";
+    const ZPOOL_STATUS_CONFIG: &str = "config:
+
+\tNAME                                        STATE     READ WRITE CKSUM
+\toxp_d462a7f7-b628-40fe-80ff-4e4189e2d62b    ONLINE       0     0     0
+\t  c1t0d0                                    ONLINE       0     0     0
+
+errors: No known data errors
+";
+
+    fn zpool_status(scan: &str) -> String {
+        format!("{ZPOOL_STATUS_HEADER}{scan}{ZPOOL_STATUS_CONFIG}")
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_scrub_status_none() {
+        let input = zpool_status("  scan: none requested\n");
+        assert_eq!(ZpoolScrubStatus::parse_zpool_status(&input), Ok(None));
+
+        // The most recent scan was a resilver, which hides any earlier scrub.
+        let input = zpool_status(
+            "  scan: resilvered 1.50G in 0 days 00:01:02 with 0 errors on \
+             Mon Jul  7 09:30:00 2025\n",
+        );
+        assert_eq!(ZpoolScrubStatus::parse_zpool_status(&input), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_scrub_status_in_progress() {
+        let input = zpool_status(
+            "  scan: scrub in progress since Tue Jul 15 10:00:00 2025
+\t1.23G scanned at 100M/s, 512M issued at 50.0M/s, 10.0G total
+\t0 repaired, 5.12% done, 0 days 00:03:00 to go
+",
+        );
+        assert_eq!(
+            ZpoolScrubStatus::parse_zpool_status(&input),
+            Ok(Some(ZpoolScrubStatus {
+                state: ZpoolScrubState::InProgress,
+                start_time: Some(utc("2025-07-15T10:00:00Z")),
+                end_time: None,
+                percent_done: Some(5),
+                errors: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_scrub_status_paused() {
+        let input = zpool_status(
+            "  scan: scrub paused since Tue Jul 15 10:05:00 2025
+\tscrub started on Tue Jul 15 10:00:00 2025
+\t1.23G scanned, 512M issued, 10.0G total
+\t0 repaired, 99.99% done
+",
+        );
+        assert_eq!(
+            ZpoolScrubStatus::parse_zpool_status(&input),
+            Ok(Some(ZpoolScrubStatus {
+                state: ZpoolScrubState::Paused,
+                start_time: Some(utc("2025-07-15T10:00:00Z")),
+                end_time: None,
+                percent_done: Some(99),
+                errors: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_scrub_status_finished() {
+        let input = zpool_status(
+            "  scan: scrub repaired 0 in 0 days 00:00:05 with 3 errors on \
+             Wed Jul  2 08:07:06 2025\n",
+        );
+        assert_eq!(
+            ZpoolScrubStatus::parse_zpool_status(&input),
+            Ok(Some(ZpoolScrubStatus {
+                state: ZpoolScrubState::Finished,
+                start_time: None,
+                end_time: Some(utc("2025-07-02T08:07:06Z")),
+                percent_done: None,
+                errors: Some(3),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_scrub_status_canceled() {
+        let input = zpool_status(
+            "  scan: scrub canceled on Wed Jul 16 23:59:59 2025\n",
+        );
+        assert_eq!(
+            ZpoolScrubStatus::parse_zpool_status(&input),
+            Ok(Some(ZpoolScrubStatus {
+                state: ZpoolScrubState::Canceled,
+                start_time: None,
+                end_time: Some(utc("2025-07-16T23:59:59Z")),
+                percent_done: None,
+                errors: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_scrub_status_errors() {
+        // No "scan:" section at all
+        let input = format!("{ZPOOL_STATUS_HEADER}{ZPOOL_STATUS_CONFIG}");
+        assert_eq!(
+            ZpoolScrubStatus::parse_zpool_status(&input),
+            Err(ParseError(
+                "Missing 'scan' section in zpool status output".to_string()
+            ))
+        );
+
+        // Garbled timestamp
+        let input =
+            zpool_status("  scan: scrub in progress since yesterday-ish\n");
+        assert!(ZpoolScrubStatus::parse_zpool_status(&input).is_err());
+
+        // Unrecognized kind of scan
+        let input = zpool_status("  scan: defragment in progress\n");
+        assert!(ZpoolScrubStatus::parse_zpool_status(&input).is_err());
+    }
+
+    #[test]
+    fn test_parse_health_status_online() {
+        let input = zpool_status("  scan: none requested\n");
+        assert_eq!(
+            ZpoolHealthStatus::parse_zpool_status(&input),
+            Ok(ZpoolHealthStatus {
+                health: ZpoolHealth::Online,
+                read_errors: 0,
+                write_errors: 0,
+                checksum_errors: 0,
+                resilver: None,
+            })
+        );
+
+        // A scrub is not a resilver.
+        let input = zpool_status(
+            "  scan: scrub canceled on Wed Jul 16 23:59:59 2025\n",
+        );
+        assert_eq!(
+            ZpoolHealthStatus::parse_zpool_status(&input).unwrap().resilver,
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_health_status_degraded() {
+        let input = "  pool: oxp_d462a7f7-b628-40fe-80ff-4e4189e2d62b
+ state: DEGRADED
+status: One or more devices has experienced an unrecoverable error.  An
+\tattempt was made to correct the error.  Applications are unaffected.
+action: Determine if the device needs to be replaced, and clear the errors
+\tusing 'zpool clear' or replace the device with 'zpool replace'.
+  scan: resilver in progress since Tue Jul 15 10:00:00 2025
+\t1610612736 scanned at 104857600/s, 536870912 issued at 52428800/s, \
+10737418240 total
+\t536870912 resilvered, 5.12% done, 0 days 00:03:00 to go
+config:
+
+\tNAME                                        STATE     READ WRITE CKSUM
+\toxp_d462a7f7-b628-40fe-80ff-4e4189e2d62b    DEGRADED     3     0  1234
+\t  c1t0d0                                    DEGRADED     3     0  1234
+
+errors: No known data errors
+";
+        assert_eq!(
+            ZpoolHealthStatus::parse_zpool_status(input),
+            Ok(ZpoolHealthStatus {
+                health: ZpoolHealth::Degraded,
+                read_errors: 3,
+                write_errors: 0,
+                checksum_errors: 1234,
+                resilver: Some(ZpoolResilverStatus {
+                    state: ZpoolResilverState::InProgress,
+                    start_time: Some(utc("2025-07-15T10:00:00Z")),
+                    end_time: None,
+                    percent_done: Some(5),
+                    errors: None,
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_health_status_resilvered() {
+        let input = zpool_status(
+            "  scan: resilvered 1610612736 in 0 days 00:01:02 with 2 errors \
+             on Mon Jul  7 09:30:00 2025\n",
+        );
+        assert_eq!(
+            ZpoolHealthStatus::parse_zpool_status(&input).unwrap().resilver,
+            Some(ZpoolResilverStatus {
+                state: ZpoolResilverState::Finished,
+                start_time: None,
+                end_time: Some(utc("2025-07-07T09:30:00Z")),
+                percent_done: None,
+                errors: Some(2),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_health_status_errors() {
+        // Unrecognized pool state
+        let input = zpool_status("  scan: none requested\n")
+            .replace("state: ONLINE", "state: CONFUSED");
+        assert!(ZpoolHealthStatus::parse_zpool_status(&input).is_err());
+
+        // Abbreviated counters, as printed without "-p"
+        let input = zpool_status("  scan: none requested\n").replace(
+            "ONLINE       0     0     0\n\t  c1t0d0",
+            "ONLINE    1.2K     0     0\n\t  c1t0d0",
+        );
+        assert!(ZpoolHealthStatus::parse_zpool_status(&input).is_err());
+
+        // No "config:" section at all
+        let input = format!("{ZPOOL_STATUS_HEADER}  scan: none requested\n");
+        assert_eq!(
+            ZpoolHealthStatus::parse_zpool_status(&input),
+            Err(ParseError(
+                "Missing 'config' section in zpool status output".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_zpool_features() {
+        let input = "size\t10000\n\
+                     health\tONLINE\n\
+                     feature@async_destroy\tenabled\n\
+                     feature@encryption\tactive\n\
+                     feature@draid\tdisabled\n\
+                     unsupported@com.example:future\tinactive\n";
+        assert_eq!(
+            parse_zpool_features(input),
+            Ok(BTreeMap::from([
+                ("async_destroy".to_string(), ZpoolFeatureState::Enabled),
+                ("draid".to_string(), ZpoolFeatureState::Disabled),
+                ("encryption".to_string(), ZpoolFeatureState::Active),
+            ]))
+        );
+
+        assert!(parse_zpool_features("feature@encryption\tsomeday\n").is_err());
+        assert!(parse_zpool_features("not tab separated\n").is_err());
+    }
 }
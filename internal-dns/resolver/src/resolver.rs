@@ -514,6 +514,7 @@ mod test {
                 store,
                 &dns_server::dns_server::Config {
                     bind_address: "[::1]:0".parse().unwrap(),
+                    query_log: Default::default(),
                 },
                 &dropshot::ConfigDropshot {
                     bind_address: "[::1]:0".parse().unwrap(),
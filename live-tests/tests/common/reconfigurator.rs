@@ -97,6 +97,7 @@ pub async fn blueprint_edit_current_target(
         .blueprint_target_set(&BlueprintTargetSet {
             enabled: true,
             target_id: blueprint2.id,
+            skip_validation: false,
         })
         .await
         .expect("setting new target");
@@ -313,6 +313,7 @@ async fn test_nexus_add_remove(lc: &LiveTestContext) {
         .blueprint_target_set(&BlueprintTargetSet {
             enabled: true,
             target_id: new_blueprint.id,
+            skip_validation: false,
         })
         .await
         .expect("setting target blueprint");
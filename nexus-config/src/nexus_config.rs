@@ -435,6 +435,18 @@ pub struct BackgroundTaskConfig {
     pub webhook_deliverator: WebhookDeliveratorConfig,
     /// configuration for SP ereport ingester task
     pub sp_ereport_ingester: SpEreportIngesterConfig,
+    /// configuration for ClickHouse backup task
+    pub clickhouse_backup: ClickhouseBackupConfig,
+    /// configuration for dataset quota tuner task
+    pub dataset_quota_tuner: DatasetQuotaTunerConfig,
+    /// configuration for snapshot scheduler task
+    pub snapshot_scheduler: SnapshotSchedulerConfig,
+    /// configuration for zpool usage trends task
+    pub zpool_usage_trends: ZpoolUsageTrendsConfig,
+    /// configuration for zpool scrub alerts task
+    pub zpool_scrub_alerts: ZpoolScrubAlertsConfig,
+    /// configuration for rolling sled reboot task
+    pub rolling_sled_reboot: RollingSledRebootConfig,
 }
 
 #[serde_as]
@@ -832,6 +844,124 @@ impl Default for SpEreportIngesterConfig {
     }
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClickhouseBackupConfig {
+    /// period (in seconds) for periodic activations of this background task,
+    /// each of which takes a new backup of the oximeter database
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub period_secs: Duration,
+
+    /// number of backups to keep; the oldest backups beyond this are deleted
+    /// after each successful backup
+    pub retain_count: usize,
+
+    /// disable ClickHouse backups altogether
+    ///
+    /// Default: Off
+    #[serde(default)]
+    pub disable: bool,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DatasetQuotaTunerConfig {
+    /// period (in seconds) for periodic activations of this background task
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub period_secs: Duration,
+
+    /// free space, as a percentage of a dataset's observed usage, to leave
+    /// under the quota proposed for that dataset
+    pub headroom_percent: u32,
+
+    /// smallest quota (in GiB) that will be proposed for a dataset
+    pub min_quota_gib: u32,
+
+    /// largest quota (in GiB) that will be proposed for a dataset
+    pub max_quota_gib: u32,
+
+    /// disable quota proposals altogether
+    ///
+    /// Default: Off
+    #[serde(default)]
+    pub disable: bool,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ZpoolUsageTrendsConfig {
+    /// period (in seconds) for periodic activations of this background task
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub period_secs: Duration,
+
+    /// how long (in days) to keep samples of each zpool's usage
+    pub retention_days: u32,
+
+    /// how far back (in days) to look when projecting each zpool's usage
+    pub trend_window_days: u32,
+
+    /// usage, as a percentage of a zpool's size, at which the zpool is
+    /// considered nearly full
+    pub threshold_percent: u8,
+
+    /// raise an alert for zpools projected to be nearly full within this many
+    /// days
+    pub warning_days: u32,
+
+    /// disable zpool usage sampling and alerts altogether
+    ///
+    /// Default: Off
+    #[serde(default)]
+    pub disable: bool,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ZpoolScrubAlertsConfig {
+    /// period (in seconds) for periodic activations of this background task
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub period_secs: Duration,
+
+    /// disable alerts for zpool scrubs that found errors
+    ///
+    /// Default: Off
+    #[serde(default)]
+    pub disable: bool,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RollingSledRebootConfig {
+    /// period (in seconds) for periodic activations of this background task
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub period_secs: Duration,
+
+    /// how long (in seconds) a sled may take to be drained of instances
+    /// before it's given up on
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub drain_timeout_secs: Duration,
+
+    /// how long (in seconds) a sled may take to report its zones running again
+    /// after being reset before it's given up on
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub rejoin_timeout_secs: Duration,
+
+    /// disable rolling sled reboots altogether
+    ///
+    /// Default: Off
+    #[serde(default)]
+    pub disable: bool,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SnapshotSchedulerConfig {
+    /// period (in seconds) for periodic activations of this background task,
+    /// each of which takes snapshots for the snapshot schedules that are due
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub period_secs: Duration,
+}
+
 /// Configuration for a nexus server
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct PackageConfig {
@@ -1110,6 +1240,22 @@ mod test {
             webhook_deliverator.first_retry_backoff_secs = 45
             webhook_deliverator.second_retry_backoff_secs = 46
             sp_ereport_ingester.period_secs = 47
+            clickhouse_backup.period_secs = 48
+            clickhouse_backup.retain_count = 49
+            dataset_quota_tuner.period_secs = 50
+            dataset_quota_tuner.headroom_percent = 25
+            dataset_quota_tuner.min_quota_gib = 10
+            dataset_quota_tuner.max_quota_gib = 4096
+            snapshot_scheduler.period_secs = 51
+            zpool_usage_trends.period_secs = 52
+            zpool_usage_trends.retention_days = 90
+            zpool_usage_trends.trend_window_days = 14
+            zpool_usage_trends.threshold_percent = 80
+            zpool_usage_trends.warning_days = 30
+            zpool_scrub_alerts.period_secs = 53
+            rolling_sled_reboot.period_secs = 54
+            rolling_sled_reboot.drain_timeout_secs = 1800
+            rolling_sled_reboot.rejoin_timeout_secs = 3600
             [default_region_allocation_strategy]
             type = "random"
             seed = 0
@@ -1331,6 +1477,39 @@ mod test {
                             period_secs: Duration::from_secs(47),
                             disable: false,
                         },
+                        clickhouse_backup: ClickhouseBackupConfig {
+                            period_secs: Duration::from_secs(48),
+                            retain_count: 49,
+                            disable: false,
+                        },
+                        dataset_quota_tuner: DatasetQuotaTunerConfig {
+                            period_secs: Duration::from_secs(50),
+                            headroom_percent: 25,
+                            min_quota_gib: 10,
+                            max_quota_gib: 4096,
+                            disable: false,
+                        },
+                        snapshot_scheduler: SnapshotSchedulerConfig {
+                            period_secs: Duration::from_secs(51),
+                        },
+                        zpool_usage_trends: ZpoolUsageTrendsConfig {
+                            period_secs: Duration::from_secs(52),
+                            retention_days: 90,
+                            trend_window_days: 14,
+                            threshold_percent: 80,
+                            warning_days: 30,
+                            disable: false,
+                        },
+                        zpool_scrub_alerts: ZpoolScrubAlertsConfig {
+                            period_secs: Duration::from_secs(53),
+                            disable: false,
+                        },
+                        rolling_sled_reboot: RollingSledRebootConfig {
+                            period_secs: Duration::from_secs(54),
+                            drain_timeout_secs: Duration::from_secs(1800),
+                            rejoin_timeout_secs: Duration::from_secs(3600),
+                            disable: false,
+                        },
                     },
                     default_region_allocation_strategy:
                         crate::nexus_config::RegionAllocationStrategy::Random {
@@ -1422,6 +1601,22 @@ mod test {
             alert_dispatcher.period_secs = 42
             webhook_deliverator.period_secs = 43
             sp_ereport_ingester.period_secs = 44
+            clickhouse_backup.period_secs = 45
+            clickhouse_backup.retain_count = 46
+            dataset_quota_tuner.period_secs = 47
+            dataset_quota_tuner.headroom_percent = 25
+            dataset_quota_tuner.min_quota_gib = 10
+            dataset_quota_tuner.max_quota_gib = 4096
+            snapshot_scheduler.period_secs = 48
+            zpool_usage_trends.period_secs = 49
+            zpool_usage_trends.retention_days = 90
+            zpool_usage_trends.trend_window_days = 14
+            zpool_usage_trends.threshold_percent = 80
+            zpool_usage_trends.warning_days = 30
+            zpool_scrub_alerts.period_secs = 50
+            rolling_sled_reboot.period_secs = 51
+            rolling_sled_reboot.drain_timeout_secs = 1800
+            rolling_sled_reboot.rejoin_timeout_secs = 3600
 
             [default_region_allocation_strategy]
             type = "random"
@@ -4,6 +4,10 @@
 
 //! Inventory types shared between Nexus and sled-agent.
 
+pub mod v1;
+pub mod v4;
+pub mod v6;
+
 use std::collections::BTreeMap;
 use std::fmt::{self, Write};
 use std::net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6};
@@ -40,6 +44,7 @@ use omicron_uuid_kinds::{SledUuid, ZpoolUuid};
 use schemars::schema::{Schema, SchemaObject};
 use schemars::{JsonSchema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 // Export these types for convenience -- this way, dependents don't have to
 // depend on sled-hardware-types.
 pub use sled_hardware_types::{Baseboard, SledCpuFamily};
@@ -67,6 +72,187 @@ pub struct InventoryDisk {
 pub struct InventoryZpool {
     pub id: ZpoolUuid,
     pub total_size: ByteCount,
+    /// Space allocated within the zpool
+    ///
+    /// `None` if reported by a sled-agent that predates this field.
+    pub allocated: Option<ByteCount>,
+    /// Fragmentation of the zpool's free space, in whole percent
+    ///
+    /// `None` if ZFS does not report fragmentation for the zpool, or if
+    /// reported by a sled-agent that predates this field.
+    pub fragmentation_percent: Option<u8>,
+    /// Progress of the most recent scrub of this zpool
+    ///
+    /// `None` if the zpool has never been scrubbed, if the most recent scan
+    /// of the zpool was a resilver, or if the sled-agent failed to determine
+    /// the scrub status.
+    pub scrub: Option<InventoryZpoolScrub>,
+    /// Health of the zpool, its error counters, and the progress of its most
+    /// recent resilver
+    ///
+    /// `None` if the sled-agent failed to determine the zpool's health, or if
+    /// reported by a sled-agent that predates this field.
+    pub health: Option<InventoryZpoolHealth>,
+}
+
+/// State of the most recent scrub of a zpool
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryZpoolScrubState {
+    /// A scrub is currently running.
+    InProgress,
+    /// A scrub was started and then paused.
+    Paused,
+    /// The most recent scrub ran to completion.
+    Finished,
+    /// The most recent scrub was canceled.
+    Canceled,
+}
+
+/// Progress of the most recent scrub of a zpool
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+pub struct InventoryZpoolScrub {
+    pub state: InventoryZpoolScrubState,
+    /// When the scrub started (only reported for in-progress or paused
+    /// scrubs)
+    pub start_time: Option<DateTime<Utc>>,
+    /// When the scrub finished or was canceled
+    pub end_time: Option<DateTime<Utc>>,
+    /// How much of the zpool has been scrubbed, in whole percent (only
+    /// reported for in-progress or paused scrubs)
+    pub percent_done: Option<u8>,
+    /// Number of errors found by the scrub (only reported for finished
+    /// scrubs)
+    pub errors: Option<u64>,
+}
+
+impl From<illumos_utils::zpool::ZpoolScrubStatus> for InventoryZpoolScrub {
+    fn from(status: illumos_utils::zpool::ZpoolScrubStatus) -> Self {
+        use illumos_utils::zpool::ZpoolScrubState;
+
+        let state = match status.state {
+            ZpoolScrubState::InProgress => InventoryZpoolScrubState::InProgress,
+            ZpoolScrubState::Paused => InventoryZpoolScrubState::Paused,
+            ZpoolScrubState::Finished => InventoryZpoolScrubState::Finished,
+            ZpoolScrubState::Canceled => InventoryZpoolScrubState::Canceled,
+        };
+        Self {
+            state,
+            start_time: status.start_time,
+            end_time: status.end_time,
+            percent_done: status.percent_done,
+            errors: status.errors,
+        }
+    }
+}
+
+/// Health of a zpool, as reported by `zpool status`
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryZpoolHealthState {
+    /// The zpool is online and functioning.
+    Online,
+    /// One or more devices are degraded or faulted, but the zpool continues
+    /// to function.
+    Degraded,
+    /// One or more devices are degraded or faulted, and the zpool cannot
+    /// continue to function.
+    Faulted,
+    /// The zpool was taken offline.
+    Offline,
+    /// The zpool's device was removed.
+    Removed,
+    /// The zpool's device could not be opened.
+    Unavailable,
+}
+
+/// State of the most recent resilver of a zpool
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryZpoolResilverState {
+    /// A resilver is currently running.
+    InProgress,
+    /// The most recent resilver ran to completion.
+    Finished,
+}
+
+/// Progress of the most recent resilver of a zpool
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+pub struct InventoryZpoolResilver {
+    pub state: InventoryZpoolResilverState,
+    /// When the resilver started (only reported for in-progress resilvers)
+    pub start_time: Option<DateTime<Utc>>,
+    /// When the resilver finished
+    pub end_time: Option<DateTime<Utc>>,
+    /// How much of the zpool has been resilvered, in whole percent (only
+    /// reported for in-progress resilvers)
+    pub percent_done: Option<u8>,
+    /// Number of errors found by the resilver (only reported for finished
+    /// resilvers)
+    pub errors: Option<u64>,
+}
+
+/// Health and error counters of a zpool
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+pub struct InventoryZpoolHealth {
+    pub state: InventoryZpoolHealthState,
+    /// Read I/O errors since the zpool's counters were last cleared
+    pub read_errors: u64,
+    /// Write I/O errors since the zpool's counters were last cleared
+    pub write_errors: u64,
+    /// Checksum errors since the zpool's counters were last cleared
+    pub checksum_errors: u64,
+    /// Progress of the most recent resilver of this zpool
+    ///
+    /// `None` if the zpool has never been resilvered, or if the most recent
+    /// scan of the zpool was a scrub.
+    pub resilver: Option<InventoryZpoolResilver>,
+}
+
+impl From<illumos_utils::zpool::ZpoolHealthStatus> for InventoryZpoolHealth {
+    fn from(status: illumos_utils::zpool::ZpoolHealthStatus) -> Self {
+        use illumos_utils::zpool::ZpoolHealth;
+        use illumos_utils::zpool::ZpoolResilverState;
+
+        let state = match status.health {
+            ZpoolHealth::Online => InventoryZpoolHealthState::Online,
+            ZpoolHealth::Degraded => InventoryZpoolHealthState::Degraded,
+            ZpoolHealth::Faulted => InventoryZpoolHealthState::Faulted,
+            ZpoolHealth::Offline => InventoryZpoolHealthState::Offline,
+            ZpoolHealth::Removed => InventoryZpoolHealthState::Removed,
+            ZpoolHealth::Unavailable => InventoryZpoolHealthState::Unavailable,
+        };
+        let resilver = status.resilver.map(|resilver| {
+            let state = match resilver.state {
+                ZpoolResilverState::InProgress => {
+                    InventoryZpoolResilverState::InProgress
+                }
+                ZpoolResilverState::Finished => {
+                    InventoryZpoolResilverState::Finished
+                }
+            };
+            InventoryZpoolResilver {
+                state,
+                start_time: resilver.start_time,
+                end_time: resilver.end_time,
+                percent_done: resilver.percent_done,
+                errors: resilver.errors,
+            }
+        });
+        Self {
+            state,
+            read_errors: status.read_errors,
+            write_errors: status.write_errors,
+            checksum_errors: status.checksum_errors,
+            resilver,
+        }
+    }
 }
 
 /// Identifies information about datasets within Oxide-managed zpools
@@ -132,6 +318,303 @@ pub struct Inventory {
     pub zone_image_resolver: ZoneImageResolverInventory,
 }
 
+impl Inventory {
+    /// Returns digests of each section of this inventory that can be omitted
+    /// from [`InventoryChanges`]
+    pub fn digests(&self) -> InventoryDigests {
+        fn digest<T: Serialize>(section: &T) -> InventorySectionDigest {
+            let json = serde_json::to_vec(section)
+                .expect("inventory sections can be serialized as JSON");
+            InventorySectionDigest(hex::encode(Sha256::digest(json)))
+        }
+
+        InventoryDigests {
+            datasets: digest(&self.datasets),
+            disks: digest(&self.disks),
+            sled_config: digest(&(
+                &self.ledgered_sled_config,
+                &self.reconciler_status,
+                &self.last_reconciliation,
+            )),
+            zone_image_resolver: digest(&self.zone_image_resolver),
+            zpools: digest(&self.zpools),
+        }
+    }
+
+    /// Returns this inventory, omitting the sections whose digests match the
+    /// ones in `known`
+    pub fn changes_since(
+        self,
+        known: &InventoryKnownDigests,
+    ) -> InventoryChanges {
+        let digests = self.digests();
+        let changed = |known: &Option<InventorySectionDigest>, current| {
+            known.as_ref() != Some(current)
+        };
+
+        // Destructure `self` so that adding a field to `Inventory` requires
+        // deciding which section it belongs to.
+        let Inventory {
+            sled_id,
+            sled_agent_address,
+            sled_role,
+            baseboard,
+            usable_hardware_threads,
+            usable_physical_ram,
+            cpu_family,
+            reservoir_size,
+            disks,
+            zpools,
+            datasets,
+            ledgered_sled_config,
+            reconciler_status,
+            last_reconciliation,
+            zone_image_resolver,
+        } = self;
+
+        InventoryChanges {
+            sled_id,
+            sled_agent_address,
+            sled_role,
+            baseboard,
+            usable_hardware_threads,
+            usable_physical_ram,
+            cpu_family,
+            reservoir_size,
+            datasets: changed(&known.datasets, &digests.datasets)
+                .then_some(datasets),
+            disks: changed(&known.disks, &digests.disks).then_some(disks),
+            sled_config: changed(&known.sled_config, &digests.sled_config)
+                .then_some(InventorySledConfig {
+                    ledgered_sled_config,
+                    reconciler_status,
+                    last_reconciliation,
+                }),
+            zone_image_resolver: changed(
+                &known.zone_image_resolver,
+                &digests.zone_image_resolver,
+            )
+            .then_some(zone_image_resolver),
+            zpools: changed(&known.zpools, &digests.zpools).then_some(zpools),
+            digests,
+        }
+    }
+}
+
+/// Digest of one section of a sled's [`Inventory`]
+///
+/// This is the hex-encoded SHA-256 hash of the section's JSON representation.
+/// Digests are only meaningful to compare against other digests reported by
+/// the same sled agent.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Deserialize,
+    JsonSchema,
+    Serialize,
+)]
+#[serde(transparent)]
+pub struct InventorySectionDigest(pub String);
+
+impl fmt::Display for InventorySectionDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Digests of each section of a sled's [`Inventory`] that can be omitted from
+/// [`InventoryChanges`]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+pub struct InventoryDigests {
+    pub datasets: InventorySectionDigest,
+    pub disks: InventorySectionDigest,
+    /// Covers the ledgered sled config and the config reconciler's status and
+    /// last reconciliation
+    pub sled_config: InventorySectionDigest,
+    pub zone_image_resolver: InventorySectionDigest,
+    pub zpools: InventorySectionDigest,
+}
+
+impl InventoryDigests {
+    /// Returns the names of the sections whose digests differ from `other`'s
+    pub fn changed_sections(
+        &self,
+        other: &InventoryDigests,
+    ) -> Vec<&'static str> {
+        [
+            ("datasets", self.datasets == other.datasets),
+            ("disks", self.disks == other.disks),
+            ("sled_config", self.sled_config == other.sled_config),
+            (
+                "zone_image_resolver",
+                self.zone_image_resolver == other.zone_image_resolver,
+            ),
+            ("zpools", self.zpools == other.zpools),
+        ]
+        .into_iter()
+        .filter_map(|(name, same)| (!same).then_some(name))
+        .collect()
+    }
+}
+
+/// Digests of the inventory sections that a client already has
+///
+/// Sections without a digest are always included in [`InventoryChanges`].
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema, Serialize,
+)]
+pub struct InventoryKnownDigests {
+    pub datasets: Option<InventorySectionDigest>,
+    pub disks: Option<InventorySectionDigest>,
+    pub sled_config: Option<InventorySectionDigest>,
+    pub zone_image_resolver: Option<InventorySectionDigest>,
+    pub zpools: Option<InventorySectionDigest>,
+}
+
+impl From<InventoryDigests> for InventoryKnownDigests {
+    fn from(digests: InventoryDigests) -> Self {
+        let InventoryDigests {
+            datasets,
+            disks,
+            sled_config,
+            zone_image_resolver,
+            zpools,
+        } = digests;
+        InventoryKnownDigests {
+            datasets: Some(datasets),
+            disks: Some(disks),
+            sled_config: Some(sled_config),
+            zone_image_resolver: Some(zone_image_resolver),
+            zpools: Some(zpools),
+        }
+    }
+}
+
+/// The sled config section of a sled's [`Inventory`]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub struct InventorySledConfig {
+    pub ledgered_sled_config: Option<OmicronSledConfig>,
+    pub reconciler_status: ConfigReconcilerInventoryStatus,
+    pub last_reconciliation: Option<ConfigReconcilerInventory>,
+}
+
+/// A sled's [`Inventory`], omitting the sections that a client already has
+///
+/// Each optional section is omitted if its digest matched the one the client
+/// provided in [`InventoryKnownDigests`], in which case the client's copy is
+/// current.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub struct InventoryChanges {
+    pub sled_id: SledUuid,
+    pub sled_agent_address: SocketAddrV6,
+    pub sled_role: SledRole,
+    pub baseboard: Baseboard,
+    pub usable_hardware_threads: u32,
+    pub usable_physical_ram: ByteCount,
+    pub cpu_family: SledCpuFamily,
+    pub reservoir_size: ByteCount,
+    /// Digests of every section of the sled's current inventory
+    pub digests: InventoryDigests,
+    pub datasets: Option<Vec<InventoryDataset>>,
+    pub disks: Option<Vec<InventoryDisk>>,
+    pub sled_config: Option<InventorySledConfig>,
+    pub zone_image_resolver: Option<ZoneImageResolverInventory>,
+    pub zpools: Option<Vec<InventoryZpool>>,
+}
+
+/// Error returned by [`InventoryChanges::into_inventory()`]
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "inventory changes for sled {sled_id} omit section {section:?}, but no \
+     previous inventory for that sled was provided"
+)]
+pub struct InventoryChangesMissingSection {
+    pub sled_id: SledUuid,
+    pub section: &'static str,
+}
+
+impl InventoryChanges {
+    /// Assembles a complete inventory, taking any omitted sections from
+    /// `previous`
+    ///
+    /// `previous` must be the inventory whose digests were passed as the
+    /// [`InventoryKnownDigests`] that produced these changes.
+    pub fn into_inventory(
+        self,
+        previous: Option<Inventory>,
+    ) -> Result<Inventory, InventoryChangesMissingSection> {
+        let sled_id = self.sled_id;
+        let (
+            prev_datasets,
+            prev_disks,
+            prev_sled_config,
+            prev_zone_image_resolver,
+            prev_zpools,
+        ) = match previous {
+            Some(previous) => (
+                Some(previous.datasets),
+                Some(previous.disks),
+                Some(InventorySledConfig {
+                    ledgered_sled_config: previous.ledgered_sled_config,
+                    reconciler_status: previous.reconciler_status,
+                    last_reconciliation: previous.last_reconciliation,
+                }),
+                Some(previous.zone_image_resolver),
+                Some(previous.zpools),
+            ),
+            None => (None, None, None, None, None),
+        };
+        fn section<T>(
+            sled_id: SledUuid,
+            section: &'static str,
+            current: Option<T>,
+            previous: Option<T>,
+        ) -> Result<T, InventoryChangesMissingSection> {
+            current
+                .or(previous)
+                .ok_or(InventoryChangesMissingSection { sled_id, section })
+        }
+
+        let sled_config = section(
+            sled_id,
+            "sled_config",
+            self.sled_config,
+            prev_sled_config,
+        )?;
+        Ok(Inventory {
+            sled_id,
+            sled_agent_address: self.sled_agent_address,
+            sled_role: self.sled_role,
+            baseboard: self.baseboard,
+            usable_hardware_threads: self.usable_hardware_threads,
+            usable_physical_ram: self.usable_physical_ram,
+            cpu_family: self.cpu_family,
+            reservoir_size: self.reservoir_size,
+            disks: section(sled_id, "disks", self.disks, prev_disks)?,
+            zpools: section(sled_id, "zpools", self.zpools, prev_zpools)?,
+            datasets: section(
+                sled_id,
+                "datasets",
+                self.datasets,
+                prev_datasets,
+            )?,
+            ledgered_sled_config: sled_config.ledgered_sled_config,
+            reconciler_status: sled_config.reconciler_status,
+            last_reconciliation: sled_config.last_reconciliation,
+            zone_image_resolver: section(
+                sled_id,
+                "zone_image_resolver",
+                self.zone_image_resolver,
+                prev_zone_image_resolver,
+            )?,
+        })
+    }
+}
+
 /// Describes the last attempt made by the sled-agent-config-reconciler to
 /// reconcile the current sled config against the actual state of the sled.
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
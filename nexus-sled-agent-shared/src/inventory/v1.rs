@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Inventory types as reported by versions of the sled-agent API prior to
+//! `ADD_ZPOOL_SCRUB_STATUS`.
+//!
+//! These must not change: they define the blessed OpenAPI documents for those
+//! versions.
+
+use super::{
+    Baseboard, ConfigReconcilerInventory, ConfigReconcilerInventoryStatus,
+    InventoryDataset, InventoryDisk, OmicronSledConfig, SledCpuFamily,
+    SledRole, ZoneImageResolverInventory,
+};
+use omicron_common::api::external::ByteCount;
+use omicron_uuid_kinds::{SledUuid, ZpoolUuid};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddrV6;
+
+/// Identifies information about zpools managed by the control plane
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub struct InventoryZpool {
+    pub id: ZpoolUuid,
+    pub total_size: ByteCount,
+}
+
+impl From<super::InventoryZpool> for InventoryZpool {
+    fn from(zpool: super::InventoryZpool) -> Self {
+        Self { id: zpool.id, total_size: zpool.total_size }
+    }
+}
+
+/// Identity and basic status information about this sled agent
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub struct Inventory {
+    pub sled_id: SledUuid,
+    pub sled_agent_address: SocketAddrV6,
+    pub sled_role: SledRole,
+    pub baseboard: Baseboard,
+    pub usable_hardware_threads: u32,
+    pub usable_physical_ram: ByteCount,
+    pub cpu_family: SledCpuFamily,
+    pub reservoir_size: ByteCount,
+    pub disks: Vec<InventoryDisk>,
+    pub zpools: Vec<InventoryZpool>,
+    pub datasets: Vec<InventoryDataset>,
+    pub ledgered_sled_config: Option<OmicronSledConfig>,
+    pub reconciler_status: ConfigReconcilerInventoryStatus,
+    pub last_reconciliation: Option<ConfigReconcilerInventory>,
+    pub zone_image_resolver: ZoneImageResolverInventory,
+}
+
+impl From<super::Inventory> for Inventory {
+    fn from(inventory: super::Inventory) -> Self {
+        let super::Inventory {
+            sled_id,
+            sled_agent_address,
+            sled_role,
+            baseboard,
+            usable_hardware_threads,
+            usable_physical_ram,
+            cpu_family,
+            reservoir_size,
+            disks,
+            zpools,
+            datasets,
+            ledgered_sled_config,
+            reconciler_status,
+            last_reconciliation,
+            zone_image_resolver,
+        } = inventory;
+        Self {
+            sled_id,
+            sled_agent_address,
+            sled_role,
+            baseboard,
+            usable_hardware_threads,
+            usable_physical_ram,
+            cpu_family,
+            reservoir_size,
+            disks,
+            zpools: zpools.into_iter().map(InventoryZpool::from).collect(),
+            datasets,
+            ledgered_sled_config,
+            reconciler_status,
+            last_reconciliation,
+            zone_image_resolver,
+        }
+    }
+}
@@ -131,6 +131,9 @@ pub fn make_omicron_oso(log: &slog::Logger) -> Result<OsoInit, anyhow::Error> {
         Project::init(),
         Disk::init(),
         Snapshot::init(),
+        SnapshotSchedule::init(),
+        SnapshotExport::init(),
+        Operation::init(),
         ProjectImage::init(),
         AffinityGroup::init(),
         AntiAffinityGroup::init(),
@@ -48,7 +48,13 @@ pub struct BackgroundTasks {
     pub task_alert_dispatcher: Activator,
     pub task_webhook_deliverator: Activator,
     pub task_sp_ereport_ingester: Activator,
+    pub task_clickhouse_backup: Activator,
+    pub task_dataset_quota_tuner: Activator,
+    pub task_snapshot_scheduler: Activator,
     pub task_chicken_switches_loader: Activator,
+    pub task_zpool_usage_trends: Activator,
+    pub task_zpool_scrub_alerts: Activator,
+    pub task_rolling_sled_reboot: Activator,
 
     // Handles to activate background tasks that do not get used by Nexus
     // at-large.  These background tasks are implementation details as far as
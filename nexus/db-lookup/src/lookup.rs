@@ -137,6 +137,21 @@ impl<'a> LookupPath<'a> {
         Snapshot::PrimaryKey(Root { lookup_root: self }, id)
     }
 
+    /// Select a resource of type SnapshotSchedule, identified by its id
+    pub fn snapshot_schedule_id(self, id: Uuid) -> SnapshotSchedule<'a> {
+        SnapshotSchedule::PrimaryKey(Root { lookup_root: self }, id)
+    }
+
+    /// Select a resource of type SnapshotExport, identified by its id
+    pub fn snapshot_export_id(self, id: Uuid) -> SnapshotExport<'a> {
+        SnapshotExport::PrimaryKey(Root { lookup_root: self }, id)
+    }
+
+    /// Select a resource of type Operation, identified by its id
+    pub fn operation_id(self, id: Uuid) -> Operation<'a> {
+        Operation::PrimaryKey(Root { lookup_root: self }, id)
+    }
+
     /// Select a resource of type InstanceNetworkInterface, identified by its id
     pub fn instance_network_interface_id(
         self,
@@ -637,6 +652,30 @@ lookup_resource! {
     primary_key_columns = [ { column_name = "id", rust_type = Uuid } ]
 }
 
+lookup_resource! {
+    name = "SnapshotSchedule",
+    ancestors = [ "Silo", "Project" ],
+    lookup_by_name = true,
+    soft_deletes = true,
+    primary_key_columns = [ { column_name = "id", rust_type = Uuid } ]
+}
+
+lookup_resource! {
+    name = "SnapshotExport",
+    ancestors = [ "Silo", "Project" ],
+    lookup_by_name = false,
+    soft_deletes = true,
+    primary_key_columns = [ { column_name = "id", rust_type = Uuid } ]
+}
+
+lookup_resource! {
+    name = "Operation",
+    ancestors = [ "Silo", "Project" ],
+    lookup_by_name = false,
+    soft_deletes = false,
+    primary_key_columns = [ { column_name = "id", rust_type = Uuid } ]
+}
+
 lookup_resource! {
     name = "Instance",
     ancestors = [ "Silo", "Project" ],
@@ -30,6 +30,8 @@ impl_enum_type!(
     TestFooBaz => b"test.foo.baz"
     TestQuuxBar => b"test.quux.bar"
     TestQuuxBarBaz => b"test.quux.bar.baz"
+    StorageZpoolCapacity => b"storage.zpool.capacity"
+    StorageZpoolScrubErrors => b"storage.zpool.scrub_errors"
 );
 
 impl AlertClass {
@@ -44,6 +46,8 @@ impl AlertClass {
             Self::TestFooBaz => "test.foo.baz",
             Self::TestQuuxBar => "test.quux.bar",
             Self::TestQuuxBarBaz => "test.quux.bar.baz",
+            Self::StorageZpoolCapacity => "storage.zpool.capacity",
+            Self::StorageZpoolScrubErrors => "storage.zpool.scrub_errors",
         }
     }
 
@@ -76,6 +80,17 @@ impl AlertClass {
             | Self::TestQuuxBarBaz => {
                 "This is a test of the emergency alert system"
             }
+            Self::StorageZpoolCapacity => {
+                "A zpool is nearly full, or the growth of its allocated space \
+                 projects that it will be nearly full soon. Storage should be \
+                 expanded (or data moved off the zpool's sled) before it \
+                 runs out of space."
+            }
+            Self::StorageZpoolScrubErrors => {
+                "A scrub of a zpool found data errors. Data on the zpool may \
+                 be damaged, and the zpool's disk may be failing and need to \
+                 be replaced."
+            }
         }
     }
 
@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::to_db_typed_uuid;
+use crate::typed_uuid::DbTypedUuid;
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::break_glass_account;
+use nexus_types::internal_api::views;
+use nexus_types::silo::BREAK_GLASS_USER_NAME;
+use nexus_types::silo::default_silo_name;
+use omicron_uuid_kinds::SiloUserKind;
+use omicron_uuid_kinds::SiloUserUuid;
+use uuid::Uuid;
+
+/// A period during which the break-glass operator account may be used
+#[derive(Queryable, Insertable, Clone, Debug, Selectable)]
+#[diesel(table_name = break_glass_account)]
+pub struct BreakGlassAccount {
+    pub id: Uuid,
+    pub time_created: DateTime<Utc>,
+    silo_user_id: DbTypedUuid<SiloUserKind>,
+    pub enabled_by: String,
+    pub time_expires: DateTime<Utc>,
+    pub time_disabled: Option<DateTime<Utc>>,
+    pub disabled_by: Option<String>,
+}
+
+impl BreakGlassAccount {
+    pub fn new(
+        silo_user_id: SiloUserUuid,
+        enabled_by: String,
+        time_expires: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            time_created: Utc::now(),
+            silo_user_id: to_db_typed_uuid(silo_user_id),
+            enabled_by,
+            time_expires,
+            time_disabled: None,
+            disabled_by: None,
+        }
+    }
+
+    pub fn silo_user_id(&self) -> SiloUserUuid {
+        self.silo_user_id.into()
+    }
+
+    /// Returns whether the account may be used to log in at time `now`
+    pub fn is_usable_at(&self, now: DateTime<Utc>) -> bool {
+        self.time_disabled.is_none() && now < self.time_expires
+    }
+}
+
+impl From<BreakGlassAccount> for views::BreakGlassAccount {
+    fn from(account: BreakGlassAccount) -> Self {
+        Self {
+            id: account.id,
+            silo_name: default_silo_name().clone(),
+            username: BREAK_GLASS_USER_NAME.to_string(),
+            silo_user_id: account.silo_user_id.into(),
+            time_created: account.time_created,
+            enabled_by: account.enabled_by,
+            time_expires: account.time_expires,
+            time_disabled: account.time_disabled,
+            disabled_by: account.disabled_by,
+        }
+    }
+}
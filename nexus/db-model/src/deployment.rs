@@ -24,12 +24,14 @@ use nexus_db_schema::schema::{
     bp_omicron_physical_disk, bp_omicron_zone, bp_omicron_zone_nic,
     bp_oximeter_read_policy, bp_pending_mgs_update_host_phase_1,
     bp_pending_mgs_update_rot, bp_pending_mgs_update_rot_bootloader,
-    bp_pending_mgs_update_sp, bp_sled_metadata, bp_target,
+    bp_pending_mgs_update_sp, bp_planning_report, bp_sled_metadata, bp_target,
 };
 use nexus_sled_agent_shared::inventory::OmicronZoneDataset;
+use nexus_types::deployment::BlueprintExecutionDisabled;
 use nexus_types::deployment::BlueprintHostPhase2DesiredSlots;
 use nexus_types::deployment::BlueprintPhysicalDiskConfig;
 use nexus_types::deployment::BlueprintPhysicalDiskDisposition;
+use nexus_types::deployment::BlueprintScope;
 use nexus_types::deployment::BlueprintTarget;
 use nexus_types::deployment::BlueprintZoneConfig;
 use nexus_types::deployment::BlueprintZoneDisposition;
@@ -42,6 +44,7 @@ use nexus_types::deployment::PendingMgsUpdateDetails;
 use nexus_types::deployment::PendingMgsUpdateRotBootloaderDetails;
 use nexus_types::deployment::PendingMgsUpdateRotDetails;
 use nexus_types::deployment::PendingMgsUpdateSpDetails;
+use nexus_types::deployment::PlanningReport;
 use nexus_types::deployment::{
     BlueprintArtifactVersion, BlueprintDatasetConfig, OximeterReadMode,
 };
@@ -82,6 +85,23 @@ pub struct Blueprint {
     pub comment: String,
     pub target_release_minimum_generation: Generation,
     pub nexus_generation: Generation,
+    /// Sleds to which the blueprint is scoped, or `None` if it covers the
+    /// whole fleet (see [`BlueprintScope`])
+    pub scope_sled_ids: Option<Vec<Uuid>>,
+}
+
+impl Blueprint {
+    pub fn scope(&self) -> BlueprintScope {
+        match &self.scope_sled_ids {
+            None => BlueprintScope::Fleet,
+            Some(sled_ids) => BlueprintScope::Sleds(
+                sled_ids
+                    .iter()
+                    .map(|id| SledUuid::from_untyped_uuid(*id))
+                    .collect(),
+            ),
+        }
+    }
 }
 
 impl From<&'_ nexus_types::deployment::Blueprint> for Blueprint {
@@ -102,6 +122,12 @@ impl From<&'_ nexus_types::deployment::Blueprint> for Blueprint {
                 bp.target_release_minimum_generation,
             ),
             nexus_generation: Generation(bp.nexus_generation),
+            scope_sled_ids: match &bp.scope {
+                BlueprintScope::Fleet => None,
+                BlueprintScope::Sleds(sled_ids) => Some(
+                    sled_ids.iter().map(|id| id.into_untyped_uuid()).collect(),
+                ),
+            },
         }
     }
 }
@@ -137,6 +163,8 @@ pub struct BpTarget {
     pub blueprint_id: DbTypedUuid<BlueprintKind>,
     pub enabled: bool,
     pub time_made_target: DateTime<Utc>,
+    pub execution_disabled_reason: Option<String>,
+    pub time_execution_disabled: Option<DateTime<Utc>>,
 }
 
 impl BpTarget {
@@ -146,6 +174,22 @@ impl BpTarget {
             blueprint_id: target.target_id.into(),
             enabled: target.enabled,
             time_made_target: target.time_made_target,
+            execution_disabled_reason: None,
+            time_execution_disabled: None,
+        }
+    }
+
+    /// Returns why and when execution of this target was disabled, if it was
+    pub fn execution_disabled(&self) -> Option<BlueprintExecutionDisabled> {
+        // The database ensures these are either both set or both NULL.
+        match (&self.execution_disabled_reason, self.time_execution_disabled) {
+            (Some(reason), Some(time_disabled)) => {
+                Some(BlueprintExecutionDisabled {
+                    reason: reason.clone(),
+                    time_disabled,
+                })
+            }
+            _ => None,
         }
     }
 }
@@ -999,6 +1043,7 @@ impl_enum_type!(
     // Enum values
     InService => b"in_service"
     Expunged => b"expunged"
+    Cordoned => b"cordoned"
 );
 
 struct DbBpZoneDispositionColumns {
@@ -1017,6 +1062,9 @@ impl From<BlueprintZoneDisposition> for DbBpZoneDispositionColumns {
             BlueprintZoneDisposition::InService => {
                 (DbBpZoneDisposition::InService, None, false)
             }
+            BlueprintZoneDisposition::Cordoned => {
+                (DbBpZoneDisposition::Cordoned, None, false)
+            }
             BlueprintZoneDisposition::Expunged {
                 as_of_generation,
                 ready_for_cleanup,
@@ -1042,6 +1090,7 @@ impl TryFrom<DbBpZoneDispositionColumns> for BlueprintZoneDisposition {
     ) -> Result<Self, Self::Error> {
         match (value.disposition, value.expunged_as_of_generation) {
             (DbBpZoneDisposition::InService, None) => Ok(Self::InService),
+            (DbBpZoneDisposition::Cordoned, None) => Ok(Self::Cordoned),
             (DbBpZoneDisposition::Expunged, Some(as_of_generation)) => {
                 Ok(Self::Expunged {
                     as_of_generation: *as_of_generation,
@@ -1049,6 +1098,7 @@ impl TryFrom<DbBpZoneDispositionColumns> for BlueprintZoneDisposition {
                 })
             }
             (DbBpZoneDisposition::InService, Some(_))
+            | (DbBpZoneDisposition::Cordoned, Some(_))
             | (DbBpZoneDisposition::Expunged, None) => Err(anyhow!(
                 "illegal database state (CHECK constraint broken?!): \
                  disposition {:?}, disposition_expunged_as_of_generation {:?}",
@@ -1320,6 +1370,31 @@ impl BpOximeterReadPolicy {
     }
 }
 
+/// The report from the planning run that produced a blueprint.
+///
+/// Reports exist to help operators and developers understand what the planner
+/// did, and nothing reads them back to make decisions, so they're stored as
+/// JSON rather than broken out into tables of their own.
+#[derive(Queryable, Clone, Debug, Selectable, Insertable)]
+#[diesel(table_name = bp_planning_report)]
+pub struct BpPlanningReport {
+    pub blueprint_id: DbTypedUuid<BlueprintKind>,
+    pub report: serde_json::Value,
+}
+
+impl BpPlanningReport {
+    pub fn new(
+        blueprint_id: BlueprintUuid,
+        report: &PlanningReport,
+    ) -> anyhow::Result<BpPlanningReport> {
+        Ok(BpPlanningReport {
+            blueprint_id: blueprint_id.into(),
+            report: serde_json::to_value(report)
+                .context("serializing planning report")?,
+        })
+    }
+}
+
 pub trait BpPendingMgsUpdateComponent {
     /// Converts a BpMgsUpdate into a PendingMgsUpdate
     fn into_generic(self, baseboard_id: Arc<BaseboardId>) -> PendingMgsUpdate;
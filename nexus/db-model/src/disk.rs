@@ -79,6 +79,9 @@ pub struct Disk {
     /// saga, then this field will contain the serialized SocketAddrV6 of that
     /// Pantry.
     pub pantry_address: Option<String>,
+
+    /// If true, this disk cannot be deleted until the flag is cleared
+    pub delete_protected: bool,
 }
 
 impl Disk {
@@ -115,6 +118,7 @@ impl Disk {
             create_snapshot_id,
             create_image_id,
             pantry_address: None,
+            delete_protected: false,
         })
     }
 
@@ -152,6 +156,8 @@ impl Into<external::Disk> for Disk {
             block_size: self.block_size.into(),
             state: self.state().into(),
             device_path,
+            delete_protected: self.delete_protected,
+            warnings: Vec::new(),
         }
     }
 }
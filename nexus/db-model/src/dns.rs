@@ -17,7 +17,17 @@ use uuid::Uuid;
 impl_enum_type!(
     DnsGroupEnum:
 
-    #[derive(Clone, Copy, Debug, AsExpression, FromSqlRow, PartialEq)]
+    #[derive(
+        Clone,
+        Copy,
+        Debug,
+        AsExpression,
+        FromSqlRow,
+        Eq,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
     pub enum DnsGroup;
 
     // Enum values
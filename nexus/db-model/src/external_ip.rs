@@ -134,6 +134,8 @@ pub struct ExternalIp {
     pub project_id: Option<Uuid>,
     pub state: IpAttachState,
     pub is_probe: bool,
+    /// When this IP finished attaching to its parent, if it's attached
+    pub time_attached: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, thiserror::Error, SlogInlineError)]
@@ -483,6 +485,12 @@ impl IncompleteExternalIp {
         &self.state
     }
 
+    /// Returns when this IP is attached to its parent, which is as soon as it's
+    /// created if it's created in the attached state
+    pub fn time_attached(&self) -> Option<DateTime<Utc>> {
+        (self.state == IpAttachState::Attached).then_some(self.time_created)
+    }
+
     pub fn explicit_ip(&self) -> &Option<IpNetwork> {
         &self.explicit_ip
     }
@@ -543,6 +551,23 @@ impl TryFrom<ExternalIp> for views::ExternalIp {
     }
 }
 
+impl TryFrom<ExternalIp> for views::InstanceExternalIp {
+    type Error = Error;
+
+    fn try_from(ip: ExternalIp) -> Result<Self, Self::Error> {
+        let time_attached = ip.time_attached.ok_or_else(|| {
+            Error::internal_error(&format!(
+                "external IP {} is not attached to an instance",
+                ip.id
+            ))
+        })?;
+        Ok(views::InstanceExternalIp {
+            external_ip: ip.try_into()?,
+            time_attached,
+        })
+    }
+}
+
 impl TryFrom<ExternalIp> for FloatingIp {
     type Error = Error;
 
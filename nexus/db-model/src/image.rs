@@ -8,7 +8,7 @@
 //! silo_id and a project_id, while SiloImage only has a silo_id. Image has a
 //! silo_id and an optional project_id to cover both possibilities.
 
-use super::{BlockSize, ByteCount, Digest};
+use super::{BlockSize, ByteCount, Digest, impl_enum_type};
 use crate::typed_uuid::DbTypedUuid;
 use db_macros::Resource;
 use nexus_db_schema::schema::{image, project_image, silo_image};
@@ -20,6 +20,37 @@ use omicron_uuid_kinds::VolumeUuid;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+impl_enum_type!(
+    ImageStateEnum:
+
+    #[derive(Clone, Copy, Debug, AsExpression, FromSqlRow, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum ImageState;
+
+    Active => b"active"
+    Deprecated => b"deprecated"
+    Blocked => b"blocked"
+);
+
+impl From<ImageState> for views::ImageState {
+    fn from(state: ImageState) -> Self {
+        match state {
+            ImageState::Active => Self::Active,
+            ImageState::Deprecated => Self::Deprecated,
+            ImageState::Blocked => Self::Blocked,
+        }
+    }
+}
+
+impl From<views::ImageState> for ImageState {
+    fn from(state: views::ImageState) -> Self {
+        match state {
+            views::ImageState::Active => Self::Active,
+            views::ImageState::Deprecated => Self::Deprecated,
+            views::ImageState::Blocked => Self::Blocked,
+        }
+    }
+}
+
 // Shared image definition
 #[derive(
     Queryable,
@@ -48,6 +79,8 @@ pub struct Image {
 
     #[diesel(column_name = size_bytes)]
     pub size: ByteCount,
+
+    pub state: ImageState,
 }
 
 impl Image {
@@ -75,6 +108,8 @@ pub struct ProjectImage {
 
     #[diesel(column_name = size_bytes)]
     pub size: ByteCount,
+
+    pub state: ImageState,
 }
 
 impl ProjectImage {
@@ -101,6 +136,8 @@ pub struct SiloImage {
 
     #[diesel(column_name = size_bytes)]
     pub size: ByteCount,
+
+    pub state: ImageState,
 }
 
 impl SiloImage {
@@ -132,6 +169,7 @@ impl TryFrom<Image> for ProjectImage {
                 digest: image.digest,
                 block_size: image.block_size,
                 size: image.size,
+                state: image.state,
             }),
             None => Err(Error::internal_error(
                 "tried to convert non-project image to project image",
@@ -165,6 +203,7 @@ impl TryFrom<Image> for SiloImage {
                 digest: image.digest,
                 block_size: image.block_size,
                 size: image.size,
+                state: image.state,
             }),
         }
     }
@@ -190,6 +229,7 @@ impl From<ProjectImage> for Image {
             digest: image.digest,
             block_size: image.block_size,
             size: image.size,
+            state: image.state,
         }
     }
 }
@@ -214,6 +254,7 @@ impl From<SiloImage> for Image {
             digest: image.digest,
             block_size: image.block_size,
             size: image.size,
+            state: image.state,
         }
     }
 }
@@ -228,6 +269,7 @@ impl From<Image> for views::Image {
             digest: image.digest.map(|x| x.into()),
             block_size: image.block_size.into(),
             size: image.size.into(),
+            state: image.state.into(),
         }
     }
 }
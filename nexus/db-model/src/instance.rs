@@ -5,17 +5,19 @@
 use super::InstanceIntendedState as IntendedState;
 use super::{
     ByteCount, Disk, ExternalIp, Generation, InstanceAutoRestartPolicy,
-    InstanceCpuCount, InstanceState, Vmm, VmmState,
+    InstanceCpuCount, InstanceState, InstanceStoppedReservationPolicy,
+    ProjectAutoRestartDefaults, Vmm, VmmState,
 };
 use crate::collection::DatastoreAttachTargetConfig;
 use crate::serde_time_delta::optional_time_delta;
 use chrono::{DateTime, TimeDelta, Utc};
 use db_macros::Resource;
 use diesel::expression::{ValidGrouping, is_aggregate};
+use diesel::helper_types::InnerJoinQuerySource;
 use diesel::pg;
 use diesel::prelude::*;
 use diesel::sql_types::{Bool, Nullable};
-use nexus_db_schema::schema::{disk, external_ip, instance};
+use nexus_db_schema::schema::{disk, external_ip, instance, project};
 use nexus_types::external_api::params;
 use omicron_uuid_kinds::{GenericUuid, InstanceUuid};
 use serde::Deserialize;
@@ -64,9 +66,9 @@ pub struct Instance {
     #[diesel(embed)]
     pub auto_restart: InstanceAutoRestart,
 
-    /// The primary boot disk for this instance.
-    #[diesel(column_name = boot_disk_id)]
-    pub boot_disk_id: Option<Uuid>,
+    /// The disks this instance boots from, in order of preference.
+    #[diesel(column_name = boot_order)]
+    pub boot_order: Vec<Uuid>,
 
     #[diesel(embed)]
     pub runtime_state: InstanceRuntimeState,
@@ -97,6 +99,11 @@ pub struct Instance {
     /// lock was not held is still valid when setting the lock ID.
     #[diesel(column_name = updater_gen)]
     pub updater_gen: Generation,
+
+    /// Whether the instance's sled resource reservation is kept while it is
+    /// stopped.
+    #[diesel(column_name = stopped_reservation_policy)]
+    pub stopped_reservation_policy: InstanceStoppedReservationPolicy,
 }
 
 impl Instance {
@@ -136,15 +143,19 @@ impl Instance {
             memory: params.memory.into(),
             hostname: params.hostname.to_string(),
             auto_restart,
-            // Intentionally ignore `params.boot_disk_id` here: we can't set
-            // `boot_disk_id` until the referenced disk is attached.
-            boot_disk_id: None,
+            // Intentionally ignore `params.boot_order` here: we can't set
+            // `boot_order` until the referenced disks are attached.
+            boot_order: Vec::new(),
 
             runtime_state,
             intended_state,
 
             updater_gen: Generation::new(),
             updater_id: None,
+
+            stopped_reservation_policy: params
+                .stopped_reservation_policy
+                .into(),
         }
     }
 
@@ -152,10 +163,15 @@ impl Instance {
         &self.runtime_state
     }
 
-    /// Returns an instance's karmic status.
+    /// Returns an instance's karmic status as of `now`.
+    ///
+    /// `project_defaults` are the auto-restart defaults of the project this
+    /// instance belongs to.
     pub fn auto_restart_status(
         &self,
+        project_defaults: &ProjectAutoRestartDefaults,
         active_vmm: Option<&Vmm>,
+        now: DateTime<Utc>,
     ) -> InstanceKarmicStatus {
         let state = &self.runtime_state;
         // Instances only need to be automatically restarted if they are in the
@@ -189,7 +205,11 @@ impl Instance {
 
         InstanceKarmicStatus {
             needs_reincarnation,
-            can_reincarnate: self.auto_restart.can_reincarnate(&state),
+            can_reincarnate: self.auto_restart.can_reincarnate(
+                project_defaults,
+                &state,
+                now,
+            ),
         }
     }
 }
@@ -319,8 +339,9 @@ pub struct InstanceAutoRestart {
     /// This indicates whether the instance should be automatically restarted by
     /// the control plane on failure. If this is `NULL`, no auto-restart policy
     /// has been configured for this instance by the user. In that case, the
-    /// control plane will use the default policy when determining whether
-    /// this instance can be automatically restarted.
+    /// control plane will use its project's default policy, or the fleet-wide
+    /// default if the project has none, when determining whether this
+    /// instance can be automatically restarted.
     #[diesel(column_name = auto_restart_policy)]
     #[serde(default)]
     pub policy: Option<InstanceAutoRestartPolicy>,
@@ -328,7 +349,8 @@ pub struct InstanceAutoRestart {
     /// instance.
     ///
     /// If this is `NULL`, no explicit cooldown period has been configured for
-    /// this instance, and the default cooldown period should be used instead.
+    /// this instance, and its project's default cooldown period (or the
+    /// fleet-wide default) should be used instead.
     #[diesel(column_name = auto_restart_cooldown)]
     #[serde(default, with = "optional_time_delta")]
     pub cooldown: Option<TimeDelta>,
@@ -367,30 +389,60 @@ pub enum Reincarnatability {
 }
 
 impl InstanceAutoRestart {
-    /// The default cooldown used when an instance has no overridden cooldown.
+    /// The default cooldown used when neither an instance nor its project
+    /// overrides the cooldown.
     pub const DEFAULT_COOLDOWN: TimeDelta = match TimeDelta::try_hours(1) {
         Some(delta) => delta,
         None => unreachable!(), // 1 hour should be representable...
     };
 
-    /// The default policy used when an instance does not override the
-    /// reincarnation policy.
+    /// The default policy used when neither an instance nor its project
+    /// overrides the reincarnation policy.
     pub const DEFAULT_POLICY: InstanceAutoRestartPolicy =
         InstanceAutoRestartPolicy::BestEffort;
 
+    /// Returns the auto-restart policy in effect for an instance with this
+    /// configuration in a project with the provided defaults.
+    ///
+    /// The instance's own policy takes precedence, followed by the project's
+    /// default, followed by [`Self::DEFAULT_POLICY`].
+    pub fn effective_policy(
+        &self,
+        project_defaults: &ProjectAutoRestartDefaults,
+    ) -> InstanceAutoRestartPolicy {
+        self.policy.or(project_defaults.policy).unwrap_or(Self::DEFAULT_POLICY)
+    }
+
+    /// Returns the cooldown period in effect for an instance with this
+    /// configuration in a project with the provided defaults.
+    ///
+    /// The instance's own cooldown takes precedence, followed by the project's
+    /// default, followed by [`Self::DEFAULT_COOLDOWN`].
+    pub fn effective_cooldown(
+        &self,
+        project_defaults: &ProjectAutoRestartDefaults,
+    ) -> TimeDelta {
+        self.cooldown
+            .or(project_defaults.cooldown)
+            .unwrap_or(Self::DEFAULT_COOLDOWN)
+    }
+
     /// Returns whether or not this auto-restart configuration will permit an
-    /// instance with the provided `InstanceRuntimeState` to reincarnate.
+    /// instance with the provided `InstanceRuntimeState`, in a project with
+    /// the provided defaults, to reincarnate at `now`.
     ///
     /// This does *not* indicate that the instance  currently needs
     /// reincarnation, but instead, whether the instance will be permitted to
     /// reincarnate should it be in such a state.
     pub fn can_reincarnate(
         &self,
+        project_defaults: &ProjectAutoRestartDefaults,
         state: &InstanceRuntimeState,
+        now: DateTime<Utc>,
     ) -> Reincarnatability {
-        // Check if the instance's configured auto-restart policy permits the
-        // control plane to automatically restart it.
-        let policy = self.policy.unwrap_or(Self::DEFAULT_POLICY);
+        // Check if the instance's auto-restart policy permits the control
+        // plane to automatically restart it.
+        let policy = self.effective_policy(project_defaults);
         if policy == InstanceAutoRestartPolicy::Never {
             return Reincarnatability::Nirvana;
         }
@@ -398,11 +450,8 @@ impl InstanceAutoRestart {
         // If the instance is permitted to reincarnate, ensure that its last
         // reincarnation was at least one cooldown period ago.
         if let Some(last) = state.time_last_auto_restarted {
-            // If no explicit cooldown is present, use the default.
-            // Eventually, we may also allow a project-level default, so we will
-            // need to consider that as well.
-            let cooldown = self.cooldown.unwrap_or(Self::DEFAULT_COOLDOWN);
-            let time_since_last = Utc::now().signed_duration_since(last);
+            let cooldown = self.effective_cooldown(project_defaults);
+            let time_since_last = now.signed_duration_since(last);
             if time_since_last >= cooldown {
                 return Reincarnatability::WillReincarnate;
             } else {
@@ -416,24 +465,32 @@ impl InstanceAutoRestart {
     }
 
     /// Filters a database query to include only instances whose auto-restart
-    /// configs permit them to reincarnate.
+    /// configs permit them to reincarnate at `now`.
+    ///
+    /// The query must join each instance with its project, so that the
+    /// project's defaults can be applied to instances that don't override
+    /// them, in the same order as [`Self::can_reincarnate`].
     ///
     /// Yes, this should probably be in `nexus-db-queries`, but it seemed nice
     /// for it to be defined on the same struct as the in-memory logic
     /// (`can_reincarnate`).
-    pub fn filter_reincarnatable()
-    -> impl diesel::query_builder::QueryFragment<pg::Pg>
+    pub fn filter_reincarnatable(
+        now: DateTime<Utc>,
+    ) -> impl diesel::query_builder::QueryFragment<pg::Pg>
     + diesel::query_builder::QueryId
-    // All elements in this expression appear on the `instance` table, so
-    // it's a valid `filter` for that table, and the expression evaluates
-    // to a bool (or NULL), making it a valid WHERE clause.
-    + AppearsOnTable<instance::table, SqlType = Nullable<Bool>>
+    // All elements in this expression appear on the `instance` table or the
+    // `project` table, so it's a valid `filter` for a join of the two, and the
+    // expression evaluates to a bool (or NULL), making it a valid WHERE
+    // clause.
+    + AppearsOnTable<
+        InnerJoinQuerySource<instance::table, project::table>,
+        SqlType = Nullable<Bool>,
+    >
     // I think this trait tells diesel that the query fragment has no
     // GROUP BY clause, so that it knows it can be used as a WHERE clause
     + ValidGrouping<(), IsAggregate = is_aggregate::No> {
         use instance::dsl;
-
-        let now = diesel::dsl::now.into_sql::<pg::sql_types::Timestamptz>();
+        use project::dsl as project_dsl;
 
         // The instance's auto-restart policy must allow the control plane
         // to restart it automatically.
@@ -443,9 +500,17 @@ impl InstanceAutoRestart {
         // (such as restart limits...)
         (dsl::auto_restart_policy
             .eq(InstanceAutoRestartPolicy::BestEffort)
-            // If the auto-restart policy is null, then it should
+            // If the instance has no auto-restart policy, use its project's
+            // default...
+            .or(dsl::auto_restart_policy.is_null().and(
+                project_dsl::default_auto_restart_policy
+                    .eq(InstanceAutoRestartPolicy::BestEffort),
+            ))
+            // ...and if the project has no default either, then it should
             // default to "best effort".
-            .or(dsl::auto_restart_policy.is_null()))
+            .or(dsl::auto_restart_policy
+                .is_null()
+                .and(project_dsl::default_auto_restart_policy.is_null())))
         // An instance should only be automatically restarted if it is intended
         // to be in the `Running` state. Instances which were requested to stop
         // should not be automatically restarted, even if they have failed.
@@ -460,15 +525,32 @@ impl InstanceAutoRestart {
                 .is_null()
                 // Or, if it has an overridden cooldown period, has that elapsed?
                 .or(dsl::auto_restart_cooldown.is_not_null().and(
-                    dsl::time_last_auto_restarted
-                        .le(now.nullable() - dsl::auto_restart_cooldown),
+                    (dsl::time_last_auto_restarted
+                        + dsl::auto_restart_cooldown)
+                        .le(now),
                 ))
-                // Or, finally, if it does not have an overridden cooldown
-                // period, has the default cooldown period elapsed?
-                .or(dsl::auto_restart_cooldown.is_null().and(
-                    dsl::time_last_auto_restarted
-                        .le((now - Self::DEFAULT_COOLDOWN).nullable()),
-                )),
+                // Or, if it does not have an overridden cooldown period, has
+                // its project's default cooldown period elapsed?
+                .or(dsl::auto_restart_cooldown
+                    .is_null()
+                    .and(
+                        project_dsl::default_auto_restart_cooldown
+                            .is_not_null(),
+                    )
+                    .and(
+                        (dsl::time_last_auto_restarted
+                            + project_dsl::default_auto_restart_cooldown)
+                            .le(now),
+                    ))
+                // Or, finally, if neither it nor its project overrides the
+                // cooldown period, has the default cooldown period elapsed?
+                .or(dsl::auto_restart_cooldown
+                    .is_null()
+                    .and(project_dsl::default_auto_restart_cooldown.is_null())
+                    .and(
+                        dsl::time_last_auto_restarted
+                            .le(now - Self::DEFAULT_COOLDOWN),
+                    )),
         )
         // Deleted instances may not be reincarnated.
         .and(dsl::time_deleted.is_null())
@@ -482,8 +564,10 @@ impl InstanceAutoRestart {
 #[derive(Clone, Debug, AsChangeset, Serialize, Deserialize)]
 #[diesel(table_name = instance, treat_none_as_null = true)]
 pub struct InstanceUpdate {
-    #[diesel(column_name = boot_disk_id)]
-    pub boot_disk_id: Option<Uuid>,
+    /// The disks this instance boots from, in order of preference. An empty
+    /// list clears the instance's boot order.
+    #[diesel(column_name = boot_order)]
+    pub boot_order: Vec<Uuid>,
 
     /// The auto-restart policy for this instance. If this is `None`, it will
     /// set the instance's auto-restart policy to `NULL`.
@@ -493,4 +577,144 @@ pub struct InstanceUpdate {
     pub ncpus: InstanceCpuCount,
 
     pub memory: ByteCount,
+
+    /// Whether the instance's sled resource reservation is kept while it is
+    /// stopped.
+    #[diesel(column_name = stopped_reservation_policy)]
+    pub stopped_reservation_policy: InstanceStoppedReservationPolicy,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_can_reincarnate_after_cooldown() {
+        let last = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        let mut state = InstanceRuntimeState::new(InstanceState::Failed, last);
+        let no_defaults = ProjectAutoRestartDefaults::default();
+
+        // An instance that has never been restarted may always reincarnate.
+        let auto_restart = InstanceAutoRestart::default();
+        assert_eq!(
+            auto_restart.can_reincarnate(&no_defaults, &state, last),
+            Reincarnatability::WillReincarnate
+        );
+
+        state.time_last_auto_restarted = Some(last);
+        let cooldown = InstanceAutoRestart::DEFAULT_COOLDOWN;
+        assert_eq!(
+            auto_restart.can_reincarnate(&no_defaults, &state, last),
+            Reincarnatability::CoolingDown(cooldown)
+        );
+        assert_eq!(
+            auto_restart.can_reincarnate(
+                &no_defaults,
+                &state,
+                last + cooldown - TimeDelta::seconds(1)
+            ),
+            Reincarnatability::CoolingDown(TimeDelta::seconds(1))
+        );
+        assert_eq!(
+            auto_restart.can_reincarnate(&no_defaults, &state, last + cooldown),
+            Reincarnatability::WillReincarnate
+        );
+
+        // An overridden cooldown takes the place of the default.
+        let auto_restart = InstanceAutoRestart {
+            policy: None,
+            cooldown: Some(TimeDelta::seconds(10)),
+        };
+        assert_eq!(
+            auto_restart.can_reincarnate(
+                &no_defaults,
+                &state,
+                last + TimeDelta::seconds(9)
+            ),
+            Reincarnatability::CoolingDown(TimeDelta::seconds(1))
+        );
+        assert_eq!(
+            auto_restart.can_reincarnate(
+                &no_defaults,
+                &state,
+                last + TimeDelta::seconds(10)
+            ),
+            Reincarnatability::WillReincarnate
+        );
+
+        // Instances that may never be restarted don't care about cooldowns.
+        let auto_restart = InstanceAutoRestart {
+            policy: Some(InstanceAutoRestartPolicy::Never),
+            cooldown: None,
+        };
+        assert_eq!(
+            auto_restart.can_reincarnate(&no_defaults, &state, last + cooldown),
+            Reincarnatability::Nirvana
+        );
+    }
+
+    #[test]
+    fn test_can_reincarnate_with_project_defaults() {
+        let last = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        let mut state = InstanceRuntimeState::new(InstanceState::Failed, last);
+        state.time_last_auto_restarted = Some(last);
+        let project_defaults = ProjectAutoRestartDefaults {
+            policy: Some(InstanceAutoRestartPolicy::Never),
+            cooldown: Some(TimeDelta::seconds(10)),
+        };
+
+        // An instance with no configuration of its own inherits its
+        // project's defaults.
+        let auto_restart = InstanceAutoRestart::default();
+        assert_eq!(
+            auto_restart.effective_policy(&project_defaults),
+            InstanceAutoRestartPolicy::Never
+        );
+        assert_eq!(
+            auto_restart.can_reincarnate(&project_defaults, &state, last),
+            Reincarnatability::Nirvana
+        );
+
+        // An instance's own policy overrides its project's, and the project's
+        // cooldown still applies if the instance doesn't override it.
+        let auto_restart = InstanceAutoRestart {
+            policy: Some(InstanceAutoRestartPolicy::BestEffort),
+            cooldown: None,
+        };
+        assert_eq!(
+            auto_restart.effective_cooldown(&project_defaults),
+            TimeDelta::seconds(10)
+        );
+        assert_eq!(
+            auto_restart.can_reincarnate(
+                &project_defaults,
+                &state,
+                last + TimeDelta::seconds(9)
+            ),
+            Reincarnatability::CoolingDown(TimeDelta::seconds(1))
+        );
+        assert_eq!(
+            auto_restart.can_reincarnate(
+                &project_defaults,
+                &state,
+                last + TimeDelta::seconds(10)
+            ),
+            Reincarnatability::WillReincarnate
+        );
+
+        // An instance's own cooldown overrides its project's.
+        let auto_restart = InstanceAutoRestart {
+            policy: Some(InstanceAutoRestartPolicy::BestEffort),
+            cooldown: Some(TimeDelta::seconds(20)),
+        };
+        assert_eq!(
+            auto_restart.can_reincarnate(
+                &project_defaults,
+                &state,
+                last + TimeDelta::seconds(10)
+            ),
+            Reincarnatability::CoolingDown(TimeDelta::seconds(10))
+        );
+    }
 }
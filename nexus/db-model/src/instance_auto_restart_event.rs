@@ -0,0 +1,72 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::impl_enum_type;
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::instance_auto_restart_event;
+use nexus_types::external_api::views;
+use omicron_uuid_kinds::{GenericUuid, InstanceUuid, PropolisUuid};
+use serde::Deserialize;
+use serde::Serialize;
+use uuid::Uuid;
+
+impl_enum_type!(
+    InstanceAutoRestartReasonEnum:
+
+    #[derive(Copy, Clone, Debug, PartialEq, AsExpression, FromSqlRow, Serialize, Deserialize)]
+    pub enum InstanceAutoRestartReason;
+
+    // Enum values
+    Failed => b"failed"
+    StartFailed => b"start_failed"
+);
+
+impl From<InstanceAutoRestartReason> for views::InstanceAutoRestartReason {
+    fn from(reason: InstanceAutoRestartReason) -> Self {
+        match reason {
+            InstanceAutoRestartReason::Failed => Self::Failed,
+            InstanceAutoRestartReason::StartFailed => Self::StartFailed,
+        }
+    }
+}
+
+/// A record of the control plane automatically restarting an instance.
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = instance_auto_restart_event)]
+pub struct InstanceAutoRestartEvent {
+    pub id: Uuid,
+    pub instance_id: Uuid,
+    pub time_restarted: DateTime<Utc>,
+    pub reason: InstanceAutoRestartReason,
+    /// The VMM created to run the restarted instance.
+    pub propolis_id: Uuid,
+}
+
+impl InstanceAutoRestartEvent {
+    pub fn new(
+        instance_id: InstanceUuid,
+        propolis_id: PropolisUuid,
+        time_restarted: DateTime<Utc>,
+        reason: InstanceAutoRestartReason,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            instance_id: instance_id.into_untyped_uuid(),
+            time_restarted,
+            reason,
+            propolis_id: propolis_id.into_untyped_uuid(),
+        }
+    }
+}
+
+impl From<InstanceAutoRestartEvent> for views::InstanceAutoRestartEvent {
+    fn from(event: InstanceAutoRestartEvent) -> Self {
+        Self {
+            id: event.id,
+            instance_id: event.instance_id,
+            time_restarted: event.time_restarted,
+            reason: event.reason.into(),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::impl_enum_type;
+use omicron_common::api::external;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+
+impl_enum_type!(
+    InstanceStoppedReservationPolicyEnum:
+
+    #[derive(Copy, Clone, Debug, PartialEq, AsExpression, FromSqlRow, Serialize, Deserialize)]
+    pub enum InstanceStoppedReservationPolicy;
+
+    // Enum values
+    Release => b"release"
+    Retain => b"retain"
+);
+
+impl InstanceStoppedReservationPolicy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Release => "release",
+            Self::Retain => "retain",
+        }
+    }
+}
+
+impl Default for InstanceStoppedReservationPolicy {
+    fn default() -> Self {
+        Self::Release
+    }
+}
+
+impl fmt::Display for InstanceStoppedReservationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.label().fmt(f)
+    }
+}
+
+impl From<InstanceStoppedReservationPolicy>
+    for external::InstanceStoppedReservationPolicy
+{
+    fn from(value: InstanceStoppedReservationPolicy) -> Self {
+        match value {
+            InstanceStoppedReservationPolicy::Release => Self::Release,
+            InstanceStoppedReservationPolicy::Retain => Self::Retain,
+        }
+    }
+}
+
+impl From<external::InstanceStoppedReservationPolicy>
+    for InstanceStoppedReservationPolicy
+{
+    fn from(value: external::InstanceStoppedReservationPolicy) -> Self {
+        match value {
+            external::InstanceStoppedReservationPolicy::Release => {
+                Self::Release
+            }
+            external::InstanceStoppedReservationPolicy::Retain => Self::Retain,
+        }
+    }
+}
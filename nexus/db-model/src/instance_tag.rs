@@ -0,0 +1,23 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use nexus_db_schema::schema::instance_tag;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
+use uuid::Uuid;
+
+/// A user-defined key/value tag attached to an instance.
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = instance_tag)]
+pub struct InstanceTag {
+    pub instance_id: Uuid,
+    pub key: String,
+    pub value: String,
+}
+
+impl InstanceTag {
+    pub fn new(instance_id: InstanceUuid, key: String, value: String) -> Self {
+        Self { instance_id: instance_id.into_untyped_uuid(), key, value }
+    }
+}
@@ -48,6 +48,12 @@ use nexus_sled_agent_shared::inventory::BootPartitionDetails;
 use nexus_sled_agent_shared::inventory::ConfigReconcilerInventoryStatus;
 use nexus_sled_agent_shared::inventory::HostPhase2DesiredContents;
 use nexus_sled_agent_shared::inventory::HostPhase2DesiredSlots;
+use nexus_sled_agent_shared::inventory::InventoryZpoolHealth;
+use nexus_sled_agent_shared::inventory::InventoryZpoolHealthState;
+use nexus_sled_agent_shared::inventory::InventoryZpoolResilver;
+use nexus_sled_agent_shared::inventory::InventoryZpoolResilverState;
+use nexus_sled_agent_shared::inventory::InventoryZpoolScrub;
+use nexus_sled_agent_shared::inventory::InventoryZpoolScrubState;
 use nexus_sled_agent_shared::inventory::MupdateOverrideBootInventory;
 use nexus_sled_agent_shared::inventory::MupdateOverrideInventory;
 use nexus_sled_agent_shared::inventory::MupdateOverrideNonBootInventory;
@@ -2030,6 +2036,114 @@ impl From<InvNvmeDiskFirmware>
     }
 }
 
+// See [`nexus_sled_agent_shared::inventory::InventoryZpoolScrubState`].
+impl_enum_type!(
+    InvZpoolScrubStateEnum:
+
+    #[derive(Copy, Clone, Debug, AsExpression, FromSqlRow, PartialEq)]
+    pub enum InvZpoolScrubState;
+
+    // Enum values
+    InProgress => b"in_progress"
+    Paused => b"paused"
+    Finished => b"finished"
+    Canceled => b"canceled"
+);
+
+impl From<InventoryZpoolScrubState> for InvZpoolScrubState {
+    fn from(value: InventoryZpoolScrubState) -> Self {
+        match value {
+            InventoryZpoolScrubState::InProgress => Self::InProgress,
+            InventoryZpoolScrubState::Paused => Self::Paused,
+            InventoryZpoolScrubState::Finished => Self::Finished,
+            InventoryZpoolScrubState::Canceled => Self::Canceled,
+        }
+    }
+}
+
+impl From<InvZpoolScrubState> for InventoryZpoolScrubState {
+    fn from(value: InvZpoolScrubState) -> Self {
+        match value {
+            InvZpoolScrubState::InProgress => Self::InProgress,
+            InvZpoolScrubState::Paused => Self::Paused,
+            InvZpoolScrubState::Finished => Self::Finished,
+            InvZpoolScrubState::Canceled => Self::Canceled,
+        }
+    }
+}
+
+// See [`nexus_sled_agent_shared::inventory::InventoryZpoolHealthState`].
+impl_enum_type!(
+    InvZpoolHealthStateEnum:
+
+    #[derive(Copy, Clone, Debug, AsExpression, FromSqlRow, PartialEq)]
+    pub enum InvZpoolHealthState;
+
+    // Enum values
+    Online => b"online"
+    Degraded => b"degraded"
+    Faulted => b"faulted"
+    Offline => b"offline"
+    Removed => b"removed"
+    Unavailable => b"unavailable"
+);
+
+impl From<InventoryZpoolHealthState> for InvZpoolHealthState {
+    fn from(value: InventoryZpoolHealthState) -> Self {
+        match value {
+            InventoryZpoolHealthState::Online => Self::Online,
+            InventoryZpoolHealthState::Degraded => Self::Degraded,
+            InventoryZpoolHealthState::Faulted => Self::Faulted,
+            InventoryZpoolHealthState::Offline => Self::Offline,
+            InventoryZpoolHealthState::Removed => Self::Removed,
+            InventoryZpoolHealthState::Unavailable => Self::Unavailable,
+        }
+    }
+}
+
+impl From<InvZpoolHealthState> for InventoryZpoolHealthState {
+    fn from(value: InvZpoolHealthState) -> Self {
+        match value {
+            InvZpoolHealthState::Online => Self::Online,
+            InvZpoolHealthState::Degraded => Self::Degraded,
+            InvZpoolHealthState::Faulted => Self::Faulted,
+            InvZpoolHealthState::Offline => Self::Offline,
+            InvZpoolHealthState::Removed => Self::Removed,
+            InvZpoolHealthState::Unavailable => Self::Unavailable,
+        }
+    }
+}
+
+// See [`nexus_sled_agent_shared::inventory::InventoryZpoolResilverState`].
+impl_enum_type!(
+    InvZpoolResilverStateEnum:
+
+    #[derive(Copy, Clone, Debug, AsExpression, FromSqlRow, PartialEq)]
+    pub enum InvZpoolResilverState;
+
+    // Enum values
+    InProgress => b"in_progress"
+    Finished => b"finished"
+);
+
+impl From<InventoryZpoolResilverState> for InvZpoolResilverState {
+    fn from(value: InventoryZpoolResilverState) -> Self {
+        match value {
+            InventoryZpoolResilverState::InProgress => Self::InProgress,
+            InventoryZpoolResilverState::Finished => Self::Finished,
+        }
+    }
+}
+
+impl From<InvZpoolResilverState> for InventoryZpoolResilverState {
+    fn from(value: InvZpoolResilverState) -> Self {
+        match value {
+            InvZpoolResilverState::InProgress => Self::InProgress,
+            InvZpoolResilverState::Finished => Self::Finished,
+        }
+    }
+}
+
 /// See [`nexus_types::inventory::Zpool`].
 #[derive(Queryable, Clone, Debug, Selectable, Insertable)]
 #[diesel(table_name = inv_zpool)]
@@ -2039,6 +2153,22 @@ pub struct InvZpool {
     pub id: Uuid,
     pub sled_id: DbTypedUuid<SledKind>,
     pub total_size: ByteCount,
+    pub scrub_state: Option<InvZpoolScrubState>,
+    pub scrub_start_time: Option<DateTime<Utc>>,
+    pub scrub_end_time: Option<DateTime<Utc>>,
+    pub scrub_percent_done: Option<SqlU8>,
+    pub scrub_errors: Option<i64>,
+    pub allocated: Option<ByteCount>,
+    pub fragmentation_percent: Option<SqlU8>,
+    pub health_state: Option<InvZpoolHealthState>,
+    pub read_errors: Option<i64>,
+    pub write_errors: Option<i64>,
+    pub checksum_errors: Option<i64>,
+    pub resilver_state: Option<InvZpoolResilverState>,
+    pub resilver_start_time: Option<DateTime<Utc>>,
+    pub resilver_end_time: Option<DateTime<Utc>>,
+    pub resilver_percent_done: Option<SqlU8>,
+    pub resilver_errors: Option<i64>,
 }
 
 impl InvZpool {
@@ -2047,22 +2177,73 @@ impl InvZpool {
         sled_id: SledUuid,
         zpool: &nexus_types::inventory::Zpool,
     ) -> Self {
+        let scrub = zpool.scrub.as_ref();
+        let health = zpool.health.as_ref();
+        let resilver = health.and_then(|h| h.resilver.as_ref());
         Self {
             inv_collection_id: inv_collection_id.into(),
             time_collected: zpool.time_collected,
             id: zpool.id.into_untyped_uuid(),
             sled_id: sled_id.into(),
             total_size: zpool.total_size.into(),
+            scrub_state: scrub.map(|s| s.state.into()),
+            scrub_start_time: scrub.and_then(|s| s.start_time),
+            scrub_end_time: scrub.and_then(|s| s.end_time),
+            scrub_percent_done: scrub
+                .and_then(|s| s.percent_done)
+                .map(SqlU8::from),
+            // As with other counters stored in inventory, we only want to
+            // faithfully store and load this value, so `as` is fine.
+            scrub_errors: scrub.and_then(|s| s.errors).map(|e| e as i64),
+            allocated: zpool.allocated.map(ByteCount::from),
+            fragmentation_percent: zpool.fragmentation_percent.map(SqlU8::from),
+            health_state: health.map(|h| h.state.into()),
+            read_errors: health.map(|h| h.read_errors as i64),
+            write_errors: health.map(|h| h.write_errors as i64),
+            checksum_errors: health.map(|h| h.checksum_errors as i64),
+            resilver_state: resilver.map(|r| r.state.into()),
+            resilver_start_time: resilver.and_then(|r| r.start_time),
+            resilver_end_time: resilver.and_then(|r| r.end_time),
+            resilver_percent_done: resilver
+                .and_then(|r| r.percent_done)
+                .map(SqlU8::from),
+            resilver_errors: resilver.and_then(|r| r.errors).map(|e| e as i64),
         }
     }
 }
 
 impl From<InvZpool> for nexus_types::inventory::Zpool {
     fn from(pool: InvZpool) -> Self {
+        let scrub = pool.scrub_state.map(|state| InventoryZpoolScrub {
+            state: state.into(),
+            start_time: pool.scrub_start_time,
+            end_time: pool.scrub_end_time,
+            percent_done: pool.scrub_percent_done.map(|p| *p),
+            errors: pool.scrub_errors.map(|e| e as u64),
+        });
+        let resilver =
+            pool.resilver_state.map(|state| InventoryZpoolResilver {
+                state: state.into(),
+                start_time: pool.resilver_start_time,
+                end_time: pool.resilver_end_time,
+                percent_done: pool.resilver_percent_done.map(|p| *p),
+                errors: pool.resilver_errors.map(|e| e as u64),
+            });
+        let health = pool.health_state.map(|state| InventoryZpoolHealth {
+            state: state.into(),
+            read_errors: pool.read_errors.unwrap_or(0) as u64,
+            write_errors: pool.write_errors.unwrap_or(0) as u64,
+            checksum_errors: pool.checksum_errors.unwrap_or(0) as u64,
+            resilver,
+        });
         Self {
             time_collected: pool.time_collected,
             id: ZpoolUuid::from_untyped_uuid(pool.id),
             total_size: *pool.total_size,
+            allocated: pool.allocated.map(|a| *a),
+            fragmentation_percent: pool.fragmentation_percent.map(|f| *f),
+            scrub,
+            health,
         }
     }
 }
@@ -2973,6 +3154,7 @@ pub struct InvNtpTimesync {
     pub inv_collection_id: DbTypedUuid<CollectionKind>,
     pub zone_id: DbTypedUuid<OmicronZoneKind>,
     pub synced: bool,
+    pub correction_usec: Option<i64>,
 }
 
 impl InvNtpTimesync {
@@ -2984,13 +3166,18 @@ impl InvNtpTimesync {
             inv_collection_id: inv_collection_id.into(),
             zone_id: timesync.zone_id.into(),
             synced: timesync.synced,
+            correction_usec: timesync.correction_usec,
         })
     }
 }
 
 impl From<InvNtpTimesync> for nexus_types::inventory::TimeSync {
     fn from(value: InvNtpTimesync) -> Self {
-        Self { zone_id: value.zone_id.into(), synced: value.synced }
+        Self {
+            zone_id: value.zone_id.into(),
+            synced: value.synced,
+            correction_usec: value.correction_usec,
+        }
     }
 }
 
@@ -36,7 +36,9 @@ impl_enum_type!(
         Eq,
         FromSqlRow,
         schemars::JsonSchema,
+        Ord,
         PartialEq,
+        PartialOrd,
         serde::Serialize,
     )]
     pub enum IpVersion;
@@ -150,6 +152,7 @@ impl_enum_type!(
     #[derive(Clone, Copy, Debug, AsExpression, FromSqlRow, PartialEq)]
     pub enum IpPoolResourceType;
 
+    Project => b"project"
     Silo => b"silo"
 );
 
@@ -172,6 +175,12 @@ impl From<IpPoolResource> for views::IpPoolSiloLink {
     }
 }
 
+impl From<IpPoolResource> for views::IpPoolProjectLink {
+    fn from(assoc: IpPoolResource) -> Self {
+        Self { ip_pool_id: assoc.ip_pool_id, project_id: assoc.resource_id }
+    }
+}
+
 /// A range of IP addresses for an IP Pool.
 #[derive(Queryable, Insertable, Selectable, Clone, Debug)]
 #[diesel(table_name = ip_pool_range)]
@@ -22,6 +22,7 @@ mod bfd;
 mod bgp;
 mod block_size;
 mod bootstore;
+mod break_glass_account;
 mod bytecount;
 mod certificate;
 mod clickhouse_policy;
@@ -42,14 +43,18 @@ mod generation;
 mod identity_provider;
 mod image;
 mod instance;
+mod instance_auto_restart_event;
 mod instance_auto_restart_policy;
 mod instance_cpu_count;
 mod instance_intended_state;
 mod instance_state;
+mod instance_stopped_reservation_policy;
+mod instance_tag;
 mod internet_gateway;
 mod inventory;
 mod ip_pool;
 mod ipnet;
+pub mod ipv4;
 mod ipv4net;
 pub mod ipv6;
 mod ipv6net;
@@ -59,6 +64,7 @@ mod migration;
 mod migration_state;
 mod name;
 mod network_interface;
+mod operation;
 mod oximeter_info;
 mod oximeter_read_policy;
 mod physical_disk;
@@ -76,6 +82,7 @@ mod silo_auth_settings;
 mod switch_interface;
 mod switch_port;
 mod target_release;
+mod technician_port_update;
 mod v2p_mapping;
 mod vmm_state;
 mod webhook_delivery;
@@ -97,6 +104,7 @@ mod region_snapshot;
 mod region_snapshot_replacement;
 mod region_snapshot_replacement_step;
 mod role_assignment;
+mod rolling_sled_reboot;
 pub mod saga_types;
 mod schema_versions;
 mod service_kind;
@@ -106,12 +114,15 @@ mod silo_user;
 mod silo_user_password_hash;
 mod sled;
 mod sled_cpu_family;
+mod sled_execution_lease;
 mod sled_instance;
 mod sled_policy;
 mod sled_resource_vmm;
 mod sled_state;
 mod sled_underlay_subnet_allocation;
 mod snapshot;
+mod snapshot_export;
+mod snapshot_schedule;
 mod ssh_key;
 mod support_bundle;
 mod switch;
@@ -135,6 +146,7 @@ mod vpc_route;
 mod vpc_router;
 mod vpc_subnet;
 mod zpool;
+mod zpool_usage_sample;
 
 // This module namespacing is a quirk to allow `db-macros` to refer to
 // `crate::db::model::Name` in both this crate and `nexus` proper.
@@ -159,6 +171,7 @@ pub use bfd::*;
 pub use bgp::*;
 pub use block_size::*;
 pub use bootstore::*;
+pub use break_glass_account::*;
 pub use bytecount::*;
 pub use certificate::*;
 pub use clickhouse_policy::*;
@@ -181,14 +194,18 @@ pub use generation::*;
 pub use identity_provider::*;
 pub use image::*;
 pub use instance::*;
+pub use instance_auto_restart_event::*;
 pub use instance_auto_restart_policy::*;
 pub use instance_cpu_count::*;
 pub use instance_intended_state::*;
 pub use instance_state::*;
+pub use instance_stopped_reservation_policy::*;
+pub use instance_tag::*;
 pub use internet_gateway::*;
 pub use inventory::*;
 pub use ip_pool::*;
 pub use ipnet::*;
+pub use ipv4::*;
 pub use ipv4net::*;
 pub use ipv6::*;
 pub use ipv6net::*;
@@ -198,6 +215,7 @@ pub use migration_state::*;
 pub use name::*;
 pub use nat_entry::*;
 pub use network_interface::*;
+pub use operation::*;
 pub use oximeter_info::*;
 pub use oximeter_read_policy::*;
 pub use physical_disk::*;
@@ -218,6 +236,7 @@ pub use region_snapshot_replacement::*;
 pub use region_snapshot_replacement_step::*;
 pub use rendezvous_debug_dataset::*;
 pub use role_assignment::*;
+pub use rolling_sled_reboot::*;
 pub use saga_types::*;
 pub use schema_versions::*;
 pub use semver_version::*;
@@ -229,18 +248,22 @@ pub use silo_user::*;
 pub use silo_user_password_hash::*;
 pub use sled::*;
 pub use sled_cpu_family::*;
+pub use sled_execution_lease::*;
 pub use sled_instance::*;
 pub use sled_policy::to_db_sled_policy; // Do not expose DbSledPolicy
 pub use sled_resource_vmm::*;
 pub use sled_state::*;
 pub use sled_underlay_subnet_allocation::*;
 pub use snapshot::*;
+pub use snapshot_export::*;
+pub use snapshot_schedule::*;
 pub use ssh_key::*;
 pub use support_bundle::*;
 pub use switch::*;
 pub use switch_interface::*;
 pub use switch_port::*;
 pub use target_release::*;
+pub use technician_port_update::*;
 pub use tuf_repo::*;
 pub use typed_uuid::DbTypedUuid;
 pub use typed_uuid::to_db_typed_uuid;
@@ -266,6 +289,7 @@ pub use webhook_delivery::*;
 pub use webhook_delivery_attempt_result::*;
 pub use webhook_rx::*;
 pub use zpool::*;
+pub use zpool_usage_sample::*;
 
 // TODO: The existence of both impl_enum_type and impl_enum_wrapper is a
 // temporary state of affairs while we do the work of converting uses of
@@ -0,0 +1,147 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Database representation of long-running operations
+
+use super::impl_enum_type;
+use crate::SqlU32;
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::operation;
+use nexus_types::external_api::views;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::OmicronZoneUuid;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+impl_enum_type!(
+    OperationKindEnum:
+
+    #[derive(Clone, Copy, Debug, AsExpression, FromSqlRow, Serialize, Deserialize, PartialEq)]
+    pub enum OperationKind;
+
+    DiskClone => b"disk_clone"
+    SnapshotExport => b"snapshot_export"
+    InstanceExport => b"instance_export"
+);
+
+impl From<OperationKind> for views::OperationKind {
+    fn from(kind: OperationKind) -> Self {
+        match kind {
+            OperationKind::DiskClone => Self::DiskClone,
+            OperationKind::SnapshotExport => Self::SnapshotExport,
+            OperationKind::InstanceExport => Self::InstanceExport,
+        }
+    }
+}
+
+impl_enum_type!(
+    OperationStateEnum:
+
+    #[derive(Clone, Copy, Debug, AsExpression, FromSqlRow, Serialize, Deserialize, PartialEq)]
+    pub enum OperationState;
+
+    Running => b"running"
+    Succeeded => b"succeeded"
+    Failed => b"failed"
+);
+
+impl From<OperationState> for views::OperationState {
+    fn from(state: OperationState) -> Self {
+        match state {
+            OperationState::Running => Self::Running,
+            OperationState::Succeeded => Self::Succeeded,
+            OperationState::Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(
+    Queryable, Insertable, Selectable, Clone, Debug, Serialize, Deserialize,
+)]
+#[diesel(table_name = operation)]
+pub struct Operation {
+    pub id: Uuid,
+    pub time_created: DateTime<Utc>,
+    pub time_modified: DateTime<Utc>,
+    pub time_completed: Option<DateTime<Utc>>,
+
+    pub project_id: Uuid,
+
+    // the Nexus carrying out the operation
+    nexus_id: Uuid,
+
+    pub kind: OperationKind,
+    pub state: OperationState,
+
+    pub steps_completed: SqlU32,
+    pub steps_total: SqlU32,
+
+    // the resource produced by the operation, once it has succeeded
+    pub result_id: Option<Uuid>,
+
+    pub error_message: Option<String>,
+}
+
+impl Operation {
+    pub fn new(
+        id: Uuid,
+        project_id: Uuid,
+        nexus_id: OmicronZoneUuid,
+        kind: OperationKind,
+        steps_total: u32,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            time_created: now,
+            time_modified: now,
+            time_completed: None,
+            project_id,
+            nexus_id: nexus_id.into_untyped_uuid(),
+            kind,
+            state: OperationState::Running,
+            steps_completed: 0.into(),
+            steps_total: steps_total.into(),
+            result_id: None,
+            error_message: None,
+        }
+    }
+
+    pub fn nexus_id(&self) -> OmicronZoneUuid {
+        OmicronZoneUuid::from_untyped_uuid(self.nexus_id)
+    }
+}
+
+impl From<Operation> for views::Operation {
+    fn from(operation: Operation) -> Self {
+        let result = operation.result_id.map(|id| {
+            let (resource_type, href) = match operation.kind {
+                OperationKind::DiskClone => (
+                    views::OperationResultType::Disk,
+                    format!("/v1/disks/{id}"),
+                ),
+                OperationKind::SnapshotExport
+                | OperationKind::InstanceExport => (
+                    views::OperationResultType::SnapshotExport,
+                    format!("/v1/snapshot-exports/{id}"),
+                ),
+            };
+            views::OperationResult { resource_type, id, href }
+        });
+        Self {
+            id: operation.id,
+            time_created: operation.time_created,
+            project_id: operation.project_id,
+            kind: operation.kind.into(),
+            state: operation.state.into(),
+            progress: views::OperationProgress {
+                steps_completed: operation.steps_completed.into(),
+                steps_total: operation.steps_total.into(),
+            },
+            time_completed: operation.time_completed,
+            result,
+            error: operation.error_message,
+        }
+    }
+}
@@ -3,16 +3,19 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::{
-    AffinityGroup, AntiAffinityGroup, Disk, Generation, Instance, Name,
-    Snapshot, Vpc,
+    AffinityGroup, AntiAffinityGroup, Disk, Generation, Instance,
+    InstanceAutoRestartPolicy, Name, Operation, Snapshot, SnapshotExport,
+    SnapshotSchedule, Vpc,
 };
 use crate::Image;
 use crate::collection::DatastoreCollectionConfig;
-use chrono::{DateTime, Utc};
+use crate::serde_time_delta::optional_time_delta;
+use chrono::{DateTime, TimeDelta, Utc};
 use db_macros::Resource;
 use nexus_db_schema::schema::{
-    affinity_group, anti_affinity_group, disk, image, instance, project,
-    snapshot, vpc,
+    affinity_group, anti_affinity_group, disk, image, instance, operation,
+    project, project_ephemeral_ip_policy, snapshot, snapshot_export,
+    snapshot_schedule, vpc,
 };
 use nexus_types::external_api::params;
 use nexus_types::external_api::views;
@@ -40,6 +43,9 @@ pub struct Project {
     /// child resource generation number, per RFD 192
     pub rcgen: Generation,
     pub silo_id: Uuid,
+
+    #[diesel(embed)]
+    pub auto_restart_defaults: ProjectAutoRestartDefaults,
 }
 
 impl Project {
@@ -58,6 +64,7 @@ impl Project {
             identity: ProjectIdentity::new(id, params.identity),
             rcgen: Generation::new(),
             silo_id,
+            auto_restart_defaults: ProjectAutoRestartDefaults::default(),
         }
     }
 }
@@ -68,6 +75,62 @@ impl From<Project> for views::Project {
     }
 }
 
+/// A project's defaults for the auto-restart configuration of its instances.
+///
+/// These apply to instances in the project which don't configure their own
+/// auto-restart policy or cooldown. If the project doesn't set a default
+/// either, the fleet-wide defaults on [`crate::InstanceAutoRestart`] apply.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    AsChangeset,
+    Selectable,
+    Insertable,
+    Queryable,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(table_name = project, treat_none_as_null = true)]
+pub struct ProjectAutoRestartDefaults {
+    /// The default auto-restart policy for instances in this project.
+    #[diesel(column_name = default_auto_restart_policy)]
+    #[serde(default)]
+    pub policy: Option<InstanceAutoRestartPolicy>,
+    /// The default cooldown period between automatic restarts of instances
+    /// in this project.
+    #[diesel(column_name = default_auto_restart_cooldown)]
+    #[serde(default, with = "optional_time_delta")]
+    pub cooldown: Option<TimeDelta>,
+}
+
+impl From<params::ProjectInstanceAutoRestartUpdate>
+    for ProjectAutoRestartDefaults
+{
+    fn from(params: params::ProjectInstanceAutoRestartUpdate) -> Self {
+        Self {
+            policy: params.policy.map(Into::into),
+            cooldown: params
+                .cooldown_secs
+                .map(|secs| TimeDelta::seconds(i64::from(secs))),
+        }
+    }
+}
+
+impl From<Project> for views::ProjectInstanceAutoRestart {
+    fn from(project: Project) -> Self {
+        let ProjectAutoRestartDefaults { policy, cooldown } =
+            project.auto_restart_defaults;
+        Self {
+            project_id: project.id(),
+            policy: policy.map(Into::into),
+            cooldown_secs: cooldown.map(|cooldown| {
+                u32::try_from(cooldown.num_seconds()).unwrap_or(u32::MAX)
+            }),
+        }
+    }
+}
+
 impl DatastoreCollectionConfig<Instance> for Project {
     type CollectionId = Uuid;
     type GenerationNumberColumn = project::dsl::rcgen;
@@ -110,6 +173,27 @@ impl DatastoreCollectionConfig<Snapshot> for Project {
     type CollectionIdColumn = snapshot::dsl::project_id;
 }
 
+impl DatastoreCollectionConfig<SnapshotSchedule> for Project {
+    type CollectionId = Uuid;
+    type GenerationNumberColumn = project::dsl::rcgen;
+    type CollectionTimeDeletedColumn = project::dsl::time_deleted;
+    type CollectionIdColumn = snapshot_schedule::dsl::project_id;
+}
+
+impl DatastoreCollectionConfig<SnapshotExport> for Project {
+    type CollectionId = Uuid;
+    type GenerationNumberColumn = project::dsl::rcgen;
+    type CollectionTimeDeletedColumn = project::dsl::time_deleted;
+    type CollectionIdColumn = snapshot_export::dsl::project_id;
+}
+
+impl DatastoreCollectionConfig<Operation> for Project {
+    type CollectionId = Uuid;
+    type GenerationNumberColumn = project::dsl::rcgen;
+    type CollectionTimeDeletedColumn = project::dsl::time_deleted;
+    type CollectionIdColumn = operation::dsl::project_id;
+}
+
 impl DatastoreCollectionConfig<Vpc> for Project {
     type CollectionId = Uuid;
     type GenerationNumberColumn = project::dsl::rcgen;
@@ -135,3 +219,66 @@ impl From<params::ProjectUpdate> for ProjectUpdate {
         }
     }
 }
+
+/// Describes how a project assigns ephemeral IPs to new instances.
+#[derive(
+    Queryable,
+    Insertable,
+    Debug,
+    Clone,
+    Selectable,
+    Serialize,
+    Deserialize,
+    AsChangeset,
+)]
+#[diesel(table_name = project_ephemeral_ip_policy)]
+pub struct ProjectEphemeralIpPolicy {
+    pub project_id: Uuid,
+    pub time_created: DateTime<Utc>,
+    pub time_modified: DateTime<Utc>,
+
+    /// Whether instances created without an ephemeral IP are given one
+    pub auto_assign: bool,
+
+    /// The pool automatically-assigned IPs come from, or `None` for the
+    /// silo's default pool
+    pub ip_pool_id: Option<Uuid>,
+
+    /// Whether instance creators may request an ephemeral IP themselves
+    pub allow_override: bool,
+}
+
+impl ProjectEphemeralIpPolicy {
+    pub fn new(
+        project_id: Uuid,
+        auto_assign: bool,
+        ip_pool_id: Option<Uuid>,
+        allow_override: bool,
+    ) -> Self {
+        Self {
+            project_id,
+            time_created: Utc::now(),
+            time_modified: Utc::now(),
+            auto_assign,
+            ip_pool_id,
+            allow_override,
+        }
+    }
+
+    /// The policy of a project that has never had one set, which preserves
+    /// the behavior of projects that predate these policies.
+    pub fn default_for_project(project_id: Uuid) -> Self {
+        Self::new(project_id, false, None, true)
+    }
+}
+
+impl From<ProjectEphemeralIpPolicy> for views::ProjectEphemeralIpPolicy {
+    fn from(policy: ProjectEphemeralIpPolicy) -> Self {
+        Self {
+            project_id: policy.project_id,
+            auto_assign: policy.auto_assign,
+            ip_pool_id: policy.ip_pool_id,
+            allow_override: policy.allow_override,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::ByteCount;
 use chrono::{DateTime, Utc};
-use nexus_db_schema::schema::silo_quotas;
+use nexus_db_schema::schema::{project_quotas, silo_quotas};
 use nexus_types::external_api::{params, views};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -96,3 +96,85 @@ impl From<params::SiloQuotasUpdate> for SiloQuotasUpdate {
         }
     }
 }
+
+#[derive(
+    Queryable,
+    Insertable,
+    Debug,
+    Clone,
+    Selectable,
+    Serialize,
+    Deserialize,
+    AsChangeset,
+)]
+#[diesel(table_name = project_quotas)]
+pub struct ProjectQuotas {
+    pub project_id: Uuid,
+    pub time_created: DateTime<Utc>,
+    pub time_modified: DateTime<Utc>,
+
+    /// The number of CPUs that this project is allowed to use
+    pub cpus: i64,
+
+    /// The amount of memory (in bytes) that this project is allowed to use
+    #[diesel(column_name = memory_bytes)]
+    pub memory: ByteCount,
+
+    /// The amount of storage (in bytes) that this project is allowed to use
+    #[diesel(column_name = storage_bytes)]
+    pub storage: ByteCount,
+}
+
+impl ProjectQuotas {
+    pub fn new(
+        project_id: Uuid,
+        cpus: i64,
+        memory: ByteCount,
+        storage: ByteCount,
+    ) -> Self {
+        Self {
+            project_id,
+            time_created: Utc::now(),
+            time_modified: Utc::now(),
+            cpus,
+            memory,
+            storage,
+        }
+    }
+}
+
+impl From<ProjectQuotas> for views::ProjectQuotas {
+    fn from(project_quotas: ProjectQuotas) -> Self {
+        Self {
+            project_id: project_quotas.project_id,
+            limits: views::VirtualResourceCounts {
+                cpus: project_quotas.cpus,
+                memory: project_quotas.memory.into(),
+                storage: project_quotas.storage.into(),
+            },
+        }
+    }
+}
+
+// Describes a set of updates for the [`ProjectQuotas`] model.
+#[derive(AsChangeset)]
+#[diesel(table_name = project_quotas)]
+pub struct ProjectQuotasUpdate {
+    pub cpus: Option<i64>,
+    #[diesel(column_name = memory_bytes)]
+    pub memory: Option<i64>,
+    #[diesel(column_name = storage_bytes)]
+    pub storage: Option<i64>,
+    pub time_modified: DateTime<Utc>,
+}
+
+impl From<params::ProjectQuotasUpdate> for ProjectQuotasUpdate {
+    fn from(params: params::ProjectQuotasUpdate) -> Self {
+        Self {
+            cpus: params.cpus,
+            memory: params.memory.map(|f| f.into()),
+            storage: params.storage.map(|f| f.into()),
+            time_modified: Utc::now(),
+        }
+    }
+}
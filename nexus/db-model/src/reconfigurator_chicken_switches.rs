@@ -4,10 +4,12 @@
 
 //! Types representing runtime configuration for reconfigurator
 
+use crate::SqlU8;
 use crate::SqlU32;
 use chrono::{DateTime, Utc};
 use nexus_db_schema::schema::reconfigurator_chicken_switches;
 use nexus_types::deployment;
+use std::num::NonZeroU8;
 
 #[derive(Queryable, Clone, Debug, Selectable, Insertable)]
 #[diesel(table_name = reconfigurator_chicken_switches)]
@@ -16,6 +18,9 @@ pub struct ReconfiguratorChickenSwitches {
     pub planner_enabled: bool,
     pub time_modified: DateTime<Utc>,
     pub add_zones_with_mupdate_override: bool,
+    pub allow_version_skew: bool,
+    pub target_nexus_zone_count: Option<SqlU8>,
+    pub target_internal_dns_zone_count: Option<SqlU8>,
 }
 
 impl From<deployment::ReconfiguratorChickenSwitchesView>
@@ -30,6 +35,20 @@ impl From<deployment::ReconfiguratorChickenSwitchesView>
                 .switches
                 .planner_switches
                 .add_zones_with_mupdate_override,
+            allow_version_skew: value
+                .switches
+                .planner_switches
+                .allow_version_skew,
+            target_nexus_zone_count: value
+                .switches
+                .planner_switches
+                .target_nexus_zone_count
+                .map(|count| SqlU8::new(count.get())),
+            target_internal_dns_zone_count: value
+                .switches
+                .planner_switches
+                .target_internal_dns_zone_count
+                .map(|count| SqlU8::new(count.get())),
         }
     }
 }
@@ -45,6 +64,14 @@ impl From<ReconfiguratorChickenSwitches>
                 planner_switches: deployment::PlannerChickenSwitches {
                     add_zones_with_mupdate_override: value
                         .add_zones_with_mupdate_override,
+                    allow_version_skew: value.allow_version_skew,
+                    // The database only allows positive values.
+                    target_nexus_zone_count: value
+                        .target_nexus_zone_count
+                        .and_then(|count| NonZeroU8::new(*count)),
+                    target_internal_dns_zone_count: value
+                        .target_internal_dns_zone_count
+                        .and_then(|count| NonZeroU8::new(*count)),
                 },
             },
             time_modified: value.time_modified,
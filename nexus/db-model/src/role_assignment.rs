@@ -23,7 +23,10 @@ impl_enum_type!(
         FromSqlRow,
         Serialize,
         Deserialize,
-        PartialEq
+        Eq,
+        Ord,
+        PartialEq,
+        PartialOrd,
     )]
     pub enum IdentityType;
 
@@ -0,0 +1,180 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::impl_enum_type;
+use crate::SqlU16;
+use crate::typed_uuid::DbTypedUuid;
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::{
+    rolling_sled_reboot, rolling_sled_reboot_instance, rolling_sled_reboot_sled,
+};
+use nexus_types::internal_api::views;
+use omicron_uuid_kinds::{InstanceKind, InstanceUuid, SledKind, SledUuid};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+impl_enum_type!(
+    RollingSledRebootStateEnum:
+
+    #[derive(Copy, Clone, Debug, PartialEq, AsExpression, FromSqlRow, Serialize, Deserialize)]
+    pub enum RollingSledRebootState;
+
+    // Enum values
+    Running => b"running"
+    Completed => b"completed"
+    Failed => b"failed"
+    Cancelled => b"cancelled"
+);
+
+impl From<RollingSledRebootState> for views::RollingSledRebootState {
+    fn from(state: RollingSledRebootState) -> Self {
+        match state {
+            RollingSledRebootState::Running => Self::Running,
+            RollingSledRebootState::Completed => Self::Completed,
+            RollingSledRebootState::Failed => Self::Failed,
+            RollingSledRebootState::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+impl_enum_type!(
+    RollingSledRebootStepEnum:
+
+    #[derive(Copy, Clone, Debug, PartialEq, AsExpression, FromSqlRow, Serialize, Deserialize)]
+    pub enum RollingSledRebootStep;
+
+    // Enum values
+    Pending => b"pending"
+    Draining => b"draining"
+    Rebooting => b"rebooting"
+    Rejoining => b"rejoining"
+    Done => b"done"
+    Failed => b"failed"
+);
+
+impl RollingSledRebootStep {
+    /// Returns `true` if a sled at this step is out of service: it counts
+    /// against the reboot's batch size.
+    pub fn is_in_progress(&self) -> bool {
+        match self {
+            Self::Draining | Self::Rebooting | Self::Rejoining => true,
+            Self::Pending | Self::Done | Self::Failed => false,
+        }
+    }
+}
+
+impl From<RollingSledRebootStep> for views::RollingSledRebootStep {
+    fn from(step: RollingSledRebootStep) -> Self {
+        match step {
+            RollingSledRebootStep::Pending => Self::Pending,
+            RollingSledRebootStep::Draining => Self::Draining,
+            RollingSledRebootStep::Rebooting => Self::Rebooting,
+            RollingSledRebootStep::Rejoining => Self::Rejoining,
+            RollingSledRebootStep::Done => Self::Done,
+            RollingSledRebootStep::Failed => Self::Failed,
+        }
+    }
+}
+
+/// An operator-requested reboot of a set of sleds, a batch at a time.
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = rolling_sled_reboot)]
+pub struct RollingSledReboot {
+    pub id: Uuid,
+    pub time_created: DateTime<Utc>,
+    pub time_finished: Option<DateTime<Utc>>,
+    pub batch_size: SqlU16,
+    pub state: RollingSledRebootState,
+    pub time_cancel_requested: Option<DateTime<Utc>>,
+}
+
+impl RollingSledReboot {
+    pub fn new(batch_size: u16) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            time_created: Utc::now(),
+            time_finished: None,
+            batch_size: batch_size.into(),
+            state: RollingSledRebootState::Running,
+            time_cancel_requested: None,
+        }
+    }
+}
+
+/// One sled of a rolling reboot, and how far the reboot has gotten with it.
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = rolling_sled_reboot_sled)]
+pub struct RollingSledRebootSled {
+    pub reboot_id: Uuid,
+    pub sled_id: DbTypedUuid<SledKind>,
+    pub position: SqlU16,
+    pub step: RollingSledRebootStep,
+    pub time_step_changed: DateTime<Utc>,
+    pub time_reset: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+impl RollingSledRebootSled {
+    pub fn new(
+        reboot: &RollingSledReboot,
+        sled_id: SledUuid,
+        position: u16,
+    ) -> Self {
+        Self {
+            reboot_id: reboot.id,
+            sled_id: sled_id.into(),
+            position: position.into(),
+            step: RollingSledRebootStep::Pending,
+            time_step_changed: reboot.time_created,
+            time_reset: None,
+            error: None,
+        }
+    }
+
+    pub fn sled_id(&self) -> SledUuid {
+        self.sled_id.into()
+    }
+}
+
+/// An instance that a rolling reboot stopped, rather than migrated, so that it
+/// could reboot the sled it was running on.
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = rolling_sled_reboot_instance)]
+pub struct RollingSledRebootInstance {
+    pub reboot_id: Uuid,
+    pub instance_id: DbTypedUuid<InstanceKind>,
+    pub sled_id: DbTypedUuid<SledKind>,
+    pub time_stopped: DateTime<Utc>,
+    pub time_restarted: Option<DateTime<Utc>>,
+}
+
+impl RollingSledRebootInstance {
+    pub fn new(
+        reboot_id: Uuid,
+        instance_id: InstanceUuid,
+        sled_id: SledUuid,
+    ) -> Self {
+        Self {
+            reboot_id,
+            instance_id: instance_id.into(),
+            sled_id: sled_id.into(),
+            time_stopped: Utc::now(),
+            time_restarted: None,
+        }
+    }
+
+    pub fn instance_id(&self) -> InstanceUuid {
+        self.instance_id.into()
+    }
+}
+
+impl From<RollingSledRebootInstance> for views::RollingSledRebootInstance {
+    fn from(instance: RollingSledRebootInstance) -> Self {
+        Self {
+            instance_id: instance.instance_id.into(),
+            time_stopped: instance.time_stopped,
+            time_restarted: instance.time_restarted,
+        }
+    }
+}
@@ -16,7 +16,7 @@ use std::{collections::BTreeMap, sync::LazyLock};
 ///
 /// This must be updated when you change the database schema.  Refer to
 /// schema/crdb/README.adoc in the root of this repository for details.
-pub const SCHEMA_VERSION: Version = Version::new(186, 0, 0);
+pub const SCHEMA_VERSION: Version = Version::new(221, 0, 0);
 
 /// List of all past database schema versions, in *reverse* order
 ///
@@ -28,6 +28,41 @@ static KNOWN_VERSIONS: LazyLock<Vec<KnownVersion>> = LazyLock::new(|| {
         // |  leaving the first copy as an example for the next person.
         // v
         // KnownVersion::new(next_int, "unique-dirname-with-the-sql-files"),
+        KnownVersion::new(221, "rolling-sled-reboot"),
+        KnownVersion::new(220, "instance-auto-restart-events"),
+        KnownVersion::new(219, "external-ip-time-attached"),
+        KnownVersion::new(218, "zpool-scrub-errors-alert"),
+        KnownVersion::new(217, "image-list-sort-indexes"),
+        KnownVersion::new(216, "crucible-pantry-load"),
+        KnownVersion::new(215, "sled-state-quiesced"),
+        KnownVersion::new(214, "operations"),
+        KnownVersion::new(213, "blueprint-scope"),
+        KnownVersion::new(212, "inv-ntp-timesync-correction"),
+        KnownVersion::new(211, "instance-stopped-reservation-policy"),
+        KnownVersion::new(210, "bp-planning-report"),
+        KnownVersion::new(209, "ip-pool-project-links"),
+        KnownVersion::new(208, "project-auto-restart-defaults"),
+        KnownVersion::new(207, "sled-execution-lease"),
+        KnownVersion::new(206, "inv-zpool-health"),
+        KnownVersion::new(205, "image-state"),
+        KnownVersion::new(204, "target-internal-dns-zone-count"),
+        KnownVersion::new(203, "bp-target-execution-disabled"),
+        KnownVersion::new(202, "zpool-usage-trends"),
+        KnownVersion::new(201, "instance-tags"),
+        KnownVersion::new(200, "target-nexus-zone-count"),
+        KnownVersion::new(199, "snapshot-exports"),
+        KnownVersion::new(198, "snapshot-schedules"),
+        KnownVersion::new(197, "delete-protection"),
+        KnownVersion::new(196, "allow-version-skew"),
+        KnownVersion::new(195, "technician-port-update"),
+        KnownVersion::new(194, "break-glass-account"),
+        KnownVersion::new(193, "vpc-subnet-secondary-blocks"),
+        KnownVersion::new(192, "instance-boot-order"),
+        KnownVersion::new(191, "project-ephemeral-ip-policy"),
+        KnownVersion::new(190, "vpc-subnet-ip-reservation"),
+        KnownVersion::new(189, "project-quotas"),
+        KnownVersion::new(188, "inv-zpool-scrub"),
+        KnownVersion::new(187, "bp-zone-disposition-cordoned"),
         KnownVersion::new(186, "nexus-generation"),
         KnownVersion::new(185, "populate-db-metadata-nexus"),
         KnownVersion::new(184, "store-silo-admin-group-name"),
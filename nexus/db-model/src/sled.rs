@@ -182,7 +182,7 @@ impl From<Sled> for params::SledAgentInfo {
             SledRole::Gimlet
         };
         let decommissioned = match sled.state {
-            SledState::Active => false,
+            SledState::Active | SledState::Quiesced => false,
             SledState::Decommissioned => true,
         };
         Self {
@@ -0,0 +1,20 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::typed_uuid::DbTypedUuid;
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::sled_execution_lease;
+use omicron_uuid_kinds::OmicronZoneKind;
+use omicron_uuid_kinds::SledKind;
+
+/// A lease held by a Nexus while blueprint execution sends mutating requests
+/// to a sled-agent.
+#[derive(Queryable, Insertable, Debug, Clone, Selectable, PartialEq)]
+#[diesel(table_name = sled_execution_lease)]
+pub struct SledExecutionLease {
+    pub sled_id: DbTypedUuid<SledKind>,
+    pub nexus_id: DbTypedUuid<OmicronZoneKind>,
+    pub time_acquired: DateTime<Utc>,
+    pub time_expires: DateTime<Utc>,
+}
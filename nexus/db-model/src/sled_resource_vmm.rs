@@ -16,7 +16,7 @@ type DbInstanceUuid = DbTypedUuid<InstanceKind>;
 type DbPropolisUuid = DbTypedUuid<PropolisKind>;
 type DbSledUuid = DbTypedUuid<SledKind>;
 
-#[derive(Clone, Selectable, Queryable, Insertable, Debug)]
+#[derive(Clone, Selectable, Queryable, Insertable, Debug, PartialEq)]
 #[diesel(table_name = sled_resource_vmm)]
 pub struct Resources {
     pub hardware_threads: SqlU32,
@@ -27,6 +27,7 @@ impl_enum_type!(
 
     // Enum values
     Active => b"active"
+    Quiesced => b"quiesced"
     Decommissioned => b"decommissioned"
 );
 
@@ -41,6 +42,7 @@ impl From<SledState> for views::SledState {
     fn from(state: SledState) -> Self {
         match state {
             SledState::Active => views::SledState::Active,
+            SledState::Quiesced => views::SledState::Quiesced,
             SledState::Decommissioned => views::SledState::Decommissioned,
         }
     }
@@ -50,6 +52,7 @@ impl From<views::SledState> for SledState {
     fn from(state: views::SledState) -> Self {
         match state {
             views::SledState::Active => SledState::Active,
+            views::SledState::Quiesced => SledState::Quiesced,
             views::SledState::Decommissioned => SledState::Decommissioned,
         }
     }
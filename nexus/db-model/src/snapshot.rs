@@ -56,6 +56,12 @@ pub struct Snapshot {
 
     #[diesel(column_name = size_bytes)]
     pub size: ByteCount,
+
+    // if true, this snapshot cannot be deleted until the flag is cleared
+    pub delete_protected: bool,
+
+    // the snapshot schedule that created this snapshot, if any
+    pub snapshot_schedule_id: Option<Uuid>,
 }
 
 impl From<Snapshot> for views::Snapshot {
@@ -66,6 +72,7 @@ impl From<Snapshot> for views::Snapshot {
             disk_id: snapshot.disk_id,
             state: snapshot.state.into(),
             size: snapshot.size.into(),
+            delete_protected: snapshot.delete_protected,
         }
     }
 }
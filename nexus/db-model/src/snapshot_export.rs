@@ -0,0 +1,123 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Database representation of snapshot exports
+
+use super::ByteCount;
+use crate::BlockSize;
+use crate::typed_uuid::DbTypedUuid;
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::snapshot_export;
+use nexus_types::external_api::views;
+use omicron_common::api::external::Error;
+use omicron_uuid_kinds::VolumeKind;
+use omicron_uuid_kinds::VolumeUuid;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddrV6;
+use uuid::Uuid;
+
+#[derive(
+    Queryable, Insertable, Selectable, Clone, Debug, Serialize, Deserialize,
+)]
+#[diesel(table_name = snapshot_export)]
+pub struct SnapshotExport {
+    pub id: Uuid,
+    pub time_created: DateTime<Utc>,
+    pub time_modified: DateTime<Utc>,
+    pub time_deleted: Option<DateTime<Utc>>,
+
+    pub project_id: Uuid,
+    pub snapshot_id: Uuid,
+
+    // the instance whose boot disk was exported, if any
+    pub instance_id: Option<Uuid>,
+
+    // the read-only copy of the snapshot's volume that is attached to a Pantry
+    volume_id: DbTypedUuid<VolumeKind>,
+
+    // the Pantry that the volume is attached to, once it has been attached
+    pub pantry_address: Option<String>,
+
+    pub block_size: BlockSize,
+
+    #[diesel(column_name = size_bytes)]
+    pub size: ByteCount,
+
+    // the serialized `views::ExportManifest`. Consumers use manifest() to get
+    // this back.
+    manifest: serde_json::Value,
+}
+
+impl SnapshotExport {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        project_id: Uuid,
+        snapshot_id: Uuid,
+        instance_id: Option<Uuid>,
+        volume_id: VolumeUuid,
+        block_size: BlockSize,
+        size: ByteCount,
+        manifest: &views::ExportManifest,
+    ) -> Result<Self, Error> {
+        let manifest = serde_json::to_value(manifest).map_err(|e| {
+            Error::internal_error(&format!(
+                "failed to serialize export manifest: {:#}",
+                e
+            ))
+        })?;
+        let now = Utc::now();
+        Ok(Self {
+            id,
+            time_created: now,
+            time_modified: now,
+            time_deleted: None,
+            project_id,
+            snapshot_id,
+            instance_id,
+            volume_id: volume_id.into(),
+            pantry_address: None,
+            block_size,
+            size,
+            manifest,
+        })
+    }
+
+    pub fn volume_id(&self) -> VolumeUuid {
+        self.volume_id.into()
+    }
+
+    pub fn pantry_address(&self) -> Option<SocketAddrV6> {
+        self.pantry_address.as_ref().map(|x| x.parse().unwrap())
+    }
+
+    pub fn manifest(&self) -> Result<views::ExportManifest, Error> {
+        serde_json::from_value(self.manifest.clone()).map_err(|e| {
+            Error::internal_error(&format!(
+                "failed to deserialize export manifest from database: {:#}",
+                e
+            ))
+        })
+    }
+}
+
+impl From<SnapshotExport> for views::SnapshotExport {
+    fn from(export: SnapshotExport) -> Self {
+        let state = if export.pantry_address.is_some() {
+            views::SnapshotExportState::Ready
+        } else {
+            views::SnapshotExportState::Creating
+        };
+        Self {
+            id: export.id,
+            time_created: export.time_created,
+            project_id: export.project_id,
+            snapshot_id: export.snapshot_id,
+            instance_id: export.instance_id,
+            state,
+            size: export.size.into(),
+            block_size: export.block_size.into(),
+        }
+    }
+}
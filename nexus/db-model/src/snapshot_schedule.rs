@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Database representation of snapshot schedules
+
+use super::Name;
+use crate::SqlU32;
+use chrono::{DateTime, TimeDelta, Utc};
+use db_macros::Resource;
+use nexus_db_schema::schema::snapshot_schedule;
+use nexus_types::external_api::params;
+use nexus_types::external_api::views;
+use nexus_types::identity::Resource;
+use uuid::Uuid;
+
+#[derive(
+    Queryable, Insertable, Clone, Debug, Resource, Selectable, PartialEq,
+)]
+#[diesel(table_name = snapshot_schedule)]
+pub struct SnapshotSchedule {
+    #[diesel(embed)]
+    pub identity: SnapshotScheduleIdentity,
+
+    pub project_id: Uuid,
+
+    // which disk to snapshot, or every disk in the project if unset
+    pub disk_id: Option<Uuid>,
+
+    pub interval_secs: SqlU32,
+    pub retain_count: SqlU32,
+
+    // when the schedule last took snapshots
+    pub time_last_run: Option<DateTime<Utc>>,
+}
+
+impl SnapshotSchedule {
+    pub fn new(
+        project_id: Uuid,
+        disk_id: Option<Uuid>,
+        params: params::SnapshotScheduleCreate,
+    ) -> Self {
+        Self {
+            identity: SnapshotScheduleIdentity::new(
+                Uuid::new_v4(),
+                params.identity,
+            ),
+            project_id,
+            disk_id,
+            interval_secs: params.interval_secs.into(),
+            retain_count: params.retain_count.into(),
+            time_last_run: None,
+        }
+    }
+
+    /// Returns the time between runs of this schedule
+    pub fn interval(&self) -> TimeDelta {
+        TimeDelta::seconds(i64::from(*self.interval_secs))
+    }
+
+    /// Returns true if this schedule should take snapshots at `now`
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match self.time_last_run {
+            None => true,
+            Some(time_last_run) => time_last_run + self.interval() <= now,
+        }
+    }
+}
+
+impl From<SnapshotSchedule> for views::SnapshotSchedule {
+    fn from(schedule: SnapshotSchedule) -> Self {
+        Self {
+            identity: schedule.identity(),
+            project_id: schedule.project_id,
+            disk_id: schedule.disk_id,
+            interval_secs: *schedule.interval_secs,
+            retain_count: *schedule.retain_count,
+            time_last_run: schedule.time_last_run,
+        }
+    }
+}
+
+/// Describes a set of updates for the [`SnapshotSchedule`] model.
+#[derive(AsChangeset)]
+#[diesel(table_name = snapshot_schedule)]
+pub struct SnapshotScheduleUpdate {
+    pub name: Option<Name>,
+    pub description: Option<String>,
+    pub interval_secs: Option<SqlU32>,
+    pub retain_count: Option<SqlU32>,
+    pub time_modified: DateTime<Utc>,
+}
+
+impl From<params::SnapshotScheduleUpdate> for SnapshotScheduleUpdate {
+    fn from(params: params::SnapshotScheduleUpdate) -> Self {
+        Self {
+            name: params.identity.name.map(Name),
+            description: params.identity.description,
+            interval_secs: params.interval_secs.map(SqlU32::from),
+            retain_count: params.retain_count.map(SqlU32::from),
+            time_modified: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::{SpMgsSlot, SpType, SqlU32, impl_enum_type};
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::{
+    technician_port_update, technician_port_update_component,
+};
+use nexus_types::external_api::shared;
+use nexus_types::external_api::views;
+use uuid::Uuid;
+
+impl_enum_type!(
+    UpdateComponentKindEnum:
+
+    /// See [`shared::UpdateComponentKind`].
+    #[derive(
+        Copy,
+        Clone,
+        Debug,
+        AsExpression,
+        FromSqlRow,
+        PartialEq,
+        Eq,
+        PartialOrd,
+        Ord
+    )]
+    pub enum UpdateComponentKind;
+
+    RotBootloader => b"rot_bootloader"
+    Rot => b"rot"
+    Sp => b"sp"
+    Host => b"host"
+);
+
+impl From<shared::UpdateComponentKind> for UpdateComponentKind {
+    fn from(value: shared::UpdateComponentKind) -> Self {
+        match value {
+            shared::UpdateComponentKind::RotBootloader => Self::RotBootloader,
+            shared::UpdateComponentKind::Rot => Self::Rot,
+            shared::UpdateComponentKind::Sp => Self::Sp,
+            shared::UpdateComponentKind::Host => Self::Host,
+        }
+    }
+}
+
+impl From<UpdateComponentKind> for shared::UpdateComponentKind {
+    fn from(value: UpdateComponentKind) -> Self {
+        match value {
+            UpdateComponentKind::RotBootloader => Self::RotBootloader,
+            UpdateComponentKind::Rot => Self::Rot,
+            UpdateComponentKind::Sp => Self::Sp,
+            UpdateComponentKind::Host => Self::Host,
+        }
+    }
+}
+
+impl_enum_type!(
+    UpdateComponentStateEnum:
+
+    /// See [`shared::UpdateComponentState`].
+    #[derive(Copy, Clone, Debug, AsExpression, FromSqlRow, PartialEq, Eq)]
+    pub enum UpdateComponentState;
+
+    Waiting => b"waiting"
+    Updating => b"updating"
+    Updated => b"updated"
+    Skipped => b"skipped"
+    Failed => b"failed"
+    Aborted => b"aborted"
+);
+
+impl From<shared::UpdateComponentState> for UpdateComponentState {
+    fn from(value: shared::UpdateComponentState) -> Self {
+        match value {
+            shared::UpdateComponentState::Waiting => Self::Waiting,
+            shared::UpdateComponentState::Updating => Self::Updating,
+            shared::UpdateComponentState::Updated => Self::Updated,
+            shared::UpdateComponentState::Skipped => Self::Skipped,
+            shared::UpdateComponentState::Failed => Self::Failed,
+            shared::UpdateComponentState::Aborted => Self::Aborted,
+        }
+    }
+}
+
+impl From<UpdateComponentState> for shared::UpdateComponentState {
+    fn from(value: UpdateComponentState) -> Self {
+        match value {
+            UpdateComponentState::Waiting => Self::Waiting,
+            UpdateComponentState::Updating => Self::Updating,
+            UpdateComponentState::Updated => Self::Updated,
+            UpdateComponentState::Skipped => Self::Skipped,
+            UpdateComponentState::Failed => Self::Failed,
+            UpdateComponentState::Aborted => Self::Aborted,
+        }
+    }
+}
+
+impl From<shared::ServiceProcessorType> for SpType {
+    fn from(value: shared::ServiceProcessorType) -> Self {
+        match value {
+            shared::ServiceProcessorType::Sled => SpType::Sled,
+            shared::ServiceProcessorType::Switch => SpType::Switch,
+            shared::ServiceProcessorType::Power => SpType::Power,
+        }
+    }
+}
+
+impl From<SpType> for shared::ServiceProcessorType {
+    fn from(value: SpType) -> Self {
+        match value {
+            SpType::Sled => Self::Sled,
+            SpType::Switch => Self::Switch,
+            SpType::Power => Self::Power,
+        }
+    }
+}
+
+/// An update of a single service processor's components, driven from the
+/// technician port and reported to Nexus by wicketd.
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = technician_port_update)]
+pub struct TechnicianPortUpdate {
+    /// ID of the wicketd update execution.
+    pub id: Uuid,
+    pub sp_type: SpType,
+    pub sp_slot: SpMgsSlot,
+    /// When progress for this update was first reported.
+    pub time_started: DateTime<Utc>,
+    /// When progress for this update was most recently reported.
+    pub time_reported: DateTime<Utc>,
+}
+
+impl TechnicianPortUpdate {
+    pub fn new(
+        id: Uuid,
+        sp_type: shared::ServiceProcessorType,
+        sp_slot: u16,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            sp_type: sp_type.into(),
+            sp_slot: sp_slot.into(),
+            time_started: now,
+            time_reported: now,
+        }
+    }
+
+    pub fn into_view(
+        self,
+        components: Vec<TechnicianPortUpdateComponent>,
+    ) -> views::TechnicianPortUpdate {
+        views::TechnicianPortUpdate {
+            id: self.id,
+            sp_type: self.sp_type.into(),
+            sp_slot: **self.sp_slot,
+            time_started: self.time_started,
+            time_reported: self.time_reported,
+            components: components.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Most recently reported progress of one component within a
+/// [`TechnicianPortUpdate`].
+#[derive(Clone, Debug, Insertable, Queryable, Selectable)]
+#[diesel(table_name = technician_port_update_component)]
+pub struct TechnicianPortUpdateComponent {
+    pub update_id: Uuid,
+    pub component: UpdateComponentKind,
+    pub state: UpdateComponentState,
+    pub steps_completed: SqlU32,
+    pub total_steps: SqlU32,
+    pub current_step: Option<String>,
+    pub message: Option<String>,
+}
+
+impl TechnicianPortUpdateComponent {
+    pub fn new(
+        update_id: Uuid,
+        progress: shared::UpdateComponentProgress,
+    ) -> Self {
+        Self {
+            update_id,
+            component: progress.component.into(),
+            state: progress.state.into(),
+            steps_completed: progress.steps_completed.into(),
+            total_steps: progress.total_steps.into(),
+            current_step: progress.current_step,
+            message: progress.message,
+        }
+    }
+}
+
+impl From<TechnicianPortUpdateComponent> for shared::UpdateComponentProgress {
+    fn from(value: TechnicianPortUpdateComponent) -> Self {
+        Self {
+            component: value.component.into(),
+            state: value.state.into(),
+            steps_completed: *value.steps_completed,
+            total_steps: *value.total_steps,
+            current_step: value.current_step,
+            message: value.message,
+        }
+    }
+}
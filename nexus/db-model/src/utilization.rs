@@ -62,10 +62,15 @@ impl From<SiloUtilization> for views::Utilization {
 pub struct IpPoolUtilization {
     pub remaining: f64,
     pub capacity: f64,
+    pub ranges: Vec<views::IpPoolRangeUtilization>,
 }
 
 impl From<IpPoolUtilization> for views::IpPoolUtilization {
     fn from(util: IpPoolUtilization) -> Self {
-        Self { remaining: util.remaining, capacity: util.capacity }
+        Self {
+            remaining: util.remaining,
+            capacity: util.capacity,
+            ranges: util.ranges,
+        }
     }
 }
@@ -5,6 +5,7 @@
 use super::impl_enum_type;
 use crate::typed_uuid::DbTypedUuid;
 use nexus_db_schema::schema::volume_resource_usage;
+use nexus_types::internal_api::views;
 use omicron_uuid_kinds::DatasetKind;
 use omicron_uuid_kinds::DatasetUuid;
 use omicron_uuid_kinds::VolumeKind;
@@ -59,7 +60,7 @@ pub struct VolumeResourceUsageRecord {
     pub region_snapshot_snapshot_id: Option<Uuid>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum VolumeResourceUsage {
     ReadOnlyRegion {
         region_id: Uuid,
@@ -150,3 +151,18 @@ impl TryFrom<VolumeResourceUsageRecord> for VolumeResourceUsage {
         }
     }
 }
+
+impl From<VolumeResourceUsage> for views::VolumeReadOnlyResource {
+    fn from(usage: VolumeResourceUsage) -> Self {
+        match usage {
+            VolumeResourceUsage::ReadOnlyRegion { region_id } => {
+                Self::ReadOnlyRegion { region_id }
+            }
+            VolumeResourceUsage::RegionSnapshot {
+                dataset_id,
+                region_id,
+                snapshot_id,
+            } => Self::RegionSnapshot { dataset_id, region_id, snapshot_id },
+        }
+    }
+}
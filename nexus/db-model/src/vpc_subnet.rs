@@ -3,6 +3,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use super::Generation;
+use super::Ipv4Addr;
 use super::{Ipv4Net, Ipv6Net, Name};
 use crate::NetworkInterface;
 use crate::collection::DatastoreCollectionConfig;
@@ -11,6 +12,7 @@ use db_macros::Resource;
 use nexus_config::NUM_INITIAL_RESERVED_IP_ADDRESSES;
 use nexus_db_schema::schema::network_interface;
 use nexus_db_schema::schema::vpc_subnet;
+use nexus_db_schema::schema::vpc_subnet_ip_reservation;
 use nexus_types::external_api::params;
 use nexus_types::external_api::views;
 use nexus_types::identity::Resource;
@@ -40,6 +42,7 @@ pub struct VpcSubnet {
     pub ipv4_block: Ipv4Net,
     pub ipv6_block: Ipv6Net,
     pub custom_router_id: Option<Uuid>,
+    pub ipv4_secondary_blocks: Vec<Ipv4Net>,
 }
 
 impl VpcSubnet {
@@ -62,22 +65,40 @@ impl VpcSubnet {
             ipv4_block: Ipv4Net(ipv4_block),
             ipv6_block: Ipv6Net(ipv6_block),
             custom_router_id: None,
+            ipv4_secondary_blocks: Vec::new(),
         }
     }
 
+    /// Return all IPv4 blocks of the subnet, primary block first.
+    pub fn ipv4_blocks(&self) -> impl Iterator<Item = oxnet::Ipv4Net> + '_ {
+        std::iter::once(self.ipv4_block.0)
+            .chain(self.ipv4_secondary_blocks.iter().map(|block| block.0))
+    }
+
+    /// Return the IPv4 block of the subnet containing `addr`, if any.
+    pub fn ipv4_block_containing(
+        &self,
+        addr: std::net::Ipv4Addr,
+    ) -> Option<oxnet::Ipv4Net> {
+        self.ipv4_blocks().find(|block| block.contains(addr))
+    }
+
     /// Verify that the provided IP address is contained in the VPC Subnet.
     ///
     /// This checks:
     ///
     /// - The subnet has an allocated block of the same version as the address
-    /// - The allocated block contains the address.
+    /// - One of the allocated blocks contains the address.
     /// - The address is not reserved.
     pub fn check_requestable_addr(
         &self,
         addr: IpAddr,
     ) -> Result<(), external::Error> {
         match addr {
-            IpAddr::V4(addr) => self.ipv4_block.check_requestable_addr(addr),
+            IpAddr::V4(addr) => match self.ipv4_block_containing(addr) {
+                Some(block) => Ipv4Net(block).check_requestable_addr(addr),
+                None => self.ipv4_block.check_requestable_addr(addr),
+            },
             IpAddr::V6(addr) => self.ipv6_block.check_requestable_addr(addr),
         }
         .map_err(|e| external::Error::invalid_request(e.to_string()))
@@ -109,6 +130,19 @@ impl From<VpcSubnet> for views::VpcSubnet {
     }
 }
 
+impl From<VpcSubnet> for views::VpcSubnetIpv4Blocks {
+    fn from(subnet: VpcSubnet) -> Self {
+        Self {
+            primary: subnet.ipv4_block.0,
+            secondary: subnet
+                .ipv4_secondary_blocks
+                .into_iter()
+                .map(|block| block.0)
+                .collect(),
+        }
+    }
+}
+
 #[derive(AsChangeset, Clone, Deserialize, Serialize, Debug)]
 #[diesel(table_name = vpc_subnet)]
 pub struct VpcSubnetUpdate {
@@ -135,3 +169,56 @@ impl DatastoreCollectionConfig<NetworkInterface> for VpcSubnet {
     type CollectionTimeDeletedColumn = vpc_subnet::dsl::time_deleted;
     type CollectionIdColumn = network_interface::dsl::subnet_id;
 }
+
+/// A private IP address held in a VPC Subnet, independent of any interface
+#[derive(
+    Queryable, Insertable, Clone, Debug, Selectable, Serialize, Deserialize,
+)]
+#[diesel(table_name = vpc_subnet_ip_reservation)]
+pub struct VpcSubnetIpReservation {
+    pub id: Uuid,
+    pub description: String,
+    pub time_created: DateTime<Utc>,
+    pub time_deleted: Option<DateTime<Utc>>,
+    pub vpc_id: Uuid,
+    pub subnet_id: Uuid,
+    pub ip: Ipv4Addr,
+}
+
+impl VpcSubnetIpReservation {
+    pub fn new(
+        subnet: &VpcSubnet,
+        ip: std::net::Ipv4Addr,
+        description: String,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            description,
+            time_created: Utc::now(),
+            time_deleted: None,
+            vpc_id: subnet.vpc_id,
+            subnet_id: subnet.identity.id,
+            ip: ip.into(),
+        }
+    }
+}
+
+impl From<VpcSubnetIpReservation> for views::VpcSubnetIpReservation {
+    fn from(reservation: VpcSubnetIpReservation) -> Self {
+        Self {
+            id: reservation.id,
+            description: reservation.description,
+            time_created: reservation.time_created,
+            vpc_id: reservation.vpc_id,
+            subnet_id: reservation.subnet_id,
+            ip: reservation.ip.into(),
+        }
+    }
+}
+
+impl DatastoreCollectionConfig<VpcSubnetIpReservation> for VpcSubnet {
+    type CollectionId = Uuid;
+    type GenerationNumberColumn = vpc_subnet::dsl::rcgen;
+    type CollectionTimeDeletedColumn = vpc_subnet::dsl::time_deleted;
+    type CollectionIdColumn = vpc_subnet_ip_reservation::dsl::subnet_id;
+}
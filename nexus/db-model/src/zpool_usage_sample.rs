@@ -0,0 +1,43 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::ByteCount;
+use crate::SqlU8;
+use crate::typed_uuid::DbTypedUuid;
+use chrono::{DateTime, Utc};
+use nexus_db_schema::schema::zpool_usage_sample;
+use omicron_uuid_kinds::SledKind;
+use omicron_uuid_kinds::SledUuid;
+use omicron_uuid_kinds::ZpoolKind;
+
+/// The space used within a zpool at a point in time, as reported by
+/// inventory.
+#[derive(Queryable, Insertable, Debug, Clone, Selectable, PartialEq)]
+#[diesel(table_name = zpool_usage_sample)]
+pub struct ZpoolUsageSample {
+    pub zpool_id: DbTypedUuid<ZpoolKind>,
+    pub time_sampled: DateTime<Utc>,
+    pub sled_id: DbTypedUuid<SledKind>,
+    pub total_size: ByteCount,
+    pub allocated: ByteCount,
+    pub fragmentation_percent: Option<SqlU8>,
+}
+
+impl ZpoolUsageSample {
+    /// Returns a sample of the usage of `zpool`, or `None` if the sled-agent
+    /// that reported it did not report its allocated space.
+    pub fn from_inventory(
+        sled_id: SledUuid,
+        zpool: &nexus_types::inventory::Zpool,
+    ) -> Option<Self> {
+        Some(Self {
+            zpool_id: zpool.id.into(),
+            time_sampled: zpool.time_collected,
+            sled_id: sled_id.into(),
+            total_size: zpool.total_size.into(),
+            allocated: zpool.allocated?.into(),
+            fragmentation_percent: zpool.fragmentation_percent.map(SqlU8::from),
+        })
+    }
+}
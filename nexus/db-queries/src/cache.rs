@@ -0,0 +1,344 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! In-memory caches of data that is read far more often than it changes
+//!
+//! Some data is consulted while handling nearly every request (like the role
+//! assignments used to authorize it) but is only rarely modified. Each
+//! [`DataStore`](crate::db::DataStore) keeps that data in caches so that it
+//! doesn't need to be read from the database over and over again.
+
+use crate::authz;
+use crate::db::model::DnsGroup;
+use crate::db::model::IdentityType;
+use crate::db::model::IpPool;
+use crate::db::model::IpVersion;
+use crate::db::model::RoleAssignment;
+use chrono::DateTime;
+use chrono::Utc;
+use nexus_types::internal_api::params::DnsConfigParams;
+use omicron_common::api::external::ResourceType;
+use oximeter::MetricsError;
+use oximeter::Sample;
+use oximeter::types::Cumulative;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+use uuid::Uuid;
+
+oximeter::use_timeseries!("database-cache.toml");
+use database_cache::DatabaseCache;
+use database_cache::Hits;
+use database_cache::Invalidations;
+use database_cache::Misses;
+
+/// How long entries that can be modified by other Nexus instances are kept
+///
+/// This bounds how long it takes for this Nexus to notice those changes.
+const MAX_AGE: Duration = Duration::from_secs(5);
+
+/// The most entries that any one cache holds
+const MAX_ENTRIES: usize = 4096;
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+/// A cache of values read from the database, keyed by `K`
+///
+/// Each cache has a generation number, which is bumped whenever the cache is
+/// invalidated. Callers must invalidate the cache after any write to the data
+/// that it holds. A value that was read from the database is only inserted if
+/// the generation hasn't changed since the read started, so a read that raced
+/// with a write can't put stale data back after the write invalidated it.
+///
+/// Invalidation only covers writes made through this Nexus. Entries can be
+/// given a maximum age to bound how long writes made by other Nexus instances
+/// go unnoticed.
+#[derive(Debug)]
+pub(crate) struct GenerationCache<K, V> {
+    max_age: Option<Duration>,
+    max_entries: usize,
+    inner: Mutex<CacheInner<K, V>>,
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug)]
+struct CacheInner<K, V> {
+    generation: u64,
+    entries: BTreeMap<K, CacheEntry<V>>,
+}
+
+#[derive(Debug)]
+struct CacheEntry<V> {
+    value: V,
+    time_inserted: Instant,
+}
+
+impl<K: Ord, V: Clone> GenerationCache<K, V> {
+    pub(crate) fn new(max_age: Option<Duration>, max_entries: usize) -> Self {
+        GenerationCache {
+            max_age,
+            max_entries,
+            inner: Mutex::new(CacheInner {
+                generation: 0,
+                entries: BTreeMap::new(),
+            }),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Returns the cached value for `key`, or loads it with `load` and caches
+    /// it if there isn't one
+    pub(crate) async fn get_or_load<F, Fut, E>(
+        &self,
+        key: K,
+        load: F,
+    ) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        self.get_or_load_if(key, |_| true, load).await
+    }
+
+    /// Like [`GenerationCache::get_or_load()`], but a cached value for which
+    /// `is_current` returns false is replaced by a freshly loaded one
+    pub(crate) async fn get_or_load_if<P, F, Fut, E>(
+        &self,
+        key: K,
+        is_current: P,
+        load: F,
+    ) -> Result<V, E>
+    where
+        P: FnOnce(&V) -> bool,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        let generation = {
+            let inner = self.inner.lock().unwrap();
+            if let Some(entry) = inner.entries.get(&key) {
+                if !self.is_expired(entry) && is_current(&entry.value) {
+                    self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(entry.value.clone());
+                }
+            }
+            inner.generation
+        };
+
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+        let value = load().await?;
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation == generation {
+            if inner.entries.len() >= self.max_entries
+                && !inner.entries.contains_key(&key)
+            {
+                // Make room by dropping whatever has expired, or everything if
+                // nothing has.  Either way, the next reads go to the database.
+                inner.entries.retain(|_, entry| !self.is_expired(entry));
+                if inner.entries.len() >= self.max_entries {
+                    inner.entries.clear();
+                }
+            }
+            inner.entries.insert(
+                key,
+                CacheEntry {
+                    value: value.clone(),
+                    time_inserted: Instant::now(),
+                },
+            );
+        }
+        Ok(value)
+    }
+
+    /// Discards everything in the cache
+    ///
+    /// This must be called after any write to the data that the cache holds.
+    pub(crate) fn invalidate(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.generation += 1;
+        inner.entries.clear();
+        self.counters.invalidations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn is_expired(&self, entry: &CacheEntry<V>) -> bool {
+        self.max_age
+            .is_some_and(|max_age| entry.time_inserted.elapsed() >= max_age)
+    }
+}
+
+/// Identifies the role assignments that an identity has on a resource
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub(crate) struct RoleAssignmentKey {
+    pub identity_type: IdentityType,
+    pub identity_id: Uuid,
+    pub resource_type: ResourceType,
+    pub resource_id: Uuid,
+}
+
+/// Identifies an IP pool that's looked up by something other than its ID
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub(crate) enum IpPoolKey {
+    /// the default IP pool of a Silo
+    SiloDefault(Uuid),
+    /// the IP pool for internal services of one IP version
+    Service(IpVersion),
+}
+
+/// The caches kept by each [`DataStore`](crate::db::DataStore)
+#[derive(Debug)]
+pub(crate) struct DataStoreCaches {
+    /// role assignments, which are loaded for every authorization check
+    pub role_assignments:
+        GenerationCache<RoleAssignmentKey, Vec<RoleAssignment>>,
+    /// default and internal service IP pools
+    pub ip_pools: GenerationCache<IpPoolKey, (authz::IpPool, IpPool)>,
+    /// the latest configuration of each DNS group
+    ///
+    /// DNS configuration is versioned in the database, so entries are checked
+    /// against the latest version rather than expiring.
+    pub dns_config: GenerationCache<DnsGroup, DnsConfigParams>,
+    start_time: DateTime<Utc>,
+}
+
+impl DataStoreCaches {
+    pub(crate) fn new() -> Self {
+        DataStoreCaches {
+            role_assignments: GenerationCache::new(Some(MAX_AGE), MAX_ENTRIES),
+            ip_pools: GenerationCache::new(Some(MAX_AGE), MAX_ENTRIES),
+            dns_config: GenerationCache::new(None, MAX_ENTRIES),
+            start_time: Utc::now(),
+        }
+    }
+
+    /// Returns an oximeter producer reporting the hits, misses, and
+    /// invalidations of each cache
+    pub(crate) fn producer(&self) -> Producer {
+        Producer {
+            start_time: self.start_time,
+            caches: vec![
+                (
+                    DatabaseCache { name: "role_assignments".into() },
+                    Arc::clone(&self.role_assignments.counters),
+                ),
+                (
+                    DatabaseCache { name: "ip_pools".into() },
+                    Arc::clone(&self.ip_pools.counters),
+                ),
+                (
+                    DatabaseCache { name: "dns_config".into() },
+                    Arc::clone(&self.dns_config.counters),
+                ),
+            ],
+        }
+    }
+}
+
+/// Produces samples of the counters of a [`DataStoreCaches`]
+#[derive(Debug, Clone)]
+pub(crate) struct Producer {
+    start_time: DateTime<Utc>,
+    caches: Vec<(DatabaseCache, Arc<Counters>)>,
+}
+
+impl oximeter::Producer for Producer {
+    fn produce(
+        &mut self,
+    ) -> Result<Box<dyn Iterator<Item = Sample> + 'static>, MetricsError> {
+        let cumulative = |counter: &AtomicU64| {
+            Cumulative::with_start_time(
+                self.start_time,
+                counter.load(Ordering::Relaxed),
+            )
+        };
+        let mut samples = Vec::with_capacity(3 * self.caches.len());
+        for (target, counters) in &self.caches {
+            samples.push(Sample::new(
+                target,
+                &Hits { datum: cumulative(&counters.hits) },
+            )?);
+            samples.push(Sample::new(
+                target,
+                &Misses { datum: cumulative(&counters.misses) },
+            )?);
+            samples.push(Sample::new(
+                target,
+                &Invalidations { datum: cumulative(&counters.invalidations) },
+            )?);
+        }
+        Ok(Box::new(samples.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn load(value: u32) -> Result<u32, ()> {
+        Ok(value)
+    }
+
+    #[tokio::test]
+    async fn test_generation_cache_hits_and_invalidation() {
+        let cache = GenerationCache::new(None, 2);
+
+        assert_eq!(cache.get_or_load("a", || load(1)).await, Ok(1));
+        assert_eq!(cache.get_or_load("a", || load(2)).await, Ok(1));
+        assert_eq!(cache.get_or_load("b", || load(3)).await, Ok(3));
+
+        // A value that isn't current is reloaded.
+        assert_eq!(
+            cache.get_or_load_if("b", |value| *value == 4, || load(4)).await,
+            Ok(4)
+        );
+        assert_eq!(cache.get_or_load("b", || load(5)).await, Ok(4));
+
+        // Errors aren't cached.
+        assert_eq!(cache.get_or_load("c", || async { Err(()) }).await, Err(()));
+
+        // Adding an entry to a full cache empties it.
+        assert_eq!(cache.get_or_load("c", || load(6)).await, Ok(6));
+        assert_eq!(cache.get_or_load("a", || load(7)).await, Ok(7));
+
+        cache.invalidate();
+        assert_eq!(cache.get_or_load("a", || load(8)).await, Ok(8));
+
+        assert_eq!(cache.counters.hits.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.counters.misses.load(Ordering::Relaxed), 7);
+        assert_eq!(cache.counters.invalidations.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_generation_cache_racing_invalidation() {
+        let cache = GenerationCache::new(None, 16);
+
+        // A value read before an invalidation must not be cached after it.
+        let value = cache
+            .get_or_load("a", || async {
+                cache.invalidate();
+                Ok::<_, ()>(1)
+            })
+            .await;
+        assert_eq!(value, Ok(1));
+        assert_eq!(cache.get_or_load("a", || load(2)).await, Ok(2));
+        assert_eq!(cache.get_or_load("a", || load(3)).await, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_generation_cache_max_age() {
+        let cache = GenerationCache::new(Some(Duration::ZERO), 16);
+        assert_eq!(cache.get_or_load("a", || load(1)).await, Ok(1));
+        assert_eq!(cache.get_or_load("a", || load(2)).await, Ok(2));
+        assert_eq!(cache.counters.hits.load(Ordering::Relaxed), 0);
+    }
+}
@@ -7,6 +7,7 @@
 use nexus_db_errors::ErrorHandler;
 use nexus_db_errors::public_error_from_diesel;
 
+use crate::cache::RoleAssignmentKey;
 use async_bb8_diesel::AsyncRunQueryDsl;
 use diesel::prelude::*;
 use nexus_auth::context::OpContext;
@@ -28,6 +29,41 @@ impl Storage for super::DataStore {
         identity_id: Uuid,
         resource_type: ResourceType,
         resource_id: Uuid,
+    ) -> Result<Vec<RoleAssignment>, Error> {
+        // This is called for every authorization check, so the results are
+        // cached.  Anything that modifies role assignments or group
+        // memberships must invalidate the cache.
+        let key = RoleAssignmentKey {
+            identity_type: identity_type.clone(),
+            identity_id,
+            resource_type,
+            resource_id,
+        };
+        self.caches
+            .role_assignments
+            .get_or_load(key, || {
+                self.role_asgn_list_for_uncached(
+                    opctx,
+                    identity_type,
+                    identity_id,
+                    resource_type,
+                    resource_id,
+                )
+            })
+            .await
+    }
+}
+
+impl super::DataStore {
+    /// Like [`Storage::role_asgn_list_for()`], but always reads the role
+    /// assignments from the database
+    async fn role_asgn_list_for_uncached(
+        &self,
+        opctx: &OpContext,
+        identity_type: IdentityType,
+        identity_id: Uuid,
+        resource_type: ResourceType,
+        resource_id: Uuid,
     ) -> Result<Vec<RoleAssignment>, Error> {
         use nexus_db_schema::schema::role_assignment::dsl as role_dsl;
         use nexus_db_schema::schema::silo_group_membership::dsl as group_dsl;
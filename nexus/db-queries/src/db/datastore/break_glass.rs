@@ -0,0 +1,518 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods related to the break-glass operator account.
+//!
+//! The break-glass account is a local user in the default silo that operators
+//! can enable from the technician port when they can't otherwise log in (e.g.,
+//! because the external identity provider is broken).  While enabled, the user
+//! can log in with a password chosen by the operator and is granted the fleet
+//! "viewer" role, and nothing else.  Each time the account is enabled, we
+//! record a row in `break_glass_account` saying who enabled it and when it
+//! expires; these rows are kept as an audit trail.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::model::BreakGlassAccount;
+use crate::db::model::IdentityType;
+use crate::db::model::PasswordHashString;
+use crate::db::model::RoleAssignment;
+use crate::db::model::SiloUser;
+use crate::db::model::SiloUserPasswordHash;
+use crate::db::model::SiloUserPasswordUpdate;
+use crate::db::model::to_db_typed_uuid;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use nexus_db_lookup::DbConnection;
+use nexus_types::identity::Asset;
+use nexus_types::silo::BREAK_GLASS_USER_NAME;
+use nexus_types::silo::DEFAULT_SILO_ID;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::LookupType;
+use omicron_common::api::external::ResourceType;
+use omicron_common::api::external::UpdateResult;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::SiloUserUuid;
+
+/// The only role the break-glass user is ever granted
+const BREAK_GLASS_FLEET_ROLE: &str = "viewer";
+
+impl DataStore {
+    /// Enables the break-glass account until `time_expires`
+    ///
+    /// This creates the break-glass user if it doesn't already exist, sets its
+    /// password, and resets its privileges to read-only access to the fleet.
+    /// Any account that is still enabled is disabled first.
+    pub async fn break_glass_account_enable(
+        &self,
+        opctx: &OpContext,
+        password_hash: omicron_passwords::PasswordHashString,
+        enabled_by: String,
+        time_expires: DateTime<Utc>,
+    ) -> CreateResult<BreakGlassAccount> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let result = self
+            .transaction_retry_wrapper("break_glass_account_enable")
+            .transaction(&conn, |conn| {
+                let password_hash = password_hash.clone();
+                let enabled_by = enabled_by.clone();
+                async move {
+                    let silo_user_id =
+                        Self::break_glass_user_ensure(&conn).await?;
+
+                    // Supersede any account that is still enabled.
+                    Self::break_glass_account_disable_on_connection(
+                        &conn,
+                        &enabled_by,
+                    )
+                    .await?;
+
+                    Self::break_glass_user_set_password(
+                        &conn,
+                        silo_user_id,
+                        Some(PasswordHashString::from(password_hash)),
+                    )
+                    .await?;
+
+                    // Whatever privileges the user may have picked up since
+                    // the account was last enabled, it gets read-only access
+                    // to the fleet and nothing else.
+                    Self::break_glass_user_revoke_privileges(
+                        &conn,
+                        silo_user_id,
+                    )
+                    .await?;
+                    {
+                        use nexus_db_schema::schema::role_assignment::dsl;
+                        diesel::insert_into(dsl::role_assignment)
+                            .values(RoleAssignment::new_for_silo_user(
+                                silo_user_id,
+                                ResourceType::Fleet,
+                                *nexus_db_fixed_data::FLEET_ID,
+                                BREAK_GLASS_FLEET_ROLE,
+                            ))
+                            .execute_async(&conn)
+                            .await?;
+                    }
+
+                    use nexus_db_schema::schema::break_glass_account::dsl;
+                    diesel::insert_into(dsl::break_glass_account)
+                        .values(BreakGlassAccount::new(
+                            silo_user_id,
+                            enabled_by,
+                            time_expires,
+                        ))
+                        .returning(BreakGlassAccount::as_returning())
+                        .get_result_async(&conn)
+                        .await
+                }
+            })
+            .await;
+        self.caches.role_assignments.invalidate();
+        result.map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Disables the break-glass account, if it's enabled
+    ///
+    /// The break-glass user's password, privileges, and sessions are removed
+    /// whether or not the account was enabled.  Returns the account that was
+    /// disabled, if any.
+    pub async fn break_glass_account_disable(
+        &self,
+        opctx: &OpContext,
+        disabled_by: String,
+    ) -> UpdateResult<Option<BreakGlassAccount>> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let result = self
+            .transaction_retry_wrapper("break_glass_account_disable")
+            .transaction(&conn, |conn| {
+                let disabled_by = disabled_by.clone();
+                async move {
+                    let disabled =
+                        Self::break_glass_account_disable_on_connection(
+                            &conn,
+                            &disabled_by,
+                        )
+                        .await?;
+
+                    let Some(silo_user_id) =
+                        Self::break_glass_user_fetch(&conn).await?
+                    else {
+                        return Ok(disabled);
+                    };
+
+                    Self::break_glass_user_set_password(
+                        &conn,
+                        silo_user_id,
+                        None,
+                    )
+                    .await?;
+                    Self::break_glass_user_revoke_privileges(
+                        &conn,
+                        silo_user_id,
+                    )
+                    .await?;
+                    {
+                        use nexus_db_schema::schema::console_session::dsl;
+                        diesel::delete(dsl::console_session)
+                            .filter(
+                                dsl::silo_user_id
+                                    .eq(to_db_typed_uuid(silo_user_id)),
+                            )
+                            .execute_async(&conn)
+                            .await?;
+                    }
+                    {
+                        use nexus_db_schema::schema::device_access_token::dsl;
+                        diesel::delete(dsl::device_access_token)
+                            .filter(
+                                dsl::silo_user_id
+                                    .eq(to_db_typed_uuid(silo_user_id)),
+                            )
+                            .execute_async(&conn)
+                            .await?;
+                    }
+
+                    Ok(disabled)
+                }
+            })
+            .await;
+        self.caches.role_assignments.invalidate();
+        result.map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Checks whether `silo_user_id` may authenticate, as far as the
+    /// break-glass account is concerned
+    ///
+    /// Users that have never been used for the break-glass account are always
+    /// allowed.  The break-glass user is only allowed while the account is
+    /// enabled and unexpired, and only if it has picked up no privileges
+    /// beyond read-only access to the fleet.  This is what keeps the account
+    /// from being used to get at anything inside a silo.
+    pub async fn break_glass_account_check(
+        &self,
+        opctx: &OpContext,
+        silo_user_id: SiloUserUuid,
+    ) -> Result<(), Error> {
+        // This is called while authenticating, so the caller is the external
+        // authenticator, which is allowed to list the users in any silo.
+        let authz_silo = authz::Silo::new(
+            authz::FLEET,
+            DEFAULT_SILO_ID,
+            LookupType::ById(DEFAULT_SILO_ID),
+        );
+        opctx.authorize(authz::Action::ListChildren, &authz_silo).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let accounts = {
+            use nexus_db_schema::schema::break_glass_account::dsl;
+            dsl::break_glass_account
+                .filter(dsl::silo_user_id.eq(to_db_typed_uuid(silo_user_id)))
+                .select(BreakGlassAccount::as_select())
+                .load_async(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?
+        };
+        if accounts.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        if !accounts.iter().any(|account| account.is_usable_at(now)) {
+            return Err(Error::Unauthenticated {
+                internal_message: String::from(
+                    "break-glass account is disabled or expired",
+                ),
+            });
+        }
+
+        let roles = {
+            use nexus_db_schema::schema::role_assignment::dsl;
+            dsl::role_assignment
+                .filter(dsl::identity_type.eq(IdentityType::SiloUser))
+                .filter(dsl::identity_id.eq(silo_user_id.into_untyped_uuid()))
+                .select(RoleAssignment::as_select())
+                .load_async(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?
+        };
+        let groups: i64 = {
+            use nexus_db_schema::schema::silo_group_membership::dsl;
+            dsl::silo_group_membership
+                .filter(dsl::silo_user_id.eq(to_db_typed_uuid(silo_user_id)))
+                .count()
+                .get_result_async(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?
+        };
+        let unexpected_roles = roles.iter().any(|role| {
+            role.resource_type != ResourceType::Fleet.to_string()
+                || role.role_name != BREAK_GLASS_FLEET_ROLE
+        });
+        if unexpected_roles || groups > 0 {
+            return Err(Error::Unauthenticated {
+                internal_message: String::from(
+                    "break-glass user has been granted privileges beyond \
+                     read-only fleet access",
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the ID of the break-glass user, if it exists
+    async fn break_glass_user_fetch(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+    ) -> Result<Option<SiloUserUuid>, DieselError> {
+        use nexus_db_schema::schema::silo_user::dsl;
+        Ok(dsl::silo_user
+            .filter(dsl::silo_id.eq(DEFAULT_SILO_ID))
+            .filter(dsl::external_id.eq(BREAK_GLASS_USER_NAME))
+            .filter(dsl::time_deleted.is_null())
+            .select(SiloUser::as_select())
+            .get_result_async(conn)
+            .await
+            .optional()?
+            .map(|user| user.id()))
+    }
+
+    /// Returns the ID of the break-glass user, creating it if necessary
+    async fn break_glass_user_ensure(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+    ) -> Result<SiloUserUuid, DieselError> {
+        if let Some(silo_user_id) = Self::break_glass_user_fetch(conn).await? {
+            return Ok(silo_user_id);
+        }
+
+        use nexus_db_schema::schema::silo_user::dsl;
+        let silo_user_id = SiloUserUuid::new_v4();
+        diesel::insert_into(dsl::silo_user)
+            .values(SiloUser::new(
+                DEFAULT_SILO_ID,
+                silo_user_id,
+                BREAK_GLASS_USER_NAME.to_string(),
+            ))
+            .execute_async(conn)
+            .await?;
+        Ok(silo_user_id)
+    }
+
+    /// Sets the break-glass user's password hash, or removes it if
+    /// `password_hash` is `None`
+    async fn break_glass_user_set_password(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        silo_user_id: SiloUserUuid,
+        password_hash: Option<PasswordHashString>,
+    ) -> Result<(), DieselError> {
+        use nexus_db_schema::schema::silo_user_password_hash::dsl;
+        match password_hash {
+            Some(hash) => {
+                diesel::insert_into(dsl::silo_user_password_hash)
+                    .values(SiloUserPasswordHash::new(
+                        silo_user_id,
+                        hash.clone(),
+                    ))
+                    .on_conflict(dsl::silo_user_id)
+                    .do_update()
+                    .set(SiloUserPasswordUpdate::new(hash))
+                    .execute_async(conn)
+                    .await?;
+            }
+            None => {
+                diesel::delete(dsl::silo_user_password_hash)
+                    .filter(
+                        dsl::silo_user_id.eq(to_db_typed_uuid(silo_user_id)),
+                    )
+                    .execute_async(conn)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all of the break-glass user's role assignments and group
+    /// memberships
+    async fn break_glass_user_revoke_privileges(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        silo_user_id: SiloUserUuid,
+    ) -> Result<(), DieselError> {
+        {
+            use nexus_db_schema::schema::role_assignment::dsl;
+            diesel::delete(dsl::role_assignment)
+                .filter(dsl::identity_type.eq(IdentityType::SiloUser))
+                .filter(dsl::identity_id.eq(silo_user_id.into_untyped_uuid()))
+                .execute_async(conn)
+                .await?;
+        }
+        {
+            use nexus_db_schema::schema::silo_group_membership::dsl;
+            diesel::delete(dsl::silo_group_membership)
+                .filter(dsl::silo_user_id.eq(to_db_typed_uuid(silo_user_id)))
+                .execute_async(conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Marks whichever account is enabled as disabled by `disabled_by`
+    async fn break_glass_account_disable_on_connection(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        disabled_by: &str,
+    ) -> Result<Option<BreakGlassAccount>, DieselError> {
+        use nexus_db_schema::schema::break_glass_account::dsl;
+        Ok(diesel::update(dsl::break_glass_account)
+            .filter(dsl::time_disabled.is_null())
+            .set((
+                dsl::time_disabled.eq(Utc::now()),
+                dsl::disabled_by.eq(disabled_by.to_string()),
+            ))
+            .returning(BreakGlassAccount::as_returning())
+            .get_results_async(conn)
+            .await?
+            .pop())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pub_test_utils::TestDatabase;
+    use chrono::TimeDelta;
+    use omicron_test_utils::dev;
+
+    fn password_hash() -> omicron_passwords::PasswordHashString {
+        let mut hasher = omicron_passwords::Hasher::default();
+        hasher
+            .create_password(
+                &omicron_passwords::Password::new("oops-it-broke").unwrap(),
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_break_glass_account_lifecycle() {
+        let logctx = dev::test_setup_log("test_break_glass_account_lifecycle");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        // Nothing to disable yet.
+        assert!(
+            datastore
+                .break_glass_account_disable(opctx, "tester".to_string())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // Enabling the account creates the user, and the user is allowed to
+        // authenticate.
+        let expires = Utc::now() + TimeDelta::hours(1);
+        let first = datastore
+            .break_glass_account_enable(
+                opctx,
+                password_hash(),
+                "tester".to_string(),
+                expires,
+            )
+            .await
+            .unwrap();
+        let silo_user_id = first.silo_user_id();
+        datastore.break_glass_account_check(opctx, silo_user_id).await.unwrap();
+
+        // Other users are unaffected.
+        datastore
+            .break_glass_account_check(opctx, SiloUserUuid::new_v4())
+            .await
+            .unwrap();
+
+        // Granting the user anything beyond read-only fleet access locks it
+        // out.
+        {
+            use nexus_db_schema::schema::role_assignment::dsl;
+            diesel::insert_into(dsl::role_assignment)
+                .values(RoleAssignment::new_for_silo_user(
+                    silo_user_id,
+                    ResourceType::Silo,
+                    DEFAULT_SILO_ID,
+                    "viewer",
+                ))
+                .execute_async(
+                    &*datastore.pool_connection_for_tests().await.unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+        let error = datastore
+            .break_glass_account_check(opctx, silo_user_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Unauthenticated { .. }), "{error:?}");
+
+        // Enabling the account again supersedes the first one, reuses the
+        // user, and strips the extra privileges.
+        let second = datastore
+            .break_glass_account_enable(
+                opctx,
+                password_hash(),
+                "tester".to_string(),
+                expires,
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.silo_user_id(), silo_user_id);
+        assert_ne!(second.id, first.id);
+        datastore.break_glass_account_check(opctx, silo_user_id).await.unwrap();
+
+        // Disabling the account locks the user out.
+        let disabled = datastore
+            .break_glass_account_disable(opctx, "tester".to_string())
+            .await
+            .unwrap()
+            .expect("account was enabled");
+        assert_eq!(disabled.id, second.id);
+        assert_eq!(disabled.disabled_by.as_deref(), Some("tester"));
+        let error = datastore
+            .break_glass_account_check(opctx, silo_user_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Unauthenticated { .. }), "{error:?}");
+
+        // So does letting it expire.
+        datastore
+            .break_glass_account_enable(
+                opctx,
+                password_hash(),
+                "tester".to_string(),
+                Utc::now() - TimeDelta::seconds(1),
+            )
+            .await
+            .unwrap();
+        let error = datastore
+            .break_glass_account_check(opctx, silo_user_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Unauthenticated { .. }), "{error:?}");
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
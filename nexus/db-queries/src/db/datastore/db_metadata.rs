@@ -1138,6 +1138,7 @@ mod test {
     use nexus_inventory::now_db_precision;
     use nexus_types::deployment::Blueprint;
     use nexus_types::deployment::BlueprintHostPhase2DesiredSlots;
+    use nexus_types::deployment::BlueprintScope;
     use nexus_types::deployment::BlueprintSledConfig;
     use nexus_types::deployment::BlueprintTarget;
     use nexus_types::deployment::BlueprintZoneConfig;
@@ -2055,6 +2056,7 @@ mod test {
         Blueprint {
             id: blueprint_id,
             sleds,
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             parent_blueprint_id: None,
             internal_dns_version: Generation::new(),
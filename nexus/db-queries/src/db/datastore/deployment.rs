@@ -58,6 +58,7 @@ use nexus_db_model::BpPendingMgsUpdateHostPhase1;
 use nexus_db_model::BpPendingMgsUpdateRot;
 use nexus_db_model::BpPendingMgsUpdateRotBootloader;
 use nexus_db_model::BpPendingMgsUpdateSp;
+use nexus_db_model::BpPlanningReport;
 use nexus_db_model::BpSledMetadata;
 use nexus_db_model::BpTarget;
 use nexus_db_model::DbArtifactVersion;
@@ -75,9 +76,11 @@ use nexus_db_schema::enums::HwM2SlotEnum;
 use nexus_db_schema::enums::HwRotSlotEnum;
 use nexus_db_schema::enums::SpTypeEnum;
 use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintExecutionDisabled;
 use nexus_types::deployment::BlueprintMetadata;
 use nexus_types::deployment::BlueprintSledConfig;
 use nexus_types::deployment::BlueprintTarget;
+use nexus_types::deployment::BlueprintTargetExecution;
 use nexus_types::deployment::ClickhouseClusterConfig;
 use nexus_types::deployment::CockroachDbPreserveDowngrade;
 use nexus_types::deployment::ExpectedVersion;
@@ -105,6 +108,7 @@ use omicron_uuid_kinds::OmicronZoneUuid;
 use omicron_uuid_kinds::SledUuid;
 use omicron_uuid_kinds::TypedUuid;
 use slog::Logger;
+use slog_error_chain::InlineErrorChain;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::sync::Arc;
@@ -345,6 +349,10 @@ impl DataStore {
             &blueprint.oximeter_read_mode,
         );
 
+        let planning_report =
+            BpPlanningReport::new(blueprint_id, &blueprint.report)
+                .map_err(|e| Error::internal_error(&format!("{:#}", e)))?;
+
         // This implementation inserts all records associated with the
         // blueprint in one transaction.  This is required: we don't want
         // any planner or executor to see a half-inserted blueprint, nor do we
@@ -496,6 +504,15 @@ impl DataStore {
                         .await?;
                 }
 
+                // Insert the planning report for this blueprint.
+                {
+                    use nexus_db_schema::schema::bp_planning_report::dsl;
+                    let _ = diesel::insert_into(dsl::bp_planning_report)
+                        .values(planning_report)
+                        .execute_async(&conn)
+                        .await?;
+                }
+
                 // Insert pending MGS updates for this blueprint.
                 for update in &blueprint.pending_mgs_updates {
                     insert_pending_mgs_update(
@@ -554,6 +571,7 @@ impl DataStore {
             time_created,
             creator,
             comment,
+            scope,
         ) = {
             use nexus_db_schema::schema::blueprint::dsl;
 
@@ -570,6 +588,7 @@ impl DataStore {
                 return Err(authz_blueprint.not_found());
             };
 
+            let scope = blueprint.scope();
             (
                 blueprint.parent_blueprint_id.map(From::from),
                 *blueprint.internal_dns_version,
@@ -581,6 +600,7 @@ impl DataStore {
                 blueprint.time_created,
                 blueprint.creator,
                 blueprint.comment,
+                scope,
             )
         };
         let cockroachdb_setting_preserve_downgrade =
@@ -1316,13 +1336,46 @@ impl DataStore {
             )?;
         }
 
-        // FIXME: Once reports are stored in the database, read them out here.
-        let report = PlanningReport::new(blueprint_id);
+        let report = {
+            use nexus_db_schema::schema::bp_planning_report::dsl;
+
+            let res = dsl::bp_planning_report
+                .filter(dsl::blueprint_id.eq(to_db_typed_uuid(blueprint_id)))
+                .select(BpPlanningReport::as_select())
+                .get_result_async(&*conn)
+                .await
+                .optional()
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?;
+
+            // Blueprints inserted before reports were stored don't have one.
+            // A stored report may also fail to parse if its format has changed
+            // since it was written.  Reports are informational only, so in
+            // either case, use an empty one rather than failing to read the
+            // blueprint.
+            match res {
+                None => PlanningReport::new(blueprint_id),
+                Some(row) => match serde_json::from_value(row.report) {
+                    Ok(report) => report,
+                    Err(error) => {
+                        warn!(
+                            opctx.log,
+                            "failed to parse stored planning report";
+                            "blueprint_id" => %blueprint_id,
+                            InlineErrorChain::new(&error),
+                        );
+                        PlanningReport::new(blueprint_id)
+                    }
+                },
+            }
+        };
 
         Ok(Blueprint {
             id: blueprint_id,
             pending_mgs_updates,
             sleds: sled_configs,
+            scope,
             parent_blueprint_id,
             internal_dns_version,
             external_dns_version,
@@ -1372,6 +1425,7 @@ impl DataStore {
             nclickhouse_keepers: usize,
             nclickhouse_servers: usize,
             noximeter_policy: usize,
+            nplanning_reports: usize,
             npending_mgs_updates_sp: usize,
             npending_mgs_updates_rot: usize,
             npending_mgs_updates_rot_bootloader: usize,
@@ -1389,6 +1443,7 @@ impl DataStore {
             nclickhouse_keepers,
             nclickhouse_servers,
             noximeter_policy,
+            nplanning_reports,
             npending_mgs_updates_sp,
             npending_mgs_updates_rot,
             npending_mgs_updates_rot_bootloader,
@@ -1575,6 +1630,18 @@ impl DataStore {
                         .await?
                     };
 
+                    let nplanning_reports = {
+                        use nexus_db_schema::schema::bp_planning_report::dsl;
+                        diesel::delete(
+                            dsl::bp_planning_report.filter(
+                                dsl::blueprint_id
+                                    .eq(to_db_typed_uuid(blueprint_id)),
+                            ),
+                        )
+                        .execute_async(&conn)
+                        .await?
+                    };
+
                     let npending_mgs_updates_sp = {
                         // Skip rustfmt because it bails out on this long line.
                         #[rustfmt::skip]
@@ -1646,6 +1713,7 @@ impl DataStore {
                         nclickhouse_keepers,
                         nclickhouse_servers,
                         noximeter_policy,
+                        nplanning_reports,
                         npending_mgs_updates_sp,
                         npending_mgs_updates_rot,
                         npending_mgs_updates_rot_bootloader,
@@ -1671,6 +1739,7 @@ impl DataStore {
             "nclickhouse_keepers" => nclickhouse_keepers,
             "nclickhouse_servers" => nclickhouse_servers,
             "noximeter_policy" => noximeter_policy,
+            "nplanning_reports" => nplanning_reports,
             "npending_mgs_updates_sp" => npending_mgs_updates_sp,
             "npending_mgs_updates_rot" => npending_mgs_updates_rot,
             "npending_mgs_updates_rot_bootloader" =>
@@ -1891,7 +1960,9 @@ impl DataStore {
         //        version + 1,
         //        blueprint_id,
         //        <target.enabled>,
-        //        <target.time_made_target>
+        //        <target.time_made_target>,
+        //        execution_disabled_reason,
+        //        time_execution_disabled
         //    FROM bp_target
         //    WHERE
         //        -- This part of the subquery restricts us to only the
@@ -1913,6 +1984,8 @@ impl DataStore {
                 dsl::blueprint_id,
                 target.enabled.into_sql::<sql_types::Bool>(),
                 target.time_made_target.into_sql::<sql_types::Timestamptz>(),
+                dsl::execution_disabled_reason,
+                dsl::time_execution_disabled,
             ))
             .filter(
                 dsl::version.eq_any(
@@ -1946,6 +2019,88 @@ impl DataStore {
         }
     }
 
+    /// Disable (if `disabled` is `Some(_)`) or re-enable (if `None`) execution
+    /// of the current target blueprint
+    ///
+    /// `target_id` must be the current target blueprint. As with
+    /// [`DataStore::blueprint_target_set_current_enabled`], this inserts a new
+    /// `bp_target` row that's a copy of the current one, except for the
+    /// execution state. That state is then carried over to subsequent targets
+    /// by [`DataStore::blueprint_target_set_current`].
+    pub async fn blueprint_target_set_current_execution(
+        &self,
+        opctx: &OpContext,
+        target_id: BlueprintUuid,
+        disabled: Option<BlueprintExecutionDisabled>,
+    ) -> Result<(), Error> {
+        use nexus_db_schema::schema::bp_target::dsl;
+
+        opctx
+            .authorize(authz::Action::Modify, &authz::BLUEPRINT_CONFIG)
+            .await?;
+
+        // See `blueprint_target_set_current_enabled()` for details on this
+        // query; it differs only in which columns are copied from the current
+        // target.
+        let bp_target2 =
+            diesel::alias!(nexus_db_schema::schema::bp_target as bp_target1);
+        let (reason, time_disabled) = match disabled {
+            Some(BlueprintExecutionDisabled { reason, time_disabled }) => {
+                (Some(reason), Some(time_disabled))
+            }
+            None => (None, None),
+        };
+        let query = dsl::bp_target
+            .select((
+                dsl::version + 1,
+                dsl::blueprint_id,
+                dsl::enabled,
+                dsl::time_made_target,
+                reason.into_sql::<sql_types::Nullable<sql_types::Text>>(),
+                time_disabled
+                    .into_sql::<sql_types::Nullable<sql_types::Timestamptz>>(),
+            ))
+            .filter(
+                dsl::version.eq_any(
+                    bp_target2
+                        .select(bp_target2.field(dsl::version))
+                        .order_by(bp_target2.field(dsl::version).desc())
+                        .limit(1),
+                ),
+            )
+            .filter(dsl::blueprint_id.eq(to_db_typed_uuid(target_id)))
+            .insert_into(dsl::bp_target);
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+
+        let num_inserted = query
+            .execute_async(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+
+        match num_inserted {
+            0 => Err(Error::invalid_request(format!(
+                "Blueprint {target_id} is not the current target blueprint",
+            ))),
+            1 => Ok(()),
+            _ => unreachable!("query inserted more than one row"),
+        }
+    }
+
+    /// Get whether execution of the current target blueprint is disabled
+    pub async fn blueprint_target_get_current_execution(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<BlueprintTargetExecution, Error> {
+        opctx.authorize(authz::Action::Read, &authz::BLUEPRINT_CONFIG).await?;
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let current_target = Self::blueprint_current_target_row(&conn).await?;
+        Ok(BlueprintTargetExecution {
+            target_id: current_target.blueprint_id.into(),
+            disabled: current_target.execution_disabled(),
+        })
+    }
+
     /// Get the current target blueprint, if one exists
     ///
     /// Returns both the metadata about the target and the full blueprint
@@ -1972,6 +2127,36 @@ impl DataStore {
         Ok((target, blueprint))
     }
 
+    /// Get the current target blueprint along with its metadata, without
+    /// reading the rest of the blueprint
+    pub async fn blueprint_target_get_current_metadata(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<(BlueprintTarget, BlueprintMetadata), Error> {
+        use nexus_db_schema::schema::blueprint::dsl;
+
+        opctx.authorize(authz::Action::Read, &authz::BLUEPRINT_CONFIG).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let target = Self::blueprint_current_target_only(&conn).await?;
+
+        // As in `blueprint_target_get_current_full()`, the target may have
+        // changed and the blueprint been deleted between these two queries, in
+        // which case this fails with a "not found" error.
+        let Some(blueprint) = dsl::blueprint
+            .filter(dsl::id.eq(to_db_typed_uuid(target.target_id)))
+            .select(DbBlueprint::as_select())
+            .get_result_async(&*conn)
+            .await
+            .optional()
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?
+        else {
+            return Err(authz_blueprint_from_id(target.target_id).not_found());
+        };
+
+        Ok((target, BlueprintMetadata::from(blueprint)))
+    }
+
     /// Get the current target blueprint, if one exists
     pub async fn blueprint_target_get_current_on_connection(
         conn: &async_bb8_diesel::Connection<DbConnection>,
@@ -1998,6 +2183,15 @@ impl DataStore {
     async fn blueprint_current_target_only(
         conn: &async_bb8_diesel::Connection<DbConnection>,
     ) -> Result<BlueprintTarget, TransactionError<Error>> {
+        Ok(Self::blueprint_current_target_row(conn).await?.into())
+    }
+
+    // Helper to fetch the current `bp_target` row.
+    //
+    // Caller is responsible for checking authz for this operation.
+    async fn blueprint_current_target_row(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+    ) -> Result<BpTarget, TransactionError<Error>> {
         use nexus_db_schema::schema::bp_target::dsl;
 
         let current_target = dsl::bp_target
@@ -2016,7 +2210,7 @@ impl DataStore {
                 internal_message: "no target blueprint set".to_string(),
             })?;
 
-        Ok(current_target.into())
+        Ok(current_target)
     }
 }
 
@@ -2650,7 +2844,9 @@ impl From<InsertTargetError> for Error {
 ///   current_target AS (
 ///     SELECT
 ///       "version" AS version,
-///       "blueprint_id" AS blueprint_id
+///       "blueprint_id" AS blueprint_id,
+///       "execution_disabled_reason" AS execution_disabled_reason,
+///       "time_execution_disabled" AS time_execution_disabled
 ///     FROM "bp_target"
 ///     ORDER BY "version" DESC
 ///     LIMIT 1
@@ -2734,15 +2930,20 @@ impl From<InsertTargetError> for Error {
 ///         AND "parent_blueprint_id" = current_target.blueprint_id
 ///   )
 ///
-///   -- Perform the actual insertion.
+///   -- Perform the actual insertion. Whether execution is disabled is carried
+///   -- over from the current target (if any): these subqueries return NULL if
+///   -- there is no current target.
 ///   INSERT INTO "bp_target"(
-///     "version","blueprint_id","enabled","time_made_target"
+///     "version","blueprint_id","enabled","time_made_target",
+///     "execution_disabled_reason","time_execution_disabled"
 ///   )
 ///   SELECT
 ///     new_target.new_version,
 ///     <new_target_id>,
 ///     <new_target_enabled>,
-///     <new_target_time_made_target>
+///     <new_target_time_made_target>,
+///     (SELECT execution_disabled_reason FROM current_target),
+///     (SELECT time_execution_disabled FROM current_target)
 ///     FROM new_target
 /// ```
 #[derive(Debug, Clone, Copy)]
@@ -2814,7 +3015,11 @@ impl QueryFragment<Pg> for InsertTargetQuery {
         out.push_identifier(dsl::version::NAME)?;
         out.push_sql(" AS version,");
         out.push_identifier(dsl::blueprint_id::NAME)?;
-        out.push_sql(" AS blueprint_id FROM ");
+        out.push_sql(" AS blueprint_id,");
+        out.push_identifier(dsl::execution_disabled_reason::NAME)?;
+        out.push_sql(" AS execution_disabled_reason,");
+        out.push_identifier(dsl::time_execution_disabled::NAME)?;
+        out.push_sql(" AS time_execution_disabled FROM ");
         BP_TARGET_FROM_CLAUSE.walk_ast(out.reborrow())?;
         out.push_sql(" ORDER BY ");
         out.push_identifier(dsl::version::NAME)?;
@@ -2932,6 +3137,10 @@ impl QueryFragment<Pg> for InsertTargetQuery {
         out.push_identifier(dsl::enabled::NAME)?;
         out.push_sql(",");
         out.push_identifier(dsl::time_made_target::NAME)?;
+        out.push_sql(",");
+        out.push_identifier(dsl::execution_disabled_reason::NAME)?;
+        out.push_sql(",");
+        out.push_identifier(dsl::time_execution_disabled::NAME)?;
         out.push_sql(") SELECT new_target.new_version, ");
         out.push_bind_param::<sql_types::Uuid, Uuid>(
             self.target_id.as_untyped_uuid(),
@@ -2942,7 +3151,11 @@ impl QueryFragment<Pg> for InsertTargetQuery {
         out.push_bind_param::<sql_types::Timestamptz, DateTime<Utc>>(
             &self.time_made_target,
         )?;
-        out.push_sql(" FROM new_target");
+        out.push_sql(
+            ", (SELECT execution_disabled_reason FROM current_target), \
+             (SELECT time_execution_disabled FROM current_target) \
+             FROM new_target",
+        );
 
         Ok(())
     }
@@ -2970,6 +3183,7 @@ mod tests {
     use nexus_types::deployment::BlueprintHostPhase2DesiredContents;
     use nexus_types::deployment::BlueprintHostPhase2DesiredSlots;
     use nexus_types::deployment::BlueprintPhysicalDiskDisposition;
+    use nexus_types::deployment::BlueprintScope;
     use nexus_types::deployment::BlueprintZoneConfig;
     use nexus_types::deployment::BlueprintZoneDisposition;
     use nexus_types::deployment::BlueprintZoneImageSource;
@@ -3081,6 +3295,7 @@ mod tests {
             query_count!(bp_clickhouse_keeper_zone_id_to_node_id, blueprint_id),
             query_count!(bp_clickhouse_server_zone_id_to_node_id, blueprint_id),
             query_count!(bp_oximeter_read_policy, blueprint_id),
+            query_count!(bp_planning_report, blueprint_id),
             query_count!(bp_pending_mgs_update_sp, blueprint_id),
             query_count!(bp_pending_mgs_update_rot, blueprint_id),
             query_count!(bp_pending_mgs_update_rot_bootloader, blueprint_id),
@@ -3259,6 +3474,38 @@ mod tests {
         logctx.cleanup_successful();
     }
 
+    #[tokio::test]
+    async fn test_scoped_blueprint() {
+        // Setup
+        let logctx = dev::test_setup_log("test_scoped_blueprint");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        // Create a blueprint scoped to one of its two sleds.
+        let sled_id = SledUuid::new_v4();
+        let mut blueprint1 = BlueprintBuilder::build_empty_with_sleds(
+            [sled_id, SledUuid::new_v4()].into_iter(),
+            "test",
+        );
+        blueprint1.scope = BlueprintScope::Sleds(BTreeSet::from([sled_id]));
+        let authz_blueprint = authz_blueprint_from_id(blueprint1.id);
+
+        // The scope survives a round trip through the database.
+        datastore
+            .blueprint_insert(&opctx, &blueprint1)
+            .await
+            .expect("failed to insert blueprint");
+        let blueprint_read = datastore
+            .blueprint_read(&opctx, &authz_blueprint)
+            .await
+            .expect("failed to read blueprint back");
+        assert_eq!(blueprint1, blueprint_read);
+
+        // Clean up.
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+
     #[tokio::test]
     async fn test_representative_blueprint() {
         const TEST_NAME: &str = "test_representative_blueprint";
@@ -3597,11 +3844,18 @@ mod tests {
             artifact_version: "2.0.0".parse().unwrap(),
         });
 
+        // Give the blueprint a non-empty planning report, to check that it's
+        // stored along with the rest of the blueprint.
+        let mut report = PlanningReport::new(builder.new_blueprint_id());
+        report.decommission.zombie_sleds.push(new_sled_id);
+        builder.set_report(report);
+
         let num_new_ntp_zones = 1;
         let num_new_crucible_zones = new_sled_zpools.len();
         let num_new_sled_zones = num_new_ntp_zones + num_new_crucible_zones;
 
         let blueprint2 = builder.build();
+        assert!(!blueprint2.report.is_empty());
         let authz_blueprint2 = authz_blueprint_from_id(blueprint2.id);
 
         let diff = blueprint2.diff_since_blueprint(&blueprint1);
@@ -4202,6 +4456,155 @@ mod tests {
         logctx.cleanup_successful();
     }
 
+    #[tokio::test]
+    async fn test_set_target_execution() {
+        // Setup
+        let logctx = dev::test_setup_log("test_set_target_execution");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        // Create an initial blueprint and a child, and insert both.
+        let collection = CollectionBuilder::new("test").build();
+        let blueprint1 = BlueprintBuilder::build_empty_with_sleds(
+            std::iter::empty(),
+            "test1",
+        );
+        let blueprint2 = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint1,
+            &EMPTY_PLANNING_INPUT,
+            &collection,
+            "test2",
+            PlannerRng::from_entropy(),
+        )
+        .expect("failed to create builder")
+        .build();
+        datastore.blueprint_insert(&opctx, &blueprint1).await.unwrap();
+        datastore.blueprint_insert(&opctx, &blueprint2).await.unwrap();
+
+        let bp1_target = BlueprintTarget {
+            target_id: blueprint1.id,
+            enabled: true,
+            time_made_target: now_db_precision(),
+        };
+        let bp2_target = BlueprintTarget {
+            target_id: blueprint2.id,
+            enabled: true,
+            time_made_target: now_db_precision(),
+        };
+        datastore
+            .blueprint_target_set_current(&opctx, bp1_target)
+            .await
+            .unwrap();
+
+        // Execution starts out enabled.
+        assert_eq!(
+            datastore
+                .blueprint_target_get_current_execution(&opctx)
+                .await
+                .unwrap(),
+            BlueprintTargetExecution {
+                target_id: blueprint1.id,
+                disabled: None
+            },
+        );
+
+        // Disable it.
+        let disabled = BlueprintExecutionDisabled {
+            reason: "investigating an incident".to_string(),
+            time_disabled: now_db_precision(),
+        };
+        datastore
+            .blueprint_target_set_current_execution(
+                &opctx,
+                blueprint1.id,
+                Some(disabled.clone()),
+            )
+            .await
+            .unwrap();
+        let expected = BlueprintTargetExecution {
+            target_id: blueprint1.id,
+            disabled: Some(disabled.clone()),
+        };
+        assert_eq!(
+            datastore
+                .blueprint_target_get_current_execution(&opctx)
+                .await
+                .unwrap(),
+            expected,
+        );
+
+        // Disabling execution doesn't change the rest of the target, and
+        // toggling `enabled` doesn't change whether execution is disabled.
+        assert_eq!(
+            datastore.blueprint_target_get_current(&opctx).await.unwrap(),
+            bp1_target,
+        );
+        let bp1_disabled_target =
+            BlueprintTarget { enabled: false, ..bp1_target };
+        datastore
+            .blueprint_target_set_current_enabled(&opctx, bp1_disabled_target)
+            .await
+            .unwrap();
+        assert_eq!(
+            datastore
+                .blueprint_target_get_current_execution(&opctx)
+                .await
+                .unwrap(),
+            expected,
+        );
+
+        // We can't change the execution state of a blueprint that isn't the
+        // current target.
+        let err = datastore
+            .blueprint_target_set_current_execution(&opctx, blueprint2.id, None)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("is not the current target blueprint")
+        );
+
+        // Making a new target carries the execution state over.
+        datastore
+            .blueprint_target_set_current(&opctx, bp2_target)
+            .await
+            .unwrap();
+        assert_eq!(
+            datastore
+                .blueprint_target_get_current_execution(&opctx)
+                .await
+                .unwrap(),
+            BlueprintTargetExecution {
+                target_id: blueprint2.id,
+                disabled: Some(disabled),
+            },
+        );
+
+        // Re-enable execution.
+        datastore
+            .blueprint_target_set_current_execution(&opctx, blueprint2.id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            datastore
+                .blueprint_target_get_current_execution(&opctx)
+                .await
+                .unwrap(),
+            BlueprintTargetExecution {
+                target_id: blueprint2.id,
+                disabled: None
+            },
+        );
+        assert_eq!(
+            datastore.blueprint_target_get_current(&opctx).await.unwrap(),
+            bp2_target,
+        );
+
+        // Clean up.
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+
     async fn create_blueprint_with_external_ip(
         datastore: &DataStore,
         opctx: &OpContext,
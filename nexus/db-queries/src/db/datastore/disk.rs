@@ -48,6 +48,7 @@ use omicron_common::api::external::http_pagination::PaginatedBy;
 use omicron_common::bail_unless;
 use omicron_uuid_kinds::VolumeUuid;
 use ref_cast::RefCast;
+use std::collections::BTreeMap;
 use std::net::SocketAddrV6;
 use uuid::Uuid;
 
@@ -338,10 +339,9 @@ impl DataStore {
                 .filter(instance::dsl::state
                         .eq_any(ok_to_detach_instance_states)
                         .and(instance::dsl::active_propolis_id.is_null())
-                        .and(
-                            instance::dsl::boot_disk_id.ne(authz_disk.id())
-                                .or(instance::dsl::boot_disk_id.is_null())
-                        )),
+                        .and(diesel::dsl::not(
+                            instance::dsl::boot_order.contains(vec![authz_disk.id()])
+                        ))),
             disk::table
                 .into_boxed()
                 .filter(disk::dsl::disk_state.eq_any(ok_to_detach_disk_state_labels)),
@@ -394,7 +394,7 @@ impl DataStore {
                                 // Ok-to-be-detached instance states:
                                 api::external::InstanceState::Creating |
                                 api::external::InstanceState::Stopped => {
-                                    if collection.boot_disk_id == Some(authz_disk.id()) {
+                                    if collection.boot_order.contains(&authz_disk.id()) {
                                         return Err(Error::conflict(
                                             "boot disk cannot be detached"
                                         ));
@@ -448,6 +448,55 @@ impl DataStore {
         Ok(disk)
     }
 
+    /// Forcibly detaches a disk from the instance with ID `instance_id`,
+    /// whatever the state of that instance.
+    ///
+    /// This is a recovery operation for disks whose instance record has been
+    /// deleted or corrupted, which [`Self::instance_detach_disk`] can't work
+    /// with. It is the caller's responsibility to make sure that no VMM still
+    /// has the disk open. The disk is only detached if it's still attached to
+    /// `instance_id`.
+    pub async fn disk_force_detach(
+        &self,
+        opctx: &OpContext,
+        authz_disk: &authz::Disk,
+        instance_id: Uuid,
+    ) -> UpdateResult<Disk> {
+        opctx.authorize(authz::Action::Modify, authz_disk).await?;
+
+        use nexus_db_schema::schema::disk::dsl;
+        let disk_id = authz_disk.id();
+        let detached_label = api::external::DiskState::Detached.label();
+        let result = diesel::update(dsl::disk)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(disk_id))
+            .filter(dsl::attach_instance_id.eq(instance_id))
+            .set((
+                dsl::disk_state.eq(detached_label),
+                dsl::attach_instance_id.eq(Option::<Uuid>::None),
+                dsl::slot.eq(Option::<i16>::None),
+                dsl::state_generation.eq(dsl::state_generation + 1),
+                dsl::time_state_updated.eq(Utc::now()),
+            ))
+            .check_if_exists::<Disk>(disk_id)
+            .execute_and_check(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_disk),
+                )
+            })?;
+
+        match result.status {
+            // `result.found` is the record from before the update.
+            UpdateStatus::Updated => self.disk_refetch(opctx, authz_disk).await,
+            UpdateStatus::NotUpdatedButExists => Err(Error::conflict(format!(
+                "disk is no longer attached to instance {instance_id}",
+            ))),
+        }
+    }
+
     pub async fn disk_update_runtime(
         &self,
         opctx: &OpContext,
@@ -520,6 +569,55 @@ impl DataStore {
         Ok(updated)
     }
 
+    /// Set or clear a disk's delete protection
+    ///
+    /// A disk that is still being created can't be protected, so that the
+    /// disk create saga can always delete it if it unwinds.
+    pub async fn disk_set_delete_protected(
+        &self,
+        opctx: &OpContext,
+        authz_disk: &authz::Disk,
+        delete_protected: bool,
+    ) -> UpdateResult<Disk> {
+        opctx.authorize(authz::Action::Modify, authz_disk).await?;
+
+        let disk_id = authz_disk.id();
+        let creating = api::external::DiskState::Creating.label();
+        use nexus_db_schema::schema::disk::dsl;
+        let result = diesel::update(dsl::disk)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(disk_id))
+            .filter(dsl::disk_state.ne(creating))
+            .set((
+                dsl::delete_protected.eq(delete_protected),
+                dsl::time_modified.eq(Utc::now()),
+            ))
+            .check_if_exists::<Disk>(disk_id)
+            .execute_and_check(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_disk),
+                )
+            })?;
+
+        match result.status {
+            UpdateStatus::Updated => Ok(result.found),
+            UpdateStatus::NotUpdatedButExists => {
+                let disk = result.found;
+                if disk.time_deleted().is_some() {
+                    Err(Error::not_found_by_id(ResourceType::Disk, &disk_id))
+                } else {
+                    Err(Error::invalid_request(format!(
+                        "disk cannot be modified in state \"{}\"",
+                        disk.runtime_state.disk_state
+                    )))
+                }
+            }
+        }
+    }
+
     pub async fn disk_clear_pantry(
         &self,
         opctx: &OpContext,
@@ -550,6 +648,54 @@ impl DataStore {
         Ok(updated)
     }
 
+    /// Returns the number of disks and snapshot exports attached to each
+    /// Crucible Pantry
+    ///
+    /// Pantries with nothing attached to them don't appear in the result.
+    pub async fn crucible_pantry_load(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<BTreeMap<SocketAddrV6, usize>, Error> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+        let conn = self.pool_connection_authorized(opctx).await?;
+
+        let disk_pantries = {
+            use nexus_db_schema::schema::disk::dsl;
+            dsl::disk
+                .filter(dsl::time_deleted.is_null())
+                .filter(dsl::pantry_address.is_not_null())
+                .select(dsl::pantry_address.assume_not_null())
+                .load_async::<String>(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?
+        };
+        let export_pantries = {
+            use nexus_db_schema::schema::snapshot_export::dsl;
+            dsl::snapshot_export
+                .filter(dsl::time_deleted.is_null())
+                .filter(dsl::pantry_address.is_not_null())
+                .select(dsl::pantry_address.assume_not_null())
+                .load_async::<String>(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?
+        };
+
+        let mut load = BTreeMap::new();
+        for address in disk_pantries.iter().chain(&export_pantries) {
+            let address: SocketAddrV6 = address.parse().map_err(|_| {
+                Error::internal_error(&format!(
+                    "invalid Crucible Pantry address: {address:?}"
+                ))
+            })?;
+            *load.entry(address).or_insert(0) += 1;
+        }
+        Ok(load)
+    }
+
     /// Fetches information about a Disk that the caller has previously fetched
     ///
     /// The only difference between this function and a new fetch by id is that
@@ -618,6 +764,7 @@ impl DataStore {
             .filter(dsl::id.eq(*disk_id))
             .filter(dsl::disk_state.eq_any(ok_to_delete_state_labels))
             .filter(dsl::attach_instance_id.is_null())
+            .filter(dsl::delete_protected.eq(false))
             .set((dsl::disk_state.eq(destroyed), dsl::time_deleted.eq(now)))
             .check_if_exists::<Disk>(*disk_id)
             .execute_and_check(&conn)
@@ -644,6 +791,11 @@ impl DataStore {
                     // To maintain idempotency, if the disk has already been
                     // destroyed, don't throw an error.
                     return Ok(disk);
+                } else if disk.delete_protected {
+                    return Err(Error::delete_protected(
+                        ResourceType::Disk,
+                        disk.name().as_str(),
+                    ));
                 } else if !ok_to_delete_states.contains(disk_state.state()) {
                     return Err(Error::invalid_request(format!(
                         "disk cannot be deleted in state \"{}\"",
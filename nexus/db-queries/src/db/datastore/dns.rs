@@ -202,13 +202,23 @@ impl DataStore {
         );
         assert_eq!(version.dns_group, dns_group);
 
-        self.dns_config_read_version(
-            opctx,
-            &log,
-            NonZeroU32::new(100).unwrap(),
-            &version,
-        )
-        .await
+        // Reading the whole config is expensive, so the result is cached and
+        // reused for as long as its version remains the latest.
+        self.caches
+            .dns_config
+            .get_or_load_if(
+                dns_group,
+                |config| config.generation == version.version.0,
+                || {
+                    self.dns_config_read_version(
+                        opctx,
+                        &log,
+                        NonZeroU32::new(100).unwrap(),
+                        &version,
+                    )
+                },
+            )
+            .await
     }
 
     /// Private helper for reading a specific version of a group's DNS config
@@ -28,6 +28,7 @@ use crate::db::queries::external_ip::SAFE_TO_ATTACH_INSTANCE_STATES_CREATING;
 use crate::db::update_and_check::UpdateAndCheck;
 use crate::db::update_and_check::UpdateStatus;
 use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
 use chrono::Utc;
 use diesel::prelude::*;
 use nexus_db_errors::ErrorHandler;
@@ -50,6 +51,7 @@ use omicron_common::api::external::Error;
 use omicron_common::api::external::IdentityMetadataCreateParams;
 use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::LookupType;
 use omicron_common::api::external::ResourceType;
 use omicron_common::api::external::UpdateResult;
 use omicron_common::api::external::http_pagination::PaginatedBy;
@@ -87,7 +89,8 @@ impl DataStore {
         probe_id: Uuid,
         pool: Option<authz::IpPool>,
     ) -> CreateResult<ExternalIp> {
-        let authz_pool = self.resolve_pool_for_allocation(opctx, pool).await?;
+        let authz_pool =
+            self.resolve_pool_for_allocation(opctx, pool, None).await?;
         let data = IncompleteExternalIp::for_ephemeral_probe(
             ip_id,
             probe_id,
@@ -123,7 +126,16 @@ impl DataStore {
         // Naturally, we now *need* to destroy the ephemeral IP if the newly alloc'd
         // IP was not attached, including on idempotent success.
 
-        let authz_pool = self.resolve_pool_for_allocation(opctx, pool).await?;
+        // A pool that's linked only to the instance's project (rather than
+        // its silo) can be used too, so we need to know which project that is.
+        let project_id = match pool {
+            Some(_) => {
+                Some(self.instance_project_id(opctx, instance_id).await?)
+            }
+            None => None,
+        };
+        let authz_pool =
+            self.resolve_pool_for_allocation(opctx, pool, project_id).await?;
         let data = IncompleteExternalIp::for_ephemeral(ip_id, authz_pool.id());
 
         // We might not be able to acquire a new IP, but in the event of an
@@ -186,19 +198,58 @@ impl DataStore {
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
     }
 
-    /// If a pool is specified, make sure it's linked to this silo. If a pool is
-    /// not specified, fetch the default pool for this silo. Once the pool is
-    /// resolved (by either method) do an auth check. Then return the pool.
+    /// Return the ID of the project containing the given instance.
+    async fn instance_project_id(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+    ) -> LookupResult<Uuid> {
+        use nexus_db_schema::schema::instance::dsl;
+        dsl::instance
+            .filter(dsl::id.eq(instance_id.into_untyped_uuid()))
+            .filter(dsl::time_deleted.is_null())
+            .select(dsl::project_id)
+            .first_async::<Uuid>(
+                &*self.pool_connection_authorized(opctx).await?,
+            )
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByLookup(
+                        ResourceType::Instance,
+                        LookupType::ById(instance_id.into_untyped_uuid()),
+                    ),
+                )
+            })
+    }
+
+    /// If a pool is specified, make sure it's linked to this silo (or to
+    /// `project_id`, if given). If a pool is not specified, fetch the default
+    /// pool for this silo. Once the pool is resolved (by either method) do an
+    /// auth check. Then return the pool.
     async fn resolve_pool_for_allocation(
         &self,
         opctx: &OpContext,
         pool: Option<authz::IpPool>,
+        project_id: Option<Uuid>,
     ) -> LookupResult<authz::IpPool> {
         let authz_pool = match pool {
             Some(authz_pool) => {
-                self.ip_pool_fetch_link(opctx, authz_pool.id())
-                    .await
-                    .map_err(|_| authz_pool.not_found())?;
+                match project_id {
+                    Some(project_id) => {
+                        self.ip_pool_fetch_link_for_project(
+                            opctx,
+                            authz_pool.id(),
+                            project_id,
+                        )
+                        .await
+                    }
+                    None => {
+                        self.ip_pool_fetch_link(opctx, authz_pool.id()).await
+                    }
+                }
+                .map_err(|_| authz_pool.not_found())?;
 
                 authz_pool
             }
@@ -224,7 +275,9 @@ impl DataStore {
     ) -> CreateResult<ExternalIp> {
         let ip_id = Uuid::new_v4();
 
-        let authz_pool = self.resolve_pool_for_allocation(opctx, pool).await?;
+        let authz_pool = self
+            .resolve_pool_for_allocation(opctx, pool, Some(project_id))
+            .await?;
 
         let data = if let Some(ip) = ip {
             IncompleteExternalIp::for_floating_explicit(
@@ -795,6 +848,7 @@ impl DataStore {
                 dsl::time_modified.eq(Utc::now()),
                 dsl::parent_id.eq(Option::<Uuid>::None),
                 dsl::state.eq(IpAttachState::Detached),
+                dsl::time_attached.eq(Option::<DateTime<Utc>>::None),
             ))
             .execute_async(&*self.pool_connection_authorized(opctx).await?)
             .await
@@ -803,6 +857,9 @@ impl DataStore {
 
     /// Fetch all external IP addresses of any kind for the provided instance
     /// in all attachment states.
+    ///
+    /// Addresses are returned in the order in which they finished attaching,
+    /// after any that are still attaching.
     pub async fn instance_lookup_external_ips(
         &self,
         opctx: &OpContext,
@@ -814,6 +871,7 @@ impl DataStore {
             .filter(dsl::is_probe.eq(false))
             .filter(dsl::parent_id.eq(instance_id.into_untyped_uuid()))
             .filter(dsl::time_deleted.is_null())
+            .order_by((dsl::time_attached.asc(), dsl::id.asc()))
             .select(ExternalIp::as_select())
             .get_results_async(&*self.pool_connection_authorized(opctx).await?)
             .await
@@ -1081,6 +1139,7 @@ impl DataStore {
                         dsl::time_modified.eq(now),
                         dsl::time_deleted.eq(now),
                         dsl::state.eq(target_state),
+                        dsl::time_attached.eq(Option::<DateTime<Utc>>::None),
                     ))
                     .execute_async(&*conn)
                     .await
@@ -1092,6 +1151,7 @@ impl DataStore {
                         dsl::parent_id.eq(Option::<Uuid>::None),
                         dsl::time_modified.eq(now),
                         dsl::state.eq(target_state),
+                        dsl::time_attached.eq(Option::<DateTime<Utc>>::None),
                     ))
                     .execute_async(&*conn)
                     .await
@@ -1104,8 +1164,9 @@ impl DataStore {
             (_, IpAttachState::Attaching, IpAttachState::Attached) => {
                 return part_out
                     .set((
-                        dsl::time_modified.eq(Utc::now()),
+                        dsl::time_modified.eq(now),
                         dsl::state.eq(target_state),
+                        dsl::time_attached.eq(Some(now)),
                     ))
                     .check_if_exists::<ExternalIp>(ip_id)
                     .execute_and_check(
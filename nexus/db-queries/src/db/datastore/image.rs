@@ -3,12 +3,15 @@ use crate::authz::ApiResource;
 use crate::context::OpContext;
 use crate::db::collection_insert::AsyncInsertError;
 use crate::db::collection_insert::DatastoreCollection;
+use crate::db::model::ByteCount;
 use crate::db::model::Image;
+use crate::db::model::ImageState;
 use crate::db::model::Project;
 use crate::db::model::ProjectImage;
 use crate::db::model::Silo;
 use crate::db::model::SiloImage;
 use crate::db::pagination::paginated;
+use crate::db::pagination::paginated_multicolumn;
 use async_bb8_diesel::AsyncRunQueryDsl;
 use chrono::Utc;
 use diesel::prelude::*;
@@ -16,78 +19,160 @@ use nexus_db_errors::ErrorHandler;
 use nexus_db_errors::public_error_from_diesel;
 use nexus_db_model::Name;
 use nexus_types::identity::Resource;
+use omicron_common::api::external;
 use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
 use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::ResourceType;
 use omicron_common::api::external::UpdateResult;
-use omicron_common::api::external::http_pagination::PaginatedBy;
+use omicron_common::api::external::http_pagination::ImagePaginatedBy;
 use ref_cast::RefCast;
 use uuid::Uuid;
 
 use super::DataStore;
 
+/// Criteria for narrowing down a listing of images
+#[derive(Clone, Debug, Default)]
+pub struct ImageFilters {
+    /// If present, include only images of this operating system.
+    pub os: Option<String>,
+    /// If present, include only images of this version of the operating
+    /// system.
+    pub version: Option<String>,
+    /// If present, include only images that are at least this large.
+    pub min_size: Option<external::ByteCount>,
+}
+
+/// Converts the marker of a listing by size into its database representation
+fn size_marker(
+    pagparams: &DataPageParams<'_, (external::ByteCount, Uuid)>,
+) -> Option<(ByteCount, Uuid)> {
+    pagparams.marker.map(|(size, id)| (ByteCount::from(*size), *id))
+}
+
 impl DataStore {
     pub async fn project_image_list(
         &self,
         opctx: &OpContext,
         authz_project: &authz::Project,
-        pagparams: &PaginatedBy<'_>,
+        filters: &ImageFilters,
+        pagparams: &ImagePaginatedBy<'_>,
     ) -> ListResultVec<Image> {
         opctx.authorize(authz::Action::ListChildren, authz_project).await?;
 
         use nexus_db_schema::schema::project_image::dsl as project_dsl;
-        match pagparams {
-            PaginatedBy::Id(pagparams) => paginated(
+        let mut query = match pagparams {
+            ImagePaginatedBy::Id(pagparams) => paginated(
                 project_dsl::project_image,
                 project_dsl::id,
                 &pagparams,
             ),
-            PaginatedBy::Name(pagparams) => paginated(
+            ImagePaginatedBy::Name(pagparams) => paginated(
                 project_dsl::project_image,
                 project_dsl::name,
                 &pagparams.map_name(|n| Name::ref_cast(n)),
             ),
+            ImagePaginatedBy::Created(pagparams) => paginated_multicolumn(
+                project_dsl::project_image,
+                (project_dsl::time_created, project_dsl::id),
+                pagparams,
+            ),
+            ImagePaginatedBy::Size(pagparams) => {
+                let marker = size_marker(pagparams);
+                paginated_multicolumn(
+                    project_dsl::project_image,
+                    (project_dsl::size_bytes, project_dsl::id),
+                    &DataPageParams {
+                        marker: marker.as_ref(),
+                        direction: pagparams.direction,
+                        limit: pagparams.limit,
+                    },
+                )
+            }
         }
         .filter(project_dsl::time_deleted.is_null())
-        .filter(project_dsl::project_id.eq(authz_project.id()))
-        .select(ProjectImage::as_select())
-        .load_async::<ProjectImage>(
-            &*self.pool_connection_authorized(opctx).await?,
-        )
-        .await
-        .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
-        .map(|v| v.into_iter().map(|v| v.into()).collect())
+        .filter(project_dsl::project_id.eq(authz_project.id()));
+
+        if let Some(os) = &filters.os {
+            query = query.filter(project_dsl::os.eq(os.clone()));
+        }
+        if let Some(version) = &filters.version {
+            query = query.filter(project_dsl::version.eq(version.clone()));
+        }
+        if let Some(min_size) = filters.min_size {
+            query = query
+                .filter(project_dsl::size_bytes.ge(ByteCount::from(min_size)));
+        }
+
+        query
+            .select(ProjectImage::as_select())
+            .load_async::<ProjectImage>(
+                &*self.pool_connection_authorized(opctx).await?,
+            )
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+            .map(|v| v.into_iter().map(|v| v.into()).collect())
     }
 
     pub async fn silo_image_list(
         &self,
         opctx: &OpContext,
         authz_silo: &authz::Silo,
-        pagparams: &PaginatedBy<'_>,
+        filters: &ImageFilters,
+        pagparams: &ImagePaginatedBy<'_>,
     ) -> ListResultVec<Image> {
         opctx.authorize(authz::Action::ListChildren, authz_silo).await?;
 
         use nexus_db_schema::schema::silo_image::dsl;
-        match pagparams {
-            PaginatedBy::Id(pagparams) => {
+        let mut query = match pagparams {
+            ImagePaginatedBy::Id(pagparams) => {
                 paginated(dsl::silo_image, dsl::id, &pagparams)
             }
-            PaginatedBy::Name(pagparams) => paginated(
+            ImagePaginatedBy::Name(pagparams) => paginated(
                 dsl::silo_image,
                 dsl::name,
                 &pagparams.map_name(|n| Name::ref_cast(n)),
             ),
+            ImagePaginatedBy::Created(pagparams) => paginated_multicolumn(
+                dsl::silo_image,
+                (dsl::time_created, dsl::id),
+                pagparams,
+            ),
+            ImagePaginatedBy::Size(pagparams) => {
+                let marker = size_marker(pagparams);
+                paginated_multicolumn(
+                    dsl::silo_image,
+                    (dsl::size_bytes, dsl::id),
+                    &DataPageParams {
+                        marker: marker.as_ref(),
+                        direction: pagparams.direction,
+                        limit: pagparams.limit,
+                    },
+                )
+            }
         }
         .filter(dsl::time_deleted.is_null())
-        .filter(dsl::silo_id.eq(authz_silo.id()))
-        .select(SiloImage::as_select())
-        .load_async::<SiloImage>(
-            &*self.pool_connection_authorized(opctx).await?,
-        )
-        .await
-        .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
-        .map(|v| v.into_iter().map(|v| v.into()).collect())
+        .filter(dsl::silo_id.eq(authz_silo.id()));
+
+        if let Some(os) = &filters.os {
+            query = query.filter(dsl::os.eq(os.clone()));
+        }
+        if let Some(version) = &filters.version {
+            query = query.filter(dsl::version.eq(version.clone()));
+        }
+        if let Some(min_size) = filters.min_size {
+            query = query.filter(dsl::size_bytes.ge(ByteCount::from(min_size)));
+        }
+
+        query
+            .select(SiloImage::as_select())
+            .load_async::<SiloImage>(
+                &*self.pool_connection_authorized(opctx).await?,
+            )
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+            .map(|v| v.into_iter().map(|v| v.into()).collect())
     }
 
     pub async fn silo_image_create(
@@ -253,6 +338,49 @@ impl DataStore {
         self.image_delete(opctx, image.into()).await
     }
 
+    pub async fn silo_image_set_state(
+        &self,
+        opctx: &OpContext,
+        authz_image: &authz::SiloImage,
+        state: ImageState,
+    ) -> UpdateResult<Image> {
+        opctx.authorize(authz::Action::Modify, authz_image).await?;
+        self.image_set_state(opctx, authz_image, authz_image.id(), state).await
+    }
+
+    pub async fn project_image_set_state(
+        &self,
+        opctx: &OpContext,
+        authz_image: &authz::ProjectImage,
+        state: ImageState,
+    ) -> UpdateResult<Image> {
+        opctx.authorize(authz::Action::Modify, authz_image).await?;
+        self.image_set_state(opctx, authz_image, authz_image.id(), state).await
+    }
+
+    async fn image_set_state(
+        &self,
+        opctx: &OpContext,
+        authz_image: &dyn authz::ApiResource,
+        image_id: Uuid,
+        state: ImageState,
+    ) -> UpdateResult<Image> {
+        use nexus_db_schema::schema::image::dsl;
+        diesel::update(dsl::image)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(image_id))
+            .set((dsl::state.eq(state), dsl::time_modified.eq(Utc::now())))
+            .returning(Image::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_image),
+                )
+            })
+    }
+
     async fn image_delete(
         &self,
         opctx: &OpContext,
@@ -29,6 +29,8 @@ use crate::db::model::Migration;
 use crate::db::model::MigrationState;
 use crate::db::model::Name;
 use crate::db::model::Project;
+use crate::db::model::ProjectAutoRestartDefaults;
+use crate::db::model::Reincarnatability;
 use crate::db::model::Sled;
 use crate::db::model::Vmm;
 use crate::db::model::VmmState;
@@ -38,6 +40,7 @@ use crate::db::update_and_check::UpdateAndCheck;
 use crate::db::update_and_check::UpdateAndQueryResult;
 use crate::db::update_and_check::UpdateStatus;
 use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
 use chrono::Utc;
 use diesel::prelude::*;
 use nexus_db_errors::ErrorHandler;
@@ -66,6 +69,7 @@ use omicron_uuid_kinds::InstanceUuid;
 use omicron_uuid_kinds::PropolisUuid;
 use omicron_uuid_kinds::SledUuid;
 use ref_cast::RefCast;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 /// Returns the operator-visible [external API
@@ -177,6 +181,8 @@ impl<'s> From<&'s InstanceAndActiveVmm> for InstanceStateComputer<'s> {
 pub struct InstanceAndActiveVmm {
     pub instance: Instance,
     pub vmm: Option<Vmm>,
+    /// The auto-restart defaults of the project the instance belongs to.
+    pub project_auto_restart: ProjectAutoRestartDefaults,
 }
 
 impl InstanceAndActiveVmm {
@@ -200,9 +206,16 @@ impl InstanceAndActiveVmm {
     }
 }
 
+/// Pairs an instance with its active VMM, assuming that its project has no
+/// auto-restart defaults. Callers that report on the instance's auto-restart
+/// status should fetch the instance's project defaults instead.
 impl From<(Instance, Option<Vmm>)> for InstanceAndActiveVmm {
     fn from(value: (Instance, Option<Vmm>)) -> Self {
-        Self { instance: value.0, vmm: value.1 }
+        Self {
+            instance: value.0,
+            vmm: value.1,
+            project_auto_restart: ProjectAutoRestartDefaults::default(),
+        }
     }
 }
 
@@ -214,43 +227,60 @@ impl From<InstanceAndActiveVmm> for external::Instance {
             .map(|vmm| vmm.runtime.time_state_updated)
             .unwrap_or(value.instance.runtime_state.time_updated);
         let auto_restart_status = {
-            let cooldown_expiration =
-                value.instance.runtime_state.time_last_auto_restarted.map(
-                    |t| {
-                        // The instance may or may not explicitly override the cooldown and
-                        // auto-restart policy settings. If it does not, return whatever
-                        // default values Nexus is currently using, so that they can be
-                        // displayed in the UI.
-                        //
-                        // Eventually, these fields may have project-level defaults, so if the
-                        // instance doesn't provide a value we'll have to use the
-                        // project's default if one exists. For now, though, fall back
-                        // to the hard- coded default if the instance hasn't overridden
-                        // it.
-                        let cooldown_duration =
-                            value.instance.auto_restart.cooldown.unwrap_or(
-                                InstanceAutoRestart::DEFAULT_COOLDOWN,
-                            );
-                        t + cooldown_duration
-                    },
-                );
+            // The instance may or may not explicitly override the cooldown
+            // setting. If it does not, use its project's default, or else
+            // whatever default Nexus is currently using, so that it can be
+            // displayed in the UI.
+            let cooldown_duration = value
+                .instance
+                .auto_restart
+                .effective_cooldown(&value.project_auto_restart);
+            let cooldown_expiration = value
+                .instance
+                .runtime_state
+                .time_last_auto_restarted
+                .map(|t| t + cooldown_duration);
 
             let policy = value.instance.auto_restart.policy;
             // The active policy for this instance --- either its configured
-            // policy or the default. We report the configured policy as the
-            // instance's policy, but we must use this to determine whether it
-            // will be auto-restarted, since it may have no configured policy.
-            let active_policy =
-                policy.unwrap_or(InstanceAutoRestart::DEFAULT_POLICY);
+            // policy, its project's default, or the fleet-wide default. We
+            // report the configured policy as the instance's policy, but we
+            // must use this to determine whether it will be auto-restarted,
+            // since it may have no configured policy.
+            let active_policy = value
+                .instance
+                .auto_restart
+                .effective_policy(&value.project_auto_restart);
 
             let enabled = match active_policy {
                 InstanceAutoRestartPolicy::Never => false,
                 InstanceAutoRestartPolicy::BestEffort => true,
             };
+            let state = match value.instance.auto_restart.can_reincarnate(
+                &value.project_auto_restart,
+                &value.instance.runtime_state,
+                Utc::now(),
+            ) {
+                Reincarnatability::WillReincarnate => {
+                    external::InstanceAutoRestartState::Ready
+                }
+                Reincarnatability::CoolingDown(_) => {
+                    external::InstanceAutoRestartState::CoolingDown
+                }
+                Reincarnatability::Nirvana => {
+                    external::InstanceAutoRestartState::Disabled
+                }
+            };
             external::InstanceAutoRestartStatus {
                 enabled,
                 policy: policy.map(Into::into),
                 cooldown_expiration,
+                cooldown_secs: u32::try_from(cooldown_duration.num_seconds())
+                    .unwrap_or(u32::MAX),
+                state,
+                // Auto-restart events are stored separately from the instance
+                // record, so callers that need the count must fill it in.
+                count: 0,
             }
         };
 
@@ -264,7 +294,10 @@ impl From<InstanceAndActiveVmm> for external::Instance {
                 .hostname
                 .parse()
                 .expect("found invalid hostname in the database"),
-            boot_disk_id: value.instance.boot_disk_id,
+            boot_disk_id: value.instance.boot_order.first().copied(),
+            // Tags are stored separately from the instance record, so callers
+            // that need them must fill them in.
+            tags: BTreeMap::new(),
             runtime: external::InstanceRuntimeState {
                 run_state: value.effective_state(),
                 time_run_state_updated,
@@ -275,6 +308,10 @@ impl From<InstanceAndActiveVmm> for external::Instance {
             },
 
             auto_restart_status,
+            stopped_reservation_policy: value
+                .instance
+                .stopped_reservation_policy
+                .into(),
         }
     }
 }
@@ -398,13 +435,16 @@ impl DataStore {
         &self,
         opctx: &OpContext,
         authz_project: &authz::Project,
+        tag: Option<(&str, &str)>,
         pagparams: &PaginatedBy<'_>,
     ) -> ListResultVec<InstanceAndActiveVmm> {
         opctx.authorize(authz::Action::ListChildren, authz_project).await?;
 
         use nexus_db_schema::schema::instance::dsl;
+        use nexus_db_schema::schema::instance_tag::dsl as tag_dsl;
+        use nexus_db_schema::schema::project::dsl as project_dsl;
         use nexus_db_schema::schema::vmm::dsl as vmm_dsl;
-        Ok(match pagparams {
+        let mut query = match pagparams {
             PaginatedBy::Id(pagparams) => {
                 paginated(dsl::instance, dsl::id, &pagparams)
             }
@@ -415,22 +455,42 @@ impl DataStore {
             ),
         }
         .filter(dsl::project_id.eq(authz_project.id()))
-        .filter(dsl::time_deleted.is_null())
-        .left_join(
-            vmm_dsl::vmm.on(vmm_dsl::id
-                .nullable()
-                .eq(dsl::active_propolis_id)
-                .and(vmm_dsl::time_deleted.is_null())),
-        )
-        .select((Instance::as_select(), Option::<Vmm>::as_select()))
-        .load_async::<(Instance, Option<Vmm>)>(
-            &*self.pool_connection_authorized(opctx).await?,
-        )
-        .await
-        .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?
-        .into_iter()
-        .map(|(instance, vmm)| InstanceAndActiveVmm { instance, vmm })
-        .collect())
+        .filter(dsl::time_deleted.is_null());
+        if let Some((key, value)) = tag {
+            query = query.filter(
+                dsl::id.eq_any(
+                    tag_dsl::instance_tag
+                        .filter(tag_dsl::key.eq(key.to_string()))
+                        .filter(tag_dsl::value.eq(value.to_string()))
+                        .select(tag_dsl::instance_id),
+                ),
+            );
+        }
+        Ok(query
+            .inner_join(project_dsl::project)
+            .left_join(
+                vmm_dsl::vmm.on(vmm_dsl::id
+                    .nullable()
+                    .eq(dsl::active_propolis_id)
+                    .and(vmm_dsl::time_deleted.is_null())),
+            )
+            .select((
+                Instance::as_select(),
+                Option::<Vmm>::as_select(),
+                ProjectAutoRestartDefaults::as_select(),
+            ))
+            .load_async::<(Instance, Option<Vmm>, ProjectAutoRestartDefaults)>(
+                &*self.pool_connection_authorized(opctx).await?,
+            )
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?
+            .into_iter()
+            .map(|(instance, vmm, project_auto_restart)| InstanceAndActiveVmm {
+                instance,
+                vmm,
+                project_auto_restart,
+            })
+            .collect())
     }
 
     /// List all instances with active VMMs in the provided [`VmmState`] which
@@ -503,10 +563,13 @@ impl DataStore {
 
     /// List all instances in the [`Failed`](InstanceState::Failed) state with an
     /// auto-restart policy that permits them to be automatically restarted by
-    /// the control plane.
+    /// the control plane at `now`.
     ///
     /// This is used by the `instance_reincarnation` RPW to ensure that that any
-    /// such instances are restarted.
+    /// such instances are restarted. Instances in any of the projects in
+    /// `skip_projects` are left out, so that the RPW can stop looking at a
+    /// project once it has restarted as many of that project's instances as it
+    /// is willing to in one pass.
     ///
     /// This query returns `n` randomly-ordered instances which are eligible for
     /// reincarnation. Because reincarnating an instance changes its state so
@@ -520,14 +583,21 @@ impl DataStore {
         &self,
         opctx: &OpContext,
         reason: ReincarnationReason,
+        now: DateTime<Utc>,
+        skip_projects: &[Uuid],
         pagparams: &DataPageParams<'_, Uuid>,
     ) -> ListResultVec<Instance> {
         use nexus_db_schema::schema::instance::dsl;
+        use nexus_db_schema::schema::project::dsl as project_dsl;
         use nexus_db_schema::schema::vmm::dsl as vmm_dsl;
 
         let q = paginated(dsl::instance, dsl::id, &pagparams)
+            // Join each instance with its project, whose auto-restart
+            // defaults apply if the instance doesn't override them.
+            .inner_join(project_dsl::project)
             // Select only those instances which may be reincarnated.
-            .filter(InstanceAutoRestart::filter_reincarnatable());
+            .filter(InstanceAutoRestart::filter_reincarnatable(now))
+            .filter(dsl::project_id.ne_all(skip_projects.to_vec()));
 
         match reason {
             ReincarnationReason::Failed => {
@@ -613,22 +683,32 @@ impl DataStore {
         authz_instance: &authz::Instance,
     ) -> Result<InstanceAndActiveVmm, diesel::result::Error> {
         use nexus_db_schema::schema::instance::dsl as instance_dsl;
+        use nexus_db_schema::schema::project::dsl as project_dsl;
         use nexus_db_schema::schema::vmm::dsl as vmm_dsl;
 
-        let (instance, vmm) = instance_dsl::instance
+        let (instance, vmm, project_auto_restart) = instance_dsl::instance
             .filter(instance_dsl::id.eq(authz_instance.id()))
             .filter(instance_dsl::time_deleted.is_null())
+            .inner_join(project_dsl::project)
             .left_join(
                 vmm_dsl::vmm.on(vmm_dsl::id
                     .nullable()
                     .eq(instance_dsl::active_propolis_id)
                     .and(vmm_dsl::time_deleted.is_null())),
             )
-            .select((Instance::as_select(), Option::<Vmm>::as_select()))
-            .get_result_async::<(Instance, Option<Vmm>)>(conn)
+            .select((
+                Instance::as_select(),
+                Option::<Vmm>::as_select(),
+                ProjectAutoRestartDefaults::as_select(),
+            ))
+            .get_result_async::<(
+                Instance,
+                Option<Vmm>,
+                ProjectAutoRestartDefaults,
+            )>(conn)
             .await?;
 
-        Ok(InstanceAndActiveVmm { instance, vmm })
+        Ok(InstanceAndActiveVmm { instance, vmm, project_auto_restart })
     }
 
     /// Fetches all database records describing the state of the provided
@@ -1075,6 +1155,45 @@ impl DataStore {
         Ok(result)
     }
 
+    /// Lists the VMMs that exist on `sled_id`, along with the instance each
+    /// belongs to and the auto-restart defaults of that instance's project.
+    ///
+    /// This includes both instances' active VMMs and the targets of
+    /// migrations to the sled that are still in progress.
+    pub async fn vmm_and_instance_list_on_sled(
+        &self,
+        opctx: &OpContext,
+        sled_id: SledUuid,
+    ) -> ListResultVec<(Vmm, Instance, ProjectAutoRestartDefaults)> {
+        use nexus_db_schema::schema::{
+            instance::dsl as instance_dsl, project::dsl as project_dsl,
+            vmm::dsl as vmm_dsl,
+        };
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        vmm_dsl::vmm
+            .filter(vmm_dsl::sled_id.eq(sled_id.into_untyped_uuid()))
+            .filter(vmm_dsl::time_deleted.is_null())
+            .filter(vmm_dsl::state.ne_all(VmmState::NONEXISTENT_STATES))
+            .inner_join(
+                instance_dsl::instance
+                    .on(instance_dsl::id.eq(vmm_dsl::instance_id)),
+            )
+            .inner_join(
+                project_dsl::project
+                    .on(project_dsl::id.eq(instance_dsl::project_id)),
+            )
+            .order_by(vmm_dsl::id)
+            .select((
+                Vmm::as_select(),
+                Instance::as_select(),
+                ProjectAutoRestartDefaults::as_select(),
+            ))
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
     pub async fn instance_reconfigure(
         &self,
         opctx: &OpContext,
@@ -1092,19 +1211,22 @@ impl DataStore {
             .transaction(&conn, |conn| {
                 let err = err.clone();
                 let InstanceUpdate {
-                    boot_disk_id,
+                    boot_order,
                     auto_restart_policy,
                     ncpus,
                     memory,
+                    stopped_reservation_policy,
                 } = update.clone();
                 async move {
-                    // Set the auto-restart policy.
+                    // Set the auto-restart and stopped-reservation policies.
                     diesel::update(instance_dsl::instance)
                         .filter(instance_dsl::id.eq(authz_instance.id()))
-                        .set(
+                        .set((
                             instance_dsl::auto_restart_policy
                                 .eq(auto_restart_policy),
-                        )
+                            instance_dsl::stopped_reservation_policy
+                                .eq(stopped_reservation_policy),
+                        ))
                         .execute_async(&conn)
                         .await?;
 
@@ -1118,12 +1240,12 @@ impl DataStore {
                     )
                     .await?;
 
-                    // Next, set the boot disk if needed.
-                    self.instance_set_boot_disk_on_conn(
+                    // Next, set the boot order if needed.
+                    self.instance_set_boot_order_on_conn(
                         &conn,
                         &err,
                         &authz_instance,
-                        boot_disk_id,
+                        boot_order,
                     )
                     .await?;
 
@@ -1144,27 +1266,28 @@ impl DataStore {
         Ok(instance_and_vmm)
     }
 
-    /// Set the boot disk on an instance, bypassing the rest of an instance
+    /// Set the boot order on an instance, bypassing the rest of an instance
     /// update. You probably don't need this; it's only used at the end of
-    /// instance creation, since the boot disk can't be set until the new
+    /// instance creation, since the boot order can't be set until the new
     /// instance's disks are all attached.
-    pub async fn instance_set_boot_disk(
+    pub async fn instance_set_boot_order(
         &self,
         opctx: &OpContext,
         authz_instance: &authz::Instance,
-        boot_disk_id: Option<Uuid>,
+        boot_order: Vec<Uuid>,
     ) -> Result<(), Error> {
         let err = OptionalError::new();
         let conn = self.pool_connection_authorized(opctx).await?;
-        self.transaction_retry_wrapper("instance_set_boot_disk")
+        self.transaction_retry_wrapper("instance_set_boot_order")
             .transaction(&conn, |conn| {
                 let err = err.clone();
+                let boot_order = boot_order.clone();
                 async move {
-                    self.instance_set_boot_disk_on_conn(
+                    self.instance_set_boot_order_on_conn(
                         &conn,
                         &err,
                         authz_instance,
-                        boot_disk_id,
+                        boot_order,
                     )
                     .await?;
                     Ok(())
@@ -1180,79 +1303,66 @@ impl DataStore {
             })
     }
 
-    /// Set an instance's boot disk to the provided `boot_disk_id` (or unset it,
-    /// if `boot_disk_id` is `None`), within an existing transaction.
+    /// Set an instance's boot order to the provided list of disk IDs (or
+    /// clear it, if `boot_order` is empty), within an existing transaction.
     ///
     /// The instance must be in an updatable state for this update to succeed.
     /// If the instance is not updatable, return `Error::Conflict`.
     ///
-    /// To update the boot disk an instance must not be incarnated by a VMM and,
-    /// if the boot disk is to be set non-NULL, the disk must already be
-    /// attached. These constraints together ensure that the boot disk reflected
-    /// in Nexus is always reflective of the disk a VMM should be allowed to
-    /// use.
+    /// To update the boot order an instance must not be incarnated by a VMM
+    /// and every disk in the new boot order must already be attached. These
+    /// constraints together ensure that the boot order reflected in Nexus only
+    /// ever names disks a VMM should be allowed to use.
     ///
     /// This is factored out as it is used by both
     /// [`DataStore::instance_reconfigure`], which mutates many instance fields,
-    /// and [`DataStore::instance_set_boot_disk`], which only touches the boot
-    /// disk.
-    async fn instance_set_boot_disk_on_conn(
+    /// and [`DataStore::instance_set_boot_order`], which only touches the boot
+    /// order.
+    async fn instance_set_boot_order_on_conn(
         &self,
         conn: &async_bb8_diesel::Connection<DbConnection>,
         err: &OptionalError<Error>,
         authz_instance: &authz::Instance,
-        boot_disk_id: Option<Uuid>,
+        boot_order: Vec<Uuid>,
     ) -> Result<(), diesel::result::Error> {
         use nexus_db_schema::schema::disk::dsl as disk_dsl;
         use nexus_db_schema::schema::instance::dsl as instance_dsl;
 
-        if let Some(disk_id) = boot_disk_id {
-            // Ensure the disk is currently attached before updating the
+        if !boot_order.is_empty() {
+            // Ensure the disks are all currently attached before updating the
             // database.
             let expected_state =
                 api::external::DiskState::Attached(authz_instance.id());
 
-            let attached_disk: Option<Uuid> = disk_dsl::disk
-                .filter(disk_dsl::id.eq(disk_id))
+            let attached_disks: Vec<Uuid> = disk_dsl::disk
+                .filter(disk_dsl::id.eq_any(boot_order.clone()))
                 .filter(disk_dsl::attach_instance_id.eq(authz_instance.id()))
                 .filter(disk_dsl::disk_state.eq(expected_state.label()))
                 .select(disk_dsl::id)
-                .first_async::<Uuid>(conn)
-                .await
-                .optional()?;
+                .load_async::<Uuid>(conn)
+                .await?;
 
-            if attached_disk.is_none() {
+            if boot_order.iter().any(|id| !attached_disks.contains(id)) {
                 return Err(
                     err.bail(Error::conflict("boot disk must be attached"))
                 );
             }
         }
 
-        let query = diesel::update(instance_dsl::instance)
-            .into_boxed()
+        let r = diesel::update(instance_dsl::instance)
             .filter(instance_dsl::id.eq(authz_instance.id()))
             .filter(
                 instance_dsl::state
                     .eq_any(InstanceState::NOT_INCARNATED_STATES),
-            );
-        let query = if boot_disk_id.is_some() {
-            query.filter(
-                instance_dsl::boot_disk_id
-                    .ne(boot_disk_id)
-                    .or(instance_dsl::boot_disk_id.is_null()),
             )
-        } else {
-            query.filter(instance_dsl::boot_disk_id.is_not_null())
-        };
-
-        let r = query
-            .set(instance_dsl::boot_disk_id.eq(boot_disk_id))
+            .filter(instance_dsl::boot_order.ne(boot_order.clone()))
+            .set(instance_dsl::boot_order.eq(boot_order.clone()))
             .check_if_exists::<Instance>(authz_instance.id())
             .execute_and_check(&conn)
             .await?;
         match r.status {
             UpdateStatus::NotUpdatedButExists => {
-                if r.found.boot_disk_id == boot_disk_id {
+                if r.found.boot_order == boot_order {
                     // Not updated, because the update is no change..
                     return Ok(());
                 }
@@ -1268,13 +1378,13 @@ impl DataStore {
                 // There should be no other reason the update fails on an
                 // existing instance.
                 warn!(
-                    self.log, "failed to instance_set_boot_disk_on_conn on an \
+                    self.log, "failed to instance_set_boot_order_on_conn on an \
                     instance that should have been updatable";
                     "instance_id" => %r.found.id(),
-                    "new boot_disk_id" => ?boot_disk_id
+                    "new boot_order" => ?boot_order
                 );
                 return Err(err.bail(Error::internal_error(
-                    "unable to reconfigure instance boot disk",
+                    "unable to reconfigure instance boot order",
                 )));
             }
             UpdateStatus::Updated => Ok(()),
@@ -1424,6 +1534,8 @@ impl DataStore {
         )
         .await?;
         self.instance_ssh_keys_delete(opctx, instance_id).await?;
+        self.instance_tags_delete(opctx, instance_id).await?;
+        self.instance_auto_restart_events_delete(opctx, instance_id).await?;
         self.instance_mark_migrations_deleted(opctx, instance_id).await?;
 
         Ok(())
@@ -2233,11 +2345,13 @@ mod tests {
                             params::InstanceNetworkInterfaceAttachment::None,
                         external_ips: Vec::new(),
                         disks: Vec::new(),
-                        boot_disk: None,
+                        boot_order: Vec::new(),
                         ssh_public_keys: None,
                         start: false,
                         auto_restart_policy: Default::default(),
                         anti_affinity_groups: Vec::new(),
+                        tags: Default::default(),
+                        stopped_reservation_policy: Default::default(),
                     },
                 ),
             )
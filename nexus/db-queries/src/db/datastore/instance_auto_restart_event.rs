@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods related to [`InstanceAutoRestartEvent`]s.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::model::InstanceAutoRestartEvent;
+use crate::db::pagination::paginated;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use diesel::prelude::*;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::DeleteResult;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+impl DataStore {
+    /// Records that the control plane automatically restarted an instance.
+    ///
+    /// This is used by the instance-start saga, and is idempotent: an event
+    /// for the same VMM is only recorded once.
+    pub async fn instance_auto_restart_event_insert(
+        &self,
+        opctx: &OpContext,
+        event: InstanceAutoRestartEvent,
+    ) -> Result<(), Error> {
+        use nexus_db_schema::schema::instance_auto_restart_event::dsl;
+        diesel::insert_into(dsl::instance_auto_restart_event)
+            .values(event)
+            .on_conflict(dsl::propolis_id)
+            .do_nothing()
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(())
+    }
+
+    /// Lists the times the control plane has automatically restarted an
+    /// instance.
+    pub async fn instance_auto_restart_event_list(
+        &self,
+        opctx: &OpContext,
+        authz_instance: &authz::Instance,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<InstanceAutoRestartEvent> {
+        opctx.authorize(authz::Action::Read, authz_instance).await?;
+
+        use nexus_db_schema::schema::instance_auto_restart_event::dsl;
+        paginated(dsl::instance_auto_restart_event, dsl::id, pagparams)
+            .filter(dsl::instance_id.eq(authz_instance.id()))
+            .select(InstanceAutoRestartEvent::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Returns the number of times each of a batch of instances has been
+    /// automatically restarted. Instances which have never been automatically
+    /// restarted are omitted.
+    ///
+    /// This does not perform an authz check: callers are expected to have
+    /// already fetched the instances themselves (e.g., by listing a project's
+    /// instances).
+    pub async fn instance_auto_restart_counts_batch(
+        &self,
+        opctx: &OpContext,
+        instance_ids: &[InstanceUuid],
+    ) -> Result<BTreeMap<Uuid, u32>, Error> {
+        use nexus_db_schema::schema::instance_auto_restart_event::dsl;
+        let ids: Vec<_> =
+            instance_ids.iter().map(|id| id.into_untyped_uuid()).collect();
+        let counts = dsl::instance_auto_restart_event
+            .filter(dsl::instance_id.eq_any(ids))
+            .group_by(dsl::instance_id)
+            .select((dsl::instance_id, diesel::dsl::count_star()))
+            .load_async::<(Uuid, i64)>(
+                &*self.pool_connection_authorized(opctx).await?,
+            )
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(counts
+            .into_iter()
+            .map(|(id, count)| (id, u32::try_from(count).unwrap_or(u32::MAX)))
+            .collect())
+    }
+
+    /// Removes the auto-restart history of an instance.
+    pub async fn instance_auto_restart_events_delete(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+    ) -> DeleteResult {
+        use nexus_db_schema::schema::instance_auto_restart_event::dsl;
+        diesel::delete(dsl::instance_auto_restart_event)
+            .filter(dsl::instance_id.eq(instance_id.into_untyped_uuid()))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(())
+    }
+}
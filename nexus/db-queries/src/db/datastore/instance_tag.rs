@@ -0,0 +1,152 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods related to [`InstanceTag`]s.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::model::InstanceTag;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use diesel::prelude::*;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external::DeleteResult;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::UpdateResult;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
+use std::collections::BTreeMap;
+
+impl DataStore {
+    /// Returns the tags attached to an instance, keyed by tag key.
+    pub async fn instance_tags_list(
+        &self,
+        opctx: &OpContext,
+        authz_instance: &authz::Instance,
+    ) -> LookupResult<BTreeMap<String, String>> {
+        opctx.authorize(authz::Action::Read, authz_instance).await?;
+
+        use nexus_db_schema::schema::instance_tag::dsl;
+        let tags = dsl::instance_tag
+            .filter(dsl::instance_id.eq(authz_instance.id()))
+            .select(InstanceTag::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(tags.into_iter().map(|tag| (tag.key, tag.value)).collect())
+    }
+
+    /// Returns the tags attached to each of a batch of instances.
+    ///
+    /// This does not perform an authz check: callers are expected to have
+    /// already fetched the instances themselves (e.g., by listing a project's
+    /// instances).
+    pub async fn instance_tags_list_batch(
+        &self,
+        opctx: &OpContext,
+        instance_ids: &[InstanceUuid],
+    ) -> ListResultVec<InstanceTag> {
+        use nexus_db_schema::schema::instance_tag::dsl;
+        let ids: Vec<_> =
+            instance_ids.iter().map(|id| id.into_untyped_uuid()).collect();
+        dsl::instance_tag
+            .filter(dsl::instance_id.eq_any(ids))
+            .select(InstanceTag::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Attaches tags to a newly-created instance.
+    ///
+    /// This is used by the instance-create saga, and is idempotent: tags that
+    /// already exist are left untouched.
+    pub async fn instance_tags_insert(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+        tags: &BTreeMap<String, String>,
+    ) -> Result<(), Error> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+
+        let tags: Vec<_> = tags
+            .iter()
+            .map(|(k, v)| InstanceTag::new(instance_id, k.clone(), v.clone()))
+            .collect();
+
+        use nexus_db_schema::schema::instance_tag::dsl;
+        diesel::insert_into(dsl::instance_tag)
+            .values(tags)
+            .on_conflict((dsl::instance_id, dsl::key))
+            .do_nothing()
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(())
+    }
+
+    /// Replaces all of an instance's tags with `tags`.
+    pub async fn instance_tags_replace(
+        &self,
+        opctx: &OpContext,
+        authz_instance: &authz::Instance,
+        tags: &BTreeMap<String, String>,
+    ) -> UpdateResult<BTreeMap<String, String>> {
+        opctx.authorize(authz::Action::Modify, authz_instance).await?;
+
+        let instance_id = authz_instance.id();
+        let new_tags: Vec<_> = tags
+            .iter()
+            .map(|(k, v)| InstanceTag {
+                instance_id,
+                key: k.clone(),
+                value: v.clone(),
+            })
+            .collect();
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        self.transaction_retry_wrapper("instance_tags_replace")
+            .transaction(&conn, |conn| {
+                let new_tags = new_tags.clone();
+                async move {
+                    use nexus_db_schema::schema::instance_tag::dsl;
+                    diesel::delete(dsl::instance_tag)
+                        .filter(dsl::instance_id.eq(instance_id))
+                        .execute_async(&conn)
+                        .await?;
+                    if !new_tags.is_empty() {
+                        diesel::insert_into(dsl::instance_tag)
+                            .values(new_tags)
+                            .execute_async(&conn)
+                            .await?;
+                    }
+                    Ok(())
+                }
+            })
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+
+        Ok(tags.clone())
+    }
+
+    /// Removes all tags from an instance.
+    pub async fn instance_tags_delete(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+    ) -> DeleteResult {
+        use nexus_db_schema::schema::instance_tag::dsl;
+        diesel::delete(dsl::instance_tag)
+            .filter(dsl::instance_id.eq(instance_id.into_untyped_uuid()))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@
 use super::DataStore;
 use super::SQL_BATCH_SIZE;
 use crate::authz;
+use crate::cache::IpPoolKey;
 use crate::context::OpContext;
 use crate::db::collection_insert::AsyncInsertError;
 use crate::db::collection_insert::DatastoreCollection;
@@ -55,8 +56,22 @@ use omicron_common::api::external::ResourceType;
 use omicron_common::api::external::UpdateResult;
 use omicron_common::api::external::http_pagination::PaginatedBy;
 use ref_cast::RefCast;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
+/// The allocation of addresses from an IP pool, as returned by
+/// [`DataStore::ip_pool_utilization`].
+#[derive(Debug, Clone)]
+pub struct IpPoolAllocation {
+    /// The number of addresses allocated from the pool.
+    pub allocated: i64,
+    /// The total number of addresses in the pool.
+    pub capacity: u128,
+    /// Each of the pool's ranges, with the number of addresses allocated from
+    /// it.
+    pub ranges: Vec<(IpPoolRange, i64)>,
+}
+
 /// Helper type with both an authz IP Pool and the actual DB record.
 #[derive(Debug, Clone)]
 pub struct ServiceIpPool {
@@ -149,6 +164,46 @@ impl DataStore {
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
     }
 
+    /// Look up whether the given pool is available to users in the given
+    /// project in the current silo, i.e., whether the pool is linked either to
+    /// that project or to the current silo. If it's linked to both, the
+    /// project link is returned.
+    pub async fn ip_pool_fetch_link_for_project(
+        &self,
+        opctx: &OpContext,
+        ip_pool_id: Uuid,
+        project_id: Uuid,
+    ) -> LookupResult<IpPoolResource> {
+        use nexus_db_schema::schema::ip_pool;
+        use nexus_db_schema::schema::ip_pool_resource;
+
+        let authz_silo = opctx
+            .authn
+            .silo_required()
+            .internal_context("fetching link from an IP pool to a project")?;
+
+        ip_pool::table
+            .inner_join(ip_pool_resource::table)
+            .filter(
+                ip_pool_resource::resource_type
+                    .eq(IpPoolResourceType::Silo)
+                    .and(ip_pool_resource::resource_id.eq(authz_silo.id()))
+                    .or(ip_pool_resource::resource_type
+                        .eq(IpPoolResourceType::Project)
+                        .and(ip_pool_resource::resource_id.eq(project_id))),
+            )
+            .filter(ip_pool::id.eq(ip_pool_id))
+            .filter(ip_pool::time_deleted.is_null())
+            // Most specific first; see `ip_pools_fetch_default`.
+            .order(ip_pool_resource::resource_type.asc())
+            .select(IpPoolResource::as_select())
+            .first_async::<IpPoolResource>(
+                &*self.pool_connection_authorized(opctx).await?,
+            )
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
     /// Look up the default IP pool for the current silo. If there is no default
     /// at silo scope, fall back to the next level up, namely the fleet default.
     /// There should always be a default pool at the fleet level, though this
@@ -158,9 +213,6 @@ impl DataStore {
         &self,
         opctx: &OpContext,
     ) -> LookupResult<(authz::IpPool, IpPool)> {
-        use nexus_db_schema::schema::ip_pool;
-        use nexus_db_schema::schema::ip_pool_resource;
-
         let authz_silo_id = opctx.authn.silo_required()?.id();
 
         // TODO: Need auth check here. Only fleet viewers can list children on
@@ -172,6 +224,25 @@ impl DataStore {
         //     .authorize(authz::Action::ListChildren, &authz::IP_POOL_LIST)
         //     .await?;
 
+        // This is used whenever an instance or floating IP is created, so the
+        // result is cached.
+        self.caches
+            .ip_pools
+            .get_or_load(IpPoolKey::SiloDefault(authz_silo_id), || {
+                self.ip_pools_fetch_default_uncached(opctx, authz_silo_id)
+            })
+            .await
+    }
+
+    /// Private helper that reads a silo's default IP pool from the database
+    async fn ip_pools_fetch_default_uncached(
+        &self,
+        opctx: &OpContext,
+        authz_silo_id: Uuid,
+    ) -> LookupResult<(authz::IpPool, IpPool)> {
+        use nexus_db_schema::schema::ip_pool;
+        use nexus_db_schema::schema::ip_pool_resource;
+
         let lookup_type =
             LookupType::ByOther("default IP pool for current silo".to_string());
 
@@ -243,7 +314,19 @@ impl DataStore {
         };
         let name =
             Name(name.parse().expect("should be able to parse builtin names"));
-        LookupPath::new(&opctx, self).ip_pool_name(&name).fetch().await
+
+        // These pools are looked up whenever a service's external IP or NIC is
+        // allocated, so they're cached.  The lookup checks that the caller can
+        // read the pool, which still has to be done when it's cached.
+        let (authz_pool, db_pool) = self
+            .caches
+            .ip_pools
+            .get_or_load(IpPoolKey::Service(ip_version), || {
+                LookupPath::new(&opctx, self).ip_pool_name(&name).fetch()
+            })
+            .await?;
+        opctx.authorize(authz::Action::Read, &authz_pool).await?;
+        Ok((authz_pool, db_pool))
     }
 
     /// Creates a new IP pool.
@@ -317,6 +400,7 @@ impl DataStore {
                     ErrorHandler::NotFoundByResource(authz_pool),
                 )
             })?;
+        self.caches.ip_pools.invalidate();
 
         if updated_rows == 0 {
             return Err(Error::invalid_request(
@@ -376,32 +460,33 @@ impl DataStore {
         use nexus_db_schema::schema::ip_pool::dsl;
         opctx.authorize(authz::Action::Modify, authz_pool).await?;
 
-        diesel::update(dsl::ip_pool)
+        let result = diesel::update(dsl::ip_pool)
             .filter(dsl::id.eq(authz_pool.id()))
             .filter(dsl::time_deleted.is_null())
             .set(updates)
             .returning(IpPool::as_returning())
             .get_result_async(&*self.pool_connection_authorized(opctx).await?)
-            .await
-            .map_err(|e| {
-                public_error_from_diesel(
-                    e,
-                    ErrorHandler::NotFoundByResource(authz_pool),
-                )
-            })
+            .await;
+        self.caches.ip_pools.invalidate();
+        result.map_err(|e| {
+            public_error_from_diesel(
+                e,
+                ErrorHandler::NotFoundByResource(authz_pool),
+            )
+        })
     }
 
     /// Return the number of IPs allocated from and the capacity of the provided
-    /// IP Pool.
+    /// IP Pool, in total and for each of its ranges.
     pub async fn ip_pool_utilization(
         &self,
         opctx: &OpContext,
         authz_pool: &authz::IpPool,
-    ) -> Result<(i64, u128), Error> {
+    ) -> Result<IpPoolAllocation, Error> {
         opctx.authorize(authz::Action::Read, authz_pool).await?;
         opctx.authorize(authz::Action::ListChildren, authz_pool).await?;
         let conn = self.pool_connection_authorized(opctx).await?;
-        let (allocated, ranges) = self
+        let (allocated, ranges, range_allocated) = self
             .transaction_retry_wrapper("ip_pool_utilization")
             .transaction(&conn, |conn| async move {
                 let allocated = self
@@ -412,7 +497,12 @@ impl DataStore {
                         &conn, authz_pool,
                     )
                     .await?;
-                Ok((allocated, ranges))
+                let range_allocated = self
+                    .ip_pool_range_allocated_counts_on_connection(
+                        &conn, authz_pool,
+                    )
+                    .await?;
+                Ok((allocated, ranges, range_allocated))
             })
             .await
             .map_err(|e| match &e {
@@ -422,8 +512,18 @@ impl DataStore {
                 ),
                 _ => public_error_from_diesel(e, ErrorHandler::Server),
             })?;
-        let capacity = Self::accumulate_ip_range_sizes(ranges)?;
-        Ok((allocated, capacity))
+        let capacity = Self::accumulate_ip_range_sizes(
+            ranges.iter().map(|r| (r.first_address, r.last_address)).collect(),
+        )?;
+        let ranges = ranges
+            .into_iter()
+            .map(|range| {
+                let allocated =
+                    range_allocated.get(&range.id).copied().unwrap_or(0);
+                (range, allocated)
+            })
+            .collect();
+        Ok(IpPoolAllocation { allocated, capacity, ranges })
     }
 
     /// Return the total number of IPs allocated from the provided pool.
@@ -454,6 +554,27 @@ impl DataStore {
             .await
     }
 
+    /// Return the number of IPs allocated from each range of the provided
+    /// pool, keyed by range ID. Ranges with no allocated IPs are omitted.
+    async fn ip_pool_range_allocated_counts_on_connection(
+        &self,
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        authz_pool: &authz::IpPool,
+    ) -> Result<BTreeMap<Uuid, i64>, DieselError> {
+        use nexus_db_schema::schema::external_ip;
+        external_ip::table
+            .filter(external_ip::ip_pool_id.eq(authz_pool.id()))
+            .filter(external_ip::time_deleted.is_null())
+            .group_by(external_ip::ip_pool_range_id)
+            .select((
+                external_ip::ip_pool_range_id,
+                diesel::dsl::count_distinct(external_ip::ip),
+            ))
+            .load_async::<(Uuid, i64)>(conn)
+            .await
+            .map(|counts| counts.into_iter().collect())
+    }
+
     /// Return the total capacity of the provided pool.
     #[cfg(test)]
     async fn ip_pool_total_capacity(
@@ -472,19 +593,27 @@ impl DataStore {
                     ErrorHandler::NotFoundByResource(authz_pool),
                 )
             })
-            .and_then(Self::accumulate_ip_range_sizes)
+            .and_then(|ranges| {
+                Self::accumulate_ip_range_sizes(
+                    ranges
+                        .iter()
+                        .map(|r| (r.first_address, r.last_address))
+                        .collect(),
+                )
+            })
     }
 
     async fn ip_pool_list_ranges_batched_on_connection(
         &self,
         conn: &async_bb8_diesel::Connection<DbConnection>,
         authz_pool: &authz::IpPool,
-    ) -> Result<Vec<(IpNetwork, IpNetwork)>, DieselError> {
+    ) -> Result<Vec<IpPoolRange>, DieselError> {
         use nexus_db_schema::schema::ip_pool_range;
         ip_pool_range::table
             .filter(ip_pool_range::ip_pool_id.eq(authz_pool.id()))
             .filter(ip_pool_range::time_deleted.is_null())
-            .select((ip_pool_range::first_address, ip_pool_range::last_address))
+            .order(ip_pool_range::first_address.asc())
+            .select(IpPoolRange::as_select())
             // This is a rare unpaginated DB query, which means we are
             // vulnerable to a resource exhaustion attack in which someone
             // creates a very large number of ranges in order to make this
@@ -494,7 +623,7 @@ impl DataStore {
             // than 10,000 ranges in a pool, we will undercount, but I have a
             // hard time seeing that as a practical problem.
             .limit(10000)
-            .get_results_async::<(IpNetwork, IpNetwork)>(conn)
+            .get_results_async::<IpPoolRange>(conn)
             .await
     }
 
@@ -605,6 +734,7 @@ impl DataStore {
                     ),
                 )
             })?;
+        self.caches.ip_pools.invalidate();
 
         if ip_pool_resource.is_default {
             self.link_default_gateway(
@@ -808,6 +938,7 @@ impl DataStore {
                         e
                     ))
                 })?;
+            self.caches.ip_pools.invalidate();
             return Ok(updated_link);
         }
 
@@ -820,7 +951,8 @@ impl DataStore {
 
         let err = OptionalError::new();
 
-        self.transaction_retry_wrapper("ip_pool_set_default")
+        let result = self
+            .transaction_retry_wrapper("ip_pool_set_default")
             .transaction(&conn, |conn| {
                 let err = err.clone();
                 async move {
@@ -879,29 +1011,28 @@ impl DataStore {
                     Ok(updated_link)
                 }
             })
-            .await
-            .map_err(|e| match err.take() {
-                Some(TxnError::CustomError(
-                    IpPoolResourceUpdateError::FailedToUnsetDefault(err),
-                )) => public_error_from_diesel(err, ErrorHandler::Server),
-                Some(TxnError::Database(err)) => {
-                    public_error_from_diesel(err, ErrorHandler::Server)
-                }
-                None => {
-                    public_error_from_diesel(
-                        e,
-                        ErrorHandler::NotFoundByLookup(
-                            ResourceType::IpPoolResource,
-                            // TODO: would be nice to put the actual names and/or ids in
-                            // here but LookupType on each of the two silos doesn't have
-                            // a nice to_string yet or a way of composing them
-                            LookupType::ByCompositeId(
-                                "(pool, silo)".to_string(),
-                            ),
-                        ),
-                    )
-                }
-            })
+            .await;
+        self.caches.ip_pools.invalidate();
+        result.map_err(|e| match err.take() {
+            Some(TxnError::CustomError(
+                IpPoolResourceUpdateError::FailedToUnsetDefault(err),
+            )) => public_error_from_diesel(err, ErrorHandler::Server),
+            Some(TxnError::Database(err)) => {
+                public_error_from_diesel(err, ErrorHandler::Server)
+            }
+            None => {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByLookup(
+                        ResourceType::IpPoolResource,
+                        // TODO: would be nice to put the actual names and/or ids in
+                        // here but LookupType on each of the two silos doesn't have
+                        // a nice to_string yet or a way of composing them
+                        LookupType::ByCompositeId("(pool, silo)".to_string()),
+                    ),
+                )
+            }
+        })
     }
 
     /// Ephemeral and snat IPs are associated with a silo through an instance,
@@ -1032,6 +1163,7 @@ impl DataStore {
                     e
                 ))
             })?;
+        self.caches.ip_pools.invalidate();
 
         self.unlink_ip_pool_gateway(
             opctx,
@@ -1044,6 +1176,161 @@ impl DataStore {
         Ok(())
     }
 
+    /// List the projects linked to the given pool.
+    pub async fn ip_pool_project_list(
+        &self,
+        opctx: &OpContext,
+        authz_pool: &authz::IpPool,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<IpPoolResource> {
+        use nexus_db_schema::schema::ip_pool;
+        use nexus_db_schema::schema::ip_pool_resource;
+        use nexus_db_schema::schema::project;
+
+        paginated(
+            ip_pool_resource::table,
+            ip_pool_resource::resource_id,
+            pagparams,
+        )
+        .inner_join(ip_pool::table)
+        .inner_join(
+            project::table.on(project::id.eq(ip_pool_resource::resource_id)),
+        )
+        .filter(ip_pool_resource::resource_type.eq(IpPoolResourceType::Project))
+        .filter(ip_pool::id.eq(authz_pool.id()))
+        .filter(ip_pool::time_deleted.is_null())
+        .filter(project::time_deleted.is_null())
+        .select(IpPoolResource::as_select())
+        .load_async::<IpPoolResource>(
+            &*self.pool_connection_authorized(opctx).await?,
+        )
+        .await
+        .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Link the given pool to a project, allowing IPs to be allocated from the
+    /// pool within the project.
+    ///
+    /// Unlike silo links, project links can't make a pool the default: users
+    /// must ask for a project-linked pool by name.
+    pub async fn ip_pool_link_project(
+        &self,
+        opctx: &OpContext,
+        authz_pool: &authz::IpPool,
+        authz_project: &authz::Project,
+    ) -> CreateResult<IpPoolResource> {
+        use nexus_db_schema::schema::ip_pool_resource::dsl;
+
+        opctx.authorize(authz::Action::Modify, authz_pool).await?;
+        opctx.authorize(authz::Action::Modify, authz_project).await?;
+
+        let link = IpPoolResource {
+            ip_pool_id: authz_pool.id(),
+            resource_type: IpPoolResourceType::Project,
+            resource_id: authz_project.id(),
+            is_default: false,
+        };
+        diesel::insert_into(dsl::ip_pool_resource)
+            .values(link.clone())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::Conflict(
+                        ResourceType::IpPoolResource,
+                        &format!(
+                            "ip_pool_id: {:?}, resource_id: {:?}, \
+                             resource_type: {:?}",
+                            link.ip_pool_id,
+                            link.resource_id,
+                            link.resource_type,
+                        ),
+                    ),
+                )
+            })
+    }
+
+    /// Delete the link between the given pool and project, unless there are
+    /// outstanding IPs allocated from the pool in the project that the link is
+    /// needed for.
+    ///
+    /// If the pool is also linked to the project's silo, the project's IPs
+    /// remain available through that link, so they don't block unlinking.
+    pub async fn ip_pool_unlink_project(
+        &self,
+        opctx: &OpContext,
+        authz_pool: &authz::IpPool,
+        authz_silo: &authz::Silo,
+        authz_project: &authz::Project,
+    ) -> DeleteResult {
+        use nexus_db_schema::schema::external_ip;
+        use nexus_db_schema::schema::instance;
+        use nexus_db_schema::schema::ip_pool_resource;
+
+        opctx.authorize(authz::Action::Modify, authz_pool).await?;
+        opctx.authorize(authz::Action::Modify, authz_project).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+
+        let silo_link = ip_pool_resource::table
+            .filter(ip_pool_resource::ip_pool_id.eq(authz_pool.id()))
+            .filter(
+                ip_pool_resource::resource_type.eq(IpPoolResourceType::Silo),
+            )
+            .filter(ip_pool_resource::resource_id.eq(authz_silo.id()))
+            .select(IpPoolResource::as_select())
+            .first_async::<IpPoolResource>(&*conn)
+            .await
+            .optional()
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+
+        if silo_link.is_none() {
+            // Floating IPs belong to the project directly, while ephemeral IPs
+            // belong to the project through an instance.
+            let existing_ips = external_ip::table
+                .left_join(
+                    instance::table
+                        .on(external_ip::parent_id.eq(instance::id.nullable())),
+                )
+                .filter(external_ip::is_service.eq(false))
+                .filter(external_ip::time_deleted.is_null())
+                .filter(external_ip::ip_pool_id.eq(authz_pool.id()))
+                .filter(
+                    external_ip::project_id.eq(authz_project.id()).or(
+                        instance::project_id
+                            .eq(authz_project.id())
+                            .and(instance::time_deleted.is_null()),
+                    ),
+                )
+                .select(external_ip::id)
+                .limit(1)
+                .load_async::<Uuid>(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?;
+
+            if !existing_ips.is_empty() {
+                return Err(Error::invalid_request(
+                    "IP addresses from this pool are in use in the linked \
+                     project",
+                ));
+            }
+        }
+
+        diesel::delete(ip_pool_resource::table)
+            .filter(ip_pool_resource::ip_pool_id.eq(authz_pool.id()))
+            .filter(
+                ip_pool_resource::resource_type.eq(IpPoolResourceType::Project),
+            )
+            .filter(ip_pool_resource::resource_id.eq(authz_project.id()))
+            .execute_async(&*conn)
+            .await
+            .map(|_rows_deleted| ())
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
     pub async fn ip_pool_list_ranges(
         &self,
         opctx: &OpContext,
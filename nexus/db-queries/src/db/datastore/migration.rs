@@ -5,6 +5,7 @@
 //! [`DataStore`] methods on [`Migration`]s.
 
 use super::DataStore;
+use crate::authz;
 use crate::context::OpContext;
 use crate::db::model::Generation;
 use crate::db::model::Migration;
@@ -28,6 +29,7 @@ use omicron_common::api::internal::nexus;
 use omicron_uuid_kinds::GenericUuid;
 use omicron_uuid_kinds::InstanceUuid;
 use omicron_uuid_kinds::PropolisUuid;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 impl DataStore {
@@ -64,6 +66,57 @@ impl DataStore {
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
     }
 
+    /// List the migration history of the provided instance, along with the
+    /// IDs of the sleds each migration moved the instance between.
+    ///
+    /// Unlike [`DataStore::instance_list_migrations`], this includes migration
+    /// records that have been marked as deleted, since migration records are
+    /// deleted once the migration resolves. A sled ID is `None` if the
+    /// corresponding VMM record could not be found.
+    pub async fn instance_list_migration_history(
+        &self,
+        opctx: &OpContext,
+        authz_instance: &authz::Instance,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<(Migration, Option<Uuid>, Option<Uuid>)> {
+        opctx.authorize(authz::Action::Read, authz_instance).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let migrations = paginated(dsl::migration, dsl::id, pagparams)
+            .filter(dsl::instance_id.eq(authz_instance.id()))
+            .select(Migration::as_select())
+            .load_async(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+
+        // VMM records are never hard-deleted, so look them up regardless of
+        // whether they've been marked as deleted.
+        let vmm_ids: Vec<Uuid> = migrations
+            .iter()
+            .flat_map(|m| [m.source_propolis_id, m.target_propolis_id])
+            .collect();
+        let sleds_by_vmm: HashMap<Uuid, Uuid> = {
+            use nexus_db_schema::schema::vmm::dsl as vmm_dsl;
+            vmm_dsl::vmm
+                .filter(vmm_dsl::id.eq_any(vmm_ids))
+                .select((vmm_dsl::id, vmm_dsl::sled_id))
+                .load_async::<(Uuid, Uuid)>(&*conn)
+                .await
+                .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?
+                .into_iter()
+                .collect()
+        };
+
+        Ok(migrations
+            .into_iter()
+            .map(|m| {
+                let source_sled = sleds_by_vmm.get(&m.source_propolis_id);
+                let target_sled = sleds_by_vmm.get(&m.target_propolis_id);
+                (m, source_sled.copied(), target_sled.copied())
+            })
+            .collect())
+    }
+
     /// Mark *all* migrations for the provided instance as deleted.
     ///
     /// This should be called when deleting an instance.
@@ -234,11 +287,13 @@ mod tests {
                             params::InstanceNetworkInterfaceAttachment::None,
                         external_ips: Vec::new(),
                         disks: Vec::new(),
-                        boot_disk: None,
+                        boot_order: Vec::new(),
                         ssh_public_keys: None,
                         start: false,
                         auto_restart_policy: Default::default(),
                         anti_affinity_groups: Vec::new(),
+                        tags: Default::default(),
+                        stopped_reservation_policy: Default::default(),
                     },
                 ),
             )
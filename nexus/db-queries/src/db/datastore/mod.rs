@@ -55,6 +55,7 @@ mod auth;
 mod bfd;
 mod bgp;
 mod bootstore;
+mod break_glass;
 mod certificate;
 mod clickhouse_policy;
 mod cockroachdb_node_id;
@@ -71,6 +72,8 @@ mod external_ip;
 mod identity_provider;
 mod image;
 pub mod instance;
+mod instance_auto_restart_event;
+mod instance_tag;
 mod inventory;
 mod ip_pool;
 mod lldp;
@@ -78,6 +81,7 @@ mod lookup_interface;
 mod migration;
 mod nat_entry;
 mod network_interface;
+mod operation;
 mod oximeter;
 mod oximeter_read_policy;
 mod physical_disk;
@@ -85,6 +89,7 @@ mod probe;
 mod project;
 mod quota;
 mod rack;
+mod rack_activity;
 mod reconfigurator_chicken_switches;
 mod region;
 mod region_replacement;
@@ -92,20 +97,25 @@ mod region_snapshot;
 pub mod region_snapshot_replacement;
 mod rendezvous_debug_dataset;
 mod role;
+mod rolling_sled_reboot;
 mod saga;
 mod silo;
 mod silo_auth_settings;
 mod silo_group;
 mod silo_user;
 mod sled;
+pub mod sled_execution_lease;
 mod sled_instance;
 mod snapshot;
+mod snapshot_export;
+mod snapshot_schedule;
 mod ssh_key;
 mod support_bundle;
 mod switch;
 mod switch_interface;
 mod switch_port;
 mod target_release;
+mod technician_port_update;
 #[cfg(test)]
 pub(crate) mod test_utils;
 mod update;
@@ -115,26 +125,33 @@ mod v2p_mapping;
 mod virtual_provisioning_collection;
 mod vmm;
 mod volume;
+mod volume_reference_check;
 mod volume_repair;
 mod vpc;
 pub mod webhook_delivery;
 mod zpool;
+mod zpool_usage;
 
 pub use address_lot::AddressLotCreateResult;
 pub use dns::DataStoreDnsTest;
 pub use dns::DnsVersionUpdateBuilder;
 pub use ereport::EreportFilters;
+pub use image::ImageFilters;
 pub use instance::{
     InstanceAndActiveVmm, InstanceGestalt, InstanceStateComputer,
 };
 pub use inventory::DataStoreInventoryTest;
+pub use ip_pool::IpPoolAllocation;
 use nexus_db_model::AllSchemaVersions;
 use nexus_types::internal_api::views::HeldDbClaimInfo;
 pub use oximeter::CollectorReassignment;
 pub use rack::RackInit;
 pub use rack::SledUnderlayAllocationResult;
+pub use rack_activity::RackActivityFilters;
+pub use rack_activity::RackActivityRecord;
 pub use region::RegionAllocationFor;
 pub use region::RegionAllocationParameters;
+pub use region::SledVolumeRedundancy;
 pub use region_snapshot_replacement::NewRegionVolumeId;
 pub use region_snapshot_replacement::OldSnapshotVolumeId;
 pub use silo::Discoverability;
@@ -209,6 +226,7 @@ pub struct DataStore {
     pool: Arc<Pool>,
     virtual_provisioning_collection_producer: crate::provisioning::Producer,
     transaction_retry_producer: crate::transaction_retry::Producer,
+    caches: crate::cache::DataStoreCaches,
 }
 
 // The majority of `DataStore`'s methods live in our submodules as a concession
@@ -228,6 +246,7 @@ impl DataStore {
                 crate::provisioning::Producer::new(),
             transaction_retry_producer: crate::transaction_retry::Producer::new(
             ),
+            caches: crate::cache::DataStoreCaches::new(),
         }
     }
 
@@ -348,6 +367,7 @@ impl DataStore {
         registry
             .register_producer(self.transaction_retry_producer.clone())
             .unwrap();
+        registry.register_producer(self.caches.producer()).unwrap();
     }
 
     /// Constructs a transaction retry helper
@@ -832,6 +852,22 @@ mod test {
             id: zpool_id,
             sled_id: to_db_typed_uuid(sled_id),
             total_size: test_zpool_size().into(),
+            scrub_state: None,
+            scrub_start_time: None,
+            scrub_end_time: None,
+            scrub_percent_done: None,
+            scrub_errors: None,
+            allocated: None,
+            fragmentation_percent: None,
+            health_state: None,
+            read_errors: None,
+            write_errors: None,
+            checksum_errors: None,
+            resilver_state: None,
+            resilver_start_time: None,
+            resilver_end_time: None,
+            resilver_percent_done: None,
+            resilver_errors: None,
         };
         diesel::insert_into(dsl::inv_zpool)
             .values(inv_pool)
@@ -1947,6 +1983,7 @@ mod test {
                 last_port: crate::db::model::SqlU16(10),
                 state: nexus_db_model::IpAttachState::Attached,
                 is_probe: false,
+                time_attached: Some(now),
             })
             .collect::<Vec<_>>();
         diesel::insert_into(dsl::external_ip)
@@ -2010,6 +2047,7 @@ mod test {
             last_port: crate::db::model::SqlU16(10),
             state: nexus_db_model::IpAttachState::Attached,
             is_probe: false,
+            time_attached: Some(now),
         };
         diesel::insert_into(dsl::external_ip)
             .values(ip.clone())
@@ -2084,6 +2122,7 @@ mod test {
             last_port: crate::db::model::SqlU16(10),
             state: nexus_db_model::IpAttachState::Attached,
             is_probe: false,
+            time_attached: Some(now),
         };
 
         // Combinations of NULL and non-NULL for:
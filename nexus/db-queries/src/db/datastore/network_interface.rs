@@ -57,6 +57,7 @@ struct NicInfo {
     ip: ipnetwork::IpNetwork,
     mac: db::model::MacAddr,
     ipv4_block: db::model::Ipv4Net,
+    ipv4_secondary_blocks: Vec<db::model::Ipv4Net>,
     ipv6_block: db::model::Ipv6Net,
     vni: db::model::Vni,
     primary: bool,
@@ -68,8 +69,16 @@ impl From<NicInfo> for omicron_common::api::internal::shared::NetworkInterface {
     fn from(
         nic: NicInfo,
     ) -> omicron_common::api::internal::shared::NetworkInterface {
-        let ip_subnet = if nic.ip.is_ipv4() {
-            oxnet::IpNet::V4(nic.ipv4_block.0)
+        let ip_subnet = if let std::net::IpAddr::V4(ip) = nic.ip.ip() {
+            // OPTE needs the block the address was allocated from, which may be
+            // one of the subnet's secondary blocks.
+            let block = nic
+                .ipv4_secondary_blocks
+                .iter()
+                .map(|block| block.0)
+                .find(|block| block.contains(ip))
+                .unwrap_or(nic.ipv4_block.0);
+            oxnet::IpNet::V4(block)
         } else {
             oxnet::IpNet::V6(nic.ipv6_block.0)
         };
@@ -518,6 +527,7 @@ impl DataStore {
                 network_interface::ip,
                 network_interface::mac,
                 vpc_subnet::ipv4_block,
+                vpc_subnet::ipv4_secondary_blocks,
                 vpc_subnet::ipv6_block,
                 vpc::vni,
                 network_interface::is_primary,
@@ -0,0 +1,352 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods on [`Operation`]s.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::collection_insert::AsyncInsertError;
+use crate::db::collection_insert::DatastoreCollection;
+use crate::db::model::Operation;
+use crate::db::model::OperationState;
+use crate::db::model::Project;
+use crate::db::pagination::paginated;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
+use chrono::Utc;
+use diesel::prelude::*;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::UpdateResult;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::OmicronZoneUuid;
+use uuid::Uuid;
+
+impl DataStore {
+    pub async fn operation_list(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<Operation> {
+        opctx.authorize(authz::Action::ListChildren, authz_project).await?;
+
+        use nexus_db_schema::schema::operation::dsl;
+        paginated(dsl::operation, dsl::id, pagparams)
+            .filter(dsl::project_id.eq(authz_project.id()))
+            .select(Operation::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    pub async fn operation_create(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        operation: Operation,
+    ) -> CreateResult<Operation> {
+        use nexus_db_schema::schema::operation::dsl;
+
+        opctx.authorize(authz::Action::CreateChild, authz_project).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        Project::insert_resource(
+            authz_project.id(),
+            diesel::insert_into(dsl::operation).values(operation),
+        )
+        .insert_and_get_result_async(&conn)
+        .await
+        .map_err(|e| match e {
+            AsyncInsertError::CollectionNotFound => authz_project.not_found(),
+            AsyncInsertError::DatabaseError(e) => {
+                public_error_from_diesel(e, ErrorHandler::Server)
+            }
+        })
+    }
+
+    /// Record that a running operation has completed one more of its steps
+    ///
+    /// This has no effect once the operation has stopped running or has
+    /// completed all of its steps.
+    pub async fn operation_step_completed(
+        &self,
+        opctx: &OpContext,
+        authz_operation: &authz::Operation,
+    ) -> UpdateResult<()> {
+        opctx.authorize(authz::Action::Modify, authz_operation).await?;
+
+        use nexus_db_schema::schema::operation::dsl;
+        diesel::update(dsl::operation)
+            .filter(dsl::id.eq(authz_operation.id()))
+            .filter(dsl::state.eq(OperationState::Running))
+            .filter(dsl::steps_completed.lt(dsl::steps_total))
+            .set((
+                dsl::steps_completed.eq(dsl::steps_completed + 1),
+                dsl::time_modified.eq(Utc::now()),
+            ))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_operation),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Record that a running operation completed, producing the resource
+    /// `result_id`
+    pub async fn operation_succeeded(
+        &self,
+        opctx: &OpContext,
+        authz_operation: &authz::Operation,
+        result_id: Uuid,
+    ) -> UpdateResult<()> {
+        opctx.authorize(authz::Action::Modify, authz_operation).await?;
+
+        let now = Utc::now();
+        use nexus_db_schema::schema::operation::dsl;
+        diesel::update(dsl::operation)
+            .filter(dsl::id.eq(authz_operation.id()))
+            .filter(dsl::state.eq(OperationState::Running))
+            .set((
+                dsl::state.eq(OperationState::Succeeded),
+                dsl::steps_completed.eq(dsl::steps_total),
+                dsl::result_id.eq(result_id),
+                dsl::time_completed.eq(now),
+                dsl::time_modified.eq(now),
+            ))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_operation),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Record that a running operation stopped without completing
+    pub async fn operation_failed(
+        &self,
+        opctx: &OpContext,
+        authz_operation: &authz::Operation,
+        error_message: String,
+    ) -> UpdateResult<()> {
+        opctx.authorize(authz::Action::Modify, authz_operation).await?;
+
+        let now = Utc::now();
+        use nexus_db_schema::schema::operation::dsl;
+        diesel::update(dsl::operation)
+            .filter(dsl::id.eq(authz_operation.id()))
+            .filter(dsl::state.eq(OperationState::Running))
+            .set((
+                dsl::state.eq(OperationState::Failed),
+                dsl::error_message.eq(error_message),
+                dsl::time_completed.eq(now),
+                dsl::time_modified.eq(now),
+            ))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_operation),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Mark as failed every operation created on Nexus `nexus_id` before
+    /// `time_started` that is still running
+    ///
+    /// Operations are carried out in memory by the Nexus that started them, so
+    /// when that Nexus starts up again, whatever it had been running is gone.
+    /// Returns the number of operations that were marked failed.
+    pub async fn operation_fail_abandoned(
+        &self,
+        opctx: &OpContext,
+        nexus_id: OmicronZoneUuid,
+        time_started: DateTime<Utc>,
+    ) -> Result<usize, Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        let now = Utc::now();
+        use nexus_db_schema::schema::operation::dsl;
+        diesel::update(dsl::operation)
+            .filter(dsl::nexus_id.eq(nexus_id.into_untyped_uuid()))
+            .filter(dsl::state.eq(OperationState::Running))
+            .filter(dsl::time_created.lt(time_started))
+            .set((
+                dsl::state.eq(OperationState::Failed),
+                dsl::error_message
+                    .eq("operation was interrupted by a Nexus restart"),
+                dsl::time_completed.eq(now),
+                dsl::time_modified.eq(now),
+            ))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::authz;
+    use crate::db::model::Operation;
+    use crate::db::model::OperationKind;
+    use crate::db::model::OperationState;
+    use crate::db::model::Project;
+    use crate::db::pub_test_utils::TestDatabase;
+    use chrono::Utc;
+    use nexus_db_lookup::LookupPath;
+    use nexus_types::external_api::params;
+    use nexus_types::silo::DEFAULT_SILO_ID;
+    use omicron_common::api::external::DataPageParams;
+    use omicron_common::api::external::IdentityMetadataCreateParams;
+    use omicron_common::api::external::LookupType;
+    use omicron_test_utils::dev;
+    use omicron_uuid_kinds::OmicronZoneUuid;
+    use std::num::NonZeroU32;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_operation_lifecycle() {
+        let logctx = dev::test_setup_log("test_operation_lifecycle");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        let (authz_project, _) = datastore
+            .project_create(
+                &opctx,
+                Project::new(
+                    DEFAULT_SILO_ID,
+                    params::ProjectCreate {
+                        identity: IdentityMetadataCreateParams {
+                            name: "proj".parse().unwrap(),
+                            description: "".to_string(),
+                        },
+                    },
+                ),
+            )
+            .await
+            .unwrap();
+
+        // Create two operations on one Nexus and one on another, then one
+        // more on the first Nexus after it has (notionally) restarted.
+        let nexus_id = OmicronZoneUuid::new_v4();
+        let other_nexus_id = OmicronZoneUuid::new_v4();
+        let mut authz_operations = Vec::new();
+        let mut time_started = None;
+        for (i, nexus_id) in [nexus_id, nexus_id, other_nexus_id, nexus_id]
+            .into_iter()
+            .enumerate()
+        {
+            if i == 3 {
+                time_started = Some(Utc::now());
+            }
+            let operation = datastore
+                .operation_create(
+                    &opctx,
+                    &authz_project,
+                    Operation::new(
+                        Uuid::new_v4(),
+                        authz_project.id(),
+                        nexus_id,
+                        OperationKind::DiskClone,
+                        2,
+                    ),
+                )
+                .await
+                .unwrap();
+            assert_eq!(operation.state, OperationState::Running);
+            authz_operations.push(authz::Operation::new(
+                authz_project.clone(),
+                operation.id,
+                LookupType::ById(operation.id),
+            ));
+        }
+
+        // Steps can't be completed beyond the total.
+        for _ in 0..3 {
+            datastore
+                .operation_step_completed(&opctx, &authz_operations[0])
+                .await
+                .unwrap();
+        }
+        let (.., operation) = LookupPath::new(&opctx, datastore)
+            .operation_id(authz_operations[0].id())
+            .fetch()
+            .await
+            .unwrap();
+        assert_eq!(*operation.steps_completed, 2);
+
+        let result_id = Uuid::new_v4();
+        datastore
+            .operation_succeeded(&opctx, &authz_operations[0], result_id)
+            .await
+            .unwrap();
+
+        // Only the running operation started by the first Nexus before it
+        // restarted is abandoned.
+        let nabandoned = datastore
+            .operation_fail_abandoned(&opctx, nexus_id, time_started.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(nabandoned, 1);
+
+        // A completed operation can't be completed again.
+        datastore
+            .operation_failed(&opctx, &authz_operations[0], "oops".to_string())
+            .await
+            .unwrap();
+
+        let pagparams = DataPageParams {
+            marker: None,
+            limit: NonZeroU32::new(100).unwrap(),
+            direction: dropshot::PaginationOrder::Ascending,
+        };
+        let operations = datastore
+            .operation_list(&opctx, &authz_project, &pagparams)
+            .await
+            .unwrap();
+        assert_eq!(operations.len(), 4);
+        for operation in operations {
+            let i = authz_operations
+                .iter()
+                .position(|authz_op| authz_op.id() == operation.id)
+                .unwrap();
+            match i {
+                0 => {
+                    assert_eq!(operation.state, OperationState::Succeeded);
+                    assert_eq!(operation.result_id, Some(result_id));
+                    assert_eq!(operation.error_message, None);
+                }
+                1 => {
+                    assert_eq!(operation.state, OperationState::Failed);
+                    assert!(operation.time_completed.is_some());
+                    assert!(operation.error_message.is_some());
+                }
+                _ => {
+                    assert_eq!(operation.state, OperationState::Running);
+                    assert_eq!(operation.time_completed, None);
+                }
+            }
+        }
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
@@ -49,6 +49,19 @@ impl From<nexus_db_model::IpKind> for IpKind {
     }
 }
 
+/// Return the IPv4 block of `subnet` that OPTE should use for a probe's
+/// interface, which is the block its address was allocated from.
+fn probe_ipv4_block(
+    subnet: &VpcSubnet,
+    interface: &nexus_db_model::NetworkInterface,
+) -> oxnet::Ipv4Net {
+    match interface.ip.ip() {
+        std::net::IpAddr::V4(ip) => subnet.ipv4_block_containing(ip),
+        std::net::IpAddr::V6(_) => None,
+    }
+    .unwrap_or(subnet.ipv4_block.0)
+}
+
 impl super::DataStore {
     /// List the probes for the given project.
     pub async fn probe_list(
@@ -105,8 +118,9 @@ impl super::DataStore {
                     public_error_from_diesel(e, ErrorHandler::Server)
                 })?;
 
+            let ipv4_block = probe_ipv4_block(&db_subnet, &interface);
             let mut interface: NetworkInterface =
-                interface.into_internal(db_subnet.ipv4_block.0.into());
+                interface.into_internal(ipv4_block.into());
 
             interface.vni = vni.0;
 
@@ -149,8 +163,9 @@ impl super::DataStore {
 
         let vni = self.resolve_vpc_to_vni(opctx, interface.vpc_id).await?;
 
+        let ipv4_block = probe_ipv4_block(&db_subnet, &interface);
         let mut interface: NetworkInterface =
-            interface.into_internal(db_subnet.ipv4_block.0.into());
+            interface.into_internal(ipv4_block.into());
         interface.vni = vni.0;
 
         Ok(ProbeInfo {
@@ -13,8 +13,11 @@ use crate::db::collection_insert::AsyncInsertError;
 use crate::db::collection_insert::DatastoreCollection;
 use crate::db::identity::Resource;
 use crate::db::model::CollectionTypeProvisioned;
+use crate::db::model::IpPoolResourceType;
 use crate::db::model::Name;
 use crate::db::model::Project;
+use crate::db::model::ProjectAutoRestartDefaults;
+use crate::db::model::ProjectEphemeralIpPolicy;
 use crate::db::model::ProjectUpdate;
 use crate::db::model::Silo;
 use crate::db::model::VirtualProvisioningCollection;
@@ -32,6 +35,7 @@ use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::Error;
 use omicron_common::api::external::InternalContext;
 use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::LookupType;
 use omicron_common::api::external::ResourceType;
 use omicron_common::api::external::UpdateResult;
@@ -224,6 +228,8 @@ impl DataStore {
     generate_fn_to_ensure_none_in_project!(floating_ip, name, String);
     generate_fn_to_ensure_none_in_project!(project_image, name, String);
     generate_fn_to_ensure_none_in_project!(snapshot, name, String);
+    generate_fn_to_ensure_none_in_project!(snapshot_schedule, name, String);
+    generate_fn_to_ensure_none_in_project!(snapshot_export);
     generate_fn_to_ensure_none_in_project!(vpc, name, String);
     generate_fn_to_ensure_none_in_project!(affinity_group, name, String);
     generate_fn_to_ensure_none_in_project!(anti_affinity_group, name, String);
@@ -243,6 +249,10 @@ impl DataStore {
         self.ensure_no_floating_ips_in_project(opctx, authz_project).await?;
         self.ensure_no_project_images_in_project(opctx, authz_project).await?;
         self.ensure_no_snapshots_in_project(opctx, authz_project).await?;
+        self.ensure_no_snapshot_schedules_in_project(opctx, authz_project)
+            .await?;
+        self.ensure_no_snapshot_exports_in_project(opctx, authz_project)
+            .await?;
         self.ensure_no_vpcs_in_project(opctx, authz_project).await?;
         self.ensure_no_affinity_groups_in_project(opctx, authz_project).await?;
         self.ensure_no_anti_affinity_groups_in_project(opctx, authz_project)
@@ -289,6 +299,33 @@ impl DataStore {
                         db_project.id(),
                     )
                     .await?;
+
+                    {
+                        use nexus_db_schema::schema::project_quotas::dsl;
+                        diesel::delete(dsl::project_quotas)
+                            .filter(dsl::project_id.eq(db_project.id()))
+                            .execute_async(&conn)
+                            .await?;
+                    }
+                    {
+                        use nexus_db_schema::schema::project_ephemeral_ip_policy::dsl;
+                        diesel::delete(dsl::project_ephemeral_ip_policy)
+                            .filter(dsl::project_id.eq(db_project.id()))
+                            .execute_async(&conn)
+                            .await?;
+                    }
+                    {
+                        // Delete IP pool links (not IP pools, just the links).
+                        use nexus_db_schema::schema::ip_pool_resource::dsl;
+                        diesel::delete(dsl::ip_pool_resource)
+                            .filter(dsl::resource_id.eq(db_project.id()))
+                            .filter(
+                                dsl::resource_type
+                                    .eq(IpPoolResourceType::Project),
+                            )
+                            .execute_async(&conn)
+                            .await?;
+                    }
                     Ok(())
                 }
             })
@@ -354,4 +391,87 @@ impl DataStore {
                 )
             })
     }
+
+    /// Sets the auto-restart defaults for instances in a project (clobbering
+    /// update -- no etag)
+    pub async fn project_auto_restart_defaults_update(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        defaults: ProjectAutoRestartDefaults,
+    ) -> UpdateResult<Project> {
+        opctx.authorize(authz::Action::Modify, authz_project).await?;
+
+        use nexus_db_schema::schema::project::dsl;
+        diesel::update(dsl::project)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(authz_project.id()))
+            .set((defaults, dsl::time_modified.eq(Utc::now())))
+            .returning(Project::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_project),
+                )
+            })
+    }
+
+    /// Fetches the ephemeral IP policy of a project
+    ///
+    /// Projects that have never had a policy set get
+    /// [`ProjectEphemeralIpPolicy::default_for_project()`].
+    pub async fn project_ephemeral_ip_policy_view(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+    ) -> LookupResult<ProjectEphemeralIpPolicy> {
+        opctx.authorize(authz::Action::Read, authz_project).await?;
+
+        use nexus_db_schema::schema::project_ephemeral_ip_policy::dsl;
+        let project_id = authz_project.id();
+        let policy = dsl::project_ephemeral_ip_policy
+            .filter(dsl::project_id.eq(project_id))
+            .select(ProjectEphemeralIpPolicy::as_select())
+            .first_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .optional()
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(policy.unwrap_or_else(|| {
+            ProjectEphemeralIpPolicy::default_for_project(project_id)
+        }))
+    }
+
+    /// Sets the ephemeral IP policy of a project (clobbering update -- no
+    /// etag)
+    pub async fn project_ephemeral_ip_policy_update(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        policy: ProjectEphemeralIpPolicy,
+    ) -> UpdateResult<ProjectEphemeralIpPolicy> {
+        opctx.authorize(authz::Action::Modify, authz_project).await?;
+
+        use nexus_db_schema::schema::project_ephemeral_ip_policy::dsl;
+        diesel::insert_into(dsl::project_ephemeral_ip_policy)
+            .values(policy.clone())
+            .on_conflict(dsl::project_id)
+            .do_update()
+            .set((
+                dsl::time_modified.eq(policy.time_modified),
+                dsl::auto_assign.eq(policy.auto_assign),
+                dsl::ip_pool_id.eq(policy.ip_pool_id),
+                dsl::allow_override.eq(policy.allow_override),
+            ))
+            .returning(ProjectEphemeralIpPolicy::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_project),
+                )
+            })
+    }
 }
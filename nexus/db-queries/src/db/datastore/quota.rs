@@ -7,12 +7,17 @@ use diesel::prelude::*;
 use nexus_db_errors::ErrorHandler;
 use nexus_db_errors::public_error_from_diesel;
 use nexus_db_lookup::DbConnection;
+use nexus_db_model::ProjectQuotas;
+use nexus_db_model::ProjectQuotasUpdate;
 use nexus_db_model::SiloQuotas;
 use nexus_db_model::SiloQuotasUpdate;
+use omicron_common::api::external::CreateResult;
 use omicron_common::api::external::DataPageParams;
 use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::Error;
 use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::LookupType;
 use omicron_common::api::external::ResourceType;
 use omicron_common::api::external::UpdateResult;
 use uuid::Uuid;
@@ -123,4 +128,114 @@ impl DataStore {
             .await
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
     }
+
+    /// Sets quotas for a project, which must not already have any.
+    ///
+    /// Project quotas are managed by the administrators of the project's silo,
+    /// not by the project's own collaborators, so the check here is against
+    /// the silo.
+    pub async fn project_quotas_create(
+        &self,
+        opctx: &OpContext,
+        authz_silo: &authz::Silo,
+        authz_project: &authz::Project,
+        quotas: ProjectQuotas,
+    ) -> CreateResult<ProjectQuotas> {
+        opctx.authorize(authz::Action::Modify, authz_silo).await?;
+        opctx.authorize(authz::Action::Read, authz_project).await?;
+        let project_id = authz_project.id();
+
+        use nexus_db_schema::schema::project_quotas::dsl;
+        diesel::insert_into(dsl::project_quotas)
+            .values(quotas)
+            .returning(ProjectQuotas::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::Conflict(
+                        ResourceType::ProjectQuotas,
+                        &project_id.to_string(),
+                    ),
+                )
+            })
+    }
+
+    pub async fn project_quotas_view(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+    ) -> LookupResult<ProjectQuotas> {
+        opctx.authorize(authz::Action::Read, authz_project).await?;
+        use nexus_db_schema::schema::project_quotas::dsl;
+        let project_id = authz_project.id();
+        dsl::project_quotas
+            .filter(dsl::project_id.eq(project_id))
+            .select(ProjectQuotas::as_select())
+            .first_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByLookup(
+                        ResourceType::ProjectQuotas,
+                        LookupType::ById(project_id),
+                    ),
+                )
+            })
+    }
+
+    pub async fn project_quotas_update(
+        &self,
+        opctx: &OpContext,
+        authz_silo: &authz::Silo,
+        authz_project: &authz::Project,
+        updates: ProjectQuotasUpdate,
+    ) -> UpdateResult<ProjectQuotas> {
+        opctx.authorize(authz::Action::Modify, authz_silo).await?;
+        opctx.authorize(authz::Action::Read, authz_project).await?;
+        use nexus_db_schema::schema::project_quotas::dsl;
+        let project_id = authz_project.id();
+        diesel::update(dsl::project_quotas)
+            .filter(dsl::project_id.eq(project_id))
+            .set(updates)
+            .returning(ProjectQuotas::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByLookup(
+                        ResourceType::ProjectQuotas,
+                        LookupType::ById(project_id),
+                    ),
+                )
+            })
+    }
+
+    /// Removes a project's quotas, leaving it limited only by its silo's.
+    pub async fn project_quotas_delete(
+        &self,
+        opctx: &OpContext,
+        authz_silo: &authz::Silo,
+        authz_project: &authz::Project,
+    ) -> DeleteResult {
+        opctx.authorize(authz::Action::Modify, authz_silo).await?;
+        opctx.authorize(authz::Action::Read, authz_project).await?;
+        use nexus_db_schema::schema::project_quotas::dsl;
+        let project_id = authz_project.id();
+        let deleted = diesel::delete(dsl::project_quotas)
+            .filter(dsl::project_id.eq(project_id))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        if deleted == 0 {
+            return Err(Error::not_found_by_id(
+                ResourceType::ProjectQuotas,
+                &project_id,
+            ));
+        }
+        Ok(())
+    }
 }
@@ -343,7 +343,7 @@ impl DataStore {
                     .await?
                     .map(|(_, sled)| sled.state())
                 {
-                    Some(SledState::Active) => {
+                    Some(SledState::Active | SledState::Quiesced) => {
                         // This allocation is for an active sled; return the
                         // existing allocation.
                         return Ok(
@@ -1052,6 +1052,7 @@ mod test {
     };
     use nexus_sled_agent_shared::inventory::OmicronZoneDataset;
     use nexus_types::deployment::BlueprintHostPhase2DesiredSlots;
+    use nexus_types::deployment::BlueprintScope;
     use nexus_types::deployment::BlueprintSledConfig;
     use nexus_types::deployment::CockroachDbPreserveDowngrade;
     use nexus_types::deployment::PendingMgsUpdates;
@@ -1100,6 +1101,7 @@ mod test {
                 blueprint: Blueprint {
                     id: blueprint_id,
                     sleds: BTreeMap::new(),
+                    scope: BlueprintScope::Fleet,
                     pending_mgs_updates: PendingMgsUpdates::new(),
                     cockroachdb_setting_preserve_downgrade:
                         CockroachDbPreserveDowngrade::DoNotModify,
@@ -1596,6 +1598,7 @@ mod test {
         let blueprint = Blueprint {
             id: blueprint_id,
             sleds: make_sled_config_only_zones(blueprint_zones),
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             cockroachdb_setting_preserve_downgrade:
                 CockroachDbPreserveDowngrade::DoNotModify,
@@ -1864,6 +1867,7 @@ mod test {
         let blueprint = Blueprint {
             id: blueprint_id,
             sleds: make_sled_config_only_zones(blueprint_zones),
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             cockroachdb_setting_preserve_downgrade:
                 CockroachDbPreserveDowngrade::DoNotModify,
@@ -2115,6 +2119,7 @@ mod test {
         let blueprint = Blueprint {
             id: blueprint_id,
             sleds: make_sled_config_only_zones(blueprint_zones),
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             cockroachdb_setting_preserve_downgrade:
                 CockroachDbPreserveDowngrade::DoNotModify,
@@ -2316,6 +2321,7 @@ mod test {
         let blueprint = Blueprint {
             id: blueprint_id,
             sleds: make_sled_config_only_zones(blueprint_zones),
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             cockroachdb_setting_preserve_downgrade:
                 CockroachDbPreserveDowngrade::DoNotModify,
@@ -2460,6 +2466,7 @@ mod test {
         let blueprint = Blueprint {
             id: blueprint_id,
             sleds: make_sled_config_only_zones(blueprint_zones),
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             cockroachdb_setting_preserve_downgrade:
                 CockroachDbPreserveDowngrade::DoNotModify,
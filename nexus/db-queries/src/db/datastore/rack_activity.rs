@@ -0,0 +1,547 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods for the rack activity feed
+//!
+//! The activity feed doesn't have a table of its own. It's assembled from the
+//! records that other parts of the system already keep: the audit log, the
+//! history of blueprint targets, the sleds and physical disks that have been
+//! added to (or removed from) the rack, and the alerts raised for problems in
+//! the system.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::identity::Asset;
+use crate::db::model::Alert;
+use crate::db::model::AlertClass;
+use crate::db::model::AuditLogEntry;
+use crate::db::model::BpTarget;
+use crate::db::model::PhysicalDisk;
+use crate::db::model::Sled;
+use crate::db::pagination::paginated_multicolumn;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
+use chrono::Utc;
+use diesel::prelude::*;
+use dropshot::PaginationOrder;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use nexus_types::external_api::views;
+use nexus_types::external_api::views::RackActivityKind;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_uuid_kinds::BlueprintUuid;
+use omicron_uuid_kinds::GenericUuid;
+use uuid::Uuid;
+
+/// Filters applied when listing the rack activity feed
+#[derive(Clone, Debug)]
+pub struct RackActivityFilters {
+    /// only list activity at or after this time
+    pub start_time: DateTime<Utc>,
+    /// only list activity before this time
+    pub end_time: Option<DateTime<Utc>>,
+    /// only list activity of this kind
+    pub kind: Option<RackActivityKind>,
+}
+
+impl RackActivityFilters {
+    fn includes(&self, kind: RackActivityKind) -> bool {
+        self.kind.is_none_or(|k| k == kind)
+    }
+}
+
+/// One item in the rack activity feed, along with the record it came from
+#[derive(Clone, Debug)]
+pub enum RackActivityRecord {
+    /// a request that was recorded in the audit log
+    AuditLog(AuditLogEntry),
+    /// a blueprint that was made the current target
+    BlueprintTarget(BpTarget),
+    /// a sled that was added to the rack
+    SledAdded(Sled),
+    /// a physical disk that was added to a sled
+    PhysicalDiskAdded(PhysicalDisk),
+    /// a physical disk that was removed from service
+    PhysicalDiskRemoved(PhysicalDisk),
+    /// an alert raised for a problem in the system
+    Fault(Alert),
+}
+
+impl RackActivityRecord {
+    /// When this activity happened
+    pub fn time(&self) -> DateTime<Utc> {
+        match self {
+            Self::AuditLog(entry) => entry.time_completed,
+            Self::BlueprintTarget(target) => target.time_made_target,
+            Self::SledAdded(sled) => sled.time_created(),
+            Self::PhysicalDiskAdded(disk) => disk.time_created(),
+            // Only removed disks are listed as removed, so this fallback is
+            // never used.
+            Self::PhysicalDiskRemoved(disk) => {
+                disk.time_deleted().unwrap_or_else(|| disk.time_modified())
+            }
+            Self::Fault(alert) => alert.time_created(),
+        }
+    }
+
+    /// The ID of the record this activity came from
+    ///
+    /// Together with [`RackActivityRecord::time()`], this uniquely identifies
+    /// an item in the feed.
+    pub fn id(&self) -> Uuid {
+        match self {
+            Self::AuditLog(entry) => entry.id,
+            Self::BlueprintTarget(target) => {
+                target.blueprint_id.into_untyped_uuid()
+            }
+            Self::SledAdded(sled) => sled.id(),
+            Self::PhysicalDiskAdded(disk) | Self::PhysicalDiskRemoved(disk) => {
+                disk.id().into_untyped_uuid()
+            }
+            Self::Fault(alert) => alert.id().into_untyped_uuid(),
+        }
+    }
+}
+
+impl TryFrom<RackActivityRecord> for views::RackActivity {
+    type Error = Error;
+
+    fn try_from(record: RackActivityRecord) -> Result<Self, Self::Error> {
+        let id = record.id();
+        let time = record.time();
+        let (kind, description, details) = match record {
+            RackActivityRecord::AuditLog(entry) => {
+                let entry = views::AuditLogEntry::try_from(entry)?;
+                let outcome = match &entry.result {
+                    views::AuditLogEntryResult::Success { .. } => "succeeded",
+                    views::AuditLogEntryResult::Error { .. } => "failed",
+                    views::AuditLogEntryResult::Unknown => "completed",
+                };
+                (
+                    RackActivityKind::AuditLog,
+                    format!("request `{}` {outcome}", entry.operation_id),
+                    views::RackActivityDetails::AuditLog { entry },
+                )
+            }
+            RackActivityRecord::BlueprintTarget(target) => {
+                let blueprint_id = BlueprintUuid::from(target.blueprint_id);
+                (
+                    RackActivityKind::BlueprintTarget,
+                    format!("blueprint {blueprint_id} made the target"),
+                    views::RackActivityDetails::BlueprintTarget {
+                        blueprint_id,
+                        enabled: target.enabled,
+                    },
+                )
+            }
+            RackActivityRecord::SledAdded(sled) => (
+                RackActivityKind::Hardware,
+                format!("sled {} added", sled.serial_number()),
+                views::RackActivityDetails::SledAdded {
+                    sled_id: sled.id(),
+                    serial: sled.serial_number().to_string(),
+                    part: sled.part_number().to_string(),
+                },
+            ),
+            RackActivityRecord::PhysicalDiskAdded(disk) => (
+                RackActivityKind::Hardware,
+                format!("physical disk {} added", disk.serial),
+                views::RackActivityDetails::PhysicalDiskAdded {
+                    disk_id: disk.id().into_untyped_uuid(),
+                    sled_id: disk.sled_id,
+                    vendor: disk.vendor,
+                    serial: disk.serial,
+                    model: disk.model,
+                },
+            ),
+            RackActivityRecord::PhysicalDiskRemoved(disk) => (
+                RackActivityKind::Hardware,
+                format!("physical disk {} removed", disk.serial),
+                views::RackActivityDetails::PhysicalDiskRemoved {
+                    disk_id: disk.id().into_untyped_uuid(),
+                    sled_id: disk.sled_id,
+                    vendor: disk.vendor,
+                    serial: disk.serial,
+                    model: disk.model,
+                },
+            ),
+            RackActivityRecord::Fault(alert) => (
+                RackActivityKind::Fault,
+                format!("alert raised: {}", alert.class),
+                views::RackActivityDetails::Fault {
+                    alert_class: alert.class.to_string(),
+                    payload: alert.payload,
+                },
+            ),
+        };
+        Ok(Self { id, time, kind, description, details })
+    }
+}
+
+impl DataStore {
+    /// List the rack activity feed, ordered by time and ID.
+    ///
+    /// Each source is paginated separately and the results are merged, so
+    /// that a page of the feed is assembled from at most one page of each
+    /// source. Like the audit log, activity is listed for `start_time <= time
+    /// < end_time`.
+    pub async fn rack_activity_list(
+        &self,
+        opctx: &OpContext,
+        filters: &RackActivityFilters,
+        pagparams: &DataPageParams<'_, (DateTime<Utc>, Uuid)>,
+    ) -> ListResultVec<RackActivityRecord> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        let start_time = filters.start_time;
+        let end_time = filters.end_time;
+        let mut records = Vec::new();
+
+        if filters.includes(RackActivityKind::AuditLog) {
+            records.extend(
+                self.audit_log_list(opctx, pagparams, start_time, end_time)
+                    .await?
+                    .into_iter()
+                    .map(RackActivityRecord::AuditLog),
+            );
+        }
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+
+        if filters.includes(RackActivityKind::BlueprintTarget) {
+            use nexus_db_schema::schema::bp_target::dsl;
+            let mut query = paginated_multicolumn(
+                dsl::bp_target,
+                (dsl::time_made_target, dsl::blueprint_id),
+                pagparams,
+            )
+            .filter(dsl::time_made_target.ge(start_time));
+            if let Some(end_time) = end_time {
+                query = query.filter(dsl::time_made_target.lt(end_time));
+            }
+            records.extend(
+                query
+                    .select(BpTarget::as_select())
+                    .load_async(&*conn)
+                    .await
+                    .map_err(|e| {
+                        public_error_from_diesel(e, ErrorHandler::Server)
+                    })?
+                    .into_iter()
+                    .map(RackActivityRecord::BlueprintTarget),
+            );
+        }
+
+        if filters.includes(RackActivityKind::Hardware) {
+            use nexus_db_schema::schema::physical_disk::dsl as disk_dsl;
+            use nexus_db_schema::schema::sled::dsl as sled_dsl;
+
+            let mut query = paginated_multicolumn(
+                sled_dsl::sled,
+                (sled_dsl::time_created, sled_dsl::id),
+                pagparams,
+            )
+            .filter(sled_dsl::time_created.ge(start_time));
+            if let Some(end_time) = end_time {
+                query = query.filter(sled_dsl::time_created.lt(end_time));
+            }
+            records.extend(
+                query
+                    .select(Sled::as_select())
+                    .load_async(&*conn)
+                    .await
+                    .map_err(|e| {
+                        public_error_from_diesel(e, ErrorHandler::Server)
+                    })?
+                    .into_iter()
+                    .map(RackActivityRecord::SledAdded),
+            );
+
+            let mut query = paginated_multicolumn(
+                disk_dsl::physical_disk,
+                (disk_dsl::time_created, disk_dsl::id),
+                pagparams,
+            )
+            .filter(disk_dsl::time_created.ge(start_time));
+            if let Some(end_time) = end_time {
+                query = query.filter(disk_dsl::time_created.lt(end_time));
+            }
+            records.extend(
+                query
+                    .select(PhysicalDisk::as_select())
+                    .load_async(&*conn)
+                    .await
+                    .map_err(|e| {
+                        public_error_from_diesel(e, ErrorHandler::Server)
+                    })?
+                    .into_iter()
+                    .map(RackActivityRecord::PhysicalDiskAdded),
+            );
+
+            // `time_deleted` is nullable, so this can't use
+            // `paginated_multicolumn()`.
+            let mut query = disk_dsl::physical_disk
+                .filter(disk_dsl::time_deleted.ge(start_time))
+                .into_boxed();
+            if let Some(end_time) = end_time {
+                query = query.filter(disk_dsl::time_deleted.lt(end_time));
+            }
+            query = match (pagparams.direction, pagparams.marker) {
+                (PaginationOrder::Ascending, Some(&(time, id))) => query
+                    .filter(
+                        disk_dsl::time_deleted.gt(time).or(
+                            disk_dsl::time_deleted
+                                .eq(time)
+                                .and(disk_dsl::id.gt(id)),
+                        ),
+                    ),
+                (PaginationOrder::Descending, Some(&(time, id))) => query
+                    .filter(
+                        disk_dsl::time_deleted.lt(time).or(
+                            disk_dsl::time_deleted
+                                .eq(time)
+                                .and(disk_dsl::id.lt(id)),
+                        ),
+                    ),
+                (_, None) => query,
+            };
+            query = match pagparams.direction {
+                PaginationOrder::Ascending => query
+                    .order_by(disk_dsl::time_deleted.asc())
+                    .then_order_by(disk_dsl::id.asc()),
+                PaginationOrder::Descending => query
+                    .order_by(disk_dsl::time_deleted.desc())
+                    .then_order_by(disk_dsl::id.desc()),
+            };
+            records.extend(
+                query
+                    .limit(i64::from(pagparams.limit.get()))
+                    .select(PhysicalDisk::as_select())
+                    .load_async(&*conn)
+                    .await
+                    .map_err(|e| {
+                        public_error_from_diesel(e, ErrorHandler::Server)
+                    })?
+                    .into_iter()
+                    .map(RackActivityRecord::PhysicalDiskRemoved),
+            );
+        }
+
+        if filters.includes(RackActivityKind::Fault) {
+            use nexus_db_schema::schema::alert::dsl;
+            // Probes and test alerts don't describe anything that happened
+            // to the rack.
+            let fault_classes: Vec<_> = AlertClass::ALL_CLASSES
+                .iter()
+                .copied()
+                .filter(|class| !class.is_test() && *class != AlertClass::Probe)
+                .collect();
+            let mut query = paginated_multicolumn(
+                dsl::alert,
+                (dsl::time_created, dsl::id),
+                pagparams,
+            )
+            .filter(dsl::alert_class.eq_any(fault_classes))
+            .filter(dsl::time_created.ge(start_time));
+            if let Some(end_time) = end_time {
+                query = query.filter(dsl::time_created.lt(end_time));
+            }
+            records.extend(
+                query
+                    .select(Alert::as_select())
+                    .load_async(&*conn)
+                    .await
+                    .map_err(|e| {
+                        public_error_from_diesel(e, ErrorHandler::Server)
+                    })?
+                    .into_iter()
+                    .map(RackActivityRecord::Fault),
+            );
+        }
+
+        records.sort_by_key(|record| (record.time(), record.id()));
+        if pagparams.direction == PaginationOrder::Descending {
+            records.reverse();
+        }
+        records.truncate(usize::try_from(pagparams.limit.get()).unwrap());
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::pub_test_utils::TestDatabase;
+    use nexus_db_model::AuditLogActor;
+    use nexus_db_model::AuditLogCompletion;
+    use nexus_db_model::AuditLogEntryInitParams;
+    use nexus_types::deployment::BlueprintTarget;
+    use omicron_test_utils::dev;
+    use omicron_uuid_kinds::AlertUuid;
+    use std::num::NonZeroU32;
+
+    #[tokio::test]
+    async fn test_rack_activity_list() {
+        let logctx = dev::test_setup_log("test_rack_activity_list");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        let start_time = Utc::now();
+
+        // An operator request, recorded in the audit log
+        let entry = datastore
+            .audit_log_entry_init(
+                opctx,
+                AuditLogEntryInitParams {
+                    request_id: "req-1".to_string(),
+                    operation_id: "sled_set_provision_policy".to_string(),
+                    request_uri: "/v1/system/hardware/sleds/x".to_string(),
+                    source_ip: "1.1.1.1".parse().unwrap(),
+                    user_agent: None,
+                    actor: AuditLogActor::Unauthenticated,
+                    auth_method: None,
+                }
+                .into(),
+            )
+            .await
+            .unwrap();
+        datastore
+            .audit_log_entry_complete(
+                opctx,
+                &entry,
+                AuditLogCompletion::Success { http_status_code: 204 }.into(),
+            )
+            .await
+            .unwrap();
+
+        // A new target blueprint
+        let blueprint_id = BlueprintUuid::new_v4();
+        {
+            use nexus_db_schema::schema::bp_target::dsl;
+            diesel::insert_into(dsl::bp_target)
+                .values(BpTarget::new(
+                    1,
+                    BlueprintTarget {
+                        target_id: blueprint_id,
+                        enabled: true,
+                        time_made_target: Utc::now(),
+                    },
+                ))
+                .execute_async(
+                    &*datastore.pool_connection_for_tests().await.unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        // A fault, along with a test alert that isn't one
+        let fault_id = AlertUuid::new_v4();
+        datastore
+            .alert_create(
+                opctx,
+                fault_id,
+                AlertClass::StorageZpoolCapacity,
+                serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+        datastore
+            .alert_create(
+                opctx,
+                AlertUuid::new_v4(),
+                AlertClass::TestFoo,
+                serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+
+        let filters =
+            RackActivityFilters { start_time, end_time: None, kind: None };
+        let pagparams = DataPageParams {
+            marker: None,
+            limit: NonZeroU32::new(100).unwrap(),
+            direction: PaginationOrder::Ascending,
+        };
+        let records = datastore
+            .rack_activity_list(opctx, &filters, &pagparams)
+            .await
+            .unwrap();
+        let ids: Vec<_> = records.iter().map(|r| r.id()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                entry.id,
+                blueprint_id.into_untyped_uuid(),
+                fault_id.into_untyped_uuid(),
+            ]
+        );
+
+        // Pages are assembled from every source, in order.
+        let page_params = |marker, direction| DataPageParams {
+            marker,
+            limit: NonZeroU32::new(1).unwrap(),
+            direction,
+        };
+        let mut paged = Vec::new();
+        let mut marker = None;
+        loop {
+            let page = datastore
+                .rack_activity_list(
+                    opctx,
+                    &filters,
+                    &page_params(marker.as_ref(), PaginationOrder::Ascending),
+                )
+                .await
+                .unwrap();
+            let Some(record) = page.first() else { break };
+            paged.push(record.id());
+            marker = Some((record.time(), record.id()));
+        }
+        assert_eq!(paged, ids);
+
+        let records = datastore
+            .rack_activity_list(
+                opctx,
+                &filters,
+                &page_params(None, PaginationOrder::Descending),
+            )
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id(), fault_id.into_untyped_uuid());
+
+        // Activity can be limited to one source.
+        let filters = RackActivityFilters {
+            kind: Some(RackActivityKind::BlueprintTarget),
+            ..filters
+        };
+        let records = datastore
+            .rack_activity_list(opctx, &filters, &pagparams)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id(), blueprint_id.into_untyped_uuid());
+
+        // Nothing happened before the start time.
+        let filters = RackActivityFilters {
+            start_time: start_time - chrono::TimeDelta::days(1),
+            end_time: Some(start_time),
+            kind: None,
+        };
+        let records = datastore
+            .rack_activity_list(opctx, &filters, &pagparams)
+            .await
+            .unwrap();
+        assert!(
+            records.iter().all(|r| r.time() < start_time),
+            "unexpected activity: {records:?}"
+        );
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
@@ -18,6 +18,7 @@ use diesel::sql_types;
 use nexus_db_errors::ErrorHandler;
 use nexus_db_errors::public_error_from_diesel;
 use nexus_db_model::ReconfiguratorChickenSwitches as DbReconfiguratorChickenSwitches;
+use nexus_db_model::SqlU8;
 use nexus_db_model::SqlU32;
 use nexus_types::deployment::ReconfiguratorChickenSwitchesParam;
 use nexus_types::deployment::ReconfiguratorChickenSwitchesView;
@@ -145,8 +146,9 @@ impl DataStore {
         sql_query(
             r"INSERT INTO reconfigurator_chicken_switches
                 (version, planner_enabled, time_modified,
-                 add_zones_with_mupdate_override)
-              SELECT $1, $2, $3, $4
+                 add_zones_with_mupdate_override, allow_version_skew,
+                 target_nexus_zone_count, target_internal_dns_zone_count)
+              SELECT $1, $2, $3, $4, $5, $6, $7
               WHERE $1 - 1 IN (
                   SELECT COALESCE(MAX(version), 0)
                   FROM reconfigurator_chicken_switches
@@ -158,6 +160,23 @@ impl DataStore {
         .bind::<sql_types::Bool, _>(
             switches.switches.planner_switches.add_zones_with_mupdate_override,
         )
+        .bind::<sql_types::Bool, _>(
+            switches.switches.planner_switches.allow_version_skew,
+        )
+        .bind::<sql_types::Nullable<sql_types::Int2>, _>(
+            switches
+                .switches
+                .planner_switches
+                .target_nexus_zone_count
+                .map(|count| SqlU8::new(count.get())),
+        )
+        .bind::<sql_types::Nullable<sql_types::Int2>, _>(
+            switches
+                .switches
+                .planner_switches
+                .target_internal_dns_zone_count
+                .map(|count| SqlU8::new(count.get())),
+        )
         .execute_async(&*self.pool_connection_authorized(opctx).await?)
         .await
         .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
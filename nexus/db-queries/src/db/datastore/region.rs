@@ -35,8 +35,11 @@ use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::UpdateResult;
 use omicron_uuid_kinds::DatasetUuid;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::SledUuid;
 use omicron_uuid_kinds::VolumeUuid;
 use slog::Logger;
+use std::collections::BTreeMap;
 use std::net::SocketAddrV6;
 use uuid::Uuid;
 
@@ -546,6 +549,109 @@ impl DataStore {
             .await
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
     }
+
+    /// For each volume with regions on `sled_id`'s in-service physical disks,
+    /// count how many of its regions are on other sleds' in-service physical
+    /// disks
+    ///
+    /// This describes how many copies of each volume's data would be left if
+    /// the sled were expunged.
+    pub async fn sled_volume_redundancy(
+        &self,
+        opctx: &OpContext,
+        sled_id: SledUuid,
+    ) -> ListResultVec<SledVolumeRedundancy> {
+        let conn = self.pool_connection_authorized(opctx).await?;
+
+        use nexus_db_schema::schema::crucible_dataset::dsl as dataset_dsl;
+        use nexus_db_schema::schema::physical_disk::dsl as physical_disk_dsl;
+        use nexus_db_schema::schema::region::dsl as region_dsl;
+        use nexus_db_schema::schema::zpool::dsl as zpool_dsl;
+
+        let regions_on_sled: Vec<Region> = region_dsl::region
+            .filter(region_dsl::dataset_id.eq_any(
+                dataset_dsl::crucible_dataset
+                    .filter(dataset_dsl::time_deleted.is_null())
+                    .filter(dataset_dsl::pool_id.eq_any(
+                        zpool_dsl::zpool
+                            .filter(zpool_dsl::time_deleted.is_null())
+                            .filter(zpool_dsl::sled_id.eq(sled_id.into_untyped_uuid()))
+                            .filter(zpool_dsl::physical_disk_id.eq_any(
+                                physical_disk_dsl::physical_disk
+                                    .filter(physical_disk_dsl::disk_policy.eq(PhysicalDiskPolicy::InService))
+                                    .select(physical_disk_dsl::id)
+                            ))
+                            .select(zpool_dsl::id)
+                    ))
+                    .select(dataset_dsl::id)
+            ))
+            .select(Region::as_select())
+            .load_async(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+
+        let mut redundancy: BTreeMap<VolumeUuid, SledVolumeRedundancy> =
+            BTreeMap::new();
+        for region in &regions_on_sled {
+            redundancy
+                .entry(region.volume_id())
+                .or_insert_with(|| SledVolumeRedundancy {
+                    volume_id: region.volume_id(),
+                    regions_on_sled: 0,
+                    regions_elsewhere: 0,
+                })
+                .regions_on_sled += 1;
+        }
+        if redundancy.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let volume_ids: Vec<_> =
+            redundancy.keys().map(|id| to_db_typed_uuid(*id)).collect();
+        let regions_elsewhere: Vec<Region> = region_dsl::region
+            .filter(region_dsl::volume_id.eq_any(volume_ids))
+            .filter(region_dsl::dataset_id.eq_any(
+                dataset_dsl::crucible_dataset
+                    .filter(dataset_dsl::time_deleted.is_null())
+                    .filter(dataset_dsl::pool_id.eq_any(
+                        zpool_dsl::zpool
+                            .filter(zpool_dsl::time_deleted.is_null())
+                            .filter(zpool_dsl::sled_id.ne(sled_id.into_untyped_uuid()))
+                            .filter(zpool_dsl::physical_disk_id.eq_any(
+                                physical_disk_dsl::physical_disk
+                                    .filter(physical_disk_dsl::disk_policy.eq(PhysicalDiskPolicy::InService))
+                                    .select(physical_disk_dsl::id)
+                            ))
+                            .select(zpool_dsl::id)
+                    ))
+                    .select(dataset_dsl::id)
+            ))
+            .select(Region::as_select())
+            .load_async(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        for region in &regions_elsewhere {
+            if let Some(volume) = redundancy.get_mut(&region.volume_id()) {
+                volume.regions_elsewhere += 1;
+            }
+        }
+
+        Ok(redundancy.into_values().collect())
+    }
+}
+
+/// How many regions of a volume are on a particular sled, and how many are
+/// elsewhere
+///
+/// See [`DataStore::sled_volume_redundancy()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SledVolumeRedundancy {
+    pub volume_id: VolumeUuid,
+    /// Number of the volume's regions on the sled's in-service physical disks
+    pub regions_on_sled: usize,
+    /// Number of the volume's regions on other sleds' in-service physical
+    /// disks
+    pub regions_elsewhere: usize,
 }
 
 #[cfg(test)]
@@ -50,7 +50,9 @@ impl DataStore {
             ))
             .do_nothing()
             .execute_async(&*conn)
-            .await
+            .await;
+        self.caches.role_assignments.invalidate();
+        let count = count
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
         info!(opctx.log, "created {} built-in role assignments", count);
         Ok(())
@@ -155,18 +157,20 @@ impl DataStore {
         // complicated by the cloning semantics of the queries, which
         // must be Clone to be retried.
         let conn = self.pool_connection_authorized(opctx).await?;
-        self.transaction_non_retry_wrapper("role_assignment_replace_visible")
+        let result = self
+            .transaction_non_retry_wrapper("role_assignment_replace_visible")
             .transaction(&conn, |conn| async move {
                 delete_old_query.execute_async(&conn).await?;
                 Ok(insert_new_query.get_results_async(&conn).await?)
             })
-            .await
-            .map_err(|e| match e {
-                TransactionError::CustomError(e) => e,
-                TransactionError::Database(e) => {
-                    public_error_from_diesel(e, ErrorHandler::Server)
-                }
-            })
+            .await;
+        self.caches.role_assignments.invalidate();
+        result.map_err(|e| match e {
+            TransactionError::CustomError(e) => e,
+            TransactionError::Database(e) => {
+                public_error_from_diesel(e, ErrorHandler::Server)
+            }
+        })
     }
 
     pub async fn role_assignment_replace_visible_queries<T>(
@@ -0,0 +1,580 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods on [`RollingSledReboot`]s.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::model::RollingSledReboot;
+use crate::db::model::RollingSledRebootInstance;
+use crate::db::model::RollingSledRebootSled;
+use crate::db::model::RollingSledRebootState;
+use crate::db::model::RollingSledRebootStep;
+use crate::db::model::to_db_typed_uuid;
+use crate::db::pagination::paginated;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::OptionalError;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::UpdateResult;
+use omicron_uuid_kinds::InstanceUuid;
+use omicron_uuid_kinds::SledUuid;
+use uuid::Uuid;
+
+impl DataStore {
+    /// Records a new rolling reboot of `sled_ids`, which are rebooted in the
+    /// order given.
+    ///
+    /// Only one rolling reboot may run at a time: this fails with a conflict
+    /// if another one hasn't finished yet.
+    pub async fn rolling_sled_reboot_create(
+        &self,
+        opctx: &OpContext,
+        reboot: RollingSledReboot,
+        sled_ids: &[SledUuid],
+    ) -> CreateResult<RollingSledReboot> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        let sleds = sled_ids
+            .iter()
+            .enumerate()
+            .map(|(position, &sled_id)| {
+                let position = u16::try_from(position).map_err(|_| {
+                    Error::invalid_request("too many sleds to reboot")
+                })?;
+                Ok(RollingSledRebootSled::new(&reboot, sled_id, position))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let err = OptionalError::new();
+        let conn = self.pool_connection_authorized(opctx).await?;
+        self.transaction_retry_wrapper("rolling_sled_reboot_create")
+            .transaction(&conn, |conn| {
+                let err = err.clone();
+                let reboot = reboot.clone();
+                let sleds = sleds.clone();
+                async move {
+                    use nexus_db_schema::schema::rolling_sled_reboot::dsl;
+                    use nexus_db_schema::schema::rolling_sled_reboot_sled::dsl as sled_dsl;
+
+                    let running = dsl::rolling_sled_reboot
+                        .filter(dsl::state.eq(RollingSledRebootState::Running))
+                        .select(dsl::id)
+                        .first_async::<Uuid>(&conn)
+                        .await
+                        .optional()?;
+                    if let Some(running) = running {
+                        return Err(err.bail(Error::conflict(format!(
+                            "rolling sled reboot {running} is still running"
+                        ))));
+                    }
+
+                    diesel::insert_into(dsl::rolling_sled_reboot)
+                        .values(reboot.clone())
+                        .execute_async(&conn)
+                        .await?;
+                    diesel::insert_into(sled_dsl::rolling_sled_reboot_sled)
+                        .values(sleds)
+                        .execute_async(&conn)
+                        .await?;
+                    Ok(reboot)
+                }
+            })
+            .await
+            .map_err(|e| match err.take() {
+                Some(err) => err,
+                None => public_error_from_diesel(e, ErrorHandler::Server),
+            })
+    }
+
+    /// Fetches a rolling reboot by ID.
+    pub async fn rolling_sled_reboot_fetch(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+    ) -> LookupResult<RollingSledReboot> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot::dsl;
+        dsl::rolling_sled_reboot
+            .filter(dsl::id.eq(reboot_id))
+            .select(RollingSledReboot::as_select())
+            .first_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .optional()
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?
+            .ok_or_else(|| {
+                Error::non_resourcetype_not_found(format!(
+                    "rolling sled reboot {reboot_id}"
+                ))
+            })
+    }
+
+    /// Returns the rolling reboot that is currently running, if any.
+    pub async fn rolling_sled_reboot_get_running(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<Option<RollingSledReboot>, Error> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot::dsl;
+        dsl::rolling_sled_reboot
+            .filter(dsl::state.eq(RollingSledRebootState::Running))
+            .select(RollingSledReboot::as_select())
+            .first_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .optional()
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Lists one page of rolling reboots, ordered by ID.
+    pub async fn rolling_sled_reboot_list(
+        &self,
+        opctx: &OpContext,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<RollingSledReboot> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot::dsl;
+        paginated(dsl::rolling_sled_reboot, dsl::id, pagparams)
+            .select(RollingSledReboot::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Asks for a running rolling reboot to be cancelled.
+    ///
+    /// No further sleds are started on. Sleds that are already being drained
+    /// are returned to service, while sleds that have already been reset are
+    /// seen through to rejoining. This is idempotent, but fails with a
+    /// conflict if the reboot has already finished.
+    pub async fn rolling_sled_reboot_request_cancel(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+    ) -> UpdateResult<RollingSledReboot> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot::dsl;
+        diesel::update(dsl::rolling_sled_reboot)
+            .filter(dsl::id.eq(reboot_id))
+            .filter(dsl::state.eq(RollingSledRebootState::Running))
+            .filter(dsl::time_cancel_requested.is_null())
+            .set(dsl::time_cancel_requested.eq(Utc::now()))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+
+        let reboot = self.rolling_sled_reboot_fetch(opctx, reboot_id).await?;
+        if reboot.state != RollingSledRebootState::Running {
+            return Err(Error::conflict(format!(
+                "rolling sled reboot {reboot_id} has already finished"
+            )));
+        }
+        Ok(reboot)
+    }
+
+    /// Marks a running rolling reboot as finished, in `state`.
+    ///
+    /// Returns `false` if the reboot had already finished.
+    pub async fn rolling_sled_reboot_finish(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        state: RollingSledRebootState,
+    ) -> Result<bool, Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot::dsl;
+        let updated = diesel::update(dsl::rolling_sled_reboot)
+            .filter(dsl::id.eq(reboot_id))
+            .filter(dsl::state.eq(RollingSledRebootState::Running))
+            .set((dsl::state.eq(state), dsl::time_finished.eq(Utc::now())))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(updated != 0)
+    }
+
+    /// Lists the sleds of a rolling reboot, in the order in which they are
+    /// rebooted.
+    pub async fn rolling_sled_reboot_sled_list(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+    ) -> ListResultVec<RollingSledRebootSled> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot_sled::dsl;
+        dsl::rolling_sled_reboot_sled
+            .filter(dsl::reboot_id.eq(reboot_id))
+            .order_by(dsl::position)
+            .select(RollingSledRebootSled::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Moves a sled of a rolling reboot from step `from` to step `to`,
+    /// recording `error` as the reason if the sled failed.
+    ///
+    /// Returns `false` if the sled was no longer at step `from`, e.g., because
+    /// another Nexus moved it along first.
+    pub async fn rolling_sled_reboot_sled_set_step(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled_id: SledUuid,
+        from: RollingSledRebootStep,
+        to: RollingSledRebootStep,
+        error: Option<String>,
+    ) -> Result<bool, Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot_sled::dsl;
+        let updated = diesel::update(dsl::rolling_sled_reboot_sled)
+            .filter(dsl::reboot_id.eq(reboot_id))
+            .filter(dsl::sled_id.eq(to_db_typed_uuid(sled_id)))
+            .filter(dsl::step.eq(from))
+            .set((
+                dsl::step.eq(to),
+                dsl::time_step_changed.eq(Utc::now()),
+                dsl::error.eq(error),
+            ))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(updated != 0)
+    }
+
+    /// Moves a sled of a rolling reboot from rebooting to rejoining,
+    /// recording that its SP is being asked to reset it at `time_reset`.
+    ///
+    /// Returns `false` if the sled was no longer rebooting. Only the caller
+    /// that gets `true` should go on to reset the sled.
+    pub async fn rolling_sled_reboot_sled_reset(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled_id: SledUuid,
+        time_reset: DateTime<Utc>,
+    ) -> Result<bool, Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot_sled::dsl;
+        let updated = diesel::update(dsl::rolling_sled_reboot_sled)
+            .filter(dsl::reboot_id.eq(reboot_id))
+            .filter(dsl::sled_id.eq(to_db_typed_uuid(sled_id)))
+            .filter(dsl::step.eq(RollingSledRebootStep::Rebooting))
+            .set((
+                dsl::step.eq(RollingSledRebootStep::Rejoining),
+                dsl::time_step_changed.eq(time_reset),
+                dsl::time_reset.eq(time_reset),
+            ))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(updated != 0)
+    }
+
+    /// Records that a rolling reboot stopped an instance on `sled_id`, so
+    /// that it can be started again once the sled is back.
+    ///
+    /// An instance that was already stopped (and restarted) earlier in the
+    /// same reboot is recorded as stopped again.
+    pub async fn rolling_sled_reboot_instance_stopped(
+        &self,
+        opctx: &OpContext,
+        instance: RollingSledRebootInstance,
+    ) -> Result<(), Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot_instance::dsl;
+        diesel::insert_into(dsl::rolling_sled_reboot_instance)
+            .values(instance)
+            .on_conflict((dsl::reboot_id, dsl::instance_id))
+            .do_update()
+            .set((
+                dsl::sled_id.eq(excluded(dsl::sled_id)),
+                dsl::time_stopped.eq(excluded(dsl::time_stopped)),
+                dsl::time_restarted.eq(excluded(dsl::time_restarted)),
+            ))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(())
+    }
+
+    /// Records that an instance stopped by a rolling reboot has been started
+    /// again (or no longer needs to be).
+    pub async fn rolling_sled_reboot_instance_restarted(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        instance_id: InstanceUuid,
+    ) -> Result<(), Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot_instance::dsl;
+        diesel::update(dsl::rolling_sled_reboot_instance)
+            .filter(dsl::reboot_id.eq(reboot_id))
+            .filter(dsl::instance_id.eq(to_db_typed_uuid(instance_id)))
+            .filter(dsl::time_restarted.is_null())
+            .set(dsl::time_restarted.eq(Utc::now()))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(())
+    }
+
+    /// Lists the instances a rolling reboot has stopped.
+    pub async fn rolling_sled_reboot_instance_list(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+    ) -> ListResultVec<RollingSledRebootInstance> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::rolling_sled_reboot_instance::dsl;
+        dsl::rolling_sled_reboot_instance
+            .filter(dsl::reboot_id.eq(reboot_id))
+            .order_by(dsl::instance_id)
+            .select(RollingSledRebootInstance::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pub_test_utils::TestDatabase;
+    use omicron_test_utils::dev;
+
+    #[tokio::test]
+    async fn test_rolling_sled_reboot() {
+        let logctx = dev::test_setup_log("test_rolling_sled_reboot");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        assert!(
+            datastore
+                .rolling_sled_reboot_get_running(opctx)
+                .await
+                .expect("looked for running reboot")
+                .is_none()
+        );
+
+        let sleds = [SledUuid::new_v4(), SledUuid::new_v4()];
+        let reboot = datastore
+            .rolling_sled_reboot_create(
+                opctx,
+                RollingSledReboot::new(1),
+                &sleds,
+            )
+            .await
+            .expect("created reboot");
+        let running = datastore
+            .rolling_sled_reboot_get_running(opctx)
+            .await
+            .expect("looked for running reboot")
+            .expect("reboot is running");
+        assert_eq!(running.id, reboot.id);
+
+        // Another reboot can't start while this one is running.
+        let error = datastore
+            .rolling_sled_reboot_create(
+                opctx,
+                RollingSledReboot::new(1),
+                &sleds,
+            )
+            .await
+            .expect_err("created a second reboot");
+        assert!(matches!(error, Error::Conflict { .. }), "{error:?}");
+
+        // Sleds are listed in order, and start out pending.
+        let listed = datastore
+            .rolling_sled_reboot_sled_list(opctx, reboot.id)
+            .await
+            .expect("listed sleds");
+        assert_eq!(
+            listed.iter().map(|sled| sled.sled_id()).collect::<Vec<_>>(),
+            sleds
+        );
+        assert!(
+            listed
+                .iter()
+                .all(|sled| sled.step == RollingSledRebootStep::Pending)
+        );
+
+        // Steps only move forward from where the caller expects them to be.
+        let (reboot_id, sled_id) = (reboot.id, sleds[0]);
+        for (from, to, expected) in [
+            (
+                RollingSledRebootStep::Pending,
+                RollingSledRebootStep::Draining,
+                true,
+            ),
+            (
+                RollingSledRebootStep::Pending,
+                RollingSledRebootStep::Draining,
+                false,
+            ),
+            (
+                RollingSledRebootStep::Draining,
+                RollingSledRebootStep::Rebooting,
+                true,
+            ),
+        ] {
+            assert_eq!(
+                datastore
+                    .rolling_sled_reboot_sled_set_step(
+                        opctx, reboot_id, sled_id, from, to, None
+                    )
+                    .await
+                    .expect("set step"),
+                expected,
+                "{from:?} -> {to:?}"
+            );
+        }
+        let time_reset = Utc::now();
+        assert!(
+            datastore
+                .rolling_sled_reboot_sled_reset(
+                    opctx, reboot_id, sled_id, time_reset
+                )
+                .await
+                .expect("reset sled")
+        );
+        assert!(
+            !datastore
+                .rolling_sled_reboot_sled_reset(
+                    opctx, reboot_id, sled_id, time_reset
+                )
+                .await
+                .expect("reset sled")
+        );
+        let listed = datastore
+            .rolling_sled_reboot_sled_list(opctx, reboot_id)
+            .await
+            .expect("listed sleds");
+        assert_eq!(listed[0].step, RollingSledRebootStep::Rejoining);
+        assert!(listed[0].time_reset.is_some());
+
+        // Stopped instances are recorded until they're restarted.
+        let instance_id = InstanceUuid::new_v4();
+        datastore
+            .rolling_sled_reboot_instance_stopped(
+                opctx,
+                RollingSledRebootInstance::new(reboot_id, instance_id, sled_id),
+            )
+            .await
+            .expect("recorded stopped instance");
+        datastore
+            .rolling_sled_reboot_instance_restarted(
+                opctx,
+                reboot_id,
+                instance_id,
+            )
+            .await
+            .expect("recorded restarted instance");
+        let instances = datastore
+            .rolling_sled_reboot_instance_list(opctx, reboot_id)
+            .await
+            .expect("listed instances");
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].instance_id(), instance_id);
+        assert!(instances[0].time_restarted.is_some());
+
+        // Stopping the same instance again later in the reboot starts its
+        // record over.
+        datastore
+            .rolling_sled_reboot_instance_stopped(
+                opctx,
+                RollingSledRebootInstance::new(
+                    reboot_id,
+                    instance_id,
+                    sleds[1],
+                ),
+            )
+            .await
+            .expect("recorded stopped instance");
+        let instances = datastore
+            .rolling_sled_reboot_instance_list(opctx, reboot_id)
+            .await
+            .expect("listed instances");
+        assert_eq!(instances.len(), 1);
+        assert!(instances[0].time_restarted.is_none());
+
+        // Cancelling is idempotent until the reboot finishes.
+        for _ in 0..2 {
+            let reboot = datastore
+                .rolling_sled_reboot_request_cancel(opctx, reboot_id)
+                .await
+                .expect("requested cancellation");
+            assert!(reboot.time_cancel_requested.is_some());
+        }
+        assert!(
+            datastore
+                .rolling_sled_reboot_finish(
+                    opctx,
+                    reboot_id,
+                    RollingSledRebootState::Cancelled
+                )
+                .await
+                .expect("finished reboot")
+        );
+        assert!(
+            !datastore
+                .rolling_sled_reboot_finish(
+                    opctx,
+                    reboot_id,
+                    RollingSledRebootState::Completed
+                )
+                .await
+                .expect("finished reboot")
+        );
+        let error = datastore
+            .rolling_sled_reboot_request_cancel(opctx, reboot_id)
+            .await
+            .expect_err("cancelled finished reboot");
+        assert!(matches!(error, Error::Conflict { .. }), "{error:?}");
+        let reboot = datastore
+            .rolling_sled_reboot_fetch(opctx, reboot_id)
+            .await
+            .expect("fetched reboot");
+        assert_eq!(reboot.state, RollingSledRebootState::Cancelled);
+        assert!(reboot.time_finished.is_some());
+
+        // With that one finished, another reboot can start.
+        datastore
+            .rolling_sled_reboot_create(
+                opctx,
+                RollingSledReboot::new(2),
+                &sleds,
+            )
+            .await
+            .expect("created another reboot");
+        let reboots = datastore
+            .rolling_sled_reboot_list(opctx, &DataPageParams::max_page())
+            .await
+            .expect("listed reboots");
+        assert_eq!(reboots.len(), 2);
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
@@ -547,6 +547,8 @@ impl DataStore {
                     public_error_from_diesel(e, ErrorHandler::Server)
                 })?;
 
+        self.caches.role_assignments.invalidate();
+
         debug!(
             opctx.log,
             "deleted {} silo group memberships for silo {}", updated_rows, id
@@ -619,6 +621,8 @@ impl DataStore {
             .await
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
 
+        self.caches.ip_pools.invalidate();
+
         debug!(
             opctx.log,
             "deleted {} IP pool links for silo {}", updated_rows, id
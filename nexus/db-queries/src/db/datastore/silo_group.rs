@@ -160,7 +160,8 @@ impl DataStore {
 
         let conn = self.pool_connection_authorized(opctx).await?;
 
-        self.transaction_retry_wrapper("silo_group_membership_replace_for_user")
+        let result = self
+            .transaction_retry_wrapper("silo_group_membership_replace_for_user")
             .transaction(&conn, |conn| {
                 let silo_group_ids = silo_group_ids.clone();
                 async move {
@@ -195,8 +196,9 @@ impl DataStore {
                     Ok(())
                 }
             })
-            .await
-            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+            .await;
+        self.caches.role_assignments.invalidate();
+        result.map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
     }
 
     pub async fn silo_group_delete(
@@ -94,7 +94,8 @@ impl DataStore {
         let authz_silo_user_id = authz_silo_user.id();
 
         let conn = self.pool_connection_authorized(opctx).await?;
-        self.transaction_retry_wrapper("silo_user_delete")
+        let result = self
+            .transaction_retry_wrapper("silo_user_delete")
             .transaction(&conn, |conn| async move {
                 // Delete the user record.
                 {
@@ -164,14 +165,15 @@ impl DataStore {
 
                 Ok(())
             })
-            .await
-            .map_err(|e| {
-                public_error_from_diesel(
-                    e,
-                    ErrorHandler::NotFoundByResource(authz_silo_user),
-                )
-                .internal_context("deleting silo user")
-            })
+            .await;
+        self.caches.role_assignments.invalidate();
+        result.map_err(|e| {
+            public_error_from_diesel(
+                e,
+                ErrorHandler::NotFoundByResource(authz_silo_user),
+            )
+            .internal_context("deleting silo user")
+        })
     }
 
     /// Given an external ID, return
@@ -466,7 +468,9 @@ impl DataStore {
             ))
             .do_nothing()
             .execute_async(&*self.pool_connection_authorized(opctx).await?)
-            .await
+            .await;
+        self.caches.role_assignments.invalidate();
+        let count = count
             .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
         info!(opctx.log, "created {} silo user role assignments", count);
 
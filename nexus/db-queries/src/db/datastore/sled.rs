@@ -18,6 +18,7 @@ use crate::db::model::SledUpdate;
 use crate::db::model::to_db_sled_policy;
 use crate::db::pagination::Paginator;
 use crate::db::pagination::paginated;
+use crate::db::queries::sled_reservation::sled_capacity_query;
 use crate::db::queries::sled_reservation::sled_find_targets_query;
 use crate::db::queries::sled_reservation::sled_insert_resource_query;
 use crate::db::update_and_check::{UpdateAndCheck, UpdateStatus};
@@ -31,9 +32,12 @@ use nexus_db_errors::public_error_from_diesel;
 use nexus_db_lookup::DbConnection;
 use nexus_db_model::ApplySledFilterExt;
 use nexus_types::deployment::SledFilter;
+use nexus_types::deployment::SledInstanceUsage;
 use nexus_types::external_api::views::SledPolicy;
 use nexus_types::external_api::views::SledProvisionPolicy;
 use nexus_types::identity::Asset;
+use nexus_types::internal_api::views::SledPlacementExplanation;
+use nexus_types::internal_api::views::SledPlacementRejection;
 use omicron_common::api::external;
 use omicron_common::api::external::CreateResult;
 use omicron_common::api::external::DataPageParams;
@@ -47,6 +51,8 @@ use omicron_uuid_kinds::InstanceUuid;
 use omicron_uuid_kinds::PropolisUuid;
 use omicron_uuid_kinds::SledUuid;
 use slog::Logger;
+use slog_error_chain::InlineErrorChain;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::fmt;
 use strum::IntoEnumIterator;
@@ -86,8 +92,21 @@ enum SledReservationError {
 
 impl From<SledReservationError> for external::Error {
     fn from(err: SledReservationError) -> Self {
-        let msg = format!("Failed to place instance: {err}");
-        match err {
+        err.into_external_error(None)
+    }
+}
+
+impl SledReservationError {
+    /// Converts this error into an external one
+    ///
+    /// If there's a shortage of capacity, `diagnostics` (when available)
+    /// explains what kept the instance off of each sled.
+    fn into_external_error(
+        self,
+        diagnostics: Option<&SledPlacementDiagnostics>,
+    ) -> external::Error {
+        let msg = format!("Failed to place instance: {self}");
+        match self {
             // "NotFound" can be resolved by adding more capacity
             SledReservationError::NotFound
             // "RequiredAffinitySledNotValid" is *usually* the result of a
@@ -100,7 +119,13 @@ impl From<SledReservationError> for external::Error {
             // (Disambiguating these cases would require additional database
             // queries, hence why this isn't being done right now)
             | SledReservationError::RequiredAffinitySledNotValid => {
-                external::Error::insufficient_capacity(&msg, &msg)
+                match diagnostics {
+                    Some(diagnostics) => external::Error::insufficient_capacity(
+                        format!("{msg}. {diagnostics}"),
+                        format!("{msg}. {}", diagnostics.display_by_sled()),
+                    ),
+                    None => external::Error::insufficient_capacity(&msg, &msg),
+                }
             },
             // The following cases are constraint violations due to excessive
             // affinity/anti-affinity groups -- even if additional capacity is
@@ -112,6 +137,208 @@ impl From<SledReservationError> for external::Error {
             },
         }
     }
+
+    /// Returns whether this error could be explained by
+    /// [`SledPlacementDiagnostics`]
+    fn is_capacity_shortage(&self) -> bool {
+        match self {
+            SledReservationError::NotFound
+            | SledReservationError::RequiredAffinitySledNotValid => true,
+            SledReservationError::TooManyAffinityConstraints
+            | SledReservationError::ConflictingAntiAndAffinityConstraints => {
+                false
+            }
+        }
+    }
+}
+
+/// The most sleds whose shortfalls are listed in the external message of a
+/// placement failure
+const MAX_SHORTFALLS_REPORTED: usize = 8;
+
+/// How much more of each resource a sled would need to fit a VMM
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SledShortfall {
+    hardware_threads: u64,
+    reservoir_ram: external::ByteCount,
+    rss_ram: external::ByteCount,
+    sled_id: SledUuid,
+}
+
+impl fmt::Display for SledShortfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        match self.hardware_threads {
+            0 => (),
+            1 => parts.push(String::from("1 vCPU")),
+            n => parts.push(format!("{n} vCPUs")),
+        }
+        if self.reservoir_ram.to_bytes() > 0 {
+            parts.push(format!("{} of memory", self.reservoir_ram));
+        }
+        if self.rss_ram.to_bytes() > 0 {
+            parts.push(format!("{} of host memory", self.rss_ram));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Explains why a VMM could not be placed on any sled
+///
+/// This is gathered after placement fails, so it's a best-effort account of
+/// the sleds that were considered.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct SledPlacementDiagnostics {
+    /// Number of sleds on which VMMs may be placed
+    sleds_considered: usize,
+    /// Sleds without room for the VMM, from the smallest shortfall to the
+    /// largest
+    shortfalls: Vec<SledShortfall>,
+    /// Sleds that had room for the VMM, but were ruled out by an
+    /// anti-affinity group with the "fail" policy
+    anti_affinity_excluded: Vec<SledUuid>,
+}
+
+impl SledPlacementDiagnostics {
+    fn new(
+        capacity: impl IntoIterator<Item = SledCapacityRow>,
+        resources: &db::model::Resources,
+        banned: &HashSet<SledUuid>,
+    ) -> Self {
+        let mut diagnostics = SledPlacementDiagnostics::default();
+        for row in capacity {
+            diagnostics.sleds_considered += 1;
+            let sled_id = SledUuid::from_untyped_uuid(row.sled_id);
+            if let Some(shortfall) = row.shortfall(resources) {
+                diagnostics.shortfalls.push(shortfall);
+            } else if banned.contains(&sled_id) {
+                diagnostics.anti_affinity_excluded.push(sled_id);
+            }
+        }
+        diagnostics.shortfalls.sort();
+        diagnostics.anti_affinity_excluded.sort();
+        diagnostics
+    }
+
+    /// Returns a description of these diagnostics that identifies each sled
+    ///
+    /// Sled IDs aren't meaningful to the users that start instances, so the
+    /// `Display` implementation leaves them out; this is for operators.
+    fn display_by_sled(&self) -> impl fmt::Display + '_ {
+        struct BySled<'a>(&'a SledPlacementDiagnostics);
+        impl fmt::Display for BySled<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let diagnostics = self.0;
+                diagnostics.fmt_summary(f)?;
+                let mut reasons = Vec::new();
+                for shortfall in &diagnostics.shortfalls {
+                    reasons.push(format!(
+                        "sled {} is short {shortfall}",
+                        shortfall.sled_id
+                    ));
+                }
+                for sled_id in &diagnostics.anti_affinity_excluded {
+                    reasons.push(format!(
+                        "sled {sled_id} is excluded by anti-affinity"
+                    ));
+                }
+                if !reasons.is_empty() {
+                    write!(f, ". {}", reasons.join("; "))?;
+                }
+                Ok(())
+            }
+        }
+        BySled(self)
+    }
+
+    fn fmt_summary(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sleds considered: {}, without enough room: {}, excluded by \
+             anti-affinity: {}",
+            self.sleds_considered,
+            self.shortfalls.len(),
+            self.anti_affinity_excluded.len(),
+        )
+    }
+}
+
+impl fmt::Display for SledPlacementDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_summary(f)?;
+        if self.shortfalls.is_empty() {
+            return Ok(());
+        }
+        let shortfalls: Vec<_> = self
+            .shortfalls
+            .iter()
+            .take(MAX_SHORTFALLS_REPORTED)
+            .map(|shortfall| shortfall.to_string())
+            .collect();
+        write!(f, ". Shortfall by sled: {}", shortfalls.join("; "))?;
+        let omitted = self.shortfalls.len() - shortfalls.len();
+        if omitted > 0 {
+            write!(f, " (and {omitted} more)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A row returned by [`sled_capacity_query`]
+struct SledCapacityRow {
+    sled_id: Uuid,
+    usable_hardware_threads: i64,
+    usable_physical_ram: i64,
+    reservoir_size: i64,
+    used_hardware_threads: i64,
+    used_rss_ram: i64,
+    used_reservoir_ram: i64,
+}
+
+impl SledCapacityRow {
+    /// Returns how much more of each resource this sled would need to fit a
+    /// VMM using `resources`, or `None` if the VMM fits
+    fn shortfall(
+        &self,
+        resources: &db::model::Resources,
+    ) -> Option<SledShortfall> {
+        let shortfall = |needed: u64, used: i64, usable: i64| -> u64 {
+            let available =
+                u64::try_from(usable.saturating_sub(used)).unwrap_or(0);
+            needed.saturating_sub(available)
+        };
+        let byte_count = |bytes: u64| {
+            // A shortfall can't exceed the size of the request, which is
+            // itself a valid byte count.
+            external::ByteCount::try_from(bytes)
+                .expect("shortfall is no larger than the request")
+        };
+
+        let hardware_threads = shortfall(
+            u64::from(resources.hardware_threads.0),
+            self.used_hardware_threads,
+            self.usable_hardware_threads,
+        );
+        let rss_ram = shortfall(
+            resources.rss_ram.to_bytes(),
+            self.used_rss_ram,
+            self.usable_physical_ram,
+        );
+        let reservoir_ram = shortfall(
+            resources.reservoir_ram.to_bytes(),
+            self.used_reservoir_ram,
+            self.reservoir_size,
+        );
+        if hardware_threads == 0 && rss_ram == 0 && reservoir_ram == 0 {
+            return None;
+        }
+        Some(SledShortfall {
+            hardware_threads,
+            reservoir_ram: byte_count(reservoir_ram),
+            rss_ram: byte_count(rss_ram),
+            sled_id: SledUuid::from_untyped_uuid(self.sled_id),
+        })
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -424,21 +651,221 @@ impl DataStore {
         resources: db::model::Resources,
         constraints: db::model::SledReservationConstraints,
     ) -> CreateResult<db::model::SledResourceVmm> {
-        self.sled_reservation_create_inner(
-            opctx,
-            instance_id,
-            propolis_id,
-            resources,
-            constraints,
-        )
-        .await
-        .map_err(|e| match e {
-            SledReservationTransactionError::Connection(e) => e,
-            SledReservationTransactionError::Diesel(e) => {
-                public_error_from_diesel(e, ErrorHandler::Server)
+        let result = self
+            .sled_reservation_create_inner(
+                opctx,
+                instance_id,
+                propolis_id,
+                resources.clone(),
+                constraints,
+            )
+            .await;
+        match result {
+            Ok(resource) => Ok(resource),
+            Err(SledReservationTransactionError::Connection(e)) => Err(e),
+            Err(SledReservationTransactionError::Diesel(e)) => {
+                Err(public_error_from_diesel(e, ErrorHandler::Server))
             }
-            SledReservationTransactionError::Reservation(e) => e.into(),
-        })
+            Err(SledReservationTransactionError::Reservation(e))
+                if e.is_capacity_shortage() =>
+            {
+                let diagnostics = self
+                    .sled_placement_diagnostics(opctx, instance_id, &resources)
+                    .await
+                    .inspect_err(|error| {
+                        warn!(
+                            opctx.log,
+                            "failed to gather sled placement diagnostics";
+                            InlineErrorChain::new(error),
+                        );
+                    })
+                    .ok();
+                Err(e.into_external_error(diagnostics.as_ref()))
+            }
+            Err(SledReservationTransactionError::Reservation(e)) => {
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Explains why a VMM using `resources` for `instance_id` couldn't be
+    /// placed on any sled
+    async fn sled_placement_diagnostics(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+        resources: &db::model::Resources,
+    ) -> Result<SledPlacementDiagnostics, Error> {
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let capacity = Self::sled_capacity_on_conn(&conn).await?;
+
+        // The placement query reports which sleds are off-limits because of
+        // anti-affinity groups.
+        let banned: HashSet<SledUuid> =
+            Self::sled_affinity_policies_on_conn(&conn, instance_id, resources)
+                .await?
+                .into_iter()
+                .filter(|(_, (_, anti_affinity_policy))| {
+                    *anti_affinity_policy == Some(AffinityPolicy::Fail)
+                })
+                .map(|(sled_id, _)| sled_id)
+                .collect();
+
+        Ok(SledPlacementDiagnostics::new(capacity, resources, &banned))
+    }
+
+    /// Returns the capacity of, and resources reserved on, every sled where
+    /// VMMs may be placed
+    async fn sled_capacity_on_conn(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+    ) -> Result<Vec<SledCapacityRow>, Error> {
+        let capacity = sled_capacity_query()
+            .get_results_async::<(Uuid, i64, i64, i64, i64, i64, i64)>(conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(capacity
+            .into_iter()
+            .map(
+                |(
+                    sled_id,
+                    usable_hardware_threads,
+                    usable_physical_ram,
+                    reservoir_size,
+                    used_hardware_threads,
+                    used_rss_ram,
+                    used_reservoir_ram,
+                )| SledCapacityRow {
+                    sled_id,
+                    usable_hardware_threads,
+                    usable_physical_ram,
+                    reservoir_size,
+                    used_hardware_threads,
+                    used_rss_ram,
+                    used_reservoir_ram,
+                },
+            )
+            .collect())
+    }
+
+    /// Returns the affinity and anti-affinity policies that apply to placing
+    /// a VMM for `instance_id` on each sled, as seen by the placement query
+    ///
+    /// Like placement itself, this only reports anti-affinity policies for
+    /// sleds with room for the VMM.
+    async fn sled_affinity_policies_on_conn(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        instance_id: InstanceUuid,
+        resources: &db::model::Resources,
+    ) -> Result<
+        BTreeMap<SledUuid, (Option<AffinityPolicy>, Option<AffinityPolicy>)>,
+        Error,
+    > {
+        let rows = sled_find_targets_query(instance_id, resources)
+            .get_results_async::<(
+                Uuid,
+                bool,
+                Option<AffinityPolicy>,
+                Option<AffinityPolicy>,
+            )>(conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(rows
+            .into_iter()
+            .map(|(sled_id, _, affinity_policy, anti_affinity_policy)| {
+                (
+                    SledUuid::from_untyped_uuid(sled_id),
+                    (affinity_policy, anti_affinity_policy),
+                )
+            })
+            .collect())
+    }
+
+    /// Explains, for each commissioned sled, whether a VMM using `resources`
+    /// could be placed there, and if not, why not
+    ///
+    /// This applies the same rules as [`DataStore::sled_reservation_create`]:
+    /// the sled's policy and state, the room left on it, and the affinity and
+    /// anti-affinity groups `instance_id` belongs to. Nothing is reserved, so
+    /// the answer may be out of date by the time it's returned.
+    pub async fn sled_placement_explain(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+        resources: &db::model::Resources,
+    ) -> ListResultVec<SledPlacementExplanation> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+
+        let sleds =
+            self.sled_list_all_batched(opctx, SledFilter::Commissioned).await?;
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let capacity: BTreeMap<SledUuid, SledCapacityRow> =
+            Self::sled_capacity_on_conn(&conn)
+                .await?
+                .into_iter()
+                .map(|row| (SledUuid::from_untyped_uuid(row.sled_id), row))
+                .collect();
+        let policies =
+            Self::sled_affinity_policies_on_conn(&conn, instance_id, resources)
+                .await?;
+
+        // The sleds an affinity group with the "fail" policy requires this
+        // instance to be placed on. Placement fails unless there's exactly
+        // one.
+        let required: Vec<SledUuid> = policies
+            .iter()
+            .filter(|(_, (affinity_policy, _))| {
+                *affinity_policy == Some(AffinityPolicy::Fail)
+            })
+            .map(|(sled_id, _)| *sled_id)
+            .collect();
+
+        let mut explanations: Vec<_> = sleds
+            .into_iter()
+            .map(|sled| {
+                let sled_id = SledUuid::from_untyped_uuid(sled.id());
+                let (affinity_policy, anti_affinity_policy) =
+                    policies.get(&sled_id).copied().unwrap_or_default();
+
+                let mut rejections = Vec::new();
+                let policy = sled.policy();
+                if !policy.matches(SledFilter::ReservationCreate) {
+                    rejections.push(SledPlacementRejection::Policy { policy });
+                }
+                let state = nexus_types::external_api::views::SledState::from(
+                    sled.state(),
+                );
+                if !state.matches(SledFilter::ReservationCreate) {
+                    rejections.push(SledPlacementRejection::State { state });
+                }
+                if let Some(shortfall) = capacity
+                    .get(&sled_id)
+                    .and_then(|row| row.shortfall(resources))
+                {
+                    rejections.push(SledPlacementRejection::Capacity {
+                        hardware_threads: shortfall.hardware_threads,
+                        rss_ram: shortfall.rss_ram,
+                        reservoir_ram: shortfall.reservoir_ram,
+                    });
+                }
+                if anti_affinity_policy == Some(AffinityPolicy::Fail) {
+                    rejections.push(SledPlacementRejection::AntiAffinity);
+                }
+                if !required.is_empty() && required != [sled_id] {
+                    rejections.push(SledPlacementRejection::Affinity {
+                        required_sleds: required.clone(),
+                    });
+                }
+
+                SledPlacementExplanation {
+                    sled_id,
+                    affinity_policy: affinity_policy.map(Into::into),
+                    anti_affinity_policy: anti_affinity_policy.map(Into::into),
+                    rejections,
+                }
+            })
+            .collect();
+        explanations.sort_by_key(|explanation| explanation.sled_id);
+        Ok(explanations)
     }
 
     async fn sled_reservation_create_inner(
@@ -468,6 +895,25 @@ impl DataStore {
             return Ok(old_resource[0].clone());
         }
 
+        // If the instance kept its reservation when it last stopped, we'd like
+        // to take that reservation over rather than placing the VMM anew. The
+        // held reservation is only useful if it still describes what the VMM
+        // needs, though (the instance may have been resized while it was
+        // stopped), so release any that don't before looking for space.
+        let mut held = None;
+        for resource in
+            Self::sled_reservations_held_on_conn(&conn, instance_id).await?
+        {
+            if held.is_none() && resource.resources == resources {
+                held = Some(resource);
+            } else {
+                diesel::delete(resource_dsl::sled_resource_vmm)
+                    .filter(resource_dsl::id.eq(resource.id))
+                    .execute_async(&*conn)
+                    .await?;
+            }
+        }
+
         let must_use_sleds: HashSet<SledUuid> = constraints
             .must_select_from()
             .into_iter()
@@ -526,6 +972,36 @@ impl DataStore {
             }
         }
 
+        // Take over the held reservation if its sled is still somewhere we're
+        // allowed to put this VMM. Otherwise, release it: the instance can't
+        // go back there, and it shouldn't keep holding space it can't use.
+        if let Some(resource) = held {
+            let sled_id = SledUuid::from(resource.sled_id);
+            let usable = (must_use_sleds.is_empty()
+                || must_use_sleds.contains(&sled_id))
+                && !banned.contains(&sled_id)
+                && (required.is_empty() || required.contains(&sled_id))
+                && Self::sled_accepts_reservations_on_conn(&conn, sled_id)
+                    .await?;
+            if usable {
+                let taken = diesel::update(resource_dsl::sled_resource_vmm)
+                    .filter(resource_dsl::id.eq(resource.id))
+                    .set(resource_dsl::id.eq(propolis_id.into_untyped_uuid()))
+                    .returning(SledResourceVmm::as_returning())
+                    .get_result_async(&*conn)
+                    .await
+                    .optional()?;
+                if let Some(taken) = taken {
+                    return Ok(taken);
+                }
+            } else {
+                diesel::delete(resource_dsl::sled_resource_vmm)
+                    .filter(resource_dsl::id.eq(resource.id))
+                    .execute_async(&*conn)
+                    .await?;
+            }
+        }
+
         // We loop here because our attempts to INSERT may be violated by
         // concurrent operations. We'll respond by looking through a slightly
         // smaller set of possible sleds.
@@ -584,6 +1060,143 @@ impl DataStore {
         Ok(())
     }
 
+    /// Releases any sled reservation that `instance_id` kept when it last
+    /// stopped.
+    ///
+    /// Returns the number of reservations released.
+    pub async fn sled_reservation_release_held(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+    ) -> Result<usize, Error> {
+        use nexus_db_schema::schema::sled_resource_vmm::dsl as resource_dsl;
+        use nexus_db_schema::schema::vmm::dsl as vmm_dsl;
+        diesel::delete(resource_dsl::sled_resource_vmm)
+            .filter(
+                resource_dsl::instance_id.eq(instance_id.into_untyped_uuid()),
+            )
+            .filter(
+                resource_dsl::id.eq_any(
+                    vmm_dsl::vmm
+                        .filter(
+                            vmm_dsl::instance_id
+                                .eq(instance_id.into_untyped_uuid()),
+                        )
+                        .filter(vmm_dsl::time_deleted.is_not_null())
+                        .select(vmm_dsl::id),
+                ),
+            )
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Returns the sled reservations that `instance_id` kept when it last
+    /// stopped.
+    ///
+    /// A reservation is held for an instance once the VMM it was made for has
+    /// been destroyed and deleted without the reservation being released. At
+    /// most one is expected, but callers should tolerate more.
+    async fn sled_reservations_held_on_conn(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        instance_id: InstanceUuid,
+    ) -> Result<Vec<SledResourceVmm>, diesel::result::Error> {
+        use nexus_db_schema::schema::sled_resource_vmm::dsl as resource_dsl;
+        use nexus_db_schema::schema::vmm::dsl as vmm_dsl;
+        resource_dsl::sled_resource_vmm
+            .filter(
+                resource_dsl::instance_id.eq(instance_id.into_untyped_uuid()),
+            )
+            .filter(
+                resource_dsl::id.eq_any(
+                    vmm_dsl::vmm
+                        .filter(
+                            vmm_dsl::instance_id
+                                .eq(instance_id.into_untyped_uuid()),
+                        )
+                        .filter(vmm_dsl::time_deleted.is_not_null())
+                        .select(vmm_dsl::id),
+                ),
+            )
+            .select(SledResourceVmm::as_select())
+            .load_async(conn)
+            .await
+    }
+
+    /// Returns whether new VMM reservations may be placed on `sled_id`.
+    async fn sled_accepts_reservations_on_conn(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        sled_id: SledUuid,
+    ) -> Result<bool, diesel::result::Error> {
+        use nexus_db_schema::schema::sled::dsl;
+        let found = dsl::sled
+            .filter(dsl::id.eq(sled_id.into_untyped_uuid()))
+            .sled_filter(SledFilter::ReservationCreate)
+            .select(dsl::id)
+            .first_async::<Uuid>(conn)
+            .await
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// Returns the resources reserved by VMMs on each sled, for sleds that
+    /// have any reservations at all
+    ///
+    /// This makes as many queries as needed to visit every reservation, so
+    /// it should not be used in latency-sensitive contexts.
+    pub async fn sled_instance_usage_by_sled(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<BTreeMap<SledUuid, SledInstanceUsage>, Error> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+        opctx.check_complex_operations_allowed()?;
+
+        use nexus_db_schema::schema::sled_resource_vmm::dsl as resource_dsl;
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let mut totals: BTreeMap<SledUuid, (u64, u64)> = BTreeMap::new();
+        let mut paginator = Paginator::new(
+            SQL_BATCH_SIZE,
+            dropshot::PaginationOrder::Ascending,
+        );
+        while let Some(p) = paginator.next() {
+            let batch = paginated(
+                resource_dsl::sled_resource_vmm,
+                resource_dsl::id,
+                &p.current_pagparams(),
+            )
+            .select(SledResourceVmm::as_select())
+            .load_async(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+            paginator = p.found_batch(&batch, &|r: &SledResourceVmm| {
+                r.id.into_untyped_uuid()
+            });
+            for reservation in batch {
+                let (threads, reservoir) =
+                    totals.entry(reservation.sled_id.into()).or_default();
+                *threads += u64::from(reservation.resources.hardware_threads.0);
+                *reservoir += reservation.resources.reservoir_ram.to_bytes();
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|(sled_id, (hardware_threads, reservoir))| {
+                let reservoir_ram = external::ByteCount::try_from(reservoir)
+                    .map_err(|e| {
+                        Error::internal_error(&format!(
+                            "reservoir usage on sled {sled_id} out of \
+                             range: {e}"
+                        ))
+                    })?;
+                Ok((
+                    sled_id,
+                    SledInstanceUsage { hardware_threads, reservoir_ram },
+                ))
+            })
+            .collect()
+    }
+
     /// Sets the provision policy for this sled.
     ///
     /// Errors if the sled is not in service.
@@ -789,6 +1402,28 @@ impl DataStore {
         .await
     }
 
+    /// Quiesces an active sled, so that nothing new is placed on it, or
+    /// returns a quiesced sled to the active state.
+    ///
+    /// This is idempotent, and it returns the old state of the sled.
+    pub async fn sled_set_quiesced(
+        &self,
+        opctx: &OpContext,
+        authz_sled: &authz::Sled,
+        quiesced: bool,
+    ) -> Result<SledState, external::Error> {
+        let new_state =
+            if quiesced { SledState::Quiesced } else { SledState::Active };
+        self.sled_set_state_impl(
+            opctx,
+            authz_sled,
+            new_state,
+            ValidateTransition::Yes,
+        )
+        .await
+        .map_err(|error| error.into_external_error())
+    }
+
     pub(super) async fn sled_set_state_impl(
         &self,
         opctx: &OpContext,
@@ -886,7 +1521,7 @@ impl DataStore {
                     // disk state if we add any addition sled states in the
                     // future.
                     let new_disk_state = match new_sled_state {
-                        SledState::Active => None,
+                        SledState::Active | SledState::Quiesced => None,
                         SledState::Decommissioned => Some(
                             nexus_db_model::PhysicalDiskState::Decommissioned,
                         ),
@@ -964,6 +1599,11 @@ impl SledTransition {
                         // Any policy is valid for the active state.
                         SledPolicy::iter().collect()
                     }
+                    Quiesced => {
+                        // Only sleds that are in service can be quiesced.
+                        SledPolicy::all_matching(SledFilter::InService)
+                            .collect()
+                    }
                     Decommissioned => {
                         SledPolicy::all_decommissionable().to_vec()
                     }
@@ -982,14 +1622,14 @@ impl SledTransition {
 
         match self {
             SledTransition::Policy(_) => {
-                // Policies can only be transitioned in the active state. (In
-                // the future, this will include other non-decommissioned
-                // states.)
-                vec![Active]
+                // Policies can only be transitioned in non-decommissioned
+                // states.
+                vec![Active, Quiesced]
             }
             SledTransition::State(state) => match state {
-                Active => vec![],
-                Decommissioned => vec![Active],
+                Active => vec![Quiesced],
+                Quiesced => vec![Active],
+                Decommissioned => vec![Active, Quiesced],
             },
         }
     }
@@ -1332,6 +1972,130 @@ pub(in crate::db::datastore) mod test {
         logctx.cleanup_successful();
     }
 
+    // Destroys and deletes the VMM that `resource` was reserved for without
+    // releasing the reservation, as the instance update saga does for an
+    // instance whose stopped-reservation policy is `retain`.
+    async fn delete_vmm_keeping_reservation(
+        datastore: &DataStore,
+        instance_id: InstanceUuid,
+        resource: &SledResourceVmm,
+    ) {
+        use nexus_db_schema::schema::vmm::dsl;
+        let mut vmm = db::model::Vmm::new(
+            resource.id.into(),
+            instance_id,
+            resource.sled_id.into(),
+            "fd00:1122:3344:101::1".parse().unwrap(),
+            12400,
+        );
+        vmm.runtime.state = db::model::VmmState::Destroyed;
+        vmm.time_deleted = Some(Utc::now());
+        diesel::insert_into(dsl::vmm)
+            .values(vmm)
+            .execute_async(
+                &*datastore.pool_connection_for_tests().await.unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn reservation_exists(
+        datastore: &DataStore,
+        propolis_id: PropolisUuid,
+    ) -> bool {
+        use nexus_db_schema::schema::sled_resource_vmm::dsl;
+        dsl::sled_resource_vmm
+            .filter(dsl::id.eq(propolis_id.into_untyped_uuid()))
+            .select(dsl::id)
+            .first_async::<Uuid>(
+                &*datastore.pool_connection_for_tests().await.unwrap(),
+            )
+            .await
+            .optional()
+            .unwrap()
+            .is_some()
+    }
+
+    #[tokio::test]
+    async fn sled_reservation_held_while_stopped() {
+        let logctx = dev::test_setup_log("sled_reservation_held_while_stopped");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+        create_sleds(&datastore, 4).await;
+
+        let instance_id = InstanceUuid::new_v4();
+        let first_id = PropolisUuid::new_v4();
+        let first = datastore
+            .sled_reservation_create(
+                &opctx,
+                instance_id,
+                first_id,
+                small_resource_request(),
+                db::model::SledReservationConstraints::none(),
+            )
+            .await
+            .unwrap();
+        delete_vmm_keeping_reservation(&datastore, instance_id, &first).await;
+
+        // The next VMM takes over the held reservation, so it lands on the
+        // same sled without a second reservation being made.
+        let second_id = PropolisUuid::new_v4();
+        let second = datastore
+            .sled_reservation_create(
+                &opctx,
+                instance_id,
+                second_id,
+                small_resource_request(),
+                db::model::SledReservationConstraints::none(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(PropolisUuid::from(second.id), second_id);
+        assert_eq!(second.sled_id, first.sled_id);
+        assert!(!reservation_exists(&datastore, first_id).await);
+
+        // A reservation held for VMMs that are still around isn't released.
+        assert_eq!(
+            datastore
+                .sled_reservation_release_held(&opctx, instance_id)
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(reservation_exists(&datastore, second_id).await);
+
+        // A held reservation that no longer fits the instance is released
+        // rather than taken over.
+        delete_vmm_keeping_reservation(&datastore, instance_id, &second).await;
+        let third_id = PropolisUuid::new_v4();
+        let third = datastore
+            .sled_reservation_create(
+                &opctx,
+                instance_id,
+                third_id,
+                large_resource_request(),
+                db::model::SledReservationConstraints::none(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(PropolisUuid::from(third.id), third_id);
+        assert!(!reservation_exists(&datastore, second_id).await);
+
+        // Held reservations can be released explicitly.
+        delete_vmm_keeping_reservation(&datastore, instance_id, &third).await;
+        assert_eq!(
+            datastore
+                .sled_reservation_release_held(&opctx, instance_id)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(!reservation_exists(&datastore, third_id).await);
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+
     // Utilities to help with Affinity Testing
 
     // Create a resource request that will entirely fill a sled.
@@ -1690,6 +2454,192 @@ pub(in crate::db::datastore) mod test {
         logctx.cleanup_successful();
     }
 
+    // When no sled can fit an instance, the error explains why each sled
+    // was ruled out.
+    #[tokio::test]
+    async fn sled_reservation_capacity_diagnostics() {
+        let logctx =
+            dev::test_setup_log("sled_reservation_capacity_diagnostics");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+        let (authz_project, _project) =
+            create_project(&opctx, &datastore, "project").await;
+
+        const SLED_COUNT: usize = 3;
+        let sleds = create_sleds(&datastore, SLED_COUNT).await;
+
+        let groups = [Group {
+            affinity: Affinity::Negative,
+            name: "anti-affinity",
+            policy: external::AffinityPolicy::Fail,
+        }];
+        let all_groups =
+            AllGroups::create(&opctx, &datastore, &authz_project, &groups)
+                .await;
+
+        // Fill the first and last sleds, and put a member of the
+        // anti-affinity group on the one in between.
+        let instances = [
+            Instance::new().use_many_resources().sled(sleds[0].id()),
+            Instance::new().group("anti-affinity").sled(sleds[1].id()),
+            Instance::new().use_many_resources().sled(sleds[2].id()),
+        ];
+        for instance in instances {
+            instance
+                .add_to_groups_and_reserve(&opctx, &datastore, &all_groups)
+                .await
+                .expect("Failed to set up instances");
+        }
+
+        let test_instance = Instance::new().group("anti-affinity");
+        test_instance.add_to_groups(&datastore, &all_groups).await;
+        let error = datastore
+            .sled_reservation_create(
+                &opctx,
+                test_instance.id,
+                PropolisUuid::new_v4(),
+                test_instance.resources.clone(),
+                db::model::SledReservationConstraints::none(),
+            )
+            .await
+            .expect_err("Should have failed to place instance");
+        let external::Error::InsufficientCapacity { message } = error else {
+            panic!("Unexpected error: {error:?}");
+        };
+
+        let external_message = message.external_message();
+        assert!(
+            external_message.contains(
+                "sleds considered: 3, without enough room: 2, excluded by \
+                 anti-affinity: 1. Shortfall by sled: 1 vCPU, 1 KiB of \
+                 memory, 1 KiB of host memory; 1 vCPU, 1 KiB of memory, \
+                 1 KiB of host memory"
+            ),
+            "unexpected message: {external_message}"
+        );
+        assert!(!external_message.contains(&sleds[1].id().to_string()));
+
+        // Operators get to see which sled is which.
+        let internal_context = message.internal_context();
+        for sled in [&sleds[0], &sleds[2]] {
+            assert!(
+                internal_context
+                    .contains(&format!("sled {} is short 1 vCPU", sled.id())),
+                "unexpected context: {internal_context}"
+            );
+        }
+        assert!(
+            internal_context.contains(&format!(
+                "sled {} is excluded by anti-affinity",
+                sleds[1].id()
+            )),
+            "unexpected context: {internal_context}"
+        );
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+
+    // Placement can be explained sled by sled without reserving anything.
+    #[tokio::test]
+    async fn sled_placement_explain() {
+        let logctx = dev::test_setup_log("sled_placement_explain");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+        let (authz_project, _project) =
+            create_project(&opctx, &datastore, "project").await;
+
+        const SLED_COUNT: usize = 4;
+        let sleds = create_sleds(&datastore, SLED_COUNT).await;
+
+        let groups = [Group {
+            affinity: Affinity::Negative,
+            name: "anti-affinity",
+            policy: external::AffinityPolicy::Fail,
+        }];
+        let all_groups =
+            AllGroups::create(&opctx, &datastore, &authz_project, &groups)
+                .await;
+
+        // Fill the first sled, put a member of the anti-affinity group on the
+        // second, and leave the other two empty.
+        let instances = [
+            Instance::new().use_many_resources().sled(sleds[0].id()),
+            Instance::new().group("anti-affinity").sled(sleds[1].id()),
+        ];
+        for instance in instances {
+            instance
+                .add_to_groups_and_reserve(&opctx, &datastore, &all_groups)
+                .await
+                .expect("Failed to set up instances");
+        }
+
+        // Stop new instances from landing on the third sled.
+        let non_provisionable = SledPolicy::InService {
+            provision_policy: SledProvisionPolicy::NonProvisionable,
+        };
+        sled_set_policy(
+            &opctx,
+            &datastore,
+            SledUuid::from_untyped_uuid(sleds[2].id()),
+            non_provisionable,
+            ValidateTransition::Yes,
+            Expected::Ok(SledPolicy::provisionable()),
+        )
+        .await
+        .unwrap();
+
+        let test_instance = Instance::new().group("anti-affinity");
+        test_instance.add_to_groups(&datastore, &all_groups).await;
+        let explanations = datastore
+            .sled_placement_explain(
+                &opctx,
+                test_instance.id,
+                &test_instance.resources,
+            )
+            .await
+            .expect("explained placement");
+        assert_eq!(explanations.len(), SLED_COUNT);
+        let rejections = |sled: &Sled| {
+            explanations
+                .iter()
+                .find(|e| e.sled_id.into_untyped_uuid() == sled.id())
+                .expect("sled was explained")
+                .rejections
+                .clone()
+        };
+
+        let one_kib = external::ByteCount::from_kibibytes_u32(1);
+        assert_eq!(
+            rejections(&sleds[0]),
+            [SledPlacementRejection::Capacity {
+                hardware_threads: 1,
+                rss_ram: one_kib,
+                reservoir_ram: one_kib,
+            }]
+        );
+        assert_eq!(
+            rejections(&sleds[1]),
+            [SledPlacementRejection::AntiAffinity]
+        );
+        assert_eq!(
+            rejections(&sleds[2]),
+            [SledPlacementRejection::Policy { policy: non_provisionable }]
+        );
+        assert_eq!(rejections(&sleds[3]), []);
+
+        // Nothing was reserved for the instance, and it lands on the sled
+        // that was explained as having no reason to reject it.
+        let resource =
+            create_instance_reservation(&datastore, &opctx, &test_instance)
+                .await
+                .expect("Should have succeeded allocation");
+        assert_eq!(resource.sled_id.into_untyped_uuid(), sleds[3].id());
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+
     // Anti-Affinity, Policy = Fail
     // We should reliably pick a sled not occupied by another instance
     #[tokio::test]
@@ -2788,12 +3738,13 @@ pub(in crate::db::datastore) mod test {
         // explicitly since tests are really about writing things down twice.
         let valid_transitions = [
             (
-                // In-service and active sleds can be marked as expunged.
+                // In-service and active (or quiesced) sleds can be marked as
+                // expunged.
                 Before::new(
                     predicate::in_iter(SledPolicy::all_matching(
                         SledFilter::InService,
                     )),
-                    predicate::eq(SledState::Active),
+                    predicate::ne(SledState::Decommissioned),
                 ),
                 SledTransition::Policy(SledPolicy::Expunged),
             ),
@@ -2804,7 +3755,7 @@ pub(in crate::db::datastore) mod test {
                     predicate::in_iter(SledPolicy::all_matching(
                         SledFilter::InService,
                     )),
-                    predicate::eq(SledState::Active),
+                    predicate::ne(SledState::Decommissioned),
                 ),
                 SledTransition::Policy(SledPolicy::InService {
                     provision_policy: SledProvisionPolicy::Provisionable,
@@ -2816,26 +3767,37 @@ pub(in crate::db::datastore) mod test {
                     predicate::in_iter(SledPolicy::all_matching(
                         SledFilter::InService,
                     )),
-                    predicate::eq(SledState::Active),
+                    predicate::ne(SledState::Decommissioned),
                 ),
                 SledTransition::Policy(SledPolicy::InService {
                     provision_policy: SledProvisionPolicy::NonProvisionable,
                 }),
             ),
             (
-                // Active sleds can be marked as active, regardless of their
-                // policy.
+                // Active and quiesced sleds can be marked as active,
+                // regardless of their policy.
                 Before::new(
                     predicate::always(),
-                    predicate::eq(SledState::Active),
+                    predicate::ne(SledState::Decommissioned),
                 ),
                 SledTransition::State(SledState::Active),
             ),
+            (
+                // In-service sleds can be quiesced, unless they've been
+                // decommissioned.
+                Before::new(
+                    predicate::in_iter(SledPolicy::all_matching(
+                        SledFilter::InService,
+                    )),
+                    predicate::ne(SledState::Decommissioned),
+                ),
+                SledTransition::State(SledState::Quiesced),
+            ),
             (
                 // Expunged sleds can be marked as decommissioned.
                 Before::new(
                     predicate::eq(SledPolicy::Expunged),
-                    predicate::eq(SledState::Active),
+                    predicate::ne(SledState::Decommissioned),
                 ),
                 SledTransition::State(SledState::Decommissioned),
             ),
@@ -0,0 +1,229 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods on [`SledExecutionLease`]s.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::model::SledExecutionLease;
+use crate::db::model::to_db_typed_uuid;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external::Error;
+use omicron_uuid_kinds::OmicronZoneUuid;
+use omicron_uuid_kinds::SledUuid;
+
+/// The outcome of [`DataStore::sled_execution_lease_acquire`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SledExecutionLeaseAcquire {
+    /// The caller now holds the lease.
+    Acquired(SledExecutionLease),
+    /// Another Nexus holds an unexpired lease on the sled.
+    HeldByOther { nexus_id: OmicronZoneUuid, time_expires: DateTime<Utc> },
+}
+
+impl DataStore {
+    /// Attempts to acquire the execution lease on `sled_id` for `nexus_id`,
+    /// valid for `duration` from now.
+    ///
+    /// This succeeds if no Nexus holds the lease, if the lease has expired,
+    /// or if `nexus_id` already holds it (in which case the lease is
+    /// renewed). Expiry is judged by the database's clock, so Nexus
+    /// instances needn't agree on the time.
+    pub async fn sled_execution_lease_acquire(
+        &self,
+        opctx: &OpContext,
+        sled_id: SledUuid,
+        nexus_id: OmicronZoneUuid,
+        duration: TimeDelta,
+    ) -> Result<SledExecutionLeaseAcquire, Error> {
+        opctx
+            .authorize(authz::Action::Modify, &authz::BLUEPRINT_CONFIG)
+            .await?;
+
+        use nexus_db_schema::schema::sled_execution_lease::dsl;
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let now =
+            diesel::dsl::now.into_sql::<diesel::pg::sql_types::Timestamptz>();
+
+        loop {
+            let acquired = diesel::insert_into(dsl::sled_execution_lease)
+                .values((
+                    dsl::sled_id.eq(to_db_typed_uuid(sled_id)),
+                    dsl::nexus_id.eq(to_db_typed_uuid(nexus_id)),
+                    dsl::time_acquired.eq(now),
+                    dsl::time_expires.eq(now + duration),
+                ))
+                .on_conflict(dsl::sled_id)
+                .do_update()
+                .set((
+                    dsl::nexus_id.eq(excluded(dsl::nexus_id)),
+                    dsl::time_acquired.eq(excluded(dsl::time_acquired)),
+                    dsl::time_expires.eq(excluded(dsl::time_expires)),
+                ))
+                .filter(
+                    dsl::nexus_id
+                        .eq(to_db_typed_uuid(nexus_id))
+                        .or(dsl::time_expires.le(now)),
+                )
+                .returning(SledExecutionLease::as_returning())
+                .get_result_async(&*conn)
+                .await
+                .optional()
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?;
+            if let Some(lease) = acquired {
+                return Ok(SledExecutionLeaseAcquire::Acquired(lease));
+            }
+
+            // Someone else holds the lease.  Report who, unless they released
+            // it in the meantime, in which case we can try again.
+            let holder = dsl::sled_execution_lease
+                .filter(dsl::sled_id.eq(to_db_typed_uuid(sled_id)))
+                .select(SledExecutionLease::as_select())
+                .first_async(&*conn)
+                .await
+                .optional()
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?;
+            if let Some(holder) = holder {
+                return Ok(SledExecutionLeaseAcquire::HeldByOther {
+                    nexus_id: holder.nexus_id.into(),
+                    time_expires: holder.time_expires,
+                });
+            }
+        }
+    }
+
+    /// Releases the execution lease on `sled_id`, if `nexus_id` holds it.
+    ///
+    /// Returns whether a lease was released.
+    pub async fn sled_execution_lease_release(
+        &self,
+        opctx: &OpContext,
+        sled_id: SledUuid,
+        nexus_id: OmicronZoneUuid,
+    ) -> Result<bool, Error> {
+        opctx
+            .authorize(authz::Action::Modify, &authz::BLUEPRINT_CONFIG)
+            .await?;
+
+        use nexus_db_schema::schema::sled_execution_lease::dsl;
+        let deleted = diesel::delete(dsl::sled_execution_lease)
+            .filter(dsl::sled_id.eq(to_db_typed_uuid(sled_id)))
+            .filter(dsl::nexus_id.eq(to_db_typed_uuid(nexus_id)))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pub_test_utils::TestDatabase;
+    use omicron_test_utils::dev;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_sled_execution_lease() {
+        let logctx = dev::test_setup_log("test_sled_execution_lease");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        let sled1 = SledUuid::new_v4();
+        let sled2 = SledUuid::new_v4();
+        let nexus1 = OmicronZoneUuid::new_v4();
+        let nexus2 = OmicronZoneUuid::new_v4();
+        let long = TimeDelta::minutes(5);
+
+        // The first Nexus to ask gets the lease.
+        let first = match datastore
+            .sled_execution_lease_acquire(opctx, sled1, nexus1, long)
+            .await
+            .expect("acquired lease")
+        {
+            SledExecutionLeaseAcquire::Acquired(lease) => lease,
+            other => panic!("expected to acquire lease, got {other:?}"),
+        };
+        assert_eq!(first.nexus_id, to_db_typed_uuid(nexus1));
+
+        // Another Nexus can't take it, but can take a lease on another sled.
+        assert_eq!(
+            datastore
+                .sled_execution_lease_acquire(opctx, sled1, nexus2, long)
+                .await
+                .expect("tried to acquire lease"),
+            SledExecutionLeaseAcquire::HeldByOther {
+                nexus_id: nexus1,
+                time_expires: first.time_expires,
+            }
+        );
+        assert!(matches!(
+            datastore
+                .sled_execution_lease_acquire(opctx, sled2, nexus2, long)
+                .await
+                .expect("acquired lease"),
+            SledExecutionLeaseAcquire::Acquired(_)
+        ));
+
+        // The holder can renew it.
+        let renewed = match datastore
+            .sled_execution_lease_acquire(opctx, sled1, nexus1, long)
+            .await
+            .expect("renewed lease")
+        {
+            SledExecutionLeaseAcquire::Acquired(lease) => lease,
+            other => panic!("expected to renew lease, got {other:?}"),
+        };
+        assert!(renewed.time_expires >= first.time_expires);
+
+        // Only the holder can release it; once released, anyone can take it.
+        assert!(
+            !datastore
+                .sled_execution_lease_release(opctx, sled1, nexus2)
+                .await
+                .expect("tried to release lease")
+        );
+        assert!(
+            datastore
+                .sled_execution_lease_release(opctx, sled1, nexus1)
+                .await
+                .expect("released lease")
+        );
+        let short = TimeDelta::milliseconds(10);
+        assert!(matches!(
+            datastore
+                .sled_execution_lease_acquire(opctx, sled1, nexus2, short)
+                .await
+                .expect("acquired lease"),
+            SledExecutionLeaseAcquire::Acquired(_)
+        ));
+
+        // An expired lease can be taken over without being released.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let taken = match datastore
+            .sled_execution_lease_acquire(opctx, sled1, nexus1, long)
+            .await
+            .expect("took over expired lease")
+        {
+            SledExecutionLeaseAcquire::Acquired(lease) => lease,
+            other => panic!("expected to take over lease, got {other:?}"),
+        };
+        assert_eq!(taken.nexus_id, to_db_typed_uuid(nexus1));
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
@@ -190,6 +190,57 @@ impl DataStore {
             })
     }
 
+    /// Set or clear a snapshot's delete protection
+    ///
+    /// A snapshot that is still being created can't be protected, so that the
+    /// snapshot create saga can always delete it if it unwinds.
+    pub async fn snapshot_set_delete_protected(
+        &self,
+        opctx: &OpContext,
+        authz_snapshot: &authz::Snapshot,
+        delete_protected: bool,
+    ) -> UpdateResult<Snapshot> {
+        opctx.authorize(authz::Action::Modify, authz_snapshot).await?;
+
+        let snapshot_id = authz_snapshot.id();
+        use nexus_db_schema::schema::snapshot::dsl;
+        let result = diesel::update(dsl::snapshot)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(snapshot_id))
+            .filter(dsl::state.ne(SnapshotState::Creating))
+            .set((
+                dsl::delete_protected.eq(delete_protected),
+                dsl::time_modified.eq(Utc::now()),
+            ))
+            .check_if_exists::<Snapshot>(snapshot_id)
+            .execute_and_check(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_snapshot),
+                )
+            })?;
+
+        match result.status {
+            UpdateStatus::Updated => Ok(result.found),
+            UpdateStatus::NotUpdatedButExists => {
+                let snapshot = result.found;
+                if snapshot.time_deleted().is_some() {
+                    Err(Error::not_found_by_id(
+                        ResourceType::Snapshot,
+                        &snapshot_id,
+                    ))
+                } else {
+                    Err(Error::invalid_request(format!(
+                        "snapshot cannot be modified in state {:?}",
+                        snapshot.state,
+                    )))
+                }
+            }
+        }
+    }
+
     pub async fn snapshot_list(
         &self,
         opctx: &OpContext,
@@ -245,6 +296,7 @@ impl DataStore {
             .filter(dsl::gen.eq(gen))
             .filter(dsl::id.eq(snapshot_id))
             .filter(dsl::state.eq_any(ok_to_delete_states.clone()))
+            .filter(dsl::delete_protected.eq(false))
             .set((
                 dsl::time_deleted.eq(now),
                 dsl::state.eq(SnapshotState::Destroyed),
@@ -279,7 +331,12 @@ impl DataStore {
                     Ok(snapshot.id())
                 } else {
                     // if the snapshot was not deleted, figure out why
-                    if !ok_to_delete_states.contains(&snapshot.state) {
+                    if snapshot.delete_protected {
+                        Err(Error::delete_protected(
+                            ResourceType::Snapshot,
+                            snapshot.name().as_str(),
+                        ))
+                    } else if !ok_to_delete_states.contains(&snapshot.state) {
                         Err(Error::invalid_request(&format!(
                             "snapshot cannot be deleted in state {:?}",
                             snapshot.state,
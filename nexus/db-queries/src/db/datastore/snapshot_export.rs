@@ -0,0 +1,136 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods on [`SnapshotExport`]s.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::collection_insert::AsyncInsertError;
+use crate::db::collection_insert::DatastoreCollection;
+use crate::db::model::Project;
+use crate::db::model::SnapshotExport;
+use crate::db::pagination::paginated;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::Utc;
+use diesel::prelude::*;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::DeleteResult;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::UpdateResult;
+use std::net::SocketAddrV6;
+use uuid::Uuid;
+
+impl DataStore {
+    pub async fn snapshot_export_list(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<SnapshotExport> {
+        opctx.authorize(authz::Action::ListChildren, authz_project).await?;
+
+        use nexus_db_schema::schema::snapshot_export::dsl;
+        paginated(dsl::snapshot_export, dsl::id, pagparams)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::project_id.eq(authz_project.id()))
+            .select(SnapshotExport::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    pub async fn snapshot_export_create(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        export: SnapshotExport,
+    ) -> CreateResult<SnapshotExport> {
+        use nexus_db_schema::schema::snapshot_export::dsl;
+
+        opctx.authorize(authz::Action::CreateChild, authz_project).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+
+        // Inserting the same export twice (for example, when a saga action is
+        // replayed) is a no-op.
+        Project::insert_resource(
+            authz_project.id(),
+            diesel::insert_into(dsl::snapshot_export)
+                .values(export)
+                .on_conflict(dsl::id)
+                .do_update()
+                .set(dsl::time_modified.eq(dsl::time_modified)),
+        )
+        .insert_and_get_result_async(&conn)
+        .await
+        .map_err(|e| match e {
+            AsyncInsertError::CollectionNotFound => authz_project.not_found(),
+            AsyncInsertError::DatabaseError(e) => {
+                public_error_from_diesel(e, ErrorHandler::Server)
+            }
+        })
+    }
+
+    /// Record the Pantry that a snapshot export's volume is attached to,
+    /// making the export available for download
+    pub async fn snapshot_export_set_pantry_address(
+        &self,
+        opctx: &OpContext,
+        authz_export: &authz::SnapshotExport,
+        pantry_address: SocketAddrV6,
+    ) -> UpdateResult<SnapshotExport> {
+        opctx.authorize(authz::Action::Modify, authz_export).await?;
+
+        use nexus_db_schema::schema::snapshot_export::dsl;
+        diesel::update(dsl::snapshot_export)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(authz_export.id()))
+            .set((
+                dsl::pantry_address.eq(pantry_address.to_string()),
+                dsl::time_modified.eq(Utc::now()),
+            ))
+            .returning(SnapshotExport::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_export),
+                )
+            })
+    }
+
+    /// Delete a snapshot export
+    ///
+    /// Deleting an export that was already deleted is a no-op. The export's
+    /// volume is not deleted here: callers are responsible for detaching it
+    /// from its Pantry and deleting it.
+    pub async fn snapshot_export_delete(
+        &self,
+        opctx: &OpContext,
+        authz_export: &authz::SnapshotExport,
+    ) -> DeleteResult {
+        opctx.authorize(authz::Action::Delete, authz_export).await?;
+
+        use nexus_db_schema::schema::snapshot_export::dsl;
+        diesel::update(dsl::snapshot_export)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(authz_export.id()))
+            .set(dsl::time_deleted.eq(Utc::now()))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_export),
+                )
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,359 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods on [`SnapshotSchedule`]s.
+
+use super::DataStore;
+use super::SQL_BATCH_SIZE;
+use crate::authz;
+use crate::authz::ApiResource;
+use crate::context::OpContext;
+use crate::db::collection_insert::AsyncInsertError;
+use crate::db::collection_insert::DatastoreCollection;
+use crate::db::identity::Resource;
+use crate::db::model::Disk;
+use crate::db::model::Name;
+use crate::db::model::Project;
+use crate::db::model::Snapshot;
+use crate::db::model::SnapshotSchedule;
+use crate::db::model::SnapshotScheduleUpdate;
+use crate::db::pagination::Paginator;
+use crate::db::pagination::paginated;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
+use chrono::Utc;
+use diesel::prelude::*;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::DeleteResult;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::ResourceType;
+use omicron_common::api::external::UpdateResult;
+use omicron_common::api::external::http_pagination::PaginatedBy;
+use ref_cast::RefCast;
+use uuid::Uuid;
+
+impl DataStore {
+    pub async fn snapshot_schedule_list(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        pagparams: &PaginatedBy<'_>,
+    ) -> ListResultVec<SnapshotSchedule> {
+        opctx.authorize(authz::Action::ListChildren, authz_project).await?;
+
+        use nexus_db_schema::schema::snapshot_schedule::dsl;
+        match pagparams {
+            PaginatedBy::Id(pagparams) => {
+                paginated(dsl::snapshot_schedule, dsl::id, &pagparams)
+            }
+            PaginatedBy::Name(pagparams) => paginated(
+                dsl::snapshot_schedule,
+                dsl::name,
+                &pagparams.map_name(|n| Name::ref_cast(n)),
+            ),
+        }
+        .filter(dsl::time_deleted.is_null())
+        .filter(dsl::project_id.eq(authz_project.id()))
+        .select(SnapshotSchedule::as_select())
+        .load_async(&*self.pool_connection_authorized(opctx).await?)
+        .await
+        .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    pub async fn snapshot_schedule_create(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        schedule: SnapshotSchedule,
+    ) -> CreateResult<SnapshotSchedule> {
+        use nexus_db_schema::schema::snapshot_schedule::dsl;
+
+        opctx.authorize(authz::Action::CreateChild, authz_project).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let name = schedule.name().as_str().to_string();
+
+        Project::insert_resource(
+            authz_project.id(),
+            diesel::insert_into(dsl::snapshot_schedule).values(schedule),
+        )
+        .insert_and_get_result_async(&conn)
+        .await
+        .map_err(|e| match e {
+            AsyncInsertError::CollectionNotFound => authz_project.not_found(),
+            AsyncInsertError::DatabaseError(diesel_error) => {
+                public_error_from_diesel(
+                    diesel_error,
+                    ErrorHandler::Conflict(
+                        ResourceType::SnapshotSchedule,
+                        &name,
+                    ),
+                )
+            }
+        })
+    }
+
+    pub async fn snapshot_schedule_update(
+        &self,
+        opctx: &OpContext,
+        authz_schedule: &authz::SnapshotSchedule,
+        updates: SnapshotScheduleUpdate,
+    ) -> UpdateResult<SnapshotSchedule> {
+        opctx.authorize(authz::Action::Modify, authz_schedule).await?;
+
+        use nexus_db_schema::schema::snapshot_schedule::dsl;
+        diesel::update(dsl::snapshot_schedule)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(authz_schedule.id()))
+            .set(updates)
+            .returning(SnapshotSchedule::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_schedule),
+                )
+            })
+    }
+
+    /// Delete a snapshot schedule
+    ///
+    /// Snapshots that the schedule already created are left in place.
+    pub async fn snapshot_schedule_delete(
+        &self,
+        opctx: &OpContext,
+        authz_schedule: &authz::SnapshotSchedule,
+    ) -> DeleteResult {
+        opctx.authorize(authz::Action::Delete, authz_schedule).await?;
+
+        use nexus_db_schema::schema::snapshot_schedule::dsl;
+        diesel::update(dsl::snapshot_schedule)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(authz_schedule.id()))
+            .set(dsl::time_deleted.eq(Utc::now()))
+            .returning(SnapshotSchedule::as_returning())
+            .get_result_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| {
+                public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_schedule),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// List one page of snapshot schedules in all projects
+    async fn snapshot_schedule_list_all_page(
+        &self,
+        opctx: &OpContext,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<SnapshotSchedule> {
+        use nexus_db_schema::schema::snapshot_schedule::dsl;
+
+        paginated(dsl::snapshot_schedule, dsl::id, pagparams)
+            .filter(dsl::time_deleted.is_null())
+            .select(SnapshotSchedule::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// List the snapshot schedules in all projects
+    ///
+    /// This should generally not be used in API handlers or other
+    /// latency-sensitive contexts, but it can make sense in saga actions or
+    /// background tasks.
+    pub async fn snapshot_schedule_list_all_batched(
+        &self,
+        opctx: &OpContext,
+    ) -> ListResultVec<SnapshotSchedule> {
+        opctx.authorize(authz::Action::ListChildren, &authz::FLEET).await?;
+        opctx.check_complex_operations_allowed()?;
+
+        let mut all_schedules = Vec::new();
+        let mut paginator = Paginator::new(
+            SQL_BATCH_SIZE,
+            dropshot::PaginationOrder::Ascending,
+        );
+        while let Some(p) = paginator.next() {
+            let batch = self
+                .snapshot_schedule_list_all_page(opctx, &p.current_pagparams())
+                .await?;
+            paginator = p.found_batch(&batch, &|s: &SnapshotSchedule| s.id());
+            all_schedules.extend(batch);
+        }
+
+        Ok(all_schedules)
+    }
+
+    /// Record that a snapshot schedule took snapshots at `time_last_run`
+    pub async fn snapshot_schedule_mark_run(
+        &self,
+        opctx: &OpContext,
+        schedule_id: Uuid,
+        time_last_run: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::snapshot_schedule::dsl;
+        diesel::update(dsl::snapshot_schedule)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::id.eq(schedule_id))
+            .set(dsl::time_last_run.eq(time_last_run))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+
+        Ok(())
+    }
+
+    /// List the disks that a snapshot schedule should take snapshots of
+    ///
+    /// Disks that are not attached or detached (for example, disks that are
+    /// still being created or imported) are skipped.
+    pub async fn snapshot_schedule_list_disks(
+        &self,
+        opctx: &OpContext,
+        schedule: &SnapshotSchedule,
+    ) -> ListResultVec<Disk> {
+        opctx.authorize(authz::Action::ListChildren, &authz::FLEET).await?;
+        opctx.check_complex_operations_allowed()?;
+
+        use nexus_db_schema::schema::disk::dsl;
+
+        let ok_to_snapshot_state_labels = [
+            external::DiskState::Detached.label(),
+            external::DiskState::Attached(Uuid::nil()).label(),
+        ];
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let mut all_disks = Vec::new();
+        let mut paginator = Paginator::new(
+            SQL_BATCH_SIZE,
+            dropshot::PaginationOrder::Ascending,
+        );
+        while let Some(p) = paginator.next() {
+            let mut query =
+                paginated(dsl::disk, dsl::id, &p.current_pagparams())
+                    .filter(dsl::time_deleted.is_null())
+                    .filter(dsl::project_id.eq(schedule.project_id))
+                    .filter(dsl::disk_state.eq_any(ok_to_snapshot_state_labels))
+                    .into_boxed();
+            if let Some(disk_id) = schedule.disk_id {
+                query = query.filter(dsl::id.eq(disk_id));
+            }
+            let batch = query
+                .select(Disk::as_select())
+                .load_async(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?;
+            paginator = p.found_batch(&batch, &|d: &Disk| d.id());
+            all_disks.extend(batch);
+        }
+
+        Ok(all_disks)
+    }
+
+    /// List the snapshots of a disk that a snapshot schedule created, newest
+    /// first
+    pub async fn snapshot_schedule_list_snapshots(
+        &self,
+        opctx: &OpContext,
+        schedule_id: Uuid,
+        disk_id: Uuid,
+    ) -> ListResultVec<Snapshot> {
+        opctx.authorize(authz::Action::ListChildren, &authz::FLEET).await?;
+
+        use nexus_db_schema::schema::snapshot::dsl;
+        dsl::snapshot
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::snapshot_schedule_id.eq(schedule_id))
+            .filter(dsl::disk_id.eq(disk_id))
+            .order_by(dsl::time_created.desc())
+            .select(Snapshot::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::pub_test_utils::TestDatabase;
+    use crate::db::pub_test_utils::helpers::create_project;
+    use nexus_types::external_api::params;
+    use omicron_common::api::external::IdentityMetadataCreateParams;
+    use omicron_test_utils::dev;
+
+    #[tokio::test]
+    async fn test_snapshot_schedule_is_due() {
+        let logctx = dev::test_setup_log("test_snapshot_schedule_is_due");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        let (authz_project, _) =
+            create_project(&opctx, &datastore, "project").await;
+
+        let schedule = datastore
+            .snapshot_schedule_create(
+                &opctx,
+                &authz_project,
+                SnapshotSchedule::new(
+                    authz_project.id(),
+                    None,
+                    params::SnapshotScheduleCreate {
+                        identity: IdentityMetadataCreateParams {
+                            name: "hourly".parse().unwrap(),
+                            description: String::from("hourly snapshots"),
+                        },
+                        disk: None,
+                        interval_secs: 3600,
+                        retain_count: 24,
+                    },
+                ),
+            )
+            .await
+            .unwrap();
+
+        // A schedule that has never run is due immediately.
+        let now = Utc::now();
+        assert!(schedule.is_due(now));
+
+        datastore
+            .snapshot_schedule_mark_run(&opctx, schedule.id(), now)
+            .await
+            .unwrap();
+
+        let schedules =
+            datastore.snapshot_schedule_list_all_batched(&opctx).await.unwrap();
+        assert_eq!(schedules.len(), 1);
+        let schedule = &schedules[0];
+        assert!(!schedule.is_due(now));
+        assert!(!schedule.is_due(now + chrono::TimeDelta::minutes(59)));
+        assert!(schedule.is_due(now + chrono::TimeDelta::hours(1)));
+
+        // The project has no disks to snapshot.
+        assert!(
+            datastore
+                .snapshot_schedule_list_disks(&opctx, schedule)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
@@ -0,0 +1,274 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods related to technician port update progress.
+//!
+//! Updates driven from the technician port (via wicket and wicketd) happen
+//! outside the control plane's own update system.  wicketd periodically
+//! reports the progress of those updates to Nexus so that they're visible to
+//! operators who aren't sitting at the technician port.
+
+use super::DataStore;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::model::TechnicianPortUpdate;
+use crate::db::model::TechnicianPortUpdateComponent;
+use crate::db::pagination::paginated;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use nexus_types::internal_api::params::TechnicianPortUpdateProgress;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+impl DataStore {
+    /// Records the latest reported progress for a set of technician port
+    /// updates
+    ///
+    /// Updates that haven't been seen before are created; for those that
+    /// have, the reported progress of each component replaces whatever was
+    /// recorded previously.
+    pub async fn technician_port_update_report(
+        &self,
+        opctx: &OpContext,
+        updates: Vec<TechnicianPortUpdateProgress>,
+    ) -> Result<(), Error> {
+        use nexus_db_schema::schema::technician_port_update::dsl;
+        use nexus_db_schema::schema::technician_port_update_component::dsl as component_dsl;
+
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        self.transaction_retry_wrapper("technician_port_update_report")
+            .transaction(&conn, |conn| {
+                let updates = updates.clone();
+                async move {
+                    for update in updates {
+                        let record = TechnicianPortUpdate::new(
+                            update.id,
+                            update.sp_type,
+                            update.sp_slot,
+                        );
+                        diesel::insert_into(dsl::technician_port_update)
+                            .values(record)
+                            .on_conflict(dsl::id)
+                            .do_update()
+                            .set(dsl::time_reported.eq(Utc::now()))
+                            .execute_async(&conn)
+                            .await?;
+
+                        let components: Vec<_> = update
+                            .components
+                            .into_iter()
+                            .map(|progress| {
+                                TechnicianPortUpdateComponent::new(
+                                    update.id, progress,
+                                )
+                            })
+                            .collect();
+                        if components.is_empty() {
+                            continue;
+                        }
+                        diesel::insert_into(
+                            component_dsl::technician_port_update_component,
+                        )
+                        .values(components)
+                        .on_conflict((
+                            component_dsl::update_id,
+                            component_dsl::component,
+                        ))
+                        .do_update()
+                        .set((
+                            component_dsl::state
+                                .eq(excluded(component_dsl::state)),
+                            component_dsl::steps_completed
+                                .eq(excluded(component_dsl::steps_completed)),
+                            component_dsl::total_steps
+                                .eq(excluded(component_dsl::total_steps)),
+                            component_dsl::current_step
+                                .eq(excluded(component_dsl::current_step)),
+                            component_dsl::message
+                                .eq(excluded(component_dsl::message)),
+                        ))
+                        .execute_async(&conn)
+                        .await?;
+                    }
+                    Ok(())
+                }
+            })
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Lists technician port updates, along with the most recently reported
+    /// progress of each of their components
+    pub async fn technician_port_update_list(
+        &self,
+        opctx: &OpContext,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<(TechnicianPortUpdate, Vec<TechnicianPortUpdateComponent>)>
+    {
+        use nexus_db_schema::schema::technician_port_update::dsl;
+        use nexus_db_schema::schema::technician_port_update_component::dsl as component_dsl;
+
+        opctx.authorize(authz::Action::ListChildren, &authz::FLEET).await?;
+
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let updates =
+            paginated(dsl::technician_port_update, dsl::id, pagparams)
+                .select(TechnicianPortUpdate::as_select())
+                .load_async(&*conn)
+                .await
+                .map_err(|e| {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                })?;
+
+        let ids: Vec<Uuid> = updates.iter().map(|update| update.id).collect();
+        let mut components_by_update: BTreeMap<Uuid, Vec<_>> = BTreeMap::new();
+        for component in component_dsl::technician_port_update_component
+            .filter(component_dsl::update_id.eq_any(ids))
+            .order_by((component_dsl::update_id, component_dsl::component))
+            .select(TechnicianPortUpdateComponent::as_select())
+            .load_async(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?
+        {
+            components_by_update
+                .entry(component.update_id)
+                .or_default()
+                .push(component);
+        }
+
+        Ok(updates
+            .into_iter()
+            .map(|update| {
+                let components =
+                    components_by_update.remove(&update.id).unwrap_or_default();
+                (update, components)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db::pub_test_utils::TestDatabase;
+    use dropshot::PaginationOrder;
+    use nexus_types::external_api::shared::ServiceProcessorType;
+    use nexus_types::external_api::shared::UpdateComponentKind;
+    use nexus_types::external_api::shared::UpdateComponentProgress;
+    use nexus_types::external_api::shared::UpdateComponentState;
+    use nexus_types::internal_api::params::TechnicianPortUpdateProgress;
+    use omicron_common::api::external::DataPageParams;
+    use omicron_test_utils::dev;
+    use std::num::NonZeroU32;
+    use uuid::Uuid;
+
+    fn progress(
+        component: UpdateComponentKind,
+        state: UpdateComponentState,
+        steps_completed: u32,
+    ) -> UpdateComponentProgress {
+        UpdateComponentProgress {
+            component,
+            state,
+            steps_completed,
+            total_steps: 3,
+            current_step: None,
+            message: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_technician_port_update_report() {
+        let logctx = dev::test_setup_log("test_technician_port_update_report");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        let pagparams = DataPageParams {
+            marker: None,
+            direction: PaginationOrder::Ascending,
+            limit: NonZeroU32::new(100).unwrap(),
+        };
+        assert!(
+            datastore
+                .technician_port_update_list(opctx, &pagparams)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Report initial progress for an update.
+        let update_id = Uuid::new_v4();
+        let mut update = TechnicianPortUpdateProgress {
+            id: update_id,
+            sp_type: ServiceProcessorType::Sled,
+            sp_slot: 7,
+            components: vec![
+                progress(
+                    UpdateComponentKind::Rot,
+                    UpdateComponentState::Updating,
+                    1,
+                ),
+                progress(
+                    UpdateComponentKind::Sp,
+                    UpdateComponentState::Waiting,
+                    0,
+                ),
+            ],
+        };
+        datastore
+            .technician_port_update_report(opctx, vec![update.clone()])
+            .await
+            .expect("reported progress");
+
+        let updates = datastore
+            .technician_port_update_list(opctx, &pagparams)
+            .await
+            .unwrap();
+        assert_eq!(updates.len(), 1);
+        let (first, components) = updates.into_iter().next().unwrap();
+        let view = first.clone().into_view(components);
+        assert_eq!(view.id, update_id);
+        assert_eq!(view.sp_type, ServiceProcessorType::Sled);
+        assert_eq!(view.sp_slot, 7);
+        assert_eq!(view.components, update.components);
+
+        // Report later progress; this should replace the existing component
+        // rows and bump the report time, but not the start time.
+        update.components = vec![
+            progress(
+                UpdateComponentKind::Rot,
+                UpdateComponentState::Updated,
+                3,
+            ),
+            progress(UpdateComponentKind::Sp, UpdateComponentState::Failed, 2),
+        ];
+        update.components[1].message = Some("injected failure".to_string());
+        datastore
+            .technician_port_update_report(opctx, vec![update.clone()])
+            .await
+            .expect("reported progress");
+
+        let updates = datastore
+            .technician_port_update_list(opctx, &pagparams)
+            .await
+            .unwrap();
+        assert_eq!(updates.len(), 1);
+        let (second, components) = updates.into_iter().next().unwrap();
+        assert_eq!(second.time_started, first.time_started);
+        assert!(second.time_reported >= first.time_reported);
+        let view = second.into_view(components);
+        assert_eq!(view.components, update.components);
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
@@ -454,11 +454,13 @@ mod test {
                             params::InstanceNetworkInterfaceAttachment::None,
                         external_ips: Vec::new(),
                         disks: Vec::new(),
-                        boot_disk: None,
+                        boot_order: Vec::new(),
                         ssh_public_keys: None,
                         start: false,
                         auto_restart_policy: Default::default(),
                         anti_affinity_groups: Vec::new(),
+                        tags: Default::default(),
+                        stopped_reservation_policy: Default::default(),
                     },
                 ),
             )
@@ -33,6 +33,7 @@ use omicron_common::api::external::UpdateResult;
 use omicron_common::api::internal::nexus;
 use omicron_common::api::internal::nexus::Migrations;
 use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
 use omicron_uuid_kinds::PropolisUuid;
 use std::net::SocketAddr;
 use uuid::Uuid;
@@ -131,6 +132,25 @@ impl DataStore {
         Ok(vmm)
     }
 
+    /// Lists the VMMs created for the instance with ID `instance_id` that
+    /// haven't been deleted yet, whatever their state.
+    ///
+    /// This doesn't require the instance record to exist (or to be intact).
+    pub async fn vmm_list_by_instance(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+    ) -> ListResultVec<Vmm> {
+        dsl::vmm
+            .filter(dsl::instance_id.eq(instance_id.into_untyped_uuid()))
+            .filter(dsl::time_deleted.is_null())
+            .order_by(dsl::time_created)
+            .select(Vmm::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
     pub async fn vmm_update_runtime(
         &self,
         vmm_id: &PropolisUuid,
@@ -448,7 +468,6 @@ mod tests {
     use crate::db::pub_test_utils::TestDatabase;
     use omicron_common::api::internal::nexus;
     use omicron_test_utils::dev;
-    use omicron_uuid_kinds::InstanceUuid;
 
     #[tokio::test]
     async fn test_vmm_and_migration_update_runtime() {
@@ -325,7 +325,7 @@ impl DataStore {
             .optional()
     }
 
-    async fn read_only_target_to_volume_resource_usage(
+    pub(super) async fn read_only_target_to_volume_resource_usage(
         conn: &async_bb8_diesel::Connection<DbConnection>,
         read_only_target: &SocketAddrV6,
     ) -> Result<Option<VolumeResourceUsage>, diesel::result::Error> {
@@ -452,6 +452,31 @@ impl DataStore {
     }
 
     /// Return a `Option<Volume>` based on id, even if it's soft deleted.
+    /// Returns whether a volume still has a read-only parent, i.e. whether it
+    /// still depends on the contents of the volume it was created from
+    pub async fn volume_has_read_only_parent(
+        &self,
+        volume_id: VolumeUuid,
+    ) -> LookupResult<bool> {
+        let Some(volume) = self.volume_get(volume_id).await? else {
+            return Err(Error::internal_error(&format!(
+                "volume {volume_id} not found"
+            )));
+        };
+
+        let vcr: VolumeConstructionRequest =
+            serde_json::from_str(volume.data()).map_err(|e| {
+                Error::internal_error(&format!(
+                    "failed to deserialize volume {volume_id} data: {e}"
+                ))
+            })?;
+
+        Ok(matches!(
+            vcr,
+            VolumeConstructionRequest::Volume { read_only_parent: Some(_), .. }
+        ))
+    }
+
     pub async fn volume_get(
         &self,
         volume_id: VolumeUuid,
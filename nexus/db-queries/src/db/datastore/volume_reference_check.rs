@@ -0,0 +1,544 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods for checking (and repairing) the reference counts of
+//! read-only Crucible resources.
+//!
+//! Read-only resources (region snapshots and read-only regions) can be shared
+//! by many volumes, and each volume's use of one is recorded in the
+//! `volume_resource_usage` table. A resource is freed when its last usage
+//! record is removed, so a missing record means the resource may be freed
+//! while a volume still uses it, and a stale record means it's never freed.
+
+use super::DataStore;
+use super::volume::CrucibleTargets;
+use super::volume::read_only_resources_associated_with_volume;
+use crate::db::datastore::OpContext;
+use crate::db::datastore::SQL_BATCH_SIZE;
+use crate::db::identity::Asset;
+use crate::db::model::Volume;
+use crate::db::model::VolumeResourceUsage;
+use crate::db::model::VolumeResourceUsageRecord;
+use crate::db::model::VolumeResourceUsageType;
+use crate::db::model::to_db_typed_uuid;
+use crate::db::pagination::Paginator;
+use crate::db::pagination::paginated;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::OptionalError;
+use nexus_db_errors::public_error_from_diesel;
+use nexus_db_lookup::DbConnection;
+use nexus_db_schema::schema::volume_resource_usage;
+use nexus_types::internal_api::views::VolumeFreedTarget;
+use nexus_types::internal_api::views::VolumeReferenceCheckReport;
+use nexus_types::internal_api::views::VolumeReferenceInconsistency;
+use omicron_common::api::external::Error;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::VolumeUuid;
+use sled_agent_client::VolumeConstructionRequest;
+use std::collections::BTreeMap;
+use std::net::SocketAddrV6;
+use uuid::Uuid;
+
+/// How many times a volume references, and has usage records for, a single
+/// read-only resource
+#[derive(Debug, Default)]
+struct ReferenceCount {
+    references: usize,
+    records: usize,
+}
+
+impl DataStore {
+    /// Checks that the recorded usage of read-only Crucible resources matches
+    /// what volumes (that haven't been deleted) actually reference, and
+    /// optionally repairs any mismatches.
+    ///
+    /// Only mismatched usage records are repaired: a missing record is added,
+    /// and a stale record is removed. A volume that references a resource that
+    /// has already been freed (or is being deleted) is reported, but can't be
+    /// fixed here.
+    ///
+    /// The initial scan isn't a consistent snapshot of the database, as volumes
+    /// may be created, deleted, or have their resources replaced while it
+    /// runs. Each repair is therefore made in its own transaction, which first
+    /// re-checks that the mismatch still exists.
+    pub async fn volume_reference_check(
+        &self,
+        opctx: &OpContext,
+        repair: bool,
+    ) -> Result<VolumeReferenceCheckReport, Error> {
+        opctx.check_complex_operations_allowed()?;
+
+        let mut report =
+            VolumeReferenceCheckReport { repair, ..Default::default() };
+        let conn = self.pool_connection_authorized(opctx).await?;
+
+        let mut counts: BTreeMap<
+            (VolumeUuid, VolumeResourceUsage),
+            ReferenceCount,
+        > = BTreeMap::new();
+
+        // First, find everything that volumes reference.
+        let mut paginator = Paginator::new(
+            SQL_BATCH_SIZE,
+            dropshot::PaginationOrder::Ascending,
+        );
+        while let Some(p) = paginator.next() {
+            use nexus_db_schema::schema::volume::dsl;
+
+            let volumes =
+                paginated(dsl::volume, dsl::id, &p.current_pagparams())
+                    .filter(dsl::time_deleted.is_null())
+                    .select(Volume::as_select())
+                    .get_results_async::<Volume>(&*conn)
+                    .await
+                    .map_err(|e| {
+                        public_error_from_diesel(e, ErrorHandler::Server)
+                    })?;
+            paginator = p.found_batch(&volumes, &|v| *v.id().as_untyped_uuid());
+
+            for volume in volumes {
+                report.volumes_checked += 1;
+
+                let targets = match volume_read_only_targets(&volume) {
+                    Ok(targets) => targets,
+                    Err(e) => {
+                        report
+                            .errors
+                            .push(format!("volume {}: {e}", volume.id()));
+                        continue;
+                    }
+                };
+
+                for target in targets {
+                    let maybe_usage =
+                        Self::read_only_target_to_volume_resource_usage(
+                            &conn, &target,
+                        )
+                        .await
+                        .map_err(|e| {
+                            public_error_from_diesel(e, ErrorHandler::Server)
+                        })?;
+
+                    match maybe_usage {
+                        Some(usage) => {
+                            counts
+                                .entry((volume.id(), usage))
+                                .or_default()
+                                .references += 1;
+                        }
+                        None => {
+                            report.freed_while_referenced.push(
+                                VolumeFreedTarget {
+                                    volume_id: volume.id(),
+                                    target,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Then, compare that with the usage that's been recorded.
+        let mut paginator = Paginator::new(
+            SQL_BATCH_SIZE,
+            dropshot::PaginationOrder::Ascending,
+        );
+        while let Some(p) = paginator.next() {
+            use nexus_db_schema::schema::volume_resource_usage::dsl;
+
+            let records = paginated(
+                dsl::volume_resource_usage,
+                dsl::usage_id,
+                &p.current_pagparams(),
+            )
+            .select(VolumeResourceUsageRecord::as_select())
+            .get_results_async::<VolumeResourceUsageRecord>(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+            paginator = p.found_batch(&records, &|r| r.usage_id);
+
+            for record in records {
+                report.usage_records_checked += 1;
+
+                let volume_id = record.volume_id();
+                let usage_id = record.usage_id;
+                match VolumeResourceUsage::try_from(record) {
+                    Ok(usage) => {
+                        counts
+                            .entry((volume_id, usage))
+                            .or_default()
+                            .records += 1;
+                    }
+                    Err(e) => {
+                        report.errors.push(format!(
+                            "usage record {usage_id} is not a {e}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        for ((volume_id, usage), count) in counts {
+            let missing = count.references.saturating_sub(count.records);
+            for _ in 0..missing {
+                let inconsistency = self
+                    .volume_reference_inconsistency(
+                        &conn,
+                        &mut report,
+                        volume_id,
+                        &usage,
+                    )
+                    .await;
+                report.missing_usage.push(inconsistency);
+            }
+
+            let stale = count.records.saturating_sub(count.references);
+            for _ in 0..stale {
+                let inconsistency = self
+                    .volume_reference_inconsistency(
+                        &conn,
+                        &mut report,
+                        volume_id,
+                        &usage,
+                    )
+                    .await;
+                report.stale_usage.push(inconsistency);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Describes (and, if we're repairing, repairs) one mismatch between a
+    /// volume's references to a resource and its usage records for it
+    async fn volume_reference_inconsistency(
+        &self,
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        report: &mut VolumeReferenceCheckReport,
+        volume_id: VolumeUuid,
+        usage: &VolumeResourceUsage,
+    ) -> VolumeReferenceInconsistency {
+        let repaired = if report.repair {
+            match self.volume_reference_repair(conn, volume_id, usage).await {
+                Ok(repaired) => repaired,
+                Err(e) => {
+                    report.errors.push(format!(
+                        "failed to repair usage of {usage:?} by volume \
+                         {volume_id}: {e}"
+                    ));
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
+        VolumeReferenceInconsistency {
+            volume_id,
+            resource: usage.clone().into(),
+            repaired,
+        }
+    }
+
+    /// Adds or removes a single usage record for a volume's use of a read-only
+    /// resource, if the number of records for it still doesn't match the
+    /// number of references to it.
+    ///
+    /// Returns whether a record was added or removed.
+    async fn volume_reference_repair(
+        &self,
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        volume_id: VolumeUuid,
+        usage: &VolumeResourceUsage,
+    ) -> Result<bool, Error> {
+        let err = OptionalError::new();
+        self.transaction_retry_wrapper("volume_reference_repair")
+            .transaction(conn, |conn| {
+                let err = err.clone();
+                let usage = usage.clone();
+                async move {
+                    let count = Self::volume_reference_count_on_conn(
+                        &conn, &err, volume_id, &usage,
+                    )
+                    .await?;
+
+                    if count.records < count.references {
+                        use nexus_db_schema::schema::volume_resource_usage::dsl;
+                        diesel::insert_into(dsl::volume_resource_usage)
+                            .values(VolumeResourceUsageRecord::new(
+                                volume_id, usage,
+                            ))
+                            .execute_async(&conn)
+                            .await?;
+                        Ok(true)
+                    } else if count.records > count.references {
+                        use nexus_db_schema::schema::volume_resource_usage::dsl;
+                        let usage_id: Uuid =
+                            volume_usage_records_query(volume_id, &usage)
+                                .select(dsl::usage_id)
+                                .first_async(&conn)
+                                .await?;
+                        diesel::delete(dsl::volume_resource_usage)
+                            .filter(dsl::usage_id.eq(usage_id))
+                            .execute_async(&conn)
+                            .await?;
+                        Ok(true)
+                    } else {
+                        Ok(false)
+                    }
+                }
+            })
+            .await
+            .map_err(|e| {
+                if let Some(err) = err.take() {
+                    Error::internal_error(&err)
+                } else {
+                    public_error_from_diesel(e, ErrorHandler::Server)
+                }
+            })
+    }
+
+    async fn volume_reference_count_on_conn(
+        conn: &async_bb8_diesel::Connection<DbConnection>,
+        err: &OptionalError<String>,
+        volume_id: VolumeUuid,
+        usage: &VolumeResourceUsage,
+    ) -> Result<ReferenceCount, diesel::result::Error> {
+        let mut count = ReferenceCount::default();
+
+        // A deleted volume doesn't reference anything.
+        let maybe_volume = Self::volume_get_impl(conn, volume_id).await?;
+        if let Some(volume) =
+            maybe_volume.filter(|volume| volume.time_deleted.is_none())
+        {
+            let targets = volume_read_only_targets(&volume)
+                .map_err(|e| err.bail(format!("volume {volume_id}: {e}")))?;
+            for target in targets {
+                if Self::read_only_target_to_volume_resource_usage(
+                    conn, &target,
+                )
+                .await?
+                .as_ref()
+                    == Some(usage)
+                {
+                    count.references += 1;
+                }
+            }
+        }
+
+        let records: i64 = volume_usage_records_query(volume_id, usage)
+            .count()
+            .get_result_async(conn)
+            .await?;
+        count.records = records as usize;
+
+        Ok(count)
+    }
+}
+
+/// Returns a query for a volume's usage records for a read-only resource
+fn volume_usage_records_query(
+    volume_id: VolumeUuid,
+    usage: &VolumeResourceUsage,
+) -> volume_resource_usage::BoxedQuery<'static, Pg> {
+    use nexus_db_schema::schema::volume_resource_usage::dsl;
+
+    let query = dsl::volume_resource_usage
+        .filter(dsl::volume_id.eq(to_db_typed_uuid(volume_id)))
+        .into_boxed();
+    match usage {
+        VolumeResourceUsage::ReadOnlyRegion { region_id } => query
+            .filter(dsl::usage_type.eq(VolumeResourceUsageType::ReadOnlyRegion))
+            .filter(dsl::region_id.eq(*region_id)),
+        VolumeResourceUsage::RegionSnapshot {
+            dataset_id,
+            region_id,
+            snapshot_id,
+        } => query
+            .filter(dsl::usage_type.eq(VolumeResourceUsageType::RegionSnapshot))
+            .filter(
+                dsl::region_snapshot_dataset_id
+                    .eq(to_db_typed_uuid(*dataset_id)),
+            )
+            .filter(dsl::region_snapshot_region_id.eq(*region_id))
+            .filter(dsl::region_snapshot_snapshot_id.eq(*snapshot_id)),
+    }
+}
+
+/// Returns the read-only targets referenced by a volume
+fn volume_read_only_targets(
+    volume: &Volume,
+) -> Result<Vec<SocketAddrV6>, String> {
+    let vcr: VolumeConstructionRequest = serde_json::from_str(volume.data())
+        .map_err(|e| format!("cannot deserialize volume data: {e}"))?;
+
+    let mut crucible_targets = CrucibleTargets::default();
+    read_only_resources_associated_with_volume(&vcr, &mut crucible_targets);
+
+    crucible_targets
+        .read_only_targets
+        .iter()
+        .map(|target| {
+            target
+                .parse()
+                .map_err(|e| format!("could not parse target {target}: {e}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::db::model::RegionSnapshot;
+    use crate::db::pub_test_utils::TestDatabase;
+    use nexus_types::internal_api::views::VolumeReadOnlyResource;
+    use omicron_test_utils::dev;
+    use omicron_uuid_kinds::DatasetUuid;
+    use sled_agent_client::CrucibleOpts;
+
+    #[tokio::test]
+    async fn test_volume_reference_check() {
+        let logctx = dev::test_setup_log("test_volume_reference_check");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+        let conn = datastore.pool_connection_for_tests().await.unwrap();
+
+        // Create a volume with a region snapshot as its read-only parent.
+        let address: SocketAddrV6 =
+            "[fd00:1122:3344:104::1]:400".parse().unwrap();
+        let region_snapshot = RegionSnapshot::new(
+            DatasetUuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            address.to_string(),
+        );
+        datastore
+            .region_snapshot_create(region_snapshot.clone())
+            .await
+            .unwrap();
+
+        let volume_id = VolumeUuid::new_v4();
+        datastore
+            .volume_create(
+                volume_id,
+                VolumeConstructionRequest::Volume {
+                    id: *volume_id.as_untyped_uuid(),
+                    block_size: 512,
+                    sub_volumes: vec![],
+                    read_only_parent: Some(Box::new(
+                        VolumeConstructionRequest::Region {
+                            block_size: 512,
+                            blocks_per_extent: 10,
+                            extent_count: 10,
+                            gen: 1,
+                            opts: CrucibleOpts {
+                                id: Uuid::new_v4(),
+                                target: vec![address.into()],
+                                lossy: false,
+                                flush_timeout: None,
+                                key: None,
+                                cert_pem: None,
+                                key_pem: None,
+                                root_cert_pem: None,
+                                control: None,
+                                read_only: true,
+                            },
+                        },
+                    )),
+                },
+            )
+            .await
+            .unwrap();
+
+        let usage = VolumeResourceUsage::RegionSnapshot {
+            dataset_id: region_snapshot.dataset_id(),
+            region_id: region_snapshot.region_id,
+            snapshot_id: region_snapshot.snapshot_id,
+        };
+        let resource = VolumeReadOnlyResource::from(usage.clone());
+
+        // Initially, everything is consistent.
+        let report =
+            datastore.volume_reference_check(&opctx, false).await.unwrap();
+        assert_eq!(report.volumes_checked, 1);
+        assert_eq!(report.usage_records_checked, 1);
+        assert!(report.missing_usage.is_empty());
+        assert!(report.stale_usage.is_empty());
+        assert!(report.freed_while_referenced.is_empty());
+        assert!(report.errors.is_empty());
+
+        // Remove the volume's usage record, as though its reference had been
+        // lost. Checking should report it, but not add it back...
+        {
+            use nexus_db_schema::schema::volume_resource_usage::dsl;
+            diesel::delete(dsl::volume_resource_usage)
+                .filter(dsl::volume_id.eq(to_db_typed_uuid(volume_id)))
+                .execute_async(&*conn)
+                .await
+                .unwrap();
+        }
+        let expected = VolumeReferenceInconsistency {
+            volume_id,
+            resource: resource.clone(),
+            repaired: false,
+        };
+        let report =
+            datastore.volume_reference_check(&opctx, false).await.unwrap();
+        assert_eq!(report.missing_usage, vec![expected.clone()]);
+        assert!(report.stale_usage.is_empty());
+        let report =
+            datastore.volume_reference_check(&opctx, false).await.unwrap();
+        assert_eq!(report.missing_usage, vec![expected.clone()]);
+
+        // ...until we ask it to.
+        let report =
+            datastore.volume_reference_check(&opctx, true).await.unwrap();
+        assert_eq!(
+            report.missing_usage,
+            vec![VolumeReferenceInconsistency {
+                repaired: true,
+                ..expected.clone()
+            }]
+        );
+        assert!(report.errors.is_empty());
+        let report =
+            datastore.volume_reference_check(&opctx, false).await.unwrap();
+        assert!(report.missing_usage.is_empty());
+        assert!(report.stale_usage.is_empty());
+
+        // Add an extra usage record, as though a reference had been leaked.
+        {
+            use nexus_db_schema::schema::volume_resource_usage::dsl;
+            diesel::insert_into(dsl::volume_resource_usage)
+                .values(VolumeResourceUsageRecord::new(volume_id, usage))
+                .execute_async(&*conn)
+                .await
+                .unwrap();
+        }
+        let report =
+            datastore.volume_reference_check(&opctx, false).await.unwrap();
+        assert!(report.missing_usage.is_empty());
+        assert_eq!(report.stale_usage, vec![expected.clone()]);
+
+        let report =
+            datastore.volume_reference_check(&opctx, true).await.unwrap();
+        assert_eq!(
+            report.stale_usage,
+            vec![VolumeReferenceInconsistency { repaired: true, ..expected }]
+        );
+        let report =
+            datastore.volume_reference_check(&opctx, false).await.unwrap();
+        assert_eq!(report.usage_records_checked, 1);
+        assert!(report.missing_usage.is_empty());
+        assert!(report.stale_usage.is_empty());
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
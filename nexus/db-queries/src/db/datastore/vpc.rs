@@ -30,6 +30,7 @@ use crate::db::model::VpcRouter;
 use crate::db::model::VpcRouterKind;
 use crate::db::model::VpcRouterUpdate;
 use crate::db::model::VpcSubnet;
+use crate::db::model::VpcSubnetIpReservation;
 use crate::db::model::VpcSubnetUpdate;
 use crate::db::model::VpcUpdate;
 use crate::db::model::{Ipv4Net, Ipv6Net};
@@ -66,6 +67,7 @@ use nexus_db_model::IpPoolRange;
 use nexus_db_model::NetworkInterfaceKind;
 use nexus_types::deployment::SledFilter;
 use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
 use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::Error;
 use omicron_common::api::external::IdentityMetadataCreateParams;
@@ -88,6 +90,7 @@ use omicron_common::api::internal::shared::RouterTarget;
 use oxnet::IpNet;
 use ref_cast::RefCast;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::net::IpAddr;
@@ -669,6 +672,37 @@ impl DataStore {
         &self,
         opctx: &OpContext,
         authz_vpc: &authz::Vpc,
+        rules: Vec<VpcFirewallRule>,
+    ) -> UpdateResult<Vec<VpcFirewallRule>> {
+        self.vpc_update_firewall_rules_impl(opctx, authz_vpc, None, rules).await
+    }
+
+    /// Replace all firewall rules with the given rules, provided the VPC's
+    /// live rules are still exactly those with IDs `expected_rule_ids`.
+    ///
+    /// This fails with a conflict if the rules were replaced concurrently,
+    /// e.g., between previewing a replacement and applying it.
+    pub async fn vpc_update_firewall_rules_if_unchanged(
+        &self,
+        opctx: &OpContext,
+        authz_vpc: &authz::Vpc,
+        expected_rule_ids: BTreeSet<Uuid>,
+        rules: Vec<VpcFirewallRule>,
+    ) -> UpdateResult<Vec<VpcFirewallRule>> {
+        self.vpc_update_firewall_rules_impl(
+            opctx,
+            authz_vpc,
+            Some(expected_rule_ids),
+            rules,
+        )
+        .await
+    }
+
+    async fn vpc_update_firewall_rules_impl(
+        &self,
+        opctx: &OpContext,
+        authz_vpc: &authz::Vpc,
+        expected_rule_ids: Option<BTreeSet<Uuid>>,
         mut rules: Vec<VpcFirewallRule>,
     ) -> UpdateResult<Vec<VpcFirewallRule>> {
         opctx.authorize(authz::Action::Modify, authz_vpc).await?;
@@ -694,6 +728,7 @@ impl DataStore {
         #[derive(Debug)]
         enum FirewallUpdateError {
             CollectionNotFound,
+            RulesChanged,
         }
 
         let err = OptionalError::new();
@@ -709,7 +744,24 @@ impl DataStore {
                 let err = err.clone();
                 let delete_old_query = delete_old_query.clone();
                 let rules = rules.clone();
+                let expected_rule_ids = expected_rule_ids.clone();
                 async move {
+                    if let Some(expected_rule_ids) = expected_rule_ids {
+                        let current_rule_ids = dsl::vpc_firewall_rule
+                            .filter(dsl::time_deleted.is_null())
+                            .filter(dsl::vpc_id.eq(authz_vpc.id()))
+                            .select(dsl::id)
+                            .load_async::<Uuid>(&conn)
+                            .await?
+                            .into_iter()
+                            .collect::<BTreeSet<_>>();
+                        if current_rule_ids != expected_rule_ids {
+                            return Err(
+                                err.bail(FirewallUpdateError::RulesChanged)
+                            );
+                        }
+                    }
+
                     delete_old_query.execute_async(&conn).await?;
 
                     // The generation count update on the vpc table row will take a
@@ -743,6 +795,9 @@ impl DataStore {
                                 &authz_vpc.id(),
                             )
                         }
+                        FirewallUpdateError::RulesChanged => Error::conflict(
+                            "VPC firewall rules were changed concurrently",
+                        ),
                     }
                 } else {
                     public_error_from_diesel(
@@ -886,13 +941,15 @@ impl DataStore {
                 ),
             )
             // Filter out services that are expunged and shouldn't be resolved
-            // here.
+            // here. (Cordoned zones are still running, so they still need
+            // firewall rules.)
             //
             // TODO: We should reference a rendezvous table instead of filtering
             // for in-service zones.
-            .filter(
-                bp_omicron_zone::disposition.eq(DbBpZoneDisposition::InService),
-            )
+            .filter(bp_omicron_zone::disposition.eq_any([
+                DbBpZoneDisposition::InService,
+                DbBpZoneDisposition::Cordoned,
+            ]))
             .filter(service_network_interface::vpc_id.eq(vpc_id))
             .filter(service_network_interface::time_deleted.is_null())
             .select(Sled::as_select());
@@ -1008,6 +1065,7 @@ impl DataStore {
 
         use nexus_db_schema::schema::network_interface;
         use nexus_db_schema::schema::vpc_subnet::dsl;
+        use nexus_db_schema::schema::vpc_subnet_ip_reservation;
 
         let conn = self.pool_connection_authorized(opctx).await?;
 
@@ -1029,6 +1087,26 @@ impl DataStore {
             ));
         }
 
+        // Verify there are no IP reservations in this VPC Subnet
+        if vpc_subnet_ip_reservation::dsl::vpc_subnet_ip_reservation
+            .filter(
+                vpc_subnet_ip_reservation::dsl::subnet_id.eq(authz_subnet.id()),
+            )
+            .filter(vpc_subnet_ip_reservation::dsl::time_deleted.is_null())
+            .select(vpc_subnet_ip_reservation::dsl::id)
+            .limit(1)
+            .first_async::<Uuid>(&*conn)
+            .await
+            .optional()
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?
+            .is_some()
+        {
+            return Err(Error::invalid_request(
+                "VPC Subnet cannot be deleted while IP reservations in the \
+                subnet exist",
+            ));
+        }
+
         // Delete the subnet, conditional on the rcgen not having changed.
         let now = Utc::now();
         diesel::update(dsl::vpc_subnet)
@@ -1304,6 +1382,87 @@ impl DataStore {
         .await
     }
 
+    /// Add a secondary IPv4 block to a VPC Subnet.
+    ///
+    /// The block must not overlap any IPv4 block of a live subnet in the same
+    /// VPC, including the subnet itself. The caller is responsible for checking
+    /// that the block is otherwise valid for a VPC Subnet.
+    pub async fn vpc_subnet_add_ipv4_block(
+        &self,
+        opctx: &OpContext,
+        authz_subnet: &authz::VpcSubnet,
+        block: oxnet::Ipv4Net,
+    ) -> UpdateResult<VpcSubnet> {
+        opctx.authorize(authz::Action::Modify, authz_subnet).await?;
+
+        #[derive(Debug)]
+        enum AddBlockError {
+            SubnetNotFound,
+            OverlappingIpRange,
+        }
+
+        let err = OptionalError::new();
+        let conn = self.pool_connection_authorized(opctx).await?;
+        self.transaction_retry_wrapper("vpc_subnet_add_ipv4_block")
+            .transaction(&conn, |conn| {
+                let err = err.clone();
+                async move {
+                    use nexus_db_schema::schema::vpc_subnet::dsl;
+
+                    let subnet = dsl::vpc_subnet
+                        .filter(dsl::time_deleted.is_null())
+                        .filter(dsl::id.eq(authz_subnet.id()))
+                        .select(VpcSubnet::as_select())
+                        .get_result_async(&conn)
+                        .await
+                        .optional()?
+                        .ok_or_else(|| {
+                            err.bail(AddBlockError::SubnetNotFound)
+                        })?;
+
+                    let subnets_in_vpc = dsl::vpc_subnet
+                        .filter(dsl::time_deleted.is_null())
+                        .filter(dsl::vpc_id.eq(subnet.vpc_id))
+                        .select(VpcSubnet::as_select())
+                        .load_async(&conn)
+                        .await?;
+                    let new_block = oxnet::IpNet::V4(block);
+                    if subnets_in_vpc
+                        .iter()
+                        .flat_map(|s| s.ipv4_blocks())
+                        .any(|b| oxnet::IpNet::V4(b).overlaps(&new_block))
+                    {
+                        return Err(err.bail(AddBlockError::OverlappingIpRange));
+                    }
+
+                    let mut blocks = subnet.ipv4_secondary_blocks;
+                    blocks.push(Ipv4Net(block));
+                    diesel::update(dsl::vpc_subnet)
+                        .filter(dsl::time_deleted.is_null())
+                        .filter(dsl::id.eq(authz_subnet.id()))
+                        .set((
+                            dsl::ipv4_secondary_blocks.eq(blocks),
+                            dsl::time_modified.eq(Utc::now()),
+                        ))
+                        .returning(VpcSubnet::as_returning())
+                        .get_result_async(&conn)
+                        .await
+                }
+            })
+            .await
+            .map_err(|e| match err.take() {
+                Some(AddBlockError::SubnetNotFound) => authz_subnet.not_found(),
+                Some(AddBlockError::OverlappingIpRange) => {
+                    InsertVpcSubnetError::OverlappingIpRange(block.into())
+                        .into_external()
+                }
+                None => public_error_from_diesel(
+                    e,
+                    ErrorHandler::NotFoundByResource(authz_subnet),
+                ),
+            })
+    }
+
     pub async fn subnet_list_instance_network_interfaces(
         &self,
         opctx: &OpContext,
@@ -1334,6 +1493,89 @@ impl DataStore {
         .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
     }
 
+    /// Reserve a private IP address in a VPC Subnet.
+    ///
+    /// The caller is responsible for checking that the address is requestable
+    /// within the subnet.
+    pub async fn vpc_subnet_ip_reservation_create(
+        &self,
+        opctx: &OpContext,
+        authz_subnet: &authz::VpcSubnet,
+        reservation: VpcSubnetIpReservation,
+    ) -> CreateResult<VpcSubnetIpReservation> {
+        opctx.authorize(authz::Action::CreateChild, authz_subnet).await?;
+
+        use nexus_db_schema::schema::vpc_subnet_ip_reservation::dsl;
+        let ip = reservation.ip.to_string();
+        VpcSubnet::insert_resource(
+            authz_subnet.id(),
+            diesel::insert_into(dsl::vpc_subnet_ip_reservation)
+                .values(reservation),
+        )
+        .insert_and_get_result_async(
+            &*self.pool_connection_authorized(opctx).await?,
+        )
+        .await
+        .map_err(|e| match e {
+            AsyncInsertError::CollectionNotFound => authz_subnet.not_found(),
+            AsyncInsertError::DatabaseError(e) => public_error_from_diesel(
+                e,
+                ErrorHandler::Conflict(
+                    ResourceType::VpcSubnetIpReservation,
+                    &ip,
+                ),
+            ),
+        })
+    }
+
+    /// List the IP reservations in a VPC Subnet.
+    pub async fn vpc_subnet_ip_reservation_list(
+        &self,
+        opctx: &OpContext,
+        authz_subnet: &authz::VpcSubnet,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<VpcSubnetIpReservation> {
+        opctx.authorize(authz::Action::ListChildren, authz_subnet).await?;
+
+        use nexus_db_schema::schema::vpc_subnet_ip_reservation::dsl;
+        paginated(dsl::vpc_subnet_ip_reservation, dsl::id, pagparams)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::subnet_id.eq(authz_subnet.id()))
+            .select(VpcSubnetIpReservation::as_select())
+            .load_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Release the reservation of `ip` in a VPC Subnet.
+    ///
+    /// An interface already using the address keeps it; the address just
+    /// becomes eligible for automatic allocation again once the interface is
+    /// deleted.
+    pub async fn vpc_subnet_ip_reservation_delete(
+        &self,
+        opctx: &OpContext,
+        authz_subnet: &authz::VpcSubnet,
+        ip: IpAddr,
+    ) -> DeleteResult {
+        opctx.authorize(authz::Action::Modify, authz_subnet).await?;
+
+        use nexus_db_schema::schema::vpc_subnet_ip_reservation::dsl;
+        let updated = diesel::update(dsl::vpc_subnet_ip_reservation)
+            .filter(dsl::time_deleted.is_null())
+            .filter(dsl::subnet_id.eq(authz_subnet.id()))
+            .filter(dsl::ip.eq(IpNetwork::from(ip)))
+            .set(dsl::time_deleted.eq(Utc::now()))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+        if updated == 0 {
+            return Err(LookupType::ByCompositeId(ip.to_string())
+                .into_not_found(ResourceType::VpcSubnetIpReservation));
+        }
+        Ok(())
+    }
+
     pub async fn vpc_router_list(
         &self,
         opctx: &OpContext,
@@ -2288,6 +2530,7 @@ impl DataStore {
             name: Name,
             ipv4_block: Ipv4Net,
             ipv6_block: Ipv6Net,
+            ipv4_secondary_blocks: Vec<Ipv4Net>,
         }
 
         use nexus_db_schema::schema::vpc_subnet;
@@ -2299,6 +2542,7 @@ impl DataStore {
                 vpc_subnet::name,
                 vpc_subnet::ipv4_block,
                 vpc_subnet::ipv6_block,
+                vpc_subnet::ipv4_secondary_blocks,
             ))
             .get_results_async::<SubnetIps>(
                 &*self.pool_connection_unauthorized().await?,
@@ -2311,6 +2555,12 @@ impl DataStore {
             let entry = result.entry(subnet.name).or_insert_with(Vec::new);
             entry.push(IpNetwork::V4(subnet.ipv4_block.0.into()));
             entry.push(IpNetwork::V6(subnet.ipv6_block.0.into()));
+            entry.extend(
+                subnet
+                    .ipv4_secondary_blocks
+                    .iter()
+                    .map(|block| IpNetwork::V4(block.0.into())),
+            );
         }
         Ok(result)
     }
@@ -2787,6 +3037,20 @@ impl DataStore {
                     None
                 };
 
+            // Any secondary IPv4 blocks of a subnet destination are routed in
+            // the same way as its primary block.
+            let v4_secondary_dests: Vec<IpNet> =
+                match (&rule.destination.0, parent_subnet) {
+                    (_, Some(sub)) => sub.ipv4_secondary_blocks.iter(),
+                    (RouteDestination::Subnet(n), _) => subnets_by_name
+                        .get(n)
+                        .map(|s| s.ipv4_secondary_blocks.iter())
+                        .unwrap_or_default(),
+                    _ => [].iter(),
+                }
+                .map(|block| block.0.into())
+                .collect();
+
             // Some dests/targets (e.g., subnet) resolve to *several* specifiers
             // to handle both v4 and v6. The user-facing API will prevent severe
             // mistakes on naked IPs/CIDRs (mixed v4/6), but we need to be smarter
@@ -2901,6 +3165,18 @@ impl DataStore {
             if let (Some(dest), Some(target)) = (v6_dest, v6_target) {
                 out.insert(ResolvedVpcRoute { dest, target });
             }
+
+            for dest in v4_secondary_dests {
+                // A VpcSubnet route delivers each of its blocks locally.
+                let target = if parent_subnet.is_some() {
+                    Some(RouterTarget::VpcSubnet(dest))
+                } else {
+                    v4_target
+                };
+                if let Some(target) = target {
+                    out.insert(ResolvedVpcRoute { dest, target });
+                }
+            }
         }
 
         Ok(out)
@@ -3978,11 +4254,13 @@ mod tests {
                             params::InstanceNetworkInterfaceAttachment::None,
                         external_ips: vec![],
                         disks: vec![],
-                        boot_disk: None,
+                        boot_order: Vec::new(),
                         ssh_public_keys: None,
                         start: false,
                         auto_restart_policy: Default::default(),
                         anti_affinity_groups: Vec::new(),
+                        tags: Default::default(),
+                        stopped_reservation_policy: Default::default(),
                     },
                 ),
             )
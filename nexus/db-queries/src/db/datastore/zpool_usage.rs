@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`DataStore`] methods on [`ZpoolUsageSample`]s.
+
+use super::DataStore;
+use super::SQL_BATCH_SIZE;
+use crate::authz;
+use crate::context::OpContext;
+use crate::db::model::ZpoolUsageSample;
+use crate::db::pagination::Paginator;
+use crate::db::pagination::paginated_multicolumn;
+use async_bb8_diesel::AsyncRunQueryDsl;
+use chrono::DateTime;
+use chrono::Utc;
+use diesel::prelude::*;
+use nexus_db_errors::ErrorHandler;
+use nexus_db_errors::public_error_from_diesel;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_uuid_kinds::GenericUuid;
+
+impl DataStore {
+    /// Records zpool usage samples.
+    ///
+    /// Samples that have already been recorded (i.e., for the same zpool and
+    /// time) are ignored, so it's fine to record the samples from a given
+    /// inventory collection more than once. Returns the number of new
+    /// samples.
+    pub async fn zpool_usage_samples_insert(
+        &self,
+        opctx: &OpContext,
+        samples: Vec<ZpoolUsageSample>,
+    ) -> Result<usize, Error> {
+        opctx.authorize(authz::Action::Modify, &authz::INVENTORY).await?;
+        if samples.is_empty() {
+            return Ok(0);
+        }
+
+        use nexus_db_schema::schema::zpool_usage_sample::dsl;
+        diesel::insert_into(dsl::zpool_usage_sample)
+            .values(samples)
+            .on_conflict((dsl::zpool_id, dsl::time_sampled))
+            .do_nothing()
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+
+    /// Lists all zpool usage samples taken at or after `since`, ordered by
+    /// zpool and then by time.
+    pub async fn zpool_usage_samples_list_since(
+        &self,
+        opctx: &OpContext,
+        since: DateTime<Utc>,
+    ) -> ListResultVec<ZpoolUsageSample> {
+        opctx.authorize(authz::Action::Read, &authz::INVENTORY).await?;
+        opctx.check_complex_operations_allowed()?;
+
+        use nexus_db_schema::schema::zpool_usage_sample::dsl;
+        let conn = self.pool_connection_authorized(opctx).await?;
+        let mut samples = Vec::new();
+        let mut paginator = Paginator::new(
+            SQL_BATCH_SIZE,
+            dropshot::PaginationOrder::Ascending,
+        );
+        while let Some(p) = paginator.next() {
+            let batch = paginated_multicolumn(
+                dsl::zpool_usage_sample,
+                (dsl::zpool_id, dsl::time_sampled),
+                &p.current_pagparams(),
+            )
+            .filter(dsl::time_sampled.ge(since))
+            .select(ZpoolUsageSample::as_select())
+            .load_async(&*conn)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))?;
+            paginator = p.found_batch(&batch, &|s: &ZpoolUsageSample| {
+                (s.zpool_id.into_untyped_uuid(), s.time_sampled)
+            });
+            samples.extend(batch);
+        }
+        Ok(samples)
+    }
+
+    /// Deletes all zpool usage samples taken before `before`, returning the
+    /// number deleted.
+    pub async fn zpool_usage_samples_prune(
+        &self,
+        opctx: &OpContext,
+        before: DateTime<Utc>,
+    ) -> Result<usize, Error> {
+        opctx.authorize(authz::Action::Modify, &authz::INVENTORY).await?;
+
+        use nexus_db_schema::schema::zpool_usage_sample::dsl;
+        diesel::delete(dsl::zpool_usage_sample)
+            .filter(dsl::time_sampled.lt(before))
+            .execute_async(&*self.pool_connection_authorized(opctx).await?)
+            .await
+            .map_err(|e| public_error_from_diesel(e, ErrorHandler::Server))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::model::ByteCount;
+    use crate::db::pub_test_utils::TestDatabase;
+    use chrono::Duration;
+    use omicron_common::api::external;
+    use omicron_test_utils::dev;
+    use omicron_uuid_kinds::SledUuid;
+    use omicron_uuid_kinds::ZpoolUuid;
+
+    fn sample(
+        zpool_id: ZpoolUuid,
+        time_sampled: DateTime<Utc>,
+        allocated: u32,
+    ) -> ZpoolUsageSample {
+        ZpoolUsageSample {
+            zpool_id: zpool_id.into(),
+            time_sampled,
+            sled_id: SledUuid::new_v4().into(),
+            total_size: ByteCount::from(external::ByteCount::from(4096u32)),
+            allocated: ByteCount::from(external::ByteCount::from(allocated)),
+            fragmentation_percent: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zpool_usage_samples() {
+        let logctx = dev::test_setup_log("test_zpool_usage_samples");
+        let db = TestDatabase::new_with_datastore(&logctx.log).await;
+        let (opctx, datastore) = (db.opctx(), db.datastore());
+
+        // Truncate to microseconds, which is all CockroachDB stores.
+        let now =
+            DateTime::from_timestamp_micros(Utc::now().timestamp_micros())
+                .unwrap();
+        let old = now - Duration::days(10);
+        let zpool1 = ZpoolUuid::new_v4();
+        let zpool2 = ZpoolUuid::new_v4();
+        let samples = vec![
+            sample(zpool1, old, 100),
+            sample(zpool1, now, 200),
+            sample(zpool2, now, 300),
+        ];
+
+        // Recording the same samples twice is a no-op the second time.
+        let n = datastore
+            .zpool_usage_samples_insert(opctx, samples.clone())
+            .await
+            .expect("inserted samples");
+        assert_eq!(n, 3);
+        let n = datastore
+            .zpool_usage_samples_insert(opctx, samples.clone())
+            .await
+            .expect("inserted samples again");
+        assert_eq!(n, 0);
+
+        let all = datastore
+            .zpool_usage_samples_list_since(opctx, old)
+            .await
+            .expect("listed samples");
+        assert_eq!(all.len(), 3);
+        for s in &samples {
+            assert!(all.contains(s), "missing sample {s:?}");
+        }
+        let recent = datastore
+            .zpool_usage_samples_list_since(opctx, now - Duration::days(1))
+            .await
+            .expect("listed recent samples");
+        assert_eq!(recent.len(), 2);
+        assert!(recent.iter().all(|s| s.time_sampled == now));
+
+        // Pruning removes only samples older than the cutoff.
+        let n = datastore
+            .zpool_usage_samples_prune(opctx, now - Duration::days(1))
+            .await
+            .expect("pruned samples");
+        assert_eq!(n, 1);
+        let all = datastore
+            .zpool_usage_samples_list_since(opctx, old)
+            .await
+            .expect("listed samples");
+        assert_eq!(all, recent);
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+}
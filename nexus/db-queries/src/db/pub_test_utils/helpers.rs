@@ -17,6 +17,7 @@ use nexus_db_model::BlockSize;
 use nexus_db_model::ByteCount;
 use nexus_db_model::Generation;
 use nexus_db_model::Image;
+use nexus_db_model::ImageState;
 use nexus_db_model::Instance;
 use nexus_db_model::InstanceRuntimeState;
 use nexus_db_model::InstanceState;
@@ -235,11 +236,13 @@ pub async fn create_stopped_instance_record(
                 params::InstanceNetworkInterfaceAttachment::None,
             external_ips: Vec::new(),
             disks: Vec::new(),
-            boot_disk: None,
+            boot_order: Vec::new(),
             ssh_public_keys: None,
             start: false,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         },
     );
 
@@ -447,6 +450,8 @@ pub async fn create_project_snapshot(
                 block_size: BlockSize::AdvancedFormat,
 
                 size: external::ByteCount::from_gibibytes_u32(2).into(),
+                delete_protected: false,
+                snapshot_schedule_id: None,
             },
         )
         .await
@@ -489,6 +494,7 @@ pub async fn create_project_image(
                 block_size: BlockSize::Iso,
 
                 size: external::ByteCount::from_gibibytes_u32(1).into(),
+                state: ImageState::Active,
             },
         )
         .await
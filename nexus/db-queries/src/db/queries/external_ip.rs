@@ -127,8 +127,9 @@ const MAX_PORT: u16 = u16::MAX;
 ///         CAST(candidate_first_port AS INT4) AS first_port,
 ///         CAST(candidate_last_port AS INT4) AS last_port,
 ///         <project_id> AS project_id,
+///         <state> AS state,
 ///         <is_probe> AS is_probe,
-///         <state> AS state
+///         <time_attached> AS time_attached
 ///     FROM
 ///         SELECT * FROM (
 ///             -- Select all IP addresses by pool and range.
@@ -300,6 +301,8 @@ pub struct NextExternalIp {
     // because the ranges must not overlap and `generate_series` is inclusive of
     // its endpoints.
     last_port_offset: i32,
+    // When the IP is attached to its parent, if it's created attached.
+    time_attached: Option<DateTime<Utc>>,
     now: DateTime<Utc>,
 }
 
@@ -307,10 +310,12 @@ impl NextExternalIp {
     pub fn new(ip: IncompleteExternalIp) -> Self {
         let now = Utc::now();
         let n_ports_per_chunk = i32::from(NUM_SOURCE_NAT_PORTS);
+        let time_attached = ip.time_attached();
         Self {
             ip,
             n_ports_per_chunk,
             last_port_offset: n_ports_per_chunk - 1,
+            time_attached,
             now,
         }
     }
@@ -421,6 +426,15 @@ impl NextExternalIp {
         out.push_bind_param::<sql_types::Bool, bool>(self.ip.is_probe())?;
         out.push_sql(" AS ");
         out.push_identifier(dsl::is_probe::NAME)?;
+        out.push_sql(", ");
+
+        // Time-attached, if this IP is attached as soon as it's created
+        out.push_bind_param::<
+            sql_types::Nullable<sql_types::Timestamptz>,
+            Option<DateTime<Utc>>,
+        >(&self.time_attached)?;
+        out.push_sql(" AS ");
+        out.push_identifier(dsl::time_attached::NAME)?;
 
         out.push_sql(" FROM (");
         self.push_address_sequence_subquery(out.reborrow())?;
@@ -1006,10 +1020,12 @@ mod tests {
                 network_interfaces: Default::default(),
                 external_ips: vec![],
                 disks: vec![],
-                boot_disk: None,
+                boot_order: Vec::new(),
                 start: false,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             });
 
             let conn = self
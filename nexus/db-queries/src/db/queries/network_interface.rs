@@ -519,29 +519,149 @@ fn last_available_address(subnet: &IpNetwork) -> IpAddr {
     }
 }
 
-/// The `NextIpv4Address` query is a `NextItem` query for choosing the next
-/// available IPv4 address for an interface.
-#[derive(Debug, Clone, Copy)]
+/// The `NextIpv4Address` query selects the next available IPv4 address for an
+/// interface.
+///
+/// An address is available if it's in the usable range of one of the subnet's
+/// IPv4 blocks, and is neither assigned to a live interface nor held by a live
+/// reservation in the `vpc_subnet_ip_reservation` table. This pushes a subquery
+/// that looks like:
+///
+/// ```sql
+/// SELECT candidate FROM (
+///     SELECT <min_addr_0> AS candidate
+///     UNION ALL
+///     -- One per secondary block
+///     SELECT <min_addr_N>
+///     UNION ALL
+///     SELECT ip + 1 FROM network_interface
+///         WHERE subnet_id = <subnet_id> AND time_deleted IS NULL
+///     UNION ALL
+///     SELECT ip + 1 FROM vpc_subnet_ip_reservation
+///         WHERE subnet_id = <subnet_id> AND time_deleted IS NULL
+/// ) AS candidates
+/// WHERE
+///     (
+///         candidate BETWEEN <min_addr_0> AND <max_addr_0> OR
+///         -- One per secondary block
+///         candidate BETWEEN <min_addr_N> AND <max_addr_N>
+///     ) AND
+///     NOT EXISTS (
+///         SELECT 1 FROM network_interface
+///         WHERE subnet_id = <subnet_id> AND time_deleted IS NULL AND
+///         ip = candidate
+///     ) AND
+///     NOT EXISTS (
+///         SELECT 1 FROM vpc_subnet_ip_reservation
+///         WHERE subnet_id = <subnet_id> AND time_deleted IS NULL AND
+///         ip = candidate
+///     )
+/// ORDER BY candidate
+/// LIMIT 1
+/// ```
+///
+/// The smallest available address is either the first usable address of a
+/// block, or one past an address that's taken, so only those candidates need to
+/// be checked. The subquery returns no rows (and so evaluates to `NULL`) when
+/// every block of the subnet is exhausted.
+#[derive(Debug, Clone)]
 pub struct NextIpv4Address {
-    inner: NextItemSelfJoined<
-        nexus_db_schema::schema::network_interface::table,
-        IpNetwork,
-        nexus_db_schema::schema::network_interface::dsl::ip,
-        Uuid,
-        nexus_db_schema::schema::network_interface::dsl::subnet_id,
-    >,
+    subnet_id: Uuid,
+    ranges: Vec<(IpNetwork, IpNetwork)>,
 }
 
 impl NextIpv4Address {
-    pub fn new(subnet: Ipv4Network, subnet_id: Uuid) -> Self {
-        let subnet = IpNetwork::from(subnet);
-        let min = IpNetwork::from(first_available_address(&subnet));
-        let max = IpNetwork::from(last_available_address(&subnet));
-        Self { inner: NextItemSelfJoined::new_scoped(subnet_id, min, max) }
+    /// Construct a query over the provided blocks of a subnet.
+    ///
+    /// The first block should be the subnet's primary block.
+    pub fn new(
+        blocks: impl IntoIterator<Item = Ipv4Network>,
+        subnet_id: Uuid,
+    ) -> Self {
+        let ranges: Vec<_> = blocks
+            .into_iter()
+            .map(|block| {
+                let block = IpNetwork::from(block);
+                let min = IpNetwork::from(first_available_address(&block));
+                let max = IpNetwork::from(last_available_address(&block));
+                (min, max)
+            })
+            .collect();
+        assert!(!ranges.is_empty(), "subnet must have at least one IPv4 block");
+        Self { subnet_id, ranges }
+    }
+
+    // Push a filter selecting live rows in this subnet of the given table.
+    fn push_live_in_subnet<'a>(
+        &'a self,
+        mut out: AstPass<'_, 'a, Pg>,
+        table: &'static str,
+    ) -> QueryResult<()> {
+        out.push_sql(" FROM ");
+        out.push_identifier(table)?;
+        out.push_sql(" WHERE ");
+        out.push_identifier(dsl::subnet_id::NAME)?;
+        out.push_sql(" = ");
+        out.push_bind_param::<sql_types::Uuid, Uuid>(&self.subnet_id)?;
+        out.push_sql(" AND ");
+        out.push_identifier(dsl::time_deleted::NAME)?;
+        out.push_sql(" IS NULL");
+        Ok(())
     }
 }
 
-delegate_query_fragment_impl!(NextIpv4Address);
+const NEXT_IPV4_CANDIDATE: &str = "candidate";
+const NETWORK_INTERFACE_TABLE: &str = "network_interface";
+const VPC_SUBNET_IP_RESERVATION_TABLE: &str = "vpc_subnet_ip_reservation";
+
+impl QueryFragment<Pg> for NextIpv4Address {
+    fn walk_ast<'a>(&'a self, mut out: AstPass<'_, 'a, Pg>) -> QueryResult<()> {
+        let tables = [NETWORK_INTERFACE_TABLE, VPC_SUBNET_IP_RESERVATION_TABLE];
+
+        out.push_sql("SELECT ");
+        out.push_identifier(NEXT_IPV4_CANDIDATE)?;
+        out.push_sql(" FROM (SELECT ");
+        let (first_min, _) = &self.ranges[0];
+        out.push_bind_param::<sql_types::Inet, IpNetwork>(first_min)?;
+        out.push_sql(" AS ");
+        out.push_identifier(NEXT_IPV4_CANDIDATE)?;
+        for (min, _) in &self.ranges[1..] {
+            out.push_sql(" UNION ALL SELECT ");
+            out.push_bind_param::<sql_types::Inet, IpNetwork>(min)?;
+        }
+        for table in tables {
+            out.push_sql(" UNION ALL SELECT ");
+            out.push_identifier(dsl::ip::NAME)?;
+            out.push_sql(" + 1");
+            self.push_live_in_subnet(out.reborrow(), table)?;
+        }
+        out.push_sql(") AS candidates WHERE (");
+        for (i, (min, max)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                out.push_sql(" OR ");
+            }
+            out.push_identifier(NEXT_IPV4_CANDIDATE)?;
+            out.push_sql(" BETWEEN ");
+            out.push_bind_param::<sql_types::Inet, IpNetwork>(min)?;
+            out.push_sql(" AND ");
+            out.push_bind_param::<sql_types::Inet, IpNetwork>(max)?;
+        }
+        out.push_sql(")");
+        for table in tables {
+            out.push_sql(" AND NOT EXISTS (SELECT 1");
+            self.push_live_in_subnet(out.reborrow(), table)?;
+            out.push_sql(" AND ");
+            out.push_identifier(dsl::ip::NAME)?;
+            out.push_sql(" = ");
+            out.push_identifier(NEXT_IPV4_CANDIDATE)?;
+            out.push_sql(")");
+        }
+        out.push_sql(" ORDER BY ");
+        out.push_identifier(NEXT_IPV4_CANDIDATE)?;
+        out.push_sql(" LIMIT 1");
+        Ok(())
+    }
+}
 
 /// A `NextItem` subquery that selects the next empty slot for an interface.
 ///
@@ -1028,7 +1148,7 @@ impl InsertQuery {
         let next_mac_subquery =
             NextMacAddress::new(interface.subnet.vpc_id, interface.kind);
         let next_ipv4_address_subquery = NextIpv4Address::new(
-            interface.subnet.ipv4_block.0.into(),
+            interface.subnet.ipv4_blocks().map(Ipv4Network::from),
             interface.subnet.identity.id,
         );
         let next_slot_subquery = NextNicSlot::new(interface.parent_id);
@@ -1855,6 +1975,7 @@ mod tests {
     use crate::db::model::NetworkInterface;
     use crate::db::model::Project;
     use crate::db::model::VpcSubnet;
+    use crate::db::model::VpcSubnetIpReservation;
     use crate::db::pub_test_utils::TestDatabase;
     use crate::db::queries::network_interface::last_available_address;
     use async_bb8_diesel::AsyncRunQueryDsl;
@@ -1906,10 +2027,12 @@ mod tests {
             network_interfaces: InstanceNetworkInterfaceAttachment::None,
             external_ips: vec![],
             disks: vec![],
-            boot_disk: None,
+            boot_order: Vec::new(),
             start: true,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         };
 
         let instance = Instance::new(instance_id, project_id, &params);
@@ -2255,6 +2378,93 @@ mod tests {
         context.success().await;
     }
 
+    #[tokio::test]
+    async fn test_insert_skips_reserved_ip() {
+        let context =
+            TestContext::new("test_insert_skips_reserved_ip", 2).await;
+        let instance = context.create_stopped_instance().await;
+        let instance_id = InstanceUuid::from_untyped_uuid(instance.id());
+        let subnet = &context.net1.subnets[0];
+
+        // Reserve the first available address in the subnet.
+        let reserved_ip = first_available_address(&ipnetwork::IpNetwork::V4(
+            subnet.ipv4_block.0.into(),
+        ));
+        {
+            use nexus_db_schema::schema::vpc_subnet_ip_reservation::dsl;
+            let conn = context
+                .datastore()
+                .pool_connection_authorized(context.opctx())
+                .await
+                .unwrap();
+            diesel::insert_into(dsl::vpc_subnet_ip_reservation)
+                .values(VpcSubnetIpReservation::new(
+                    subnet,
+                    match reserved_ip {
+                        IpAddr::V4(ip) => ip,
+                        IpAddr::V6(_) => {
+                            unreachable!("reserved an IPv6 address")
+                        }
+                    },
+                    String::new(),
+                ))
+                .execute_async(&*conn)
+                .await
+                .unwrap();
+        }
+
+        // An automatically-allocated address should skip the reservation.
+        let interface = IncompleteNetworkInterface::new_instance(
+            Uuid::new_v4(),
+            instance_id,
+            subnet.clone(),
+            IdentityMetadataCreateParams {
+                name: "interface-a".parse().unwrap(),
+                description: String::from("description"),
+            },
+            None,
+            vec![],
+        )
+        .unwrap();
+        let inserted_interface = context
+            .datastore()
+            .instance_create_network_interface_raw(context.opctx(), interface)
+            .await
+            .expect("Failed to insert interface");
+        let expected_ip = match reserved_ip {
+            IpAddr::V4(ip) => IpAddr::V4(Ipv4Addr::from(u32::from(ip) + 1)),
+            IpAddr::V6(_) => unreachable!("reserved an IPv4 address"),
+        };
+        assert_eq!(
+            inserted_interface.ip.ip(),
+            expected_ip,
+            "Automatically-allocated addresses should skip reserved addresses"
+        );
+
+        // Explicitly requesting the reserved address binds it to the new
+        // interface.
+        let instance = context.create_stopped_instance().await;
+        let interface = IncompleteNetworkInterface::new_instance(
+            Uuid::new_v4(),
+            InstanceUuid::from_untyped_uuid(instance.id()),
+            subnet.clone(),
+            IdentityMetadataCreateParams {
+                name: "interface-b".parse().unwrap(),
+                description: String::from("description"),
+            },
+            Some(reserved_ip),
+            vec![],
+        )
+        .unwrap();
+        let inserted_interface = context
+            .datastore()
+            .instance_create_network_interface_raw(context.opctx(), interface)
+            .await
+            .expect("Failed to insert interface with a reserved IP address");
+        assert_eq!(inserted_interface.ip.ip(), reserved_ip);
+        context.success().await;
+    }
+
     #[tokio::test]
     async fn test_insert_no_instance_fails() {
         let context =
@@ -234,6 +234,50 @@ pub fn sled_find_targets_query(
     query.query()
 }
 
+/// Return the capacity of, and resources reserved on, every sled where VMMs
+/// may be placed
+///
+/// The rows returned by this query indicate:
+///
+/// - The Sled
+/// - Its usable hardware threads, physical RAM, and reservoir size
+/// - The hardware threads, RSS RAM, and reservoir RAM reserved on it
+///
+/// This is only used to explain why an allocation didn't fit anywhere, so it
+/// considers the same sleds as [sled_find_targets_query], but it doesn't
+/// filter them by space.
+pub fn sled_capacity_query() -> TypedSqlQuery<(
+    sql_types::Uuid,
+    sql_types::BigInt,
+    sql_types::BigInt,
+    sql_types::BigInt,
+    sql_types::BigInt,
+    sql_types::BigInt,
+    sql_types::BigInt,
+)> {
+    let mut query = QueryBuilder::new();
+    query.sql("
+        SELECT
+            sled.id,
+            sled.usable_hardware_threads,
+            sled.usable_physical_ram,
+            sled.reservoir_size,
+            CAST(COALESCE(SUM(CAST(sled_resource_vmm.hardware_threads AS INT8)), 0) AS INT8),
+            CAST(COALESCE(SUM(CAST(sled_resource_vmm.rss_ram AS INT8)), 0) AS INT8),
+            CAST(COALESCE(SUM(CAST(sled_resource_vmm.reservoir_ram AS INT8)), 0) AS INT8)
+        FROM sled
+        LEFT JOIN sled_resource_vmm
+        ON sled_resource_vmm.sled_id = sled.id
+        WHERE
+            sled.time_deleted IS NULL AND
+            sled.sled_policy = 'in_service' AND
+            sled.sled_state = 'active'
+        GROUP BY sled.id
+    ");
+
+    query.query()
+}
+
 /// Inserts a sled_resource_vmm record into the database, if it is
 /// a valid reservation.
 pub fn sled_insert_resource_query(
@@ -443,6 +487,33 @@ mod test {
         logctx.cleanup_successful();
     }
 
+    #[tokio::test]
+    async fn expectorate_sled_capacity_query() {
+        let query = sled_capacity_query();
+        expectorate_query_contents(
+            &query,
+            "tests/output/sled_capacity_query.sql",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn explain_sled_capacity_query() {
+        let logctx = dev::test_setup_log("explain_sled_capacity_query");
+        let db = TestDatabase::new_with_pool(&logctx.log).await;
+        let pool = db.pool();
+        let conn = pool.claim().await.unwrap();
+
+        let query = sled_capacity_query();
+        let _ = query
+            .explain_async(&conn)
+            .await
+            .expect("Failed to explain query - is it valid SQL?");
+
+        db.terminate().await;
+        logctx.cleanup_successful();
+    }
+
     #[tokio::test]
     async fn expectorate_sled_insert_resource_query() {
         let resource = SledResourceVmm::new(
@@ -30,6 +30,11 @@ type AllColumnsOfVirtualCollection =
 const NOT_ENOUGH_CPUS_SENTINEL: &'static str = "Not enough cpus";
 const NOT_ENOUGH_MEMORY_SENTINEL: &'static str = "Not enough memory";
 const NOT_ENOUGH_STORAGE_SENTINEL: &'static str = "Not enough storage";
+const PROJECT_CPU_QUOTA_SENTINEL: &'static str = "Project cpu quota exceeded";
+const PROJECT_MEMORY_QUOTA_SENTINEL: &'static str =
+    "Project memory quota exceeded";
+const PROJECT_STORAGE_QUOTA_SENTINEL: &'static str =
+    "Project storage quota exceeded";
 
 /// Translates a generic pool error to an external error based
 /// on messages which may be emitted when provisioning virtual resources
@@ -39,6 +44,9 @@ pub fn from_diesel(e: DieselError) -> external::Error {
         NOT_ENOUGH_CPUS_SENTINEL,
         NOT_ENOUGH_MEMORY_SENTINEL,
         NOT_ENOUGH_STORAGE_SENTINEL,
+        PROJECT_CPU_QUOTA_SENTINEL,
+        PROJECT_MEMORY_QUOTA_SENTINEL,
+        PROJECT_STORAGE_QUOTA_SENTINEL,
     ];
     if let Some(sentinel) = matches_sentinel(&e, &sentinels) {
         match sentinel {
@@ -66,6 +74,24 @@ pub fn from_diesel(e: DieselError) -> external::Error {
                     )
                 }
             }
+            PROJECT_CPU_QUOTA_SENTINEL => {
+                return external::Error::insufficient_quota(
+                    "vCPU Quota Exceeded: The project does not have enough vCPUs left in its quota to complete the request. Either stop unused instances in the project or ask a silo administrator to raise the project's quota.",
+                    "User tried to allocate an instance but the project's quota did not allow enough CPUs to satisfy the request.",
+                );
+            }
+            PROJECT_MEMORY_QUOTA_SENTINEL => {
+                return external::Error::insufficient_quota(
+                    "Memory Quota Exceeded: The project does not have enough memory left in its quota to complete the request. Either stop unused instances in the project or ask a silo administrator to raise the project's quota.",
+                    "User tried to allocate an instance but the project's quota did not allow enough RAM to satisfy the request.",
+                );
+            }
+            PROJECT_STORAGE_QUOTA_SENTINEL => {
+                return external::Error::insufficient_quota(
+                    "Storage Quota Exceeded: The project does not have enough storage left in its quota to complete the request. Either remove unneeded disks and snapshots in the project or ask a silo administrator to raise the project's quota.",
+                    "User tried to allocate a disk or snapshot but the project's quota did not allow enough storage to satisfy the request.",
+                );
+            }
             _ => {}
         }
     }
@@ -144,8 +170,34 @@ WITH
     ),");
 
         match update_kind.clone() {
-            UpdateKind::InsertInstance(resource) | UpdateKind::InsertStorage(resource) => {
+            UpdateKind::InsertInstance(resource)
+            | UpdateKind::InsertStorage(resource) => {
+                // Projects may optionally have their own quotas, which are
+                // checked in addition to the silo's.  A project without a row
+                // in `project_quotas` is limited only by its silo.
                 query.sql("
+  project_quota
+    AS (
+      SELECT
+        project_quotas.cpus,
+        project_quotas.memory_bytes AS memory,
+        project_quotas.storage_bytes AS storage
+      FROM
+        project_quotas
+      WHERE
+        project_quotas.project_id = ").param().sql("
+    ),
+  project_provisioned
+    AS (
+      SELECT
+        virtual_provisioning_collection.cpus_provisioned,
+        virtual_provisioning_collection.ram_provisioned,
+        virtual_provisioning_collection.virtual_disk_bytes_provisioned
+      FROM
+        virtual_provisioning_collection
+      WHERE
+        virtual_provisioning_collection.id = ").param().sql("
+    ),
   do_update
     AS (
       SELECT
@@ -212,8 +264,65 @@ WITH
             )
               AS BOOL
           )
+        AND CAST(
+            IF(
+              (
+                ")).param().sql(" = 0
+                OR NOT EXISTS(SELECT 1 FROM project_quota)
+                OR (SELECT project_quota.cpus FROM project_quota LIMIT 1)
+                  >= (
+                      (SELECT project_provisioned.cpus_provisioned FROM project_provisioned LIMIT 1)
+                      + ").param().sql(concatcp!("
+                    )
+              ),
+              'TRUE',
+              '", PROJECT_CPU_QUOTA_SENTINEL, "'
+            )
+              AS BOOL
+          )
+        AND CAST(
+            IF(
+              (
+                ")).param().sql(" = 0
+                OR NOT EXISTS(SELECT 1 FROM project_quota)
+                OR (SELECT project_quota.memory FROM project_quota LIMIT 1)
+                  >= (
+                      (SELECT project_provisioned.ram_provisioned FROM project_provisioned LIMIT 1)
+                      + ").param().sql(concatcp!("
+                    )
+              ),
+              'TRUE',
+              '", PROJECT_MEMORY_QUOTA_SENTINEL, "'
+            )
+              AS BOOL
+          )
+        AND CAST(
+            IF(
+              (
+                ")).param().sql(" = 0
+                OR NOT EXISTS(SELECT 1 FROM project_quota)
+                OR (SELECT project_quota.storage FROM project_quota LIMIT 1)
+                  >= (
+                      (
+                        SELECT
+                          project_provisioned.virtual_disk_bytes_provisioned
+                        FROM
+                          project_provisioned
+                        LIMIT
+                          1
+                      )
+                      + ").param().sql(concatcp!("
+                    )
+              ),
+              'TRUE',
+              '", PROJECT_STORAGE_QUOTA_SENTINEL, "'
+            )
+              AS BOOL
+          )
           AS update
     ),"))
+                .bind::<sql_types::Uuid, _>(project_id)
+                .bind::<sql_types::Uuid, _>(project_id)
                 .bind::<sql_types::Uuid, _>(resource.id)
                 .bind::<sql_types::BigInt, _>(resource.cpus_provisioned)
                 .bind::<sql_types::BigInt, _>(resource.cpus_provisioned)
@@ -221,9 +330,16 @@ WITH
                 .bind::<sql_types::BigInt, _>(resource.ram_provisioned)
                 .bind::<sql_types::BigInt, _>(resource.virtual_disk_bytes_provisioned)
                 .bind::<sql_types::BigInt, _>(resource.virtual_disk_bytes_provisioned)
-            },
-            UpdateKind::DeleteStorage { id, .. } => {
-                query.sql("
+                .bind::<sql_types::BigInt, _>(resource.cpus_provisioned)
+                .bind::<sql_types::BigInt, _>(resource.cpus_provisioned)
+                .bind::<sql_types::BigInt, _>(resource.ram_provisioned)
+                .bind::<sql_types::BigInt, _>(resource.ram_provisioned)
+                .bind::<sql_types::BigInt, _>(resource.virtual_disk_bytes_provisioned)
+                .bind::<sql_types::BigInt, _>(resource.virtual_disk_bytes_provisioned)
+            }
+            UpdateKind::DeleteStorage { id, .. } => query
+                .sql(
+                    "
   do_update
     AS (
       SELECT
@@ -233,21 +349,27 @@ WITH
           FROM
             virtual_provisioning_resource
           WHERE
-            virtual_provisioning_resource.id = ").param().sql("
+            virtual_provisioning_resource.id = ",
+                )
+                .param()
+                .sql(
+                    "
           LIMIT
             1
         ) = 1
           AS update
-    ),")
-                .bind::<sql_types::Uuid, _>(id)
-            },
+    ),",
+                )
+                .bind::<sql_types::Uuid, _>(id),
             UpdateKind::DeleteInstance { id, .. } => {
                 // If the relevant instance ID is not in the database, then some
                 // other operation must have ensured the instance was previously
                 // stopped (because that's the only way it could have been deleted),
                 // and that operation should have cleaned up the resources already,
                 // in which case there's nothing to do here.
-                query.sql("
+                query
+                    .sql(
+                        "
   do_update
     AS (
       SELECT
@@ -257,7 +379,11 @@ WITH
           FROM
             virtual_provisioning_resource
           WHERE
-            virtual_provisioning_resource.id = ").param().sql("
+            virtual_provisioning_resource.id = ",
+                    )
+                    .param()
+                    .sql(
+                        "
           LIMIT
             1
         ) = 1 AND
@@ -266,14 +392,19 @@ WITH
           FROM
             instance
           WHERE
-            instance.id = ").param().sql("
+            instance.id = ",
+                    )
+                    .param()
+                    .sql(
+                        "
           LIMIT 1
         )
           AS update
-    ),")
-                .bind::<sql_types::Uuid, _>(id)
-                .bind::<sql_types::Uuid, _>(id)
-            },
+    ),",
+                    )
+                    .bind::<sql_types::Uuid, _>(id)
+                    .bind::<sql_types::Uuid, _>(id)
+            }
         };
 
         match update_kind.clone() {
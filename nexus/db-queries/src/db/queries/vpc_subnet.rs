@@ -22,8 +22,9 @@ use uuid::Uuid;
 /// Query used to insert VPC Subnets.
 ///
 /// This query is used to idempotently insert a VPC Subnet. The query also looks
-/// for any other subnets in the same VPC whose IP address blocks overlap. All
-/// Subnets are required to have non-overlapping IP blocks.
+/// for any other subnets in the same VPC whose IP address blocks overlap,
+/// including any secondary IPv4 blocks added to those subnets. All Subnets are
+/// required to have non-overlapping IP blocks.
 ///
 /// Note that this query is idempotent. If a record with the provided primary
 /// key already exists, that record is returned exactly from the DB, without any
@@ -47,7 +48,14 @@ use uuid::Uuid;
 ///         -- we're trying to cacth.
 ///         CAST(
 ///             IF(
-///                (<ipv4_block> && ipv4_block),
+///                (
+///                    ipv4_block && <ipv4_block> OR
+///                    EXISTS (
+///                        SELECT 1
+///                        FROM unnest(ipv4_secondary_blocks) AS secondary (block)
+///                        WHERE block && <ipv4_block>
+///                    )
+///                ),
 ///                'ipv4',
 ///                'ipv6'
 ///             )
@@ -60,7 +68,14 @@ use uuid::Uuid;
 ///         time_deleted IS NULL AND
 ///         id != <id> AND
 ///         (
-///             (ipv4_block && <ipv4_block>) OR
+///             (
+///                 ipv4_block && <ipv4_block> OR
+///                 EXISTS (
+///                     SELECT 1
+///                     FROM unnest(ipv4_secondary_blocks) AS secondary (block)
+///                     WHERE block && <ipv4_block>
+///                 )
+///             ) OR
 ///             (ipv6_block && <ipv6_block>)
 ///         )
 /// )
@@ -94,6 +109,25 @@ impl InsertVpcSubnetQuery {
     }
 }
 
+impl InsertVpcSubnetQuery {
+    // Push a predicate that is true if an existing row's primary or any of
+    // its secondary IPv4 blocks overlap the new subnet's IPv4 block.
+    fn walk_ipv4_overlap<'a>(
+        &'a self,
+        mut out: AstPass<'_, 'a, Pg>,
+    ) -> diesel::QueryResult<()> {
+        out.push_identifier(dsl::ipv4_block::NAME)?;
+        out.push_sql(" && ");
+        out.push_bind_param::<sql_types::Inet, IpNetwork>(&self.ipv4_block)?;
+        out.push_sql(" OR EXISTS (SELECT 1 FROM unnest(");
+        out.push_identifier(dsl::ipv4_secondary_blocks::NAME)?;
+        out.push_sql(") AS secondary (block) WHERE block && ");
+        out.push_bind_param::<sql_types::Inet, IpNetwork>(&self.ipv4_block)?;
+        out.push_sql(")");
+        Ok(())
+    }
+}
+
 impl QueryId for InsertVpcSubnetQuery {
     type QueryId = ();
     const HAS_STATIC_QUERY_ID: bool = false;
@@ -105,9 +139,7 @@ impl QueryFragment<Pg> for InsertVpcSubnetQuery {
         mut out: AstPass<'_, 'a, Pg>,
     ) -> diesel::QueryResult<()> {
         out.push_sql("WITH overlap AS MATERIALIZED (SELECT CAST(IF((");
-        out.push_identifier(dsl::ipv4_block::NAME)?;
-        out.push_sql(" && ");
-        out.push_bind_param::<sql_types::Inet, _>(&self.ipv4_block)?;
+        self.walk_ipv4_overlap(out.reborrow())?;
         out.push_sql("), ");
         out.push_bind_param::<sql_types::Text, _>(
             InsertVpcSubnetError::OVERLAPPING_IPV4_BLOCK_SENTINEL,
@@ -129,9 +161,7 @@ impl QueryFragment<Pg> for InsertVpcSubnetQuery {
         out.push_sql(" != ");
         out.push_bind_param::<sql_types::Uuid, Uuid>(&self.subnet.identity.id)?;
         out.push_sql(" AND ((");
-        out.push_identifier(dsl::ipv4_block::NAME)?;
-        out.push_sql(" && ");
-        out.push_bind_param::<sql_types::Inet, IpNetwork>(&self.ipv4_block)?;
+        self.walk_ipv4_overlap(out.reborrow())?;
         out.push_sql(") OR (");
         out.push_identifier(dsl::ipv6_block::NAME)?;
         out.push_sql(" && ");
@@ -173,6 +203,10 @@ impl QueryFragment<Pg> for InsertVpcSubnetQuery {
         out.push_bind_param::<sql_types::Nullable<sql_types::Uuid>, _>(
             &self.subnet.custom_router_id,
         )?;
+        out.push_sql(", ");
+        out.push_bind_param::<sql_types::Array<sql_types::Inet>, _>(
+            &self.subnet.ipv4_secondary_blocks,
+        )?;
         out.push_sql(") ON CONFLICT (");
         out.push_identifier(dsl::id::NAME)?;
         out.push_sql(") DO UPDATE SET ");
@@ -8,6 +8,7 @@ pub use nexus_auth::authn;
 pub use nexus_auth::authz;
 pub use nexus_auth::context;
 
+mod cache;
 pub mod db;
 pub mod provisioning;
 pub mod transaction_retry;
@@ -260,6 +260,7 @@ impl_dyn_authorized_resource_for_resource!(authz::InternetGatewayIpAddress);
 impl_dyn_authorized_resource_for_resource!(authz::InternetGatewayIpPool);
 impl_dyn_authorized_resource_for_resource!(authz::LoopbackAddress);
 impl_dyn_authorized_resource_for_resource!(authz::Rack);
+impl_dyn_authorized_resource_for_resource!(authz::Operation);
 impl_dyn_authorized_resource_for_resource!(authz::PhysicalDisk);
 impl_dyn_authorized_resource_for_resource!(authz::Project);
 impl_dyn_authorized_resource_for_resource!(authz::ProjectImage);
@@ -271,6 +272,8 @@ impl_dyn_authorized_resource_for_resource!(authz::SiloImage);
 impl_dyn_authorized_resource_for_resource!(authz::SiloUser);
 impl_dyn_authorized_resource_for_resource!(authz::Sled);
 impl_dyn_authorized_resource_for_resource!(authz::Snapshot);
+impl_dyn_authorized_resource_for_resource!(authz::SnapshotExport);
+impl_dyn_authorized_resource_for_resource!(authz::SnapshotSchedule);
 impl_dyn_authorized_resource_for_resource!(authz::SshKey);
 impl_dyn_authorized_resource_for_resource!(authz::SupportBundle);
 impl_dyn_authorized_resource_for_resource!(authz::TufArtifact);
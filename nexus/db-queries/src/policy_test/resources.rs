@@ -380,6 +380,24 @@ async fn make_project(
         LookupType::ByName(format!("{}-snapshot1", disk_name)),
     ));
 
+    builder.new_resource(authz::SnapshotSchedule::new(
+        project.clone(),
+        Uuid::new_v4(),
+        LookupType::ByName(format!("{}-snapshot-schedule1", disk_name)),
+    ));
+
+    builder.new_resource(authz::SnapshotExport::new(
+        project.clone(),
+        Uuid::new_v4(),
+        LookupType::ByName(format!("{}-snapshot-export1", disk_name)),
+    ));
+
+    builder.new_resource(authz::Operation::new(
+        project.clone(),
+        Uuid::new_v4(),
+        LookupType::ByName(format!("{}-operation1", project_name)),
+    ));
+
     let image_name = format!("{}-image1", project_name);
     builder.new_resource(authz::ProjectImage::new(
         project.clone(),
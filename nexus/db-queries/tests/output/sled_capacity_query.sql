@@ -0,0 +1,14 @@
+SELECT
+  sled.id,
+  sled.usable_hardware_threads,
+  sled.usable_physical_ram,
+  sled.reservoir_size,
+  CAST(COALESCE(sum(CAST(sled_resource_vmm.hardware_threads AS INT8)), 0) AS INT8),
+  CAST(COALESCE(sum(CAST(sled_resource_vmm.rss_ram AS INT8)), 0) AS INT8),
+  CAST(COALESCE(sum(CAST(sled_resource_vmm.reservoir_ram AS INT8)), 0) AS INT8)
+FROM
+  sled LEFT JOIN sled_resource_vmm ON sled_resource_vmm.sled_id = sled.id
+WHERE
+  sled.time_deleted IS NULL AND sled.sled_policy = 'in_service' AND sled.sled_state = 'active'
+GROUP BY
+  sled.id
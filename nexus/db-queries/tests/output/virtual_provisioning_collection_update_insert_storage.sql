@@ -26,6 +26,28 @@ WITH
         virtual_provisioning_collection
         INNER JOIN parent_silo ON virtual_provisioning_collection.id = parent_silo.id
     ),
+  project_quota
+    AS (
+      SELECT
+        project_quotas.cpus,
+        project_quotas.memory_bytes AS memory,
+        project_quotas.storage_bytes AS storage
+      FROM
+        project_quotas
+      WHERE
+        project_quotas.project_id = $4
+    ),
+  project_provisioned
+    AS (
+      SELECT
+        virtual_provisioning_collection.cpus_provisioned,
+        virtual_provisioning_collection.ram_provisioned,
+        virtual_provisioning_collection.virtual_disk_bytes_provisioned
+      FROM
+        virtual_provisioning_collection
+      WHERE
+        virtual_provisioning_collection.id = $5
+    ),
   do_update
     AS (
       SELECT
@@ -37,7 +59,7 @@ WITH
               FROM
                 virtual_provisioning_resource
               WHERE
-                virtual_provisioning_resource.id = $4
+                virtual_provisioning_resource.id = $6
               LIMIT
                 1
             )
@@ -45,11 +67,11 @@ WITH
             AND CAST(
                 IF(
                   (
-                    $5 = 0
+                    $7 = 0
                     OR (SELECT quotas.cpus FROM quotas LIMIT 1)
                       >= (
                           (SELECT silo_provisioned.cpus_provisioned FROM silo_provisioned LIMIT 1)
-                          + $6
+                          + $8
                         )
                   ),
                   'TRUE',
@@ -61,10 +83,10 @@ WITH
           AND CAST(
               IF(
                 (
-                  $7 = 0
+                  $9 = 0
                   OR (SELECT quotas.memory FROM quotas LIMIT 1)
                     >= (
-                        (SELECT silo_provisioned.ram_provisioned FROM silo_provisioned LIMIT 1) + $8
+                        (SELECT silo_provisioned.ram_provisioned FROM silo_provisioned LIMIT 1) + $10
                       )
                 ),
                 'TRUE',
@@ -76,7 +98,7 @@ WITH
         AND CAST(
             IF(
               (
-                $9 = 0
+                $11 = 0
                 OR (SELECT quotas.storage FROM quotas LIMIT 1)
                   >= (
                       (
@@ -87,7 +109,7 @@ WITH
                         LIMIT
                           1
                       )
-                      + $10
+                      + $12
                     )
               ),
               'TRUE',
@@ -95,6 +117,61 @@ WITH
             )
               AS BOOL
           )
+        AND CAST(
+            IF(
+              (
+                $13 = 0
+                OR NOT EXISTS(SELECT 1 FROM project_quota)
+                OR (SELECT project_quota.cpus FROM project_quota LIMIT 1)
+                  >= (
+                      (SELECT project_provisioned.cpus_provisioned FROM project_provisioned LIMIT 1)
+                      + $14
+                    )
+              ),
+              'TRUE',
+              'Project cpu quota exceeded'
+            )
+              AS BOOL
+          )
+        AND CAST(
+            IF(
+              (
+                $15 = 0
+                OR NOT EXISTS(SELECT 1 FROM project_quota)
+                OR (SELECT project_quota.memory FROM project_quota LIMIT 1)
+                  >= (
+                      (SELECT project_provisioned.ram_provisioned FROM project_provisioned LIMIT 1)
+                      + $16
+                    )
+              ),
+              'TRUE',
+              'Project memory quota exceeded'
+            )
+              AS BOOL
+          )
+        AND CAST(
+            IF(
+              (
+                $17 = 0
+                OR NOT EXISTS(SELECT 1 FROM project_quota)
+                OR (SELECT project_quota.storage FROM project_quota LIMIT 1)
+                  >= (
+                      (
+                        SELECT
+                          project_provisioned.virtual_disk_bytes_provisioned
+                        FROM
+                          project_provisioned
+                        LIMIT
+                          1
+                      )
+                      + $18
+                    )
+              ),
+              'TRUE',
+              'Project storage quota exceeded'
+            )
+              AS BOOL
+          )
           AS update
     ),
   unused_cte_arm
@@ -111,7 +188,7 @@ WITH
             ram_provisioned
           )
       VALUES
-        ($11, DEFAULT, $12, $13, $14, $15)
+        ($19, DEFAULT, $20, $21, $22, $23)
       ON CONFLICT
       DO
         NOTHING
@@ -130,7 +207,7 @@ WITH
       SET
         time_modified = current_timestamp(),
         virtual_disk_bytes_provisioned
-          = virtual_provisioning_collection.virtual_disk_bytes_provisioned + $16
+          = virtual_provisioning_collection.virtual_disk_bytes_provisioned + $24
       WHERE
         virtual_provisioning_collection.id = ANY (SELECT all_collections.id FROM all_collections)
         AND (SELECT do_update.update FROM do_update LIMIT 1)
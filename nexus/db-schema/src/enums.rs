@@ -47,18 +47,26 @@ define_enums! {
     HwRotSlotEnum => "hw_rot_slot",
     IdentityProviderTypeEnum => "provider_type",
     IdentityTypeEnum => "identity_type",
+    ImageStateEnum => "image_state",
     InstanceAutoRestartPolicyEnum => "instance_auto_restart",
+    InstanceAutoRestartReasonEnum => "instance_auto_restart_reason",
     InstanceStateEnum => "instance_state_v2",
+    InstanceStoppedReservationPolicyEnum => "instance_stopped_reservation_policy",
     InstanceIntendedStateEnum => "instance_intended_state",
     InvConfigReconcilerStatusKindEnum => "inv_config_reconciler_status_kind",
     InvZoneImageSourceEnum => "inv_zone_image_source",
     InvZoneManifestSourceEnum => "inv_zone_manifest_source",
+    InvZpoolHealthStateEnum => "inv_zpool_health_state",
+    InvZpoolResilverStateEnum => "inv_zpool_resilver_state",
+    InvZpoolScrubStateEnum => "inv_zpool_scrub_state",
     IpAttachStateEnum => "ip_attach_state",
     IpKindEnum => "ip_kind",
     IpPoolResourceTypeEnum => "ip_pool_resource_type",
     IpVersionEnum => "ip_version",
     MigrationStateEnum => "migration_state",
     NetworkInterfaceKindEnum => "network_interface_kind",
+    OperationKindEnum => "operation_kind",
+    OperationStateEnum => "operation_state",
     OximeterReadModeEnum => "oximeter_read_mode",
     PhysicalDiskKindEnum => "physical_disk_kind",
     PhysicalDiskPolicyEnum => "physical_disk_policy",
@@ -73,6 +81,8 @@ define_enums! {
     // NOTE: The database enum name starts with "clear_" for legacy reasons.
     // Prefer "remove" in the future.
     RemoveMupdateOverrideBootSuccessEnum => "clear_mupdate_override_boot_success",
+    RollingSledRebootStateEnum => "rolling_sled_reboot_state",
+    RollingSledRebootStepEnum => "rolling_sled_reboot_step",
     RotImageErrorEnum => "rot_image_error",
     RotPageWhichEnum => "root_of_trust_page_which",
     RouterRouteKindEnum => "router_route_kind",
@@ -90,6 +100,8 @@ define_enums! {
     SwitchLinkSpeedEnum => "switch_link_speed",
     SwitchPortGeometryEnum => "switch_port_geometry",
     TargetReleaseSourceEnum => "target_release_source",
+    UpdateComponentKindEnum => "update_component_kind",
+    UpdateComponentStateEnum => "update_component_state",
     UpstairsRepairNotificationTypeEnum => "upstairs_repair_notification_type",
     UpstairsRepairTypeEnum => "upstairs_repair_type",
     UserDataExportResourceTypeEnum => "user_data_export_resource_type",
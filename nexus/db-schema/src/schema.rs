@@ -29,6 +29,7 @@ table! {
         origin_snapshot -> Nullable<Uuid>,
         origin_image -> Nullable<Uuid>,
         pantry_address -> Nullable<Text>,
+        delete_protected -> Bool,
     }
 }
 
@@ -49,6 +50,7 @@ table! {
         digest -> Nullable<Text>,
         block_size -> crate::enums::BlockSizeEnum,
         size_bytes -> Int8,
+        state -> crate::enums::ImageStateEnum,
     }
 }
 
@@ -69,6 +71,7 @@ table! {
         digest -> Nullable<Text>,
         block_size -> crate::enums::BlockSizeEnum,
         size_bytes -> Int8,
+        state -> crate::enums::ImageStateEnum,
     }
 }
 
@@ -88,6 +91,7 @@ table! {
         digest -> Nullable<Text>,
         block_size -> crate::enums::BlockSizeEnum,
         size_bytes -> Int8,
+        state -> crate::enums::ImageStateEnum,
     }
 }
 
@@ -405,6 +409,58 @@ table! {
         state -> crate::enums::SnapshotStateEnum,
         block_size -> crate::enums::BlockSizeEnum,
         size_bytes -> Int8,
+        delete_protected -> Bool,
+        snapshot_schedule_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    snapshot_schedule (id) {
+        id -> Uuid,
+        name -> Text,
+        description -> Text,
+        time_created -> Timestamptz,
+        time_modified -> Timestamptz,
+        time_deleted -> Nullable<Timestamptz>,
+        project_id -> Uuid,
+        disk_id -> Nullable<Uuid>,
+        interval_secs -> Int8,
+        retain_count -> Int8,
+        time_last_run -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    snapshot_export (id) {
+        id -> Uuid,
+        time_created -> Timestamptz,
+        time_modified -> Timestamptz,
+        time_deleted -> Nullable<Timestamptz>,
+        project_id -> Uuid,
+        snapshot_id -> Uuid,
+        instance_id -> Nullable<Uuid>,
+        volume_id -> Uuid,
+        pantry_address -> Nullable<Text>,
+        block_size -> crate::enums::BlockSizeEnum,
+        size_bytes -> Int8,
+        manifest -> Jsonb,
+    }
+}
+
+table! {
+    operation (id) {
+        id -> Uuid,
+        time_created -> Timestamptz,
+        time_modified -> Timestamptz,
+        time_completed -> Nullable<Timestamptz>,
+        project_id -> Uuid,
+        nexus_id -> Uuid,
+        kind -> crate::enums::OperationKindEnum,
+        state -> crate::enums::OperationStateEnum,
+        steps_completed -> Int8,
+        steps_total -> Int8,
+        result_id -> Nullable<Uuid>,
+        error_message -> Nullable<Text>,
     }
 }
 
@@ -423,7 +479,7 @@ table! {
         hostname -> Text,
         auto_restart_policy -> Nullable<crate::enums::InstanceAutoRestartPolicyEnum>,
         auto_restart_cooldown -> Nullable<Interval>,
-        boot_disk_id -> Nullable<Uuid>,
+        boot_order -> Array<Uuid>,
         time_state_updated -> Timestamptz,
         state_generation -> Int8,
         active_propolis_id -> Nullable<Uuid>,
@@ -434,10 +490,12 @@ table! {
         intended_state -> crate::enums::InstanceIntendedStateEnum,
         updater_id -> Nullable<Uuid>,
         updater_gen-> Int8,
+        stopped_reservation_policy -> crate::enums::InstanceStoppedReservationPolicyEnum,
     }
 }
 
 joinable!(instance -> vmm (active_propolis_id));
+joinable!(instance -> project (project_id));
 
 table! {
     vmm (id) {
@@ -537,6 +595,28 @@ table! {
     }
 }
 
+table! {
+    project_quotas(project_id) {
+        project_id -> Uuid,
+        time_created -> Timestamptz,
+        time_modified -> Timestamptz,
+        cpus -> Int8,
+        memory_bytes -> Int8,
+        storage_bytes -> Int8,
+    }
+}
+
+table! {
+    project_ephemeral_ip_policy(project_id) {
+        project_id -> Uuid,
+        time_created -> Timestamptz,
+        time_modified -> Timestamptz,
+        auto_assign -> Bool,
+        ip_pool_id -> Nullable<Uuid>,
+        allow_override -> Bool,
+    }
+}
+
 table! {
     silo_utilization(silo_id) {
         silo_id -> Uuid,
@@ -714,6 +794,7 @@ table! {
         project_id -> Nullable<Uuid>,
         state -> crate::enums::IpAttachStateEnum,
         is_probe -> Bool,
+        time_attached -> Nullable<Timestamptz>,
     }
 }
 
@@ -803,6 +884,18 @@ allow_tables_to_appear_in_same_query!(
 );
 allow_tables_to_appear_in_same_query!(role_assignment, silo_group_membership);
 
+table! {
+    break_glass_account (id) {
+        id -> Uuid,
+        time_created -> Timestamptz,
+        silo_user_id -> Uuid,
+        enabled_by -> Text,
+        time_expires -> Timestamptz,
+        time_disabled -> Nullable<Timestamptz>,
+        disabled_by -> Nullable<Text>,
+    }
+}
+
 table! {
     identity_provider (silo_id, id) {
         id -> Uuid,
@@ -861,6 +954,24 @@ table! {
     }
 }
 
+table! {
+    instance_tag (instance_id, key) {
+        instance_id -> Uuid,
+        key -> Text,
+        value -> Text,
+    }
+}
+
+table! {
+    instance_auto_restart_event (id) {
+        id -> Uuid,
+        instance_id -> Uuid,
+        time_restarted -> Timestamptz,
+        reason -> crate::enums::InstanceAutoRestartReasonEnum,
+        propolis_id -> Uuid,
+    }
+}
+
 table! {
     oximeter (id) {
         id -> Uuid,
@@ -882,6 +993,8 @@ table! {
         time_deleted -> Nullable<Timestamptz>,
         rcgen -> Int8,
         silo_id -> Uuid,
+        default_auto_restart_policy -> Nullable<crate::enums::InstanceAutoRestartPolicyEnum>,
+        default_auto_restart_cooldown -> Nullable<Interval>,
     }
 }
 
@@ -999,6 +1112,48 @@ table! {
 }
 allow_tables_to_appear_in_same_query!(rack, sled_underlay_subnet_allocation);
 
+table! {
+    sled_execution_lease (sled_id) {
+        sled_id -> Uuid,
+        nexus_id -> Uuid,
+        time_acquired -> Timestamptz,
+        time_expires -> Timestamptz,
+    }
+}
+
+table! {
+    rolling_sled_reboot (id) {
+        id -> Uuid,
+        time_created -> Timestamptz,
+        time_finished -> Nullable<Timestamptz>,
+        batch_size -> Int4,
+        state -> crate::enums::RollingSledRebootStateEnum,
+        time_cancel_requested -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    rolling_sled_reboot_sled (reboot_id, sled_id) {
+        reboot_id -> Uuid,
+        sled_id -> Uuid,
+        position -> Int4,
+        step -> crate::enums::RollingSledRebootStepEnum,
+        time_step_changed -> Timestamptz,
+        time_reset -> Nullable<Timestamptz>,
+        error -> Nullable<Text>,
+    }
+}
+
+table! {
+    rolling_sled_reboot_instance (reboot_id, instance_id) {
+        reboot_id -> Uuid,
+        instance_id -> Uuid,
+        sled_id -> Uuid,
+        time_stopped -> Timestamptz,
+        time_restarted -> Nullable<Timestamptz>,
+    }
+}
+
 table! {
     switch (id) {
         id -> Uuid,
@@ -1099,6 +1254,17 @@ table! {
     }
 }
 
+table! {
+    zpool_usage_sample (zpool_id, time_sampled) {
+        zpool_id -> Uuid,
+        time_sampled -> Timestamptz,
+        sled_id -> Uuid,
+        total_size -> Int8,
+        allocated -> Int8,
+        fragmentation_percent -> Nullable<Int2>,
+    }
+}
+
 allow_tables_to_appear_in_same_query! {
     zpool,
     physical_disk
@@ -1221,6 +1387,19 @@ table! {
         ipv4_block -> Inet,
         ipv6_block -> Inet,
         custom_router_id -> Nullable<Uuid>,
+        ipv4_secondary_blocks -> Array<Inet>,
+    }
+}
+
+table! {
+    vpc_subnet_ip_reservation (id) {
+        id -> Uuid,
+        description -> Text,
+        time_created -> Timestamptz,
+        time_deleted -> Nullable<Timestamptz>,
+        vpc_id -> Uuid,
+        subnet_id -> Uuid,
+        ip -> Inet,
     }
 }
 
@@ -1800,6 +1979,22 @@ table! {
         id -> Uuid,
         sled_id -> Uuid,
         total_size -> Int8,
+        scrub_state -> Nullable<crate::enums::InvZpoolScrubStateEnum>,
+        scrub_start_time -> Nullable<Timestamptz>,
+        scrub_end_time -> Nullable<Timestamptz>,
+        scrub_percent_done -> Nullable<Int2>,
+        scrub_errors -> Nullable<Int8>,
+        allocated -> Nullable<Int8>,
+        fragmentation_percent -> Nullable<Int2>,
+        health_state -> Nullable<crate::enums::InvZpoolHealthStateEnum>,
+        read_errors -> Nullable<Int8>,
+        write_errors -> Nullable<Int8>,
+        checksum_errors -> Nullable<Int8>,
+        resilver_state -> Nullable<crate::enums::InvZpoolResilverStateEnum>,
+        resilver_start_time -> Nullable<Timestamptz>,
+        resilver_end_time -> Nullable<Timestamptz>,
+        resilver_percent_done -> Nullable<Int2>,
+        resilver_errors -> Nullable<Int8>,
     }
 }
 
@@ -1923,6 +2118,9 @@ table! {
         planner_enabled -> Bool,
         time_modified -> Timestamptz,
         add_zones_with_mupdate_override -> Bool,
+        allow_version_skew -> Bool,
+        target_nexus_zone_count -> Nullable<Int2>,
+        target_internal_dns_zone_count -> Nullable<Int2>,
     }
 }
 
@@ -1940,6 +2138,7 @@ table! {
         inv_collection_id -> Uuid,
         zone_id -> Uuid,
         synced -> Bool,
+        correction_usec -> Nullable<Int8>,
     }
 }
 
@@ -1972,6 +2171,8 @@ table! {
         target_release_minimum_generation -> Int8,
 
         nexus_generation -> Int8,
+
+        scope_sled_ids -> Nullable<Array<Uuid>>,
     }
 }
 
@@ -1983,6 +2184,9 @@ table! {
 
         enabled -> Bool,
         time_made_target -> Timestamptz,
+
+        execution_disabled_reason -> Nullable<Text>,
+        time_execution_disabled -> Nullable<Timestamptz>,
     }
 }
 
@@ -2135,6 +2339,13 @@ table! {
     }
 }
 
+table! {
+    bp_planning_report (blueprint_id) {
+        blueprint_id -> Uuid,
+        report -> Jsonb,
+    }
+}
+
 table! {
     bp_pending_mgs_update_rot_bootloader (blueprint_id, hw_baseboard_id) {
         blueprint_id -> Uuid,
@@ -2197,6 +2408,28 @@ table! {
     }
 }
 
+table! {
+    technician_port_update (id) {
+        id -> Uuid,
+        sp_type -> crate::enums::SpTypeEnum,
+        sp_slot -> Int4,
+        time_started -> Timestamptz,
+        time_reported -> Timestamptz,
+    }
+}
+
+table! {
+    technician_port_update_component (update_id, component) {
+        update_id -> Uuid,
+        component -> crate::enums::UpdateComponentKindEnum,
+        state -> crate::enums::UpdateComponentStateEnum,
+        steps_completed -> Int8,
+        total_steps -> Int8,
+        current_step -> Nullable<Text>,
+        message -> Nullable<Text>,
+    }
+}
+
 table! {
     bootstore_keys (key, generation) {
         key -> Text,
@@ -2540,6 +2773,12 @@ allow_tables_to_appear_in_same_query!(ssh_key, instance_ssh_key, instance);
 joinable!(instance_ssh_key -> ssh_key (ssh_key_id));
 joinable!(instance_ssh_key -> instance (instance_id));
 
+allow_tables_to_appear_in_same_query!(instance_tag, instance);
+joinable!(instance_tag -> instance (instance_id));
+
+allow_tables_to_appear_in_same_query!(instance_auto_restart_event, instance);
+joinable!(instance_auto_restart_event -> instance (instance_id));
+
 allow_tables_to_appear_in_same_query!(sled, sled_instance);
 
 joinable!(network_interface -> probe (parent_id));
@@ -19,14 +19,91 @@ use nexus_types::{
 use omicron_common::api::external::{
     http_pagination::{
         PaginatedById, PaginatedByName, PaginatedByNameOrId,
-        PaginatedByTimeAndId,
+        PaginatedByTimeAndId, PaginatedImages,
     },
     *,
 };
-use openapi_manager_types::ValidationContext;
+use openapi_manager_types::{
+    SupportedVersion, SupportedVersions, ValidationContext, api_versions,
+};
 use openapiv3::OpenAPI;
 
-pub const API_VERSION: &str = "20250730.0.0";
+api_versions!([
+    // WHEN CHANGING THE API (part 1 of 2):
+    //
+    // +- Pick a new semver and define it in the list below.  The list MUST
+    // |  remain sorted, which generally means that your version should go at
+    // |  the very top.
+    // |
+    // |  External API versions are date-based (YYYYMMDD): use the date on
+    // |  which the change is expected to land.
+    // |
+    // |  Duplicate this line, uncomment the *second* copy, update that copy for
+    // |  your new API version, and leave the first copy commented out as an
+    // |  example for the next person.
+    // v
+    // (next_int, IDENT),
+    (20261115, INSTANCE_BULK_ACTION),
+    (20261101, INSTANCE_AUTO_RESTART_HISTORY),
+    (20261015, COMPONENT_VERSIONS),
+    (20261001, SLED_EXPUNGE_PREVIEW),
+    (20260901, INSTANCE_EXTERNAL_IP_DETAILS),
+    (20260801, RACK_ACTIVITY),
+    (20260715, IMAGE_LIST_FILTERS),
+    (20260701, OPERATIONS),
+    (20260615, BACKGROUND_TASK_HISTORY),
+    (20260601, INSTANCE_STOPPED_RESERVATION),
+    (20260515, DISK_FORCE_DETACH),
+    (20260501, IP_POOL_PROJECT_LINKS),
+    (20260415, PROJECT_INSTANCE_AUTO_RESTART),
+    (20260401, IMAGE_STATE),
+    (20260315, INSTANCE_MIGRATIONS),
+    (20260301, INSTANCE_FORCE_FAIL),
+    (20260215, INSTANCE_TAGS),
+    (20260201, SNAPSHOT_EXPORTS),
+    (20260115, SNAPSHOT_SCHEDULES),
+    (20260101, DISK_CLONE),
+    (20251215, DELETE_PROTECTION),
+    (20251201, BLUEPRINT_LIST),
+    (20251115, TECHNICIAN_PORT_UPDATES),
+    (20251101, VPC_FIREWALL_RULES_REPLACE),
+    (20251015, VPC_SUBNET_SECONDARY_BLOCKS),
+    (20251001, INSTANCE_BOOT_ORDER),
+    (20250915, PROJECT_EPHEMERAL_IP_POLICY),
+    (20250901, VPC_SUBNET_IP_RESERVATIONS),
+    (20250815, PROJECT_QUOTAS),
+    (20250730, INITIAL),
+]);
+
+// WHEN CHANGING THE API (part 2 of 2):
+//
+// The call to `api_versions!` above defines constants of type
+// `semver::Version` that you can use in your Dropshot API definition to specify
+// the version when a particular endpoint was added or removed.  For example, if
+// you used:
+//
+//     (20250901, ADD_FOOBAR)
+//
+// Then you could use `VERSION_ADD_FOOBAR` as the version in which endpoints
+// were added or removed.
+//
+// Unlike internal APIs, old versions of the external API cannot be retired
+// once all deployed clients have been updated: customer automation may pin an
+// old version indefinitely.  Prefer adding a new versioned endpoint (keeping the
+// old one, and converting between the old and new types) over changing an
+// existing one in place.
+
+/// The most recent version of the external API
+///
+/// This is the version served to clients that do not specify one.
+pub fn latest_version() -> semver::Version {
+    supported_versions()
+        .iter()
+        .last()
+        .expect("at least one external API version")
+        .semver()
+        .clone()
+}
 
 const MIB: usize = 1024 * 1024;
 const GIB: usize = 1024 * MIB;
@@ -254,6 +331,36 @@ pub trait NexusExternalApi {
         Ok(HttpResponseOk(views::Ping { status: views::PingStatus::Ok }))
     }
 
+    /// List background tasks
+    ///
+    /// Each Nexus instance runs its own copy of every background task. This
+    /// lists the tasks run by the Nexus instance that handled the request,
+    /// along with the outcome of each task's most recent activation.
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/background-tasks",
+        tags = ["system/status"],
+        versions = VERSION_BACKGROUND_TASK_HISTORY..,
+    }]
+    async fn system_background_task_list(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<Vec<views::BackgroundTask>>, HttpError>;
+
+    /// Fetch background task history
+    ///
+    /// Returns the most recently completed activations of the task, most
+    /// recent first. Only a bounded number of activations are kept.
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/background-tasks/{name}/history",
+        tags = ["system/status"],
+        versions = VERSION_BACKGROUND_TASK_HISTORY..,
+    }]
+    async fn system_background_task_history(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::BackgroundTaskPath>,
+    ) -> Result<HttpResponseOk<Vec<views::BackgroundTaskActivation>>, HttpError>;
+
     /// Fetch top-level IAM policy
     #[endpoint {
         method = GET,
@@ -385,6 +492,63 @@ pub trait NexusExternalApi {
         new_quota: TypedBody<params::SiloQuotasUpdate>,
     ) -> Result<HttpResponseOk<views::SiloQuotas>, HttpError>;
 
+    /// Fetch resource quotas for project
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/projects/{project}/quotas",
+        tags = ["system/silos"],
+        versions = VERSION_PROJECT_QUOTAS..,
+    }]
+    async fn project_quotas_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseOk<views::ProjectQuotas>, HttpError>;
+
+    /// Create resource quotas for project
+    ///
+    /// Projects without quotas are limited only by the quotas of their silo.
+    /// Project quotas are enforced in addition to silo quotas.
+    #[endpoint {
+        method = POST,
+        path = "/v1/system/projects/{project}/quotas",
+        tags = ["system/silos"],
+        versions = VERSION_PROJECT_QUOTAS..,
+    }]
+    async fn project_quotas_create(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+        new_quota: TypedBody<params::ProjectQuotasCreate>,
+    ) -> Result<HttpResponseCreated<views::ProjectQuotas>, HttpError>;
+
+    /// Update resource quotas for project
+    ///
+    /// If a quota value is not specified, it will remain unchanged.
+    #[endpoint {
+        method = PUT,
+        path = "/v1/system/projects/{project}/quotas",
+        tags = ["system/silos"],
+        versions = VERSION_PROJECT_QUOTAS..,
+    }]
+    async fn project_quotas_update(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+        new_quota: TypedBody<params::ProjectQuotasUpdate>,
+    ) -> Result<HttpResponseOk<views::ProjectQuotas>, HttpError>;
+
+    /// Delete resource quotas for project
+    ///
+    /// The project will be limited only by the quotas of its silo.
+    #[endpoint {
+        method = DELETE,
+        path = "/v1/system/projects/{project}/quotas",
+        tags = ["system/silos"],
+        versions = VERSION_PROJECT_QUOTAS..,
+    }]
+    async fn project_quotas_delete(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseDeleted, HttpError>;
+
     /// List silos
     ///
     /// Lists silos that are discoverable based on the current permissions.
@@ -672,6 +836,64 @@ pub trait NexusExternalApi {
         new_policy: TypedBody<shared::Policy<shared::ProjectRole>>,
     ) -> Result<HttpResponseOk<shared::Policy<shared::ProjectRole>>, HttpError>;
 
+    /// Fetch project's instance auto-restart defaults
+    #[endpoint {
+        method = GET,
+        path = "/v1/projects/{project}/instance-auto-restart",
+        tags = ["projects"],
+        versions = VERSION_PROJECT_INSTANCE_AUTO_RESTART..,
+    }]
+    async fn project_instance_auto_restart_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseOk<views::ProjectInstanceAutoRestart>, HttpError>;
+
+    /// Update project's instance auto-restart defaults
+    ///
+    /// Instances in the project which don't set their own auto-restart policy
+    /// or cooldown use these defaults. If the project doesn't set them either,
+    /// the fleet-wide defaults are used.
+    #[endpoint {
+        method = PUT,
+        path = "/v1/projects/{project}/instance-auto-restart",
+        tags = ["projects"],
+        versions = VERSION_PROJECT_INSTANCE_AUTO_RESTART..,
+    }]
+    async fn project_instance_auto_restart_update(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+        new_defaults: TypedBody<params::ProjectInstanceAutoRestartUpdate>,
+    ) -> Result<HttpResponseOk<views::ProjectInstanceAutoRestart>, HttpError>;
+
+    /// Fetch project's ephemeral IP policy
+    #[endpoint {
+        method = GET,
+        path = "/v1/projects/{project}/ephemeral-ip-policy",
+        tags = ["projects"],
+        versions = VERSION_PROJECT_EPHEMERAL_IP_POLICY..,
+    }]
+    async fn project_ephemeral_ip_policy_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseOk<views::ProjectEphemeralIpPolicy>, HttpError>;
+
+    /// Update project's ephemeral IP policy
+    ///
+    /// The policy determines whether instances created in the project are
+    /// given an ephemeral IP when they don't request one, which pool it comes
+    /// from, and whether instance creators may request one themselves.
+    #[endpoint {
+        method = PUT,
+        path = "/v1/projects/{project}/ephemeral-ip-policy",
+        tags = ["projects"],
+        versions = VERSION_PROJECT_EPHEMERAL_IP_POLICY..,
+    }]
+    async fn project_ephemeral_ip_policy_update(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ProjectPath>,
+        new_policy: TypedBody<params::ProjectEphemeralIpPolicyUpdate>,
+    ) -> Result<HttpResponseOk<views::ProjectEphemeralIpPolicy>, HttpError>;
+
     // IP Pools
 
     /// List IP pools
@@ -753,6 +975,9 @@ pub trait NexusExternalApi {
     ) -> Result<HttpResponseOk<views::IpPool>, HttpError>;
 
     /// Fetch IP pool utilization
+    ///
+    /// Reports the allocated and remaining addresses for the pool as a whole
+    /// and for each of its ranges.
     #[endpoint {
         method = GET,
         path = "/v1/system/ip-pools/{pool}/utilization",
@@ -832,6 +1057,52 @@ pub trait NexusExternalApi {
         update: TypedBody<params::IpPoolSiloUpdate>,
     ) -> Result<HttpResponseOk<views::IpPoolSiloLink>, HttpError>;
 
+    /// List IP pool's linked projects
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/ip-pools/{pool}/projects",
+        tags = ["system/ip-pools"],
+        versions = VERSION_IP_POOL_PROJECT_LINKS..,
+    }]
+    async fn ip_pool_project_list(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::IpPoolPath>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::IpPoolProjectLink>>, HttpError>;
+
+    /// Link IP pool to project
+    ///
+    /// Users in a linked project can allocate external IPs from this pool for
+    /// their instances and floating IPs, even if the pool isn't linked to the
+    /// project's silo. A project-linked pool is never used as a default: users
+    /// must ask for it by name.
+    #[endpoint {
+        method = POST,
+        path = "/v1/system/ip-pools/{pool}/projects",
+        tags = ["system/ip-pools"],
+        versions = VERSION_IP_POOL_PROJECT_LINKS..,
+    }]
+    async fn ip_pool_project_link(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::IpPoolPath>,
+        resource_assoc: TypedBody<params::IpPoolLinkProject>,
+    ) -> Result<HttpResponseCreated<views::IpPoolProjectLink>, HttpError>;
+
+    /// Unlink IP pool from project
+    ///
+    /// Will fail if there are any outstanding IPs allocated in the project,
+    /// unless the pool is also linked to the project's silo.
+    #[endpoint {
+        method = DELETE,
+        path = "/v1/system/ip-pools/{pool}/projects/{project}",
+        tags = ["system/ip-pools"],
+        versions = VERSION_IP_POOL_PROJECT_LINKS..,
+    }]
+    async fn ip_pool_project_unlink(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::IpPoolProjectPath>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
+
     /// Fetch Oxide service IP pool
     #[endpoint {
         method = GET,
@@ -1048,6 +1319,23 @@ pub trait NexusExternalApi {
         query_params: Query<params::OptionalProjectSelector>,
     ) -> Result<HttpResponseOk<Disk>, HttpError>;
 
+    /// Update disk
+    ///
+    /// A disk that is delete-protected cannot be deleted until its protection
+    /// is cleared.
+    #[endpoint {
+        method = PUT,
+        path = "/v1/disks/{disk}",
+        tags = ["disks"],
+        versions = VERSION_DELETE_PROTECTION..,
+    }]
+    async fn disk_update(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        updated_disk: TypedBody<params::DiskUpdate>,
+    ) -> Result<HttpResponseOk<Disk>, HttpError>;
+
     /// Delete disk
     #[endpoint {
         method = DELETE,
@@ -1115,24 +1403,130 @@ pub trait NexusExternalApi {
         finalize_params: TypedBody<params::FinalizeDisk>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 
+    /// Clone disk
+    ///
+    /// Create a new, independent disk with the current contents of an
+    /// existing disk, in the same project. The new disk's contents are copied
+    /// from the source in the background; use the copy progress endpoint to
+    /// find out when the copy is complete.
+    #[endpoint {
+        method = POST,
+        path = "/v1/disks/{disk}/clone",
+        operation_id = "disk_clone",
+        tags = ["disks"],
+        versions = VERSION_DISK_CLONE..VERSION_OPERATIONS,
+    }]
+    async fn disk_clone_v20260615(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        clone_params: TypedBody<params::DiskClone>,
+    ) -> Result<HttpResponseCreated<Disk>, HttpError>;
+
+    /// Clone disk
+    ///
+    /// Create a new, independent disk with the current contents of an
+    /// existing disk, in the same project. Returns an operation that succeeds
+    /// once the new disk has been created. The new disk's contents are then
+    /// copied from the source in the background; use the copy progress
+    /// endpoint to find out when the copy is complete.
+    #[endpoint {
+        method = POST,
+        path = "/v1/disks/{disk}/clone",
+        tags = ["disks"],
+        versions = VERSION_OPERATIONS..,
+    }]
+    async fn disk_clone(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        clone_params: TypedBody<params::DiskClone>,
+    ) -> Result<HttpResponseAccepted<views::Operation>, HttpError>;
+
+    /// Fetch disk copy progress
+    ///
+    /// Report whether a disk's contents have been fully copied from the
+    /// snapshot or image it was created from.
+    #[endpoint {
+        method = GET,
+        path = "/v1/disks/{disk}/copy-progress",
+        tags = ["disks"],
+        versions = VERSION_DISK_CLONE..,
+    }]
+    async fn disk_copy_progress_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseOk<views::DiskCopyProgress>, HttpError>;
+
+    /// Force disk to detach
+    ///
+    /// Detaches a disk from an instance whose record has been deleted or
+    /// corrupted, which the usual instance disk detach can't do. This requires
+    /// fleet administrator privileges, and is refused if the instance still
+    /// exists or if any of its VMMs may still have the disk open.
+    #[endpoint {
+        method = POST,
+        path = "/v1/disks/{disk}/force-detach",
+        tags = ["disks"],
+        versions = VERSION_DISK_FORCE_DETACH..,
+    }]
+    async fn disk_force_detach(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        force_detach_params: TypedBody<params::DiskForceDetach>,
+    ) -> Result<HttpResponseOk<Disk>, HttpError>;
+
     // Instances
 
     /// List instances
     #[endpoint {
         method = GET,
         path = "/v1/instances",
+        operation_id = "instance_list",
         tags = ["instances"],
+        versions = ..VERSION_INSTANCE_TAGS,
     }]
-    async fn instance_list(
+    async fn instance_list_v20260201(
         rqctx: RequestContext<Self::Context>,
         query_params: Query<PaginatedByNameOrId<params::ProjectSelector>>,
     ) -> Result<HttpResponseOk<ResultsPage<Instance>>, HttpError>;
 
+    /// List instances
+    ///
+    /// Instances can be filtered by tag with `tag=key:value`.
+    #[endpoint {
+        method = GET,
+        path = "/v1/instances",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_TAGS..,
+    }]
+    async fn instance_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedByNameOrId<params::InstanceListSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<Instance>>, HttpError>;
+
     /// Create instance
     #[endpoint {
         method = POST,
         path = "/v1/instances",
+        operation_id = "instance_create",
         tags = ["instances"],
+        versions = ..VERSION_INSTANCE_BOOT_ORDER,
+    }]
+    async fn instance_create_v20250915(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::ProjectSelector>,
+        new_instance: TypedBody<params::v20250915::InstanceCreate>,
+    ) -> Result<HttpResponseCreated<Instance>, HttpError>;
+
+    /// Create instance
+    #[endpoint {
+        method = POST,
+        path = "/v1/instances",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_BOOT_ORDER..,
     }]
     async fn instance_create(
         rqctx: RequestContext<Self::Context>,
@@ -1168,7 +1562,23 @@ pub trait NexusExternalApi {
     #[endpoint {
         method = PUT,
         path = "/v1/instances/{instance}",
+        operation_id = "instance_update",
         tags = ["instances"],
+        versions = ..VERSION_INSTANCE_BOOT_ORDER,
+    }]
+    async fn instance_update_v20250915(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+        instance_config: TypedBody<params::v20250915::InstanceUpdate>,
+    ) -> Result<HttpResponseOk<Instance>, HttpError>;
+
+    /// Update instance
+    #[endpoint {
+        method = PUT,
+        path = "/v1/instances/{instance}",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_BOOT_ORDER..,
     }]
     async fn instance_update(
         rqctx: RequestContext<Self::Context>,
@@ -1213,6 +1623,80 @@ pub trait NexusExternalApi {
         path_params: Path<params::InstancePath>,
     ) -> Result<HttpResponseAccepted<Instance>, HttpError>;
 
+    /// Start, stop, or reboot many instances
+    ///
+    /// The action is taken on each instance independently, several at a time,
+    /// and the outcome for each is reported in the order the instances were
+    /// given. The request succeeds even if the action fails for some (or all)
+    /// of the instances. At most 1000 instances may be given.
+    #[endpoint {
+        method = POST,
+        path = "/v1/instance-bulk-action",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_BULK_ACTION..,
+    }]
+    async fn instance_bulk_action(
+        rqctx: RequestContext<Self::Context>,
+        params: TypedBody<params::InstanceBulkAction>,
+    ) -> Result<HttpResponseOk<views::InstanceBulkActionResults>, HttpError>;
+
+    /// Force instance to fail
+    ///
+    /// Moves a wedged instance, such as one whose VMM has stopped responding,
+    /// to the `failed` state so that it can be restarted or deleted. This
+    /// requires fleet administrator privileges, and is refused if the instance
+    /// does not appear to be wedged.
+    #[endpoint {
+        method = POST,
+        path = "/v1/instances/{instance}/force-fail",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_FORCE_FAIL..,
+    }]
+    async fn instance_force_fail(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+        force_fail_params: TypedBody<params::InstanceForceFail>,
+    ) -> Result<HttpResponseAccepted<Instance>, HttpError>;
+
+    /// Export instance
+    ///
+    /// Takes a snapshot of the instance's boot disk and exports it, along
+    /// with a manifest describing the instance, so that the instance can be
+    /// downloaded and run elsewhere.
+    #[endpoint {
+        method = POST,
+        path = "/v1/instances/{instance}/export",
+        operation_id = "instance_export",
+        tags = ["instances"],
+        versions = VERSION_SNAPSHOT_EXPORTS..VERSION_OPERATIONS,
+    }]
+    async fn instance_export_v20260615(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+        export_params: TypedBody<params::InstanceExportCreate>,
+    ) -> Result<HttpResponseCreated<views::SnapshotExport>, HttpError>;
+
+    /// Export instance
+    ///
+    /// Takes a snapshot of the instance's boot disk and exports it, along
+    /// with a manifest describing the instance, so that the instance can be
+    /// downloaded and run elsewhere. Returns an operation that succeeds once
+    /// the export is ready to be downloaded.
+    #[endpoint {
+        method = POST,
+        path = "/v1/instances/{instance}/export",
+        tags = ["instances"],
+        versions = VERSION_OPERATIONS..,
+    }]
+    async fn instance_export(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+        export_params: TypedBody<params::InstanceExportCreate>,
+    ) -> Result<HttpResponseAccepted<views::Operation>, HttpError>;
+
     /// Fetch instance serial console
     #[endpoint {
         method = GET,
@@ -1256,6 +1740,41 @@ pub trait NexusExternalApi {
         >,
     ) -> Result<HttpResponseOk<ResultsPage<views::SshKey>>, HttpError>;
 
+    /// List live migrations of instance
+    ///
+    /// Lists every live migration of the instance from one sled to another,
+    /// including migrations that are still in progress or that failed.
+    #[endpoint {
+        method = GET,
+        path = "/v1/instances/{instance}/migrations",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_MIGRATIONS..,
+    }]
+    async fn instance_migration_list(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::InstancePath>,
+        query_params: Query<PaginatedById<params::OptionalProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::InstanceMigration>>, HttpError>;
+
+    /// List automatic restarts of instance
+    ///
+    /// Lists every time the control plane has automatically restarted the
+    /// instance after it failed.
+    #[endpoint {
+        method = GET,
+        path = "/v1/instances/{instance}/auto-restart-history",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_AUTO_RESTART_HISTORY..,
+    }]
+    async fn instance_auto_restart_history_list(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::InstancePath>,
+        query_params: Query<PaginatedById<params::OptionalProjectSelector>>,
+    ) -> Result<
+        HttpResponseOk<ResultsPage<views::InstanceAutoRestartEvent>>,
+        HttpError,
+    >;
+
     /// List disks for instance
     #[endpoint {
         method = GET,
@@ -2036,15 +2555,34 @@ pub trait NexusExternalApi {
     #[endpoint {
         method = GET,
         path = "/v1/images",
+        operation_id = "image_list",
         tags = ["images"],
+        versions = ..VERSION_IMAGE_LIST_FILTERS,
     }]
-    async fn image_list(
+    async fn image_list_v20260701(
         rqctx: RequestContext<Self::Context>,
         query_params: Query<
             PaginatedByNameOrId<params::OptionalProjectSelector>,
         >,
     ) -> Result<HttpResponseOk<ResultsPage<views::Image>>, HttpError>;
 
+    /// List images
+    ///
+    /// List images which are global or scoped to the specified project.
+    /// Images can be narrowed down by operating system, version, and minimum
+    /// size, and sorted by name, ID, creation date (most recent first), or
+    /// size (smallest first).
+    #[endpoint {
+        method = GET,
+        path = "/v1/images",
+        tags = ["images"],
+        versions = VERSION_IMAGE_LIST_FILTERS..,
+    }]
+    async fn image_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedImages<params::ImageListSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::Image>>, HttpError>;
+
     /// Create image
     ///
     /// Create a new image in a project.
@@ -2117,6 +2655,24 @@ pub trait NexusExternalApi {
         query_params: Query<params::ProjectSelector>,
     ) -> Result<HttpResponseAccepted<views::Image>, HttpError>;
 
+    /// Set image state
+    ///
+    /// Deprecated images can still be used to create disks, but the response
+    /// includes a warning. Blocked images can't be used to create new disks.
+    /// Existing disks created from the image are unaffected.
+    #[endpoint {
+        method = PUT,
+        path = "/v1/images/{image}/state",
+        tags = ["images"],
+        versions = VERSION_IMAGE_STATE..,
+    }]
+    async fn image_state_update(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::ImagePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        state: TypedBody<params::ImageStateUpdate>,
+    ) -> Result<HttpResponseOk<views::Image>, HttpError>;
+
     /// List network interfaces
     #[endpoint {
         method = GET,
@@ -2188,14 +2744,32 @@ pub trait NexusExternalApi {
     #[endpoint {
         method = GET,
         path = "/v1/instances/{instance}/external-ips",
+        operation_id = "instance_external_ip_list",
         tags = ["instances"],
+        versions = ..VERSION_INSTANCE_EXTERNAL_IP_DETAILS,
     }]
-    async fn instance_external_ip_list(
+    async fn instance_external_ip_list_v20260801(
         rqctx: RequestContext<Self::Context>,
         query_params: Query<params::OptionalProjectSelector>,
         path_params: Path<params::InstancePath>,
     ) -> Result<HttpResponseOk<ResultsPage<views::ExternalIp>>, HttpError>;
 
+    /// List external IP addresses
+    ///
+    /// Addresses are listed in the order in which they were attached to the
+    /// instance.
+    #[endpoint {
+        method = GET,
+        path = "/v1/instances/{instance}/external-ips",
+        tags = ["instances"],
+        versions = VERSION_INSTANCE_EXTERNAL_IP_DETAILS..,
+    }]
+    async fn instance_external_ip_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::InstanceExternalIp>>, HttpError>;
+
     /// Allocate and attach ephemeral IP to instance
     #[endpoint {
         method = POST,
@@ -2260,6 +2834,23 @@ pub trait NexusExternalApi {
         query_params: Query<params::OptionalProjectSelector>,
     ) -> Result<HttpResponseOk<views::Snapshot>, HttpError>;
 
+    /// Update snapshot
+    ///
+    /// A snapshot that is delete-protected cannot be deleted until its
+    /// protection is cleared.
+    #[endpoint {
+        method = PUT,
+        path = "/v1/snapshots/{snapshot}",
+        tags = ["snapshots"],
+        versions = VERSION_DELETE_PROTECTION..,
+    }]
+    async fn snapshot_update(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        updated_snapshot: TypedBody<params::SnapshotUpdate>,
+    ) -> Result<HttpResponseOk<views::Snapshot>, HttpError>;
+
     /// Delete snapshot
     #[endpoint {
         method = DELETE,
@@ -2272,6 +2863,216 @@ pub trait NexusExternalApi {
         query_params: Query<params::OptionalProjectSelector>,
     ) -> Result<HttpResponseDeleted, HttpError>;
 
+    // Snapshot schedules
+
+    /// List snapshot schedules
+    #[endpoint {
+        method = GET,
+        path = "/v1/snapshot-schedules",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_SCHEDULES..,
+    }]
+    async fn snapshot_schedule_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedByNameOrId<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::SnapshotSchedule>>, HttpError>;
+
+    /// Create snapshot schedule
+    ///
+    /// Periodically takes snapshots of a disk, or of every disk in the
+    /// project, and deletes the oldest of those snapshots beyond the
+    /// schedule's retention count.
+    #[endpoint {
+        method = POST,
+        path = "/v1/snapshot-schedules",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_SCHEDULES..,
+    }]
+    async fn snapshot_schedule_create(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::ProjectSelector>,
+        new_schedule: TypedBody<params::SnapshotScheduleCreate>,
+    ) -> Result<HttpResponseCreated<views::SnapshotSchedule>, HttpError>;
+
+    /// Fetch snapshot schedule
+    #[endpoint {
+        method = GET,
+        path = "/v1/snapshot-schedules/{snapshot_schedule}",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_SCHEDULES..,
+    }]
+    async fn snapshot_schedule_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotSchedulePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseOk<views::SnapshotSchedule>, HttpError>;
+
+    /// Update snapshot schedule
+    #[endpoint {
+        method = PUT,
+        path = "/v1/snapshot-schedules/{snapshot_schedule}",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_SCHEDULES..,
+    }]
+    async fn snapshot_schedule_update(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotSchedulePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        updated_schedule: TypedBody<params::SnapshotScheduleUpdate>,
+    ) -> Result<HttpResponseOk<views::SnapshotSchedule>, HttpError>;
+
+    /// Delete snapshot schedule
+    ///
+    /// Snapshots already taken by the schedule are not deleted.
+    #[endpoint {
+        method = DELETE,
+        path = "/v1/snapshot-schedules/{snapshot_schedule}",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_SCHEDULES..,
+    }]
+    async fn snapshot_schedule_delete(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotSchedulePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseDeleted, HttpError>;
+
+    // Snapshot exports
+
+    /// List snapshot exports
+    #[endpoint {
+        method = GET,
+        path = "/v1/snapshot-exports",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_EXPORTS..,
+    }]
+    async fn snapshot_export_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedById<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::SnapshotExport>>, HttpError>;
+
+    /// Export snapshot
+    ///
+    /// Makes the contents of the snapshot available for download as a raw
+    /// disk image. The export remains available until it is deleted, even if
+    /// the snapshot itself is deleted.
+    #[endpoint {
+        method = POST,
+        path = "/v1/snapshots/{snapshot}/export",
+        operation_id = "snapshot_export_create",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_EXPORTS..VERSION_OPERATIONS,
+    }]
+    async fn snapshot_export_create_v20260615(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseCreated<views::SnapshotExport>, HttpError>;
+
+    /// Export snapshot
+    ///
+    /// Makes the contents of the snapshot available for download as a raw
+    /// disk image. The export remains available until it is deleted, even if
+    /// the snapshot itself is deleted. Returns an operation that succeeds once
+    /// the export is ready to be downloaded.
+    #[endpoint {
+        method = POST,
+        path = "/v1/snapshots/{snapshot}/export",
+        tags = ["snapshots"],
+        versions = VERSION_OPERATIONS..,
+    }]
+    async fn snapshot_export_create(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseAccepted<views::Operation>, HttpError>;
+
+    /// Fetch snapshot export
+    #[endpoint {
+        method = GET,
+        path = "/v1/snapshot-exports/{snapshot_export}",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_EXPORTS..,
+    }]
+    async fn snapshot_export_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<HttpResponseOk<views::SnapshotExport>, HttpError>;
+
+    /// Fetch snapshot export manifest
+    ///
+    /// The manifest describes the exported image and, for exports of an
+    /// instance, how the instance was configured.
+    #[endpoint {
+        method = GET,
+        path = "/v1/snapshot-exports/{snapshot_export}/manifest",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_EXPORTS..,
+    }]
+    async fn snapshot_export_manifest(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<HttpResponseOk<views::ExportManifest>, HttpError>;
+
+    /// Download snapshot export
+    ///
+    /// Returns the raw disk image. Range requests are supported, so
+    /// interrupted downloads may be resumed.
+    #[endpoint {
+        method = GET,
+        path = "/v1/snapshot-exports/{snapshot_export}/download",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_EXPORTS..,
+    }]
+    async fn snapshot_export_download(
+        rqctx: RequestContext<Self::Context>,
+        headers: Header<headers::RangeRequest>,
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<Response<Body>, HttpError>;
+
+    /// Delete snapshot export
+    #[endpoint {
+        method = DELETE,
+        path = "/v1/snapshot-exports/{snapshot_export}",
+        tags = ["snapshots"],
+        versions = VERSION_SNAPSHOT_EXPORTS..,
+    }]
+    async fn snapshot_export_delete(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<HttpResponseDeleted, HttpError>;
+
+    // Operations
+
+    /// List operations
+    ///
+    /// Operations track requests that take a long time to complete, such as
+    /// cloning a disk or exporting a snapshot.
+    #[endpoint {
+        method = GET,
+        path = "/v1/operations",
+        tags = ["projects"],
+        versions = VERSION_OPERATIONS..,
+    }]
+    async fn operation_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedById<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::Operation>>, HttpError>;
+
+    /// Fetch operation
+    ///
+    /// Poll an operation until its state is no longer `running`. Once it has
+    /// succeeded, its result links to the resource that it produced.
+    #[endpoint {
+        method = GET,
+        path = "/v1/operations/{operation}",
+        tags = ["projects"],
+        versions = VERSION_OPERATIONS..,
+    }]
+    async fn operation_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::OperationPath>,
+    ) -> Result<HttpResponseOk<views::Operation>, HttpError>;
+
     // VPCs
 
     /// List VPCs
@@ -2410,6 +3211,86 @@ pub trait NexusExternalApi {
         query_params: Query<PaginatedByNameOrId<params::OptionalVpcSelector>>,
     ) -> Result<HttpResponseOk<ResultsPage<InstanceNetworkInterface>>, HttpError>;
 
+    /// List IP reservations
+    #[endpoint {
+        method = GET,
+        path = "/v1/vpc-subnets/{subnet}/ip-reservations",
+        tags = ["vpcs"],
+        versions = VERSION_VPC_SUBNET_IP_RESERVATIONS..,
+    }]
+    async fn vpc_subnet_ip_reservation_list(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<PaginatedById<params::OptionalVpcSelector>>,
+    ) -> Result<
+        HttpResponseOk<ResultsPage<views::VpcSubnetIpReservation>>,
+        HttpError,
+    >;
+
+    /// Reserve IP address
+    ///
+    /// Reserved addresses are never automatically assigned to network
+    /// interfaces. To bind a reservation to an instance, request the reserved
+    /// address explicitly when creating the instance's network interface.
+    #[endpoint {
+        method = POST,
+        path = "/v1/vpc-subnets/{subnet}/ip-reservations",
+        tags = ["vpcs"],
+        versions = VERSION_VPC_SUBNET_IP_RESERVATIONS..,
+    }]
+    async fn vpc_subnet_ip_reservation_create(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<params::OptionalVpcSelector>,
+        reservation_params: TypedBody<params::VpcSubnetIpReservationCreate>,
+    ) -> Result<HttpResponseCreated<views::VpcSubnetIpReservation>, HttpError>;
+
+    /// Release IP reservation
+    ///
+    /// A network interface already using the address keeps it.
+    #[endpoint {
+        method = DELETE,
+        path = "/v1/vpc-subnets/{subnet}/ip-reservations/{address}",
+        tags = ["vpcs"],
+        versions = VERSION_VPC_SUBNET_IP_RESERVATIONS..,
+    }]
+    async fn vpc_subnet_ip_reservation_delete(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::VpcSubnetIpReservationPath>,
+        query_params: Query<params::OptionalVpcSelector>,
+    ) -> Result<HttpResponseDeleted, HttpError>;
+
+    /// List IPv4 blocks of subnet
+    #[endpoint {
+        method = GET,
+        path = "/v1/vpc-subnets/{subnet}/ipv4-blocks",
+        tags = ["vpcs"],
+        versions = VERSION_VPC_SUBNET_SECONDARY_BLOCKS..,
+    }]
+    async fn vpc_subnet_ipv4_blocks_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<params::OptionalVpcSelector>,
+    ) -> Result<HttpResponseOk<views::VpcSubnetIpv4Blocks>, HttpError>;
+
+    /// Add secondary IPv4 block to subnet
+    ///
+    /// Network interfaces in the subnet are assigned addresses from the new
+    /// block once the existing blocks are exhausted. Existing interfaces keep
+    /// their addresses.
+    #[endpoint {
+        method = POST,
+        path = "/v1/vpc-subnets/{subnet}/ipv4-blocks",
+        tags = ["vpcs"],
+        versions = VERSION_VPC_SUBNET_SECONDARY_BLOCKS..,
+    }]
+    async fn vpc_subnet_ipv4_block_add(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<params::OptionalVpcSelector>,
+        block_params: TypedBody<params::VpcSubnetIpv4BlockAdd>,
+    ) -> Result<HttpResponseOk<views::VpcSubnetIpv4Blocks>, HttpError>;
+
     // VPC Firewalls
 
     /// List firewall rules
@@ -2451,6 +3332,25 @@ pub trait NexusExternalApi {
         router_params: TypedBody<VpcFirewallRuleUpdateParams>,
     ) -> Result<HttpResponseOk<VpcFirewallRules>, HttpError>;
 
+    /// Preview or apply firewall rules replacement
+    ///
+    /// Computes the difference between the VPC's current firewall rules and
+    /// the provided rules, which will replace all existing rules. Without a
+    /// confirm token, nothing is changed, and the response carries a token
+    /// that applies this replacement when passed back with the same rules.
+    /// Applying fails if the VPC's rules have changed since the preview.
+    #[endpoint {
+        method = POST,
+        path = "/v1/vpc-firewall-rules/replace",
+        tags = ["vpcs"],
+        versions = VERSION_VPC_FIREWALL_RULES_REPLACE..,
+    }]
+    async fn vpc_firewall_rules_replace(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<params::VpcSelector>,
+        replace_params: TypedBody<params::VpcFirewallRulesReplace>,
+    ) -> Result<HttpResponseOk<views::VpcFirewallRulesReplaceResult>, HttpError>;
+
     // VPC Routers
 
     /// List routers
@@ -2793,6 +3693,23 @@ pub trait NexusExternalApi {
         new_provision_state: TypedBody<params::SledProvisionPolicyParams>,
     ) -> Result<HttpResponseOk<params::SledProvisionPolicyResponse>, HttpError>;
 
+    /// Preview sled expungement
+    ///
+    /// Reports what the update planner would do if the sled were expunged,
+    /// and whether expunging it now would leave any data without enough
+    /// copies. Expunging a sled can't be undone; check this first. Nothing is
+    /// changed.
+    #[endpoint {
+        method = POST,
+        path = "/v1/system/hardware/sleds/{sled_id}/expunge:preview",
+        tags = ["system/hardware"],
+        versions = VERSION_SLED_EXPUNGE_PREVIEW..,
+    }]
+    async fn sled_expunge_preview(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<params::SledPath>,
+    ) -> Result<HttpResponseOk<views::SledExpungePreview>, HttpError>;
+
     /// List instances running on given sled
     #[endpoint {
         method = GET,
@@ -3068,6 +3985,70 @@ pub trait NexusExternalApi {
         params: TypedBody<params::SetTargetReleaseParams>,
     ) -> Result<HttpResponseCreated<views::TargetRelease>, HttpError>;
 
+    /// View the versions of the rack's updateable components
+    ///
+    /// For every in-service sled, lists the version of each updateable
+    /// component (the service processor, root of trust and its bootloader, the
+    /// host OS, and each control plane zone): the release its software
+    /// currently comes from, according to the latest inventory collection, and
+    /// the release it's being updated to.
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/update/components",
+        tags = ["system/update"],
+        versions = VERSION_COMPONENT_VERSIONS..,
+    }]
+    async fn system_update_component_versions(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<views::ComponentVersionMatrix>, HttpError>;
+
+    /// List updates driven from the technician port
+    ///
+    /// Updates performed from the technician port (e.g., to recover a rack
+    /// that can't update itself) are carried out by wicketd rather than by
+    /// the rack reconfigurator. wicketd reports the progress of each of its
+    /// updates here, so it can be followed by operators elsewhere.
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/update/technician-port",
+        tags = ["system/update"],
+        versions = VERSION_TECHNICIAN_PORT_UPDATES..,
+    }]
+    async fn system_update_technician_port_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<
+        HttpResponseOk<ResultsPage<views::TechnicianPortUpdate>>,
+        HttpError,
+    >;
+
+    /// List blueprints
+    ///
+    /// Blueprints describe the intended configuration of the rack. The rack
+    /// reconfigurator generates a new blueprint whenever it decides to change
+    /// the system, and works toward whichever blueprint is the current target.
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/blueprints",
+        tags = ["system/update"],
+        versions = VERSION_BLUEPRINT_LIST..,
+    }]
+    async fn system_blueprint_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::Blueprint>>, HttpError>;
+
+    /// Fetch current target blueprint
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/blueprints/target",
+        tags = ["system/update"],
+        versions = VERSION_BLUEPRINT_LIST..,
+    }]
+    async fn system_blueprint_target_view(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<views::BlueprintTarget>, HttpError>;
+
     // Silo users
 
     /// List users
@@ -3482,6 +4463,24 @@ pub trait NexusExternalApi {
         query_params: Query<PaginatedByTimeAndId<params::AuditLog>>,
     ) -> Result<HttpResponseOk<ResultsPage<views::AuditLogEntry>>, HttpError>;
 
+    /// View rack activity
+    ///
+    /// A single chronological feed of what happened to the rack: requests
+    /// recorded in the audit log, changes to the target blueprint, sleds and
+    /// physical disks added to or removed from the rack, and faults that
+    /// raised alerts. Like the audit log, activity is listed for a time range,
+    /// and can be narrowed down to one kind of activity.
+    #[endpoint {
+        method = GET,
+        path = "/v1/system/activity",
+        tags = ["system/audit-log"],
+        versions = VERSION_RACK_ACTIVITY..,
+    }]
+    async fn rack_activity_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedByTimeAndId<params::RackActivity>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::RackActivity>>, HttpError>;
+
     // Console API: logins
 
     /// SAML login console page (just a link to the IdP)
@@ -3961,10 +4960,13 @@ pub fn validate_api(spec: &OpenAPI, mut cx: ValidationContext<'_>) {
             spec.info.title,
         ));
     }
-    if spec.info.version != API_VERSION {
+    if !supported_versions()
+        .iter()
+        .any(|v| v.semver().to_string() == spec.info.version)
+    {
         cx.report_error(anyhow!(
-            "Expected OpenAPI version to be '{}', found '{}'",
-            API_VERSION,
+            "Expected OpenAPI version to be a supported external API \
+             version, found '{}'",
             spec.info.version,
         ));
     }
@@ -4018,7 +5020,9 @@ pub fn validate_api(spec: &OpenAPI, mut cx: ValidationContext<'_>) {
             ));
     }
 
-    if ops_by_tag_valid {
+    // Every supported version of the API is validated, but the tags file only
+    // describes the latest one.
+    if ops_by_tag_valid && spec.info.version == latest_version().to_string() {
         let mut tags = String::new();
         for (tag, mut ops) in ops_by_tag {
             ops.sort();
@@ -12,9 +12,11 @@ use dropshot::{
 use http::Response;
 use nexus_types::{
     deployment::{
-        Blueprint, BlueprintMetadata, BlueprintTarget, BlueprintTargetSet,
-        ClickhousePolicy, OximeterReadPolicy,
-        ReconfiguratorChickenSwitchesParam, ReconfiguratorChickenSwitchesView,
+        Blueprint, BlueprintMetadata, BlueprintTarget,
+        BlueprintTargetExecution, BlueprintTargetExecutionSet,
+        BlueprintTargetSet, CapacityReport, ClickhousePolicy,
+        OximeterReadPolicy, ReconfiguratorChickenSwitchesParam,
+        ReconfiguratorChickenSwitchesView,
     },
     external_api::{
         headers::RangeRequest,
@@ -24,12 +26,17 @@ use nexus_types::{
     },
     internal_api::{
         params::{
+            BreakGlassDisableRequest, BreakGlassEnableRequest,
             InstanceMigrateRequest, OximeterInfo, RackInitializationRequest,
-            SledAgentInfo, SwitchPutRequest, SwitchPutResponse,
+            RollingSledRebootRequest, RuntimeConfigUpdate, SledAgentInfo,
+            SwitchPutRequest, SwitchPutResponse, TechnicianPortUpdateReport,
+            VolumeReferenceCheckParams,
         },
         views::{
-            BackgroundTask, DemoSaga, MgsUpdateDriverStatus, NatEntryView,
-            QuiesceStatus, Saga, UpdateStatus,
+            BackgroundTask, BreakGlassAccount, DemoSaga,
+            InstancePlacementExplanation, MgsUpdateDriverStatus, NatEntryView,
+            QuiesceStatus, RollingSledReboot, RuntimeConfig, Saga,
+            UpdateStatus, VolumeReferenceCheckReport,
         },
     },
 };
@@ -45,8 +52,8 @@ use omicron_common::api::{
     },
 };
 use omicron_uuid_kinds::{
-    DemoSagaUuid, DownstairsKind, PropolisUuid, SledUuid, TypedUuid,
-    UpstairsKind, UpstairsRepairKind, VolumeUuid,
+    DemoSagaUuid, DownstairsKind, InstanceUuid, OmicronZoneUuid, PropolisUuid,
+    SledUuid, TypedUuid, UpstairsKind, UpstairsRepairKind, VolumeUuid,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -367,6 +374,30 @@ pub trait NexusInternalApi {
         body: TypedBody<BackgroundTasksActivateRequest>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 
+    // Runtime configuration
+
+    /// Fetch the configuration of this Nexus that can be changed at runtime
+    #[endpoint {
+        method = GET,
+        path = "/runtime-config",
+    }]
+    async fn runtime_config_view(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<RuntimeConfig>, HttpError>;
+
+    /// Change the configuration of this Nexus without restarting it
+    ///
+    /// The update is validated in full before any of it is applied.  Changes
+    /// are not persisted: they're lost when Nexus restarts.
+    #[endpoint {
+        method = PUT,
+        path = "/runtime-config",
+    }]
+    async fn runtime_config_update(
+        rqctx: RequestContext<Self::Context>,
+        body: TypedBody<RuntimeConfigUpdate>,
+    ) -> Result<HttpResponseOk<RuntimeConfig>, HttpError>;
+
     // Debug interfaces for ongoing MGS updates
 
     /// Fetch information about ongoing MGS updates
@@ -465,6 +496,29 @@ pub trait NexusInternalApi {
         target: TypedBody<BlueprintTargetSet>,
     ) -> Result<HttpResponseOk<BlueprintTarget>, HttpError>;
 
+    /// Fetches whether execution of the current target blueprint is disabled
+    #[endpoint {
+        method = GET,
+        path = "/deployment/blueprints/target/execution",
+    }]
+    async fn blueprint_target_execution_view(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<BlueprintTargetExecution>, HttpError>;
+
+    /// Disable or re-enable execution of the current target blueprint
+    ///
+    /// Disabling execution requires a reason, which is recorded along with the
+    /// time. Execution remains disabled, even as new blueprints are made the
+    /// target, until it's re-enabled.
+    #[endpoint {
+        method = PUT,
+        path = "/deployment/blueprints/target/execution",
+    }]
+    async fn blueprint_target_set_execution(
+        rqctx: RequestContext<Self::Context>,
+        execution: TypedBody<BlueprintTargetExecutionSet>,
+    ) -> Result<HttpResponseOk<BlueprintTargetExecution>, HttpError>;
+
     // Generating blueprints
 
     /// Generates a new blueprint for the current system, re-evaluating anything
@@ -489,6 +543,34 @@ pub trait NexusInternalApi {
         blueprint: TypedBody<Blueprint>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 
+    /// Generates a new blueprint, based on the current target, in which the
+    /// specified zone is cordoned
+    ///
+    /// A cordoned zone keeps running on its sled, but is removed from DNS and
+    /// does not take part in load-sharing. The new blueprint is not made the
+    /// target.
+    #[endpoint {
+        method = POST,
+        path = "/deployment/zones/{zone_id}/cordon",
+    }]
+    async fn zone_cordon(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<OmicronZonePathParam>,
+    ) -> Result<HttpResponseOk<Blueprint>, HttpError>;
+
+    /// Generates a new blueprint, based on the current target, in which the
+    /// specified zone is returned to service after being cordoned
+    ///
+    /// The new blueprint is not made the target.
+    #[endpoint {
+        method = POST,
+        path = "/deployment/zones/{zone_id}/uncordon",
+    }]
+    async fn zone_uncordon(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<OmicronZonePathParam>,
+    ) -> Result<HttpResponseOk<Blueprint>, HttpError>;
+
     /// Get the current set of chicken switches
     #[endpoint {
         method = GET,
@@ -527,6 +609,35 @@ pub trait NexusInternalApi {
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<UpdateStatus>, HttpError>;
 
+    /// Report how many more control plane zones and customer instances could
+    /// be placed before the system runs out of room
+    ///
+    /// This is based on the current target blueprint, the latest inventory
+    /// collection, and the resources currently reserved by instances.
+    #[endpoint {
+        method = GET,
+        path = "/deployment/capacity"
+    }]
+    async fn capacity_report(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<CapacityReportQueryParams>,
+    ) -> Result<HttpResponseOk<CapacityReport>, HttpError>;
+
+    /// Explain where an instance could be placed
+    ///
+    /// For an instance of the given shape, this evaluates every commissioned
+    /// sled the way instance placement does and reports why each one that
+    /// can't host the instance was rejected. If an instance is given, its
+    /// affinity and anti-affinity groups are taken into account.
+    #[endpoint {
+        method = GET,
+        path = "/placement/explain"
+    }]
+    async fn instance_placement_explain(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<InstancePlacementExplainQueryParams>,
+    ) -> Result<HttpResponseOk<InstancePlacementExplanation>, HttpError>;
+
     /// List uninitialized sleds
     #[endpoint {
         method = GET,
@@ -581,6 +692,58 @@ pub trait NexusInternalApi {
         disk: TypedBody<PhysicalDiskPath>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 
+    /// Start a rolling reboot of sleds
+    ///
+    /// Sleds are rebooted in the order given, no more than the batch size at
+    /// a time. Each sled is quiesced, and its instances are migrated to other
+    /// sleds; instances that can't be migrated are stopped if their
+    /// auto-restart policy allows it, and started again once the sled is
+    /// back. The sled is then reset via its SP, and the next sled isn't
+    /// started on until it reports its zones running again. Only one rolling
+    /// reboot may run at a time.
+    #[endpoint {
+        method = POST,
+        path = "/sleds/rolling-reboots",
+    }]
+    async fn rolling_sled_reboot_start(
+        rqctx: RequestContext<Self::Context>,
+        request: TypedBody<RollingSledRebootRequest>,
+    ) -> Result<HttpResponseCreated<RollingSledReboot>, HttpError>;
+
+    /// List rolling reboots of sleds
+    #[endpoint {
+        method = GET,
+        path = "/sleds/rolling-reboots",
+    }]
+    async fn rolling_sled_reboot_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<HttpResponseOk<ResultsPage<RollingSledReboot>>, HttpError>;
+
+    /// View the progress of a rolling reboot of sleds
+    #[endpoint {
+        method = GET,
+        path = "/sleds/rolling-reboots/{reboot_id}",
+    }]
+    async fn rolling_sled_reboot_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<RollingSledRebootPathParam>,
+    ) -> Result<HttpResponseOk<RollingSledReboot>, HttpError>;
+
+    /// Cancel a rolling reboot of sleds
+    ///
+    /// No further sleds are started on. Sleds that are being drained are
+    /// returned to service, and sleds that have already been reset are seen
+    /// through to rejoining before the reboot finishes.
+    #[endpoint {
+        method = POST,
+        path = "/sleds/rolling-reboots/{reboot_id}/cancel",
+    }]
+    async fn rolling_sled_reboot_cancel(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<RollingSledRebootPathParam>,
+    ) -> Result<HttpResponseOk<RollingSledReboot>, HttpError>;
+
     // Support bundles (experimental)
 
     /// List all support bundles
@@ -722,6 +885,20 @@ pub trait NexusInternalApi {
         policy: TypedBody<ClickhousePolicy>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 
+    /// Restore the single-node clickhouse database from a backup
+    ///
+    /// Backups are taken periodically by the `clickhouse_backup` background
+    /// task, whose status lists the backups currently available. Data in the
+    /// backup is added to whatever the database already contains.
+    #[endpoint {
+        method = POST,
+        path = "/clickhouse/backups/{name}/restore"
+    }]
+    async fn clickhouse_backup_restore(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<ClickhouseBackupPathParam>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
+
     /// Get the current oximeter read policy
     #[endpoint {
             method = GET,
@@ -762,6 +939,65 @@ pub trait NexusInternalApi {
     async fn quiesce_get(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<QuiesceStatus>, HttpError>;
+
+    /// Enable the break-glass operator account
+    ///
+    /// This is intended to be used from the technician port when operators
+    /// cannot otherwise log in.  The account is a local user in the default
+    /// silo with read-only access to the fleet, and it can be used until it
+    /// expires or is disabled.  Enabling the account again replaces its
+    /// password and expiration time.
+    #[endpoint {
+        method = POST,
+        path = "/break-glass/enable"
+    }]
+    async fn break_glass_enable(
+        rqctx: RequestContext<Self::Context>,
+        request: TypedBody<BreakGlassEnableRequest>,
+    ) -> Result<HttpResponseOk<BreakGlassAccount>, HttpError>;
+
+    /// Disable the break-glass operator account
+    ///
+    /// This also ends any sessions the account has started.
+    #[endpoint {
+        method = POST,
+        path = "/break-glass/disable"
+    }]
+    async fn break_glass_disable(
+        rqctx: RequestContext<Self::Context>,
+        request: TypedBody<BreakGlassDisableRequest>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
+
+    /// Report progress of updates driven from the technician port
+    ///
+    /// wicketd calls this periodically while it is updating service
+    /// processors and their components, so that the progress is visible to
+    /// operators through the external API.
+    #[endpoint {
+        method = POST,
+        path = "/technician-port-updates"
+    }]
+    async fn technician_port_update_report(
+        rqctx: RequestContext<Self::Context>,
+        report: TypedBody<TechnicianPortUpdateReport>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
+
+    /// Check volume resource usage records for consistency
+    ///
+    /// Every read-only region and region snapshot referenced by a live volume
+    /// should have exactly one usage record per reference. This reports usage
+    /// records that are missing (a premature free is possible) or stale (the
+    /// resource will leak), as well as references to resources that have
+    /// already been freed. If `repair` is set, missing and stale records are
+    /// corrected.
+    #[endpoint {
+        method = POST,
+        path = "/volumes/reference-check"
+    }]
+    async fn volume_reference_check(
+        rqctx: RequestContext<Self::Context>,
+        params: TypedBody<VolumeReferenceCheckParams>,
+    ) -> Result<HttpResponseOk<VolumeReferenceCheckReport>, HttpError>;
 }
 
 /// Path parameters for Sled Agent requests (internal API)
@@ -770,6 +1006,19 @@ pub struct SledAgentPathParam {
     pub sled_id: SledUuid,
 }
 
+/// Path parameters for Omicron zone requests (internal API)
+#[derive(Deserialize, JsonSchema)]
+pub struct OmicronZonePathParam {
+    pub zone_id: OmicronZoneUuid,
+}
+
+/// Path parameters for ClickHouse backup requests (internal API)
+#[derive(Deserialize, JsonSchema)]
+pub struct ClickhouseBackupPathParam {
+    /// Name of the backup
+    pub name: String,
+}
+
 /// Path parameters for Disk requests (internal API)
 #[derive(Deserialize, JsonSchema)]
 pub struct DiskPathParam {
@@ -886,3 +1135,32 @@ pub struct ProbePathParam {
 pub struct VersionPathParam {
     pub version: u32,
 }
+
+/// Query parameters for the capacity report
+///
+/// These describe the instance shape used to express instance headroom as a
+/// count of instances.
+#[derive(Clone, Copy, Debug, Deserialize, JsonSchema)]
+pub struct CapacityReportQueryParams {
+    /// Number of vCPUs (defaults to 4)
+    pub ncpus: Option<u32>,
+    /// Guest memory, in GiB (defaults to 16)
+    pub memory_gib: Option<u32>,
+}
+
+/// Query parameters for explaining instance placement
+#[derive(Clone, Copy, Debug, Deserialize, JsonSchema)]
+pub struct InstancePlacementExplainQueryParams {
+    /// Number of vCPUs
+    pub ncpus: u32,
+    /// Guest memory, in GiB
+    pub memory_gib: u32,
+    /// Instance whose affinity and anti-affinity groups should be considered
+    pub instance_id: Option<InstanceUuid>,
+}
+
+/// Path parameters for rolling sled reboot requests
+#[derive(Clone, Copy, Debug, Deserialize, JsonSchema)]
+pub struct RollingSledRebootPathParam {
+    pub reboot_id: Uuid,
+}
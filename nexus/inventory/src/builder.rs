@@ -322,6 +322,94 @@ impl CollectionBuilder {
         Some(baseboard)
     }
 
+    /// Returns true if we already found service processor state for baseboard
+    /// `baseboard`
+    pub fn found_sp_state_already(&self, baseboard: &BaseboardId) -> bool {
+        self.sps.contains_key(baseboard)
+    }
+
+    /// Returns true if we already found state for the service processor of
+    /// type `sp_type` in slot `sp_slot` (whatever its baseboard)
+    pub fn found_sp_state_in_slot_already(
+        &self,
+        sp_type: SpType,
+        sp_slot: u16,
+    ) -> bool {
+        self.sps
+            .values()
+            .any(|sp| sp.sp_type == sp_type && sp.sp_slot == sp_slot)
+    }
+
+    /// Record everything that `previous` (an earlier collection) found via the
+    /// service processor for baseboard `baseboard`
+    ///
+    /// This is used when the SP can't be reached during this collection.  The
+    /// information keeps the collection times from `previous`, which is how
+    /// consumers can tell that it's stale.  (See
+    /// [`Collection::sp_stale_since()`].)
+    pub fn found_stale_sp(
+        &mut self,
+        previous: &Collection,
+        baseboard: &BaseboardId,
+    ) -> Result<(), CollectorBug> {
+        let Some(sp) = previous.sps.get(baseboard) else {
+            return Err(CollectorBug::from(anyhow!(
+                "reporting stale SP for baseboard not found in previous \
+                 collection: {baseboard:?}"
+            )));
+        };
+        if self.sps.contains_key(baseboard) {
+            return Err(CollectorBug::from(anyhow!(
+                "reporting stale SP for baseboard already found: {baseboard:?}"
+            )));
+        }
+
+        let baseboard =
+            Self::normalize_item(&mut self.baseboards, baseboard.clone());
+        self.sps.insert(baseboard.clone(), sp.clone());
+        if let Some(rot) = previous.rots.get(&baseboard) {
+            self.rots.insert(baseboard.clone(), rot.clone());
+        }
+        if let Some(slot) = previous.host_phase_1_active_slots.get(&baseboard) {
+            self.host_phase_1_active_slots
+                .insert(baseboard.clone(), slot.clone());
+        }
+        for (slot, by_id) in &previous.host_phase_1_flash_hashes {
+            if let Some(hash) = by_id.get(&baseboard) {
+                self.host_phase_1_flash_hashes
+                    .entry(*slot)
+                    .or_default()
+                    .insert(baseboard.clone(), hash.clone());
+            }
+        }
+        for (which, by_id) in &previous.cabooses_found {
+            if let Some(found) = by_id.get(&baseboard) {
+                let caboose = Self::normalize_item(
+                    &mut self.cabooses,
+                    (*found.caboose).clone(),
+                );
+                self.cabooses_found.entry(*which).or_default().insert(
+                    baseboard.clone(),
+                    CabooseFound { caboose, ..found.clone() },
+                );
+            }
+        }
+        for (which, by_id) in &previous.rot_pages_found {
+            if let Some(found) = by_id.get(&baseboard) {
+                let page = Self::normalize_item(
+                    &mut self.rot_pages,
+                    (*found.page).clone(),
+                );
+                self.rot_pages_found.entry(*which).or_default().insert(
+                    baseboard.clone(),
+                    RotPageFound { page, ..found.clone() },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns true if we already found the active host phase 1 flash slot for
     /// baseboard `baseboard`
     ///
@@ -5,15 +5,19 @@
 //! Collection of inventory from Omicron components
 
 use crate::SledAgentEnumerator;
+use crate::SledAgentInventoryCache;
 use crate::builder::CollectionBuilder;
 use crate::builder::InventoryError;
 use anyhow::Context;
 use anyhow::anyhow;
+use chrono::Utc;
 use gateway_client::types::GetCfpaParams;
 use gateway_client::types::RotCfpaSlot;
 use gateway_client::types::SpType;
 use gateway_messages::SpComponent;
 use itertools::Itertools;
+use nexus_sled_agent_shared::inventory::Inventory;
+use nexus_sled_agent_shared::inventory::InventoryKnownDigests;
 use nexus_sled_agent_shared::inventory::OmicronZoneType;
 use nexus_sled_agent_shared::inventory::ZoneKind;
 use nexus_types::inventory::CabooseWhich;
@@ -27,7 +31,8 @@ use omicron_common::disk::M2Slot;
 use omicron_uuid_kinds::OmicronZoneUuid;
 use slog::Logger;
 use slog::o;
-use slog::{debug, error};
+use slog::{debug, error, warn};
+use std::collections::BTreeSet;
 use std::net::SocketAddrV6;
 use std::time::Duration;
 use strum::IntoEnumIterator;
@@ -36,6 +41,10 @@ use tufaceous_artifact::ArtifactHash;
 /// connection and request timeout used for Sled Agent HTTP client
 const SLED_AGENT_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// how long we'll keep reusing information about a service processor that
+/// can't be reached, after which we'd rather report it missing
+const MAX_STALE_SP_AGE: Duration = Duration::from_secs(60 * 60);
+
 /// Collect all inventory data from an Oxide system
 pub struct Collector<'a> {
     log: slog::Logger,
@@ -43,6 +52,8 @@ pub struct Collector<'a> {
     keeper_admin_clients: Vec<clickhouse_admin_keeper_client::Client>,
     cockroach_admin_client: &'a CockroachClusterAdminClient,
     sled_agent_lister: &'a (dyn SledAgentEnumerator + Send + Sync),
+    sled_agent_cache: Option<&'a mut SledAgentInventoryCache>,
+    previous_collection: Option<&'a Collection>,
     in_progress: CollectionBuilder,
 }
 
@@ -61,10 +72,35 @@ impl<'a> Collector<'a> {
             keeper_admin_clients,
             cockroach_admin_client,
             sled_agent_lister,
+            sled_agent_cache: None,
+            previous_collection: None,
             in_progress: CollectionBuilder::new(creator),
         }
     }
 
+    /// Use `cache` to fetch only the sections of each sled agent's inventory
+    /// that have changed since the last collection that used it
+    pub fn with_sled_agent_cache(
+        mut self,
+        cache: &'a mut SledAgentInventoryCache,
+    ) -> Self {
+        self.sled_agent_cache = Some(cache);
+        self
+    }
+
+    /// Use `previous`, an earlier collection, to fill in information about
+    /// service processors that can't be reached during this one
+    ///
+    /// Without this, a temporarily unreachable MGS or SP leaves a hole in the
+    /// collection.
+    pub fn with_previous_collection(
+        mut self,
+        previous: &'a Collection,
+    ) -> Self {
+        self.previous_collection = Some(previous);
+        self
+    }
+
     /// Begin the process of collecting a complete hardware/software inventory
     /// of the rack
     ///
@@ -99,17 +135,36 @@ impl<'a> Collector<'a> {
 
     /// Collect inventory from all MGS instances
     async fn collect_all_mgs(&mut self) {
+        let mut mgs_reachable = false;
+        let mut sps_powered_on = BTreeSet::new();
         for client in &self.mgs_clients {
-            Self::collect_one_mgs(client, &self.log, &mut self.in_progress)
-                .await;
+            if let Some(sps) =
+                Self::collect_one_mgs(client, &self.log, &mut self.in_progress)
+                    .await
+            {
+                mgs_reachable = true;
+                sps_powered_on.extend(sps);
+            }
+        }
+
+        if let Some(previous) = self.previous_collection {
+            self.reuse_unreachable_sps(
+                previous,
+                mgs_reachable,
+                &sps_powered_on,
+            );
         }
     }
 
+    /// Collect inventory from one MGS instance
+    ///
+    /// Returns the SPs that ignition reported as powered on, or `None` if we
+    /// couldn't get that far.
     async fn collect_one_mgs(
         client: &gateway_client::Client,
         log: &Logger,
         in_progress: &mut CollectionBuilder,
-    ) {
+    ) -> Option<BTreeSet<(SpType, u16)>> {
         debug!(log, "begin collection from MGS";
             "mgs_url" => client.baseurl()
         );
@@ -130,28 +185,29 @@ impl<'a> Collector<'a> {
         // its attempt to reach it (currently several seconds).  This choice
         // enables inventory to complete much faster, at the expense of not
         // being able to identify this particular condition.
-        let sps = match ignition_result {
+        let sps: Vec<_> = match ignition_result {
             Err(error) => {
                 in_progress.found_error(InventoryError::from(error));
-                return;
+                return None;
             }
 
-            Ok(targets) => {
-                targets.into_inner().into_iter().filter_map(|sp_ignition| {
-                    match sp_ignition.details {
-                        gateway_client::types::SpIgnition::No => None,
-                        gateway_client::types::SpIgnition::Yes {
-                            power: false,
-                            ..
-                        } => None,
-                        gateway_client::types::SpIgnition::Yes {
-                            power: true,
-                            ..
-                        } => Some(sp_ignition.id),
-                    }
+            Ok(targets) => targets
+                .into_inner()
+                .into_iter()
+                .filter_map(|sp_ignition| match sp_ignition.details {
+                    gateway_client::types::SpIgnition::No => None,
+                    gateway_client::types::SpIgnition::Yes {
+                        power: false,
+                        ..
+                    } => None,
+                    gateway_client::types::SpIgnition::Yes {
+                        power: true,
+                        ..
+                    } => Some(sp_ignition.id),
                 })
-            }
+                .collect(),
         };
+        let sps_powered_on = sps.iter().map(|sp| (sp.type_, sp.slot)).collect();
 
         // For each SP that ignition reports up, fetch the state and caboose
         // information.
@@ -431,6 +487,70 @@ impl<'a> Collector<'a> {
                 }
             }
         }
+
+        Some(sps_powered_on)
+    }
+
+    /// Fill in information about service processors that `previous` found,
+    /// but that we couldn't reach during this collection
+    ///
+    /// If no MGS could be reached at all, this covers every SP in `previous`.
+    /// Otherwise, it covers only those SPs that ignition reported powered on
+    /// but whose state we couldn't fetch.  An SP that ignition reports absent
+    /// or powered off isn't just unreachable, so we don't paper over that.
+    /// Either way, we stop reusing information once it's older than
+    /// `MAX_STALE_SP_AGE`.
+    fn reuse_unreachable_sps(
+        &mut self,
+        previous: &Collection,
+        mgs_reachable: bool,
+        sps_powered_on: &BTreeSet<(SpType, u16)>,
+    ) {
+        let now = Utc::now();
+        for (baseboard_id, sp) in &previous.sps {
+            if self.in_progress.found_sp_state_already(baseboard_id)
+                || self
+                    .in_progress
+                    .found_sp_state_in_slot_already(sp.sp_type, sp.sp_slot)
+            {
+                continue;
+            }
+            if mgs_reachable
+                && !sps_powered_on.contains(&(sp.sp_type, sp.sp_slot))
+            {
+                continue;
+            }
+            let too_old = (now - sp.time_collected)
+                .to_std()
+                .is_ok_and(|age| age > MAX_STALE_SP_AGE);
+            if too_old {
+                debug!(
+                    &self.log,
+                    "not reusing information about unreachable SP \
+                     (too old)";
+                    "baseboard_id" => ?baseboard_id,
+                    "time_collected" => %sp.time_collected,
+                );
+                continue;
+            }
+
+            warn!(
+                &self.log,
+                "SP unreachable; reusing information from an earlier \
+                 collection";
+                "baseboard_id" => ?baseboard_id,
+                "time_collected" => %sp.time_collected,
+                "previous_collection_id" => %previous.id,
+            );
+            if let Err(error) =
+                self.in_progress.found_stale_sp(previous, baseboard_id)
+            {
+                error!(
+                    &self.log,
+                    "error reporting stale SP: {:?}: {:#}", baseboard_id, error
+                );
+            }
+        }
     }
 
     /// Collect inventory from all sled agent instances
@@ -443,6 +563,10 @@ impl<'a> Collector<'a> {
             Ok(clients) => clients,
         };
 
+        if let Some(cache) = self.sled_agent_cache.as_deref_mut() {
+            cache.retain_sled_agents(&urls);
+        }
+
         for url in urls {
             let log = self.log.new(o!("SledAgent" => url.clone()));
             let reqwest_client = reqwest::ClientBuilder::new()
@@ -476,11 +600,15 @@ impl<'a> Collector<'a> {
             "sled_agent_url" => client.baseurl()
         );
 
-        let maybe_ident = client.inventory().await.with_context(|| {
-            format!("Sled Agent {:?}: inventory", &sled_agent_url)
-        });
+        let maybe_ident = match self.sled_agent_cache.as_deref_mut() {
+            Some(cache) => {
+                Self::sled_agent_inventory_changes(&self.log, client, cache)
+                    .await
+            }
+            None => Self::sled_agent_inventory(client).await,
+        };
         let inventory = match maybe_ident {
-            Ok(inventory) => inventory.into_inner(),
+            Ok(inventory) => inventory,
             Err(error) => {
                 self.in_progress.found_error(InventoryError::from(error));
                 return Ok(());
@@ -490,6 +618,88 @@ impl<'a> Collector<'a> {
         self.in_progress.found_sled_inventory(&sled_agent_url, inventory)
     }
 
+    async fn sled_agent_inventory(
+        client: &sled_agent_client::Client,
+    ) -> Result<Inventory, anyhow::Error> {
+        let inventory = client.inventory().await.with_context(|| {
+            format!("Sled Agent {:?}: inventory", client.baseurl())
+        })?;
+        Ok(inventory.into_inner())
+    }
+
+    /// Fetches a sled agent's inventory, asking it only for the sections that
+    /// have changed since it was put into `cache`
+    async fn sled_agent_inventory_changes(
+        log: &Logger,
+        client: &sled_agent_client::Client,
+        cache: &mut SledAgentInventoryCache,
+    ) -> Result<Inventory, anyhow::Error> {
+        let sled_agent_url = client.baseurl();
+
+        // If anything goes wrong, we'll fetch everything from this sled agent
+        // again next time.
+        let previous = cache.remove(sled_agent_url);
+        let known = previous
+            .as_ref()
+            .map(|previous| {
+                InventoryKnownDigests::from(previous.digests.clone())
+            })
+            .unwrap_or_default();
+
+        let result = client
+            .inventory_changes(
+                known.datasets.as_ref(),
+                known.disks.as_ref(),
+                known.sled_config.as_ref(),
+                known.zone_image_resolver.as_ref(),
+                known.zpools.as_ref(),
+            )
+            .await;
+        let changes = match result {
+            Ok(changes) => changes.into_inner(),
+            // Sled agents from before the inventory changes endpoint was added
+            // reject requests for it.  Fetch their whole inventory instead.
+            Err(sled_agent_client::Error::ErrorResponse(response))
+                if response.status().is_client_error() =>
+            {
+                debug!(
+                    log,
+                    "sled agent rejected request for inventory changes; \
+                     fetching whole inventory";
+                    "sled_agent_url" => sled_agent_url,
+                    "status" => %response.status(),
+                );
+                return Self::sled_agent_inventory(client).await;
+            }
+            Err(error) => {
+                return Err(anyhow!(error)).with_context(|| {
+                    format!(
+                        "Sled Agent {:?}: inventory changes",
+                        sled_agent_url
+                    )
+                });
+            }
+        };
+
+        if let Some(previous) = &previous {
+            debug!(
+                log,
+                "fetched sled agent inventory changes";
+                "sled_agent_url" => sled_agent_url,
+                "changed_sections" =>
+                    ?previous.digests.changed_sections(&changes.digests),
+            );
+        }
+        let digests = changes.digests.clone();
+        let inventory = changes
+            .into_inventory(previous.map(|previous| previous.inventory))
+            .with_context(|| {
+                format!("Sled Agent {:?}: inventory changes", sled_agent_url)
+            })?;
+        cache.insert(sled_agent_url.clone(), digests, inventory.clone());
+        Ok(inventory)
+    }
+
     /// Collect timesync status from all sleds
     async fn collect_all_timesync(&mut self) {
         let ntp_admin_clients: Vec<_> = self
@@ -555,10 +765,17 @@ impl<'a> Collector<'a> {
             format!("Sled Agent {:?}: timesync", &sled_agent_url)
         });
         let timesync = match maybe_ident {
-            Ok(timesync) => nexus_types::inventory::TimeSync {
-                zone_id,
-                synced: timesync.into_inner().sync,
-            },
+            Ok(timesync) => {
+                let timesync = timesync.into_inner();
+                nexus_types::inventory::TimeSync {
+                    zone_id,
+                    synced: timesync.sync,
+                    // chrony reports the correction in seconds.
+                    correction_usec: Some(
+                        (timesync.correction * 1_000_000.0).round() as i64,
+                    ),
+                }
+            }
             Err(error) => {
                 in_progress.found_error(InventoryError::from(error));
                 return Ok(());
@@ -717,6 +934,7 @@ impl<'a> Collector<'a> {
 #[cfg(test)]
 mod test {
     use super::Collector;
+    use crate::SledAgentInventoryCache;
     use crate::StaticSledAgentEnumerator;
     use gateway_messages::SpPort;
     use id_map::IdMap;
@@ -987,23 +1205,26 @@ mod test {
         let filesystem_pool = ZpoolName::new_external(ZpoolUuid::new_v4());
         let zone_address = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 123, 0, 0);
         client
-            .omicron_config_put(&OmicronSledConfig {
-                generation: Generation::from(3),
-                disks: IdMap::default(),
-                datasets: IdMap::default(),
-                zones: [OmicronZoneConfig {
-                    id: zone_id,
-                    zone_type: OmicronZoneType::Oximeter {
-                        address: zone_address,
-                    },
-                    filesystem_pool: Some(filesystem_pool),
-                    image_source: OmicronZoneImageSource::InstallDataset,
-                }]
-                .into_iter()
-                .collect(),
-                remove_mupdate_override: None,
-                host_phase_2: HostPhase2DesiredSlots::current_contents(),
-            })
+            .omicron_config_put(
+                None,
+                &OmicronSledConfig {
+                    generation: Generation::from(3),
+                    disks: IdMap::default(),
+                    datasets: IdMap::default(),
+                    zones: [OmicronZoneConfig {
+                        id: zone_id,
+                        zone_type: OmicronZoneType::Oximeter {
+                            address: zone_address,
+                        },
+                        filesystem_pool: Some(filesystem_pool),
+                        image_source: OmicronZoneImageSource::InstallDataset,
+                    }]
+                    .into_iter()
+                    .collect(),
+                    remove_mupdate_override: None,
+                    host_phase_2: HostPhase2DesiredSlots::current_contents(),
+                },
+            )
             .await
             .expect("failed to write initial zone version to fake sled agent");
 
@@ -1267,6 +1488,87 @@ mod test {
         gwtestctx.teardown().await;
     }
 
+    #[tokio::test]
+    async fn test_mgs_unreachable() {
+        // Collect inventory from a working MGS, then again with that MGS
+        // unreachable.  The second collection should reuse what the first
+        // found about each SP, and say that it's stale.
+        let gwtestctx = gateway_test_utils::setup::test_setup(
+            "test_mgs_unreachable",
+            SpPort::One,
+        )
+        .await;
+        let log = &gwtestctx.logctx.log;
+        let sled_enum = StaticSledAgentEnumerator::empty();
+        let timeout = Duration::from_secs(15);
+        let crdb_cluster =
+            CockroachClusterAdminClient::new(log.clone(), timeout);
+        let crdb_admin_server = mock_crdb_admin_server();
+        crdb_cluster.update_backends(&[*crdb_admin_server.address()]).await;
+
+        let first = Collector::new(
+            "test-suite",
+            vec![gwtestctx.client.clone()],
+            Vec::new(),
+            &crdb_cluster,
+            &sled_enum,
+            log.clone(),
+        )
+        .collect_all()
+        .await
+        .expect("failed to carry out collection");
+        assert!(!first.sps.is_empty());
+        assert!(first.sps.keys().all(|bb| first.sp_stale_since(bb).is_none()));
+
+        // This IP range is guaranteed by RFC 6666 to discard traffic.
+        let bad_client =
+            gateway_client::Client::new("http://[100::1]:12345", log.clone());
+        let second = Collector::new(
+            "test-suite",
+            vec![bad_client],
+            Vec::new(),
+            &crdb_cluster,
+            &sled_enum,
+            log.clone(),
+        )
+        .with_previous_collection(&first)
+        .collect_all()
+        .await
+        .expect("failed to carry out collection");
+        assert!(!second.errors.is_empty());
+        assert_eq!(first.sps, second.sps);
+        assert_eq!(first.rots, second.rots);
+        assert_eq!(first.cabooses_found, second.cabooses_found);
+        assert_eq!(first.rot_pages_found, second.rot_pages_found);
+        for (baseboard_id, sp) in &second.sps {
+            assert_eq!(
+                second.sp_stale_since(baseboard_id),
+                Some(sp.time_collected)
+            );
+        }
+
+        // Once MGS is reachable again, nothing is stale.
+        let third = Collector::new(
+            "test-suite",
+            vec![gwtestctx.client.clone()],
+            Vec::new(),
+            &crdb_cluster,
+            &sled_enum,
+            log.clone(),
+        )
+        .with_previous_collection(&second)
+        .collect_all()
+        .await
+        .expect("failed to carry out collection");
+        assert_eq!(
+            first.sps.keys().collect::<Vec<_>>(),
+            third.sps.keys().collect::<Vec<_>>()
+        );
+        assert!(third.sps.keys().all(|bb| third.sp_stale_since(bb).is_none()));
+
+        gwtestctx.teardown().await;
+    }
+
     #[tokio::test]
     async fn test_sled_agent_failure() {
         // Similar to the basic test, but use multiple sled agents, one of which
@@ -1328,4 +1630,118 @@ mod test {
         sled1.http_server.close().await.unwrap();
         gwtestctx.teardown().await;
     }
+
+    #[tokio::test]
+    async fn test_sled_agent_cache() {
+        // This is the same as the basic test, but we collect several times
+        // using the same sled agent cache.  Collections that reuse parts of
+        // sled agents' cached inventories should look the same as ones that
+        // don't.
+        let gwtestctx = gateway_test_utils::setup::test_setup(
+            "test_sled_agent_cache",
+            SpPort::One,
+        )
+        .await;
+        let log = &gwtestctx.logctx.log;
+
+        let simulated_upstairs =
+            Arc::new(sim::SimulatedUpstairs::new(log.new(o!(
+                "component" => "omicron_sled_agent::sim::SimulatedUpstairs",
+            ))));
+
+        let sled1_id = "9cb9b78f-5614-440c-b66d-e8e81fab69b0".parse().unwrap();
+        let sled1 = sim_sled_agent(
+            log.clone(),
+            sled1_id,
+            "5125277f-0988-490b-ac01-3bba20cc8f07".parse().unwrap(),
+            &simulated_upstairs,
+        )
+        .await;
+
+        let sled2 = sim_sled_agent(
+            log.clone(),
+            "03265caf-da7d-46c7-b1c2-39fa90ce5c65".parse().unwrap(),
+            "8b88a56f-3eb6-4d80-ba42-75d867bc427d".parse().unwrap(),
+            &simulated_upstairs,
+        )
+        .await;
+
+        let sled1_url = format!("http://{}/", sled1.http_server.local_addr());
+        let sled2_url = format!("http://{}/", sled2.http_server.local_addr());
+        let sled_enum =
+            StaticSledAgentEnumerator::new([sled1_url.clone(), sled2_url]);
+        let timeout = Duration::from_secs(15);
+        let crdb_cluster =
+            CockroachClusterAdminClient::new(log.clone(), timeout);
+        let crdb_admin_server = mock_crdb_admin_server();
+        crdb_cluster.update_backends(&[*crdb_admin_server.address()]).await;
+
+        let mut cache = SledAgentInventoryCache::new();
+        for _ in 0..2 {
+            let collection = Collector::new(
+                "test-suite",
+                vec![gwtestctx.client.clone()],
+                Vec::new(),
+                &crdb_cluster,
+                &sled_enum,
+                log.clone(),
+            )
+            .with_sled_agent_cache(&mut cache)
+            .collect_all()
+            .await
+            .expect("failed to carry out collection");
+            assert!(
+                collection.errors.is_empty(),
+                "Collection errors: {:#?}",
+                collection.errors
+            );
+
+            let s = dump_collection(&collection);
+            expectorate::assert_contents(
+                "tests/output/collector_basic.txt",
+                &s,
+            );
+            assert_eq!(cache.len(), 2);
+        }
+
+        // Change one sled's config.  The next collection should pick that up.
+        let client = sled_agent_client::Client::new(&sled1_url, log.clone());
+        let mut config = client
+            .inventory()
+            .await
+            .expect("fetched inventory from sled agent")
+            .into_inner()
+            .ledgered_sled_config
+            .expect("sled agent has a ledgered config");
+        config.generation = config.generation.next();
+        client
+            .omicron_config_put(None, &config)
+            .await
+            .expect("updated sled agent config");
+
+        let collection = Collector::new(
+            "test-suite",
+            vec![gwtestctx.client.clone()],
+            Vec::new(),
+            &crdb_cluster,
+            &sled_enum,
+            log.clone(),
+        )
+        .with_sled_agent_cache(&mut cache)
+        .collect_all()
+        .await
+        .expect("failed to carry out collection");
+        let sled1_config = collection
+            .sled_agents
+            .iter()
+            .find(|sled| sled.sled_id == sled1_id)
+            .expect("collection includes sled 1")
+            .ledgered_sled_config
+            .as_ref()
+            .expect("sled 1 has a ledgered config");
+        assert_eq!(sled1_config.generation, config.generation);
+
+        sled1.http_server.close().await.unwrap();
+        gwtestctx.teardown().await;
+    }
 }
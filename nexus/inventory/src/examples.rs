@@ -26,6 +26,12 @@ use nexus_sled_agent_shared::inventory::Inventory;
 use nexus_sled_agent_shared::inventory::InventoryDataset;
 use nexus_sled_agent_shared::inventory::InventoryDisk;
 use nexus_sled_agent_shared::inventory::InventoryZpool;
+use nexus_sled_agent_shared::inventory::InventoryZpoolHealth;
+use nexus_sled_agent_shared::inventory::InventoryZpoolHealthState;
+use nexus_sled_agent_shared::inventory::InventoryZpoolResilver;
+use nexus_sled_agent_shared::inventory::InventoryZpoolResilverState;
+use nexus_sled_agent_shared::inventory::InventoryZpoolScrub;
+use nexus_sled_agent_shared::inventory::InventoryZpoolScrubState;
 use nexus_sled_agent_shared::inventory::OmicronSledConfig;
 use nexus_sled_agent_shared::inventory::OmicronZonesConfig;
 use nexus_sled_agent_shared::inventory::OrphanedDataset;
@@ -499,16 +505,50 @@ pub fn representative() -> Representative {
         },
     ];
     let mut zpools = Vec::new();
-    for disk in &disks {
+    for (i, disk) in disks.iter().enumerate() {
         let pool_id = zpool_id_iter.next().unwrap();
         sled14.disks.insert(OmicronPhysicalDiskConfig {
             identity: disk.identity.clone(),
             id: disk_id_iter.next().unwrap(),
             pool_id,
         });
+        // Exercise both reported and unreported scrubs, usage, and health.
+        let scrub = (i == 0).then(|| InventoryZpoolScrub {
+            state: InventoryZpoolScrubState::InProgress,
+            start_time: Some(now_db_precision()),
+            end_time: None,
+            percent_done: Some(42),
+            errors: None,
+        });
         zpools.push(InventoryZpool {
             id: pool_id,
             total_size: ByteCount::from(4096),
+            allocated: (i == 0).then(|| ByteCount::from(1024)),
+            fragmentation_percent: (i == 0).then_some(7),
+            scrub,
+            health: match i {
+                0 => Some(InventoryZpoolHealth {
+                    state: InventoryZpoolHealthState::Degraded,
+                    read_errors: 2,
+                    write_errors: 0,
+                    checksum_errors: 17,
+                    resilver: Some(InventoryZpoolResilver {
+                        state: InventoryZpoolResilverState::InProgress,
+                        start_time: Some(now_db_precision()),
+                        end_time: None,
+                        percent_done: Some(12),
+                        errors: None,
+                    }),
+                }),
+                1 => Some(InventoryZpoolHealth {
+                    state: InventoryZpoolHealthState::Online,
+                    read_errors: 0,
+                    write_errors: 0,
+                    checksum_errors: 0,
+                    resilver: None,
+                }),
+                _ => None,
+            },
         });
     }
     let dataset_name = DatasetName::new(
@@ -666,6 +706,7 @@ pub fn representative() -> Representative {
         .found_ntp_timesync(nexus_types::inventory::TimeSync {
             zone_id: omicron_uuid_kinds::OmicronZoneUuid::new_v4(),
             synced: true,
+            correction_usec: Some(-12),
         })
         .unwrap();
 
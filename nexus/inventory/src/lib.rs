@@ -20,6 +20,7 @@
 mod builder;
 mod collector;
 pub mod examples;
+mod sled_agent_cache;
 mod sled_agent_enumerator;
 
 // only exposed for test code to construct collections
@@ -32,5 +33,7 @@ pub use builder::now_db_precision;
 
 pub use collector::Collector;
 
+pub use sled_agent_cache::SledAgentInventoryCache;
+
 pub use sled_agent_enumerator::SledAgentEnumerator;
 pub use sled_agent_enumerator::StaticSledAgentEnumerator;
@@ -0,0 +1,59 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use nexus_sled_agent_shared::inventory::Inventory;
+use nexus_sled_agent_shared::inventory::InventoryDigests;
+use std::collections::BTreeMap;
+
+/// Sled agent inventories fetched by previous collections
+///
+/// A `Collector` given one of these asks each sled agent only for the
+/// sections of its inventory that have changed since the last collection that
+/// used the same cache, and fills in the rest from here.  Whoever runs
+/// collections periodically should keep one of these around between them.
+#[derive(Debug, Default)]
+pub struct SledAgentInventoryCache {
+    sleds: BTreeMap<String, CachedSledInventory>,
+}
+
+/// A sled agent's inventory, plus the section digests that the sled agent
+/// reported for it
+#[derive(Debug)]
+pub(crate) struct CachedSledInventory {
+    pub(crate) digests: InventoryDigests,
+    pub(crate) inventory: Inventory,
+}
+
+impl SledAgentInventoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of sled agents with a cached inventory
+    pub fn len(&self) -> usize {
+        self.sleds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sleds.is_empty()
+    }
+
+    /// Forgets sled agents other than those at `urls`
+    pub(crate) fn retain_sled_agents(&mut self, urls: &[String]) {
+        self.sleds.retain(|url, _| urls.contains(url));
+    }
+
+    pub(crate) fn remove(&mut self, url: &str) -> Option<CachedSledInventory> {
+        self.sleds.remove(url)
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        url: String,
+        digests: InventoryDigests,
+        inventory: Inventory,
+    ) {
+        self.sleds.insert(url, CachedSledInventory { digests, inventory });
+    }
+}
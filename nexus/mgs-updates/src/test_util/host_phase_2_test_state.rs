@@ -206,12 +206,17 @@ mod api_impl {
     use nexus_sled_agent_shared::inventory::HostPhase2DesiredContents;
     use nexus_sled_agent_shared::inventory::HostPhase2DesiredSlots;
     use nexus_sled_agent_shared::inventory::Inventory;
+    use nexus_sled_agent_shared::inventory::InventoryChanges;
+    use nexus_sled_agent_shared::inventory::InventoryKnownDigests;
     use nexus_sled_agent_shared::inventory::MupdateOverrideInventory;
     use nexus_sled_agent_shared::inventory::OmicronSledConfig;
     use nexus_sled_agent_shared::inventory::SledCpuFamily;
     use nexus_sled_agent_shared::inventory::SledRole;
     use nexus_sled_agent_shared::inventory::ZoneImageResolverInventory;
     use nexus_sled_agent_shared::inventory::ZoneManifestInventory;
+    use nexus_sled_agent_shared::inventory::v1;
+    use nexus_sled_agent_shared::inventory::v4;
+    use nexus_sled_agent_shared::inventory::v6;
     use omicron_common::api::external::Generation;
     use omicron_common::api::internal::nexus::DiskRuntimeState;
     use omicron_common::api::internal::nexus::SledVmmState;
@@ -246,6 +251,27 @@ mod api_impl {
     impl sled_agent_api::SledAgentApi for HostPhase2SledAgentImpl {
         type Context = HostPhase2SledAgentContext;
 
+        async fn inventory_v1(
+            rqctx: RequestContext<Self::Context>,
+        ) -> Result<HttpResponseOk<v1::Inventory>, HttpError> {
+            let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+            Ok(HttpResponseOk(inventory.into()))
+        }
+
+        async fn inventory_v4(
+            rqctx: RequestContext<Self::Context>,
+        ) -> Result<HttpResponseOk<v4::Inventory>, HttpError> {
+            let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+            Ok(HttpResponseOk(inventory.into()))
+        }
+
+        async fn inventory_v6(
+            rqctx: RequestContext<Self::Context>,
+        ) -> Result<HttpResponseOk<v6::Inventory>, HttpError> {
+            let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+            Ok(HttpResponseOk(inventory.into()))
+        }
+
         async fn inventory(
             rqctx: RequestContext<Self::Context>,
         ) -> Result<HttpResponseOk<Inventory>, HttpError> {
@@ -359,6 +385,16 @@ mod api_impl {
             }))
         }
 
+        async fn inventory_changes(
+            rqctx: RequestContext<Self::Context>,
+            query_params: Query<InventoryKnownDigests>,
+        ) -> Result<HttpResponseOk<InventoryChanges>, HttpError> {
+            let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+            Ok(HttpResponseOk(
+                inventory.changes_since(&query_params.into_inner()),
+            ))
+        }
+
         async fn zone_bundle_list_all(
             _rqctx: RequestContext<Self::Context>,
             _query: Query<ZoneBundleFilter>,
@@ -517,8 +553,16 @@ mod api_impl {
             unimplemented!()
         }
 
+        async fn omicron_config_put_v1(
+            _rqctx: RequestContext<Self::Context>,
+            _body: TypedBody<OmicronSledConfig>,
+        ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+            unimplemented!()
+        }
+
         async fn omicron_config_put(
             _rqctx: RequestContext<Self::Context>,
+            _headers: Header<IdempotencyKeyHeaders>,
             _body: TypedBody<OmicronSledConfig>,
         ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
             unimplemented!()
@@ -530,8 +574,17 @@ mod api_impl {
             unimplemented!()
         }
 
+        async fn vmm_register_v1(
+            _rqctx: RequestContext<Self::Context>,
+            _path_params: Path<VmmPathParam>,
+            _body: TypedBody<InstanceEnsureBody>,
+        ) -> Result<HttpResponseOk<SledVmmState>, HttpError> {
+            unimplemented!()
+        }
+
         async fn vmm_register(
             _rqctx: RequestContext<Self::Context>,
+            _headers: Header<IdempotencyKeyHeaders>,
             _path_params: Path<VmmPathParam>,
             _body: TypedBody<InstanceEnsureBody>,
         ) -> Result<HttpResponseOk<SledVmmState>, HttpError> {
@@ -787,6 +840,13 @@ mod api_impl {
             unimplemented!()
         }
 
+        async fn support_kstat_info(
+            _request_context: RequestContext<Self::Context>,
+        ) -> Result<HttpResponseOk<Vec<SledDiagnosticsQueryOutput>>, HttpError>
+        {
+            unimplemented!()
+        }
+
         async fn support_health_check(
             _request_context: RequestContext<Self::Context>,
         ) -> Result<HttpResponseOk<Vec<SledDiagnosticsQueryOutput>>, HttpError>
@@ -10,8 +10,10 @@ use nexus_types::deployment::Blueprint;
 use nexus_types::deployment::BlueprintArtifactVersion;
 use nexus_types::deployment::BlueprintDatasetConfig;
 use nexus_types::deployment::BlueprintZoneConfig;
+use nexus_types::deployment::PlanningInput;
 use nexus_types::inventory::ZpoolName;
 use omicron_common::address::DnsSubnet;
+use omicron_common::address::Ipv6Range;
 use omicron_common::address::Ipv6Subnet;
 use omicron_common::address::SLED_PREFIX;
 use omicron_common::api::external::MacAddr;
@@ -99,6 +101,9 @@ pub enum SledKind {
         zone1: BlueprintZoneConfig,
         zone2: BlueprintZoneConfig,
     },
+    /// A running zone has an underlay IP inside a range the planning input
+    /// reserves.
+    UnderlayIpInReservedRange { zone: BlueprintZoneConfig, range: Ipv6Range },
     /// A sled has two zones that are not members of the same sled subnet.
     SledWithMixedUnderlaySubnets {
         zone1: BlueprintZoneConfig,
@@ -209,6 +214,18 @@ impl fmt::Display for SledKind {
                     zone2.id,
                 )
             }
+            SledKind::UnderlayIpInReservedRange { zone, range } => {
+                write!(
+                    f,
+                    "{:?} zone {} underlay IP {} is within reserved range \
+                     {} - {}",
+                    zone.zone_type.kind(),
+                    zone.id,
+                    zone.underlay_ip(),
+                    range.first,
+                    range.last,
+                )
+            }
             SledKind::SledWithMixedUnderlaySubnets { zone1, zone2 } => {
                 write!(
                     f,
@@ -469,6 +486,18 @@ impl<'a> Blippy<'a> {
         slf
     }
 
+    /// Like [`Blippy::new()`], but additionally checks that the blueprint
+    /// respects constraints expressed by the planning input it was (or will
+    /// be) generated from, such as reserved underlay IP ranges.
+    pub fn new_with_planning_input(
+        blueprint: &'a Blueprint,
+        input: &PlanningInput,
+    ) -> Self {
+        let mut slf = Self::new(blueprint);
+        checks::perform_planning_input_checks(&mut slf, input);
+        slf
+    }
+
     pub fn blueprint(&self) -> &'a Blueprint {
         self.blueprint
     }
@@ -16,6 +16,7 @@ use nexus_types::deployment::BlueprintZoneDisposition;
 use nexus_types::deployment::BlueprintZoneImageSource;
 use nexus_types::deployment::BlueprintZoneType;
 use nexus_types::deployment::OmicronZoneExternalIp;
+use nexus_types::deployment::PlanningInput;
 use nexus_types::deployment::SledFilter;
 use nexus_types::deployment::blueprint_zone_type;
 use omicron_common::address::DnsSubnet;
@@ -39,6 +40,40 @@ pub(crate) fn perform_all_blueprint_only_checks(blippy: &mut Blippy<'_>) {
     check_mupdate_override(blippy);
 }
 
+pub(crate) fn perform_planning_input_checks(
+    blippy: &mut Blippy<'_>,
+    input: &PlanningInput,
+) {
+    check_reserved_underlay_ranges(blippy, input);
+}
+
+fn check_reserved_underlay_ranges(
+    blippy: &mut Blippy<'_>,
+    input: &PlanningInput,
+) {
+    let reserved = input.reserved_underlay_ranges();
+    if reserved.is_empty() {
+        return;
+    }
+
+    for (sled_id, zone) in blippy
+        .blueprint()
+        .all_omicron_zones(BlueprintZoneDisposition::should_be_running)
+    {
+        let ip = zone.underlay_ip();
+        if let Some(range) = reserved.iter().find(|range| range.contains(ip)) {
+            blippy.push_sled_note(
+                sled_id,
+                Severity::Fatal,
+                SledKind::UnderlayIpInReservedRange {
+                    zone: zone.clone(),
+                    range: *range,
+                },
+            );
+        }
+    }
+}
+
 fn check_underlay_ips(blippy: &mut Blippy<'_>) {
     let mut underlay_ips: BTreeMap<Ipv6Addr, &BlueprintZoneConfig> =
         BTreeMap::new();
@@ -54,7 +89,7 @@ fn check_underlay_ips(blippy: &mut Blippy<'_>) {
 
     for (sled_id, zone) in blippy
         .blueprint()
-        .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+        .all_omicron_zones(BlueprintZoneDisposition::should_be_running)
     {
         let ip = zone.underlay_ip();
 
@@ -77,7 +112,8 @@ fn check_underlay_ips(blippy: &mut Blippy<'_>) {
             if rack_dns_subnets.is_empty() {
                 // The blueprint doesn't store the rack subnet explicitly, so we
                 // infer it based on the first internal DNS zone we see.
-                rack_dns_subnets.extend(subnet.rack_subnet().get_dns_subnets());
+                rack_dns_subnets
+                    .extend(subnet.rack_subnet().get_reserved_dns_subnets());
             }
             if !rack_dns_subnets.contains(&subnet) {
                 blippy.push_sled_note(
@@ -147,7 +183,7 @@ fn check_external_networking(blippy: &mut Blippy<'_>) {
 
     for (sled_id, zone, external_ip, nic) in blippy
         .blueprint()
-        .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+        .all_omicron_zones(BlueprintZoneDisposition::should_be_running)
         .filter_map(|(sled_id, zone)| {
             zone.zone_type
                 .external_networking()
@@ -235,7 +271,7 @@ fn check_dataset_zpool_uniqueness(blippy: &mut Blippy<'_>) {
     // kind.
     for (sled_id, zone) in blippy
         .blueprint()
-        .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+        .all_omicron_zones(BlueprintZoneDisposition::should_be_running)
     {
         // Check "one kind per zpool" for transient datasets...
         let filesystem_dataset = zone.filesystem_dataset();
@@ -404,8 +440,10 @@ fn check_datasets(blippy: &mut Blippy<'_>) {
 
         // There should be a dataset for every dataset referenced by a running
         // zone (filesystem or durable).
-        for zone_config in
-            sled_config.zones.iter().filter(|z| z.disposition.is_in_service())
+        for zone_config in sled_config
+            .zones
+            .iter()
+            .filter(|z| z.disposition.should_be_running())
         {
             let dataset = zone_config.filesystem_dataset();
             match sled_datasets
@@ -558,7 +596,7 @@ fn check_mupdate_override(blippy: &mut Blippy<'_>) {
         if let Some(mupdate_override_id) = sled.remove_mupdate_override {
             // All in-service zones should be set to InstallDataset.
             for zone in &sled.zones {
-                if zone.disposition.is_in_service() {
+                if zone.disposition.should_be_running() {
                     match &zone.image_source {
                         BlueprintZoneImageSource::InstallDataset => {
                             // This is valid.
@@ -643,6 +681,7 @@ mod tests {
     use nexus_types::deployment::BlueprintArtifactVersion;
     use nexus_types::deployment::BlueprintZoneType;
     use nexus_types::deployment::blueprint_zone_type;
+    use omicron_common::address::Ipv6Range;
     use omicron_test_utils::dev::test_setup_log;
     use omicron_uuid_kinds::MupdateOverrideUuid;
     use std::mem;
@@ -829,7 +868,7 @@ mod tests {
                 kind: SledKind::InternalDnsZoneBadSubnet {
                     zone: dns1.clone(),
                     rack_dns_subnets: rack_subnet
-                        .get_dns_subnets()
+                        .get_reserved_dns_subnets()
                         .into_iter()
                         .collect(),
                 },
@@ -1810,6 +1849,52 @@ mod tests {
         eprintln!("{}", report.display());
         assert_eq!(report.notes(), &expected_notes);
 
+        logctx.cleanup_successful();
+    }
+    #[test]
+    fn test_underlay_ip_in_reserved_range() {
+        static TEST_NAME: &str = "test_underlay_ip_in_reserved_range";
+        let logctx = test_setup_log(TEST_NAME);
+        let (_, input, blueprint) = example(&logctx.log, TEST_NAME);
+
+        // The example input reserves nothing.
+        let report = Blippy::new_with_planning_input(&blueprint, &input)
+            .into_report(BlippyReportSortKey::Kind);
+        assert!(report.notes().is_empty(), "{}", report.display());
+
+        // Reserve a range covering one Nexus zone's underlay IP.
+        let (sled_id, nexus) = blueprint
+            .all_omicron_zones(BlueprintZoneDisposition::should_be_running)
+            .find(|(_, zone)| zone.zone_type.is_nexus())
+            .expect("at least one Nexus zone");
+        let range = Ipv6Range::from(nexus.underlay_ip());
+        let input = {
+            let mut builder = input.into_builder();
+            builder.policy_mut().reserved_underlay_ranges = vec![range];
+            builder.build()
+        };
+
+        let expected_notes = vec![Note {
+            severity: Severity::Fatal,
+            kind: Kind::Sled {
+                sled_id,
+                kind: SledKind::UnderlayIpInReservedRange {
+                    zone: nexus.clone(),
+                    range,
+                },
+            },
+        }];
+
+        let report = Blippy::new_with_planning_input(&blueprint, &input)
+            .into_report(BlippyReportSortKey::Kind);
+        eprintln!("{}", report.display());
+        assert_eq!(report.notes(), &expected_notes);
+
+        // Blueprint-only checks know nothing about reserved ranges.
+        let report =
+            Blippy::new(&blueprint).into_report(BlippyReportSortKey::Kind);
+        assert!(report.notes().is_empty(), "{}", report.display());
+
         logctx.cleanup_successful();
     }
 }
@@ -204,11 +204,14 @@ async fn test_blueprint_edit(cptestctx: &ControlPlaneTestContext) {
         .into_inner();
     assert_eq!(found_blueprint, new_blueprint2);
 
-    // Set the blueprint as the (disabled) target.
+    // Set the blueprint as the (disabled) target.  The test environment's
+    // blueprints aren't realistic (e.g., all zones share an underlay IP), so
+    // they wouldn't pass validation.
     nexus_client
         .blueprint_target_set(&nexus_client::types::BlueprintTargetSet {
             target_id: new_blueprint.id,
             enabled: false,
+            skip_validation: true,
         })
         .await
         .context("setting target blueprint")
@@ -208,7 +208,9 @@ pub async fn realize_blueprint(
     register_deploy_sled_configs_step(
         &engine.for_component(ExecutionComponent::SledAgent),
         &opctx,
+        datastore,
         blueprint,
+        nexus_id,
         sled_list.clone(),
     );
 
@@ -428,7 +430,9 @@ fn register_deploy_db_metadata_nexus_records_step<'a>(
 fn register_deploy_sled_configs_step<'a>(
     registrar: &ComponentRegistrar<'_, 'a>,
     opctx: &'a OpContext,
+    datastore: &'a DataStore,
     blueprint: &'a Blueprint,
+    nexus_id: Option<OmicronZoneUuid>,
     sleds: SharedStepHandle<Arc<IdOrdMap<Sled>>>,
 ) {
     registrar
@@ -437,10 +441,18 @@ fn register_deploy_sled_configs_step<'a>(
             "Deploy sled configs",
             async move |cx| {
                 let sleds_by_id = sleds.into_value(cx.token()).await;
+                // A scoped blueprint leaves the sleds outside its scope
+                // alone.
+                let sled_configs: BTreeMap<_, _> = blueprint
+                    .sleds_in_scope()
+                    .map(|(sled_id, config)| (sled_id, config.clone()))
+                    .collect();
                 let res = omicron_sled_config::deploy_sled_configs(
                     opctx,
+                    datastore,
+                    nexus_id,
                     &sleds_by_id,
-                    &blueprint.sleds,
+                    &sled_configs,
                 )
                 .await
                 .map_err(merge_anyhow_list);
@@ -7,22 +7,41 @@
 use crate::Sled;
 use anyhow::Context;
 use anyhow::anyhow;
+use chrono::TimeDelta;
 use futures::StreamExt;
 use futures::stream;
 use iddqd::IdOrdMap;
 use nexus_db_queries::context::OpContext;
+use nexus_db_queries::db::DataStore;
+use nexus_db_queries::db::datastore::sled_execution_lease::SledExecutionLeaseAcquire;
 use nexus_types::deployment::BlueprintSledConfig;
 use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::OmicronZoneUuid;
 use omicron_uuid_kinds::SledUuid;
 use slog::info;
 use slog::warn;
 use slog_error_chain::InlineErrorChain;
 use std::collections::BTreeMap;
 
+/// How long a Nexus may hold a sled's execution lease before another Nexus can
+/// take it over
+///
+/// The lease is released as soon as the sled's config has been sent, so this
+/// only matters if the Nexus holding it dies or loses its database connection
+/// in the meantime.
+const SLED_EXECUTION_LEASE_TIMEOUT: TimeDelta = TimeDelta::minutes(5);
+
 /// Idempotently ensure that the specified Omicron sled configs are deployed to
 /// the corresponding sleds
+///
+/// If `nexus_id` is provided, this holds each sled's execution lease while
+/// sending it its config, so that several Nexus instances executing blueprints
+/// concurrently don't interleave their requests to a sled. Sleds whose lease
+/// is held by another Nexus are skipped and reported as errors.
 pub(crate) async fn deploy_sled_configs(
     opctx: &OpContext,
+    datastore: &DataStore,
+    nexus_id: Option<OmicronZoneUuid>,
     sleds_by_id: &IdOrdMap<Sled>,
     sled_configs: &BTreeMap<SledUuid, BlueprintSledConfig>,
 ) -> Result<(), Vec<anyhow::Error>> {
@@ -50,6 +69,40 @@ pub(crate) async fn deploy_sled_configs(
                 }
             };
 
+            if let Some(nexus_id) = nexus_id {
+                match datastore
+                    .sled_execution_lease_acquire(
+                        opctx,
+                        *sled_id,
+                        nexus_id,
+                        SLED_EXECUTION_LEASE_TIMEOUT,
+                    )
+                    .await
+                {
+                    Ok(SledExecutionLeaseAcquire::Acquired(_)) => {}
+                    Ok(SledExecutionLeaseAcquire::HeldByOther {
+                        nexus_id: holder,
+                        time_expires,
+                    }) => {
+                        let err = anyhow!(
+                            "not deploying config to sled {sled_id}: its \
+                             execution lease is held by Nexus {holder} \
+                             (until {time_expires})"
+                        );
+                        info!(log, "{err:#}");
+                        return Some(err);
+                    }
+                    Err(error) => {
+                        let err = anyhow!(error).context(format!(
+                            "failed to acquire execution lease for sled \
+                             {sled_id}"
+                        ));
+                        warn!(log, "{err:#}");
+                        return Some(err);
+                    }
+                }
+            }
+
             let client = nexus_networking::sled_client_from_address(
                 sled_id.into_untyped_uuid(),
                 db_sled.sled_agent_address(),
@@ -57,10 +110,32 @@ pub(crate) async fn deploy_sled_configs(
             );
 
             let config = config.clone().into_in_service_sled_config();
-            let result =
-                client.omicron_config_put(&config).await.with_context(|| {
+            // A sled's config is fully determined by its generation, so use
+            // that as the idempotency key: if an earlier attempt to put this
+            // config timed out after the sled agent applied it, the sled agent
+            // reports success without applying it again.
+            let idempotency_key =
+                format!("omicron-config-{sled_id}-{}", config.generation);
+            let result = client
+                .omicron_config_put(Some(&idempotency_key), &config)
+                .await
+                .with_context(|| {
                     format!("Failed to put {config:#?} to sled {sled_id}")
                 });
+
+            // Failing to release the lease isn't fatal: it will expire on its
+            // own.
+            if let Some(nexus_id) = nexus_id {
+                if let Err(error) = datastore
+                    .sled_execution_lease_release(opctx, *sled_id, nexus_id)
+                    .await
+                {
+                    warn!(
+                        log, "failed to release sled execution lease";
+                        InlineErrorChain::new(&error),
+                    );
+                }
+            }
             match result {
                 Ok(_) => None,
                 Err(error) => {
@@ -260,9 +335,15 @@ mod tests {
             [(sim_sled_agent.id, sled_config.clone())].into_iter().collect();
 
         // Give the simulated sled agent a configuration to deploy
-        deploy_sled_configs(&opctx, &sleds_by_id, &sled_configs)
-            .await
-            .expect("Deploying datasets should have succeeded");
+        deploy_sled_configs(
+            &opctx,
+            datastore,
+            None,
+            &sleds_by_id,
+            &sled_configs,
+        )
+        .await
+        .expect("Deploying datasets should have succeeded");
 
         // Observe the latest configuration stored on the simulated sled agent,
         // and verify that this output matches the input.
@@ -309,5 +390,73 @@ mod tests {
         assert!(observed_datasets.datasets.contains_key(&dataset_id));
         assert_eq!(observed_zones.zones.len(), 1);
         assert_eq!(observed_zones.zones[0].id, zone_id);
+
+        // If another Nexus holds the sled's execution lease, we leave the sled
+        // alone.
+        let our_nexus = OmicronZoneUuid::new_v4();
+        let other_nexus = OmicronZoneUuid::new_v4();
+        datastore
+            .sled_execution_lease_acquire(
+                &opctx,
+                sim_sled_agent.id,
+                other_nexus,
+                SLED_EXECUTION_LEASE_TIMEOUT,
+            )
+            .await
+            .expect("acquired lease");
+        let mut sled_config = sled_config;
+        sled_config.sled_agent_generation =
+            sled_config.sled_agent_generation.next();
+        let sled_configs =
+            [(sim_sled_agent.id, sled_config.clone())].into_iter().collect();
+        let errors = deploy_sled_configs(
+            &opctx,
+            datastore,
+            Some(our_nexus),
+            &sleds_by_id,
+            &sled_configs,
+        )
+        .await
+        .expect_err("sled should have been skipped");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            sim_sled_agent.omicron_zones_list().generation,
+            in_service_config.generation
+        );
+
+        // Once the lease is released, we deploy the config, and release the
+        // lease ourselves afterwards.
+        datastore
+            .sled_execution_lease_release(
+                &opctx,
+                sim_sled_agent.id,
+                other_nexus,
+            )
+            .await
+            .expect("released lease");
+        deploy_sled_configs(
+            &opctx,
+            datastore,
+            Some(our_nexus),
+            &sleds_by_id,
+            &sled_configs,
+        )
+        .await
+        .expect("Deploying config should have succeeded");
+        assert_eq!(
+            sim_sled_agent.omicron_zones_list().generation,
+            sled_config.sled_agent_generation
+        );
+        assert!(
+            !datastore
+                .sled_execution_lease_release(
+                    &opctx,
+                    sim_sled_agent.id,
+                    our_nexus,
+                )
+                .await
+                .expect("tried to release lease"),
+            "lease should already have been released"
+        );
     }
 }
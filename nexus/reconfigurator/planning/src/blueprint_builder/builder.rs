@@ -18,7 +18,6 @@ use crate::mgs_updates::PendingHostPhase2Changes;
 use crate::planner::NoopConvertGlobalIneligibleReason;
 use crate::planner::NoopConvertInfo;
 use crate::planner::NoopConvertSledIneligibleReason;
-use crate::planner::ZoneExpungeReason;
 use crate::planner::rng::PlannerRng;
 use anyhow::Context as _;
 use anyhow::anyhow;
@@ -34,11 +33,13 @@ use nexus_sled_agent_shared::inventory::MupdateOverrideBootInventory;
 use nexus_sled_agent_shared::inventory::OmicronZoneDataset;
 use nexus_sled_agent_shared::inventory::ZoneKind;
 use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintDatasetConfig;
 use nexus_types::deployment::BlueprintDatasetDisposition;
 use nexus_types::deployment::BlueprintHostPhase2DesiredContents;
 use nexus_types::deployment::BlueprintHostPhase2DesiredSlots;
 use nexus_types::deployment::BlueprintPhysicalDiskConfig;
 use nexus_types::deployment::BlueprintPhysicalDiskDisposition;
+use nexus_types::deployment::BlueprintScope;
 use nexus_types::deployment::BlueprintSledConfig;
 use nexus_types::deployment::BlueprintZoneConfig;
 use nexus_types::deployment::BlueprintZoneDisposition;
@@ -58,6 +59,7 @@ use nexus_types::deployment::PlanningReport;
 use nexus_types::deployment::SledFilter;
 use nexus_types::deployment::SledResources;
 use nexus_types::deployment::TufRepoContentsError;
+use nexus_types::deployment::ZoneExpungeReason;
 use nexus_types::deployment::ZpoolFilter;
 use nexus_types::deployment::ZpoolName;
 use nexus_types::deployment::blueprint_zone_type;
@@ -68,13 +70,15 @@ use omicron_common::address::DNS_HTTP_PORT;
 use omicron_common::address::DNS_PORT;
 use omicron_common::address::NTP_PORT;
 use omicron_common::address::ReservedRackSubnet;
+use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::Generation;
 use omicron_common::api::external::Vni;
 use omicron_common::api::internal::shared::NetworkInterface;
 use omicron_common::api::internal::shared::NetworkInterfaceKind;
 use omicron_common::disk::M2Slot;
-use omicron_common::policy::INTERNAL_DNS_REDUNDANCY;
+use omicron_common::policy::RESERVED_INTERNAL_DNS_REDUNDANCY;
 use omicron_uuid_kinds::BlueprintUuid;
+use omicron_uuid_kinds::DatasetUuid;
 use omicron_uuid_kinds::GenericUuid;
 use omicron_uuid_kinds::MupdateOverrideUuid;
 use omicron_uuid_kinds::OmicronZoneUuid;
@@ -139,7 +143,9 @@ pub enum Error {
     AllocateInternalDnsSubnet(#[from] NoAvailableDnsSubnets),
     #[error("error allocating external networking resources")]
     AllocateExternalNetworking(#[from] ExternalNetworkingError),
-    #[error("can only have {INTERNAL_DNS_REDUNDANCY} internal DNS servers")]
+    #[error(
+        "can only have {RESERVED_INTERNAL_DNS_REDUNDANCY} internal DNS servers"
+    )]
     PolicySpecifiesTooManyInternalDnsServers,
     #[error("zone is already up-to-date and should not be updated")]
     ZoneAlreadyUpToDate,
@@ -296,6 +302,8 @@ impl From<StorageEditCounts> for SledEditCounts {
 pub struct EditedSledScalarEdits {
     /// Whether the remove_mupdate_override field was modified.
     pub remove_mupdate_override: bool,
+    /// Whether the sled was quiesced or unquiesced.
+    pub quiesced: bool,
     /// Whether the debug operation to force a Sled Agent generation bump was
     /// set.
     pub debug_force_generation_bump: bool,
@@ -306,11 +314,14 @@ impl EditedSledScalarEdits {
         Self {
             debug_force_generation_bump: false,
             remove_mupdate_override: false,
+            quiesced: false,
         }
     }
 
     pub fn has_edits(&self) -> bool {
-        self.debug_force_generation_bump || self.remove_mupdate_override
+        self.debug_force_generation_bump
+            || self.remove_mupdate_override
+            || self.quiesced
     }
 }
 
@@ -357,6 +368,9 @@ pub(crate) enum Operation {
         current_generation: Generation,
         new_generation: Generation,
     },
+    SetScope {
+        scope: BlueprintScope,
+    },
     SetTargetReleaseMinimumGeneration {
         current_generation: Generation,
         new_generation: Generation,
@@ -397,14 +411,6 @@ impl fmt::Display for Operation {
                 )
             }
             Self::ZoneExpunged { sled_id, reason, count } => {
-                let reason = match reason {
-                    ZoneExpungeReason::ClickhouseClusterDisabled => {
-                        "clickhouse cluster disabled via policy"
-                    }
-                    ZoneExpungeReason::ClickhouseSingleNodeDisabled => {
-                        "clickhouse single-node disabled via policy"
-                    }
-                };
                 write!(
                     f,
                     "sled {sled_id}: expunged {count} zones because: {reason}"
@@ -476,6 +482,9 @@ impl fmt::Display for Operation {
                      {current_generation} to {new_generation}"
                 )
             }
+            Self::SetScope { scope } => {
+                write!(f, "set scope to {scope}")
+            }
         }
     }
 }
@@ -527,6 +536,7 @@ pub struct BlueprintBuilder<'a> {
     cockroachdb_setting_preserve_downgrade: CockroachDbPreserveDowngrade,
     target_release_minimum_generation: Generation,
     nexus_generation: Generation,
+    scope: BlueprintScope,
     report: Option<PlanningReport>,
 
     creator: String,
@@ -589,6 +599,7 @@ impl<'a> BlueprintBuilder<'a> {
         Blueprint {
             id,
             sleds,
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             parent_blueprint_id: None,
             internal_dns_version: Generation::new(),
@@ -629,7 +640,7 @@ impl<'a> BlueprintBuilder<'a> {
             let state = sled_cfg.state;
 
             let editor = match state {
-                SledState::Active => {
+                SledState::Active | SledState::Quiesced => {
                     let subnet = input
                         .sled_lookup(SledFilter::Commissioned, *sled_id)
                         .with_context(|| {
@@ -640,7 +651,11 @@ impl<'a> BlueprintBuilder<'a> {
                         })?
                         .resources
                         .subnet;
-                    SledEditor::for_existing_active(subnet, sled_cfg.clone())
+                    SledEditor::for_existing_active(
+                        subnet,
+                        input.reserved_underlay_ranges(),
+                        sled_cfg.clone(),
+                    )
                 }
                 SledState::Decommissioned => {
                     SledEditor::for_existing_decommissioned(sled_cfg.clone())
@@ -659,6 +674,7 @@ impl<'a> BlueprintBuilder<'a> {
             if let Entry::Vacant(slot) = sled_editors.entry(sled_id) {
                 slot.insert(SledEditor::for_new_active(
                     details.resources.subnet,
+                    input.reserved_underlay_ranges(),
                 ));
             }
         }
@@ -677,6 +693,8 @@ impl<'a> BlueprintBuilder<'a> {
             target_release_minimum_generation: parent_blueprint
                 .target_release_minimum_generation,
             nexus_generation: parent_blueprint.nexus_generation,
+            // Scopes are chosen per-blueprint; they aren't inherited.
+            scope: BlueprintScope::Fleet,
             report: None,
             creator: creator.to_owned(),
             operations: Vec::new(),
@@ -754,6 +772,20 @@ impl<'a> BlueprintBuilder<'a> {
         Either::Right(editor.disks(filter))
     }
 
+    pub fn current_sled_datasets<F>(
+        &self,
+        sled_id: SledUuid,
+        filter: F,
+    ) -> impl Iterator<Item = &BlueprintDatasetConfig>
+    where
+        F: FnMut(BlueprintDatasetDisposition) -> bool,
+    {
+        let Some(editor) = self.sled_editors.get(&sled_id) else {
+            return Either::Left(iter::empty());
+        };
+        Either::Right(editor.datasets(filter))
+    }
+
     pub fn current_sled_host_phase_2(
         &self,
         sled_id: SledUuid,
@@ -781,6 +813,7 @@ impl<'a> BlueprintBuilder<'a> {
                 let EditedSledScalarEdits {
                     debug_force_generation_bump,
                     remove_mupdate_override,
+                    quiesced,
                 } = scalar_edits;
                 debug!(
                     self.log, "sled modified in new blueprint";
@@ -791,6 +824,7 @@ impl<'a> BlueprintBuilder<'a> {
                     "zone_edits" => ?edit_counts.zones,
                     "debug_force_generation_bump" => debug_force_generation_bump,
                     "remove_mupdate_override_modified" => remove_mupdate_override,
+                    "quiesced_modified" => quiesced,
                 );
             } else {
                 debug!(
@@ -801,6 +835,31 @@ impl<'a> BlueprintBuilder<'a> {
             }
         }
 
+        // A scoped blueprint carries over every sled outside its scope
+        // unchanged from the parent, discarding any edits made to them.
+        if let BlueprintScope::Sleds(in_scope) = &self.scope {
+            sleds.retain(|sled_id, config| {
+                if in_scope.contains(sled_id) {
+                    return true;
+                }
+                let parent_config = self.parent_blueprint.sleds.get(sled_id);
+                if parent_config != Some(&*config) {
+                    info!(
+                        self.log,
+                        "discarding changes to sled outside blueprint scope";
+                        "sled_id" => %sled_id,
+                    );
+                }
+                match parent_config {
+                    Some(parent_config) => {
+                        *config = parent_config.clone();
+                        true
+                    }
+                    None => false,
+                }
+            });
+        }
+
         // If we have the clickhouse cluster setup enabled via policy and we
         // don't yet have a `ClickhouseClusterConfiguration`, then we must
         // create one and feed it to our `ClickhouseAllocator`.
@@ -865,6 +924,7 @@ impl<'a> BlueprintBuilder<'a> {
         Blueprint {
             id: blueprint_id,
             sleds,
+            scope: self.scope,
             pending_mgs_updates: self.pending_mgs_updates,
             parent_blueprint_id: Some(self.parent_blueprint.id),
             internal_dns_version: self.input.internal_dns_version(),
@@ -1023,7 +1083,8 @@ impl<'a> BlueprintBuilder<'a> {
                     // as ready for cleanup.
                     zones_ready_for_cleanup.push(zone.id);
                 }
-                BlueprintZoneDisposition::InService => {
+                BlueprintZoneDisposition::InService
+                | BlueprintZoneDisposition::Cordoned => {
                     return Err(Error::Planner(anyhow!(
                         "expunged all disks but a zone \
                          is still in service: {zone:?}"
@@ -1978,6 +2039,44 @@ impl<'a> BlueprintBuilder<'a> {
         Ok(final_counts.difference_since(initial_counts))
     }
 
+    /// Cordon (if `cordoned` is true) or uncordon (if false) a zone.
+    ///
+    /// A cordoned zone keeps running on its sled but is removed from DNS and
+    /// load-sharing. Returns `true` if the zone's disposition changed.
+    pub fn sled_set_zone_cordoned(
+        &mut self,
+        sled_id: SledUuid,
+        zone_id: OmicronZoneUuid,
+        cordoned: bool,
+    ) -> Result<bool, Error> {
+        let editor = self.sled_editors.get_mut(&sled_id).ok_or_else(|| {
+            Error::Planner(anyhow!(
+                "tried to cordon or uncordon zone on unknown sled {sled_id}"
+            ))
+        })?;
+        editor
+            .set_zone_cordoned(&zone_id, cordoned)
+            .map_err(|err| Error::SledEditError { sled_id, err })
+    }
+
+    /// Set the quota of an in-service dataset, returning `true` if it
+    /// changed.
+    pub fn sled_set_dataset_quota(
+        &mut self,
+        sled_id: SledUuid,
+        dataset_id: DatasetUuid,
+        quota: Option<ByteCount>,
+    ) -> Result<bool, Error> {
+        let editor = self.sled_editors.get_mut(&sled_id).ok_or_else(|| {
+            Error::Planner(anyhow!(
+                "tried to set dataset quota on unknown sled {sled_id}"
+            ))
+        })?;
+        editor
+            .set_dataset_quota(dataset_id, quota)
+            .map_err(|err| Error::SledEditError { sled_id, err })
+    }
+
     pub fn sled_set_zone_source(
         &mut self,
         sled_id: SledUuid,
@@ -2054,6 +2153,25 @@ impl<'a> BlueprintBuilder<'a> {
             .map_err(|err| Error::SledEditError { sled_id, err })
     }
 
+    /// Quiesce or unquiesce the given sled.
+    ///
+    /// A quiesced sled keeps running everything it already has, but the
+    /// planner won't place any new discretionary zones on it.
+    pub fn sled_set_quiesced(
+        &mut self,
+        sled_id: SledUuid,
+        quiesced: bool,
+    ) -> Result<(), Error> {
+        let editor = self.sled_editors.get_mut(&sled_id).ok_or_else(|| {
+            Error::Planner(anyhow!(
+                "tried to set sled state for unknown sled {sled_id}"
+            ))
+        })?;
+        editor
+            .set_quiesced(quiesced)
+            .map_err(|err| Error::SledEditError { sled_id, err })
+    }
+
     fn sled_add_zone(
         &mut self,
         sled_id: SledUuid,
@@ -2214,6 +2332,15 @@ impl<'a> BlueprintBuilder<'a> {
         });
     }
 
+    /// Restrict the blueprint to changing only some sleds
+    ///
+    /// When the blueprint is built, every sled outside `scope` is carried over
+    /// unchanged from the parent blueprint.  See [`BlueprintScope`].
+    pub fn set_scope(&mut self, scope: BlueprintScope) {
+        self.scope = scope.clone();
+        self.record_operation(Operation::SetScope { scope });
+    }
+
     /// Allow a test to manually add an external DNS address, which could
     /// ordinarily only come from RSS.
     ///
@@ -2753,6 +2880,7 @@ pub mod test {
     use nexus_reconfigurator_blippy::BlippyReportSortKey;
     use nexus_types::deployment::BlueprintArtifactVersion;
     use nexus_types::deployment::BlueprintDatasetDisposition;
+    use nexus_types::deployment::BlueprintScopeError;
     use nexus_types::deployment::OmicronZoneNetworkResources;
     use nexus_types::external_api::views::SledPolicy;
     use omicron_common::address::IpRange;
@@ -3173,6 +3301,252 @@ pub mod test {
         logctx.cleanup_successful();
     }
 
+    #[test]
+    fn test_cordon_and_uncordon_zone() {
+        static TEST_NAME: &str = "blueprint_builder_test_cordon_zone";
+        let logctx = test_setup_log(TEST_NAME);
+        let mut rng = SimRngState::from_seed(TEST_NAME);
+        let (collection, input, blueprint1) = example(&logctx.log, TEST_NAME);
+
+        let (sled_id, nexus_id) = blueprint1
+            .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+            .find(|(_, zone)| zone.zone_type.is_nexus())
+            .map(|(sled_id, zone)| (sled_id, zone.id))
+            .expect("example system has a Nexus zone");
+
+        let mut builder = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint1,
+            &input,
+            &collection,
+            "test",
+            rng.next_planner_rng(),
+        )
+        .expect("failed to create builder");
+        assert!(
+            builder.sled_set_zone_cordoned(sled_id, nexus_id, true).unwrap()
+        );
+        // Cordoning is idempotent.
+        assert!(
+            !builder.sled_set_zone_cordoned(sled_id, nexus_id, true).unwrap()
+        );
+        let blueprint2 = builder.build();
+        verify_blueprint(&blueprint2);
+
+        // The zone is cordoned, but is still part of the sled's config, and
+        // cordoning does not require a new sled-agent generation.
+        let sled_config = &blueprint2.sleds[&sled_id];
+        assert_eq!(
+            sled_config.zones.get(&nexus_id).unwrap().disposition,
+            BlueprintZoneDisposition::Cordoned
+        );
+        assert_eq!(
+            sled_config.sled_agent_generation,
+            blueprint1.sleds[&sled_id].sled_agent_generation
+        );
+        assert!(
+            sled_config
+                .clone()
+                .into_in_service_sled_config()
+                .zones
+                .contains_key(&nexus_id)
+        );
+
+        // Uncordoning returns the zone to service.
+        let mut builder = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint2,
+            &input,
+            &collection,
+            "test",
+            rng.next_planner_rng(),
+        )
+        .expect("failed to create builder");
+        assert!(
+            builder.sled_set_zone_cordoned(sled_id, nexus_id, false).unwrap()
+        );
+        let blueprint3 = builder.build();
+        verify_blueprint(&blueprint3);
+        assert_eq!(
+            blueprint3.sleds[&sled_id]
+                .zones
+                .get(&nexus_id)
+                .unwrap()
+                .disposition,
+            BlueprintZoneDisposition::InService
+        );
+
+        // Expunged zones cannot be cordoned.
+        let mut builder = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint3,
+            &input,
+            &collection,
+            "test",
+            rng.next_planner_rng(),
+        )
+        .expect("failed to create builder");
+        builder.sled_expunge_zone(sled_id, nexus_id).unwrap();
+        builder
+            .sled_set_zone_cordoned(sled_id, nexus_id, true)
+            .expect_err("cannot cordon an expunged zone");
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_quiesce_and_unquiesce_sled() {
+        static TEST_NAME: &str = "blueprint_builder_test_quiesce_sled";
+        let logctx = test_setup_log(TEST_NAME);
+        let mut rng = SimRngState::from_seed(TEST_NAME);
+        let (collection, input, blueprint1) = example(&logctx.log, TEST_NAME);
+        let sled_id =
+            blueprint1.sleds.keys().copied().next().expect("at least one sled");
+
+        let mut builder = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint1,
+            &input,
+            &collection,
+            "test",
+            rng.next_planner_rng(),
+        )
+        .expect("failed to create builder");
+        builder.sled_set_quiesced(sled_id, true).unwrap();
+        assert_eq!(
+            builder.current_sled_state(sled_id).unwrap(),
+            SledState::Quiesced
+        );
+        let blueprint2 = builder.build();
+        verify_blueprint(&blueprint2);
+
+        // The sled is quiesced, but keeps its zones, and quiescing does not
+        // require a new sled-agent generation.
+        let sled_config = &blueprint2.sleds[&sled_id];
+        assert_eq!(sled_config.state, SledState::Quiesced);
+        assert_eq!(sled_config.zones, blueprint1.sleds[&sled_id].zones);
+        assert_eq!(
+            sled_config.sled_agent_generation,
+            blueprint1.sleds[&sled_id].sled_agent_generation
+        );
+        let diff = blueprint2.diff_since_blueprint(&blueprint1);
+        assert!(
+            diff.display().to_string().contains("active -> quiesced"),
+            "diff should show the sled being quiesced"
+        );
+
+        // Unquiescing returns the sled to the active state.
+        let mut builder = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint2,
+            &input,
+            &collection,
+            "test",
+            rng.next_planner_rng(),
+        )
+        .expect("failed to create builder");
+        builder.sled_set_quiesced(sled_id, false).unwrap();
+        let blueprint3 = builder.build();
+        verify_blueprint(&blueprint3);
+        assert_eq!(blueprint3.sleds[&sled_id].state, SledState::Active);
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_scoped_blueprint() {
+        static TEST_NAME: &str = "blueprint_builder_test_scoped_blueprint";
+        let logctx = test_setup_log(TEST_NAME);
+        let mut rng = SimRngState::from_seed(TEST_NAME);
+        let (collection, input, blueprint1) = example(&logctx.log, TEST_NAME);
+
+        // Find Nexus zones on two different sleds.
+        let mut nexus_zones = BTreeMap::new();
+        for (sled_id, zone) in blueprint1
+            .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+            .filter(|(_, zone)| zone.zone_type.is_nexus())
+        {
+            nexus_zones.entry(sled_id).or_insert(zone.id);
+        }
+        let mut nexus_zones = nexus_zones.into_iter();
+        let (in_scope_sled, in_scope_zone) =
+            nexus_zones.next().expect("example system has a Nexus zone");
+        let (out_of_scope_sled, out_of_scope_zone) =
+            nexus_zones.next().expect("example system has two Nexus zones");
+
+        // Cordon both zones, but scope the blueprint to only one of their
+        // sleds.
+        let mut builder = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint1,
+            &input,
+            &collection,
+            "test",
+            rng.next_planner_rng(),
+        )
+        .expect("failed to create builder");
+        builder
+            .set_scope(BlueprintScope::Sleds(BTreeSet::from([in_scope_sled])));
+        builder
+            .sled_set_zone_cordoned(in_scope_sled, in_scope_zone, true)
+            .unwrap();
+        builder
+            .sled_set_zone_cordoned(out_of_scope_sled, out_of_scope_zone, true)
+            .unwrap();
+        let blueprint2 = builder.build();
+        verify_blueprint(&blueprint2);
+
+        // Only the change to the sled within the scope survives.
+        assert_eq!(
+            blueprint2.sleds[&in_scope_sled]
+                .zones
+                .get(&in_scope_zone)
+                .unwrap()
+                .disposition,
+            BlueprintZoneDisposition::Cordoned
+        );
+        for (sled_id, config) in &blueprint2.sleds {
+            if *sled_id != in_scope_sled {
+                assert_eq!(config, &blueprint1.sleds[sled_id]);
+            }
+        }
+        blueprint2.validate_scope(&blueprint1).expect("blueprint is in scope");
+
+        // Widening the scope of the same blueprint after the fact is caught.
+        let mut blueprint3 = blueprint2.clone();
+        blueprint3
+            .sleds
+            .get_mut(&out_of_scope_sled)
+            .unwrap()
+            .zones
+            .get_mut(&out_of_scope_zone)
+            .unwrap()
+            .disposition = BlueprintZoneDisposition::Cordoned;
+        let error = blueprint3
+            .validate_scope(&blueprint1)
+            .expect_err("blueprint changes a sled outside its scope");
+        match error {
+            BlueprintScopeError::OutOfScopeSledChanged(sled_id) => {
+                assert_eq!(sled_id, out_of_scope_sled);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        // Scopes are not inherited by child blueprints.
+        let builder = BlueprintBuilder::new_based_on(
+            &logctx.log,
+            &blueprint2,
+            &input,
+            &collection,
+            "test",
+            rng.next_planner_rng(),
+        )
+        .expect("failed to create builder");
+        assert_eq!(builder.build().scope, BlueprintScope::Fleet);
+
+        logctx.cleanup_successful();
+    }
+
     #[test]
     fn test_datasets_for_zpools_and_zones() {
         static TEST_NAME: &str = "test_datasets_for_zpools_and_zones";
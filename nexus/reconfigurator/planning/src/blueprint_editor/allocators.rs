@@ -10,6 +10,7 @@ use super::SledEditor;
 use nexus_types::deployment::BlueprintZoneDisposition;
 use nexus_types::deployment::BlueprintZoneType;
 use nexus_types::deployment::blueprint_zone_type::InternalDns;
+use nexus_types::external_api::views::SledState;
 use omicron_common::address::DnsSubnet;
 use omicron_common::address::IpRange;
 use omicron_common::address::ReservedRackSubnet;
@@ -48,6 +49,11 @@ impl BlueprintResourceAllocator {
     {
         let internal_dns_subnets_in_use = all_sleds
             .clone()
+            // A decommissioned sled isn't running anything, even if an older
+            // blueprint never got around to marking its expunged zones ready
+            // for cleanup, so its subnets are free to be handed to
+            // replacements on other sleds.
+            .filter(|editor| editor.state() != SledState::Decommissioned)
             .flat_map(|editor| {
                 editor
                     // We use `could_be_running` here instead of `in_service` to
@@ -67,8 +73,10 @@ impl BlueprintResourceAllocator {
             InternalDnsSubnetAllocator::new(internal_dns_subnets_in_use);
 
         let external_networking = ExternalNetworkingAllocator::new(
+            // Cordoned zones keep running (and keep their external IPs and
+            // NICs), so treat them the same as in-service zones here.
             all_sleds.clone().flat_map(|editor| {
-                editor.zones(BlueprintZoneDisposition::is_in_service)
+                editor.zones(BlueprintZoneDisposition::should_be_running)
             }),
             all_sleds.flat_map(|editor| {
                 editor.zones(BlueprintZoneDisposition::is_expunged)
@@ -13,8 +13,12 @@ pub struct NoAvailableDnsSubnets;
 /// Internal DNS zones are not allocated an address in the sled's subnet.
 /// Instead, they get a /64 subnet of the "reserved" rack subnet (so that
 /// it's routable with IPv6), and use the first address in that. There may
-/// be at most `INTERNAL_DNS_REDUNDANCY` subnets (and so servers)
+/// be at most `RESERVED_INTERNAL_DNS_REDUNDANCY` subnets (and so servers)
 /// allocated. This structure tracks which subnets are currently allocated.
+///
+/// Subnets are always handed out lowest-first, so the first
+/// `INTERNAL_DNS_REDUNDANCY` subnets (the ones clients bootstrap from) are
+/// filled before any of the others.
 #[derive(Debug)]
 pub struct InternalDnsSubnetAllocator {
     in_use: BTreeSet<DnsSubnet>,
@@ -36,11 +40,11 @@ impl InternalDnsSubnetAllocator {
     ) -> Result<DnsSubnet, NoAvailableDnsSubnets> {
         let new = if let Some(first) = self.in_use.first() {
             // Take the first available DNS subnet. We currently generate
-            // all `INTERNAL_DNS_REDUNDANCY` subnets and subtract any
+            // all `RESERVED_INTERNAL_DNS_REDUNDANCY` subnets and subtract any
             // that are in use; this is fine as long as that constant is small.
             let subnets = BTreeSet::from_iter(
                 ReservedRackSubnet::from_subnet(first.subnet())
-                    .get_dns_subnets(),
+                    .get_reserved_dns_subnets(),
             );
             let mut avail = subnets.difference(&self.in_use);
             if let Some(first) = avail.next() {
@@ -86,6 +90,7 @@ pub mod test {
     use nexus_types::deployment::blueprint_zone_type::InternalDns;
     use omicron_common::disk::DatasetKind;
     use omicron_common::policy::INTERNAL_DNS_REDUNDANCY;
+    use omicron_common::policy::RESERVED_INTERNAL_DNS_REDUNDANCY;
     use omicron_test_utils::dev::test_setup_log;
 
     #[test]
@@ -171,6 +176,27 @@ pub mod test {
             INTERNAL_DNS_REDUNDANCY,
             "should be {INTERNAL_DNS_REDUNDANCY} subnets allocated"
         );
+        assert_eq!(
+            allocator.last(),
+            rack_subnet.get_dns_subnets().last().copied(),
+            "should have filled the well-known subnets first"
+        );
+
+        // We can keep allocating beyond the well-known subnets, up to the
+        // reserved maximum.
+        for _ in INTERNAL_DNS_REDUNDANCY..RESERVED_INTERNAL_DNS_REDUNDANCY {
+            let new = allocator.alloc(rack_subnet).expect("allocated a subnet");
+            assert!(
+                new > last,
+                "newly allocated subnets should be after prior ones"
+            );
+            last = new;
+        }
+        assert_eq!(
+            allocator.len(),
+            RESERVED_INTERNAL_DNS_REDUNDANCY,
+            "should be {RESERVED_INTERNAL_DNS_REDUNDANCY} subnets allocated"
+        );
         allocator.alloc(rack_subnet).expect_err("no subnets available");
 
         // Test packing.
@@ -35,8 +35,10 @@ use nexus_types::deployment::BlueprintZoneType;
 use nexus_types::deployment::PendingMgsUpdate;
 use nexus_types::deployment::blueprint_zone_type;
 use nexus_types::external_api::views::SledState;
+use omicron_common::address::Ipv6Range;
 use omicron_common::address::Ipv6Subnet;
 use omicron_common::address::SLED_PREFIX;
+use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::Generation;
 use omicron_common::disk::DatasetKind;
 use omicron_common::disk::M2Slot;
@@ -159,14 +161,15 @@ enum InnerSledEditor {
 impl SledEditor {
     pub fn for_existing_active(
         subnet: Ipv6Subnet<SLED_PREFIX>,
+        reserved_underlay_ranges: &[Ipv6Range],
         config: BlueprintSledConfig,
     ) -> Result<Self, SledInputError> {
-        assert_eq!(
-            config.state,
-            SledState::Active,
+        assert!(
+            matches!(config.state, SledState::Active | SledState::Quiesced),
             "for_existing_active called on non-active sled"
         );
-        let inner = ActiveSledEditor::new(subnet, config)?;
+        let inner =
+            ActiveSledEditor::new(subnet, reserved_underlay_ranges, config)?;
         Ok(Self(InnerSledEditor::Active(inner)))
     }
 
@@ -186,8 +189,14 @@ impl SledEditor {
         Ok(Self(InnerSledEditor::Decommissioned(inner)))
     }
 
-    pub fn for_new_active(subnet: Ipv6Subnet<SLED_PREFIX>) -> Self {
-        Self(InnerSledEditor::Active(ActiveSledEditor::new_empty(subnet)))
+    pub fn for_new_active(
+        subnet: Ipv6Subnet<SLED_PREFIX>,
+        reserved_underlay_ranges: &[Ipv6Range],
+    ) -> Self {
+        Self(InnerSledEditor::Active(ActiveSledEditor::new_empty(
+            subnet,
+            reserved_underlay_ranges,
+        )))
     }
 
     pub fn finalize(self) -> EditedSled {
@@ -199,7 +208,7 @@ impl SledEditor {
 
     pub fn state(&self) -> SledState {
         match &self.0 {
-            InnerSledEditor::Active(_) => SledState::Active,
+            InnerSledEditor::Active(editor) => editor.state(),
             InnerSledEditor::Decommissioned(_) => SledState::Decommissioned,
         }
     }
@@ -230,9 +239,10 @@ impl SledEditor {
                 // below, we'll be left in the active state with an empty sled
                 // editor), but omicron in general is not panic safe and aborts
                 // on panic. Plus `finalize()` should never panic.
-                let mut stolen = ActiveSledEditor::new_empty(Ipv6Subnet::new(
-                    Ipv6Addr::LOCALHOST,
-                ));
+                let mut stolen = ActiveSledEditor::new_empty(
+                    Ipv6Subnet::new(Ipv6Addr::LOCALHOST),
+                    &[],
+                );
                 mem::swap(editor, &mut stolen);
 
                 let mut finalized = stolen.finalize();
@@ -391,6 +401,26 @@ impl SledEditor {
         self.as_active_mut()?.mark_expunged_zone_ready_for_cleanup(zone_id)
     }
 
+    /// Cordons or uncordons a zone, returning `true` if its disposition
+    /// changed.
+    pub fn set_zone_cordoned(
+        &mut self,
+        zone_id: &OmicronZoneUuid,
+        cordoned: bool,
+    ) -> Result<bool, SledEditError> {
+        self.as_active_mut()?.set_zone_cordoned(zone_id, cordoned)
+    }
+
+    /// Sets the quota of an in-service dataset, returning `true` if it
+    /// changed.
+    pub fn set_dataset_quota(
+        &mut self,
+        dataset_id: DatasetUuid,
+        quota: Option<ByteCount>,
+    ) -> Result<bool, SledEditError> {
+        self.as_active_mut()?.set_dataset_quota(dataset_id, quota)
+    }
+
     /// Sets the image source for a zone, returning the old image source.
     pub fn set_zone_image_source(
         &mut self,
@@ -451,6 +481,14 @@ impl SledEditor {
         Ok(())
     }
 
+    pub fn set_quiesced(
+        &mut self,
+        quiesced: bool,
+    ) -> Result<(), SledEditError> {
+        self.as_active_mut()?.set_quiesced(quiesced);
+        Ok(())
+    }
+
     /// Backwards compatibility / test helper: If we're given a blueprint that
     /// has zones but wasn't created via `SledEditor`, it might not have
     /// datasets for all its zones. This method backfills them.
@@ -480,6 +518,7 @@ struct ActiveSledEditor {
     disks: DisksEditor,
     datasets: DatasetsEditor,
     remove_mupdate_override: ScalarEditor<Option<MupdateOverrideUuid>>,
+    quiesced: ScalarEditor<bool>,
     host_phase_2: HostPhase2Editor,
     debug_force_generation_bump: bool,
 }
@@ -494,6 +533,7 @@ pub(crate) struct EditedSled {
 impl ActiveSledEditor {
     pub fn new(
         subnet: Ipv6Subnet<SLED_PREFIX>,
+        reserved_underlay_ranges: &[Ipv6Range],
         config: BlueprintSledConfig,
     ) -> Result<Self, SledInputError> {
         let zones =
@@ -508,7 +548,9 @@ impl ActiveSledEditor {
 
         Ok(Self {
             underlay_ip_allocator: SledUnderlayIpAllocator::new(
-                subnet, zone_ips,
+                subnet,
+                reserved_underlay_ranges,
+                zone_ips,
             ),
             incoming_sled_agent_generation: config.sled_agent_generation,
             zones,
@@ -517,18 +559,25 @@ impl ActiveSledEditor {
             remove_mupdate_override: ScalarEditor::new(
                 config.remove_mupdate_override,
             ),
+            quiesced: ScalarEditor::new(config.state == SledState::Quiesced),
             host_phase_2: HostPhase2Editor::new(config.host_phase_2),
             debug_force_generation_bump: false,
         })
     }
 
-    pub fn new_empty(subnet: Ipv6Subnet<SLED_PREFIX>) -> Self {
+    pub fn new_empty(
+        subnet: Ipv6Subnet<SLED_PREFIX>,
+        reserved_underlay_ranges: &[Ipv6Range],
+    ) -> Self {
         // Creating the underlay IP allocator can only fail if we have a zone
         // with an IP outside the sled subnet, but we don't have any zones at
         // all, so this can't fail. Match explicitly to guard against this error
         // turning into an enum and getting new variants we'd need to check.
-        let underlay_ip_allocator =
-            SledUnderlayIpAllocator::new(subnet, iter::empty());
+        let underlay_ip_allocator = SledUnderlayIpAllocator::new(
+            subnet,
+            reserved_underlay_ranges,
+            iter::empty(),
+        );
 
         Self {
             underlay_ip_allocator,
@@ -537,6 +586,7 @@ impl ActiveSledEditor {
             disks: DisksEditor::empty(),
             datasets: DatasetsEditor::empty(),
             remove_mupdate_override: ScalarEditor::new(None),
+            quiesced: ScalarEditor::new(false),
             host_phase_2: HostPhase2Editor::new(
                 BlueprintHostPhase2DesiredSlots::current_contents(),
             ),
@@ -550,15 +600,19 @@ impl ActiveSledEditor {
         let (zones, zones_counts) = self.zones.finalize();
         let remove_mupdate_override_is_modified =
             self.remove_mupdate_override.is_modified();
+        let quiesced_is_modified = self.quiesced.is_modified();
         let changed_host_phase_2 = self.host_phase_2.is_modified();
         let mut sled_agent_generation = self.incoming_sled_agent_generation;
 
         let scalar_edits = EditedSledScalarEdits {
             debug_force_generation_bump: self.debug_force_generation_bump,
             remove_mupdate_override: remove_mupdate_override_is_modified,
+            quiesced: quiesced_is_modified,
         };
 
         // Bump the generation if we made any changes of concern to sled-agent.
+        // (Quiescing is not one of them: it only affects what the planner is
+        // willing to place on the sled.)
         if self.debug_force_generation_bump
             || disks_counts.has_nonzero_counts()
             || datasets_counts.has_nonzero_counts()
@@ -571,7 +625,11 @@ impl ActiveSledEditor {
 
         EditedSled {
             config: BlueprintSledConfig {
-                state: SledState::Active,
+                state: if self.quiesced.finalize() {
+                    SledState::Quiesced
+                } else {
+                    SledState::Active
+                },
                 sled_agent_generation,
                 disks,
                 datasets,
@@ -592,9 +650,10 @@ impl ActiveSledEditor {
 
     fn validate_decommisionable(&self) -> Result<(), SledEditError> {
         // A sled is only decommissionable if all its zones have been expunged
-        // (i.e., there are no zones left with an in-service disposition).
+        // (i.e., there are no zones left with an in-service or cordoned
+        // disposition).
         if let Some(zone) =
-            self.zones(BlueprintZoneDisposition::is_in_service).next()
+            self.zones(BlueprintZoneDisposition::should_be_running).next()
         {
             return Err(SledEditError::NonDecommissionableZoneNotExpunged {
                 zone_id: zone.id,
@@ -768,6 +827,24 @@ impl ActiveSledEditor {
         Ok(did_mark_ready)
     }
 
+    pub fn set_zone_cordoned(
+        &mut self,
+        zone_id: &OmicronZoneUuid,
+        cordoned: bool,
+    ) -> Result<bool, SledEditError> {
+        let changed = self.zones.set_zone_cordoned(zone_id, cordoned)?;
+        Ok(changed)
+    }
+
+    pub fn set_dataset_quota(
+        &mut self,
+        dataset_id: DatasetUuid,
+        quota: Option<ByteCount>,
+    ) -> Result<bool, SledEditError> {
+        let changed = self.datasets.set_quota(dataset_id, quota)?;
+        Ok(changed)
+    }
+
     /// Set the image source for a zone, returning the old image source.
     pub fn set_zone_image_source(
         &mut self,
@@ -803,7 +880,9 @@ impl ActiveSledEditor {
         &mut self,
         rng: &mut SledPlannerRng,
     ) -> Result<(), SledEditError> {
-        for zone in self.zones.zones(BlueprintZoneDisposition::is_in_service) {
+        for zone in
+            self.zones.zones(BlueprintZoneDisposition::should_be_running)
+        {
             ZoneDatasetConfigs::new(&self.disks, zone)?
                 .ensure_in_service(&mut self.datasets, rng);
         }
@@ -879,7 +958,7 @@ impl ActiveSledEditor {
                 // Set all zone image sources to InstallDataset. This is an
                 // acknowledgement of the current state of the world.
                 let zone_ids: Vec<_> = self
-                    .zones(BlueprintZoneDisposition::is_in_service)
+                    .zones(BlueprintZoneDisposition::should_be_running)
                     .map(|zone| (zone.id, zone.kind()))
                     .collect();
 
@@ -1041,6 +1120,18 @@ impl ActiveSledEditor {
         self.remove_mupdate_override.set_value(remove_mupdate_override);
     }
 
+    pub fn state(&self) -> SledState {
+        if *self.quiesced.value() {
+            SledState::Quiesced
+        } else {
+            SledState::Active
+        }
+    }
+
+    pub fn set_quiesced(&mut self, quiesced: bool) {
+        self.quiesced.set_value(quiesced);
+    }
+
     /// Debug method to force a sled agent generation number to be bumped, even
     /// if there are no changes to the sled.
     ///
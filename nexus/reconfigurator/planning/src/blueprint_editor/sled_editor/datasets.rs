@@ -38,6 +38,8 @@ pub enum DatasetsEditError {
          zpool {zpool_id}, kind {kind}"
     )]
     ExpungeNonexistentDataset { zpool_id: ZpoolUuid, kind: DatasetKind },
+    #[error("tried to set quota on nonexistent dataset {id}")]
+    SetQuotaNonexistentDataset { id: DatasetUuid },
 }
 
 /// Container for most of the information needed to construct a
@@ -228,6 +230,27 @@ impl DatasetsEditor {
         Ok(())
     }
 
+    /// Set the quota of the in-service dataset with the given ID.
+    ///
+    /// Returns `true` if the quota changed.
+    pub fn set_quota(
+        &mut self,
+        id: DatasetUuid,
+        quota: Option<ByteCount>,
+    ) -> Result<bool, DatasetsEditError> {
+        let Some(mut dataset) = self.datasets.get_mut(&id).filter(|dataset| {
+            dataset.disposition == BlueprintDatasetDisposition::InService
+        }) else {
+            return Err(DatasetsEditError::SetQuotaNonexistentDataset { id });
+        };
+        if dataset.quota == quota {
+            return Ok(false);
+        }
+        dataset.quota = quota;
+        self.counts.updated += 1;
+        Ok(true)
+    }
+
     pub fn expunge_all_on_zpool(&mut self, zpool: &ZpoolUuid) -> usize {
         let Some(by_kind) = self.in_service_by_zpool_and_kind.remove(zpool)
         else {
@@ -285,6 +308,13 @@ impl DatasetsEditor {
             }
         };
 
+        // Quotas on some datasets are sized based on their observed usage
+        // (see `set_quota()`); keep whatever quota such a dataset already has.
+        let quota = match self.datasets.get(&id) {
+            Some(prev) if kind.has_usage_based_quota() => prev.quota,
+            _ => quota,
+        };
+
         let dataset = BlueprintDatasetConfig {
             disposition: BlueprintDatasetDisposition::InService,
             id,
@@ -6,6 +6,7 @@
 
 use ipnet::IpAdd;
 use omicron_common::address::CP_SERVICES_RESERVED_ADDRESSES;
+use omicron_common::address::Ipv6Range;
 use omicron_common::address::Ipv6Subnet;
 use omicron_common::address::SLED_PREFIX;
 use omicron_common::address::SLED_RESERVED_ADDRESSES;
@@ -19,20 +20,30 @@ use std::net::Ipv6Addr;
 /// next one.  This will never reuse old IPs.  That avoids a bunch of
 /// operational issues.  It does mean we will eventually run out of IPs.  But we
 /// do have a big space right now (2^16).
+///
+/// Addresses within any of the operator-reserved ranges supplied at
+/// construction are never handed out.
 // This overlaps with the bump allocator that's used in RSS.  That one is not
 // general enough to use here, though this one could potentially be used there.
 #[derive(Debug)]
 pub(crate) struct SledUnderlayIpAllocator {
     last: Ipv6Addr,
     maximum: Ipv6Addr,
+    /// reserved ranges that overlap this allocator's range, sorted
+    reserved: Vec<Ipv6Range>,
 }
 
 impl SledUnderlayIpAllocator {
     /// Create a new allocator for the given sled subnet that reserves all the
-    /// specified IPs.
+    /// specified IPs and never allocates from any of `reserved_ranges`.
     ///
-    /// Fails if any of the specified IPs are not part of the sled subnet.
-    pub fn new<I>(sled_subnet: Ipv6Subnet<SLED_PREFIX>, in_use_ips: I) -> Self
+    /// Reserved ranges (or parts of ranges) outside the sled subnet are
+    /// ignored.
+    pub fn new<I>(
+        sled_subnet: Ipv6Subnet<SLED_PREFIX>,
+        reserved_ranges: &[Ipv6Range],
+        in_use_ips: I,
+    ) -> Self
     where
         I: Iterator<Item = Ipv6Addr>,
     {
@@ -55,7 +66,14 @@ impl SledUnderlayIpAllocator {
         assert!(sled_subnet.net().contains(minimum));
         assert!(sled_subnet.net().contains(maximum));
 
-        let mut slf = Self { last: minimum, maximum };
+        let mut reserved: Vec<_> = reserved_ranges
+            .iter()
+            .filter(|range| range.last > minimum && range.first < maximum)
+            .copied()
+            .collect();
+        reserved.sort();
+
+        let mut slf = Self { last: minimum, maximum, reserved };
         for ip in in_use_ips {
             slf.mark_as_allocated(ip);
         }
@@ -83,19 +101,31 @@ impl SledUnderlayIpAllocator {
 
     /// Allocate an unused address from this allocator's range
     pub fn alloc(&mut self) -> Option<Ipv6Addr> {
-        let next = self.last.saturating_add(1);
-        if next == self.last {
-            // We ran out of the entire IPv6 address space.
-            return None;
-        }
-
-        if next >= self.maximum {
-            // We ran out of our allotted range.
-            return None;
+        loop {
+            let next = self.last.saturating_add(1);
+            if next == self.last {
+                // We ran out of the entire IPv6 address space.
+                return None;
+            }
+
+            if next >= self.maximum {
+                // We ran out of our allotted range.
+                return None;
+            }
+
+            // If `next` is reserved, skip past the whole reserved range and
+            // try again. Like everything else we skip over, the reserved
+            // addresses are never revisited.
+            if let Some(range) =
+                self.reserved.iter().find(|range| range.contains(next))
+            {
+                self.last = range.last;
+                continue;
+            }
+
+            self.last = next;
+            return Some(next);
         }
-
-        self.last = next;
-        Some(next)
     }
 }
 
@@ -115,8 +145,11 @@ mod test {
         ];
         let reserved_ips = reserved.iter().copied().collect::<BTreeSet<_>>();
 
-        let mut allocator =
-            SledUnderlayIpAllocator::new(sled_subnet, reserved.iter().copied());
+        let mut allocator = SledUnderlayIpAllocator::new(
+            sled_subnet,
+            &[],
+            reserved.iter().copied(),
+        );
 
         let mut allocated = Vec::new();
         for _ in 0..16 {
@@ -153,4 +186,65 @@ mod test {
             .to_vec()
         );
     }
+    #[test]
+    fn test_reserved_ranges() {
+        let sled_subnet = Ipv6Subnet::new("fd00::d0".parse().unwrap());
+        let reserved_ranges = [
+            Ipv6Range::new(
+                "fd00::22".parse().unwrap(),
+                "fd00::24".parse().unwrap(),
+            )
+            .unwrap(),
+            Ipv6Range::from("fd00::26".parse::<Ipv6Addr>().unwrap()),
+            // Entirely outside the sled subnet: ignored.
+            Ipv6Range::new(
+                "fd00:1::".parse().unwrap(),
+                "fd00:1::ffff".parse().unwrap(),
+            )
+            .unwrap(),
+        ];
+
+        let mut allocator = SledUnderlayIpAllocator::new(
+            sled_subnet,
+            &reserved_ranges,
+            std::iter::empty(),
+        );
+
+        let mut allocated = Vec::new();
+        for _ in 0..8 {
+            let addr = allocator.alloc().expect("allocated IP");
+            println!("allocated: {addr}");
+            assert!(
+                !reserved_ranges.iter().any(|range| range.contains(addr)),
+                "allocated reserved address {addr}"
+            );
+            allocated.push(addr);
+        }
+        assert_eq!(
+            allocated,
+            [
+                "fd00::21".parse::<Ipv6Addr>().unwrap(),
+                "fd00::25".parse().unwrap(),
+                "fd00::27".parse().unwrap(),
+                "fd00::28".parse().unwrap(),
+                "fd00::29".parse().unwrap(),
+                "fd00::2a".parse().unwrap(),
+                "fd00::2b".parse().unwrap(),
+                "fd00::2c".parse().unwrap(),
+            ]
+            .to_vec()
+        );
+
+        // Reserving the rest of the sled's range leaves nothing to allocate.
+        let mut allocator = SledUnderlayIpAllocator::new(
+            sled_subnet,
+            &[Ipv6Range::new(
+                "fd00::2d".parse().unwrap(),
+                "fd00::ffff".parse().unwrap(),
+            )
+            .unwrap()],
+            allocated.iter().copied(),
+        );
+        assert_eq!(allocator.alloc(), None);
+    }
 }
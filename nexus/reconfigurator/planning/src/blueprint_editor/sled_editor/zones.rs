@@ -25,6 +25,10 @@ pub enum ZonesEditError {
     MarkNonexistentZoneReadyForCleanup { id: OmicronZoneUuid },
     #[error("tried to mark a non-expunged zone as ready for cleanup: {id}")]
     MarkNonExpungedZoneReadyForCleanup { id: OmicronZoneUuid },
+    #[error("tried to cordon or uncordon nonexistent zone {id}")]
+    CordonNonexistentZone { id: OmicronZoneUuid },
+    #[error("tried to cordon or uncordon expunged zone {id}")]
+    CordonExpungedZone { id: OmicronZoneUuid },
     #[error(
         "tried to set image source for nonexistent zone {id} to {image_source:?}"
     )]
@@ -140,7 +144,8 @@ impl ZonesEditor {
         })?;
 
         match &mut config.disposition {
-            BlueprintZoneDisposition::InService => {
+            BlueprintZoneDisposition::InService
+            | BlueprintZoneDisposition::Cordoned => {
                 Err(ZonesEditError::MarkNonExpungedZoneReadyForCleanup {
                     id: *zone_id,
                 })
@@ -155,6 +160,40 @@ impl ZonesEditor {
         }
     }
 
+    /// Cordon (if `cordoned` is true) or uncordon (if false) a zone, returning
+    /// `true` if the zone's disposition changed.
+    ///
+    /// Like `mark_expunged_zone_ready_for_cleanup()`, this does not result in
+    /// an increased generation when `finalize()` is called: cordoned zones are
+    /// still sent to sled-agent, so its config is unaffected.
+    ///
+    /// # Errors
+    ///
+    /// Fails if this zone ID does not exist or has been expunged.
+    pub fn set_zone_cordoned(
+        &mut self,
+        zone_id: &OmicronZoneUuid,
+        cordoned: bool,
+    ) -> Result<bool, ZonesEditError> {
+        let mut config = self.zones.get_mut(zone_id).ok_or_else(|| {
+            ZonesEditError::CordonNonexistentZone { id: *zone_id }
+        })?;
+
+        let new_disposition = match (config.disposition, cordoned) {
+            (BlueprintZoneDisposition::Expunged { .. }, _) => {
+                return Err(ZonesEditError::CordonExpungedZone {
+                    id: *zone_id,
+                });
+            }
+            (_, true) => BlueprintZoneDisposition::Cordoned,
+            (_, false) => BlueprintZoneDisposition::InService,
+        };
+        let changed = config.disposition != new_disposition;
+        config.disposition = new_disposition;
+
+        Ok(changed)
+    }
+
     /// Set the image source for a zone, returning the old image source.
     pub fn set_zone_image_source(
         &mut self,
@@ -183,7 +222,8 @@ impl ZonesEditor {
         current_generation: Generation,
     ) -> bool {
         match config.disposition {
-            BlueprintZoneDisposition::InService => {
+            BlueprintZoneDisposition::InService
+            | BlueprintZoneDisposition::Cordoned => {
                 config.disposition = BlueprintZoneDisposition::Expunged {
                     as_of_generation: current_generation.next(),
                     ready_for_cleanup: false,
@@ -0,0 +1,761 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Validation of blueprints before they're made the target
+//!
+//! A blueprint that's made the target is executed as-is, so a broken one can
+//! do real damage before anyone notices. This module checks the invariants
+//! that every target blueprint must satisfy: the fatal problems reported by
+//! [`Blippy`] (like duplicate underlay IPs), the presence of DNS zones, that
+//! no two internal DNS zones that might be running share a subnet, that every
+//! zone and dataset that should exist sits on an in-service disk, and that
+//! generation numbers only move forward relative to the parent blueprint.
+//!
+//! Separately, [`check_against_inventory()`] compares a blueprint against what
+//! the latest inventory collection found on the rack: that its sleds are
+//! reporting, that its disks exist, and that nothing else is using the
+//! underlay IPs of its zones.
+
+use nexus_reconfigurator_blippy::Blippy;
+use nexus_reconfigurator_blippy::BlippyNote;
+use nexus_reconfigurator_blippy::BlippyReportSortKey;
+use nexus_reconfigurator_blippy::BlippySeverity;
+use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintZoneDisposition;
+use nexus_types::deployment::BlueprintZoneType;
+use nexus_types::external_api::views::SledState;
+use nexus_types::inventory::Collection;
+use omicron_common::address::DnsSubnet;
+use omicron_common::api::external::Generation;
+use omicron_common::disk::DiskIdentity;
+use omicron_common::policy::RESERVED_INTERNAL_DNS_REDUNDANCY;
+use omicron_uuid_kinds::BlueprintUuid;
+use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::DatasetUuid;
+use omicron_uuid_kinds::OmicronZoneUuid;
+use omicron_uuid_kinds::PhysicalDiskUuid;
+use omicron_uuid_kinds::SledUuid;
+use omicron_uuid_kinds::ZpoolUuid;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::net::Ipv6Addr;
+
+/// A single invariant violated by a blueprint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlueprintValidationError {
+    /// Blippy found a fatal problem with the blueprint.
+    Blippy(BlippyNote),
+    /// The blueprint has in-service zones, but no internal DNS zones.
+    NoInternalDnsZones,
+    /// The blueprint has more internal DNS zones than there are subnets
+    /// reserved for them.
+    TooManyInternalDnsZones { count: usize },
+    /// Two internal DNS zones that might both be running use the same
+    /// reserved DNS subnet.
+    ///
+    /// A subnet can only be handed to a replacement zone once the zone that
+    /// had it is known to be gone (i.e., it's ready for cleanup, or its sled
+    /// is decommissioned).
+    InternalDnsSubnetInUse {
+        subnet: DnsSubnet,
+        zone1: OmicronZoneUuid,
+        zone2: OmicronZoneUuid,
+    },
+    /// The parent blueprint had external DNS zones, but this one has none.
+    NoExternalDnsZones,
+    /// A zone that should be running uses a zpool that isn't on one of its
+    /// sled's in-service disks.
+    ZoneOnUnavailableZpool {
+        sled_id: SledUuid,
+        zone_id: OmicronZoneUuid,
+        zpool: ZpoolUuid,
+    },
+    /// An in-service dataset is on a zpool that isn't on one of its sled's
+    /// in-service disks.
+    DatasetOnUnavailableZpool {
+        sled_id: SledUuid,
+        dataset_id: DatasetUuid,
+        zpool: ZpoolUuid,
+    },
+    /// A generation number is lower than the parent blueprint's.
+    GenerationRegressed {
+        what: &'static str,
+        parent: Generation,
+        child: Generation,
+    },
+    /// A sled's generation is lower than in the parent blueprint.
+    SledGenerationRegressed {
+        sled_id: SledUuid,
+        parent: Generation,
+        child: Generation,
+    },
+    /// A sled's configuration changed from the parent blueprint without a
+    /// new generation, so its sled-agent would never see the change.
+    SledChangedWithoutGeneration { sled_id: SledUuid, generation: Generation },
+}
+
+impl fmt::Display for BlueprintValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blippy(note) => write!(
+                f,
+                "{}: {}",
+                note.kind.display_component(),
+                note.kind.display_subkind(),
+            ),
+            Self::NoInternalDnsZones => {
+                write!(f, "blueprint has no in-service internal DNS zones")
+            }
+            Self::TooManyInternalDnsZones { count } => write!(
+                f,
+                "blueprint has {count} internal DNS zones, \
+                 but at most {RESERVED_INTERNAL_DNS_REDUNDANCY} are allowed",
+            ),
+            Self::InternalDnsSubnetInUse { subnet, zone1, zone2 } => write!(
+                f,
+                "internal DNS zones {zone1} and {zone2} might both be \
+                 running on subnet {}",
+                subnet.subnet().net(),
+            ),
+            Self::NoExternalDnsZones => write!(
+                f,
+                "blueprint has no in-service external DNS zones, \
+                 but its parent does",
+            ),
+            Self::ZoneOnUnavailableZpool { sled_id, zone_id, zpool } => {
+                write!(
+                    f,
+                    "sled {sled_id}: zone {zone_id} uses zpool {zpool}, \
+                     which is not on an in-service disk",
+                )
+            }
+            Self::DatasetOnUnavailableZpool { sled_id, dataset_id, zpool } => {
+                write!(
+                    f,
+                    "sled {sled_id}: dataset {dataset_id} is on zpool \
+                     {zpool}, which is not on an in-service disk",
+                )
+            }
+            Self::GenerationRegressed { what, parent, child } => write!(
+                f,
+                "{what} went backwards from {parent} (in parent) to {child}",
+            ),
+            Self::SledGenerationRegressed { sled_id, parent, child } => {
+                write!(
+                    f,
+                    "sled {sled_id}: generation went backwards \
+                     from {parent} (in parent) to {child}",
+                )
+            }
+            Self::SledChangedWithoutGeneration { sled_id, generation } => {
+                write!(
+                    f,
+                    "sled {sled_id}: configuration changed from parent \
+                     without bumping its generation ({generation})",
+                )
+            }
+        }
+    }
+}
+
+/// The invariants violated by a blueprint, as found by [`validate()`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct BlueprintValidationErrors {
+    pub blueprint_id: BlueprintUuid,
+    pub errors: Vec<BlueprintValidationError>,
+}
+
+impl fmt::Display for BlueprintValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "blueprint {} is invalid: ", self.blueprint_id)?;
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `blueprint` satisfies the invariants required of every target
+/// blueprint
+///
+/// `parent` should be the blueprint's parent, if it has one; checks that
+/// compare the blueprint against its parent are skipped without it.
+pub fn validate(
+    blueprint: &Blueprint,
+    parent: Option<&Blueprint>,
+) -> Result<(), BlueprintValidationErrors> {
+    let mut errors = Vec::new();
+
+    let report = Blippy::new(blueprint).into_report(BlippyReportSortKey::Kind);
+    errors.extend(
+        report
+            .notes()
+            .iter()
+            .filter(|note| note.severity == BlippySeverity::Fatal)
+            .cloned()
+            .map(BlueprintValidationError::Blippy),
+    );
+
+    check_dns_zones(blueprint, parent, &mut errors);
+    check_internal_dns_subnets(blueprint, &mut errors);
+    check_zpools_in_service(blueprint, &mut errors);
+    if let Some(parent) = parent {
+        check_generations(blueprint, parent, &mut errors);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(BlueprintValidationErrors { blueprint_id: blueprint.id, errors })
+    }
+}
+
+fn check_dns_zones(
+    blueprint: &Blueprint,
+    parent: Option<&Blueprint>,
+    errors: &mut Vec<BlueprintValidationError>,
+) {
+    let count_zones =
+        |blueprint: &Blueprint, is_dns: fn(&BlueprintZoneType) -> bool| {
+            blueprint
+                .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+                .filter(|(_, zone)| is_dns(&zone.zone_type))
+                .count()
+        };
+
+    // Nothing can find anything in a rack without internal DNS.
+    let has_zones = blueprint
+        .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+        .next()
+        .is_some();
+    let internal_dns =
+        count_zones(blueprint, BlueprintZoneType::is_internal_dns);
+    if has_zones && internal_dns == 0 {
+        errors.push(BlueprintValidationError::NoInternalDnsZones);
+    }
+    if internal_dns > RESERVED_INTERNAL_DNS_REDUNDANCY {
+        errors.push(BlueprintValidationError::TooManyInternalDnsZones {
+            count: internal_dns,
+        });
+    }
+
+    // Not every rack runs external DNS, but one that does can't reach its
+    // external API without it.
+    if let Some(parent) = parent {
+        if count_zones(parent, BlueprintZoneType::is_external_dns) > 0
+            && count_zones(blueprint, BlueprintZoneType::is_external_dns) == 0
+        {
+            errors.push(BlueprintValidationError::NoExternalDnsZones);
+        }
+    }
+}
+
+fn check_internal_dns_subnets(
+    blueprint: &Blueprint,
+    errors: &mut Vec<BlueprintValidationError>,
+) {
+    // Blippy already catches zones that should be running on the same address.
+    // This also covers expunged zones that haven't been confirmed gone, whose
+    // subnets aren't free yet. Sleds that are decommissioned aren't running
+    // anything.
+    let mut subnets: BTreeMap<DnsSubnet, OmicronZoneUuid> = BTreeMap::new();
+    for sled in blueprint
+        .sleds
+        .values()
+        .filter(|sled| sled.state != SledState::Decommissioned)
+    {
+        for zone in sled.zones.iter().filter(|zone| {
+            zone.disposition.could_be_running()
+                && zone.zone_type.is_internal_dns()
+        }) {
+            let subnet = DnsSubnet::from_addr(zone.underlay_ip());
+            if let Some(&previous) = subnets.get(&subnet) {
+                errors.push(BlueprintValidationError::InternalDnsSubnetInUse {
+                    subnet,
+                    zone1: previous,
+                    zone2: zone.id,
+                });
+            } else {
+                subnets.insert(subnet, zone.id);
+            }
+        }
+    }
+}
+
+fn check_zpools_in_service(
+    blueprint: &Blueprint,
+    errors: &mut Vec<BlueprintValidationError>,
+) {
+    for (&sled_id, sled) in &blueprint.sleds {
+        let zpools: BTreeSet<ZpoolUuid> = sled
+            .disks
+            .iter()
+            .filter(|disk| disk.disposition.is_in_service())
+            .map(|disk| disk.pool_id)
+            .collect();
+
+        for zone in sled
+            .zones
+            .iter()
+            .filter(|zone| zone.disposition.should_be_running())
+        {
+            let zone_zpools = std::iter::once(&zone.filesystem_pool)
+                .chain(zone.zone_type.durable_zpool());
+            for zpool in zone_zpools {
+                if !zpools.contains(&zpool.id()) {
+                    errors.push(
+                        BlueprintValidationError::ZoneOnUnavailableZpool {
+                            sled_id,
+                            zone_id: zone.id,
+                            zpool: zpool.id(),
+                        },
+                    );
+                }
+            }
+        }
+
+        for dataset in sled
+            .datasets
+            .iter()
+            .filter(|dataset| dataset.disposition.is_in_service())
+        {
+            if !zpools.contains(&dataset.pool.id()) {
+                errors.push(
+                    BlueprintValidationError::DatasetOnUnavailableZpool {
+                        sled_id,
+                        dataset_id: dataset.id,
+                        zpool: dataset.pool.id(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+fn check_generations(
+    blueprint: &Blueprint,
+    parent: &Blueprint,
+    errors: &mut Vec<BlueprintValidationError>,
+) {
+    for (what, parent_gen, child_gen) in [
+        (
+            "internal DNS version",
+            parent.internal_dns_version,
+            blueprint.internal_dns_version,
+        ),
+        (
+            "external DNS version",
+            parent.external_dns_version,
+            blueprint.external_dns_version,
+        ),
+        (
+            "Nexus generation",
+            parent.nexus_generation,
+            blueprint.nexus_generation,
+        ),
+        (
+            "target release minimum generation",
+            parent.target_release_minimum_generation,
+            blueprint.target_release_minimum_generation,
+        ),
+        (
+            "oximeter read policy version",
+            parent.oximeter_read_version,
+            blueprint.oximeter_read_version,
+        ),
+    ] {
+        if child_gen < parent_gen {
+            errors.push(BlueprintValidationError::GenerationRegressed {
+                what,
+                parent: parent_gen,
+                child: child_gen,
+            });
+        }
+    }
+
+    for (&sled_id, sled) in &blueprint.sleds {
+        let Some(parent_sled) = parent.sleds.get(&sled_id) else {
+            continue;
+        };
+        let (parent_gen, child_gen) =
+            (parent_sled.sled_agent_generation, sled.sled_agent_generation);
+        if child_gen < parent_gen {
+            errors.push(BlueprintValidationError::SledGenerationRegressed {
+                sled_id,
+                parent: parent_gen,
+                child: child_gen,
+            });
+        } else if child_gen == parent_gen
+            && sled.clone().into_in_service_sled_config()
+                != parent_sled.clone().into_in_service_sled_config()
+        {
+            errors.push(
+                BlueprintValidationError::SledChangedWithoutGeneration {
+                    sled_id,
+                    generation: child_gen,
+                },
+            );
+        }
+    }
+}
+
+/// Something inventory found that's at odds with a blueprint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlueprintInventoryFinding {
+    /// An active sled in the blueprint didn't report to inventory.
+    SledNotReporting { sled_id: SledUuid },
+    /// An in-service disk in the blueprint wasn't found on its sled.
+    DiskMissing {
+        sled_id: SledUuid,
+        disk_id: PhysicalDiskUuid,
+        identity: DiskIdentity,
+    },
+    /// A zone that should be running has an underlay IP that inventory found
+    /// in use by something else.
+    UnderlayIpInUse {
+        sled_id: SledUuid,
+        zone_id: OmicronZoneUuid,
+        ip: Ipv6Addr,
+        used_by: InventoryIpUser,
+    },
+}
+
+/// Something that inventory found using an underlay IP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryIpUser {
+    SledAgent { sled_id: SledUuid },
+    Zone { sled_id: SledUuid, zone_id: OmicronZoneUuid },
+}
+
+impl fmt::Display for InventoryIpUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SledAgent { sled_id } => {
+                write!(f, "the sled-agent of sled {sled_id}")
+            }
+            Self::Zone { sled_id, zone_id } => {
+                write!(f, "zone {zone_id} on sled {sled_id}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for BlueprintInventoryFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SledNotReporting { sled_id } => {
+                write!(f, "sled {sled_id}: not found in inventory")
+            }
+            Self::DiskMissing { sled_id, disk_id, identity } => write!(
+                f,
+                "sled {sled_id}: disk {disk_id} ({} {} {}) not found in \
+                 inventory",
+                identity.vendor, identity.model, identity.serial,
+            ),
+            Self::UnderlayIpInUse { sled_id, zone_id, ip, used_by } => write!(
+                f,
+                "sled {sled_id}: zone {zone_id} has underlay IP {ip}, \
+                 which inventory found in use by {used_by}",
+            ),
+        }
+    }
+}
+
+/// The findings from comparing a blueprint against inventory, as found by
+/// [`check_against_inventory()`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct BlueprintInventoryFindings {
+    pub blueprint_id: BlueprintUuid,
+    pub collection_id: CollectionUuid,
+    pub findings: Vec<BlueprintInventoryFinding>,
+}
+
+impl fmt::Display for BlueprintInventoryFindings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "blueprint {} disagrees with inventory collection {}: ",
+            self.blueprint_id, self.collection_id,
+        )?;
+        for (i, finding) in self.findings.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{finding}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks `blueprint` against what the inventory collection `collection`
+/// found on the rack
+///
+/// Unlike [`validate()`], this can only tell what was true when the collection
+/// was taken, so a finding may be stale (e.g., for a sled that was briefly
+/// unreachable).
+pub fn check_against_inventory(
+    blueprint: &Blueprint,
+    collection: &Collection,
+) -> Result<(), BlueprintInventoryFindings> {
+    let mut findings = Vec::new();
+
+    // Find out what's using each underlay IP, according to inventory.  Zones
+    // may show up in both a sled's ledger and its last reconciliation.
+    let mut ips_in_use = BTreeMap::new();
+    for sled_agent in &collection.sled_agents {
+        let sled_id = sled_agent.sled_id;
+        ips_in_use.insert(
+            *sled_agent.sled_agent_address.ip(),
+            InventoryIpUser::SledAgent { sled_id },
+        );
+        let ledgered = sled_agent
+            .ledgered_sled_config
+            .iter()
+            .flat_map(|config| config.zones.iter());
+        let running = sled_agent
+            .last_reconciliation
+            .iter()
+            .flat_map(|reconciliation| reconciliation.running_omicron_zones());
+        for zone in ledgered.chain(running) {
+            ips_in_use
+                .entry(zone.underlay_ip())
+                .or_insert(InventoryIpUser::Zone { sled_id, zone_id: zone.id });
+        }
+    }
+
+    for (&sled_id, sled) in &blueprint.sleds {
+        if sled.state != SledState::Active {
+            continue;
+        }
+        let Some(sled_agent) = collection.sled_agents.get(&sled_id) else {
+            findings
+                .push(BlueprintInventoryFinding::SledNotReporting { sled_id });
+            continue;
+        };
+
+        let disks: BTreeSet<&DiskIdentity> =
+            sled_agent.disks.iter().map(|disk| &disk.identity).collect();
+        for disk in
+            sled.disks.iter().filter(|disk| disk.disposition.is_in_service())
+        {
+            if !disks.contains(&disk.identity) {
+                findings.push(BlueprintInventoryFinding::DiskMissing {
+                    sled_id,
+                    disk_id: disk.id,
+                    identity: disk.identity.clone(),
+                });
+            }
+        }
+
+        for zone in sled
+            .zones
+            .iter()
+            .filter(|zone| zone.disposition.should_be_running())
+        {
+            let ip = zone.underlay_ip();
+            match ips_in_use.get(&ip) {
+                None => (),
+                Some(InventoryIpUser::Zone { zone_id, .. })
+                    if *zone_id == zone.id => {}
+                Some(&used_by) => {
+                    findings.push(BlueprintInventoryFinding::UnderlayIpInUse {
+                        sled_id,
+                        zone_id: zone.id,
+                        ip,
+                        used_by,
+                    });
+                }
+            }
+        }
+    }
+
+    if findings.is_empty() {
+        Ok(())
+    } else {
+        Err(BlueprintInventoryFindings {
+            blueprint_id: blueprint.id,
+            collection_id: collection.id,
+            findings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::example::example;
+    use nexus_types::deployment::BlueprintPhysicalDiskDisposition;
+    use omicron_common::api::external::ByteCount;
+    use omicron_test_utils::dev::test_setup_log;
+
+    /// Returns a child of `parent` that's identical to it
+    fn child_of(parent: &Blueprint) -> Blueprint {
+        Blueprint {
+            id: BlueprintUuid::new_v4(),
+            parent_blueprint_id: Some(parent.id),
+            ..parent.clone()
+        }
+    }
+
+    #[test]
+    fn test_example_blueprint_is_valid() {
+        static TEST_NAME: &str = "test_example_blueprint_is_valid";
+        let logctx = test_setup_log(TEST_NAME);
+        let (_, _, blueprint) = example(&logctx.log, TEST_NAME);
+
+        assert_eq!(validate(&blueprint, None), Ok(()));
+        assert_eq!(validate(&child_of(&blueprint), Some(&blueprint)), Ok(()));
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_invalid_blueprints() {
+        static TEST_NAME: &str = "test_invalid_blueprints";
+        let logctx = test_setup_log(TEST_NAME);
+        let (_, _, parent) = example(&logctx.log, TEST_NAME);
+
+        // Removing a disk from service without removing the zones and
+        // datasets on it leaves them without storage.
+        let mut blueprint = child_of(&parent);
+        let (&sled_id, sled) = blueprint.sleds.iter_mut().next().unwrap();
+        let generation = sled.sled_agent_generation.next();
+        sled.sled_agent_generation = generation;
+        let mut disk = sled.disks.iter_mut().next().unwrap();
+        disk.disposition = BlueprintPhysicalDiskDisposition::Expunged {
+            as_of_generation: generation,
+            ready_for_cleanup: false,
+        };
+        let zpool = disk.pool_id;
+        drop(disk);
+        let errors = validate(&blueprint, Some(&parent)).unwrap_err().errors;
+        assert!(
+            errors.iter().any(|error| matches!(
+                error,
+                BlueprintValidationError::ZoneOnUnavailableZpool {
+                    sled_id: s,
+                    zpool: z,
+                    ..
+                } if (*s, *z) == (sled_id, zpool)
+            )),
+            "{errors:?}"
+        );
+        assert!(
+            errors.iter().any(|error| matches!(
+                error,
+                BlueprintValidationError::DatasetOnUnavailableZpool {
+                    sled_id: s,
+                    zpool: z,
+                    ..
+                } if (*s, *z) == (sled_id, zpool)
+            )),
+            "{errors:?}"
+        );
+
+        // Changing a sled without bumping its generation means sled-agent
+        // never hears about it, and generations can't go backwards.
+        let mut blueprint = child_of(&parent);
+        let sled = blueprint.sleds.get_mut(&sled_id).unwrap();
+        let mut dataset = sled.datasets.iter_mut().next().unwrap();
+        dataset.quota = Some(ByteCount::from_gibibytes_u32(1));
+        drop(dataset);
+        blueprint.internal_dns_version = Generation::new();
+        let errors = validate(&blueprint, Some(&parent)).unwrap_err().errors;
+        assert_eq!(
+            errors,
+            [
+                BlueprintValidationError::GenerationRegressed {
+                    what: "internal DNS version",
+                    parent: parent.internal_dns_version,
+                    child: Generation::new(),
+                },
+                BlueprintValidationError::SledChangedWithoutGeneration {
+                    sled_id,
+                    generation: parent.sleds[&sled_id].sled_agent_generation,
+                },
+            ]
+        );
+
+        // Every blueprint needs internal DNS.
+        let mut blueprint = parent.clone();
+        for sled in blueprint.sleds.values_mut() {
+            for mut zone in sled.zones.iter_mut() {
+                if zone.zone_type.is_internal_dns() {
+                    zone.disposition = BlueprintZoneDisposition::Expunged {
+                        as_of_generation: sled.sled_agent_generation,
+                        ready_for_cleanup: true,
+                    };
+                }
+            }
+        }
+        let errors = validate(&blueprint, None).unwrap_err().errors;
+        assert!(
+            errors.contains(&BlueprintValidationError::NoInternalDnsZones),
+            "{errors:?}"
+        );
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_check_against_inventory() {
+        static TEST_NAME: &str = "test_check_against_inventory";
+        let logctx = test_setup_log(TEST_NAME);
+        let (collection, _, blueprint) = example(&logctx.log, TEST_NAME);
+
+        assert_eq!(check_against_inventory(&blueprint, &collection), Ok(()));
+
+        // Make one sled stop reporting, and on another, lose a disk and move
+        // the sled-agent onto the underlay IP of one of the sled's zones.
+        let mut collection = collection;
+        let mut sled_ids = blueprint.sleds.keys().copied();
+        let (missing_sled_id, sled_id) =
+            (sled_ids.next().unwrap(), sled_ids.next().unwrap());
+        collection.sled_agents.remove(&missing_sled_id).unwrap();
+        let sled = &blueprint.sleds[&sled_id];
+        let zone = sled
+            .zones
+            .iter()
+            .find(|zone| zone.disposition.should_be_running())
+            .unwrap();
+        let mut sled_agent = collection.sled_agents.get_mut(&sled_id).unwrap();
+        let missing_disk = sled_agent.disks.remove(0);
+        sled_agent.sled_agent_address.set_ip(zone.underlay_ip());
+        drop(sled_agent);
+        let disk = sled
+            .disks
+            .iter()
+            .find(|disk| disk.identity == missing_disk.identity)
+            .unwrap();
+
+        let findings = check_against_inventory(&blueprint, &collection)
+            .unwrap_err()
+            .findings;
+        assert_eq!(findings.len(), 3, "{findings:?}");
+        for finding in [
+            BlueprintInventoryFinding::SledNotReporting {
+                sled_id: missing_sled_id,
+            },
+            BlueprintInventoryFinding::DiskMissing {
+                sled_id,
+                disk_id: disk.id,
+                identity: disk.identity.clone(),
+            },
+            BlueprintInventoryFinding::UnderlayIpInUse {
+                sled_id,
+                zone_id: zone.id,
+                ip: zone.underlay_ip(),
+                used_by: InventoryIpUser::SledAgent { sled_id },
+            },
+        ] {
+            assert!(findings.contains(&finding), "{findings:?}");
+        }
+
+        logctx.cleanup_successful();
+    }
+}
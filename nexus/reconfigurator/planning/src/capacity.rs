@@ -0,0 +1,209 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Capacity planning: how many more control plane zones and customer
+//! instances the system could hold
+//!
+//! This cross-references the planning input (sled policy and zpools), a
+//! blueprint (which zones are already placed where), an inventory collection
+//! (each sled's usable hardware), and the resources currently reserved by VMMs
+//! to produce a [`CapacityReport`]. It is intended to help operators plan
+//! hardware procurement; nothing here affects what the planner does.
+
+use nexus_sled_agent_shared::inventory::ZoneKind;
+use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::CapacityInstanceShape;
+use nexus_types::deployment::CapacityReport;
+use nexus_types::deployment::PlanningInput;
+use nexus_types::deployment::RackCapacity;
+use nexus_types::deployment::SledCapacity;
+use nexus_types::deployment::SledFilter;
+use nexus_types::deployment::SledInstanceUsage;
+use nexus_types::deployment::ZpoolFilter;
+use nexus_types::inventory::Collection;
+use omicron_uuid_kinds::SledUuid;
+use std::collections::BTreeMap;
+
+/// Build a [`CapacityReport`].
+///
+/// `instance_usage` describes the resources already reserved by VMMs on each
+/// sled; sleds that are missing from it are assumed to have no reservations.
+pub fn capacity_report(
+    input: &PlanningInput,
+    blueprint: &Blueprint,
+    collection: &Collection,
+    instance_usage: &BTreeMap<SledUuid, SledInstanceUsage>,
+    instance_shape: CapacityInstanceShape,
+) -> CapacityReport {
+    let discretionary: Vec<_> =
+        input.all_sled_ids(SledFilter::Discretionary).collect();
+    let reservable: Vec<_> =
+        input.all_sled_ids(SledFilter::ReservationCreate).collect();
+
+    let mut sleds = BTreeMap::new();
+    let mut total = RackCapacity::default();
+    for (sled_id, resources) in input.all_sled_resources(SledFilter::InService)
+    {
+        let num_zpools = resources.all_zpools(ZpoolFilter::InService).count();
+
+        // Mirror the planner's placement constraint: a sled can host at most
+        // one zone of a given kind per in-service zpool. Cordoned zones are
+        // still running, so they count against that limit too.
+        let zone_headroom = |kind: ZoneKind| {
+            if !discretionary.contains(&sled_id) {
+                return None;
+            }
+            let existing = blueprint.sleds.get(&sled_id).map_or(0, |config| {
+                config
+                    .zones
+                    .iter()
+                    .filter(|z| {
+                        z.disposition.should_be_running()
+                            && z.zone_type.kind() == kind
+                    })
+                    .count()
+            });
+            Some(num_zpools.saturating_sub(existing))
+        };
+        let nexus_headroom = zone_headroom(ZoneKind::Nexus);
+        let cockroachdb_headroom = zone_headroom(ZoneKind::CockroachDb);
+
+        let sled_agent = collection.sled_agents.get(&sled_id);
+        let usable_hardware_threads =
+            sled_agent.map(|sa| sa.usable_hardware_threads);
+        let reservoir_size = sled_agent.map(|sa| sa.reservoir_size);
+        let usage = instance_usage.get(&sled_id).copied().unwrap_or_default();
+        let instance_headroom = match (usable_hardware_threads, reservoir_size)
+        {
+            (Some(threads), Some(reservoir))
+                if reservable.contains(&sled_id) =>
+            {
+                let free_threads =
+                    u64::from(threads).saturating_sub(usage.hardware_threads);
+                let free_reservoir = reservoir
+                    .to_bytes()
+                    .saturating_sub(usage.reservoir_ram.to_bytes());
+                let by_threads = free_threads
+                    .checked_div(u64::from(instance_shape.ncpus))
+                    .unwrap_or(0);
+                let by_memory = free_reservoir
+                    .checked_div(instance_shape.memory.to_bytes())
+                    .unwrap_or(0);
+                Some(by_threads.min(by_memory))
+            }
+            _ => None,
+        };
+
+        total.nexus_headroom += nexus_headroom.unwrap_or(0);
+        total.cockroachdb_headroom += cockroachdb_headroom.unwrap_or(0);
+        total.instance_headroom += instance_headroom.unwrap_or(0);
+        sleds.insert(
+            sled_id,
+            SledCapacity {
+                num_zpools,
+                nexus_headroom,
+                cockroachdb_headroom,
+                usable_hardware_threads,
+                reservoir_size,
+                instance_usage: usage,
+                instance_headroom,
+            },
+        );
+    }
+
+    CapacityReport {
+        blueprint_id: blueprint.id,
+        collection_id: collection.id,
+        instance_shape,
+        sleds,
+        total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::example::example;
+    use omicron_common::api::external::ByteCount;
+    use omicron_test_utils::dev::test_setup_log;
+
+    #[test]
+    fn test_capacity_report() {
+        static TEST_NAME: &str = "test_capacity_report";
+        let logctx = test_setup_log(TEST_NAME);
+        let (collection, input, blueprint) = example(&logctx.log, TEST_NAME);
+
+        // The example system's sleds are tiny; pick an instance shape that
+        // fits on them.
+        let shape =
+            CapacityInstanceShape { ncpus: 2, memory: ByteCount::from(256u32) };
+        let empty = capacity_report(
+            &input,
+            &blueprint,
+            &collection,
+            &BTreeMap::new(),
+            shape,
+        );
+        eprintln!("{}", empty.display());
+        assert_eq!(
+            empty.sleds.len(),
+            input.all_sled_ids(SledFilter::InService).count()
+        );
+
+        // Every sled in the example system is eligible for zones and
+        // instances, and the zone headroom must match the number of zpools
+        // not already hosting a zone of each kind.
+        for (sled_id, sled) in &empty.sleds {
+            let zones_of = |kind| {
+                blueprint
+                    .sleds
+                    .get(sled_id)
+                    .unwrap()
+                    .zones
+                    .iter()
+                    .filter(|z| z.zone_type.kind() == kind)
+                    .count()
+            };
+            assert_eq!(
+                sled.nexus_headroom,
+                Some(sled.num_zpools - zones_of(ZoneKind::Nexus))
+            );
+            assert_eq!(
+                sled.cockroachdb_headroom,
+                Some(sled.num_zpools - zones_of(ZoneKind::CockroachDb))
+            );
+            assert!(sled.instance_headroom.unwrap() > 0);
+        }
+
+        // Reserving resources on one sled reduces its instance headroom (and
+        // the rack-wide total) without affecting any other sled.
+        let (&busy_sled, before) = empty.sleds.iter().next().unwrap();
+        let usage = SledInstanceUsage {
+            hardware_threads: u64::from(
+                before.usable_hardware_threads.unwrap(),
+            ),
+            reservoir_ram: ByteCount::from(0u32),
+        };
+        let busy = capacity_report(
+            &input,
+            &blueprint,
+            &collection,
+            &BTreeMap::from([(busy_sled, usage)]),
+            shape,
+        );
+        eprintln!("{}", busy.display());
+        assert_eq!(busy.sleds[&busy_sled].instance_headroom, Some(0));
+        assert_eq!(
+            busy.total.instance_headroom,
+            empty.total.instance_headroom - before.instance_headroom.unwrap()
+        );
+        for (sled_id, sled) in &busy.sleds {
+            if *sled_id != busy_sled {
+                assert_eq!(sled, &empty.sleds[sled_id]);
+            }
+        }
+
+        logctx.cleanup_successful();
+    }
+}
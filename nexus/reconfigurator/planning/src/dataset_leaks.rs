@@ -0,0 +1,295 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Detection of datasets that exist on sleds but are unaccounted for by a
+//! blueprint
+//!
+//! The executor (via sled-agent's config reconciler) eventually cleans up
+//! datasets that are no longer part of a sled's config. This module lets
+//! operators see those datasets _before_ anything is garbage-collected by
+//! cross-referencing an inventory collection against a blueprint.
+
+use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintDatasetDisposition;
+use nexus_types::inventory::Collection;
+use omicron_common::api::external::ByteCount;
+use omicron_uuid_kinds::BlueprintUuid;
+use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::DatasetUuid;
+use omicron_uuid_kinds::SledUuid;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Why a dataset reported in inventory was classified as leaked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DatasetLeakReason {
+    /// The sled reporting the dataset is not present in the blueprint at all.
+    SledNotInBlueprint,
+    /// The dataset's ID is not present in the blueprint's config for the sled
+    /// reporting it.
+    NotInBlueprint,
+    /// The blueprint knows about the dataset, but has expunged it; it is
+    /// waiting to be cleaned up.
+    ExpungedInBlueprint,
+}
+
+impl fmt::Display for DatasetLeakReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            DatasetLeakReason::SledNotInBlueprint => "sled not in blueprint",
+            DatasetLeakReason::NotInBlueprint => "not in blueprint",
+            DatasetLeakReason::ExpungedInBlueprint => "expunged in blueprint",
+        };
+        s.fmt(f)
+    }
+}
+
+/// A single dataset found in inventory that the blueprint does not expect to
+/// be present
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeakedDataset {
+    pub sled_id: SledUuid,
+    pub id: DatasetUuid,
+    /// Full ZFS name of the dataset, as reported by the sled
+    pub name: String,
+    /// Space consumed by this dataset and its descendents
+    pub used: ByteCount,
+    pub reason: DatasetLeakReason,
+    /// Whether the sled-agent also reported this dataset as orphaned during
+    /// its last reconciliation
+    pub reported_orphaned_by_sled: bool,
+}
+
+/// Report of datasets present on sleds but not accounted for by a blueprint
+///
+/// Only datasets carrying a control-plane-assigned [`DatasetUuid`] are
+/// considered: datasets without an ID (e.g., encryption roots or children
+/// managed by a zone) were never part of any blueprint and so cannot be
+/// "leaked" from one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetLeakReport {
+    pub blueprint_id: BlueprintUuid,
+    pub collection_id: CollectionUuid,
+    pub leaks: Vec<LeakedDataset>,
+}
+
+impl DatasetLeakReport {
+    /// Cross-reference the datasets reported in `collection` against those
+    /// described by `blueprint`.
+    pub fn new(blueprint: &Blueprint, collection: &Collection) -> Self {
+        let mut leaks = Vec::new();
+
+        for sled_agent in collection.sled_agents.iter() {
+            let sled_id = sled_agent.sled_id;
+            let sled_config = blueprint.sleds.get(&sled_id);
+            let orphaned_by_sled = sled_agent
+                .last_reconciliation
+                .as_ref()
+                .map(|r| &r.orphaned_datasets);
+
+            for dataset in &sled_agent.datasets {
+                let Some(id) = dataset.id else {
+                    continue;
+                };
+
+                let reason = match sled_config {
+                    None => DatasetLeakReason::SledNotInBlueprint,
+                    Some(config) => match config.datasets.get(&id) {
+                        None => DatasetLeakReason::NotInBlueprint,
+                        Some(d) => match d.disposition {
+                            BlueprintDatasetDisposition::InService => continue,
+                            BlueprintDatasetDisposition::Expunged => {
+                                DatasetLeakReason::ExpungedInBlueprint
+                            }
+                        },
+                    },
+                };
+
+                let reported_orphaned_by_sled =
+                    orphaned_by_sled.is_some_and(|orphans| {
+                        orphans.iter().any(|o| o.id == Some(id))
+                    });
+
+                leaks.push(LeakedDataset {
+                    sled_id,
+                    id,
+                    name: dataset.name.clone(),
+                    used: dataset.used,
+                    reason,
+                    reported_orphaned_by_sled,
+                });
+            }
+        }
+
+        leaks.sort_by(|a, b| {
+            (a.sled_id, &a.name, a.id).cmp(&(b.sled_id, &b.name, b.id))
+        });
+
+        Self { blueprint_id: blueprint.id, collection_id: collection.id, leaks }
+    }
+
+    /// Returns true if no leaked datasets were found.
+    pub fn is_empty(&self) -> bool {
+        self.leaks.is_empty()
+    }
+
+    /// Iterate over leaked datasets, grouped by sled.
+    pub fn leaks_by_sled(&self) -> BTreeMap<SledUuid, Vec<&LeakedDataset>> {
+        let mut by_sled: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for leak in &self.leaks {
+            by_sled.entry(leak.sled_id).or_default().push(leak);
+        }
+        by_sled
+    }
+
+    /// Total space consumed by all leaked datasets, in bytes.
+    pub fn total_bytes_used(&self) -> u64 {
+        self.leaks.iter().map(|leak| leak.used.to_bytes()).sum()
+    }
+
+    pub fn display(&self) -> DatasetLeakReportDisplay<'_> {
+        DatasetLeakReportDisplay { report: self }
+    }
+}
+
+#[derive(Debug)]
+pub struct DatasetLeakReportDisplay<'a> {
+    report: &'a DatasetLeakReport,
+}
+
+impl fmt::Display for DatasetLeakReportDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = self.report;
+        let pluralize = if report.leaks.len() == 1 { "" } else { "s" };
+        writeln!(
+            f,
+            "dataset leak report for blueprint {} against collection {}: \
+             {} leaked dataset{pluralize} using {} bytes",
+            report.blueprint_id,
+            report.collection_id,
+            report.leaks.len(),
+            report.total_bytes_used(),
+        )?;
+        for (sled_id, leaks) in report.leaks_by_sled() {
+            writeln!(f, "  sled {sled_id}:")?;
+            for leak in leaks {
+                writeln!(
+                    f,
+                    "    {} ({}): {}, used {}{}",
+                    leak.name,
+                    leak.id,
+                    leak.reason,
+                    leak.used,
+                    if leak.reported_orphaned_by_sled {
+                        " (orphaned per sled-agent)"
+                    } else {
+                        ""
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::example::example;
+    use nexus_types::inventory::Dataset;
+    use omicron_test_utils::dev::test_setup_log;
+
+    #[test]
+    fn test_example_system_has_no_leaks() {
+        static TEST_NAME: &str = "test_example_system_has_no_leaks";
+        let logctx = test_setup_log(TEST_NAME);
+        let (collection, _, blueprint) = example(&logctx.log, TEST_NAME);
+
+        let report = DatasetLeakReport::new(&blueprint, &collection);
+        if !report.is_empty() {
+            eprintln!("{}", report.display());
+            panic!("example system should have no leaked datasets");
+        }
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_leaked_datasets_are_reported() {
+        static TEST_NAME: &str = "test_leaked_datasets_are_reported";
+        let logctx = test_setup_log(TEST_NAME);
+        let (mut collection, _, mut blueprint) =
+            example(&logctx.log, TEST_NAME);
+
+        let sled_id = *blueprint.sleds.keys().next().unwrap();
+
+        // Expunge one of the sled's datasets in the blueprint; it is still
+        // present in inventory.
+        let expunged_id = {
+            let sled_config = blueprint.sleds.get_mut(&sled_id).unwrap();
+            let mut dataset = sled_config.datasets.iter_mut().next().unwrap();
+            dataset.disposition = BlueprintDatasetDisposition::Expunged;
+            dataset.id
+        };
+
+        // Add a dataset to inventory that the blueprint knows nothing about.
+        let unknown_id = DatasetUuid::new_v4();
+        let unidentified_name = {
+            let mut sled_agent =
+                collection.sled_agents.get_mut(&sled_id).unwrap();
+            let template = sled_agent.datasets[0].clone();
+            sled_agent.datasets.push(Dataset {
+                id: Some(unknown_id),
+                name: format!("{}-leaked", template.name),
+                ..template.clone()
+            });
+            // Datasets without IDs are never reported.
+            let unidentified = Dataset {
+                id: None,
+                name: format!("{}-unidentified", template.name),
+                ..template
+            };
+            let name = unidentified.name.clone();
+            sled_agent.datasets.push(unidentified);
+            name
+        };
+
+        let report = DatasetLeakReport::new(&blueprint, &collection);
+        eprintln!("{}", report.display());
+        assert_eq!(report.leaks.len(), 2);
+        let reasons: BTreeMap<_, _> =
+            report.leaks.iter().map(|leak| (leak.id, leak.reason)).collect();
+        assert_eq!(
+            reasons.get(&expunged_id),
+            Some(&DatasetLeakReason::ExpungedInBlueprint)
+        );
+        assert_eq!(
+            reasons.get(&unknown_id),
+            Some(&DatasetLeakReason::NotInBlueprint)
+        );
+        assert!(report.leaks.iter().all(|leak| leak.sled_id == sled_id));
+        assert!(report.leaks.iter().all(|leak| leak.name != unidentified_name));
+
+        // If the sled disappears from the blueprint entirely, every dataset
+        // with an ID on it is reported.
+        let nidentified = collection
+            .sled_agents
+            .get(&sled_id)
+            .unwrap()
+            .datasets
+            .iter()
+            .filter(|d| d.id.is_some())
+            .count();
+        blueprint.sleds.remove(&sled_id);
+        let report = DatasetLeakReport::new(&blueprint, &collection);
+        assert_eq!(report.leaks.len(), nidentified);
+        assert!(
+            report.leaks.iter().all(
+                |leak| leak.reason == DatasetLeakReason::SledNotInBlueprint
+            )
+        );
+
+        logctx.cleanup_successful();
+    }
+}
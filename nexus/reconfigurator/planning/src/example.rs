@@ -641,6 +641,7 @@ mod tests {
     use nexus_sled_agent_shared::inventory::{OmicronZoneConfig, ZoneKind};
     use nexus_types::deployment::BlueprintZoneConfig;
     use nexus_types::deployment::BlueprintZoneDisposition;
+    use nexus_types::deployment::PortableBlueprintError;
     use omicron_test_utils::dev::test_setup_log;
 
     use super::*;
@@ -804,4 +805,57 @@ mod tests {
             .filter(|zone| zone.zone_type.kind() == kind)
             .collect()
     }
+    #[test]
+    fn portable_blueprint_round_trip() {
+        static TEST_NAME: &str = "example_portable_blueprint_round_trip";
+        let logctx = test_setup_log(TEST_NAME);
+        let (_, _, blueprint) = example(&logctx.log, TEST_NAME);
+
+        let bytes = blueprint.to_portable_bytes().expect("serialized");
+        let round_tripped =
+            Blueprint::from_portable_bytes(&bytes).expect("deserialized");
+        assert_eq!(blueprint, round_tripped);
+
+        // Serialization is deterministic, so re-exporting an imported
+        // blueprint produces identical bytes.
+        assert_eq!(bytes, round_tripped.to_portable_bytes().unwrap());
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn portable_blueprint_rejects_corruption() {
+        static TEST_NAME: &str =
+            "example_portable_blueprint_rejects_corruption";
+        let logctx = test_setup_log(TEST_NAME);
+        let (_, _, blueprint) = example(&logctx.log, TEST_NAME);
+        let bytes = blueprint.to_portable_bytes().expect("serialized");
+        let header_len = bytes.iter().position(|b| *b == b'\n').unwrap() + 1;
+
+        // Truncated payload
+        let err = Blueprint::from_portable_bytes(&bytes[..bytes.len() - 1])
+            .unwrap_err();
+        assert!(
+            matches!(err, PortableBlueprintError::LengthMismatch { .. }),
+            "unexpected error: {err}"
+        );
+
+        // Same-length modification of the payload: change the first
+        // character of the blueprint's ID.
+        let mut corrupted = bytes.clone();
+        let id = blueprint.id.to_string();
+        let offset = corrupted[header_len..]
+            .windows(id.len())
+            .position(|w| w == id.as_bytes())
+            .expect("payload contains blueprint ID");
+        let i = header_len + offset;
+        corrupted[i] = if corrupted[i] == b'0' { b'1' } else { b'0' };
+        let err = Blueprint::from_portable_bytes(&corrupted).unwrap_err();
+        assert!(
+            matches!(err, PortableBlueprintError::ChecksumMismatch { .. }),
+            "unexpected error: {err}"
+        );
+
+        logctx.cleanup_successful();
+    }
 }
@@ -8,6 +8,9 @@
 
 pub mod blueprint_builder;
 pub mod blueprint_editor;
+pub mod blueprint_validate;
+pub mod capacity;
+pub mod dataset_leaks;
 pub mod example;
 pub mod mgs_updates;
 pub mod planner;
@@ -17,6 +17,8 @@ use crate::mgs_updates::rot_bootloader::try_make_update_rot_bootloader;
 use crate::mgs_updates::sp::mgs_update_status_sp;
 use crate::mgs_updates::sp::try_make_update_sp;
 
+use chrono::DateTime;
+use chrono::Utc;
 use gateway_types::rot::RotSlot;
 use nexus_types::deployment::ExpectedActiveRotSlot;
 use nexus_types::deployment::ExpectedVersion;
@@ -197,6 +199,20 @@ pub(crate) fn plan_mgs_updates(
             };
         }
 
+        // If we couldn't reach this board's SP during the latest collection,
+        // what we know about it may be out of date.  Don't start an update
+        // based on that.
+        if let Some(time_collected) = inventory.sp_stale_since(board) {
+            info!(
+                log,
+                "skipping board for MGS-driven update \
+                 (SP information is stale)";
+                board,
+                "time_collected" => %time_collected,
+            );
+            continue;
+        }
+
         match try_make_update(log, board, inventory, current_artifacts) {
             Some((update, mut host_phase_2)) => {
                 info!(log, "configuring MGS-driven update"; &update);
@@ -238,6 +254,8 @@ enum MgsUpdateStatus {
 enum MgsUpdateStatusError {
     #[error("no SP info found in inventory")]
     MissingSpInfo,
+    #[error("SP info in inventory is stale (collected at {0})")]
+    StaleSpInfo(DateTime<Utc>),
     #[error("no caboose found for active slot in inventory")]
     MissingActiveCaboose,
     #[error("no RoT state found in inventory")]
@@ -270,6 +288,12 @@ fn mgs_update_status(
     let desired_artifact_hash = update.artifact_hash;
     let desired_version = &update.artifact_version;
 
+    // If we couldn't reach this board's SP during the latest collection, we
+    // can't tell whether the update has made progress.
+    if let Some(time_collected) = inventory.sp_stale_since(baseboard_id) {
+        return Err(MgsUpdateStatusError::StaleSpInfo(time_collected));
+    }
+
     // Check the contents of the target of `update` against what we expect
     // either before or after the update.
     //
@@ -546,6 +570,7 @@ mod test {
     use super::test_helpers::ARTIFACT_VERSION_1_5;
     use super::test_helpers::ARTIFACT_VERSION_2;
     use super::test_helpers::TestBoards;
+    use chrono::TimeDelta;
     use dropshot::ConfigLogging;
     use dropshot::ConfigLoggingLevel;
     use gateway_client::types::SpType;
@@ -1949,4 +1974,74 @@ mod test {
 
         logctx.cleanup_successful();
     }
+
+    // Tests that we don't act on stale information about an SP
+    #[test]
+    fn test_stale_sp() {
+        let test_name = "planning_mgs_updates_stale_sp";
+        let logctx = LogContext::new(
+            test_name,
+            &ConfigLogging::StderrTerminal { level: ConfigLoggingLevel::Debug },
+        );
+        let test_boards = TestBoards::new(test_name);
+
+        // Configure an update for one SP.
+        let log = &logctx.log;
+        let repo = test_boards.tuf_repo();
+        let mut collection = test_boards
+            .collection_builder()
+            .sp_active_version_exception(SpType::Sled, 0, ARTIFACT_VERSION_1)
+            .build();
+        let nmax_updates = 1;
+        let impossible_update_policy = ImpossibleUpdatePolicy::Reevaluate;
+        let PlannedMgsUpdates { pending_updates: updates, .. } =
+            plan_mgs_updates(
+                log,
+                &collection,
+                &collection.baseboards,
+                &PendingMgsUpdates::new(),
+                &TargetReleaseDescription::TufRepo(repo.clone()),
+                nmax_updates,
+                impossible_update_policy,
+            );
+        assert_eq!(updates.len(), 1);
+
+        // Make it look like that SP couldn't be reached during the latest
+        // collection, so what we know about it came from an earlier one.
+        let time_collected = collection.time_started - TimeDelta::minutes(10);
+        let sp_info = collection
+            .sps
+            .values_mut()
+            .find(|sp| sp.sp_type == SpType::Sled && sp.sp_slot == 0)
+            .expect("missing sled 0 SP");
+        sp_info.time_collected = time_collected;
+
+        // The pending update is kept as-is, since we can't tell how it's going.
+        let PlannedMgsUpdates { pending_updates: new_updates, .. } =
+            plan_mgs_updates(
+                log,
+                &collection,
+                &collection.baseboards,
+                &updates,
+                &TargetReleaseDescription::TufRepo(repo.clone()),
+                nmax_updates,
+                impossible_update_policy,
+            );
+        assert_eq!(updates, new_updates);
+
+        // No new update is configured for the board, either.
+        let PlannedMgsUpdates { pending_updates: new_updates, .. } =
+            plan_mgs_updates(
+                log,
+                &collection,
+                &collection.baseboards,
+                &PendingMgsUpdates::new(),
+                &TargetReleaseDescription::TufRepo(repo.clone()),
+                nmax_updates,
+                impossible_update_policy,
+            );
+        assert!(new_updates.is_empty());
+
+        logctx.cleanup_successful();
+    }
 }
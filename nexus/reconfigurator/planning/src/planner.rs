@@ -13,6 +13,7 @@ use crate::blueprint_builder::EnsureMupdateOverrideAction;
 use crate::blueprint_builder::Error;
 use crate::blueprint_builder::Operation;
 use crate::blueprint_editor::DisksEditError;
+use crate::blueprint_editor::ExternalNetworkingError;
 use crate::blueprint_editor::SledEditError;
 use crate::mgs_updates::ImpossibleUpdatePolicy;
 use crate::mgs_updates::PlannedMgsUpdates;
@@ -20,6 +21,7 @@ use crate::mgs_updates::plan_mgs_updates;
 use crate::planner::image_source::NoopConvertHostPhase2Contents;
 use crate::planner::image_source::NoopConvertZoneStatus;
 use crate::planner::omicron_zone_placement::PlacementError;
+use clickhouse_admin_types::ClickhouseKeeperClusterMembership;
 use gateway_client::types::SpType;
 use itertools::Itertools;
 use nexus_sled_agent_shared::inventory::ConfigReconcilerInventoryResult;
@@ -27,10 +29,12 @@ use nexus_sled_agent_shared::inventory::OmicronZoneImageSource;
 use nexus_sled_agent_shared::inventory::OmicronZoneType;
 use nexus_sled_agent_shared::inventory::ZoneKind;
 use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintDatasetDisposition;
 use nexus_types::deployment::BlueprintPhysicalDiskDisposition;
 use nexus_types::deployment::BlueprintZoneConfig;
 use nexus_types::deployment::BlueprintZoneDisposition;
 use nexus_types::deployment::BlueprintZoneImageSource;
+use nexus_types::deployment::ClickhouseClusterConfig;
 use nexus_types::deployment::CockroachDbClusterVersion;
 use nexus_types::deployment::CockroachDbPreserveDowngrade;
 use nexus_types::deployment::CockroachDbSettings;
@@ -42,12 +46,14 @@ use nexus_types::deployment::SledFilter;
 use nexus_types::deployment::TufRepoContentsError;
 use nexus_types::deployment::ZpoolFilter;
 use nexus_types::deployment::{
-    CockroachdbUnsafeToShutdown, PlanningAddStepReport,
-    PlanningCockroachdbSettingsStepReport, PlanningDecommissionStepReport,
-    PlanningExpungeStepReport, PlanningMgsUpdatesStepReport,
+    ClickhouseKeeperUnsafeToShutdown, CockroachdbUnsafeToShutdown,
+    MaintenanceCohortWaitingOn, MaintenanceCohortZoneKindBlocked,
+    PlanningAddStepReport, PlanningCockroachdbSettingsStepReport,
+    PlanningDecommissionStepReport, PlanningExpungeStepReport,
+    PlanningMaintenanceCohortStepReport, PlanningMgsUpdatesStepReport,
     PlanningNoopImageSourceStepReport, PlanningReport,
-    PlanningZoneUpdatesStepReport, ZoneAddWaitingOn, ZoneUnsafeToShutdown,
-    ZoneUpdatesWaitingOn,
+    PlanningZoneUpdatesStepReport, ZoneAddWaitingOn, ZoneExpungeReason,
+    ZoneUnsafeToShutdown, ZoneUpdatesWaitingOn,
 };
 use nexus_types::external_api::views::PhysicalDiskPolicy;
 use nexus_types::external_api::views::SledPolicy;
@@ -57,6 +63,8 @@ use omicron_common::disk::M2Slot;
 use omicron_common::policy::BOUNDARY_NTP_REDUNDANCY;
 use omicron_common::policy::COCKROACHDB_REDUNDANCY;
 use omicron_common::policy::INTERNAL_DNS_REDUNDANCY;
+use omicron_common::policy::RESERVED_INTERNAL_DNS_REDUNDANCY;
+use omicron_common::policy::SLEDS_PER_CRUCIBLE_PANTRY;
 use omicron_uuid_kinds::OmicronZoneUuid;
 use omicron_uuid_kinds::PhysicalDiskUuid;
 use omicron_uuid_kinds::SledUuid;
@@ -77,10 +85,17 @@ use self::omicron_zone_placement::OmicronZonePlacement;
 use self::omicron_zone_placement::OmicronZonePlacementSledState;
 pub use self::rng::PlannerRng;
 pub use self::rng::SledPlannerRng;
+pub use self::simulate::MAX_SIMULATION_STEPS;
+pub use self::simulate::PolicyChange;
+pub use self::simulate::SimulateError;
+pub use self::simulate::Simulation;
+use self::version_skew::find_version_skew_violations;
 
 mod image_source;
 mod omicron_zone_placement;
 pub(crate) mod rng;
+mod simulate;
+mod version_skew;
 
 /// Maximum number of MGS-managed updates (updates to SP, RoT, RoT bootloader,
 /// or host OS) that we allow to be pending across the whole system at one time
@@ -157,7 +172,8 @@ impl<'a> Planner<'a> {
     }
 
     fn check_input_validity(&self) -> Result<InputChecked, Error> {
-        if self.input.target_internal_dns_zone_count() > INTERNAL_DNS_REDUNDANCY
+        if self.input.target_internal_dns_zone_count()
+            > RESERVED_INTERNAL_DNS_REDUNDANCY
         {
             return Err(Error::PolicySpecifiesTooManyInternalDnsServers);
         }
@@ -232,6 +248,31 @@ impl<'a> Planner<'a> {
             self.do_plan_zone_updates(&mgs_updates)?
         };
 
+        // Only move zones off of the maintenance cohort once everything else
+        // has settled: replacements for the zones we remove are placed by
+        // `do_plan_add`, and we don't want to reduce redundancy while zones
+        // are being updated.
+        let maintenance_cohort = if self.input.maintenance_cohort().is_empty() {
+            PlanningMaintenanceCohortStepReport::new()
+        } else if add.any_discretionary_zones_placed() {
+            PlanningMaintenanceCohortStepReport::waiting_on(
+                MaintenanceCohortWaitingOn::DiscretionaryZones,
+            )
+        } else if !mgs_updates.is_empty() {
+            PlanningMaintenanceCohortStepReport::waiting_on(
+                MaintenanceCohortWaitingOn::PendingMgsUpdates,
+            )
+        } else if !zone_updates.expunged_zones.is_empty()
+            || !zone_updates.updated_zones.is_empty()
+            || !zone_updates.surged_zones.is_empty()
+        {
+            PlanningMaintenanceCohortStepReport::waiting_on(
+                MaintenanceCohortWaitingOn::ZoneUpdates,
+            )
+        } else {
+            self.do_plan_maintenance_cohort()?
+        };
+
         // CockroachDB settings aren't dependent on zones, so they can be
         // planned independently of the rest of the system.
         let cockroachdb_settings = self.do_plan_cockroachdb_settings();
@@ -245,6 +286,7 @@ impl<'a> Planner<'a> {
             add,
             mgs_updates,
             zone_updates,
+            maintenance_cohort,
             cockroachdb_settings,
         })
     }
@@ -296,7 +338,10 @@ impl<'a> Planner<'a> {
                 }
                 // The sled is expunged but not yet decommissioned; fall through
                 // to check the rest of the criteria.
-                (SledPolicy::Expunged, SledState::Active) => (),
+                (
+                    SledPolicy::Expunged,
+                    SledState::Active | SledState::Quiesced,
+                ) => (),
             }
 
             // Check that the sled isn't already decommissioned in the parent
@@ -455,8 +500,17 @@ impl<'a> Planner<'a> {
                         PhysicalDiskPolicy::Expunged => true,
                     })
                 {
+                    let zones_before =
+                        self.zones_that_could_be_running(sled_id);
                     match self.blueprint.expunge_disk(sled_id, disk.disk_id) {
-                        Ok(()) => (),
+                        Ok(()) => self.report_expunged_zones(
+                            sled_id,
+                            zones_before,
+                            ZoneExpungeReason::DiskExpunged {
+                                disk_id: disk.disk_id,
+                            },
+                            report,
+                        ),
                         Err(Error::SledEditError {
                             err:
                                 SledEditError::EditDisks(
@@ -481,19 +535,34 @@ impl<'a> Planner<'a> {
                 // Expunge any multinode clickhouse zones if the policy says
                 // they shouldn't exist.
                 if !self.input.clickhouse_cluster_enabled() {
-                    self.blueprint.expunge_all_multinode_clickhouse(
+                    let reason = ZoneExpungeReason::ClickhouseClusterDisabled;
+                    let zones_before =
+                        self.zones_that_could_be_running(sled_id);
+                    self.blueprint
+                        .expunge_all_multinode_clickhouse(sled_id, reason)?;
+                    self.report_expunged_zones(
                         sled_id,
-                        ZoneExpungeReason::ClickhouseClusterDisabled,
-                    )?;
+                        zones_before,
+                        reason,
+                        report,
+                    );
                 }
 
                 // Similarly, expunge any singlenode clickhouse if the policy
                 // says they should exist.
                 if !self.input.clickhouse_single_node_enabled() {
-                    self.blueprint.expunge_all_singlenode_clickhouse(
+                    let reason =
+                        ZoneExpungeReason::ClickhouseSingleNodeDisabled;
+                    let zones_before =
+                        self.zones_that_could_be_running(sled_id);
+                    self.blueprint
+                        .expunge_all_singlenode_clickhouse(sled_id, reason)?;
+                    self.report_expunged_zones(
                         sled_id,
-                        ZoneExpungeReason::ClickhouseSingleNodeDisabled,
-                    )?;
+                        zones_before,
+                        reason,
+                        report,
+                    );
                 }
 
                 // Are there any expunged zones that haven't yet been marked as
@@ -510,8 +579,16 @@ impl<'a> Planner<'a> {
             // sled from the blueprint.
             SledPolicy::Expunged => {
                 match self.blueprint.current_sled_state(sled_id)? {
-                    SledState::Active => {
+                    SledState::Active | SledState::Quiesced => {
+                        let zones_before =
+                            self.zones_that_could_be_running(sled_id);
                         self.blueprint.expunge_sled(sled_id)?;
+                        self.report_expunged_zones(
+                            sled_id,
+                            zones_before,
+                            ZoneExpungeReason::SledExpunged,
+                            report,
+                        );
                     }
                     // If the sled is decommissioned, we've already expunged it
                     // in a prior planning run.
@@ -523,6 +600,41 @@ impl<'a> Planner<'a> {
         Ok(())
     }
 
+    fn zones_that_could_be_running(
+        &self,
+        sled_id: SledUuid,
+    ) -> Vec<BlueprintZoneConfig> {
+        self.blueprint
+            .current_sled_zones(
+                sled_id,
+                BlueprintZoneDisposition::could_be_running,
+            )
+            .cloned()
+            .collect()
+    }
+
+    /// Records in `report` which of `zones_before` (as returned by
+    /// `zones_that_could_be_running()` before some expunge operation) are now
+    /// expunged, attributing them to `reason`.
+    fn report_expunged_zones(
+        &self,
+        sled_id: SledUuid,
+        zones_before: Vec<BlueprintZoneConfig>,
+        reason: ZoneExpungeReason,
+        report: &mut PlanningExpungeStepReport,
+    ) {
+        let still_running: BTreeSet<_> = self
+            .zones_that_could_be_running(sled_id)
+            .into_iter()
+            .map(|zone| zone.id)
+            .collect();
+        for zone in zones_before {
+            if !still_running.contains(&zone.id) {
+                report.expunged_zone(sled_id, &zone, reason);
+            }
+        }
+    }
+
     fn check_zones_eligible_for_cleanup(
         &mut self,
         sled_id: SledUuid,
@@ -550,6 +662,7 @@ impl<'a> Planner<'a> {
                     ready_for_cleanup,
                 } if !ready_for_cleanup => as_of_generation,
                 BlueprintZoneDisposition::InService
+                | BlueprintZoneDisposition::Cordoned
                 | BlueprintZoneDisposition::Expunged { .. } => continue,
             };
 
@@ -950,6 +1063,60 @@ impl<'a> Planner<'a> {
                     removed,
                 });
             }
+            self.do_plan_dataset_quotas(sled_id)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the quotas proposed in the planning input (based on observed
+    /// usage) to the sled's in-service datasets.
+    ///
+    /// Proposals for datasets that aren't in service on this sled, or whose
+    /// quota isn't sized based on usage, are ignored.
+    fn do_plan_dataset_quotas(
+        &mut self,
+        sled_id: SledUuid,
+    ) -> Result<(), Error> {
+        let dataset_quotas = self.input.dataset_quotas();
+        let proposed: Vec<_> = self
+            .blueprint
+            .current_sled_datasets(
+                sled_id,
+                BlueprintDatasetDisposition::is_in_service,
+            )
+            .filter(|dataset| dataset.kind.has_usage_based_quota())
+            .filter_map(|dataset| {
+                dataset_quotas
+                    .get(&dataset.id)
+                    .map(|quota| (dataset.id, *quota))
+            })
+            .collect();
+
+        let mut updated = 0;
+        for (dataset_id, quota) in proposed {
+            if self.blueprint.sled_set_dataset_quota(
+                sled_id,
+                dataset_id,
+                Some(quota),
+            )? {
+                info!(
+                    &self.log,
+                    "set dataset quota";
+                    "sled_id" => %sled_id,
+                    "dataset_id" => %dataset_id,
+                    "quota" => %quota,
+                );
+                updated += 1;
+            }
+        }
+        if updated > 0 {
+            self.blueprint.record_operation(Operation::UpdateDatasets {
+                sled_id,
+                added: 0,
+                updated,
+                expunged: 0,
+                removed: 0,
+            });
         }
         Ok(())
     }
@@ -989,33 +1156,12 @@ impl<'a> Planner<'a> {
                 // zones. This will remain valid as we loop through the
                 // `zone_kind`s in this function, as any zone additions will
                 // update the `zone_placement` heap in-place.
-                let current_discretionary_zones = self
-                    .input
-                    .all_sled_resources(SledFilter::Discretionary)
-                    .filter(|(sled_id, _)| {
-                        !report.sleds_waiting_for_ntp_zone.contains(&sled_id)
-                    })
-                    .map(|(sled_id, sled_resources)| {
-                        OmicronZonePlacementSledState {
-                            sled_id,
-                            num_zpools: sled_resources
-                                .all_zpools(ZpoolFilter::InService)
-                                .count(),
-                            discretionary_zones: self
-                                .blueprint
-                                .current_sled_zones(
-                                    sled_id,
-                                    BlueprintZoneDisposition::is_in_service,
-                                )
-                                .filter_map(|zone| {
-                                    DiscretionaryOmicronZone::from_zone_type(
-                                        &zone.zone_type,
-                                    )
-                                })
-                                .collect(),
-                        }
-                    });
-                OmicronZonePlacement::new(current_discretionary_zones)
+                //
+                // New zones never go on sleds in the maintenance cohort.
+                self.discretionary_zone_placement(|sled_id| {
+                    !report.sleds_waiting_for_ntp_zone.contains(&sled_id)
+                        && !self.input.maintenance_cohort().contains(&sled_id)
+                })
             });
             self.add_discretionary_zones(
                 zone_placement,
@@ -1029,6 +1175,46 @@ impl<'a> Planner<'a> {
         Ok(())
     }
 
+    /// Constructs an `OmicronZonePlacement` describing the discretionary zones
+    /// currently on each sled that's eligible for them, limited to the sleds
+    /// for which `include_sled` returns true.
+    fn discretionary_zone_placement(
+        &self,
+        mut include_sled: impl FnMut(SledUuid) -> bool,
+    ) -> OmicronZonePlacement {
+        let current_discretionary_zones = self
+            .input
+            .all_sled_resources(SledFilter::Discretionary)
+            .filter(|(sled_id, _)| include_sled(*sled_id))
+            // Sleds that have been quiesced in this blueprint keep the zones
+            // they have, but don't take on new ones.
+            .filter(|(sled_id, _)| {
+                !matches!(
+                    self.blueprint.current_sled_state(*sled_id),
+                    Ok(SledState::Quiesced)
+                )
+            })
+            .map(|(sled_id, sled_resources)| OmicronZonePlacementSledState {
+                sled_id,
+                num_zpools: sled_resources
+                    .all_zpools(ZpoolFilter::InService)
+                    .count(),
+                discretionary_zones: self
+                    .blueprint
+                    .current_sled_zones(
+                        sled_id,
+                        BlueprintZoneDisposition::is_in_service,
+                    )
+                    .filter_map(|zone| {
+                        DiscretionaryOmicronZone::from_zone_type(
+                            &zone.zone_type,
+                        )
+                    })
+                    .collect(),
+            });
+        OmicronZonePlacement::new(current_discretionary_zones)
+    }
+
     /// Given the current blueprint state and policy, returns the number of
     /// additional zones needed of the given `zone_kind` to satisfy the policy.
     fn num_additional_zones_needed(
@@ -1040,8 +1226,19 @@ impl<'a> Planner<'a> {
         // will include sleds that are in service but not eligible for new
         // services, but will not include sleds that have been expunged or
         // decommissioned.
+        //
+        // Zones on sleds in the maintenance cohort are on their way out, so
+        // they don't count either -- unless they can't be replaced until
+        // they're gone, in which case `do_plan_maintenance_cohort` removes
+        // them first.
+        let skip_maintenance_cohort = can_place_replacement_first(zone_kind);
         let mut num_existing_kind_zones = 0;
         for sled_id in self.input.all_sled_ids(SledFilter::InService) {
+            if skip_maintenance_cohort
+                && self.input.maintenance_cohort().contains(&sled_id)
+            {
+                continue;
+            }
             let zone_kind = ZoneKind::from(zone_kind);
 
             // Internal DNS is special: if we have an expunged internal DNS zone
@@ -1049,6 +1246,13 @@ impl<'a> Planner<'a> {
             // reuse its subnet until it's ready for cleanup. For all other
             // services, we want to go ahead and replace them if they're below
             // the desired count based on purely "in service vs expunged".
+            //
+            // Zones on expunged sleds aren't counted at all: the expunge step
+            // marks them ready for cleanup (the sled is gone), so their
+            // subnets can go to replacements on other sleds right away. Only
+            // one blueprint built from the current target can become the next
+            // target, so two Nexus instances planning at once can't both hand
+            // out the same subnet.
             let disposition_filter = if zone_kind == ZoneKind::InternalDns {
                 BlueprintZoneDisposition::could_be_running
             } else {
@@ -1061,7 +1265,27 @@ impl<'a> Planner<'a> {
                 .count();
         }
 
-        let target_count = match zone_kind {
+        let target_count = self.target_zone_count(zone_kind);
+
+        // TODO-correctness What should we do if we have _too many_
+        // `zone_kind` zones? For now, just report the number of zones
+        // any time we have at least the minimum number.
+        let num_zones_to_add =
+            target_count.saturating_sub(num_existing_kind_zones);
+        if num_zones_to_add == 0 {
+            report.sufficient_zones_exist(
+                ZoneKind::from(zone_kind).report_str(),
+                target_count,
+                num_existing_kind_zones,
+            );
+        }
+        num_zones_to_add
+    }
+
+    /// Returns the number of zones of the given `zone_kind` the policy asks
+    /// for.
+    fn target_zone_count(&self, zone_kind: DiscretionaryOmicronZone) -> usize {
+        match zone_kind {
             DiscretionaryOmicronZone::BoundaryNtp => {
                 self.input.target_boundary_ntp_zone_count()
             }
@@ -1078,7 +1302,10 @@ impl<'a> Planner<'a> {
                 self.input.target_cockroachdb_zone_count()
             }
             DiscretionaryOmicronZone::CruciblePantry => {
-                self.input.target_crucible_pantry_zone_count()
+                crucible_pantry_target_zone_count(
+                    self.input.target_crucible_pantry_zone_count(),
+                    self.input.all_sled_ids(SledFilter::InService).count(),
+                )
             }
             DiscretionaryOmicronZone::InternalDns => {
                 self.input.target_internal_dns_zone_count()
@@ -1094,21 +1321,7 @@ impl<'a> Planner<'a> {
             DiscretionaryOmicronZone::Oximeter => {
                 self.input.target_oximeter_zone_count()
             }
-        };
-
-        // TODO-correctness What should we do if we have _too many_
-        // `zone_kind` zones? For now, just report the number of zones
-        // any time we have at least the minimum number.
-        let num_zones_to_add =
-            target_count.saturating_sub(num_existing_kind_zones);
-        if num_zones_to_add == 0 {
-            report.sufficient_zones_exist(
-                ZoneKind::from(zone_kind).report_str(),
-                target_count,
-                num_existing_kind_zones,
-            );
         }
-        num_zones_to_add
     }
 
     /// Attempts to place `num_zones_to_add` new zones of `kind`.
@@ -1268,6 +1481,10 @@ impl<'a> Planner<'a> {
         mgs_updates: &PlanningMgsUpdatesStepReport,
     ) -> Result<PlanningZoneUpdatesStepReport, Error> {
         let mut report = PlanningZoneUpdatesStepReport::new();
+        report.version_skew =
+            find_version_skew_violations(&self.log, self.input, self.inventory);
+        report.allow_version_skew =
+            self.input.chicken_switches().allow_version_skew;
 
         // We are only interested in non-decommissioned sleds.
         let sleds = self
@@ -1436,7 +1653,10 @@ impl<'a> Planner<'a> {
         // bounce them.
         let mut updateable_zones = out_of_date_zones.iter().filter(
             |(_sled_id, zone, _new_image_source)| {
-                if !self.can_zone_be_shut_down_safely(zone, &mut report) {
+                if !self.can_zone_be_shut_down_safely(
+                    zone,
+                    &mut report.unsafe_zones,
+                ) {
                     return false;
                 }
                 match self.is_zone_ready_for_update(
@@ -1477,6 +1697,8 @@ impl<'a> Planner<'a> {
 
     /// Update a zone to use a new image source, either in-place or by
     /// expunging it and letting it be replaced in a future iteration.
+    ///
+    /// Nexus zones are the exception: see `surge_or_expunge_nexus_zone()`.
     fn update_or_expunge_zone(
         &mut self,
         sled_id: SledUuid,
@@ -1508,12 +1730,19 @@ impl<'a> Planner<'a> {
                     new_image_source,
                 )?;
             }
+            ZoneKind::Nexus => {
+                self.surge_or_expunge_nexus_zone(
+                    sled_id,
+                    zone,
+                    new_image_source,
+                    &mut report,
+                )?;
+            }
             ZoneKind::BoundaryNtp
             | ZoneKind::CruciblePantry
             | ZoneKind::ExternalDns
             | ZoneKind::InternalDns
             | ZoneKind::InternalNtp
-            | ZoneKind::Nexus
             | ZoneKind::Oximeter => {
                 self.blueprint.comment(format!(
                     "expunge {:?} zone {} for update",
@@ -1528,6 +1757,261 @@ impl<'a> Planner<'a> {
         Ok(report)
     }
 
+    /// Update an out-of-date Nexus zone without dropping below the target
+    /// number of Nexus zones.
+    ///
+    /// Rather than expunging the zone and letting `do_plan_add` replace it,
+    /// we first add its replacement ("surge") from the new image, leaving the
+    /// old zone in service. `do_plan_zone_updates` won't consider any further
+    /// updates until inventory reports the new zone running that image, so by
+    /// the time we're called for this zone again, there are more Nexus zones
+    /// in service than the policy asks for and we can expunge it ("drain").
+    ///
+    /// If there's nowhere to put a surge zone (no eligible sled, or no spare
+    /// external IP for it), we fall back to expunging the old zone first.
+    fn surge_or_expunge_nexus_zone(
+        &mut self,
+        sled_id: SledUuid,
+        zone: &BlueprintZoneConfig,
+        new_image_source: BlueprintZoneImageSource,
+        report: &mut PlanningZoneUpdatesStepReport,
+    ) -> Result<(), Error> {
+        let num_nexus_zones = self
+            .input
+            .all_sled_ids(SledFilter::InService)
+            .filter(|sled_id| {
+                !self.input.maintenance_cohort().contains(sled_id)
+            })
+            .flat_map(|sled_id| {
+                self.blueprint.current_sled_zones(
+                    sled_id,
+                    BlueprintZoneDisposition::is_in_service,
+                )
+            })
+            .filter(|z| z.zone_type.is_nexus())
+            .count();
+
+        if num_nexus_zones <= self.input.target_nexus_zone_count() {
+            let mut zone_placement =
+                self.discretionary_zone_placement(|sled_id| {
+                    !self.input.maintenance_cohort().contains(&sled_id)
+                });
+            match zone_placement.place_zone(DiscretionaryOmicronZone::Nexus) {
+                Ok(replacement_sled_id) => {
+                    match self.blueprint.sled_add_zone_nexus(
+                        replacement_sled_id,
+                        new_image_source,
+                    ) {
+                        Ok(()) => {
+                            self.blueprint.comment(format!(
+                                "add Nexus zone on sled {} to replace \
+                                 out-of-date zone {}",
+                                replacement_sled_id, zone.id
+                            ));
+                            report.surged_zone(
+                                sled_id,
+                                zone,
+                                replacement_sled_id,
+                            );
+                            return Ok(());
+                        }
+                        Err(Error::AllocateExternalNetworking(
+                            ExternalNetworkingError::NoExternalServiceIpAvailable,
+                        )) => {
+                            info!(
+                                self.log,
+                                "no external IP available for a surge Nexus \
+                                 zone; expunging out-of-date zone first";
+                                "zone_id" => %zone.id,
+                            );
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+                Err(PlacementError::NoSledsEligible { .. }) => {
+                    info!(
+                        self.log,
+                        "no sled eligible for a surge Nexus zone; \
+                         expunging out-of-date zone first";
+                        "zone_id" => %zone.id,
+                    );
+                }
+            }
+        }
+
+        self.blueprint.comment(format!(
+            "expunge {:?} zone {} for update",
+            zone.zone_type.kind(),
+            zone.id
+        ));
+        report.expunged_zone(sled_id, zone);
+        self.blueprint.sled_expunge_zone(sled_id, zone.id)?;
+        Ok(())
+    }
+
+    /// Move discretionary zones off of the sleds in the maintenance cohort, at
+    /// most one zone per blueprint.
+    ///
+    /// Most zones are replaced before they're removed: when counting zones
+    /// toward their policy targets, `num_additional_zones_needed` ignores
+    /// those in the cohort, so replacements have already been placed on other
+    /// sleds by the time we get here, and we only expunge a zone from the
+    /// cohort if enough zones of its kind remain outside it. DNS zones can only
+    /// be replaced once the zone they replace is gone (see
+    /// `can_place_replacement_first()`), so for those we settle for expunging
+    /// a zone only when all zones of its kind are in service and there's a
+    /// sled outside the cohort for its replacement. That's the same temporary
+    /// loss of one zone that we accept when updating them.
+    fn do_plan_maintenance_cohort(
+        &mut self,
+    ) -> Result<PlanningMaintenanceCohortStepReport, Error> {
+        let input = self.input;
+        let cohort = input.maintenance_cohort();
+
+        // Don't remove anything until all in-service zones (in particular,
+        // the replacements for zones we've already removed) are running.
+        let running_zones = self
+            .inventory
+            .all_reconciled_omicron_zones()
+            .filter(|(_, result)| {
+                matches!(result, ConfigReconcilerInventoryResult::Ok)
+            })
+            .map(|(zone, _)| zone.id)
+            .collect::<BTreeSet<_>>();
+        for sled_id in input.all_sled_ids(SledFilter::InService) {
+            let not_running = self
+                .blueprint
+                .current_sled_zones(
+                    sled_id,
+                    BlueprintZoneDisposition::is_in_service,
+                )
+                .filter(|zone| !running_zones.contains(&zone.id))
+                .map(|zone| zone.id)
+                .collect::<Vec<_>>();
+            if !not_running.is_empty() {
+                info!(
+                    self.log, "some zones not yet running";
+                    "sled_id" => %sled_id,
+                    "zones" => ?not_running,
+                );
+                return Ok(PlanningMaintenanceCohortStepReport::waiting_on(
+                    MaintenanceCohortWaitingOn::ZoneReconciliation,
+                ));
+            }
+        }
+
+        let candidates = input
+            .all_sled_ids(SledFilter::InService)
+            .filter(|sled_id| cohort.contains(sled_id))
+            .flat_map(|sled_id| {
+                self.blueprint
+                    .current_sled_zones(
+                        sled_id,
+                        BlueprintZoneDisposition::is_in_service,
+                    )
+                    .filter_map(move |zone| {
+                        let kind = DiscretionaryOmicronZone::from_zone_type(
+                            &zone.zone_type,
+                        )?;
+                        Some((sled_id, kind, zone.clone()))
+                    })
+            })
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Ok(PlanningMaintenanceCohortStepReport::new());
+        }
+
+        // Only used to check whether replacements could be placed; it's
+        // never handed a zone we actually add.
+        let zone_placement = self
+            .discretionary_zone_placement(|sled_id| !cohort.contains(&sled_id));
+
+        let mut report = PlanningMaintenanceCohortStepReport::new();
+        for (sled_id, kind, zone) in candidates {
+            let kind_str = ZoneKind::from(kind).report_str();
+            if report.blocked_zone_kinds.contains_key(kind_str) {
+                continue;
+            }
+            if let Some(blocked) =
+                self.maintenance_cohort_blocker(kind, &zone_placement)
+            {
+                report.blocked_zone_kind(kind_str, blocked);
+                continue;
+            }
+            if !self
+                .can_zone_be_shut_down_safely(&zone, &mut report.unsafe_zones)
+            {
+                continue;
+            }
+
+            self.blueprint.comment(format!(
+                "expunge {:?} zone {} from maintenance cohort",
+                zone.zone_type.kind(),
+                zone.id
+            ));
+            report.expunged_zone(sled_id, &zone);
+            self.blueprint.sled_expunge_zone(sled_id, zone.id)?;
+            break;
+        }
+
+        Ok(report)
+    }
+
+    /// Returns why zones of the given kind can't be removed from the
+    /// maintenance cohort right now, if they can't.
+    fn maintenance_cohort_blocker(
+        &self,
+        kind: DiscretionaryOmicronZone,
+        zone_placement: &OmicronZonePlacement,
+    ) -> Option<MaintenanceCohortZoneKindBlocked> {
+        let cohort = self.input.maintenance_cohort();
+        let zone_kind = ZoneKind::from(kind);
+        let target_count = self.target_zone_count(kind);
+        let replace_first = can_place_replacement_first(kind);
+
+        let mut num_in_service = 0;
+        for sled_id in self.input.all_sled_ids(SledFilter::InService) {
+            if replace_first && cohort.contains(&sled_id) {
+                continue;
+            }
+            num_in_service += self
+                .blueprint
+                .current_sled_zones(
+                    sled_id,
+                    BlueprintZoneDisposition::is_in_service,
+                )
+                .filter(|zone| zone.zone_type.kind() == zone_kind)
+                .count();
+        }
+
+        if replace_first {
+            // The replacements should already be in service outside the
+            // cohort.
+            if num_in_service < target_count {
+                return Some(
+                    MaintenanceCohortZoneKindBlocked::InsufficientRedundancy {
+                        target_count,
+                        num_in_service,
+                    },
+                );
+            }
+        } else {
+            if num_in_service < target_count {
+                return Some(
+                    MaintenanceCohortZoneKindBlocked::InsufficientRedundancy {
+                        target_count,
+                        num_in_service: num_in_service.saturating_sub(1),
+                    },
+                );
+            }
+            if zone_placement.clone().place_zone(kind).is_err() {
+                return Some(MaintenanceCohortZoneKindBlocked::NoEligibleSled);
+            }
+        }
+
+        None
+    }
+
     /// Perform planning for mupdate overrides, returning a map of sleds to
     /// actions taken.
     fn do_plan_mupdate_override(
@@ -1914,6 +2398,20 @@ impl<'a> Planner<'a> {
                     }
                 }
 
+                // Nor can Nexus be updated if that would leave any host OS or
+                // SP further behind than it supports, unless the operator has
+                // explicitly allowed it.
+                if !self.input.chicken_switches().allow_version_skew
+                    && !find_version_skew_violations(
+                        &self.log,
+                        self.input,
+                        self.inventory,
+                    )
+                    .is_empty()
+                {
+                    return Ok(false);
+                }
+
                 Ok(true)
             }
             _ => Ok(true), // other zone kinds have no special dependencies
@@ -1931,70 +2429,50 @@ impl<'a> Planner<'a> {
     /// because the underlying disk / sled has been expunged" case. In this
     /// case, we have no choice but to reconcile with the fact that the zone is
     /// now gone.
+    ///
+    /// If the zone can't be shut down, the reason is recorded in
+    /// `unsafe_zones`.
     fn can_zone_be_shut_down_safely(
         &self,
         zone: &BlueprintZoneConfig,
-        report: &mut PlanningZoneUpdatesStepReport,
+        unsafe_zones: &mut BTreeMap<BlueprintZoneConfig, ZoneUnsafeToShutdown>,
     ) -> bool {
         use ZoneUnsafeToShutdown::*;
         match zone.zone_type.kind() {
             ZoneKind::CockroachDb => {
-                use CockroachdbUnsafeToShutdown::*;
-
-                // We must hear from all nodes
-                let all_statuses = &self.inventory.cockroach_status;
-                if all_statuses.len() < COCKROACHDB_REDUNDANCY {
-                    report.unsafe_zone(
-                        zone,
-                        Cockroachdb { reason: NotEnoughNodes },
-                    );
-                    return false;
-                }
-
-                // All nodes must report: "We have the necessary redundancy, and
-                // have observed no underreplicated ranges".
-                for (_node_id, status) in all_statuses {
-                    let Some(ranges_underreplicated) =
-                        status.ranges_underreplicated
-                    else {
-                        report.unsafe_zone(
-                            zone,
-                            Cockroachdb { reason: MissingUnderreplicatedStat },
-                        );
-                        return false;
-                    };
-                    if ranges_underreplicated != 0 {
-                        report.unsafe_zone(
-                            zone,
-                            Cockroachdb {
-                                reason: UnderreplicatedRanges {
-                                    n: ranges_underreplicated,
-                                },
-                            },
-                        );
-                        return false;
-                    }
-                    let Some(live_nodes) = status.liveness_live_nodes else {
-                        report.unsafe_zone(
-                            zone,
-                            Cockroachdb { reason: MissingLiveNodesStat },
-                        );
-                        return false;
-                    };
-                    if live_nodes < COCKROACHDB_REDUNDANCY as u64 {
-                        report.unsafe_zone(
-                            zone,
-                            Cockroachdb {
-                                reason: NotEnoughLiveNodes { live_nodes },
-                            },
-                        );
-                        return false;
+                match cockroachdb_unsafe_to_shut_down(self.inventory) {
+                    Some(reason) => {
+                        unsafe_zones
+                            .insert(zone.clone(), Cockroachdb { reason });
+                        false
                     }
+                    None => true,
                 }
-                true
             }
-            ZoneKind::BoundaryNtp => {
-                // Find all boundary NTP zones expected to be in-service by our
+            ZoneKind::ClickhouseKeeper => {
+                // Like CockroachDB nodes, we only take down one keeper at a
+                // time: the keeper cluster must have absorbed the last
+                // membership change the planner asked for before we disturb
+                // it again.
+                match clickhouse_keeper_unsafe_to_shutdown(
+                    self.blueprint
+                        .parent_blueprint()
+                        .clickhouse_cluster_config
+                        .as_ref(),
+                    self.inventory
+                        .latest_clickhouse_keeper_membership()
+                        .as_ref(),
+                ) {
+                    Some(reason) => {
+                        unsafe_zones
+                            .insert(zone.clone(), ClickhouseKeeper { reason });
+                        false
+                    }
+                    None => true,
+                }
+            }
+            ZoneKind::BoundaryNtp => {
+                // Find all boundary NTP zones expected to be in-service by our
                 // blueprint.
                 let mut boundary_ntp_zones = std::collections::HashSet::new();
                 for sled_id in self.blueprint.sled_ids_with_zones() {
@@ -2024,8 +2502,8 @@ impl<'a> Planner<'a> {
                 }
 
                 if synchronized_boundary_ntp_count < BOUNDARY_NTP_REDUNDANCY {
-                    report.unsafe_zone(
-                        zone,
+                    unsafe_zones.insert(
+                        zone.clone(),
                         BoundaryNtp {
                             total_boundary_ntp_zones: boundary_ntp_zones.len(),
                             synchronized_count: synchronized_boundary_ntp_count,
@@ -2088,8 +2566,8 @@ impl<'a> Planner<'a> {
                 if synchronized_internal_dns_count >= INTERNAL_DNS_REDUNDANCY {
                     true
                 } else {
-                    report.unsafe_zone(
-                        zone,
+                    unsafe_zones.insert(
+                        zone.clone(),
                         InternalDns {
                             total_internal_dns_zones: internal_dns_zones.len(),
                             synchronized_count: synchronized_internal_dns_count,
@@ -2103,20 +2581,99 @@ impl<'a> Planner<'a> {
     }
 }
 
-/// The reason a sled's zones need to be expunged.
+/// Returns why a CockroachDB node can't be shut down right now, if it can't,
+/// based on the cluster status reported in `inventory`.
 ///
-/// This is used only for introspection and logging -- it's not part of the
-/// logical flow.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
-pub(crate) enum ZoneExpungeReason {
-    ClickhouseClusterDisabled,
-    ClickhouseSingleNodeDisabled,
+/// We must hear from all nodes, and all of them must report that the cluster
+/// has the necessary redundancy and no underreplicated ranges.
+pub fn cockroachdb_unsafe_to_shut_down(
+    inventory: &Collection,
+) -> Option<CockroachdbUnsafeToShutdown> {
+    use CockroachdbUnsafeToShutdown::*;
+
+    let all_statuses = &inventory.cockroach_status;
+    if all_statuses.len() < COCKROACHDB_REDUNDANCY {
+        return Some(NotEnoughNodes);
+    }
+    for (_node_id, status) in all_statuses {
+        let Some(ranges_underreplicated) = status.ranges_underreplicated else {
+            return Some(MissingUnderreplicatedStat);
+        };
+        if ranges_underreplicated != 0 {
+            return Some(UnderreplicatedRanges { n: ranges_underreplicated });
+        }
+        let Some(live_nodes) = status.liveness_live_nodes else {
+            return Some(MissingLiveNodesStat);
+        };
+        if live_nodes < COCKROACHDB_REDUNDANCY as u64 {
+            return Some(NotEnoughLiveNodes { live_nodes });
+        }
+    }
+    None
+}
+
+/// Returns why a clickhouse keeper zone can't be shut down right now, if it
+/// can't.
+///
+/// The keeper cluster can only add or remove one member at a time (see
+/// `ClickhouseAllocator`), so we must not shut down a keeper while the cluster
+/// membership reported by inventory differs from the membership in the parent
+/// blueprint: that means a reconfiguration is still in progress. If there is
+/// no clustered clickhouse configuration at all, there's nothing to protect.
+fn clickhouse_keeper_unsafe_to_shutdown(
+    parent_config: Option<&ClickhouseClusterConfig>,
+    membership: Option<&ClickhouseKeeperClusterMembership>,
+) -> Option<ClickhouseKeeperUnsafeToShutdown> {
+    use ClickhouseKeeperUnsafeToShutdown::*;
+
+    let parent_config = parent_config?;
+    let Some(membership) = membership else {
+        return Some(MissingMembership);
+    };
+    let expected: BTreeSet<_> =
+        parent_config.keepers.values().copied().collect();
+    if expected != membership.raft_config {
+        return Some(MembershipChangeInProgress);
+    }
+    None
+}
+
+/// Returns whether a replacement for a zone of this kind can be placed before
+/// the zone it replaces is expunged.
+///
+/// DNS zones can't: each one uses one of a fixed set of addresses (internal
+/// DNS subnets or external DNS IPs), which can only be reused once the old zone
+/// is gone.
+fn can_place_replacement_first(kind: DiscretionaryOmicronZone) -> bool {
+    !matches!(
+        kind,
+        DiscretionaryOmicronZone::InternalDns
+            | DiscretionaryOmicronZone::ExternalDns
+    )
+}
+
+/// Returns the number of Crucible Pantry zones to run on a rack with
+/// `num_in_service_sleds` sleds, given the number the policy asks for.
+///
+/// The policy's count is a minimum: larger racks get one Pantry for every
+/// `SLEDS_PER_CRUCIBLE_PANTRY` sleds. A policy count of zero turns Pantries off
+/// altogether.
+fn crucible_pantry_target_zone_count(
+    policy_count: usize,
+    num_in_service_sleds: usize,
+) -> usize {
+    if policy_count == 0 {
+        return 0;
+    }
+    policy_count.max(num_in_service_sleds.div_ceil(SLEDS_PER_CRUCIBLE_PANTRY))
 }
 
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
     use crate::blueprint_builder::test::verify_blueprint;
+    use crate::blueprint_validate;
+    use crate::blueprint_validate::BlueprintValidationError;
     use crate::example::ExampleSystem;
     use crate::example::ExampleSystemBuilder;
     use crate::example::SimRngState;
@@ -2143,6 +2700,7 @@ pub(crate) mod test {
     use nexus_types::deployment::SledDisk;
     use nexus_types::deployment::TargetReleaseDescription;
     use nexus_types::deployment::TufRepoPolicy;
+    use nexus_types::deployment::VersionSkewViolation;
     use nexus_types::deployment::blueprint_zone_type;
     use nexus_types::deployment::blueprint_zone_type::InternalDns;
     use nexus_types::external_api::views::PhysicalDiskState;
@@ -2151,6 +2709,8 @@ pub(crate) mod test {
     use nexus_types::inventory::CockroachStatus;
     use nexus_types::inventory::InternalDnsGenerationStatus;
     use nexus_types::inventory::TimeSync;
+    use omicron_common::address::DnsSubnet;
+    use omicron_common::api::external::ByteCount;
     use omicron_common::api::external::Generation;
     use omicron_common::api::external::MacAddr;
     use omicron_common::api::external::TufArtifactMeta;
@@ -2176,6 +2736,7 @@ pub(crate) mod test {
     use std::collections::HashMap;
     use std::net::IpAddr;
     use std::net::Ipv6Addr;
+    use std::num::NonZeroU8;
     use tufaceous_artifact::ArtifactHash;
     use tufaceous_artifact::ArtifactKind;
     use tufaceous_artifact::ArtifactVersion;
@@ -2296,6 +2857,17 @@ pub(crate) mod test {
             &summary.display().to_string(),
         );
 
+        // The summary rendering reduces the new sled to a line of counts.
+        let summary_only = summary.display().summary().to_string();
+        assert!(
+            summary_only.contains(&format!(
+                "  sled {new_sled_id}: zones +1 -0 ~0, disks +10 -0 ~0, \
+                 datasets +21 -0 ~0"
+            )),
+            "unexpected summary:\n{summary_only}"
+        );
+        assert!(!summary_only.contains("MODIFIED SLEDS"));
+
         assert_eq!(summary.diff.sleds.added.len(), 1);
         assert_eq!(summary.total_disks_added(), 10);
         assert_eq!(summary.total_datasets_added(), 21);
@@ -2410,6 +2982,80 @@ pub(crate) mod test {
         logctx.cleanup_successful();
     }
 
+    /// Check that blueprint diffs flag datasets whose properties in inventory
+    /// have drifted from the blueprint's intent
+    #[test]
+    fn test_diff_dataset_drift() {
+        static TEST_NAME: &str = "planner_diff_dataset_drift";
+        let logctx = test_setup_log(TEST_NAME);
+        let (example, blueprint) =
+            ExampleSystemBuilder::new(&logctx.log, TEST_NAME).build();
+
+        let (sled_id, dataset) = blueprint
+            .sleds
+            .iter()
+            .find_map(|(sled_id, config)| {
+                config
+                    .datasets
+                    .iter()
+                    .find(|d| {
+                        d.disposition.is_in_service() && d.quota.is_some()
+                    })
+                    .map(|d| (*sled_id, d.clone()))
+            })
+            .expect("blueprint has an in-service dataset with a quota");
+
+        // Report only that dataset in inventory, without its quota.
+        let mut collection = example.collection.clone();
+        {
+            let mut sled_agent =
+                collection.sled_agents.get_mut(&sled_id).unwrap();
+            sled_agent.datasets = vec![nexus_types::inventory::Dataset {
+                id: Some(dataset.id),
+                name: omicron_common::disk::DatasetName::new(
+                    dataset.pool,
+                    dataset.kind.clone(),
+                )
+                .full_name(),
+                available: ByteCount::from_gibibytes_u32(1),
+                used: ByteCount::from_gibibytes_u32(0),
+                quota: None,
+                reservation: dataset.reservation,
+                compression: dataset.compression.to_string(),
+            }];
+        }
+
+        // Diffing a blueprint against itself shows no changes, and nothing
+        // is flagged unless drift is requested.
+        let summary = blueprint.diff_since_blueprint(&blueprint);
+        assert!(!summary.display().to_string().contains("DRIFTED"));
+
+        let diff = summary.display().with_inventory_drift(&collection);
+        let diff = diff.to_string();
+        println!("{diff}");
+        assert!(diff.contains(" SLEDS WITH DRIFTED DATASETS:"), "{diff}");
+        let drifted_rows: Vec<_> =
+            diff.lines().filter(|line| line.starts_with('!')).collect();
+        assert_eq!(drifted_rows.len(), 1, "{diff}");
+        assert!(drifted_rows[0].contains(&dataset.id.to_string()));
+        assert!(drifted_rows[0].contains("- none"), "{diff}");
+
+        let summary_only = summary
+            .display()
+            .with_inventory_drift(&collection)
+            .summary()
+            .to_string();
+        assert!(
+            summary_only.contains(&format!(
+                "  sled {sled_id}: zones +0 -0 ~0, disks +0 -0 ~0, \
+                 datasets +0 -0 ~0 !1"
+            )),
+            "unexpected summary:\n{summary_only}"
+        );
+
+        logctx.cleanup_successful();
+    }
+
     /// Check that the planner will add more Nexus zones to a single sled, if
     /// needed
     #[test]
@@ -2598,6 +3244,127 @@ pub(crate) mod test {
         logctx.cleanup_successful();
     }
 
+    /// Check that the `target_nexus_zone_count` chicken switch overrides the
+    /// policy's Nexus zone count.
+    #[test]
+    fn test_target_nexus_zone_count_chicken_switch() {
+        static TEST_NAME: &str =
+            "planner_target_nexus_zone_count_chicken_switch";
+        let logctx = test_setup_log(TEST_NAME);
+
+        let (collection, input, blueprint1) = example(&logctx.log, TEST_NAME);
+        let count_nexus_zones = |blueprint: &Blueprint| {
+            blueprint
+                .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+                .filter(|(_, z)| z.zone_type.is_nexus())
+                .count()
+        };
+        assert_eq!(count_nexus_zones(&blueprint1), 3);
+
+        let mut builder = input.into_builder();
+        builder.policy_mut().chicken_switches.target_nexus_zone_count =
+            Some(NonZeroU8::new(5).unwrap());
+        let input = builder.build();
+        assert_eq!(input.target_nexus_zone_count(), 5);
+
+        let blueprint2 = Planner::new_based_on(
+            logctx.log.clone(),
+            &blueprint1,
+            &input,
+            "test_blueprint2",
+            &collection,
+            PlannerRng::from_seed((TEST_NAME, "bp2")),
+        )
+        .expect("failed to create planner")
+        .plan()
+        .expect("failed to plan");
+        assert_eq!(count_nexus_zones(&blueprint2), 5);
+
+        assert_planning_makes_no_changes(
+            &logctx.log,
+            &blueprint2,
+            &input,
+            &collection,
+            TEST_NAME,
+        );
+
+        logctx.cleanup_successful();
+    }
+
+    /// Check that the `target_internal_dns_zone_count` chicken switch lets the
+    /// planner run more internal DNS zones than the default redundancy, using
+    /// the reserved DNS subnets beyond the well-known ones.
+    #[test]
+    fn test_target_internal_dns_zone_count_chicken_switch() {
+        static TEST_NAME: &str =
+            "planner_target_internal_dns_zone_count_chicken_switch";
+        let logctx = test_setup_log(TEST_NAME);
+
+        let (example, blueprint1) =
+            ExampleSystemBuilder::new(&logctx.log, TEST_NAME).nsleds(5).build();
+        let collection = example.collection;
+        let internal_dns_subnets = |blueprint: &Blueprint| {
+            blueprint
+                .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+                .filter_map(|(_, z)| match &z.zone_type {
+                    BlueprintZoneType::InternalDns(InternalDns {
+                        dns_address,
+                        ..
+                    }) => Some(DnsSubnet::from_addr(*dns_address.ip())),
+                    _ => None,
+                })
+                .collect::<BTreeSet<_>>()
+        };
+        let subnets1 = internal_dns_subnets(&blueprint1);
+        assert_eq!(subnets1.len(), INTERNAL_DNS_REDUNDANCY);
+        let rack_subnet = subnets1.first().unwrap().rack_subnet();
+        assert_eq!(
+            subnets1,
+            rack_subnet.get_dns_subnets().into_iter().collect::<BTreeSet<_>>()
+        );
+
+        let mut builder = example.input.into_builder();
+        builder.policy_mut().chicken_switches.target_internal_dns_zone_count =
+            Some(NonZeroU8::new(5).unwrap());
+        let input = builder.build();
+        assert_eq!(input.target_internal_dns_zone_count(), 5);
+
+        let blueprint2 = Planner::new_based_on(
+            logctx.log.clone(),
+            &blueprint1,
+            &input,
+            "test_blueprint2",
+            &collection,
+            PlannerRng::from_seed((TEST_NAME, "bp2")),
+        )
+        .expect("failed to create planner")
+        .plan()
+        .expect("failed to plan");
+
+        // The new zones take the next reserved subnets, leaving the
+        // well-known ones (which clients bootstrap from) untouched.
+        let subnets2 = internal_dns_subnets(&blueprint2);
+        assert_eq!(
+            subnets2,
+            rack_subnet
+                .get_reserved_dns_subnets()
+                .into_iter()
+                .take(5)
+                .collect::<BTreeSet<_>>()
+        );
+        assert!(subnets2.is_superset(&subnets1));
+
+        assert_planning_makes_no_changes(
+            &logctx.log,
+            &blueprint2,
+            &input,
+            &collection,
+            TEST_NAME,
+        );
+
+        logctx.cleanup_successful();
+    }
+
     /// Check that the planner will spread additional internal DNS zones out across
     /// sleds as it adds them
     #[test]
@@ -2625,9 +3392,11 @@ pub(crate) mod test {
         }
 
         // Try to run the planner with a high number of internal DNS zones;
-        // it will fail because the target is > MAX_DNS_REDUNDANCY.
+        // it will fail because the target is more than we have reserved
+        // subnets for.
         let mut builder = input.clone().into_builder();
-        builder.policy_mut().target_internal_dns_zone_count = 14;
+        builder.policy_mut().target_internal_dns_zone_count =
+            RESERVED_INTERNAL_DNS_REDUNDANCY + 1;
         match Planner::new_based_on(
             logctx.log.clone(),
             &blueprint1,
@@ -3202,6 +3971,87 @@ pub(crate) mod test {
         logctx.cleanup_successful();
     }
 
+    #[test]
+    fn test_dataset_quotas_applied_from_input() {
+        static TEST_NAME: &str = "planner_dataset_quotas_applied_from_input";
+        let logctx = test_setup_log(TEST_NAME);
+
+        // Create an example system with a single sled
+        let (example, blueprint1) =
+            ExampleSystemBuilder::new(&logctx.log, TEST_NAME).nsleds(1).build();
+        let collection = example.collection;
+        let mut builder = example.input.into_builder();
+
+        // Avoid churning on the quantity of Nexus and internal DNS zones -
+        // we're okay staying at one each.
+        builder.policy_mut().target_nexus_zone_count = 1;
+        builder.policy_mut().target_internal_dns_zone_count = 1;
+
+        // Propose a quota for one Crucible dataset and for the debug dataset;
+        // only the former has a usage-based quota.
+        let (_, sled_config) = blueprint1.sleds.first_key_value().unwrap();
+        let crucible_dataset = sled_config
+            .datasets
+            .iter()
+            .find(|config| matches!(config.kind, DatasetKind::Crucible))
+            .expect("no crucible dataset found");
+        let debug_dataset = sled_config
+            .datasets
+            .iter()
+            .find(|config| matches!(config.kind, DatasetKind::Debug))
+            .expect("no debug dataset found");
+        let quota = ByteCount::from_gibibytes_u32(100);
+        builder.set_dataset_quotas(
+            [(crucible_dataset.id, quota), (debug_dataset.id, quota)]
+                .into_iter()
+                .collect(),
+        );
+        let input = builder.build();
+
+        let blueprint2 = Planner::new_based_on(
+            logctx.log.clone(),
+            &blueprint1,
+            &input,
+            "test: apply dataset quotas",
+            &collection,
+            PlannerRng::from_seed((TEST_NAME, "bp2")),
+        )
+        .expect("failed to create planner")
+        .plan()
+        .expect("failed to plan");
+
+        let summary = blueprint2.diff_since_blueprint(&blueprint1);
+        println!("1 -> 2 (apply dataset quotas):\n{}", summary.display());
+        assert_eq!(summary.total_datasets_added(), 0);
+        assert_eq!(summary.total_datasets_removed(), 0);
+        assert_eq!(summary.total_datasets_modified(), 1);
+
+        let (_, sled_config) = blueprint2.sleds.first_key_value().unwrap();
+        let find = |id| {
+            sled_config
+                .datasets
+                .iter()
+                .find(|config| config.id == id)
+                .expect("dataset is still present")
+        };
+        assert_eq!(find(crucible_dataset.id).quota, Some(quota));
+        assert_eq!(find(debug_dataset.id).quota, debug_dataset.quota);
+
+        // Once applied, the quota sticks even without a proposal.
+        let mut builder = input.into_builder();
+        builder.set_dataset_quotas(BTreeMap::new());
+        let input = builder.build();
+        assert_planning_makes_no_changes(
+            &logctx.log,
+            &blueprint2,
+            &input,
+            &collection,
+            TEST_NAME,
+        );
+
+        logctx.cleanup_successful();
+    }
+
     #[test]
     fn test_disk_add_expunge_decommission() {
         static TEST_NAME: &str = "planner_disk_add_expunge_decommission";
@@ -3450,6 +4300,7 @@ pub(crate) mod test {
             })
             .expect("Couldn't find zpool only used by a single zone");
         disk.policy = PhysicalDiskPolicy::Expunged;
+        let expunged_disk_id = disk.disk_id;
 
         let input = builder.build();
 
@@ -3548,6 +4399,21 @@ pub(crate) mod test {
             "Should have expunged this zone"
         );
 
+        // The planning report should say why the zone was expunged.
+        let expunged_zones = blueprint2
+            .report
+            .expunge
+            .expunged_zones
+            .values()
+            .flatten()
+            .collect::<Vec<_>>();
+        assert_eq!(expunged_zones.len(), 1);
+        assert!(expunged_zones[0].zone_config.zone_type.is_crucible());
+        assert_eq!(
+            expunged_zones[0].reason,
+            ZoneExpungeReason::DiskExpunged { disk_id: expunged_disk_id }
+        );
+
         // Test a no-op planning iteration.
         assert_planning_makes_no_changes(
             &logctx.log,
@@ -4378,6 +5244,29 @@ pub(crate) mod test {
         logctx.cleanup_successful();
     }
 
+    #[test]
+    fn test_crucible_pantry_target_zone_count() {
+        // Small racks get the number of pantries the policy asks for.
+        for nsleds in [0, 1, 16, 24] {
+            assert_eq!(
+                crucible_pantry_target_zone_count(
+                    CRUCIBLE_PANTRY_REDUNDANCY,
+                    nsleds
+                ),
+                CRUCIBLE_PANTRY_REDUNDANCY,
+            );
+        }
+
+        // Larger racks get one pantry for each `SLEDS_PER_CRUCIBLE_PANTRY`
+        // sleds.
+        assert_eq!(crucible_pantry_target_zone_count(3, 25), 4);
+        assert_eq!(crucible_pantry_target_zone_count(3, 32), 4);
+        assert_eq!(crucible_pantry_target_zone_count(5, 32), 5);
+
+        // Pantries can still be turned off.
+        assert_eq!(crucible_pantry_target_zone_count(0, 32), 0);
+    }
+
     /// Check that the planner can replace a single-node ClickHouse zone.
     /// This is completely distinct from (and much simpler than) the replicated
     /// (multi-node) case.
@@ -5570,6 +6459,155 @@ pub(crate) mod test {
         logctx.cleanup_successful();
     }
 
+    /// Check that the internal DNS subnets of a failed sled are handed to
+    /// replacement zones on other sleds once the sled is expunged, whether or
+    /// not earlier blueprints marked its zones ready for cleanup.
+    #[test]
+    fn test_internal_dns_subnet_reassigned_from_expunged_sled() {
+        static TEST_NAME: &str =
+            "planner_internal_dns_subnet_reassigned_from_expunged_sled";
+        let logctx = test_setup_log(TEST_NAME);
+
+        // Use more sleds than internal DNS zones, so there's somewhere to put
+        // replacements.
+        let (example, blueprint1) =
+            ExampleSystemBuilder::new(&logctx.log, TEST_NAME).nsleds(5).build();
+        let collection = example.collection;
+        let input = example.input;
+
+        let (sled_id, dns_zone) = blueprint1
+            .sleds
+            .iter()
+            .find_map(|(sled_id, sled_config)| {
+                let zone = sled_config
+                    .zones
+                    .iter()
+                    .find(|z| z.zone_type.is_internal_dns())?;
+                Some((*sled_id, zone.clone()))
+            })
+            .expect("found an internal DNS zone");
+        let dns_address = dns_zone.underlay_ip();
+
+        // Returns the sleds with an in-service internal DNS zone on the
+        // subnet `dns_zone` had.
+        let sleds_with_subnet = |bp: &Blueprint| {
+            bp.all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+                .filter(|(_, z)| {
+                    z.zone_type.is_internal_dns()
+                        && z.underlay_ip() == dns_address
+                })
+                .map(|(sled_id, _)| sled_id)
+                .collect::<Vec<_>>()
+        };
+
+        // Expunge the sled. The planner should expunge its zones (which are
+        // ready for cleanup right away, since the sled is gone) and hand the
+        // DNS subnet to a new zone on another sled in the same blueprint.
+        let mut builder = input.clone().into_builder();
+        builder.expunge_sled(&sled_id).unwrap();
+        let expunged_input = builder.build();
+        let blueprint2 = Planner::new_based_on(
+            logctx.log.clone(),
+            &blueprint1,
+            &expunged_input,
+            "expunge sled",
+            &collection,
+            PlannerRng::from_seed((TEST_NAME, "bp2")),
+        )
+        .expect("created planner")
+        .plan()
+        .expect("planned");
+        verify_blueprint(&blueprint2);
+        assert_eq!(
+            blueprint2.sleds[&sled_id]
+                .zones
+                .get(&dns_zone.id)
+                .map(|z| z.disposition.is_ready_for_cleanup()),
+            Some(true),
+        );
+        let new_sleds = sleds_with_subnet(&blueprint2);
+        assert_eq!(new_sleds.len(), 1, "{new_sleds:?}");
+        assert_ne!(new_sleds[0], sled_id);
+        assert_eq!(
+            blueprint_validate::validate(&blueprint2, Some(&blueprint1)),
+            Ok(())
+        );
+        assert_planning_makes_no_changes(
+            &logctx.log,
+            &blueprint2,
+            &expunged_input,
+            &collection,
+            TEST_NAME,
+        );
+
+        // A blueprint that hands the subnet out while the old zone might
+        // still be running must not be made the target.
+        let mut bad_blueprint = blueprint2.clone();
+        let sled_config = bad_blueprint.sleds.get_mut(&sled_id).unwrap();
+        sled_config.state = SledState::Active;
+        for mut zone in sled_config.zones.iter_mut() {
+            if zone.id == dns_zone.id {
+                zone.disposition = BlueprintZoneDisposition::Expunged {
+                    as_of_generation: Generation::new(),
+                    ready_for_cleanup: false,
+                };
+            }
+        }
+        let errors = blueprint_validate::validate(&bad_blueprint, None)
+            .unwrap_err()
+            .errors;
+        assert!(
+            errors.iter().any(|error| matches!(
+                error,
+                BlueprintValidationError::InternalDnsSubnetInUse {
+                    zone1,
+                    zone2,
+                    ..
+                } if *zone1 == dns_zone.id || *zone2 == dns_zone.id
+            )),
+            "{errors:?}"
+        );
+
+        // An older blueprint might have decommissioned the sled without
+        // marking its zones ready for cleanup. The subnet is still free: a
+        // decommissioned sled isn't running anything.
+        let mut blueprint1 = blueprint1;
+        let sled_config = blueprint1.sleds.get_mut(&sled_id).unwrap();
+        for mut zone in sled_config.zones.iter_mut() {
+            zone.disposition = BlueprintZoneDisposition::Expunged {
+                as_of_generation: Generation::new(),
+                ready_for_cleanup: false,
+            };
+        }
+        sled_config.state = SledState::Decommissioned;
+        let mut builder = input.into_builder();
+        builder.expunge_sled(&sled_id).unwrap();
+        builder.sleds_mut().get_mut(&sled_id).unwrap().state =
+            SledState::Decommissioned;
+        let decommissioned_input = builder.build();
+        let blueprint3 = Planner::new_based_on(
+            logctx.log.clone(),
+            &blueprint1,
+            &decommissioned_input,
+            "decommissioned sled",
+            &collection,
+            PlannerRng::from_seed((TEST_NAME, "bp3")),
+        )
+        .expect("created planner")
+        .plan()
+        .expect("planned");
+        verify_blueprint(&blueprint3);
+        let new_sleds = sleds_with_subnet(&blueprint3);
+        assert_eq!(new_sleds.len(), 1, "{new_sleds:?}");
+        assert_ne!(new_sleds[0], sled_id);
+        assert_eq!(
+            blueprint_validate::validate(&blueprint3, Some(&blueprint1)),
+            Ok(())
+        );
+
+        logctx.cleanup_successful();
+    }
+
     /// Manually update the example system's inventory collection's zones
     /// from a blueprint.
     fn update_collection_from_blueprint(
@@ -5847,7 +6885,8 @@ pub(crate) mod test {
         );
 
         // Now we can update Nexus, because all of its dependent zones
-        // are up-to-date w/r/t the new repo.
+        // are up-to-date w/r/t the new repo. Each Nexus zone is replaced by
+        // first adding an up-to-date zone, then expunging the old one.
         assert_eq!(
             blueprint8
                 .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
@@ -5876,9 +6915,6 @@ pub(crate) mod test {
                 let summary = blueprint.diff_since_blueprint(&parent);
                 for sled in summary.diff.sleds.modified_values_diff() {
                     if i % 2 == 1 {
-                        assert!(sled.zones.added.is_empty());
-                        assert!(sled.zones.removed.is_empty());
-                    } else {
                         assert!(sled.zones.removed.is_empty());
                         assert_eq!(sled.zones.added.len(), 1);
                         let added = sled.zones.added.values().next().unwrap();
@@ -5887,10 +6923,22 @@ pub(crate) mod test {
                             BlueprintZoneType::Nexus(_)
                         ));
                         assert_eq!(added.image_source, image_source);
+                    } else {
+                        assert!(sled.zones.added.is_empty());
+                        assert!(sled.zones.removed.is_empty());
                     }
                 }
             }
 
+            // We never drop below the target number of Nexus zones.
+            assert!(
+                blueprint
+                    .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+                    .filter(|(_, z)| z.zone_type.is_nexus())
+                    .count()
+                    >= NEXUS_REDUNDANCY + 1
+            );
+
             parent = blueprint;
         }
 
@@ -6504,7 +7552,11 @@ pub(crate) mod test {
                 };
 
                 ntp_timesync
-                    .insert_unique(TimeSync { zone_id, synced: true })
+                    .insert_unique(TimeSync {
+                        zone_id,
+                        synced: true,
+                        correction_usec: Some(0),
+                    })
                     .expect("NTP zone with same zone ID seen repeatedly");
             }
             collection.ntp_timesync = ntp_timesync;
@@ -7073,6 +8125,174 @@ pub(crate) mod test {
         logctx.cleanup_successful();
     }
 
+    /// Ensure that we don't update Nexus if doing so would leave a sled's host
+    /// OS further behind than we support, unless the corresponding chicken
+    /// switch is set.
+    #[test]
+    fn test_nexus_update_blocked_by_version_skew() {
+        static TEST_NAME: &str = "nexus_update_blocked_by_version_skew";
+        let logctx = test_setup_log(TEST_NAME);
+        let log = logctx.log.clone();
+
+        // Use our example system.
+        let mut rng = SimRngState::from_seed(TEST_NAME);
+        let (mut example, mut blueprint1) = ExampleSystemBuilder::new_with_rng(
+            &logctx.log,
+            rng.next_system_rng(),
+        )
+        .build();
+        verify_blueprint(&blueprint1);
+
+        // Manually specify both a target release and the release before it.
+        // Neither contains the host OS image the simulated sleds are running.
+        let fake_repo = |version: &ArtifactVersion, host_phase_2_hash| {
+            let mut artifacts = create_artifacts_at_version(version);
+            artifacts.push(TufArtifactMeta {
+                id: ArtifactId {
+                    name: String::from("host"),
+                    version: version.clone(),
+                    kind: ArtifactKind::HOST_PHASE_2,
+                },
+                hash: host_phase_2_hash,
+                size: 0,
+                board: None,
+                sign: None,
+            });
+            TargetReleaseDescription::TufRepo(TufRepoDescription {
+                repo: TufRepoMeta {
+                    hash: ArtifactHash([0; 32]),
+                    targets_role_version: 0,
+                    valid_until: Utc::now(),
+                    system_version: Version::new(1, 0, 0),
+                    file_name: String::from(""),
+                },
+                artifacts,
+            })
+        };
+        let old_version = ArtifactVersion::new_static("1.0.0-freeform")
+            .expect("can't parse artifact version");
+        let new_version = ArtifactVersion::new_static("2.0.0-freeform")
+            .expect("can't parse artifact version");
+        let old_host_phase_2_hash = ArtifactHash([0x1a; 32]);
+        let mut input_builder = example.input.clone().into_builder();
+        input_builder.policy_mut().old_repo = TufRepoPolicy {
+            target_release_generation: Generation::new().next(),
+            description: fake_repo(&old_version, old_host_phase_2_hash),
+        };
+        input_builder.policy_mut().tuf_repo = TufRepoPolicy {
+            target_release_generation: Generation::new().next().next(),
+            description: fake_repo(&new_version, ArtifactHash([0x2a; 32])),
+        };
+        let input = input_builder.build();
+
+        // Manually update all zones except Nexus, so that Nexus is the only
+        // thing left to update.
+        for mut zone in blueprint1
+            .sleds
+            .values_mut()
+            .flat_map(|config| config.zones.iter_mut())
+            .filter(|z| !z.zone_type.is_nexus())
+        {
+            zone.image_source = BlueprintZoneImageSource::Artifact {
+                version: BlueprintArtifactVersion::Available {
+                    version: new_version.clone(),
+                },
+                hash: ArtifactHash([0; 32]),
+            };
+        }
+        update_collection_from_blueprint(&mut example, &blueprint1);
+
+        // Every sled's host OS is too far behind, so Nexus must not be
+        // updated.
+        let expected_violations: Vec<_> = input
+            .all_sled_ids(SledFilter::InService)
+            .map(|sled_id| VersionSkewViolation::HostOs {
+                sled_id,
+                hash: ArtifactHash([0x0a; 32]),
+            })
+            .collect();
+        assert!(!expected_violations.is_empty());
+        let blueprint2 = Planner::new_based_on(
+            log.clone(),
+            &blueprint1,
+            &input,
+            "test_blueprint2",
+            &example.collection,
+            PlannerRng::from_seed((TEST_NAME, "bp2")),
+        )
+        .expect("can't create planner")
+        .plan()
+        .expect("can't re-plan");
+        let zone_updates = &blueprint2.report.zone_updates;
+        eprintln!("{}", blueprint2.report);
+        assert_eq!(zone_updates.version_skew, expected_violations);
+        assert!(!zone_updates.allow_version_skew);
+        assert!(zone_updates.expunged_zones.is_empty());
+        assert!(zone_updates.updated_zones.is_empty());
+        assert!(zone_updates.surged_zones.is_empty());
+
+        // With the chicken switch set, we update Nexus anyway, while still
+        // reporting the violations.
+        let mut input_builder = input.clone().into_builder();
+        input_builder.policy_mut().chicken_switches.allow_version_skew = true;
+        let input_allow_skew = input_builder.build();
+        let blueprint3 = Planner::new_based_on(
+            log.clone(),
+            &blueprint1,
+            &input_allow_skew,
+            "test_blueprint3",
+            &example.collection,
+            PlannerRng::from_seed((TEST_NAME, "bp3")),
+        )
+        .expect("can't create planner")
+        .plan()
+        .expect("can't re-plan");
+        let zone_updates = &blueprint3.report.zone_updates;
+        eprintln!("{}", blueprint3.report);
+        assert_eq!(zone_updates.version_skew, expected_violations);
+        assert!(zone_updates.allow_version_skew);
+        let surged: Vec<_> =
+            zone_updates.surged_zones.values().flatten().collect();
+        assert_eq!(surged.len(), 1);
+        assert!(surged[0].zone_config.zone_type.is_nexus());
+
+        // Once every sled is running the host OS from the previous release,
+        // Nexus can be updated without the chicken switch.
+        for sled_id in input.all_sled_ids(SledFilter::InService) {
+            example
+                .system
+                .sled_update_host_phase_2_artifacts(
+                    sled_id,
+                    None,
+                    Some(old_host_phase_2_hash),
+                    None,
+                )
+                .expect("updated host phase 2 artifacts");
+        }
+        example.collection =
+            example.system.to_collection_builder().unwrap().build();
+        let blueprint4 = Planner::new_based_on(
+            log.clone(),
+            &blueprint1,
+            &input,
+            "test_blueprint4",
+            &example.collection,
+            PlannerRng::from_seed((TEST_NAME, "bp4")),
+        )
+        .expect("can't create planner")
+        .plan()
+        .expect("can't re-plan");
+        let zone_updates = &blueprint4.report.zone_updates;
+        eprintln!("{}", blueprint4.report);
+        assert!(zone_updates.version_skew.is_empty());
+        let surged: Vec<_> =
+            zone_updates.surged_zones.values().flatten().collect();
+        assert_eq!(surged.len(), 1);
+        assert!(surged[0].zone_config.zone_type.is_nexus());
+
+        logctx.cleanup_successful();
+    }
+
     /// Ensure that planning to update all zones terminates.
     #[test]
     fn test_update_all_zones() {
@@ -7194,4 +8414,274 @@ pub(crate) mod test {
 
         panic!("did not converge after {MAX_PLANNING_ITERATIONS} iterations");
     }
+
+    /// Drain a maintenance cohort, checking that zones leave it one at a time
+    /// without any zone kind dropping below its policy target (or, for DNS,
+    /// more than one below it).
+    #[test]
+    fn test_drain_maintenance_cohort() {
+        static TEST_NAME: &str = "planner_drain_maintenance_cohort";
+        let logctx = test_setup_log(TEST_NAME);
+        let log = logctx.log.clone();
+
+        let mut rng = SimRngState::from_seed(TEST_NAME);
+        let (mut example, blueprint1) =
+            ExampleSystemBuilder::new_with_rng(&log, rng.next_system_rng())
+                .nsleds(5)
+                .build();
+        verify_blueprint(&blueprint1);
+        update_collection_from_blueprint(&mut example, &blueprint1);
+
+        let cohort_zones = |blueprint: &Blueprint, sled_id: SledUuid| {
+            blueprint.sleds[&sled_id]
+                .zones
+                .iter()
+                .filter(|zone| {
+                    zone.disposition.is_in_service()
+                        && DiscretionaryOmicronZone::from_zone_type(
+                            &zone.zone_type,
+                        )
+                        .is_some()
+                })
+                .map(|zone| zone.id)
+                .collect::<BTreeSet<_>>()
+        };
+        let count_in_service = |blueprint: &Blueprint, kind: ZoneKind| {
+            blueprint
+                .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+                .filter(|(_, zone)| zone.zone_type.kind() == kind)
+                .count()
+        };
+        let targets = [
+            (
+                ZoneKind::Clickhouse,
+                example.input.target_clickhouse_zone_count(),
+            ),
+            (
+                ZoneKind::CruciblePantry,
+                example.input.target_crucible_pantry_zone_count(),
+            ),
+            (
+                ZoneKind::InternalDns,
+                example.input.target_internal_dns_zone_count(),
+            ),
+            (ZoneKind::Nexus, example.input.target_nexus_zone_count()),
+        ];
+
+        // If every sled is in the cohort, there's nowhere for zones to go, so
+        // the planner must refuse to remove any of them.
+        let mut input_builder = example.input.clone().into_builder();
+        input_builder.policy_mut().maintenance_cohort =
+            example.input.all_sled_ids(SledFilter::Commissioned).collect();
+        let input = input_builder.build();
+        let blueprint = Planner::new_based_on(
+            log.clone(),
+            &blueprint1,
+            &input,
+            "all sleds in cohort",
+            &example.collection,
+            PlannerRng::from_seed((TEST_NAME, "all sleds in cohort")),
+        )
+        .expect("created planner")
+        .plan()
+        .expect("planned");
+        eprintln!("{}\n", blueprint.report);
+        let summary = blueprint.diff_since_blueprint(&blueprint1);
+        assert_eq!(summary.total_zones_added(), 0);
+        assert_eq!(summary.total_zones_removed(), 0);
+        assert_eq!(summary.total_zones_modified(), 0);
+        let report = &blueprint.report.maintenance_cohort;
+        assert!(report.expunged_zones.is_empty());
+        assert_eq!(
+            report.blocked_zone_kinds.get(ZoneKind::Nexus.report_str()),
+            Some(&MaintenanceCohortZoneKindBlocked::InsufficientRedundancy {
+                target_count: input.target_nexus_zone_count(),
+                num_in_service: 0,
+            })
+        );
+        assert_eq!(
+            report.blocked_zone_kinds.get(ZoneKind::InternalDns.report_str()),
+            Some(&MaintenanceCohortZoneKindBlocked::NoEligibleSled)
+        );
+
+        // Now put just the first sled in the cohort, and keep planning until
+        // it's been drained.
+        let cohort_sled_id = example
+            .input
+            .all_sled_ids(SledFilter::Commissioned)
+            .next()
+            .expect("at least one sled");
+        assert!(!cohort_zones(&blueprint1, cohort_sled_id).is_empty());
+        let mut input_builder = example.input.clone().into_builder();
+        input_builder.policy_mut().maintenance_cohort =
+            BTreeSet::from([cohort_sled_id]);
+        let input = input_builder.build();
+
+        /// Planning must not take more than this number of iterations.
+        const MAX_PLANNING_ITERATIONS: usize = 50;
+
+        let mut parent = blueprint1;
+        for i in 2..=MAX_PLANNING_ITERATIONS {
+            update_collection_from_blueprint(&mut example, &parent);
+
+            let blueprint_name = format!("blueprint{i}");
+            let blueprint = Planner::new_based_on(
+                log.clone(),
+                &parent,
+                &input,
+                &blueprint_name,
+                &example.collection,
+                PlannerRng::from_seed((TEST_NAME, &blueprint_name)),
+            )
+            .expect("can't create planner")
+            .plan()
+            .unwrap_or_else(|_| panic!("can't re-plan after {i} iterations"));
+            verify_blueprint(&blueprint);
+            eprintln!("{}\n", blueprint.report);
+
+            // Zones leave the cohort one at a time, and no new ones arrive.
+            let before = cohort_zones(&parent, cohort_sled_id);
+            let after = cohort_zones(&blueprint, cohort_sled_id);
+            assert!(after.is_subset(&before), "zone added to cohort");
+            assert!(before.len() - after.len() <= 1, "expunged too many zones");
+
+            for (kind, target) in targets {
+                let count = count_in_service(&blueprint, kind);
+                if kind == ZoneKind::InternalDns {
+                    assert!(count + 1 >= target, "too few {kind:?} zones");
+                } else {
+                    assert!(count >= target, "too few {kind:?} zones");
+                }
+            }
+
+            let summary = blueprint.diff_since_blueprint(&parent);
+            if summary.total_zones_added() == 0
+                && summary.total_zones_removed() == 0
+                && summary.total_zones_modified() == 0
+            {
+                assert!(
+                    after.is_empty(),
+                    "failed to drain cohort: {after:?} remain"
+                );
+                for (kind, target) in targets {
+                    assert_eq!(
+                        count_in_service(&blueprint, kind),
+                        target,
+                        "wrong number of {kind:?} zones"
+                    );
+                }
+                assert!(blueprint.report.maintenance_cohort.is_empty());
+                println!("planning converged after {i} iterations");
+
+                logctx.cleanup_successful();
+                return;
+            }
+
+            parent = blueprint;
+        }
+
+        panic!("did not converge after {MAX_PLANNING_ITERATIONS} iterations");
+    }
+
+    #[test]
+    fn test_simulate_sled_expungement() {
+        static TEST_NAME: &str = "planner_simulate_sled_expungement";
+        let logctx = test_setup_log(TEST_NAME);
+
+        let mut rng = SimRngState::from_seed(TEST_NAME);
+        let (example, blueprint1) = ExampleSystemBuilder::new_with_rng(
+            &logctx.log,
+            rng.next_system_rng(),
+        )
+        .build();
+        let sled_id = *blueprint1.sleds.keys().next().unwrap();
+
+        // Simulating a no-op change should produce no blueprints at all.
+        let simulation = Planner::simulate(
+            &logctx.log,
+            example.system.clone(),
+            &blueprint1,
+            PolicyChange::TargetNexusZoneCount(
+                example.input.target_nexus_zone_count(),
+            ),
+            PlannerRng::from_seed((TEST_NAME, "no-op")),
+        )
+        .expect("simulated no-op change");
+        assert!(simulation.blueprints().is_empty());
+        assert_eq!(simulation.final_blueprint().id, blueprint1.id);
+
+        // Expunging a sled should eventually leave no in-service zones on it.
+        let simulation = Planner::simulate(
+            &logctx.log,
+            example.system.clone(),
+            &blueprint1,
+            PolicyChange::SledPolicy { sled_id, policy: SledPolicy::Expunged },
+            PlannerRng::from_seed((TEST_NAME, "expunge")),
+        )
+        .expect("simulated sled expungement");
+        assert!(!simulation.blueprints().is_empty());
+        assert_eq!(simulation.diffs().count(), simulation.blueprints().len());
+
+        let mut parent_id = blueprint1.id;
+        for blueprint in simulation.blueprints() {
+            verify_blueprint(blueprint);
+            assert_eq!(blueprint.parent_blueprint_id, Some(parent_id));
+            parent_id = blueprint.id;
+        }
+
+        let final_sled = &simulation.final_blueprint().sleds[&sled_id];
+        assert!(
+            final_sled.zones.iter().all(|z| !z.disposition.is_in_service()),
+            "expected no in-service zones on expunged sled {sled_id}"
+        );
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_clickhouse_keeper_unsafe_to_shutdown() {
+        // Without a clustered clickhouse config, there's nothing to protect.
+        assert_eq!(clickhouse_keeper_unsafe_to_shutdown(None, None), None);
+
+        let mut config = ClickhouseClusterConfig::new(
+            "test_cluster".to_string(),
+            "test_secret".to_string(),
+        );
+        for i in 1..=3 {
+            config.keepers.insert(OmicronZoneUuid::new_v4(), KeeperId(i));
+        }
+        config.max_used_keeper_id = KeeperId(3);
+
+        // We can't tell whether a reconfiguration is in progress without
+        // keeper inventory.
+        assert_eq!(
+            clickhouse_keeper_unsafe_to_shutdown(Some(&config), None),
+            Some(ClickhouseKeeperUnsafeToShutdown::MissingMembership)
+        );
+
+        // The keeper cluster hasn't absorbed the third keeper yet.
+        let mut membership = ClickhouseKeeperClusterMembership {
+            queried_keeper: KeeperId(1),
+            leader_committed_log_index: 1,
+            raft_config: [KeeperId(1), KeeperId(2)].into_iter().collect(),
+        };
+        assert_eq!(
+            clickhouse_keeper_unsafe_to_shutdown(
+                Some(&config),
+                Some(&membership)
+            ),
+            Some(ClickhouseKeeperUnsafeToShutdown::MembershipChangeInProgress)
+        );
+
+        // Once it has, we can shut down a keeper.
+        membership.raft_config.insert(KeeperId(3));
+        membership.leader_committed_log_index = 2;
+        assert_eq!(
+            clickhouse_keeper_unsafe_to_shutdown(
+                Some(&config),
+                Some(&membership)
+            ),
+            None
+        );
+    }
 }
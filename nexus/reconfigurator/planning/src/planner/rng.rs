@@ -78,6 +78,15 @@ impl PlannerRng {
     pub fn next_clickhouse(&mut self) -> Uuid {
         self.clickhouse_rng.next()
     }
+
+    /// Derive an independent `PlannerRng` for planning a subsequent
+    /// blueprint.
+    pub fn next_planner_rng(&mut self) -> PlannerRng {
+        Self::new_from_parent(typed_rng::from_parent_and_seed(
+            &mut self.parent,
+            "next-planner",
+        ))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,252 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Dry-run planning of policy changes
+//!
+//! Before making a policy change (e.g., expunging a sled), operators want to
+//! know what the planner will do in response to it.  [`Planner::simulate`]
+//! applies the change to a simulated [`SystemDescription`], then repeatedly
+//! plans a blueprint and "executes" it against the simulated system until the
+//! planner stops making changes.  Nothing is committed anywhere: the result is
+//! just the sequence of blueprints the planner would produce.
+
+use super::Planner;
+use super::PlannerRng;
+use crate::blueprint_builder::Error;
+use crate::system::SystemDescription;
+use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintDiffSummary;
+use nexus_types::deployment::BlueprintZoneDisposition;
+use nexus_types::deployment::OmicronZoneNic;
+use nexus_types::deployment::PlanningInput;
+use nexus_types::external_api::views::SledPolicy;
+use nexus_types::external_api::views::SledState;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::SledUuid;
+use omicron_uuid_kinds::VnicUuid;
+use slog::{Logger, debug, o};
+
+/// The most blueprints we'll plan before giving up on the planner converging
+///
+/// Real policy changes settle within a handful of blueprints; hitting this
+/// limit almost certainly means the planner is oscillating.
+pub const MAX_SIMULATION_STEPS: usize = 32;
+
+/// Name recorded as the creator of simulated blueprints
+const SIMULATION_CREATOR: &str = "planner simulation";
+
+/// A policy change whose effects should be simulated
+#[derive(Clone, Debug)]
+pub enum PolicyChange {
+    /// Set the policy of a sled (e.g., to expunge it)
+    SledPolicy { sled_id: SledUuid, policy: SledPolicy },
+    /// Set the target number of Nexus zones
+    TargetNexusZoneCount(usize),
+    /// Set the target number of internal DNS zones
+    TargetInternalDnsZoneCount(usize),
+    /// Set the target number of Crucible Pantry zones
+    TargetCruciblePantryZoneCount(usize),
+}
+
+impl PolicyChange {
+    fn apply(&self, system: &mut SystemDescription) -> anyhow::Result<()> {
+        match self {
+            PolicyChange::SledPolicy { sled_id, policy } => {
+                system.sled_set_policy(*sled_id, *policy)?;
+            }
+            PolicyChange::TargetNexusZoneCount(count) => {
+                system.target_nexus_zone_count(*count);
+            }
+            PolicyChange::TargetInternalDnsZoneCount(count) => {
+                system.target_internal_dns_zone_count(*count);
+            }
+            PolicyChange::TargetCruciblePantryZoneCount(count) => {
+                system.target_crucible_pantry_zone_count(*count);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimulateError {
+    #[error("failed to apply policy change")]
+    PolicyChange(#[source] anyhow::Error),
+    #[error("failed to assemble simulated system state (step {step})")]
+    SystemState {
+        step: usize,
+        #[source]
+        err: anyhow::Error,
+    },
+    #[error("planning failed (step {step})")]
+    Planning {
+        step: usize,
+        #[source]
+        err: Error,
+    },
+    #[error("planner did not converge after {MAX_SIMULATION_STEPS} steps")]
+    NotConverged,
+}
+
+/// The blueprints the planner produced in response to a policy change
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    parent_blueprint: Blueprint,
+    blueprints: Vec<Blueprint>,
+}
+
+impl Simulation {
+    /// The blueprint the simulation started from
+    pub fn parent_blueprint(&self) -> &Blueprint {
+        &self.parent_blueprint
+    }
+
+    /// The blueprints produced by the planner, in order
+    ///
+    /// Each blueprint's parent is the one before it (or
+    /// [`Self::parent_blueprint()`], for the first).  This is empty if the
+    /// policy change requires no changes at all.
+    pub fn blueprints(&self) -> &[Blueprint] {
+        &self.blueprints
+    }
+
+    /// The blueprint the system converges to
+    pub fn final_blueprint(&self) -> &Blueprint {
+        self.blueprints.last().unwrap_or(&self.parent_blueprint)
+    }
+
+    /// The differences between each blueprint and its parent, in order
+    pub fn diffs(&self) -> impl Iterator<Item = BlueprintDiffSummary<'_>> {
+        std::iter::once(&self.parent_blueprint)
+            .chain(self.blueprints.iter())
+            .zip(self.blueprints.iter())
+            .map(|(before, after)| after.diff_since_blueprint(before))
+    }
+}
+
+impl Planner<'_> {
+    /// Simulate the blueprints the planner would produce in response to
+    /// `policy_change`
+    ///
+    /// `system` should describe the system as it is now, and
+    /// `parent_blueprint` should be the current target blueprint, which is
+    /// assumed to have been fully executed.  After applying `policy_change`
+    /// to `system`, this plans a blueprint, updates `system` as though that
+    /// blueprint had been executed successfully, and repeats until planning
+    /// makes no further changes.
+    pub fn simulate(
+        log: &Logger,
+        mut system: SystemDescription,
+        parent_blueprint: &Blueprint,
+        policy_change: PolicyChange,
+        mut rng: PlannerRng,
+    ) -> Result<Simulation, SimulateError> {
+        let log = log.new(o!("component" => "PlannerSimulation"));
+        policy_change
+            .apply(&mut system)
+            .map_err(SimulateError::PolicyChange)?;
+        execute_blueprint(&mut system, None, parent_blueprint)
+            .map_err(|err| SimulateError::SystemState { step: 0, err })?;
+
+        let mut blueprints: Vec<Blueprint> = Vec::new();
+        for step in 0..MAX_SIMULATION_STEPS {
+            let parent = blueprints.last().unwrap_or(parent_blueprint);
+            let input = planning_input(&system, parent)
+                .map_err(|err| SimulateError::SystemState { step, err })?;
+            let collection = system
+                .to_collection_builder()
+                .map_err(|err| SimulateError::SystemState { step, err })?
+                .build();
+            let blueprint = Planner::new_based_on(
+                log.clone(),
+                parent,
+                &input,
+                SIMULATION_CREATOR,
+                &collection,
+                rng.next_planner_rng(),
+            )
+            .map_err(|err| SimulateError::SystemState { step, err })?
+            .plan()
+            .map_err(|err| SimulateError::Planning { step, err })?;
+
+            if !blueprint.diff_since_blueprint(parent).has_changes() {
+                debug!(log, "planner converged"; "steps" => step);
+                return Ok(Simulation {
+                    parent_blueprint: parent_blueprint.clone(),
+                    blueprints,
+                });
+            }
+
+            execute_blueprint(&mut system, Some(parent), &blueprint)
+                .map_err(|err| SimulateError::SystemState { step, err })?;
+            blueprints.push(blueprint);
+        }
+
+        Err(SimulateError::NotConverged)
+    }
+}
+
+/// Assemble the planning input for `system`, whose current target is
+/// `blueprint`
+///
+/// [`SystemDescription`] doesn't track external networking resources, which
+/// are recorded in the database as blueprints are executed; fill them in from
+/// the blueprint.
+fn planning_input(
+    system: &SystemDescription,
+    blueprint: &Blueprint,
+) -> anyhow::Result<PlanningInput> {
+    let mut builder = system.to_planning_input_builder()?;
+    for (_, zone) in blueprint.all_omicron_zones(BlueprintZoneDisposition::any)
+    {
+        if let Some((external_ip, nic)) = zone.zone_type.external_networking() {
+            builder.add_omicron_zone_external_ip(zone.id, external_ip)?;
+            builder.add_omicron_zone_nic(
+                zone.id,
+                OmicronZoneNic {
+                    id: VnicUuid::from_untyped_uuid(nic.id),
+                    mac: nic.mac,
+                    ip: nic.ip,
+                    slot: nic.slot,
+                    primary: nic.primary,
+                },
+            )?;
+        }
+    }
+    Ok(builder.build())
+}
+
+/// Update `system` as though `blueprint` had been successfully executed
+///
+/// Datasets in `previous` (the blueprint executed before this one, if any) are
+/// assumed to already exist in `system`.
+fn execute_blueprint(
+    system: &mut SystemDescription,
+    previous: Option<&Blueprint>,
+    blueprint: &Blueprint,
+) -> anyhow::Result<()> {
+    for (sled_id, sled_config) in &blueprint.sleds {
+        if sled_config.state == SledState::Decommissioned {
+            continue;
+        }
+        system.sled_set_omicron_config(
+            *sled_id,
+            sled_config.clone().into_in_service_sled_config(),
+        )?;
+
+        let Some(previous) = previous else {
+            continue;
+        };
+        let sled = system.get_sled_mut(*sled_id)?;
+        for dataset in sled_config.datasets.iter() {
+            let existed = previous.sleds.get(sled_id).is_some_and(|config| {
+                config.datasets.contains_key(&dataset.id)
+            });
+            if !existed && dataset.disposition.is_in_service() {
+                sled.add_synthetic_dataset(dataset.clone().into());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Checks for the supported version skew between Nexus and the rest of the
+//! system.
+//!
+//! Nexus supports running alongside host OS and SP software from its own
+//! release or the release immediately before it. Normally the update ordering
+//! guarantees this (Nexus is the last thing updated, after every SP and host
+//! OS), but a component that can't be updated -- e.g., because it's missing
+//! from inventory or its update keeps failing -- could otherwise be left
+//! behind as Nexus moves on to the next release.
+
+use gateway_client::types::SpType;
+use nexus_types::deployment::PlanningInput;
+use nexus_types::deployment::SledFilter;
+use nexus_types::deployment::VersionSkewViolation;
+use nexus_types::inventory::CabooseWhich;
+use nexus_types::inventory::Collection;
+use omicron_common::api::external::TufRepoDescription;
+use slog::Logger;
+use slog::warn;
+use std::collections::BTreeSet;
+use tufaceous_artifact::ArtifactKind;
+use tufaceous_artifact::KnownArtifactKind;
+
+/// Returns the components that would be further behind than we support if
+/// Nexus were updated to the current target release.
+///
+/// We can only tell how far behind a component is if we know both the target
+/// release and the one before it. If either is missing (e.g., because only
+/// one target release has ever been set), no violations are reported.
+/// Components whose current software we can't determine from inventory are
+/// also not reported.
+pub(crate) fn find_version_skew_violations(
+    log: &Logger,
+    input: &PlanningInput,
+    inventory: &Collection,
+) -> Vec<VersionSkewViolation> {
+    let (Some(new_repo), Some(old_repo)) = (
+        input.tuf_repo().description().tuf_repo(),
+        input.old_repo().description().tuf_repo(),
+    ) else {
+        return Vec::new();
+    };
+    let supported_repos = [new_repo, old_repo];
+
+    let mut violations = Vec::new();
+    find_sp_violations(
+        log,
+        input,
+        inventory,
+        &supported_repos,
+        &mut violations,
+    );
+    find_host_os_violations(
+        log,
+        input,
+        inventory,
+        &supported_repos,
+        &mut violations,
+    );
+    violations
+}
+
+fn find_sp_violations(
+    log: &Logger,
+    input: &PlanningInput,
+    inventory: &Collection,
+    supported_repos: &[&TufRepoDescription],
+    violations: &mut Vec<VersionSkewViolation>,
+) {
+    // Consider the same SPs that we would update: those of sleds that are part
+    // of the control plane, plus all switches and PSCs.
+    let included_sled_baseboards: BTreeSet<_> = input
+        .all_sleds(SledFilter::SpsUpdatedByReconfigurator)
+        .map(|(_sled_id, details)| &details.baseboard_id)
+        .collect();
+
+    for (baseboard_id, sp_state) in &inventory.sps {
+        if sp_state.sp_type == SpType::Sled
+            && !included_sled_baseboards.contains(baseboard_id.as_ref())
+        {
+            continue;
+        }
+
+        let Some(active_caboose) =
+            inventory.caboose_for(CabooseWhich::SpSlot0, baseboard_id)
+        else {
+            warn!(
+                log,
+                "cannot check SP version skew for board \
+                 (missing active caboose from inventory)";
+                baseboard_id,
+            );
+            continue;
+        };
+        let caboose = &active_caboose.caboose;
+
+        // Only artifacts built for this board are relevant. If neither release
+        // contains any, we have no basis for a comparison.
+        let mut sp_artifacts = supported_repos
+            .iter()
+            .flat_map(|repo| repo.artifacts.iter())
+            .filter(|artifact| {
+                artifact.board.as_ref() == Some(&caboose.board)
+                    && matches!(
+                        artifact.id.kind.to_known(),
+                        Some(
+                            KnownArtifactKind::GimletSp
+                                | KnownArtifactKind::PscSp
+                                | KnownArtifactKind::SwitchSp
+                        )
+                    )
+            })
+            .peekable();
+        if sp_artifacts.peek().is_none() {
+            continue;
+        }
+        if !sp_artifacts
+            .any(|artifact| artifact.id.version.as_str() == caboose.version)
+        {
+            violations.push(VersionSkewViolation::Sp {
+                serial_number: baseboard_id.serial_number.clone(),
+                version: caboose.version.clone(),
+            });
+        }
+    }
+}
+
+fn find_host_os_violations(
+    log: &Logger,
+    input: &PlanningInput,
+    inventory: &Collection,
+    supported_repos: &[&TufRepoDescription],
+    violations: &mut Vec<VersionSkewViolation>,
+) {
+    for sled_id in input.all_sled_ids(SledFilter::InService) {
+        let Some(last_reconciliation) = inventory
+            .sled_agents
+            .get(&sled_id)
+            .and_then(|sled_agent| sled_agent.last_reconciliation.as_ref())
+        else {
+            warn!(
+                log,
+                "cannot check host OS version skew for sled \
+                 (missing last reconciliation details from inventory)";
+                "sled_id" => %sled_id,
+            );
+            continue;
+        };
+        let boot_partitions = &last_reconciliation.boot_partitions;
+        let Ok(boot_disk) = &boot_partitions.boot_disk else {
+            warn!(
+                log,
+                "cannot check host OS version skew for sled \
+                 (sled-agent reported an error determining boot disk)";
+                "sled_id" => %sled_id,
+            );
+            continue;
+        };
+        let Ok(details) = boot_partitions.slot_details(*boot_disk) else {
+            warn!(
+                log,
+                "cannot check host OS version skew for sled \
+                 (sled-agent reported an error reading boot disk)";
+                "sled_id" => %sled_id,
+            );
+            continue;
+        };
+
+        let hash = details.artifact_hash;
+        if !supported_repos.iter().any(|repo| {
+            repo.artifacts.iter().any(|artifact| {
+                artifact.id.kind == ArtifactKind::HOST_PHASE_2
+                    && artifact.hash == hash
+            })
+        }) {
+            violations.push(VersionSkewViolation::HostOs { sled_id, hash });
+        }
+    }
+}
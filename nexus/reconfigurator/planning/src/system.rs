@@ -54,6 +54,7 @@ use nexus_types::inventory::PowerState;
 use nexus_types::inventory::RotSlot;
 use nexus_types::inventory::SpType;
 use omicron_common::address::IpRange;
+use omicron_common::address::Ipv6Range;
 use omicron_common::address::Ipv6Subnet;
 use omicron_common::address::RACK_PREFIX;
 use omicron_common::address::SLED_PREFIX;
@@ -117,6 +118,8 @@ pub struct SystemDescription {
     target_cockroachdb_cluster_version: CockroachDbClusterVersion,
     target_crucible_pantry_zone_count: usize,
     service_ip_pool_ranges: Vec<IpRange>,
+    reserved_underlay_ranges: Vec<Ipv6Range>,
+    maintenance_cohort: BTreeSet<SledUuid>,
     internal_dns_version: Generation,
     external_dns_version: Generation,
     clickhouse_policy: Option<ClickhousePolicy>,
@@ -200,6 +203,8 @@ impl SystemDescription {
             target_cockroachdb_cluster_version,
             target_crucible_pantry_zone_count,
             service_ip_pool_ranges,
+            reserved_underlay_ranges: Vec::new(),
+            maintenance_cohort: BTreeSet::new(),
             internal_dns_version: Generation::new(),
             external_dns_version: Generation::new(),
             clickhouse_policy: None,
@@ -282,6 +287,28 @@ impl SystemDescription {
         self
     }
 
+    /// Set the underlay IPv6 ranges that must not be allocated to new zones
+    pub fn reserved_underlay_ranges(
+        &mut self,
+        ranges: Vec<Ipv6Range>,
+    ) -> &mut Self {
+        self.reserved_underlay_ranges = ranges;
+        self
+    }
+
+    /// Set the sleds to be drained of discretionary zones together
+    pub fn maintenance_cohort(
+        &mut self,
+        sled_ids: BTreeSet<SledUuid>,
+    ) -> &mut Self {
+        self.maintenance_cohort = sled_ids;
+        self
+    }
+
+    pub fn get_maintenance_cohort(&self) -> &BTreeSet<SledUuid> {
+        &self.maintenance_cohort
+    }
+
     /// Set the clickhouse policy
     pub fn clickhouse_policy(&mut self, policy: ClickhousePolicy) -> &mut Self {
         self.clickhouse_policy = Some(policy);
@@ -1009,6 +1036,8 @@ impl SystemDescription {
     ) -> anyhow::Result<PlanningInputBuilder> {
         let policy = Policy {
             service_ip_pool_ranges: self.service_ip_pool_ranges.clone(),
+            reserved_underlay_ranges: self.reserved_underlay_ranges.clone(),
+            maintenance_cohort: self.maintenance_cohort.clone(),
             target_boundary_ntp_zone_count: self.target_boundary_ntp_zone_count,
             target_nexus_zone_count: self.target_nexus_zone_count,
             target_internal_dns_zone_count: self.target_internal_dns_zone_count,
@@ -1319,6 +1348,10 @@ impl Sled {
                     .map(|id| InventoryZpool {
                         id: *id,
                         total_size: ByteCount::from_gibibytes_u32(100),
+                        allocated: None,
+                        fragmentation_percent: None,
+                        scrub: None,
+                        health: None,
                     })
                     .collect(),
                 datasets: vec![],
@@ -237,6 +237,12 @@ impl PlanningInputFromDb<'_> {
             self.ip_pool_range_rows.iter().map(IpRange::from).collect();
         let policy = Policy {
             service_ip_pool_ranges,
+            // TODO: Reserved underlay ranges are not yet stored in the
+            // database; until they are, nothing is reserved on a real system.
+            reserved_underlay_ranges: Vec::new(),
+            // TODO: Likewise, there's no way to choose a maintenance cohort
+            // on a real system yet.
+            maintenance_cohort: BTreeSet::new(),
             target_boundary_ntp_zone_count: self.target_boundary_ntp_zone_count,
             target_nexus_zone_count: self.target_nexus_zone_count,
             target_internal_dns_zone_count: self.target_internal_dns_zone_count,
@@ -887,6 +887,12 @@ impl SimSystemBuilderInner {
         );
         system_res.service_ip_pool_ranges =
             state.planning_input.service_ip_pool_ranges().to_vec();
+        self.system.description.reserved_underlay_ranges(
+            state.planning_input.reserved_underlay_ranges().to_vec(),
+        );
+        self.system.description.maintenance_cohort(
+            state.planning_input.maintenance_cohort().clone(),
+        );
 
         self.set_internal_dns(state.internal_dns);
         self.set_external_dns(state.external_dns);
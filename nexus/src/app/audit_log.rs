@@ -9,6 +9,8 @@ use nexus_db_model::{
     AuditLogEntryInitParams,
 };
 use nexus_db_queries::context::OpContext;
+use nexus_db_queries::db::datastore::RackActivityFilters;
+use nexus_db_queries::db::datastore::RackActivityRecord;
 use omicron_common::api::external::{
     CreateResult, DataPageParams, ListResultVec, UpdateResult,
 };
@@ -41,6 +43,17 @@ impl super::Nexus {
             .await
     }
 
+    /// List the rack activity feed, which combines the audit log with other
+    /// records of what happened to the rack
+    pub(crate) async fn rack_activity_list(
+        &self,
+        opctx: &OpContext,
+        filters: &RackActivityFilters,
+        pagparams: &DataPageParams<'_, (DateTime<Utc>, Uuid)>,
+    ) -> ListResultVec<RackActivityRecord> {
+        self.db_datastore.rack_activity_list(opctx, filters, pagparams).await
+    }
+
     /// Use for authenticated operations because we want to pull the actor from
     /// the opctx.
     pub(crate) async fn audit_log_entry_init(
@@ -26,11 +26,16 @@ use nexus_types::internal_api::views::LastResult;
 use nexus_types::internal_api::views::LastResultCompleted;
 use nexus_types::internal_api::views::TaskStatus;
 use std::collections::BTreeMap;
+use std::collections::VecDeque;
 use std::time::Duration;
 use std::time::Instant;
 use tokio::sync::watch;
 use tokio::time::MissedTickBehavior;
 
+/// Number of completed activations kept for each task (see
+/// [`Driver::task_history()`])
+pub const TASK_HISTORY_LEN: usize = 16;
+
 /// Drives the execution of background tasks
 ///
 /// Nexus has only one Driver.  All background tasks are registered with the
@@ -47,10 +52,19 @@ struct Task {
     /// what this task does (for developers)
     description: String,
     /// configured period of the task
-    period: Duration,
+    ///
+    /// This starts out as the period the task was registered with, but can be
+    /// changed at runtime with [`Driver::set_task_period()`].
+    period: watch::Sender<Duration>,
     /// channel used to receive updates from the background task's tokio task
     /// about what the background task is doing
     status: watch::Receiver<TaskStatus>,
+    /// channel used to receive the most recently completed activations of
+    /// the background task, oldest first
+    history: watch::Receiver<VecDeque<LastResultCompleted>>,
+    /// channel that the background task's tokio task updates each time an
+    /// activation completes (see [`Driver::completion_watcher()`])
+    completions: watch::Receiver<u64>,
     /// join handle for the tokio task that's executing this background task
     tokio_task: tokio::task::JoinHandle<()>,
     /// `Activator` used to wake up the tokio task when a caller explicit wants to
@@ -83,7 +97,9 @@ impl Driver {
     /// activate the background task when any of these receivers' data has
     /// changed.  This can be used to create dependencies between background
     /// tasks, so that when one of them finishes doing something, it kicks off
-    /// another one.
+    /// another one.  To run a task after every activation of another task
+    /// (rather than only when it produces new data), use
+    /// [`Driver::completion_watcher()`].
     ///
     /// `activator` is an [`Activator`] that has not previously been used in a
     /// call to this function.  It will be wired up so that using it will
@@ -112,6 +128,9 @@ impl Driver {
             current: CurrentStatus::Idle,
             last: LastResult::NeverCompleted,
         });
+        let (history_tx, history_rx) =
+            watch::channel(VecDeque::with_capacity(TASK_HISTORY_LEN));
+        let (completions_tx, completions_rx) = watch::channel(0);
 
         // We'll use a `Notify` to wake up that tokio task when an activation is
         // requested.  The caller provides their own Activator, which just
@@ -130,13 +149,16 @@ impl Driver {
             "background_task".to_string(),
             name.clone(),
         )]));
+        let (period_tx, period_rx) = watch::channel(taskdef.period);
         let task_exec = TaskExec::new(
             &name,
-            taskdef.period,
+            period_rx,
             taskdef.task_impl,
             activator.clone(),
             opctx,
             status_tx,
+            history_tx,
+            completions_tx,
         );
         let tokio_task = tokio::task::spawn(task_exec.run(taskdef.watchers));
 
@@ -145,8 +167,10 @@ impl Driver {
         // tokio task.
         let task = Task {
             description: taskdef.description.to_string(),
-            period: taskdef.period,
+            period: period_tx,
             status: status_rx,
+            history: history_rx,
+            completions: completions_rx,
             tokio_task,
             activator: activator.clone(),
         };
@@ -184,7 +208,39 @@ impl Driver {
 
     /// Returns the configured period of the task
     pub fn task_period(&self, task: &TaskName) -> Duration {
-        self.task_required(task).period
+        *self.task_required(task).period.borrow()
+    }
+
+    /// Change the period of the specified background task
+    ///
+    /// The new period takes effect immediately: the task will next be
+    /// activated periodically `period` from now (unless it's activated for
+    /// some other reason before then).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `period` is zero.
+    pub(super) fn set_task_period(&self, task: &TaskName, period: Duration) {
+        assert!(!period.is_zero(), "background task period must be non-zero");
+        self.task_required(task).period.send_replace(period);
+    }
+
+    /// Returns a watcher that changes each time the specified task completes
+    /// an activation
+    ///
+    /// Passing this in the `watchers` of another task's [`TaskDefinition`]
+    /// makes that task a dependent of this one: it will be activated (with
+    /// reason [`ActivationReason::Dependency`]) after each activation of this
+    /// task, in addition to its own periodic activations.  Completions that
+    /// happened before this function was called don't trigger an activation.
+    ///
+    /// Since a task has to be registered before it can be named here, and
+    /// dependents are registered after the tasks they depend on, dependencies
+    /// created this way can't form a cycle.
+    pub fn completion_watcher(&self, task: &TaskName) -> watch::Receiver<u64> {
+        let mut watcher = self.task_required(task).completions.clone();
+        watcher.mark_unchanged();
+        watcher
     }
 
     /// Activate the specified background task
@@ -202,6 +258,19 @@ impl Driver {
         // on to a reference.
         self.task_required(task).status.borrow().clone()
     }
+
+    /// Returns up to [`TASK_HISTORY_LEN`] of the most recently completed
+    /// activations of the background task, most recent first
+    pub fn task_history(&self, task: &TaskName) -> Vec<LastResultCompleted> {
+        // As with `task_status()`, don't hang on to the borrow.
+        self.task_required(task)
+            .history
+            .borrow()
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
 }
 
 impl Drop for Driver {
@@ -240,7 +309,7 @@ struct TaskExec {
     /// the name of this background task
     name: String,
     /// how often the background task should be activated
-    period: Duration,
+    period: watch::Receiver<Duration>,
     /// impl of the background task
     imp: Box<dyn BackgroundTask>,
     /// used to receive notifications from the Driver that someone has requested
@@ -250,6 +319,10 @@ struct TaskExec {
     opctx: OpContext,
     /// used to send current status back to the Driver
     status_tx: watch::Sender<TaskStatus>,
+    /// used to send recently completed activations back to the Driver
+    history_tx: watch::Sender<VecDeque<LastResultCompleted>>,
+    /// used to notify dependent tasks each time an activation completes
+    completions_tx: watch::Sender<u64>,
     /// counts iterations of the task, for debuggability
     iteration: u64,
 }
@@ -257,11 +330,13 @@ struct TaskExec {
 impl TaskExec {
     fn new(
         name: impl ToString,
-        period: Duration,
+        period: watch::Receiver<Duration>,
         imp: Box<dyn BackgroundTask>,
         activation: Activator,
         opctx: OpContext,
         status_tx: watch::Sender<TaskStatus>,
+        history_tx: watch::Sender<VecDeque<LastResultCompleted>>,
+        completions_tx: watch::Sender<u64>,
     ) -> TaskExec {
         let name = name.to_string();
         TaskExec {
@@ -271,13 +346,16 @@ impl TaskExec {
             activation,
             opctx,
             status_tx,
+            history_tx,
+            completions_tx,
             iteration: 0,
         }
     }
 
     /// Body of the tokio task that manages activation of this background task
     async fn run(mut self, mut deps: Vec<Box<dyn GenericWatcher>>) {
-        let mut interval = tokio::time::interval(self.period);
+        let period = *self.period.borrow_and_update();
+        let mut interval = tokio::time::interval(period);
         interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
         // Wait for either the timeout to elapse, or an explicit activation
@@ -299,6 +377,19 @@ impl TaskExec {
                 _ = dependencies.next(), if !dependencies.is_empty() => {
                     self.activate(ActivationReason::Dependency).await;
                 }
+
+                Ok(()) = self.period.changed() => {
+                    // Our period was changed at runtime.  Restart the clock
+                    // with the new period, without activating the task now.
+                    let period = *self.period.borrow_and_update();
+                    interval = tokio::time::interval_at(
+                        tokio::time::Instant::now() + period,
+                        period,
+                    );
+                    interval.set_missed_tick_behavior(
+                        MissedTickBehavior::Delay,
+                    );
+                }
             }
         }
     }
@@ -346,23 +437,33 @@ impl TaskExec {
 
         let elapsed = start_instant.elapsed();
 
-        // Update our status with the driver.
+        // Update our status and history with the driver.
+        let completed = LastResultCompleted {
+            iteration,
+            start_time,
+            reason,
+            elapsed,
+            details,
+        };
+        self.history_tx.send_modify(|history| {
+            if history.len() == TASK_HISTORY_LEN {
+                history.pop_front();
+            }
+            history.push_back(completed.clone());
+        });
         self.status_tx.send_modify(|status| {
             assert!(!status.current.is_idle());
             let current = status.current.unwrap_running();
             assert_eq!(current.iteration, iteration);
             *status = TaskStatus {
                 current: CurrentStatus::Idle,
-                last: LastResult::Completed(LastResultCompleted {
-                    iteration,
-                    start_time,
-                    reason,
-                    elapsed,
-                    details,
-                }),
+                last: LastResult::Completed(completed),
             };
         });
 
+        // Kick off any tasks that depend on this one.
+        self.completions_tx.send_replace(iteration);
+
         debug!(
             &self.opctx.log,
             "activation complete";
@@ -396,6 +497,7 @@ impl<T: Send + Sync> GenericWatcher for watch::Receiver<T> {
 mod test {
     use super::BackgroundTask;
     use super::Driver;
+    use super::TASK_HISTORY_LEN;
     use crate::app::background::Activator;
     use crate::app::background::driver::TaskDefinition;
     use assert_matches::assert_matches;
@@ -611,6 +713,179 @@ mod test {
         assert_eq!(last.iteration, 4);
     }
 
+    // Verifies that changing a task's period at runtime takes effect without
+    // re-registering the task.
+    #[nexus_test(server = crate::Server)]
+    async fn test_driver_set_period(cptestctx: &ControlPlaneTestContext) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let (t1, rx1) = ReportingTask::new();
+        let act1 = Activator::new();
+        let mut driver = Driver::new();
+        let h1 = driver.register(TaskDefinition {
+            name: "t1",
+            description: "test task",
+            period: Duration::from_secs(300), // should not elapse during test
+            task_impl: Box::new(t1),
+            opctx,
+            watchers: vec![],
+            activator: &act1,
+        });
+        assert_eq!(driver.task_period(&h1), Duration::from_secs(300));
+
+        // Wait for the beginning-of-time activation.
+        wait_until_count(rx1.clone(), 1).await;
+
+        // Shorten the period.  The task should now be activated periodically
+        // at the new period.
+        let start = Instant::now();
+        driver.set_task_period(&h1, Duration::from_millis(100));
+        assert_eq!(driver.task_period(&h1), Duration::from_millis(100));
+        wait_until_count(rx1.clone(), 3).await;
+        let duration = start.elapsed();
+        assert!(duration.as_millis() >= 200);
+        let status = driver.task_status(&h1);
+        let last = status.last.unwrap_completion();
+        assert_eq!(last.reason, ActivationReason::Timeout);
+
+        // Lengthen it again.  The task should stop being activated.
+        driver.set_task_period(&h1, Duration::from_secs(300));
+        let count = *rx1.borrow();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(*rx1.borrow() <= count + 1);
+    }
+
+    async fn wait_for_completion(
+        completions: &mut watch::Receiver<u64>,
+        iteration: u64,
+    ) {
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            completions.wait_for(|i| *i >= iteration),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    }
+
+    // Verifies that the driver keeps a bounded history of each task's
+    // completed activations, most recent first.
+    #[nexus_test(server = crate::Server)]
+    async fn test_driver_history(cptestctx: &ControlPlaneTestContext) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let (t1, _rx1) = ReportingTask::new();
+        let act1 = Activator::new();
+        let mut driver = Driver::new();
+        let h1 = driver.register(TaskDefinition {
+            name: "t1",
+            description: "test task",
+            period: Duration::from_secs(300), // should not elapse during test
+            task_impl: Box::new(t1),
+            opctx,
+            watchers: vec![],
+            activator: &act1,
+        });
+
+        // The completion watcher is updated only after the history has been,
+        // so waiting on it avoids racing with the driver.
+        let mut completions = driver.completion_watcher(&h1);
+
+        // Wait for the beginning-of-time activation.
+        wait_for_completion(&mut completions, 1).await;
+        let history = driver.task_history(&h1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].iteration, 1);
+        assert_eq!(history[0].reason, ActivationReason::Timeout);
+
+        // Activate the task enough times to overflow the history.
+        let total = u64::try_from(TASK_HISTORY_LEN).unwrap() + 2;
+        for iteration in 2..=total {
+            driver.activate(&h1);
+            wait_for_completion(&mut completions, iteration).await;
+        }
+
+        let history = driver.task_history(&h1);
+        assert_eq!(history.len(), TASK_HISTORY_LEN);
+        let iterations: Vec<_> = history.iter().map(|h| h.iteration).collect();
+        let expected: Vec<_> = (3..=total).rev().collect();
+        assert_eq!(iterations, expected);
+        for h in &history {
+            assert_eq!(h.details, serde_json::json!(h.iteration));
+        }
+        assert!(history.iter().all(|h| h.reason == ActivationReason::Signaled));
+    }
+
+    // Verifies that a task that watches another task's completions is
+    // activated after each activation of that task, and not the other way
+    // around.
+    #[nexus_test(server = crate::Server)]
+    async fn test_driver_completion_watcher(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let (t1, rx1) = ReportingTask::new();
+        let (t2, rx2) = ReportingTask::new();
+        let act1 = Activator::new();
+        let act2 = Activator::new();
+        let mut driver = Driver::new();
+        let h1 = driver.register(TaskDefinition {
+            name: "t1",
+            description: "test task",
+            period: Duration::from_secs(300), // should not elapse during test
+            task_impl: Box::new(t1),
+            opctx: opctx.child(std::collections::BTreeMap::new()),
+            watchers: vec![],
+            activator: &act1,
+        });
+
+        // Wait for the beginning-of-time activation of "t1" before registering
+        // "t2" so that it doesn't trigger an activation of "t2".
+        wait_until_count(rx1.clone(), 1).await;
+        let h2 = driver.register(TaskDefinition {
+            name: "t2",
+            description: "test task",
+            period: Duration::from_secs(300), // should not elapse during test
+            task_impl: Box::new(t2),
+            opctx,
+            watchers: vec![Box::new(driver.completion_watcher(&h1))],
+            activator: &act2,
+        });
+        wait_until_count(rx2.clone(), 1).await;
+
+        // Activating "t1" should activate "t2" once it's done.
+        act1.activate();
+        wait_until_count(rx1.clone(), 2).await;
+        wait_until_count(rx2.clone(), 2).await;
+        let status = driver.task_status(&h2);
+        let last = status.last.unwrap_completion();
+        assert_eq!(last.iteration, 2);
+        assert_eq!(last.reason, ActivationReason::Dependency);
+
+        // Activating "t2" should not activate "t1".
+        act2.activate();
+        wait_until_count(rx2.clone(), 3).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(*rx1.borrow(), 2);
+        assert_eq!(*rx2.borrow(), 3);
+    }
+
     /// Simple background task that moves in lockstep with a consumer, allowing
     /// the creator to be notified when it becomes active and to determine when
     /// the activation finishes.
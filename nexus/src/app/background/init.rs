@@ -97,7 +97,9 @@ use super::tasks::blueprint_load;
 use super::tasks::blueprint_planner;
 use super::tasks::blueprint_rendezvous;
 use super::tasks::chicken_switches::ChickenSwitchesLoader;
+use super::tasks::clickhouse_backup;
 use super::tasks::crdb_node_id_collector;
+use super::tasks::dataset_quota_tuner;
 use super::tasks::decommissioned_disk_cleaner;
 use super::tasks::dns_config;
 use super::tasks::dns_propagation;
@@ -120,8 +122,10 @@ use super::tasks::region_snapshot_replacement_finish::*;
 use super::tasks::region_snapshot_replacement_garbage_collect::*;
 use super::tasks::region_snapshot_replacement_start::*;
 use super::tasks::region_snapshot_replacement_step::*;
+use super::tasks::rolling_sled_reboot;
 use super::tasks::saga_recovery;
 use super::tasks::service_firewall_rules;
+use super::tasks::snapshot_scheduler;
 use super::tasks::support_bundle_collector;
 use super::tasks::sync_service_zone_nat::ServiceZoneNatTracker;
 use super::tasks::sync_switch_configuration::SwitchPortSettingsManager;
@@ -129,6 +133,8 @@ use super::tasks::tuf_artifact_replication;
 use super::tasks::v2p_mappings::V2PManager;
 use super::tasks::vpc_routes;
 use super::tasks::webhook_deliverator;
+use super::tasks::zpool_scrub_alerts;
+use super::tasks::zpool_usage_trends;
 use crate::Nexus;
 use crate::app::oximeter::PRODUCER_LEASE_DURATION;
 use crate::app::quiesce::NexusQuiesceHandle;
@@ -140,6 +146,7 @@ use nexus_config::DnsTasksConfig;
 use nexus_db_model::DnsGroup;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db::DataStore;
+use nexus_types::clock::SystemClock;
 use nexus_types::deployment::PendingMgsUpdates;
 use omicron_uuid_kinds::OmicronZoneUuid;
 use oximeter::types::ProducerRegistry;
@@ -232,7 +239,13 @@ impl BackgroundTasksInitializer {
             task_alert_dispatcher: Activator::new(),
             task_webhook_deliverator: Activator::new(),
             task_sp_ereport_ingester: Activator::new(),
+            task_clickhouse_backup: Activator::new(),
+            task_dataset_quota_tuner: Activator::new(),
+            task_snapshot_scheduler: Activator::new(),
             task_chicken_switches_loader: Activator::new(),
+            task_zpool_usage_trends: Activator::new(),
+            task_zpool_scrub_alerts: Activator::new(),
+            task_rolling_sled_reboot: Activator::new(),
 
             task_internal_dns_propagation: Activator::new(),
             task_external_dns_propagation: Activator::new(),
@@ -309,7 +322,13 @@ impl BackgroundTasksInitializer {
             task_alert_dispatcher,
             task_webhook_deliverator,
             task_sp_ereport_ingester,
+            task_clickhouse_backup,
+            task_dataset_quota_tuner,
+            task_snapshot_scheduler,
             task_chicken_switches_loader,
+            task_zpool_usage_trends,
+            task_zpool_scrub_alerts,
+            task_rolling_sled_reboot,
             // Add new background tasks here.  Be sure to use this binding in a
             // call to `Driver::register()` below.  That's what actually wires
             // up the Activator to the corresponding background task.
@@ -494,15 +513,95 @@ impl BackgroundTasksInitializer {
             activator: task_chicken_switches_loader,
         });
 
+        // Background task: dataset quota tuner
+        //
+        // Proposes quotas for datasets based on the usage reported in each
+        // new inventory collection. The planner applies these proposals.
+        let dataset_quota_tuner = dataset_quota_tuner::DatasetQuotaTuner::new(
+            datastore.clone(),
+            inventory_watcher.clone(),
+            rx_blueprint.clone(),
+            dataset_quota_tuner::DatasetQuotaPolicy {
+                headroom_percent: config.dataset_quota_tuner.headroom_percent,
+                min_quota_gib: config.dataset_quota_tuner.min_quota_gib,
+                max_quota_gib: config.dataset_quota_tuner.max_quota_gib,
+            },
+            config.dataset_quota_tuner.disable,
+        );
+        let rx_dataset_quotas = dataset_quota_tuner.watcher();
+        driver.register(TaskDefinition {
+            name: "dataset_quota_tuner",
+            description: "proposes quotas for Crucible and CockroachDB \
+                datasets based on their observed usage",
+            period: config.dataset_quota_tuner.period_secs,
+            task_impl: Box::new(dataset_quota_tuner),
+            opctx: opctx.child(BTreeMap::new()),
+            watchers: vec![Box::new(inventory_watcher.clone())],
+            activator: task_dataset_quota_tuner,
+        });
+
+        // Background task: zpool usage trends
+        //
+        // Records the usage reported for each zpool in each new inventory
+        // collection and raises alerts for zpools that are projected to fill
+        // up soon.
+        let zpool_usage_trends = zpool_usage_trends::ZpoolUsageTrends::new(
+            datastore.clone(),
+            inventory_watcher.clone(),
+            task_alert_dispatcher.clone(),
+            zpool_usage_trends::ZpoolTrendPolicy {
+                retention: chrono::TimeDelta::days(
+                    config.zpool_usage_trends.retention_days.into(),
+                ),
+                trend_window: chrono::TimeDelta::days(
+                    config.zpool_usage_trends.trend_window_days.into(),
+                ),
+                threshold_percent: config.zpool_usage_trends.threshold_percent,
+                warning_days: config.zpool_usage_trends.warning_days,
+            },
+            config.zpool_usage_trends.disable,
+        );
+        driver.register(TaskDefinition {
+            name: "zpool_usage_trends",
+            description: "records zpool usage over time and raises alerts \
+                for zpools projected to fill up soon",
+            period: config.zpool_usage_trends.period_secs,
+            task_impl: Box::new(zpool_usage_trends),
+            opctx: opctx.child(BTreeMap::new()),
+            watchers: vec![Box::new(inventory_watcher.clone())],
+            activator: task_zpool_usage_trends,
+        });
+
+        // Background task: zpool scrub alerts
+        //
+        // Raises alerts for zpool scrubs reported in each new inventory
+        // collection that found errors.
+        let zpool_scrub_alerts = zpool_scrub_alerts::ZpoolScrubAlerts::new(
+            datastore.clone(),
+            inventory_watcher.clone(),
+            task_alert_dispatcher.clone(),
+            config.zpool_scrub_alerts.disable,
+        );
+        driver.register(TaskDefinition {
+            name: "zpool_scrub_alerts",
+            description: "raises alerts for zpool scrubs that found errors",
+            period: config.zpool_scrub_alerts.period_secs,
+            task_impl: Box::new(zpool_scrub_alerts),
+            opctx: opctx.child(BTreeMap::new()),
+            watchers: vec![Box::new(inventory_watcher.clone())],
+            activator: task_zpool_scrub_alerts,
+        });
+
         // Background task: blueprint planner
         //
-        // Replans on inventory collection and changes to the current
-        // target blueprint.
+        // Replans on inventory collection, changes to the current target
+        // blueprint, and new dataset quota proposals.
         let blueprint_planner = blueprint_planner::BlueprintPlanner::new(
             datastore.clone(),
             chicken_switches_watcher.clone(),
             inventory_watcher.clone(),
             rx_blueprint.clone(),
+            rx_dataset_quotas.clone(),
         );
         let rx_planner = blueprint_planner.watcher();
         driver.register(TaskDefinition {
@@ -515,6 +614,7 @@ impl BackgroundTasksInitializer {
                 Box::new(inventory_watcher.clone()),
                 Box::new(rx_blueprint.clone()),
                 Box::new(chicken_switches_watcher),
+                Box::new(rx_dataset_quotas),
             ],
             activator: task_blueprint_planner,
         });
@@ -700,7 +800,12 @@ impl BackgroundTasksInitializer {
             });
         };
 
-        {
+        // Background task: check instance states with sled agents
+        //
+        // The tasks below that act on instance states run after each
+        // activation of this one, so that problems it finds are acted on
+        // without waiting for their own periodic activations.
+        let instance_watcher = {
             let watcher = instance_watcher::InstanceWatcher::new(
                 datastore.clone(),
                 sagas.clone(),
@@ -732,7 +837,9 @@ impl BackgroundTasksInitializer {
                 period: config.instance_watcher.period_secs,
                 task_impl: Box::new(updater),
                 opctx: opctx.child(BTreeMap::new()),
-                watchers: vec![],
+                watchers: vec![Box::new(
+                    driver.completion_watcher(&instance_watcher),
+                )],
                 activator: task_instance_updater,
             });
         }
@@ -744,6 +851,7 @@ impl BackgroundTasksInitializer {
                 instance_reincarnation::InstanceReincarnation::new(
                     datastore.clone(),
                     sagas.clone(),
+                    Arc::new(SystemClock),
                     config.instance_reincarnation.disable,
                 );
             driver.register(TaskDefinition {
@@ -753,11 +861,43 @@ impl BackgroundTasksInitializer {
                 period: config.instance_reincarnation.period_secs,
                 task_impl: Box::new(reincarnator),
                 opctx: opctx.child(BTreeMap::new()),
-                watchers: vec![],
+                watchers: vec![Box::new(
+                    driver.completion_watcher(&instance_watcher),
+                )],
                 activator: task_instance_reincarnation,
             });
         }
 
+        // Background task: drive operator-requested rolling reboots of sleds
+        //
+        // Activated when a rolling reboot is started or cancelled, and on each
+        // new inventory collection, which is how rebooted sleds are seen to
+        // have rejoined.
+        {
+            let rolling_sled_reboot =
+                rolling_sled_reboot::RollingSledReboots::new(
+                    datastore.clone(),
+                    sagas.clone(),
+                    resolver.clone(),
+                    inventory_watcher.clone(),
+                    rolling_sled_reboot::RollingSledRebootTimeouts {
+                        drain: config.rolling_sled_reboot.drain_timeout_secs,
+                        rejoin: config.rolling_sled_reboot.rejoin_timeout_secs,
+                    },
+                    config.rolling_sled_reboot.disable,
+                );
+            driver.register(TaskDefinition {
+                name: "rolling_sled_reboot",
+                description: "drains, resets, and waits for sleds to rejoin \
+                    during a rolling reboot of sleds",
+                period: config.rolling_sled_reboot.period_secs,
+                task_impl: Box::new(rolling_sled_reboot),
+                opctx: opctx.child(BTreeMap::new()),
+                watchers: vec![Box::new(inventory_watcher.clone())],
+                activator: task_rolling_sled_reboot,
+            });
+        }
+
         // Background task: service firewall rule propagation
         driver.register(TaskDefinition {
             name: "service_firewall_rule_propagation",
@@ -889,7 +1029,7 @@ impl BackgroundTasksInitializer {
             period: config.region_snapshot_replacement_finish.period_secs,
             task_impl: Box::new(RegionSnapshotReplacementFinishDetector::new(
                 datastore.clone(),
-                sagas,
+                sagas.clone(),
             )),
             opctx: opctx.child(BTreeMap::new()),
             watchers: vec![],
@@ -986,7 +1126,7 @@ impl BackgroundTasksInitializer {
             description: "collects error reports from service processors",
             period: config.sp_ereport_ingester.period_secs,
             task_impl: Box::new(ereport_ingester::SpEreportIngester::new(
-                datastore,
+                datastore.clone(),
                 resolver,
                 nexus_id,
                 config.sp_ereport_ingester.disable,
@@ -996,6 +1136,36 @@ impl BackgroundTasksInitializer {
             activator: task_sp_ereport_ingester,
         });
 
+        driver.register(TaskDefinition {
+            name: "clickhouse_backup",
+            description: "periodically backs up the single-node ClickHouse \
+                database and prunes old backups",
+            period: config.clickhouse_backup.period_secs,
+            task_impl: Box::new(clickhouse_backup::ClickhouseBackup::new(
+                rx_blueprint.clone(),
+                config.clickhouse_backup.retain_count,
+                config.clickhouse_backup.disable,
+            )),
+            opctx: opctx.child(BTreeMap::new()),
+            watchers: vec![],
+            activator: task_clickhouse_backup,
+        });
+
+        driver.register(TaskDefinition {
+            name: "snapshot_scheduler",
+            description: "takes snapshots for snapshot schedules that are due \
+                and prunes snapshots beyond their retention counts",
+            period: config.snapshot_scheduler.period_secs,
+            task_impl: Box::new(snapshot_scheduler::SnapshotScheduler::new(
+                datastore,
+                sagas,
+                Arc::new(SystemClock),
+            )),
+            opctx: opctx.child(BTreeMap::new()),
+            watchers: vec![],
+            activator: task_snapshot_scheduler,
+        });
+
         driver
     }
 }
@@ -1270,6 +1440,7 @@ pub mod test {
             store,
             &dns_server::dns_server::Config {
                 bind_address: "[::1]:0".parse().unwrap(),
+                query_log: Default::default(),
             },
             &dropshot::ConfigDropshot {
                 bind_address: "[::1]:0".parse().unwrap(),
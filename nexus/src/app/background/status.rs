@@ -8,13 +8,19 @@ use super::Driver;
 use crate::Nexus;
 use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
+use nexus_types::external_api::views;
+use nexus_types::internal_api::views::ActivationReason;
 use nexus_types::internal_api::views::BackgroundTask;
+use nexus_types::internal_api::views::CurrentStatus;
+use nexus_types::internal_api::views::LastResult;
+use nexus_types::internal_api::views::LastResultCompleted;
 use omicron_common::api::external::Error;
 use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::LookupType;
 use omicron_common::api::external::ResourceType;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::time::Duration;
 
 impl Nexus {
     pub(crate) async fn bgtasks_list(
@@ -56,6 +62,59 @@ impl Nexus {
         Ok(BackgroundTask::new(task.as_str(), description, period, status))
     }
 
+    /// Lists the background tasks of this Nexus for the external API
+    pub(crate) async fn bgtasks_list_external(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<Vec<views::BackgroundTask>, Error> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+        let driver = self.driver()?;
+        Ok(driver
+            .tasks()
+            .map(|t| {
+                let status = driver.task_status(t);
+                views::BackgroundTask {
+                    name: t.as_str().to_owned(),
+                    description: driver.task_description(t).to_owned(),
+                    period_ms: duration_ms(driver.task_period(t)),
+                    time_running_since: match status.current {
+                        CurrentStatus::Idle => None,
+                        CurrentStatus::Running(running) => {
+                            Some(running.start_time)
+                        }
+                    },
+                    last_activation: match status.last {
+                        LastResult::NeverCompleted => None,
+                        LastResult::Completed(completed) => {
+                            Some(activation_to_view(completed))
+                        }
+                    },
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the most recently completed activations of a background task
+    /// of this Nexus for the external API, most recent first
+    pub(crate) async fn bgtask_history_external(
+        &self,
+        opctx: &OpContext,
+        name: &str,
+    ) -> LookupResult<Vec<views::BackgroundTaskActivation>> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+        let driver = self.driver()?;
+        let task =
+            driver.tasks().find(|t| t.as_str() == name).ok_or_else(|| {
+                LookupType::ByName(name.to_owned())
+                    .into_not_found(ResourceType::BackgroundTask)
+            })?;
+        Ok(driver
+            .task_history(task)
+            .into_iter()
+            .map(activation_to_view)
+            .collect())
+    }
+
     pub(crate) async fn bgtask_activate(
         &self,
         opctx: &OpContext,
@@ -90,9 +149,94 @@ impl Nexus {
         Ok(())
     }
 
+    /// Returns the current period of each background task
+    pub(crate) async fn bgtask_periods(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<BTreeMap<String, Duration>, Error> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+        let driver = self.driver()?;
+        Ok(driver
+            .tasks()
+            .map(|t| (t.as_str().to_owned(), driver.task_period(t)))
+            .collect())
+    }
+
+    /// Changes the periods of the given background tasks
+    ///
+    /// If any task names aren't recognized, no periods are changed.  Periods
+    /// must be non-zero.
+    pub(crate) async fn bgtasks_set_periods(
+        &self,
+        opctx: &OpContext,
+        periods: &BTreeMap<String, Duration>,
+    ) -> Result<(), Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+        let driver = self.driver()?;
+
+        let tasks_to_change: Vec<_> = driver
+            .tasks()
+            .filter_map(|t| periods.get(t.as_str()).map(|period| (t, *period)))
+            .collect();
+        if tasks_to_change.len() != periods.len() {
+            let names = periods
+                .keys()
+                .filter(|name| {
+                    !tasks_to_change.iter().any(|(t, _)| t.as_str() == *name)
+                })
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(LookupType::ByOther(format!(
+                "background tasks: {names}"
+            ))
+            .into_not_found(ResourceType::BackgroundTask));
+        }
+
+        for (task, period) in tasks_to_change {
+            driver.set_task_period(task, period);
+        }
+
+        Ok(())
+    }
+
     fn driver(&self) -> Result<&Driver, Error> {
         self.background_tasks_driver.get().ok_or_else(|| {
             Error::unavail("background tasks not yet initialized")
         })
     }
 }
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn activation_to_view(
+    completed: LastResultCompleted,
+) -> views::BackgroundTaskActivation {
+    // By convention, tasks report failure with a top-level "error" string in
+    // their details.  (omdb relies on the same convention.)
+    let error = completed
+        .details
+        .get("error")
+        .and_then(|e| e.as_str())
+        .map(str::to_owned);
+    views::BackgroundTaskActivation {
+        iteration: completed.iteration,
+        reason: match completed.reason {
+            ActivationReason::Signaled => {
+                views::BackgroundTaskActivationReason::Signaled
+            }
+            ActivationReason::Timeout => {
+                views::BackgroundTaskActivationReason::Timeout
+            }
+            ActivationReason::Dependency => {
+                views::BackgroundTaskActivationReason::Dependency
+            }
+        },
+        time_started: completed.start_time,
+        duration_ms: duration_ms(completed.elapsed),
+        error,
+        details: completed.details,
+    }
+}
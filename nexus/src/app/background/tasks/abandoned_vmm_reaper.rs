@@ -20,7 +20,12 @@
 //!
 //! Such VMMs may be deleted fairly simply: any sled resources reserved for the
 //! VMM process can be deallocated, and the VMM record in the database is then
-//! marked as deleted. Note that reaping abandoned VMMs does not require
+//! marked as deleted. Before doing so, though, we ask the VMM's sled-agent
+//! whether it still knows about the VMM. If it does, the Propolis zone may
+//! still be running and consuming the resources we'd be releasing, so we leave
+//! the VMM alone and report it as leaked rather than deleting it. (If the
+//! VMM's sled has been expunged, or no longer exists at all, there's nothing
+//! to ask and nothing left running.) Note that reaping abandoned VMMs does not require
 //! deallocating virtual provisioning resources, NAT entries, and other such
 //! resources which are owned by the *instance*, rather than the VMM process;
 //! this task is only responsible for cleaning up VMMs left behind by an
@@ -32,15 +37,20 @@
 //! saga.
 
 use crate::app::background::BackgroundTask;
+use crate::app::instance::SledAgentInstanceError;
 use anyhow::Context;
 use futures::FutureExt;
 use futures::future::BoxFuture;
+use nexus_db_lookup::LookupPath;
 use nexus_db_model::Vmm;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db::DataStore;
 use nexus_db_queries::db::datastore::SQL_BATCH_SIZE;
 use nexus_db_queries::db::pagination::Paginator;
+use nexus_types::external_api::views::SledPolicy;
+use nexus_types::identity::Asset;
 use nexus_types::internal_api::background::AbandonedVmmReaperStatus;
+use omicron_common::api::external::Error;
 use omicron_uuid_kinds::{GenericUuid, PropolisUuid};
 use std::sync::Arc;
 
@@ -101,6 +111,36 @@ impl AbandonedVmmReaper {
 
         for vmm in vmms {
             let vmm_id = PropolisUuid::from_untyped_uuid(vmm.id);
+
+            // Make sure the VMM is really gone before releasing its resources.
+            match self.vmm_is_gone_from_sled(opctx, vmm, vmm_id).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    slog::warn!(
+                        opctx.log,
+                        "Abandoned VMM is still present on its sled; \
+                         not deleting it";
+                        "vmm" => %vmm_id,
+                        "sled_id" => %vmm.sled_id,
+                    );
+                    status.vmms_still_on_sled += 1;
+                    continue;
+                }
+                Err(e) => {
+                    const ERR_MSG: &'static str =
+                        "Failed to check whether sled still has";
+                    slog::warn!(
+                        opctx.log,
+                        "{ERR_MSG} abandoned VMM";
+                        "vmm" => %vmm_id,
+                        "sled_id" => %vmm.sled_id,
+                        "error" => %e,
+                    );
+                    status.errors.push(format!("{ERR_MSG} {vmm_id}: {e:#}"));
+                    continue;
+                }
+            }
+
             slog::trace!(opctx.log, "Deleting abandoned VMM"; "vmm" => %vmm_id);
             // Attempt to remove the abandoned VMM's sled resource reservation.
             match self.datastore.sled_reservation_delete(opctx, vmm_id).await {
@@ -156,6 +196,44 @@ impl AbandonedVmmReaper {
             }
         }
     }
+
+    /// Returns `true` if the sled that hosted an abandoned VMM no longer has
+    /// any record of it, or if there's no sled left to host it at all.
+    async fn vmm_is_gone_from_sled(
+        &self,
+        opctx: &OpContext,
+        vmm: &Vmm,
+        vmm_id: PropolisUuid,
+    ) -> Result<bool, anyhow::Error> {
+        let sled = match LookupPath::new(opctx, &*self.datastore)
+            .sled_id(vmm.sled_id)
+            .fetch()
+            .await
+        {
+            Ok((_, sled)) => sled,
+            Err(Error::ObjectNotFound { .. }) => return Ok(true),
+            Err(e) => return Err(e).context("failed to look up sled"),
+        };
+        if sled.policy() == SledPolicy::Expunged {
+            return Ok(true);
+        }
+
+        let client = nexus_networking::sled_client_from_address(
+            sled.id(),
+            sled.address(),
+            &opctx.log,
+        );
+        match client
+            .vmm_get_state(&vmm_id)
+            .await
+            .map_err(SledAgentInstanceError)
+        {
+            Ok(_) => Ok(false),
+            Err(e) if e.vmm_gone() => Ok(true),
+            Err(e) => Err(anyhow::Error::new(e))
+                .context("failed to query sled-agent for VMM state"),
+        }
+    }
 }
 
 impl BackgroundTask for AbandonedVmmReaper {
@@ -172,6 +250,7 @@ impl BackgroundTask for AbandonedVmmReaper {
                         "sled_reservations_deleted" => status.sled_reservations_deleted,
                         "vmms_deleted" => status.vmms_deleted,
                         "vmms_already_deleted" => status.vmms_already_deleted,
+                        "vmms_still_on_sled" => status.vmms_still_on_sled,
                     );
                 }
                 Err(err) => {
@@ -181,6 +260,7 @@ impl BackgroundTask for AbandonedVmmReaper {
                         "sled_reservations_deleted" => status.sled_reservations_deleted,
                         "vmms_deleted" => status.vmms_deleted,
                         "vmms_already_deleted" => status.vmms_already_deleted,
+                        "vmms_still_on_sled" => status.vmms_still_on_sled,
                     );
                     status.errors.push(err.to_string());
                 }
@@ -222,6 +302,15 @@ mod tests {
             client: &dropshot::test_util::ClientTestContext,
             datastore: &Arc<DataStore>,
             opctx: &OpContext,
+        ) -> Self {
+            Self::setup_on_sled(client, datastore, opctx, Uuid::new_v4()).await
+        }
+
+        async fn setup_on_sled(
+            client: &dropshot::test_util::ClientTestContext,
+            datastore: &Arc<DataStore>,
+            opctx: &OpContext,
+            sled_id: Uuid,
         ) -> Self {
             resource_helpers::create_default_ip_pool(&client).await;
 
@@ -243,7 +332,7 @@ mod tests {
                         time_created: Utc::now(),
                         time_deleted: None,
                         instance_id: instance.identity.id,
-                        sled_id: Uuid::new_v4(),
+                        sled_id,
                         propolis_ip: "::1".parse().unwrap(),
                         propolis_port: 12345.into(),
                         runtime: VmmRuntimeState {
@@ -449,4 +538,40 @@ mod tests {
 
         fixture.assert_reaped(datastore).await
     }
+
+    #[nexus_test(server = crate::Server)]
+    async fn vmm_unknown_to_sled_agent_is_reaped(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+        // Put the VMM on a sled that exists, so that the task has to ask that
+        // sled's (simulated) sled-agent about it. The sled-agent has never
+        // heard of this VMM, so it should be reaped.
+        let fixture = TestFixture::setup_on_sled(
+            &cptestctx.external_client,
+            datastore,
+            &opctx,
+            cptestctx.first_sled_id().into_untyped_uuid(),
+        )
+        .await;
+
+        let mut task = AbandonedVmmReaper::new(datastore.clone());
+
+        let mut status = AbandonedVmmReaperStatus::default();
+        dbg!(task.reap_all(&mut status, &opctx,).await)
+            .expect("activation completes successfully");
+        dbg!(&status);
+
+        assert_eq!(status.vmms_found, 1);
+        assert_eq!(status.vmms_deleted, 1);
+        assert_eq!(status.sled_reservations_deleted, 1);
+        assert_eq!(status.vmms_still_on_sled, 0);
+        assert_eq!(status.errors, Vec::<String>::new());
+        fixture.assert_reaped(datastore).await;
+    }
 }
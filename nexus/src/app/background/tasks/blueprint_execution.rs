@@ -17,7 +17,8 @@ use nexus_reconfigurator_execution::{
     RealizeBlueprintOutput, RequiredRealizeArgs,
 };
 use nexus_types::deployment::{
-    Blueprint, BlueprintTarget, PendingMgsUpdates, execution::EventBuffer,
+    Blueprint, BlueprintTarget, BlueprintTargetExecution, PendingMgsUpdates,
+    execution::EventBuffer,
 };
 use omicron_uuid_kinds::OmicronZoneUuid;
 use serde_json::json;
@@ -129,6 +130,21 @@ impl BlueprintExecutor {
             }
         };
 
+        // Similarly, propagate whether this Nexus is slated for expungement.
+        // Blueprint execution removes it from external DNS; until it's shut
+        // down, it turns away new operations so that clients move elsewhere.
+        match blueprint.is_nexus_draining(self.nexus_id) {
+            Ok(draining) => self.nexus_quiesce.set_draining(draining),
+            Err(error) => {
+                error!(
+                    &opctx.log,
+                    "blueprint execution: failed to determine if this Nexus \
+                     is draining";
+                    InlineErrorChain::new(&*error)
+                );
+            }
+        };
+
         if !bp_target.enabled {
             warn!(&opctx.log,
                       "Blueprint execution: skipped";
@@ -140,6 +156,44 @@ impl BlueprintExecutor {
             });
         }
 
+        // An operator may have disabled execution (e.g., during an incident)
+        // independently of the target's `enabled` flag. This isn't part of
+        // what the blueprint loader gives us, so check the database each time.
+        match self.datastore.blueprint_target_get_current_execution(opctx).await
+        {
+            Ok(BlueprintTargetExecution {
+                disabled: Some(disabled), ..
+            }) => {
+                warn!(&opctx.log,
+                      "Blueprint execution: skipped";
+                      "reason" => "execution disabled by operator",
+                      "disabled_reason" => &disabled.reason,
+                      "time_disabled" => %disabled.time_disabled,
+                      "target_id" => %blueprint.id);
+                return json!({
+                    "target_id": blueprint.id.to_string(),
+                    "enabled": false,
+                    "execution_disabled": disabled,
+                });
+            }
+            Ok(BlueprintTargetExecution { disabled: None, .. }) => (),
+            Err(error) => {
+                // Err on the side of not executing: if we can't tell whether
+                // execution is disabled, it may be.
+                warn!(&opctx.log,
+                      "Blueprint execution: skipped";
+                      "reason" => "failed to check whether execution is \
+                                   disabled",
+                      InlineErrorChain::new(&error));
+                return json!({
+                    "error": format!(
+                        "failed to check whether execution is disabled: {}",
+                        InlineErrorChain::new(&error),
+                    ),
+                });
+            }
+        }
+
         let (sender, mut receiver) = update_engine::channel();
 
         let receiver_task = tokio::spawn(async move {
@@ -236,6 +290,7 @@ mod test {
     use nexus_db_queries::authn;
     use nexus_db_queries::context::OpContext;
     use nexus_db_queries::db::DataStore;
+    use nexus_inventory::now_db_precision;
     use nexus_sled_agent_shared::inventory::OmicronZoneDataset;
     use nexus_test_utils_macros::nexus_test;
     use nexus_types::deployment::execution::{
@@ -243,8 +298,9 @@ mod test {
         StepOutcome, StepStatus,
     };
     use nexus_types::deployment::{
-        Blueprint, BlueprintHostPhase2DesiredSlots, BlueprintSledConfig,
-        BlueprintTarget, BlueprintZoneConfig, BlueprintZoneDisposition,
+        Blueprint, BlueprintExecutionDisabled, BlueprintHostPhase2DesiredSlots,
+        BlueprintScope, BlueprintSledConfig, BlueprintTarget,
+        BlueprintZoneConfig, BlueprintZoneDisposition,
         BlueprintZoneImageSource, BlueprintZoneType,
         CockroachDbPreserveDowngrade, OximeterReadMode, PendingMgsUpdates,
         PlanningReport, blueprint_zone_type,
@@ -313,6 +369,7 @@ mod test {
         let blueprint = Blueprint {
             id,
             sleds: blueprint_sleds,
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             cockroachdb_setting_preserve_downgrade:
                 CockroachDbPreserveDowngrade::DoNotModify,
@@ -599,9 +656,47 @@ mod test {
         mock_server_ignore_spurious_http_requests(&mut s1);
         mock_server_ignore_spurious_http_requests(&mut s2);
 
+        // Likewise, if an operator disables execution, we shouldn't invoke the
+        // sled agent even though the target itself is enabled.
+        blueprint.0.enabled = true;
+        blueprint_tx.send(Some(Arc::new(blueprint.clone()))).unwrap();
+        let disabled = BlueprintExecutionDisabled {
+            reason: "testing".to_string(),
+            time_disabled: now_db_precision(),
+        };
+        datastore
+            .blueprint_target_set_current_execution(
+                &opctx,
+                blueprint.1.id,
+                Some(disabled.clone()),
+            )
+            .await
+            .expect("disabled execution");
+        let value = task.activate(&opctx).await;
+        println!("when execution disabled: {:?}", value);
+        assert_eq!(
+            value,
+            json!({
+                "enabled": false,
+                "execution_disabled": disabled,
+                "target_id": blueprint.1.id.to_string()
+            })
+        );
+        s1.verify_and_clear();
+        s2.verify_and_clear();
+        mock_server_ignore_spurious_http_requests(&mut s1);
+        mock_server_ignore_spurious_http_requests(&mut s2);
+        datastore
+            .blueprint_target_set_current_execution(
+                &opctx,
+                blueprint.1.id,
+                None,
+            )
+            .await
+            .expect("re-enabled execution");
+
         // Do it all again, but configure one of the servers to fail so we can
         // verify the task's returned summary of what happened.
-        blueprint.0.enabled = true;
         blueprint_tx.send(Some(Arc::new(blueprint))).unwrap();
         s1.expect(
             Expectation::matching(match_put_omicron_config())
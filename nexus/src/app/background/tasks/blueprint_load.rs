@@ -194,8 +194,9 @@ mod test {
     use nexus_inventory::now_db_precision;
     use nexus_test_utils_macros::nexus_test;
     use nexus_types::deployment::{
-        Blueprint, BlueprintTarget, CockroachDbPreserveDowngrade,
-        OximeterReadMode, PendingMgsUpdates, PlanningReport,
+        Blueprint, BlueprintScope, BlueprintTarget,
+        CockroachDbPreserveDowngrade, OximeterReadMode, PendingMgsUpdates,
+        PlanningReport,
     };
     use omicron_common::api::external::Generation;
     use omicron_uuid_kinds::BlueprintUuid;
@@ -218,6 +219,7 @@ mod test {
             Blueprint {
                 id,
                 sleds: BTreeMap::new(),
+                scope: BlueprintScope::Fleet,
                 pending_mgs_updates: PendingMgsUpdates::new(),
                 cockroachdb_setting_preserve_downgrade:
                     CockroachDbPreserveDowngrade::DoNotModify,
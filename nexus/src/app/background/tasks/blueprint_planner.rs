@@ -16,10 +16,13 @@ use nexus_reconfigurator_preparation::PlanningInputFromDb;
 use nexus_types::deployment::ReconfiguratorChickenSwitchesView;
 use nexus_types::deployment::{Blueprint, BlueprintTarget};
 use nexus_types::internal_api::background::BlueprintPlannerStatus;
+use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::LookupType;
 use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::DatasetUuid;
 use omicron_uuid_kinds::GenericUuid as _;
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use tokio::sync::watch::{self, Receiver, Sender};
 
@@ -29,6 +32,7 @@ pub struct BlueprintPlanner {
     rx_chicken_switches: Receiver<ReconfiguratorChickenSwitchesView>,
     rx_inventory: Receiver<Option<CollectionUuid>>,
     rx_blueprint: Receiver<Option<Arc<(BlueprintTarget, Blueprint)>>>,
+    rx_dataset_quotas: Receiver<BTreeMap<DatasetUuid, ByteCount>>,
     tx_blueprint: Sender<Option<Arc<(BlueprintTarget, Blueprint)>>>,
 }
 
@@ -38,6 +42,7 @@ impl BlueprintPlanner {
         rx_chicken_switches: Receiver<ReconfiguratorChickenSwitchesView>,
         rx_inventory: Receiver<Option<CollectionUuid>>,
         rx_blueprint: Receiver<Option<Arc<(BlueprintTarget, Blueprint)>>>,
+        rx_dataset_quotas: Receiver<BTreeMap<DatasetUuid, ByteCount>>,
     ) -> Self {
         let (tx_blueprint, _) = watch::channel(None);
         Self {
@@ -45,6 +50,7 @@ impl BlueprintPlanner {
             rx_chicken_switches,
             rx_inventory,
             rx_blueprint,
+            rx_dataset_quotas,
             tx_blueprint,
         }
     }
@@ -134,6 +140,15 @@ impl BlueprintPlanner {
             }
         };
 
+        // Include the latest dataset quotas proposed based on observed usage.
+        let input = {
+            let mut builder = input.into_builder();
+            builder.set_dataset_quotas(
+                self.rx_dataset_quotas.borrow_and_update().clone(),
+            );
+            builder.build()
+        };
+
         // Generate a new blueprint.
         let planner = match Planner::new_based_on(
             opctx.log.clone(),
@@ -335,12 +350,17 @@ mod test {
                 time_modified: now_db_precision(),
             });
 
+        // No dataset quotas are proposed.
+        let (_tx_dataset_quotas, rx_dataset_quotas) =
+            watch::channel(BTreeMap::new());
+
         // Finally, spin up the planner background task.
         let mut planner = BlueprintPlanner::new(
             datastore.clone(),
             chicken_switches_collector_rx,
             rx_collector,
             rx_loader.clone(),
+            rx_dataset_quotas,
         );
         let _rx_planner = planner.watcher();
 
@@ -0,0 +1,198 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Background task for taking periodic backups of the single-node ClickHouse
+//! database
+//!
+//! Each activation asks the admin server in the in-service single-node
+//! ClickHouse zone to back up the oximeter database into the zone's durable
+//! dataset, then deletes the oldest backups beyond the configured retention
+//! count. Restoring from one of these backups is an operator-driven action;
+//! see `Nexus::clickhouse_backup_restore`.
+//!
+//! Replicated (multi-node) ClickHouse clusters are not backed up by this task.
+
+use crate::app::background::BackgroundTask;
+use crate::app::oximeter::clickhouse_single_node_backup_client;
+use futures::future::BoxFuture;
+use nexus_auth::context::OpContext;
+use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintTarget;
+use nexus_types::internal_api::background::ClickhouseBackupStatus;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+pub struct ClickhouseBackup {
+    rx_blueprint: watch::Receiver<Option<Arc<(BlueprintTarget, Blueprint)>>>,
+    retain_count: usize,
+    disabled: bool,
+}
+
+impl BackgroundTask for ClickhouseBackup {
+    fn activate<'a>(
+        &'a mut self,
+        opctx: &'a OpContext,
+    ) -> BoxFuture<'a, serde_json::Value> {
+        Box::pin(async move {
+            let status = self.actually_activate(opctx).await;
+            serde_json::json!(status)
+        })
+    }
+}
+
+impl ClickhouseBackup {
+    #[must_use]
+    pub fn new(
+        rx_blueprint: watch::Receiver<
+            Option<Arc<(BlueprintTarget, Blueprint)>>,
+        >,
+        retain_count: usize,
+        disabled: bool,
+    ) -> Self {
+        Self { rx_blueprint, retain_count, disabled }
+    }
+
+    async fn actually_activate(
+        &mut self,
+        opctx: &OpContext,
+    ) -> ClickhouseBackupStatus {
+        let mut status = ClickhouseBackupStatus::default();
+        if self.disabled {
+            status.disabled = true;
+            slog::trace!(
+                &opctx.log,
+                "ClickHouse backups disabled, doing nothing",
+            );
+            return status;
+        }
+
+        // Get the latest blueprint, cloning to prevent holding a read lock
+        // on the watch.
+        let update = self.rx_blueprint.borrow_and_update().clone();
+        let Some((_, blueprint)) = update.as_deref() else {
+            const MSG: &str = "no blueprint loaded";
+            warn!(opctx.log, "ClickHouse backup skipped: {MSG}");
+            status.errors.push(MSG.to_string());
+            return status;
+        };
+
+        let (zone_id, client) =
+            match clickhouse_single_node_backup_client(&opctx.log, blueprint) {
+                Ok(Some(found)) => found,
+                Ok(None) => {
+                    // Nothing to back up: either this system runs replicated
+                    // ClickHouse only, or the zone hasn't been deployed yet.
+                    debug!(
+                        opctx.log,
+                        "no single-node ClickHouse zone in service; \
+                         skipping backup",
+                    );
+                    return status;
+                }
+                Err(error) => {
+                    status.errors.push(format!("{error:#}"));
+                    return status;
+                }
+            };
+        status.zone_id = Some(zone_id);
+
+        match client.backup_create().await {
+            Ok(backup) => {
+                info!(
+                    opctx.log,
+                    "created ClickHouse backup";
+                    "zone_id" => %zone_id,
+                    "backup" => &backup.name,
+                    "size_bytes" => backup.size_bytes,
+                );
+                status.backup_created = Some(backup.into_inner().name);
+            }
+            Err(error) => {
+                // Keep going: even if this backup failed, older ones may
+                // still need to be pruned.
+                let msg = format!("failed to create backup: {error}");
+                error!(opctx.log, "{msg}"; "zone_id" => %zone_id);
+                status.errors.push(msg);
+            }
+        }
+
+        let mut backups = match client.backup_list().await {
+            Ok(backups) => backups.into_inner(),
+            Err(error) => {
+                let msg = format!("failed to list backups: {error}");
+                error!(opctx.log, "{msg}"; "zone_id" => %zone_id);
+                status.errors.push(msg);
+                return status;
+            }
+        };
+        backups.sort_by(|a, b| {
+            (a.time_created, &a.name).cmp(&(b.time_created, &b.name))
+        });
+
+        let nexpired = backups.len().saturating_sub(self.retain_count);
+        for backup in backups.drain(..nexpired) {
+            match client.backup_delete(&backup.name).await {
+                Ok(_) => {
+                    info!(
+                        opctx.log,
+                        "deleted expired ClickHouse backup";
+                        "zone_id" => %zone_id,
+                        "backup" => &backup.name,
+                    );
+                    status.backups_deleted.push(backup.name);
+                }
+                Err(error) => {
+                    let msg = format!(
+                        "failed to delete backup {:?}: {error}",
+                        backup.name
+                    );
+                    error!(opctx.log, "{msg}"; "zone_id" => %zone_id);
+                    status.errors.push(msg);
+                    status.backups_retained.push(backup.name);
+                }
+            }
+        }
+        status
+            .backups_retained
+            .extend(backups.into_iter().map(|backup| backup.name));
+
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_test_utils_macros::nexus_test;
+
+    type ControlPlaneTestContext =
+        nexus_test_utils::ControlPlaneTestContext<crate::Server>;
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_clickhouse_backup_disabled(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let (_tx, rx_blueprint) = watch::channel(None);
+        let mut task = ClickhouseBackup::new(rx_blueprint, 3, true);
+        let status = task.actually_activate(&opctx).await;
+        assert_eq!(
+            status,
+            ClickhouseBackupStatus { disabled: true, ..Default::default() }
+        );
+
+        // With backups enabled but no blueprint loaded, nothing is attempted.
+        let mut task = ClickhouseBackup::new(task.rx_blueprint, 3, false);
+        let status = task.actually_activate(&opctx).await;
+        assert!(!status.disabled);
+        assert_eq!(status.zone_id, None);
+        assert_eq!(status.errors, vec!["no blueprint loaded".to_string()]);
+    }
+}
@@ -0,0 +1,317 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Background task for proposing dataset quotas based on observed usage
+//!
+//! Each activation compares the space used by each in-service dataset with a
+//! usage-based quota (Crucible and CockroachDB datasets), as reported in the
+//! latest inventory collection, against that dataset's quota in the current
+//! target blueprint. Quotas that leave too little or too much free space are
+//! proposed to the blueprint planner, which applies them when it next plans.
+
+use crate::app::background::BackgroundTask;
+use futures::future::BoxFuture;
+use nexus_auth::context::OpContext;
+use nexus_db_queries::db::DataStore;
+use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintDatasetDisposition;
+use nexus_types::deployment::BlueprintTarget;
+use nexus_types::internal_api::background::DatasetQuotaTunerStatus;
+use omicron_common::api::external::ByteCount;
+use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::DatasetUuid;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+const GIB: u128 = 1 << 30;
+
+/// Bounds within which usage-based dataset quotas are proposed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DatasetQuotaPolicy {
+    /// free space to leave under the quota, as a percentage of usage
+    pub headroom_percent: u32,
+    /// smallest quota to propose, in GiB
+    pub min_quota_gib: u32,
+    /// largest quota to propose, in GiB
+    pub max_quota_gib: u32,
+}
+
+impl DatasetQuotaPolicy {
+    /// Returns the quota, in GiB, that leaves `headroom_percent` of `used`
+    /// free, rounded up to a whole GiB and clamped to the policy's bounds.
+    fn quota_gib(&self, used: ByteCount, headroom_percent: u32) -> u32 {
+        let bytes = u128::from(used.to_bytes())
+            * (100 + u128::from(headroom_percent))
+            / 100;
+        u32::try_from(bytes.div_ceil(GIB))
+            .unwrap_or(u32::MAX)
+            .max(self.min_quota_gib)
+            .min(self.max_quota_gib)
+    }
+
+    /// Returns the quota to propose for a dataset using `used` bytes whose
+    /// quota is currently `current`, or `None` if `current` is fine as is.
+    ///
+    /// To avoid churning blueprints as usage fluctuates, a quota is left
+    /// alone as long as the free space under it is between half and double
+    /// the policy's headroom.
+    pub fn propose(
+        &self,
+        used: ByteCount,
+        current: Option<ByteCount>,
+    ) -> Option<ByteCount> {
+        let target = ByteCount::from_gibibytes_u32(
+            self.quota_gib(used, self.headroom_percent),
+        );
+        let Some(current) = current else {
+            return Some(target);
+        };
+        let low = ByteCount::from_gibibytes_u32(
+            self.quota_gib(used, self.headroom_percent / 2),
+        );
+        let high = ByteCount::from_gibibytes_u32(
+            self.quota_gib(used, self.headroom_percent.saturating_mul(2)),
+        );
+        if (low.to_bytes()..=high.to_bytes()).contains(&current.to_bytes()) {
+            None
+        } else {
+            Some(target)
+        }
+    }
+}
+
+pub struct DatasetQuotaTuner {
+    datastore: Arc<DataStore>,
+    rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+    rx_blueprint: watch::Receiver<Option<Arc<(BlueprintTarget, Blueprint)>>>,
+    tx_quotas: watch::Sender<BTreeMap<DatasetUuid, ByteCount>>,
+    policy: DatasetQuotaPolicy,
+    disabled: bool,
+}
+
+impl BackgroundTask for DatasetQuotaTuner {
+    fn activate<'a>(
+        &'a mut self,
+        opctx: &'a OpContext,
+    ) -> BoxFuture<'a, serde_json::Value> {
+        Box::pin(async move {
+            let status = self.actually_activate(opctx).await;
+            serde_json::json!(status)
+        })
+    }
+}
+
+impl DatasetQuotaTuner {
+    #[must_use]
+    pub fn new(
+        datastore: Arc<DataStore>,
+        rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+        rx_blueprint: watch::Receiver<
+            Option<Arc<(BlueprintTarget, Blueprint)>>,
+        >,
+        policy: DatasetQuotaPolicy,
+        disabled: bool,
+    ) -> Self {
+        let (tx_quotas, _) = watch::channel(BTreeMap::new());
+        Self {
+            datastore,
+            rx_inventory,
+            rx_blueprint,
+            tx_quotas,
+            policy,
+            disabled,
+        }
+    }
+
+    /// Returns a receiver for the quotas most recently proposed, keyed by
+    /// dataset ID.
+    pub fn watcher(&self) -> watch::Receiver<BTreeMap<DatasetUuid, ByteCount>> {
+        self.tx_quotas.subscribe()
+    }
+
+    async fn actually_activate(
+        &mut self,
+        opctx: &OpContext,
+    ) -> DatasetQuotaTunerStatus {
+        let mut status = DatasetQuotaTunerStatus::default();
+        if self.disabled {
+            status.disabled = true;
+            slog::trace!(
+                &opctx.log,
+                "dataset quota tuning disabled, doing nothing",
+            );
+            return status;
+        }
+
+        // Get the latest blueprint, cloning to prevent holding a read lock
+        // on the watch.
+        let update = self.rx_blueprint.borrow_and_update().clone();
+        let Some((_, blueprint)) = update.as_deref() else {
+            const MSG: &str = "no blueprint loaded";
+            warn!(opctx.log, "dataset quota tuning skipped: {MSG}");
+            status.errors.push(MSG.to_string());
+            return status;
+        };
+        status.blueprint_id = Some(blueprint.id);
+
+        let Some(collection_id) = *self.rx_inventory.borrow_and_update() else {
+            const MSG: &str = "no inventory collection available";
+            warn!(opctx.log, "dataset quota tuning skipped: {MSG}");
+            status.errors.push(MSG.to_string());
+            return status;
+        };
+        let collection = match self
+            .datastore
+            .inventory_collection_read(opctx, collection_id)
+            .await
+        {
+            Ok(collection) => collection,
+            Err(error) => {
+                let msg = format!(
+                    "can't read inventory collection {collection_id}: {error}"
+                );
+                error!(opctx.log, "{msg}");
+                status.errors.push(msg);
+                return status;
+            }
+        };
+        status.inventory_collection_id = Some(collection_id);
+
+        let used_by_dataset: BTreeMap<DatasetUuid, ByteCount> = collection
+            .sled_agents
+            .iter()
+            .flat_map(|sled_agent| &sled_agent.datasets)
+            .filter_map(|dataset| Some((dataset.id?, dataset.used)))
+            .collect();
+
+        let mut proposed = BTreeMap::new();
+        for (sled_id, dataset) in blueprint
+            .all_omicron_datasets(BlueprintDatasetDisposition::is_in_service)
+            .filter(|(_, dataset)| dataset.kind.has_usage_based_quota())
+        {
+            let Some(used) = used_by_dataset.get(&dataset.id) else {
+                // The dataset may not have been created yet.
+                continue;
+            };
+            status.datasets_observed += 1;
+            if let Some(quota) = self.policy.propose(*used, dataset.quota) {
+                info!(
+                    opctx.log,
+                    "proposing dataset quota";
+                    "sled_id" => %sled_id,
+                    "dataset_id" => %dataset.id,
+                    "kind" => %dataset.kind,
+                    "used" => %used,
+                    "quota" => %quota,
+                );
+                proposed.insert(dataset.id, quota);
+            }
+        }
+
+        // Only notify the planner if the proposals have changed.
+        self.tx_quotas.send_if_modified(|quotas| {
+            if *quotas == proposed {
+                false
+            } else {
+                *quotas = proposed.clone();
+                true
+            }
+        });
+        status.proposed_quotas = proposed;
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_test_utils_macros::nexus_test;
+
+    type ControlPlaneTestContext =
+        nexus_test_utils::ControlPlaneTestContext<crate::Server>;
+
+    const POLICY: DatasetQuotaPolicy = DatasetQuotaPolicy {
+        headroom_percent: 50,
+        min_quota_gib: 10,
+        max_quota_gib: 1000,
+    };
+
+    fn gib(n: u32) -> ByteCount {
+        ByteCount::from_gibibytes_u32(n)
+    }
+
+    #[test]
+    fn test_propose_quota() {
+        // Datasets without a quota always get one.
+        assert_eq!(POLICY.propose(gib(100), None), Some(gib(150)));
+
+        // Quotas are kept within the policy's bounds.
+        assert_eq!(POLICY.propose(gib(1), None), Some(gib(10)));
+        assert_eq!(POLICY.propose(gib(900), None), Some(gib(1000)));
+        assert_eq!(POLICY.propose(gib(1), Some(gib(5))), Some(gib(10)));
+        assert_eq!(POLICY.propose(gib(900), Some(gib(2000))), Some(gib(1000)));
+
+        // Partial GiBs are rounded up.
+        assert_eq!(
+            POLICY.propose(
+                ByteCount::try_from(100 * GIB as u64 + 1).unwrap(),
+                None
+            ),
+            Some(gib(151))
+        );
+
+        // Quotas leaving between half and double the headroom free are left
+        // alone.
+        assert_eq!(POLICY.propose(gib(100), Some(gib(125))), None);
+        assert_eq!(POLICY.propose(gib(100), Some(gib(150))), None);
+        assert_eq!(POLICY.propose(gib(100), Some(gib(200))), None);
+
+        // Quotas leaving too little or too much free space are replaced.
+        assert_eq!(POLICY.propose(gib(100), Some(gib(110))), Some(gib(150)));
+        assert_eq!(POLICY.propose(gib(100), Some(gib(300))), Some(gib(150)));
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_dataset_quota_tuner_disabled(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let (_tx_inventory, rx_inventory) = watch::channel(None);
+        let (_tx_blueprint, rx_blueprint) = watch::channel(None);
+        let mut task = DatasetQuotaTuner::new(
+            datastore.clone(),
+            rx_inventory,
+            rx_blueprint,
+            POLICY,
+            true,
+        );
+        let status = task.actually_activate(&opctx).await;
+        assert_eq!(
+            status,
+            DatasetQuotaTunerStatus { disabled: true, ..Default::default() }
+        );
+
+        // With tuning enabled but no blueprint loaded, nothing is proposed.
+        let mut task = DatasetQuotaTuner::new(
+            datastore.clone(),
+            task.rx_inventory,
+            task.rx_blueprint,
+            POLICY,
+            false,
+        );
+        let rx_quotas = task.watcher();
+        let status = task.actually_activate(&opctx).await;
+        assert!(!status.disabled);
+        assert!(status.proposed_quotas.is_empty());
+        assert_eq!(status.errors, vec!["no blueprint loaded".to_string()]);
+        assert!(rx_quotas.borrow().is_empty());
+    }
+}
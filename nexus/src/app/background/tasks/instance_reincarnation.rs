@@ -12,12 +12,14 @@ use futures::future::BoxFuture;
 use nexus_db_queries::authn;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db::DataStore;
-use nexus_db_queries::db::pagination::Paginator;
+use nexus_types::clock::Clock;
 use nexus_types::identity::Resource;
 use nexus_types::internal_api::background::InstanceReincarnationStatus;
 use nexus_types::internal_api::background::ReincarnatableInstance;
 use nexus_types::internal_api::background::ReincarnationReason;
+use omicron_common::api::external::DataPageParams;
 use omicron_common::api::external::Error;
+use std::collections::BTreeMap;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 use steno::SagaId;
@@ -26,8 +28,17 @@ use uuid::Uuid;
 pub struct InstanceReincarnation {
     datastore: Arc<DataStore>,
     sagas: Arc<dyn StartSaga>,
+    /// The source of the current time, against which instances' cooldown
+    /// periods are measured.
+    clock: Arc<dyn Clock>,
     /// The maximum number of concurrently executing instance-start sagas.
     concurrency_limit: NonZeroU32,
+    /// The maximum number of instance-start sagas started by one activation.
+    max_per_pass: NonZeroU32,
+    /// The maximum number of instance-start sagas started by one activation
+    /// for instances in the same project, so that a project full of
+    /// crash-looping instances can't starve every other project.
+    max_per_project: NonZeroU32,
     disabled: bool,
 }
 
@@ -37,6 +48,24 @@ const DEFAULT_MAX_CONCURRENT_REINCARNATIONS: NonZeroU32 =
         None => unreachable!(), // 16 > 0
     };
 
+const DEFAULT_MAX_REINCARNATIONS_PER_PASS: NonZeroU32 =
+    match NonZeroU32::new(64) {
+        Some(n) => n,
+        None => unreachable!(), // 64 > 0
+    };
+
+const DEFAULT_MAX_REINCARNATIONS_PER_PROJECT: NonZeroU32 =
+    match NonZeroU32::new(4) {
+        Some(n) => n,
+        None => unreachable!(), // 4 > 0
+    };
+
+/// Tracks how many more instance-start sagas an activation may start.
+struct PassBudget {
+    remaining: u32,
+    started_per_project: BTreeMap<Uuid, u32>,
+}
+
 type RunningSaga = (Uuid, SagaId, BoxFuture<'static, Result<(), Error>>);
 
 impl BackgroundTask for InstanceReincarnation {
@@ -58,12 +87,17 @@ impl BackgroundTask for InstanceReincarnation {
 
             let mut running_sagas =
                 Vec::with_capacity(self.concurrency_limit.get() as usize);
+            let mut budget = PassBudget {
+                remaining: self.max_per_pass.get(),
+                started_per_project: BTreeMap::new(),
+            };
 
             if let Err(error) = self
                 .reincarnate_all(
                     &opctx,
                     ReincarnationReason::Failed,
                     &mut status,
+                    &mut budget,
                     &mut running_sagas,
                 )
                 .await
@@ -84,6 +118,7 @@ impl BackgroundTask for InstanceReincarnation {
                     &opctx,
                     ReincarnationReason::SagaUnwound,
                     &mut status,
+                    &mut budget,
                     &mut running_sagas,
                 )
                 .await
@@ -106,6 +141,8 @@ impl BackgroundTask for InstanceReincarnation {
                     "instances_found" => status.total_instances_found(),
                     "instances_reincarnated" => status.instances_reincarnated.len(),
                     "instances_changed_state" => status.changed_state.len(),
+                    "pass_limit_reached" => status.pass_limit_reached,
+                    "projects_at_limit" => status.projects_at_limit.len(),
                     "query_errors" => status.errors.len(),
                     "restart_errors" => status.restart_errors.len(),
                 );
@@ -116,6 +153,8 @@ impl BackgroundTask for InstanceReincarnation {
                     "instances_found" => status.total_instances_found(),
                     "instances_reincarnated" => status.instances_reincarnated.len(),
                     "instances_changed_state" => status.changed_state.len(),
+                    "pass_limit_reached" => status.pass_limit_reached,
+                    "projects_at_limit" => status.projects_at_limit.len(),
                 );
             }
 
@@ -128,12 +167,16 @@ impl InstanceReincarnation {
     pub(crate) fn new(
         datastore: Arc<DataStore>,
         sagas: Arc<dyn StartSaga>,
+        clock: Arc<dyn Clock>,
         disabled: bool,
     ) -> Self {
         Self {
             datastore,
             sagas,
+            clock,
             concurrency_limit: DEFAULT_MAX_CONCURRENT_REINCARNATIONS,
+            max_per_pass: DEFAULT_MAX_REINCARNATIONS_PER_PASS,
+            max_per_project: DEFAULT_MAX_REINCARNATIONS_PER_PROJECT,
             disabled,
         }
     }
@@ -143,26 +186,49 @@ impl InstanceReincarnation {
         opctx: &OpContext,
         reason: ReincarnationReason,
         status: &mut InstanceReincarnationStatus,
+        budget: &mut PassBudget,
         running_sagas: &mut Vec<RunningSaga>,
     ) -> anyhow::Result<()> {
         let serialized_authn = authn::saga::Serialized::for_opctx(opctx);
 
-        let mut paginator = Paginator::new(
-            self.concurrency_limit,
-            dropshot::PaginationOrder::Ascending,
-        );
+        // Instances are found in batches, in order of their IDs, each batch
+        // starting after the last instance of the previous one. Projects that
+        // have used up their share of this activation are left out of the
+        // query, so that their remaining instances don't take up space in
+        // later batches.
+        let mut marker = None;
         let instances_found = status.instances_found.entry(reason).or_insert(0);
         let mut sagas_started = 0;
-        while let Some(p) = paginator.next() {
+        loop {
+            let Some(limit) = NonZeroU32::new(
+                self.concurrency_limit.get().min(budget.remaining),
+            ) else {
+                debug!(
+                    opctx.log,
+                    "started as many instance-start sagas as one activation \
+                     may; leaving any remaining instances for later";
+                    "reincarnation_reason" => %reason,
+                    "max_per_pass" => self.max_per_pass.get(),
+                );
+                status.pass_limit_reached = true;
+                break;
+            };
+            let skip_projects =
+                status.projects_at_limit.iter().copied().collect::<Vec<_>>();
             let batch = self
                 .datastore
                 .find_reincarnatable_instances(
                     &opctx,
                     reason,
-                    &p.current_pagparams(),
+                    self.clock.now(),
+                    &skip_projects,
+                    &DataPageParams {
+                        marker: marker.as_ref(),
+                        direction: dropshot::PaginationOrder::Ascending,
+                        limit,
+                    },
                 )
                 .await?;
-            paginator = p.found_batch(&batch, &|instance| instance.id());
 
             let found = batch.len();
             *instances_found += found;
@@ -175,8 +241,35 @@ impl InstanceReincarnation {
                 );
                 break;
             }
+            let last_batch = found < limit.get() as usize;
+            marker = batch.last().map(|instance| instance.id());
+
             for db_instance in batch {
                 let instance_id = db_instance.id();
+                let project_id = db_instance.project_id;
+
+                // The project may have reached its limit earlier in this
+                // batch. Its other instances will be found again by a later
+                // activation.
+                if status.projects_at_limit.contains(&project_id) {
+                    debug!(
+                        opctx.log,
+                        "deferring reincarnation of instance, as its project \
+                         has reached its limit for this activation";
+                        "instance_id" => %instance_id,
+                        "project_id" => %project_id,
+                        "reincarnation_reason" => %reason,
+                    );
+                    continue;
+                }
+                let started =
+                    budget.started_per_project.entry(project_id).or_insert(0);
+                *started += 1;
+                if *started >= self.max_per_project.get() {
+                    status.projects_at_limit.insert(project_id);
+                }
+                budget.remaining -= 1;
+
                 info!(
                     opctx.log,
                     "attempting to reincarnate instance...";
@@ -298,6 +391,11 @@ impl InstanceReincarnation {
                     }
                 }
             }
+
+            // A short batch means there are no more instances to find.
+            if last_batch {
+                break;
+            }
         }
 
         Ok(())
@@ -315,6 +413,7 @@ mod test {
     use nexus_db_model::InstanceIntendedState;
     use nexus_db_model::InstanceRuntimeState;
     use nexus_db_model::InstanceState;
+    use nexus_db_model::ProjectAutoRestartDefaults;
     use nexus_db_model::Vmm;
     use nexus_db_model::VmmRuntimeState;
     use nexus_db_model::VmmState;
@@ -323,12 +422,14 @@ mod test {
         create_default_ip_pool, create_project, object_create,
     };
     use nexus_test_utils_macros::nexus_test;
+    use nexus_types::clock::SystemClock;
+    use nexus_types::clock::TestClock;
     use omicron_common::api::external::ByteCount;
     use omicron_common::api::external::IdentityMetadataCreateParams;
     use omicron_common::api::external::InstanceAutoRestartPolicy;
     use omicron_uuid_kinds::GenericUuid;
     use omicron_uuid_kinds::InstanceUuid;
-    use std::time::Duration;
+    use std::collections::BTreeSet;
 
     type ControlPlaneTestContext =
         nexus_test_utils::ControlPlaneTestContext<crate::Server>;
@@ -388,11 +489,13 @@ mod test {
                         params::InstanceNetworkInterfaceAttachment::None,
                     external_ips: Vec::new(),
                     disks: Vec::new(),
-                    boot_disk: None,
+                    boot_order: Vec::new(),
                     ssh_public_keys: None,
                     start: state == InstanceState::Vmm,
                     auto_restart_policy,
                     anti_affinity_groups: Vec::new(),
+                    tags: Default::default(),
+                    stopped_reservation_policy: Default::default(),
                 },
             )
             .await;
@@ -555,6 +658,7 @@ mod test {
         let mut task = InstanceReincarnation::new(
             datastore.clone(),
             nexus.sagas.clone(),
+            Arc::new(SystemClock),
             false,
         );
 
@@ -591,6 +695,129 @@ mod test {
         .await;
     }
 
+    #[nexus_test(server = crate::Server)]
+    async fn test_limits_reincarnations_per_project(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let authz_project = setup_test_project(&cptestctx, &opctx).await;
+
+        let mut task = InstanceReincarnation::new(
+            datastore.clone(),
+            nexus.sagas.clone(),
+            Arc::new(SystemClock),
+            false,
+        );
+        task.max_per_project = NonZeroU32::new(1).unwrap();
+
+        let mut instance_ids = BTreeSet::new();
+        for name in ["victor", "frankenstein"] {
+            let instance = create_instance(
+                &cptestctx,
+                &opctx,
+                name,
+                InstanceAutoRestartPolicy::BestEffort,
+                InstanceState::Failed,
+                InstanceIntendedState::Running,
+            )
+            .await;
+            instance_ids.insert(instance.id());
+        }
+
+        // Both instances are in the same project, so each activation should
+        // only restart one of them.
+        let mut reincarnated = BTreeSet::new();
+        for _ in 0..2 {
+            let status = assert_activation_ok!(task.activate(&opctx).await);
+            assert_eq!(status.instances_reincarnated.len(), 1);
+            assert_eq!(
+                status.projects_at_limit,
+                BTreeSet::from([authz_project.id()])
+            );
+            assert!(!status.pass_limit_reached);
+
+            let instance_id = status.instances_reincarnated[0].instance_id;
+            assert!(reincarnated.insert(instance_id));
+            test_helpers::instance_wait_for_state(
+                &cptestctx,
+                InstanceUuid::from_untyped_uuid(instance_id),
+                InstanceState::Vmm,
+            )
+            .await;
+        }
+        assert_eq!(reincarnated, instance_ids);
+
+        // Nothing is left to restart.
+        let status = assert_activation_ok!(task.activate(&opctx).await);
+        assert_eq!(status.total_instances_found(), 0);
+        assert_eq!(status.projects_at_limit, BTreeSet::new());
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_limits_reincarnations_per_pass(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        setup_test_project(&cptestctx, &opctx).await;
+
+        let mut task = InstanceReincarnation::new(
+            datastore.clone(),
+            nexus.sagas.clone(),
+            Arc::new(SystemClock),
+            false,
+        );
+        task.max_per_pass = NonZeroU32::new(1).unwrap();
+
+        for name in ["victor", "frankenstein"] {
+            create_instance(
+                &cptestctx,
+                &opctx,
+                name,
+                InstanceAutoRestartPolicy::BestEffort,
+                InstanceState::Failed,
+                InstanceIntendedState::Running,
+            )
+            .await;
+        }
+
+        let status = assert_activation_ok!(task.activate(&opctx).await);
+        assert_eq!(status.instances_reincarnated.len(), 1);
+        assert!(status.pass_limit_reached);
+        test_helpers::instance_wait_for_state(
+            &cptestctx,
+            InstanceUuid::from_untyped_uuid(
+                status.instances_reincarnated[0].instance_id,
+            ),
+            InstanceState::Vmm,
+        )
+        .await;
+
+        // The next activation picks up the instance that was left behind.
+        let status = assert_activation_ok!(task.activate(&opctx).await);
+        assert_eq!(status.instances_reincarnated.len(), 1);
+        assert!(status.pass_limit_reached);
+        test_helpers::instance_wait_for_state(
+            &cptestctx,
+            InstanceUuid::from_untyped_uuid(
+                status.instances_reincarnated[0].instance_id,
+            ),
+            InstanceState::Vmm,
+        )
+        .await;
+    }
+
     #[nexus_test(server = crate::Server)]
     async fn test_default_policy_is_reincarnatable(
         cptestctx: &ControlPlaneTestContext,
@@ -607,6 +834,7 @@ mod test {
         let mut task = InstanceReincarnation::new(
             datastore.clone(),
             nexus.sagas.clone(),
+            Arc::new(SystemClock),
             false,
         );
 
@@ -637,6 +865,72 @@ mod test {
         .await;
     }
 
+    #[nexus_test(server = crate::Server)]
+    async fn test_project_default_policy_is_inherited(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let authz_project = setup_test_project(&cptestctx, &opctx).await;
+        datastore
+            .project_auto_restart_defaults_update(
+                &opctx,
+                &authz_project,
+                ProjectAutoRestartDefaults {
+                    policy: Some(InstanceAutoRestartPolicy::Never.into()),
+                    cooldown: None,
+                },
+            )
+            .await
+            .expect("should set project auto-restart defaults");
+
+        let mut task = InstanceReincarnation::new(
+            datastore.clone(),
+            nexus.sagas.clone(),
+            Arc::new(SystemClock),
+            false,
+        );
+
+        // An instance with no policy of its own inherits the project's
+        // "never" policy...
+        let _inherits = create_instance(
+            &cptestctx,
+            &opctx,
+            "inherits-never",
+            None,
+            InstanceState::Failed,
+            InstanceIntendedState::Running,
+        )
+        .await;
+        // ...while one with its own policy ignores the project's.
+        let overrides = create_instance(
+            &cptestctx,
+            &opctx,
+            "overrides-never",
+            InstanceAutoRestartPolicy::BestEffort,
+            InstanceState::Failed,
+            InstanceIntendedState::Running,
+        )
+        .await;
+
+        let status = assert_activation_ok!(task.activate(&opctx).await);
+        assert_eq!(status.total_instances_found(), 1);
+        assert_eq!(status.instances_reincarnated, vec![failed(overrides.id())]);
+        assert_eq!(status.changed_state, Vec::new());
+
+        test_helpers::instance_wait_for_state(
+            &cptestctx,
+            InstanceUuid::from_untyped_uuid(overrides.id()),
+            InstanceState::Vmm,
+        )
+        .await;
+    }
+
     #[nexus_test(server = crate::Server)]
     async fn test_only_reincarnates_eligible_instances(
         cptestctx: &ControlPlaneTestContext,
@@ -653,6 +947,7 @@ mod test {
         let mut task = InstanceReincarnation::new(
             datastore.clone(),
             nexus.sagas.clone(),
+            Arc::new(SystemClock),
             false,
         );
 
@@ -830,9 +1125,13 @@ mod test {
 
         setup_test_project(&cptestctx, &opctx).await;
 
+        // The task's clock stands still unless we move it, so that instances
+        // only come out of their cooldown periods when the test says so.
+        let clock = TestClock::new_at_system_time();
         let mut task = InstanceReincarnation::new(
             datastore.clone(),
             nexus.sagas.clone(),
+            Arc::new(clock.clone()),
             false,
         );
 
@@ -848,15 +1147,10 @@ mod test {
         let instance1_id = InstanceUuid::from_untyped_uuid(instance1.id());
 
         // Use the test-only API to set the cooldown period for instance 1 to ten
-        // seconds, so that we don't have to make the test run for an hour to wait
-        // out the default cooldown.
-        const COOLDOWN_SECS: u64 = 10;
+        // seconds, so that it differs from the default cooldown.
+        let cooldown = chrono::TimeDelta::seconds(10);
         datastore
-            .instance_set_auto_restart_cooldown(
-                &opctx,
-                &instance1_id,
-                chrono::TimeDelta::seconds(COOLDOWN_SECS as i64),
-            )
+            .instance_set_auto_restart_cooldown(&opctx, &instance1_id, cooldown)
             .await
             .expect("we must be able to set the cooldown period");
 
@@ -923,9 +1217,26 @@ mod test {
         )
         .await;
 
-        // Wait out the cooldown period, and give it another shot. Now, instance
-        // 1 should be restarted again.
-        tokio::time::sleep(Duration::from_secs(COOLDOWN_SECS + 1)).await;
+        // Move the clock to just short of the end of instance 1's cooldown
+        // period. It still shouldn't be restarted.
+        let (.., db_instance1) = LookupPath::new(&opctx, datastore)
+            .instance_id(instance1_id.into_untyped_uuid())
+            .fetch()
+            .await
+            .expect("instance must exist");
+        let last_restarted = db_instance1
+            .runtime()
+            .time_last_auto_restarted
+            .expect("instance 1 must have been restarted");
+        clock.set(last_restarted + cooldown - chrono::TimeDelta::seconds(1));
+
+        let status = assert_activation_ok!(task.activate(&opctx).await);
+        assert_eq!(status.total_instances_found(), 0);
+        assert_eq!(status.instances_reincarnated, Vec::new());
+
+        // Once the cooldown period has elapsed, instance 1 should be restarted
+        // again.
+        clock.set(last_restarted + cooldown);
 
         let status = assert_activation_ok!(task.activate(&opctx).await);
         assert_eq!(status.total_instances_found(), 1);
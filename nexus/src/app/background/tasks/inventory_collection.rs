@@ -13,6 +13,7 @@ use internal_dns_types::names::ServiceName;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db::DataStore;
 use nexus_inventory::InventoryError;
+use nexus_inventory::SledAgentInventoryCache;
 use nexus_types::deployment::SledFilter;
 use nexus_types::inventory::Collection;
 use omicron_cockroach_metrics::CockroachClusterAdminClient;
@@ -33,6 +34,12 @@ pub struct InventoryCollector {
     disable: bool,
     tx: watch::Sender<Option<CollectionUuid>>,
     cockroach_admin_client: CockroachClusterAdminClient,
+    /// Sled agent inventories from previous activations, so that we only
+    /// need to fetch what has changed since
+    sled_agent_cache: SledAgentInventoryCache,
+    /// The collection from the last successful activation, used to fill in
+    /// for service processors that can't be reached during the next one
+    last_collection: Option<Collection>,
 }
 
 impl InventoryCollector {
@@ -60,6 +67,8 @@ impl InventoryCollector {
             disable,
             tx,
             cockroach_admin_client,
+            sled_agent_cache: SledAgentInventoryCache::new(),
+            last_collection: None,
         }
     }
 
@@ -82,6 +91,8 @@ impl BackgroundTask for InventoryCollector {
                 self.nkeep,
                 self.disable,
                 &self.cockroach_admin_client,
+                &mut self.sled_agent_cache,
+                self.last_collection.as_ref(),
             )
             .await
             .context("failed to collect inventory")
@@ -103,6 +114,7 @@ impl BackgroundTask for InventoryCollector {
                         "time_done": collection.time_done.to_string()
                     });
                     self.tx.send_replace(Some(collection.id));
+                    self.last_collection = Some(collection);
                     json
                 }
             }
@@ -119,6 +131,8 @@ async fn inventory_activate(
     nkeep: u32,
     disabled: bool,
     cockroach_admin_client: &CockroachClusterAdminClient,
+    sled_agent_cache: &mut SledAgentInventoryCache,
+    previous_collection: Option<&Collection>,
 ) -> Result<Collection, anyhow::Error> {
     // If we're disabled, don't do anything.  (This switch is only intended for
     // unforeseen production emergencies.)
@@ -209,14 +223,18 @@ async fn inventory_activate(
     let sled_enum = DbSledAgentEnumerator { opctx, datastore };
 
     // Run a collection.
-    let inventory = nexus_inventory::Collector::new(
+    let mut inventory = nexus_inventory::Collector::new(
         creator,
         mgs_clients,
         keeper_admin_clients,
         cockroach_admin_client,
         &sled_enum,
         opctx.log.clone(),
-    );
+    )
+    .with_sled_agent_cache(sled_agent_cache);
+    if let Some(previous) = previous_collection {
+        inventory = inventory.with_previous_collection(previous);
+    }
     let collection =
         inventory.collect_all().await.context("collecting inventory")?;
 
@@ -12,7 +12,9 @@ pub mod blueprint_load;
 pub mod blueprint_planner;
 pub mod blueprint_rendezvous;
 pub mod chicken_switches;
+pub mod clickhouse_backup;
 pub mod crdb_node_id_collector;
+pub mod dataset_quota_tuner;
 pub mod decommissioned_disk_cleaner;
 pub mod dns_config;
 pub mod dns_propagation;
@@ -36,8 +38,10 @@ pub mod region_snapshot_replacement_finish;
 pub mod region_snapshot_replacement_garbage_collect;
 pub mod region_snapshot_replacement_start;
 pub mod region_snapshot_replacement_step;
+pub mod rolling_sled_reboot;
 pub mod saga_recovery;
 pub mod service_firewall_rules;
+pub mod snapshot_scheduler;
 pub mod support_bundle_collector;
 pub mod sync_service_zone_nat;
 pub mod sync_switch_configuration;
@@ -45,3 +49,5 @@ pub mod tuf_artifact_replication;
 pub mod v2p_mappings;
 pub mod vpc_routes;
 pub mod webhook_deliverator;
+pub mod zpool_scrub_alerts;
+pub mod zpool_usage_trends;
@@ -305,6 +305,8 @@ mod test {
                     size: external::ByteCount::try_from(MIN_DISK_SIZE_BYTES)
                         .unwrap()
                         .into(),
+                    delete_protected: false,
+                    snapshot_schedule_id: None,
                 },
             )
             .await
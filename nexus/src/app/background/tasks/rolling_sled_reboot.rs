@@ -0,0 +1,1098 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Background task for driving rolling reboots of sleds
+//!
+//! An operator starts a rolling reboot of a list of sleds via the internal
+//! API. Each activation of this task moves the running reboot (there's at most
+//! one) along:
+//!
+//! * Pending sleds are started on, in order, while fewer than the reboot's
+//!   batch size are out of service. Starting on a sled quiesces it, so that
+//!   nothing new is placed on it.
+//! * Draining sleds have their instances migrated to other sleds. Instances
+//!   that can't be placed anywhere else are stopped instead, if their
+//!   auto-restart policy allows the control plane to start them again.
+//!   Once no instances are left, the sled moves on.
+//! * Rebooting sleds are reset via their SP.
+//! * Rejoining sleds are done once an inventory collection taken after the
+//!   reset shows the sled agent having reconciled its current config with
+//!   all of its zones running. The sled is then returned to service.
+//!
+//! Instances that were stopped are started again once the sled they were
+//! stopped on is no longer out of service, whether or not it was rebooted.
+//!
+//! Cancelling a reboot, or any of its sleds failing, keeps further sleds from
+//! being started on. Sleds that are still draining are returned to service
+//! right away, while sleds that have already been reset are seen through to
+//! rejoining. Once no sleds are out of service, the reboot finishes.
+
+use crate::app::background::BackgroundTask;
+use crate::app::instance::check_migration_timesync;
+use crate::app::saga::StartSaga;
+use crate::app::sagas::NexusSaga;
+use crate::app::sagas::instance_migrate;
+use crate::app::sagas::instance_start;
+use chrono::DateTime;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use gateway_client::SpComponent;
+use internal_dns_types::names::ServiceName;
+use nexus_db_lookup::LookupPath;
+use nexus_db_model::Instance;
+use nexus_db_model::InstanceAutoRestartPolicy;
+use nexus_db_model::InstanceIntendedState;
+use nexus_db_model::ProjectAutoRestartDefaults;
+use nexus_db_model::Resources;
+use nexus_db_model::RollingSledReboot;
+use nexus_db_model::RollingSledRebootInstance;
+use nexus_db_model::RollingSledRebootSled;
+use nexus_db_model::RollingSledRebootState;
+use nexus_db_model::RollingSledRebootStep;
+use nexus_db_model::Vmm;
+use nexus_db_model::VmmState;
+use nexus_db_queries::authn;
+use nexus_db_queries::authz;
+use nexus_db_queries::context::OpContext;
+use nexus_db_queries::db::DataStore;
+use nexus_sled_agent_shared::inventory::ConfigReconcilerInventoryResult;
+use nexus_types::identity::Resource;
+use nexus_types::internal_api::background::RollingSledRebootStatus;
+use nexus_types::internal_api::params::InstanceMigrateRequest;
+use nexus_types::inventory::BaseboardId;
+use nexus_types::inventory::Collection;
+use nexus_types::inventory::SpType;
+use omicron_common::api::external::ByteCount;
+use omicron_common::api::external::Error;
+use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
+use omicron_uuid_kinds::PropolisUuid;
+use omicron_uuid_kinds::SledUuid;
+use sled_agent_client::types::VmmPutStateBody;
+use sled_agent_client::types::VmmStateRequested;
+use slog_error_chain::InlineErrorChain;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use steno::SagaId;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+/// How long a sled may spend in the steps of a rolling reboot that wait on
+/// the rest of the system before it's given up on
+#[derive(Clone, Copy, Debug)]
+pub struct RollingSledRebootTimeouts {
+    /// how long a sled may take to be drained of instances
+    pub drain: Duration,
+    /// how long a sled may take to rejoin the control plane after being reset
+    pub rejoin: Duration,
+}
+
+pub struct RollingSledReboots {
+    datastore: Arc<DataStore>,
+    sagas: Arc<dyn StartSaga>,
+    resolver: internal_dns_resolver::Resolver,
+    rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+    timeouts: RollingSledRebootTimeouts,
+    disabled: bool,
+}
+
+type RunningSaga =
+    (InstanceUuid, SagaId, BoxFuture<'static, Result<(), Error>>);
+
+impl BackgroundTask for RollingSledReboots {
+    fn activate<'a>(
+        &'a mut self,
+        opctx: &'a OpContext,
+    ) -> BoxFuture<'a, serde_json::Value> {
+        Box::pin(async move {
+            let status = self.actually_activate(opctx).await;
+            serde_json::json!(status)
+        })
+    }
+}
+
+impl RollingSledReboots {
+    pub fn new(
+        datastore: Arc<DataStore>,
+        sagas: Arc<dyn StartSaga>,
+        resolver: internal_dns_resolver::Resolver,
+        rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+        timeouts: RollingSledRebootTimeouts,
+        disabled: bool,
+    ) -> Self {
+        Self { datastore, sagas, resolver, rx_inventory, timeouts, disabled }
+    }
+
+    async fn actually_activate(
+        &mut self,
+        opctx: &OpContext,
+    ) -> RollingSledRebootStatus {
+        let mut status = RollingSledRebootStatus::default();
+        if self.disabled {
+            status.disabled = true;
+            slog::trace!(
+                &opctx.log,
+                "rolling sled reboots disabled, doing nothing",
+            );
+            return status;
+        }
+
+        let reboot =
+            match self.datastore.rolling_sled_reboot_get_running(opctx).await {
+                Ok(Some(reboot)) => reboot,
+                Ok(None) => {
+                    slog::trace!(&opctx.log, "no rolling sled reboot running");
+                    return status;
+                }
+                Err(error) => {
+                    let msg = format!(
+                        "failed to look up running rolling sled reboot: {}",
+                        InlineErrorChain::new(&error),
+                    );
+                    error!(opctx.log, "{msg}");
+                    status.errors.push(msg);
+                    return status;
+                }
+            };
+        status.reboot_id = Some(reboot.id);
+
+        if let Err(error) = self.drive(opctx, &reboot, &mut status).await {
+            let msg = format!(
+                "failed to drive rolling sled reboot {}: {}",
+                reboot.id,
+                InlineErrorChain::new(&error),
+            );
+            error!(opctx.log, "{msg}");
+            status.errors.push(msg);
+        }
+
+        status
+    }
+
+    async fn drive(
+        &mut self,
+        opctx: &OpContext,
+        reboot: &RollingSledReboot,
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<(), Error> {
+        let mut sleds = self
+            .datastore
+            .rolling_sled_reboot_sled_list(opctx, reboot.id)
+            .await?;
+        let stopping = reboot.time_cancel_requested.is_some()
+            || sleds
+                .iter()
+                .any(|sled| sled.step == RollingSledRebootStep::Failed);
+
+        // Start on pending sleds, in order, while there's room in the batch.
+        if !stopping {
+            let batch_size = usize::from(*reboot.batch_size);
+            let mut in_progress =
+                sleds.iter().filter(|sled| sled.step.is_in_progress()).count();
+            for sled in &mut sleds {
+                if in_progress >= batch_size {
+                    break;
+                }
+                if sled.step == RollingSledRebootStep::Pending
+                    && self
+                        .advance(
+                            opctx,
+                            reboot.id,
+                            sled,
+                            RollingSledRebootStep::Draining,
+                            None,
+                            status,
+                        )
+                        .await?
+                {
+                    in_progress += 1;
+                }
+            }
+        }
+
+        // Resetting sleds and noticing that they've rejoined both rely on
+        // inventory, as does making sure instances aren't migrated to sleds
+        // whose clocks are out of sync.
+        let collection = if sleds.iter().any(|sled| sled.step.is_in_progress())
+        {
+            self.latest_collection(opctx, status).await
+        } else {
+            None
+        };
+
+        // Sleds that are waiting to be rebooted are the last place instances
+        // should be migrated to.
+        let pending: BTreeSet<SledUuid> = sleds
+            .iter()
+            .filter(|sled| sled.step == RollingSledRebootStep::Pending)
+            .map(|sled| sled.sled_id())
+            .collect();
+
+        for sled in &mut sleds {
+            let sled_id = sled.sled_id();
+            let result = match sled.step {
+                RollingSledRebootStep::Draining if stopping => {
+                    self.abandon_drain(opctx, reboot.id, sled, status).await
+                }
+                RollingSledRebootStep::Draining => {
+                    self.drain(
+                        opctx,
+                        reboot.id,
+                        sled,
+                        &pending,
+                        collection.as_ref(),
+                        status,
+                    )
+                    .await
+                }
+                RollingSledRebootStep::Rebooting => {
+                    self.reset(
+                        opctx,
+                        reboot.id,
+                        sled,
+                        collection.as_ref(),
+                        status,
+                    )
+                    .await
+                }
+                RollingSledRebootStep::Rejoining => {
+                    self.check_rejoined(
+                        opctx,
+                        reboot.id,
+                        sled,
+                        collection.as_ref(),
+                        status,
+                    )
+                    .await
+                }
+                RollingSledRebootStep::Pending
+                | RollingSledRebootStep::Done
+                | RollingSledRebootStep::Failed => continue,
+            };
+            if let Err(error) = result {
+                let msg = format!(
+                    "sled {sled_id} ({:?}): {}",
+                    sled.step,
+                    InlineErrorChain::new(&error),
+                );
+                warn!(opctx.log, "rolling sled reboot: {msg}");
+                status.errors.push(msg);
+            }
+        }
+
+        self.restart_stopped_instances(opctx, reboot.id, &sleds, status)
+            .await?;
+
+        // The reboot is over once no sleds are out of service and there are
+        // no more sleds to start on.
+        if sleds.iter().any(|sled| sled.step.is_in_progress()) {
+            return Ok(());
+        }
+        let state = if reboot.time_cancel_requested.is_some() {
+            RollingSledRebootState::Cancelled
+        } else if sleds
+            .iter()
+            .any(|sled| sled.step == RollingSledRebootStep::Failed)
+        {
+            RollingSledRebootState::Failed
+        } else if sleds
+            .iter()
+            .all(|sled| sled.step == RollingSledRebootStep::Done)
+        {
+            RollingSledRebootState::Completed
+        } else {
+            return Ok(());
+        };
+        if self
+            .datastore
+            .rolling_sled_reboot_finish(opctx, reboot.id, state)
+            .await?
+        {
+            info!(
+                opctx.log,
+                "rolling sled reboot finished";
+                "reboot_id" => %reboot.id,
+                "state" => ?state,
+            );
+            status.finished = Some(state.into());
+        }
+        Ok(())
+    }
+
+    async fn latest_collection(
+        &mut self,
+        opctx: &OpContext,
+        status: &mut RollingSledRebootStatus,
+    ) -> Option<Collection> {
+        let Some(collection_id) = *self.rx_inventory.borrow_and_update() else {
+            const MSG: &str = "no inventory collection available";
+            warn!(opctx.log, "rolling sled reboot: {MSG}");
+            status.errors.push(MSG.to_string());
+            return None;
+        };
+        match self
+            .datastore
+            .inventory_collection_read(opctx, collection_id)
+            .await
+        {
+            Ok(collection) => Some(collection),
+            Err(error) => {
+                let msg = format!(
+                    "can't read inventory collection {collection_id}: {}",
+                    InlineErrorChain::new(&error),
+                );
+                error!(opctx.log, "rolling sled reboot: {msg}");
+                status.errors.push(msg);
+                None
+            }
+        }
+    }
+
+    /// Moves `sled` from its current step to `to`, returning `false` if
+    /// another Nexus got there first.
+    async fn advance(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled: &mut RollingSledRebootSled,
+        to: RollingSledRebootStep,
+        error: Option<String>,
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<bool, Error> {
+        let sled_id = sled.sled_id();
+        let advanced = self
+            .datastore
+            .rolling_sled_reboot_sled_set_step(
+                opctx,
+                reboot_id,
+                sled_id,
+                sled.step,
+                to,
+                error.clone(),
+            )
+            .await?;
+        if advanced {
+            info!(
+                opctx.log,
+                "rolling sled reboot: sled moved to next step";
+                "reboot_id" => %reboot_id,
+                "sled_id" => %sled_id,
+                "from" => ?sled.step,
+                "to" => ?to,
+                "error" => ?error,
+            );
+            sled.step = to;
+            sled.time_step_changed = Utc::now();
+            sled.error = error;
+            status.sleds_advanced.insert(sled_id, to.into());
+        }
+        Ok(advanced)
+    }
+
+    async fn set_quiesced(
+        &self,
+        opctx: &OpContext,
+        sled_id: SledUuid,
+        quiesced: bool,
+    ) -> Result<(), Error> {
+        let (authz_sled,) = LookupPath::new(opctx, &self.datastore)
+            .sled_id(sled_id.into_untyped_uuid())
+            .lookup_for(authz::Action::Modify)
+            .await?;
+        self.datastore
+            .sled_set_quiesced(opctx, &authz_sled, quiesced)
+            .await
+            .map(|_| ())
+    }
+
+    /// Returns a draining sled to service, because the reboot is being
+    /// cancelled or another sled has failed.
+    async fn abandon_drain(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled: &mut RollingSledRebootSled,
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<(), Error> {
+        self.set_quiesced(opctx, sled.sled_id(), false).await?;
+        self.advance(
+            opctx,
+            reboot_id,
+            sled,
+            RollingSledRebootStep::Pending,
+            None,
+            status,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn drain(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled: &mut RollingSledRebootSled,
+        pending: &BTreeSet<SledUuid>,
+        collection: Option<&Collection>,
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<(), Error> {
+        let sled_id = sled.sled_id();
+        // This is idempotent, so it's done on each activation in case a
+        // previous one didn't get this far after starting on the sled.
+        self.set_quiesced(opctx, sled_id, true).await?;
+
+        let vmms = self
+            .datastore
+            .vmm_and_instance_list_on_sled(opctx, sled_id)
+            .await?;
+        if vmms.is_empty() {
+            self.advance(
+                opctx,
+                reboot_id,
+                sled,
+                RollingSledRebootStep::Rebooting,
+                None,
+                status,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        if timed_out(sled.time_step_changed, self.timeouts.drain) {
+            let error = format!(
+                "{} VMM(s) were still on the sled after {:?}",
+                vmms.len(),
+                self.timeouts.drain,
+            );
+            self.set_quiesced(opctx, sled_id, false).await?;
+            self.advance(
+                opctx,
+                reboot_id,
+                sled,
+                RollingSledRebootStep::Failed,
+                Some(error),
+                status,
+            )
+            .await?;
+            return Ok(());
+        }
+
+        for (vmm, instance, project_defaults) in vmms {
+            let instance_id = InstanceUuid::from_untyped_uuid(instance.id());
+            if let Err(error) = self
+                .drain_instance(
+                    opctx,
+                    reboot_id,
+                    sled_id,
+                    vmm,
+                    instance,
+                    &project_defaults,
+                    pending,
+                    collection,
+                    status,
+                )
+                .await
+            {
+                let msg = format!(
+                    "instance {instance_id} on sled {sled_id}: {}",
+                    InlineErrorChain::new(&error),
+                );
+                warn!(opctx.log, "rolling sled reboot: {msg}");
+                status.errors.push(msg);
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn drain_instance(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled_id: SledUuid,
+        vmm: Vmm,
+        instance: Instance,
+        project_defaults: &ProjectAutoRestartDefaults,
+        pending: &BTreeSet<SledUuid>,
+        collection: Option<&Collection>,
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<(), Error> {
+        let instance_id = InstanceUuid::from_untyped_uuid(instance.id());
+
+        // VMMs that are starting, stopping, or migrating in or out will be
+        // somewhere else soon enough (or running here, and then they can be
+        // moved). The same goes for instances that have already been asked
+        // to stop.
+        if instance.runtime().propolis_id != Some(vmm.id)
+            || instance.runtime().migration_id.is_some()
+            || vmm.runtime.state != VmmState::Running
+            || instance.intended_state == InstanceIntendedState::Stopped
+        {
+            return Ok(());
+        }
+
+        let resources = Resources::new(
+            u32::from(instance.ncpus.0.0),
+            ByteCount::from(0u32).into(),
+            instance.memory,
+        );
+        let target = self
+            .datastore
+            .sled_placement_explain(opctx, instance_id, &resources)
+            .await?
+            .into_iter()
+            .filter(|candidate| {
+                candidate.sled_id != sled_id
+                    && candidate.rejections.is_empty()
+                    && collection.is_none_or(|collection| {
+                        check_migration_timesync(
+                            collection,
+                            sled_id,
+                            candidate.sled_id,
+                        )
+                        .is_ok()
+                    })
+            })
+            .min_by_key(|candidate| pending.contains(&candidate.sled_id))
+            .map(|candidate| candidate.sled_id);
+
+        if let Some(dst_sled_id) = target {
+            info!(
+                opctx.log,
+                "rolling sled reboot: migrating instance";
+                "instance_id" => %instance_id,
+                "src_sled_id" => %sled_id,
+                "dst_sled_id" => %dst_sled_id,
+            );
+            let dag = instance_migrate::SagaInstanceMigrate::prepare(
+                &instance_migrate::Params {
+                    serialized_authn: authn::saga::Serialized::for_opctx(opctx),
+                    instance,
+                    src_vmm: vmm,
+                    migrate_params: InstanceMigrateRequest {
+                        dst_sled_id: dst_sled_id.into_untyped_uuid(),
+                    },
+                },
+            )?;
+            self.sagas.saga_start(dag).await?;
+            status.migrations_started.push(instance_id);
+            return Ok(());
+        }
+
+        if instance.auto_restart.effective_policy(project_defaults)
+            != InstanceAutoRestartPolicy::BestEffort
+        {
+            // Capacity may yet free up elsewhere; if it doesn't, the drain
+            // times out.
+            return Err(Error::unavail(
+                "no other sled can host the instance, and its auto-restart \
+                 policy doesn't allow it to be stopped",
+            ));
+        }
+
+        info!(
+            opctx.log,
+            "rolling sled reboot: stopping instance that can't be migrated";
+            "instance_id" => %instance_id,
+            "sled_id" => %sled_id,
+        );
+        let (.., authz_instance) = LookupPath::new(opctx, &self.datastore)
+            .instance_id(instance_id.into_untyped_uuid())
+            .lookup_for(authz::Action::Modify)
+            .await?;
+        self.datastore
+            .instance_set_intended_state(
+                opctx,
+                &authz_instance,
+                InstanceIntendedState::Stopped,
+            )
+            .await?;
+        // Record the instance before stopping it, so that it's started again
+        // even if this Nexus doesn't get any further.
+        self.datastore
+            .rolling_sled_reboot_instance_stopped(
+                opctx,
+                RollingSledRebootInstance::new(reboot_id, instance_id, sled_id),
+            )
+            .await?;
+        let client = nexus_networking::sled_client(
+            &self.datastore,
+            opctx,
+            sled_id.into_untyped_uuid(),
+            &opctx.log,
+        )
+        .await?;
+        client
+            .vmm_put_state(
+                &PropolisUuid::from_untyped_uuid(vmm.id),
+                &VmmPutStateBody { state: VmmStateRequested::Stopped },
+            )
+            .await
+            .map_err(|error| {
+                Error::internal_error(&format!(
+                    "failed to stop instance: {}",
+                    InlineErrorChain::new(&error),
+                ))
+            })?;
+        status.instances_stopped.push(instance_id);
+        Ok(())
+    }
+
+    async fn reset(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled: &mut RollingSledRebootSled,
+        collection: Option<&Collection>,
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<(), Error> {
+        let sled_id = sled.sled_id();
+        let Some(collection) = collection else {
+            return Err(Error::unavail(
+                "no inventory collection in which to find the sled's SP",
+            ));
+        };
+        let (.., db_sled) = LookupPath::new(opctx, &self.datastore)
+            .sled_id(sled_id.into_untyped_uuid())
+            .fetch()
+            .await?;
+        let baseboard = BaseboardId {
+            part_number: db_sled.part_number().to_string(),
+            serial_number: db_sled.serial_number().to_string(),
+        };
+        let Some(sp) = collection.sps.get(&baseboard) else {
+            // The sled hasn't been reset, so it can go straight back into
+            // service.
+            self.set_quiesced(opctx, sled_id, false).await?;
+            let error = format!(
+                "no SP found for baseboard {} (part {}) in inventory \
+                 collection {}",
+                baseboard.serial_number, baseboard.part_number, collection.id,
+            );
+            self.advance(
+                opctx,
+                reboot_id,
+                sled,
+                RollingSledRebootStep::Failed,
+                Some(error),
+                status,
+            )
+            .await?;
+            return Ok(());
+        };
+
+        // Only the Nexus that records the reset goes on to reset the sled.
+        // If it doesn't get that far, the sled is treated as having been
+        // reset anyway; we'd rather miss a reset than reset a sled twice.
+        let time_reset = Utc::now();
+        if !self
+            .datastore
+            .rolling_sled_reboot_sled_reset(
+                opctx, reboot_id, sled_id, time_reset,
+            )
+            .await?
+        {
+            return Ok(());
+        }
+        info!(
+            opctx.log,
+            "rolling sled reboot: resetting sled";
+            "reboot_id" => %reboot_id,
+            "sled_id" => %sled_id,
+            "sp_type" => ?sp.sp_type,
+            "sp_slot" => sp.sp_slot,
+        );
+        sled.step = RollingSledRebootStep::Rejoining;
+        sled.time_step_changed = time_reset;
+        sled.time_reset = Some(time_reset);
+        status
+            .sleds_advanced
+            .insert(sled_id, RollingSledRebootStep::Rejoining.into());
+
+        if let Err(error) = self.reset_sp(opctx, sp.sp_type, sp.sp_slot).await {
+            // The reset didn't happen, so the sled can go back into service.
+            self.set_quiesced(opctx, sled_id, false).await?;
+            self.advance(
+                opctx,
+                reboot_id,
+                sled,
+                RollingSledRebootStep::Failed,
+                Some(format!("failed to reset the sled via its SP: {error}")),
+                status,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Asks an MGS to reset an SP, which also resets its sled's host.
+    async fn reset_sp(
+        &self,
+        opctx: &OpContext,
+        sp_type: SpType,
+        sp_slot: u16,
+    ) -> Result<(), String> {
+        let addrs = self
+            .resolver
+            .lookup_all_socket_v6(ServiceName::ManagementGatewayService)
+            .await
+            .map_err(|error| {
+                format!(
+                    "failed to resolve MGS addresses: {}",
+                    InlineErrorChain::new(&error)
+                )
+            })?;
+
+        // An MGS won't reset the SP of the sled it's running on, so any
+        // failure is retried with the next MGS.
+        let mut last_error = String::from("no MGS addresses resolved");
+        for addr in addrs {
+            let url = format!("http://{addr}");
+            let log = opctx.log.new(o!("gateway_url" => url.clone()));
+            let client = gateway_client::Client::new(&url, log);
+            match client
+                .sp_component_reset(
+                    sp_type,
+                    sp_slot,
+                    SpComponent::SP_ITSELF.const_as_str(),
+                )
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err(error) => {
+                    last_error = format!(
+                        "MGS {addr}: {}",
+                        InlineErrorChain::new(&error)
+                    );
+                    warn!(
+                        opctx.log,
+                        "rolling sled reboot: failed to reset SP";
+                        "gateway_url" => &url,
+                        "error" => &last_error,
+                    );
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    async fn check_rejoined(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sled: &mut RollingSledRebootSled,
+        collection: Option<&Collection>,
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<(), Error> {
+        let sled_id = sled.sled_id();
+        let rejoined = match (collection, sled.time_reset) {
+            (Some(collection), Some(time_reset)) => {
+                sled_rejoined(collection, sled_id, time_reset)
+            }
+            _ => false,
+        };
+        if rejoined {
+            self.set_quiesced(opctx, sled_id, false).await?;
+            self.advance(
+                opctx,
+                reboot_id,
+                sled,
+                RollingSledRebootStep::Done,
+                None,
+                status,
+            )
+            .await?;
+        } else if timed_out(sled.time_step_changed, self.timeouts.rejoin) {
+            // The sled is left quiesced: nothing new should be placed on a
+            // sled that hasn't come back healthy.
+            let error = format!(
+                "the sled didn't report its zones running within {:?} of \
+                 being reset",
+                self.timeouts.rejoin,
+            );
+            self.advance(
+                opctx,
+                reboot_id,
+                sled,
+                RollingSledRebootStep::Failed,
+                Some(error),
+                status,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Starts the instances that were stopped to drain sleds that are no
+    /// longer out of service.
+    async fn restart_stopped_instances(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+        sleds: &[RollingSledRebootSled],
+        status: &mut RollingSledRebootStatus,
+    ) -> Result<(), Error> {
+        let out_of_service: BTreeSet<SledUuid> = sleds
+            .iter()
+            .filter(|sled| sled.step.is_in_progress())
+            .map(|sled| sled.sled_id())
+            .collect();
+        let stopped = self
+            .datastore
+            .rolling_sled_reboot_instance_list(opctx, reboot_id)
+            .await?
+            .into_iter()
+            .filter(|instance| {
+                instance.time_restarted.is_none()
+                    && !out_of_service
+                        .contains(&SledUuid::from(instance.sled_id))
+            });
+
+        let mut running_sagas: Vec<RunningSaga> = Vec::new();
+        for instance in stopped {
+            let instance_id = instance.instance_id();
+            match self.restart_instance(opctx, instance_id).await {
+                Ok(Some((saga_id, completed))) => {
+                    running_sagas.push((instance_id, saga_id, completed));
+                }
+                Ok(None) => {
+                    self.datastore
+                        .rolling_sled_reboot_instance_restarted(
+                            opctx,
+                            reboot_id,
+                            instance_id,
+                        )
+                        .await?;
+                }
+                Err(error) => {
+                    let msg = format!(
+                        "failed to start instance-start saga for instance \
+                         {instance_id}: {}",
+                        InlineErrorChain::new(&error),
+                    );
+                    warn!(opctx.log, "rolling sled reboot: {msg}");
+                    status.errors.push(msg);
+                }
+            }
+        }
+
+        for (instance_id, saga_id, completed) in running_sagas {
+            match completed.await {
+                Ok(()) => {
+                    self.datastore
+                        .rolling_sled_reboot_instance_restarted(
+                            opctx,
+                            reboot_id,
+                            instance_id,
+                        )
+                        .await?;
+                    status.instances_restarted.push(instance_id);
+                }
+                Err(error) => {
+                    let msg = format!(
+                        "instance-start saga {saga_id} for instance \
+                         {instance_id} failed: {}",
+                        InlineErrorChain::new(&error),
+                    );
+                    warn!(opctx.log, "rolling sled reboot: {msg}");
+                    status.errors.push(msg);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts a start saga for an instance that was stopped to drain a sled,
+    /// or returns `None` if it's since been deleted or started by someone
+    /// else.
+    async fn restart_instance(
+        &self,
+        opctx: &OpContext,
+        instance_id: InstanceUuid,
+    ) -> Result<Option<(SagaId, BoxFuture<'static, Result<(), Error>>)>, Error>
+    {
+        let (.., authz_instance) = match LookupPath::new(opctx, &self.datastore)
+            .instance_id(instance_id.into_untyped_uuid())
+            .lookup_for(authz::Action::Modify)
+            .await
+        {
+            Ok(lookup) => lookup,
+            Err(Error::ObjectNotFound { .. }) => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let state = self
+            .datastore
+            .instance_fetch_with_vmm(opctx, &authz_instance)
+            .await?;
+        if state.vmm().is_some() {
+            return Ok(None);
+        }
+
+        let db_instance = self
+            .datastore
+            .instance_set_intended_state(
+                opctx,
+                &authz_instance,
+                InstanceIntendedState::Running,
+            )
+            .await?;
+        let dag = instance_start::SagaInstanceStart::prepare(
+            &instance_start::Params {
+                db_instance,
+                serialized_authn: authn::saga::Serialized::for_opctx(opctx),
+                reason: instance_start::Reason::SledRebooted,
+            },
+        )?;
+        self.sagas.saga_run(dag).await.map(Some)
+    }
+}
+
+/// Returns `true` if more than `timeout` has passed since `since`.
+fn timed_out(since: DateTime<Utc>, timeout: Duration) -> bool {
+    (Utc::now() - since).to_std().is_ok_and(|elapsed| elapsed > timeout)
+}
+
+/// Returns `true` if `collection` shows that the sled agent on `sled_id` has
+/// come back since `time_reset`, and has reconciled its current config with
+/// all of its zones running.
+fn sled_rejoined(
+    collection: &Collection,
+    sled_id: SledUuid,
+    time_reset: DateTime<Utc>,
+) -> bool {
+    let Some(sled_agent) = collection.sled_agents.get(&sled_id) else {
+        return false;
+    };
+    if sled_agent.time_collected <= time_reset {
+        return false;
+    }
+    let (Some(ledgered), Some(reconciliation)) =
+        (&sled_agent.ledgered_sled_config, &sled_agent.last_reconciliation)
+    else {
+        return false;
+    };
+    reconciliation.last_reconciled_config.generation == ledgered.generation
+        && reconciliation
+            .zones
+            .values()
+            .all(|result| matches!(result, ConfigReconcilerInventoryResult::Ok))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use nexus_test_utils_macros::nexus_test;
+
+    type ControlPlaneTestContext =
+        nexus_test_utils::ControlPlaneTestContext<crate::Server>;
+
+    #[test]
+    fn test_sled_rejoined() {
+        let mut collection =
+            nexus_inventory::examples::representative().builder.build();
+        let sled_id = collection
+            .sled_agents
+            .iter()
+            .find(|sled_agent| {
+                sled_agent.ledgered_sled_config.is_some()
+                    && sled_agent.last_reconciliation.is_some()
+            })
+            .expect("representative collection has a reconciled sled")
+            .sled_id;
+        let time_collected =
+            collection.sled_agents.get(&sled_id).unwrap().time_collected;
+        let before = time_collected - TimeDelta::minutes(5);
+
+        // A sled that's reconciled its config since being reset has rejoined.
+        assert!(sled_rejoined(&collection, sled_id, before));
+
+        // Inventory from before the reset says nothing about whether the
+        // sled has come back.
+        assert!(!sled_rejoined(&collection, sled_id, time_collected));
+
+        // Nor does inventory that's missing the sled altogether.
+        assert!(!sled_rejoined(&collection, SledUuid::new_v4(), before));
+
+        // A sled that hasn't yet reconciled its ledgered config hasn't
+        // rejoined.
+        {
+            let mut sled_agent =
+                collection.sled_agents.get_mut(&sled_id).unwrap();
+            let ledgered = sled_agent.ledgered_sled_config.as_mut().unwrap();
+            ledgered.generation = ledgered.generation.next();
+        }
+        assert!(!sled_rejoined(&collection, sled_id, before));
+
+        // Nor has one whose zones aren't all running.
+        {
+            let mut sled_agent =
+                collection.sled_agents.get_mut(&sled_id).unwrap();
+            let reconciliation =
+                sled_agent.last_reconciliation.as_mut().unwrap();
+            sled_agent.ledgered_sled_config =
+                Some(reconciliation.last_reconciled_config.clone());
+            let zone_id = *reconciliation
+                .zones
+                .keys()
+                .next()
+                .expect("reconciled sled has zones");
+            reconciliation.zones.insert(
+                zone_id,
+                ConfigReconcilerInventoryResult::Err {
+                    message: "zone failed to start".to_string(),
+                },
+            );
+        }
+        assert!(!sled_rejoined(&collection, sled_id, before));
+    }
+
+    #[test]
+    fn test_timed_out() {
+        let timeout = Duration::from_secs(60);
+        assert!(!timed_out(Utc::now(), timeout));
+        assert!(timed_out(Utc::now() - TimeDelta::minutes(2), timeout));
+        // A time in the future (e.g., from a Nexus with a fast clock) hasn't
+        // timed out.
+        assert!(!timed_out(Utc::now() + TimeDelta::minutes(2), timeout));
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_rolling_sled_reboot_idle(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+        let timeouts = RollingSledRebootTimeouts {
+            drain: Duration::from_secs(1800),
+            rejoin: Duration::from_secs(3600),
+        };
+
+        let (_tx_inventory, rx_inventory) = watch::channel(None);
+        let mut task = RollingSledReboots::new(
+            datastore.clone(),
+            nexus.sagas.clone(),
+            nexus.resolver().clone(),
+            rx_inventory,
+            timeouts,
+            true,
+        );
+        let status = task.actually_activate(&opctx).await;
+        assert_eq!(
+            status,
+            RollingSledRebootStatus { disabled: true, ..Default::default() }
+        );
+
+        // With reboots enabled but none running, nothing happens.
+        task.disabled = false;
+        let status = task.actually_activate(&opctx).await;
+        assert_eq!(status, RollingSledRebootStatus::default());
+    }
+}
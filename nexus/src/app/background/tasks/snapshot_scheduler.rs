@@ -0,0 +1,380 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Background task for taking snapshots on behalf of snapshot schedules
+//!
+//! Each activation prunes each schedule's snapshots of each disk down to the
+//! schedule's retention count, then starts a snapshot create saga for every
+//! disk covered by a schedule that is due. Only snapshots that are ready are
+//! pruned, and snapshots that are delete-protected are never pruned (nor do
+//! they count towards the retention count). This means that a schedule holds
+//! one more snapshot than its retention count from the time a new snapshot is
+//! created until the next activation of this task.
+
+use crate::app::authn;
+use crate::app::background::BackgroundTask;
+use crate::app::saga::StartSaga;
+use crate::app::sagas;
+use crate::app::sagas::NexusSaga;
+use crate::app::snapshot::snapshot_use_the_pantry;
+use chrono::DateTime;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use nexus_db_lookup::LookupPath;
+use nexus_db_model::Disk;
+use nexus_db_model::SnapshotSchedule;
+use nexus_db_model::SnapshotState;
+use nexus_db_queries::authz;
+use nexus_db_queries::context::OpContext;
+use nexus_db_queries::db::DataStore;
+use nexus_types::clock::Clock;
+use nexus_types::external_api::params;
+use nexus_types::identity::Resource;
+use nexus_types::internal_api::background::SnapshotScheduleAction;
+use nexus_types::internal_api::background::SnapshotSchedulerStatus;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::IdentityMetadataCreateParams;
+use omicron_common::api::external::Name;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct SnapshotScheduler {
+    datastore: Arc<DataStore>,
+    sagas: Arc<dyn StartSaga>,
+    /// The source of the current time, against which schedules' intervals
+    /// are measured.
+    clock: Arc<dyn Clock>,
+}
+
+impl BackgroundTask for SnapshotScheduler {
+    fn activate<'a>(
+        &'a mut self,
+        opctx: &'a OpContext,
+    ) -> BoxFuture<'a, serde_json::Value> {
+        Box::pin(async move {
+            let status = self.actually_activate(opctx).await;
+            serde_json::json!(status)
+        })
+    }
+}
+
+impl SnapshotScheduler {
+    pub fn new(
+        datastore: Arc<DataStore>,
+        sagas: Arc<dyn StartSaga>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self { datastore, sagas, clock }
+    }
+
+    async fn actually_activate(
+        &mut self,
+        opctx: &OpContext,
+    ) -> SnapshotSchedulerStatus {
+        let mut status = SnapshotSchedulerStatus::default();
+
+        let schedules = match self
+            .datastore
+            .snapshot_schedule_list_all_batched(opctx)
+            .await
+        {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                let s = format!("failed to list snapshot schedules: {e}");
+                error!(&opctx.log, "{s}");
+                status.errors.push(s);
+                return status;
+            }
+        };
+
+        let now = self.clock.now();
+        for schedule in &schedules {
+            let disks = match self
+                .datastore
+                .snapshot_schedule_list_disks(opctx, schedule)
+                .await
+            {
+                Ok(disks) => disks,
+                Err(e) => {
+                    let s = format!(
+                        "failed to list disks for snapshot schedule {}: {e}",
+                        schedule.id(),
+                    );
+                    error!(&opctx.log, "{s}");
+                    status.errors.push(s);
+                    continue;
+                }
+            };
+
+            for disk in &disks {
+                self.prune_snapshots(opctx, schedule, disk, &mut status).await;
+            }
+
+            if !schedule.is_due(now) {
+                continue;
+            }
+
+            status.schedules_run += 1;
+            for disk in &disks {
+                self.request_snapshot(opctx, schedule, disk, now, &mut status)
+                    .await;
+            }
+
+            if let Err(e) = self
+                .datastore
+                .snapshot_schedule_mark_run(opctx, schedule.id(), now)
+                .await
+            {
+                let s = format!(
+                    "failed to record run of snapshot schedule {}: {e}",
+                    schedule.id(),
+                );
+                error!(&opctx.log, "{s}");
+                status.errors.push(s);
+            }
+        }
+
+        status
+    }
+
+    /// Start a saga to take a snapshot of `disk` on behalf of `schedule`
+    async fn request_snapshot(
+        &self,
+        opctx: &OpContext,
+        schedule: &SnapshotSchedule,
+        disk: &Disk,
+        now: DateTime<Utc>,
+        status: &mut SnapshotSchedulerStatus,
+    ) {
+        let snapshot_name = scheduled_snapshot_name(schedule, now);
+        match self
+            .send_create_request(opctx, schedule, disk, &snapshot_name)
+            .await
+        {
+            Ok(()) => {
+                info!(
+                    &opctx.log,
+                    "started scheduled snapshot";
+                    "schedule_id" => %schedule.id(),
+                    "disk_id" => %disk.id(),
+                    "snapshot_name" => %snapshot_name,
+                );
+                status.snapshots_requested.push(SnapshotScheduleAction {
+                    schedule_id: schedule.id(),
+                    disk_id: disk.id(),
+                    snapshot_name: snapshot_name.to_string(),
+                });
+            }
+            Err(e) => {
+                let s = format!(
+                    "failed to start snapshot of disk {} for snapshot \
+                    schedule {}: {e}",
+                    disk.id(),
+                    schedule.id(),
+                );
+                error!(&opctx.log, "{s}");
+                status.errors.push(s);
+            }
+        }
+    }
+
+    async fn send_create_request(
+        &self,
+        opctx: &OpContext,
+        schedule: &SnapshotSchedule,
+        disk: &Disk,
+        snapshot_name: &Name,
+    ) -> Result<(), Error> {
+        let (authz_silo, authz_project, authz_disk) =
+            LookupPath::new(opctx, &self.datastore)
+                .disk_id(disk.id())
+                .lookup_for(authz::Action::Read)
+                .await?;
+
+        let use_the_pantry =
+            snapshot_use_the_pantry(opctx, &self.datastore, disk).await?;
+
+        let params = sagas::snapshot_create::Params {
+            serialized_authn: authn::saga::Serialized::for_opctx(opctx),
+            silo_id: authz_silo.id(),
+            project_id: authz_project.id(),
+            disk_id: authz_disk.id(),
+            attach_instance_id: disk.runtime_state.attach_instance_id,
+            use_the_pantry,
+            create_params: params::SnapshotCreate {
+                identity: IdentityMetadataCreateParams {
+                    name: snapshot_name.clone(),
+                    description: format!(
+                        "snapshot of disk \"{}\" taken by snapshot schedule \
+                        \"{}\"",
+                        disk.name(),
+                        schedule.name(),
+                    ),
+                },
+                disk: disk.id().into(),
+            },
+            snapshot_schedule_id: Some(schedule.id()),
+        };
+
+        let saga_dag =
+            sagas::snapshot_create::SagaSnapshotCreate::prepare(&params)?;
+        // We only care that the saga was started, and don't wish to wait for it
+        // to complete, so use `StartSaga::saga_start`, rather than `saga_run`.
+        self.sagas.saga_start(saga_dag).await?;
+        Ok(())
+    }
+
+    /// Start sagas to delete the snapshots of `disk` that `schedule` took
+    /// beyond its retention count
+    async fn prune_snapshots(
+        &self,
+        opctx: &OpContext,
+        schedule: &SnapshotSchedule,
+        disk: &Disk,
+        status: &mut SnapshotSchedulerStatus,
+    ) {
+        let snapshots = match self
+            .datastore
+            .snapshot_schedule_list_snapshots(opctx, schedule.id(), disk.id())
+            .await
+        {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                let s = format!(
+                    "failed to list snapshots of disk {} for snapshot \
+                    schedule {}: {e}",
+                    disk.id(),
+                    schedule.id(),
+                );
+                error!(&opctx.log, "{s}");
+                status.errors.push(s);
+                return;
+            }
+        };
+
+        // Snapshots are listed newest first, so everything after the first
+        // `retain_count` is to be pruned.
+        let expired = snapshots
+            .into_iter()
+            .filter(|snapshot| {
+                snapshot.state == SnapshotState::Ready
+                    && !snapshot.delete_protected
+            })
+            .skip(*schedule.retain_count as usize);
+
+        for snapshot in expired {
+            let snapshot_name = snapshot.name().to_string();
+            match self.send_delete_request(opctx, snapshot.id()).await {
+                Ok(()) => {
+                    info!(
+                        &opctx.log,
+                        "started pruning scheduled snapshot";
+                        "schedule_id" => %schedule.id(),
+                        "disk_id" => %disk.id(),
+                        "snapshot_id" => %snapshot.id(),
+                    );
+                    status.snapshots_pruned.push(SnapshotScheduleAction {
+                        schedule_id: schedule.id(),
+                        disk_id: disk.id(),
+                        snapshot_name,
+                    });
+                }
+                Err(e) => {
+                    let s = format!(
+                        "failed to start pruning snapshot {} for snapshot \
+                        schedule {}: {e}",
+                        snapshot.id(),
+                        schedule.id(),
+                    );
+                    error!(&opctx.log, "{s}");
+                    status.errors.push(s);
+                }
+            }
+        }
+    }
+
+    async fn send_delete_request(
+        &self,
+        opctx: &OpContext,
+        snapshot_id: Uuid,
+    ) -> Result<(), Error> {
+        let (.., authz_snapshot, db_snapshot) =
+            LookupPath::new(opctx, &self.datastore)
+                .snapshot_id(snapshot_id)
+                .fetch_for(authz::Action::Delete)
+                .await?;
+
+        let params = sagas::snapshot_delete::Params {
+            serialized_authn: authn::saga::Serialized::for_opctx(opctx),
+            authz_snapshot,
+            snapshot: db_snapshot,
+        };
+
+        let saga_dag =
+            sagas::snapshot_delete::SagaSnapshotDelete::prepare(&params)?;
+        self.sagas.saga_start(saga_dag).await?;
+        Ok(())
+    }
+}
+
+/// Maximum length of a resource name (see `omicron_common::api::external::Name`)
+const MAX_NAME_LEN: usize = 63;
+
+/// Returns the name of the snapshot to take on behalf of `schedule` at `now`
+///
+/// The name is made of the (possibly truncated) schedule name, the time, and a
+/// random suffix, so that schedules covering several disks in a project create
+/// distinct names.
+fn scheduled_snapshot_name(
+    schedule: &SnapshotSchedule,
+    now: DateTime<Utc>,
+) -> Name {
+    let suffix = format!(
+        "-{}-{}",
+        now.format("%Y%m%d-%H%M%S"),
+        &Uuid::new_v4().simple().to_string()[..8],
+    );
+    let max_prefix_len = MAX_NAME_LEN - suffix.len();
+    let prefix = schedule.name().as_str();
+    let prefix =
+        prefix[..prefix.len().min(max_prefix_len)].trim_end_matches('-');
+    format!("{prefix}{suffix}")
+        .parse()
+        .expect("schedule name prefix with time suffix is a valid name")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_scheduled_snapshot_name() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        let make = |name: &str| {
+            SnapshotSchedule::new(
+                Uuid::new_v4(),
+                None,
+                params::SnapshotScheduleCreate {
+                    identity: IdentityMetadataCreateParams {
+                        name: name.parse().unwrap(),
+                        description: String::new(),
+                    },
+                    disk: None,
+                    interval_secs: 3600,
+                    retain_count: 1,
+                },
+            )
+        };
+
+        let name = scheduled_snapshot_name(&make("hourly"), now);
+        assert!(name.as_str().starts_with("hourly-20260102-030405-"));
+
+        // Long schedule names are truncated to leave room for the suffix.
+        let long = format!("a{}", "-b".repeat(31));
+        let name = scheduled_snapshot_name(&make(&long), now);
+        assert!(name.as_str().len() <= MAX_NAME_LEN);
+        assert!(!name.as_str().contains("--"));
+    }
+}
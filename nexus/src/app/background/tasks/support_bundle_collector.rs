@@ -905,6 +905,12 @@ impl BundleCollection {
                 sled_client.support_zpool_info(),
             )
             .boxed(),
+            save_diag_cmd_output_or_error(
+                &sled_path,
+                "kstat",
+                sled_client.support_kstat_info(),
+            )
+            .boxed(),
             save_diag_cmd_output_or_error(
                 &sled_path,
                 "health-check",
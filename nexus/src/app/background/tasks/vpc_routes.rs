@@ -73,7 +73,7 @@ impl BackgroundTask for VpcRouteManager {
             }
             .into_iter()
             .filter(|sled| {
-                matches!(sled.state(), SledState::Active)
+                matches!(sled.state(), SledState::Active | SledState::Quiesced)
                     && matches!(sled.policy(), SledPolicy::InService { .. })
             });
 
@@ -218,6 +218,25 @@ impl BackgroundTask for VpcRouteManager {
                             )
                         },
                     ));
+                    // Ports allocated from a secondary IPv4 block ask for
+                    // the router keyed on that block.
+                    db_routers.extend(custom_routers.iter().flat_map(
+                        |(subnet, router)| {
+                            subnet.ipv4_secondary_blocks.iter().map(
+                                |block| {
+                                    (
+                                        RouterId {
+                                            vni: set.id.vni,
+                                            kind: RouterKind::Custom(
+                                                block.0.into(),
+                                            ),
+                                        },
+                                        router.clone(),
+                                    )
+                                },
+                            )
+                        },
+                    ));
                     db_routers.extend(custom_routers.into_iter().map(
                         |(subnet, router)| {
                             (
@@ -0,0 +1,285 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Background task for raising alerts about zpool scrubs that found errors
+//!
+//! Sled-agents scrub their zpools on a rotating schedule and report the
+//! outcome of each zpool's most recent scrub in inventory. Each activation
+//! looks through the latest inventory collection for finished scrubs that
+//! found errors and reports each of them once via a
+//! `storage.zpool.scrub_errors` alert. (A Nexus that restarts may report a
+//! scrub it had already reported again.)
+
+use crate::app::background::Activator;
+use crate::app::background::BackgroundTask;
+use chrono::DateTime;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use nexus_auth::context::OpContext;
+use nexus_db_model::AlertClass;
+use nexus_db_queries::db::DataStore;
+use nexus_sled_agent_shared::inventory::InventoryZpoolScrubState;
+use nexus_types::internal_api::background::ZpoolScrubAlertsStatus;
+use nexus_types::internal_api::background::ZpoolScrubErrors;
+use nexus_types::inventory::Zpool;
+use omicron_uuid_kinds::AlertUuid;
+use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::SledUuid;
+use omicron_uuid_kinds::ZpoolUuid;
+use slog_error_chain::InlineErrorChain;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Returns the errors found by `zpool`'s most recent scrub, or `None` if that
+/// scrub hasn't finished or didn't find any errors.
+fn scrub_errors(sled_id: SledUuid, zpool: &Zpool) -> Option<ZpoolScrubErrors> {
+    let scrub = zpool.scrub.as_ref()?;
+    if scrub.state != InventoryZpoolScrubState::Finished {
+        return None;
+    }
+    let errors = scrub.errors.filter(|&errors| errors > 0)?;
+    Some(ZpoolScrubErrors {
+        zpool_id: zpool.id,
+        sled_id,
+        time_finished: scrub.end_time?,
+        errors,
+    })
+}
+
+pub struct ZpoolScrubAlerts {
+    datastore: Arc<DataStore>,
+    rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+    alert_dispatcher: Activator,
+    disabled: bool,
+    /// When the scrub we last published an alert for finished, for each
+    /// zpool, so that each scrub is only alerted about once
+    last_alerted: BTreeMap<ZpoolUuid, DateTime<Utc>>,
+}
+
+impl BackgroundTask for ZpoolScrubAlerts {
+    fn activate<'a>(
+        &'a mut self,
+        opctx: &'a OpContext,
+    ) -> BoxFuture<'a, serde_json::Value> {
+        Box::pin(async move {
+            let status = self.actually_activate(opctx).await;
+            serde_json::json!(status)
+        })
+    }
+}
+
+impl ZpoolScrubAlerts {
+    pub fn new(
+        datastore: Arc<DataStore>,
+        rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+        alert_dispatcher: Activator,
+        disabled: bool,
+    ) -> Self {
+        Self {
+            datastore,
+            rx_inventory,
+            alert_dispatcher,
+            disabled,
+            last_alerted: BTreeMap::new(),
+        }
+    }
+
+    async fn actually_activate(
+        &mut self,
+        opctx: &OpContext,
+    ) -> ZpoolScrubAlertsStatus {
+        let mut status = ZpoolScrubAlertsStatus::default();
+        if self.disabled {
+            status.disabled = true;
+            slog::trace!(
+                &opctx.log,
+                "zpool scrub alerts disabled, doing nothing",
+            );
+            return status;
+        }
+
+        let Some(collection_id) = *self.rx_inventory.borrow_and_update() else {
+            const MSG: &str = "no inventory collection available";
+            warn!(opctx.log, "zpool scrub alerts skipped: {MSG}");
+            status.errors.push(MSG.to_string());
+            return status;
+        };
+        let collection = match self
+            .datastore
+            .inventory_collection_read(opctx, collection_id)
+            .await
+        {
+            Ok(collection) => collection,
+            Err(error) => {
+                let msg = format!(
+                    "can't read inventory collection {collection_id}: {error}"
+                );
+                error!(opctx.log, "{msg}");
+                status.errors.push(msg);
+                return status;
+            }
+        };
+        status.inventory_collection_id = Some(collection_id);
+
+        for sled_agent in &collection.sled_agents {
+            for zpool in &sled_agent.zpools {
+                status.zpools_checked += 1;
+                if let Some(errors) = scrub_errors(sled_agent.sled_id, zpool) {
+                    status.scrubs_with_errors.push(errors);
+                }
+            }
+        }
+
+        for scrub in &status.scrubs_with_errors {
+            let already_alerted = self
+                .last_alerted
+                .get(&scrub.zpool_id)
+                .is_some_and(|&t| t >= scrub.time_finished);
+            if already_alerted {
+                continue;
+            }
+            warn!(
+                opctx.log,
+                "zpool scrub found errors";
+                "zpool_id" => %scrub.zpool_id,
+                "sled_id" => %scrub.sled_id,
+                "time_finished" => %scrub.time_finished,
+                "errors" => scrub.errors,
+            );
+            let alert_id = AlertUuid::new_v4();
+            match self
+                .datastore
+                .alert_create(
+                    opctx,
+                    alert_id,
+                    AlertClass::StorageZpoolScrubErrors,
+                    serde_json::json!(scrub),
+                )
+                .await
+            {
+                Ok(_) => {
+                    self.last_alerted
+                        .insert(scrub.zpool_id, scrub.time_finished);
+                    status.alerts_published.push(alert_id);
+                }
+                Err(error) => {
+                    let msg = format!(
+                        "failed to publish scrub alert for zpool {}: {}",
+                        scrub.zpool_id,
+                        InlineErrorChain::new(&error),
+                    );
+                    error!(opctx.log, "{msg}");
+                    status.errors.push(msg);
+                }
+            }
+        }
+        if !status.alerts_published.is_empty() {
+            self.alert_dispatcher.activate();
+        }
+
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use nexus_sled_agent_shared::inventory::InventoryZpoolScrub;
+    use nexus_test_utils_macros::nexus_test;
+    use omicron_common::api::external::ByteCount;
+
+    type ControlPlaneTestContext =
+        nexus_test_utils::ControlPlaneTestContext<crate::Server>;
+
+    fn day(n: i64) -> DateTime<Utc> {
+        DateTime::UNIX_EPOCH + TimeDelta::days(n)
+    }
+
+    #[test]
+    fn test_scrub_errors() {
+        let sled_id = SledUuid::new_v4();
+        let zpool = |state, end_time, errors| Zpool {
+            time_collected: day(10),
+            id: ZpoolUuid::new_v4(),
+            total_size: ByteCount::from_gibibytes_u32(1000),
+            allocated: None,
+            fragmentation_percent: None,
+            scrub: Some(InventoryZpoolScrub {
+                state,
+                start_time: None,
+                end_time,
+                percent_done: None,
+                errors,
+            }),
+            health: None,
+        };
+
+        // Scrubs that haven't finished, or that found nothing, are fine.
+        for zpool in [
+            zpool(InventoryZpoolScrubState::InProgress, None, None),
+            zpool(InventoryZpoolScrubState::Canceled, Some(day(9)), None),
+            zpool(InventoryZpoolScrubState::Finished, Some(day(9)), Some(0)),
+            Zpool {
+                scrub: None,
+                ..zpool(InventoryZpoolScrubState::Paused, None, None)
+            },
+        ] {
+            assert_eq!(scrub_errors(sled_id, &zpool), None);
+        }
+
+        let zpool =
+            zpool(InventoryZpoolScrubState::Finished, Some(day(9)), Some(3));
+        assert_eq!(
+            scrub_errors(sled_id, &zpool),
+            Some(ZpoolScrubErrors {
+                zpool_id: zpool.id,
+                sled_id,
+                time_finished: day(9),
+                errors: 3,
+            })
+        );
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_zpool_scrub_alerts_disabled(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let (_tx_inventory, rx_inventory) = watch::channel(None);
+        let mut task = ZpoolScrubAlerts::new(
+            datastore.clone(),
+            rx_inventory,
+            Activator::new(),
+            true,
+        );
+        let status = task.actually_activate(&opctx).await;
+        assert_eq!(
+            status,
+            ZpoolScrubAlertsStatus { disabled: true, ..Default::default() }
+        );
+
+        // With alerts enabled but no inventory collection, nothing happens.
+        let mut task = ZpoolScrubAlerts::new(
+            datastore.clone(),
+            task.rx_inventory,
+            Activator::new(),
+            false,
+        );
+        let status = task.actually_activate(&opctx).await;
+        assert!(!status.disabled);
+        assert!(status.scrubs_with_errors.is_empty());
+        assert_eq!(
+            status.errors,
+            vec!["no inventory collection available".to_string()]
+        );
+    }
+}
@@ -0,0 +1,479 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Background task for tracking zpool capacity trends
+//!
+//! Inventory collections are pruned quickly, so each activation copies the
+//! space allocated within each zpool (as reported by the latest inventory
+//! collection) into a longer-lived history and prunes samples older than the
+//! retention period. It then fits a linear trend to each zpool's recent
+//! samples to project when the zpool will reach the configured "nearly full"
+//! threshold. Zpools that have already reached it, or are projected to within
+//! the warning horizon, are reported via `storage.zpool.capacity` alerts so
+//! that storage can be expanded ahead of time.
+
+use crate::app::background::Activator;
+use crate::app::background::BackgroundTask;
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use nexus_auth::context::OpContext;
+use nexus_db_model::AlertClass;
+use nexus_db_model::ZpoolUsageSample;
+use nexus_db_queries::db::DataStore;
+use nexus_types::internal_api::background::ZpoolCapacityWarning;
+use nexus_types::internal_api::background::ZpoolUsageTrendsStatus;
+use omicron_uuid_kinds::AlertUuid;
+use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::ZpoolUuid;
+use slog_error_chain::InlineErrorChain;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Parameters controlling how zpool usage trends are computed and reported
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZpoolTrendPolicy {
+    /// how long to keep usage samples
+    pub retention: TimeDelta,
+    /// how far back to look when fitting a trend to a zpool's usage
+    pub trend_window: TimeDelta,
+    /// percentage of a zpool's size considered nearly full
+    pub threshold_percent: u8,
+    /// warn about zpools projected to be nearly full within this many days
+    pub warning_days: u32,
+}
+
+impl ZpoolTrendPolicy {
+    /// Returns a warning for the zpool whose samples (oldest first) are
+    /// `samples`, or `None` if it isn't nearly full and isn't projected to be
+    /// within the warning horizon.
+    fn check(
+        &self,
+        samples: &[ZpoolUsageSample],
+    ) -> Option<ZpoolCapacityWarning> {
+        let latest = samples.last()?;
+        let threshold = u128::from(latest.total_size.to_bytes())
+            * u128::from(self.threshold_percent)
+            / 100;
+        let points: Vec<_> = samples
+            .iter()
+            .map(|s| (s.time_sampled, s.allocated.to_bytes()))
+            .collect();
+        let days = days_until_threshold(
+            &points,
+            u64::try_from(threshold).unwrap_or(u64::MAX),
+        );
+        if !days.is_some_and(|days| days <= f64::from(self.warning_days)) {
+            return None;
+        }
+        Some(ZpoolCapacityWarning {
+            zpool_id: latest.zpool_id.into(),
+            sled_id: latest.sled_id.into(),
+            total_size: *latest.total_size,
+            allocated: *latest.allocated,
+            fragmentation_percent: latest.fragmentation_percent.map(|f| *f),
+            threshold_percent: self.threshold_percent,
+            // `as` truncates to whole days (and saturates), which is what we
+            // want here.
+            days_until_threshold: days.map(|days| days as u32),
+        })
+    }
+}
+
+/// Projects the number of days until allocated space reaches `threshold`
+/// bytes, given `(time, allocated bytes)` samples ordered oldest first.
+///
+/// The growth rate is the slope of a least-squares line fit through the
+/// samples, and the projection starts from the most recent sample. Returns
+/// `Some(0.0)` if the most recent sample has already reached the threshold,
+/// and `None` if there's no upward trend to project from (including when
+/// there are too few samples to fit one).
+fn days_until_threshold(
+    samples: &[(DateTime<Utc>, u64)],
+    threshold: u64,
+) -> Option<f64> {
+    let &(first_time, _) = samples.first()?;
+    let &(_, latest) = samples.last()?;
+    if latest >= threshold {
+        return Some(0.0);
+    }
+
+    const SECS_PER_DAY: f64 = 86400.0;
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|&(time, allocated)| {
+            let days = (time - first_time).num_seconds() as f64 / SECS_PER_DAY;
+            (days, allocated as f64)
+        })
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), &(x, y)| {
+        let dx = x - mean_x;
+        (cov + dx * (y - mean_y), var + dx * dx)
+    });
+    if var == 0.0 {
+        // All samples were taken at the same time.
+        return None;
+    }
+    let bytes_per_day = cov / var;
+    if bytes_per_day <= 0.0 {
+        return None;
+    }
+    Some((threshold - latest) as f64 / bytes_per_day)
+}
+
+pub struct ZpoolUsageTrends {
+    datastore: Arc<DataStore>,
+    rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+    alert_dispatcher: Activator,
+    policy: ZpoolTrendPolicy,
+    disabled: bool,
+    /// When we last published an alert for each zpool, so that we don't
+    /// alert about the same zpool on every activation
+    last_alerted: BTreeMap<ZpoolUuid, DateTime<Utc>>,
+}
+
+impl BackgroundTask for ZpoolUsageTrends {
+    fn activate<'a>(
+        &'a mut self,
+        opctx: &'a OpContext,
+    ) -> BoxFuture<'a, serde_json::Value> {
+        Box::pin(async move {
+            let status = self.actually_activate(opctx).await;
+            serde_json::json!(status)
+        })
+    }
+}
+
+impl ZpoolUsageTrends {
+    pub fn new(
+        datastore: Arc<DataStore>,
+        rx_inventory: watch::Receiver<Option<CollectionUuid>>,
+        alert_dispatcher: Activator,
+        policy: ZpoolTrendPolicy,
+        disabled: bool,
+    ) -> Self {
+        Self {
+            datastore,
+            rx_inventory,
+            alert_dispatcher,
+            policy,
+            disabled,
+            last_alerted: BTreeMap::new(),
+        }
+    }
+
+    async fn actually_activate(
+        &mut self,
+        opctx: &OpContext,
+    ) -> ZpoolUsageTrendsStatus {
+        let mut status = ZpoolUsageTrendsStatus::default();
+        if self.disabled {
+            status.disabled = true;
+            slog::trace!(
+                &opctx.log,
+                "zpool usage trend analysis disabled, doing nothing",
+            );
+            return status;
+        }
+
+        let Some(collection_id) = *self.rx_inventory.borrow_and_update() else {
+            const MSG: &str = "no inventory collection available";
+            warn!(opctx.log, "zpool usage trend analysis skipped: {MSG}");
+            status.errors.push(MSG.to_string());
+            return status;
+        };
+        let collection = match self
+            .datastore
+            .inventory_collection_read(opctx, collection_id)
+            .await
+        {
+            Ok(collection) => collection,
+            Err(error) => {
+                let msg = format!(
+                    "can't read inventory collection {collection_id}: {error}"
+                );
+                error!(opctx.log, "{msg}");
+                status.errors.push(msg);
+                return status;
+            }
+        };
+        status.inventory_collection_id = Some(collection_id);
+
+        // Record the usage of every zpool in this collection. We only analyze
+        // these zpools below, so that zpools that have since been removed
+        // don't keep generating warnings.
+        let samples: Vec<_> = collection
+            .sled_agents
+            .iter()
+            .flat_map(|sled_agent| {
+                sled_agent.zpools.iter().filter_map(|zpool| {
+                    ZpoolUsageSample::from_inventory(sled_agent.sled_id, zpool)
+                })
+            })
+            .collect();
+        let current_zpools: BTreeSet<ZpoolUuid> =
+            samples.iter().map(|s| s.zpool_id.into()).collect();
+        match self.datastore.zpool_usage_samples_insert(opctx, samples).await {
+            Ok(n) => status.samples_recorded = n,
+            Err(error) => {
+                let msg = format!("failed to record zpool usage: {error}");
+                error!(opctx.log, "{msg}");
+                status.errors.push(msg);
+            }
+        }
+
+        let now = Utc::now();
+        match self
+            .datastore
+            .zpool_usage_samples_prune(opctx, now - self.policy.retention)
+            .await
+        {
+            Ok(n) => status.samples_pruned = n,
+            Err(error) => {
+                let msg = format!("failed to prune zpool usage: {error}");
+                error!(opctx.log, "{msg}");
+                status.errors.push(msg);
+            }
+        }
+
+        let history = match self
+            .datastore
+            .zpool_usage_samples_list_since(
+                opctx,
+                now - self.policy.trend_window,
+            )
+            .await
+        {
+            Ok(history) => history,
+            Err(error) => {
+                let msg = format!("failed to list zpool usage: {error}");
+                error!(opctx.log, "{msg}");
+                status.errors.push(msg);
+                return status;
+            }
+        };
+        let mut by_zpool: BTreeMap<ZpoolUuid, Vec<ZpoolUsageSample>> =
+            BTreeMap::new();
+        for sample in history {
+            by_zpool.entry(sample.zpool_id.into()).or_default().push(sample);
+        }
+
+        for (zpool_id, samples) in &by_zpool {
+            if !current_zpools.contains(zpool_id) {
+                continue;
+            }
+            status.zpools_analyzed += 1;
+            if let Some(warning) = self.policy.check(samples) {
+                status.warnings.push(warning);
+            }
+        }
+
+        for warning in &status.warnings {
+            let recently_alerted = self
+                .last_alerted
+                .get(&warning.zpool_id)
+                .is_some_and(|&t| now - t < TimeDelta::days(1));
+            if recently_alerted {
+                continue;
+            }
+            warn!(
+                opctx.log,
+                "zpool is nearly full";
+                "zpool_id" => %warning.zpool_id,
+                "sled_id" => %warning.sled_id,
+                "allocated" => %warning.allocated,
+                "total_size" => %warning.total_size,
+                "days_until_threshold" => ?warning.days_until_threshold,
+            );
+            let alert_id = AlertUuid::new_v4();
+            match self
+                .datastore
+                .alert_create(
+                    opctx,
+                    alert_id,
+                    AlertClass::StorageZpoolCapacity,
+                    serde_json::json!(warning),
+                )
+                .await
+            {
+                Ok(_) => {
+                    self.last_alerted.insert(warning.zpool_id, now);
+                    status.alerts_published.push(alert_id);
+                }
+                Err(error) => {
+                    let msg = format!(
+                        "failed to publish capacity alert for zpool {}: {}",
+                        warning.zpool_id,
+                        InlineErrorChain::new(&error),
+                    );
+                    error!(opctx.log, "{msg}");
+                    status.errors.push(msg);
+                }
+            }
+        }
+        if !status.alerts_published.is_empty() {
+            self.alert_dispatcher.activate();
+        }
+
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_db_model::ByteCount;
+    use nexus_db_model::SqlU8;
+    use nexus_test_utils_macros::nexus_test;
+    use omicron_common::api::external;
+    use omicron_uuid_kinds::SledUuid;
+
+    type ControlPlaneTestContext =
+        nexus_test_utils::ControlPlaneTestContext<crate::Server>;
+
+    const POLICY: ZpoolTrendPolicy = ZpoolTrendPolicy {
+        retention: TimeDelta::days(90),
+        trend_window: TimeDelta::days(14),
+        threshold_percent: 80,
+        warning_days: 30,
+    };
+
+    const GIB: u64 = 1 << 30;
+
+    fn day(n: i64) -> DateTime<Utc> {
+        DateTime::UNIX_EPOCH + TimeDelta::days(n)
+    }
+
+    #[test]
+    fn test_days_until_threshold() {
+        // Too few samples to establish a trend.
+        assert_eq!(days_until_threshold(&[], 100), None);
+        assert_eq!(days_until_threshold(&[(day(0), 10)], 100), None);
+        assert_eq!(
+            days_until_threshold(&[(day(0), 10), (day(0), 20)], 100),
+            None
+        );
+
+        // Already at or past the threshold, regardless of trend.
+        assert_eq!(days_until_threshold(&[(day(0), 100)], 100), Some(0.0));
+        assert_eq!(
+            days_until_threshold(&[(day(0), 200), (day(1), 150)], 100),
+            Some(0.0)
+        );
+
+        // Flat or shrinking usage never reaches the threshold.
+        assert_eq!(
+            days_until_threshold(&[(day(0), 10), (day(1), 10)], 100),
+            None
+        );
+        assert_eq!(
+            days_until_threshold(&[(day(0), 20), (day(1), 10)], 100),
+            None
+        );
+
+        // Steady growth of 10 bytes/day from 40 bytes reaches 100 bytes in 6
+        // days.
+        let samples: Vec<_> = (0..5).map(|n| (day(n), 10 * n as u64)).collect();
+        assert_eq!(days_until_threshold(&samples, 100), Some(6.0));
+
+        // Noisy samples are fit by least squares: this is a slope of 9.2
+        // bytes/day, projected from the last sample.
+        let samples = [(day(0), 2), (day(1), 8), (day(2), 22), (day(3), 28)];
+        let days = days_until_threshold(&samples, 100).unwrap();
+        assert!((days - 72.0 / 9.2).abs() < 1e-6, "bad projection: {days}");
+    }
+
+    #[test]
+    fn test_policy_check() {
+        let zpool_id = ZpoolUuid::new_v4();
+        let sled_id = SledUuid::new_v4();
+        let sample = |day_n: i64, allocated_gib: u64| ZpoolUsageSample {
+            zpool_id: zpool_id.into(),
+            time_sampled: day(day_n),
+            sled_id: sled_id.into(),
+            total_size: ByteCount::from(
+                external::ByteCount::try_from(1000 * GIB).unwrap(),
+            ),
+            allocated: ByteCount::from(
+                external::ByteCount::try_from(allocated_gib * GIB).unwrap(),
+            ),
+            fragmentation_percent: Some(SqlU8(15)),
+        };
+
+        // Growing by 1 GiB/day from 500 GiB won't reach 800 GiB for 300 days.
+        let slow: Vec<_> = (0..10).map(|n| sample(n, 491 + n as u64)).collect();
+        assert_eq!(POLICY.check(&slow), None);
+
+        // Growing by 20 GiB/day from 490 GiB reaches 800 GiB in 15.5 days.
+        let fast: Vec<_> =
+            (0..10).map(|n| sample(n, 310 + 20 * n as u64)).collect();
+        let warning = POLICY.check(&fast).expect("zpool should be flagged");
+        assert_eq!(
+            warning,
+            ZpoolCapacityWarning {
+                zpool_id,
+                sled_id,
+                total_size: external::ByteCount::try_from(1000 * GIB).unwrap(),
+                allocated: external::ByteCount::try_from(490 * GIB).unwrap(),
+                fragmentation_percent: Some(15),
+                threshold_percent: 80,
+                days_until_threshold: Some(15),
+            }
+        );
+
+        // A zpool that's already past the threshold is flagged even if it
+        // isn't growing.
+        let full = vec![sample(0, 900), sample(1, 850)];
+        let warning = POLICY.check(&full).expect("zpool should be flagged");
+        assert_eq!(warning.days_until_threshold, Some(0));
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_zpool_usage_trends_disabled(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let nexus = &cptestctx.server.server_context().nexus;
+        let datastore = nexus.datastore();
+        let opctx = OpContext::for_tests(
+            cptestctx.logctx.log.clone(),
+            datastore.clone(),
+        );
+
+        let (_tx_inventory, rx_inventory) = watch::channel(None);
+        let mut task = ZpoolUsageTrends::new(
+            datastore.clone(),
+            rx_inventory,
+            Activator::new(),
+            POLICY,
+            true,
+        );
+        let status = task.actually_activate(&opctx).await;
+        assert_eq!(
+            status,
+            ZpoolUsageTrendsStatus { disabled: true, ..Default::default() }
+        );
+
+        // With analysis enabled but no inventory collection, nothing happens.
+        let mut task = ZpoolUsageTrends::new(
+            datastore.clone(),
+            task.rx_inventory,
+            Activator::new(),
+            POLICY,
+            false,
+        );
+        let status = task.actually_activate(&opctx).await;
+        assert!(!status.disabled);
+        assert!(status.warnings.is_empty());
+        assert_eq!(
+            status.errors,
+            vec!["no inventory collection available".to_string()]
+        );
+    }
+}
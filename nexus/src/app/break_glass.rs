@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Break-glass operator account
+//!
+//! See the datastore module of the same name for what the account is and
+//! how it's kept from being used to access anything inside a silo.
+
+use chrono::TimeDelta;
+use chrono::Utc;
+use nexus_db_queries::context::OpContext;
+use nexus_types::internal_api::params::BreakGlassDisableRequest;
+use nexus_types::internal_api::params::BreakGlassEnableRequest;
+use nexus_types::internal_api::views::BreakGlassAccount;
+use nexus_types::silo::BREAK_GLASS_USER_NAME;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::UpdateResult;
+
+/// The longest the break-glass account may be enabled for at once
+const BREAK_GLASS_MAX_TTL_SECS: u32 = 24 * 60 * 60;
+
+impl super::Nexus {
+    pub(crate) async fn break_glass_enable(
+        &self,
+        opctx: &OpContext,
+        request: BreakGlassEnableRequest,
+    ) -> CreateResult<BreakGlassAccount> {
+        if request.ttl_secs == 0 || request.ttl_secs > BREAK_GLASS_MAX_TTL_SECS
+        {
+            return Err(Error::invalid_request(format!(
+                "break-glass account must be enabled for between 1 and {} \
+                 seconds",
+                BREAK_GLASS_MAX_TTL_SECS
+            )));
+        }
+        let enabled_by = request.enabled_by.trim();
+        if enabled_by.is_empty() {
+            return Err(Error::invalid_request(
+                "must say who is enabling the break-glass account",
+            ));
+        }
+
+        let time_expires =
+            Utc::now() + TimeDelta::seconds(i64::from(request.ttl_secs));
+        let account = self
+            .db_datastore
+            .break_glass_account_enable(
+                opctx,
+                request.password_hash.into(),
+                enabled_by.to_string(),
+                time_expires,
+            )
+            .await?;
+        warn!(
+            opctx.log,
+            "break-glass account enabled";
+            "username" => BREAK_GLASS_USER_NAME,
+            "account_id" => %account.id,
+            "silo_user_id" => %account.silo_user_id(),
+            "enabled_by" => &account.enabled_by,
+            "time_expires" => %account.time_expires,
+        );
+        Ok(account.into())
+    }
+
+    pub(crate) async fn break_glass_disable(
+        &self,
+        opctx: &OpContext,
+        request: BreakGlassDisableRequest,
+    ) -> UpdateResult<()> {
+        let disabled_by = request.disabled_by.trim();
+        if disabled_by.is_empty() {
+            return Err(Error::invalid_request(
+                "must say who is disabling the break-glass account",
+            ));
+        }
+
+        let account = self
+            .db_datastore
+            .break_glass_account_disable(opctx, disabled_by.to_string())
+            .await?;
+        match account {
+            Some(account) => warn!(
+                opctx.log,
+                "break-glass account disabled";
+                "account_id" => %account.id,
+                "silo_user_id" => %account.silo_user_id(),
+                "disabled_by" => disabled_by,
+            ),
+            None => info!(
+                opctx.log,
+                "break-glass account was not enabled";
+                "disabled_by" => disabled_by,
+            ),
+        }
+        Ok(())
+    }
+}
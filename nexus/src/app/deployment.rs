@@ -4,17 +4,31 @@
 
 //! Configuration of the deployment system
 
+use nexus_db_lookup::lookup;
 use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
+use nexus_db_queries::db::datastore::REGION_REDUNDANCY_THRESHOLD;
+use nexus_reconfigurator_planning::blueprint_builder::BlueprintBuilder;
+use nexus_reconfigurator_planning::blueprint_validate;
+use nexus_reconfigurator_planning::capacity::capacity_report;
 use nexus_reconfigurator_planning::planner::Planner;
 use nexus_reconfigurator_planning::planner::PlannerRng;
+use nexus_reconfigurator_planning::planner::cockroachdb_unsafe_to_shut_down;
 use nexus_reconfigurator_preparation::PlanningInputFromDb;
 use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintExecutionDisabled;
 use nexus_types::deployment::BlueprintMetadata;
 use nexus_types::deployment::BlueprintTarget;
+use nexus_types::deployment::BlueprintTargetExecution;
+use nexus_types::deployment::BlueprintTargetExecutionSet;
 use nexus_types::deployment::BlueprintTargetSet;
+use nexus_types::deployment::BlueprintZoneDisposition;
+use nexus_types::deployment::CapacityInstanceShape;
+use nexus_types::deployment::CapacityReport;
 use nexus_types::deployment::PlannerChickenSwitches;
 use nexus_types::deployment::PlanningInput;
+use nexus_types::external_api::views;
+use nexus_types::external_api::views::SledPolicy;
 use nexus_types::internal_api::views::UpdateStatus;
 use nexus_types::inventory::Collection;
 use omicron_common::api::external::CreateResult;
@@ -25,7 +39,11 @@ use omicron_common::api::external::InternalContext;
 use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::LookupType;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::OmicronZoneUuid;
+use omicron_uuid_kinds::SledUuid;
 use slog_error_chain::InlineErrorChain;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 /// Common structure for collecting information that the planner needs
@@ -77,11 +95,90 @@ impl super::Nexus {
         self.db_datastore.blueprint_target_get_current(opctx).await
     }
 
+    /// Lists blueprints for the external API, noting which of them is the
+    /// current target
+    pub(crate) async fn blueprint_list_external(
+        &self,
+        opctx: &OpContext,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<views::Blueprint> {
+        let blueprints =
+            self.db_datastore.blueprints_list(opctx, pagparams).await?;
+        let target = self.blueprint_target_view(opctx).await?;
+        Ok(blueprints
+            .into_iter()
+            .map(|metadata| {
+                let is_target = metadata.id == target.target_id;
+                blueprint_metadata_to_view(metadata, is_target)
+            })
+            .collect())
+    }
+
+    /// Fetches the current target blueprint for the external API
+    pub(crate) async fn blueprint_target_view_external(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<views::BlueprintTarget, Error> {
+        let (target, metadata) = self
+            .db_datastore
+            .blueprint_target_get_current_metadata(opctx)
+            .await?;
+        Ok(views::BlueprintTarget {
+            blueprint: blueprint_metadata_to_view(metadata, true),
+            enabled: target.enabled,
+            time_made_target: target.time_made_target,
+        })
+    }
+
     pub async fn blueprint_target_set(
         &self,
         opctx: &OpContext,
         params: BlueprintTargetSet,
     ) -> Result<BlueprintTarget, Error> {
+        // Check that the blueprint is valid before making it the target
+        // (unless the caller insists), and that a scoped blueprint only changes
+        // the sleds within its scope.  (The datastore separately requires the
+        // parent to be the current target.)
+        let blueprint = self
+            .blueprint_view(opctx, params.target_id.into_untyped_uuid())
+            .await?;
+        let parent = match blueprint.parent_blueprint_id {
+            Some(parent_id) => Some(
+                self.blueprint_view(opctx, parent_id.into_untyped_uuid())
+                    .await?,
+            ),
+            None => None,
+        };
+        if params.skip_validation {
+            warn!(
+                opctx.log,
+                "making blueprint the target without validating it";
+                "blueprint_id" => %blueprint.id,
+            );
+        } else {
+            self.blueprint_validate_for_target(
+                opctx,
+                &blueprint,
+                parent.as_ref(),
+            )
+            .await?;
+        }
+        if !blueprint.scope.is_fleet() {
+            let parent = parent.as_ref().ok_or_else(|| {
+                Error::invalid_request(format!(
+                    "blueprint {} is scoped to some sleds, but has no parent",
+                    blueprint.id
+                ))
+            })?;
+            blueprint.validate_scope(parent).map_err(|error| {
+                Error::invalid_request(format!(
+                    "cannot make blueprint {} the target: {}",
+                    blueprint.id,
+                    InlineErrorChain::new(&error)
+                ))
+            })?;
+        }
+
         let new_target = BlueprintTarget {
             target_id: params.target_id,
             enabled: params.enabled,
@@ -100,6 +197,38 @@ impl super::Nexus {
         Ok(new_target)
     }
 
+    /// Checks that `blueprint` satisfies the invariants of every target
+    /// blueprint and that it's consistent with the latest inventory collection
+    async fn blueprint_validate_for_target(
+        &self,
+        opctx: &OpContext,
+        blueprint: &Blueprint,
+        parent: Option<&Blueprint>,
+    ) -> Result<(), Error> {
+        blueprint_validate::validate(blueprint, parent).map_err(|error| {
+            Error::invalid_request(InlineErrorChain::new(&error).to_string())
+        })?;
+
+        let collection = self
+            .db_datastore
+            .inventory_get_latest_collection(opctx)
+            .await?
+            .ok_or_else(|| {
+                Error::invalid_request(format!(
+                    "cannot check blueprint {} against inventory: \
+                     no inventory collection found",
+                    blueprint.id
+                ))
+            })?;
+        blueprint_validate::check_against_inventory(blueprint, &collection)
+            .map_err(|findings| {
+                Error::invalid_request(format!(
+                    "{} (to make it the target anyway, skip validation)",
+                    InlineErrorChain::new(&findings)
+                ))
+            })
+    }
+
     pub async fn blueprint_target_set_enabled(
         &self,
         opctx: &OpContext,
@@ -123,6 +252,47 @@ impl super::Nexus {
         Ok(new_target)
     }
 
+    pub async fn blueprint_target_execution_view(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<BlueprintTargetExecution, Error> {
+        self.db_datastore.blueprint_target_get_current_execution(opctx).await
+    }
+
+    pub async fn blueprint_target_set_execution(
+        &self,
+        opctx: &OpContext,
+        params: BlueprintTargetExecutionSet,
+    ) -> Result<BlueprintTargetExecution, Error> {
+        let disabled = match params.disabled_reason {
+            Some(reason) if reason.trim().is_empty() => {
+                return Err(Error::invalid_request(
+                    "a reason is required to disable blueprint execution",
+                ));
+            }
+            Some(reason) => Some(BlueprintExecutionDisabled {
+                reason,
+                time_disabled: chrono::Utc::now(),
+            }),
+            None => None,
+        };
+
+        self.db_datastore
+            .blueprint_target_set_current_execution(
+                opctx,
+                params.target_id,
+                disabled.clone(),
+            )
+            .await?;
+
+        // Activate the executor so that it promptly notices (in particular,
+        // so that it resumes promptly if execution was re-enabled).
+        self.background_tasks
+            .activate(&self.background_tasks.task_blueprint_executor);
+
+        Ok(BlueprintTargetExecution { target_id: params.target_id, disabled })
+    }
+
     async fn blueprint_planning_context(
         &self,
         opctx: &OpContext,
@@ -207,6 +377,68 @@ impl super::Nexus {
         Ok(blueprint)
     }
 
+    /// Generates (and saves, but does not make the target) a new blueprint
+    /// based on the current target in which `zone_id` is cordoned (if
+    /// `cordoned` is true) or returned to service (if false).
+    pub async fn blueprint_create_zone_cordoned(
+        &self,
+        opctx: &OpContext,
+        zone_id: OmicronZoneUuid,
+        cordoned: bool,
+    ) -> CreateResult<Blueprint> {
+        let (_, parent_blueprint) =
+            self.db_datastore.blueprint_target_get_current_full(opctx).await?;
+
+        let Some(sled_id) = parent_blueprint
+            .all_omicron_zones(BlueprintZoneDisposition::any)
+            .find(|(_, zone)| zone.id == zone_id)
+            .map(|(sled_id, _)| sled_id)
+        else {
+            return Err(Error::invalid_request(format!(
+                "zone {zone_id} is not present in the current target blueprint"
+            )));
+        };
+
+        let planning_context = self.blueprint_planning_context(opctx).await?;
+        let inventory = planning_context.inventory.ok_or_else(|| {
+            Error::internal_error("no recent inventory collection found")
+        })?;
+        let mut builder = BlueprintBuilder::new_based_on(
+            &opctx.log,
+            &parent_blueprint,
+            &planning_context.planning_input,
+            &inventory,
+            &planning_context.creator,
+            PlannerRng::from_entropy(),
+        )
+        .map_err(|error| {
+            Error::internal_error(&format!(
+                "error creating blueprint builder: {error:#}",
+            ))
+        })?;
+
+        let action = if cordoned { "cordon" } else { "uncordon" };
+        let changed = builder
+            .sled_set_zone_cordoned(sled_id, zone_id, cordoned)
+            .map_err(|error| {
+                Error::invalid_request(format!(
+                    "cannot {action} zone {zone_id}: {}",
+                    InlineErrorChain::new(&error)
+                ))
+            })?;
+        if !changed {
+            return Err(Error::conflict(format!(
+                "zone {zone_id} is already {}",
+                if cordoned { "cordoned" } else { "in service" }
+            )));
+        }
+        builder.comment(format!("{action} zone {zone_id} (operator request)"));
+
+        let blueprint = builder.build();
+        self.blueprint_add(&opctx, &blueprint).await?;
+        Ok(blueprint)
+    }
+
     pub async fn blueprint_import(
         &self,
         opctx: &OpContext,
@@ -230,4 +462,207 @@ impl super::Nexus {
 
         Ok(status)
     }
+
+    /// Reports the current and target versions of every updateable component
+    /// on each in-service sled, based on the latest inventory collection and
+    /// the current target blueprint.
+    pub(crate) async fn component_version_matrix(
+        &self,
+        opctx: &OpContext,
+    ) -> Result<views::ComponentVersionMatrix, Error> {
+        let (_, blueprint) =
+            self.db_datastore.blueprint_target_get_current_full(opctx).await?;
+        let planning_context = self.blueprint_planning_context(opctx).await?;
+        let inventory = planning_context.inventory.ok_or_else(|| {
+            Error::unavail("no inventory collection has been completed yet")
+        })?;
+        let new = planning_context.planning_input.tuf_repo().description();
+        let old = planning_context.planning_input.old_repo().description();
+        let status = UpdateStatus::new(old, new, &inventory);
+
+        Ok(status.version_matrix(old, new, &inventory, &blueprint))
+    }
+
+    /// Reports how many more control plane zones and instances of
+    /// `instance_shape` could be placed, based on the current target
+    /// blueprint and the latest inventory collection.
+    pub async fn capacity_report(
+        &self,
+        opctx: &OpContext,
+        instance_shape: CapacityInstanceShape,
+    ) -> Result<CapacityReport, Error> {
+        if instance_shape.ncpus == 0 || instance_shape.memory.to_bytes() == 0 {
+            return Err(Error::invalid_request(
+                "instance shape must have at least one vCPU and nonzero memory",
+            ));
+        }
+
+        let (_, blueprint) =
+            self.db_datastore.blueprint_target_get_current_full(opctx).await?;
+        let planning_context = self.blueprint_planning_context(opctx).await?;
+        let inventory = planning_context.inventory.ok_or_else(|| {
+            Error::internal_error("no recent inventory collection found")
+        })?;
+        let instance_usage =
+            self.db_datastore.sled_instance_usage_by_sled(opctx).await?;
+
+        Ok(capacity_report(
+            &planning_context.planning_input,
+            &blueprint,
+            &inventory,
+            &instance_usage,
+            instance_shape,
+        ))
+    }
+
+    /// Previews what expunging a sled would do
+    ///
+    /// This plans a blueprint as though the sled had been expunged (without
+    /// saving it), and checks for data that would be left without enough
+    /// copies.
+    pub(crate) async fn sled_expunge_preview(
+        &self,
+        opctx: &OpContext,
+        sled_lookup: &lookup::Sled<'_>,
+    ) -> Result<views::SledExpungePreview, Error> {
+        let (.., sled) = sled_lookup.fetch().await?;
+        if sled.policy() == SledPolicy::Expunged {
+            return Err(Error::invalid_request(format!(
+                "sled {} is already expunged",
+                sled.id()
+            )));
+        }
+        let sled_id = SledUuid::from_untyped_uuid(sled.id());
+
+        let (_, parent_blueprint) =
+            self.db_datastore.blueprint_target_get_current_full(opctx).await?;
+        let planning_context = self.blueprint_planning_context(opctx).await?;
+        let inventory = planning_context.inventory.ok_or_else(|| {
+            Error::internal_error("no recent inventory collection found")
+        })?;
+        let mut risks = Vec::new();
+
+        // Plan a blueprint with the sled expunged, and see what changes.
+        let mut input_builder = planning_context.planning_input.into_builder();
+        input_builder.expunge_sled(&sled_id).map_err(|error| {
+            Error::internal_error(&format!(
+                "failed to expunge sled in planning input: {}",
+                InlineErrorChain::new(&error)
+            ))
+        })?;
+        let planning_input = input_builder.build();
+        let planner = Planner::new_based_on(
+            opctx.log.clone(),
+            &parent_blueprint,
+            &planning_input,
+            &planning_context.creator,
+            &inventory,
+            PlannerRng::from_entropy(),
+        )
+        .map_err(|error| {
+            Error::internal_error(&format!(
+                "error creating blueprint planner: {error:#}",
+            ))
+        })?;
+        let mut zones_expunged = Vec::new();
+        let mut zones_added = Vec::new();
+        match planner.plan() {
+            Ok(blueprint) => {
+                let parent_zones: BTreeMap<_, _> = parent_blueprint
+                    .all_omicron_zones(BlueprintZoneDisposition::any)
+                    .map(|(_, zone)| (zone.id, zone))
+                    .collect();
+                for (zone_sled_id, zone) in
+                    blueprint.all_omicron_zones(BlueprintZoneDisposition::any)
+                {
+                    let preview_zone = views::SledExpungePreviewZone {
+                        zone_id: zone.id.into_untyped_uuid(),
+                        kind: zone.zone_type.kind().report_str().to_string(),
+                        sled_id: zone_sled_id.into_untyped_uuid(),
+                    };
+                    match parent_zones.get(&zone.id) {
+                        None => zones_added.push(preview_zone),
+                        Some(parent_zone)
+                            if zone_sled_id == sled_id
+                                && !parent_zone.disposition.is_expunged()
+                                && zone.disposition.is_expunged() =>
+                        {
+                            zones_expunged.push(preview_zone);
+                        }
+                        Some(_) => (),
+                    }
+                }
+            }
+            Err(error) => {
+                risks.push(views::SledExpungeRisk::PlanningFailed {
+                    message: InlineErrorChain::new(&error).to_string(),
+                });
+            }
+        }
+
+        // Losing a CockroachDB node is only safe if the rest of the cluster
+        // is healthy.
+        if let Some(reason) = cockroachdb_unsafe_to_shut_down(&inventory) {
+            for (_, zone) in parent_blueprint
+                .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+                .filter(|(zone_sled_id, zone)| {
+                    *zone_sled_id == sled_id && zone.zone_type.is_cockroach()
+                })
+            {
+                risks.push(
+                    views::SledExpungeRisk::CockroachdbUnderreplicated {
+                        zone_id: zone.id.into_untyped_uuid(),
+                        reason: reason.to_string(),
+                    },
+                );
+            }
+        }
+
+        // Crucible keeps REGION_REDUNDANCY_THRESHOLD copies of each region.
+        // Losing the sled's copy is expected (region replacement will make a
+        // new one), but if that would leave fewer than two copies, the data
+        // is at risk until replacement finishes.
+        for volume in
+            self.db_datastore.sled_volume_redundancy(opctx, sled_id).await?
+        {
+            if volume.regions_elsewhere >= REGION_REDUNDANCY_THRESHOLD - 1 {
+                continue;
+            }
+            let disk_id = self
+                .db_datastore
+                .disk_for_volume_id(volume.volume_id)
+                .await?
+                .map(|disk| disk.id());
+            risks.push(views::SledExpungeRisk::UnreplicatedVolume {
+                volume_id: volume.volume_id.into_untyped_uuid(),
+                disk_id,
+                regions_on_sled: u32::try_from(volume.regions_on_sled)
+                    .unwrap_or(u32::MAX),
+                regions_remaining: u32::try_from(volume.regions_elsewhere)
+                    .unwrap_or(u32::MAX),
+            });
+        }
+
+        Ok(views::SledExpungePreview {
+            sled_id: sled_id.into_untyped_uuid(),
+            parent_blueprint_id: parent_blueprint.id,
+            zones_expunged,
+            zones_added,
+            risks,
+        })
+    }
+}
+
+fn blueprint_metadata_to_view(
+    metadata: BlueprintMetadata,
+    is_target: bool,
+) -> views::Blueprint {
+    views::Blueprint {
+        id: metadata.id,
+        parent_blueprint_id: metadata.parent_blueprint_id,
+        time_created: metadata.time_created,
+        creator: metadata.creator,
+        comment: metadata.comment,
+        is_target,
+    }
 }
@@ -222,6 +222,19 @@ impl super::Nexus {
             })?;
         let silo_id = db_silo_user.silo_id;
 
+        self.db_datastore
+            .break_glass_account_check(opctx, silo_user_id)
+            .await
+            .map_err(|e| match e {
+                Error::Unauthenticated { internal_message } => {
+                    Reason::BadCredentials {
+                        actor: Actor::SiloUser { silo_user_id, silo_id },
+                        source: anyhow!(internal_message),
+                    }
+                }
+                e => Reason::UnknownError { source: e },
+            })?;
+
         if let Some(time_expires) = db_access_token.time_expires {
             let now = Utc::now();
             if time_expires < now {
@@ -4,32 +4,45 @@
 
 //! Disks
 
+use crate::app::instance::SledAgentInstanceError;
 use crate::app::sagas;
 use crate::external_api::params;
+use futures::FutureExt;
 use nexus_db_lookup::LookupPath;
 use nexus_db_lookup::lookup;
 use nexus_db_queries::authn;
 use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
+use nexus_types::external_api::views;
+use nexus_types::external_api::views::SledPolicy;
+use nexus_types::identity::Resource;
 use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::CreateResult;
 use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::DiskState;
 use omicron_common::api::external::Error;
+use omicron_common::api::external::IdentityMetadataCreateParams;
 use omicron_common::api::external::InternalContext;
 use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::Name;
 use omicron_common::api::external::NameOrId;
 use omicron_common::api::external::UpdateResult;
 use omicron_common::api::external::http_pagination::PaginatedBy;
 use omicron_common::api::internal::nexus::DiskRuntimeState;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
+use omicron_uuid_kinds::PropolisUuid;
+use omicron_uuid_kinds::SledUuid;
 use sled_agent_client::Client as SledAgentClient;
+use slog_error_chain::InlineErrorChain;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use super::MAX_DISK_SIZE_BYTES;
 use super::MIN_DISK_SIZE_BYTES;
+use super::operation::OperationProgress;
 
 impl super::Nexus {
     // Disks
@@ -64,12 +77,17 @@ impl super::Nexus {
         }
     }
 
+    /// Checks that a disk can be created with the given parameters
+    ///
+    /// On success, returns warnings about the disk's source that should be
+    /// reported to the caller, such as the image being deprecated.
     pub(super) async fn validate_disk_create_params(
         self: &Arc<Self>,
         opctx: &OpContext,
         authz_project: &authz::Project,
         params: &params::DiskCreate,
-    ) -> Result<(), Error> {
+    ) -> Result<Vec<String>, Error> {
+        let mut warnings = Vec::new();
         let block_size: u64 = match params.disk_source {
             params::DiskSource::Blank { block_size }
             | params::DiskSource::ImportingBlocks { block_size } => {
@@ -128,6 +146,23 @@ impl super::Nexus {
                     )));
                 }
 
+                match db_image.state {
+                    db::model::ImageState::Active => (),
+                    db::model::ImageState::Deprecated => {
+                        warnings.push(format!(
+                            "image \"{}\" is deprecated",
+                            db_image.name()
+                        ));
+                    }
+                    db::model::ImageState::Blocked => {
+                        return Err(Error::invalid_request(format!(
+                            "image \"{}\" is blocked and cannot be used to \
+                             create new disks",
+                            db_image.name()
+                        )));
+                    }
+                }
+
                 db_image.block_size.to_bytes().into()
             }
         };
@@ -179,18 +214,21 @@ impl super::Nexus {
             ));
         }
 
-        Ok(())
+        Ok(warnings)
     }
 
+    /// Creates a disk, returning it along with any warnings about its source
     pub(crate) async fn project_create_disk(
         self: &Arc<Self>,
         opctx: &OpContext,
         project_lookup: &lookup::Project<'_>,
         params: &params::DiskCreate,
-    ) -> CreateResult<db::model::Disk> {
+    ) -> CreateResult<(db::model::Disk, Vec<String>)> {
         let (.., authz_project) =
             project_lookup.lookup_for(authz::Action::CreateChild).await?;
-        self.validate_disk_create_params(opctx, &authz_project, params).await?;
+        let warnings = self
+            .validate_disk_create_params(opctx, &authz_project, params)
+            .await?;
 
         let saga_params = sagas::disk_create::Params {
             serialized_authn: authn::saga::Serialized::for_opctx(opctx),
@@ -205,7 +243,7 @@ impl super::Nexus {
             .lookup_node_output::<db::model::Disk>("created_disk")
             .map_err(|e| Error::internal_error(&format!("{:#}", &e)))
             .internal_context("looking up output from disk create saga")?;
-        Ok(disk_created)
+        Ok((disk_created, warnings))
     }
 
     pub(crate) async fn disk_list(
@@ -319,6 +357,292 @@ impl super::Nexus {
         }
     }
 
+    /// Create a new disk with the current contents of an existing disk
+    ///
+    /// This takes a snapshot of the source disk, creates the new disk from
+    /// that snapshot, and then deletes the snapshot. The new disk's contents
+    /// are copied from the snapshot in the background (see
+    /// `disk_copy_progress`), and the snapshot's data is kept until that copy
+    /// completes even though the snapshot itself is gone.
+    ///
+    /// When run as part of an operation, one step is reported as completed
+    /// once the snapshot has been taken.
+    pub(crate) async fn disk_clone(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        disk_lookup: &lookup::Disk<'_>,
+        params: &params::DiskClone,
+        progress: Option<&OperationProgress>,
+    ) -> CreateResult<db::model::Disk> {
+        let (.., authz_project, authz_disk, db_disk) =
+            disk_lookup.fetch_for(authz::Action::Read).await?;
+
+        let snapshot_name: Name =
+            format!("clone-{}", Uuid::new_v4()).parse().map_err(|e| {
+                Error::internal_error(&format!(
+                    "invalid temporary snapshot name: {e}"
+                ))
+            })?;
+        let snapshot = self
+            .snapshot_create(
+                opctx,
+                LookupPath::new(opctx, &self.db_datastore)
+                    .project_id(authz_project.id()),
+                &params::SnapshotCreate {
+                    identity: IdentityMetadataCreateParams {
+                        name: snapshot_name,
+                        description: format!(
+                            "temporary snapshot for cloning disk {}",
+                            authz_disk.id()
+                        ),
+                    },
+                    disk: NameOrId::Id(authz_disk.id()),
+                },
+            )
+            .await?;
+        if let Some(progress) = progress {
+            progress.step_completed().await;
+        }
+
+        let project_lookup = LookupPath::new(opctx, &self.db_datastore)
+            .project_id(authz_project.id());
+        let result = self
+            .project_create_disk(
+                opctx,
+                &project_lookup,
+                &params::DiskCreate {
+                    identity: params.identity.clone(),
+                    disk_source: params::DiskSource::Snapshot {
+                        snapshot_id: snapshot.id(),
+                    },
+                    size: db_disk.size.into(),
+                },
+            )
+            .await
+            .map(|(disk, _)| disk);
+
+        // Whether or not the new disk was created, the snapshot is no longer
+        // needed. Failing to delete it doesn't affect the new disk, and it
+        // remains visible so that it can be deleted later.
+        let snapshot_lookup = LookupPath::new(opctx, &self.db_datastore)
+            .snapshot_id(snapshot.id());
+        if let Err(error) = self.snapshot_delete(opctx, &snapshot_lookup).await
+        {
+            warn!(
+                opctx.log,
+                "failed to delete temporary snapshot for disk clone";
+                "source_disk_id" => %authz_disk.id(),
+                "snapshot_id" => %snapshot.id(),
+                InlineErrorChain::new(&error),
+            );
+        }
+
+        result
+    }
+
+    /// Start cloning a disk as an operation, returning the operation without
+    /// waiting for the clone to be created
+    pub(crate) async fn disk_clone_operation(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        disk_lookup: &lookup::Disk<'_>,
+        params: params::DiskClone,
+    ) -> CreateResult<views::Operation> {
+        let (.., authz_project, authz_disk) =
+            disk_lookup.lookup_for(authz::Action::Read).await?;
+        let disk_id = authz_disk.id();
+
+        self.operation_start(
+            opctx,
+            &authz_project,
+            db::model::OperationKind::DiskClone,
+            2,
+            move |nexus, progress| {
+                async move {
+                    let disk_lookup =
+                        LookupPath::new(progress.opctx(), &nexus.db_datastore)
+                            .disk_id(disk_id);
+                    let disk = nexus
+                        .disk_clone(
+                            progress.opctx(),
+                            &disk_lookup,
+                            &params,
+                            Some(&progress),
+                        )
+                        .await?;
+                    Ok(disk.id())
+                }
+                .boxed()
+            },
+        )
+        .await
+    }
+
+    /// Report whether a disk's contents have been fully copied from the
+    /// snapshot or image it was created from
+    pub(crate) async fn disk_copy_progress(
+        &self,
+        disk_lookup: &lookup::Disk<'_>,
+    ) -> LookupResult<views::DiskCopyProgress> {
+        let (.., db_disk) = disk_lookup.fetch().await?;
+        let has_read_only_parent = self
+            .db_datastore
+            .volume_has_read_only_parent(db_disk.volume_id())
+            .await?;
+        Ok(views::DiskCopyProgress {
+            snapshot_id: db_disk.create_snapshot_id,
+            image_id: db_disk.create_image_id,
+            complete: !has_read_only_parent,
+        })
+    }
+
+    pub(crate) async fn disk_update(
+        &self,
+        opctx: &OpContext,
+        disk_lookup: &lookup::Disk<'_>,
+        params: &params::DiskUpdate,
+    ) -> UpdateResult<db::model::Disk> {
+        let (.., authz_disk) =
+            disk_lookup.lookup_for(authz::Action::Modify).await?;
+        self.db_datastore
+            .disk_set_delete_protected(
+                opctx,
+                &authz_disk,
+                params.delete_protected,
+            )
+            .await
+    }
+
+    /// Forcibly detaches a disk from an instance whose record has been deleted
+    /// or corrupted.
+    ///
+    /// Such disks can't be detached with the usual instance disk detach, which
+    /// has to work through the instance. This is an operator override: it
+    /// requires fleet administrator privileges, and is refused if the disk's
+    /// instance still exists (in which case the disk should be detached from
+    /// it normally) or if any of the instance's VMMs may still have the disk
+    /// open, as far as their sled agents can tell.
+    pub(crate) async fn disk_force_detach(
+        &self,
+        opctx: &OpContext,
+        disk_lookup: &lookup::Disk<'_>,
+        params: &params::DiskForceDetach,
+    ) -> UpdateResult<db::model::Disk> {
+        let (.., authz_disk, db_disk) =
+            disk_lookup.fetch_for(authz::Action::Modify).await?;
+        // Only operators may override the disk's attachment. Check this after
+        // looking up the disk so that users who can't see the disk get a 404
+        // rather than learning that it exists.
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+
+        let Some(instance_id) = db_disk.runtime().attach_instance_id else {
+            return Err(Error::conflict("disk is not attached to an instance"));
+        };
+
+        match LookupPath::new(opctx, &self.db_datastore)
+            .instance_id(instance_id)
+            .fetch()
+            .await
+        {
+            Ok(_) => {
+                return Err(Error::conflict(format!(
+                    "disk's instance {instance_id} still exists; detach the \
+                     disk from the instance instead",
+                )));
+            }
+            Err(Error::ObjectNotFound { .. }) => {}
+            // An instance record we can't read is exactly the sort of thing
+            // this operation is for.
+            Err(e) => {
+                info!(opctx.log, "could not fetch disk's instance";
+                    "disk_id" => %authz_disk.id(),
+                    "instance_id" => %instance_id,
+                    InlineErrorChain::new(&e),
+                );
+            }
+        }
+
+        // The instance may be gone, but its VMMs may not be. Make sure that
+        // none of them still has the disk open before pulling it out from
+        // under them. Unlike forcing an instance to fail, we don't leave an
+        // unresponsive sled agent to the operator's judgment: two VMMs writing
+        // to the same disk would corrupt it.
+        let vmms = self
+            .db_datastore
+            .vmm_list_by_instance(
+                opctx,
+                InstanceUuid::from_untyped_uuid(instance_id),
+            )
+            .await?;
+        for vmm in &vmms {
+            let vmm_id = PropolisUuid::from_untyped_uuid(vmm.id);
+            match self.vmm_is_gone_from_sled(opctx, vmm, vmm_id).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Err(Error::conflict(format!(
+                        "VMM {vmm_id} of disk's instance is still present on \
+                         sled {}",
+                        vmm.sled_id,
+                    )));
+                }
+                Err(e) => {
+                    return Err(Error::unavail(&format!(
+                        "could not confirm that VMM {vmm_id} of disk's \
+                         instance is gone from sled {}: {}",
+                        vmm.sled_id,
+                        InlineErrorChain::new(&e),
+                    )));
+                }
+            }
+        }
+
+        warn!(opctx.log, "operator is forcibly detaching disk";
+            "disk_id" => %authz_disk.id(),
+            "instance_id" => %instance_id,
+            "disk_state" => %db_disk.runtime().disk_state,
+            "vmms_checked" => vmms.len(),
+            "actor" => ?opctx.authn.actor(),
+            "reason" => &params.reason,
+        );
+        self.db_datastore
+            .disk_force_detach(opctx, &authz_disk, instance_id)
+            .await
+    }
+
+    /// Returns `true` if the sled that hosted `vmm` no longer has any record
+    /// of it, or if there's no sled left to host it at all.
+    async fn vmm_is_gone_from_sled(
+        &self,
+        opctx: &OpContext,
+        vmm: &db::model::Vmm,
+        vmm_id: PropolisUuid,
+    ) -> Result<bool, Error> {
+        let sled = match LookupPath::new(opctx, &self.db_datastore)
+            .sled_id(vmm.sled_id)
+            .fetch()
+            .await
+        {
+            Ok((_, sled)) => sled,
+            Err(Error::ObjectNotFound { .. }) => return Ok(true),
+            Err(e) => return Err(e),
+        };
+        if sled.policy() == SledPolicy::Expunged {
+            return Ok(true);
+        }
+
+        let client =
+            self.sled_client(&SledUuid::from_untyped_uuid(vmm.sled_id)).await?;
+        match client
+            .vmm_get_state(&vmm_id)
+            .await
+            .map_err(SledAgentInstanceError)
+        {
+            Ok(_) => Ok(false),
+            Err(e) if e.vmm_gone() => Ok(true),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     pub(crate) async fn project_delete_disk(
         self: &Arc<Self>,
         opctx: &OpContext,
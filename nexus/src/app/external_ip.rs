@@ -6,7 +6,6 @@
 
 use std::sync::Arc;
 
-use crate::external_api::views::ExternalIp;
 use crate::external_api::views::FloatingIp;
 use nexus_db_lookup::LookupPath;
 use nexus_db_lookup::lookup;
@@ -27,29 +26,25 @@ use omicron_uuid_kinds::GenericUuid;
 use omicron_uuid_kinds::InstanceUuid;
 
 impl super::Nexus {
+    /// List the external IP addresses attached to an instance, in the order in
+    /// which they were attached
     pub(crate) async fn instance_list_external_ips(
         &self,
         opctx: &OpContext,
         instance_lookup: &lookup::Instance<'_>,
-    ) -> ListResultVec<ExternalIp> {
+    ) -> ListResultVec<views::InstanceExternalIp> {
         let (.., authz_instance) =
             instance_lookup.lookup_for(authz::Action::Read).await?;
-        Ok(self
-            .db_datastore
+        self.db_datastore
             .instance_lookup_external_ips(
                 opctx,
                 InstanceUuid::from_untyped_uuid(authz_instance.id()),
             )
             .await?
             .into_iter()
-            .filter_map(|ip| {
-                if ip.state != IpAttachState::Attached {
-                    None
-                } else {
-                    Some(ip.try_into().unwrap())
-                }
-            })
-            .collect::<Vec<_>>())
+            .filter(|ip| ip.state == IpAttachState::Attached)
+            .map(views::InstanceExternalIp::try_from)
+            .collect()
     }
 
     pub(crate) fn floating_ip_lookup<'a>(
@@ -13,6 +13,7 @@ use nexus_db_queries::authn;
 use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
+use nexus_db_queries::db::datastore::ImageFilters;
 use omicron_common::api::external::CreateResult;
 use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::Error;
@@ -21,7 +22,7 @@ use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::NameOrId;
 use omicron_common::api::external::UpdateResult;
-use omicron_common::api::external::http_pagination::PaginatedBy;
+use omicron_common::api::external::http_pagination::ImagePaginatedBy;
 use std::sync::Arc;
 
 use super::sagas;
@@ -128,21 +129,27 @@ impl super::Nexus {
         &self,
         opctx: &OpContext,
         parent_lookup: &ImageParentLookup<'_>,
-        pagparams: &PaginatedBy<'_>,
+        filters: &ImageFilters,
+        pagparams: &ImagePaginatedBy<'_>,
     ) -> ListResultVec<db::model::Image> {
         match parent_lookup {
             ImageParentLookup::Project(project) => {
                 let (.., authz_project) =
                     project.lookup_for(authz::Action::ListChildren).await?;
                 self.db_datastore
-                    .project_image_list(opctx, &authz_project, pagparams)
+                    .project_image_list(
+                        opctx,
+                        &authz_project,
+                        filters,
+                        pagparams,
+                    )
                     .await
             }
             ImageParentLookup::Silo(silo) => {
                 let (.., authz_silo) =
                     silo.lookup_for(authz::Action::ListChildren).await?;
                 self.db_datastore
-                    .silo_image_list(opctx, &authz_silo, pagparams)
+                    .silo_image_list(opctx, &authz_silo, filters, pagparams)
                     .await
             }
         }
@@ -178,6 +185,33 @@ impl super::Nexus {
         Ok(())
     }
 
+    /// Changes an image's lifecycle state, which controls whether new disks
+    /// can be created from it
+    pub(crate) async fn image_set_state(
+        &self,
+        opctx: &OpContext,
+        image_lookup: &ImageLookup<'_>,
+        params: &params::ImageStateUpdate,
+    ) -> UpdateResult<db::model::Image> {
+        let state = params.state.into();
+        match image_lookup {
+            ImageLookup::ProjectImage(lookup) => {
+                let (.., authz_image) =
+                    lookup.lookup_for(authz::Action::Modify).await?;
+                self.db_datastore
+                    .project_image_set_state(opctx, &authz_image, state)
+                    .await
+            }
+            ImageLookup::SiloImage(lookup) => {
+                let (.., authz_image) =
+                    lookup.lookup_for(authz::Action::Modify).await?;
+                self.db_datastore
+                    .silo_image_set_state(opctx, &authz_image, state)
+                    .await
+            }
+        }
+    }
+
     /// Converts a project scoped image into a silo scoped image
     pub(crate) async fn image_promote(
         self: &Arc<Self>,
@@ -217,8 +251,9 @@ impl super::Nexus {
             ImageLookup::SiloImage(lookup) => {
                 let (_, authz_silo_image, silo_image) =
                     lookup.fetch_for(authz::Action::Modify).await?;
-                let (_, authz_project) =
-                    project_lookup.lookup_for(authz::Action::Modify).await?;
+                let (_, authz_project) = project_lookup
+                    .lookup_for(authz::Action::CreateChild)
+                    .await?;
                 self.db_datastore
                     .silo_image_demote(
                         opctx,
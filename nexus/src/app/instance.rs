@@ -4,12 +4,17 @@
 
 //! Virtual Machine Instances
 
+use super::MAX_CONCURRENT_BULK_INSTANCE_ACTIONS;
 use super::MAX_DISKS_PER_INSTANCE;
 use super::MAX_EPHEMERAL_IPS_PER_INSTANCE;
 use super::MAX_EXTERNAL_IPS_PER_INSTANCE;
+use super::MAX_INSTANCE_TAG_KEY_BYTES;
+use super::MAX_INSTANCE_TAG_VALUE_BYTES;
+use super::MAX_INSTANCES_PER_BULK_ACTION;
 use super::MAX_MEMORY_BYTES_PER_INSTANCE;
 use super::MAX_NICS_PER_INSTANCE;
 use super::MAX_SSH_KEYS_PER_INSTANCE;
+use super::MAX_TAGS_PER_INSTANCE;
 use super::MAX_VCPU_PER_INSTANCE;
 use super::MIN_MEMORY_BYTES_PER_INSTANCE;
 use crate::app::sagas;
@@ -21,6 +26,7 @@ use futures::{FutureExt, SinkExt, StreamExt};
 use nexus_db_lookup::LookupPath;
 use nexus_db_lookup::lookup;
 use nexus_db_model::InstanceIntendedState as IntendedState;
+use nexus_db_model::InstanceStoppedReservationPolicy;
 use nexus_db_model::InstanceUpdate;
 use nexus_db_model::IpAttachState;
 use nexus_db_model::IpKind;
@@ -36,6 +42,8 @@ use nexus_db_queries::db::datastore::InstanceAndActiveVmm;
 use nexus_db_queries::db::datastore::InstanceStateComputer;
 use nexus_db_queries::db::identity::Resource;
 use nexus_types::external_api::views;
+use nexus_types::inventory::Collection;
+use omicron_common::api::external;
 use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::CreateResult;
 use omicron_common::api::external::DataPageParams;
@@ -67,6 +75,8 @@ use sagas::instance_start;
 use sagas::instance_update;
 use sled_agent_client::types::InstanceMigrationTargetParams;
 use sled_agent_client::types::VmmPutStateBody;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::matches;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -313,6 +323,22 @@ async fn normalize_anti_affinity_groups(
         .collect::<Vec<NameOrId>>())
 }
 
+/// How long to wait for a sled agent to report on a VMM that an operator is
+/// forcing to fail.
+const FORCE_FAIL_PROBE_TIMEOUT: std::time::Duration =
+    std::time::Duration::from_secs(10);
+
+/// How many times to try registering a VMM with a sled agent when the request
+/// fails without a response.
+const VMM_REGISTER_MAX_ATTEMPTS: u32 = 3;
+
+/// The largest difference between the clocks of a migrating instance's source
+/// and destination sleds (as last reported to inventory) that we'll tolerate.
+///
+/// The guest's clock is carried across the migration, so it jumps by however
+/// far apart the two sleds' clocks are.
+const MAX_MIGRATION_CLOCK_SKEW_USEC: u64 = 100_000;
+
 impl super::Nexus {
     pub fn instance_lookup<'a>(
         &'a self,
@@ -354,39 +380,128 @@ impl super::Nexus {
         instance_lookup: &lookup::Instance<'_>,
         params: &params::InstanceUpdate,
     ) -> UpdateResult<InstanceAndActiveVmm> {
-        let (.., authz_project, authz_instance) =
-            instance_lookup.lookup_for(authz::Action::Modify).await?;
+        let (.., authz_project, authz_instance, db_instance) =
+            instance_lookup.fetch_for(authz::Action::Modify).await?;
 
         check_instance_cpu_memory_sizes(params.ncpus, params.memory)?;
+        if let Some(tags) = &params.tags {
+            check_instance_tags(tags)?;
+        }
 
-        let boot_disk_id = match params.boot_disk.clone() {
-            Some(disk) => {
-                let selector = params::DiskSelector {
-                    project: match &disk {
-                        NameOrId::Name(_) => Some(authz_project.id().into()),
-                        NameOrId::Id(_) => None,
-                    },
-                    disk,
-                };
-                let (.., authz_disk) = self
-                    .disk_lookup(opctx, selector)?
-                    .lookup_for(authz::Action::Modify)
-                    .await?;
+        let mut boot_order = Vec::with_capacity(params.boot_order.len());
+        for disk in params.boot_order.iter().cloned() {
+            let selector = params::DiskSelector {
+                project: match &disk {
+                    NameOrId::Name(_) => Some(authz_project.id().into()),
+                    NameOrId::Id(_) => None,
+                },
+                disk,
+            };
+            let (.., authz_disk) = self
+                .disk_lookup(opctx, selector)?
+                .lookup_for(authz::Action::Modify)
+                .await?;
 
-                Some(authz_disk.id())
+            if boot_order.contains(&authz_disk.id()) {
+                return Err(Error::invalid_request(&format!(
+                    "disk {} appears more than once in the boot order",
+                    authz_disk.id(),
+                )));
             }
-            None => None,
-        };
+            boot_order.push(authz_disk.id());
+        }
 
         let auto_restart_policy = params.auto_restart_policy.map(Into::into);
         let ncpus = params.ncpus.into();
         let memory = params.memory.into();
 
-        let update =
-            InstanceUpdate { boot_disk_id, auto_restart_policy, ncpus, memory };
-        self.datastore()
+        let stopped_reservation_policy = params
+            .stopped_reservation_policy
+            .map(Into::into)
+            .unwrap_or(db_instance.stopped_reservation_policy);
+        // A reservation kept while the instance is stopped is no longer wanted
+        // if the instance shouldn't keep one, and no longer fits it if it has
+        // been resized.
+        let release_held_reservation = stopped_reservation_policy
+            == InstanceStoppedReservationPolicy::Release
+            || db_instance.ncpus != ncpus
+            || db_instance.memory != memory;
+
+        let update = InstanceUpdate {
+            boot_order,
+            auto_restart_policy,
+            ncpus,
+            memory,
+            stopped_reservation_policy,
+        };
+        let instance = self
+            .datastore()
             .instance_reconfigure(opctx, &authz_instance, update)
-            .await
+            .await?;
+
+        if release_held_reservation {
+            self.datastore()
+                .sled_reservation_release_held(
+                    opctx,
+                    InstanceUuid::from_untyped_uuid(authz_instance.id()),
+                )
+                .await?;
+        }
+
+        if let Some(tags) = &params.tags {
+            self.datastore()
+                .instance_tags_replace(opctx, &authz_instance, tags)
+                .await?;
+        }
+
+        Ok(instance)
+    }
+
+    /// Converts an instance and its active VMM into its external API view,
+    /// including the instance's tags.
+    pub(crate) async fn instance_view(
+        &self,
+        opctx: &OpContext,
+        instance: InstanceAndActiveVmm,
+    ) -> LookupResult<external::Instance> {
+        let mut views = self.instance_views(opctx, vec![instance]).await?;
+        Ok(views.pop().expect("one view for one instance"))
+    }
+
+    /// Converts a batch of instances into their external API views, loading
+    /// all of their tags and auto-restart counts with a query apiece.
+    pub(crate) async fn instance_views(
+        &self,
+        opctx: &OpContext,
+        instances: Vec<InstanceAndActiveVmm>,
+    ) -> ListResultVec<external::Instance> {
+        let ids: Vec<_> = instances
+            .iter()
+            .map(|i| InstanceUuid::from_untyped_uuid(i.instance().id()))
+            .collect();
+        let mut tags: BTreeMap<Uuid, BTreeMap<String, String>> =
+            BTreeMap::new();
+        for tag in
+            self.db_datastore.instance_tags_list_batch(opctx, &ids).await?
+        {
+            tags.entry(tag.instance_id).or_default().insert(tag.key, tag.value);
+        }
+        let auto_restart_counts = self
+            .db_datastore
+            .instance_auto_restart_counts_batch(opctx, &ids)
+            .await?;
+
+        Ok(instances
+            .into_iter()
+            .map(|instance| {
+                let id = instance.instance().id();
+                let mut view = external::Instance::from(instance);
+                view.tags = tags.remove(&id).unwrap_or_default();
+                view.auto_restart_status.count =
+                    auto_restart_counts.get(&id).copied().unwrap_or(0);
+                view
+            })
+            .collect())
     }
 
     pub(crate) async fn project_create_instance(
@@ -400,30 +515,35 @@ impl super::Nexus {
 
         check_instance_cpu_memory_sizes(params.ncpus, params.memory)?;
 
-        let all_disks: Vec<&params::InstanceDiskAttachment> =
-            params.boot_disk.iter().chain(params.disks.iter()).collect();
-
         // Validate parameters
-        if all_disks.len() > MAX_DISKS_PER_INSTANCE as usize {
+        if params.disks.len() > MAX_DISKS_PER_INSTANCE as usize {
             return Err(Error::invalid_request(&format!(
                 "cannot attach more than {} disks to instance",
                 MAX_DISKS_PER_INSTANCE
             )));
         }
-        for disk in all_disks.iter() {
+        for disk in params.disks.iter() {
             if let params::InstanceDiskAttachment::Create(create) = disk {
                 self.validate_disk_create_params(opctx, &authz_project, create)
                     .await?;
             }
         }
-        if params.external_ips.len() > MAX_EXTERNAL_IPS_PER_INSTANCE {
+        check_instance_boot_order(&params.boot_order, &params.disks)?;
+        check_instance_tags(&params.tags)?;
+        let external_ips = self
+            .apply_ephemeral_ip_policy(
+                opctx,
+                &authz_project,
+                &params.external_ips,
+            )
+            .await?;
+        if external_ips.len() > MAX_EXTERNAL_IPS_PER_INSTANCE {
             return Err(Error::invalid_request(&format!(
                 "An instance may not have more than {} external IP addresses",
                 MAX_EXTERNAL_IPS_PER_INSTANCE,
             )));
         }
-        if params
-            .external_ips
+        if external_ips
             .iter()
             .filter(|v| matches!(v, params::ExternalIpCreate::Ephemeral { .. }))
             .count()
@@ -499,6 +619,7 @@ impl super::Nexus {
             create_params: params::InstanceCreate {
                 ssh_public_keys: ssh_keys,
                 anti_affinity_groups,
+                external_ips,
                 ..params.clone()
             },
             boundary_switches: self
@@ -560,15 +681,51 @@ impl super::Nexus {
         self.db_datastore.instance_fetch_with_vmm(opctx, &authz_instance).await
     }
 
+    /// Applies the project's ephemeral IP policy to the external IPs requested
+    /// for a new instance, returning the external IPs it should actually get.
+    async fn apply_ephemeral_ip_policy(
+        &self,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        external_ips: &[params::ExternalIpCreate],
+    ) -> Result<Vec<params::ExternalIpCreate>, Error> {
+        let policy = self
+            .db_datastore
+            .project_ephemeral_ip_policy_view(opctx, authz_project)
+            .await?;
+
+        let mut external_ips = external_ips.to_vec();
+        let requested_ephemeral_ip = external_ips
+            .iter()
+            .any(|v| matches!(v, params::ExternalIpCreate::Ephemeral { .. }));
+        if requested_ephemeral_ip {
+            if !policy.allow_override {
+                return Err(Error::invalid_request(
+                    "This project's ephemeral IP policy does not allow \
+                    requesting an ephemeral IP address",
+                ));
+            }
+        } else if policy.auto_assign {
+            external_ips.push(params::ExternalIpCreate::Ephemeral {
+                pool: policy.ip_pool_id.map(NameOrId::Id),
+            });
+        }
+        Ok(external_ips)
+    }
+
     pub(crate) async fn instance_list(
         &self,
         opctx: &OpContext,
         project_lookup: &lookup::Project<'_>,
+        tag: Option<&str>,
         pagparams: &PaginatedBy<'_>,
     ) -> ListResultVec<InstanceAndActiveVmm> {
+        let tag = tag.map(parse_instance_tag_filter).transpose()?;
         let (.., authz_project) =
             project_lookup.lookup_for(authz::Action::ListChildren).await?;
-        self.db_datastore.instance_list(opctx, &authz_project, pagparams).await
+        self.db_datastore
+            .instance_list(opctx, &authz_project, tag, pagparams)
+            .await
     }
 
     // This operation may only occur on stopped instances, which implies that
@@ -653,6 +810,16 @@ impl super::Nexus {
             return Err(Error::conflict("instance is already migrating"));
         }
 
+        if let Some(collection) =
+            self.db_datastore.inventory_get_latest_collection(opctx).await?
+        {
+            check_migration_timesync(
+                &collection,
+                SledUuid::from_untyped_uuid(vmm.sled_id),
+                SledUuid::from_untyped_uuid(params.dst_sled_id),
+            )?;
+        }
+
         // Kick off the migration saga
         let saga_params = sagas::instance_migrate::Params {
             serialized_authn: authn::saga::Serialized::for_opctx(opctx),
@@ -820,6 +987,89 @@ impl super::Nexus {
             .map_err(Into::into)
     }
 
+    /// Starts, stops, or reboots each of the given instances
+    ///
+    /// The action is taken on up to [`MAX_CONCURRENT_BULK_INSTANCE_ACTIONS`]
+    /// instances at a time, and its failing for one instance doesn't affect
+    /// the others. Results are returned in the order the instances were given.
+    pub(crate) async fn instance_bulk_action(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        params: params::InstanceBulkAction,
+    ) -> Result<Vec<views::InstanceBulkActionResult>, Error> {
+        let params::InstanceBulkAction { action, instance_ids } = params;
+        if instance_ids.is_empty() {
+            return Err(Error::invalid_request("no instances were given"));
+        }
+        if instance_ids.len() > MAX_INSTANCES_PER_BULK_ACTION {
+            return Err(Error::invalid_request(format!(
+                "cannot act on more than {MAX_INSTANCES_PER_BULK_ACTION} \
+                 instances at once"
+            )));
+        }
+        let mut seen = BTreeSet::new();
+        if let Some(id) = instance_ids.iter().find(|id| !seen.insert(*id)) {
+            return Err(Error::invalid_request(format!(
+                "instance {id} was given more than once"
+            )));
+        }
+
+        let results = futures::stream::iter(instance_ids)
+            .map(|instance_id| async move {
+                let outcome = match self
+                    .instance_bulk_action_one(opctx, action, instance_id)
+                    .await
+                {
+                    Ok(instance) => {
+                        views::InstanceBulkActionOutcome::Success { instance }
+                    }
+                    Err(error) => {
+                        let error = dropshot::HttpError::from(error);
+                        views::InstanceBulkActionOutcome::Error {
+                            http_status_code: error
+                                .status_code
+                                .as_status()
+                                .as_u16(),
+                            error_code: error.error_code,
+                            error_message: error.external_message,
+                        }
+                    }
+                };
+                views::InstanceBulkActionResult { instance_id, outcome }
+            })
+            .buffered(MAX_CONCURRENT_BULK_INSTANCE_ACTIONS)
+            .collect()
+            .await;
+        Ok(results)
+    }
+
+    async fn instance_bulk_action_one(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        action: params::InstanceBulkActionKind,
+        instance_id: Uuid,
+    ) -> Result<external::Instance, InstanceStateChangeError> {
+        let instance_lookup =
+            LookupPath::new(opctx, &self.db_datastore).instance_id(instance_id);
+        let state = match action {
+            params::InstanceBulkActionKind::Start => {
+                self.instance_start(
+                    opctx,
+                    &instance_lookup,
+                    instance_start::Reason::User,
+                )
+                .await?
+            }
+            params::InstanceBulkActionKind::Stop => {
+                self.instance_stop(opctx, &instance_lookup).await?
+            }
+            params::InstanceBulkActionKind::Reboot => {
+                self.instance_reboot(opctx, &instance_lookup).await?
+            }
+        };
+        Ok(self.instance_view(opctx, state).await?)
+    }
+
     /// Idempotently ensures that the sled specified in `db_instance` does not
     /// have a record of the instance. If the instance is currently running on
     /// this sled, this operation rudely terminates it.
@@ -1305,26 +1555,50 @@ impl super::Nexus {
                 }
             },
         };
-        let instance_register_result = sa
-            .vmm_register(
-                propolis_id,
-                &sled_agent_client::types::InstanceEnsureBody {
-                    vmm_spec,
-                    local_config,
-                    migration_id: db_instance.runtime().migration_id,
-                    vmm_runtime,
-                    instance_id,
-                    propolis_addr: SocketAddr::new(
-                        initial_vmm.propolis_ip.ip(),
-                        initial_vmm.propolis_port.into(),
-                    )
-                    .to_string(),
-                    metadata,
-                },
+        let body = sled_agent_client::types::InstanceEnsureBody {
+            vmm_spec,
+            local_config,
+            migration_id: db_instance.runtime().migration_id,
+            vmm_runtime,
+            instance_id,
+            propolis_addr: SocketAddr::new(
+                initial_vmm.propolis_ip.ip(),
+                initial_vmm.propolis_port.into(),
             )
-            .await
-            .map(|res| res.into_inner().into())
-            .map_err(|e| SledAgentInstanceError(e));
+            .to_string(),
+            metadata,
+        };
+
+        // If registration times out, the sled agent may or may not have
+        // registered the VMM. Retry a few times with the same idempotency key
+        // so that, if it did, it replays its original response rather than
+        // registering the VMM again.
+        let idempotency_key = Uuid::new_v4().to_string();
+        let mut attempt = 1;
+        let instance_register_result = loop {
+            match sa
+                .vmm_register(propolis_id, Some(&idempotency_key), &body)
+                .await
+            {
+                Err(SledAgentClientError::CommunicationError(e))
+                    if attempt < VMM_REGISTER_MAX_ATTEMPTS =>
+                {
+                    warn!(
+                        opctx.log,
+                        "failed to register VMM with sled agent, retrying";
+                        "propolis_id" => %propolis_id,
+                        "attempt" => attempt,
+                        "error" => %e,
+                    );
+                    attempt += 1;
+                }
+                result => {
+                    break result
+                        .map(|res| res.into_inner().into())
+                        .map_err(|e| SledAgentInstanceError(e));
+                }
+            }
+        };
 
         match instance_register_result {
             Ok(state) => {
@@ -1380,51 +1654,238 @@ impl super::Nexus {
                "vmm_id" => %vmm_id,
                "error" => ?reason);
 
+        // XXX: It's not clear what to do with this error; should it be
+        // bubbled back up to the caller?
+        if let Err(e) = self.write_vmm_failed(opctx, authz_instance, vmm).await
+        {
+            error!(self.log,
+                "failed to write Failed instance state to DB";
+                "instance_id" => %instance_id,
+                "vmm_id" => %vmm_id,
+                "error" => ?e);
+        }
+
+        Ok(())
+    }
+
+    /// Writes a `Failed` state for `vmm` and, if that succeeds, runs an
+    /// `instance-update` saga to move its instance to `Failed`.
+    ///
+    /// Returns `false` if the VMM's state was not written because its record
+    /// has changed since `vmm` was read.
+    async fn write_vmm_failed(
+        &self,
+        opctx: &OpContext,
+        authz_instance: authz::Instance,
+        vmm: &db::model::Vmm,
+    ) -> Result<bool, Error> {
+        let instance_id = InstanceUuid::from_untyped_uuid(authz_instance.id());
+        let vmm_id = PropolisUuid::from_untyped_uuid(vmm.id);
         let new_runtime = VmmRuntimeState {
             state: db::model::VmmState::Failed,
             time_state_updated: chrono::Utc::now(),
             r#gen: db::model::Generation(vmm.runtime.r#gen.next()),
         };
 
-        match self.db_datastore.vmm_update_runtime(&vmm_id, &new_runtime).await
-        {
-            Ok(_) => {
-                info!(self.log, "marked VMM as Failed, preparing update saga";
-                    "instance_id" => %instance_id,
-                    "vmm_id" => %vmm_id,
-                    "reason" => ?reason,
-                );
-                let saga = instance_update::SagaInstanceUpdate::prepare(
-                    &instance_update::Params {
-                        serialized_authn: authn::saga::Serialized::for_opctx(
-                            opctx,
-                        ),
-                        authz_instance,
-                    },
-                )?;
-                // Unlike `notify_vmm_updated`, which spawns the update
-                // saga in a "fire and forget" fashion so that it can return a
-                // HTTP 200 OK as soon as the changed VMM records are persisted
-                // to the database, `mark_vmm_failed` performs the instance
-                // update "synchronously". This is so that we can make a
-                // best-effort attempt to ensure that the instance record will
-                // be in the failed state prior to returning an error to the
-                // caller. That way, the caller will not see an error when
-                // attempting to transition their instance's state, and then,
-                // upon fetching the instance, transiently see an instance state
-                // suggesting it's "doing fine" until the update saga completes.
-                self.update_instance(&opctx.log, saga, instance_id).await;
+        let updated =
+            self.db_datastore.vmm_update_runtime(&vmm_id, &new_runtime).await?;
+        info!(self.log, "marked VMM as Failed, preparing update saga";
+            "instance_id" => %instance_id,
+            "vmm_id" => %vmm_id,
+            "updated" => updated,
+        );
+        let saga = instance_update::SagaInstanceUpdate::prepare(
+            &instance_update::Params {
+                serialized_authn: authn::saga::Serialized::for_opctx(opctx),
+                authz_instance,
+            },
+        )?;
+        // Unlike `notify_vmm_updated`, which spawns the update saga in a "fire
+        // and forget" fashion so that it can return a HTTP 200 OK as soon as
+        // the changed VMM records are persisted to the database, this performs
+        // the instance update "synchronously". This is so that we can make a
+        // best-effort attempt to ensure that the instance record will be in the
+        // failed state prior to returning to the caller. That way, the caller
+        // will not see an error when attempting to transition their instance's
+        // state, and then, upon fetching the instance, transiently see an
+        // instance state suggesting it's "doing fine" until the update saga
+        // completes.
+        self.update_instance(&opctx.log, saga, instance_id).await;
+        Ok(updated)
+    }
+
+    /// Forcibly moves a wedged instance to the `Failed` state.
+    ///
+    /// This is an operator override for instances whose VMM has stopped
+    /// responding or whose state is stuck behind a saga that never finished.
+    /// The instance's active VMM is marked as `Failed`, and an
+    /// `instance-update` saga moves the instance to `Failed`, from which it
+    /// may be automatically restarted (if its auto-restart policy allows) or
+    /// stopped and deleted.
+    ///
+    /// Before doing so, this checks that the instance actually looks wedged:
+    /// it must have an active VMM that hasn't already failed, it must not be
+    /// migrating, and the VMM's sled agent must not report the VMM as running
+    /// normally (a running instance should just be stopped).
+    pub(crate) async fn instance_force_fail(
+        &self,
+        opctx: &OpContext,
+        instance_lookup: &lookup::Instance<'_>,
+        params: &params::InstanceForceFail,
+    ) -> UpdateResult<InstanceAndActiveVmm> {
+        let (.., authz_instance) =
+            instance_lookup.lookup_for(authz::Action::Modify).await?;
+        // Only operators may override the instance's state. Check this after
+        // looking up the instance so that users who can't see the instance
+        // get a 404 rather than learning that it exists.
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+        let state = self
+            .db_datastore
+            .instance_fetch_with_vmm(opctx, &authz_instance)
+            .await?;
+        let instance_id = InstanceUuid::from_untyped_uuid(authz_instance.id());
+
+        let Some(vmm) = state.vmm() else {
+            return Err(Error::conflict(
+                "instance has no active VMM, so there is nothing to fail",
+            ));
+        };
+        let vmm_id = PropolisUuid::from_untyped_uuid(vmm.id);
+        let vmm_state = vmm.runtime.state;
+        if DbVmmState::DESTROYABLE_STATES.contains(&vmm_state) {
+            return Err(Error::conflict(format!(
+                "instance's active VMM is already {vmm_state}; it will be \
+                 cleaned up without intervention",
+            )));
+        }
+        if state.instance().runtime().migration_id.is_some() {
+            return Err(Error::conflict(
+                "instance is migrating; wait for the migration to resolve \
+                 before forcing it to fail",
+            ));
+        }
+
+        // Ask the sled agent about the VMM. If it answers that the VMM is
+        // running normally, there's nothing wedged about it. Anything else
+        // (the sled agent is unreachable, returns an error, or reports the VMM
+        // as stuck in a transitional state) is left to the operator's
+        // judgment.
+        if vmm_state.exists_on_sled() {
+            let sled_id = SledUuid::from_untyped_uuid(vmm.sled_id);
+            let probe = async {
+                let client = self.sled_client(&sled_id).await?;
+                client
+                    .vmm_get_state(&vmm_id)
+                    .await
+                    .map_err(|e| Error::from(SledAgentInstanceError(e)))
+            };
+            match tokio::time::timeout(FORCE_FAIL_PROBE_TIMEOUT, probe).await {
+                Ok(Ok(rsp)) => {
+                    let reported: nexus::SledVmmState = rsp.into_inner().into();
+                    if reported.vmm_state.state == nexus::VmmState::Running {
+                        return Err(Error::conflict(
+                            "sled agent reports that the instance's VMM is \
+                             running normally; stop the instance instead",
+                        ));
+                    }
+                }
+                Ok(Err(e)) => {
+                    info!(opctx.log, "sled agent could not report VMM state";
+                        "instance_id" => %instance_id,
+                        "vmm_id" => %vmm_id,
+                        "error" => %e,
+                    );
+                }
+                Err(_) => {
+                    info!(opctx.log, "timed out asking sled agent for VMM state";
+                        "instance_id" => %instance_id,
+                        "vmm_id" => %vmm_id,
+                    );
+                }
             }
-            // XXX: It's not clear what to do with this error; should it be
-            // bubbled back up to the caller?
-            Err(e) => error!(self.log,
-                            "failed to write Failed instance state to DB";
-                            "instance_id" => %instance_id,
-                            "vmm_id" => %vmm_id,
-                            "error" => ?e),
         }
 
-        Ok(())
+        warn!(opctx.log, "operator is forcing instance to fail";
+            "instance_id" => %instance_id,
+            "vmm_id" => %vmm_id,
+            "vmm_state" => %vmm_state,
+            "actor" => ?opctx.authn.actor(),
+            "reason" => &params.reason,
+        );
+        if !self.write_vmm_failed(opctx, authz_instance.clone(), vmm).await? {
+            return Err(Error::conflict(
+                "instance's VMM changed state while it was being forced to \
+                 fail; try again",
+            ));
+        }
+
+        self.db_datastore.instance_fetch_with_vmm(opctx, &authz_instance).await
+    }
+
+    /// Lists the live migrations an instance has undergone.
+    ///
+    /// The sleds involved in each migration are only reported to callers who
+    /// are allowed to view the fleet.
+    pub(crate) async fn instance_migration_list(
+        &self,
+        opctx: &OpContext,
+        instance_lookup: &lookup::Instance<'_>,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<views::InstanceMigration> {
+        let (.., authz_instance) =
+            instance_lookup.lookup_for(authz::Action::Read).await?;
+        let can_see_sleds =
+            opctx.authorize(authz::Action::Read, &authz::FLEET).await.is_ok();
+        let migrations = self
+            .db_datastore
+            .instance_list_migration_history(opctx, &authz_instance, pagparams)
+            .await?;
+
+        Ok(migrations
+            .into_iter()
+            .map(|(migration, source_sled_id, target_sled_id)| {
+                let state = if migration.either_side_failed() {
+                    views::InstanceMigrationState::Failed
+                } else if migration.either_side_completed() {
+                    views::InstanceMigrationState::Completed
+                } else {
+                    views::InstanceMigrationState::InProgress
+                };
+                let time_finished = if migration.is_terminal() {
+                    migration
+                        .time_source_updated
+                        .max(migration.time_target_updated)
+                } else {
+                    None
+                };
+                views::InstanceMigration {
+                    id: migration.id,
+                    instance_id: migration.instance_id,
+                    time_created: migration.time_created,
+                    time_finished,
+                    state,
+                    source_sled_id: source_sled_id.filter(|_| can_see_sleds),
+                    target_sled_id: target_sled_id.filter(|_| can_see_sleds),
+                }
+            })
+            .collect())
+    }
+
+    /// Lists the times the control plane has automatically restarted an
+    /// instance.
+    pub(crate) async fn instance_auto_restart_history_list(
+        &self,
+        opctx: &OpContext,
+        instance_lookup: &lookup::Instance<'_>,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<views::InstanceAutoRestartEvent> {
+        let (.., authz_instance) =
+            instance_lookup.lookup_for(authz::Action::Read).await?;
+        let events = self
+            .db_datastore
+            .instance_auto_restart_event_list(opctx, &authz_instance, pagparams)
+            .await?;
+        Ok(events.into_iter().map(Into::into).collect())
     }
 
     /// Lists disks attached to the instance.
@@ -2190,6 +2651,51 @@ pub(crate) async fn process_vmm_update(
     }
 }
 
+/// Determines whether inventory shows the clocks of the sleds an instance would
+/// migrate between to be close enough that the migration won't disrupt the
+/// guest's clock.
+///
+/// Sleds for which inventory has no time synchronization status are assumed to
+/// be fine: we only refuse a migration on positive evidence of a problem.
+pub(crate) fn check_migration_timesync(
+    collection: &Collection,
+    src_sled_id: SledUuid,
+    dst_sled_id: SledUuid,
+) -> Result<(), Error> {
+    let src = collection.sled_timesync(src_sled_id);
+    let dst = collection.sled_timesync(dst_sled_id);
+
+    for (which, sled_id, timesync) in
+        [("source", src_sled_id, src), ("destination", dst_sled_id, dst)]
+    {
+        if timesync.is_some_and(|timesync| !timesync.synced) {
+            return Err(Error::conflict(format!(
+                "cannot migrate instance: the {which} sled ({sled_id}) \
+                 reports that its clock is not synchronized; try again once \
+                 time synchronization is restored"
+            )));
+        }
+    }
+
+    let src_correction = src.and_then(|timesync| timesync.correction_usec);
+    let dst_correction = dst.and_then(|timesync| timesync.correction_usec);
+    if let (Some(src_correction), Some(dst_correction)) =
+        (src_correction, dst_correction)
+    {
+        let skew = src_correction.abs_diff(dst_correction);
+        if skew > MAX_MIGRATION_CLOCK_SKEW_USEC {
+            return Err(Error::conflict(format!(
+                "cannot migrate instance: the clocks of the source sled \
+                 ({src_sled_id}) and destination sled ({dst_sled_id}) differ \
+                 by {skew}us, more than the {MAX_MIGRATION_CLOCK_SKEW_USEC}us \
+                 allowed"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 /// Determines whether the supplied instance sizes (CPU count and memory size)
 /// are acceptable.
 fn check_instance_cpu_memory_sizes(
@@ -2241,6 +2747,79 @@ fn check_instance_cpu_memory_sizes(
     Ok(())
 }
 
+/// Validates that an instance's requested boot order doesn't name the same
+/// disk twice and that every disk it names is among those being attached to
+/// the instance at creation time.
+///
+/// Disks named by ID can't be checked until they're attached; the instance
+/// create saga fails if one of those isn't.
+fn check_instance_boot_order(
+    boot_order: &[NameOrId],
+    disks: &[params::InstanceDiskAttachment],
+) -> Result<(), Error> {
+    for (i, entry) in boot_order.iter().enumerate() {
+        if boot_order[..i].contains(entry) {
+            return Err(Error::invalid_request(&format!(
+                "disk {entry} appears more than once in the boot order"
+            )));
+        }
+        let NameOrId::Name(name) = entry else {
+            continue;
+        };
+        if !disks.iter().any(|disk| disk.name() == *name) {
+            return Err(Error::invalid_request(&format!(
+                "boot disk {name} is not among the instance's disks"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Validates the user-defined tags requested for an instance.
+fn check_instance_tags(tags: &BTreeMap<String, String>) -> Result<(), Error> {
+    if tags.len() > MAX_TAGS_PER_INSTANCE {
+        return Err(Error::invalid_request(&format!(
+            "an instance may not have more than {} tags",
+            MAX_TAGS_PER_INSTANCE,
+        )));
+    }
+    for (key, value) in tags {
+        if key.is_empty() || key.len() > MAX_INSTANCE_TAG_KEY_BYTES {
+            return Err(Error::invalid_request(&format!(
+                "tag key {key:?} must be between 1 and {} bytes long",
+                MAX_INSTANCE_TAG_KEY_BYTES,
+            )));
+        }
+        // The `:` separates a tag's key from its value when filtering
+        // instances by tag.
+        if key.contains(':') {
+            return Err(Error::invalid_request(&format!(
+                "tag key {key:?} must not contain ':'"
+            )));
+        }
+        if value.len() > MAX_INSTANCE_TAG_VALUE_BYTES {
+            return Err(Error::invalid_request(&format!(
+                "value of tag {key:?} must be at most {} bytes long",
+                MAX_INSTANCE_TAG_VALUE_BYTES,
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Parses a `key:value` instance tag filter.
+///
+/// The filter is split at its first `:`, so values may themselves contain
+/// colons.
+fn parse_instance_tag_filter(filter: &str) -> Result<(&str, &str), Error> {
+    match filter.split_once(':') {
+        Some((key, value)) if !key.is_empty() => Ok((key, value)),
+        _ => Err(Error::invalid_request(&format!(
+            "tag filter {filter:?} must have the form \"key:value\""
+        ))),
+    }
+}
+
 /// Determines the disposition of a request to start an instance given its state
 /// (and its current VMM's state, if it has one) in the database.
 fn instance_start_allowed(
@@ -2335,10 +2914,13 @@ mod tests {
     use super::*;
     use core::time::Duration;
     use futures::{SinkExt, StreamExt};
+    use iddqd::IdOrdMap;
     use nexus_db_model::{
         Instance as DbInstance, InstanceState as DbInstanceState,
         VmmState as DbVmmState,
     };
+    use nexus_reconfigurator_planning::example::ExampleSystemBuilder;
+    use nexus_types::inventory::TimeSync;
     use omicron_common::api::external::{
         Hostname, IdentityMetadataCreateParams, InstanceCpuCount, Name,
     };
@@ -2458,11 +3040,13 @@ mod tests {
             network_interfaces: InstanceNetworkInterfaceAttachment::None,
             external_ips: vec![],
             disks: vec![],
-            boot_disk: None,
+            boot_order: Vec::new(),
             ssh_public_keys: None,
             start: false,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         };
 
         let instance_id = InstanceUuid::from_untyped_uuid(Uuid::new_v4());
@@ -2593,4 +3177,73 @@ mod tests {
         );
         logctx.cleanup_successful();
     }
+
+    #[test]
+    fn test_check_migration_timesync() {
+        let test_name = "test_check_migration_timesync";
+        let logctx = test_setup_log(test_name);
+        let (example, _) =
+            ExampleSystemBuilder::new(&logctx.log, test_name).nsleds(2).build();
+        let mut collection = example.collection;
+        let sled_ids: Vec<_> =
+            collection.sled_agents.iter().map(|sa| sa.sled_id).collect();
+        let (src, dst) = (sled_ids[0], sled_ids[1]);
+
+        // With no timesync status in inventory, migration is allowed.
+        collection.ntp_timesync = IdOrdMap::new();
+        check_migration_timesync(&collection, src, dst)
+            .expect("migration allowed without timesync status");
+
+        let set_timesync =
+            |collection: &mut Collection,
+             sled_id: SledUuid,
+             synced: bool,
+             correction_usec: Option<i64>| {
+                let zone_id = collection
+                    .sled_agents
+                    .get(&sled_id)
+                    .and_then(|sa| sa.last_reconciliation.as_ref())
+                    .and_then(|r| {
+                        r.last_reconciled_config
+                            .zones
+                            .iter()
+                            .find(|zone| zone.zone_type.is_ntp())
+                    })
+                    .expect("sled has an NTP zone")
+                    .id;
+                collection.ntp_timesync.insert_overwrite(TimeSync {
+                    zone_id,
+                    synced,
+                    correction_usec,
+                });
+            };
+
+        // Synchronized sleds with similar clocks are fine.
+        set_timesync(&mut collection, src, true, Some(-1_000));
+        set_timesync(&mut collection, dst, true, Some(2_000));
+        check_migration_timesync(&collection, src, dst)
+            .expect("migration allowed between synchronized sleds");
+
+        // An unsynchronized sled on either end is not.
+        set_timesync(&mut collection, dst, false, Some(2_000));
+        let err = check_migration_timesync(&collection, src, dst)
+            .expect_err("migration refused to unsynchronized sled");
+        assert!(matches!(err, Error::Conflict { .. }), "{err}");
+        assert!(err.to_string().contains("destination sled"), "{err}");
+
+        // Nor are sleds whose clocks are too far apart.
+        let skew = i64::try_from(MAX_MIGRATION_CLOCK_SKEW_USEC).unwrap() + 1;
+        set_timesync(&mut collection, dst, true, Some(-1_000 + skew));
+        let err = check_migration_timesync(&collection, src, dst)
+            .expect_err("migration refused between skewed sleds");
+        assert!(matches!(err, Error::Conflict { .. }), "{err}");
+
+        // Collections that predate recording the clock correction only have
+        // the synchronization status to go on.
+        set_timesync(&mut collection, dst, true, None);
+        check_migration_timesync(&collection, src, dst)
+            .expect("migration allowed without clock corrections");
+
+        logctx.cleanup_successful();
+    }
 }
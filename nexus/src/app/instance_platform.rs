@@ -454,24 +454,27 @@ impl super::Nexus {
         // Add the instance's boot settings. Propolis expects boot order entries
         // that specify disks to refer to the *device* components of those
         // disks, not the backend components, so use the disk map to get this
-        // module's selected device name for the appropriate disk.
-        if let Some(boot_disk_id) = instance.boot_disk_id {
-            if let Some(disk) = disks.0.get(&boot_disk_id) {
-                let entry = BootOrderEntry {
-                    id: SpecKey::Name(disk.device_name.clone()),
+        // module's selected device name for the appropriate disk. The guest
+        // firmware tries the entries in order, so a guest that fails to boot
+        // from one disk falls back to the next.
+        if !instance.boot_order.is_empty() {
+            let mut order = Vec::with_capacity(instance.boot_order.len());
+            for boot_disk_id in &instance.boot_order {
+                let Some(disk) = disks.0.get(boot_disk_id) else {
+                    return Err(Error::internal_error(&format!(
+                        "instance's boot disk {boot_disk_id} is not attached"
+                    )));
                 };
 
-                components.add(
-                    component_names::BOOT_SETTINGS.to_owned(),
-                    ComponentV0::BootSettings(BootSettings {
-                        order: vec![entry],
-                    }),
-                )?;
-            } else {
-                return Err(Error::internal_error(&format!(
-                    "instance's boot disk {boot_disk_id} is not attached"
-                )));
+                order.push(BootOrderEntry {
+                    id: SpecKey::Name(disk.device_name.clone()),
+                });
             }
+
+            components.add(
+                component_names::BOOT_SETTINGS.to_owned(),
+                ComponentV0::BootSettings(BootSettings { order }),
+            )?;
         }
 
         components.add_disks(disks)?;
@@ -242,6 +242,75 @@ impl super::Nexus {
             .await
     }
 
+    /// List projects linked to a given pool
+    pub(crate) async fn ip_pool_project_list(
+        &self,
+        opctx: &OpContext,
+        pool_lookup: &lookup::IpPool<'_>,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<db::model::IpPoolResource> {
+        let (.., authz_pool) =
+            pool_lookup.lookup_for(authz::Action::ListChildren).await?;
+
+        // check ability to list silos (and their projects) in general
+        opctx.authorize(authz::Action::ListChildren, &authz::FLEET).await?;
+
+        self.db_datastore
+            .ip_pool_project_list(opctx, &authz_pool, pagparams)
+            .await
+    }
+
+    pub(crate) async fn ip_pool_link_project(
+        &self,
+        opctx: &OpContext,
+        pool_lookup: &lookup::IpPool<'_>,
+        project_link: &params::IpPoolLinkProject,
+    ) -> CreateResult<db::model::IpPoolResource> {
+        let (authz_pool,) =
+            pool_lookup.lookup_for(authz::Action::Modify).await?;
+
+        if self.db_datastore.ip_pool_is_internal(opctx, &authz_pool).await? {
+            return Err(not_found_from_lookup(pool_lookup));
+        }
+
+        let (.., authz_project) = LookupPath::new(opctx, &self.db_datastore)
+            .project_id(project_link.project)
+            .lookup_for(authz::Action::Modify)
+            .await?;
+        self.db_datastore
+            .ip_pool_link_project(opctx, &authz_pool, &authz_project)
+            .await
+    }
+
+    pub(crate) async fn ip_pool_unlink_project(
+        &self,
+        opctx: &OpContext,
+        pool_lookup: &lookup::IpPool<'_>,
+        project_id: Uuid,
+    ) -> DeleteResult {
+        let (.., authz_pool) =
+            pool_lookup.lookup_for(authz::Action::Modify).await?;
+
+        if self.db_datastore.ip_pool_is_internal(opctx, &authz_pool).await? {
+            return Err(not_found_from_lookup(pool_lookup));
+        }
+
+        let (authz_silo, authz_project) =
+            LookupPath::new(opctx, &self.db_datastore)
+                .project_id(project_id)
+                .lookup_for(authz::Action::Modify)
+                .await?;
+
+        self.db_datastore
+            .ip_pool_unlink_project(
+                opctx,
+                &authz_pool,
+                &authz_silo,
+                &authz_project,
+            )
+            .await
+    }
+
     pub(crate) async fn ip_pools_list(
         &self,
         opctx: &OpContext,
@@ -14,6 +14,7 @@ use crate::populate::PopulateStatus;
 use crate::populate::populate_start;
 use ::oximeter::types::ProducerRegistry;
 use anyhow::anyhow;
+use chrono::Utc;
 use internal_dns_types::names::ServiceName;
 use nexus_background_task_interface::BackgroundTasks;
 use nexus_config::NexusConfig;
@@ -38,6 +39,7 @@ use oximeter_producer::Server as ProducerServer;
 use sagas::common_storage::PooledPantryClient;
 use sagas::common_storage::make_pantry_connection_pool;
 use slog::Logger;
+use slog_error_chain::InlineErrorChain;
 use std::collections::HashMap;
 use std::net::SocketAddrV6;
 use std::net::{IpAddr, Ipv6Addr};
@@ -58,6 +60,7 @@ mod audit_log;
 pub(crate) mod background;
 mod bfd;
 mod bgp;
+mod break_glass;
 mod certificate;
 mod crucible;
 mod deployment;
@@ -77,18 +80,22 @@ mod lldp;
 mod login;
 mod metrics;
 mod network_interface;
+mod operation;
 pub(crate) mod oximeter;
 mod probe;
 mod project;
 mod quiesce;
 mod quota;
 mod rack;
+mod runtime_config;
 pub(crate) mod saga;
 mod session;
 mod silo;
 mod sled;
 mod sled_instance;
 mod snapshot;
+mod snapshot_export;
+mod snapshot_schedule;
 mod ssh_key;
 pub(crate) mod support_bundles;
 mod switch;
@@ -115,6 +122,7 @@ pub(crate) use nexus_db_model::MAX_NICS_PER_INSTANCE;
 pub(crate) use nexus_db_queries::db::queries::disk::MAX_DISKS_PER_INSTANCE;
 use nexus_mgs_updates::DEFAULT_RETRY_TIMEOUT;
 use nexus_types::internal_api::views::MgsUpdateDriverStatus;
+pub use runtime_config::RuntimeLogLevel;
 use sagas::demo::CompletingDemoSagas;
 
 // XXX: Might want to recast as max *floating* IPs, we have at most one
@@ -152,6 +160,18 @@ pub const MAX_DISK_SIZE_BYTES: u64 = 1023 * (1 << 30); // 1023 GiB
 /// This value is aribtrary
 pub const MAX_SSH_KEYS_PER_INSTANCE: u32 = 100;
 
+/// These values are arbitrary, but the key and value lengths must fit in the
+/// `instance_tag` table's columns.
+pub const MAX_TAGS_PER_INSTANCE: usize = 64;
+pub const MAX_INSTANCE_TAG_KEY_BYTES: usize = 63;
+pub const MAX_INSTANCE_TAG_VALUE_BYTES: usize = 255;
+
+/// These values are arbitrary: the first bounds the size of a bulk instance
+/// action request, and the second bounds how many of its instances are acted
+/// on at once.
+pub const MAX_INSTANCES_PER_BULK_ACTION: usize = 1000;
+pub(crate) const MAX_CONCURRENT_BULK_INSTANCE_ACTIONS: usize = 16;
+
 /// The amount of disk space to reserve for non-Crucible / control plane
 /// storage. This amount represents a buffer that the region allocation query
 /// will not use for each U2.
@@ -281,6 +301,9 @@ pub struct Nexus {
 
     /// state of overall Nexus quiesce activity
     quiesce: NexusQuiesceHandle,
+
+    /// level of the Nexus log, which can be changed at runtime
+    log_level: RuntimeLogLevel,
 }
 
 impl Nexus {
@@ -298,6 +321,7 @@ impl Nexus {
         producer_registry: &ProducerRegistry,
         config: &NexusConfig,
         authz: Arc<authz::Authz>,
+        log_level: RuntimeLogLevel,
     ) -> Result<Arc<Nexus>, String> {
         let all_versions = config
             .pkg
@@ -513,6 +537,7 @@ impl Nexus {
             mgs_resolver,
             repo_depot_resolver,
             quiesce,
+            log_level,
         };
 
         // TODO-cleanup all the extra Arcs here seems wrong
@@ -534,6 +559,9 @@ impl Nexus {
         let task_log = nexus.log.clone();
         let task_registry = producer_registry.clone();
         let task_config = config.clone();
+        // Operations created from here on belong to this process, not to any
+        // previous run of this Nexus.
+        let time_started = Utc::now();
         tokio::spawn(async move {
             match task_nexus.wait_for_populate().await {
                 Ok(_) => {
@@ -548,6 +576,34 @@ impl Nexus {
             // start the background tasks so that whatever can work will work.
             info!(task_log, "activating background tasks");
 
+            // Any operations that this Nexus was running before it restarted
+            // are gone, so record that they won't complete.
+            match task_nexus
+                .db_datastore
+                .operation_fail_abandoned(
+                    &background_ctx,
+                    task_nexus.id(),
+                    time_started,
+                )
+                .await
+            {
+                Ok(0) => {}
+                Ok(count) => {
+                    info!(
+                        task_log,
+                        "marked abandoned operations failed";
+                        "count" => count,
+                    );
+                }
+                Err(error) => {
+                    warn!(
+                        task_log,
+                        "failed to mark abandoned operations failed";
+                        InlineErrorChain::new(&error),
+                    );
+                }
+            }
+
             let driver = background_tasks_initializer.start(
                 &task_nexus.background_tasks,
                 BackgroundTasksData {
@@ -0,0 +1,198 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Long-running operations
+//!
+//! Some requests take long enough that clients shouldn't have to hold a
+//! connection open (or guess at a timeout) until they complete. These
+//! requests create an operation record and return it right away, while the
+//! work itself continues in a task of its own that records its progress and
+//! outcome on the operation. Clients poll the operation to find out when the
+//! work is done and which resource it produced.
+
+use dropshot::HttpError;
+use futures::future::BoxFuture;
+use nexus_db_lookup::LookupPath;
+use nexus_db_lookup::lookup;
+use nexus_db_queries::authz;
+use nexus_db_queries::context::OpContext;
+use nexus_db_queries::db;
+use nexus_db_queries::db::DataStore;
+use nexus_types::external_api::params;
+use nexus_types::external_api::views;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::LookupType;
+use slog_error_chain::InlineErrorChain;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Handle used by the work behind an operation to act on behalf of the user
+/// that started it and to report how far it has gotten
+pub(crate) struct OperationProgress {
+    opctx: OpContext,
+    datastore: Arc<DataStore>,
+    authz_operation: authz::Operation,
+}
+
+impl OperationProgress {
+    pub fn opctx(&self) -> &OpContext {
+        &self.opctx
+    }
+
+    /// Record that the operation has completed another of its steps
+    ///
+    /// Progress is only informational, so failing to record it doesn't fail
+    /// the operation.
+    pub async fn step_completed(&self) {
+        if let Err(error) = self
+            .datastore
+            .operation_step_completed(&self.opctx, &self.authz_operation)
+            .await
+        {
+            warn!(
+                self.opctx.log,
+                "failed to record operation progress";
+                InlineErrorChain::new(&error),
+            );
+        }
+    }
+}
+
+/// The work carried out by an operation, which returns the ID of the resource
+/// it produced
+pub(crate) type OperationWork = BoxFuture<'static, Result<Uuid, Error>>;
+
+impl super::Nexus {
+    pub fn operation_lookup<'a>(
+        &'a self,
+        opctx: &'a OpContext,
+        path: params::OperationPath,
+    ) -> lookup::Operation<'a> {
+        LookupPath::new(opctx, &self.db_datastore).operation_id(path.operation)
+    }
+
+    pub(crate) async fn operation_view(
+        &self,
+        operation_lookup: &lookup::Operation<'_>,
+    ) -> LookupResult<views::Operation> {
+        let (.., db_operation) = operation_lookup.fetch().await?;
+        Ok(db_operation.into())
+    }
+
+    pub(crate) async fn operation_list(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<views::Operation> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::ListChildren).await?;
+
+        Ok(self
+            .db_datastore
+            .operation_list(opctx, &authz_project, pagparams)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Start an operation in `authz_project`, returning it as soon as it has
+    /// been recorded
+    ///
+    /// `work` is called with a handle for reporting progress on the operation
+    /// and runs in the background. The operation succeeds or fails along with
+    /// it. Operations are not resumed if this Nexus restarts; instead, they're
+    /// marked failed when it starts up again.
+    pub(crate) async fn operation_start<F>(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        authz_project: &authz::Project,
+        kind: db::model::OperationKind,
+        steps_total: u32,
+        work: F,
+    ) -> CreateResult<views::Operation>
+    where
+        F: FnOnce(Arc<Self>, OperationProgress) -> OperationWork,
+    {
+        let operation = self
+            .db_datastore
+            .operation_create(
+                opctx,
+                authz_project,
+                db::model::Operation::new(
+                    Uuid::new_v4(),
+                    authz_project.id(),
+                    self.id(),
+                    kind,
+                    steps_total,
+                ),
+            )
+            .await?;
+
+        let authz_operation = authz::Operation::new(
+            authz_project.clone(),
+            operation.id,
+            LookupType::ById(operation.id),
+        );
+        let metadata = BTreeMap::from([(
+            "operation_id".to_string(),
+            operation.id.to_string(),
+        )]);
+        let progress = OperationProgress {
+            opctx: opctx.child(metadata.clone()),
+            datastore: Arc::clone(&self.db_datastore),
+            authz_operation: authz_operation.clone(),
+        };
+        let work = work(Arc::clone(self), progress);
+
+        let opctx = opctx.child(metadata);
+        let datastore = Arc::clone(&self.db_datastore);
+        tokio::spawn(async move {
+            let result = match work.await {
+                Ok(result_id) => {
+                    info!(
+                        opctx.log,
+                        "operation succeeded";
+                        "result_id" => %result_id,
+                    );
+                    datastore
+                        .operation_succeeded(
+                            &opctx,
+                            &authz_operation,
+                            result_id,
+                        )
+                        .await
+                }
+                Err(error) => {
+                    warn!(
+                        opctx.log,
+                        "operation failed";
+                        InlineErrorChain::new(&error),
+                    );
+                    // Only the part of the error meant for users is recorded,
+                    // since they're the ones who will see it.
+                    let message = HttpError::from(error).external_message;
+                    datastore
+                        .operation_failed(&opctx, &authz_operation, message)
+                        .await
+                }
+            };
+            if let Err(error) = result {
+                error!(
+                    opctx.log,
+                    "failed to record operation outcome";
+                    InlineErrorChain::new(&error),
+                );
+            }
+        });
+
+        Ok(operation.into())
+    }
+}
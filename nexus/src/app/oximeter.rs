@@ -6,16 +6,24 @@
 
 use crate::external_api::params::ResourceMetrics;
 use crate::internal_api::params::OximeterInfo;
+use anyhow::Context;
+use clickhouse_admin_single_client::Client as ClickhouseSingleClient;
 use dropshot::PaginationParams;
+use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
 use nexus_db_queries::db::DataStore;
+use nexus_types::deployment::Blueprint;
+use nexus_types::deployment::BlueprintZoneDisposition;
+use omicron_common::address::CLICKHOUSE_ADMIN_PORT;
 use omicron_common::api::external::{DataPageParams, Error, ListResultVec};
 use omicron_common::api::internal::nexus::{self, ProducerEndpoint};
+use omicron_uuid_kinds::OmicronZoneUuid;
 use oximeter_client::Client as OximeterClient;
 use oximeter_db::Measurement;
 use oximeter_db::query::Timestamp;
 use std::net::SocketAddr;
+use std::net::SocketAddrV6;
 use std::num::NonZeroU32;
 use std::time::Duration;
 use uuid::Uuid;
@@ -26,6 +34,10 @@ use uuid::Uuid;
 /// some interval of this overall duration.
 pub const PRODUCER_LEASE_DURATION: Duration = Duration::from_secs(10 * 60);
 
+/// How long to wait for the ClickHouse admin server to finish taking or
+/// restoring a backup of the oximeter database.
+pub const CLICKHOUSE_BACKUP_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
 impl super::Nexus {
     /// Insert a new record of an Oximeter collector server.
     pub(crate) async fn upsert_oximeter_collector(
@@ -183,6 +195,81 @@ impl super::Nexus {
         )
         .unwrap())
     }
+
+    /// Restore the single-node ClickHouse database from the named backup.
+    ///
+    /// Data in the backup is added to whatever is already in the database;
+    /// the backup itself is left in place.
+    pub(crate) async fn clickhouse_backup_restore(
+        &self,
+        opctx: &OpContext,
+        name: &str,
+    ) -> Result<(), Error> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+        let (_, blueprint) =
+            self.db_datastore.blueprint_target_get_current_full(opctx).await?;
+        let (zone_id, client) =
+            clickhouse_single_node_backup_client(&opctx.log, &blueprint)
+                .map_err(|e| Error::internal_error(&format!("{e:#}")))?
+                .ok_or_else(|| {
+                    Error::invalid_request(
+                        "no single-node ClickHouse zone is in service",
+                    )
+                })?;
+
+        info!(
+            opctx.log,
+            "restoring ClickHouse backup";
+            "zone_id" => %zone_id,
+            "backup" => name,
+        );
+        client.backup_restore(name).await.map_err(|e| match e {
+            clickhouse_admin_single_client::Error::ErrorResponse(rv)
+                if rv.status().is_client_error() =>
+            {
+                Error::invalid_request(&rv.message)
+            }
+            e => Error::internal_error(&format!(
+                "failed to restore ClickHouse backup {name:?} in zone \
+                 {zone_id}: {e}"
+            )),
+        })?;
+        Ok(())
+    }
+}
+
+/// Returns the ID of the in-service single-node ClickHouse zone in
+/// `blueprint`, if there is one, along with a client for its admin server.
+///
+/// Backups and restores can take far longer than ordinary requests, so the
+/// client is configured with [`CLICKHOUSE_BACKUP_TIMEOUT`] rather than sharing
+/// Nexus's general-purpose `reqwest` client.
+pub(crate) fn clickhouse_single_node_backup_client(
+    log: &slog::Logger,
+    blueprint: &Blueprint,
+) -> anyhow::Result<Option<(OmicronZoneUuid, ClickhouseSingleClient)>> {
+    let Some((_, zone)) = blueprint
+        .all_omicron_zones(BlueprintZoneDisposition::is_in_service)
+        .find(|(_, zone)| zone.zone_type.is_clickhouse())
+    else {
+        return Ok(None);
+    };
+
+    let admin_addr =
+        SocketAddrV6::new(zone.underlay_ip(), CLICKHOUSE_ADMIN_PORT, 0, 0);
+    let admin_url = format!("http://{admin_addr}");
+    let reqwest_client = reqwest::ClientBuilder::new()
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(CLICKHOUSE_BACKUP_TIMEOUT)
+        .build()
+        .context("failed to build ClickHouse admin client")?;
+    let client_log = log.new(o!("admin_url" => admin_url.clone()));
+    let client = ClickhouseSingleClient::new_with_client(
+        &admin_url,
+        reqwest_client,
+        client_log,
+    );
+    Ok(Some((zone.id, client)))
 }
 
 /// Idempotently un-assign a producer from an oximeter collector.
@@ -12,6 +12,7 @@ use nexus_db_lookup::LookupPath;
 use nexus_db_lookup::lookup;
 use nexus_db_queries::authn;
 use nexus_db_queries::authz;
+use nexus_db_queries::authz::ApiResource;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
 use omicron_common::api::external::CreateResult;
@@ -106,6 +107,80 @@ impl super::Nexus {
             .await
     }
 
+    // Instance auto-restart defaults
+
+    pub(crate) async fn project_instance_auto_restart_update(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+        params: &params::ProjectInstanceAutoRestartUpdate,
+    ) -> UpdateResult<db::model::Project> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::Modify).await?;
+        self.db_datastore
+            .project_auto_restart_defaults_update(
+                opctx,
+                &authz_project,
+                params.clone().into(),
+            )
+            .await
+    }
+
+    // Ephemeral IP policy
+
+    pub(crate) async fn project_ephemeral_ip_policy_view(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+    ) -> LookupResult<db::model::ProjectEphemeralIpPolicy> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::Read).await?;
+        self.db_datastore
+            .project_ephemeral_ip_policy_view(opctx, &authz_project)
+            .await
+    }
+
+    pub(crate) async fn project_ephemeral_ip_policy_update(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+        params: &params::ProjectEphemeralIpPolicyUpdate,
+    ) -> UpdateResult<db::model::ProjectEphemeralIpPolicy> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::Modify).await?;
+
+        // Make sure the pool can actually be allocated from by this project,
+        // so that instance creation doesn't fail later on.
+        let ip_pool_id = match &params.pool {
+            Some(pool) => {
+                let (authz_pool, ..) = self
+                    .ip_pool_lookup(opctx, pool)?
+                    .lookup_for(authz::Action::CreateChild)
+                    .await?;
+                self.db_datastore
+                    .ip_pool_fetch_link_for_project(
+                        opctx,
+                        authz_pool.id(),
+                        authz_project.id(),
+                    )
+                    .await
+                    .map_err(|_| authz_pool.not_found())?;
+                Some(authz_pool.id())
+            }
+            None => None,
+        };
+
+        let policy = db::model::ProjectEphemeralIpPolicy::new(
+            authz_project.id(),
+            params.auto_assign,
+            ip_pool_id,
+            params.allow_override,
+        );
+        self.db_datastore
+            .project_ephemeral_ip_policy_update(opctx, &authz_project, policy)
+            .await
+    }
+
     // Role assignments
 
     pub(crate) async fn project_fetch_policy(
@@ -36,6 +36,12 @@ impl super::Nexus {
         let db_claims = self.datastore().claims_held();
         Ok(QuiesceStatus { state, sagas_pending, db_claims })
     }
+
+    /// Returns whether this Nexus is slated for expungement and draining its
+    /// clients
+    pub(crate) fn is_draining(&self) -> bool {
+        self.quiesce.is_draining()
+    }
 }
 
 /// Describes the configuration and state around quiescing Nexus
@@ -45,6 +51,11 @@ pub struct NexusQuiesceHandle {
     datastore: Arc<DataStore>,
     sagas: SagaQuiesceHandle,
     state: watch::Sender<QuiesceState>,
+    /// whether this Nexus is slated for expungement and draining its clients
+    /// (see [`Blueprint::is_nexus_draining()`])
+    ///
+    /// [`Blueprint::is_nexus_draining()`]: nexus_types::deployment::Blueprint::is_nexus_draining
+    draining: watch::Sender<bool>,
 }
 
 impl NexusQuiesceHandle {
@@ -53,7 +64,32 @@ impl NexusQuiesceHandle {
         let saga_quiesce_log = log.new(o!("component" => "SagaQuiesceHandle"));
         let sagas = SagaQuiesceHandle::new(saga_quiesce_log);
         let (state, _) = watch::channel(QuiesceState::Undetermined);
-        NexusQuiesceHandle { log: my_log, datastore, sagas, state }
+        let (draining, _) = watch::channel(false);
+        NexusQuiesceHandle { log: my_log, datastore, sagas, state, draining }
+    }
+
+    /// Returns whether this Nexus is draining its clients
+    ///
+    /// While draining, this Nexus keeps serving requests that read state, but
+    /// turns away requests that would start new operations, so that clients
+    /// retry against a Nexus that isn't going away.
+    pub fn is_draining(&self) -> bool {
+        *self.draining.borrow()
+    }
+
+    pub fn set_draining(&self, draining: bool) {
+        self.draining.send_if_modified(|d| {
+            if *d == draining {
+                return false;
+            }
+            if draining {
+                info!(&self.log, "slated for expungement: draining clients");
+            } else {
+                info!(&self.log, "no longer draining clients");
+            }
+            *d = draining;
+            true
+        });
     }
 
     pub fn sagas(&self) -> SagaQuiesceHandle {
@@ -211,11 +247,18 @@ mod test {
     use chrono::Utc;
     use diesel::ExpressionMethods;
     use diesel::QueryDsl;
+    use http::Method;
     use http::StatusCode;
     use nexus_client::types::QuiesceState;
     use nexus_client::types::QuiesceStatus;
     use nexus_test_interface::NexusServer;
+    use nexus_test_utils::background::activate_background_task;
+    use nexus_test_utils::http_testing::AuthnMode;
+    use nexus_test_utils::http_testing::NexusRequest;
+    use nexus_test_utils::http_testing::RequestBuilder;
     use nexus_test_utils_macros::nexus_test;
+    use nexus_types::external_api::params;
+    use omicron_common::api::external::IdentityMetadataCreateParams;
     use omicron_test_utils::dev::poll::CondCheckError;
     use omicron_test_utils::dev::poll::wait_for_condition;
     use slog::Logger;
@@ -469,4 +512,54 @@ mod test {
             StatusCode::SERVICE_UNAVAILABLE
         );
     }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_draining(cptestctx: &ControlPlaneTestContext) {
+        let client = &cptestctx.external_client;
+        let nexus = &cptestctx.server.server_context().nexus;
+        let project_params = params::ProjectCreate {
+            identity: IdentityMetadataCreateParams {
+                name: "drained".parse().unwrap(),
+                description: String::from("created after draining"),
+            },
+        };
+
+        // Let blueprint execution determine that this Nexus is not draining
+        // before we override that, so that it doesn't change the state out
+        // from under us.
+        activate_background_task(
+            &cptestctx.internal_client,
+            "blueprint_executor",
+        )
+        .await;
+        assert!(!nexus.is_draining());
+        nexus.quiesce.set_draining(true);
+
+        // Requests that only read state are still served.
+        NexusRequest::object_get(client, "/v1/projects")
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute()
+            .await
+            .expect("listing projects while draining");
+
+        // Requests that would start new work are turned away.
+        NexusRequest::new(
+            RequestBuilder::new(client, Method::POST, "/v1/projects")
+                .body(Some(&project_params))
+                .expect_status(Some(StatusCode::SERVICE_UNAVAILABLE))
+                .expect_response_header(http::header::RETRY_AFTER, "5"),
+        )
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .expect("creating a project while draining");
+
+        // Once no longer draining, they succeed again.
+        nexus.quiesce.set_draining(false);
+        NexusRequest::objects_post(client, "/v1/projects", &project_params)
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute()
+            .await
+            .expect("creating a project after draining");
+    }
 }
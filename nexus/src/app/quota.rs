@@ -9,9 +9,12 @@ use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
 use nexus_types::external_api::params;
+use omicron_common::api::external::CreateResult;
 use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::Error;
 use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::UpdateResult;
 use uuid::Uuid;
 
@@ -46,4 +49,63 @@ impl super::Nexus {
             .silo_update_quota(opctx, &authz_silo, updates.clone().into())
             .await
     }
+
+    pub(crate) async fn project_quotas_create(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+        quotas: &params::ProjectQuotasCreate,
+    ) -> CreateResult<db::model::ProjectQuotas> {
+        let (authz_silo, authz_project) =
+            project_lookup.lookup_for(authz::Action::Read).await?;
+        let quotas = db::model::ProjectQuotas::new(
+            authz_project.id(),
+            quotas.cpus,
+            quotas.memory.into(),
+            quotas.storage.into(),
+        );
+        self.db_datastore
+            .project_quotas_create(opctx, &authz_silo, &authz_project, quotas)
+            .await
+    }
+
+    pub(crate) async fn project_quotas_view(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+    ) -> LookupResult<db::model::ProjectQuotas> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::Read).await?;
+        self.db_datastore.project_quotas_view(opctx, &authz_project).await
+    }
+
+    pub(crate) async fn project_quotas_update(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+        updates: &params::ProjectQuotasUpdate,
+    ) -> UpdateResult<db::model::ProjectQuotas> {
+        let (authz_silo, authz_project) =
+            project_lookup.lookup_for(authz::Action::Read).await?;
+        self.db_datastore
+            .project_quotas_update(
+                opctx,
+                &authz_silo,
+                &authz_project,
+                updates.clone().into(),
+            )
+            .await
+    }
+
+    pub(crate) async fn project_quotas_delete(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+    ) -> DeleteResult {
+        let (authz_silo, authz_project) =
+            project_lookup.lookup_for(authz::Action::Read).await?;
+        self.db_datastore
+            .project_quotas_delete(opctx, &authz_silo, &authz_project)
+            .await
+    }
 }
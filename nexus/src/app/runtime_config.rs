@@ -0,0 +1,251 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Configuration that can be changed while Nexus is running
+//!
+//! Most Nexus configuration comes from its config file and can only be changed
+//! by restarting Nexus.  A few settings that are useful to tune while debugging
+//! a live system (the log level and background task periods) can also be
+//! changed at runtime through the internal API.  Changes made this way are not
+//! persisted.
+
+use dropshot::ConfigLogging;
+use dropshot::ConfigLoggingLevel;
+use nexus_db_queries::authz;
+use nexus_db_queries::context::OpContext;
+use nexus_types::internal_api::params::LogLevel;
+use nexus_types::internal_api::params::RuntimeConfigUpdate;
+use nexus_types::internal_api::views::RuntimeConfig;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::UpdateResult;
+use slog::Drain;
+use slog::Level;
+use slog::Logger;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+impl super::Nexus {
+    pub(crate) async fn runtime_config_view(
+        &self,
+        opctx: &OpContext,
+    ) -> LookupResult<RuntimeConfig> {
+        opctx.authorize(authz::Action::Read, &authz::FLEET).await?;
+        Ok(RuntimeConfig {
+            log_level: level_to_api(self.log_level.get()),
+            bgtask_periods: self.bgtask_periods(opctx).await?,
+        })
+    }
+
+    /// Apply runtime configuration changes
+    ///
+    /// The whole update is validated before any of it is applied, so an
+    /// invalid update changes nothing.
+    pub(crate) async fn runtime_config_update(
+        &self,
+        opctx: &OpContext,
+        update: RuntimeConfigUpdate,
+    ) -> UpdateResult<RuntimeConfig> {
+        opctx.authorize(authz::Action::Modify, &authz::FLEET).await?;
+        let RuntimeConfigUpdate { log_level, bgtask_periods } = update;
+
+        if let Some((name, _)) =
+            bgtask_periods.iter().find(|(_, period)| period.is_zero())
+        {
+            return Err(Error::invalid_request(format!(
+                "period for background task {name:?} must be non-zero"
+            )));
+        }
+
+        // This validates the task names before changing any periods, and it's
+        // the last step that can fail.
+        if !bgtask_periods.is_empty() {
+            self.bgtasks_set_periods(opctx, &bgtask_periods).await?;
+            for (name, period) in &bgtask_periods {
+                info!(
+                    self.log,
+                    "changed background task period";
+                    "background_task" => name,
+                    "period" => ?period,
+                );
+            }
+        }
+
+        if let Some(log_level) = log_level {
+            let level = level_from_api(log_level);
+            // Log this at a level that will show up both before and after the
+            // change.
+            warn!(
+                self.log,
+                "changing log level";
+                "old_level" => self.log_level.get().as_str(),
+                "new_level" => level.as_str(),
+            );
+            self.log_level.set(level);
+        }
+
+        self.runtime_config_view(opctx).await
+    }
+}
+
+/// Minimum severity of messages written to the Nexus log, which can be changed
+/// at runtime
+///
+/// This is cheap to clone; all clones share the same level.
+#[derive(Clone, Debug)]
+pub struct RuntimeLogLevel(Arc<AtomicUsize>);
+
+impl RuntimeLogLevel {
+    pub fn new(level: Level) -> RuntimeLogLevel {
+        RuntimeLogLevel(Arc::new(AtomicUsize::new(level.as_usize())))
+    }
+
+    /// Returns a `RuntimeLogLevel` starting at the level configured by
+    /// `config`, along with a copy of `config` that logs messages of all
+    /// severities.
+    ///
+    /// A logger built from the returned config should be wrapped with
+    /// [`RuntimeLogLevel::filter()`].  Otherwise the level could never be
+    /// lowered below the one in `config`.
+    pub fn from_config(
+        config: &ConfigLogging,
+    ) -> (RuntimeLogLevel, ConfigLogging) {
+        let mut config = config.clone();
+        let level = match &mut config {
+            ConfigLogging::StderrTerminal { level }
+            | ConfigLogging::File { level, .. } => level,
+        };
+        let initial = match level {
+            ConfigLoggingLevel::Trace => Level::Trace,
+            ConfigLoggingLevel::Debug => Level::Debug,
+            ConfigLoggingLevel::Info => Level::Info,
+            ConfigLoggingLevel::Warn => Level::Warning,
+            ConfigLoggingLevel::Error => Level::Error,
+            ConfigLoggingLevel::Critical => Level::Critical,
+        };
+        *level = ConfigLoggingLevel::Trace;
+        (RuntimeLogLevel::new(initial), config)
+    }
+
+    pub fn get(&self) -> Level {
+        Level::from_usize(self.0.load(Ordering::Relaxed))
+            .expect("stored a valid log level")
+    }
+
+    pub fn set(&self, level: Level) {
+        self.0.store(level.as_usize(), Ordering::Relaxed);
+    }
+
+    /// Returns a logger that passes messages on to `log` only if they're at
+    /// least as severe as the current level.
+    pub fn filter(&self, log: Logger) -> Logger {
+        Logger::root(LevelFilter { log, level: self.clone() }, slog::o!())
+    }
+}
+
+/// Drain that drops messages less severe than a [`RuntimeLogLevel`]
+struct LevelFilter {
+    log: Logger,
+    level: RuntimeLogLevel,
+}
+
+impl Drain for LevelFilter {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(
+        &self,
+        record: &slog::Record<'_>,
+        values: &slog::OwnedKVList,
+    ) -> Result<(), slog::Never> {
+        if record.level().is_at_least(self.level.get()) {
+            Drain::log(&self.log, record, values)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_enabled(&self, level: Level) -> bool {
+        level.is_at_least(self.level.get())
+            && Drain::is_enabled(&self.log, level)
+    }
+}
+
+fn level_to_api(level: Level) -> LogLevel {
+    match level {
+        Level::Trace => LogLevel::Trace,
+        Level::Debug => LogLevel::Debug,
+        Level::Info => LogLevel::Info,
+        Level::Warning => LogLevel::Warning,
+        Level::Error => LogLevel::Error,
+        Level::Critical => LogLevel::Critical,
+    }
+}
+
+fn level_from_api(level: LogLevel) -> Level {
+    match level {
+        LogLevel::Trace => Level::Trace,
+        LogLevel::Debug => Level::Debug,
+        LogLevel::Info => Level::Info,
+        LogLevel::Warning => Level::Warning,
+        LogLevel::Error => Level::Error,
+        LogLevel::Critical => Level::Critical,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use slog::o;
+    use std::sync::Mutex;
+
+    /// Drain that records the messages it's given
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Drain for Recorder {
+        type Ok = ();
+        type Err = slog::Never;
+
+        fn log(
+            &self,
+            record: &slog::Record<'_>,
+            _: &slog::OwnedKVList,
+        ) -> Result<(), slog::Never> {
+            self.0.lock().unwrap().push(record.msg().to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_runtime_log_level() {
+        let recorder = Recorder::default();
+        let level = RuntimeLogLevel::new(Level::Info);
+        let log = level.filter(Logger::root(recorder.clone(), o!())).new(o!());
+
+        slog::debug!(log, "hidden");
+        slog::info!(log, "shown");
+        level.set(Level::Debug);
+        assert_eq!(level.get(), Level::Debug);
+        slog::debug!(log, "now shown");
+        level.set(Level::Error);
+        slog::warn!(log, "hidden again");
+
+        assert_eq!(*recorder.0.lock().unwrap(), vec!["shown", "now shown"]);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let (level, config) =
+            RuntimeLogLevel::from_config(&ConfigLogging::StderrTerminal {
+                level: ConfigLoggingLevel::Warn,
+            });
+        assert_eq!(level.get(), Level::Warning);
+        assert!(matches!(
+            config,
+            ConfigLogging::StderrTerminal { level: ConfigLoggingLevel::Trace }
+        ));
+    }
+}
@@ -17,6 +17,7 @@ use nexus_db_queries::db;
 use omicron_common::api::external::Error;
 use omicron_common::progenitor_operation_retry::ProgenitorOperationRetry;
 use omicron_common::progenitor_operation_retry::ProgenitorOperationRetryError;
+use rand::seq::SliceRandom;
 use slog::Logger;
 use slog_error_chain::InlineErrorChain;
 use std::net::SocketAddrV6;
@@ -28,9 +29,18 @@ pub(crate) use pantry_pool::make_pantry_connection_pool;
 
 // Common Pantry operations
 
+/// Picks a Pantry for a new disk import, snapshot, or export
+///
+/// Work is spread across Pantries by preferring whichever healthy one has the
+/// fewest disks and snapshot exports attached to it. If that can't be
+/// determined, any healthy Pantry from the connection pool is used instead.
 pub(crate) async fn get_pantry_address(
     nexus: &Nexus,
 ) -> Result<SocketAddrV6, ActionError> {
+    if let Some(address) = least_loaded_pantry_address(nexus).await {
+        return Ok(address);
+    }
+
     let client = nexus.pantry_connection_pool().claim().await.map_err(|e| {
         ActionError::action_failed(format!(
             "failed to claim pantry client from pool: {}",
@@ -40,6 +50,59 @@ pub(crate) async fn get_pantry_address(
     Ok(client.address())
 }
 
+async fn least_loaded_pantry_address(nexus: &Nexus) -> Option<SocketAddrV6> {
+    let log = &nexus.log;
+    let mut addresses = match nexus
+        .resolver()
+        .lookup_all_socket_v6(ServiceName::CruciblePantry)
+        .await
+    {
+        Ok(addresses) => addresses,
+        Err(err) => {
+            warn!(
+                log, "failed to look up Crucible pantries in DNS";
+                InlineErrorChain::new(&err),
+            );
+            return None;
+        }
+    };
+    let load = match nexus
+        .datastore()
+        .crucible_pantry_load(&nexus.opctx_alloc)
+        .await
+    {
+        Ok(load) => load,
+        Err(err) => {
+            warn!(
+                log, "failed to fetch Crucible pantry load";
+                InlineErrorChain::new(&err),
+            );
+            return None;
+        }
+    };
+
+    // Shuffle before sorting so that equally-loaded pantries are picked
+    // evenly, rather than always picking the one with the lowest address.
+    addresses.shuffle(&mut rand::rng());
+    addresses.sort_by_key(|address| load.get(address).copied().unwrap_or(0));
+
+    for address in addresses {
+        let client =
+            crucible_pantry_client::Client::new(&format!("http://{address}"));
+        match client.pantry_status().await {
+            Ok(_) => return Some(address),
+            Err(err) => {
+                warn!(
+                    log, "skipping unhealthy Crucible pantry";
+                    "pantry_address" => %address,
+                    InlineErrorChain::new(&err),
+                );
+            }
+        }
+    }
+    None
+}
+
 // Helper function for attach/detach below: we retry as long as the pantry isn't
 // gone, and we detect "gone" by seeing whether the pantry address we've chosen
 // is still present when we resolve all the crucible pantry records in DNS.
@@ -701,7 +701,7 @@ async fn sdc_get_pantry_address(
     let datastore = osagactx.datastore();
     let disk_id = sagactx.lookup::<Uuid>("disk_id")?;
 
-    // Pick a random Pantry and use it for this disk. This will be the
+    // Pick the least loaded Pantry and use it for this disk. This will be the
     // Pantry used for all subsequent import operations until the disk
     // is "finalized".
     let pantry_address = get_pantry_address(osagactx.nexus()).await?;
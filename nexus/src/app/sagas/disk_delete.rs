@@ -224,6 +224,7 @@ pub(crate) mod test {
             )
             .await
             .expect("Failed to create disk")
+            .0
     }
 
     #[nexus_test(server = crate::Server)]
@@ -92,6 +92,7 @@ impl NexusSaga for SagaFinalizeDisk {
                     },
                     disk: params.disk_id.into(),
                 },
+                snapshot_schedule_id: None,
             };
 
             let subsaga_dag = {
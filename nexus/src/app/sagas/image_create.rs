@@ -263,6 +263,7 @@ async fn simc_create_image_record(
                 digest: None, // TODO
                 block_size: source_volume.block_size,
                 size: source_volume.size.into(),
+                state: db::model::ImageState::Active,
             }
         }
 
@@ -281,6 +282,7 @@ async fn simc_create_image_record(
                 digest: None,
                 block_size: source_volume.block_size,
                 size: source_volume.size.into(),
+                state: db::model::ImageState::Active,
             }
         }
     };
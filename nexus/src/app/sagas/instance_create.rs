@@ -98,6 +98,10 @@ declare_saga_actions! {
         + sic_associate_ssh_keys
         - sic_associate_ssh_keys_undo
     }
+    CREATE_TAGS -> "output" {
+        + sic_create_tags
+        - sic_create_tags_undo
+    }
     ADD_TO_ANTI_AFFINITY_GROUP -> "output" {
         + sic_add_to_anti_affinity_group
         // NOTE: Deleting the instance record deletes all anti-affinity group memberships.
@@ -119,9 +123,9 @@ declare_saga_actions! {
         + sic_attach_disk_to_instance
         - sic_attach_disk_to_instance_undo
     }
-    SET_BOOT_DISK -> "set_boot_disk" {
-        + sic_set_boot_disk
-        - sic_set_boot_disk_undo
+    SET_BOOT_ORDER -> "set_boot_order" {
+        + sic_set_boot_order
+        - sic_set_boot_order_undo
     }
     MOVE_TO_STOPPED -> "stopped_instance" {
         + sic_move_to_stopped
@@ -159,6 +163,8 @@ impl NexusSaga for SagaInstanceCreate {
 
         builder.append(associate_ssh_keys_action());
 
+        builder.append(create_tags_action());
+
         // Helper function for appending subsagas to our parent saga.
         fn subsaga_append<S: Serialize>(
             node_basename: String,
@@ -304,14 +310,10 @@ impl NexusSaga for SagaInstanceCreate {
         }
 
         // Build an iterator of all InstanceDiskAttachment entries in the
-        // request; these could either be a boot disk or data disks. As far as
+        // request. Whether or not they appear in the boot order, as far as
         // create/attach is concerned, they're all disks and all need to be
         // processed just the same.
-        let all_disks = params
-            .create_params
-            .boot_disk
-            .iter()
-            .chain(params.create_params.disks.iter());
+        let all_disks = params.create_params.disks.iter();
 
         // Appends the disk create saga as a subsaga directly to the instance
         // create builder.
@@ -361,7 +363,7 @@ impl NexusSaga for SagaInstanceCreate {
             )?;
         }
 
-        builder.append(set_boot_disk_action());
+        builder.append(set_boot_order_action());
         builder.append(move_to_stopped_action());
         Ok(builder.build()?)
     }
@@ -439,6 +441,48 @@ async fn sic_associate_ssh_keys_undo(
     Ok(())
 }
 
+async fn sic_create_tags(
+    sagactx: NexusActionContext,
+) -> Result<(), ActionError> {
+    let osagactx = sagactx.user_data();
+    let datastore = osagactx.datastore();
+    let saga_params = sagactx.saga_params::<Params>()?;
+
+    let opctx = crate::context::op_context_for_saga_action(
+        &sagactx,
+        &saga_params.serialized_authn,
+    );
+    let instance_id = sagactx.lookup::<InstanceUuid>("instance_id")?;
+    datastore
+        .instance_tags_insert(
+            &opctx,
+            instance_id,
+            &saga_params.create_params.tags,
+        )
+        .await
+        .map_err(ActionError::action_failed)?;
+    Ok(())
+}
+
+async fn sic_create_tags_undo(
+    sagactx: NexusActionContext,
+) -> Result<(), anyhow::Error> {
+    let osagactx = sagactx.user_data();
+    let datastore = osagactx.datastore();
+    let saga_params = sagactx.saga_params::<Params>()?;
+
+    let opctx = crate::context::op_context_for_saga_action(
+        &sagactx,
+        &saga_params.serialized_authn,
+    );
+    let instance_id = sagactx.lookup::<InstanceUuid>("instance_id")?;
+    datastore
+        .instance_tags_delete(&opctx, instance_id)
+        .await
+        .map_err(ActionError::action_failed)?;
+    Ok(())
+}
+
 async fn sic_add_to_anti_affinity_group(
     sagactx: NexusActionContext,
 ) -> Result<(), ActionError> {
@@ -1117,13 +1161,13 @@ async fn sic_delete_instance_record(
 }
 
 // This is done intentionally late in instance creation:
-// * if the boot disk is provided by name and that disk is created along with
-//   the disk, there would not have been an ID to use any earlier
-// * if the boot disk is pre-existing, we still must wait for `disk-attach`
-//   subsagas to complete; attempting to set the boot disk earlier would error
-//   out because the desired boot disk is not attached.
-/// Set the instance's boot disk, if one was specified.
-async fn sic_set_boot_disk(
+// * if a boot disk is provided by name and that disk is created along with
+//   the instance, there would not have been an ID to use any earlier
+// * if a boot disk is pre-existing, we still must wait for `disk-attach`
+//   subsagas to complete; attempting to set the boot order earlier would error
+//   out because the desired boot disks are not attached.
+/// Set the instance's boot order, if one was specified.
+async fn sic_set_boot_order(
     sagactx: NexusActionContext,
 ) -> Result<(), ActionError> {
     let osagactx = sagactx.user_data();
@@ -1134,13 +1178,9 @@ async fn sic_set_boot_disk(
         &params.serialized_authn,
     );
 
-    // TODO: instead of taking this from create_params, if this is a name, take
-    // it from the ID we get when creating the named disk.
-    let Some(boot_disk) =
-        params.create_params.boot_disk.as_ref().map(|x| x.name())
-    else {
+    if params.create_params.boot_order.is_empty() {
         return Ok(());
-    };
+    }
 
     let instance_id = sagactx.lookup::<InstanceUuid>("instance_id")?;
 
@@ -1150,22 +1190,37 @@ async fn sic_set_boot_disk(
         .await
         .map_err(ActionError::action_failed)?;
 
-    let (.., authz_disk) = LookupPath::new(&opctx, datastore)
-        .project_id(params.project_id)
-        .disk_name_owned(boot_disk.into())
-        .lookup_for(authz::Action::Read)
-        .await
+    let mut boot_order =
+        Vec::with_capacity(params.create_params.boot_order.len());
+    for disk in &params.create_params.boot_order {
+        let (.., authz_disk) = match disk {
+            NameOrId::Name(name) => {
+                LookupPath::new(&opctx, datastore)
+                    .project_id(params.project_id)
+                    .disk_name_owned(name.clone().into())
+                    .lookup_for(authz::Action::Read)
+                    .await
+            }
+            NameOrId::Id(id) => {
+                LookupPath::new(&opctx, datastore)
+                    .disk_id(*id)
+                    .lookup_for(authz::Action::Read)
+                    .await
+            }
+        }
         .map_err(ActionError::action_failed)?;
+        boot_order.push(authz_disk.id());
+    }
 
     datastore
-        .instance_set_boot_disk(&opctx, &authz_instance, Some(authz_disk.id()))
+        .instance_set_boot_order(&opctx, &authz_instance, boot_order)
         .await
         .map_err(ActionError::action_failed)?;
 
     Ok(())
 }
 
-async fn sic_set_boot_disk_undo(
+async fn sic_set_boot_order_undo(
     sagactx: NexusActionContext,
 ) -> Result<(), anyhow::Error> {
     let osagactx = sagactx.user_data();
@@ -1184,10 +1239,10 @@ async fn sic_set_boot_disk_undo(
         .await
         .map_err(ActionError::action_failed)?;
 
-    // If there was a boot disk, clear it. If there was not a boot disk,
+    // If there was a boot order, clear it. If there was not a boot order,
     // this is a no-op.
     datastore
-        .instance_set_boot_disk(&opctx, &authz_instance, None)
+        .instance_set_boot_order(&opctx, &authz_instance, Vec::new())
         .await
         .map_err(ActionError::action_failed)?;
 
@@ -1293,15 +1348,19 @@ pub mod test {
                 external_ips: vec![params::ExternalIpCreate::Ephemeral {
                     pool: None,
                 }],
-                boot_disk: Some(params::InstanceDiskAttachment::Attach(
+                boot_order: vec![DISK_NAME.parse().unwrap()],
+                disks: vec![params::InstanceDiskAttachment::Attach(
                     params::InstanceDiskAttach {
                         name: DISK_NAME.parse().unwrap(),
                     },
-                )),
-                disks: Vec::new(),
+                )],
                 start: false,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: [("role".to_string(), "test".to_string())]
+                    .into_iter()
+                    .collect(),
+                stopped_reservation_policy: Default::default(),
             },
             boundary_switches: HashSet::from([SwitchLocation::Switch0]),
         }
@@ -1342,6 +1401,21 @@ pub mod test {
             .is_none()
     }
 
+    async fn no_instance_tag_records_exist(datastore: &DataStore) -> bool {
+        use nexus_db_queries::db::model::InstanceTag;
+        use nexus_db_schema::schema::instance_tag::dsl;
+
+        dsl::instance_tag
+            .select(InstanceTag::as_select())
+            .first_async::<InstanceTag>(
+                &*datastore.pool_connection_for_tests().await.unwrap(),
+            )
+            .await
+            .optional()
+            .unwrap()
+            .is_none()
+    }
+
     async fn no_network_interface_records_exist(datastore: &DataStore) -> bool {
         use nexus_db_queries::db::model::NetworkInterface;
         use nexus_db_queries::db::model::NetworkInterfaceKind;
@@ -1407,6 +1481,7 @@ pub mod test {
 
         // Check that no partial artifacts of instance creation exist
         assert!(no_instance_records_exist(datastore).await);
+        assert!(no_instance_tag_records_exist(datastore).await);
         assert!(no_network_interface_records_exist(datastore).await);
         assert!(no_external_ip_records_exist(datastore).await);
         assert!(
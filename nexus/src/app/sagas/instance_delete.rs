@@ -11,6 +11,8 @@ use crate::app::sagas::declare_saga_actions;
 use nexus_db_lookup::LookupPath;
 use nexus_db_queries::{authn, authz, db};
 use omicron_common::api::internal::shared::SwitchLocation;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
 use serde::Deserialize;
 use serde::Serialize;
 use steno::ActionError;
@@ -42,6 +44,9 @@ declare_saga_actions! {
     INSTANCE_DELETE_NAT -> "no_result4" {
         + sid_delete_nat
     }
+    RELEASE_HELD_SLED_RESERVATION -> "no_result5" {
+        + sid_release_held_sled_reservation
+    }
 }
 
 // instance delete saga: definition
@@ -64,6 +69,7 @@ impl NexusSaga for SagaInstanceDelete {
         builder.append(instance_delete_record_action());
         builder.append(delete_network_interfaces_action());
         builder.append(deallocate_external_ip_action());
+        builder.append(release_held_sled_reservation_action());
         Ok(builder.build()?)
     }
 }
@@ -157,6 +163,28 @@ async fn sid_deallocate_external_ip(
     Ok(())
 }
 
+async fn sid_release_held_sled_reservation(
+    sagactx: NexusActionContext,
+) -> Result<(), ActionError> {
+    let osagactx = sagactx.user_data();
+    let params = sagactx.saga_params::<Params>()?;
+    let opctx = crate::context::op_context_for_saga_action(
+        &sagactx,
+        &params.serialized_authn,
+    );
+    // An instance whose stopped-reservation policy is `retain` may still hold
+    // a reservation on the sled it last ran on.
+    osagactx
+        .datastore()
+        .sled_reservation_release_held(
+            &opctx,
+            InstanceUuid::from_untyped_uuid(params.authz_instance.id()),
+        )
+        .await
+        .map_err(ActionError::action_failed)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
@@ -232,13 +260,15 @@ mod test {
             external_ips: vec![params::ExternalIpCreate::Ephemeral {
                 pool: None,
             }],
-            boot_disk: Some(params::InstanceDiskAttachment::Attach(
+            boot_order: vec![DISK_NAME.parse().unwrap()],
+            disks: vec![params::InstanceDiskAttachment::Attach(
                 params::InstanceDiskAttach { name: DISK_NAME.parse().unwrap() },
-            )),
-            disks: Vec::new(),
+            )],
             start: false,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         }
     }
 
@@ -611,10 +611,12 @@ mod tests {
                     params::InstanceNetworkInterfaceAttachment::None,
                 external_ips: vec![],
                 disks: vec![],
-                boot_disk: None,
+                boot_order: Vec::new(),
                 start: true,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             },
         )
         .await
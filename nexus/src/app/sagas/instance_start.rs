@@ -15,10 +15,10 @@ use crate::app::instance::{
     InstanceStateChangeError,
 };
 use crate::app::sagas::declare_saga_actions;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use nexus_db_lookup::LookupPath;
 use nexus_db_queries::db::identity::Resource;
-use nexus_db_queries::{authn, authz, db};
+use nexus_db_queries::{authn, authz, context, db};
 use omicron_common::api::external::Error;
 use omicron_uuid_kinds::{GenericUuid, InstanceUuid, PropolisUuid, SledUuid};
 use serde::{Deserialize, Serialize};
@@ -52,6 +52,9 @@ pub(crate) enum Reason {
     /// The instance has failed and is being automatically restarted by the
     /// control plane.
     AutoRestart,
+    /// The instance was stopped so that the sled it was running on could be
+    /// rebooted, and is being started again now that the sled is back.
+    SledRebooted,
 }
 
 declare_saga_actions! {
@@ -247,8 +250,32 @@ async fn sis_destroy_vmm_record(
     // Now that the VMM record has been marked as `SagaUnwound`, the instance
     // may be permitted to reincarnate. If it is, activate the instance
     // reincarnation background task to help it along.
-    let karmic_status =
-        db_instance.auto_restart.can_reincarnate(db_instance.runtime());
+    //
+    // The instance inherits its project's auto-restart defaults. If we can't
+    // fetch them, fall back to the fleet-wide defaults rather than failing to
+    // unwind: the reincarnation task decides for itself which instances may
+    // be reincarnated, so this only determines whether we nudge it along.
+    let project_defaults = match LookupPath::new(&opctx, osagactx.datastore())
+        .project_id(db_instance.project_id)
+        .fetch()
+        .await
+    {
+        Ok((.., project)) => project.auto_restart_defaults,
+        Err(error) => {
+            warn!(
+                osagactx.log(),
+                "start saga unwound; failed to fetch instance's project";
+                "instance_id" => %db_instance.id(),
+                "error" => %error,
+            );
+            db::model::ProjectAutoRestartDefaults::default()
+        }
+    };
+    let karmic_status = db_instance.auto_restart.can_reincarnate(
+        &project_defaults,
+        db_instance.runtime(),
+        Utc::now(),
+    );
     if karmic_status == db::model::Reincarnatability::WillReincarnate {
         info!(
             osagactx.log(),
@@ -321,6 +348,20 @@ async fn sis_move_to_starting(
                   "instance_id" => %instance_id,
                   "start_reason" => ?params.reason);
 
+            // A previous attempt at this step may have failed after updating
+            // the instance but before recording the restart.
+            if let Some(time_restarted) =
+                db_instance.runtime().time_last_auto_restarted
+            {
+                sis_record_auto_restart(
+                    datastore,
+                    &opctx,
+                    &params,
+                    propolis_id,
+                    time_restarted,
+                )
+                .await?;
+            }
             return Ok(db_instance.clone());
         }
 
@@ -378,6 +419,17 @@ async fn sis_move_to_starting(
         )));
     }
 
+    if let Some(time_restarted) = new_runtime.time_last_auto_restarted {
+        sis_record_auto_restart(
+            datastore,
+            &opctx,
+            &params,
+            propolis_id,
+            time_restarted,
+        )
+        .await?;
+    }
+
     // Don't fear the reaper!
     if abandoned_unwound_vmm {
         osagactx.nexus().background_tasks.task_abandoned_vmm_reaper.activate();
@@ -388,6 +440,42 @@ async fn sis_move_to_starting(
     Ok(new_record)
 }
 
+/// Records an automatic restart of the instance in its auto-restart history.
+///
+/// This does nothing unless the saga is automatically restarting the instance,
+/// and is idempotent, as an event is only recorded once for each VMM.
+async fn sis_record_auto_restart(
+    datastore: &db::DataStore,
+    opctx: &context::OpContext,
+    params: &Params,
+    propolis_id: PropolisUuid,
+    time_restarted: DateTime<Utc>,
+) -> Result<(), ActionError> {
+    if params.reason != Reason::AutoRestart {
+        return Ok(());
+    }
+
+    // Instances are only reincarnated if they have failed, or if a previous
+    // start saga left behind an unwound VMM.
+    let reason = if params.db_instance.runtime().nexus_state
+        == db::model::InstanceState::Failed
+    {
+        db::model::InstanceAutoRestartReason::Failed
+    } else {
+        db::model::InstanceAutoRestartReason::StartFailed
+    };
+    let event = db::model::InstanceAutoRestartEvent::new(
+        InstanceUuid::from_untyped_uuid(params.db_instance.id()),
+        propolis_id,
+        time_restarted,
+        reason,
+    );
+    datastore
+        .instance_auto_restart_event_insert(opctx, event)
+        .await
+        .map_err(ActionError::action_failed)
+}
+
 async fn sis_move_to_starting_undo(
     sagactx: NexusActionContext,
 ) -> Result<(), anyhow::Error> {
@@ -842,10 +930,12 @@ mod test {
                     params::InstanceNetworkInterfaceAttachment::None,
                 external_ips: vec![],
                 disks: vec![],
-                boot_disk: None,
+                boot_order: Vec::new(),
                 start: false,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             },
         )
         .await
@@ -28,6 +28,12 @@ pub(super) struct Params {
 
     /// UUID of the VMM to destroy.
     pub(super) vmm_id: PropolisUuid,
+
+    /// If `true`, the instance has stopped and its stopped-reservation policy
+    /// asks that it keep this VMM's sled resource reservation, so the
+    /// reservation must not be released.
+    #[serde(default)]
+    pub(super) retain_reservation: bool,
 }
 
 // destroy VMM subsaga: actions
@@ -73,8 +79,23 @@ async fn siu_destroyed_release_sled_resources(
     sagactx: NexusActionContext,
 ) -> Result<(), ActionError> {
     let osagactx = sagactx.user_data();
-    let Params { ref serialized_authn, instance_id, vmm_id, .. } =
-        sagactx.saga_params::<Params>()?;
+    let Params {
+        ref serialized_authn,
+        instance_id,
+        vmm_id,
+        retain_reservation,
+    } = sagactx.saga_params::<Params>()?;
+
+    if retain_reservation {
+        info!(
+            osagactx.log(),
+            "instance update (VMM destroyed): keeping sled resource \
+             reservation for stopped instance";
+            "instance_id" => %instance_id,
+            "propolis_id" => %vmm_id,
+        );
+        return Ok(());
+    }
 
     let opctx =
         crate::context::op_context_for_saga_action(&sagactx, serialized_authn);
@@ -352,6 +352,7 @@ use crate::app::db::model::Generation;
 use crate::app::db::model::InstanceIntendedState;
 use crate::app::db::model::InstanceRuntimeState;
 use crate::app::db::model::InstanceState;
+use crate::app::db::model::InstanceStoppedReservationPolicy;
 use crate::app::db::model::MigrationState;
 use crate::app::db::model::Vmm;
 use crate::app::db::model::VmmState;
@@ -479,6 +480,12 @@ struct UpdatesRequired {
     /// cleaned up by a [`destroyed`] subsaga.
     destroy_target_vmm: Option<PropolisUuid>,
 
+    /// If `true`, the instance stopped cleanly and its stopped-reservation
+    /// policy asks that it keep its sled resource reservation, so the
+    /// [`destroyed`] subsaga for `destroy_active_vmm` must not release it.
+    #[serde(default)]
+    retain_active_vmm_reservation: bool,
+
     /// If this is [`Some`], the instance no longer has an active VMM, and its
     /// virtual provisioning resource records and Oximeter producer should be
     /// deallocated.
@@ -727,6 +734,16 @@ impl UpdatesRequired {
             None
         };
 
+        // An instance that stopped on its own sled (rather than failing, or
+        // stopping in the middle of a migration) may keep its reservation on
+        // that sled, if it's been asked to.
+        let retain_active_vmm_reservation = destroy_active_vmm.is_some()
+            && deprovision.is_some()
+            && !active_vmm_failed
+            && snapshot.migration.is_none()
+            && snapshot.instance.stopped_reservation_policy
+                == InstanceStoppedReservationPolicy::Retain;
+
         if !update_required {
             return None;
         }
@@ -736,6 +753,7 @@ impl UpdatesRequired {
             new_runtime,
             destroy_active_vmm,
             destroy_target_vmm,
+            retain_active_vmm_reservation,
             deprovision,
             network_config,
         })
@@ -908,13 +926,16 @@ impl NexusSaga for SagaDoActualInstanceUpdate {
         // deleted until they've been unlinked from the instance by the
         // `update_and_unlock_instance` action.
         let mut append_destroyed_vmm_subsaga =
-            |vmm_id: PropolisUuid, which_vmm: &'static str| {
+            |vmm_id: PropolisUuid,
+             which_vmm: &'static str,
+             retain_reservation: bool| {
                 let params = destroyed::Params {
                     vmm_id,
                     instance_id: InstanceUuid::from_untyped_uuid(
                         params.authz_instance.id(),
                     ),
                     serialized_authn: params.serialized_authn.clone(),
+                    retain_reservation,
                 };
                 let name = format!("destroy_{which_vmm}_vmm");
 
@@ -937,11 +958,15 @@ impl NexusSaga for SagaDoActualInstanceUpdate {
             };
 
         if let Some(vmm_id) = params.update.destroy_active_vmm {
-            append_destroyed_vmm_subsaga(vmm_id, "active")?;
+            append_destroyed_vmm_subsaga(
+                vmm_id,
+                "active",
+                params.update.retain_active_vmm_reservation,
+            )?;
         }
 
         if let Some(vmm_id) = params.update.destroy_target_vmm {
-            append_destroyed_vmm_subsaga(vmm_id, "target")?;
+            append_destroyed_vmm_subsaga(vmm_id, "target", false)?;
         }
 
         // Finally, check if any additional updates are required to reconcile
@@ -1311,10 +1336,18 @@ async fn siu_chain_successor_saga(
             // additional update saga is required, check if the instance's
             // auto-restart policy allows it to be automatically restarted. If
             // it does, activate the instance-reincarnation background task to
-            // automatically restart it.
-            let karmic_state = new_state
-                .instance
-                .auto_restart_status(new_state.active_vmm.as_ref());
+            // automatically restart it. The instance inherits its project's
+            // auto-restart defaults, so we need those too.
+            let (.., project) = LookupPath::new(&opctx, osagactx.datastore())
+                .project_id(new_state.instance.project_id)
+                .fetch()
+                .await
+                .context("failed to fetch instance's project")?;
+            let karmic_state = new_state.instance.auto_restart_status(
+                &project.auto_restart_defaults,
+                new_state.active_vmm.as_ref(),
+                Utc::now(),
+            );
             if karmic_state.should_reincarnate() {
                 info!(
                     log,
@@ -1576,10 +1609,12 @@ mod test {
                     params::InstanceNetworkInterfaceAttachment::None,
                 external_ips: vec![],
                 disks: vec![],
-                boot_disk: None,
+                boot_order: Vec::new(),
                 start: true,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             },
         )
         .await
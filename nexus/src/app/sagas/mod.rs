@@ -47,6 +47,8 @@ pub mod region_snapshot_replacement_step;
 pub mod region_snapshot_replacement_step_garbage_collect;
 pub mod snapshot_create;
 pub mod snapshot_delete;
+pub mod snapshot_export_create;
+pub mod snapshot_export_delete;
 pub mod test_saga;
 pub mod volume_delete;
 pub mod volume_remove_rop;
@@ -169,6 +171,8 @@ fn make_action_registry() -> ActionRegistry {
         project_create::SagaProjectCreate,
         snapshot_create::SagaSnapshotCreate,
         snapshot_delete::SagaSnapshotDelete,
+        snapshot_export_create::SagaSnapshotExportCreate,
+        snapshot_export_delete::SagaSnapshotExportDelete,
         volume_delete::SagaVolumeDelete,
         volume_remove_rop::SagaVolumeRemoveROP,
         vpc_create::SagaVpcCreate,
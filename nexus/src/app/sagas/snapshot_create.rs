@@ -83,8 +83,8 @@
 //! to an instance, there's no Upstairs to send the snapshot request to. The
 //! Crucible Pantry is a service that will be launched on each Sled and will be
 //! used for these types of maintenance tasks. In this case this saga will
-//! attach the volume "to" a random Pantry by sending a volume construction
-//! request, then send a snapshot request, then detach "from" that random
+//! attach the volume "to" the least loaded Pantry by sending a volume
+//! construction request, then send a snapshot request, then detach "from" that
 //! Pantry. Most of the rest of the saga is unchanged.
 //!
 
@@ -138,6 +138,9 @@ pub(crate) struct Params {
     pub attach_instance_id: Option<Uuid>,
     pub use_the_pantry: bool,
     pub create_params: params::SnapshotCreate,
+    /// The snapshot schedule on whose behalf this snapshot is being taken,
+    /// if any
+    pub snapshot_schedule_id: Option<Uuid>,
 }
 
 // snapshot create saga: actions
@@ -683,6 +686,8 @@ async fn ssc_create_snapshot_record(
         state: db::model::SnapshotState::Creating,
         block_size: disk.block_size,
         size: disk.size,
+        delete_protected: false,
+        snapshot_schedule_id: params.snapshot_schedule_id,
     };
 
     let (.., authz_project) = LookupPath::new(&opctx, osagactx.datastore())
@@ -925,9 +930,9 @@ async fn ssc_get_pantry_address(
         &params.serialized_authn,
     );
 
-    // If the disk is already attached to a Pantry, use that, otherwise get a
-    // random one. Return boolean indicating if additional saga nodes need to
-    // attach this disk to that random pantry.
+    // If the disk is already attached to a Pantry, use that, otherwise pick
+    // one. Return boolean indicating if additional saga nodes need to attach
+    // this disk to that pantry.
     let (.., disk) = LookupPath::new(&opctx, osagactx.datastore())
         .disk_id(params.disk_id)
         .fetch()
@@ -2027,6 +2032,7 @@ mod test {
                 },
                 disk,
             },
+            snapshot_schedule_id: None,
         }
     }
 
@@ -2148,9 +2154,12 @@ mod test {
     ) -> InstanceAndActiveVmm {
         let instances_url = format!("/v1/instances?project={}", PROJECT_NAME,);
 
-        let mut disks_iter = disks_to_attach.into_iter();
-        let boot_disk = disks_iter.next();
-        let data_disks: Vec<InstanceDiskAttachment> = disks_iter.collect();
+        // Boot from the first of the supplied disks.
+        let boot_order = disks_to_attach
+            .first()
+            .map(|disk| NameOrId::Name(disk.name()))
+            .into_iter()
+            .collect();
 
         let instance: Instance = object_create(
             client,
@@ -2169,12 +2178,14 @@ mod test {
                 ssh_public_keys:  Some(Vec::new()),
                 network_interfaces:
                     params::InstanceNetworkInterfaceAttachment::None,
-                boot_disk,
-                disks: data_disks,
+                boot_order,
+                disks: disks_to_attach,
                 external_ips: vec![],
                 start: true,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             },
         )
         .await;
@@ -0,0 +1,488 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Export a snapshot so that its contents can be downloaded
+//!
+//! Exporting a snapshot creates a read-only copy of the snapshot's volume and
+//! attaches it to a Crucible Pantry. Downloads of the export are then served
+//! by reading from that Pantry. Because the copy holds its own references to
+//! the snapshot's regions, the snapshot itself may be deleted while the export
+//! exists.
+
+use super::{
+    ACTION_GENERATE_ID, ActionRegistry, NexusActionContext, NexusSaga,
+    SagaInitError,
+    common_storage::{
+        call_pantry_attach_for_volume, call_pantry_detach, get_pantry_address,
+    },
+};
+use crate::app::sagas::declare_saga_actions;
+use crate::app::{authn, authz, db};
+use nexus_db_queries::db::identity::Resource;
+use nexus_types::external_api::views;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::LookupType;
+use omicron_common::progenitor_operation_retry::ProgenitorOperationRetryError;
+use omicron_uuid_kinds::VolumeUuid;
+use serde::Deserialize;
+use serde::Serialize;
+use sled_agent_client::VolumeConstructionRequest;
+use slog_error_chain::InlineErrorChain;
+use std::net::SocketAddrV6;
+use steno::ActionError;
+use steno::Node;
+use uuid::Uuid;
+
+// snapshot export create saga: input parameters
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Params {
+    pub serialized_authn: authn::saga::Serialized,
+    pub authz_project: authz::Project,
+    pub snapshot: db::model::Snapshot,
+    /// The instance whose boot disk is being exported, if any
+    pub instance_id: Option<Uuid>,
+    pub manifest: views::ExportManifest,
+}
+
+// snapshot export create saga: actions
+
+declare_saga_actions! {
+    snapshot_export_create;
+    GET_PANTRY_ADDRESS -> "pantry_address" {
+        + secr_get_pantry_address
+    }
+    COPY_SNAPSHOT_VOLUME -> "no_result1" {
+        + secr_copy_snapshot_volume
+        - secr_copy_snapshot_volume_undo
+    }
+    CREATE_EXPORT_RECORD -> "created_export" {
+        + secr_create_export_record
+        - secr_create_export_record_undo
+    }
+    CALL_PANTRY_ATTACH -> "no_result2" {
+        + secr_call_pantry_attach
+        - secr_call_pantry_attach_undo
+    }
+    FINALIZE_EXPORT_RECORD -> "finalized_export" {
+        + secr_finalize_export_record
+    }
+}
+
+// snapshot export create saga: definition
+
+#[derive(Debug)]
+pub(crate) struct SagaSnapshotExportCreate;
+impl NexusSaga for SagaSnapshotExportCreate {
+    const NAME: &'static str = "snapshot-export-create";
+    type Params = Params;
+
+    fn register_actions(registry: &mut ActionRegistry) {
+        snapshot_export_create_register_actions(registry);
+    }
+
+    fn make_saga_dag(
+        _params: &Self::Params,
+        mut builder: steno::DagBuilder,
+    ) -> Result<steno::Dag, SagaInitError> {
+        builder.append(Node::action(
+            "export_id",
+            "GenerateExportId",
+            ACTION_GENERATE_ID.as_ref(),
+        ));
+
+        builder.append(Node::action(
+            "volume_id",
+            "GenerateVolumeId",
+            ACTION_GENERATE_ID.as_ref(),
+        ));
+
+        builder.append(get_pantry_address_action());
+        builder.append(copy_snapshot_volume_action());
+        builder.append(create_export_record_action());
+        builder.append(call_pantry_attach_action());
+        builder.append(finalize_export_record_action());
+
+        Ok(builder.build()?)
+    }
+}
+
+// snapshot export create saga: action implementations
+
+fn authz_export(params: &Params, export_id: Uuid) -> authz::SnapshotExport {
+    authz::SnapshotExport::new(
+        params.authz_project.clone(),
+        export_id,
+        LookupType::ById(export_id),
+    )
+}
+
+async fn secr_get_pantry_address(
+    sagactx: NexusActionContext,
+) -> Result<SocketAddrV6, ActionError> {
+    let log = sagactx.user_data().log();
+    let osagactx = sagactx.user_data();
+    let export_id = sagactx.lookup::<Uuid>("export_id")?;
+
+    let pantry_address = get_pantry_address(osagactx.nexus()).await?;
+
+    info!(
+        log,
+        "using pantry at {} for snapshot export {}", pantry_address, export_id
+    );
+
+    Ok(pantry_address)
+}
+
+async fn secr_copy_snapshot_volume(
+    sagactx: NexusActionContext,
+) -> Result<(), ActionError> {
+    let osagactx = sagactx.user_data();
+    let params = sagactx.saga_params::<Params>()?;
+    let volume_id = sagactx.lookup::<VolumeUuid>("volume_id")?;
+
+    // Copy the Volume data for this snapshot with randomized ids - this is
+    // safe because the snapshot is read-only, and even though volume_checkout
+    // will bump the gen numbers multiple Upstairs can connect to read-only
+    // downstairs without kicking each other out.
+    osagactx
+        .datastore()
+        .volume_checkout_randomize_ids(
+            db::datastore::SourceVolume(params.snapshot.volume_id()),
+            db::datastore::DestVolume(volume_id),
+            db::datastore::VolumeCheckoutReason::ReadOnlyCopy,
+        )
+        .await
+        .map_err(ActionError::action_failed)?;
+
+    Ok(())
+}
+
+async fn secr_copy_snapshot_volume_undo(
+    sagactx: NexusActionContext,
+) -> Result<(), anyhow::Error> {
+    let osagactx = sagactx.user_data();
+    let volume_id = sagactx.lookup::<VolumeUuid>("volume_id")?;
+
+    osagactx.datastore().soft_delete_volume(volume_id).await?;
+
+    Ok(())
+}
+
+async fn secr_create_export_record(
+    sagactx: NexusActionContext,
+) -> Result<db::model::SnapshotExport, ActionError> {
+    let osagactx = sagactx.user_data();
+    let params = sagactx.saga_params::<Params>()?;
+    let opctx = crate::context::op_context_for_saga_action(
+        &sagactx,
+        &params.serialized_authn,
+    );
+
+    let export_id = sagactx.lookup::<Uuid>("export_id")?;
+    let volume_id = sagactx.lookup::<VolumeUuid>("volume_id")?;
+
+    let export = db::model::SnapshotExport::new(
+        export_id,
+        params.authz_project.id(),
+        params.snapshot.id(),
+        params.instance_id,
+        volume_id,
+        params.snapshot.block_size,
+        params.snapshot.size,
+        &params.manifest,
+    )
+    .map_err(ActionError::action_failed)?;
+
+    osagactx
+        .datastore()
+        .snapshot_export_create(&opctx, &params.authz_project, export)
+        .await
+        .map_err(ActionError::action_failed)
+}
+
+async fn secr_create_export_record_undo(
+    sagactx: NexusActionContext,
+) -> Result<(), anyhow::Error> {
+    let osagactx = sagactx.user_data();
+    let params = sagactx.saga_params::<Params>()?;
+    let opctx = crate::context::op_context_for_saga_action(
+        &sagactx,
+        &params.serialized_authn,
+    );
+
+    let export_id = sagactx.lookup::<Uuid>("export_id")?;
+
+    osagactx
+        .datastore()
+        .snapshot_export_delete(&opctx, &authz_export(&params, export_id))
+        .await?;
+
+    Ok(())
+}
+
+async fn secr_call_pantry_attach(
+    sagactx: NexusActionContext,
+) -> Result<(), ActionError> {
+    let log = sagactx.user_data().log();
+    let osagactx = sagactx.user_data();
+    let export_id = sagactx.lookup::<Uuid>("export_id")?;
+    let volume_id = sagactx.lookup::<VolumeUuid>("volume_id")?;
+    let pantry_address = sagactx.lookup::<SocketAddrV6>("pantry_address")?;
+
+    let volume = osagactx
+        .datastore()
+        .volume_get(volume_id)
+        .await
+        .map_err(ActionError::action_failed)?
+        .ok_or_else(|| {
+            ActionError::action_failed(Error::internal_error(&format!(
+                "volume {volume_id} for snapshot export {export_id} is gone!"
+            )))
+        })?;
+
+    let volume_construction_request: VolumeConstructionRequest =
+        serde_json::from_str(volume.data()).map_err(|e| {
+            ActionError::action_failed(Error::internal_error(&format!(
+                "failed to deserialize volume {volume_id} data: {e}"
+            )))
+        })?;
+
+    call_pantry_attach_for_volume(
+        &log,
+        osagactx.nexus(),
+        export_id,
+        volume_construction_request,
+        pantry_address,
+    )
+    .await
+}
+
+async fn secr_call_pantry_attach_undo(
+    sagactx: NexusActionContext,
+) -> Result<(), anyhow::Error> {
+    let log = sagactx.user_data().log();
+    let export_id = sagactx.lookup::<Uuid>("export_id")?;
+    let pantry_address = sagactx.lookup::<SocketAddrV6>("pantry_address")?;
+
+    match call_pantry_detach(
+        sagactx.user_data().nexus(),
+        &log,
+        export_id,
+        pantry_address,
+    )
+    .await
+    {
+        // We can treat the pantry being permanently gone as success.
+        Ok(()) | Err(ProgenitorOperationRetryError::Gone) => Ok(()),
+        Err(err) => Err(anyhow::anyhow!(
+            "failed to detach snapshot export {} from pantry at {}: {}",
+            export_id,
+            pantry_address,
+            InlineErrorChain::new(&err)
+        )),
+    }
+}
+
+async fn secr_finalize_export_record(
+    sagactx: NexusActionContext,
+) -> Result<db::model::SnapshotExport, ActionError> {
+    let osagactx = sagactx.user_data();
+    let params = sagactx.saga_params::<Params>()?;
+    let opctx = crate::context::op_context_for_saga_action(
+        &sagactx,
+        &params.serialized_authn,
+    );
+
+    let export_id = sagactx.lookup::<Uuid>("export_id")?;
+    let pantry_address = sagactx.lookup::<SocketAddrV6>("pantry_address")?;
+
+    osagactx
+        .datastore()
+        .snapshot_export_set_pantry_address(
+            &opctx,
+            &authz_export(&params, export_id),
+            pantry_address,
+        )
+        .await
+        .map_err(ActionError::action_failed)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::app::saga::create_saga_dag;
+    use async_bb8_diesel::AsyncRunQueryDsl;
+    use diesel::{
+        ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+    };
+    use nexus_db_lookup::LookupPath;
+    use nexus_db_queries::authn::saga::Serialized;
+    use nexus_db_queries::context::OpContext;
+    use nexus_db_queries::db::datastore::DataStore;
+    use nexus_test_utils::resource_helpers::create_disk;
+    use nexus_test_utils::resource_helpers::create_project;
+    use nexus_test_utils::resource_helpers::create_snapshot;
+    use nexus_test_utils_macros::nexus_test;
+
+    type ControlPlaneTestContext =
+        nexus_test_utils::ControlPlaneTestContext<crate::Server>;
+    type DiskTest<'a> =
+        nexus_test_utils::resource_helpers::DiskTest<'a, crate::Server>;
+
+    const DISK_NAME: &str = "my-disk";
+    const PROJECT_NAME: &str = "springfield-squidport";
+    const SNAPSHOT_NAME: &str = "my-snapshot";
+
+    fn test_opctx(cptestctx: &ControlPlaneTestContext) -> OpContext {
+        OpContext::for_tests(
+            cptestctx.logctx.log.new(o!()),
+            cptestctx.server.server_context().nexus.datastore().clone(),
+        )
+    }
+
+    async fn new_test_params(
+        cptestctx: &ControlPlaneTestContext,
+        snapshot_id: Uuid,
+    ) -> Params {
+        let opctx = test_opctx(cptestctx);
+        let datastore = cptestctx.server.server_context().nexus.datastore();
+
+        let (.., authz_project, _authz_snapshot, snapshot) =
+            LookupPath::new(&opctx, datastore)
+                .snapshot_id(snapshot_id)
+                .fetch()
+                .await
+                .unwrap();
+
+        let manifest =
+            crate::app::snapshot_export::export_manifest(&snapshot, None);
+
+        Params {
+            serialized_authn: Serialized::for_opctx(&opctx),
+            authz_project,
+            snapshot,
+            instance_id: None,
+            manifest,
+        }
+    }
+
+    async fn create_test_snapshot(cptestctx: &ControlPlaneTestContext) -> Uuid {
+        let client = &cptestctx.external_client;
+        create_project(&client, PROJECT_NAME).await;
+        create_disk(&client, PROJECT_NAME, DISK_NAME).await;
+        create_snapshot(&client, PROJECT_NAME, DISK_NAME, SNAPSHOT_NAME)
+            .await
+            .identity
+            .id
+    }
+
+    async fn no_export_records_exist(datastore: &DataStore) -> bool {
+        use nexus_db_queries::db::model::SnapshotExport;
+        use nexus_db_schema::schema::snapshot_export::dsl;
+
+        dsl::snapshot_export
+            .filter(dsl::time_deleted.is_null())
+            .select(SnapshotExport::as_select())
+            .first_async::<SnapshotExport>(
+                &*datastore.pool_connection_for_tests().await.unwrap(),
+            )
+            .await
+            .optional()
+            .unwrap()
+            .is_none()
+    }
+
+    pub(crate) async fn verify_clean_slate(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        let datastore = cptestctx.server.server_context().nexus.datastore();
+
+        crate::app::sagas::test_helpers::assert_no_failed_undo_steps(
+            &cptestctx.logctx.log,
+            datastore,
+        )
+        .await;
+
+        assert!(no_export_records_exist(datastore).await);
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_saga_basic_usage_succeeds(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        DiskTest::new(cptestctx).await;
+
+        let nexus = &cptestctx.server.server_context().nexus;
+        let snapshot_id = create_test_snapshot(cptestctx).await;
+
+        let params = new_test_params(cptestctx, snapshot_id).await;
+        let output = nexus
+            .sagas
+            .saga_execute::<SagaSnapshotExportCreate>(params)
+            .await
+            .unwrap();
+
+        let export = output
+            .lookup_node_output::<db::model::SnapshotExport>("finalized_export")
+            .unwrap();
+        assert_eq!(export.snapshot_id, snapshot_id);
+        assert!(export.pantry_address().is_some());
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_action_failure_can_unwind(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        DiskTest::new(cptestctx).await;
+
+        let log = &cptestctx.logctx.log;
+        let nexus = &cptestctx.server.server_context().nexus;
+        let snapshot_id = create_test_snapshot(cptestctx).await;
+
+        crate::app::sagas::test_helpers::action_failure_can_unwind::<
+            SagaSnapshotExportCreate,
+            _,
+            _,
+        >(
+            nexus,
+            || {
+                Box::pin(async {
+                    new_test_params(&cptestctx, snapshot_id).await
+                })
+            },
+            || {
+                Box::pin(async {
+                    verify_clean_slate(&cptestctx).await;
+                })
+            },
+            log,
+        )
+        .await;
+    }
+
+    #[nexus_test(server = crate::Server)]
+    async fn test_actions_succeed_idempotently(
+        cptestctx: &ControlPlaneTestContext,
+    ) {
+        DiskTest::new(cptestctx).await;
+
+        let nexus = &cptestctx.server.server_context().nexus;
+        let snapshot_id = create_test_snapshot(cptestctx).await;
+
+        let params = new_test_params(cptestctx, snapshot_id).await;
+        let dag = create_saga_dag::<SagaSnapshotExportCreate>(params).unwrap();
+        crate::app::sagas::test_helpers::actions_succeed_idempotently(
+            nexus, dag,
+        )
+        .await;
+
+        assert!(
+            !no_export_records_exist(
+                cptestctx.server.server_context().nexus.datastore()
+            )
+            .await
+        );
+    }
+}
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::common_storage::call_pantry_detach;
+use super::{ActionRegistry, NexusActionContext, NexusSaga};
+use crate::app::sagas;
+use crate::app::sagas::declare_saga_actions;
+use nexus_db_queries::{authn, authz, db};
+use omicron_common::progenitor_operation_retry::ProgenitorOperationRetryError;
+use serde::Deserialize;
+use serde::Serialize;
+use slog_error_chain::InlineErrorChain;
+use steno::ActionError;
+use steno::Node;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Params {
+    pub serialized_authn: authn::saga::Serialized,
+    pub authz_export: authz::SnapshotExport,
+    pub export: db::model::SnapshotExport,
+}
+
+declare_saga_actions! {
+    snapshot_export_delete;
+    CALL_PANTRY_DETACH -> "no_result1" {
+        + sedr_call_pantry_detach
+    }
+    DELETE_EXPORT_RECORD -> "no_result2" {
+        + sedr_delete_export_record
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SagaSnapshotExportDelete;
+impl NexusSaga for SagaSnapshotExportDelete {
+    const NAME: &'static str = "snapshot-export-delete";
+    type Params = Params;
+
+    fn register_actions(registry: &mut ActionRegistry) {
+        snapshot_export_delete_register_actions(registry);
+    }
+
+    fn make_saga_dag(
+        params: &Self::Params,
+        mut builder: steno::DagBuilder,
+    ) -> Result<steno::Dag, super::SagaInitError> {
+        builder.append(call_pantry_detach_action());
+        builder.append(delete_export_record_action());
+
+        const DELETE_VOLUME_PARAMS: &'static str = "delete_volume_params";
+
+        let volume_delete_params = sagas::volume_delete::Params {
+            serialized_authn: params.serialized_authn.clone(),
+            volume_id: params.export.volume_id(),
+        };
+        builder.append(Node::constant(
+            DELETE_VOLUME_PARAMS,
+            serde_json::to_value(&volume_delete_params).map_err(|e| {
+                super::SagaInitError::SerializeError(
+                    String::from("volume_id"),
+                    e,
+                )
+            })?,
+        ));
+
+        let make_volume_delete_dag = || {
+            let subsaga_builder = steno::DagBuilder::new(steno::SagaName::new(
+                sagas::volume_delete::SagaVolumeDelete::NAME,
+            ));
+            sagas::volume_delete::create_dag(subsaga_builder)
+        };
+        builder.append(steno::Node::subsaga(
+            "delete_volume",
+            make_volume_delete_dag()?,
+            DELETE_VOLUME_PARAMS,
+        ));
+
+        Ok(builder.build()?)
+    }
+}
+
+// snapshot export delete saga: action implementations
+
+async fn sedr_call_pantry_detach(
+    sagactx: NexusActionContext,
+) -> Result<(), ActionError> {
+    let log = sagactx.user_data().log();
+    let params = sagactx.saga_params::<Params>()?;
+
+    // An export that was never attached to a Pantry has nothing to detach.
+    let Some(pantry_address) = params.export.pantry_address() else {
+        return Ok(());
+    };
+
+    match call_pantry_detach(
+        sagactx.user_data().nexus(),
+        &log,
+        params.export.id,
+        pantry_address,
+    )
+    .await
+    {
+        // We can treat the pantry being permanently gone as success.
+        Ok(()) | Err(ProgenitorOperationRetryError::Gone) => Ok(()),
+        Err(err) => Err(ActionError::action_failed(format!(
+            "pantry detach failed: {}",
+            InlineErrorChain::new(&err)
+        ))),
+    }
+}
+
+async fn sedr_delete_export_record(
+    sagactx: NexusActionContext,
+) -> Result<(), ActionError> {
+    let osagactx = sagactx.user_data();
+    let params = sagactx.saga_params::<Params>()?;
+    let opctx = crate::context::op_context_for_saga_action(
+        &sagactx,
+        &params.serialized_authn,
+    );
+
+    osagactx
+        .datastore()
+        .snapshot_export_delete(&opctx, &params.authz_export)
+        .await
+        .map_err(ActionError::action_failed)?;
+
+    Ok(())
+}
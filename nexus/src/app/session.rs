@@ -60,6 +60,9 @@ impl super::Nexus {
             .silo_user_id(db_session.silo_user_id())
             .fetch()
             .await?;
+        self.db_datastore
+            .break_glass_account_check(opctx, db_silo_user.id())
+            .await?;
 
         Ok(authn::ConsoleSessionWithSiloId {
             console_session: db_session,
@@ -103,6 +106,7 @@ impl super::Nexus {
                     Reason::UnknownActor { actor: silo_user_id.to_string() }
                 }
                 Error::ObjectAlreadyExists { .. }
+                | Error::DeleteProtected { .. }
                 | Error::InvalidRequest { .. }
                 | Error::Unauthenticated { .. }
                 | Error::InvalidValue { .. }
@@ -110,6 +114,7 @@ impl super::Nexus {
                 | Error::InternalError { .. }
                 | Error::ServiceUnavailable { .. }
                 | Error::InsufficientCapacity { .. }
+                | Error::InsufficientQuota { .. }
                 | Error::TypeVersionMismatch { .. }
                 | Error::Conflict { .. }
                 | Error::NotFound { .. }
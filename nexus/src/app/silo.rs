@@ -754,6 +754,9 @@ impl super::Nexus {
                 "passed password verification without a valid user"
             );
             let db_user = fetch_user.unwrap().1;
+            self.datastore()
+                .break_glass_account_check(opctx, db_user.id())
+                .await?;
             Ok(db_user)
         } else {
             Err(Error::Unauthenticated {
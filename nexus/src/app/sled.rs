@@ -6,19 +6,24 @@
 
 use crate::external_api::params;
 use crate::internal_api::params::{
-    PhysicalDiskPutRequest, SledAgentInfo, ZpoolPutRequest,
+    PhysicalDiskPutRequest, RollingSledRebootRequest, SledAgentInfo,
+    ZpoolPutRequest,
 };
 use nexus_db_lookup::LookupPath;
 use nexus_db_lookup::lookup;
 use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
+use nexus_db_queries::db::identity::Asset;
 use nexus_sled_agent_shared::inventory::SledRole;
 use nexus_types::deployment::DiskFilter;
 use nexus_types::deployment::SledFilter;
 use nexus_types::external_api::views::PhysicalDiskPolicy;
 use nexus_types::external_api::views::SledPolicy;
 use nexus_types::external_api::views::SledProvisionPolicy;
+use nexus_types::internal_api::views::InstancePlacementExplanation;
+use nexus_types::internal_api::views::RollingSledReboot;
+use nexus_types::internal_api::views::RollingSledRebootSled;
 use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::DataPageParams;
 use omicron_common::api::external::Error;
@@ -31,6 +36,8 @@ use omicron_uuid_kinds::PhysicalDiskUuid;
 use omicron_uuid_kinds::PropolisUuid;
 use omicron_uuid_kinds::SledUuid;
 use sled_agent_client::Client as SledAgentClient;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::net::SocketAddrV6;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -204,6 +211,49 @@ impl super::Nexus {
             .await
     }
 
+    /// Explains where an instance with `ncpus` vCPUs and `memory` of guest
+    /// memory could be placed, and why each sled that can't host it was
+    /// rejected
+    ///
+    /// If `instance_id` is provided, the affinity and anti-affinity groups that
+    /// instance belongs to are taken into account.
+    pub(crate) async fn instance_placement_explain(
+        &self,
+        opctx: &OpContext,
+        instance_id: Option<InstanceUuid>,
+        ncpus: u32,
+        memory: ByteCount,
+    ) -> Result<InstancePlacementExplanation, Error> {
+        if ncpus == 0 || memory.to_bytes() == 0 {
+            return Err(Error::invalid_request(
+                "instance shape must have at least one vCPU and nonzero memory",
+            ));
+        }
+
+        // Instances reserve all of their guest memory from the reservoir,
+        // just as they do when they're started.
+        let resources = db::model::Resources::new(
+            ncpus,
+            ByteCount::from(0u32).into(),
+            memory.into(),
+        );
+        // An instance that doesn't exist isn't in any affinity groups.
+        let sleds = self
+            .db_datastore
+            .sled_placement_explain(
+                opctx,
+                instance_id.unwrap_or_else(InstanceUuid::new_v4),
+                &resources,
+            )
+            .await?;
+        Ok(InstancePlacementExplanation {
+            instance_id,
+            hardware_threads: ncpus,
+            reservoir_ram: memory,
+            sleds,
+        })
+    }
+
     /// Returns the old provision policy.
     pub(crate) async fn sled_set_provision_policy(
         &self,
@@ -218,6 +268,164 @@ impl super::Nexus {
             .await
     }
 
+    // Rolling sled reboots
+
+    /// Starts rebooting `request.sled_ids`, in order, no more than
+    /// `request.batch_size` at a time
+    ///
+    /// The reboot itself is carried out by the `rolling_sled_reboot`
+    /// background task.
+    pub(crate) async fn rolling_sled_reboot_start(
+        &self,
+        opctx: &OpContext,
+        request: RollingSledRebootRequest,
+    ) -> Result<RollingSledReboot, Error> {
+        let batch_size = request.batch_size.unwrap_or(1);
+        if batch_size == 0 {
+            return Err(Error::invalid_request(
+                "batch size must be at least 1",
+            ));
+        }
+        if request.sled_ids.is_empty() {
+            return Err(Error::invalid_request("no sleds to reboot"));
+        }
+        let mut seen = BTreeSet::new();
+        if let Some(dup) =
+            request.sled_ids.iter().find(|sled_id| !seen.insert(**sled_id))
+        {
+            return Err(Error::invalid_request(format!(
+                "sled {dup} is listed more than once"
+            )));
+        }
+
+        // Only sleds that are in service and active can be rebooted: we
+        // quiesce each sled while it's being rebooted, and return it to the
+        // active state afterwards.
+        let sleds: BTreeMap<_, _> = self
+            .db_datastore
+            .sled_list_all_batched(opctx, SledFilter::InService)
+            .await?
+            .into_iter()
+            .map(|sled| (SledUuid::from_untyped_uuid(sled.id()), sled))
+            .collect();
+        for sled_id in &request.sled_ids {
+            match sleds.get(sled_id) {
+                None => {
+                    return Err(Error::invalid_request(format!(
+                        "sled {sled_id} does not exist or is not in service"
+                    )));
+                }
+                Some(sled) if sled.state() != db::model::SledState::Active => {
+                    return Err(Error::invalid_request(format!(
+                        "sled {sled_id} is {}, not active",
+                        sled.state()
+                    )));
+                }
+                Some(_) => (),
+            }
+        }
+
+        let reboot = self
+            .db_datastore
+            .rolling_sled_reboot_create(
+                opctx,
+                db::model::RollingSledReboot::new(batch_size),
+                &request.sled_ids,
+            )
+            .await?;
+        self.background_tasks.task_rolling_sled_reboot.activate();
+        self.rolling_sled_reboot_view_from(opctx, reboot).await
+    }
+
+    pub(crate) async fn rolling_sled_reboot_list(
+        &self,
+        opctx: &OpContext,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<RollingSledReboot> {
+        let reboots = self
+            .db_datastore
+            .rolling_sled_reboot_list(opctx, pagparams)
+            .await?;
+        let mut views = Vec::with_capacity(reboots.len());
+        for reboot in reboots {
+            views
+                .push(self.rolling_sled_reboot_view_from(opctx, reboot).await?);
+        }
+        Ok(views)
+    }
+
+    pub(crate) async fn rolling_sled_reboot_view(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+    ) -> LookupResult<RollingSledReboot> {
+        let reboot = self
+            .db_datastore
+            .rolling_sled_reboot_fetch(opctx, reboot_id)
+            .await?;
+        self.rolling_sled_reboot_view_from(opctx, reboot).await
+    }
+
+    /// Asks for a running rolling reboot to stop before rebooting any more
+    /// sleds
+    pub(crate) async fn rolling_sled_reboot_cancel(
+        &self,
+        opctx: &OpContext,
+        reboot_id: Uuid,
+    ) -> Result<RollingSledReboot, Error> {
+        let reboot = self
+            .db_datastore
+            .rolling_sled_reboot_request_cancel(opctx, reboot_id)
+            .await?;
+        self.background_tasks.task_rolling_sled_reboot.activate();
+        self.rolling_sled_reboot_view_from(opctx, reboot).await
+    }
+
+    async fn rolling_sled_reboot_view_from(
+        &self,
+        opctx: &OpContext,
+        reboot: db::model::RollingSledReboot,
+    ) -> Result<RollingSledReboot, Error> {
+        let sleds = self
+            .db_datastore
+            .rolling_sled_reboot_sled_list(opctx, reboot.id)
+            .await?;
+        let mut instances = self
+            .db_datastore
+            .rolling_sled_reboot_instance_list(opctx, reboot.id)
+            .await?;
+        let sleds = sleds
+            .into_iter()
+            .map(|sled| {
+                let sled_id = sled.sled_id();
+                let (stopped, rest) = instances
+                    .drain(..)
+                    .partition(|instance| instance.sled_id == sled.sled_id);
+                instances = rest;
+                RollingSledRebootSled {
+                    sled_id,
+                    step: sled.step.into(),
+                    time_step_changed: sled.time_step_changed,
+                    time_reset: sled.time_reset,
+                    error: sled.error,
+                    stopped_instances: stopped
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                }
+            })
+            .collect();
+        Ok(RollingSledReboot {
+            id: reboot.id,
+            time_created: reboot.time_created,
+            time_finished: reboot.time_finished,
+            batch_size: *reboot.batch_size,
+            state: reboot.state.into(),
+            time_cancel_requested: reboot.time_cancel_requested,
+            sleds,
+        })
+    }
+
     // Physical disks
 
     pub fn physical_disk_lookup<'a>(
@@ -12,6 +12,7 @@ use nexus_db_queries::authn;
 use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
+use nexus_db_queries::db::DataStore;
 use nexus_types::external_api::params;
 use nexus_types::external_api::params::DiskSelector;
 use omicron_common::api::external::CreateResult;
@@ -20,10 +21,39 @@ use omicron_common::api::external::Error;
 use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::NameOrId;
+use omicron_common::api::external::UpdateResult;
 use omicron_common::api::external::http_pagination::PaginatedBy;
 
 use super::sagas;
 
+/// Returns true if a snapshot of `db_disk` must be taken by the Crucible
+/// Pantry, rather than by the Propolis of the instance the disk is attached to
+pub(crate) async fn snapshot_use_the_pantry(
+    opctx: &OpContext,
+    datastore: &DataStore,
+    db_disk: &db::model::Disk,
+) -> Result<bool, Error> {
+    // If there isn't a running propolis, Nexus needs to use the Crucible
+    // Pantry to make this snapshot
+    if let Some(attach_instance_id) = &db_disk.runtime_state.attach_instance_id
+    {
+        let (.., authz_instance) = LookupPath::new(opctx, datastore)
+            .instance_id(*attach_instance_id)
+            .lookup_for(authz::Action::Read)
+            .await?;
+
+        let instance_state =
+            datastore.instance_fetch_with_vmm(&opctx, &authz_instance).await?;
+
+        // If a Propolis _may_ exist, send the snapshot request there,
+        // otherwise use the pantry.
+        Ok(instance_state.vmm().is_none())
+    } else {
+        // This disk is not attached to an instance, use the pantry.
+        Ok(true)
+    }
+}
+
 impl super::Nexus {
     // Snapshots
 
@@ -96,30 +126,10 @@ impl super::Nexus {
             ));
         }
 
-        // If there isn't a running propolis, Nexus needs to use the Crucible
-        // Pantry to make this snapshot
-        let use_the_pantry = if let Some(attach_instance_id) =
-            &db_disk.runtime_state.attach_instance_id
-        {
-            let (.., authz_instance) =
-                LookupPath::new(opctx, &self.db_datastore)
-                    .instance_id(*attach_instance_id)
-                    .lookup_for(authz::Action::Read)
-                    .await?;
-
-            let instance_state = self
-                .datastore()
-                .instance_fetch_with_vmm(&opctx, &authz_instance)
+        let use_the_pantry =
+            snapshot_use_the_pantry(opctx, &self.db_datastore, &db_disk)
                 .await?;
 
-            // If a Propolis _may_ exist, send the snapshot request there,
-            // otherwise use the pantry.
-            instance_state.vmm().is_none()
-        } else {
-            // This disk is not attached to an instance, use the pantry.
-            true
-        };
-
         let saga_params = sagas::snapshot_create::Params {
             serialized_authn: authn::saga::Serialized::for_opctx(opctx),
             silo_id: authz_silo.id(),
@@ -128,6 +138,7 @@ impl super::Nexus {
             attach_instance_id: db_disk.runtime_state.attach_instance_id,
             use_the_pantry,
             create_params: params.clone(),
+            snapshot_schedule_id: None,
         };
 
         let saga_outputs = self
@@ -158,6 +169,23 @@ impl super::Nexus {
         self.db_datastore.snapshot_list(opctx, &authz_project, pagparams).await
     }
 
+    pub(crate) async fn snapshot_update(
+        &self,
+        opctx: &OpContext,
+        snapshot_lookup: &lookup::Snapshot<'_>,
+        params: &params::SnapshotUpdate,
+    ) -> UpdateResult<db::model::Snapshot> {
+        let (.., authz_snapshot) =
+            snapshot_lookup.lookup_for(authz::Action::Modify).await?;
+        self.db_datastore
+            .snapshot_set_delete_protected(
+                opctx,
+                &authz_snapshot,
+                params.delete_protected,
+            )
+            .await
+    }
+
     pub(crate) async fn snapshot_delete(
         self: &Arc<Self>,
         opctx: &OpContext,
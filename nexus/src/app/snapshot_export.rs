@@ -0,0 +1,460 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Snapshot exports
+
+use std::sync::Arc;
+
+use dropshot::Body;
+use futures::FutureExt;
+use http::Response;
+use nexus_db_lookup::LookupPath;
+use nexus_db_lookup::lookup;
+use nexus_db_queries::authn;
+use nexus_db_queries::authz;
+use nexus_db_queries::context::OpContext;
+use nexus_db_queries::db;
+use nexus_db_queries::db::identity::Resource;
+use nexus_types::external_api::params;
+use nexus_types::external_api::views;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
+use omicron_common::api::external::DeleteResult;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::IdentityMetadataCreateParams;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::NameOrId;
+use range_requests::PotentialRange;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use super::operation::OperationProgress;
+use super::sagas;
+
+/// The largest amount of data read from a Pantry in a single request when
+/// downloading a snapshot export
+const EXPORT_READ_CHUNK_SIZE: u64 = 512 * 1024;
+
+/// Returns the manifest describing an export of `snapshot`, optionally taken
+/// of the boot disk of `instance`
+pub(crate) fn export_manifest(
+    snapshot: &db::model::Snapshot,
+    instance: Option<&db::model::Instance>,
+) -> views::ExportManifest {
+    views::ExportManifest {
+        format_version: views::ExportManifest::FORMAT_VERSION,
+        image_format: views::ExportImageFormat::Raw,
+        size: snapshot.size.into(),
+        block_size: snapshot.block_size.into(),
+        snapshot: views::ExportManifestSnapshot {
+            id: snapshot.id(),
+            name: snapshot.name().clone(),
+            description: snapshot.description().to_string(),
+            time_created: snapshot.time_created(),
+            disk_id: snapshot.disk_id,
+        },
+        instance: instance.map(|instance| views::ExportManifestInstance {
+            id: instance.id(),
+            name: instance.name().clone(),
+            description: instance.description().to_string(),
+            hostname: instance.hostname.clone(),
+            ncpus: instance.ncpus.into(),
+            memory: instance.memory.into(),
+        }),
+    }
+}
+
+impl super::Nexus {
+    pub fn snapshot_export_lookup<'a>(
+        &'a self,
+        opctx: &'a OpContext,
+        path: params::SnapshotExportPath,
+    ) -> lookup::SnapshotExport<'a> {
+        LookupPath::new(opctx, &self.db_datastore)
+            .snapshot_export_id(path.snapshot_export)
+    }
+
+    pub(crate) async fn snapshot_export_list(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<views::SnapshotExport> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::ListChildren).await?;
+
+        Ok(self
+            .db_datastore
+            .snapshot_export_list(opctx, &authz_project, pagparams)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Export a snapshot, making its contents available for download
+    pub(crate) async fn snapshot_export_create(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        snapshot_lookup: &lookup::Snapshot<'_>,
+    ) -> CreateResult<views::SnapshotExport> {
+        let (.., authz_project, _authz_snapshot, db_snapshot) =
+            snapshot_lookup.fetch_for(authz::Action::Read).await?;
+
+        let manifest = export_manifest(&db_snapshot, None);
+        self.snapshot_export_create_impl(
+            opctx,
+            authz_project,
+            db_snapshot,
+            None,
+            manifest,
+        )
+        .await
+    }
+
+    /// Start exporting a snapshot as an operation, returning the operation
+    /// without waiting for the export to be ready
+    pub(crate) async fn snapshot_export_create_operation(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        snapshot_lookup: &lookup::Snapshot<'_>,
+    ) -> CreateResult<views::Operation> {
+        let (.., authz_project, authz_snapshot) =
+            snapshot_lookup.lookup_for(authz::Action::Read).await?;
+        let snapshot_id = authz_snapshot.id();
+
+        self.operation_start(
+            opctx,
+            &authz_project,
+            db::model::OperationKind::SnapshotExport,
+            1,
+            move |nexus, progress| {
+                async move {
+                    let snapshot_lookup =
+                        LookupPath::new(progress.opctx(), &nexus.db_datastore)
+                            .snapshot_id(snapshot_id);
+                    let export = nexus
+                        .snapshot_export_create(
+                            progress.opctx(),
+                            &snapshot_lookup,
+                        )
+                        .await?;
+                    Ok(export.id)
+                }
+                .boxed()
+            },
+        )
+        .await
+    }
+
+    /// Export an instance's boot disk, by taking a snapshot of it and then
+    /// exporting that snapshot along with a description of the instance
+    ///
+    /// When run as part of an operation, one step is reported as completed
+    /// once the snapshot has been taken.
+    pub(crate) async fn instance_export_create(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        instance_lookup: &lookup::Instance<'_>,
+        params: &params::InstanceExportCreate,
+        progress: Option<&OperationProgress>,
+    ) -> CreateResult<views::SnapshotExport> {
+        let (.., authz_project, _authz_instance, db_instance) =
+            instance_lookup.fetch_for(authz::Action::Read).await?;
+
+        let Some(boot_disk_id) = db_instance.boot_order.first().copied() else {
+            return Err(Error::invalid_request(
+                "instance has no boot disk to export",
+            ));
+        };
+
+        let project_lookup = LookupPath::new(opctx, &self.db_datastore)
+            .project_id(authz_project.id());
+        let db_snapshot = self
+            .snapshot_create(
+                opctx,
+                project_lookup,
+                &params::SnapshotCreate {
+                    identity: IdentityMetadataCreateParams {
+                        name: params.snapshot_name.clone(),
+                        description: format!(
+                            "boot disk of instance {}",
+                            db_instance.name()
+                        ),
+                    },
+                    disk: NameOrId::Id(boot_disk_id),
+                },
+            )
+            .await?;
+        if let Some(progress) = progress {
+            progress.step_completed().await;
+        }
+
+        let manifest = export_manifest(&db_snapshot, Some(&db_instance));
+        self.snapshot_export_create_impl(
+            opctx,
+            authz_project,
+            db_snapshot,
+            Some(db_instance.id()),
+            manifest,
+        )
+        .await
+    }
+
+    /// Start exporting an instance's boot disk as an operation, returning the
+    /// operation without waiting for the export to be ready
+    pub(crate) async fn instance_export_operation(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        instance_lookup: &lookup::Instance<'_>,
+        params: params::InstanceExportCreate,
+    ) -> CreateResult<views::Operation> {
+        let (.., authz_project, authz_instance) =
+            instance_lookup.lookup_for(authz::Action::Read).await?;
+        let instance_id = authz_instance.id();
+
+        self.operation_start(
+            opctx,
+            &authz_project,
+            db::model::OperationKind::InstanceExport,
+            2,
+            move |nexus, progress| {
+                async move {
+                    let instance_lookup =
+                        LookupPath::new(progress.opctx(), &nexus.db_datastore)
+                            .instance_id(instance_id);
+                    let export = nexus
+                        .instance_export_create(
+                            progress.opctx(),
+                            &instance_lookup,
+                            &params,
+                            Some(&progress),
+                        )
+                        .await?;
+                    Ok(export.id)
+                }
+                .boxed()
+            },
+        )
+        .await
+    }
+
+    async fn snapshot_export_create_impl(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        authz_project: authz::Project,
+        db_snapshot: db::model::Snapshot,
+        instance_id: Option<Uuid>,
+        manifest: views::ExportManifest,
+    ) -> CreateResult<views::SnapshotExport> {
+        opctx.authorize(authz::Action::CreateChild, &authz_project).await?;
+
+        if db_snapshot.state != db::model::SnapshotState::Ready {
+            return Err(Error::invalid_request(format!(
+                "snapshot {} is not ready to be exported",
+                db_snapshot.id()
+            )));
+        }
+
+        let saga_params = sagas::snapshot_export_create::Params {
+            serialized_authn: authn::saga::Serialized::for_opctx(opctx),
+            authz_project,
+            snapshot: db_snapshot,
+            instance_id,
+            manifest,
+        };
+
+        let saga_outputs = self
+            .sagas
+            .saga_execute::<sagas::snapshot_export_create::SagaSnapshotExportCreate>(
+                saga_params,
+            )
+            .await?;
+
+        let export_created = saga_outputs
+            .lookup_node_output::<db::model::SnapshotExport>("finalized_export")
+            .map_err(|e| Error::InternalError {
+                internal_message: e.to_string(),
+            })?;
+
+        Ok(export_created.into())
+    }
+
+    pub(crate) async fn snapshot_export_view(
+        &self,
+        export_lookup: &lookup::SnapshotExport<'_>,
+    ) -> LookupResult<views::SnapshotExport> {
+        let (.., db_export) = export_lookup.fetch().await?;
+        Ok(db_export.into())
+    }
+
+    pub(crate) async fn snapshot_export_manifest(
+        &self,
+        export_lookup: &lookup::SnapshotExport<'_>,
+    ) -> LookupResult<views::ExportManifest> {
+        let (.., db_export) = export_lookup.fetch().await?;
+        db_export.manifest()
+    }
+
+    pub(crate) async fn snapshot_export_delete(
+        self: &Arc<Self>,
+        opctx: &OpContext,
+        export_lookup: &lookup::SnapshotExport<'_>,
+    ) -> DeleteResult {
+        let (.., authz_export, db_export) =
+            export_lookup.fetch_for(authz::Action::Delete).await?;
+
+        let saga_params = sagas::snapshot_export_delete::Params {
+            serialized_authn: authn::saga::Serialized::for_opctx(opctx),
+            authz_export,
+            export: db_export,
+        };
+
+        self.sagas
+            .saga_execute::<sagas::snapshot_export_delete::SagaSnapshotExportDelete>(
+                saga_params,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Download the raw image of a snapshot export, or the part of it
+    /// described by `range`
+    pub(crate) async fn snapshot_export_download(
+        &self,
+        export_lookup: &lookup::SnapshotExport<'_>,
+        range: Option<PotentialRange>,
+    ) -> Result<Response<Body>, Error> {
+        let (.., db_export) = export_lookup.fetch().await?;
+
+        let Some(pantry_address) = db_export.pantry_address() else {
+            return Err(Error::invalid_request(
+                "snapshot export is not ready to be downloaded",
+            ));
+        };
+
+        let len = db_export.size.to_bytes();
+        let (range, start, length) = match range {
+            Some(range) => match range.parse(len) {
+                Ok(range) => {
+                    let start = range.start();
+                    let length = range.content_length().get();
+                    (Some(range), start, length)
+                }
+                // The range is invalid -- send the error back as the body.
+                Err(err_response) => return Ok(err_response),
+            },
+            None => (None, 0, len),
+        };
+
+        // Reads are performed in the background, and are written to one end
+        // of a pipe that the response body streams from. If a read fails, the
+        // pipe is closed early and the client sees a truncated body.
+        let (mut writer, reader) =
+            tokio::io::duplex(EXPORT_READ_CHUNK_SIZE as usize);
+        let client = crucible_pantry_client::Client::new_with_client(
+            &format!("http://{}", pantry_address),
+            self.reqwest_client.clone(),
+        );
+        let export_id = db_export.id;
+        let block_size = u64::from(db_export.block_size.to_bytes());
+        let log = self.log.new(o!(
+            "snapshot_export_id" => export_id.to_string(),
+            "pantry_address" => pantry_address.to_string(),
+        ));
+        tokio::spawn(async move {
+            if let Err(e) = snapshot_export_read(
+                &client,
+                export_id,
+                block_size,
+                start,
+                length,
+                &mut writer,
+            )
+            .await
+            {
+                warn!(
+                    log,
+                    "failed to download snapshot export";
+                    "error" => %e,
+                );
+            }
+        });
+
+        const CONTENT_TYPE: http::HeaderValue =
+            http::HeaderValue::from_static("application/octet-stream");
+        range_requests::make_get_response(
+            range,
+            len,
+            Some(CONTENT_TYPE),
+            tokio_util::io::ReaderStream::new(reader),
+        )
+        .map_err(|e| {
+            Error::internal_error(&format!(
+                "failed to build snapshot export download response: {e}"
+            ))
+        })
+    }
+}
+
+/// Read `length` bytes starting at `start` from the volume of snapshot export
+/// `export_id`, and write them to `writer`
+///
+/// The Pantry only serves reads of whole blocks, so reads are widened to block
+/// boundaries and trimmed before they are written.
+async fn snapshot_export_read(
+    client: &crucible_pantry_client::Client,
+    export_id: Uuid,
+    block_size: u64,
+    start: u64,
+    length: u64,
+    writer: &mut tokio::io::DuplexStream,
+) -> Result<(), Error> {
+    let end = start + length;
+    let mut offset = start - (start % block_size);
+    while offset < end {
+        let aligned_end = end.div_ceil(block_size) * block_size;
+        let size = std::cmp::min(EXPORT_READ_CHUNK_SIZE, aligned_end - offset);
+
+        let response = client
+            .bulk_read(
+                &export_id.to_string(),
+                &crucible_pantry_client::types::BulkReadRequest {
+                    offset,
+                    size: size as usize,
+                },
+            )
+            .await
+            .map_err(|e| {
+                Error::internal_error(&format!(
+                    "error sending bulk read to pantry: {e}"
+                ))
+            })?
+            .into_inner();
+
+        let data = response.base64_encoded_data;
+        if data.len() as u64 != size {
+            return Err(Error::internal_error(&format!(
+                "pantry returned {} bytes for a read of {size} bytes",
+                data.len()
+            )));
+        }
+
+        let skip = start.saturating_sub(offset) as usize;
+        let take = (std::cmp::min(end, offset + size) - offset) as usize;
+        if let Err(e) = writer.write_all(&data[skip..take]).await {
+            // The client went away, so there's no one to send the rest of the
+            // export to.
+            return Err(Error::unavail(&format!(
+                "download of snapshot export {export_id} ended early: {e}"
+            )));
+        }
+
+        offset += size;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Snapshot schedules
+
+use nexus_db_lookup::LookupPath;
+use nexus_db_lookup::lookup;
+use nexus_db_model::SnapshotSchedule;
+use nexus_db_queries::authz;
+use nexus_db_queries::context::OpContext;
+use nexus_types::external_api::params;
+use nexus_types::external_api::params::DiskSelector;
+use nexus_types::external_api::views;
+use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DeleteResult;
+use omicron_common::api::external::Error;
+use omicron_common::api::external::ListResultVec;
+use omicron_common::api::external::LookupResult;
+use omicron_common::api::external::NameOrId;
+use omicron_common::api::external::UpdateResult;
+use omicron_common::api::external::http_pagination::PaginatedBy;
+
+/// The shortest interval at which a snapshot schedule may take snapshots
+///
+/// Schedules are only evaluated periodically by a background task, so very
+/// short intervals can't be honored anyway.
+pub const MIN_SNAPSHOT_SCHEDULE_INTERVAL_SECS: u32 = 5 * 60;
+
+fn validate_interval_secs(interval_secs: u32) -> Result<(), Error> {
+    if interval_secs < MIN_SNAPSHOT_SCHEDULE_INTERVAL_SECS {
+        return Err(Error::invalid_value(
+            "interval_secs",
+            format!(
+                "snapshot schedules must have an interval of at least \
+                {MIN_SNAPSHOT_SCHEDULE_INTERVAL_SECS} seconds"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_retain_count(retain_count: u32) -> Result<(), Error> {
+    if retain_count == 0 {
+        return Err(Error::invalid_value(
+            "retain_count",
+            "snapshot schedules must retain at least one snapshot",
+        ));
+    }
+    Ok(())
+}
+
+impl super::Nexus {
+    pub fn snapshot_schedule_lookup<'a>(
+        &'a self,
+        opctx: &'a OpContext,
+        snapshot_schedule_selector: params::SnapshotScheduleSelector,
+    ) -> LookupResult<lookup::SnapshotSchedule<'a>> {
+        match snapshot_schedule_selector {
+            params::SnapshotScheduleSelector {
+                snapshot_schedule: NameOrId::Id(id),
+                project: None,
+            } => {
+                let schedule = LookupPath::new(opctx, &self.db_datastore)
+                    .snapshot_schedule_id(id);
+                Ok(schedule)
+            }
+            params::SnapshotScheduleSelector {
+                snapshot_schedule: NameOrId::Name(name),
+                project: Some(project),
+            } => {
+                let schedule = self
+                    .project_lookup(opctx, params::ProjectSelector { project })?
+                    .snapshot_schedule_name_owned(name.into());
+                Ok(schedule)
+            }
+            params::SnapshotScheduleSelector {
+                snapshot_schedule: NameOrId::Id(_),
+                ..
+            } => Err(Error::invalid_request(
+                "when providing snapshot_schedule as an ID, project should not \
+                be specified",
+            )),
+            _ => Err(Error::invalid_request(
+                "snapshot_schedule should either be an ID or project should be \
+                specified",
+            )),
+        }
+    }
+
+    pub(crate) async fn snapshot_schedule_list(
+        &self,
+        opctx: &OpContext,
+        project_lookup: &lookup::Project<'_>,
+        pagparams: &PaginatedBy<'_>,
+    ) -> ListResultVec<views::SnapshotSchedule> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::ListChildren).await?;
+
+        Ok(self
+            .db_datastore
+            .snapshot_schedule_list(opctx, &authz_project, pagparams)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    pub(crate) async fn snapshot_schedule_create(
+        &self,
+        opctx: &OpContext,
+        // Is passed by value due to `disk_name_owned` taking ownership of
+        // `self` below
+        project_lookup: lookup::Project<'_>,
+        params: params::SnapshotScheduleCreate,
+    ) -> CreateResult<views::SnapshotSchedule> {
+        let (.., authz_project) =
+            project_lookup.lookup_for(authz::Action::CreateChild).await?;
+
+        validate_interval_secs(params.interval_secs)?;
+        validate_retain_count(params.retain_count)?;
+
+        let disk_id = match params.disk.clone() {
+            None => None,
+            Some(disk) => {
+                let (.., authz_disk_project, authz_disk) = match disk {
+                    NameOrId::Id(id) => self.disk_lookup(
+                        opctx,
+                        DiskSelector { disk: NameOrId::Id(id), project: None },
+                    )?,
+                    NameOrId::Name(name) => {
+                        project_lookup.disk_name_owned(name.into())
+                    }
+                }
+                .lookup_for(authz::Action::Read)
+                .await?;
+
+                if authz_disk_project.id() != authz_project.id() {
+                    return Err(Error::invalid_request(
+                        "can't schedule snapshots of a disk in a different \
+                        project",
+                    ));
+                }
+                Some(authz_disk.id())
+            }
+        };
+
+        let schedule =
+            SnapshotSchedule::new(authz_project.id(), disk_id, params);
+        self.db_datastore
+            .snapshot_schedule_create(opctx, &authz_project, schedule)
+            .await
+            .map(Into::into)
+    }
+
+    pub(crate) async fn snapshot_schedule_view(
+        &self,
+        schedule_lookup: &lookup::SnapshotSchedule<'_>,
+    ) -> LookupResult<views::SnapshotSchedule> {
+        let (.., db_schedule) = schedule_lookup.fetch().await?;
+        Ok(db_schedule.into())
+    }
+
+    pub(crate) async fn snapshot_schedule_update(
+        &self,
+        opctx: &OpContext,
+        schedule_lookup: &lookup::SnapshotSchedule<'_>,
+        updates: &params::SnapshotScheduleUpdate,
+    ) -> UpdateResult<views::SnapshotSchedule> {
+        let (.., authz_schedule) =
+            schedule_lookup.lookup_for(authz::Action::Modify).await?;
+
+        if let Some(interval_secs) = updates.interval_secs {
+            validate_interval_secs(interval_secs)?;
+        }
+        if let Some(retain_count) = updates.retain_count {
+            validate_retain_count(retain_count)?;
+        }
+
+        self.db_datastore
+            .snapshot_schedule_update(
+                opctx,
+                &authz_schedule,
+                updates.clone().into(),
+            )
+            .await
+            .map(Into::into)
+    }
+
+    pub(crate) async fn snapshot_schedule_delete(
+        &self,
+        opctx: &OpContext,
+        schedule_lookup: &lookup::SnapshotSchedule<'_>,
+    ) -> DeleteResult {
+        let (.., authz_schedule) =
+            schedule_lookup.lookup_for(authz::Action::Delete).await?;
+        self.db_datastore.snapshot_schedule_delete(opctx, &authz_schedule).await
+    }
+}
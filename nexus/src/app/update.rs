@@ -13,8 +13,11 @@ use nexus_db_model::{TufRepoDescription, TufTrustRoot};
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db::{datastore::SQL_BATCH_SIZE, pagination::Paginator};
 use nexus_types::external_api::shared::TufSignedRootRole;
+use nexus_types::external_api::views;
+use nexus_types::internal_api::params::TechnicianPortUpdateReport;
 use omicron_common::api::external::{
-    DataPageParams, Error, TufRepoInsertResponse, TufRepoInsertStatus,
+    DataPageParams, Error, ListResultVec, TufRepoInsertResponse,
+    TufRepoInsertStatus,
 };
 use omicron_uuid_kinds::{GenericUuid, TufTrustRootUuid};
 use semver::Version;
@@ -149,4 +152,43 @@ impl super::Nexus {
             .await
             .map_err(HttpError::from)
     }
+
+    /// Records progress of updates driven from the technician port, as
+    /// reported by wicketd
+    pub(crate) async fn technician_port_update_report(
+        &self,
+        opctx: &OpContext,
+        report: TechnicianPortUpdateReport,
+    ) -> Result<(), Error> {
+        for update in &report.updates {
+            for component in &update.components {
+                if component.steps_completed > component.total_steps {
+                    return Err(Error::invalid_request(format!(
+                        "update {} reports {} of {} steps completed for {:?}",
+                        update.id,
+                        component.steps_completed,
+                        component.total_steps,
+                        component.component,
+                    )));
+                }
+            }
+        }
+        self.db_datastore
+            .technician_port_update_report(opctx, report.updates)
+            .await
+    }
+
+    pub(crate) async fn technician_port_update_list(
+        &self,
+        opctx: &OpContext,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<views::TechnicianPortUpdate> {
+        Ok(self
+            .db_datastore
+            .technician_port_update_list(opctx, pagparams)
+            .await?
+            .into_iter()
+            .map(|(update, components)| update.into_view(components))
+            .collect())
+    }
 }
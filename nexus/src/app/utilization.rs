@@ -9,6 +9,8 @@ use nexus_db_model::IpPoolUtilization;
 use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
+use nexus_types::external_api::shared::IpRange;
+use nexus_types::external_api::views;
 use omicron_common::api::external::Error;
 use omicron_common::api::external::ListResultVec;
 use omicron_common::api::external::http_pagination::PaginatedBy;
@@ -40,27 +42,52 @@ impl super::Nexus {
         let (.., authz_pool) =
             pool_lookup.lookup_for(authz::Action::Read).await?;
 
-        let (allocated, capacity) =
+        let allocation =
             self.db_datastore.ip_pool_utilization(opctx, &authz_pool).await?;
 
-        // Compute the remaining count in full 128-bit arithmetic, checking for
-        // negative values, and convert to f64s at the end.
-        let Ok(allocated) = u128::try_from(allocated) else {
-            return Err(Error::internal_error(
-                "Impossible negative number of allocated IP addresses",
-            ));
-        };
-        let Some(remaining) = capacity.checked_sub(allocated) else {
-            return Err(Error::internal_error(
-                format!(
-                    "Computed an impossible negative count of remaining IP \
-                addresses. Capacity = {capacity}, allocated = {allocated}"
-                )
-                .as_str(),
-            ));
-        };
+        let remaining =
+            remaining_ips(allocation.allocated, allocation.capacity)?;
+        let ranges = allocation
+            .ranges
+            .into_iter()
+            .map(|(range, allocated)| {
+                let id = range.id;
+                let range = IpRange::from(&range);
+                let capacity = match range {
+                    IpRange::V4(r) => u128::from(r.len()),
+                    IpRange::V6(r) => r.len(),
+                };
+                let remaining = remaining_ips(allocated, capacity)?;
+                Ok(views::IpPoolRangeUtilization {
+                    id,
+                    range,
+                    allocated: allocated as f64,
+                    remaining: remaining as f64,
+                    capacity: capacity as f64,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
         let remaining = remaining as f64;
-        let capacity = capacity as f64;
-        Ok(IpPoolUtilization { remaining, capacity })
+        let capacity = allocation.capacity as f64;
+        Ok(IpPoolUtilization { remaining, capacity, ranges })
     }
 }
+
+/// Compute the remaining count of IP addresses in full 128-bit arithmetic,
+/// checking for negative values.
+fn remaining_ips(allocated: i64, capacity: u128) -> Result<u128, Error> {
+    let Ok(allocated) = u128::try_from(allocated) else {
+        return Err(Error::internal_error(
+            "Impossible negative number of allocated IP addresses",
+        ));
+    };
+    capacity.checked_sub(allocated).ok_or_else(|| {
+        Error::internal_error(
+            format!(
+                "Computed an impossible negative count of remaining IP \
+                addresses. Capacity = {capacity}, allocated = {allocated}"
+            )
+            .as_str(),
+        )
+    })
+}
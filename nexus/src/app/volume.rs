@@ -9,7 +9,9 @@ use nexus_db_model::UpstairsRepairNotification;
 use nexus_db_model::UpstairsRepairNotificationType;
 use nexus_db_queries::authn;
 use nexus_db_queries::context::OpContext;
+use nexus_types::internal_api::views::VolumeReferenceCheckReport;
 use omicron_common::api::external::DeleteResult;
+use omicron_common::api::external::Error;
 use omicron_common::api::internal::nexus::DownstairsClientStopRequest;
 use omicron_common::api::internal::nexus::DownstairsClientStopped;
 use omicron_common::api::internal::nexus::RepairFinishInfo;
@@ -212,4 +214,30 @@ impl super::Nexus {
             )
             .await
     }
+
+    /// Compare the read-only resources referenced by every live volume
+    /// against the recorded volume resource usage, optionally repairing any
+    /// inconsistencies that are found.
+    pub(crate) async fn volume_reference_check(
+        &self,
+        opctx: &OpContext,
+        repair: bool,
+    ) -> Result<VolumeReferenceCheckReport, Error> {
+        let report =
+            self.db_datastore.volume_reference_check(opctx, repair).await?;
+
+        let inconsistencies =
+            report.missing_usage.len() + report.stale_usage.len();
+        if inconsistencies > 0 || !report.freed_while_referenced.is_empty() {
+            warn!(
+                self.log,
+                "volume reference check found inconsistencies";
+                "repair" => repair,
+                "inconsistencies" => inconsistencies,
+                "freed_while_referenced" => report.freed_while_referenced.len(),
+            );
+        }
+
+        Ok(report)
+    }
 }
@@ -14,6 +14,8 @@ use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
 use nexus_db_queries::db::model::Name;
 use nexus_defaults as defaults;
+use nexus_types::external_api::views;
+use nexus_types::identity::Resource;
 use omicron_common::api::external;
 use omicron_common::api::external::CreateResult;
 use omicron_common::api::external::DeleteResult;
@@ -28,6 +30,10 @@ use omicron_common::api::external::UpdateResult;
 use omicron_common::api::external::VpcFirewallRuleUpdateParams;
 use omicron_common::api::external::http_pagination::PaginatedBy;
 use omicron_common::api::internal::shared::ResolvedVpcFirewallRule;
+use sha2::Digest;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -192,6 +198,74 @@ impl super::Nexus {
         Ok(rules)
     }
 
+    /// Preview or apply a replacement of all of a VPC's firewall rules.
+    ///
+    /// Without a confirm token this only computes the difference between the
+    /// current and desired rules. The returned token commits to both the
+    /// current rules and the desired ones, so applying it fails if either has
+    /// changed since the preview.
+    pub(crate) async fn vpc_replace_firewall_rules(
+        &self,
+        opctx: &OpContext,
+        vpc_lookup: &lookup::Vpc<'_>,
+        params: &params::VpcFirewallRulesReplace,
+    ) -> UpdateResult<views::VpcFirewallRulesReplaceResult> {
+        let (.., authz_vpc, db_vpc) =
+            vpc_lookup.fetch_for(authz::Action::Modify).await?;
+        let new_rules = db::model::VpcFirewallRule::vec_from_params(
+            authz_vpc.id(),
+            VpcFirewallRuleUpdateParams { rules: params.rules.clone() },
+        )?;
+        let current_rules = self
+            .db_datastore
+            .vpc_list_firewall_rules(opctx, &authz_vpc)
+            .await?;
+
+        let current_rule_ids: BTreeSet<Uuid> =
+            current_rules.iter().map(|rule| rule.id()).collect();
+        let current = firewall_rules_by_name(current_rules.clone());
+        let desired = firewall_rules_by_name(new_rules.clone());
+        let diff = firewall_rules_diff(&current, &desired);
+        let confirm_token = firewall_rules_confirm_token(
+            authz_vpc.id(),
+            &current_rule_ids,
+            &desired,
+        )?;
+
+        let Some(token) = &params.confirm_token else {
+            return Ok(views::VpcFirewallRulesReplaceResult {
+                applied: false,
+                diff,
+                confirm_token,
+                rules: current_rules.into_iter().map(Into::into).collect(),
+            });
+        };
+        if *token != confirm_token {
+            return Err(Error::conflict(
+                "confirm token does not match the current and requested \
+                firewall rules; preview the replacement again",
+            ));
+        }
+
+        let rules = self
+            .db_datastore
+            .vpc_update_firewall_rules_if_unchanged(
+                opctx,
+                &authz_vpc,
+                current_rule_ids,
+                new_rules,
+            )
+            .await?;
+        self.send_sled_agents_firewall_rules(opctx, &db_vpc, &rules, &[])
+            .await?;
+        Ok(views::VpcFirewallRulesReplaceResult {
+            applied: true,
+            diff,
+            confirm_token,
+            rules: rules.into_iter().map(Into::into).collect(),
+        })
+    }
+
     /// Customize the default firewall rules for a particular VPC
     /// by replacing the name `default` with the VPC's actual name.
     pub(crate) async fn default_firewall_rules_for_vpc(
@@ -328,3 +402,77 @@ impl super::Nexus {
         }
     }
 }
+
+/// Convert firewall rules into their update form, keyed by name.
+///
+/// Both the current and desired rules are converted from their database form,
+/// so that equivalent rules compare equal.
+fn firewall_rules_by_name(
+    rules: Vec<db::model::VpcFirewallRule>,
+) -> BTreeMap<external::Name, external::VpcFirewallRuleUpdate> {
+    rules
+        .into_iter()
+        .map(|rule| {
+            let rule: external::VpcFirewallRule = rule.into();
+            let name = rule.identity.name.clone();
+            let update = external::VpcFirewallRuleUpdate {
+                name: rule.identity.name,
+                description: rule.identity.description,
+                status: rule.status,
+                direction: rule.direction,
+                targets: rule.targets,
+                filters: rule.filters,
+                action: rule.action,
+                priority: rule.priority,
+            };
+            (name, update)
+        })
+        .collect()
+}
+
+fn firewall_rules_diff(
+    current: &BTreeMap<external::Name, external::VpcFirewallRuleUpdate>,
+    desired: &BTreeMap<external::Name, external::VpcFirewallRuleUpdate>,
+) -> views::VpcFirewallRulesDiff {
+    let mut diff = views::VpcFirewallRulesDiff::default();
+    for (name, after) in desired {
+        match current.get(name) {
+            None => diff.added.push(after.clone()),
+            Some(before) if before != after => {
+                diff.modified.push(views::VpcFirewallRuleModification {
+                    before: before.clone(),
+                    after: after.clone(),
+                })
+            }
+            Some(_) => (),
+        }
+    }
+    diff.removed = current
+        .iter()
+        .filter(|(name, _)| !desired.contains_key(*name))
+        .map(|(_, rule)| rule.clone())
+        .collect();
+    diff
+}
+
+/// Compute the token that confirms replacing the rules with IDs
+/// `current_rule_ids` in `vpc_id` by `desired`.
+fn firewall_rules_confirm_token(
+    vpc_id: Uuid,
+    current_rule_ids: &BTreeSet<Uuid>,
+    desired: &BTreeMap<external::Name, external::VpcFirewallRuleUpdate>,
+) -> Result<String, Error> {
+    let desired = serde_json::to_vec(&desired.values().collect::<Vec<_>>())
+        .map_err(|e| {
+            Error::internal_error(&format!(
+                "failed to serialize firewall rules: {e}"
+            ))
+        })?;
+    let mut hasher = Sha256::new();
+    hasher.update(vpc_id.as_bytes());
+    for id in current_rule_ids {
+        hasher.update(id.as_bytes());
+    }
+    hasher.update(&desired);
+    Ok(hex::encode(hasher.finalize()))
+}
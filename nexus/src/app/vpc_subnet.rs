@@ -14,8 +14,10 @@ use nexus_db_queries::authz;
 use nexus_db_queries::context::OpContext;
 use nexus_db_queries::db;
 use nexus_db_queries::db::model::VpcSubnet;
+use nexus_db_queries::db::model::VpcSubnetIpReservation;
 use omicron_common::api::external;
 use omicron_common::api::external::CreateResult;
+use omicron_common::api::external::DataPageParams;
 use omicron_common::api::external::DeleteResult;
 use omicron_common::api::external::Error;
 use omicron_common::api::external::InternalContext;
@@ -25,6 +27,8 @@ use omicron_common::api::external::LookupResult;
 use omicron_common::api::external::NameOrId;
 use omicron_common::api::external::UpdateResult;
 use omicron_common::api::external::http_pagination::PaginatedBy;
+use std::net::IpAddr;
+use uuid::Uuid;
 
 impl super::Nexus {
     pub fn vpc_subnet_lookup<'a>(
@@ -85,22 +89,7 @@ impl super::Nexus {
         };
 
         // Validate IPv4 range
-        if !params.ipv4_block.prefix().is_private() {
-            return Err(external::Error::invalid_request(
-                "VPC Subnet IPv4 address ranges must be from a private range",
-            ));
-        }
-        if params.ipv4_block.width() < MIN_VPC_IPV4_SUBNET_PREFIX
-            || params.ipv4_block.width()
-                > self.tunables.max_vpc_ipv4_subnet_prefix
-        {
-            return Err(external::Error::invalid_request(&format!(
-                "VPC Subnet IPv4 address ranges must have prefix \
-                length between {} and {}, inclusive",
-                MIN_VPC_IPV4_SUBNET_PREFIX,
-                self.tunables.max_vpc_ipv4_subnet_prefix,
-            )));
-        }
+        self.validate_vpc_subnet_ipv4_block(&params.ipv4_block)?;
 
         // If the client provided an IPv6 range, we try to insert that or fail
         // with a conflict error.
@@ -169,6 +158,29 @@ impl super::Nexus {
         Ok(out)
     }
 
+    /// Check that `block` may be used as an IPv4 block of a VPC Subnet.
+    fn validate_vpc_subnet_ipv4_block(
+        &self,
+        block: &oxnet::Ipv4Net,
+    ) -> Result<(), Error> {
+        if !block.prefix().is_private() {
+            return Err(external::Error::invalid_request(
+                "VPC Subnet IPv4 address ranges must be from a private range",
+            ));
+        }
+        if block.width() < MIN_VPC_IPV4_SUBNET_PREFIX
+            || block.width() > self.tunables.max_vpc_ipv4_subnet_prefix
+        {
+            return Err(external::Error::invalid_request(&format!(
+                "VPC Subnet IPv4 address ranges must have prefix \
+                length between {} and {}, inclusive",
+                MIN_VPC_IPV4_SUBNET_PREFIX,
+                self.tunables.max_vpc_ipv4_subnet_prefix,
+            )));
+        }
+        Ok(())
+    }
+
     pub(crate) async fn vpc_subnet_list(
         &self,
         opctx: &OpContext,
@@ -247,6 +259,31 @@ impl super::Nexus {
         Ok(())
     }
 
+    pub(crate) async fn vpc_subnet_add_ipv4_block(
+        &self,
+        opctx: &OpContext,
+        vpc_subnet_lookup: &lookup::VpcSubnet<'_>,
+        params: &params::VpcSubnetIpv4BlockAdd,
+    ) -> UpdateResult<VpcSubnet> {
+        let (.., authz_vpc, authz_subnet) =
+            vpc_subnet_lookup.lookup_for(authz::Action::Modify).await?;
+        self.validate_vpc_subnet_ipv4_block(&params.block)?;
+
+        let out = self
+            .db_datastore
+            .vpc_subnet_add_ipv4_block(opctx, &authz_subnet, params.block)
+            .await?;
+
+        // Routes to the subnet, and custom routers attached to it, now cover
+        // the new block too.
+        self.db_datastore
+            .vpc_increment_rpw_version(opctx, authz_vpc.id())
+            .await?;
+        self.vpc_needed_notify_sleds();
+
+        Ok(out)
+    }
+
     pub(crate) async fn subnet_list_instance_network_interfaces(
         &self,
         opctx: &OpContext,
@@ -263,4 +300,58 @@ impl super::Nexus {
             )
             .await
     }
+
+    pub(crate) async fn vpc_subnet_ip_reservation_create(
+        &self,
+        opctx: &OpContext,
+        subnet_lookup: &lookup::VpcSubnet<'_>,
+        params: &params::VpcSubnetIpReservationCreate,
+    ) -> CreateResult<VpcSubnetIpReservation> {
+        let (.., authz_subnet, db_subnet) =
+            subnet_lookup.fetch_for(authz::Action::CreateChild).await?;
+
+        // Interfaces are only ever automatically assigned IPv4 addresses, so
+        // there's nothing to gain from reserving an IPv6 address.
+        let IpAddr::V4(ip) = params.ip else {
+            return Err(Error::invalid_request(
+                "only IPv4 addresses may be reserved",
+            ));
+        };
+        db_subnet.check_requestable_addr(params.ip)?;
+
+        let reservation = VpcSubnetIpReservation::new(
+            &db_subnet,
+            ip,
+            params.description.clone(),
+        );
+        self.db_datastore
+            .vpc_subnet_ip_reservation_create(opctx, &authz_subnet, reservation)
+            .await
+    }
+
+    pub(crate) async fn vpc_subnet_ip_reservation_list(
+        &self,
+        opctx: &OpContext,
+        subnet_lookup: &lookup::VpcSubnet<'_>,
+        pagparams: &DataPageParams<'_, Uuid>,
+    ) -> ListResultVec<VpcSubnetIpReservation> {
+        let (.., authz_subnet) =
+            subnet_lookup.lookup_for(authz::Action::ListChildren).await?;
+        self.db_datastore
+            .vpc_subnet_ip_reservation_list(opctx, &authz_subnet, pagparams)
+            .await
+    }
+
+    pub(crate) async fn vpc_subnet_ip_reservation_delete(
+        &self,
+        opctx: &OpContext,
+        subnet_lookup: &lookup::VpcSubnet<'_>,
+        ip: IpAddr,
+    ) -> DeleteResult {
+        let (.., authz_subnet) =
+            subnet_lookup.lookup_for(authz::Action::Modify).await?;
+        self.db_datastore
+            .vpc_subnet_ip_reservation_delete(opctx, &authz_subnet, ip)
+            .await
+    }
 }
@@ -3,6 +3,7 @@
 
 //! Shared state used by API request handlers
 use super::Nexus;
+use crate::app::RuntimeLogLevel;
 use crate::saga_interface::SagaContext;
 use async_trait::async_trait;
 use authn::external::HttpAuthnScheme;
@@ -57,12 +58,15 @@ pub struct ApiContext {
 impl ApiContext {
     /// Create a new context with a rack ID and logger. This creates the
     /// underlying `Nexus` as well.
+    ///
+    /// `log_level` controls the level of `log`.
     pub async fn for_internal(
         rack_id: Uuid,
         log: Logger,
+        log_level: RuntimeLogLevel,
         config: &NexusConfig,
     ) -> Result<Self, String> {
-        ServerContext::new(rack_id, log, config)
+        ServerContext::new(rack_id, log, log_level, config)
             .await
             .map(|context| Self { kind: ServerKind::Internal, context })
     }
@@ -124,6 +128,7 @@ impl ServerContext {
     pub async fn new(
         rack_id: Uuid,
         log: Logger,
+        log_level: RuntimeLogLevel,
         config: &NexusConfig,
     ) -> Result<Arc<ServerContext>, String> {
         let nexus_schemes = config
@@ -295,6 +300,7 @@ impl ServerContext {
             &producer_registry,
             config,
             Arc::clone(&authz),
+            log_level,
         )
         .await
         {
@@ -330,10 +336,17 @@ impl ServerContext {
 
 /// Authenticates an incoming request to the external API and produces a new
 /// operation context for it
+///
+/// If this Nexus is draining (see [`Nexus::is_draining()`]), requests that
+/// could start new operations are turned away with a 503 that tells the client
+/// when to retry.  Requests that only read state are still served.
 pub(crate) async fn op_context_for_external_api(
     rqctx: &dropshot::RequestContext<ApiContext>,
 ) -> Result<OpContext, dropshot::HttpError> {
     let apictx = rqctx.context();
+    if apictx.context.nexus.is_draining() && !rqctx.request.method().is_safe() {
+        return Err(draining_error());
+    }
     OpContext::new_async(
         &rqctx.log,
         async {
@@ -354,6 +367,30 @@ pub(crate) async fn op_context_for_external_api(
     .await
 }
 
+/// How long clients turned away by a draining Nexus are asked to wait before
+/// retrying (by which time they should have found another Nexus in DNS)
+const DRAINING_RETRY_AFTER: std::time::Duration =
+    std::time::Duration::from_secs(5);
+
+/// Returns the error used to turn away external requests while this Nexus is
+/// draining
+fn draining_error() -> dropshot::HttpError {
+    let mut error = dropshot::HttpError::for_unavail(
+        None,
+        String::from("Nexus is draining"),
+    );
+    error.external_message = String::from(
+        "This Nexus is shutting down. Retry the request to reach another one.",
+    );
+    error
+        .add_header(
+            http::header::RETRY_AFTER,
+            DRAINING_RETRY_AFTER.as_secs().to_string(),
+        )
+        .expect("Retry-After value is a valid header value");
+    error
+}
+
 pub(crate) async fn op_context_for_internal_api(
     rqctx: &dropshot::RequestContext<ApiContext>,
 ) -> OpContext {
@@ -44,6 +44,8 @@ use nexus_db_lookup::lookup::ImageParentLookup;
 use nexus_db_queries::authn::external::session_cookie::{self, SessionStore};
 use nexus_db_queries::authz;
 use nexus_db_queries::db;
+use nexus_db_queries::db::datastore::ImageFilters;
+use nexus_db_queries::db::datastore::RackActivityFilters;
 use nexus_db_queries::db::identity::Resource;
 use nexus_db_queries::db::model::Name;
 use nexus_external_api::*;
@@ -94,13 +96,17 @@ use omicron_common::api::external::http_pagination::PaginatedById;
 use omicron_common::api::external::http_pagination::PaginatedByName;
 use omicron_common::api::external::http_pagination::PaginatedByNameOrId;
 use omicron_common::api::external::http_pagination::PaginatedByTimeAndId;
+use omicron_common::api::external::http_pagination::PaginatedImages;
 use omicron_common::api::external::http_pagination::ScanById;
 use omicron_common::api::external::http_pagination::ScanByName;
 use omicron_common::api::external::http_pagination::ScanByNameOrId;
 use omicron_common::api::external::http_pagination::ScanByTimeAndId;
+use omicron_common::api::external::http_pagination::ScanImages;
 use omicron_common::api::external::http_pagination::ScanParams;
 use omicron_common::api::external::http_pagination::data_page_params_for;
+use omicron_common::api::external::http_pagination::image_pagination;
 use omicron_common::api::external::http_pagination::marker_for_id;
+use omicron_common::api::external::http_pagination::marker_for_image;
 use omicron_common::api::external::http_pagination::marker_for_name;
 use omicron_common::api::external::http_pagination::marker_for_name_or_id;
 use omicron_common::api::external::http_pagination::name_or_id_pagination;
@@ -126,9 +132,125 @@ pub(crate) fn external_api() -> NexusApiDescription {
 
 enum NexusExternalApiImpl {}
 
+// Handler bodies shared by several versions of the same endpoint. Older
+// versions convert their parameters to the latest types and call these.
+impl NexusExternalApiImpl {
+    async fn instance_create_common(
+        rqctx: RequestContext<ApiContext>,
+        project_selector: params::ProjectSelector,
+        new_instance_params: params::InstanceCreate,
+    ) -> Result<HttpResponseCreated<Instance>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let audit = nexus.audit_log_entry_init(&opctx, &rqctx).await?;
+
+            let result = async {
+                let project_lookup =
+                    nexus.project_lookup(&opctx, project_selector)?;
+                let instance = nexus
+                    .project_create_instance(
+                        &opctx,
+                        &project_lookup,
+                        &new_instance_params,
+                    )
+                    .await?;
+                let instance = nexus.instance_view(&opctx, instance).await?;
+                Ok(HttpResponseCreated(instance))
+            }
+            .await;
+
+            let _ =
+                nexus.audit_log_entry_complete(&opctx, &audit, &result).await;
+            result
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_update_common(
+        rqctx: RequestContext<ApiContext>,
+        query: params::OptionalProjectSelector,
+        path: params::InstancePath,
+        reconfigure_params: params::InstanceUpdate,
+    ) -> Result<HttpResponseOk<Instance>, HttpError> {
+        let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let instance_selector = params::InstanceSelector {
+            project: query.project,
+            instance: path.instance,
+        };
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let instance_lookup =
+                nexus.instance_lookup(&opctx, instance_selector)?;
+            let instance = nexus
+                .instance_reconfigure(
+                    &opctx,
+                    &instance_lookup,
+                    &reconfigure_params,
+                )
+                .await?;
+            let instance = nexus.instance_view(&opctx, instance).await?;
+            Ok(HttpResponseOk(instance))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+}
+
 impl NexusExternalApi for NexusExternalApiImpl {
     type Context = ApiContext;
 
+    async fn system_background_task_list(
+        rqctx: RequestContext<ApiContext>,
+    ) -> Result<HttpResponseOk<Vec<views::BackgroundTask>>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let tasks = nexus.bgtasks_list_external(&opctx).await?;
+            Ok(HttpResponseOk(tasks))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn system_background_task_history(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::BackgroundTaskPath>,
+    ) -> Result<HttpResponseOk<Vec<views::BackgroundTaskActivation>>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let history =
+                nexus.bgtask_history_external(&opctx, &path.name).await?;
+            Ok(HttpResponseOk(history))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn system_policy_view(
         rqctx: RequestContext<ApiContext>,
     ) -> Result<HttpResponseOk<shared::Policy<shared::FleetRole>>, HttpError>
@@ -451,6 +573,117 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn project_quotas_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseOk<views::ProjectQuotas>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let project_selector = params::ProjectSelector {
+                project: path_params.into_inner().project,
+            };
+            let project_lookup =
+                nexus.project_lookup(&opctx, project_selector)?;
+            let quota =
+                nexus.project_quotas_view(&opctx, &project_lookup).await?;
+            Ok(HttpResponseOk(quota.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn project_quotas_create(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::ProjectPath>,
+        new_quota: TypedBody<params::ProjectQuotasCreate>,
+    ) -> Result<HttpResponseCreated<views::ProjectQuotas>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let project_selector = params::ProjectSelector {
+                project: path_params.into_inner().project,
+            };
+            let project_lookup =
+                nexus.project_lookup(&opctx, project_selector)?;
+            let quota = nexus
+                .project_quotas_create(
+                    &opctx,
+                    &project_lookup,
+                    &new_quota.into_inner(),
+                )
+                .await?;
+            Ok(HttpResponseCreated(quota.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn project_quotas_update(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::ProjectPath>,
+        new_quota: TypedBody<params::ProjectQuotasUpdate>,
+    ) -> Result<HttpResponseOk<views::ProjectQuotas>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let project_selector = params::ProjectSelector {
+                project: path_params.into_inner().project,
+            };
+            let project_lookup =
+                nexus.project_lookup(&opctx, project_selector)?;
+            let quota = nexus
+                .project_quotas_update(
+                    &opctx,
+                    &project_lookup,
+                    &new_quota.into_inner(),
+                )
+                .await?;
+            Ok(HttpResponseOk(quota.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn project_quotas_delete(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseDeleted, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let project_selector = params::ProjectSelector {
+                project: path_params.into_inner().project,
+            };
+            let project_lookup =
+                nexus.project_lookup(&opctx, project_selector)?;
+            nexus.project_quotas_delete(&opctx, &project_lookup).await?;
+            Ok(HttpResponseDeleted())
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn silo_list(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<PaginatedByNameOrId>,
@@ -1091,35 +1324,22 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    // IP Pools
-
-    async fn project_ip_pool_list(
+    async fn project_instance_auto_restart_view(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<PaginatedByNameOrId>,
-    ) -> Result<HttpResponseOk<ResultsPage<views::SiloIpPool>>, HttpError> {
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseOk<views::ProjectInstanceAutoRestart>, HttpError>
+    {
         let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let path = path_params.into_inner();
         let handler = async {
-            let nexus = &apictx.context.nexus;
-            let query = query_params.into_inner();
-            let pag_params = data_page_params_for(&rqctx, &query)?;
-            let scan_params = ScanByNameOrId::from_query(&query)?;
-            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
-            let pools = nexus
-                .current_silo_ip_pool_list(&opctx, &paginated_by)
-                .await?
-                .into_iter()
-                .map(|(pool, silo_link)| views::SiloIpPool {
-                    identity: pool.identity(),
-                    is_default: silo_link.is_default,
-                })
-                .collect();
-            Ok(HttpResponseOk(ScanByNameOrId::results_page(
-                &query,
-                pools,
-                &marker_for_name_or_id,
-            )?))
+            let project_selector =
+                params::ProjectSelector { project: path.project };
+            let (.., project) =
+                nexus.project_lookup(&opctx, project_selector)?.fetch().await?;
+            Ok(HttpResponseOk(project.into()))
         };
         apictx
             .context
@@ -1128,22 +1348,31 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn project_ip_pool_view(
+    async fn project_instance_auto_restart_update(
         rqctx: RequestContext<ApiContext>,
-        path_params: Path<params::IpPoolPath>,
-    ) -> Result<HttpResponseOk<views::SiloIpPool>, HttpError> {
+        path_params: Path<params::ProjectPath>,
+        new_defaults: TypedBody<params::ProjectInstanceAutoRestartUpdate>,
+    ) -> Result<HttpResponseOk<views::ProjectInstanceAutoRestart>, HttpError>
+    {
         let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let path = path_params.into_inner();
+        let new_defaults = new_defaults.into_inner();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
-            let nexus = &apictx.context.nexus;
-            let pool_selector = path_params.into_inner().pool;
-            let (.., pool, silo_link) =
-                nexus.silo_ip_pool_fetch(&opctx, &pool_selector).await?;
-            Ok(HttpResponseOk(views::SiloIpPool {
-                identity: pool.identity(),
-                is_default: silo_link.is_default,
-            }))
+            let project_selector =
+                params::ProjectSelector { project: path.project };
+            let project_lookup =
+                nexus.project_lookup(&opctx, project_selector)?;
+            let project = nexus
+                .project_instance_auto_restart_update(
+                    &opctx,
+                    &project_lookup,
+                    &new_defaults,
+                )
+                .await?;
+            Ok(HttpResponseOk(project.into()))
         };
         apictx
             .context
@@ -1152,25 +1381,146 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn ip_pool_list(
+    async fn project_ephemeral_ip_policy_view(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<PaginatedByNameOrId>,
-    ) -> Result<HttpResponseOk<ResultsPage<IpPool>>, HttpError> {
+        path_params: Path<params::ProjectPath>,
+    ) -> Result<HttpResponseOk<views::ProjectEphemeralIpPolicy>, HttpError>
+    {
         let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let path = path_params.into_inner();
         let handler = async {
-            let nexus = &apictx.context.nexus;
-            let query = query_params.into_inner();
-            let pag_params = data_page_params_for(&rqctx, &query)?;
-            let scan_params = ScanByNameOrId::from_query(&query)?;
-            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
-            let pools = nexus
-                .ip_pools_list(&opctx, &paginated_by)
-                .await?
-                .into_iter()
-                .map(IpPool::from)
-                .collect();
+            let project_selector =
+                params::ProjectSelector { project: path.project };
+            let project_lookup =
+                nexus.project_lookup(&opctx, project_selector)?;
+            let policy = nexus
+                .project_ephemeral_ip_policy_view(&opctx, &project_lookup)
+                .await?;
+            Ok(HttpResponseOk(policy.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn project_ephemeral_ip_policy_update(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::ProjectPath>,
+        new_policy: TypedBody<params::ProjectEphemeralIpPolicyUpdate>,
+    ) -> Result<HttpResponseOk<views::ProjectEphemeralIpPolicy>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let path = path_params.into_inner();
+        let new_policy = new_policy.into_inner();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let project_selector =
+                params::ProjectSelector { project: path.project };
+            let project_lookup =
+                nexus.project_lookup(&opctx, project_selector)?;
+            let policy = nexus
+                .project_ephemeral_ip_policy_update(
+                    &opctx,
+                    &project_lookup,
+                    &new_policy,
+                )
+                .await?;
+            Ok(HttpResponseOk(policy.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    // IP Pools
+
+    async fn project_ip_pool_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedByNameOrId>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::SiloIpPool>>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanByNameOrId::from_query(&query)?;
+            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let pools = nexus
+                .current_silo_ip_pool_list(&opctx, &paginated_by)
+                .await?
+                .into_iter()
+                .map(|(pool, silo_link)| views::SiloIpPool {
+                    identity: pool.identity(),
+                    is_default: silo_link.is_default,
+                })
+                .collect();
+            Ok(HttpResponseOk(ScanByNameOrId::results_page(
+                &query,
+                pools,
+                &marker_for_name_or_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn project_ip_pool_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::IpPoolPath>,
+    ) -> Result<HttpResponseOk<views::SiloIpPool>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let pool_selector = path_params.into_inner().pool;
+            let (.., pool, silo_link) =
+                nexus.silo_ip_pool_fetch(&opctx, &pool_selector).await?;
+            Ok(HttpResponseOk(views::SiloIpPool {
+                identity: pool.identity(),
+                is_default: silo_link.is_default,
+            }))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn ip_pool_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedByNameOrId>,
+    ) -> Result<HttpResponseOk<ResultsPage<IpPool>>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanByNameOrId::from_query(&query)?;
+            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let pools = nexus
+                .ip_pools_list(&opctx, &paginated_by)
+                .await?
+                .into_iter()
+                .map(IpPool::from)
+                .collect();
             Ok(HttpResponseOk(ScanByNameOrId::results_page(
                 &query,
                 pools,
@@ -1424,6 +1774,92 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn ip_pool_project_list(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::IpPoolPath>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::IpPoolProjectLink>>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+
+            let path = path_params.into_inner();
+            let pool_lookup = nexus.ip_pool_lookup(&opctx, &path.pool)?;
+
+            let assocs = nexus
+                .ip_pool_project_list(&opctx, &pool_lookup, &pag_params)
+                .await?
+                .into_iter()
+                .map(|assoc| assoc.into())
+                .collect();
+
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                assocs,
+                &|_, x: &views::IpPoolProjectLink| x.project_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn ip_pool_project_link(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::IpPoolPath>,
+        resource_assoc: TypedBody<params::IpPoolLinkProject>,
+    ) -> Result<HttpResponseCreated<views::IpPoolProjectLink>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let resource_assoc = resource_assoc.into_inner();
+            let pool_lookup = nexus.ip_pool_lookup(&opctx, &path.pool)?;
+            let assoc = nexus
+                .ip_pool_link_project(&opctx, &pool_lookup, &resource_assoc)
+                .await?;
+            Ok(HttpResponseCreated(assoc.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn ip_pool_project_unlink(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::IpPoolProjectPath>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let pool_lookup = nexus.ip_pool_lookup(&opctx, &path.pool)?;
+            nexus
+                .ip_pool_unlink_project(&opctx, &pool_lookup, path.project)
+                .await?;
+            Ok(HttpResponseUpdatedNoContent())
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn ip_pool_service_view(
         rqctx: RequestContext<ApiContext>,
     ) -> Result<HttpResponseOk<views::IpPool>, HttpError> {
@@ -1878,10 +2314,12 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 let query = query_params.into_inner();
                 let params = new_disk.into_inner();
                 let project_lookup = nexus.project_lookup(&opctx, query)?;
-                let disk = nexus
+                let (disk, warnings) = nexus
                     .project_create_disk(&opctx, &project_lookup, &params)
                     .await?;
-                Ok(HttpResponseCreated(disk.into()))
+                let mut disk: Disk = disk.into();
+                disk.warnings = warnings;
+                Ok(HttpResponseCreated(disk))
             }
             .await;
 
@@ -1923,6 +2361,36 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn disk_update(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        updated_disk: TypedBody<params::DiskUpdate>,
+    ) -> Result<HttpResponseOk<Disk>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let updated_disk = updated_disk.into_inner();
+            let disk_selector = params::DiskSelector {
+                disk: path.disk,
+                project: query.project,
+            };
+            let disk_lookup = nexus.disk_lookup(&opctx, disk_selector)?;
+            let disk =
+                nexus.disk_update(&opctx, &disk_lookup, &updated_disk).await?;
+            Ok(HttpResponseOk(disk.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn disk_delete(
         rqctx: RequestContext<ApiContext>,
         path_params: Path<params::DiskPath>,
@@ -2082,34 +2550,28 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    // Instances
-
-    async fn instance_list(
+    async fn disk_clone_v20260615(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<PaginatedByNameOrId<params::ProjectSelector>>,
-    ) -> Result<HttpResponseOk<ResultsPage<Instance>>, HttpError> {
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        clone_params: TypedBody<params::DiskClone>,
+    ) -> Result<HttpResponseCreated<Disk>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
-            let nexus = &apictx.context.nexus;
-            let query = query_params.into_inner();
-            let pag_params = data_page_params_for(&rqctx, &query)?;
-            let scan_params = ScanByNameOrId::from_query(&query)?;
-            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
-            let project_lookup =
-                nexus.project_lookup(&opctx, scan_params.selector.clone())?;
-            let instances = nexus
-                .instance_list(&opctx, &project_lookup, &paginated_by)
-                .await?
-                .into_iter()
-                .map(|i| i.into())
-                .collect();
-            Ok(HttpResponseOk(ScanByNameOrId::results_page(
-                &query,
-                instances,
-                &marker_for_name_or_id,
-            )?))
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let params = clone_params.into_inner();
+            let disk_selector = params::DiskSelector {
+                disk: path.disk,
+                project: query.project,
+            };
+            let disk_lookup = nexus.disk_lookup(&opctx, disk_selector)?;
+            let disk =
+                nexus.disk_clone(&opctx, &disk_lookup, &params, None).await?;
+            Ok(HttpResponseCreated(disk.into()))
         };
         apictx
             .context
@@ -2118,11 +2580,70 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn instance_create(
+    async fn disk_clone(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<params::ProjectSelector>,
-        new_instance: TypedBody<params::InstanceCreate>,
-    ) -> Result<HttpResponseCreated<Instance>, HttpError> {
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        clone_params: TypedBody<params::DiskClone>,
+    ) -> Result<HttpResponseAccepted<views::Operation>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let params = clone_params.into_inner();
+            let disk_selector = params::DiskSelector {
+                disk: path.disk,
+                project: query.project,
+            };
+            let disk_lookup = nexus.disk_lookup(&opctx, disk_selector)?;
+            let operation = nexus
+                .disk_clone_operation(&opctx, &disk_lookup, params)
+                .await?;
+            Ok(HttpResponseAccepted(operation))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn disk_copy_progress_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseOk<views::DiskCopyProgress>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let disk_selector = params::DiskSelector {
+                disk: path.disk,
+                project: query.project,
+            };
+            let disk_lookup = nexus.disk_lookup(&opctx, disk_selector)?;
+            let progress = nexus.disk_copy_progress(&disk_lookup).await?;
+            Ok(HttpResponseOk(progress))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn disk_force_detach(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::DiskPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        force_detach_params: TypedBody<params::DiskForceDetach>,
+    ) -> Result<HttpResponseOk<Disk>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
@@ -2131,18 +2652,22 @@ impl NexusExternalApi for NexusExternalApiImpl {
             let audit = nexus.audit_log_entry_init(&opctx, &rqctx).await?;
 
             let result = async {
-                let project_selector = query_params.into_inner();
-                let new_instance_params = &new_instance.into_inner();
-                let project_lookup =
-                    nexus.project_lookup(&opctx, project_selector)?;
-                let instance = nexus
-                    .project_create_instance(
+                let path = path_params.into_inner();
+                let query = query_params.into_inner();
+                let force_detach_params = force_detach_params.into_inner();
+                let disk_selector = params::DiskSelector {
+                    disk: path.disk,
+                    project: query.project,
+                };
+                let disk_lookup = nexus.disk_lookup(&opctx, disk_selector)?;
+                let disk = nexus
+                    .disk_force_detach(
                         &opctx,
-                        &project_lookup,
-                        &new_instance_params,
+                        &disk_lookup,
+                        &force_detach_params,
                     )
                     .await?;
-                Ok(HttpResponseCreated(instance.into()))
+                Ok(HttpResponseOk(disk.into()))
             }
             .await;
 
@@ -2157,6 +2682,105 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    // Instances
+
+    async fn instance_list_v20260201(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedByNameOrId<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<Instance>>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanByNameOrId::from_query(&query)?;
+            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let project_lookup =
+                nexus.project_lookup(&opctx, scan_params.selector.clone())?;
+            let instances = nexus
+                .instance_list(&opctx, &project_lookup, None, &paginated_by)
+                .await?;
+            let instances = nexus.instance_views(&opctx, instances).await?;
+            Ok(HttpResponseOk(ScanByNameOrId::results_page(
+                &query,
+                instances,
+                &marker_for_name_or_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedByNameOrId<params::InstanceListSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<Instance>>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanByNameOrId::from_query(&query)?;
+            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let selector = scan_params.selector.clone();
+            let tag = selector.tag.clone();
+            let project_lookup =
+                nexus.project_lookup(&opctx, selector.into())?;
+            let instances = nexus
+                .instance_list(
+                    &opctx,
+                    &project_lookup,
+                    tag.as_deref(),
+                    &paginated_by,
+                )
+                .await?;
+            let instances = nexus.instance_views(&opctx, instances).await?;
+            Ok(HttpResponseOk(ScanByNameOrId::results_page(
+                &query,
+                instances,
+                &marker_for_name_or_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_create_v20250915(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::ProjectSelector>,
+        new_instance: TypedBody<params::v20250915::InstanceCreate>,
+    ) -> Result<HttpResponseCreated<Instance>, HttpError> {
+        Self::instance_create_common(
+            rqctx,
+            query_params.into_inner(),
+            new_instance.into_inner().into(),
+        )
+        .await
+    }
+
+    async fn instance_create(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::ProjectSelector>,
+        new_instance: TypedBody<params::InstanceCreate>,
+    ) -> Result<HttpResponseCreated<Instance>, HttpError> {
+        Self::instance_create_common(
+            rqctx,
+            query_params.into_inner(),
+            new_instance.into_inner(),
+        )
+        .await
+    }
+
     async fn instance_view(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<params::OptionalProjectSelector>,
@@ -2181,7 +2805,9 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 .datastore()
                 .instance_fetch_with_vmm(&opctx, &authz_instance)
                 .await?;
-            Ok(HttpResponseOk(instance_and_vmm.into()))
+            let instance =
+                nexus.instance_view(&opctx, instance_and_vmm).await?;
+            Ok(HttpResponseOk(instance))
         };
         apictx
             .context
@@ -2229,40 +2855,34 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn instance_update_v20250915(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+        reconfigure_params: TypedBody<params::v20250915::InstanceUpdate>,
+    ) -> Result<HttpResponseOk<Instance>, HttpError> {
+        Self::instance_update_common(
+            rqctx,
+            query_params.into_inner(),
+            path_params.into_inner(),
+            reconfigure_params.into_inner().into(),
+        )
+        .await
+    }
+
     async fn instance_update(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<params::OptionalProjectSelector>,
         path_params: Path<params::InstancePath>,
         reconfigure_params: TypedBody<params::InstanceUpdate>,
     ) -> Result<HttpResponseOk<Instance>, HttpError> {
-        let apictx = rqctx.context();
-        let nexus = &apictx.context.nexus;
-        let path = path_params.into_inner();
-        let query = query_params.into_inner();
-        let reconfigure_params = reconfigure_params.into_inner();
-        let instance_selector = params::InstanceSelector {
-            project: query.project,
-            instance: path.instance,
-        };
-        let handler = async {
-            let opctx =
-                crate::context::op_context_for_external_api(&rqctx).await?;
-            let instance_lookup =
-                nexus.instance_lookup(&opctx, instance_selector)?;
-            let instance = nexus
-                .instance_reconfigure(
-                    &opctx,
-                    &instance_lookup,
-                    &reconfigure_params,
-                )
-                .await?;
-            Ok(HttpResponseOk(instance.into()))
-        };
-        apictx
-            .context
-            .external_latencies
-            .instrument_dropshot_handler(&rqctx, handler)
-            .await
+        Self::instance_update_common(
+            rqctx,
+            query_params.into_inner(),
+            path_params.into_inner(),
+            reconfigure_params.into_inner(),
+        )
+        .await
     }
 
     async fn instance_reboot(
@@ -2285,7 +2905,8 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 nexus.instance_lookup(&opctx, instance_selector)?;
             let instance =
                 nexus.instance_reboot(&opctx, &instance_lookup).await?;
-            Ok(HttpResponseAccepted(instance.into()))
+            let instance = nexus.instance_view(&opctx, instance).await?;
+            Ok(HttpResponseAccepted(instance))
         };
         apictx
             .context
@@ -2319,7 +2940,8 @@ impl NexusExternalApi for NexusExternalApiImpl {
                     crate::app::sagas::instance_start::Reason::User,
                 )
                 .await?;
-            Ok(HttpResponseAccepted(instance.into()))
+            let instance = nexus.instance_view(&opctx, instance).await?;
+            Ok(HttpResponseAccepted(instance))
         };
         apictx
             .context
@@ -2348,7 +2970,8 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 nexus.instance_lookup(&opctx, instance_selector)?;
             let instance =
                 nexus.instance_stop(&opctx, &instance_lookup).await?;
-            Ok(HttpResponseAccepted(instance.into()))
+            let instance = nexus.instance_view(&opctx, instance).await?;
+            Ok(HttpResponseAccepted(instance))
         };
         apictx
             .context
@@ -2357,29 +2980,19 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn instance_serial_console(
+    async fn instance_bulk_action(
         rqctx: RequestContext<ApiContext>,
-        path_params: Path<params::InstancePath>,
-        query_params: Query<params::InstanceSerialConsoleRequest>,
-    ) -> Result<HttpResponseOk<params::InstanceSerialConsoleData>, HttpError>
+        params: TypedBody<params::InstanceBulkAction>,
+    ) -> Result<HttpResponseOk<views::InstanceBulkActionResults>, HttpError>
     {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let nexus = &apictx.context.nexus;
-            let path = path_params.into_inner();
-            let query = query_params.into_inner();
-            let instance_selector = params::InstanceSelector {
-                project: query.project.clone(),
-                instance: path.instance,
-            };
-            let instance_lookup =
-                nexus.instance_lookup(&opctx, instance_selector)?;
-            let data = nexus
-                .instance_serial_console_data(&opctx, &instance_lookup, &query)
-                .await?;
-            Ok(HttpResponseOk(data))
+            let results =
+                nexus.instance_bulk_action(&opctx, params.into_inner()).await?;
+            Ok(HttpResponseOk(views::InstanceBulkActionResults { results }))
         };
         apictx
             .context
@@ -2388,57 +3001,207 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn instance_serial_console_stream(
+    async fn instance_force_fail(
         rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::OptionalProjectSelector>,
         path_params: Path<params::InstancePath>,
-        query_params: Query<params::InstanceSerialConsoleStreamRequest>,
-        conn: WebsocketConnection,
-    ) -> WebsocketChannelResult {
+        force_fail_params: TypedBody<params::InstanceForceFail>,
+    ) -> Result<HttpResponseAccepted<Instance>, HttpError> {
         let apictx = rqctx.context();
-        let nexus = &apictx.context.nexus;
-        let path = path_params.into_inner();
-        let query = query_params.into_inner();
-        let opctx = crate::context::op_context_for_external_api(&rqctx).await?;
-        let instance_selector = params::InstanceSelector {
-            project: query.project.clone(),
-            instance: path.instance,
-        };
-        let mut client_stream = WebSocketStream::from_raw_socket(
-            conn.into_inner(),
-            WebSocketRole::Server,
-            None,
-        )
-        .await;
-        match nexus.instance_lookup(&opctx, instance_selector) {
-            Ok(instance_lookup) => {
-                nexus
-                    .instance_serial_console_stream(
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let audit = nexus.audit_log_entry_init(&opctx, &rqctx).await?;
+
+            let result = async {
+                let path = path_params.into_inner();
+                let query = query_params.into_inner();
+                let force_fail_params = force_fail_params.into_inner();
+                let instance_selector = params::InstanceSelector {
+                    project: query.project,
+                    instance: path.instance,
+                };
+                let instance_lookup =
+                    nexus.instance_lookup(&opctx, instance_selector)?;
+                let instance = nexus
+                    .instance_force_fail(
                         &opctx,
-                        client_stream,
                         &instance_lookup,
-                        &query,
+                        &force_fail_params,
                     )
                     .await?;
-                Ok(())
-            }
-            Err(e) => {
-                let _ = client_stream
-                    .close(Some(CloseFrame {
-                        code: CloseCode::Error,
-                        reason: e.to_string().into(),
-                    }))
-                    .await
-                    .is_ok();
-                Err(e.into())
+                let instance = nexus.instance_view(&opctx, instance).await?;
+                Ok(HttpResponseAccepted(instance))
             }
-        }
-    }
+            .await;
 
-    async fn instance_ssh_public_key_list(
-        rqctx: RequestContext<ApiContext>,
-        path_params: Path<params::InstancePath>,
-        query_params: Query<
-            PaginatedByNameOrId<params::OptionalProjectSelector>,
+            let _ =
+                nexus.audit_log_entry_complete(&opctx, &audit, &result).await;
+            result
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_export_v20260615(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+        export_params: TypedBody<params::InstanceExportCreate>,
+    ) -> Result<HttpResponseCreated<views::SnapshotExport>, HttpError> {
+        let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let path = path_params.into_inner();
+        let query = query_params.into_inner();
+        let export_params = export_params.into_inner();
+        let instance_selector = params::InstanceSelector {
+            project: query.project,
+            instance: path.instance,
+        };
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let instance_lookup =
+                nexus.instance_lookup(&opctx, instance_selector)?;
+            let export = nexus
+                .instance_export_create(
+                    &opctx,
+                    &instance_lookup,
+                    &export_params,
+                    None,
+                )
+                .await?;
+            Ok(HttpResponseCreated(export))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_export(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+        export_params: TypedBody<params::InstanceExportCreate>,
+    ) -> Result<HttpResponseAccepted<views::Operation>, HttpError> {
+        let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let path = path_params.into_inner();
+        let query = query_params.into_inner();
+        let export_params = export_params.into_inner();
+        let instance_selector = params::InstanceSelector {
+            project: query.project,
+            instance: path.instance,
+        };
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let instance_lookup =
+                nexus.instance_lookup(&opctx, instance_selector)?;
+            let operation = nexus
+                .instance_export_operation(
+                    &opctx,
+                    &instance_lookup,
+                    export_params,
+                )
+                .await?;
+            Ok(HttpResponseAccepted(operation))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_serial_console(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::InstancePath>,
+        query_params: Query<params::InstanceSerialConsoleRequest>,
+    ) -> Result<HttpResponseOk<params::InstanceSerialConsoleData>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let instance_selector = params::InstanceSelector {
+                project: query.project.clone(),
+                instance: path.instance,
+            };
+            let instance_lookup =
+                nexus.instance_lookup(&opctx, instance_selector)?;
+            let data = nexus
+                .instance_serial_console_data(&opctx, &instance_lookup, &query)
+                .await?;
+            Ok(HttpResponseOk(data))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_serial_console_stream(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::InstancePath>,
+        query_params: Query<params::InstanceSerialConsoleStreamRequest>,
+        conn: WebsocketConnection,
+    ) -> WebsocketChannelResult {
+        let apictx = rqctx.context();
+        let nexus = &apictx.context.nexus;
+        let path = path_params.into_inner();
+        let query = query_params.into_inner();
+        let opctx = crate::context::op_context_for_external_api(&rqctx).await?;
+        let instance_selector = params::InstanceSelector {
+            project: query.project.clone(),
+            instance: path.instance,
+        };
+        let mut client_stream = WebSocketStream::from_raw_socket(
+            conn.into_inner(),
+            WebSocketRole::Server,
+            None,
+        )
+        .await;
+        match nexus.instance_lookup(&opctx, instance_selector) {
+            Ok(instance_lookup) => {
+                nexus
+                    .instance_serial_console_stream(
+                        &opctx,
+                        client_stream,
+                        &instance_lookup,
+                        &query,
+                    )
+                    .await?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = client_stream
+                    .close(Some(CloseFrame {
+                        code: CloseCode::Error,
+                        reason: e.to_string().into(),
+                    }))
+                    .await
+                    .is_ok();
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn instance_ssh_public_key_list(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::InstancePath>,
+        query_params: Query<
+            PaginatedByNameOrId<params::OptionalProjectSelector>,
         >,
     ) -> Result<HttpResponseOk<ResultsPage<SshKey>>, HttpError> {
         let apictx = rqctx.context();
@@ -2476,6 +3239,86 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn instance_migration_list(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::InstancePath>,
+        query_params: Query<PaginatedById<params::OptionalProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::InstanceMigration>>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanById::from_query(&query)?;
+            let instance_selector = params::InstanceSelector {
+                project: scan_params.selector.project.clone(),
+                instance: path.instance,
+            };
+            let instance_lookup =
+                nexus.instance_lookup(&opctx, instance_selector)?;
+            let migrations = nexus
+                .instance_migration_list(&opctx, &instance_lookup, &pag_params)
+                .await?;
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                migrations,
+                &marker_for_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_auto_restart_history_list(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::InstancePath>,
+        query_params: Query<PaginatedById<params::OptionalProjectSelector>>,
+    ) -> Result<
+        HttpResponseOk<ResultsPage<views::InstanceAutoRestartEvent>>,
+        HttpError,
+    > {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanById::from_query(&query)?;
+            let instance_selector = params::InstanceSelector {
+                project: scan_params.selector.project.clone(),
+                instance: path.instance,
+            };
+            let instance_lookup =
+                nexus.instance_lookup(&opctx, instance_selector)?;
+            let events = nexus
+                .instance_auto_restart_history_list(
+                    &opctx,
+                    &instance_lookup,
+                    &pag_params,
+                )
+                .await?;
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                events,
+                &marker_for_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn instance_disk_list(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<
@@ -4397,7 +5240,7 @@ impl NexusExternalApi for NexusExternalApiImpl {
 
     // Images
 
-    async fn image_list(
+    async fn image_list_v20260701(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<
             PaginatedByNameOrId<params::OptionalProjectSelector>,
@@ -4426,7 +5269,12 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 }
             };
             let images = nexus
-                .image_list(&opctx, &parent_lookup, &paginated_by)
+                .image_list(
+                    &opctx,
+                    &parent_lookup,
+                    &ImageFilters::default(),
+                    &paginated_by.into(),
+                )
                 .await?
                 .into_iter()
                 .map(|d| d.into())
@@ -4444,19 +5292,21 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn image_create(
+    async fn image_list(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<params::OptionalProjectSelector>,
-        new_image: TypedBody<params::ImageCreate>,
-    ) -> Result<HttpResponseCreated<Image>, HttpError> {
+        query_params: Query<PaginatedImages<params::ImageListSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<Image>>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let nexus = &apictx.context.nexus;
             let query = query_params.into_inner();
-            let params = &new_image.into_inner();
-            let parent_lookup = match query.project.clone() {
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanImages::from_query(&query)?;
+            let paginated_by = image_pagination(&pag_params, scan_params)?;
+            let selector = scan_params.selector.clone();
+            let parent_lookup = match selector.project {
                 Some(project) => {
                     let project_lookup = nexus.project_lookup(
                         &opctx,
@@ -4469,9 +5319,25 @@ impl NexusExternalApi for NexusExternalApiImpl {
                     ImageParentLookup::Silo(silo_lookup)
                 }
             };
-            let image =
-                nexus.image_create(&opctx, &parent_lookup, &params).await?;
-            Ok(HttpResponseCreated(image.into()))
+            let filters = ImageFilters {
+                os: selector.os,
+                version: selector.version,
+                min_size: selector.min_size,
+            };
+            let images = nexus
+                .image_list(&opctx, &parent_lookup, &filters, &paginated_by)
+                .await?
+                .into_iter()
+                .map(|d| d.into())
+                .collect();
+            Ok(HttpResponseOk(ScanImages::results_page(
+                &query,
+                images,
+                &|scan: &ScanImages<params::ImageListSelector>,
+                  image: &Image| {
+                    marker_for_image(scan, image, image.size)
+                },
+            )?))
         };
         apictx
             .context
@@ -4480,24 +5346,60 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn image_view(
+    async fn image_create(
         rqctx: RequestContext<ApiContext>,
-        path_params: Path<params::ImagePath>,
         query_params: Query<params::OptionalProjectSelector>,
-    ) -> Result<HttpResponseOk<Image>, HttpError> {
+        new_image: TypedBody<params::ImageCreate>,
+    ) -> Result<HttpResponseCreated<Image>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let nexus = &apictx.context.nexus;
-            let path = path_params.into_inner();
             let query = query_params.into_inner();
-            let image: nexus_db_model::Image = match nexus
-                .image_lookup(
-                    &opctx,
-                    params::ImageSelector {
-                        image: path.image,
-                        project: query.project,
+            let params = &new_image.into_inner();
+            let parent_lookup = match query.project.clone() {
+                Some(project) => {
+                    let project_lookup = nexus.project_lookup(
+                        &opctx,
+                        params::ProjectSelector { project },
+                    )?;
+                    ImageParentLookup::Project(project_lookup)
+                }
+                None => {
+                    let silo_lookup = nexus.current_silo_lookup(&opctx)?;
+                    ImageParentLookup::Silo(silo_lookup)
+                }
+            };
+            let image =
+                nexus.image_create(&opctx, &parent_lookup, &params).await?;
+            Ok(HttpResponseCreated(image.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn image_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::ImagePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseOk<Image>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let image: nexus_db_model::Image = match nexus
+                .image_lookup(
+                    &opctx,
+                    params::ImageSelector {
+                        image: path.image,
+                        project: query.project,
                     },
                 )
                 .await?
@@ -4615,6 +5517,40 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn image_state_update(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::ImagePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        state: TypedBody<params::ImageStateUpdate>,
+    ) -> Result<HttpResponseOk<Image>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let state = state.into_inner();
+            let image_lookup = nexus
+                .image_lookup(
+                    &opctx,
+                    params::ImageSelector {
+                        image: path.image,
+                        project: query.project,
+                    },
+                )
+                .await?;
+            let image =
+                nexus.image_set_state(&opctx, &image_lookup, &state).await?;
+            Ok(HttpResponseOk(image.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn instance_network_interface_list(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<PaginatedByNameOrId<params::InstanceSelector>>,
@@ -4788,11 +5724,26 @@ impl NexusExternalApi for NexusExternalApiImpl {
 
     // External IP addresses for instances
 
-    async fn instance_external_ip_list(
+    async fn instance_external_ip_list_v20260801(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<params::OptionalProjectSelector>,
         path_params: Path<params::InstancePath>,
     ) -> Result<HttpResponseOk<ResultsPage<views::ExternalIp>>, HttpError> {
+        let HttpResponseOk(page) =
+            Self::instance_external_ip_list(rqctx, query_params, path_params)
+                .await?;
+        Ok(HttpResponseOk(ResultsPage {
+            items: page.items.into_iter().map(|ip| ip.external_ip).collect(),
+            next_page: page.next_page,
+        }))
+    }
+
+    async fn instance_external_ip_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::OptionalProjectSelector>,
+        path_params: Path<params::InstancePath>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::InstanceExternalIp>>, HttpError>
+    {
         let apictx = rqctx.context();
         let handler = async {
             let nexus = &apictx.context.nexus;
@@ -4887,34 +5838,466 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    // Snapshots
-
-    async fn snapshot_list(
+    // Snapshots
+
+    async fn snapshot_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedByNameOrId<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<Snapshot>>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanByNameOrId::from_query(&query)?;
+            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let project_lookup =
+                nexus.project_lookup(&opctx, scan_params.selector.clone())?;
+            let snapshots = nexus
+                .snapshot_list(&opctx, &project_lookup, &paginated_by)
+                .await?
+                .into_iter()
+                .map(|d| d.into())
+                .collect();
+            Ok(HttpResponseOk(ScanByNameOrId::results_page(
+                &query,
+                snapshots,
+                &marker_for_name_or_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_create(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::ProjectSelector>,
+        new_snapshot: TypedBody<params::SnapshotCreate>,
+    ) -> Result<HttpResponseCreated<Snapshot>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let new_snapshot_params = &new_snapshot.into_inner();
+            let project_lookup = nexus.project_lookup(&opctx, query)?;
+            let snapshot = nexus
+                .snapshot_create(&opctx, project_lookup, &new_snapshot_params)
+                .await?;
+            Ok(HttpResponseCreated(snapshot.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseOk<Snapshot>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let snapshot_selector = params::SnapshotSelector {
+                project: query.project,
+                snapshot: path.snapshot,
+            };
+            let (.., snapshot) = nexus
+                .snapshot_lookup(&opctx, snapshot_selector)?
+                .fetch()
+                .await?;
+            Ok(HttpResponseOk(snapshot.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_update(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        updated_snapshot: TypedBody<params::SnapshotUpdate>,
+    ) -> Result<HttpResponseOk<Snapshot>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let updated_snapshot = updated_snapshot.into_inner();
+            let snapshot_selector = params::SnapshotSelector {
+                project: query.project,
+                snapshot: path.snapshot,
+            };
+            let snapshot_lookup =
+                nexus.snapshot_lookup(&opctx, snapshot_selector)?;
+            let snapshot = nexus
+                .snapshot_update(&opctx, &snapshot_lookup, &updated_snapshot)
+                .await?;
+            Ok(HttpResponseOk(snapshot.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_delete(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseDeleted, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let snapshot_selector = params::SnapshotSelector {
+                project: query.project,
+                snapshot: path.snapshot,
+            };
+            let snapshot_lookup =
+                nexus.snapshot_lookup(&opctx, snapshot_selector)?;
+            nexus.snapshot_delete(&opctx, &snapshot_lookup).await?;
+            Ok(HttpResponseDeleted())
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    // Snapshot schedules
+
+    async fn snapshot_schedule_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedByNameOrId<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::SnapshotSchedule>>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanByNameOrId::from_query(&query)?;
+            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let project_lookup =
+                nexus.project_lookup(&opctx, scan_params.selector.clone())?;
+            let schedules = nexus
+                .snapshot_schedule_list(&opctx, &project_lookup, &paginated_by)
+                .await?;
+            Ok(HttpResponseOk(ScanByNameOrId::results_page(
+                &query,
+                schedules,
+                &marker_for_name_or_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_schedule_create(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::ProjectSelector>,
+        new_schedule: TypedBody<params::SnapshotScheduleCreate>,
+    ) -> Result<HttpResponseCreated<views::SnapshotSchedule>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let new_schedule_params = new_schedule.into_inner();
+            let project_lookup = nexus.project_lookup(&opctx, query)?;
+            let schedule = nexus
+                .snapshot_schedule_create(
+                    &opctx,
+                    project_lookup,
+                    new_schedule_params,
+                )
+                .await?;
+            Ok(HttpResponseCreated(schedule))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_schedule_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotSchedulePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseOk<views::SnapshotSchedule>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let schedule_selector = params::SnapshotScheduleSelector {
+                project: query.project,
+                snapshot_schedule: path.snapshot_schedule,
+            };
+            let schedule_lookup =
+                nexus.snapshot_schedule_lookup(&opctx, schedule_selector)?;
+            let schedule =
+                nexus.snapshot_schedule_view(&schedule_lookup).await?;
+            Ok(HttpResponseOk(schedule))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_schedule_update(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotSchedulePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+        updated_schedule: TypedBody<params::SnapshotScheduleUpdate>,
+    ) -> Result<HttpResponseOk<views::SnapshotSchedule>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let updated_schedule = updated_schedule.into_inner();
+            let schedule_selector = params::SnapshotScheduleSelector {
+                project: query.project,
+                snapshot_schedule: path.snapshot_schedule,
+            };
+            let schedule_lookup =
+                nexus.snapshot_schedule_lookup(&opctx, schedule_selector)?;
+            let schedule = nexus
+                .snapshot_schedule_update(
+                    &opctx,
+                    &schedule_lookup,
+                    &updated_schedule,
+                )
+                .await?;
+            Ok(HttpResponseOk(schedule))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_schedule_delete(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotSchedulePath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseDeleted, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let schedule_selector = params::SnapshotScheduleSelector {
+                project: query.project,
+                snapshot_schedule: path.snapshot_schedule,
+            };
+            let schedule_lookup =
+                nexus.snapshot_schedule_lookup(&opctx, schedule_selector)?;
+            nexus.snapshot_schedule_delete(&opctx, &schedule_lookup).await?;
+            Ok(HttpResponseDeleted())
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    // Snapshot exports
+
+    async fn snapshot_export_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedById<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::SnapshotExport>>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanById::from_query(&query)?;
+            let project_lookup =
+                nexus.project_lookup(&opctx, scan_params.selector.clone())?;
+            let exports = nexus
+                .snapshot_export_list(&opctx, &project_lookup, &pag_params)
+                .await?;
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                exports,
+                &marker_for_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_export_create_v20260615(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseCreated<views::SnapshotExport>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let snapshot_selector = params::SnapshotSelector {
+                project: query.project,
+                snapshot: path.snapshot,
+            };
+            let snapshot_lookup =
+                nexus.snapshot_lookup(&opctx, snapshot_selector)?;
+            let export =
+                nexus.snapshot_export_create(&opctx, &snapshot_lookup).await?;
+            Ok(HttpResponseCreated(export))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_export_create(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotPath>,
+        query_params: Query<params::OptionalProjectSelector>,
+    ) -> Result<HttpResponseAccepted<views::Operation>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let snapshot_selector = params::SnapshotSelector {
+                project: query.project,
+                snapshot: path.snapshot,
+            };
+            let snapshot_lookup =
+                nexus.snapshot_lookup(&opctx, snapshot_selector)?;
+            let operation = nexus
+                .snapshot_export_create_operation(&opctx, &snapshot_lookup)
+                .await?;
+            Ok(HttpResponseAccepted(operation))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_export_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<HttpResponseOk<views::SnapshotExport>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let export_lookup = nexus.snapshot_export_lookup(&opctx, path);
+            let export = nexus.snapshot_export_view(&export_lookup).await?;
+            Ok(HttpResponseOk(export))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_export_manifest(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<HttpResponseOk<views::ExportManifest>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let export_lookup = nexus.snapshot_export_lookup(&opctx, path);
+            let manifest =
+                nexus.snapshot_export_manifest(&export_lookup).await?;
+            Ok(HttpResponseOk(manifest))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn snapshot_export_download(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<PaginatedByNameOrId<params::ProjectSelector>>,
-    ) -> Result<HttpResponseOk<ResultsPage<Snapshot>>, HttpError> {
+        headers: Header<RangeRequest>,
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<Response<Body>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let nexus = &apictx.context.nexus;
-            let query = query_params.into_inner();
-            let pag_params = data_page_params_for(&rqctx, &query)?;
-            let scan_params = ScanByNameOrId::from_query(&query)?;
-            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
-            let project_lookup =
-                nexus.project_lookup(&opctx, scan_params.selector.clone())?;
-            let snapshots = nexus
-                .snapshot_list(&opctx, &project_lookup, &paginated_by)
-                .await?
-                .into_iter()
-                .map(|d| d.into())
-                .collect();
-            Ok(HttpResponseOk(ScanByNameOrId::results_page(
-                &query,
-                snapshots,
-                &marker_for_name_or_id,
-            )?))
+            let path = path_params.into_inner();
+            let range = headers
+                .into_inner()
+                .range
+                .map(|r| PotentialRange::new(r.as_bytes()));
+            let export_lookup = nexus.snapshot_export_lookup(&opctx, path);
+            let body =
+                nexus.snapshot_export_download(&export_lookup, range).await?;
+            Ok(body)
         };
         apictx
             .context
@@ -4923,23 +6306,19 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn snapshot_create(
+    async fn snapshot_export_delete(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<params::ProjectSelector>,
-        new_snapshot: TypedBody<params::SnapshotCreate>,
-    ) -> Result<HttpResponseCreated<Snapshot>, HttpError> {
+        path_params: Path<params::SnapshotExportPath>,
+    ) -> Result<HttpResponseDeleted, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let nexus = &apictx.context.nexus;
-            let query = query_params.into_inner();
-            let new_snapshot_params = &new_snapshot.into_inner();
-            let project_lookup = nexus.project_lookup(&opctx, query)?;
-            let snapshot = nexus
-                .snapshot_create(&opctx, project_lookup, &new_snapshot_params)
-                .await?;
-            Ok(HttpResponseCreated(snapshot.into()))
+            let path = path_params.into_inner();
+            let export_lookup = nexus.snapshot_export_lookup(&opctx, path);
+            nexus.snapshot_export_delete(&opctx, &export_lookup).await?;
+            Ok(HttpResponseDeleted())
         };
         apictx
             .context
@@ -4948,27 +6327,30 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn snapshot_view(
+    // Operations
+
+    async fn operation_list(
         rqctx: RequestContext<ApiContext>,
-        path_params: Path<params::SnapshotPath>,
-        query_params: Query<params::OptionalProjectSelector>,
-    ) -> Result<HttpResponseOk<Snapshot>, HttpError> {
+        query_params: Query<PaginatedById<params::ProjectSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::Operation>>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let nexus = &apictx.context.nexus;
-            let path = path_params.into_inner();
             let query = query_params.into_inner();
-            let snapshot_selector = params::SnapshotSelector {
-                project: query.project,
-                snapshot: path.snapshot,
-            };
-            let (.., snapshot) = nexus
-                .snapshot_lookup(&opctx, snapshot_selector)?
-                .fetch()
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanById::from_query(&query)?;
+            let project_lookup =
+                nexus.project_lookup(&opctx, scan_params.selector.clone())?;
+            let operations = nexus
+                .operation_list(&opctx, &project_lookup, &pag_params)
                 .await?;
-            Ok(HttpResponseOk(snapshot.into()))
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                operations,
+                &marker_for_id,
+            )?))
         };
         apictx
             .context
@@ -4977,26 +6359,19 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn snapshot_delete(
+    async fn operation_view(
         rqctx: RequestContext<ApiContext>,
-        path_params: Path<params::SnapshotPath>,
-        query_params: Query<params::OptionalProjectSelector>,
-    ) -> Result<HttpResponseDeleted, HttpError> {
+        path_params: Path<params::OperationPath>,
+    ) -> Result<HttpResponseOk<views::Operation>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let nexus = &apictx.context.nexus;
             let path = path_params.into_inner();
-            let query = query_params.into_inner();
-            let snapshot_selector = params::SnapshotSelector {
-                project: query.project,
-                snapshot: path.snapshot,
-            };
-            let snapshot_lookup =
-                nexus.snapshot_lookup(&opctx, snapshot_selector)?;
-            nexus.snapshot_delete(&opctx, &snapshot_lookup).await?;
-            Ok(HttpResponseDeleted())
+            let operation_lookup = nexus.operation_lookup(&opctx, path);
+            let operation = nexus.operation_view(&operation_lookup).await?;
+            Ok(HttpResponseOk(operation))
         };
         apictx
             .context
@@ -5165,11 +6540,177 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 .vpc_subnet_list(&opctx, &vpc_lookup, &paginated_by)
                 .await?
                 .into_iter()
-                .map(|vpc| vpc.into())
+                .map(|vpc| vpc.into())
+                .collect();
+            Ok(HttpResponseOk(ScanByNameOrId::results_page(
+                &query,
+                subnets,
+                &marker_for_name_or_id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn vpc_subnet_create(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::VpcSelector>,
+        create_params: TypedBody<params::VpcSubnetCreate>,
+    ) -> Result<HttpResponseCreated<VpcSubnet>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let create = create_params.into_inner();
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let vpc_lookup = nexus.vpc_lookup(&opctx, query)?;
+            let subnet =
+                nexus.vpc_create_subnet(&opctx, &vpc_lookup, &create).await?;
+            Ok(HttpResponseCreated(subnet.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn vpc_subnet_view(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<params::OptionalVpcSelector>,
+    ) -> Result<HttpResponseOk<VpcSubnet>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let subnet_selector = params::SubnetSelector {
+                project: query.project,
+                vpc: query.vpc,
+                subnet: path.subnet,
+            };
+            let (.., subnet) = nexus
+                .vpc_subnet_lookup(&opctx, subnet_selector)?
+                .fetch()
+                .await?;
+            Ok(HttpResponseOk(subnet.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn vpc_subnet_delete(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<params::OptionalVpcSelector>,
+    ) -> Result<HttpResponseDeleted, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let subnet_selector = params::SubnetSelector {
+                project: query.project,
+                vpc: query.vpc,
+                subnet: path.subnet,
+            };
+            let subnet_lookup =
+                nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
+            nexus.vpc_delete_subnet(&opctx, &subnet_lookup).await?;
+            Ok(HttpResponseDeleted())
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn vpc_subnet_update(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<params::OptionalVpcSelector>,
+        subnet_params: TypedBody<params::VpcSubnetUpdate>,
+    ) -> Result<HttpResponseOk<VpcSubnet>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let query = query_params.into_inner();
+            let subnet_params = subnet_params.into_inner();
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let subnet_selector = params::SubnetSelector {
+                project: query.project,
+                vpc: query.vpc,
+                subnet: path.subnet,
+            };
+            let subnet_lookup =
+                nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
+            let subnet = nexus
+                .vpc_update_subnet(&opctx, &subnet_lookup, &subnet_params)
+                .await?;
+            Ok(HttpResponseOk(subnet.into()))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    // This endpoint is likely temporary. We would rather list all IPs allocated in
+    // a subnet whether they come from NICs or something else. See
+    // https://github.com/oxidecomputer/omicron/issues/2476
+
+    async fn vpc_subnet_list_network_interfaces(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<PaginatedByNameOrId<params::OptionalVpcSelector>>,
+    ) -> Result<HttpResponseOk<ResultsPage<InstanceNetworkInterface>>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let path = path_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanByNameOrId::from_query(&query)?;
+            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let subnet_selector = params::SubnetSelector {
+                project: scan_params.selector.project.clone(),
+                vpc: scan_params.selector.vpc.clone(),
+                subnet: path.subnet,
+            };
+            let subnet_lookup =
+                nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
+            let interfaces = nexus
+                .subnet_list_instance_network_interfaces(
+                    &opctx,
+                    &subnet_lookup,
+                    &paginated_by,
+                )
+                .await?
+                .into_iter()
+                .map(|interfaces| interfaces.into())
                 .collect();
             Ok(HttpResponseOk(ScanByNameOrId::results_page(
                 &query,
-                subnets,
+                interfaces,
                 &marker_for_name_or_id,
             )?))
         };
@@ -5180,22 +6721,45 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn vpc_subnet_create(
+    async fn vpc_subnet_ip_reservation_list(
         rqctx: RequestContext<ApiContext>,
-        query_params: Query<params::VpcSelector>,
-        create_params: TypedBody<params::VpcSubnetCreate>,
-    ) -> Result<HttpResponseCreated<VpcSubnet>, HttpError> {
+        path_params: Path<params::SubnetPath>,
+        query_params: Query<PaginatedById<params::OptionalVpcSelector>>,
+    ) -> Result<
+        HttpResponseOk<ResultsPage<views::VpcSubnetIpReservation>>,
+        HttpError,
+    > {
         let apictx = rqctx.context();
         let handler = async {
             let nexus = &apictx.context.nexus;
             let query = query_params.into_inner();
-            let create = create_params.into_inner();
+            let path = path_params.into_inner();
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let scan_params = ScanById::from_query(&query)?;
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
-            let vpc_lookup = nexus.vpc_lookup(&opctx, query)?;
-            let subnet =
-                nexus.vpc_create_subnet(&opctx, &vpc_lookup, &create).await?;
-            Ok(HttpResponseCreated(subnet.into()))
+            let subnet_selector = params::SubnetSelector {
+                project: scan_params.selector.project.clone(),
+                vpc: scan_params.selector.vpc.clone(),
+                subnet: path.subnet,
+            };
+            let subnet_lookup =
+                nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
+            let reservations = nexus
+                .vpc_subnet_ip_reservation_list(
+                    &opctx,
+                    &subnet_lookup,
+                    &pag_params,
+                )
+                .await?
+                .into_iter()
+                .map(|reservation| reservation.into())
+                .collect();
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                reservations,
+                &marker_for_id,
+            )?))
         };
         apictx
             .context
@@ -5204,16 +6768,19 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn vpc_subnet_view(
+    async fn vpc_subnet_ip_reservation_create(
         rqctx: RequestContext<ApiContext>,
         path_params: Path<params::SubnetPath>,
         query_params: Query<params::OptionalVpcSelector>,
-    ) -> Result<HttpResponseOk<VpcSubnet>, HttpError> {
+        reservation_params: TypedBody<params::VpcSubnetIpReservationCreate>,
+    ) -> Result<HttpResponseCreated<views::VpcSubnetIpReservation>, HttpError>
+    {
         let apictx = rqctx.context();
         let handler = async {
             let nexus = &apictx.context.nexus;
-            let path = path_params.into_inner();
             let query = query_params.into_inner();
+            let path = path_params.into_inner();
+            let create = reservation_params.into_inner();
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let subnet_selector = params::SubnetSelector {
@@ -5221,11 +6788,16 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 vpc: query.vpc,
                 subnet: path.subnet,
             };
-            let (.., subnet) = nexus
-                .vpc_subnet_lookup(&opctx, subnet_selector)?
-                .fetch()
+            let subnet_lookup =
+                nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
+            let reservation = nexus
+                .vpc_subnet_ip_reservation_create(
+                    &opctx,
+                    &subnet_lookup,
+                    &create,
+                )
                 .await?;
-            Ok(HttpResponseOk(subnet.into()))
+            Ok(HttpResponseCreated(reservation.into()))
         };
         apictx
             .context
@@ -5234,16 +6806,16 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn vpc_subnet_delete(
+    async fn vpc_subnet_ip_reservation_delete(
         rqctx: RequestContext<ApiContext>,
-        path_params: Path<params::SubnetPath>,
+        path_params: Path<params::VpcSubnetIpReservationPath>,
         query_params: Query<params::OptionalVpcSelector>,
     ) -> Result<HttpResponseDeleted, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let nexus = &apictx.context.nexus;
-            let path = path_params.into_inner();
             let query = query_params.into_inner();
+            let path = path_params.into_inner();
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let subnet_selector = params::SubnetSelector {
@@ -5253,7 +6825,13 @@ impl NexusExternalApi for NexusExternalApiImpl {
             };
             let subnet_lookup =
                 nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
-            nexus.vpc_delete_subnet(&opctx, &subnet_lookup).await?;
+            nexus
+                .vpc_subnet_ip_reservation_delete(
+                    &opctx,
+                    &subnet_lookup,
+                    path.address,
+                )
+                .await?;
             Ok(HttpResponseDeleted())
         };
         apictx
@@ -5263,18 +6841,16 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    async fn vpc_subnet_update(
+    async fn vpc_subnet_ipv4_blocks_view(
         rqctx: RequestContext<ApiContext>,
         path_params: Path<params::SubnetPath>,
         query_params: Query<params::OptionalVpcSelector>,
-        subnet_params: TypedBody<params::VpcSubnetUpdate>,
-    ) -> Result<HttpResponseOk<VpcSubnet>, HttpError> {
+    ) -> Result<HttpResponseOk<views::VpcSubnetIpv4Blocks>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let nexus = &apictx.context.nexus;
-            let path = path_params.into_inner();
             let query = query_params.into_inner();
-            let subnet_params = subnet_params.into_inner();
+            let path = path_params.into_inner();
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let subnet_selector = params::SubnetSelector {
@@ -5282,10 +6858,9 @@ impl NexusExternalApi for NexusExternalApiImpl {
                 vpc: query.vpc,
                 subnet: path.subnet,
             };
-            let subnet_lookup =
-                nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
-            let subnet = nexus
-                .vpc_update_subnet(&opctx, &subnet_lookup, &subnet_params)
+            let (.., subnet) = nexus
+                .vpc_subnet_lookup(&opctx, subnet_selector)?
+                .fetch()
                 .await?;
             Ok(HttpResponseOk(subnet.into()))
         };
@@ -5296,48 +6871,31 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
-    // This endpoint is likely temporary. We would rather list all IPs allocated in
-    // a subnet whether they come from NICs or something else. See
-    // https://github.com/oxidecomputer/omicron/issues/2476
-
-    async fn vpc_subnet_list_network_interfaces(
+    async fn vpc_subnet_ipv4_block_add(
         rqctx: RequestContext<ApiContext>,
         path_params: Path<params::SubnetPath>,
-        query_params: Query<PaginatedByNameOrId<params::OptionalVpcSelector>>,
-    ) -> Result<HttpResponseOk<ResultsPage<InstanceNetworkInterface>>, HttpError>
-    {
+        query_params: Query<params::OptionalVpcSelector>,
+        block_params: TypedBody<params::VpcSubnetIpv4BlockAdd>,
+    ) -> Result<HttpResponseOk<views::VpcSubnetIpv4Blocks>, HttpError> {
         let apictx = rqctx.context();
         let handler = async {
             let nexus = &apictx.context.nexus;
             let query = query_params.into_inner();
             let path = path_params.into_inner();
-            let pag_params = data_page_params_for(&rqctx, &query)?;
-            let scan_params = ScanByNameOrId::from_query(&query)?;
-            let paginated_by = name_or_id_pagination(&pag_params, scan_params)?;
+            let add = block_params.into_inner();
             let opctx =
                 crate::context::op_context_for_external_api(&rqctx).await?;
             let subnet_selector = params::SubnetSelector {
-                project: scan_params.selector.project.clone(),
-                vpc: scan_params.selector.vpc.clone(),
+                project: query.project,
+                vpc: query.vpc,
                 subnet: path.subnet,
             };
             let subnet_lookup =
                 nexus.vpc_subnet_lookup(&opctx, subnet_selector)?;
-            let interfaces = nexus
-                .subnet_list_instance_network_interfaces(
-                    &opctx,
-                    &subnet_lookup,
-                    &paginated_by,
-                )
-                .await?
-                .into_iter()
-                .map(|interfaces| interfaces.into())
-                .collect();
-            Ok(HttpResponseOk(ScanByNameOrId::results_page(
-                &query,
-                interfaces,
-                &marker_for_name_or_id,
-            )?))
+            let subnet = nexus
+                .vpc_subnet_add_ipv4_block(&opctx, &subnet_lookup, &add)
+                .await?;
+            Ok(HttpResponseOk(subnet.into()))
         };
         apictx
             .context
@@ -5407,6 +6965,36 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn vpc_firewall_rules_replace(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<params::VpcSelector>,
+        replace_params: TypedBody<params::VpcFirewallRulesReplace>,
+    ) -> Result<HttpResponseOk<views::VpcFirewallRulesReplaceResult>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let replace_params = replace_params.into_inner();
+            let vpc_lookup = nexus.vpc_lookup(&opctx, query)?;
+            let result = nexus
+                .vpc_replace_firewall_rules(
+                    &opctx,
+                    &vpc_lookup,
+                    &replace_params,
+                )
+                .await?;
+            Ok(HttpResponseOk(result))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     // VPC Routers
 
     async fn vpc_router_list(
@@ -6252,6 +7840,28 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn sled_expunge_preview(
+        rqctx: RequestContext<ApiContext>,
+        path_params: Path<params::SledPath>,
+    ) -> Result<HttpResponseOk<views::SledExpungePreview>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let nexus = &apictx.context.nexus;
+            let path = path_params.into_inner();
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let sled_lookup = nexus.sled_lookup(&opctx, &path.sled_id)?;
+            let preview =
+                nexus.sled_expunge_preview(&opctx, &sled_lookup).await?;
+            Ok(HttpResponseOk(preview))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn sled_instance_list(
         rqctx: RequestContext<ApiContext>,
         path_params: Path<params::SledPath>,
@@ -6872,6 +8482,105 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn system_update_component_versions(
+        rqctx: RequestContext<ApiContext>,
+    ) -> Result<HttpResponseOk<views::ComponentVersionMatrix>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let matrix = nexus.component_version_matrix(&opctx).await?;
+            Ok(HttpResponseOk(matrix))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn system_update_technician_port_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<
+        HttpResponseOk<ResultsPage<views::TechnicianPortUpdate>>,
+        HttpError,
+    > {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+
+            let query = query_params.into_inner();
+            let pagparams = data_page_params_for(&rqctx, &query)?;
+
+            let updates =
+                nexus.technician_port_update_list(&opctx, &pagparams).await?;
+
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                updates,
+                &|_, update: &views::TechnicianPortUpdate| update.id,
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn system_blueprint_list(
+        rqctx: RequestContext<ApiContext>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::Blueprint>>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+
+            let query = query_params.into_inner();
+            let pagparams = data_page_params_for(&rqctx, &query)?;
+
+            let blueprints =
+                nexus.blueprint_list_external(&opctx, &pagparams).await?;
+
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                blueprints,
+                &|_, blueprint: &views::Blueprint| {
+                    blueprint.id.into_untyped_uuid()
+                },
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn system_blueprint_target_view(
+        rqctx: RequestContext<ApiContext>,
+    ) -> Result<HttpResponseOk<views::BlueprintTarget>, HttpError> {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+            let nexus = &apictx.context.nexus;
+            let target = nexus.blueprint_target_view_external(&opctx).await?;
+            Ok(HttpResponseOk(target))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     // Silo users
 
     async fn user_list(
@@ -7920,6 +9629,44 @@ impl NexusExternalApi for NexusExternalApiImpl {
             .await
     }
 
+    async fn rack_activity_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedByTimeAndId<params::RackActivity>>,
+    ) -> Result<HttpResponseOk<ResultsPage<views::RackActivity>>, HttpError>
+    {
+        let apictx = rqctx.context();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_external_api(&rqctx).await?;
+
+            let nexus = &apictx.context.nexus;
+            let query = query_params.into_inner();
+            let scan_params = ScanByTimeAndId::from_query(&query)?;
+            let pag_params = data_page_params_for(&rqctx, &query)?;
+            let filters = RackActivityFilters {
+                start_time: scan_params.selector.start_time,
+                end_time: scan_params.selector.end_time,
+                kind: scan_params.selector.kind,
+            };
+
+            let activity =
+                nexus.rack_activity_list(&opctx, &filters, &pag_params).await?;
+            Ok(HttpResponseOk(ScanByTimeAndId::results_page(
+                &query,
+                activity
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<Vec<_>, _>>()?,
+                &|_, item: &views::RackActivity| (item.time, item.id),
+            )?))
+        };
+        apictx
+            .context
+            .external_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn login_saml_begin(
         rqctx: RequestContext<Self::Context>,
         _path_params: Path<params::LoginToProviderPathParam>,
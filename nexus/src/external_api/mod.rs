@@ -4,6 +4,7 @@
 
 pub mod console_api;
 pub(crate) mod http_entrypoints;
+pub(crate) mod versioning;
 
 pub(crate) use nexus_types::external_api::params;
 pub(crate) use nexus_types::external_api::shared;
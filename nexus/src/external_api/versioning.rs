@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Selection of the external API version used to serve each request
+//!
+//! Nexus serves every supported version of the external API at once.  Clients
+//! choose a version with the `api-version` header; generated clients (e.g.,
+//! `oxide-client`) always send the version of the OpenAPI document they were
+//! built from, so they keep getting the behavior they were built against even
+//! after Nexus is upgraded.
+//!
+//! Unlike internal APIs, we can't require the header: plenty of existing
+//! customer automation talks to the API with hand-rolled HTTP requests that
+//! don't send it.  Requests without the header are served the latest version.
+
+use dropshot::ClientSpecifiesVersionInHeader;
+use dropshot::DynamicVersionPolicy;
+use dropshot::HttpError;
+use omicron_common::api::VERSION_HEADER;
+use semver::Version;
+use slog::Logger;
+
+/// [`DynamicVersionPolicy`] for the external API
+///
+/// Requests that specify a version in the `api-version` header are served
+/// that version (or rejected, if it's newer than the latest version this Nexus
+/// knows about).  Requests that don't specify a version are served the latest
+/// version.
+#[derive(Debug)]
+pub struct ExternalApiVersionPolicy {
+    from_header: ClientSpecifiesVersionInHeader,
+    latest: Version,
+}
+
+impl ExternalApiVersionPolicy {
+    pub fn new() -> Self {
+        let latest = nexus_external_api::latest_version();
+        ExternalApiVersionPolicy {
+            from_header: ClientSpecifiesVersionInHeader::new(
+                VERSION_HEADER,
+                latest.clone(),
+            ),
+            latest,
+        }
+    }
+}
+
+impl Default for ExternalApiVersionPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicVersionPolicy for ExternalApiVersionPolicy {
+    fn request_extract_version(
+        &self,
+        request: &http::Request<hyper::body::Incoming>,
+        log: &Logger,
+    ) -> Result<Version, HttpError> {
+        if request.headers().contains_key(VERSION_HEADER) {
+            self.from_header.request_extract_version(request, log)
+        } else {
+            Ok(self.latest.clone())
+        }
+    }
+}
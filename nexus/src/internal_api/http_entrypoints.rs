@@ -26,7 +26,11 @@ use nexus_internal_api::*;
 use nexus_types::deployment::Blueprint;
 use nexus_types::deployment::BlueprintMetadata;
 use nexus_types::deployment::BlueprintTarget;
+use nexus_types::deployment::BlueprintTargetExecution;
+use nexus_types::deployment::BlueprintTargetExecutionSet;
 use nexus_types::deployment::BlueprintTargetSet;
+use nexus_types::deployment::CapacityInstanceShape;
+use nexus_types::deployment::CapacityReport;
 use nexus_types::deployment::ClickhousePolicy;
 use nexus_types::deployment::OximeterReadPolicy;
 use nexus_types::deployment::ReconfiguratorChickenSwitchesParam;
@@ -41,18 +45,30 @@ use nexus_types::external_api::params::UninitializedSledId;
 use nexus_types::external_api::shared::ProbeInfo;
 use nexus_types::external_api::shared::UninitializedSled;
 use nexus_types::external_api::views::SledPolicy;
+use nexus_types::internal_api::params::BreakGlassDisableRequest;
+use nexus_types::internal_api::params::BreakGlassEnableRequest;
 use nexus_types::internal_api::params::InstanceMigrateRequest;
+use nexus_types::internal_api::params::RollingSledRebootRequest;
+use nexus_types::internal_api::params::RuntimeConfigUpdate;
 use nexus_types::internal_api::params::SledAgentInfo;
 use nexus_types::internal_api::params::SwitchPutRequest;
 use nexus_types::internal_api::params::SwitchPutResponse;
+use nexus_types::internal_api::params::TechnicianPortUpdateReport;
+use nexus_types::internal_api::params::VolumeReferenceCheckParams;
 use nexus_types::internal_api::views::BackgroundTask;
+use nexus_types::internal_api::views::BreakGlassAccount;
 use nexus_types::internal_api::views::DemoSaga;
+use nexus_types::internal_api::views::InstancePlacementExplanation;
 use nexus_types::internal_api::views::MgsUpdateDriverStatus;
 use nexus_types::internal_api::views::NatEntryView;
 use nexus_types::internal_api::views::QuiesceStatus;
+use nexus_types::internal_api::views::RollingSledReboot;
+use nexus_types::internal_api::views::RuntimeConfig;
 use nexus_types::internal_api::views::Saga;
 use nexus_types::internal_api::views::UpdateStatus;
+use nexus_types::internal_api::views::VolumeReferenceCheckReport;
 use nexus_types::internal_api::views::to_list;
+use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::Instance;
 use omicron_common::api::external::http_pagination::PaginatedById;
 use omicron_common::api::external::http_pagination::PaginatedByTimeAndId;
@@ -233,7 +249,8 @@ impl NexusInternalApi for NexusInternalApiImpl {
                     migrate,
                 )
                 .await?;
-            Ok(HttpResponseOk(instance.into()))
+            let instance = nexus.instance_view(&opctx, instance).await?;
+            Ok(HttpResponseOk(instance))
         };
         apictx
             .internal_latencies
@@ -658,6 +675,44 @@ impl NexusInternalApi for NexusInternalApiImpl {
             .await
     }
 
+    // Runtime configuration
+
+    async fn runtime_config_view(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<RuntimeConfig>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            Ok(HttpResponseOk(nexus.runtime_config_view(&opctx).await?))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn runtime_config_update(
+        rqctx: RequestContext<Self::Context>,
+        body: TypedBody<RuntimeConfigUpdate>,
+    ) -> Result<HttpResponseOk<RuntimeConfig>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let update = body.into_inner();
+            Ok(HttpResponseOk(
+                nexus.runtime_config_update(&opctx, update).await?,
+            ))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     // Debug interfaces for MGS updates
 
     async fn mgs_updates(
@@ -828,6 +883,44 @@ impl NexusInternalApi for NexusInternalApiImpl {
             .await
     }
 
+    async fn blueprint_target_execution_view(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<BlueprintTargetExecution>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let execution =
+                nexus.blueprint_target_execution_view(&opctx).await?;
+            Ok(HttpResponseOk(execution))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn blueprint_target_set_execution(
+        rqctx: RequestContext<Self::Context>,
+        execution: TypedBody<BlueprintTargetExecutionSet>,
+    ) -> Result<HttpResponseOk<BlueprintTargetExecution>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let execution = execution.into_inner();
+            let execution =
+                nexus.blueprint_target_set_execution(&opctx, execution).await?;
+            Ok(HttpResponseOk(execution))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn blueprint_regenerate(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<Blueprint>, HttpError> {
@@ -864,6 +957,48 @@ impl NexusInternalApi for NexusInternalApiImpl {
             .await
     }
 
+    async fn zone_cordon(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<OmicronZonePathParam>,
+    ) -> Result<HttpResponseOk<Blueprint>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let path = path_params.into_inner();
+            let blueprint = nexus
+                .blueprint_create_zone_cordoned(&opctx, path.zone_id, true)
+                .await?;
+            Ok(HttpResponseOk(blueprint))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn zone_uncordon(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<OmicronZonePathParam>,
+    ) -> Result<HttpResponseOk<Blueprint>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let path = path_params.into_inner();
+            let blueprint = nexus
+                .blueprint_create_zone_cordoned(&opctx, path.zone_id, false)
+                .await?;
+            Ok(HttpResponseOk(blueprint))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn reconfigurator_chicken_switches_show_current(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<ReconfiguratorChickenSwitchesView>, HttpError>
@@ -961,6 +1096,59 @@ impl NexusInternalApi for NexusInternalApiImpl {
             .await
     }
 
+    async fn capacity_report(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<CapacityReportQueryParams>,
+    ) -> Result<HttpResponseOk<CapacityReport>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let query = query_params.into_inner();
+            let default_shape = CapacityInstanceShape::default();
+            let instance_shape = CapacityInstanceShape {
+                ncpus: query.ncpus.unwrap_or(default_shape.ncpus),
+                memory: query.memory_gib.map_or(
+                    default_shape.memory,
+                    ByteCount::from_gibibytes_u32,
+                ),
+            };
+            let report = nexus.capacity_report(&opctx, instance_shape).await?;
+            Ok(HttpResponseOk(report))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn instance_placement_explain(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<InstancePlacementExplainQueryParams>,
+    ) -> Result<HttpResponseOk<InstancePlacementExplanation>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let query = query_params.into_inner();
+            let explanation = nexus
+                .instance_placement_explain(
+                    &opctx,
+                    query.instance_id,
+                    query.ncpus,
+                    ByteCount::from_gibibytes_u32(query.memory_gib),
+                )
+                .await?;
+            Ok(HttpResponseOk(explanation))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn sled_list_uninitialized(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<ResultsPage<UninitializedSled>>, HttpError> {
@@ -1033,6 +1221,91 @@ impl NexusInternalApi for NexusInternalApiImpl {
             .await
     }
 
+    async fn rolling_sled_reboot_start(
+        rqctx: RequestContext<Self::Context>,
+        request: TypedBody<RollingSledRebootRequest>,
+    ) -> Result<HttpResponseCreated<RollingSledReboot>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let nexus = &apictx.nexus;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let reboot = nexus
+                .rolling_sled_reboot_start(&opctx, request.into_inner())
+                .await?;
+            Ok(HttpResponseCreated(reboot))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn rolling_sled_reboot_list(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<PaginatedById>,
+    ) -> Result<HttpResponseOk<ResultsPage<RollingSledReboot>>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let nexus = &apictx.nexus;
+            let query = query_params.into_inner();
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let pagparams = data_page_params_for(&rqctx, &query)?;
+            let reboots =
+                nexus.rolling_sled_reboot_list(&opctx, &pagparams).await?;
+            Ok(HttpResponseOk(ScanById::results_page(
+                &query,
+                reboots,
+                &|_, reboot: &RollingSledReboot| reboot.id,
+            )?))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn rolling_sled_reboot_view(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<RollingSledRebootPathParam>,
+    ) -> Result<HttpResponseOk<RollingSledReboot>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let nexus = &apictx.nexus;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let reboot_id = path_params.into_inner().reboot_id;
+            let reboot =
+                nexus.rolling_sled_reboot_view(&opctx, reboot_id).await?;
+            Ok(HttpResponseOk(reboot))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn rolling_sled_reboot_cancel(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<RollingSledRebootPathParam>,
+    ) -> Result<HttpResponseOk<RollingSledReboot>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let nexus = &apictx.nexus;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let reboot_id = path_params.into_inner().reboot_id;
+            let reboot =
+                nexus.rolling_sled_reboot_cancel(&opctx, reboot_id).await?;
+            Ok(HttpResponseOk(reboot))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn support_bundle_list(
         rqctx: RequestContext<ApiContext>,
         query_params: Query<PaginatedByTimeAndId>,
@@ -1434,6 +1707,25 @@ impl NexusInternalApi for NexusInternalApiImpl {
             .await
     }
 
+    async fn clickhouse_backup_restore(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<ClickhouseBackupPathParam>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let apictx = &rqctx.context().context;
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let nexus = &apictx.nexus;
+            let path = path_params.into_inner();
+            nexus.clickhouse_backup_restore(&opctx, &path.name).await?;
+            Ok(HttpResponseUpdatedNoContent())
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
     async fn oximeter_read_policy_get(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<OximeterReadPolicy>, HttpError> {
@@ -1510,4 +1802,81 @@ impl NexusInternalApi for NexusInternalApiImpl {
             .instrument_dropshot_handler(&rqctx, handler)
             .await
     }
+
+    async fn break_glass_enable(
+        rqctx: RequestContext<Self::Context>,
+        request: TypedBody<BreakGlassEnableRequest>,
+    ) -> Result<HttpResponseOk<BreakGlassAccount>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let nexus = &apictx.nexus;
+        let request = request.into_inner();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let account = nexus.break_glass_enable(&opctx, request).await?;
+            Ok(HttpResponseOk(account))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn break_glass_disable(
+        rqctx: RequestContext<Self::Context>,
+        request: TypedBody<BreakGlassDisableRequest>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let apictx = &rqctx.context().context;
+        let nexus = &apictx.nexus;
+        let request = request.into_inner();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            nexus.break_glass_disable(&opctx, request).await?;
+            Ok(HttpResponseUpdatedNoContent())
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn technician_port_update_report(
+        rqctx: RequestContext<Self::Context>,
+        report: TypedBody<TechnicianPortUpdateReport>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let apictx = &rqctx.context().context;
+        let nexus = &apictx.nexus;
+        let report = report.into_inner();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            nexus.technician_port_update_report(&opctx, report).await?;
+            Ok(HttpResponseUpdatedNoContent())
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
+
+    async fn volume_reference_check(
+        rqctx: RequestContext<Self::Context>,
+        params: TypedBody<VolumeReferenceCheckParams>,
+    ) -> Result<HttpResponseOk<VolumeReferenceCheckReport>, HttpError> {
+        let apictx = &rqctx.context().context;
+        let nexus = &apictx.nexus;
+        let params = params.into_inner();
+        let handler = async {
+            let opctx =
+                crate::context::op_context_for_internal_api(&rqctx).await;
+            let report =
+                nexus.volume_reference_check(&opctx, params.repair).await?;
+            Ok(HttpResponseOk(report))
+        };
+        apictx
+            .internal_latencies
+            .instrument_dropshot_handler(&rqctx, handler)
+            .await
+    }
 }
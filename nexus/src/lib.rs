@@ -19,11 +19,13 @@ mod populate;
 mod saga_interface;
 
 pub use app::Nexus;
+use app::RuntimeLogLevel;
 pub use app::test_interfaces::TestInterfaces;
 use context::ApiContext;
 use context::ServerContext;
 use dropshot::ConfigDropshot;
 use external_api::http_entrypoints::external_api;
+use external_api::versioning::ExternalApiVersionPolicy;
 use internal_api::http_entrypoints::internal_api;
 use nexus_config::NexusConfig;
 use nexus_db_model::RendezvousDebugDataset;
@@ -71,9 +73,13 @@ pub struct InternalServer {
 
 impl InternalServer {
     /// Start a nexus server.
+    ///
+    /// `log_level` controls the level of `log`, and may be changed at runtime
+    /// through the internal API.
     pub async fn start(
         config: &NexusConfig,
         log: &Logger,
+        log_level: RuntimeLogLevel,
     ) -> Result<InternalServer, String> {
         let log = log.new(o!("name" => config.deployment.id.to_string()));
         info!(log, "setting up nexus server");
@@ -83,6 +89,7 @@ impl InternalServer {
         let context = ApiContext::for_internal(
             config.deployment.rack_id,
             ctxlog,
+            log_level,
             &config,
         )
         .await?;
@@ -178,6 +185,9 @@ impl Server {
             )
             .config(config.deployment.dropshot_external.dropshot.clone())
             .tls(tls_config.clone().map(dropshot::ConfigTls::Dynamic))
+            .version_policy(dropshot::VersionPolicy::Dynamic(Box::new(
+                ExternalApiVersionPolicy::new(),
+            )))
             .start()
             .map_err(|error| {
                 format!("initializing external server: {}", error)
@@ -191,6 +201,9 @@ impl Server {
             )
             .config(techport_server_config)
             .tls(tls_config.map(dropshot::ConfigTls::Dynamic))
+            .version_policy(dropshot::VersionPolicy::Dynamic(Box::new(
+                ExternalApiVersionPolicy::new(),
+            )))
             .start()
             .map_err(|error| {
                 format!("initializing external techport server: {}", error)
@@ -241,7 +254,12 @@ impl nexus_test_interface::NexusServer for Server {
         config: &NexusConfig,
         log: &Logger,
     ) -> Result<(InternalServer, SocketAddr), String> {
-        let internal_server = InternalServer::start(config, &log).await?;
+        // The test harness configures its own log level, so start with
+        // everything enabled.
+        let log_level = RuntimeLogLevel::new(slog::Level::Trace);
+        let log = log_level.filter(log.clone());
+        let internal_server =
+            InternalServer::start(config, &log, log_level).await?;
         internal_server.apictx.context.nexus.wait_for_populate().await.unwrap();
         let addr = internal_server.http_server_internal.local_addr();
         Ok((internal_server, addr))
@@ -449,12 +467,16 @@ impl nexus_test_interface::NexusServer for Server {
 /// Run an instance of the Nexus server.
 pub async fn run_server(config: &NexusConfig) -> Result<(), String> {
     use slog::Drain;
+    // Log at every level, but filter messages below the configured level
+    // (which may be changed at runtime) before they reach the configured
+    // destination.  DTrace probes still see every message.
+    let (log_level, log_config) = RuntimeLogLevel::from_config(&config.pkg.log);
     let (drain, registration) =
-        slog_dtrace::with_drain(
-            config.pkg.log.to_logger("nexus").map_err(|message| {
+        slog_dtrace::with_drain(log_level.filter(
+            log_config.to_logger("nexus").map_err(|message| {
                 format!("initializing logger: {}", message)
             })?,
-        );
+        ));
     let log = slog::Logger::root(drain.fuse(), slog::o!(FileKv));
     if let slog_dtrace::ProbeRegistration::Failed(e) = registration {
         let msg = format!("failed to register DTrace probes: {}", e);
@@ -463,7 +485,8 @@ pub async fn run_server(config: &NexusConfig) -> Result<(), String> {
     } else {
         debug!(log, "registered DTrace probes");
     }
-    let internal_server = InternalServer::start(config, &log).await?;
+    let internal_server =
+        InternalServer::start(config, &log, log_level).await?;
     let server = Server::start(internal_server).await?;
     server.wait_for_finish().await
 }
@@ -49,6 +49,7 @@ use nexus_types::deployment::BlueprintDatasetDisposition;
 use nexus_types::deployment::BlueprintHostPhase2DesiredSlots;
 use nexus_types::deployment::BlueprintPhysicalDiskConfig;
 use nexus_types::deployment::BlueprintPhysicalDiskDisposition;
+use nexus_types::deployment::BlueprintScope;
 use nexus_types::deployment::BlueprintSledConfig;
 use nexus_types::deployment::BlueprintZoneConfig;
 use nexus_types::deployment::BlueprintZoneDisposition;
@@ -1008,6 +1009,7 @@ impl<'a, N: NexusServer> ControlPlaneTestContextBuilder<'a, N> {
         let blueprint = Blueprint {
             id,
             sleds,
+            scope: BlueprintScope::Fleet,
             pending_mgs_updates: PendingMgsUpdates::new(),
             parent_blueprint_id: None,
             internal_dns_version: dns_config.generation,
@@ -1195,15 +1197,16 @@ impl<'a, N: NexusServer> ControlPlaneTestContextBuilder<'a, N> {
             let disks = from_clone!(sled_configs[&sled_id].disks);
             let datasets = from_clone!(sled_configs[&sled_id].datasets);
             let zones = from_clone!(sled_zones);
+            let sled_config = OmicronSledConfig {
+                generation,
+                disks,
+                datasets,
+                zones,
+                remove_mupdate_override: None,
+                host_phase_2: HostPhase2DesiredSlots::current_contents(),
+            };
             client
-                .omicron_config_put(&OmicronSledConfig {
-                    generation,
-                    disks,
-                    datasets,
-                    zones,
-                    remove_mupdate_override: None,
-                    host_phase_2: HostPhase2DesiredSlots::current_contents(),
-                })
+                .omicron_config_put(None, &sled_config)
                 .await
                 .expect("Failed to configure sled agent {sled_id} with zones");
 
@@ -2161,6 +2164,7 @@ pub async fn start_dns_server(
         store,
         &dns_server::dns_server::Config {
             bind_address: "[::1]:0".parse().unwrap(),
+            query_log: Default::default(),
         },
         &dropshot::ConfigDropshot {
             bind_address: "[::1]:0".parse().unwrap(),
@@ -171,6 +171,56 @@ where
     .unwrap()
 }
 
+/// Make a POST to an endpoint that starts an operation, then wait for the
+/// operation to finish and return it
+pub async fn object_create_operation<InputType>(
+    client: &ClientTestContext,
+    path: &str,
+    input: &InputType,
+) -> views::Operation
+where
+    InputType: serde::Serialize,
+{
+    let operation: views::Operation = NexusRequest::new(
+        RequestBuilder::new(client, Method::POST, path)
+            .body(Some(&input))
+            .expect_status(Some(StatusCode::ACCEPTED)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap_or_else(|e| {
+        panic!("failed to make \"POST\" request to {path}: {e}")
+    })
+    .parsed_body()
+    .unwrap();
+    operation_wait(client, operation.id).await
+}
+
+/// Poll an operation until it is no longer running, and return it
+pub async fn operation_wait(
+    client: &ClientTestContext,
+    operation_id: Uuid,
+) -> views::Operation {
+    let url = format!("/v1/operations/{operation_id}");
+    wait_for_condition(
+        || async {
+            let operation: views::Operation = object_get(client, &url).await;
+            if operation.state == views::OperationState::Running {
+                Err(CondCheckError::<()>::NotYet)
+            } else {
+                Ok(operation)
+            }
+        },
+        &Duration::from_millis(50),
+        &Duration::from_secs(60),
+    )
+    .await
+    .unwrap_or_else(|e| {
+        panic!("operation {operation_id} did not finish: {e:?}")
+    })
+}
+
 pub async fn object_put<InputType, OutputType>(
     client: &ClientTestContext,
     path: &str,
@@ -665,10 +715,12 @@ pub async fn create_instance_with(
             network_interfaces: nics.clone(),
             external_ips,
             disks,
-            boot_disk: None,
+            boot_order: Vec::new(),
             start,
             auto_restart_policy,
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         },
     )
     .await
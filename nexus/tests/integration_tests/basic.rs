@@ -556,3 +556,37 @@ async fn test_ping(cptestctx: &ControlPlaneTestContext) {
         .await;
     assert_eq!(health.status, views::PingStatus::Ok);
 }
+
+#[nexus_test]
+async fn test_api_version_header(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    let latest = nexus_external_api::latest_version();
+
+    // Requests that don't specify a version get the latest one.
+    RequestBuilder::new(client, Method::GET, "/v1/ping")
+        .expect_status(Some(StatusCode::OK))
+        .execute()
+        .await
+        .expect("ping without a version");
+
+    // Every supported version is served.
+    for version in nexus_external_api::supported_versions().iter() {
+        RequestBuilder::new(client, Method::GET, "/v1/ping")
+            .header("api-version", version.semver().to_string())
+            .expect_status(Some(StatusCode::OK))
+            .execute()
+            .await
+            .unwrap_or_else(|_| panic!("ping at version {}", version.semver()));
+    }
+
+    // Versions newer than the latest one, and garbage, are rejected.
+    let too_new = semver::Version::new(latest.major + 1, 0, 0);
+    for bad in [too_new.to_string(), "not-a-version".to_string()] {
+        RequestBuilder::new(client, Method::GET, "/v1/ping")
+            .header("api-version", &bad)
+            .expect_status(Some(StatusCode::BAD_REQUEST))
+            .execute()
+            .await
+            .unwrap_or_else(|_| panic!("ping at version {bad:?}"));
+    }
+}
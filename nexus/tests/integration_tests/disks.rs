@@ -26,8 +26,17 @@ use nexus_test_utils::resource_helpers::create_default_ip_pool;
 use nexus_test_utils::resource_helpers::create_disk;
 use nexus_test_utils::resource_helpers::create_instance;
 use nexus_test_utils::resource_helpers::create_project;
+use nexus_test_utils::resource_helpers::object_create;
+use nexus_test_utils::resource_helpers::object_create_error;
+use nexus_test_utils::resource_helpers::object_create_operation;
+use nexus_test_utils::resource_helpers::object_delete;
+use nexus_test_utils::resource_helpers::object_delete_error;
+use nexus_test_utils::resource_helpers::object_get;
+use nexus_test_utils::resource_helpers::object_put;
+use nexus_test_utils::resource_helpers::objects_list_page_authz;
 use nexus_test_utils_macros::nexus_test;
 use nexus_types::external_api::params;
+use nexus_types::external_api::views;
 use nexus_types::identity::Asset;
 use nexus_types::silo::DEFAULT_SILO_ID;
 use omicron_common::api::external::ByteCount;
@@ -370,6 +379,248 @@ async fn test_disk_create_disk_that_already_exists_fails(
     disks_eq(&disks[0], &disk);
 }
 
+#[nexus_test]
+async fn test_disk_delete_protection(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+    create_project_and_pool(client).await;
+    let disks_url = get_disks_url();
+    let disk_url = get_disk_url(DISK_NAME);
+
+    let disk = create_disk(&client, PROJECT_NAME, DISK_NAME).await;
+    assert!(!disk.delete_protected);
+
+    // Protect the disk, then try to delete it.
+    let disk: Disk = object_put(
+        client,
+        &disk_url,
+        &params::DiskUpdate { delete_protected: true },
+    )
+    .await;
+    assert!(disk.delete_protected);
+    assert!(disk_get(&client, &disk_url).await.delete_protected);
+
+    let error =
+        object_delete_error(client, &disk_url, StatusCode::CONFLICT).await;
+    assert_eq!(error.error_code.as_deref(), Some("DeleteProtected"));
+    assert_eq!(
+        error.message,
+        format!(
+            "disk \"{}\" is delete-protected: clear its delete protection \
+            before deleting it",
+            DISK_NAME
+        )
+    );
+
+    // The disk should be untouched.
+    let disks = disks_list(&client, &disks_url).await;
+    assert_eq!(disks.len(), 1);
+    assert_eq!(disks[0].state, DiskState::Detached);
+
+    // Once the protection is cleared, the disk can be deleted.
+    let disk: Disk = object_put(
+        client,
+        &disk_url,
+        &params::DiskUpdate { delete_protected: false },
+    )
+    .await;
+    assert!(!disk.delete_protected);
+    object_delete(client, &disk_url).await;
+    assert_eq!(disks_list(&client, &disks_url).await.len(), 0);
+}
+
+#[nexus_test]
+async fn test_disk_force_detach(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+    create_project_and_pool(client).await;
+    let nexus = &cptestctx.server.server_context().nexus;
+    let datastore = nexus.datastore();
+    let disk_url = get_disk_url(DISK_NAME);
+    let force_detach_url =
+        format!("/v1/disks/{DISK_NAME}/force-detach?project={}", PROJECT_NAME);
+    let force_detach = params::DiskForceDetach {
+        reason: String::from("instance record was lost"),
+    };
+
+    // A detached disk can't be forcibly detached.
+    let disk = create_disk(&client, PROJECT_NAME, DISK_NAME).await;
+    let error = object_create_error(
+        client,
+        &force_detach_url,
+        &force_detach,
+        StatusCode::CONFLICT,
+    )
+    .await;
+    assert_eq!(error.message, "disk is not attached to an instance");
+
+    // Attach the disk to a stopped instance.
+    let instance = create_instance(&client, PROJECT_NAME, INSTANCE_NAME).await;
+    let instance_id = InstanceUuid::from_untyped_uuid(instance.identity.id);
+    set_instance_state(&client, INSTANCE_NAME, "stop").await;
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(client, instance_id, InstanceState::Stopped).await;
+    let disk = disk_post(
+        client,
+        &get_disk_attach_url(&instance.identity.id.into()),
+        disk.identity.name.clone(),
+    )
+    .await;
+    assert_eq!(disk.state, DiskState::Attached(instance.identity.id));
+
+    // While the instance exists, the disk should be detached from it normally.
+    let error = object_create_error(
+        client,
+        &force_detach_url,
+        &force_detach,
+        StatusCode::CONFLICT,
+    )
+    .await;
+    assert_eq!(
+        error.message,
+        format!(
+            "disk's instance {} still exists; detach the disk from the \
+             instance instead",
+            instance.identity.id
+        )
+    );
+
+    // Lose the instance record out from under the disk.
+    {
+        use async_bb8_diesel::AsyncRunQueryDsl;
+        use diesel::prelude::*;
+        use nexus_db_schema::schema::instance::dsl;
+        diesel::update(dsl::instance)
+            .filter(dsl::id.eq(instance.identity.id))
+            .set(dsl::time_deleted.eq(chrono::Utc::now()))
+            .execute_async(
+                &*datastore.pool_connection_for_tests().await.unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    // Now the disk can be forcibly detached, and only once.
+    let disk: Disk = NexusRequest::new(
+        RequestBuilder::new(client, Method::POST, &force_detach_url)
+            .body(Some(&force_detach))
+            .expect_status(Some(StatusCode::OK)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap()
+    .parsed_body()
+    .unwrap();
+    assert_eq!(disk.state, DiskState::Detached);
+    assert_eq!(disk_get(&client, &disk_url).await.state, DiskState::Detached);
+
+    let error = object_create_error(
+        client,
+        &force_detach_url,
+        &force_detach,
+        StatusCode::CONFLICT,
+    )
+    .await;
+    assert_eq!(error.message, "disk is not attached to an instance");
+
+    // The disk is usable again.
+    object_delete(client, &disk_url).await;
+}
+
+#[nexus_test]
+async fn test_disk_clone(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+    create_project_and_pool(client).await;
+    let disks_url = get_disks_url();
+
+    let source = create_disk(&client, PROJECT_NAME, DISK_NAME).await;
+
+    // A blank disk has nothing to copy.
+    let progress: views::DiskCopyProgress = object_get(
+        client,
+        &format!("/v1/disks/{DISK_NAME}/copy-progress?project={PROJECT_NAME}"),
+    )
+    .await;
+    assert_eq!(
+        progress,
+        views::DiskCopyProgress {
+            snapshot_id: None,
+            image_id: None,
+            complete: true,
+        }
+    );
+
+    // Clone the disk. The clone is created by an operation, which links to
+    // the new disk once it's done.
+    let operation = object_create_operation(
+        client,
+        &format!("/v1/disks/{DISK_NAME}/clone?project={PROJECT_NAME}"),
+        &params::DiskClone {
+            identity: IdentityMetadataCreateParams {
+                name: "cloned-rainsticks".parse().unwrap(),
+                description: String::from("sells more rainsticks"),
+            },
+        },
+    )
+    .await;
+    assert_eq!(operation.kind, views::OperationKind::DiskClone);
+    assert_eq!(operation.state, views::OperationState::Succeeded);
+    assert_eq!(operation.project_id, source.project_id);
+    assert_eq!(
+        operation.progress,
+        views::OperationProgress { steps_completed: 2, steps_total: 2 }
+    );
+    assert_eq!(operation.error, None);
+    let result = operation.result.expect("operation should have a result");
+    assert_eq!(result.resource_type, views::OperationResultType::Disk);
+    let clone: Disk = object_get(client, &result.href).await;
+    assert_eq!(clone.identity.id, result.id);
+    assert_ne!(clone.identity.id, source.identity.id);
+    assert_eq!(clone.identity.name.as_str(), "cloned-rainsticks");
+    assert_eq!(clone.project_id, source.project_id);
+    assert_eq!(clone.size, source.size);
+    assert_eq!(clone.block_size, source.block_size);
+    assert_eq!(clone.state, DiskState::Detached);
+    let snapshot_id =
+        clone.snapshot_id.expect("clone should be created from a snapshot");
+
+    // The snapshot used to make the clone shouldn't stick around.
+    let snapshots = objects_list_page_authz::<views::Snapshot>(
+        client,
+        &format!("/v1/snapshots?project={PROJECT_NAME}"),
+    )
+    .await;
+    assert!(snapshots.items.is_empty());
+
+    // The clone's contents haven't been copied, as it has never been attached
+    // to a running instance.
+    let progress: views::DiskCopyProgress = object_get(
+        client,
+        &format!(
+            "/v1/disks/cloned-rainsticks/copy-progress?project={PROJECT_NAME}"
+        ),
+    )
+    .await;
+    assert_eq!(
+        progress,
+        views::DiskCopyProgress {
+            snapshot_id: Some(snapshot_id),
+            image_id: None,
+            complete: false,
+        }
+    );
+
+    // Both disks exist, and the source can be deleted independently of the
+    // clone.
+    assert_eq!(disks_list(&client, &disks_url).await.len(), 2);
+    object_delete(client, &get_disk_url(DISK_NAME)).await;
+    let disks = disks_list(&client, &disks_url).await;
+    assert_eq!(disks.len(), 1);
+    assert_eq!(disks[0].identity.id, clone.identity.id);
+}
+
 #[nexus_test]
 async fn test_disk_slot_assignment(cptestctx: &ControlPlaneTestContext) {
     let client = &cptestctx.external_client;
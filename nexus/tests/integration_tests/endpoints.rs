@@ -24,6 +24,7 @@ use nexus_types::external_api::shared;
 use nexus_types::external_api::shared::IpRange;
 use nexus_types::external_api::shared::IpVersion;
 use nexus_types::external_api::shared::Ipv4Range;
+use nexus_types::external_api::views::ImageState;
 use nexus_types::external_api::views::SledProvisionPolicy;
 use omicron_common::api::external::AddressLotKind;
 use omicron_common::api::external::AffinityPolicy;
@@ -32,6 +33,7 @@ use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::FailureDomain;
 use omicron_common::api::external::IdentityMetadataCreateParams;
 use omicron_common::api::external::IdentityMetadataUpdateParams;
+use omicron_common::api::external::InstanceAutoRestartPolicy;
 use omicron_common::api::external::InstanceCpuCount;
 use omicron_common::api::external::Name;
 use omicron_common::api::external::NameOrId;
@@ -65,6 +67,10 @@ pub static HARDWARE_SLED_PROVISION_POLICY_URL: LazyLock<String> =
             SLED_AGENT_UUID
         )
     });
+pub static HARDWARE_SLED_EXPUNGE_PREVIEW_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!("/v1/system/hardware/sleds/{}/expunge:preview", SLED_AGENT_UUID)
+    });
 pub static DEMO_SLED_PROVISION_POLICY: LazyLock<
     params::SledProvisionPolicyParams,
 > = LazyLock::new(|| params::SledProvisionPolicyParams {
@@ -174,6 +180,23 @@ pub static DEMO_PROJECT_SELECTOR: LazyLock<String> =
     LazyLock::new(|| format!("project={}", *DEMO_PROJECT_NAME));
 pub static DEMO_PROJECT_POLICY_URL: LazyLock<String> =
     LazyLock::new(|| format!("/v1/projects/{}/policy", *DEMO_PROJECT_NAME));
+pub static DEMO_PROJECT_EPHEMERAL_IP_POLICY_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!("/v1/projects/{}/ephemeral-ip-policy", *DEMO_PROJECT_NAME)
+    });
+pub static DEMO_PROJECT_INSTANCE_AUTO_RESTART_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!("/v1/projects/{}/instance-auto-restart", *DEMO_PROJECT_NAME)
+    });
+pub static DEMO_PROJECT_QUOTAS_URL: LazyLock<String> = LazyLock::new(|| {
+    format!("/v1/system/projects/{}/quotas", *DEMO_PROJECT_NAME)
+});
+pub static DEMO_PROJECT_QUOTAS_CREATE: LazyLock<params::ProjectQuotasCreate> =
+    LazyLock::new(|| params::ProjectQuotasCreate {
+        cpus: 16,
+        memory: ByteCount::from_gibibytes_u32(64),
+        storage: ByteCount::from_gibibytes_u32(1024),
+    });
 pub static DEMO_PROJECT_URL_IMAGES: LazyLock<String> =
     LazyLock::new(|| format!("/v1/images?project={}", *DEMO_PROJECT_NAME));
 pub static DEMO_PROJECT_URL_INSTANCES: LazyLock<String> =
@@ -188,6 +211,16 @@ pub static DEMO_PROJECT_URL_ANTI_AFFINITY_GROUPS: LazyLock<String> =
     });
 pub static DEMO_PROJECT_URL_SNAPSHOTS: LazyLock<String> =
     LazyLock::new(|| format!("/v1/snapshots?project={}", *DEMO_PROJECT_NAME));
+pub static DEMO_PROJECT_URL_SNAPSHOT_SCHEDULES: LazyLock<String> =
+    LazyLock::new(|| {
+        format!("/v1/snapshot-schedules?project={}", *DEMO_PROJECT_NAME)
+    });
+pub static DEMO_PROJECT_URL_SNAPSHOT_EXPORTS: LazyLock<String> =
+    LazyLock::new(|| {
+        format!("/v1/snapshot-exports?project={}", *DEMO_PROJECT_NAME)
+    });
+pub static DEMO_PROJECT_URL_OPERATIONS: LazyLock<String> =
+    LazyLock::new(|| format!("/v1/operations?project={}", *DEMO_PROJECT_NAME));
 pub static DEMO_PROJECT_URL_VPCS: LazyLock<String> =
     LazyLock::new(|| format!("/v1/vpcs?project={}", *DEMO_PROJECT_NAME));
 pub static DEMO_PROJECT_URL_FIPS: LazyLock<String> = LazyLock::new(|| {
@@ -212,6 +245,10 @@ pub static DEMO_VPC_SELECTOR: LazyLock<String> = LazyLock::new(|| {
 });
 pub static DEMO_VPC_URL_FIREWALL_RULES: LazyLock<String> =
     LazyLock::new(|| format!("/v1/vpc-firewall-rules?{}", *DEMO_VPC_SELECTOR));
+pub static DEMO_VPC_URL_FIREWALL_RULES_REPLACE: LazyLock<String> =
+    LazyLock::new(|| {
+        format!("/v1/vpc-firewall-rules/replace?{}", *DEMO_VPC_SELECTOR)
+    });
 pub static DEMO_VPC_URL_ROUTERS: LazyLock<String> =
     LazyLock::new(|| format!("/v1/vpc-routers?{}", *DEMO_VPC_SELECTOR));
 pub static DEMO_VPC_URL_SUBNETS: LazyLock<String> =
@@ -239,6 +276,40 @@ pub static DEMO_VPC_SUBNET_INTERFACES_URL: LazyLock<String> =
             *DEMO_VPC_SUBNET_NAME, *DEMO_VPC_SELECTOR
         )
     });
+pub static DEMO_VPC_SUBNET_IP_RESERVATIONS_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/vpc-subnets/{}/ip-reservations?{}",
+            *DEMO_VPC_SUBNET_NAME, *DEMO_VPC_SELECTOR
+        )
+    });
+pub static DEMO_VPC_SUBNET_IP_RESERVATION_CREATE: LazyLock<
+    params::VpcSubnetIpReservationCreate,
+> = LazyLock::new(|| params::VpcSubnetIpReservationCreate {
+    ip: "10.0.0.10".parse().unwrap(),
+    description: String::from(""),
+});
+pub static DEMO_VPC_SUBNET_IP_RESERVATION_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/vpc-subnets/{}/ip-reservations/{}?{}",
+            *DEMO_VPC_SUBNET_NAME,
+            DEMO_VPC_SUBNET_IP_RESERVATION_CREATE.ip,
+            *DEMO_VPC_SELECTOR
+        )
+    });
+pub static DEMO_VPC_SUBNET_IPV4_BLOCKS_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/vpc-subnets/{}/ipv4-blocks?{}",
+            *DEMO_VPC_SUBNET_NAME, *DEMO_VPC_SELECTOR
+        )
+    });
+pub static DEMO_VPC_SUBNET_IPV4_BLOCK_ADD: LazyLock<
+    params::VpcSubnetIpv4BlockAdd,
+> = LazyLock::new(|| params::VpcSubnetIpv4BlockAdd {
+    block: "172.30.0.0/22".parse().unwrap(),
+});
 pub static DEMO_VPC_SUBNET_CREATE: LazyLock<params::VpcSubnetCreate> =
     LazyLock::new(|| params::VpcSubnetCreate {
         identity: IdentityMetadataCreateParams {
@@ -402,6 +473,35 @@ pub static DEMO_DISK_CREATE: LazyLock<params::DiskCreate> =
             ),
         }
     });
+pub static DEMO_DISK_UPDATE: LazyLock<params::DiskUpdate> =
+    LazyLock::new(|| params::DiskUpdate { delete_protected: false });
+pub static DEMO_DISK_CLONE_URL: LazyLock<String> = LazyLock::new(|| {
+    format!("/v1/disks/{}/clone?{}", *DEMO_DISK_NAME, *DEMO_PROJECT_SELECTOR)
+});
+pub static DEMO_DISK_CLONE: LazyLock<params::DiskClone> =
+    LazyLock::new(|| params::DiskClone {
+        identity: IdentityMetadataCreateParams {
+            name: "demo-disk-clone".parse().unwrap(),
+            description: "".parse().unwrap(),
+        },
+    });
+pub static DEMO_DISK_COPY_PROGRESS_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/disks/{}/copy-progress?{}",
+            *DEMO_DISK_NAME, *DEMO_PROJECT_SELECTOR
+        )
+    });
+pub static DEMO_DISK_FORCE_DETACH_URL: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "/v1/disks/{}/force-detach?{}",
+        *DEMO_DISK_NAME, *DEMO_PROJECT_SELECTOR
+    )
+});
+pub static DEMO_DISK_FORCE_DETACH: LazyLock<params::DiskForceDetach> =
+    LazyLock::new(|| params::DiskForceDetach {
+        reason: String::from("demo disk's instance is gone"),
+    });
 
 // Related to importing blocks from an external source
 pub static DEMO_IMPORT_DISK_NAME: LazyLock<Name> =
@@ -562,6 +662,24 @@ pub static DEMO_INSTANCE_STOP_URL: LazyLock<String> = LazyLock::new(|| {
         *DEMO_INSTANCE_NAME, *DEMO_PROJECT_SELECTOR
     )
 });
+pub const DEMO_INSTANCE_BULK_ACTION_URL: &'static str =
+    "/v1/instance-bulk-action";
+pub static DEMO_INSTANCE_BULK_ACTION: LazyLock<params::InstanceBulkAction> =
+    LazyLock::new(|| params::InstanceBulkAction {
+        action: params::InstanceBulkActionKind::Stop,
+        instance_ids: vec![uuid::Uuid::new_v4()],
+    });
+pub static DEMO_INSTANCE_FORCE_FAIL_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/instances/{}/force-fail?{}",
+            *DEMO_INSTANCE_NAME, *DEMO_PROJECT_SELECTOR
+        )
+    });
+pub static DEMO_INSTANCE_FORCE_FAIL: LazyLock<params::InstanceForceFail> =
+    LazyLock::new(|| params::InstanceForceFail {
+        reason: String::from("demo instance is wedged"),
+    });
 pub static DEMO_INSTANCE_REBOOT_URL: LazyLock<String> = LazyLock::new(|| {
     format!(
         "/v1/instances/{}/reboot?{}",
@@ -581,6 +699,20 @@ pub static DEMO_INSTANCE_SERIAL_STREAM_URL: LazyLock<String> =
             *DEMO_INSTANCE_NAME, *DEMO_PROJECT_SELECTOR
         )
     });
+pub static DEMO_INSTANCE_MIGRATIONS_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/instances/{}/migrations?{}",
+            *DEMO_INSTANCE_NAME, *DEMO_PROJECT_SELECTOR
+        )
+    });
+pub static DEMO_INSTANCE_AUTO_RESTART_HISTORY_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/instances/{}/auto-restart-history?{}",
+            *DEMO_INSTANCE_NAME, *DEMO_PROJECT_SELECTOR
+        )
+    });
 pub static DEMO_INSTANCE_DISKS_URL: LazyLock<String> = LazyLock::new(|| {
     format!(
         "/v1/instances/{}/disks?{}",
@@ -657,10 +789,12 @@ pub static DEMO_INSTANCE_CREATE: LazyLock<params::InstanceCreate> =
             pool: Some(DEMO_IP_POOL_NAME.clone().into()),
         }],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     });
 pub static DEMO_STOPPED_INSTANCE_CREATE: LazyLock<params::InstanceCreate> =
     LazyLock::new(|| params::InstanceCreate {
@@ -678,17 +812,21 @@ pub static DEMO_STOPPED_INSTANCE_CREATE: LazyLock<params::InstanceCreate> =
             pool: Some(DEMO_IP_POOL_NAME.clone().into()),
         }],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     });
 pub static DEMO_INSTANCE_UPDATE: LazyLock<params::InstanceUpdate> =
     LazyLock::new(|| params::InstanceUpdate {
-        boot_disk: None,
+        boot_order: Vec::new(),
         auto_restart_policy: None,
         ncpus: InstanceCpuCount(1),
         memory: ByteCount::from_gibibytes_u32(16),
+        tags: None,
+        stopped_reservation_policy: None,
     });
 
 // The instance needs a network interface, too.
@@ -907,6 +1045,18 @@ pub static DEMO_SILO_DEMOTE_IMAGE_URL: LazyLock<String> = LazyLock::new(|| {
     )
 });
 
+pub static DEMO_PROJECT_IMAGE_STATE_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/images/{}/state?project={}",
+            *DEMO_IMAGE_NAME, *DEMO_PROJECT_NAME
+        )
+    });
+pub static DEMO_IMAGE_STATE_UPDATE: LazyLock<params::ImageStateUpdate> =
+    LazyLock::new(|| params::ImageStateUpdate {
+        state: ImageState::Deprecated,
+    });
+
 pub static DEMO_IMAGE_CREATE: LazyLock<params::ImageCreate> =
     LazyLock::new(|| params::ImageCreate {
         identity: IdentityMetadataCreateParams {
@@ -963,6 +1113,16 @@ pub static DEMO_IP_POOL_SILO_URL: LazyLock<String> = LazyLock::new(|| {
 pub static DEMO_IP_POOL_SILO_UPDATE_BODY: LazyLock<params::IpPoolSiloUpdate> =
     LazyLock::new(|| params::IpPoolSiloUpdate { is_default: false });
 
+pub static DEMO_IP_POOL_PROJECTS_URL: LazyLock<String> =
+    LazyLock::new(|| format!("{}/projects", *DEMO_IP_POOL_URL));
+pub static DEMO_IP_POOL_PROJECTS_BODY: LazyLock<params::IpPoolLinkProject> =
+    LazyLock::new(|| params::IpPoolLinkProject {
+        project: uuid::Uuid::new_v4(),
+    });
+pub static DEMO_IP_POOL_PROJECT_URL: LazyLock<String> = LazyLock::new(|| {
+    format!("{}/projects/{}", *DEMO_IP_POOL_URL, uuid::Uuid::new_v4())
+});
+
 pub static DEMO_IP_POOL_RANGE: LazyLock<IpRange> = LazyLock::new(|| {
     IpRange::V4(
         Ipv4Range::new(
@@ -1006,6 +1166,61 @@ pub static DEMO_SNAPSHOT_CREATE: LazyLock<params::SnapshotCreate> =
         },
         disk: DEMO_DISK_NAME.clone().into(),
     });
+pub static DEMO_SNAPSHOT_UPDATE: LazyLock<params::SnapshotUpdate> =
+    LazyLock::new(|| params::SnapshotUpdate { delete_protected: false });
+
+// Snapshot schedules
+pub static DEMO_SNAPSHOT_SCHEDULE_NAME: LazyLock<Name> =
+    LazyLock::new(|| "demo-snapshot-schedule".parse().unwrap());
+pub static DEMO_SNAPSHOT_SCHEDULE_URL: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "/v1/snapshot-schedules/{}?project={}",
+        *DEMO_SNAPSHOT_SCHEDULE_NAME, *DEMO_PROJECT_NAME
+    )
+});
+pub static DEMO_SNAPSHOT_SCHEDULE_CREATE: LazyLock<
+    params::SnapshotScheduleCreate,
+> = LazyLock::new(|| params::SnapshotScheduleCreate {
+    identity: IdentityMetadataCreateParams {
+        name: DEMO_SNAPSHOT_SCHEDULE_NAME.clone(),
+        description: String::from(""),
+    },
+    disk: Some(DEMO_DISK_NAME.clone().into()),
+    interval_secs: 86400,
+    retain_count: 7,
+});
+pub static DEMO_SNAPSHOT_SCHEDULE_UPDATE: LazyLock<
+    params::SnapshotScheduleUpdate,
+> = LazyLock::new(|| params::SnapshotScheduleUpdate {
+    identity: IdentityMetadataUpdateParams { name: None, description: None },
+    interval_secs: None,
+    retain_count: Some(14),
+});
+
+// Snapshot exports
+pub static DEMO_SNAPSHOT_EXPORT_CREATE_URL: LazyLock<String> =
+    LazyLock::new(|| {
+        format!(
+            "/v1/snapshots/{}/export?project={}",
+            *DEMO_SNAPSHOT_NAME, *DEMO_PROJECT_NAME
+        )
+    });
+pub const DEMO_SNAPSHOT_EXPORT_URL: &'static str = "/v1/snapshot-exports/{id}";
+pub const DEMO_SNAPSHOT_EXPORT_MANIFEST_URL: &'static str =
+    "/v1/snapshot-exports/{id}/manifest";
+pub const DEMO_SNAPSHOT_EXPORT_DOWNLOAD_URL: &'static str =
+    "/v1/snapshot-exports/{id}/download";
+pub const DEMO_OPERATION_URL: &'static str = "/v1/operations/{id}";
+pub static DEMO_INSTANCE_EXPORT_URL: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "/v1/instances/{}/export?project={}",
+        *DEMO_INSTANCE_NAME, *DEMO_PROJECT_NAME
+    )
+});
+pub static DEMO_INSTANCE_EXPORT_CREATE: LazyLock<params::InstanceExportCreate> =
+    LazyLock::new(|| params::InstanceExportCreate {
+        snapshot_name: "demo-instance-export".parse().unwrap(),
+    });
 
 // SSH keys
 pub const DEMO_SSHKEYS_URL: &'static str = "/v1/me/ssh-keys";
@@ -1269,6 +1484,9 @@ pub static DEMO_UPDATE_TRUST_ROOT_CREATE: LazyLock<serde_json::Value> =
 pub static AUDIT_LOG_URL: LazyLock<String> = LazyLock::new(|| {
     String::from("/v1/system/audit-log?start_time=2025-01-01T00:00:00Z")
 });
+pub static RACK_ACTIVITY_URL: LazyLock<String> = LazyLock::new(|| {
+    String::from("/v1/system/activity?start_time=2025-01-01T00:00:00Z")
+});
 
 /// Describes an API endpoint to be verified by the "unauthorized" test
 ///
@@ -1501,6 +1719,25 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                     ),
                 ],
             },
+            // IP pool projects endpoint
+            VerifyEndpoint {
+                url: &DEMO_IP_POOL_PROJECTS_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Post(
+                        serde_json::to_value(&*DEMO_IP_POOL_PROJECTS_BODY)
+                            .unwrap(),
+                    ),
+                ],
+            },
+            VerifyEndpoint {
+                url: &DEMO_IP_POOL_PROJECT_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Delete],
+            },
             // IP Pool ranges endpoint
             VerifyEndpoint {
                 url: &DEMO_IP_POOL_RANGES_URL,
@@ -1813,6 +2050,64 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                     ),
                 ],
             },
+            VerifyEndpoint {
+                url: &DEMO_PROJECT_EPHEMERAL_IP_POLICY_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Put(
+                        serde_json::to_value(
+                            params::ProjectEphemeralIpPolicyUpdate {
+                                auto_assign: true,
+                                pool: None,
+                                allow_override: true,
+                            },
+                        )
+                        .unwrap(),
+                    ),
+                ],
+            },
+            VerifyEndpoint {
+                url: &DEMO_PROJECT_INSTANCE_AUTO_RESTART_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Put(
+                        serde_json::to_value(
+                            params::ProjectInstanceAutoRestartUpdate {
+                                policy: Some(
+                                    InstanceAutoRestartPolicy::BestEffort,
+                                ),
+                                cooldown_secs: Some(60),
+                            },
+                        )
+                        .unwrap(),
+                    ),
+                ],
+            },
+            VerifyEndpoint {
+                url: &DEMO_PROJECT_QUOTAS_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Post(
+                        serde_json::to_value(&*DEMO_PROJECT_QUOTAS_CREATE)
+                            .unwrap(),
+                    ),
+                    AllowedMethod::Put(
+                        serde_json::to_value(params::ProjectQuotasUpdate {
+                            cpus: Some(32),
+                            memory: None,
+                            storage: None,
+                        })
+                        .unwrap(),
+                    ),
+                    AllowedMethod::Delete,
+                ],
+            },
             /* VPCs */
             VerifyEndpoint {
                 url: &DEMO_PROJECT_URL_VPCS,
@@ -1859,6 +2154,18 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                     ),
                 ],
             },
+            VerifyEndpoint {
+                url: &DEMO_VPC_URL_FIREWALL_RULES_REPLACE,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::to_value(params::VpcFirewallRulesReplace {
+                        rules: vec![],
+                        confirm_token: None,
+                    })
+                    .unwrap(),
+                )],
+            },
             /* VPC Subnets */
             VerifyEndpoint {
                 url: &DEMO_VPC_URL_SUBNETS,
@@ -1896,6 +2203,38 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                 unprivileged_access: UnprivilegedAccess::None,
                 allowed_methods: vec![AllowedMethod::Get],
             },
+            VerifyEndpoint {
+                url: &DEMO_VPC_SUBNET_IP_RESERVATIONS_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Post(
+                        serde_json::to_value(
+                            &*DEMO_VPC_SUBNET_IP_RESERVATION_CREATE,
+                        )
+                        .unwrap(),
+                    ),
+                ],
+            },
+            VerifyEndpoint {
+                url: &DEMO_VPC_SUBNET_IP_RESERVATION_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Delete],
+            },
+            VerifyEndpoint {
+                url: &DEMO_VPC_SUBNET_IPV4_BLOCKS_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Post(
+                        serde_json::to_value(&*DEMO_VPC_SUBNET_IPV4_BLOCK_ADD)
+                            .unwrap(),
+                    ),
+                ],
+            },
             /* VPC Routers */
             VerifyEndpoint {
                 url: &DEMO_VPC_URL_ROUTERS,
@@ -2043,9 +2382,46 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                 unprivileged_access: UnprivilegedAccess::None,
                 allowed_methods: vec![
                     AllowedMethod::Get,
+                    AllowedMethod::Put(
+                        serde_json::to_value(&*DEMO_DISK_UPDATE).unwrap(),
+                    ),
                     AllowedMethod::Delete,
                 ],
             },
+            VerifyEndpoint {
+                url: &DEMO_DISK_CLONE_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::to_value(&*DEMO_DISK_CLONE).unwrap(),
+                )],
+            },
+            VerifyEndpoint {
+                url: &DEMO_DISK_COPY_PROGRESS_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: &DEMO_DISK_FORCE_DETACH_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::to_value(&*DEMO_DISK_FORCE_DETACH).unwrap(),
+                )],
+            },
+            VerifyEndpoint {
+                url: &DEMO_INSTANCE_MIGRATIONS_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: &DEMO_INSTANCE_AUTO_RESTART_HISTORY_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
             VerifyEndpoint {
                 url: &DEMO_INSTANCE_DISKS_URL,
                 visibility: Visibility::Protected,
@@ -2255,6 +2631,14 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                     serde_json::value::Value::Null,
                 )],
             },
+            VerifyEndpoint {
+                url: &DEMO_PROJECT_IMAGE_STATE_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Put(
+                    serde_json::to_value(&*DEMO_IMAGE_STATE_UPDATE).unwrap(),
+                )],
+            },
             /* Snapshots */
             VerifyEndpoint {
                 url: &DEMO_PROJECT_URL_SNAPSHOTS,
@@ -2272,11 +2656,98 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                 url: &DEMO_SNAPSHOT_URL,
                 visibility: Visibility::Protected,
                 unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Put(
+                        serde_json::to_value(&*DEMO_SNAPSHOT_UPDATE).unwrap(),
+                    ),
+                    AllowedMethod::Delete,
+                ],
+            },
+            /* Snapshot schedules */
+            VerifyEndpoint {
+                url: &DEMO_PROJECT_URL_SNAPSHOT_SCHEDULES,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Post(
+                        serde_json::to_value(&*DEMO_SNAPSHOT_SCHEDULE_CREATE)
+                            .unwrap(),
+                    ),
+                ],
+            },
+            VerifyEndpoint {
+                url: &DEMO_SNAPSHOT_SCHEDULE_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![
+                    AllowedMethod::Get,
+                    AllowedMethod::Put(
+                        serde_json::to_value(&*DEMO_SNAPSHOT_SCHEDULE_UPDATE)
+                            .unwrap(),
+                    ),
+                    AllowedMethod::Delete,
+                ],
+            },
+            /* Snapshot exports */
+            VerifyEndpoint {
+                url: &DEMO_PROJECT_URL_SNAPSHOT_EXPORTS,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: &DEMO_SNAPSHOT_EXPORT_CREATE_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::value::Value::Null,
+                )],
+            },
+            VerifyEndpoint {
+                url: DEMO_SNAPSHOT_EXPORT_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
                 allowed_methods: vec![
                     AllowedMethod::Get,
                     AllowedMethod::Delete,
                 ],
             },
+            VerifyEndpoint {
+                url: DEMO_SNAPSHOT_EXPORT_MANIFEST_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: DEMO_SNAPSHOT_EXPORT_DOWNLOAD_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::GetNonexistent],
+            },
+            VerifyEndpoint {
+                url: &DEMO_INSTANCE_EXPORT_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::to_value(&*DEMO_INSTANCE_EXPORT_CREATE)
+                        .unwrap(),
+                )],
+            },
+            /* Operations */
+            VerifyEndpoint {
+                url: &DEMO_PROJECT_URL_OPERATIONS,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: DEMO_OPERATION_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
             /* Instances */
             VerifyEndpoint {
                 url: &DEMO_PROJECT_URL_INSTANCES,
@@ -2317,6 +2788,24 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                     serde_json::Value::Null,
                 )],
             },
+            // Anyone may make this request: whether the action is allowed is
+            // reported separately for each instance.
+            VerifyEndpoint {
+                url: &DEMO_INSTANCE_BULK_ACTION_URL,
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::Full,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::to_value(&*DEMO_INSTANCE_BULK_ACTION).unwrap(),
+                )],
+            },
+            VerifyEndpoint {
+                url: &DEMO_INSTANCE_FORCE_FAIL_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::to_value(&*DEMO_INSTANCE_FORCE_FAIL).unwrap(),
+                )],
+            },
             VerifyEndpoint {
                 url: &DEMO_INSTANCE_REBOOT_URL,
                 visibility: Visibility::Protected,
@@ -2453,6 +2942,14 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                     serde_json::to_value(&*DEMO_SLED_PROVISION_POLICY).unwrap(),
                 )],
             },
+            VerifyEndpoint {
+                url: &HARDWARE_SLED_EXPUNGE_PREVIEW_URL,
+                visibility: Visibility::Protected,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Post(
+                    serde_json::Value::Null,
+                )],
+            },
             VerifyEndpoint {
                 url: "/v1/system/hardware/switches",
                 visibility: Visibility::Public,
@@ -2556,6 +3053,44 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                     ),
                 ],
             },
+            VerifyEndpoint {
+                url: "/v1/system/update/components",
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: "/v1/system/update/technician-port",
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            /* Background tasks */
+            VerifyEndpoint {
+                url: "/v1/system/background-tasks",
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: "/v1/system/background-tasks/blueprint_loader/history",
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            /* Blueprints */
+            VerifyEndpoint {
+                url: "/v1/system/blueprints",
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
+            VerifyEndpoint {
+                url: "/v1/system/blueprints/target",
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
             /* Metrics */
             VerifyEndpoint {
                 url: &DEMO_SYSTEM_METRICS_URL,
@@ -3027,6 +3562,12 @@ pub static VERIFY_ENDPOINTS: LazyLock<Vec<VerifyEndpoint>> = LazyLock::new(
                 unprivileged_access: UnprivilegedAccess::None,
                 allowed_methods: vec![AllowedMethod::Get],
             },
+            VerifyEndpoint {
+                url: &RACK_ACTIVITY_URL,
+                visibility: Visibility::Public,
+                unprivileged_access: UnprivilegedAccess::None,
+                allowed_methods: vec![AllowedMethod::Get],
+            },
         ]
     },
 );
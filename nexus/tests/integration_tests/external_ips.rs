@@ -34,6 +34,7 @@ use nexus_test_utils::resource_helpers::object_delete;
 use nexus_test_utils::resource_helpers::object_delete_error;
 use nexus_test_utils::resource_helpers::object_get;
 use nexus_test_utils::resource_helpers::object_put;
+use nexus_test_utils::resource_helpers::object_put_error;
 use nexus_test_utils::resource_helpers::test_params;
 use nexus_test_utils_macros::nexus_test;
 use nexus_types::external_api::params;
@@ -55,7 +56,7 @@ use omicron_common::api::external::Name;
 use omicron_common::api::external::NameOrId;
 use omicron_uuid_kinds::GenericUuid;
 use omicron_uuid_kinds::InstanceUuid;
-use oxide_client::types::ExternalIpResultsPage;
+use oxide_client::types::InstanceExternalIpResultsPage;
 use oxide_client::types::IpPoolRangeResultsPage;
 use uuid::Uuid;
 
@@ -825,6 +826,15 @@ async fn test_external_ip_live_attach_detach(
 
         assert_eq!(eip_list.len(), 3);
         assert!(eip_list.contains(&eph_resp));
+        // Addresses are listed in the order in which they were attached.
+        assert_eq!(
+            eip_list.iter().map(|v| v.kind()).collect::<Vec<_>>(),
+            [
+                shared::IpKind::SNat,
+                shared::IpKind::Ephemeral,
+                shared::IpKind::Floating,
+            ],
+        );
         assert!(
             eip_list
                 .iter()
@@ -1039,10 +1049,12 @@ async fn test_floating_ip_attach_fail_between_projects(
                 floating_ip: fip.identity.id.into(),
             }],
             disks: vec![],
-            boot_disk: None,
+            boot_order: Vec::new(),
             start: true,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         },
         StatusCode::BAD_REQUEST,
     )
@@ -1257,6 +1269,121 @@ async fn test_external_ip_attach_ephemeral_at_pool_exhaustion(
     assert_eq!(eph_resp_2, eph_resp);
 }
 
+#[nexus_test]
+async fn test_project_ephemeral_ip_policy(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+
+    create_default_ip_pool(&client).await;
+    let other_pool_range = IpRange::V4(
+        Ipv4Range::new(Ipv4Addr::new(10, 1, 0, 1), Ipv4Addr::new(10, 1, 0, 5))
+            .unwrap(),
+    );
+    let (other_pool, ..) =
+        create_ip_pool(&client, "other-pool", Some(other_pool_range)).await;
+    link_ip_pool(&client, "other-pool", &DEFAULT_SILO.id(), false).await;
+    create_project(client, PROJECT_NAME).await;
+
+    let policy_url = format!("/v1/projects/{PROJECT_NAME}/ephemeral-ip-policy");
+    let ephemeral_ips = |ips: Vec<views::ExternalIp>| {
+        ips.into_iter()
+            .filter_map(|ip| match ip {
+                views::ExternalIp::Ephemeral { ip_pool_id, .. } => {
+                    Some(ip_pool_id)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // By default, instances only get the ephemeral IPs they ask for.
+    let policy: views::ProjectEphemeralIpPolicy =
+        object_get(client, &policy_url).await;
+    assert!(!policy.auto_assign);
+    assert_eq!(policy.ip_pool_id, None);
+    assert!(policy.allow_override);
+
+    instance_for_external_ips(client, INSTANCE_NAMES[0], false, false, &[])
+        .await;
+    let ips =
+        fetch_instance_external_ips(client, INSTANCE_NAMES[0], PROJECT_NAME)
+            .await;
+    assert!(ephemeral_ips(ips).is_empty());
+
+    // Pools not linked to the project's silo are rejected.
+    create_ip_pool(&client, "unlinked-pool", None).await;
+    object_put_error(
+        client,
+        &policy_url,
+        &params::ProjectEphemeralIpPolicyUpdate {
+            auto_assign: true,
+            pool: Some("unlinked-pool".parse::<Name>().unwrap().into()),
+            allow_override: false,
+        },
+        StatusCode::NOT_FOUND,
+    )
+    .await;
+
+    // Once the policy is set, instances that don't ask for an ephemeral IP
+    // get one from the policy's pool.
+    let policy: views::ProjectEphemeralIpPolicy = object_put(
+        client,
+        &policy_url,
+        &params::ProjectEphemeralIpPolicyUpdate {
+            auto_assign: true,
+            pool: Some("other-pool".parse::<Name>().unwrap().into()),
+            allow_override: false,
+        },
+    )
+    .await;
+    assert!(policy.auto_assign);
+    assert_eq!(policy.ip_pool_id, Some(other_pool.identity.id));
+    assert!(!policy.allow_override);
+
+    instance_for_external_ips(client, INSTANCE_NAMES[1], false, false, &[])
+        .await;
+    let ips =
+        fetch_instance_external_ips(client, INSTANCE_NAMES[1], PROJECT_NAME)
+            .await;
+    assert_eq!(ephemeral_ips(ips), vec![other_pool.identity.id]);
+
+    // Overriding the policy is not allowed.
+    let url = format!("/v1/instances?project={PROJECT_NAME}");
+    let error = object_create_error(
+        client,
+        &url,
+        &params::InstanceCreate {
+            identity: IdentityMetadataCreateParams {
+                name: "anonymous-cafe".parse().unwrap(),
+                description: "".into(),
+            },
+            ncpus: InstanceCpuCount(4),
+            memory: ByteCount::from_gibibytes_u32(1),
+            hostname: "the-host".parse().unwrap(),
+            user_data: vec![],
+            ssh_public_keys: Some(Vec::new()),
+            network_interfaces:
+                params::InstanceNetworkInterfaceAttachment::Default,
+            external_ips: vec![params::ExternalIpCreate::Ephemeral {
+                pool: None,
+            }],
+            disks: vec![],
+            boot_order: Vec::new(),
+            start: false,
+            auto_restart_policy: Default::default(),
+            anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
+        },
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    assert_eq!(
+        error.message,
+        "This project's ephemeral IP policy does not allow requesting an \
+        ephemeral IP address"
+    );
+}
+
 #[nexus_test]
 async fn can_list_instance_snat_ip(cptestctx: &ControlPlaneTestContext) {
     let client = &cptestctx.external_client;
@@ -1298,7 +1425,7 @@ async fn can_list_instance_snat_ip(cptestctx: &ControlPlaneTestContext) {
         .unwrap_or_else(|e| {
             panic!("failed to make \"get\" request to {url}: {e}")
         })
-        .parsed_body::<ExternalIpResultsPage>()
+        .parsed_body::<InstanceExternalIpResultsPage>()
         .unwrap_or_else(|e| {
             panic!("failed to make \"get\" request to {url}: {e}")
         });
@@ -1313,7 +1440,7 @@ async fn can_list_instance_snat_ip(cptestctx: &ControlPlaneTestContext) {
         ip_pool_id,
         first_port,
         last_port,
-    } = &ips[0]
+    } = &ips[0].external_ip
     else {
         panic!("Expected an SNAT external IP, found {:?}", &ips[0]);
     };
@@ -175,6 +175,110 @@ async fn test_make_disk_from_image(cptestctx: &ControlPlaneTestContext) {
         .unwrap();
 }
 
+#[nexus_test]
+async fn test_image_state(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+
+    create_project(client, PROJECT_NAME).await;
+
+    let images_url = get_project_images_url(PROJECT_NAME);
+    let image_create_params = get_image_create(
+        params::ImageSource::YouCanBootAnythingAsLongAsItsAlpine,
+    );
+    let image =
+        NexusRequest::objects_post(client, &images_url, &image_create_params)
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap::<views::Image>()
+            .await;
+    assert_eq!(image.state, views::ImageState::Active);
+
+    let state_url = format!("/v1/images/{}/state", image.identity.id);
+    let set_state = |state| {
+        let state_url = state_url.clone();
+        async move {
+            NexusRequest::object_put(
+                client,
+                &state_url,
+                Some(&params::ImageStateUpdate { state }),
+            )
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap::<views::Image>()
+            .await
+        }
+    };
+    let disks_url = format!("/v1/disks?project={}", PROJECT_NAME);
+    let disk_create = |name: &str| params::DiskCreate {
+        identity: IdentityMetadataCreateParams {
+            name: name.parse().unwrap(),
+            description: String::from(""),
+        },
+        disk_source: params::DiskSource::Image { image_id: image.identity.id },
+        size: ByteCount::from_gibibytes_u32(1),
+    };
+
+    // Disks created from an active image have no warnings.
+    let disk: Disk =
+        NexusRequest::objects_post(client, &disks_url, &disk_create("active"))
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap()
+            .await;
+    assert!(disk.warnings.is_empty());
+
+    // Disks can still be created from a deprecated image, with a warning.
+    let deprecated = set_state(views::ImageState::Deprecated).await;
+    assert_eq!(deprecated.state, views::ImageState::Deprecated);
+    let disk: Disk = NexusRequest::objects_post(
+        client,
+        &disks_url,
+        &disk_create("deprecated"),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap()
+    .await;
+    assert_eq!(disk.warnings, vec!["image \"alpine-edge\" is deprecated"]);
+
+    // The warning is only reported when the disk is created.
+    let disk: Disk = NexusRequest::object_get(
+        client,
+        &format!("/v1/disks/{}", disk.identity.id),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap()
+    .await;
+    assert!(disk.warnings.is_empty());
+
+    // Blocked images can't be used to create disks.
+    let blocked = set_state(views::ImageState::Blocked).await;
+    assert_eq!(blocked.state, views::ImageState::Blocked);
+    let error = NexusRequest::expect_failure_with_body(
+        client,
+        StatusCode::BAD_REQUEST,
+        Method::POST,
+        &disks_url,
+        &disk_create("blocked"),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .expect("expected 400")
+    .parsed_body::<dropshot::HttpErrorResponseBody>()
+    .unwrap();
+    assert_eq!(
+        error.message,
+        "image \"alpine-edge\" is blocked and cannot be used to create new \
+         disks"
+    );
+
+    // Making the image active again allows disks to be created from it.
+    set_state(views::ImageState::Active).await;
+    NexusRequest::objects_post(client, &disks_url, &disk_create("reactivated"))
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .unwrap();
+}
+
 #[nexus_test]
 async fn test_make_disk_from_other_project_image_fails(
     cptestctx: &ControlPlaneTestContext,
@@ -391,6 +495,125 @@ async fn test_image_promotion(cptestctx: &ControlPlaneTestContext) {
     .unwrap();
 }
 
+#[nexus_test]
+async fn test_image_demotion(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+
+    let silo_images_url = "/v1/images";
+    let images_url = get_project_images_url(PROJECT_NAME);
+
+    create_project(client, PROJECT_NAME).await;
+
+    let image_create_params = get_image_create(
+        params::ImageSource::YouCanBootAnythingAsLongAsItsAlpine,
+    );
+
+    let image =
+        NexusRequest::objects_post(client, &images_url, &image_create_params)
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap::<views::Image>()
+            .await;
+    let image_id = image.identity.id;
+
+    // promote the image to the silo
+    let promote_url = format!("/v1/images/{}/promote", image_id);
+    NexusRequest::new(
+        RequestBuilder::new(client, http::Method::POST, &promote_url)
+            .expect_status(Some(http::StatusCode::ACCEPTED)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap::<views::Image>()
+    .await;
+
+    // demote it back into the project
+    let demote_url =
+        format!("/v1/images/{}/demote?project={}", image_id, PROJECT_NAME);
+    let demoted = NexusRequest::new(
+        RequestBuilder::new(client, http::Method::POST, &demote_url)
+            .expect_status(Some(http::StatusCode::ACCEPTED)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap::<views::Image>()
+    .await;
+
+    assert_eq!(demoted.identity.id, image_id);
+    assert!(demoted.project_id.is_some());
+
+    let silo_images = NexusRequest::object_get(client, &silo_images_url)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute_and_parse_unwrap::<ResultsPage<views::Image>>()
+        .await
+        .items;
+    assert_eq!(silo_images.len(), 0);
+
+    let project_images = NexusRequest::object_get(client, &images_url)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute_and_parse_unwrap::<ResultsPage<views::Image>>()
+        .await
+        .items;
+    assert_eq!(project_images.len(), 1);
+    assert_eq!(project_images[0].identity.id, image_id);
+
+    // A project image cannot be demoted
+    NexusRequest::new(
+        RequestBuilder::new(client, http::Method::POST, &demote_url)
+            .expect_status(Some(StatusCode::BAD_REQUEST)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .expect("unexpected success");
+
+    // Promote it again, then create another project image with the same
+    // name. Demoting the silo image must now conflict.
+    NexusRequest::new(
+        RequestBuilder::new(client, http::Method::POST, &promote_url)
+            .expect_status(Some(http::StatusCode::ACCEPTED)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap::<views::Image>()
+    .await;
+
+    NexusRequest::objects_post(client, &images_url, &image_create_params)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute_and_parse_unwrap::<views::Image>()
+        .await;
+
+    let error = NexusRequest::new(
+        RequestBuilder::new(client, http::Method::POST, &demote_url)
+            .expect_status(Some(StatusCode::BAD_REQUEST)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .expect("unexpected success")
+    .parsed_body::<dropshot::HttpErrorResponseBody>()
+    .unwrap();
+    assert_eq!(error.error_code.as_deref(), Some("ObjectAlreadyExists"));
+
+    // The silo image is left where it was
+    let silo_images = NexusRequest::object_get(client, &silo_images_url)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute_and_parse_unwrap::<ResultsPage<views::Image>>()
+        .await
+        .items;
+    assert_eq!(silo_images.len(), 1);
+    assert_eq!(silo_images[0].identity.id, image_id);
+
+    // Demoting into a project that doesn't exist fails
+    let demote_url =
+        format!("/v1/images/{}/demote?project=nonexistent", image_id);
+    NexusRequest::new(
+        RequestBuilder::new(client, http::Method::POST, &demote_url)
+            .expect_status(Some(StatusCode::NOT_FOUND)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .expect("unexpected success");
+}
+
 #[nexus_test]
 async fn test_image_from_other_project_snapshot_fails(
     cptestctx: &ControlPlaneTestContext,
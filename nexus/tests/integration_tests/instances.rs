@@ -44,6 +44,13 @@ use nexus_types::external_api::shared::IpKind;
 use nexus_types::external_api::shared::IpRange;
 use nexus_types::external_api::shared::Ipv4Range;
 use nexus_types::external_api::shared::SiloIdentityMode;
+use nexus_types::external_api::views::InstanceAutoRestartEvent;
+use nexus_types::external_api::views::InstanceAutoRestartReason;
+use nexus_types::external_api::views::InstanceBulkActionOutcome;
+use nexus_types::external_api::views::InstanceBulkActionResult;
+use nexus_types::external_api::views::InstanceBulkActionResults;
+use nexus_types::external_api::views::InstanceMigration;
+use nexus_types::external_api::views::InstanceMigrationState;
 use nexus_types::external_api::views::SshKey;
 use nexus_types::external_api::{params, views};
 use nexus_types::identity::Resource;
@@ -59,9 +66,11 @@ use omicron_common::api::external::IdentityMetadataCreateParams;
 use omicron_common::api::external::IdentityMetadataUpdateParams;
 use omicron_common::api::external::Instance;
 use omicron_common::api::external::InstanceAutoRestartPolicy;
+use omicron_common::api::external::InstanceAutoRestartState;
 use omicron_common::api::external::InstanceCpuCount;
 use omicron_common::api::external::InstanceNetworkInterface;
 use omicron_common::api::external::InstanceState;
+use omicron_common::api::external::InstanceStoppedReservationPolicy;
 use omicron_common::api::external::Name;
 use omicron_common::api::external::NameOrId;
 use omicron_common::api::external::Vni;
@@ -79,6 +88,7 @@ use omicron_uuid_kinds::PropolisUuid;
 use omicron_uuid_kinds::SledUuid;
 use omicron_uuid_kinds::{GenericUuid, InstanceUuid};
 use sled_agent_client::TestInterfaces as _;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::net::Ipv4Addr;
@@ -232,11 +242,13 @@ async fn test_create_instance_with_bad_hostname_impl(
         network_interfaces: Default::default(),
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: false,
         ssh_public_keys: None,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let mut body: serde_json::Value =
         serde_json::from_str(&serde_json::to_string(&params).unwrap()).unwrap();
@@ -340,10 +352,12 @@ async fn test_instances_create_reboot_halt(
                     params::InstanceNetworkInterfaceAttachment::Default,
                 external_ips: vec![],
                 disks: vec![],
-                boot_disk: None,
+                boot_order: Vec::new(),
                 start: true,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             }))
             .expect_status(Some(StatusCode::BAD_REQUEST)),
     )
@@ -897,6 +911,27 @@ async fn test_instance_migrate(cptestctx: &ControlPlaneTestContext) {
     let migration = dbg!(migration_fetch(cptestctx, migration_id).await);
     assert_eq!(migration.target_state, MigrationState::Completed.into());
     assert_eq!(migration.source_state, MigrationState::Completed.into());
+
+    // The completed migration should show up in the instance's migration
+    // history.
+    let migrations_url = format!(
+        "/v1/instances/{}/migrations?project={}",
+        instance_name, PROJECT_NAME
+    );
+    let migrations =
+        objects_list_page_authz::<InstanceMigration>(client, &migrations_url)
+            .await
+            .items;
+    assert_eq!(migrations.len(), 1);
+    let migration = &migrations[0];
+    assert_eq!(migration.id, migration_id);
+    assert_eq!(migration.state, InstanceMigrationState::Completed);
+    assert!(migration.time_finished.is_some());
+    assert_eq!(
+        migration.source_sled_id,
+        Some(original_sled.into_untyped_uuid())
+    );
+    assert_eq!(migration.target_sled_id, Some(dst_sled_id.into_untyped_uuid()));
 }
 
 #[nexus_test(extra_sled_agents = 3)]
@@ -1175,6 +1210,59 @@ async fn test_instance_failed_after_sled_agent_forgets_vmm_can_be_stopped(
     expect_instance_delete_ok(client, instance_name).await;
 }
 
+// Verifies that an operator can force a wedged instance into the Failed state,
+// but not one whose sled agent reports it as running normally.
+#[nexus_test]
+async fn test_instance_force_fail(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    let apictx = &cptestctx.server.server_context();
+    let nexus = &apictx.nexus;
+
+    // Model a wedged instance with one whose sled agent has forgotten its VMM.
+    let instance_name = "wedged";
+    let instance_id = make_forgotten_instance(
+        &cptestctx,
+        instance_name,
+        InstanceAutoRestartPolicy::Never,
+    )
+    .await;
+
+    // A healthy, running instance can't be forced to fail.
+    let healthy_name = "healthy";
+    let healthy = create_instance(client, PROJECT_NAME, healthy_name).await;
+    let healthy_id = InstanceUuid::from_untyped_uuid(healthy.identity.id);
+    instance_simulate(nexus, &healthy_id).await;
+    expect_instance_force_fail(client, healthy_name, StatusCode::CONFLICT)
+        .await;
+    let healthy = instance_get(&client, &get_instance_url(healthy_name)).await;
+    assert_eq!(healthy.runtime.run_state, InstanceState::Running);
+
+    // The wedged instance can.
+    let url = get_instance_url(format!("{instance_name}/force-fail").as_str());
+    let instance: Instance = NexusRequest::new(
+        RequestBuilder::new(client, Method::POST, &url)
+            .body(Some(&params::InstanceForceFail {
+                reason: String::from("VMM stopped responding"),
+            }))
+            .expect_status(Some(StatusCode::ACCEPTED)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap()
+    .parsed_body()
+    .unwrap();
+    assert_eq!(instance.identity.id, instance_id.into_untyped_uuid());
+    instance_wait_for_state(client, instance_id, InstanceState::Failed).await;
+
+    // Having failed, the instance no longer has a VMM to fail.
+    expect_instance_force_fail(client, instance_name, StatusCode::CONFLICT)
+        .await;
+
+    // Once failed, the instance can be restarted as usual.
+    expect_instance_start_ok(client, instance_name).await;
+}
+
 // Verifies that the instance-watcher background task transitions an instance
 // to Failed when the sled-agent returns a 404, and that the instance can be
 // deleted after it transitions to Failed.
@@ -1422,6 +1510,35 @@ async fn test_instance_failed_by_instance_watcher_automatically_reincarnates(
         )
         .await
     );
+
+    // The restart should be recorded in the instance's auto-restart history,
+    // and reflected in its auto-restart status.
+    let history_url = format!(
+        "/v1/instances/resurgam/auto-restart-history?{}",
+        get_project_selector()
+    );
+    let events = objects_list_page_authz::<InstanceAutoRestartEvent>(
+        client,
+        &history_url,
+    )
+    .await
+    .items;
+    assert_eq!(events.len(), 1, "{events:#?}");
+    assert_eq!(events[0].instance_id, instance_id.into_untyped_uuid());
+    assert_eq!(events[0].reason, InstanceAutoRestartReason::Failed);
+
+    let instance = instance_get(client, &get_instance_url("resurgam")).await;
+    assert_eq!(instance.auto_restart_status.count, 1);
+    assert_eq!(
+        instance.runtime.time_last_auto_restarted,
+        Some(events[0].time_restarted)
+    );
+    // The instance was just restarted, so it won't be restarted again until
+    // its cooldown period has elapsed.
+    assert_eq!(
+        instance.auto_restart_status.state,
+        InstanceAutoRestartState::CoolingDown
+    );
 }
 
 // Verifies that if an instance transitions to `Failed` due to a failed request
@@ -1686,6 +1803,24 @@ async fn expect_instance_reboot_fail(
         .expect("expected instance reboot to fail");
 }
 
+async fn expect_instance_force_fail(
+    client: &ClientTestContext,
+    instance_name: &str,
+    status: http::StatusCode,
+) {
+    let url = get_instance_url(format!("{instance_name}/force-fail").as_str());
+    let builder = RequestBuilder::new(client, Method::POST, &url)
+        .body(Some(&params::InstanceForceFail {
+            reason: String::from("testing"),
+        }))
+        .expect_status(Some(status));
+    NexusRequest::new(builder)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .expect("expected instance force-fail to fail");
+}
+
 async fn expect_instance_stop_fail(
     client: &ClientTestContext,
     instance_name: &str,
@@ -2014,10 +2149,12 @@ async fn test_instances_create_stopped_start(
                 params::InstanceNetworkInterfaceAttachment::Default,
             external_ips: vec![],
             disks: vec![],
-            boot_disk: None,
+            boot_order: Vec::new(),
             start: false,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         },
     )
     .await;
@@ -2040,6 +2177,220 @@ async fn test_instances_create_stopped_start(
     );
 }
 
+async fn instance_bulk_action(
+    client: &ClientTestContext,
+    action: params::InstanceBulkActionKind,
+    instance_ids: Vec<Uuid>,
+) -> Vec<InstanceBulkActionResult> {
+    NexusRequest::new(
+        RequestBuilder::new(client, Method::POST, "/v1/instance-bulk-action")
+            .body(Some(&params::InstanceBulkAction { action, instance_ids }))
+            .expect_status(Some(StatusCode::OK)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap::<InstanceBulkActionResults>()
+    .await
+    .results
+}
+
+// Test that many instances can be started and stopped in one request, and that
+// the action failing for one instance doesn't affect the others.
+#[nexus_test]
+async fn test_instance_bulk_action(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    let nexus = &cptestctx.server.server_context().nexus;
+    create_project_and_pool(client).await;
+
+    let mut instance_ids = Vec::new();
+    for name in ["bulk-1", "bulk-2", "bulk-3"] {
+        let instance = create_instance_with(
+            client,
+            PROJECT_NAME,
+            name,
+            &params::InstanceNetworkInterfaceAttachment::Default,
+            Vec::<params::InstanceDiskAttachment>::new(),
+            Vec::<params::ExternalIpCreate>::new(),
+            false,
+            Default::default(),
+        )
+        .await;
+        instance_ids.push(instance.identity.id);
+    }
+    let missing_id = Uuid::new_v4();
+
+    // Start every instance, along with one that doesn't exist.
+    let mut ids = instance_ids.clone();
+    ids.insert(1, missing_id);
+    let results = instance_bulk_action(
+        client,
+        params::InstanceBulkActionKind::Start,
+        ids.clone(),
+    )
+    .await;
+    assert_eq!(
+        results.iter().map(|r| r.instance_id).collect::<Vec<_>>(),
+        ids,
+        "results should be in the order the instances were given"
+    );
+    for result in &results {
+        match &result.outcome {
+            InstanceBulkActionOutcome::Success { instance } => {
+                assert_ne!(result.instance_id, missing_id);
+                assert_eq!(instance.identity.id, result.instance_id);
+                assert_eq!(instance.runtime.run_state, InstanceState::Starting);
+            }
+            InstanceBulkActionOutcome::Error { http_status_code, .. } => {
+                assert_eq!(result.instance_id, missing_id);
+                assert_eq!(*http_status_code, StatusCode::NOT_FOUND.as_u16());
+            }
+        }
+    }
+    for id in &instance_ids {
+        let id = InstanceUuid::from_untyped_uuid(*id);
+        instance_simulate(nexus, &id).await;
+        instance_wait_for_state(client, id, InstanceState::Running).await;
+    }
+
+    // Stop them all again.
+    let results = instance_bulk_action(
+        client,
+        params::InstanceBulkActionKind::Stop,
+        instance_ids.clone(),
+    )
+    .await;
+    assert!(
+        results.iter().all(|r| matches!(
+            r.outcome,
+            InstanceBulkActionOutcome::Success { .. }
+        )),
+        "unexpected results: {results:#?}"
+    );
+    for id in &instance_ids {
+        let id = InstanceUuid::from_untyped_uuid(*id);
+        instance_simulate(nexus, &id).await;
+        instance_wait_for_state(client, id, InstanceState::Stopped).await;
+    }
+
+    // An instance may only be given once.
+    let error: HttpErrorResponseBody = NexusRequest::new(
+        RequestBuilder::new(client, Method::POST, "/v1/instance-bulk-action")
+            .body(Some(&params::InstanceBulkAction {
+                action: params::InstanceBulkActionKind::Reboot,
+                instance_ids: vec![instance_ids[0], instance_ids[0]],
+            }))
+            .expect_status(Some(StatusCode::BAD_REQUEST)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap()
+    .await;
+    assert_eq!(
+        error.message,
+        format!("instance {} was given more than once", instance_ids[0])
+    );
+}
+
+// Test that an instance whose stopped-reservation policy is `retain` keeps its
+// sled reservation while it's stopped, and gives it up once the policy is
+// changed back to `release`.
+#[nexus_test]
+async fn test_instance_stopped_reservation_policy(
+    cptestctx: &ControlPlaneTestContext,
+) {
+    let client = &cptestctx.external_client;
+    let nexus = &cptestctx.server.server_context().nexus;
+    let datastore = nexus.datastore();
+    let opctx =
+        OpContext::for_tests(cptestctx.logctx.log.new(o!()), datastore.clone());
+    let instance_name = "saved-seat";
+
+    create_project_and_pool(&client).await;
+
+    let reserved_threads = || async {
+        datastore
+            .sled_instance_usage_by_sled(&opctx)
+            .await
+            .unwrap()
+            .values()
+            .map(|usage| usage.hardware_threads)
+            .sum::<u64>()
+    };
+
+    let instance: Instance = object_create(
+        client,
+        &get_instances_url(),
+        &params::InstanceCreate {
+            identity: IdentityMetadataCreateParams {
+                name: instance_name.parse().unwrap(),
+                description: format!("instance {}", instance_name),
+            },
+            ncpus: InstanceCpuCount(4),
+            memory: ByteCount::from_gibibytes_u32(1),
+            hostname: "the-host".parse().unwrap(),
+            user_data: vec![],
+            ssh_public_keys: None,
+            network_interfaces:
+                params::InstanceNetworkInterfaceAttachment::Default,
+            external_ips: vec![],
+            disks: vec![],
+            boot_order: Vec::new(),
+            start: true,
+            auto_restart_policy: Default::default(),
+            anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy:
+                InstanceStoppedReservationPolicy::Retain,
+        },
+    )
+    .await;
+    assert_eq!(
+        instance.stopped_reservation_policy,
+        InstanceStoppedReservationPolicy::Retain
+    );
+    let instance_id = InstanceUuid::from_untyped_uuid(instance.identity.id);
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(&client, instance_id, InstanceState::Running).await;
+    assert_eq!(reserved_threads().await, 4);
+
+    // Stopping the instance keeps its reservation...
+    instance_post(&client, instance_name, InstanceOp::Stop).await;
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(&client, instance_id, InstanceState::Stopped).await;
+    assert_eq!(reserved_threads().await, 4);
+
+    // ...which is taken over, rather than added to, when it starts again.
+    instance_post(&client, instance_name, InstanceOp::Start).await;
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(&client, instance_id, InstanceState::Running).await;
+    assert_eq!(reserved_threads().await, 4);
+
+    instance_post(&client, instance_name, InstanceOp::Stop).await;
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(&client, instance_id, InstanceState::Stopped).await;
+    assert_eq!(reserved_threads().await, 4);
+
+    // Switching the policy back to `release` gives up the reservation.
+    let instance = expect_instance_reconfigure_ok(
+        client,
+        &instance_id.into_untyped_uuid(),
+        params::InstanceUpdate {
+            ncpus: InstanceCpuCount(4),
+            memory: ByteCount::from_gibibytes_u32(1),
+            boot_order: Vec::new(),
+            auto_restart_policy: None,
+            tags: None,
+            stopped_reservation_policy: Some(
+                InstanceStoppedReservationPolicy::Release,
+            ),
+        },
+    )
+    .await;
+    assert_eq!(
+        instance.stopped_reservation_policy,
+        InstanceStoppedReservationPolicy::Release
+    );
+    assert_eq!(reserved_threads().await, 0);
+}
+
 #[nexus_test]
 async fn test_instances_delete_fails_when_running_succeeds_when_stopped(
     cptestctx: &ControlPlaneTestContext,
@@ -2198,10 +2549,12 @@ async fn test_instance_using_image_from_other_project_fails(
                         size: ByteCount::from_gibibytes_u32(4),
                     },
                 )],
-                boot_disk: None,
+                boot_order: Vec::new(),
                 start: true,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             }))
             .expect_status(Some(StatusCode::BAD_REQUEST)),
     )
@@ -2265,10 +2618,12 @@ async fn test_instance_create_saga_removes_instance_database_record(
         network_interfaces: interface_params.clone(),
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let response = NexusRequest::objects_post(
         client,
@@ -2296,10 +2651,12 @@ async fn test_instance_create_saga_removes_instance_database_record(
         network_interfaces: interface_params,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let _ = NexusRequest::objects_post(
         client,
@@ -2390,11 +2747,13 @@ async fn test_instance_with_single_explicit_ip_address(
         network_interfaces: interface_params,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
 
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let response = NexusRequest::objects_post(
         client,
@@ -2511,10 +2870,12 @@ async fn test_instance_with_new_custom_network_interfaces(
         network_interfaces: interface_params,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let response = NexusRequest::objects_post(
         client,
@@ -2629,10 +2990,12 @@ async fn test_instance_create_delete_network_interface(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::None,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let response = NexusRequest::objects_post(
         client,
@@ -2883,10 +3246,12 @@ async fn test_instance_update_network_interfaces(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::None,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let response = NexusRequest::objects_post(
         client,
@@ -3518,10 +3883,12 @@ async fn test_instance_with_multiple_nics_unwinds_completely(
         network_interfaces: interface_params,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let builder =
         RequestBuilder::new(client, http::Method::POST, &get_instances_url())
@@ -3588,13 +3955,15 @@ async fn test_attach_one_disk_to_instance(cptestctx: &ControlPlaneTestContext) {
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
+        boot_order: vec![disk_name.clone().into()],
+        disks: vec![params::InstanceDiskAttachment::Attach(
             params::InstanceDiskAttach { name: disk_name.clone() },
-        )),
-        disks: Vec::new(),
+        )],
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -3649,8 +4018,11 @@ async fn test_instance_create_attach_disks(
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Create(
-            params::DiskCreate {
+        boot_order: vec![
+            Name::try_from(String::from("created-disk")).unwrap().into(),
+        ],
+        disks: vec![
+            params::InstanceDiskAttachment::Create(params::DiskCreate {
                 identity: IdentityMetadataCreateParams {
                     name: Name::try_from(String::from("created-disk")).unwrap(),
                     description: String::from(
@@ -3661,9 +4033,7 @@ async fn test_instance_create_attach_disks(
                 disk_source: params::DiskSource::Blank {
                     block_size: params::BlockSize::try_from(512).unwrap(),
                 },
-            },
-        )),
-        disks: vec![
+            }),
             params::InstanceDiskAttachment::Create(params::DiskCreate {
                 identity: IdentityMetadataCreateParams {
                     name: Name::try_from(String::from("created-disk2"))
@@ -3686,6 +4056,8 @@ async fn test_instance_create_attach_disks(
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -3780,10 +4152,12 @@ async fn test_instance_create_attach_disks_undo(
                 params::InstanceDiskAttach { name: faulted_disk.identity.name },
             ),
         ],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -3850,12 +4224,10 @@ async fn test_attach_eight_disks_to_instance(
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from("probablydata0".to_string()).unwrap(),
-            },
-        )),
-        disks: (1..8)
+        boot_order: vec![
+            Name::try_from("probablydata0".to_string()).unwrap().into(),
+        ],
+        disks: (0..8)
             .map(|i| {
                 params::InstanceDiskAttachment::Attach(
                     params::InstanceDiskAttach {
@@ -3868,6 +4240,8 @@ async fn test_attach_eight_disks_to_instance(
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -3938,12 +4312,10 @@ async fn test_cannot_attach_nine_disks_to_instance(
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from("probablydata0".to_string()).unwrap(),
-            },
-        )),
-        disks: (1..9)
+        boot_order: vec![
+            Name::try_from("probablydata0".to_string()).unwrap().into(),
+        ],
+        disks: (0..9)
             .map(|i| {
                 params::InstanceDiskAttachment::Attach(
                     params::InstanceDiskAttach {
@@ -3956,6 +4328,8 @@ async fn test_cannot_attach_nine_disks_to_instance(
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let url_instances = format!("/v1/instances?project={}", project_name);
@@ -4040,12 +4414,10 @@ async fn test_cannot_attach_faulted_disks(cptestctx: &ControlPlaneTestContext) {
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from("probablydata0".to_string()).unwrap(),
-            },
-        )),
-        disks: (1..8)
+        boot_order: vec![
+            Name::try_from("probablydata0".to_string()).unwrap().into(),
+        ],
+        disks: (0..8)
             .map(|i| {
                 params::InstanceDiskAttachment::Attach(
                     params::InstanceDiskAttach {
@@ -4058,6 +4430,8 @@ async fn test_cannot_attach_faulted_disks(cptestctx: &ControlPlaneTestContext) {
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4131,12 +4505,10 @@ async fn test_disks_detached_when_instance_destroyed(
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from("probablydata0".to_string()).unwrap(),
-            },
-        )),
-        disks: (1..8)
+        boot_order: vec![
+            Name::try_from("probablydata0".to_string()).unwrap().into(),
+        ],
+        disks: (0..8)
             .map(|i| {
                 params::InstanceDiskAttachment::Attach(
                     params::InstanceDiskAttach {
@@ -4149,6 +4521,8 @@ async fn test_disks_detached_when_instance_destroyed(
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4229,12 +4603,10 @@ async fn test_disks_detached_when_instance_destroyed(
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from("probablydata0".to_string()).unwrap(),
-            },
-        )),
-        disks: (1..8)
+        boot_order: vec![
+            Name::try_from("probablydata0".to_string()).unwrap().into(),
+        ],
+        disks: (0..8)
             .map(|i| {
                 params::InstanceDiskAttachment::Attach(
                     params::InstanceDiskAttach {
@@ -4247,6 +4619,8 @@ async fn test_disks_detached_when_instance_destroyed(
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4274,8 +4648,8 @@ async fn test_disks_detached_when_instance_destroyed(
 }
 
 // Surprising but true: mentioning a disk multiple times for attachment is just
-// fine. This means that having a disk in the boot_disk field and disks list
-// will succeed as well.
+// fine. This means that listing a disk in the disks list twice, and naming it in
+// the boot order, will succeed as well.
 //
 // Test here to ensure we're not caught by surprise if this behavior is changed,
 // rather than to assert that this is a specific desired behavior.
@@ -4327,10 +4701,12 @@ async fn test_duplicate_disk_attach_requests_ok(
                 },
             ),
         ],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4362,19 +4738,26 @@ async fn test_duplicate_disk_attach_requests_ok(
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from(String::from("alsodata")).unwrap(),
-            },
-        )),
-        disks: vec![params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from(String::from("alsodata")).unwrap(),
-            },
-        )],
+        boot_order: vec![
+            Name::try_from(String::from("alsodata")).unwrap().into(),
+        ],
+        disks: vec![
+            params::InstanceDiskAttachment::Attach(
+                params::InstanceDiskAttach {
+                    name: Name::try_from(String::from("alsodata")).unwrap(),
+                },
+            ),
+            params::InstanceDiskAttachment::Attach(
+                params::InstanceDiskAttach {
+                    name: Name::try_from(String::from("alsodata")).unwrap(),
+                },
+            ),
+        ],
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4423,15 +4806,19 @@ async fn test_cannot_detach_boot_disk(cptestctx: &ControlPlaneTestContext) {
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
+        boot_order: vec![
+            Name::try_from(String::from("probablydata0")).unwrap().into(),
+        ],
+        disks: vec![params::InstanceDiskAttachment::Attach(
             params::InstanceDiskAttach {
                 name: Name::try_from(String::from("probablydata0")).unwrap(),
             },
-        )),
-        disks: Vec::new(),
+        )],
         start: false,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4489,10 +4876,12 @@ async fn test_cannot_detach_boot_disk(cptestctx: &ControlPlaneTestContext) {
         &client,
         &instance.identity.id,
         params::InstanceUpdate {
-            boot_disk: None,
+            boot_order: Vec::new(),
             auto_restart_policy: None,
             ncpus: InstanceCpuCount::try_from(2).unwrap(),
             memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
         },
     )
     .await;
@@ -4513,46 +4902,197 @@ async fn test_cannot_detach_boot_disk(cptestctx: &ControlPlaneTestContext) {
         .expect("can attempt to detach boot disk");
 }
 
+// Create an instance with two disks in its boot order, check that the fallback
+// disk can't be detached while it's in the boot order, then drop it from the
+// boot order and detach it.
 #[nexus_test]
-async fn test_updating_running_instance_boot_disk_is_conflict(
+async fn test_cannot_detach_fallback_boot_disk(
     cptestctx: &ControlPlaneTestContext,
 ) {
     let client = &cptestctx.external_client;
-    let instance_name = "immediately-running";
+    let instance_name = "nifs";
 
     // Test pre-reqs
     DiskTest::new(&cptestctx).await;
     create_project_and_pool(&client).await;
 
-    create_disk(&client, PROJECT_NAME, "probablydata").await;
-    create_disk(&client, PROJECT_NAME, "alsodata").await;
-
-    // Verify disk is there and currently detached
-    let disks: Vec<Disk> =
-        NexusRequest::iter_collection_authn(client, &get_disks_url(), "", None)
-            .await
-            .expect("failed to list disks")
-            .all_items;
-    assert_eq!(disks.len(), 2);
-    assert_eq!(disks[0].state, DiskState::Detached);
-    assert_eq!(disks[1].state, DiskState::Detached);
-
-    let probablydata = Name::try_from(String::from("probablydata")).unwrap();
-    let alsodata = Name::try_from(String::from("alsodata")).unwrap();
+    let primary = create_disk(&client, PROJECT_NAME, "primary").await;
+    let fallback = create_disk(&client, PROJECT_NAME, "fallback").await;
 
+    // Create the instance, booting from "primary" and falling back to
+    // "fallback".
     let instance_params = params::InstanceCreate {
         identity: IdentityMetadataCreateParams {
-            name: Name::try_from(String::from(instance_name)).unwrap(),
-            description: String::from("instance to run and fail to update"),
+            name: instance_name.parse().unwrap(),
+            description: String::from("probably serving data"),
         },
         ncpus: InstanceCpuCount::try_from(2).unwrap(),
         memory: ByteCount::from_gibibytes_u32(4),
-        hostname: "inst".parse().unwrap(),
+        hostname: "nfs".parse().unwrap(),
+        user_data: vec![],
+        ssh_public_keys: None,
+        network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
+        external_ips: vec![],
+        boot_order: vec![
+            primary.identity.name.clone().into(),
+            fallback.identity.name.clone().into(),
+        ],
+        disks: vec![
+            params::InstanceDiskAttachment::Attach(
+                params::InstanceDiskAttach {
+                    name: fallback.identity.name.clone(),
+                },
+            ),
+            params::InstanceDiskAttachment::Attach(
+                params::InstanceDiskAttach {
+                    name: primary.identity.name.clone(),
+                },
+            ),
+        ],
+        start: false,
+        auto_restart_policy: Default::default(),
+        anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
+    };
+
+    let builder =
+        RequestBuilder::new(client, http::Method::POST, &get_instances_url())
+            .body(Some(&instance_params))
+            .expect_status(Some(http::StatusCode::CREATED));
+    let response = NexusRequest::new(builder)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .expect("Expected instance creation to work!");
+
+    // The first disk in the boot order is reported as the boot disk,
+    // regardless of the order in which the disks were attached.
+    let instance = response.parsed_body::<Instance>().unwrap();
+    assert_eq!(instance.boot_disk_id, Some(primary.identity.id));
+
+    // Attempt to detach the fallback disk. This should fail.
+    let url_instance_detach_disk =
+        format!("/v1/instances/{}/disks/detach", instance.identity.id);
+
+    let builder = RequestBuilder::new(
+        client,
+        http::Method::POST,
+        &url_instance_detach_disk,
+    )
+    .body(Some(&params::DiskPath { disk: fallback.identity.id.into() }))
+    .expect_status(Some(http::StatusCode::CONFLICT));
+    let response = NexusRequest::new(builder)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .expect("can attempt to detach fallback boot disk");
+
+    let err = response
+        .parsed_body::<HttpErrorResponseBody>()
+        .expect("Failed to parse error response body");
+    assert_eq!(err.message, "boot disk cannot be detached");
+
+    // Naming the same disk twice in the boot order is rejected.
+    let err = expect_instance_reconfigure_err(
+        &client,
+        &instance.identity.id,
+        params::InstanceUpdate {
+            boot_order: vec![
+                primary.identity.id.into(),
+                primary.identity.id.into(),
+            ],
+            auto_restart_policy: None,
+            ncpus: InstanceCpuCount::try_from(2).unwrap(),
+            memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
+        },
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    assert_eq!(
+        err.message,
+        format!(
+            "disk {} appears more than once in the boot order",
+            primary.identity.id
+        )
+    );
+
+    // Drop the fallback disk from the boot order.
+    let instance = expect_instance_reconfigure_ok(
+        &client,
+        &instance.identity.id,
+        params::InstanceUpdate {
+            boot_order: vec![primary.identity.id.into()],
+            auto_restart_policy: None,
+            ncpus: InstanceCpuCount::try_from(2).unwrap(),
+            memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
+        },
+    )
+    .await;
+    assert_eq!(instance.boot_disk_id, Some(primary.identity.id));
+
+    // Now the fallback disk can be detached.
+    let builder = RequestBuilder::new(
+        client,
+        http::Method::POST,
+        &url_instance_detach_disk,
+    )
+    .body(Some(&params::DiskPath { disk: fallback.identity.id.into() }))
+    .expect_status(Some(http::StatusCode::ACCEPTED));
+    NexusRequest::new(builder)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .expect("can attempt to detach former fallback boot disk");
+}
+
+#[nexus_test]
+async fn test_updating_running_instance_boot_disk_is_conflict(
+    cptestctx: &ControlPlaneTestContext,
+) {
+    let client = &cptestctx.external_client;
+    let instance_name = "immediately-running";
+
+    // Test pre-reqs
+    DiskTest::new(&cptestctx).await;
+    create_project_and_pool(&client).await;
+
+    create_disk(&client, PROJECT_NAME, "probablydata").await;
+    create_disk(&client, PROJECT_NAME, "alsodata").await;
+
+    // Verify disk is there and currently detached
+    let disks: Vec<Disk> =
+        NexusRequest::iter_collection_authn(client, &get_disks_url(), "", None)
+            .await
+            .expect("failed to list disks")
+            .all_items;
+    assert_eq!(disks.len(), 2);
+    assert_eq!(disks[0].state, DiskState::Detached);
+    assert_eq!(disks[1].state, DiskState::Detached);
+
+    let probablydata = Name::try_from(String::from("probablydata")).unwrap();
+    let alsodata = Name::try_from(String::from("alsodata")).unwrap();
+
+    let instance_params = params::InstanceCreate {
+        identity: IdentityMetadataCreateParams {
+            name: Name::try_from(String::from(instance_name)).unwrap(),
+            description: String::from("instance to run and fail to update"),
+        },
+        ncpus: InstanceCpuCount::try_from(2).unwrap(),
+        memory: ByteCount::from_gibibytes_u32(4),
+        hostname: "inst".parse().unwrap(),
         user_data: vec![],
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![
+            params::InstanceDiskAttachment::Attach(
+                params::InstanceDiskAttach { name: probablydata.clone() },
+            ),
             params::InstanceDiskAttachment::Attach(
                 params::InstanceDiskAttach { name: probablydata.clone() },
             ),
@@ -4560,12 +5100,12 @@ async fn test_updating_running_instance_boot_disk_is_conflict(
                 params::InstanceDiskAttach { name: alsodata.clone() },
             ),
         ],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach { name: probablydata.clone() },
-        )),
+        boot_order: vec![probablydata.clone().into()],
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4592,10 +5132,12 @@ async fn test_updating_running_instance_boot_disk_is_conflict(
         &client,
         &instance_id.into_untyped_uuid(),
         params::InstanceUpdate {
-            boot_disk: Some(alsodata.clone().into()),
+            boot_order: vec![alsodata.clone().into()],
             auto_restart_policy: None,
             ncpus: InstanceCpuCount::try_from(2).unwrap(),
             memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
         },
         http::StatusCode::CONFLICT,
     )
@@ -4610,10 +5152,12 @@ async fn test_updating_running_instance_boot_disk_is_conflict(
         params::InstanceUpdate {
             // Leave the boot disk the same as the one with which the instance
             // was created.
-            boot_disk: Some(probablydata.clone().into()),
+            boot_order: vec![probablydata.clone().into()],
             auto_restart_policy: Some(InstanceAutoRestartPolicy::BestEffort),
             ncpus: InstanceCpuCount::try_from(2).unwrap(),
             memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
         },
     )
     .await;
@@ -4632,10 +5176,12 @@ async fn test_updating_missing_instance_is_not_found(
         &client,
         &UUID_THAT_DOESNT_EXIST,
         params::InstanceUpdate {
-            boot_disk: None,
+            boot_order: Vec::new(),
             auto_restart_policy: None,
             ncpus: InstanceCpuCount::try_from(0).unwrap(),
             memory: ByteCount::from_gibibytes_u32(0),
+            tags: None,
+            stopped_reservation_policy: None,
         },
         http::StatusCode::NOT_FOUND,
     )
@@ -4722,12 +5268,14 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         disks: Vec::new(),
         start: true,
         // Start out with None
         auto_restart_policy: None,
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4741,8 +5289,8 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         .expect("Expected instance creation to work!");
 
     let instance = response.parsed_body::<Instance>().unwrap();
-    let boot_disk_nameorid: Option<NameOrId> =
-        instance.boot_disk_id.map(|x| x.into());
+    let boot_order: Vec<NameOrId> =
+        instance.boot_disk_id.into_iter().map(|x| x.into()).collect();
     let auto_restart_policy = instance.auto_restart_status.policy;
 
     let new_ncpus = InstanceCpuCount::try_from(4).unwrap();
@@ -4754,9 +5302,11 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: new_ncpus,
             memory: new_memory,
+            tags: None,
+            stopped_reservation_policy: None,
         },
         StatusCode::CONFLICT,
     )
@@ -4776,9 +5326,11 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: new_ncpus,
             memory: new_memory,
+            tags: None,
+            stopped_reservation_policy: None,
         },
     )
     .await;
@@ -4791,9 +5343,11 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: initial_ncpus,
             memory: new_memory,
+            tags: None,
+            stopped_reservation_policy: None,
         },
     )
     .await;
@@ -4805,9 +5359,11 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: initial_ncpus,
             memory: initial_memory,
+            tags: None,
+            stopped_reservation_policy: None,
         },
     )
     .await;
@@ -4823,9 +5379,11 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: InstanceCpuCount(MAX_VCPU_PER_INSTANCE + 1),
             memory: instance.memory,
+            tags: None,
+            stopped_reservation_policy: None,
         },
         StatusCode::BAD_REQUEST,
     )
@@ -4844,9 +5402,11 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: instance.ncpus,
             memory: ByteCount::from_mebibytes_u32(0),
+            tags: None,
+            stopped_reservation_policy: None,
         },
         StatusCode::BAD_REQUEST,
     )
@@ -4859,10 +5419,12 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: instance.ncpus,
             memory: ByteCount::try_from(MAX_MEMORY_BYTES_PER_INSTANCE - 1)
                 .unwrap(),
+            tags: None,
+            stopped_reservation_policy: None,
         },
         StatusCode::BAD_REQUEST,
     )
@@ -4876,11 +5438,13 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: instance.ncpus,
             memory: ByteCount::from_mebibytes_u32(
                 (max_mib + 1024).try_into().unwrap(),
             ),
+            tags: None,
+            stopped_reservation_policy: None,
         },
         StatusCode::BAD_REQUEST,
     )
@@ -4898,15 +5462,83 @@ async fn test_size_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &instance.identity.id,
         params::InstanceUpdate {
             auto_restart_policy,
-            boot_disk: boot_disk_nameorid.clone(),
+            boot_order: boot_order.clone(),
             ncpus: new_ncpus,
             memory: new_memory,
+            tags: None,
+            stopped_reservation_policy: None,
         },
         StatusCode::NOT_FOUND,
     )
     .await;
 }
 
+// Test that a resized instance is provisioned with its new size when it is
+// next started.
+#[nexus_test]
+async fn test_resized_instance_starts_with_new_size(
+    cptestctx: &ControlPlaneTestContext,
+) {
+    let client = &cptestctx.external_client;
+    let nexus = &cptestctx.server.server_context().nexus;
+    let datastore = nexus.datastore();
+    let opctx =
+        OpContext::for_tests(cptestctx.logctx.log.new(o!()), datastore.clone());
+    let instance_name = "growing-pains";
+
+    let project = create_project_and_pool(&client).await;
+    let project_id = project.identity.id;
+
+    // `create_instance` starts the instance with 4 vCPUs and 1 GiB of memory.
+    let instance = create_instance(client, PROJECT_NAME, instance_name).await;
+    let instance_id = InstanceUuid::from_untyped_uuid(instance.identity.id);
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(client, instance_id, InstanceState::Running).await;
+
+    instance_post(&client, instance_name, InstanceOp::Stop).await;
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(client, instance_id, InstanceState::Stopped).await;
+
+    let new_ncpus = InstanceCpuCount::try_from(8).unwrap();
+    let new_memory = ByteCount::from_gibibytes_u32(2);
+    expect_instance_reconfigure_ok(
+        client,
+        &instance.identity.id,
+        params::InstanceUpdate {
+            auto_restart_policy: instance.auto_restart_status.policy,
+            boot_order: Vec::new(),
+            ncpus: new_ncpus,
+            memory: new_memory,
+            tags: None,
+            stopped_reservation_policy: None,
+        },
+    )
+    .await;
+
+    // Nothing is provisioned while the instance is stopped...
+    let virtual_provisioning_collection = datastore
+        .virtual_provisioning_collection_get(&opctx, project_id)
+        .await
+        .unwrap();
+    assert_eq!(virtual_provisioning_collection.cpus_provisioned, 0);
+    assert_eq!(virtual_provisioning_collection.ram_provisioned.to_bytes(), 0);
+
+    // ...but starting it again should provision the new size.
+    instance_post(&client, instance_name, InstanceOp::Start).await;
+    instance_simulate(nexus, &instance_id).await;
+    instance_wait_for_state(client, instance_id, InstanceState::Running).await;
+
+    let virtual_provisioning_collection = datastore
+        .virtual_provisioning_collection_get(&opctx, project_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        virtual_provisioning_collection.cpus_provisioned,
+        i64::from(new_ncpus.0)
+    );
+    assert_eq!(virtual_provisioning_collection.ram_provisioned.0, new_memory);
+}
+
 // Test reconfiguring an instance's auto-restart policy.
 #[nexus_test]
 async fn test_auto_restart_policy_can_be_changed(
@@ -4929,12 +5561,14 @@ async fn test_auto_restart_policy_can_be_changed(
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         disks: Vec::new(),
         start: true,
         // Start out with None
         auto_restart_policy: None,
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -4958,9 +5592,11 @@ async fn test_auto_restart_policy_can_be_changed(
             &instance.identity.id,
             dbg!(params::InstanceUpdate {
                 auto_restart_policy,
-                boot_disk: None,
+                boot_order: Vec::new(),
                 ncpus: InstanceCpuCount::try_from(2).unwrap(),
                 memory: ByteCount::from_gibibytes_u32(4),
+                tags: None,
+                stopped_reservation_policy: None,
             }),
         )
         .await;
@@ -4980,6 +5616,88 @@ async fn test_auto_restart_policy_can_be_changed(
     assert_reconfigured(None).await;
 }
 
+// Test that instances without an auto-restart policy of their own inherit
+// their project's default.
+#[nexus_test]
+async fn test_auto_restart_policy_inherits_project_default(
+    cptestctx: &ControlPlaneTestContext,
+) {
+    let client = &cptestctx.external_client;
+
+    create_project_and_pool(&client).await;
+    let defaults_url =
+        format!("/v1/projects/{PROJECT_NAME}/instance-auto-restart");
+
+    // Projects start out with no defaults of their own.
+    let defaults: views::ProjectInstanceAutoRestart =
+        object_get(client, &defaults_url).await;
+    assert_eq!(defaults.policy, None);
+    assert_eq!(defaults.cooldown_secs, None);
+
+    let mk_instance = |name: &'static str, auto_restart_policy| async move {
+        create_instance_with(
+            client,
+            PROJECT_NAME,
+            name,
+            &params::InstanceNetworkInterfaceAttachment::Default,
+            Vec::<params::InstanceDiskAttachment>::new(),
+            Vec::<params::ExternalIpCreate>::new(),
+            false,
+            auto_restart_policy,
+        )
+        .await
+    };
+    let inherits = mk_instance("inherits", None).await;
+    mk_instance("overrides", Some(InstanceAutoRestartPolicy::BestEffort)).await;
+
+    // With no project default, the fleet-wide default allows restarts.
+    assert_eq!(inherits.auto_restart_status.policy, None);
+    assert!(inherits.auto_restart_status.enabled);
+
+    let defaults: views::ProjectInstanceAutoRestart = object_put(
+        client,
+        &defaults_url,
+        &params::ProjectInstanceAutoRestartUpdate {
+            policy: Some(InstanceAutoRestartPolicy::Never),
+            cooldown_secs: Some(60),
+        },
+    )
+    .await;
+    assert_eq!(defaults.project_id, inherits.project_id);
+    assert_eq!(defaults.policy, Some(InstanceAutoRestartPolicy::Never));
+    assert_eq!(defaults.cooldown_secs, Some(60));
+
+    // The instance without a policy now inherits the project's, while the one
+    // with its own policy keeps it.
+    let inherits: Instance =
+        object_get(client, &get_instance_url("inherits")).await;
+    assert_eq!(inherits.auto_restart_status.policy, None);
+    assert!(!inherits.auto_restart_status.enabled);
+    let overrides: Instance =
+        object_get(client, &get_instance_url("overrides")).await;
+    assert_eq!(
+        overrides.auto_restart_status.policy,
+        Some(InstanceAutoRestartPolicy::BestEffort)
+    );
+    assert!(overrides.auto_restart_status.enabled);
+
+    // Clearing the project's defaults restores the fleet-wide defaults.
+    let defaults: views::ProjectInstanceAutoRestart = object_put(
+        client,
+        &defaults_url,
+        &params::ProjectInstanceAutoRestartUpdate {
+            policy: None,
+            cooldown_secs: None,
+        },
+    )
+    .await;
+    assert_eq!(defaults.policy, None);
+    assert_eq!(defaults.cooldown_secs, None);
+    let inherits: Instance =
+        object_get(client, &get_instance_url("inherits")).await;
+    assert!(inherits.auto_restart_status.enabled);
+}
+
 // Create an instance with boot disk set to one of its attached disks, then set
 // it to the other disk.
 #[nexus_test]
@@ -5018,19 +5736,28 @@ async fn test_boot_disk_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         ssh_public_keys: None,
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
-        boot_disk: Some(params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from(String::from("probablydata0")).unwrap(),
-            },
-        )),
-        disks: vec![params::InstanceDiskAttachment::Attach(
-            params::InstanceDiskAttach {
-                name: Name::try_from(String::from("probablydata1")).unwrap(),
-            },
-        )],
+        boot_order: vec![
+            Name::try_from(String::from("probablydata0")).unwrap().into(),
+        ],
+        disks: vec![
+            params::InstanceDiskAttachment::Attach(
+                params::InstanceDiskAttach {
+                    name: Name::try_from(String::from("probablydata0"))
+                        .unwrap(),
+                },
+            ),
+            params::InstanceDiskAttachment::Attach(
+                params::InstanceDiskAttach {
+                    name: Name::try_from(String::from("probablydata1"))
+                        .unwrap(),
+                },
+            ),
+        ],
         start: false,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -5053,10 +5780,12 @@ async fn test_boot_disk_can_be_changed(cptestctx: &ControlPlaneTestContext) {
         &client,
         &instance.identity.id,
         params::InstanceUpdate {
-            boot_disk: Some(disks[1].identity.id.into()),
+            boot_order: vec![disks[1].identity.id.into()],
             auto_restart_policy: None,
             ncpus: InstanceCpuCount::try_from(2).unwrap(),
             memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
         },
     )
     .await;
@@ -5097,10 +5826,12 @@ async fn test_boot_disk_must_be_attached(cptestctx: &ControlPlaneTestContext) {
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: false,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -5120,10 +5851,12 @@ async fn test_boot_disk_must_be_attached(cptestctx: &ControlPlaneTestContext) {
         &client,
         &instance.identity.id,
         params::InstanceUpdate {
-            boot_disk: Some(disks[0].identity.id.into()),
+            boot_order: vec![disks[0].identity.id.into()],
             auto_restart_policy: None,
             ncpus: InstanceCpuCount::try_from(2).unwrap(),
             memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
         },
         http::StatusCode::CONFLICT,
     )
@@ -5153,10 +5886,12 @@ async fn test_boot_disk_must_be_attached(cptestctx: &ControlPlaneTestContext) {
         &client,
         &instance.identity.id,
         params::InstanceUpdate {
-            boot_disk: Some(disks[0].identity.id.into()),
+            boot_order: vec![disks[0].identity.id.into()],
             auto_restart_policy: None,
             ncpus: InstanceCpuCount::try_from(2).unwrap(),
             memory: ByteCount::from_gibibytes_u32(4),
+            tags: None,
+            stopped_reservation_policy: None,
         },
     )
     .await;
@@ -5189,10 +5924,12 @@ async fn test_instances_memory_rejected_less_than_min_memory_size(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let error = NexusRequest::new(
@@ -5242,10 +5979,12 @@ async fn test_instances_memory_not_divisible_by_min_memory_size(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let error = NexusRequest::new(
@@ -5295,10 +6034,12 @@ async fn test_instances_memory_greater_than_max_size(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let error = NexusRequest::new(
@@ -5402,9 +6143,11 @@ async fn test_instance_create_with_anti_affinity_groups(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         auto_restart_policy: Default::default(),
         anti_affinity_groups: anti_affinity_groups_param,
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -5471,9 +6214,11 @@ async fn test_instance_create_with_duplicate_anti_affinity_groups(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         auto_restart_policy: Default::default(),
         anti_affinity_groups: anti_affinity_groups_param,
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -5541,9 +6286,11 @@ async fn test_instance_create_with_anti_affinity_groups_that_do_not_exist(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         auto_restart_policy: Default::default(),
         anti_affinity_groups: anti_affinity_groups_param,
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let error = object_create_error(
@@ -5560,6 +6307,146 @@ async fn test_instance_create_with_anti_affinity_groups_that_do_not_exist(
     );
 }
 
+// Test that an instance's tags are returned in its views, can be used to filter
+// the project's instance list, and can be replaced.
+#[nexus_test]
+async fn test_instance_tags(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    create_project_and_pool(&client).await;
+
+    let tags = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    };
+    let instance_params =
+        |name: &str, tags: BTreeMap<String, String>| params::InstanceCreate {
+            identity: IdentityMetadataCreateParams {
+                name: name.parse().unwrap(),
+                description: String::from("tagged"),
+            },
+            ncpus: InstanceCpuCount::try_from(2).unwrap(),
+            memory: ByteCount::from_gibibytes_u32(4),
+            hostname: name.parse().unwrap(),
+            user_data: vec![],
+            ssh_public_keys: None,
+            network_interfaces:
+                params::InstanceNetworkInterfaceAttachment::Default,
+            external_ips: vec![],
+            boot_order: Vec::new(),
+            disks: Vec::new(),
+            start: false,
+            auto_restart_policy: Default::default(),
+            anti_affinity_groups: Vec::new(),
+            tags,
+            stopped_reservation_policy: Default::default(),
+        };
+    let list_names = |filter: &'static str| async move {
+        let url = format!("{}&tag={}", get_instances_url(), filter);
+        objects_list_page_authz::<Instance>(client, &url)
+            .await
+            .items
+            .into_iter()
+            .map(|instance| instance.identity.name.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let web_tags = tags(&[("role", "web"), ("env", "prod")]);
+    let web: Instance = object_create(
+        client,
+        &get_instances_url(),
+        &instance_params("web", web_tags.clone()),
+    )
+    .await;
+    assert_eq!(web.tags, web_tags);
+    let _: Instance = object_create(
+        client,
+        &get_instances_url(),
+        &instance_params("db", tags(&[("role", "db"), ("env", "prod")])),
+    )
+    .await;
+    let untagged: Instance = object_create(
+        client,
+        &get_instances_url(),
+        &instance_params("untagged", BTreeMap::new()),
+    )
+    .await;
+    assert!(untagged.tags.is_empty());
+
+    // Fetching or listing instances returns their tags.
+    assert_eq!(
+        instance_get(client, &get_instance_url("web")).await.tags,
+        web_tags
+    );
+    let all = objects_list_page_authz::<Instance>(client, &get_instances_url())
+        .await
+        .items;
+    assert_eq!(all.len(), 3);
+    let listed_web = all
+        .iter()
+        .find(|i| i.identity.name.as_str() == "web")
+        .expect("web is listed");
+    assert_eq!(listed_web.tags, web_tags);
+
+    // Filtering by tag only returns instances with a matching key and value.
+    assert_eq!(list_names("role:web").await, ["web"]);
+    assert_eq!(list_names("env:prod").await, ["db", "web"]);
+    assert!(list_names("env:dev").await.is_empty());
+
+    // A filter without a key is rejected.
+    let error: HttpErrorResponseBody = NexusRequest::expect_failure(
+        client,
+        StatusCode::BAD_REQUEST,
+        Method::GET,
+        &format!("{}&tag=role", get_instances_url()),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap()
+    .parsed_body()
+    .unwrap();
+    assert!(error.message.contains("key:value"), "{}", error.message);
+
+    // Tag keys may not contain the filter separator.
+    let error = object_create_error(
+        client,
+        &get_instances_url(),
+        &instance_params("bad-tags", tags(&[("a:b", "c")])),
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    assert!(
+        error.message.contains("must not contain ':'"),
+        "{}",
+        error.message
+    );
+
+    // Updating an instance's tags replaces all of them.
+    let update = |tags| params::InstanceUpdate {
+        ncpus: web.ncpus,
+        memory: web.memory,
+        boot_order: Vec::new(),
+        auto_restart_policy: None,
+        tags,
+        stopped_reservation_policy: Default::default(),
+    };
+    let cache_tags = tags(&[("role", "cache")]);
+    let updated = expect_instance_reconfigure_ok(
+        client,
+        &web.identity.id,
+        update(Some(cache_tags.clone())),
+    )
+    .await;
+    assert_eq!(updated.tags, cache_tags);
+    assert!(list_names("role:web").await.is_empty());
+    assert_eq!(list_names("role:cache").await, ["web"]);
+
+    // Updating an instance without specifying tags leaves them unchanged.
+    let updated =
+        expect_instance_reconfigure_ok(client, &web.identity.id, update(None))
+            .await;
+    assert_eq!(updated.tags, cache_tags);
+}
+
 #[nexus_test]
 async fn test_instance_create_with_ssh_keys(
     cptestctx: &ControlPlaneTestContext,
@@ -5624,9 +6511,11 @@ async fn test_instance_create_with_ssh_keys(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -5673,9 +6562,11 @@ async fn test_instance_create_with_ssh_keys(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -5721,9 +6612,11 @@ async fn test_instance_create_with_ssh_keys(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let builder =
@@ -5844,10 +6737,12 @@ async fn test_cannot_provision_instance_beyond_cpu_capacity(
                 params::InstanceNetworkInterfaceAttachment::Default,
             external_ips: vec![],
             disks: vec![],
-            boot_disk: None,
+            boot_order: Vec::new(),
             start: false,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         };
 
         let url_instances = get_instances_url();
@@ -5903,10 +6798,12 @@ async fn test_cannot_provision_instance_beyond_cpu_limit(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: false,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let url_instances = get_instances_url();
 
@@ -5959,10 +6856,12 @@ async fn test_cannot_provision_instance_beyond_ram_capacity(
                 params::InstanceNetworkInterfaceAttachment::Default,
             external_ips: vec![],
             disks: vec![],
-            boot_disk: None,
+            boot_order: Vec::new(),
             start: false,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         };
 
         let url_instances = get_instances_url();
@@ -6259,10 +7158,12 @@ async fn test_instance_ephemeral_ip_from_correct_pool(
         }],
         ssh_public_keys: None,
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let error = object_create_error(
         client,
@@ -6329,10 +7230,12 @@ async fn test_instance_ephemeral_ip_from_orphan_pool(
         }],
         ssh_public_keys: None,
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     // instance create 404s
@@ -6393,10 +7296,12 @@ async fn test_instance_ephemeral_ip_no_default_pool_error(
         }],
         ssh_public_keys: None,
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     let url = format!("/v1/instances?project={}", PROJECT_NAME);
@@ -6533,10 +7438,12 @@ async fn test_instance_allow_only_one_ephemeral_ip(
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![ephemeral_create.clone(), ephemeral_create],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let error = object_create_error(
         client,
@@ -6585,9 +7492,9 @@ pub async fn fetch_instance_external_ips(
         .execute()
         .await
         .expect("Failed to fetch external IPs")
-        .parsed_body::<ResultsPage<views::ExternalIp>>()
+        .parsed_body::<ResultsPage<views::InstanceExternalIp>>()
         .expect("Failed to parse external IPs");
-    ips.items
+    ips.items.into_iter().map(|ip| ip.external_ip).collect()
 }
 
 async fn fetch_instance_ephemeral_ip(
@@ -6668,10 +7575,12 @@ async fn test_instance_create_in_silo(cptestctx: &ControlPlaneTestContext) {
             pool: Some("default".parse::<Name>().unwrap().into()),
         }],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
     let url_instances = format!("/v1/instances?project={}", PROJECT_NAME);
     NexusRequest::objects_post(client, &url_instances, &instance_params)
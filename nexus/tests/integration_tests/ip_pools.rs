@@ -47,7 +47,9 @@ use nexus_types::external_api::shared::IpRange;
 use nexus_types::external_api::shared::Ipv4Range;
 use nexus_types::external_api::shared::SiloIdentityMode;
 use nexus_types::external_api::shared::SiloRole;
+use nexus_types::external_api::views::FloatingIp;
 use nexus_types::external_api::views::IpPool;
+use nexus_types::external_api::views::IpPoolProjectLink;
 use nexus_types::external_api::views::IpPoolRange;
 use nexus_types::external_api::views::IpPoolSiloLink;
 use nexus_types::external_api::views::IpVersion;
@@ -584,6 +586,93 @@ async fn test_ip_pool_silo_link(cptestctx: &ControlPlaneTestContext) {
     object_delete(client, "/v1/system/ip-pools/p1").await;
 }
 
+#[nexus_test]
+async fn test_ip_pool_project_link(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+
+    let range = IpRange::V4(
+        Ipv4Range::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 5))
+            .unwrap(),
+    );
+    let (p0, _) = create_ip_pool(client, "p0", Some(range)).await;
+    let project = create_project(client, "my-project").await;
+
+    // there should be no associations
+    let assocs_p0 = projects_for_pool(client, "p0").await;
+    assert_eq!(assocs_p0.items.len(), 0);
+
+    // the pool isn't linked to the project or its silo, so it can't be used
+    let error = object_create_error(
+        client,
+        "/v1/floating-ips?project=my-project",
+        &floating_ip_params("fip0", "p0"),
+        StatusCode::NOT_FOUND,
+    )
+    .await;
+    assert_eq!(error.message, "not found: ip-pool with name \"p0\"");
+
+    // expect 404 on association if the specified project doesn't exist
+    let nonexistent_project_id = Uuid::new_v4();
+    let error = object_create_error(
+        client,
+        "/v1/system/ip-pools/p0/projects",
+        &params::IpPoolLinkProject { project: nonexistent_project_id },
+        StatusCode::NOT_FOUND,
+    )
+    .await;
+    assert_eq!(
+        error.message,
+        format!("not found: project with id \"{nonexistent_project_id}\"")
+    );
+
+    let params = params::IpPoolLinkProject { project: project.identity.id };
+    let link: IpPoolProjectLink =
+        object_create(client, "/v1/system/ip-pools/p0/projects", &params).await;
+    let expected = IpPoolProjectLink {
+        ip_pool_id: p0.identity.id,
+        project_id: project.identity.id,
+    };
+    assert_eq!(link, expected);
+
+    // second attempt to create the same link errors due to conflict
+    let error = object_create_error(
+        client,
+        "/v1/system/ip-pools/p0/projects",
+        &params,
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    assert_eq!(error.error_code.unwrap(), "ObjectAlreadyExists");
+
+    let assocs_p0 = projects_for_pool(client, "p0").await;
+    assert_eq!(assocs_p0.items, vec![expected]);
+
+    // now IPs can be allocated from the pool within the project
+    let _: FloatingIp = object_create(
+        client,
+        "/v1/floating-ips?project=my-project",
+        &floating_ip_params("fip0", "p0"),
+    )
+    .await;
+    assert_ip_pool_utilization(client, "p0", 1, 5.0).await;
+
+    // the pool can't be unlinked while the project has IPs from it
+    let url =
+        format!("/v1/system/ip-pools/p0/projects/{}", project.identity.id);
+    let error =
+        object_delete_error(client, &url, StatusCode::BAD_REQUEST).await;
+    assert_eq!(
+        error.message,
+        "IP addresses from this pool are in use in the linked project"
+    );
+
+    object_delete(client, "/v1/floating-ips/fip0?project=my-project").await;
+    object_delete(client, &url).await;
+
+    let assocs_p0 = projects_for_pool(client, "p0").await;
+    assert_eq!(assocs_p0.items.len(), 0);
+}
+
 /// Non-discoverable silos can be linked to a pool, but they do not show up
 /// in the list of silos for that pool, just as they do not show up in the
 /// top-level list of silos
@@ -804,6 +893,25 @@ async fn silos_for_pool(
     objects_list_page_authz::<IpPoolSiloLink>(client, &url).await
 }
 
+async fn projects_for_pool(
+    client: &ClientTestContext,
+    pool: &str,
+) -> ResultsPage<IpPoolProjectLink> {
+    let url = format!("/v1/system/ip-pools/{}/projects", pool);
+    objects_list_page_authz::<IpPoolProjectLink>(client, &url).await
+}
+
+fn floating_ip_params(name: &str, pool: &str) -> params::FloatingIpCreate {
+    params::FloatingIpCreate {
+        identity: IdentityMetadataCreateParams {
+            name: name.parse().unwrap(),
+            description: String::new(),
+        },
+        ip: None,
+        pool: Some(NameOrId::Name(pool.parse().unwrap())),
+    }
+}
+
 async fn pools_for_silo(
     client: &ClientTestContext,
     silo: &str,
@@ -211,18 +211,22 @@ async fn test_system_timeseries_schema_list(
     wait_for_producer(&cptestctx.oximeter, nexus_id).await;
 
     // We should be able to fetch the list of timeseries, and it should include
-    // Nexus's HTTP latency distribution. This is defined in Nexus itself, and
-    // should always exist after we've registered as a producer and start
+    // Nexus's HTTP latency distributions. These are defined in Nexus itself,
+    // and should always exist after we've registered as a producer and start
     // producing data.
     MetricsQuerier::new(&cptestctx)
         .wait_for_timeseries_schema(|schemas: Vec<TimeseriesSchema>| {
-            if schemas.iter().any(|sc| {
-                sc.timeseries_name == "http_service:request_latency_histogram"
-            }) {
-                Ok(())
-            } else {
-                Err(MetricsNotYet::new("waiting for request_latency_histogram"))
+            for name in [
+                "http_service:request_latency_histogram",
+                "http_service:endpoint_latency_histogram",
+            ] {
+                if !schemas.iter().any(|sc| sc.timeseries_name == name) {
+                    return Err(MetricsNotYet::new(format!(
+                        "waiting for {name}"
+                    )));
+                }
             }
+            Ok(())
         })
         .await;
 }
@@ -38,8 +38,10 @@ mod projects;
 mod quiesce;
 mod quotas;
 mod rack;
+mod rack_activity;
 mod role_assignments;
 mod router_routes;
+mod runtime_config;
 mod saml;
 mod schema;
 mod silo_users;
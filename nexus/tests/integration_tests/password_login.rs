@@ -9,10 +9,14 @@ use nexus_test_utils::resource_helpers::grant_iam;
 use nexus_test_utils::resource_helpers::test_params;
 use nexus_test_utils::resource_helpers::{create_local_user, create_silo};
 use nexus_test_utils_macros::nexus_test;
+use nexus_types::external_api::params;
 use nexus_types::external_api::shared::{self, SiloRole};
 use nexus_types::external_api::views;
-use omicron_common::api::external::{Name, UserId};
+use omicron_common::api::external::{
+    IdentityMetadataCreateParams, Name, UserId,
+};
 use omicron_passwords::MIN_EXPECTED_PASSWORD_VERIFY_TIME;
+use omicron_passwords::NewPasswordHash;
 use std::str::FromStr;
 
 type ControlPlaneTestContext =
@@ -346,6 +350,101 @@ async fn test_local_user_with_no_initial_password(
     assert_eq!(created_user, found_user.user);
 }
 
+#[nexus_test]
+async fn test_break_glass_login(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    let log = &cptestctx.logctx.log;
+    let nexus_internal_url = format!(
+        "http://{}",
+        cptestctx.server.get_http_server_internal_address().await
+    );
+    let nexus_client =
+        nexus_client::Client::new(&nexus_internal_url, log.clone());
+
+    let test_password = "the idp is on fire";
+    let password_hash: NewPasswordHash = {
+        let mut hasher = omicron_passwords::Hasher::default();
+        let password = omicron_passwords::Password::new(test_password).unwrap();
+        hasher.create_password(&password).unwrap().to_string().parse().unwrap()
+    };
+
+    // Enable the account from the internal API, as wicketd would.
+    let account = nexus_client
+        .break_glass_enable(&nexus_client::types::BreakGlassEnableRequest {
+            enabled_by: String::from("test suite"),
+            password_hash,
+            ttl_secs: 3600,
+        })
+        .await
+        .expect("enabled break-glass account")
+        .into_inner();
+    assert_eq!(account.enabled_by, "test suite");
+    assert!(account.time_disabled.is_none());
+    let silo_name = account.silo_name;
+    let username = UserId::from_str(&account.username).unwrap();
+
+    let session_token = expect_login_success(
+        client,
+        &silo_name,
+        username.clone(),
+        test_password.to_string(),
+    )
+    .await;
+    let found_user = expect_session_valid(client, &session_token).await;
+    assert_eq!(found_user.user.id, account.silo_user_id);
+
+    // The account can look at the fleet...
+    NexusRequest::object_get(client, "/v1/system/hardware/racks")
+        .authn_as(AuthnMode::Session(session_token.clone()))
+        .execute()
+        .await
+        .expect("listed racks");
+
+    // ...but it can't change anything or see inside its silo.
+    NexusRequest::expect_failure(
+        client,
+        StatusCode::FORBIDDEN,
+        Method::GET,
+        "/v1/projects",
+    )
+    .authn_as(AuthnMode::Session(session_token.clone()))
+    .execute()
+    .await
+    .expect("listing projects should fail");
+    NexusRequest::expect_failure_with_body(
+        client,
+        StatusCode::FORBIDDEN,
+        Method::POST,
+        "/v1/projects",
+        &params::ProjectCreate {
+            identity: IdentityMetadataCreateParams {
+                name: "break-glass-project".parse().unwrap(),
+                description: String::new(),
+            },
+        },
+    )
+    .authn_as(AuthnMode::Session(session_token.clone()))
+    .execute()
+    .await
+    .expect("creating a project should fail");
+
+    // Disabling the account ends its session and prevents it from logging in.
+    nexus_client
+        .break_glass_disable(&nexus_client::types::BreakGlassDisableRequest {
+            disabled_by: String::from("test suite"),
+        })
+        .await
+        .expect("disabled break-glass account");
+    expect_session_invalid(client, &session_token).await;
+    expect_login_failure(
+        client,
+        &silo_name,
+        username,
+        test_password.to_string(),
+    )
+    .await;
+}
+
 async fn expect_session_valid(
     client: &ClientTestContext,
     session_token: &str,
@@ -90,10 +90,16 @@ async fn test_quiesce(cptestctx: &ControlPlaneTestContext) {
         .blueprint_import(&opctx, blueprint2.clone())
         .await
         .expect("importing new blueprint");
+    // The test environment's blueprints aren't realistic (e.g., all zones share
+    // an underlay IP), so they wouldn't pass validation.
     nexus
         .blueprint_target_set(
             &opctx,
-            BlueprintTargetSet { enabled: false, target_id: blueprint2.id },
+            BlueprintTargetSet {
+                enabled: false,
+                target_id: blueprint2.id,
+                skip_validation: true,
+            },
         )
         .await
         .expect("setting new target");
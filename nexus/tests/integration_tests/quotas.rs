@@ -17,7 +17,7 @@ use nexus_test_utils_macros::nexus_test;
 use nexus_types::external_api::params;
 use nexus_types::external_api::shared;
 use nexus_types::external_api::shared::SiloRole;
-use nexus_types::external_api::views::{Silo, SiloQuotas};
+use nexus_types::external_api::views::{ProjectQuotas, Silo, SiloQuotas};
 use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::IdentityMetadataCreateParams;
 use omicron_common::api::external::InstanceCpuCount;
@@ -63,6 +63,34 @@ impl ResourceAllocator {
         .expect("failed to parse quotas")
     }
 
+    async fn create_project_quotas(
+        &self,
+        client: &ClientTestContext,
+        quotas: params::ProjectQuotasCreate,
+    ) -> Result<TestResponse, Error> {
+        NexusRequest::objects_post(
+            client,
+            "/v1/system/projects/project/quotas",
+            &quotas,
+        )
+        .authn_as(self.auth.clone())
+        .execute()
+        .await
+    }
+
+    async fn get_project_quotas(
+        &self,
+        client: &ClientTestContext,
+    ) -> ProjectQuotas {
+        NexusRequest::object_get(client, "/v1/system/projects/project/quotas")
+            .authn_as(self.auth.clone())
+            .execute()
+            .await
+            .expect("failed to fetch project quotas")
+            .parsed_body()
+            .expect("failed to parse project quotas")
+    }
+
     async fn provision_instance(
         &self,
         client: &ClientTestContext,
@@ -87,10 +115,12 @@ impl ResourceAllocator {
                 network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
                 external_ips: Vec::<params::ExternalIpCreate>::new(),
                 disks: Vec::<params::InstanceDiskAttachment>::new(),
-                boot_disk: None,
+                boot_order: Vec::new(),
                 start: false,
                 auto_restart_policy: Default::default(),
                 anti_affinity_groups: Vec::new(),
+                tags: Default::default(),
+                stopped_reservation_policy: Default::default(),
             },
         )
         .authn_as(self.auth.clone())
@@ -386,3 +416,151 @@ async fn test_quota_limits(cptestctx: &ControlPlaneTestContext) {
         assert_eq!(quotas.limits.storage, quota_limit.storage.unwrap());
     }
 }
+
+#[nexus_test]
+async fn test_project_quotas(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+
+    // Simulate space for disks
+    DiskTest::new(&cptestctx).await;
+
+    // The silo's quotas are high enough that only the project's apply.
+    let system = setup_silo_with_quota(
+        &client,
+        "quota-test-silo",
+        params::SiloQuotasCreate::arbitrarily_high_default(),
+    )
+    .await;
+    let quotas_url = "/v1/system/projects/project/quotas";
+
+    // Projects start out without quotas.
+    NexusRequest::expect_failure(
+        client,
+        http::StatusCode::NOT_FOUND,
+        http::Method::GET,
+        quotas_url,
+    )
+    .authn_as(system.auth.clone())
+    .execute()
+    .await
+    .expect("project should have no quotas");
+
+    system
+        .create_project_quotas(
+            client,
+            params::ProjectQuotasCreate {
+                cpus: 2,
+                memory: ByteCount::from_gibibytes_u32(4),
+                storage: ByteCount::from_gibibytes_u32(2),
+            },
+        )
+        .await
+        .expect("failed to create project quotas");
+    let quotas = system.get_project_quotas(client).await;
+    assert_eq!(quotas.limits.cpus, 2);
+    assert_eq!(quotas.limits.memory, ByteCount::from_gibibytes_u32(4));
+    assert_eq!(quotas.limits.storage, ByteCount::from_gibibytes_u32(2));
+
+    // A project can only have one set of quotas.
+    NexusRequest::expect_failure_with_body(
+        client,
+        http::StatusCode::BAD_REQUEST,
+        http::Method::POST,
+        quotas_url,
+        &params::ProjectQuotasCreate {
+            cpus: 1,
+            memory: ByteCount::from_gibibytes_u32(1),
+            storage: ByteCount::from_gibibytes_u32(1),
+        },
+    )
+    .authn_as(system.auth.clone())
+    .execute()
+    .await
+    .expect("duplicate project quotas should be rejected");
+
+    // Each of the project's limits is enforced.
+    let err = system
+        .provision_instance(client, "instance", 4, 1)
+        .await
+        .unwrap()
+        .parsed_body::<HttpErrorResponseBody>()
+        .expect("failed to parse error body");
+    assert_eq!(err.error_code.as_deref(), Some("InsufficientQuota"));
+    assert!(
+        err.message.contains("vCPU Quota Exceeded"),
+        "Unexpected error: {0}",
+        err.message
+    );
+    system.cleanup_instance(client, "instance").await;
+
+    let err = system
+        .provision_instance(client, "instance", 1, 8)
+        .await
+        .unwrap()
+        .parsed_body::<HttpErrorResponseBody>()
+        .expect("failed to parse error body");
+    assert_eq!(err.error_code.as_deref(), Some("InsufficientQuota"));
+    assert!(
+        err.message.contains("Memory Quota Exceeded"),
+        "Unexpected error: {0}",
+        err.message
+    );
+    system.cleanup_instance(client, "instance").await;
+
+    let err = system
+        .provision_disk(client, "disk", 3)
+        .await
+        .unwrap()
+        .parsed_body::<HttpErrorResponseBody>()
+        .expect("failed to parse error body");
+    assert_eq!(err.error_code.as_deref(), Some("InsufficientQuota"));
+    assert!(
+        err.message.contains("Storage Quota Exceeded"),
+        "Unexpected error: {0}",
+        err.message
+    );
+
+    // Requests within the quotas succeed.
+    system
+        .provision_instance(client, "instance", 2, 4)
+        .await
+        .expect("Instance should've had enough resources to be provisioned");
+    system
+        .provision_disk(client, "disk", 2)
+        .await
+        .expect("Disk should be provisioned");
+
+    // Raising a limit leaves the others unchanged.
+    let quotas: ProjectQuotas = NexusRequest::object_put(
+        client,
+        quotas_url,
+        Some(&params::ProjectQuotasUpdate {
+            cpus: None,
+            memory: None,
+            storage: Some(ByteCount::from_gibibytes_u32(4)),
+        }),
+    )
+    .authn_as(system.auth.clone())
+    .execute()
+    .await
+    .expect("failed to update project quotas")
+    .parsed_body()
+    .expect("failed to parse project quotas");
+    assert_eq!(quotas.limits.cpus, 2);
+    assert_eq!(quotas.limits.storage, ByteCount::from_gibibytes_u32(4));
+    system
+        .provision_disk(client, "disk2", 2)
+        .await
+        .expect("Disk should be provisioned");
+
+    // Once the quotas are removed, only the silo's quotas apply.
+    NexusRequest::object_delete(client, quotas_url)
+        .authn_as(system.auth.clone())
+        .execute()
+        .await
+        .expect("failed to delete project quotas");
+    system
+        .provision_disk(client, "disk3", 3)
+        .await
+        .expect("Disk should be provisioned");
+}
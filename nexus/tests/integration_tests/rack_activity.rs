@@ -0,0 +1,90 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use dropshot::{ResultsPage, test_util::ClientTestContext};
+use nexus_test_utils::resource_helpers::{
+    create_project, objects_list_page_authz,
+};
+use nexus_test_utils_macros::nexus_test;
+use nexus_types::external_api::views::{
+    RackActivity, RackActivityDetails, RackActivityKind,
+};
+
+type ControlPlaneTestContext =
+    nexus_test_utils::ControlPlaneTestContext<omicron_nexus::Server>;
+
+fn to_q(d: DateTime<Utc>) -> String {
+    d.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+async fn fetch_activity(
+    client: &ClientTestContext,
+    start: DateTime<Utc>,
+    kind: Option<&str>,
+) -> ResultsPage<RackActivity> {
+    let mut qs =
+        vec![format!("start_time={}", to_q(start)), "limit=1000".into()];
+    if let Some(kind) = kind {
+        qs.push(format!("kind={kind}"));
+    }
+    let url = format!("/v1/system/activity?{}", qs.join("&"));
+    objects_list_page_authz::<RackActivity>(client, &url).await
+}
+
+#[nexus_test]
+async fn test_rack_activity_list(ctx: &ControlPlaneTestContext) {
+    let client = &ctx.external_client;
+
+    let t0: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+
+    // Setting up the rack added sleds and physical disks, and made a blueprint
+    // the target.
+    let activity = fetch_activity(client, t0, None).await;
+    let kinds: Vec<_> = activity.items.iter().map(|a| a.kind).collect();
+    assert!(kinds.contains(&RackActivityKind::Hardware), "{kinds:?}");
+    assert!(kinds.contains(&RackActivityKind::BlueprintTarget), "{kinds:?}");
+    assert!(
+        activity.items.iter().any(|a| matches!(
+            a.details,
+            RackActivityDetails::SledAdded { .. }
+        ))
+    );
+    assert!(
+        activity.items.is_sorted_by_key(|a| (a.time, a.id)),
+        "activity out of order: {activity:#?}"
+    );
+
+    // Requests made through the API show up, too. (Other activity, like
+    // blueprint planning, may be happening in the background.)
+    let t1 = Utc::now();
+    create_project(client, "test-proj").await;
+
+    let activity = fetch_activity(client, t1, Some("audit_log")).await;
+    assert_eq!(activity.items.len(), 1, "{activity:#?}");
+    let item = &activity.items[0];
+    assert_eq!(item.kind, RackActivityKind::AuditLog);
+    assert_eq!(item.description, "request `project_create` succeeded");
+    let RackActivityDetails::AuditLog { entry } = &item.details else {
+        panic!("unexpected activity details: {:?}", item.details);
+    };
+    assert_eq!(entry.operation_id, "project_create");
+    assert_eq!(item.id, entry.id);
+    assert_eq!(item.time, entry.time_completed);
+
+    // Activity can be narrowed down to one kind.
+    for kind in [
+        RackActivityKind::AuditLog,
+        RackActivityKind::BlueprintTarget,
+        RackActivityKind::Hardware,
+        RackActivityKind::Fault,
+    ] {
+        let name = serde_json::to_value(kind).unwrap();
+        let activity = fetch_activity(client, t0, name.as_str()).await;
+        assert!(
+            activity.items.iter().all(|a| a.kind == kind),
+            "unexpected activity for {kind:?}: {activity:#?}"
+        );
+    }
+}
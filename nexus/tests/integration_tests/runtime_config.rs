@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for changing Nexus configuration at runtime
+
+use http::StatusCode;
+use nexus_test_interface::NexusServer;
+use nexus_test_utils_macros::nexus_test;
+use nexus_types::internal_api::params::LogLevel;
+use nexus_types::internal_api::params::RuntimeConfigUpdate;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+type ControlPlaneTestContext =
+    nexus_test_utils::ControlPlaneTestContext<omicron_nexus::Server>;
+
+const TASK_NAME: &str = "phantom_disks";
+
+#[nexus_test]
+async fn test_runtime_config(cptestctx: &ControlPlaneTestContext) {
+    let log = &cptestctx.logctx.log;
+    let nexus_internal_url = format!(
+        "http://{}",
+        cptestctx.server.get_http_server_internal_address().await
+    );
+    let nexus_client =
+        nexus_client::Client::new(&nexus_internal_url, log.clone());
+
+    let initial = nexus_client
+        .runtime_config_view()
+        .await
+        .expect("fetched runtime config")
+        .into_inner();
+    assert_eq!(initial.log_level, LogLevel::Trace);
+    let initial_period = initial.bgtask_periods[TASK_NAME];
+    let new_period = initial_period + Duration::from_secs(1);
+
+    // Change a background task's period and the log level.
+    let config = nexus_client
+        .runtime_config_update(&RuntimeConfigUpdate {
+            log_level: Some(LogLevel::Debug),
+            bgtask_periods: BTreeMap::from([(
+                TASK_NAME.to_string(),
+                new_period,
+            )]),
+        })
+        .await
+        .expect("updated runtime config")
+        .into_inner();
+    assert_eq!(config.log_level, LogLevel::Debug);
+    assert_eq!(config.bgtask_periods[TASK_NAME], new_period);
+    let task = nexus_client
+        .bgtask_view(TASK_NAME)
+        .await
+        .expect("fetched background task")
+        .into_inner();
+    assert_eq!(Duration::from(task.period), new_period);
+
+    // An update naming a task that doesn't exist is rejected in full.
+    let error = nexus_client
+        .runtime_config_update(&RuntimeConfigUpdate {
+            log_level: Some(LogLevel::Info),
+            bgtask_periods: BTreeMap::from([
+                (TASK_NAME.to_string(), initial_period),
+                ("no_such_task".to_string(), initial_period),
+            ]),
+        })
+        .await
+        .expect_err("updating a nonexistent task should fail");
+    assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+
+    // So is an update with a zero period.
+    let error = nexus_client
+        .runtime_config_update(&RuntimeConfigUpdate {
+            log_level: Some(LogLevel::Info),
+            bgtask_periods: BTreeMap::from([(
+                TASK_NAME.to_string(),
+                Duration::ZERO,
+            )]),
+        })
+        .await
+        .expect_err("setting a zero period should fail");
+    assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+
+    let config = nexus_client
+        .runtime_config_view()
+        .await
+        .expect("fetched runtime config")
+        .into_inner();
+    assert_eq!(config.log_level, LogLevel::Debug);
+    assert_eq!(config.bgtask_periods[TASK_NAME], new_period);
+
+    // Settings that aren't specified are left alone.
+    let config = nexus_client
+        .runtime_config_update(&RuntimeConfigUpdate::default())
+        .await
+        .expect("updated runtime config")
+        .into_inner();
+    assert_eq!(config.log_level, LogLevel::Debug);
+    assert_eq!(config.bgtask_periods, {
+        let mut periods = initial.bgtask_periods.clone();
+        periods.insert(TASK_NAME.to_string(), new_period);
+        periods
+    });
+}
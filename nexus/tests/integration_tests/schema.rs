@@ -1390,11 +1390,13 @@ fn at_current_101_0_0<'a>(ctx: &'a MigrationContext<'a>) -> BoxFuture<'a, ()> {
                         network_interfaces:
                             params::InstanceNetworkInterfaceAttachment::Default,
                         external_ips: vec![],
-                        boot_disk: None,
+                        boot_order: Vec::new(),
                         disks: Vec::new(),
                         start: false,
                         auto_restart_policy: Default::default(),
                         anti_affinity_groups: Vec::new(),
+                        tags: Default::default(),
+                        stopped_reservation_policy: Default::default(),
                     },
                 ))
                 .execute_async(&*pool_and_conn.conn)
@@ -3040,6 +3042,68 @@ fn after_185_0_0<'a>(ctx: &'a MigrationContext<'a>) -> BoxFuture<'a, ()> {
     })
 }
 
+fn after_192_0_0<'a>(ctx: &'a MigrationContext<'a>) -> BoxFuture<'a, ()> {
+    Box::pin(async {
+        // The instances given boot disks in `before_107_0_0` should now have
+        // those disks as the only entries in their boot orders.
+        let rows = ctx
+            .client
+            .query(
+                &format!(
+                    "SELECT
+                       id,
+                       boot_order[1] AS first_boot_disk,
+                       cardinality(boot_order) AS boot_order_len
+                     FROM instance
+                     WHERE id IN (
+                       '{INSTANCE1}', '{INSTANCE2}', '{INSTANCE3}',
+                       '{INSTANCE4}'
+                     )
+                     ORDER BY id;"
+                ),
+                &[],
+            )
+            .await
+            .expect("failed to load instance boot orders");
+        let boot_orders = process_rows(&rows);
+
+        assert_eq!(
+            boot_orders[0].values,
+            vec![
+                ColumnValue::new("id", INSTANCE1),
+                ColumnValue::new("first_boot_disk", DISK1),
+                ColumnValue::new("boot_order_len", 1i64),
+            ],
+            "instance {INSTANCE1}'s boot disk should have been carried over"
+        );
+
+        assert_eq!(
+            boot_orders[1].values,
+            vec![
+                ColumnValue::new("id", INSTANCE2),
+                ColumnValue::new("first_boot_disk", DISK2),
+                ColumnValue::new("boot_order_len", 1i64),
+            ],
+            "instance {INSTANCE2}'s boot disk should have been carried over"
+        );
+
+        for (row, instance) in
+            boot_orders[2..].iter().zip([INSTANCE3, INSTANCE4])
+        {
+            assert_eq!(
+                row.values,
+                vec![
+                    ColumnValue::new("id", instance),
+                    ColumnValue::null("first_boot_disk"),
+                    ColumnValue::new("boot_order_len", 0i64),
+                ],
+                "instance {instance} had no boot disk, so should have an \
+                 empty boot order"
+            );
+        }
+    })
+}
+
 // Lazily initializes all migration checks. The combination of Rust function
 // pointers and async makes defining a static table fairly painful, so we're
 // using lazy initialization instead.
@@ -3142,6 +3206,10 @@ fn get_migration_checks() -> BTreeMap<Version, DataMigrationFns> {
         Version::new(185, 0, 0),
         DataMigrationFns::new().before(before_185_0_0).after(after_185_0_0),
     );
+    map.insert(
+        Version::new(192, 0, 0),
+        DataMigrationFns::new().after(after_192_0_0),
+    );
     map
 }
 
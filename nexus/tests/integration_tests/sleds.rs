@@ -6,18 +6,26 @@
 
 use camino::Utf8Path;
 use dropshot::test_util::ClientTestContext;
+use http::Method;
+use http::StatusCode;
 use nexus_db_model::PhysicalDisk as DbPhysicalDisk;
 use nexus_db_model::PhysicalDiskKind as DbPhysicalDiskKind;
 use nexus_db_queries::context::OpContext;
 use nexus_test_interface::NexusServer;
 use nexus_test_utils::SLED_AGENT_UUID;
+use nexus_test_utils::http_testing::AuthnMode;
+use nexus_test_utils::http_testing::NexusRequest;
+use nexus_test_utils::http_testing::RequestBuilder;
 use nexus_test_utils::resource_helpers::create_default_ip_pool;
 use nexus_test_utils::resource_helpers::create_instance;
 use nexus_test_utils::resource_helpers::create_project;
 use nexus_test_utils::resource_helpers::objects_list_page_authz;
 use nexus_test_utils::start_sled_agent;
 use nexus_test_utils_macros::nexus_test;
+use nexus_types::external_api::views::SledExpungePreview;
 use nexus_types::external_api::views::SledInstance;
+use nexus_types::external_api::views::SledPolicy;
+use nexus_types::external_api::views::SledProvisionPolicy;
 use nexus_types::external_api::views::{PhysicalDisk, Sled};
 use omicron_sled_agent::sim;
 use omicron_test_utils::dev::poll::{CondCheckError, wait_for_condition};
@@ -25,6 +33,7 @@ use omicron_uuid_kinds::GenericUuid;
 use omicron_uuid_kinds::PhysicalDiskUuid;
 use omicron_uuid_kinds::SledUuid;
 use std::str::FromStr;
+use std::time::Duration;
 use uuid::Uuid;
 
 type ControlPlaneTestContext =
@@ -226,3 +235,55 @@ async fn test_sled_instance_list(cptestctx: &ControlPlaneTestContext) {
     assert_eq!(project.identity.name, sled_instances[0].project_name);
     assert_eq!(instance.identity.name, sled_instances[0].name);
 }
+
+#[nexus_test]
+async fn test_sled_expunge_preview(cptestctx: &ControlPlaneTestContext) {
+    let external_client = &cptestctx.external_client;
+
+    // The planner needs an inventory collection to work from.
+    cptestctx
+        .wait_for_at_least_one_inventory_collection(Duration::from_secs(60))
+        .await;
+
+    let sled_url = format!("/v1/system/hardware/sleds/{SLED_AGENT_UUID}");
+    let preview_url = format!("{sled_url}/expunge:preview");
+    let preview = NexusRequest::new(
+        RequestBuilder::new(external_client, Method::POST, &preview_url)
+            .expect_status(Some(StatusCode::OK)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap::<SledExpungePreview>()
+    .await;
+    assert_eq!(preview.sled_id.to_string(), SLED_AGENT_UUID);
+    assert!(
+        preview
+            .zones_expunged
+            .iter()
+            .all(|zone| zone.sled_id == preview.sled_id),
+        "{preview:#?}"
+    );
+
+    // Previewing doesn't change the sled.
+    let sled = NexusRequest::object_get(external_client, &sled_url)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute_and_parse_unwrap::<Sled>()
+        .await;
+    assert_eq!(
+        sled.policy,
+        SledPolicy::InService {
+            provision_policy: SledProvisionPolicy::Provisionable,
+        }
+    );
+
+    // Sleds that don't exist can't be previewed.
+    let missing_url =
+        format!("/v1/system/hardware/sleds/{}/expunge:preview", Uuid::new_v4());
+    NexusRequest::new(
+        RequestBuilder::new(external_client, Method::POST, &missing_url)
+            .expect_status(Some(StatusCode::NOT_FOUND)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap();
+}
@@ -9,6 +9,7 @@ use chrono::Utc;
 use dropshot::test_util::ClientTestContext;
 use http::StatusCode;
 use http::method::Method;
+use nexus_client::types::LastResult;
 use nexus_config::RegionAllocationStrategy;
 use nexus_db_lookup::LookupPath;
 use nexus_db_model::to_db_typed_uuid;
@@ -20,21 +21,33 @@ use nexus_db_queries::db::datastore::RegionAllocationFor;
 use nexus_db_queries::db::datastore::RegionAllocationParameters;
 use nexus_db_queries::db::identity::Resource;
 use nexus_test_utils::SLED_AGENT_UUID;
+use nexus_test_utils::background::activate_background_task;
 use nexus_test_utils::http_testing::AuthnMode;
 use nexus_test_utils::http_testing::NexusRequest;
 use nexus_test_utils::http_testing::RequestBuilder;
 use nexus_test_utils::resource_helpers::create_default_ip_pool;
 use nexus_test_utils::resource_helpers::create_disk;
 use nexus_test_utils::resource_helpers::create_project;
+use nexus_test_utils::resource_helpers::create_snapshot;
 use nexus_test_utils::resource_helpers::object_create;
+use nexus_test_utils::resource_helpers::object_create_error;
+use nexus_test_utils::resource_helpers::object_create_operation;
+use nexus_test_utils::resource_helpers::object_delete;
+use nexus_test_utils::resource_helpers::object_delete_error;
+use nexus_test_utils::resource_helpers::object_get;
+use nexus_test_utils::resource_helpers::object_put;
+use nexus_test_utils::resource_helpers::object_put_error;
+use nexus_test_utils::resource_helpers::objects_list_page_authz;
 use nexus_test_utils_macros::nexus_test;
 use nexus_types::external_api::params;
 use nexus_types::external_api::views;
+use nexus_types::internal_api::background::SnapshotSchedulerStatus;
 use omicron_common::api::external;
 use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::Disk;
 use omicron_common::api::external::DiskState;
 use omicron_common::api::external::IdentityMetadataCreateParams;
+use omicron_common::api::external::IdentityMetadataUpdateParams;
 use omicron_common::api::external::Instance;
 use omicron_common::api::external::InstanceCpuCount;
 use omicron_common::api::external::Name;
@@ -142,14 +155,16 @@ async fn test_snapshot_basic(cptestctx: &ControlPlaneTestContext) {
             ssh_public_keys: Some(Vec::new()),
             network_interfaces:
                 params::InstanceNetworkInterfaceAttachment::None,
-            boot_disk: Some(params::InstanceDiskAttachment::Attach(
+            boot_order: vec![base_disk_name.clone().into()],
+            disks: vec![params::InstanceDiskAttachment::Attach(
                 params::InstanceDiskAttach { name: base_disk_name.clone() },
-            )),
-            disks: Vec::new(),
+            )],
             external_ips: vec![],
             start: true,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         },
     )
     .await;
@@ -179,6 +194,306 @@ async fn test_snapshot_basic(cptestctx: &ControlPlaneTestContext) {
     assert_eq!(snapshot.size, base_disk.size);
 }
 
+#[nexus_test]
+async fn test_snapshot_delete_protection(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+    create_project_and_pool(client).await;
+
+    create_disk(client, PROJECT_NAME, "base-disk").await;
+    let snapshot =
+        create_snapshot(client, PROJECT_NAME, "base-disk", "golden").await;
+    assert_eq!(snapshot.state, views::SnapshotState::Ready);
+    assert!(!snapshot.delete_protected);
+
+    let snapshot_url = format!("/v1/snapshots/golden?project={}", PROJECT_NAME);
+
+    // Protect the snapshot, then try to delete it.
+    let snapshot: views::Snapshot = object_put(
+        client,
+        &snapshot_url,
+        &params::SnapshotUpdate { delete_protected: true },
+    )
+    .await;
+    assert!(snapshot.delete_protected);
+
+    let error =
+        object_delete_error(client, &snapshot_url, StatusCode::CONFLICT).await;
+    assert_eq!(error.error_code.as_deref(), Some("DeleteProtected"));
+
+    // The snapshot should be untouched.
+    let snapshot: views::Snapshot =
+        NexusRequest::object_get(client, &snapshot_url)
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap()
+            .await;
+    assert_eq!(snapshot.state, views::SnapshotState::Ready);
+    assert!(snapshot.delete_protected);
+
+    // Once the protection is cleared, the snapshot can be deleted.
+    let snapshot: views::Snapshot = object_put(
+        client,
+        &snapshot_url,
+        &params::SnapshotUpdate { delete_protected: false },
+    )
+    .await;
+    assert!(!snapshot.delete_protected);
+    object_delete(client, &snapshot_url).await;
+    NexusRequest::expect_failure(
+        client,
+        StatusCode::NOT_FOUND,
+        Method::GET,
+        &snapshot_url,
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap();
+}
+
+#[nexus_test]
+async fn test_snapshot_schedule_crud(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+    create_project_and_pool(client).await;
+
+    let disk = create_disk(client, PROJECT_NAME, "base-disk").await;
+
+    let schedules_url =
+        format!("/v1/snapshot-schedules?project={}", PROJECT_NAME);
+    let schedule_url =
+        format!("/v1/snapshot-schedules/nightly?project={}", PROJECT_NAME);
+    let create_params =
+        |interval_secs, retain_count| params::SnapshotScheduleCreate {
+            identity: IdentityMetadataCreateParams {
+                name: "nightly".parse().unwrap(),
+                description: String::from("nightly snapshots"),
+            },
+            disk: Some("base-disk".parse::<Name>().unwrap().into()),
+            interval_secs,
+            retain_count,
+        };
+
+    // Intervals that are too short, and schedules that would retain nothing,
+    // are rejected.
+    let error = object_create_error(
+        client,
+        &schedules_url,
+        &create_params(60, 7),
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    assert!(error.message.contains("interval_secs"), "{}", error.message);
+    let error = object_create_error(
+        client,
+        &schedules_url,
+        &create_params(86400, 0),
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    assert!(error.message.contains("retain_count"), "{}", error.message);
+
+    let schedule: views::SnapshotSchedule =
+        object_create(client, &schedules_url, &create_params(86400, 7)).await;
+    assert_eq!(schedule.identity.name, "nightly");
+    assert_eq!(schedule.disk_id, Some(disk.identity.id));
+    assert_eq!(schedule.interval_secs, 86400);
+    assert_eq!(schedule.retain_count, 7);
+    assert_eq!(schedule.time_last_run, None);
+
+    let schedules = objects_list_page_authz::<views::SnapshotSchedule>(
+        client,
+        &schedules_url,
+    )
+    .await
+    .items;
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].identity.id, schedule.identity.id);
+
+    // Updates are validated the same way.
+    let error = object_put_error(
+        client,
+        &schedule_url,
+        &params::SnapshotScheduleUpdate {
+            identity: IdentityMetadataUpdateParams {
+                name: None,
+                description: None,
+            },
+            interval_secs: None,
+            retain_count: Some(0),
+        },
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    assert!(error.message.contains("retain_count"), "{}", error.message);
+
+    let schedule: views::SnapshotSchedule = object_put(
+        client,
+        &schedule_url,
+        &params::SnapshotScheduleUpdate {
+            identity: IdentityMetadataUpdateParams {
+                name: None,
+                description: None,
+            },
+            interval_secs: Some(3600),
+            retain_count: Some(2),
+        },
+    )
+    .await;
+    assert_eq!(schedule.interval_secs, 3600);
+    assert_eq!(schedule.retain_count, 2);
+
+    // The schedule has never run, so the scheduler takes a snapshot of the
+    // disk straight away.
+    let task = activate_background_task(
+        &cptestctx.internal_client,
+        "snapshot_scheduler",
+    )
+    .await;
+    let LastResult::Completed(last_result) = task.last else {
+        panic!("unexpected {:?} returned from snapshot_scheduler", task.last);
+    };
+    let status =
+        serde_json::from_value::<SnapshotSchedulerStatus>(last_result.details)
+            .unwrap();
+    assert!(status.errors.is_empty(), "{:?}", status.errors);
+    assert_eq!(status.schedules_run, 1);
+    assert_eq!(status.snapshots_requested.len(), 1);
+    assert_eq!(status.snapshots_requested[0].disk_id, disk.identity.id);
+
+    let schedule: views::SnapshotSchedule =
+        object_get(client, &schedule_url).await;
+    assert!(schedule.time_last_run.is_some());
+
+    // Deleting the schedule leaves the snapshots it took alone.
+    object_delete(client, &schedule_url).await;
+    NexusRequest::expect_failure(
+        client,
+        StatusCode::NOT_FOUND,
+        Method::GET,
+        &schedule_url,
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap();
+
+    let snapshot_name = &status.snapshots_requested[0].snapshot_name;
+    let snapshot_url =
+        format!("/v1/snapshots/{snapshot_name}?project={}", PROJECT_NAME);
+    let snapshot: views::Snapshot = object_get(client, &snapshot_url).await;
+    assert_eq!(snapshot.disk_id, disk.identity.id);
+}
+
+#[nexus_test]
+async fn test_snapshot_export(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+    DiskTest::new(&cptestctx).await;
+    create_project_and_pool(client).await;
+
+    let disk = create_disk(client, PROJECT_NAME, "base-disk").await;
+    let snapshot =
+        create_snapshot(client, PROJECT_NAME, "base-disk", "golden").await;
+
+    let exports_url = format!("/v1/snapshot-exports?project={}", PROJECT_NAME);
+    let operation = object_create_operation(
+        client,
+        &format!("/v1/snapshots/golden/export?project={}", PROJECT_NAME),
+        &serde_json::Value::Null,
+    )
+    .await;
+    assert_eq!(operation.kind, views::OperationKind::SnapshotExport);
+    assert_eq!(operation.state, views::OperationState::Succeeded);
+    let result = operation.result.expect("operation should have a result");
+    assert_eq!(
+        result.resource_type,
+        views::OperationResultType::SnapshotExport
+    );
+    let export: views::SnapshotExport = object_get(client, &result.href).await;
+
+    let operations = objects_list_page_authz::<views::Operation>(
+        client,
+        &format!("/v1/operations?project={}", PROJECT_NAME),
+    )
+    .await
+    .items;
+    assert_eq!(operations.len(), 1);
+    assert_eq!(operations[0].id, operation.id);
+    assert_eq!(export.snapshot_id, snapshot.identity.id);
+    assert_eq!(export.instance_id, None);
+    assert_eq!(export.state, views::SnapshotExportState::Ready);
+    assert_eq!(export.size, disk.size);
+    assert_eq!(export.block_size, disk.block_size);
+
+    let export_url = format!("/v1/snapshot-exports/{}", export.id);
+    let fetched: views::SnapshotExport = object_get(client, &export_url).await;
+    assert_eq!(fetched.id, export.id);
+
+    let exports =
+        objects_list_page_authz::<views::SnapshotExport>(client, &exports_url)
+            .await
+            .items;
+    assert_eq!(exports.len(), 1);
+    assert_eq!(exports[0].id, export.id);
+
+    let manifest: views::ExportManifest =
+        object_get(client, &format!("{export_url}/manifest")).await;
+    assert_eq!(manifest.format_version, views::ExportManifest::FORMAT_VERSION);
+    assert_eq!(manifest.image_format, views::ExportImageFormat::Raw);
+    assert_eq!(manifest.size, disk.size);
+    assert_eq!(manifest.snapshot.id, snapshot.identity.id);
+    assert_eq!(manifest.snapshot.name, snapshot.identity.name);
+    assert_eq!(manifest.snapshot.disk_id, disk.identity.id);
+    assert_eq!(manifest.instance, None);
+
+    // Ranges that don't line up with block boundaries are trimmed to exactly
+    // what was asked for.
+    let download_url = format!("{export_url}/download");
+    let body = NexusRequest::new(
+        RequestBuilder::new(client, Method::GET, &download_url)
+            .header(http::header::RANGE, "bytes=100-1123")
+            .expect_status(Some(StatusCode::PARTIAL_CONTENT))
+            .expect_response_header(
+                http::header::CONTENT_RANGE,
+                &format!("bytes 100-1123/{}", disk.size.to_bytes()),
+            )
+            .expect_range_requestable("application/octet-stream"),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap()
+    .body;
+    assert_eq!(body.len(), 1024);
+    assert!(body.iter().all(|b| *b == 0));
+
+    // The export outlives the snapshot it was created from.
+    object_delete(
+        client,
+        &format!("/v1/snapshots/golden?project={}", PROJECT_NAME),
+    )
+    .await;
+    let fetched: views::SnapshotExport = object_get(client, &export_url).await;
+    assert_eq!(fetched.id, export.id);
+
+    object_delete(client, &export_url).await;
+    NexusRequest::expect_failure(
+        client,
+        StatusCode::NOT_FOUND,
+        Method::GET,
+        &export_url,
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap();
+    let exports =
+        objects_list_page_authz::<views::SnapshotExport>(client, &exports_url)
+            .await
+            .items;
+    assert!(exports.is_empty());
+}
+
 #[nexus_test]
 async fn test_snapshot_without_instance(cptestctx: &ControlPlaneTestContext) {
     let client = &cptestctx.external_client;
@@ -348,14 +663,16 @@ async fn test_snapshot_stopped_instance(cptestctx: &ControlPlaneTestContext) {
             ssh_public_keys: Some(Vec::new()),
             network_interfaces:
                 params::InstanceNetworkInterfaceAttachment::None,
-            boot_disk: Some(params::InstanceDiskAttachment::Attach(
+            boot_order: vec![base_disk_name.clone().into()],
+            disks: vec![params::InstanceDiskAttachment::Attach(
                 params::InstanceDiskAttach { name: base_disk_name.clone() },
-            )),
-            disks: Vec::new(),
+            )],
             external_ips: vec![],
             start: false,
             auto_restart_policy: Default::default(),
             anti_affinity_groups: Vec::new(),
+            tags: Default::default(),
+            stopped_reservation_policy: Default::default(),
         },
     )
     .await;
@@ -587,6 +904,8 @@ async fn test_reject_creating_disk_from_snapshot(
                 size: external::ByteCount::try_from(2 * MIN_DISK_SIZE_BYTES)
                     .unwrap()
                     .into(),
+                delete_protected: false,
+                snapshot_schedule_id: None,
             },
         )
         .await
@@ -742,6 +1061,8 @@ async fn test_reject_creating_disk_from_illegal_snapshot(
                 )
                 .unwrap()
                 .into(),
+                delete_protected: false,
+                snapshot_schedule_id: None,
             },
         )
         .await
@@ -838,6 +1159,8 @@ async fn test_reject_creating_disk_from_other_project_snapshot(
                 )
                 .unwrap()
                 .into(),
+                delete_protected: false,
+                snapshot_schedule_id: None,
             },
         )
         .await
@@ -1051,6 +1374,8 @@ async fn test_create_snapshot_record_idempotent(
         state: db::model::SnapshotState::Creating,
         block_size: db::model::BlockSize::Traditional,
         size: external::ByteCount::try_from(1024u32).unwrap().into(),
+        delete_protected: false,
+        snapshot_schedule_id: None,
     };
 
     let opctx =
@@ -1102,6 +1427,8 @@ async fn test_create_snapshot_record_idempotent(
         state: db::model::SnapshotState::Creating,
         block_size: db::model::BlockSize::Traditional,
         size: external::ByteCount::try_from(1024u32).unwrap().into(),
+        delete_protected: false,
+        snapshot_schedule_id: None,
     };
 
     let dupe_snapshot_created_err = datastore
@@ -1203,6 +1530,8 @@ async fn test_create_snapshot_record_idempotent(
         state: db::model::SnapshotState::Creating,
         block_size: db::model::BlockSize::Traditional,
         size: external::ByteCount::try_from(1024u32).unwrap().into(),
+        delete_protected: false,
+        snapshot_schedule_id: None,
     };
 
     let _ = datastore
@@ -3,7 +3,9 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! Tests that subnet allocation will successfully allocate the entire space of a
-//! subnet and error appropriately when the space is exhausted.
+//! subnet and error appropriately when the space is exhausted, that it
+//! respects addresses reserved in the subnet, and that it continues into any
+//! secondary blocks added to the subnet.
 
 use dropshot::HttpErrorResponseBody;
 use dropshot::test_util::ClientTestContext;
@@ -19,11 +21,14 @@ use nexus_test_utils::resource_helpers::create_project;
 use nexus_test_utils::resource_helpers::objects_list_page_authz;
 use nexus_test_utils_macros::nexus_test;
 use nexus_types::external_api::params;
+use nexus_types::external_api::views::VpcSubnetIpReservation;
+use nexus_types::external_api::views::VpcSubnetIpv4Blocks;
 use omicron_common::api::external::{
     ByteCount, IdentityMetadataCreateParams, InstanceCpuCount,
     InstanceNetworkInterface,
 };
 use oxnet::Ipv4Net;
+use std::net::IpAddr;
 use std::net::Ipv4Addr;
 
 type ControlPlaneTestContext =
@@ -63,10 +68,12 @@ async fn create_instance_expect_failure(
         network_interfaces,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     NexusRequest::new(
@@ -198,3 +205,320 @@ async fn test_subnet_allocation(cptestctx: &ControlPlaneTestContext) {
         );
     }
 }
+
+#[nexus_test]
+async fn test_subnet_ip_reservation(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+
+    let project_name = "springfield-squidport";
+    create_default_ip_pool(&client).await;
+    create_project(&client, project_name).await;
+
+    let vpc_selector = format!("project={}&vpc=default", project_name);
+    let subnets_url = format!("/v1/vpc-subnets?{}", vpc_selector);
+    let subnet_name = "reserved";
+    let subnet = Ipv4Net::new(Ipv4Addr::new(192, 168, 42, 0), 24).unwrap();
+    let subnet_create = params::VpcSubnetCreate {
+        identity: IdentityMetadataCreateParams {
+            name: subnet_name.parse().unwrap(),
+            description: String::from("a subnet with reservations"),
+        },
+        ipv4_block: subnet,
+        ipv6_block: None,
+        custom_router: None,
+    };
+    NexusRequest::objects_post(client, &subnets_url, &Some(&subnet_create))
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .unwrap();
+
+    // Reserve the first address that would otherwise be assigned
+    // automatically.
+    let reservations_url = format!(
+        "/v1/vpc-subnets/{}/ip-reservations?{}",
+        subnet_name, vpc_selector
+    );
+    let first_addr: IpAddr = subnet
+        .addr_iter()
+        .nth(NUM_INITIAL_RESERVED_IP_ADDRESSES)
+        .unwrap()
+        .into();
+    let reservation_create = params::VpcSubnetIpReservationCreate {
+        ip: first_addr,
+        description: String::from("pre-registered in DNS"),
+    };
+    let reservation: VpcSubnetIpReservation = NexusRequest::objects_post(
+        client,
+        &reservations_url,
+        &reservation_create,
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap()
+    .await;
+    assert_eq!(reservation.ip, first_addr);
+
+    // The same address can't be reserved twice, and neither can an address
+    // reserved by the system.
+    let system_reserved = params::VpcSubnetIpReservationCreate {
+        ip: subnet.addr_iter().nth(1).unwrap().into(),
+        description: String::new(),
+    };
+    for body in [&reservation_create, &system_reserved] {
+        NexusRequest::new(
+            RequestBuilder::new(client, Method::POST, &reservations_url)
+                .body(Some(body))
+                .expect_status(Some(StatusCode::BAD_REQUEST)),
+        )
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .unwrap();
+    }
+
+    // An automatically-assigned address skips the reservation...
+    let auto = create_instance_in_subnet(
+        client,
+        project_name,
+        "auto",
+        subnet_name,
+        None,
+    )
+    .await;
+    assert_eq!(
+        auto.ip,
+        IpAddr::from(
+            subnet
+                .addr_iter()
+                .nth(NUM_INITIAL_RESERVED_IP_ADDRESSES + 1)
+                .unwrap()
+        )
+    );
+
+    // ...but an interface requesting the reserved address is given it.
+    let pinned = create_instance_in_subnet(
+        client,
+        project_name,
+        "pinned",
+        subnet_name,
+        Some(first_addr),
+    )
+    .await;
+    assert_eq!(pinned.ip, first_addr);
+
+    let reservations = objects_list_page_authz::<VpcSubnetIpReservation>(
+        client,
+        &reservations_url,
+    )
+    .await
+    .items;
+    assert_eq!(reservations, vec![reservation]);
+
+    // Releasing the reservation doesn't affect the interface using it.
+    let reservation_url = format!(
+        "/v1/vpc-subnets/{}/ip-reservations/{}?{}",
+        subnet_name, first_addr, vpc_selector
+    );
+    NexusRequest::object_delete(client, &reservation_url)
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .unwrap();
+    assert!(
+        objects_list_page_authz::<VpcSubnetIpReservation>(
+            client,
+            &reservations_url
+        )
+        .await
+        .items
+        .is_empty()
+    );
+    NexusRequest::expect_failure(
+        client,
+        StatusCode::NOT_FOUND,
+        Method::DELETE,
+        &reservation_url,
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap();
+}
+
+#[nexus_test]
+async fn test_subnet_secondary_ipv4_block(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+
+    let project_name = "springfield-squidport";
+    create_default_ip_pool(&client).await;
+    create_project(&client, project_name).await;
+
+    // Create the smallest allowed subnet, and exhaust it.
+    let subnet_size = cptestctx
+        .server
+        .server_context()
+        .nexus
+        .tunables()
+        .max_vpc_ipv4_subnet_prefix;
+    let vpc_selector = format!("project={}&vpc=default", project_name);
+    let subnets_url = format!("/v1/vpc-subnets?{}", vpc_selector);
+    let subnet_name = "growing";
+    let subnet =
+        Ipv4Net::new(Ipv4Addr::new(192, 168, 42, 0), subnet_size).unwrap();
+    let subnet_create = params::VpcSubnetCreate {
+        identity: IdentityMetadataCreateParams {
+            name: subnet_name.parse().unwrap(),
+            description: String::from("a subnet that outgrows its block"),
+        },
+        ipv4_block: subnet,
+        ipv6_block: None,
+        custom_router: None,
+    };
+    NexusRequest::objects_post(client, &subnets_url, &Some(&subnet_create))
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .unwrap();
+    let n_usable =
+        subnet.size().unwrap() as usize - 1 - NUM_INITIAL_RESERVED_IP_ADDRESSES;
+    for i in 0..n_usable {
+        create_instance_in_subnet(
+            client,
+            project_name,
+            &format!("i{i}"),
+            subnet_name,
+            None,
+        )
+        .await;
+    }
+
+    // Secondary blocks may not overlap any block in the VPC, including the
+    // subnet's own, nor may they be public.
+    let blocks_url =
+        format!("/v1/vpc-subnets/{}/ipv4-blocks?{}", subnet_name, vpc_selector);
+    for block in [
+        subnet,
+        "172.30.0.0/22".parse().unwrap(),
+        Ipv4Net::new(Ipv4Addr::new(8, 8, 8, 0), subnet_size).unwrap(),
+    ] {
+        NexusRequest::new(
+            RequestBuilder::new(client, Method::POST, &blocks_url)
+                .body(Some(&params::VpcSubnetIpv4BlockAdd { block }))
+                .expect_status(Some(StatusCode::BAD_REQUEST)),
+        )
+        .authn_as(AuthnMode::PrivilegedUser)
+        .execute()
+        .await
+        .unwrap();
+    }
+
+    let secondary =
+        Ipv4Net::new(Ipv4Addr::new(192, 168, 43, 0), subnet_size).unwrap();
+    let blocks: VpcSubnetIpv4Blocks = NexusRequest::new(
+        RequestBuilder::new(client, Method::POST, &blocks_url)
+            .body(Some(&params::VpcSubnetIpv4BlockAdd { block: secondary }))
+            .expect_status(Some(StatusCode::OK)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute_and_parse_unwrap()
+    .await;
+    let expected =
+        VpcSubnetIpv4Blocks { primary: subnet, secondary: vec![secondary] };
+    assert_eq!(blocks, expected);
+    let blocks: VpcSubnetIpv4Blocks =
+        NexusRequest::object_get(client, &blocks_url)
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap()
+            .await;
+    assert_eq!(blocks, expected);
+
+    // New interfaces are now allocated from the secondary block.
+    let nic = create_instance_in_subnet(
+        client,
+        project_name,
+        "overflow",
+        subnet_name,
+        None,
+    )
+    .await;
+    assert_eq!(
+        nic.ip,
+        IpAddr::from(
+            secondary
+                .addr_iter()
+                .nth(NUM_INITIAL_RESERVED_IP_ADDRESSES)
+                .unwrap()
+        )
+    );
+
+    // And a new subnet can't claim the secondary block.
+    let overlapping = params::VpcSubnetCreate {
+        identity: IdentityMetadataCreateParams {
+            name: "overlapping".parse().unwrap(),
+            description: String::new(),
+        },
+        ipv4_block: secondary,
+        ipv6_block: None,
+        custom_router: None,
+    };
+    let error: HttpErrorResponseBody = NexusRequest::new(
+        RequestBuilder::new(client, Method::POST, &subnets_url)
+            .body(Some(&overlapping))
+            .expect_status(Some(StatusCode::BAD_REQUEST)),
+    )
+    .authn_as(AuthnMode::PrivilegedUser)
+    .execute()
+    .await
+    .unwrap()
+    .parsed_body()
+    .unwrap();
+    assert_eq!(
+        error.message,
+        format!(
+            "IP address range '{secondary}' conflicts with an existing subnet"
+        )
+    );
+}
+
+async fn create_instance_in_subnet(
+    client: &ClientTestContext,
+    project_name: &str,
+    name: &str,
+    subnet_name: &str,
+    ip: Option<IpAddr>,
+) -> InstanceNetworkInterface {
+    let nic = params::InstanceNetworkInterfaceAttachment::Create(vec![
+        params::InstanceNetworkInterfaceCreate {
+            identity: IdentityMetadataCreateParams {
+                name: "eth0".parse().unwrap(),
+                description: String::from("some iface"),
+            },
+            vpc_name: "default".parse().unwrap(),
+            subnet_name: subnet_name.parse().unwrap(),
+            ip,
+            transit_ips: vec![],
+        },
+    ]);
+    let instance = create_instance_with(
+        client,
+        project_name,
+        name,
+        &nic,
+        // Disks=
+        Vec::<params::InstanceDiskAttachment>::new(),
+        // External IPs=
+        Vec::<params::ExternalIpCreate>::new(),
+        true,
+        Default::default(),
+    )
+    .await;
+    let url_nics = format!(
+        "/v1/network-interfaces?project={}&instance={}",
+        project_name, instance.identity.name
+    );
+    objects_list_page_authz::<InstanceNetworkInterface>(client, &url_nics)
+        .await
+        .items
+        .pop()
+        .expect("instance should have a network interface")
+}
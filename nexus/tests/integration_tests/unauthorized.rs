@@ -20,7 +20,9 @@ use nexus_test_utils::http_testing::NexusRequest;
 use nexus_test_utils::http_testing::RequestBuilder;
 use nexus_test_utils::http_testing::TestResponse;
 use nexus_test_utils::resource_helpers::TestDataset;
+use nexus_test_utils::resource_helpers::operation_wait;
 use nexus_test_utils::test_setup;
+use nexus_types::external_api::views;
 use omicron_common::disk::DatasetKind;
 use omicron_uuid_kinds::DatasetUuid;
 use omicron_uuid_kinds::ZpoolUuid;
@@ -114,6 +116,51 @@ async fn test_unauthorized() {
                     .unwrap(),
                 id_routes,
             ),
+            SetupReq::PostOperation {
+                url,
+                body,
+                id_routes,
+                operation_id_routes,
+            } => {
+                let operation: views::Operation = NexusRequest::new(
+                    RequestBuilder::new(client, Method::POST, url)
+                        .body(Some(body))
+                        .expect_status(Some(StatusCode::ACCEPTED)),
+                )
+                .authn_as(AuthnMode::PrivilegedUser)
+                .execute()
+                .await
+                .map_err(|e| panic!("Failed to POST to URL: {url}, {e}"))
+                .unwrap()
+                .parsed_body()
+                .unwrap();
+                let operation = operation_wait(client, operation.id).await;
+                let operation_result = NexusRequest::object_get(
+                    client,
+                    &format!("/v1/operations/{}", operation.id),
+                )
+                .authn_as(AuthnMode::PrivilegedUser)
+                .execute()
+                .await
+                .unwrap();
+                operation_id_routes.iter().for_each(|id_route| {
+                    setup_results.insert(id_route, operation_result.clone());
+                });
+
+                let href = operation
+                    .result
+                    .unwrap_or_else(|| {
+                        panic!("operation started by {url} failed")
+                    })
+                    .href;
+                let result = NexusRequest::object_get(client, &href)
+                    .authn_as(AuthnMode::PrivilegedUser)
+                    .execute()
+                    .await
+                    .map_err(|e| panic!("Failed to GET from URL: {href}, {e}"))
+                    .unwrap();
+                (url, result, id_routes)
+            }
         };
 
         setup_results.insert(url, result.clone());
@@ -207,6 +254,17 @@ enum SetupReq {
         body: serde_json::Value,
         id_routes: Vec<&'static str>,
     },
+    /// Like `Post`, but for a request that starts a long-running operation
+    ///
+    /// The operation is waited on, and `id_routes` are associated with the
+    /// resource it produced rather than with the operation itself, which is
+    /// associated with `operation_id_routes` instead.
+    PostOperation {
+        url: &'static str,
+        body: serde_json::Value,
+        id_routes: Vec<&'static str>,
+        operation_id_routes: Vec<&'static str>,
+    },
 }
 
 pub static HTTP_SERVER: LazyLock<httptest::Server> =
@@ -292,6 +350,13 @@ static SETUP_REQUESTS: LazyLock<Vec<SetupReq>> = LazyLock::new(|| {
             body: serde_json::to_value(&*DEMO_VPC_SUBNET_CREATE).unwrap(),
             id_routes: vec!["/by-id/vpc-subnets/{id}"],
         },
+        // Reserve an IP address in the VPC Subnet
+        SetupReq::Post {
+            url: &DEMO_VPC_SUBNET_IP_RESERVATIONS_URL,
+            body: serde_json::to_value(&*DEMO_VPC_SUBNET_IP_RESERVATION_CREATE)
+                .unwrap(),
+            id_routes: vec![],
+        },
         // Create a VPC Router in the Vpc
         SetupReq::Post {
             url: &DEMO_VPC_URL_ROUTERS,
@@ -364,6 +429,24 @@ static SETUP_REQUESTS: LazyLock<Vec<SetupReq>> = LazyLock::new(|| {
             body: serde_json::to_value(&*DEMO_SNAPSHOT_CREATE).unwrap(),
             id_routes: vec!["/by-id/snapshots/{id}"],
         },
+        // Create a Snapshot Schedule in the Project
+        SetupReq::Post {
+            url: &DEMO_PROJECT_URL_SNAPSHOT_SCHEDULES,
+            body: serde_json::to_value(&*DEMO_SNAPSHOT_SCHEDULE_CREATE)
+                .unwrap(),
+            id_routes: vec!["/v1/snapshot-schedules/{id}"],
+        },
+        // Export the Snapshot
+        SetupReq::PostOperation {
+            url: &DEMO_SNAPSHOT_EXPORT_CREATE_URL,
+            body: serde_json::Value::Null,
+            id_routes: vec![
+                DEMO_SNAPSHOT_EXPORT_URL,
+                DEMO_SNAPSHOT_EXPORT_MANIFEST_URL,
+                DEMO_SNAPSHOT_EXPORT_DOWNLOAD_URL,
+            ],
+            operation_id_routes: vec![DEMO_OPERATION_URL],
+        },
         // Create an Image in the Project
         SetupReq::Post {
             url: &DEMO_PROJECT_IMAGES_URL,
@@ -625,3 +625,164 @@ async fn test_trust_root_operations(cptestctx: &ControlPlaneTestContext) {
         .expect("failed to parse list after delete response");
     assert!(response.items.is_empty());
 }
+
+#[nexus_test]
+async fn test_component_versions(cptestctx: &ControlPlaneTestContext) {
+    use nexus_types::external_api::views::{
+        BlueprintTarget, ComponentVersionMatrix, VersionedComponent,
+    };
+
+    let client = &cptestctx.external_client;
+
+    // Current versions come from inventory.
+    cptestctx
+        .wait_for_at_least_one_inventory_collection(
+            std::time::Duration::from_secs(60),
+        )
+        .await;
+
+    let matrix =
+        NexusRequest::object_get(client, "/v1/system/update/components")
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap::<ComponentVersionMatrix>()
+            .await;
+    let target =
+        NexusRequest::object_get(client, "/v1/system/blueprints/target")
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap::<BlueprintTarget>()
+            .await;
+    assert_eq!(matrix.blueprint_id, target.blueprint.id);
+
+    // With no target release, nothing but the zones (whose images come from
+    // the blueprint) has anywhere to go.
+    assert_eq!(matrix.target_release, None);
+    assert!(!matrix.sleds.is_empty(), "{matrix:#?}");
+    for sled in &matrix.sleds {
+        let kinds: Vec<_> =
+            sled.components.iter().map(|c| c.component.clone()).collect();
+        assert_eq!(
+            kinds[..4],
+            [
+                VersionedComponent::Sp,
+                VersionedComponent::Rot,
+                VersionedComponent::RotBootloader,
+                VersionedComponent::HostOs,
+            ],
+        );
+        for component in &sled.components[..4] {
+            assert_eq!(component.current_version, component.target_version);
+        }
+    }
+    assert!(
+        matrix.sleds.iter().flat_map(|sled| &sled.components).any(|c| {
+            matches!(&c.component, VersionedComponent::Zone { kind, .. }
+                if kind == "nexus")
+        }),
+        "{matrix:#?}"
+    );
+}
+
+#[nexus_test]
+async fn test_technician_port_update_progress(
+    cptestctx: &ControlPlaneTestContext,
+) {
+    use nexus_client::types::{
+        ServiceProcessorType, TechnicianPortUpdateProgress,
+        TechnicianPortUpdateReport, UpdateComponentKind,
+        UpdateComponentProgress, UpdateComponentState,
+    };
+    use nexus_types::external_api::shared;
+    use nexus_types::external_api::views::TechnicianPortUpdate;
+
+    const TECHNICIAN_PORT_UPDATES_URL: &str =
+        "/v1/system/update/technician-port";
+
+    let client = &cptestctx.external_client;
+    let log = &cptestctx.logctx.log;
+    let nexus_internal_url = format!(
+        "http://{}",
+        cptestctx.server.get_http_server_internal_address().await
+    );
+    let nexus_client =
+        nexus_client::Client::new(&nexus_internal_url, log.clone());
+
+    let list_updates = || async {
+        NexusRequest::object_get(client, TECHNICIAN_PORT_UPDATES_URL)
+            .authn_as(AuthnMode::PrivilegedUser)
+            .execute_and_parse_unwrap::<ResultsPage<TechnicianPortUpdate>>()
+            .await
+            .items
+    };
+    assert!(list_updates().await.is_empty());
+
+    // Report progress the way wicketd would.
+    let update_id = uuid::Uuid::new_v4();
+    let report = |state, steps_completed, message: Option<&str>| {
+        TechnicianPortUpdateReport {
+            updates: vec![TechnicianPortUpdateProgress {
+                id: update_id,
+                sp_type: ServiceProcessorType::Switch,
+                sp_slot: 1,
+                components: vec![UpdateComponentProgress {
+                    component: UpdateComponentKind::Sp,
+                    state,
+                    steps_completed,
+                    total_steps: 2,
+                    current_step: None,
+                    message: message.map(String::from),
+                }],
+            }],
+        }
+    };
+    nexus_client
+        .technician_port_update_report(&report(
+            UpdateComponentState::Updating,
+            1,
+            None,
+        ))
+        .await
+        .expect("reported progress");
+
+    let updates = list_updates().await;
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].id, update_id);
+    assert_eq!(updates[0].sp_type, shared::ServiceProcessorType::Switch);
+    assert_eq!(updates[0].sp_slot, 1);
+    assert_eq!(updates[0].components.len(), 1);
+    assert_eq!(
+        updates[0].components[0].state,
+        shared::UpdateComponentState::Updating
+    );
+
+    // Later reports replace the progress of each component.
+    nexus_client
+        .technician_port_update_report(&report(
+            UpdateComponentState::Failed,
+            1,
+            Some("SP did not come back after reset"),
+        ))
+        .await
+        .expect("reported progress");
+
+    let updates = list_updates().await;
+    assert_eq!(updates.len(), 1);
+    assert_eq!(
+        updates[0].components[0].state,
+        shared::UpdateComponentState::Failed
+    );
+    assert_eq!(
+        updates[0].components[0].message.as_deref(),
+        Some("SP did not come back after reset")
+    );
+
+    // Nonsensical progress is rejected.
+    let error = nexus_client
+        .technician_port_update_report(&report(
+            UpdateComponentState::Updated,
+            3,
+            None,
+        ))
+        .await
+        .expect_err("reporting more steps than exist should fail");
+    assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+}
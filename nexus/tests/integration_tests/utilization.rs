@@ -230,10 +230,12 @@ async fn create_resources_in_test_suite_silo(client: &ClientTestContext) {
         network_interfaces: params::InstanceNetworkInterfaceAttachment::Default,
         external_ips: vec![],
         disks: vec![],
-        boot_disk: None,
+        boot_order: Vec::new(),
         start: true,
         auto_restart_policy: Default::default(),
         anti_affinity_groups: Vec::new(),
+        tags: Default::default(),
+        stopped_reservation_policy: Default::default(),
     };
 
     NexusRequest::objects_post(
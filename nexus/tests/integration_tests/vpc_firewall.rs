@@ -12,10 +12,12 @@ use nexus_db_queries::db::{self, DataStore};
 use nexus_networking::vpc_list_firewall_rules;
 use nexus_test_utils::http_testing::{AuthnMode, NexusRequest, RequestBuilder};
 use nexus_test_utils::resource_helpers::{
-    create_project, create_vpc, object_get, object_put, object_put_error,
+    create_project, create_vpc, object_create, object_create_error, object_get,
+    object_put, object_put_error,
 };
 use nexus_test_utils_macros::nexus_test;
-use nexus_types::external_api::views::Vpc;
+use nexus_types::external_api::params;
+use nexus_types::external_api::views::{Vpc, VpcFirewallRulesReplaceResult};
 use omicron_common::api::external::{
     IcmpParamRange, IdentityMetadata, L4Port, L4PortRange, ServiceIcmpConfig,
     VpcFirewallIcmpFilter, VpcFirewallRule, VpcFirewallRuleAction,
@@ -334,6 +336,95 @@ async fn test_firewall_rules_same_name(cptestctx: &ControlPlaneTestContext) {
     );
 }
 
+#[nexus_test]
+async fn test_firewall_rules_replace(cptestctx: &ControlPlaneTestContext) {
+    let client = &cptestctx.external_client;
+
+    let project_name = "replace-project";
+    create_project(&client, &project_name).await;
+    let rules_url =
+        format!("/v1/vpc-firewall-rules?vpc=default&project={}", project_name);
+    let replace_url = format!(
+        "/v1/vpc-firewall-rules/replace?vpc=default&project={}",
+        project_name
+    );
+
+    let default_rules =
+        object_get::<VpcFirewallRules>(client, &rules_url).await.rules;
+    let rule = VpcFirewallRuleUpdate {
+        name: "allow-https".parse().unwrap(),
+        description: "allow inbound HTTPS".to_string(),
+        status: VpcFirewallRuleStatus::Enabled,
+        direction: VpcFirewallRuleDirection::Inbound,
+        targets: vec![],
+        filters: VpcFirewallRuleFilter {
+            hosts: None,
+            protocols: Some(vec![VpcFirewallRuleProtocol::Tcp]),
+            ports: Some(vec![L4Port::try_from(443u16).unwrap().into()]),
+        },
+        action: VpcFirewallRuleAction::Allow,
+        priority: VpcFirewallRulePriority(100),
+    };
+    let replace =
+        |confirm_token: Option<String>| params::VpcFirewallRulesReplace {
+            rules: vec![rule.clone()],
+            confirm_token,
+        };
+
+    // A preview reports the diff without changing anything.
+    let preview: VpcFirewallRulesReplaceResult =
+        object_create(client, &replace_url, &replace(None)).await;
+    assert!(!preview.applied);
+    assert_eq!(preview.diff.added, vec![rule.clone()]);
+    assert_eq!(preview.diff.removed.len(), default_rules.len());
+    assert!(preview.diff.modified.is_empty());
+    let rule_ids = |rules: &[VpcFirewallRule]| {
+        rules.iter().map(|rule| rule.identity.id).collect::<Vec<_>>()
+    };
+    assert_eq!(rule_ids(&preview.rules), rule_ids(&default_rules));
+    assert_eq!(
+        rule_ids(
+            &object_get::<VpcFirewallRules>(client, &rules_url).await.rules
+        ),
+        rule_ids(&default_rules)
+    );
+
+    // A token that doesn't match the preview is rejected.
+    object_create_error(
+        client,
+        &replace_url,
+        &replace(Some("bogus".to_string())),
+        StatusCode::CONFLICT,
+    )
+    .await;
+
+    // Applying with the preview's token replaces the rules.
+    let applied: VpcFirewallRulesReplaceResult = object_create(
+        client,
+        &replace_url,
+        &replace(Some(preview.confirm_token.clone())),
+    )
+    .await;
+    assert!(applied.applied);
+    assert_eq!(applied.rules.len(), 1);
+    assert_eq!(applied.rules[0].identity.name, rule.name);
+    assert_eq!(
+        rule_ids(
+            &object_get::<VpcFirewallRules>(client, &rules_url).await.rules
+        ),
+        rule_ids(&applied.rules)
+    );
+
+    // The rules have changed since the preview, so its token is stale.
+    object_create_error(
+        client,
+        &replace_url,
+        &replace(Some(preview.confirm_token)),
+        StatusCode::CONFLICT,
+    )
+    .await;
+}
+
 #[nexus_test]
 async fn test_firewall_rules_max_lengths(cptestctx: &ControlPlaneTestContext) {
     let client = &cptestctx.external_client;
@@ -0,0 +1,91 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Sources of the current time
+//!
+//! Logic that depends on how much time has passed (cooldown periods, schedules,
+//! and the like) should get the current time from a [`Clock`] rather than
+//! calling [`Utc::now`] directly, so that tests can control the passage of
+//! time with a [`TestClock`] rather than sleeping.
+
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// A source of the current time
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current time according to this clock
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A [`Clock`] that reports the system's wall-clock time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A [`Clock`] that only moves when it's told to
+///
+/// Clones of a `TestClock` share the same time, so a test can hand a clone to
+/// the code under test and advance it from outside.
+#[derive(Clone, Debug)]
+pub struct TestClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl TestClock {
+    /// Returns a clock that is stopped at `now`
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Arc::new(Mutex::new(now)) }
+    }
+
+    /// Returns a clock that is stopped at the current wall-clock time
+    pub fn new_at_system_time() -> Self {
+        Self::new(Utc::now())
+    }
+
+    /// Moves the clock forward by `delta`
+    pub fn advance(&self, delta: TimeDelta) {
+        *self.now.lock().unwrap() += delta;
+    }
+
+    /// Sets the clock to `now`, which may be earlier than its current time
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_clock_only_moves_when_told() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        let clock = TestClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        // Clones share the same time.
+        let other = clock.clone();
+        clock.advance(TimeDelta::minutes(5));
+        assert_eq!(other.now(), start + TimeDelta::minutes(5));
+
+        other.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
@@ -56,6 +56,7 @@ use serde::Deserialize;
 use serde::Serialize;
 use slog::Key;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::fmt;
 use std::net::Ipv6Addr;
 use std::net::SocketAddrV6;
@@ -67,12 +68,14 @@ use tufaceous_artifact::ArtifactVersionError;
 
 mod blueprint_diff;
 mod blueprint_display;
+mod capacity;
 mod chicken_switches;
 mod clickhouse;
 pub mod execution;
 mod network_resources;
 mod planning_input;
 mod planning_report;
+mod portable;
 mod zone_type;
 
 use crate::inventory::BaseboardId;
@@ -80,6 +83,12 @@ use anyhow::anyhow;
 use anyhow::bail;
 pub use blueprint_diff::BlueprintDiffSummary;
 use blueprint_display::BpPendingMgsUpdates;
+pub use capacity::CapacityInstanceShape;
+pub use capacity::CapacityReport;
+pub use capacity::CapacityReportDisplay;
+pub use capacity::RackCapacity;
+pub use capacity::SledCapacity;
+pub use capacity::SledInstanceUsage;
 pub use chicken_switches::PlannerChickenSwitches;
 pub use chicken_switches::PlannerChickenSwitchesDiff;
 pub use chicken_switches::PlannerChickenSwitchesDisplay;
@@ -125,11 +134,16 @@ pub use planning_input::TargetReleaseDescription;
 pub use planning_input::TufRepoContentsError;
 pub use planning_input::TufRepoPolicy;
 pub use planning_input::ZpoolFilter;
+pub use planning_report::ClickhouseKeeperUnsafeToShutdown;
 pub use planning_report::CockroachdbUnsafeToShutdown;
+pub use planning_report::MaintenanceCohortWaitingOn;
+pub use planning_report::MaintenanceCohortZoneKindBlocked;
 pub use planning_report::PlanningAddStepReport;
 pub use planning_report::PlanningCockroachdbSettingsStepReport;
 pub use planning_report::PlanningDecommissionStepReport;
 pub use planning_report::PlanningExpungeStepReport;
+pub use planning_report::PlanningExpungedZone;
+pub use planning_report::PlanningMaintenanceCohortStepReport;
 pub use planning_report::PlanningMgsUpdatesStepReport;
 pub use planning_report::PlanningMupdateOverrideStepReport;
 pub use planning_report::PlanningNoopImageSourceSkipSledHostPhase2Reason;
@@ -138,9 +152,15 @@ pub use planning_report::PlanningNoopImageSourceSkipZoneReason;
 pub use planning_report::PlanningNoopImageSourceStepReport;
 pub use planning_report::PlanningReport;
 pub use planning_report::PlanningZoneUpdatesStepReport;
+pub use planning_report::VersionSkewViolation;
 pub use planning_report::ZoneAddWaitingOn;
+pub use planning_report::ZoneExpungeReason;
 pub use planning_report::ZoneUnsafeToShutdown;
 pub use planning_report::ZoneUpdatesWaitingOn;
+pub use portable::PORTABLE_BLUEPRINT_FORMAT;
+pub use portable::PORTABLE_BLUEPRINT_FORMAT_VERSION;
+pub use portable::PortableBlueprintError;
+pub use portable::PortableBlueprintHeader;
 pub use zone_type::BlueprintZoneType;
 pub use zone_type::DurableDataset;
 pub use zone_type::blueprint_zone_type;
@@ -200,6 +220,13 @@ pub struct Blueprint {
     /// A map of sled id -> desired configuration of the sled.
     pub sleds: BTreeMap<SledUuid, BlueprintSledConfig>,
 
+    /// Which sleds this blueprint is meant to change
+    ///
+    /// Blueprints written before scopes existed cover the whole fleet.
+    #[serde(default)]
+    #[daft(leaf)]
+    pub scope: BlueprintScope,
+
     /// List of pending MGS-mediated updates
     pub pending_mgs_updates: PendingMgsUpdates,
 
@@ -369,6 +396,62 @@ impl Blueprint {
         self.sleds.keys().copied()
     }
 
+    /// Iterate over the configs of the sleds within this blueprint's
+    /// [`BlueprintScope`], along with their ids
+    pub fn sleds_in_scope(
+        &self,
+    ) -> impl Iterator<Item = (SledUuid, &BlueprintSledConfig)> + '_ {
+        self.sleds
+            .iter()
+            .filter(|(sled_id, _)| self.scope.includes(**sled_id))
+            .map(|(sled_id, config)| (*sled_id, config))
+    }
+
+    /// Checks that this blueprint stays within its [`BlueprintScope`]
+    ///
+    /// `parent` must be this blueprint's parent.  Every sled outside the scope
+    /// must be carried over from it unchanged.  Blueprints that cover the
+    /// whole fleet always pass.
+    pub fn validate_scope(
+        &self,
+        parent: &Blueprint,
+    ) -> Result<(), BlueprintScopeError> {
+        let BlueprintScope::Sleds(in_scope) = &self.scope else {
+            return Ok(());
+        };
+
+        if self.parent_blueprint_id != Some(parent.id) {
+            return Err(BlueprintScopeError::ParentMismatch {
+                expected: self.parent_blueprint_id,
+                actual: parent.id,
+            });
+        }
+        if in_scope.is_empty() {
+            return Err(BlueprintScopeError::Empty);
+        }
+        if let Some(sled_id) =
+            in_scope.iter().find(|sled_id| !self.sleds.contains_key(sled_id))
+        {
+            return Err(BlueprintScopeError::UnknownSled(*sled_id));
+        }
+
+        let out_of_scope_sleds: BTreeSet<_> = self
+            .sleds
+            .keys()
+            .chain(parent.sleds.keys())
+            .filter(|sled_id| !in_scope.contains(sled_id))
+            .collect();
+        for sled_id in out_of_scope_sleds {
+            if self.sleds.get(sled_id) != parent.sleds.get(sled_id) {
+                return Err(BlueprintScopeError::OutOfScopeSledChanged(
+                    *sled_id,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Summarize the difference between two blueprints.
     ///
     /// The argument provided is the "before" side, and `self` is the "after"
@@ -405,6 +488,104 @@ impl Blueprint {
 
         Ok(zone_config.nexus_generation < self.nexus_generation)
     }
+
+    /// Returns whether the given Nexus instance is slated for expungement and
+    /// should drain its clients
+    ///
+    /// A Nexus zone that has been cordoned (or already expunged, but not yet
+    /// shut down) has been removed from external DNS.  It keeps running for
+    /// now, but should turn away new work so that clients move to the Nexus
+    /// instances that remain in service.
+    pub fn is_nexus_draining(
+        &self,
+        nexus_id: OmicronZoneUuid,
+    ) -> Result<bool, anyhow::Error> {
+        let zone = self
+            .all_omicron_zones(|_z| true)
+            .find(|(_sled_id, zone_config)| zone_config.id == nexus_id)
+            .ok_or_else(|| {
+                anyhow!("zone {} does not exist in blueprint", nexus_id)
+            })?
+            .1;
+        if !zone.zone_type.is_nexus() {
+            bail!("zone {} is not a Nexus zone", nexus_id);
+        }
+
+        Ok(!zone.disposition.is_in_service())
+    }
+}
+
+/// Which sleds a blueprint is meant to change
+///
+/// Most blueprints cover the whole fleet.  A blueprint can instead be scoped to
+/// a set of sleds, e.g., to stage a change on a few sleds before rolling it out
+/// everywhere.  A scoped blueprint still contains configs for every sled, but
+/// those for sleds outside the scope must be identical to the parent
+/// blueprint's (see [`Blueprint::validate_scope()`]), and blueprint execution
+/// only sends configs to the sleds within the scope.
+///
+/// The scope only restricts per-sled configuration.  Fleet-wide parts of the
+/// blueprint (DNS, CockroachDB settings, pending MGS updates, etc.) are
+/// executed as usual.
+#[derive(
+    Clone, Debug, Default, Eq, PartialEq, JsonSchema, Deserialize, Serialize,
+)]
+#[serde(tag = "kind", content = "sleds", rename_all = "snake_case")]
+pub enum BlueprintScope {
+    /// The blueprint may change any sled
+    #[default]
+    Fleet,
+    /// The blueprint only changes the listed sleds
+    Sleds(BTreeSet<SledUuid>),
+}
+
+impl BlueprintScope {
+    /// Returns whether this scope covers the whole fleet
+    pub fn is_fleet(&self) -> bool {
+        matches!(self, BlueprintScope::Fleet)
+    }
+
+    /// Returns whether the given sled is within this scope
+    pub fn includes(&self, sled_id: SledUuid) -> bool {
+        match self {
+            BlueprintScope::Fleet => true,
+            BlueprintScope::Sleds(sled_ids) => sled_ids.contains(&sled_id),
+        }
+    }
+}
+
+impl fmt::Display for BlueprintScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlueprintScope::Fleet => write!(f, "entire fleet"),
+            BlueprintScope::Sleds(sled_ids) => {
+                write!(f, "{} sled(s):", sled_ids.len())?;
+                for sled_id in sled_ids {
+                    write!(f, " {sled_id}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Describes why a blueprint does not stay within its [`BlueprintScope`]
+#[derive(Debug, thiserror::Error)]
+pub enum BlueprintScopeError {
+    #[error(
+        "blueprint's parent is {expected:?}, but it was checked against \
+         blueprint {actual}"
+    )]
+    ParentMismatch { expected: Option<BlueprintUuid>, actual: BlueprintUuid },
+    #[error("blueprint is scoped to an empty set of sleds")]
+    Empty,
+    #[error("blueprint is scoped to sled {0}, which it does not contain")]
+    UnknownSled(SledUuid),
+    #[error(
+        "sled {0} is outside the blueprint's scope, but its config differs \
+         from the parent blueprint's"
+    )]
+    OutOfScopeSledChanged(SledUuid),
 }
 
 /// Wrapper to display a table of a `BlueprintSledConfig`'s host phase 2
@@ -667,6 +848,7 @@ impl fmt::Display for BlueprintDisplay<'_> {
         let Blueprint {
             id,
             sleds,
+            scope,
             pending_mgs_updates,
             parent_blueprint_id,
             // These two cockroachdb_* fields are handled by
@@ -699,6 +881,11 @@ impl fmt::Display for BlueprintDisplay<'_> {
                 .map(|u| u.to_string())
                 .unwrap_or_else(|| String::from("<none>"))
         )?;
+        // Most blueprints cover the whole fleet; only call out those that
+        // don't.
+        if !scope.is_fleet() {
+            writeln!(f, "scope:     {scope}")?;
+        }
 
         // Loop through all sleds and print details of their configs.
         for (sled_id, config) in sleds {
@@ -840,6 +1027,8 @@ impl BlueprintSledConfig {
     /// This function is effectively a `From` implementation, but
     /// is named slightly more explicitly, as it filters the blueprint
     /// configuration to only consider components that should be in-service.
+    /// (Cordoned zones are included: they are out of rotation, but still
+    /// running.)
     pub fn into_in_service_sled_config(self) -> OmicronSledConfig {
         OmicronSledConfig {
             generation: self.sled_agent_generation,
@@ -869,7 +1058,9 @@ impl BlueprintSledConfig {
                 .zones
                 .into_iter()
                 .filter_map(|zone| {
-                    if zone.disposition.is_in_service() {
+                    // Cordoned zones are out of rotation but still running, so
+                    // they remain part of the sled's config.
+                    if zone.disposition.should_be_running() {
                         Some(zone.into())
                     } else {
                         None
@@ -1037,6 +1228,13 @@ pub enum BlueprintZoneDisposition {
     /// The zone is in-service.
     InService,
 
+    /// The zone is cordoned: it remains deployed and running on its sled, but
+    /// it is removed from DNS and does not take part in load-sharing.
+    ///
+    /// This is intended to let an operator take a single zone (e.g., one
+    /// Nexus) out of rotation for debugging without destroying it.
+    Cordoned,
+
     /// The zone is permanently gone.
     Expunged {
         /// Generation of the parent config in which this zone became expunged.
@@ -1075,12 +1273,23 @@ impl BlueprintZoneDisposition {
         matches!(self, Self::InService)
     }
 
+    /// Returns true if `self` is `BlueprintZoneDisposition::Cordoned`.
+    pub fn is_cordoned(self) -> bool {
+        matches!(self, Self::Cordoned)
+    }
+
     /// Returns true if `self` is `BlueprintZoneDisposition::Expunged { .. }`,
     /// regardless of the details contained within that variant.
     pub fn is_expunged(self) -> bool {
         matches!(self, Self::Expunged { .. })
     }
 
+    /// Returns true if a zone with this disposition should be deployed to its
+    /// sled (i.e., it is either in service or cordoned).
+    pub fn should_be_running(self) -> bool {
+        matches!(self, Self::InService | Self::Cordoned)
+    }
+
     /// Returns true if it's possible a zone with this disposition could be
     /// running.
     ///
@@ -1090,7 +1299,8 @@ impl BlueprintZoneDisposition {
     /// run before execution notifies the sled to shut it down).
     pub fn could_be_running(self) -> bool {
         match self {
-            BlueprintZoneDisposition::InService => true,
+            BlueprintZoneDisposition::InService
+            | BlueprintZoneDisposition::Cordoned => true,
             BlueprintZoneDisposition::Expunged {
                 ready_for_cleanup, ..
             } => !ready_for_cleanup,
@@ -1110,6 +1320,7 @@ impl fmt::Display for BlueprintZoneDisposition {
             // Neither `write!(f, "...")` nor `f.write_str("...")` obey fill
             // and alignment (used above), but this does.
             BlueprintZoneDisposition::InService => "in service".fmt(f),
+            BlueprintZoneDisposition::Cordoned => "cordoned".fmt(f),
             BlueprintZoneDisposition::Expunged {
                 ready_for_cleanup, ..
             } => {
@@ -2146,6 +2357,51 @@ pub struct BlueprintTarget {
 pub struct BlueprintTargetSet {
     pub target_id: BlueprintUuid,
     pub enabled: bool,
+    /// Make the blueprint the target without first checking that it's valid
+    /// and consistent with the latest inventory
+    ///
+    /// This is only meant for emergencies, like when inventory can't be
+    /// collected from a sled that the blueprint is trying to remove.  It's
+    /// ignored when only changing whether the current target is enabled.
+    #[serde(default)]
+    pub skip_validation: bool,
+}
+
+/// Describes whether an operator has disabled execution of the current target
+/// blueprint
+///
+/// This is separate from [`BlueprintTarget::enabled`]: it only stops
+/// execution, it records why, and it remains in effect when new blueprints are
+/// made the target (e.g., by the planner) until an operator re-enables
+/// execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BlueprintTargetExecution {
+    /// id of the current target blueprint
+    pub target_id: BlueprintUuid,
+    /// if set, execution of the target blueprint is disabled
+    pub disabled: Option<BlueprintExecutionDisabled>,
+}
+
+/// Describes why and when an operator disabled blueprint execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BlueprintExecutionDisabled {
+    /// operator-supplied reason that execution was disabled
+    pub reason: String,
+    /// when execution was disabled
+    pub time_disabled: chrono::DateTime<chrono::Utc>,
+}
+
+/// Specifies whether execution of the current target blueprint should be
+/// disabled
+#[derive(Deserialize, JsonSchema)]
+pub struct BlueprintTargetExecutionSet {
+    /// id of the current target blueprint
+    ///
+    /// This must match the current target, so that operators don't
+    /// accidentally change the state of a target they haven't looked at.
+    pub target_id: BlueprintUuid,
+    /// if set, disable execution for this reason; otherwise, re-enable it
+    pub disabled_reason: Option<String>,
 }
 
 /// A unique identifier for a dataset within a collection.
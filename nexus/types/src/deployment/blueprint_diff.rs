@@ -6,10 +6,10 @@
 
 use super::blueprint_display::{
     BpClickhouseServersTableSchema, BpDatasetsTableSchema, BpDiffState,
-    BpGeneration, BpHostPhase2TableSchema, BpOmicronZonesTableSchema,
-    BpPendingMgsUpdates, BpPhysicalDisksTableSchema, BpTable, BpTableColumn,
-    BpTableData, BpTableRow, KvList, KvPair, constants::*,
-    linear_table_modified, linear_table_unchanged,
+    BpGeneration, BpHostPhase2TableSchema, BpNetworkResourcesTableSchema,
+    BpOmicronZonesTableSchema, BpPendingMgsUpdates, BpPhysicalDisksTableSchema,
+    BpTable, BpTableColumn, BpTableData, BpTableRow, KvList, KvPair,
+    constants::*, linear_table_modified, linear_table_unchanged,
 };
 use super::{
     BlueprintDatasetConfigDiff, BlueprintDatasetDisposition, BlueprintDiff,
@@ -17,14 +17,16 @@ use super::{
     BlueprintHostPhase2TableData, BlueprintMetadata,
     BlueprintPhysicalDiskConfig, BlueprintPhysicalDiskConfigDiff,
     BlueprintZoneConfigDiff, BlueprintZoneImageSource, ClickhouseClusterConfig,
-    CockroachDbPreserveDowngrade, PendingMgsUpdatesDiff, unwrap_or_none,
-    zone_sort_key,
+    CockroachDbPreserveDowngrade, OmicronZoneExternalIp, PendingMgsUpdatesDiff,
+    unwrap_or_none, zone_sort_key,
 };
+use chrono::{DateTime, Utc};
 use daft::{Diffable, Leaf};
 use nexus_sled_agent_shared::inventory::ZoneKind;
 use omicron_common::api::external::ByteCount;
+use omicron_common::api::internal::shared::NetworkInterface;
 use omicron_common::disk::{CompressionAlgorithm, DatasetName};
-use omicron_uuid_kinds::SledUuid;
+use omicron_uuid_kinds::{BlueprintUuid, SledUuid};
 use omicron_uuid_kinds::{DatasetUuid, OmicronZoneUuid, PhysicalDiskUuid};
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Write as _};
@@ -34,6 +36,7 @@ use crate::deployment::{
     Blueprint, BlueprintDatasetConfig, BlueprintZoneConfig,
     BlueprintZoneDisposition, CollectionDatasetIdentifier, ZoneSortKey,
 };
+use crate::inventory::Collection;
 
 // A wrapper type around a `daft` generated `BlueprintDiff that provides summary
 // data and direct access to the underlying diff.
@@ -61,6 +64,7 @@ impl<'a> BlueprintDiffSummary<'a> {
         let BlueprintDiff {
             // Fields in which changes are meaningful.
             sleds,
+            scope,
             pending_mgs_updates,
             clickhouse_cluster_config,
             target_release_minimum_generation,
@@ -96,6 +100,11 @@ impl<'a> BlueprintDiffSummary<'a> {
             return true;
         }
 
+        // Did the set of sleds the blueprint may change?
+        if scope.before != scope.after {
+            return true;
+        }
+
         // Did the clickhouse config change?
         if clickhouse_cluster_config.before != clickhouse_cluster_config.after {
             return true;
@@ -134,6 +143,22 @@ impl<'a> BlueprintDiffSummary<'a> {
             .map(|&&id| id)
     }
 
+    /// Sleds that were added, removed, or modified despite being outside the
+    /// "after" blueprint's scope
+    ///
+    /// This is always empty for a valid scoped blueprint compared against its
+    /// parent.
+    pub fn out_of_scope_sled_ids(&self) -> impl Iterator<Item = SledUuid> + '_ {
+        self.diff
+            .sleds
+            .added
+            .keys()
+            .chain(self.diff.sleds.removed.keys())
+            .map(|&&id| id)
+            .chain(self.diff.sleds.modified_keys().copied())
+            .filter(|id| !self.after.scope.includes(*id))
+    }
+
     ///  The number of zones added across all sleds
     pub fn total_zones_added(&self) -> usize {
         self.diff.sleds.added.values().fold(0, |acc, c| acc + c.zones.len())
@@ -731,6 +756,125 @@ impl BpDiffZones {
     }
 }
 
+/// The external networking resources allocated to one omicron zone
+#[derive(Debug)]
+pub struct ZoneNetworkResources {
+    pub kind: ZoneKind,
+    pub zone_id: OmicronZoneUuid,
+    pub external_ip: OmicronZoneExternalIp,
+    pub nic: NetworkInterface,
+}
+
+/// External networking resources for omicron zones on a given sled with a
+/// given `BpDiffState`
+#[derive(Debug)]
+pub struct BpDiffNetworkResourcesDetails {
+    pub zones: Vec<ZoneNetworkResources>,
+}
+
+impl BpDiffNetworkResourcesDetails {
+    /// Collect the resources of any zones in `details` that have external
+    /// networking, returning `None` if there are none.
+    pub fn new(details: BpDiffZoneDetails) -> Option<Self> {
+        let zones: Vec<_> = details
+            .zones
+            .iter()
+            .filter_map(|zone| {
+                let (external_ip, nic) =
+                    zone.zone_type.external_networking()?;
+                Some(ZoneNetworkResources {
+                    kind: zone.kind(),
+                    zone_id: zone.id,
+                    external_ip,
+                    nic: nic.clone(),
+                })
+            })
+            .collect();
+        if zones.is_empty() {
+            return None;
+        }
+        Some(BpDiffNetworkResourcesDetails { zones })
+    }
+}
+
+impl BpTableData for BpDiffNetworkResourcesDetails {
+    fn rows(&self, state: BpDiffState) -> impl Iterator<Item = BpTableRow> {
+        self.zones.iter().map(move |zone| {
+            let external_ip = match &zone.external_ip {
+                OmicronZoneExternalIp::Floating(ip) => ip.ip.to_string(),
+                OmicronZoneExternalIp::Snat(snat) => {
+                    let (first, last) = snat.snat_cfg.port_range_raw();
+                    format!("{} (ports {first}-{last})", snat.snat_cfg.ip)
+                }
+            };
+            BpTableRow::from_strings(
+                state,
+                vec![
+                    zone.kind.report_str().to_string(),
+                    zone.zone_id.to_string(),
+                    external_ip,
+                    zone.nic.ip.to_string(),
+                    zone.nic.mac.to_string(),
+                ],
+            )
+        })
+    }
+}
+
+/// External IPs and service NICs allocated to omicron zones that were added or
+/// removed, across all known sleds
+///
+/// A zone's external networking can't change while the zone exists (that's
+/// reported as an error by [`BpDiffZones`]), so resources only churn when
+/// zones come and go. Unlike the zones table, this table therefore doesn't
+/// list unchanged allocations.
+#[derive(Debug, Default)]
+pub struct BpDiffNetworkResources {
+    pub added: BTreeMap<SledUuid, BpDiffNetworkResourcesDetails>,
+    pub removed: BTreeMap<SledUuid, BpDiffNetworkResourcesDetails>,
+}
+
+impl BpDiffNetworkResources {
+    pub fn from_diff_summary(summary: &BlueprintDiffSummary<'_>) -> Self {
+        let mut diffs = BpDiffNetworkResources::default();
+        for sled_id in summary.all_sled_ids() {
+            if let Some(added) = summary
+                .added_zones(&sled_id)
+                .and_then(BpDiffNetworkResourcesDetails::new)
+            {
+                diffs.added.insert(sled_id, added);
+            }
+            if let Some(removed) = summary
+                .removed_zones(&sled_id)
+                .and_then(BpDiffNetworkResourcesDetails::new)
+            {
+                diffs.removed.insert(sled_id, removed);
+            }
+        }
+        diffs
+    }
+
+    /// Return a [`BpTable`] for the given `sled_id`
+    ///
+    /// Removed resources are listed before added ones, matching the order of
+    /// the other sled subtables.
+    pub fn to_bp_sled_subtable(&self, sled_id: &SledUuid) -> Option<BpTable> {
+        let mut rows = vec![];
+        if let Some(diff) = self.removed.get(sled_id) {
+            rows.extend(diff.rows(BpDiffState::Removed));
+        }
+        if let Some(diff) = self.added.get(sled_id) {
+            rows.extend(diff.rows(BpDiffState::Added));
+        }
+
+        if rows.is_empty() {
+            None
+        } else {
+            Some(BpTable::new(BpNetworkResourcesTableSchema {}, None, rows))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct DiffPhysicalDisksDetails {
     // Disks added, removed, or unmodified
@@ -1137,12 +1281,117 @@ impl BpTableData for BpDiffDatasetsModified {
     }
 }
 
+/// Properties of a dataset as actually reported by a sled agent in inventory
+#[derive(Debug)]
+pub struct InventoryDatasetProperties {
+    pub quota: Option<ByteCount>,
+    pub reservation: Option<ByteCount>,
+    pub compression: String,
+}
+
+/// A dataset that is unchanged in the blueprint, but whose properties in
+/// inventory differ from the blueprint's intent
+#[derive(Debug)]
+pub struct DriftedDataset {
+    pub actual_properties: InventoryDatasetProperties,
+    pub dataset: BlueprintDatasetConfig,
+}
+
+impl DriftedDataset {
+    /// Compare `dataset` to the datasets reported by its sled in inventory
+    ///
+    /// Returns `None` if `dataset` is not in service, if it is missing from
+    /// inventory, or if its quota, reservation, and compression all match.
+    pub fn from_inventory(
+        dataset: &BlueprintDatasetConfig,
+        inventory: &[crate::inventory::Dataset],
+    ) -> Option<Self> {
+        if !dataset.disposition.is_in_service() {
+            return None;
+        }
+
+        let name = DatasetName::new(dataset.pool, dataset.kind.clone());
+        let name = name.full_name();
+        let actual = inventory
+            .iter()
+            .find(|d| d.id == Some(dataset.id) || d.name == name)?;
+
+        let compression = actual.compression.parse::<CompressionAlgorithm>();
+        if actual.quota == dataset.quota
+            && actual.reservation == dataset.reservation
+            && compression.as_ref().is_ok_and(|c| *c == dataset.compression)
+        {
+            return None;
+        }
+
+        Some(Self {
+            actual_properties: InventoryDatasetProperties {
+                quota: actual.quota,
+                reservation: actual.reservation,
+                // Normalize the compression algorithm where we can, so that
+                // e.g. an empty property and "off" don't render as a change.
+                compression: compression
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|_| actual.compression.clone()),
+            },
+            dataset: dataset.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct BpDiffDatasetsDrifted {
+    pub datasets: Vec<DriftedDataset>,
+}
+
+impl BpTableData for BpDiffDatasetsDrifted {
+    fn rows(&self, state: BpDiffState) -> impl Iterator<Item = BpTableRow> {
+        self.datasets.iter().map(move |dataset| {
+            let InventoryDatasetProperties { quota, reservation, compression } =
+                &dataset.actual_properties;
+
+            // Render each drifted property as a change from its actual value
+            // to the value the blueprint intends.
+            BpTableRow::new(
+                state,
+                vec![
+                    BpTableColumn::value(
+                        DatasetName::new(
+                            dataset.dataset.pool,
+                            dataset.dataset.kind.clone(),
+                        )
+                        .full_name(),
+                    ),
+                    BpTableColumn::value(dataset.dataset.id.to_string()),
+                    BpTableColumn::value(
+                        dataset.dataset.disposition.to_string(),
+                    ),
+                    BpTableColumn::new(
+                        unwrap_or_none(quota),
+                        unwrap_or_none(&dataset.dataset.quota),
+                    ),
+                    BpTableColumn::new(
+                        unwrap_or_none(reservation),
+                        unwrap_or_none(&dataset.dataset.reservation),
+                    ),
+                    BpTableColumn::new(
+                        compression.clone(),
+                        dataset.dataset.compression.to_string(),
+                    ),
+                ],
+            )
+        })
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct BpDiffDatasets {
     pub added: BTreeMap<SledUuid, DiffDatasetsDetails>,
     pub removed: BTreeMap<SledUuid, DiffDatasetsDetails>,
     pub modified: BTreeMap<SledUuid, BpDiffDatasetsModified>,
     pub unchanged: BTreeMap<SledUuid, DiffDatasetsDetails>,
+    /// Only populated by [`BpDiffDatasets::analyze_drift()`]
+    pub drifted: BTreeMap<SledUuid, BpDiffDatasetsDrifted>,
     pub errors: BTreeMap<SledUuid, BpDiffDatasetErrors>,
 }
 
@@ -1170,6 +1419,44 @@ impl BpDiffDatasets {
         }
         diffs
     }
+
+    /// Move unchanged datasets whose actual properties in `collection` differ
+    /// from the blueprint's intent into `self.drifted`
+    ///
+    /// Datasets that are added, removed, or modified by the diff are expected
+    /// to differ from inventory until the blueprint is executed, so only
+    /// unchanged datasets are considered.
+    pub fn analyze_drift(&mut self, collection: &Collection) {
+        for (sled_id, unchanged) in &mut self.unchanged {
+            let Some(sled_agent) = collection.sled_agents.get(sled_id) else {
+                continue;
+            };
+            let mut drifted = vec![];
+            unchanged.datasets.retain(|_, dataset| {
+                match DriftedDataset::from_inventory(
+                    dataset,
+                    &sled_agent.datasets,
+                ) {
+                    Some(drifted_dataset) => {
+                        drifted.push(drifted_dataset);
+                        false
+                    }
+                    None => true,
+                }
+            });
+            if !drifted.is_empty() {
+                drifted.sort_unstable_by_key(|d| {
+                    (d.dataset.kind.clone(), d.dataset.pool)
+                });
+                self.drifted.insert(
+                    *sled_id,
+                    BpDiffDatasetsDrifted { datasets: drifted },
+                );
+            }
+        }
+        self.unchanged.retain(|_, unchanged| !unchanged.datasets.is_empty());
+    }
+
     /// Return a [`BpTable`] for the given `sled_id`
     pub fn to_bp_sled_subtable(&self, sled_id: &SledUuid) -> Option<BpTable> {
         let mut rows = vec![];
@@ -1185,6 +1472,9 @@ impl BpDiffDatasets {
         if let Some(diff) = self.added.get(sled_id) {
             rows.extend(diff.rows(BpDiffState::Added));
         }
+        if let Some(diff) = self.drifted.get(sled_id) {
+            rows.extend(diff.rows(BpDiffState::Drifted));
+        }
 
         if rows.is_empty() {
             None
@@ -1758,8 +2048,10 @@ pub struct BlueprintDiffDisplay<'diff, 'b> {
     zones: BpDiffZones,
     disks: BpDiffPhysicalDisks<'diff>,
     datasets: BpDiffDatasets,
+    network_resources: BpDiffNetworkResources,
     host_phase_2: BpDiffHostPhase2<'diff>,
     pending_mgs_updates: BpDiffPendingMgsUpdates<'diff, 'b>,
+    summary_only: bool,
 }
 
 impl<'diff, 'b> BlueprintDiffDisplay<'diff, 'b> {
@@ -1770,6 +2062,8 @@ impl<'diff, 'b> BlueprintDiffDisplay<'diff, 'b> {
         let zones = BpDiffZones::from_diff_summary(summary);
         let disks = BpDiffPhysicalDisks::from_diff_summary(summary);
         let datasets = BpDiffDatasets::from_diff_summary(summary);
+        let network_resources =
+            BpDiffNetworkResources::from_diff_summary(summary);
         let host_phase_2 = BpDiffHostPhase2::from_diff_summary(summary);
         let pending_mgs_updates =
             BpDiffPendingMgsUpdates::from_diff_summary(summary);
@@ -1780,9 +2074,81 @@ impl<'diff, 'b> BlueprintDiffDisplay<'diff, 'b> {
             zones,
             disks,
             datasets,
+            network_resources,
             host_phase_2,
             pending_mgs_updates,
+            summary_only: false,
+        }
+    }
+
+    /// Display only a summary of the diff: per-sled counts of added, removed,
+    /// and modified zones, disks, and datasets, followed by any changed
+    /// metadata.
+    ///
+    /// This is useful for large racks, where the full diff can run to
+    /// thousands of lines.
+    pub fn summary(mut self) -> Self {
+        self.summary_only = true;
+        self
+    }
+
+    /// Also flag datasets that are unchanged between the blueprints, but whose
+    /// quota, reservation, or compression in `collection` has drifted from
+    /// what the "after" blueprint intends.
+    ///
+    /// Drifted datasets are rendered with the `DRIFTED` diff state, showing
+    /// the change from each property's actual value to its intended value.
+    pub fn with_inventory_drift(mut self, collection: &Collection) -> Self {
+        self.datasets.analyze_drift(collection);
+        self
+    }
+
+    /// Iterate over sleds that are unchanged between the blueprints, but have
+    /// datasets that have drifted from inventory
+    fn unchanged_sleds_with_drift(&self) -> impl Iterator<Item = &SledUuid> {
+        self.datasets.drifted.keys().filter(|sled_id| {
+            self.summary.diff.sleds.get_unchanged(sled_id).is_some()
+        })
+    }
+
+    /// Write out the scopes of the blueprints, if either is scoped, along with
+    /// any sleds that changed outside the "after" blueprint's scope
+    fn write_scope(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let before = &self.summary.before.scope;
+        let after = &self.summary.after.scope;
+        if before.is_fleet() && after.is_fleet() {
+            return Ok(());
+        }
+
+        if before == after {
+            writeln!(
+                f,
+                " SCOPE: {after}
+"
+            )?;
+        } else {
+            writeln!(
+                f,
+                " SCOPE: {before} -> {after}
+"
+            )?;
         }
+
+        let mut out_of_scope = self.summary.out_of_scope_sled_ids().peekable();
+        if out_of_scope.peek().is_some() {
+            writeln!(
+                f,
+                " SLEDS CHANGED OUTSIDE THE SCOPE OF BLUEPRINT {}:
+",
+                self.after_meta.id
+            )?;
+            for sled_id in out_of_scope {
+                writeln!(f, "  sled {sled_id}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
     }
 
     pub fn make_metadata_diff_tables(
@@ -1843,6 +2209,19 @@ impl<'diff, 'b> BlueprintDiffDisplay<'diff, 'b> {
                     diff_row!(nexus_generation, NEXUS_GENERATION),
                 ],
             ),
+            KvList::new(
+                Some(PROVENANCE_HEADING),
+                vec![
+                    diff_row!(
+                        parent_blueprint_id,
+                        PARENT_BLUEPRINT,
+                        display_optional_blueprint_id
+                    ),
+                    diff_row!(creator, CREATED_BY),
+                    diff_row!(time_created, CREATED_AT, display_time_created),
+                    diff_row!(comment, COMMENT, display_none_if_empty),
+                ],
+            ),
         ]
     }
 
@@ -1918,7 +2297,137 @@ impl<'diff, 'b> BlueprintDiffDisplay<'diff, 'b> {
         }
     }
 
-    /// Write out disk, dataset, and zone tables for a given `sled_id`
+    /// Write out a one-line summary of the changes for a given `sled_id`
+    fn write_sled_summary(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        sled_id: &SledUuid,
+    ) -> fmt::Result {
+        let zones = (
+            self.zones.added.get(sled_id).map_or(0, |d| d.zones.len()),
+            self.zones.removed.get(sled_id).map_or(0, |d| d.zones.len()),
+            self.zones.modified.get(sled_id).map_or(0, |d| d.zones.len()),
+        );
+        let disks = (
+            self.disks.added.get(sled_id).map_or(0, |d| d.disks.len()),
+            self.disks.removed.get(sled_id).map_or(0, |d| d.disks.len()),
+            self.disks.modified.get(sled_id).map_or(0, |d| d.disks.len()),
+        );
+        let datasets = (
+            self.datasets.added.get(sled_id).map_or(0, |d| d.datasets.len()),
+            self.datasets.removed.get(sled_id).map_or(0, |d| d.datasets.len()),
+            self.datasets.modified.get(sled_id).map_or(0, |d| d.datasets.len()),
+        );
+        write!(
+            f,
+            "  sled {sled_id}: zones +{} -{} ~{}, disks +{} -{} ~{}, \
+             datasets +{} -{} ~{}",
+            zones.0,
+            zones.1,
+            zones.2,
+            disks.0,
+            disks.1,
+            disks.2,
+            datasets.0,
+            datasets.1,
+            datasets.2,
+        )?;
+        if let Some(drifted) = self.datasets.drifted.get(sled_id) {
+            write!(f, " !{}", drifted.datasets.len())?;
+        }
+        writeln!(f)
+    }
+
+    /// Write out the summary rendering of the diff
+    fn fmt_summary(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sleds = &self.summary.diff.sleds;
+
+        writeln!(
+            f,
+            "from: blueprint {}\n\
+             to:   blueprint {}\n",
+            self.before_meta.id, self.after_meta.id
+        )?;
+        self.write_scope(f)?;
+
+        // Sleds are grouped in the same order as the full rendering.
+        if !sleds.removed.is_empty() {
+            writeln!(f, " REMOVED SLEDS:\n")?;
+            for sled_id in sleds.removed.keys() {
+                self.write_sled_summary(f, sled_id)?;
+            }
+            writeln!(f)?;
+        }
+        let mut modified_iter = sleds.modified_keys().peekable();
+        if modified_iter.peek().is_some() {
+            writeln!(f, " MODIFIED SLEDS:\n")?;
+            for sled_id in modified_iter {
+                self.write_sled_summary(f, sled_id)?;
+            }
+            writeln!(f)?;
+        }
+        if !sleds.added.is_empty() {
+            writeln!(f, " ADDED SLEDS:\n")?;
+            for sled_id in sleds.added.keys() {
+                self.write_sled_summary(f, sled_id)?;
+            }
+            writeln!(f)?;
+        }
+        let mut drifted_iter = self.unchanged_sleds_with_drift().peekable();
+        if drifted_iter.peek().is_some() {
+            writeln!(f, " SLEDS WITH DRIFTED DATASETS:\n")?;
+            for sled_id in drifted_iter {
+                self.write_sled_summary(f, sled_id)?;
+            }
+            writeln!(f)?;
+        }
+
+        let num_errors = self.zones.errors.len()
+            + self.disks.errors.len()
+            + self.datasets.errors.len();
+        if num_errors > 0 {
+            writeln!(
+                f,
+                " DIFF ERRORS: {num_errors} (see the full diff for details)\n"
+            )?;
+        }
+
+        // Only write out metadata that changed.
+        for table in self
+            .make_metadata_diff_tables()
+            .into_iter()
+            .chain(self.make_oximeter_read_diff_tables())
+        {
+            if let Some(table) = table.changed_only() {
+                writeln!(f, "{table}")?;
+            }
+        }
+
+        if self.summary.diff.clickhouse_cluster_config.before
+            != self.summary.diff.clickhouse_cluster_config.after
+        {
+            writeln!(f, " CLICKHOUSE CLUSTER CONFIG: modified\n")?;
+        }
+
+        let mgs_updates = &self.summary.diff.pending_mgs_updates.by_baseboard;
+        let mgs_updates = (
+            mgs_updates.added.len(),
+            mgs_updates.removed.len(),
+            mgs_updates.modified().count(),
+        );
+        if mgs_updates != (0, 0, 0) {
+            writeln!(
+                f,
+                " PENDING MGS UPDATES: +{} -{} ~{}\n",
+                mgs_updates.0, mgs_updates.1, mgs_updates.2,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Write out disk, dataset, zone, and external networking tables for a
+    /// given `sled_id`
     fn write_tables(
         &self,
         f: &mut fmt::Formatter<'_>,
@@ -1944,12 +2453,23 @@ impl<'diff, 'b> BlueprintDiffDisplay<'diff, 'b> {
             writeln!(f, "{table}\n")?;
         }
 
+        // Write the external networking table if any zones with external
+        // networking were added or removed
+        if let Some(table) = self.network_resources.to_bp_sled_subtable(sled_id)
+        {
+            writeln!(f, "{table}\n")?;
+        }
+
         Ok(())
     }
 }
 
 impl fmt::Display for BlueprintDiffDisplay<'_, '_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.summary_only {
+            return self.fmt_summary(f);
+        }
+
         let summary = self.summary;
         let before_metadata = self.summary.before.metadata();
         let after_metadata = self.summary.after.metadata();
@@ -1960,6 +2480,7 @@ impl fmt::Display for BlueprintDiffDisplay<'_, '_> {
              to:   blueprint {}\n",
             before_metadata.id, after_metadata.id
         )?;
+        self.write_scope(f)?;
 
         // Write out sled information
         //
@@ -2072,6 +2593,30 @@ impl fmt::Display for BlueprintDiffDisplay<'_, '_> {
             }
         }
 
+        // Write out dataset tables for sleds that are otherwise unchanged, but
+        // whose datasets have drifted from inventory. (Drift on modified sleds
+        // is included in their tables above.)
+        let mut drifted_iter = self.unchanged_sleds_with_drift().peekable();
+        if drifted_iter.peek().is_some() {
+            writeln!(f, " SLEDS WITH DRIFTED DATASETS:\n")?;
+            for sled_id in drifted_iter {
+                let sled = summary
+                    .diff
+                    .sleds
+                    .get_unchanged(sled_id)
+                    .expect("sled is unchanged");
+                writeln!(
+                    f,
+                    "  sled {sled_id} ({}, config generation {}):\n",
+                    sled.state, sled.sled_agent_generation
+                )?;
+                if let Some(table) = self.datasets.to_bp_sled_subtable(sled_id)
+                {
+                    writeln!(f, "{table}\n")?;
+                }
+            }
+        }
+
         // Write out zone errors.
         if !self.zones.errors.is_empty() {
             writeln!(f, "ZONE ERRORS:")?;
@@ -2148,6 +2693,17 @@ fn display_none_if_empty(value: &str) -> &str {
     if value.is_empty() { NONE_PARENS } else { value }
 }
 
+fn display_optional_blueprint_id(value: &Option<BlueprintUuid>) -> String {
+    match value {
+        Some(id) => id.to_string(),
+        None => NONE_PARENS.to_string(),
+    }
+}
+
+fn display_time_created(value: &DateTime<Utc>) -> String {
+    humantime::format_rfc3339_millis((*value).into()).to_string()
+}
+
 fn display_optional_preserve_downgrade(
     value: &Option<CockroachDbPreserveDowngrade>,
 ) -> String {
@@ -13,6 +13,7 @@ pub mod constants {
     pub(super) const REMOVED_PREFIX: char = '-';
     pub(super) const MODIFIED_PREFIX: char = '*';
     pub(super) const UNCHANGED_PREFIX: char = ' ';
+    pub(super) const DRIFTED_PREFIX: char = '!';
 
     #[allow(unused)]
     pub(super) const SUB_NOT_LAST: &str = "├─";
@@ -28,6 +29,7 @@ pub mod constants {
     pub const COCKROACHDB_PRESERVE_DOWNGRADE: &str =
         "cluster.preserve_downgrade_option";
     pub const METADATA_HEADING: &str = "METADATA";
+    pub const PROVENANCE_HEADING: &str = "PROVENANCE";
     pub const CLICKHOUSE_CLUSTER_CONFIG_HEADING: &str =
         "CLICKHOUSE CLUSTER CONFIG";
     pub const CLICKHOUSE_MAX_USED_SERVER_ID: &str = "max used server id";
@@ -38,6 +40,7 @@ pub mod constants {
         "highest seen keeper leader committed log index";
     pub const OXIMETER_HEADING: &str = "OXIMETER SETTINGS";
     pub const OXIMETER_READ_FROM: &str = "read from";
+    pub const PARENT_BLUEPRINT: &str = "parent blueprint";
     pub const CREATED_BY: &str = "created by";
     pub const CREATED_AT: &str = "created at";
     pub const INTERNAL_DNS_VERSION: &str = "internal DNS version";
@@ -65,6 +68,9 @@ pub enum BpDiffState {
     Removed,
     Modified,
     Added,
+    /// Unchanged in the blueprint, but the resource's actual state (as
+    /// reported in inventory) differs from the blueprint's intent
+    Drifted,
 }
 
 impl BpDiffState {
@@ -74,6 +80,7 @@ impl BpDiffState {
             BpDiffState::Removed => REMOVED_PREFIX,
             BpDiffState::Modified => MODIFIED_PREFIX,
             BpDiffState::Added => ADDED_PREFIX,
+            BpDiffState::Drifted => DRIFTED_PREFIX,
         }
     }
 }
@@ -85,6 +92,7 @@ impl fmt::Display for BpDiffState {
             BpDiffState::Removed => "REMOVED",
             BpDiffState::Modified => "MODIFIED",
             BpDiffState::Added => "ADDED",
+            BpDiffState::Drifted => "DRIFTED",
         };
         write!(f, "{s}")
     }
@@ -402,6 +410,18 @@ impl BpTableSchema for BpOmicronZonesTableSchema {
     }
 }
 
+/// The [`BpTable`] schema for external networking resources (external IPs and
+/// service NICs) allocated to omicron zones
+pub struct BpNetworkResourcesTableSchema {}
+impl BpTableSchema for BpNetworkResourcesTableSchema {
+    fn table_name(&self) -> &'static str {
+        "external networking"
+    }
+    fn column_names(&self) -> &'static [&'static str] {
+        &["zone type", "zone id", "external IP", "NIC IP", "NIC MAC"]
+    }
+}
+
 /// The [`BpTable`] schema for clickhouse keepers
 pub struct BpClickhouseKeepersTableSchema {}
 impl BpTableSchema for BpClickhouseKeepersTableSchema {
@@ -527,6 +547,16 @@ impl KvList {
         KvList { heading, kv }
     }
 
+    /// Drop unchanged rows from the list, returning `None` if no rows changed.
+    pub fn changed_only(self) -> Option<KvList> {
+        let kv: Vec<_> = self
+            .kv
+            .into_iter()
+            .filter(|kv| !matches!(kv.state, BpDiffState::Unchanged))
+            .collect();
+        (!kv.is_empty()).then(|| KvList { heading: self.heading, kv })
+    }
+
     /// Compute the max width of the keys for alignment purposes
     fn max_key_width(&self) -> usize {
         self.kv.iter().fold(0, |acc, kv| usize::max(acc, kv.key.len()))
@@ -0,0 +1,172 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Types describing how much more the system can hold before running out of
+//! room for new control plane zones or customer instances.
+
+use omicron_common::api::external::ByteCount;
+use omicron_uuid_kinds::BlueprintUuid;
+use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::SledUuid;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The size of a hypothetical customer instance used to express instance
+/// headroom as a count of instances
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema,
+)]
+pub struct CapacityInstanceShape {
+    /// Number of vCPUs (each of which consumes one hardware thread)
+    pub ncpus: u32,
+    /// Guest memory (which is allocated from the sled's VMM reservoir)
+    pub memory: ByteCount,
+}
+
+impl Default for CapacityInstanceShape {
+    /// A modest general-purpose instance: 4 vCPUs and 16 GiB of memory.
+    fn default() -> Self {
+        Self { ncpus: 4, memory: ByteCount::from_gibibytes_u32(16) }
+    }
+}
+
+/// Resources already reserved by VMMs on one sled
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+)]
+pub struct SledInstanceUsage {
+    pub hardware_threads: u64,
+    pub reservoir_ram: ByteCount,
+}
+
+/// Headroom on a single sled
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct SledCapacity {
+    /// Number of in-service zpools on the sled
+    ///
+    /// A sled can run at most one zone of each kind per in-service zpool.
+    pub num_zpools: usize,
+
+    /// How many more Nexus zones could be placed on this sled, or `None` if
+    /// the sled is not eligible for new control plane zones
+    pub nexus_headroom: Option<usize>,
+
+    /// How many more CockroachDB zones could be placed on this sled, or
+    /// `None` if the sled is not eligible for new control plane zones
+    pub cockroachdb_headroom: Option<usize>,
+
+    /// Hardware threads available for instances, as last reported by the
+    /// sled's agent, or `None` if inventory has no record of the sled
+    pub usable_hardware_threads: Option<u32>,
+
+    /// Size of the sled's VMM reservoir, as last reported by the sled's agent,
+    /// or `None` if inventory has no record of the sled
+    pub reservoir_size: Option<ByteCount>,
+
+    /// Resources already reserved by instances on this sled
+    pub instance_usage: SledInstanceUsage,
+
+    /// How many more instances of the requested shape could be placed on this
+    /// sled, or `None` if the sled is not eligible for new instances or its
+    /// hardware is unknown
+    pub instance_headroom: Option<u64>,
+}
+
+/// Headroom summed across all sleds
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+)]
+pub struct RackCapacity {
+    pub nexus_headroom: usize,
+    pub cockroachdb_headroom: usize,
+    pub instance_headroom: u64,
+}
+
+/// Report of how many more control plane zones and customer instances could
+/// be placed before the system runs out of room
+///
+/// Zone headroom is computed from the same placement constraint the planner
+/// uses (one zone of a given kind per in-service zpool on sleds eligible for
+/// discretionary zones), so it reflects the planner's view of the world as of
+/// `blueprint_id`. Instance headroom is computed from the hardware reported in
+/// `collection_id` less the resources already reserved by VMMs. Neither
+/// accounts for affinity rules, so treat these numbers as upper bounds.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct CapacityReport {
+    pub blueprint_id: BlueprintUuid,
+    pub collection_id: CollectionUuid,
+    pub instance_shape: CapacityInstanceShape,
+    pub sleds: BTreeMap<SledUuid, SledCapacity>,
+    pub total: RackCapacity,
+}
+
+impl CapacityReport {
+    pub fn display(&self) -> CapacityReportDisplay<'_> {
+        CapacityReportDisplay { report: self }
+    }
+}
+
+#[derive(Debug)]
+pub struct CapacityReportDisplay<'a> {
+    report: &'a CapacityReport,
+}
+
+impl fmt::Display for CapacityReportDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = self.report;
+        let shape = &report.instance_shape;
+        writeln!(
+            f,
+            "capacity report for blueprint {} against collection {}",
+            report.blueprint_id, report.collection_id,
+        )?;
+        writeln!(
+            f,
+            "  rack-wide: {} more Nexus zones, {} more CockroachDB zones, \
+             {} more {}-vCPU/{} instances",
+            report.total.nexus_headroom,
+            report.total.cockroachdb_headroom,
+            report.total.instance_headroom,
+            shape.ncpus,
+            shape.memory,
+        )?;
+
+        let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+        for (sled_id, sled) in &report.sleds {
+            writeln!(
+                f,
+                "  sled {sled_id}: zpools {}, nexus +{}, cockroachdb +{}, \
+                 instances +{} (threads used {}/{}, reservoir used {}/{})",
+                sled.num_zpools,
+                or_dash(sled.nexus_headroom.map(|n| n.to_string())),
+                or_dash(sled.cockroachdb_headroom.map(|n| n.to_string())),
+                or_dash(sled.instance_headroom.map(|n| n.to_string())),
+                sled.instance_usage.hardware_threads,
+                or_dash(sled.usable_hardware_threads.map(|n| n.to_string())),
+                sled.instance_usage.reservoir_ram,
+                or_dash(sled.reservoir_size.map(|n| n.to_string())),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -5,9 +5,10 @@
 //! Runtime configuration for reconfigurator
 
 use std::fmt::{self, Write};
+use std::num::NonZeroU8;
 
 use chrono::{DateTime, TimeZone, Utc};
-use daft::Diffable;
+use daft::{Diffable, Leaf};
 use indent_write::fmt::IndentWriter;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -219,6 +220,32 @@ pub struct PlannerChickenSwitches {
     /// even if we've detected a recent MUPdate on the system. We will want to
     /// turn it off as part of enabling Nexus-driven update.
     pub add_zones_with_mupdate_override: bool,
+
+    /// Whether to update Nexus even if doing so would exceed the supported
+    /// version skew.
+    ///
+    /// Nexus supports running alongside host OS and SP software from its own
+    /// release or the one before it. The planner normally holds Nexus back
+    /// while any sled host OS or SP is further behind than that; this switch
+    /// allows an operator to override that when they know it's safe.
+    pub allow_version_skew: bool,
+
+    /// The number of Nexus zones to run, overriding the built-in policy.
+    ///
+    /// If unset, the planner uses the default Nexus redundancy. Raising this
+    /// adds Nexus zones; lowering it does not currently remove any. Either
+    /// way, Nexus updates temporarily run one zone more than this, since the
+    /// planner adds each updated Nexus before expunging the one it replaces.
+    pub target_nexus_zone_count: Option<NonZeroU8>,
+
+    /// The number of internal DNS zones to run, overriding the built-in
+    /// policy.
+    ///
+    /// If unset, the planner uses the default internal DNS redundancy. This
+    /// may be raised up to the number of reserved internal DNS subnets so that
+    /// larger fleets can run more DNS replicas; lowering it does not currently
+    /// remove any zones.
+    pub target_internal_dns_zone_count: Option<NonZeroU8>,
 }
 
 impl PlannerChickenSwitches {
@@ -227,7 +254,12 @@ impl PlannerChickenSwitches {
     pub fn default_for_system_description() -> Self {
         // In reconfigurator-cli we set this to false to ensure tests run
         // against the desired configuration for r17.
-        Self { add_zones_with_mupdate_override: false }
+        Self {
+            add_zones_with_mupdate_override: false,
+            allow_version_skew: false,
+            target_nexus_zone_count: None,
+            target_internal_dns_zone_count: None,
+        }
     }
 
     pub fn display(&self) -> PlannerChickenSwitchesDisplay<'_> {
@@ -239,7 +271,12 @@ impl Default for PlannerChickenSwitches {
     fn default() -> Self {
         // On customer systems for now, we don't block zone additions on mupdate
         // overrides being present.
-        Self { add_zones_with_mupdate_override: true }
+        Self {
+            add_zones_with_mupdate_override: true,
+            allow_version_skew: false,
+            target_nexus_zone_count: None,
+            target_internal_dns_zone_count: None,
+        }
     }
 }
 
@@ -249,14 +286,38 @@ impl slog::KV for PlannerChickenSwitches {
         _record: &slog::Record,
         serializer: &mut dyn slog::Serializer,
     ) -> slog::Result {
-        let Self { add_zones_with_mupdate_override } = self;
+        let Self {
+            add_zones_with_mupdate_override,
+            allow_version_skew,
+            target_nexus_zone_count,
+            target_internal_dns_zone_count,
+        } = self;
         serializer.emit_bool(
             slog::Key::from("add_zones_with_mupdate_override"),
             *add_zones_with_mupdate_override,
-        )
+        )?;
+        serializer.emit_bool(
+            slog::Key::from("allow_version_skew"),
+            *allow_version_skew,
+        )?;
+        let key = slog::Key::from("target_nexus_zone_count");
+        match target_nexus_zone_count {
+            Some(count) => serializer.emit_u8(key, count.get()),
+            None => serializer.emit_none(key),
+        }?;
+        let key = slog::Key::from("target_internal_dns_zone_count");
+        match target_internal_dns_zone_count {
+            Some(count) => serializer.emit_u8(key, count.get()),
+            None => serializer.emit_none(key),
+        }
     }
 }
 
+/// Formats a zone count switch (e.g., `target_nexus_zone_count`) for display.
+fn target_zone_count_str(count: &Option<NonZeroU8>) -> String {
+    count.map_or_else(|| "default".to_string(), |count| count.to_string())
+}
+
 pub struct PlannerChickenSwitchesDisplay<'a> {
     switches: &'a PlannerChickenSwitches,
 }
@@ -264,14 +325,34 @@ pub struct PlannerChickenSwitchesDisplay<'a> {
 impl<'a> fmt::Display for PlannerChickenSwitchesDisplay<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let Self {
-            switches: PlannerChickenSwitches { add_zones_with_mupdate_override },
+            switches:
+                PlannerChickenSwitches {
+                    add_zones_with_mupdate_override,
+                    allow_version_skew,
+                    target_nexus_zone_count,
+                    target_internal_dns_zone_count,
+                },
         } = self;
         let list = KvList::new(
             None,
-            vec![KvPair::new_unchanged(
-                "add zones with mupdate override",
-                add_zones_with_mupdate_override.to_string(),
-            )],
+            vec![
+                KvPair::new_unchanged(
+                    "add zones with mupdate override",
+                    add_zones_with_mupdate_override.to_string(),
+                ),
+                KvPair::new_unchanged(
+                    "allow version skew",
+                    allow_version_skew.to_string(),
+                ),
+                KvPair::new_unchanged(
+                    "target nexus zone count",
+                    target_zone_count_str(target_nexus_zone_count),
+                ),
+                KvPair::new_unchanged(
+                    "target internal DNS zone count",
+                    target_zone_count_str(target_internal_dns_zone_count),
+                ),
+            ],
         );
         // No need for writeln! here because KvList adds its own newlines.
         write!(f, "{list}")
@@ -291,15 +372,37 @@ pub struct PlannerChickenSwitchesDiffDisplay<'a, 'b> {
 
 impl fmt::Display for PlannerChickenSwitchesDiffDisplay<'_, '_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let PlannerChickenSwitchesDiff { add_zones_with_mupdate_override } =
-            self.diff;
+        let PlannerChickenSwitchesDiff {
+            add_zones_with_mupdate_override,
+            allow_version_skew,
+            target_nexus_zone_count,
+            target_internal_dns_zone_count,
+        } = self.diff;
+        let target_nexus_zone_count = Leaf {
+            before: target_zone_count_str(target_nexus_zone_count.before),
+            after: target_zone_count_str(target_nexus_zone_count.after),
+        };
+        let target_internal_dns_zone_count = Leaf {
+            before: target_zone_count_str(
+                target_internal_dns_zone_count.before,
+            ),
+            after: target_zone_count_str(target_internal_dns_zone_count.after),
+        };
 
         let list = KvList::new(
             None,
-            vec![diff_row!(
-                add_zones_with_mupdate_override,
-                "add zones with mupdate override"
-            )],
+            vec![
+                diff_row!(
+                    add_zones_with_mupdate_override,
+                    "add zones with mupdate override"
+                ),
+                diff_row!(allow_version_skew, "allow version skew"),
+                diff_row!(target_nexus_zone_count, "target nexus zone count"),
+                diff_row!(
+                    target_internal_dns_zone_count,
+                    "target internal DNS zone count"
+                ),
+            ],
         );
 
         // No need for writeln! here because KvList adds its own newlines.
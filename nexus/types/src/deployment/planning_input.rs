@@ -25,14 +25,17 @@ use daft::Diffable;
 use ipnetwork::IpNetwork;
 use nexus_sled_agent_shared::inventory::ZoneKind;
 use omicron_common::address::IpRange;
+use omicron_common::address::Ipv6Range;
 use omicron_common::address::Ipv6Subnet;
 use omicron_common::address::SLED_PREFIX;
+use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::Generation;
 use omicron_common::api::external::TufRepoDescription;
 use omicron_common::api::internal::shared::SourceNatConfigError;
 use omicron_common::disk::DiskIdentity;
 use omicron_common::policy::SINGLE_NODE_CLICKHOUSE_REDUNDANCY;
 use omicron_common::update::ArtifactId;
+use omicron_uuid_kinds::DatasetUuid;
 use omicron_uuid_kinds::OmicronZoneUuid;
 use omicron_uuid_kinds::PhysicalDiskUuid;
 use omicron_uuid_kinds::SledUuid;
@@ -41,6 +44,7 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::btree_map::Entry;
 use std::error;
 use std::fmt;
@@ -123,6 +127,12 @@ pub struct PlanningInput {
     /// mark under the assumption that they may appear to be impossible because
     /// they're currently in progress.
     ignore_impossible_mgs_updates_since: DateTime<Utc>,
+
+    /// quotas proposed for individual datasets based on their observed usage
+    ///
+    /// The planner applies these to the corresponding in-service datasets.
+    #[serde(default)]
+    dataset_quotas: BTreeMap<DatasetUuid, ByteCount>,
 }
 
 impl PlanningInput {
@@ -145,12 +155,28 @@ impl PlanningInput {
         self.policy.target_boundary_ntp_zone_count
     }
 
+    /// Returns the desired number of Nexus zones: the value of the
+    /// `target_nexus_zone_count` chicken switch if it's set, and the policy's
+    /// otherwise.
     pub fn target_nexus_zone_count(&self) -> usize {
-        self.policy.target_nexus_zone_count
+        self.policy
+            .chicken_switches
+            .target_nexus_zone_count
+            .map_or(self.policy.target_nexus_zone_count, |count| {
+                usize::from(count.get())
+            })
     }
 
+    /// Returns the desired number of internal DNS zones: the value of the
+    /// `target_internal_dns_zone_count` chicken switch if it's set, and the
+    /// policy's otherwise.
     pub fn target_internal_dns_zone_count(&self) -> usize {
-        self.policy.target_internal_dns_zone_count
+        self.policy
+            .chicken_switches
+            .target_internal_dns_zone_count
+            .map_or(self.policy.target_internal_dns_zone_count, |count| {
+                usize::from(count.get())
+            })
     }
 
     pub fn target_oximeter_zone_count(&self) -> usize {
@@ -213,6 +239,14 @@ impl PlanningInput {
         &self.policy.service_ip_pool_ranges
     }
 
+    pub fn reserved_underlay_ranges(&self) -> &[Ipv6Range] {
+        &self.policy.reserved_underlay_ranges
+    }
+
+    pub fn maintenance_cohort(&self) -> &BTreeSet<SledUuid> {
+        &self.policy.maintenance_cohort
+    }
+
     pub fn clickhouse_cluster_enabled(&self) -> bool {
         let Some(clickhouse_policy) = &self.policy.clickhouse_policy else {
             return false;
@@ -304,6 +338,11 @@ impl PlanningInput {
         self.ignore_impossible_mgs_updates_since
     }
 
+    /// quotas proposed for individual datasets based on their observed usage
+    pub fn dataset_quotas(&self) -> &BTreeMap<DatasetUuid, ByteCount> {
+        &self.dataset_quotas
+    }
+
     /// Convert this `PlanningInput` back into a [`PlanningInputBuilder`]
     ///
     /// This is primarily useful for tests that want to mutate an existing
@@ -318,6 +357,7 @@ impl PlanningInput {
             network_resources: self.network_resources,
             ignore_impossible_mgs_updates_since: self
                 .ignore_impossible_mgs_updates_since,
+            dataset_quotas: self.dataset_quotas,
         }
     }
 }
@@ -902,6 +942,21 @@ impl SledState {
                 SledFilter::TufArtifactReplication => true,
                 SledFilter::SpsUpdatedByReconfigurator => true,
             },
+            // Quiesced sleds keep everything they're already running (and
+            // stay reachable), but nothing new is placed on them.
+            SledState::Quiesced => match filter {
+                SledFilter::All => true,
+                SledFilter::Commissioned => true,
+                SledFilter::Decommissioned => false,
+                SledFilter::Discretionary => false,
+                SledFilter::InService => true,
+                SledFilter::QueryDuringInventory => true,
+                SledFilter::ReservationCreate => false,
+                SledFilter::VpcRouting => true,
+                SledFilter::VpcFirewall => true,
+                SledFilter::TufArtifactReplication => true,
+                SledFilter::SpsUpdatedByReconfigurator => true,
+            },
             SledState::Decommissioned => match filter {
                 SledFilter::All => true,
                 SledFilter::Commissioned => false,
@@ -950,16 +1005,34 @@ pub struct Policy {
     /// services (e.g., external DNS, Nexus, boundary NTP)
     pub service_ip_pool_ranges: Vec<IpRange>,
 
+    /// ranges of underlay IPv6 addresses that must not be allocated to new
+    /// zones (e.g., because they've been set aside for future services)
+    ///
+    /// Blueprints must not place any running zone within these ranges.
+    #[serde(default)]
+    pub reserved_underlay_ranges: Vec<Ipv6Range>,
+
+    /// sleds to be drained of discretionary zones together (e.g., all the
+    /// sleds in one half of a rack, ahead of physical maintenance)
+    ///
+    /// New discretionary zones are never placed on these sleds, and the
+    /// planner gradually moves the ones already there to other sleds.
+    #[serde(default)]
+    pub maintenance_cohort: BTreeSet<SledUuid>,
+
     /// desired total number of deployed Boundary NTP zones
     pub target_boundary_ntp_zone_count: usize,
 
-    /// desired total number of deployed Nexus zones
+    /// desired total number of deployed Nexus zones, unless overridden by
+    /// [`PlannerChickenSwitches::target_nexus_zone_count`]
     pub target_nexus_zone_count: usize,
 
-    /// desired total number of internal DNS zones.
-    /// Must be <= [`omicron_common::policy::INTERNAL_DNS_REDUNDANCY`]; we
-    /// expect it to be exactly equal in general (i.e., we should be running an
-    /// internal DNS server on each of the expected reserved addresses).
+    /// desired total number of internal DNS zones, unless overridden by
+    /// [`PlannerChickenSwitches::target_internal_dns_zone_count`].
+    /// Must be <= [`omicron_common::policy::RESERVED_INTERNAL_DNS_REDUNDANCY`]
+    /// and should be at least
+    /// [`omicron_common::policy::INTERNAL_DNS_REDUNDANCY`] (i.e., we should be
+    /// running an internal DNS server on each of the well-known addresses).
     pub target_internal_dns_zone_count: usize,
 
     /// desired total number of deployed Oximeter zones
@@ -1260,6 +1333,7 @@ pub struct PlanningInputBuilder {
     sleds: BTreeMap<SledUuid, SledDetails>,
     network_resources: OmicronZoneNetworkResources,
     ignore_impossible_mgs_updates_since: DateTime<Utc>,
+    dataset_quotas: BTreeMap<DatasetUuid, ByteCount>,
 }
 
 impl PlanningInputBuilder {
@@ -1268,6 +1342,8 @@ impl PlanningInputBuilder {
         PlanningInput {
             policy: Policy {
                 service_ip_pool_ranges: Vec::new(),
+                reserved_underlay_ranges: Vec::new(),
+                maintenance_cohort: BTreeSet::new(),
                 target_boundary_ntp_zone_count: 0,
                 target_nexus_zone_count: 0,
                 target_internal_dns_zone_count: 0,
@@ -1288,6 +1364,7 @@ impl PlanningInputBuilder {
             sleds: BTreeMap::new(),
             network_resources: OmicronZoneNetworkResources::new(),
             ignore_impossible_mgs_updates_since: Utc::now(),
+            dataset_quotas: BTreeMap::new(),
         }
     }
 
@@ -1306,6 +1383,7 @@ impl PlanningInputBuilder {
             network_resources: OmicronZoneNetworkResources::new(),
             ignore_impossible_mgs_updates_since: Utc::now()
                 - MGS_UPDATE_SETTLE_TIMEOUT,
+            dataset_quotas: BTreeMap::new(),
         }
     }
 
@@ -1329,7 +1407,8 @@ impl PlanningInputBuilder {
     ///
     /// In the real code, the `PlanningInput` comes from the database. In this
     /// case all disks in a sled are marked expunged when the sled is expunged
-    /// inside a transaction. We do the same thing here for testing purposes.
+    /// inside a transaction. We do the same thing here for testing purposes,
+    /// and to preview what the planner would do if a sled were expunged.
     pub fn expunge_sled(
         &mut self,
         sled_id: &SledUuid,
@@ -1401,6 +1480,13 @@ impl PlanningInputBuilder {
         self.cockroachdb_settings = cockroachdb_settings;
     }
 
+    pub fn set_dataset_quotas(
+        &mut self,
+        dataset_quotas: BTreeMap<DatasetUuid, ByteCount>,
+    ) {
+        self.dataset_quotas = dataset_quotas;
+    }
+
     pub fn build(self) -> PlanningInput {
         PlanningInput {
             policy: self.policy,
@@ -1411,6 +1497,7 @@ impl PlanningInputBuilder {
             network_resources: self.network_resources,
             ignore_impossible_mgs_updates_since: self
                 .ignore_impossible_mgs_updates_since,
+            dataset_quotas: self.dataset_quotas,
         }
     }
 }
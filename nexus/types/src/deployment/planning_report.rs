@@ -63,6 +63,7 @@ pub struct PlanningReport {
     pub mgs_updates: PlanningMgsUpdatesStepReport,
     pub add: PlanningAddStepReport,
     pub zone_updates: PlanningZoneUpdatesStepReport,
+    pub maintenance_cohort: PlanningMaintenanceCohortStepReport,
     pub cockroachdb_settings: PlanningCockroachdbSettingsStepReport,
 }
 
@@ -79,6 +80,7 @@ impl PlanningReport {
             ),
             add: PlanningAddStepReport::new(),
             zone_updates: PlanningZoneUpdatesStepReport::new(),
+            maintenance_cohort: PlanningMaintenanceCohortStepReport::new(),
             cockroachdb_settings: PlanningCockroachdbSettingsStepReport::new(),
         }
     }
@@ -90,6 +92,7 @@ impl PlanningReport {
             && self.mgs_updates.is_empty()
             && self.add.is_empty()
             && self.zone_updates.is_empty()
+            && self.maintenance_cohort.is_empty()
             && self.cockroachdb_settings.is_empty()
     }
 }
@@ -112,6 +115,7 @@ impl fmt::Display for PlanningReport {
                 mgs_updates,
                 add,
                 zone_updates,
+                maintenance_cohort,
                 cockroachdb_settings,
             } = self;
             writeln!(f, "planning report for blueprint {blueprint_id}:")?;
@@ -128,6 +132,7 @@ impl fmt::Display for PlanningReport {
             mgs_updates.fmt(f)?;
             add.fmt(f)?;
             zone_updates.fmt(f)?;
+            maintenance_cohort.fmt(f)?;
             cockroachdb_settings.fmt(f)?;
         }
         Ok(())
@@ -140,21 +145,40 @@ impl fmt::Display for PlanningReport {
 pub struct PlanningExpungeStepReport {
     /// Expunged disks not present in the parent blueprint.
     pub orphan_disks: BTreeMap<SledUuid, PhysicalDiskUuid>,
+
+    /// Zones expunged during this step, and why.
+    pub expunged_zones: BTreeMap<SledUuid, Vec<PlanningExpungedZone>>,
 }
 
 impl PlanningExpungeStepReport {
     pub fn new() -> Self {
-        Self { orphan_disks: BTreeMap::new() }
+        Self { orphan_disks: BTreeMap::new(), expunged_zones: BTreeMap::new() }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.orphan_disks.is_empty()
+        self.orphan_disks.is_empty() && self.expunged_zones.is_empty()
+    }
+
+    pub fn expunged_zone(
+        &mut self,
+        sled_id: SledUuid,
+        zone_config: &BlueprintZoneConfig,
+        reason: ZoneExpungeReason,
+    ) {
+        let expunged = PlanningExpungedZone {
+            zone_config: zone_config.to_owned(),
+            reason,
+        };
+        self.expunged_zones
+            .entry(sled_id)
+            .and_modify(|zones| zones.push(expunged.clone()))
+            .or_insert_with(|| vec![expunged]);
     }
 }
 
 impl fmt::Display for PlanningExpungeStepReport {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let Self { orphan_disks } = self;
+        let Self { orphan_disks, expunged_zones } = self;
         if !orphan_disks.is_empty() {
             writeln!(
                 f,
@@ -165,10 +189,75 @@ impl fmt::Display for PlanningExpungeStepReport {
                 writeln!(f, "  * sled {sled}, disk {disk}",)?;
             }
         }
+        if !expunged_zones.is_empty() {
+            writeln!(f, "* zones expunged:")?;
+            for (sled_id, zones) in expunged_zones.iter() {
+                // Zones are usually expunged in bulk (e.g., every zone on an
+                // expunged sled), so summarize them by reason.
+                let mut by_reason: BTreeMap<String, usize> = BTreeMap::new();
+                for zone in zones {
+                    *by_reason.entry(zone.reason.to_string()).or_default() += 1;
+                }
+                for (reason, n) in by_reason {
+                    let s = plural(n);
+                    writeln!(f, "  * {n} zone{s} on sled {sled_id}: {reason}")?;
+                }
+            }
+        }
         Ok(())
     }
 }
 
+/// A zone expunged by the planner's expunge step.
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
+)]
+pub struct PlanningExpungedZone {
+    pub zone_config: BlueprintZoneConfig,
+    pub reason: ZoneExpungeReason,
+}
+
+/// Why the planner expunged a zone.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Diffable,
+    JsonSchema,
+)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ZoneExpungeReason {
+    /// The operator expunged the zone's sled.
+    SledExpunged,
+    /// The operator expunged a disk the zone depends on.
+    DiskExpunged { disk_id: PhysicalDiskUuid },
+    /// Policy no longer calls for a multinode ClickHouse cluster.
+    ClickhouseClusterDisabled,
+    /// Policy no longer calls for single-node ClickHouse.
+    ClickhouseSingleNodeDisabled,
+}
+
+impl fmt::Display for ZoneExpungeReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SledExpunged => write!(f, "sled expunged"),
+            Self::DiskExpunged { disk_id } => {
+                write!(f, "disk {disk_id} expunged")
+            }
+            Self::ClickhouseClusterDisabled => {
+                write!(f, "clickhouse cluster disabled via policy")
+            }
+            Self::ClickhouseSingleNodeDisabled => {
+                write!(f, "clickhouse single-node disabled via policy")
+            }
+        }
+    }
+}
+
 #[derive(
     Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
 )]
@@ -811,6 +900,16 @@ pub struct PlanningOutOfDateZone {
     pub desired_image_source: BlueprintZoneImageSource,
 }
 
+/// An out-of-date zone whose replacement was added before the zone itself is
+/// expunged.
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
+)]
+pub struct PlanningSurgedZone {
+    pub zone_config: BlueprintZoneConfig,
+    pub replacement_sled_id: SledUuid,
+}
+
 #[derive(
     Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
 )]
@@ -821,7 +920,20 @@ pub struct PlanningZoneUpdatesStepReport {
     pub out_of_date_zones: BTreeMap<SledUuid, Vec<PlanningOutOfDateZone>>,
     pub expunged_zones: BTreeMap<SledUuid, Vec<BlueprintZoneConfig>>,
     pub updated_zones: BTreeMap<SledUuid, Vec<BlueprintZoneConfig>>,
+
+    /// Out-of-date zones that stay in service until the replacement added for
+    /// them shows up in inventory.
+    pub surged_zones: BTreeMap<SledUuid, Vec<PlanningSurgedZone>>,
+
     pub unsafe_zones: BTreeMap<BlueprintZoneConfig, ZoneUnsafeToShutdown>,
+
+    /// Components running software further behind the target release than
+    /// Nexus supports. While any are present, Nexus zones are not updated.
+    pub version_skew: Vec<VersionSkewViolation>,
+
+    /// The value of the homonymous chicken switch. (What this really means is
+    /// that Nexus zones are updated despite any `version_skew` violations.)
+    pub allow_version_skew: bool,
 }
 
 impl PlanningZoneUpdatesStepReport {
@@ -831,7 +943,10 @@ impl PlanningZoneUpdatesStepReport {
             out_of_date_zones: BTreeMap::new(),
             expunged_zones: BTreeMap::new(),
             updated_zones: BTreeMap::new(),
+            surged_zones: BTreeMap::new(),
             unsafe_zones: BTreeMap::new(),
+            version_skew: Vec::new(),
+            allow_version_skew: false,
         }
     }
 
@@ -846,7 +961,9 @@ impl PlanningZoneUpdatesStepReport {
             && self.out_of_date_zones.is_empty()
             && self.expunged_zones.is_empty()
             && self.updated_zones.is_empty()
+            && self.surged_zones.is_empty()
             && self.unsafe_zones.is_empty()
+            && self.version_skew.is_empty()
     }
 
     pub fn out_of_date_zone(
@@ -887,12 +1004,20 @@ impl PlanningZoneUpdatesStepReport {
             .or_insert_with(|| vec![zone_config.to_owned()]);
     }
 
-    pub fn unsafe_zone(
+    pub fn surged_zone(
         &mut self,
-        zone: &BlueprintZoneConfig,
-        reason: ZoneUnsafeToShutdown,
+        sled_id: SledUuid,
+        zone_config: &BlueprintZoneConfig,
+        replacement_sled_id: SledUuid,
     ) {
-        self.unsafe_zones.insert(zone.clone(), reason);
+        let surged = PlanningSurgedZone {
+            zone_config: zone_config.to_owned(),
+            replacement_sled_id,
+        };
+        self.surged_zones
+            .entry(sled_id)
+            .and_modify(|zones| zones.push(surged.clone()))
+            .or_insert_with(|| vec![surged]);
     }
 }
 
@@ -903,7 +1028,10 @@ impl fmt::Display for PlanningZoneUpdatesStepReport {
             out_of_date_zones,
             expunged_zones,
             updated_zones,
+            surged_zones,
             unsafe_zones,
+            version_skew,
+            allow_version_skew,
         } = self;
 
         if let Some(waiting_on) = waiting_on {
@@ -942,6 +1070,27 @@ impl fmt::Display for PlanningZoneUpdatesStepReport {
             }
         }
 
+        if !surged_zones.is_empty() {
+            let (n, s) = plural_map_of_vec(surged_zones);
+            writeln!(
+                f,
+                "* {n} out-of-date zone{s} kept in service while \
+                   replacements start:"
+            )?;
+            for (sled_id, zones) in surged_zones.iter() {
+                for zone in zones {
+                    writeln!(
+                        f,
+                        "  * sled {}, zone {} ({}): replacement on sled {}",
+                        sled_id,
+                        zone.zone_config.id,
+                        zone.zone_config.zone_type.kind().report_str(),
+                        zone.replacement_sled_id,
+                    )?;
+                }
+            }
+        }
+
         if !out_of_date_zones.is_empty() {
             let (n, s) = plural_map_of_vec(out_of_date_zones);
             writeln!(f, "* {n} remaining out-of-date zone{s}")?;
@@ -961,6 +1110,27 @@ impl fmt::Display for PlanningZoneUpdatesStepReport {
             }
         }
 
+        if !version_skew.is_empty() {
+            let (n, s) = plural_vec(version_skew);
+            if *allow_version_skew {
+                writeln!(
+                    f,
+                    "* {n} component{s} exceed supported version skew; \
+                       updating Nexus anyway, as specified by the \
+                       `allow_version_skew` chicken switch:"
+                )?;
+            } else {
+                writeln!(
+                    f,
+                    "* {n} component{s} exceed supported version skew; \
+                       Nexus updates are blocked:"
+                )?;
+            }
+            for violation in version_skew {
+                writeln!(f, "  * {violation}")?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -992,12 +1162,42 @@ impl ZoneUpdatesWaitingOn {
     }
 }
 
+/// A component running software from neither the target release nor the
+/// release before it.
+///
+/// Nexus only supports running alongside host OS and SP software that is at
+/// most one release behind it.
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
+)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum VersionSkewViolation {
+    /// An SP's active slot contains a version not found in either release.
+    Sp { serial_number: String, version: String },
+    /// A sled booted a host OS image not found in either release.
+    HostOs { sled_id: SledUuid, hash: ArtifactHash },
+}
+
+impl fmt::Display for VersionSkewViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sp { serial_number, version } => {
+                write!(f, "SP {serial_number} is running version {version}")
+            }
+            Self::HostOs { sled_id, hash } => {
+                write!(f, "sled {sled_id} is running host OS {hash}")
+            }
+        }
+    }
+}
+
 #[derive(
     Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
 )]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum ZoneUnsafeToShutdown {
     Cockroachdb { reason: CockroachdbUnsafeToShutdown },
+    ClickhouseKeeper { reason: ClickhouseKeeperUnsafeToShutdown },
     BoundaryNtp { total_boundary_ntp_zones: usize, synchronized_count: usize },
     InternalDns { total_internal_dns_zones: usize, synchronized_count: usize },
 }
@@ -1006,6 +1206,7 @@ impl fmt::Display for ZoneUnsafeToShutdown {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Cockroachdb { reason } => write!(f, "{reason}"),
+            Self::ClickhouseKeeper { reason } => write!(f, "{reason}"),
             Self::BoundaryNtp {
                 total_boundary_ntp_zones: t,
                 synchronized_count: s,
@@ -1058,6 +1259,216 @@ impl fmt::Display for CockroachdbUnsafeToShutdown {
     }
 }
 
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
+)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ClickhouseKeeperUnsafeToShutdown {
+    MissingMembership,
+    MembershipChangeInProgress,
+}
+
+impl fmt::Display for ClickhouseKeeperUnsafeToShutdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingMembership => {
+                write!(f, "no keeper cluster membership in inventory")
+            }
+            Self::MembershipChangeInProgress => write!(
+                f,
+                "keeper cluster membership does not yet match the blueprint"
+            ),
+        }
+    }
+}
+
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
+)]
+pub struct PlanningMaintenanceCohortStepReport {
+    /// What are we waiting on to continue draining the maintenance cohort?
+    pub waiting_on: Option<MaintenanceCohortWaitingOn>,
+
+    /// Zones expunged from sleds in the maintenance cohort.
+    pub expunged_zones: BTreeMap<SledUuid, Vec<BlueprintZoneConfig>>,
+
+    /// Discretionary zone kind → why zones of that kind can't be moved off
+    /// of the maintenance cohort right now
+    pub blocked_zone_kinds: BTreeMap<String, MaintenanceCohortZoneKindBlocked>,
+
+    pub unsafe_zones: BTreeMap<BlueprintZoneConfig, ZoneUnsafeToShutdown>,
+}
+
+impl PlanningMaintenanceCohortStepReport {
+    pub fn new() -> Self {
+        Self {
+            waiting_on: None,
+            expunged_zones: BTreeMap::new(),
+            blocked_zone_kinds: BTreeMap::new(),
+            unsafe_zones: BTreeMap::new(),
+        }
+    }
+
+    pub fn waiting_on(waiting_on: MaintenanceCohortWaitingOn) -> Self {
+        let mut new = Self::new();
+        new.waiting_on = Some(waiting_on);
+        new
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiting_on.is_none()
+            && self.expunged_zones.is_empty()
+            && self.blocked_zone_kinds.is_empty()
+            && self.unsafe_zones.is_empty()
+    }
+
+    pub fn expunged_zone(
+        &mut self,
+        sled_id: SledUuid,
+        zone_config: &BlueprintZoneConfig,
+    ) {
+        self.expunged_zones
+            .entry(sled_id)
+            .and_modify(|zones| zones.push(zone_config.to_owned()))
+            .or_insert_with(|| vec![zone_config.to_owned()]);
+    }
+
+    pub fn blocked_zone_kind(
+        &mut self,
+        zone_kind: &str,
+        reason: MaintenanceCohortZoneKindBlocked,
+    ) {
+        self.blocked_zone_kinds.insert(zone_kind.to_owned(), reason);
+    }
+}
+
+impl fmt::Display for PlanningMaintenanceCohortStepReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self {
+            waiting_on,
+            expunged_zones,
+            blocked_zone_kinds,
+            unsafe_zones,
+        } = self;
+
+        if let Some(waiting_on) = waiting_on {
+            writeln!(
+                f,
+                "* maintenance cohort drain waiting on {}",
+                waiting_on.as_str()
+            )?;
+        }
+
+        if !expunged_zones.is_empty() {
+            let (n, s) = plural_map_of_vec(expunged_zones);
+            writeln!(f, "* {n} zone{s} expunged from the maintenance cohort:")?;
+            for (sled_id, zones) in expunged_zones.iter() {
+                for zone in zones {
+                    writeln!(
+                        f,
+                        "  * sled {}, zone {} ({})",
+                        sled_id,
+                        zone.id,
+                        zone.zone_type.kind().report_str(),
+                    )?;
+                }
+            }
+        }
+
+        if !blocked_zone_kinds.is_empty() {
+            writeln!(
+                f,
+                "* zones that can't be moved off of the maintenance cohort:"
+            )?;
+            for (kind, reason) in blocked_zone_kinds.iter() {
+                writeln!(f, "  * {kind}: {reason}")?;
+            }
+        }
+
+        if !unsafe_zones.is_empty() {
+            let (n, s) = plural_map(unsafe_zones);
+            writeln!(f, "* {n} zone{s} not ready to shut down safely:")?;
+            for (zone, reason) in unsafe_zones.iter() {
+                writeln!(
+                    f,
+                    "  * zone {} ({}): {}",
+                    zone.id,
+                    zone.zone_type.kind().report_str(),
+                    reason,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
+)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum MaintenanceCohortWaitingOn {
+    /// Waiting on discretionary zone placement.
+    DiscretionaryZones,
+
+    /// Waiting on updates to RoT / SP / Host OS / etc.
+    PendingMgsUpdates,
+
+    /// Waiting on zone updates.
+    ZoneUpdates,
+
+    /// Waiting on inventory to show that every in-service zone is running.
+    ZoneReconciliation,
+}
+
+impl MaintenanceCohortWaitingOn {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DiscretionaryZones => "discretionary zones",
+            Self::PendingMgsUpdates => {
+                "pending MGS updates (RoT / SP / Host OS / etc.)"
+            }
+            Self::ZoneUpdates => "zone updates",
+            Self::ZoneReconciliation => "zones to be running",
+        }
+    }
+}
+
+/// Why zones of a given kind can't currently be removed from the maintenance
+/// cohort.
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
+)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum MaintenanceCohortZoneKindBlocked {
+    /// Removing a zone would leave fewer in-service zones of this kind than
+    /// the policy requires.
+    ///
+    /// `num_in_service` only counts zones that would remain after removing
+    /// one from the cohort.
+    InsufficientRedundancy { target_count: usize, num_in_service: usize },
+
+    /// There's no sled outside the cohort that could host a replacement.
+    NoEligibleSled,
+}
+
+impl fmt::Display for MaintenanceCohortZoneKindBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InsufficientRedundancy { target_count, num_in_service } => {
+                write!(
+                    f,
+                    "only {num_in_service} of {target_count} zones would \
+                     remain in service"
+                )
+            }
+            Self::NoEligibleSled => {
+                write!(f, "no sled outside the cohort can host a replacement")
+            }
+        }
+    }
+}
+
 #[derive(
     Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Diffable, JsonSchema,
 )]
@@ -0,0 +1,260 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Stable, self-describing on-disk format for transporting a single
+//! [`Blueprint`]
+//!
+//! This is intended for moving blueprints between systems (e.g., from a
+//! customer rack to an offline `reconfigurator-cli` session) without relying
+//! on database dumps. The format is:
+//!
+//! * a single line of JSON containing a [`PortableBlueprintHeader`], which
+//!   identifies the format and its version and carries the length and SHA-256
+//!   checksum of the payload, followed by
+//! * a newline, followed by
+//! * the payload: the JSON-serialized blueprint.
+//!
+//! The checksum is computed over the exact payload bytes, so a file whose
+//! payload has been modified (or truncated) in transit is rejected rather than
+//! silently misinterpreted.
+
+use super::Blueprint;
+use omicron_uuid_kinds::BlueprintUuid;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+/// Value of [`PortableBlueprintHeader::format`] for portable blueprints
+pub const PORTABLE_BLUEPRINT_FORMAT: &str = "omicron-portable-blueprint";
+
+/// Current version of the portable blueprint format
+///
+/// This must be bumped whenever a change to [`Blueprint`] means that older
+/// software can no longer correctly deserialize the payload.
+pub const PORTABLE_BLUEPRINT_FORMAT_VERSION: u32 = 1;
+
+/// Header line preceding the payload of a portable blueprint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortableBlueprintHeader {
+    /// Always [`PORTABLE_BLUEPRINT_FORMAT`]
+    pub format: String,
+    /// Version of the format used to write the payload
+    pub format_version: u32,
+    /// ID of the blueprint contained in the payload
+    pub blueprint_id: BlueprintUuid,
+    /// Length of the payload, in bytes
+    pub payload_len: u64,
+    /// Hex-encoded SHA-256 checksum of the payload
+    pub payload_sha256: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PortableBlueprintError {
+    #[error("missing portable blueprint header line")]
+    MissingHeader,
+    #[error("failed to parse portable blueprint header")]
+    InvalidHeader(#[source] serde_json::Error),
+    #[error(
+        "not a portable blueprint (format {found:?}, expected \
+         {PORTABLE_BLUEPRINT_FORMAT:?})"
+    )]
+    WrongFormat { found: String },
+    #[error(
+        "unsupported portable blueprint format version {found} (this \
+         software supports version {PORTABLE_BLUEPRINT_FORMAT_VERSION})"
+    )]
+    UnsupportedVersion { found: u32 },
+    #[error(
+        "payload length mismatch (header says {expected} bytes, found \
+         {found} bytes)"
+    )]
+    LengthMismatch { expected: u64, found: u64 },
+    #[error(
+        "payload checksum mismatch (header says {expected}, computed \
+         {computed})"
+    )]
+    ChecksumMismatch { expected: String, computed: String },
+    #[error("failed to serialize blueprint")]
+    Serialize(#[source] serde_json::Error),
+    #[error("failed to deserialize blueprint payload")]
+    Deserialize(#[source] serde_json::Error),
+    #[error(
+        "blueprint ID mismatch (header says {expected}, payload contains \
+         {found})"
+    )]
+    IdMismatch { expected: BlueprintUuid, found: BlueprintUuid },
+}
+
+impl Blueprint {
+    /// Serialize this blueprint in a stable, self-describing on-disk format
+    /// suitable for transporting it to another system.
+    ///
+    /// The output is a single-line [`PortableBlueprintHeader`] followed by
+    /// the JSON-serialized blueprint.
+    pub fn to_portable_bytes(&self) -> Result<Vec<u8>, PortableBlueprintError> {
+        let payload = serde_json::to_vec_pretty(self)
+            .map_err(PortableBlueprintError::Serialize)?;
+        let header = PortableBlueprintHeader {
+            format: PORTABLE_BLUEPRINT_FORMAT.to_string(),
+            format_version: PORTABLE_BLUEPRINT_FORMAT_VERSION,
+            blueprint_id: self.id,
+            payload_len: payload.len() as u64,
+            payload_sha256: hex::encode(Sha256::digest(&payload)),
+        };
+        // `to_vec` (unlike `to_vec_pretty`) never emits newlines, so the
+        // header is guaranteed to be a single line.
+        let mut out = serde_json::to_vec(&header)
+            .map_err(PortableBlueprintError::Serialize)?;
+        out.push(b'\n');
+        out.extend_from_slice(&payload);
+        Ok(out)
+    }
+
+    /// Deserialize a blueprint written by [`Blueprint::to_portable_bytes()`],
+    /// verifying its format version and checksum.
+    pub fn from_portable_bytes(
+        bytes: &[u8],
+    ) -> Result<Blueprint, PortableBlueprintError> {
+        let (header, payload) = Self::portable_header(bytes)?;
+
+        let found = payload.len() as u64;
+        if found != header.payload_len {
+            return Err(PortableBlueprintError::LengthMismatch {
+                expected: header.payload_len,
+                found,
+            });
+        }
+        let computed = hex::encode(Sha256::digest(payload));
+        if !computed.eq_ignore_ascii_case(&header.payload_sha256) {
+            return Err(PortableBlueprintError::ChecksumMismatch {
+                expected: header.payload_sha256,
+                computed,
+            });
+        }
+
+        let blueprint: Blueprint = serde_json::from_slice(payload)
+            .map_err(PortableBlueprintError::Deserialize)?;
+        if blueprint.id != header.blueprint_id {
+            return Err(PortableBlueprintError::IdMismatch {
+                expected: header.blueprint_id,
+                found: blueprint.id,
+            });
+        }
+        Ok(blueprint)
+    }
+
+    /// Parse and validate the header of a portable blueprint, returning it
+    /// along with the (unverified) payload that follows it.
+    ///
+    /// This is useful for cheaply checking whether some bytes are a portable
+    /// blueprint at all.
+    pub fn portable_header(
+        bytes: &[u8],
+    ) -> Result<(PortableBlueprintHeader, &[u8]), PortableBlueprintError> {
+        let newline = bytes
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or(PortableBlueprintError::MissingHeader)?;
+        let (header, payload) = (&bytes[..newline], &bytes[newline + 1..]);
+
+        let header: PortableBlueprintHeader = serde_json::from_slice(header)
+            .map_err(PortableBlueprintError::InvalidHeader)?;
+        if header.format != PORTABLE_BLUEPRINT_FORMAT {
+            return Err(PortableBlueprintError::WrongFormat {
+                found: header.format,
+            });
+        }
+        if header.format_version != PORTABLE_BLUEPRINT_FORMAT_VERSION {
+            return Err(PortableBlueprintError::UnsupportedVersion {
+                found: header.format_version,
+            });
+        }
+        Ok((header, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_bytes(
+        header: &PortableBlueprintHeader,
+        payload: &[u8],
+    ) -> Vec<u8> {
+        let mut bytes = serde_json::to_vec(header).unwrap();
+        bytes.push(b'\n');
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    // Round-trip tests of real blueprints live alongside the example system
+    // in nexus-reconfigurator-planning; these only cover header validation.
+    #[test]
+    fn test_portable_header_validation() {
+        let payload = b"{}";
+        let header = PortableBlueprintHeader {
+            format: PORTABLE_BLUEPRINT_FORMAT.to_string(),
+            format_version: PORTABLE_BLUEPRINT_FORMAT_VERSION,
+            blueprint_id: BlueprintUuid::new_v4(),
+            payload_len: payload.len() as u64,
+            payload_sha256: hex::encode(Sha256::digest(payload)),
+        };
+
+        let (parsed, parsed_payload) =
+            Blueprint::portable_header(&header_bytes(&header, payload))
+                .expect("valid header");
+        assert_eq!(parsed, header);
+        assert_eq!(parsed_payload, payload);
+
+        // Plain JSON has no header line.
+        let err = Blueprint::portable_header(b"{\"id\": 1}").unwrap_err();
+        assert!(
+            matches!(err, PortableBlueprintError::MissingHeader),
+            "unexpected error: {err}"
+        );
+
+        let err = Blueprint::portable_header(b"not json\n{}").unwrap_err();
+        assert!(
+            matches!(err, PortableBlueprintError::InvalidHeader(_)),
+            "unexpected error: {err}"
+        );
+
+        let wrong_format = PortableBlueprintHeader {
+            format: "something-else".to_string(),
+            ..header.clone()
+        };
+        let err =
+            Blueprint::portable_header(&header_bytes(&wrong_format, payload))
+                .unwrap_err();
+        assert!(
+            matches!(err, PortableBlueprintError::WrongFormat { .. }),
+            "unexpected error: {err}"
+        );
+
+        let future = PortableBlueprintHeader {
+            format_version: PORTABLE_BLUEPRINT_FORMAT_VERSION + 1,
+            ..header.clone()
+        };
+        let err = Blueprint::portable_header(&header_bytes(&future, payload))
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                PortableBlueprintError::UnsupportedVersion { found }
+                    if found == PORTABLE_BLUEPRINT_FORMAT_VERSION + 1
+            ),
+            "unexpected error: {err}"
+        );
+
+        // The header is fine, but the payload isn't a blueprint.
+        let err =
+            Blueprint::from_portable_bytes(&header_bytes(&header, payload))
+                .unwrap_err();
+        assert!(
+            matches!(err, PortableBlueprintError::Deserialize(_)),
+            "unexpected error: {err}"
+        );
+    }
+}
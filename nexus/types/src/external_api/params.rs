@@ -5,6 +5,8 @@
 //! Params define the request bodies of API endpoints for creating or updating
 //! resources.
 
+pub mod v20250915;
+
 use crate::external_api::shared;
 use base64::Engine;
 use chrono::{DateTime, Utc};
@@ -13,8 +15,9 @@ use omicron_common::api::external::{
     AddressLotKind, AffinityPolicy, AllowedSourceIps, BfdMode, BgpPeer,
     ByteCount, FailureDomain, Hostname, IdentityMetadataCreateParams,
     IdentityMetadataUpdateParams, InstanceAutoRestartPolicy, InstanceCpuCount,
-    IpVersion, LinkFec, LinkSpeed, Name, NameOrId, Nullable, PaginationOrder,
-    RouteDestination, RouteTarget, UserId,
+    InstanceStoppedReservationPolicy, IpVersion, LinkFec, LinkSpeed, Name,
+    NameOrId, Nullable, PaginationOrder, RouteDestination, RouteTarget, UserId,
+    VpcFirewallRuleUpdate,
 };
 use omicron_common::disk::DiskVariant;
 use omicron_uuid_kinds::SiloGroupUuid;
@@ -91,6 +94,7 @@ path_param!(InternetGatewayPath, gateway, "gateway");
 path_param!(FloatingIpPath, floating_ip, "floating IP");
 path_param!(DiskPath, disk, "disk");
 path_param!(SnapshotPath, snapshot, "snapshot");
+path_param!(SnapshotSchedulePath, snapshot_schedule, "snapshot schedule");
 path_param!(ImagePath, image, "image");
 path_param!(SiloPath, silo, "silo");
 path_param!(ProviderPath, provider, "SAML identity provider");
@@ -102,6 +106,8 @@ path_param!(ProbePath, probe, "probe");
 path_param!(CertificatePath, certificate, "certificate");
 
 id_path_param!(SupportBundlePath, bundle_id, "support bundle");
+id_path_param!(SnapshotExportPath, snapshot_export, "snapshot export");
+id_path_param!(OperationPath, operation, "operation");
 id_path_param!(GroupPath, group_id, "group", SiloGroupUuid);
 id_path_param!(UserPath, user_id, "user", SiloUserUuid);
 id_path_param!(TokenPath, token_id, "token");
@@ -166,6 +172,12 @@ pub struct SupportBundleFilePath {
     pub file: String,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct BackgroundTaskPath {
+    /// Name of the background task
+    pub name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SupportBundleCreate {
     /// User comment for the support bundle
@@ -218,12 +230,71 @@ pub struct ProjectSelector {
     pub project: NameOrId,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct InstanceListSelector {
+    /// Name or ID of the project
+    pub project: NameOrId,
+    /// Only list instances with this tag, given as `key:value`
+    pub tag: Option<String>,
+}
+
+impl From<InstanceListSelector> for ProjectSelector {
+    fn from(selector: InstanceListSelector) -> Self {
+        ProjectSelector { project: selector.project }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct OptionalProjectSelector {
     /// Name or ID of the project
     pub project: Option<NameOrId>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ImageListSelector {
+    /// Name or ID of the project
+    pub project: Option<NameOrId>,
+    /// Only list images of this operating system
+    pub os: Option<String>,
+    /// Only list images of this version of the operating system
+    pub version: Option<String>,
+    /// Only list images of at least this size
+    #[serde(default, deserialize_with = "optional_byte_count_from_query")]
+    pub min_size: Option<ByteCount>,
+}
+
+/// Deserializes an optional `ByteCount` from a query string
+///
+/// Selectors are flattened into the pagination parameters, which means serde
+/// hands their values over as strings rather than parsing them as numbers.
+fn optional_byte_count_from_query<'de, D>(
+    deserializer: D,
+) -> Result<Option<ByteCount>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(u64),
+        String(String),
+    }
+
+    let Some(value) = Option::<Value>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let bytes = match value {
+        Value::Number(bytes) => bytes,
+        Value::String(s) => s.parse::<u64>().map_err(|_| {
+            de::Error::invalid_value(
+                de::Unexpected::Str(&s),
+                &"a number of bytes",
+            )
+        })?,
+    };
+    ByteCount::try_from(bytes).map(Some).map_err(de::Error::custom)
+}
+
 #[derive(Deserialize, JsonSchema, Clone)]
 pub struct FloatingIpSelector {
     /// Name or ID of the project, only required if `floating_ip` is provided as a `Name`
@@ -248,6 +319,15 @@ pub struct SnapshotSelector {
     pub snapshot: NameOrId,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct SnapshotScheduleSelector {
+    /// Name or ID of the project, only required if `snapshot_schedule` is
+    /// provided as a `Name`
+    pub project: Option<NameOrId>,
+    /// Name or ID of the snapshot schedule
+    pub snapshot_schedule: NameOrId,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct ImageSelector {
     /// Name or ID of the project, only required if `image` is provided as a `Name`
@@ -919,6 +999,63 @@ pub struct ProjectUpdate {
     pub identity: IdentityMetadataUpdateParams,
 }
 
+/// The amount of provisionable resources for a Project
+///
+/// These limits are enforced in addition to those of the Project's Silo.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProjectQuotasCreate {
+    /// The amount of virtual CPUs available for running instances in the
+    /// Project
+    pub cpus: i64,
+    /// The amount of RAM (in bytes) available for running instances in the
+    /// Project
+    pub memory: ByteCount,
+    /// The amount of storage (in bytes) available for disks or snapshots
+    pub storage: ByteCount,
+}
+
+/// Updateable properties of a Project's resource limits.
+/// If a value is omitted it will not be updated.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProjectQuotasUpdate {
+    /// The amount of virtual CPUs available for running instances in the
+    /// Project
+    pub cpus: Option<i64>,
+    /// The amount of RAM (in bytes) available for running instances in the
+    /// Project
+    pub memory: Option<ByteCount>,
+    /// The amount of storage (in bytes) available for disks or snapshots
+    pub storage: Option<ByteCount>,
+}
+
+/// How a Project assigns ephemeral IPs to new instances
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProjectEphemeralIpPolicyUpdate {
+    /// Whether instances created without an ephemeral IP are given one
+    pub auto_assign: bool,
+    /// The IP pool automatically-assigned ephemeral IPs come from. If unset,
+    /// the silo's default pool is used.
+    #[serde(default)]
+    pub pool: Option<NameOrId>,
+    /// Whether users creating instances may request an ephemeral IP
+    /// themselves, rather than leaving it to this policy
+    pub allow_override: bool,
+}
+
+/// Defaults for the auto-restart configuration of a Project's instances
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProjectInstanceAutoRestartUpdate {
+    /// The auto-restart policy used by instances in this project which don't
+    /// set their own. If unset, the fleet-wide default policy is used.
+    #[serde(default)]
+    pub policy: Option<InstanceAutoRestartPolicy>,
+    /// The minimum number of seconds between automatic restarts of an instance
+    /// in this project, used by instances which don't set their own. If unset,
+    /// the fleet-wide default cooldown is used.
+    #[serde(default)]
+    pub cooldown_secs: Option<u32>,
+}
+
 // NETWORK INTERFACES
 
 /// Create-time parameters for an `InstanceNetworkInterface`
@@ -1031,6 +1168,20 @@ pub struct IpPoolLinkSilo {
     pub is_default: bool,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct IpPoolProjectPath {
+    pub pool: NameOrId,
+    /// ID of the linked project
+    pub project: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct IpPoolLinkProject {
+    /// ID of the project to link. Projects are identified by ID because
+    /// project names are only unique within a silo.
+    pub project: Uuid,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
 pub struct IpPoolSiloUpdate {
     /// When a pool is the default for a silo, floating IPs and instance
@@ -1226,28 +1377,28 @@ pub struct InstanceCreate {
     /// "attach" must already exist.
     ///
     /// The order of this list does not guarantee a boot order for the instance.
-    /// Use the boot_disk attribute to specify a boot disk. When boot_disk is
-    /// specified it will count against the disk attachment limit.
+    /// Use the boot_order attribute to specify the disks to boot from.
     #[serde(default)]
     pub disks: Vec<InstanceDiskAttachment>,
 
-    /// The disk the instance is configured to boot from.
+    /// The disks the instance is configured to boot from, in order of
+    /// preference.
     ///
-    /// This disk can either be attached if it already exists or created along
-    /// with the instance.
+    /// If the guest fails to boot from the first disk in this list, it falls
+    /// back to the second, and so on. Each entry must name a disk in `disks`
+    /// or, if given by ID, a disk being attached to the instance.
     ///
-    /// Specifying a boot disk is optional but recommended to ensure predictable
-    /// boot behavior. The boot disk can be set during instance creation or
-    /// later if the instance is stopped. The boot disk counts against the disk
-    /// attachment limit.
+    /// Specifying a boot order is optional but recommended to ensure
+    /// predictable boot behavior. The boot order can be set during instance
+    /// creation or later if the instance is stopped.
     ///
-    /// An instance that does not have a boot disk set will use the boot
+    /// An instance that does not have a boot order set will use the boot
     /// options specified in its UEFI settings, which are controlled by both the
     /// instance's UEFI firmware and the guest operating system. Boot options
     /// can change as disks are attached and detached, which may result in an
-    /// instance that only boots to the EFI shell until a boot disk is set.
+    /// instance that only boots to the EFI shell until a boot order is set.
     #[serde(default)]
-    pub boot_disk: Option<InstanceDiskAttachment>,
+    pub boot_order: Vec<NameOrId>,
 
     /// An allowlist of SSH public keys to be transferred to the instance via
     /// cloud-init during instance creation.
@@ -1281,6 +1432,22 @@ pub struct InstanceCreate {
     /// Anti-Affinity groups which this instance should be added.
     #[serde(default)]
     pub anti_affinity_groups: Vec<NameOrId>,
+
+    /// User-defined key/value tags to attach to the instance.
+    ///
+    /// Tags can be used to filter the list of instances in a project. Keys
+    /// must not contain a `:` character.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+
+    /// Whether the instance keeps its sled resource reservation while it is
+    /// stopped.
+    ///
+    /// Retaining the reservation guarantees that a stopped instance can be
+    /// started again quickly, on the same sled, at the cost of holding that
+    /// capacity while it isn't running. Defaults to `release`.
+    #[serde(default)]
+    pub stopped_reservation_policy: InstanceStoppedReservationPolicy,
 }
 
 /// Parameters of an `Instance` that can be reconfigured after creation.
@@ -1292,10 +1459,13 @@ pub struct InstanceUpdate {
     /// The amount of memory to assign to this instance.
     pub memory: ByteCount,
 
-    /// Name or ID of the disk the instance should be instructed to boot from.
+    /// Names or IDs of the disks the instance should be instructed to boot
+    /// from, in order of preference.
     ///
-    /// If not provided, unset the instance's boot disk.
-    pub boot_disk: Option<NameOrId>,
+    /// Every disk in this list must be attached to the instance. If empty or
+    /// not provided, unset the instance's boot order.
+    #[serde(default)]
+    pub boot_order: Vec<NameOrId>,
 
     /// Sets the auto-restart policy for this instance.
     ///
@@ -1312,6 +1482,22 @@ pub struct InstanceUpdate {
     /// In that case, any configured default policy will be used if this is
     /// `null`.
     pub auto_restart_policy: Option<InstanceAutoRestartPolicy>,
+
+    /// If provided, replaces all of the instance's user-defined tags.
+    ///
+    /// If this is `null` or not provided, the instance's tags are left
+    /// unchanged.
+    #[serde(default)]
+    pub tags: Option<BTreeMap<String, String>>,
+
+    /// If provided, sets whether the instance keeps its sled resource
+    /// reservation while it is stopped.
+    ///
+    /// If this is `null` or not provided, the instance's policy is left
+    /// unchanged. Changing the policy to `release` while the instance is
+    /// stopped releases any reservation it's holding.
+    #[serde(default)]
+    pub stopped_reservation_policy: Option<InstanceStoppedReservationPolicy>,
 }
 
 #[inline]
@@ -1384,6 +1570,35 @@ impl JsonSchema for UserData {
     }
 }
 
+/// Parameters for forcing a wedged instance into the `Failed` state
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceForceFail {
+    /// Why the instance is being forced to fail, recorded in Nexus's log
+    /// along with the operator who requested it.
+    pub reason: String,
+}
+
+/// An action that can be taken on many instances at once
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceBulkActionKind {
+    /// Boot each instance
+    Start,
+    /// Stop each instance
+    Stop,
+    /// Reboot each instance
+    Reboot,
+}
+
+/// Parameters for starting, stopping, or rebooting many instances at once
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceBulkAction {
+    /// The action to take on each instance
+    pub action: InstanceBulkActionKind,
+    /// IDs of the instances to act on, each of which may only be listed once
+    pub instance_ids: Vec<Uuid>,
+}
+
 /// Forwarded to a propolis server to request the contents of an Instance's serial console.
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
 pub struct InstanceSerialConsoleRequest {
@@ -1490,6 +1705,52 @@ pub struct VpcSubnetUpdate {
     pub custom_router: Option<NameOrId>,
 }
 
+/// Create-time parameters for a `VpcSubnetIpReservation`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct VpcSubnetIpReservationCreate {
+    /// The address to reserve. It must be a requestable address within the
+    /// subnet's IPv4 block.
+    pub ip: IpAddr,
+    /// Human-readable free-form text about the reservation
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct VpcSubnetIpReservationPath {
+    /// Name or ID of the subnet
+    pub subnet: NameOrId,
+    /// The reserved address
+    pub address: IpAddr,
+}
+
+/// Parameters for adding a secondary IPv4 block to a `VpcSubnet`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct VpcSubnetIpv4BlockAdd {
+    /// The IPv4 block to add.
+    ///
+    /// It must be a private range, and must not overlap any IPv4 block of
+    /// another subnet in the VPC or of this subnet.
+    pub block: Ipv4Net,
+}
+
+/// A complete, desired set of VPC firewall rules
+///
+/// Without `confirm_token`, the replacement is only previewed: the difference
+/// from the VPC's current rules is computed and returned along with a token.
+/// Passing that token back with the same rules applies the replacement, as
+/// long as the VPC's rules haven't changed in the meantime.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct VpcFirewallRulesReplace {
+    /// Rules that will replace all existing rules
+    #[schemars(length(max = 1024))]
+    #[serde(default)]
+    pub rules: Vec<VpcFirewallRuleUpdate>,
+    /// Token from a previous preview of this replacement, required to apply it
+    #[serde(default)]
+    pub confirm_token: Option<String>,
+}
+
 // VPC ROUTERS
 
 /// Create-time parameters for a `VpcRouter`
@@ -1674,6 +1935,24 @@ pub struct DiskCreate {
     pub size: ByteCount,
 }
 
+/// Updateable properties of a `Disk`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DiskUpdate {
+    /// If true, the disk cannot be deleted until this is set back to false.
+    ///
+    /// A disk can't be protected while it is still being created.
+    pub delete_protected: bool,
+}
+
+/// Parameters for forcibly detaching a disk from a deleted or corrupted
+/// instance
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DiskForceDetach {
+    /// Why the disk is being forcibly detached, recorded in Nexus's log along
+    /// with the operator who requested it.
+    pub reason: String,
+}
+
 // equivalent to crucible_pantry_client::types::ExpectedDigest
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -1699,6 +1978,14 @@ pub struct FinalizeDisk {
     pub snapshot_name: Option<Name>,
 }
 
+/// Parameters for cloning a disk
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DiskClone {
+    /// The common identifying metadata for the new disk
+    #[serde(flatten)]
+    pub identity: IdentityMetadataCreateParams,
+}
+
 /// Select an address lot by an optional name or id.
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
 pub struct AddressLotSelector {
@@ -2292,6 +2579,12 @@ pub struct ImageCreate {
     pub source: ImageSource,
 }
 
+/// Parameters for changing the lifecycle state of an `Image`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ImageStateUpdate {
+    pub state: super::views::ImageState,
+}
+
 // SNAPSHOTS
 
 /// Create-time parameters for a `Snapshot`
@@ -2305,6 +2598,58 @@ pub struct SnapshotCreate {
     pub disk: NameOrId,
 }
 
+/// Updateable properties of a `Snapshot`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SnapshotUpdate {
+    /// If true, the snapshot cannot be deleted until this is set back to
+    /// false.
+    ///
+    /// A snapshot can't be protected while it is still being created.
+    pub delete_protected: bool,
+}
+
+// SNAPSHOT SCHEDULES
+
+/// Create-time parameters for a `SnapshotSchedule`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SnapshotScheduleCreate {
+    #[serde(flatten)]
+    pub identity: IdentityMetadataCreateParams,
+
+    /// The disk to snapshot. If not specified, every disk in the project is
+    /// snapshotted.
+    pub disk: Option<NameOrId>,
+
+    /// How often to take snapshots, in seconds
+    pub interval_secs: u32,
+
+    /// How many of the schedule's snapshots to keep for each disk. After new
+    /// snapshots are taken, the oldest ones beyond this count are deleted.
+    pub retain_count: u32,
+}
+
+/// Updateable properties of a `SnapshotSchedule`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SnapshotScheduleUpdate {
+    #[serde(flatten)]
+    pub identity: IdentityMetadataUpdateParams,
+
+    /// How often to take snapshots, in seconds
+    pub interval_secs: Option<u32>,
+
+    /// How many of the schedule's snapshots to keep for each disk
+    pub retain_count: Option<u32>,
+}
+
+// EXPORTS
+
+/// Create-time parameters for exporting an instance
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceExportCreate {
+    /// The name of the snapshot to take of the instance's boot disk
+    pub snapshot_name: Name,
+}
+
 // USERS AND GROUPS
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -2683,3 +3028,14 @@ pub struct AuditLog {
     /// Exclusive
     pub end_time: Option<DateTime<Utc>>,
 }
+
+// The rack activity feed is paginated by timestamp, like the audit log.
+#[derive(Deserialize, JsonSchema, Serialize, PartialEq, Debug, Clone)]
+pub struct RackActivity {
+    /// Required, inclusive
+    pub start_time: DateTime<Utc>,
+    /// Exclusive
+    pub end_time: Option<DateTime<Utc>>,
+    /// Only list activity of this kind
+    pub kind: Option<super::views::RackActivityKind>,
+}
@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Instance parameters as accepted by versions of the external API prior to
+//! `INSTANCE_BOOT_ORDER`.
+//!
+//! These must not change: they define the blessed OpenAPI documents for those
+//! versions.
+
+use super::{
+    ExternalIpCreate, InstanceDiskAttachment,
+    InstanceNetworkInterfaceAttachment, UserData, bool_true,
+};
+use omicron_common::api::external::{
+    ByteCount, Hostname, IdentityMetadataCreateParams,
+    InstanceAutoRestartPolicy, InstanceCpuCount, NameOrId,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Create-time parameters for an `Instance`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceCreate {
+    #[serde(flatten)]
+    pub identity: IdentityMetadataCreateParams,
+    /// The number of vCPUs to be allocated to the instance
+    pub ncpus: InstanceCpuCount,
+    /// The amount of RAM (in bytes) to be allocated to the instance
+    pub memory: ByteCount,
+    /// The hostname to be assigned to the instance
+    pub hostname: Hostname,
+
+    /// User data for instance initialization systems (such as cloud-init).
+    /// Must be a Base64-encoded string, as specified in RFC 4648 § 4 (+ and /
+    /// characters with padding). Maximum 32 KiB unencoded data.
+    // While serde happily accepts #[serde(with = "<mod>")] as a shorthand for
+    // specifing `serialize_with` and `deserialize_with`, schemars requires the
+    // argument to `with` to be a type rather than merely a path prefix (i.e. a
+    // mod or type). It's admittedly a bit tricky for schemars to address;
+    // unlike `serialize` or `deserialize`, `JsonSchema` requires several
+    // functions working together. It's unfortunate that schemars has this
+    // built-in incompatibility, exacerbated by its glacial rate of progress
+    // and immunity to offers of help.
+    #[serde(default, with = "UserData")]
+    pub user_data: Vec<u8>,
+
+    /// The network interfaces to be created for this instance.
+    #[serde(default)]
+    pub network_interfaces: InstanceNetworkInterfaceAttachment,
+
+    /// The external IP addresses provided to this instance.
+    ///
+    /// By default, all instances have outbound connectivity, but no inbound
+    /// connectivity. These external addresses can be used to provide a fixed,
+    /// known IP address for making inbound connections to the instance.
+    #[serde(default)]
+    pub external_ips: Vec<ExternalIpCreate>,
+
+    /// A list of disks to be attached to the instance.
+    ///
+    /// Disk attachments of type "create" will be created, while those of type
+    /// "attach" must already exist.
+    ///
+    /// The order of this list does not guarantee a boot order for the instance.
+    /// Use the boot_disk attribute to specify a boot disk. When boot_disk is
+    /// specified it will count against the disk attachment limit.
+    #[serde(default)]
+    pub disks: Vec<InstanceDiskAttachment>,
+
+    /// The disk the instance is configured to boot from.
+    ///
+    /// This disk can either be attached if it already exists or created along
+    /// with the instance.
+    ///
+    /// Specifying a boot disk is optional but recommended to ensure predictable
+    /// boot behavior. The boot disk can be set during instance creation or
+    /// later if the instance is stopped. The boot disk counts against the disk
+    /// attachment limit.
+    ///
+    /// An instance that does not have a boot disk set will use the boot
+    /// options specified in its UEFI settings, which are controlled by both the
+    /// instance's UEFI firmware and the guest operating system. Boot options
+    /// can change as disks are attached and detached, which may result in an
+    /// instance that only boots to the EFI shell until a boot disk is set.
+    #[serde(default)]
+    pub boot_disk: Option<InstanceDiskAttachment>,
+
+    /// An allowlist of SSH public keys to be transferred to the instance via
+    /// cloud-init during instance creation.
+    ///
+    /// If not provided, all SSH public keys from the user's profile will be sent.
+    /// If an empty list is provided, no public keys will be transmitted to the
+    /// instance.
+    pub ssh_public_keys: Option<Vec<NameOrId>>,
+
+    /// Should this instance be started upon creation; true by default.
+    #[serde(default = "bool_true")]
+    pub start: bool,
+
+    /// The auto-restart policy for this instance.
+    ///
+    /// This policy determines whether the instance should be automatically
+    /// restarted by the control plane on failure. If this is `null`, no
+    /// auto-restart policy will be explicitly configured for this instance, and
+    /// the control plane will select the default policy when determining
+    /// whether the instance can be automatically restarted.
+    ///
+    /// Currently, the global default auto-restart policy is "best-effort", so
+    /// instances with `null` auto-restart policies will be automatically
+    /// restarted. However, in the future, the default policy may be
+    /// configurable through other mechanisms, such as on a per-project basis.
+    /// In that case, any configured default policy will be used if this is
+    /// `null`.
+    #[serde(default)]
+    pub auto_restart_policy: Option<InstanceAutoRestartPolicy>,
+
+    /// Anti-Affinity groups which this instance should be added.
+    #[serde(default)]
+    pub anti_affinity_groups: Vec<NameOrId>,
+}
+
+/// Parameters of an `Instance` that can be reconfigured after creation.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceUpdate {
+    /// The number of CPUs to assign to this instance.
+    pub ncpus: InstanceCpuCount,
+
+    /// The amount of memory to assign to this instance.
+    pub memory: ByteCount,
+
+    /// Name or ID of the disk the instance should be instructed to boot from.
+    ///
+    /// If not provided, unset the instance's boot disk.
+    pub boot_disk: Option<NameOrId>,
+
+    /// Sets the auto-restart policy for this instance.
+    ///
+    /// This policy determines whether the instance should be automatically
+    /// restarted by the control plane on failure. If this is `null`, any
+    /// explicitly configured auto-restart policy will be unset, and
+    /// the control plane will select the default policy when determining
+    /// whether the instance can be automatically restarted.
+    ///
+    /// Currently, the global default auto-restart policy is "best-effort", so
+    /// instances with `null` auto-restart policies will be automatically
+    /// restarted. However, in the future, the default policy may be
+    /// configurable through other mechanisms, such as on a per-project basis.
+    /// In that case, any configured default policy will be used if this is
+    /// `null`.
+    pub auto_restart_policy: Option<InstanceAutoRestartPolicy>,
+}
+
+impl From<InstanceCreate> for super::InstanceCreate {
+    fn from(params: InstanceCreate) -> Self {
+        let InstanceCreate {
+            identity,
+            ncpus,
+            memory,
+            hostname,
+            user_data,
+            network_interfaces,
+            external_ips,
+            disks,
+            boot_disk,
+            ssh_public_keys,
+            start,
+            auto_restart_policy,
+            anti_affinity_groups,
+        } = params;
+
+        // The boot disk becomes the only entry in the boot order. It is
+        // attached (or created) along with the rest of the instance's disks.
+        let boot_order =
+            boot_disk.iter().map(|disk| NameOrId::Name(disk.name())).collect();
+        let disks = boot_disk.into_iter().chain(disks).collect();
+
+        Self {
+            identity,
+            ncpus,
+            memory,
+            hostname,
+            user_data,
+            network_interfaces,
+            external_ips,
+            disks,
+            boot_order,
+            ssh_public_keys,
+            start,
+            auto_restart_policy,
+            anti_affinity_groups,
+            tags: BTreeMap::new(),
+            stopped_reservation_policy: Default::default(),
+        }
+    }
+}
+
+impl From<InstanceUpdate> for super::InstanceUpdate {
+    fn from(params: InstanceUpdate) -> Self {
+        let InstanceUpdate { ncpus, memory, boot_disk, auto_restart_policy } =
+            params;
+        Self {
+            ncpus,
+            memory,
+            boot_order: boot_disk.into_iter().collect(),
+            auto_restart_policy,
+            tags: None,
+            stopped_reservation_policy: None,
+        }
+    }
+}
@@ -730,3 +730,79 @@ impl RelayState {
         .context("json from relay state string")
     }
 }
+
+/// The kind of service processor targeted by a technician port update
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceProcessorType {
+    Sled,
+    Switch,
+    Power,
+}
+
+/// A component updated as part of a technician port update
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateComponentKind {
+    RotBootloader,
+    Rot,
+    Sp,
+    Host,
+}
+
+/// State of a single component within a technician port update
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateComponentState {
+    /// The component has not been reached yet.
+    Waiting,
+    /// The component is being updated.
+    Updating,
+    /// The component was updated successfully.
+    Updated,
+    /// The component was already running the target software.
+    Skipped,
+    /// Updating the component failed.
+    Failed,
+    /// The update was aborted while working on this component.
+    Aborted,
+}
+
+/// Progress of a single component within a technician port update
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct UpdateComponentProgress {
+    pub component: UpdateComponentKind,
+    pub state: UpdateComponentState,
+    /// Number of update steps for this component that have finished
+    pub steps_completed: u32,
+    /// Total number of update steps for this component
+    pub total_steps: u32,
+    /// Description of the step currently running, if any
+    pub current_step: Option<String>,
+    /// Additional detail, such as the reason an update failed
+    pub message: Option<String>,
+}
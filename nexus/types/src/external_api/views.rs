@@ -16,11 +16,14 @@ use daft::Diffable;
 pub use omicron_common::api::external::IpVersion;
 use omicron_common::api::external::{
     AffinityPolicy, AllowedSourceIps as ExternalAllowedSourceIps, ByteCount,
-    Digest, Error, FailureDomain, IdentityMetadata, InstanceState, Name,
-    ObjectIdentity, SimpleIdentity, SimpleIdentityOrName,
+    Digest, Error, FailureDomain, IdentityMetadata, Instance,
+    InstanceAutoRestartPolicy, InstanceCpuCount, InstanceState, Name,
+    ObjectIdentity, SimpleIdentity, SimpleIdentityOrName, VpcFirewallRule,
+    VpcFirewallRuleUpdate,
 };
 use omicron_uuid_kinds::AlertReceiverUuid;
 use omicron_uuid_kinds::AlertUuid;
+use omicron_uuid_kinds::BlueprintUuid;
 use omicron_uuid_kinds::BuiltInUserUuid;
 use omicron_uuid_kinds::SiloGroupUuid;
 use omicron_uuid_kinds::SiloUserUuid;
@@ -87,6 +90,15 @@ pub struct SiloQuotas {
     pub limits: VirtualResourceCounts,
 }
 
+/// A collection of resource counts used to limit the virtual resources a
+/// project may provision
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProjectQuotas {
+    pub project_id: Uuid,
+    #[serde(flatten)]
+    pub limits: VirtualResourceCounts,
+}
+
 // For the eyes of end users
 /// View of the current silo's resource utilization and capacity
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
@@ -216,6 +228,33 @@ pub struct Project {
     // Important: Silo ID does not get presented to user
 }
 
+/// How a Project assigns ephemeral IPs to new instances
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProjectEphemeralIpPolicy {
+    pub project_id: Uuid,
+    /// Whether instances created without an ephemeral IP are given one
+    pub auto_assign: bool,
+    /// The IP pool automatically-assigned ephemeral IPs come from. If unset,
+    /// the silo's default pool is used.
+    pub ip_pool_id: Option<Uuid>,
+    /// Whether users creating instances may request an ephemeral IP
+    /// themselves, rather than leaving it to this policy
+    pub allow_override: bool,
+}
+
+/// Defaults for the auto-restart configuration of a Project's instances
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProjectInstanceAutoRestart {
+    pub project_id: Uuid,
+    /// The auto-restart policy used by instances in this project which don't
+    /// set their own. If unset, the fleet-wide default policy is used.
+    pub policy: Option<InstanceAutoRestartPolicy>,
+    /// The minimum number of seconds between automatic restarts of an instance
+    /// in this project, used by instances which don't set their own. If unset,
+    /// the fleet-wide default cooldown is used.
+    pub cooldown_secs: Option<u32>,
+}
+
 // CERTIFICATES
 
 /// View of a Certificate
@@ -258,6 +297,43 @@ pub struct Image {
 
     /// total size in bytes
     pub size: ByteCount,
+
+    /// Whether new disks can be created from the image
+    pub state: ImageState,
+}
+
+/// Lifecycle state of an image
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageState {
+    /// Disks can be created from the image
+    Active,
+    /// Disks can still be created from the image, but the response to
+    /// creating one includes a warning. Existing disks are unaffected.
+    Deprecated,
+    /// New disks can't be created from the image. Existing disks are
+    /// unaffected.
+    Blocked,
+}
+
+// DISKS
+
+/// Progress of copying a disk's contents from the snapshot or image it was
+/// created from
+///
+/// Blocks are copied in the background while the disk is attached to a
+/// running instance. Until the copy completes, the disk depends on the
+/// contents of its source.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct DiskCopyProgress {
+    /// ID of the snapshot from which the disk was created, if any
+    pub snapshot_id: Option<Uuid>,
+    /// ID of the image from which the disk was created, if any
+    pub image_id: Option<Uuid>,
+    /// Whether all of the disk's contents have been copied from its source
+    pub complete: bool,
 }
 
 // SNAPSHOTS
@@ -283,6 +359,321 @@ pub struct Snapshot {
     pub state: SnapshotState,
 
     pub size: ByteCount,
+
+    /// Whether the snapshot is protected from deletion
+    pub delete_protected: bool,
+}
+
+// SNAPSHOT SCHEDULES
+
+/// View of a Snapshot Schedule
+#[derive(ObjectIdentity, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SnapshotSchedule {
+    #[serde(flatten)]
+    pub identity: IdentityMetadata,
+
+    pub project_id: Uuid,
+
+    /// The disk to snapshot. If not set, every disk in the project is
+    /// snapshotted.
+    pub disk_id: Option<Uuid>,
+
+    /// How often snapshots are taken, in seconds
+    pub interval_secs: u32,
+
+    /// How many of the schedule's snapshots are kept for each disk
+    pub retain_count: u32,
+
+    /// When the schedule last took snapshots, if it ever has
+    pub time_last_run: Option<DateTime<Utc>>,
+}
+
+// SNAPSHOT EXPORTS
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotExportState {
+    /// The export is being prepared and can't be downloaded yet
+    Creating,
+    /// The export can be downloaded
+    Ready,
+}
+
+/// View of a Snapshot Export
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SnapshotExport {
+    pub id: Uuid,
+    pub time_created: DateTime<Utc>,
+
+    pub project_id: Uuid,
+
+    /// The snapshot being exported
+    pub snapshot_id: Uuid,
+
+    /// The instance whose boot disk was exported, if the export was created
+    /// from an instance
+    pub instance_id: Option<Uuid>,
+
+    pub state: SnapshotExportState,
+
+    /// The size of the exported image
+    pub size: ByteCount,
+
+    pub block_size: ByteCount,
+}
+
+impl SimpleIdentity for SnapshotExport {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+/// The format of an exported image
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportImageFormat {
+    /// The raw contents of the disk, with no header or compression
+    Raw,
+}
+
+/// Metadata describing the contents of a snapshot export
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct ExportManifest {
+    /// The version of the manifest format
+    pub format_version: u32,
+
+    pub image_format: ExportImageFormat,
+
+    /// The size of the exported image
+    pub size: ByteCount,
+
+    pub block_size: ByteCount,
+
+    /// The snapshot the image was read from
+    pub snapshot: ExportManifestSnapshot,
+
+    /// The instance whose boot disk was exported, if the export was created
+    /// from an instance
+    pub instance: Option<ExportManifestInstance>,
+}
+
+impl ExportManifest {
+    /// The current version of the manifest format
+    pub const FORMAT_VERSION: u32 = 1;
+}
+
+/// The snapshot described by an [`ExportManifest`]
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct ExportManifestSnapshot {
+    pub id: Uuid,
+    pub name: Name,
+    pub description: String,
+    pub time_created: DateTime<Utc>,
+
+    /// The disk the snapshot was taken of
+    pub disk_id: Uuid,
+}
+
+/// The instance described by an [`ExportManifest`], as it was configured when
+/// it was exported
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct ExportManifestInstance {
+    pub id: Uuid,
+    pub name: Name,
+    pub description: String,
+    pub hostname: String,
+    pub ncpus: InstanceCpuCount,
+    pub memory: ByteCount,
+}
+
+// OPERATIONS
+
+/// The request that started an operation
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    /// Cloning a disk
+    DiskClone,
+    /// Exporting a snapshot
+    SnapshotExport,
+    /// Snapshotting and exporting an instance's boot disk
+    InstanceExport,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    /// The operation is still in progress
+    Running,
+    /// The operation completed and its result is available
+    Succeeded,
+    /// The operation stopped without completing
+    Failed,
+}
+
+/// How far an operation has progressed
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct OperationProgress {
+    pub steps_completed: u32,
+    pub steps_total: u32,
+}
+
+/// The type of resource produced by an operation
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationResultType {
+    Disk,
+    SnapshotExport,
+}
+
+/// A link to the resource produced by an operation
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct OperationResult {
+    pub resource_type: OperationResultType,
+    pub id: Uuid,
+    /// The API path from which the resource can be fetched
+    pub href: String,
+}
+
+/// View of an Operation
+///
+/// Requests that take a long time to complete return an operation as soon as
+/// the work has started. Clients poll the operation until it is no longer
+/// running, then follow its result to the resource that was produced.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct Operation {
+    pub id: Uuid,
+    pub time_created: DateTime<Utc>,
+
+    pub project_id: Uuid,
+
+    pub kind: OperationKind,
+    pub state: OperationState,
+    pub progress: OperationProgress,
+
+    /// When the operation succeeded or failed
+    pub time_completed: Option<DateTime<Utc>>,
+
+    /// The resource produced by the operation, once it has succeeded
+    pub result: Option<OperationResult>,
+
+    /// Why the operation failed, if it did
+    pub error: Option<String>,
+}
+
+impl SimpleIdentity for Operation {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+// INSTANCE MIGRATIONS
+
+/// The outcome of an instance's live migration
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceMigrationState {
+    /// The migration has not yet completed or failed
+    InProgress,
+    /// The instance was moved to the migration target
+    Completed,
+    /// The migration failed and the instance was not moved
+    Failed,
+}
+
+/// View of a live migration of an instance from one sled to another
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceMigration {
+    pub id: Uuid,
+    pub instance_id: Uuid,
+
+    /// The time at which the migration was started
+    pub time_created: DateTime<Utc>,
+
+    /// The time at which the migration completed or failed, if it has
+    pub time_finished: Option<DateTime<Utc>>,
+
+    pub state: InstanceMigrationState,
+
+    /// The sled the instance was migrated from
+    ///
+    /// This is only provided to users who can view the fleet's sleds.
+    pub source_sled_id: Option<Uuid>,
+
+    /// The sled the instance was migrated to
+    ///
+    /// This is only provided to users who can view the fleet's sleds.
+    pub target_sled_id: Option<Uuid>,
+}
+
+impl SimpleIdentity for InstanceMigration {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+// INSTANCE AUTO-RESTARTS
+
+/// Why the control plane automatically restarted an instance
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceAutoRestartReason {
+    /// The instance had failed
+    Failed,
+    /// A previous attempt to start the instance had failed
+    StartFailed,
+}
+
+/// A record of the control plane automatically restarting an instance
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceAutoRestartEvent {
+    pub id: Uuid,
+    pub instance_id: Uuid,
+
+    /// The time at which the control plane began restarting the instance
+    pub time_restarted: DateTime<Utc>,
+
+    pub reason: InstanceAutoRestartReason,
+}
+
+impl SimpleIdentity for InstanceAutoRestartEvent {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+// INSTANCE BULK ACTIONS
+
+/// The outcome of a bulk action for one instance
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InstanceBulkActionOutcome {
+    /// The action was accepted
+    Success {
+        /// The instance, as of when the action was accepted
+        instance: Instance,
+    },
+    /// The action failed
+    Error {
+        /// HTTP status code the equivalent single-instance request would have
+        /// failed with
+        http_status_code: u16,
+        error_code: Option<String>,
+        error_message: String,
+    },
+}
+
+/// The result of a bulk action for one instance
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceBulkActionResult {
+    pub instance_id: Uuid,
+    pub outcome: InstanceBulkActionOutcome,
+}
+
+/// Results of a bulk action, in the order the instances were given
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceBulkActionResults {
+    pub results: Vec<InstanceBulkActionResult>,
 }
 
 // VPCs
@@ -328,6 +719,81 @@ pub struct VpcSubnet {
     pub custom_router_id: Option<Uuid>,
 }
 
+/// A private IP address reserved in a VPC Subnet
+///
+/// Reserved addresses are never automatically assigned to network interfaces.
+/// An instance network interface that explicitly requests a reserved address
+/// is given it, which lets the address be registered elsewhere (e.g., in DNS or
+/// firewalls) before the instance exists.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, JsonSchema)]
+pub struct VpcSubnetIpReservation {
+    /// Unique, immutable, system-controlled identifier for the reservation
+    pub id: Uuid,
+    /// Human-readable free-form text about the reservation
+    pub description: String,
+    /// Timestamp when this reservation was created
+    pub time_created: DateTime<Utc>,
+    /// The VPC containing the reserved address
+    pub vpc_id: Uuid,
+    /// The subnet containing the reserved address
+    pub subnet_id: Uuid,
+    /// The reserved address
+    pub ip: IpAddr,
+}
+
+impl SimpleIdentity for VpcSubnetIpReservation {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+/// A firewall rule whose contents differ between the current and desired rule
+/// sets
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, JsonSchema)]
+pub struct VpcFirewallRuleModification {
+    /// The rule as it is now
+    pub before: VpcFirewallRuleUpdate,
+    /// The rule as it will be after the replacement
+    pub after: VpcFirewallRuleUpdate,
+}
+
+/// Differences between a VPC's current firewall rules and a desired set
+///
+/// Rules are matched by name. Each list is sorted by rule name.
+#[derive(
+    Clone, Debug, Default, Deserialize, Serialize, PartialEq, JsonSchema,
+)]
+pub struct VpcFirewallRulesDiff {
+    /// Rules in the desired set but not the current one
+    pub added: Vec<VpcFirewallRuleUpdate>,
+    /// Rules in the current set but not the desired one
+    pub removed: Vec<VpcFirewallRuleUpdate>,
+    /// Rules in both sets whose contents differ
+    pub modified: Vec<VpcFirewallRuleModification>,
+}
+
+/// The result of previewing or applying a replacement of VPC firewall rules
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct VpcFirewallRulesReplaceResult {
+    /// Whether the replacement was applied
+    pub applied: bool,
+    /// Changes made by the replacement, or that would be made if applied
+    pub diff: VpcFirewallRulesDiff,
+    /// Token to pass back with the same rules to apply a previewed replacement
+    pub confirm_token: String,
+    /// The VPC's firewall rules after this request
+    pub rules: Vec<VpcFirewallRule>,
+}
+
+/// The IPv4 blocks from which addresses in a VPC Subnet are allocated
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, JsonSchema)]
+pub struct VpcSubnetIpv4Blocks {
+    /// The block the subnet was created with
+    pub primary: Ipv4Net,
+    /// Blocks added to the subnet after creation, in the order they were added
+    pub secondary: Vec<Ipv4Net>,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum VpcRouterKind {
@@ -414,6 +880,25 @@ pub struct IpPoolUtilization {
     pub remaining: f64,
     /// The total number of addresses in the pool.
     pub capacity: f64,
+    /// The utilization of each of the pool's ranges.
+    pub ranges: Vec<IpPoolRangeUtilization>,
+}
+
+/// The utilization of IP addresses in one range of a pool.
+///
+/// As with [`IpPoolUtilization`], counts are reported as floating point
+/// numbers.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct IpPoolRangeUtilization {
+    /// The ID of the range.
+    pub id: Uuid,
+    pub range: IpRange,
+    /// The number of addresses allocated from the range.
+    pub allocated: f64,
+    /// The number of remaining addresses in the range.
+    pub remaining: f64,
+    /// The total number of addresses in the range.
+    pub capacity: f64,
 }
 
 /// An IP pool in the context of a silo
@@ -440,6 +925,15 @@ pub struct IpPoolSiloLink {
     pub is_default: bool,
 }
 
+/// A link between an IP pool and a project that allows one to allocate IPs
+/// from the pool within the project, even if the pool isn't linked to the
+/// project's silo
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct IpPoolProjectLink {
+    pub ip_pool_id: Uuid,
+    pub project_id: Uuid,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema)]
 pub struct IpPoolRange {
     pub id: Uuid,
@@ -480,6 +974,18 @@ impl ExternalIp {
     }
 }
 
+/// An external IP address attached to an instance
+///
+/// An instance's external IP addresses are listed in the order in which they
+/// were attached to it.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct InstanceExternalIp {
+    /// The address, its kind, and the IP pool it's taken from
+    pub external_ip: ExternalIp,
+    /// When the address finished attaching to the instance
+    pub time_attached: DateTime<Utc>,
+}
+
 /// A source NAT IP address.
 ///
 /// SNAT addresses are ephemeral addresses used only for outbound connectivity.
@@ -723,6 +1229,14 @@ pub enum SledState {
     /// The sled is currently active, and has resources allocated on it.
     Active,
 
+    /// The sled is being prepared for a disruptive operation, such as a host
+    /// OS update.
+    ///
+    /// Resources already on the sled keep running, but no new resources are
+    /// placed on it. The sled returns to the active state once the operation
+    /// is complete.
+    Quiesced,
+
     /// The sled has been permanently removed from service.
     ///
     /// This is a terminal state: once a particular sled ID is decommissioned,
@@ -735,6 +1249,7 @@ impl fmt::Display for SledState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             SledState::Active => write!(f, "active"),
+            SledState::Quiesced => write!(f, "quiesced"),
             SledState::Decommissioned => write!(f, "decommissioned"),
         }
     }
@@ -755,6 +1270,63 @@ pub struct SledInstance {
     pub memory: i64,
 }
 
+/// A preview of what expunging a sled would do
+///
+/// This comes from running the update planner against the current system with
+/// the sled expunged, and from checking what data the sled holds that would be
+/// left without enough copies. Nothing is changed.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct SledExpungePreview {
+    /// The sled that would be expunged
+    pub sled_id: Uuid,
+    /// The current target blueprint, which the planner started from
+    #[schemars(with = "Uuid")]
+    pub parent_blueprint_id: BlueprintUuid,
+    /// Control plane zones on the sled that the planner would expunge
+    pub zones_expunged: Vec<SledExpungePreviewZone>,
+    /// Control plane zones that the planner would add
+    ///
+    /// This includes any other changes the planner would make next, which
+    /// may be unrelated to the sled.
+    pub zones_added: Vec<SledExpungePreviewZone>,
+    /// Reasons that expunging the sled right now is risky
+    ///
+    /// If this is empty, no problems were found.
+    pub risks: Vec<SledExpungeRisk>,
+}
+
+/// A control plane zone affected by expunging a sled
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct SledExpungePreviewZone {
+    pub zone_id: Uuid,
+    /// The kind of zone (e.g., `crucible` or `nexus`)
+    pub kind: String,
+    /// The sled the zone is on
+    pub sled_id: Uuid,
+}
+
+/// A reason that expunging a sled right now is risky
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SledExpungeRisk {
+    /// A Crucible volume with regions on the sled would be left with fewer
+    /// than two copies of its data until they're replaced
+    UnreplicatedVolume {
+        volume_id: Uuid,
+        /// The disk backed by the volume, if any
+        disk_id: Option<Uuid>,
+        /// Number of the volume's regions on the sled
+        regions_on_sled: u32,
+        /// Number of the volume's regions that would remain on other sleds
+        regions_remaining: u32,
+    },
+    /// The sled hosts a CockroachDB node, and the CockroachDB cluster isn't
+    /// healthy enough to lose it
+    CockroachdbUnderreplicated { zone_id: Uuid, reason: String },
+    /// The planner could not plan a blueprint with the sled expunged
+    PlanningFailed { message: String },
+}
+
 // SWITCHES
 
 /// An operator's view of a Switch.
@@ -1535,6 +2107,76 @@ pub struct TargetRelease {
     pub release_source: TargetReleaseSource,
 }
 
+/// Versions of the rack's updateable components, for each sled
+///
+/// Current versions come from the latest inventory collection. Target versions
+/// of control plane zones come from the target blueprint; other components are
+/// updated to the target release.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct ComponentVersionMatrix {
+    /// System version of the target release, if one has been set
+    pub target_release: Option<Version>,
+    /// The target blueprint that zone target versions come from
+    #[schemars(with = "Uuid")]
+    pub blueprint_id: BlueprintUuid,
+    /// When the inventory that current versions come from was collected
+    pub time_collected: DateTime<Utc>,
+    /// Component versions for each in-service sled
+    pub sleds: Vec<SledComponentVersions>,
+}
+
+/// Versions of the updateable components on a single sled
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct SledComponentVersions {
+    pub sled_id: Uuid,
+    /// Serial number of the sled's baseboard, if it's in inventory
+    pub serial: Option<String>,
+    pub components: Vec<ComponentVersions>,
+}
+
+/// Current and target versions of a single component
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct ComponentVersions {
+    pub component: VersionedComponent,
+    pub current_version: ComponentVersion,
+    pub target_version: ComponentVersion,
+}
+
+/// An updateable component of a sled
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VersionedComponent {
+    /// The service processor
+    Sp,
+    /// The root of trust
+    Rot,
+    /// The root of trust's bootloader
+    RotBootloader,
+    /// The host operating system
+    HostOs,
+    /// A control plane zone
+    Zone {
+        zone_id: Uuid,
+        /// The kind of zone (e.g., `crucible` or `nexus`)
+        kind: String,
+    },
+}
+
+/// The release a component's software comes from
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ComponentVersion {
+    /// The software doesn't match any release the rack knows about.
+    Unknown,
+    /// The software was installed at the factory or by MUPdate, and isn't
+    /// tracked as part of a release.
+    InstallDataset,
+    /// The software from the given release.
+    Release { version: Version },
+    /// The component's software couldn't be determined.
+    Error { message: String },
+}
+
 /// Trusted root role used by the update system to verify update repositories.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, JsonSchema)]
 pub struct UpdatesTrustRoot {
@@ -1547,6 +2189,116 @@ pub struct UpdatesTrustRoot {
     pub root_role: TufSignedRootRole,
 }
 
+/// Progress of an update driven from the technician port (wicket), as
+/// reported to the control plane by wicketd.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct TechnicianPortUpdate {
+    /// Unique ID of this update of a single service processor
+    pub id: Uuid,
+    /// Type of the service processor being updated
+    pub sp_type: shared::ServiceProcessorType,
+    /// Slot of the service processor being updated
+    pub sp_slot: u16,
+    /// Time progress for this update was first reported
+    pub time_started: DateTime<Utc>,
+    /// Time progress for this update was most recently reported
+    pub time_reported: DateTime<Utc>,
+    /// Progress of each component being updated
+    pub components: Vec<shared::UpdateComponentProgress>,
+}
+
+// BLUEPRINTS
+
+/// Summary of a blueprint: a description of the intended configuration of
+/// the rack, produced by the rack reconfigurator.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct Blueprint {
+    /// Unique ID of this blueprint
+    #[schemars(with = "Uuid")]
+    pub id: BlueprintUuid,
+    /// ID of the blueprint from which this one was derived, if any
+    #[schemars(with = "Option<Uuid>")]
+    pub parent_blueprint_id: Option<BlueprintUuid>,
+    /// Time this blueprint was created
+    pub time_created: DateTime<Utc>,
+    /// Identity of the component (generally a Nexus instance) that created
+    /// this blueprint
+    pub creator: String,
+    /// Reason this blueprint was created
+    pub comment: String,
+    /// Whether this blueprint is the current target blueprint
+    pub is_target: bool,
+}
+
+/// The blueprint that the rack reconfigurator is currently working toward
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct BlueprintTarget {
+    /// The target blueprint
+    pub blueprint: Blueprint,
+    /// Whether the system is actively executing the target blueprint
+    ///
+    /// When disabled, the target blueprint is recorded but its changes are
+    /// not being applied to the rack.
+    pub enabled: bool,
+    /// Time this blueprint was made the target
+    pub time_made_target: DateTime<Utc>,
+}
+
+// BACKGROUND TASKS
+
+/// Status of a background task
+///
+/// Each Nexus instance runs its own copy of every background task. This
+/// describes the tasks run by the Nexus instance that handled the request.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct BackgroundTask {
+    /// Unique name of this background task
+    pub name: String,
+    /// Brief summary of what this task does
+    pub description: String,
+    /// How long after an activation completes before another is triggered
+    /// automatically, in milliseconds
+    pub period_ms: u64,
+    /// Time the in-progress activation started, if the task is running now
+    pub time_running_since: Option<DateTime<Utc>>,
+    /// The most recently completed activation, if any
+    pub last_activation: Option<BackgroundTaskActivation>,
+}
+
+/// Describes why a background task was activated
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundTaskActivationReason {
+    /// Another part of the system explicitly asked for the task to run
+    Signaled,
+    /// The task's period elapsed
+    Timeout,
+    /// A task that this one depends on did something
+    Dependency,
+}
+
+/// A completed activation of a background task
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub struct BackgroundTaskActivation {
+    /// Which activation of the task this was, counting from 1 when Nexus
+    /// started
+    pub iteration: u64,
+    /// What triggered this activation
+    pub reason: BackgroundTaskActivationReason,
+    /// Time this activation started
+    pub time_started: DateTime<Utc>,
+    /// How long the activation took, in milliseconds
+    pub duration_ms: u64,
+    /// Error reported by the task, if the activation failed
+    pub error: Option<String>,
+    /// Task-specific details reported by the activation
+    ///
+    /// The contents vary from task to task and are not a stable interface.
+    pub details: serde_json::Value,
+}
+
 fn expected_one_of<T: strum::VariantArray + fmt::Display>() -> String {
     use std::fmt::Write;
     let mut msg = "expected one of:".to_string();
@@ -1672,3 +2424,75 @@ pub struct AuditLogEntry {
     /// Result of the operation
     pub result: AuditLogEntryResult,
 }
+
+// RACK ACTIVITY
+
+/// The kinds of activity that appear in the rack activity feed
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RackActivityKind {
+    /// A request made through the API, as recorded in the audit log
+    AuditLog,
+    /// A change to the blueprint that the control plane is executing
+    BlueprintTarget,
+    /// Sleds and physical disks being added to or removed from the rack
+    Hardware,
+    /// Problems that the control plane raised an alert about
+    Fault,
+}
+
+/// Something that happened to the rack
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RackActivity {
+    /// ID of the record this activity came from: the audit log entry,
+    /// blueprint, sled, physical disk, or alert
+    pub id: Uuid,
+    /// When the activity happened
+    pub time: DateTime<Utc>,
+    pub kind: RackActivityKind,
+    /// Human-readable summary of the activity
+    pub description: String,
+    pub details: RackActivityDetails,
+}
+
+/// Details of an item in the rack activity feed, which depend on its source
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RackActivityDetails {
+    /// A request recorded in the audit log
+    AuditLog { entry: AuditLogEntry },
+    /// A blueprint was made the current target
+    BlueprintTarget {
+        #[schemars(with = "Uuid")]
+        blueprint_id: BlueprintUuid,
+        /// Whether execution of the blueprint was enabled
+        enabled: bool,
+    },
+    /// A sled was added to the rack
+    SledAdded { sled_id: Uuid, serial: String, part: String },
+    /// A physical disk was added to a sled
+    PhysicalDiskAdded {
+        disk_id: Uuid,
+        sled_id: Uuid,
+        vendor: String,
+        serial: String,
+        model: String,
+    },
+    /// A physical disk was removed from service
+    PhysicalDiskRemoved {
+        disk_id: Uuid,
+        sled_id: Uuid,
+        vendor: String,
+        serial: String,
+        model: String,
+    },
+    /// An alert was raised for a problem in the rack
+    Fault {
+        /// The class of the alert, e.g., `storage.zpool.capacity`
+        alert_class: String,
+        /// The alert's payload
+        payload: serde_json::Value,
+    },
+}
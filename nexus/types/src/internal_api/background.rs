@@ -4,19 +4,27 @@
 
 use crate::deployment::PlanningReport;
 use crate::external_api::views;
+use crate::internal_api::views::RollingSledRebootState;
+use crate::internal_api::views::RollingSledRebootStep;
 use chrono::DateTime;
 use chrono::Utc;
+use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::Generation;
 use omicron_uuid_kinds::AlertReceiverUuid;
 use omicron_uuid_kinds::AlertUuid;
 use omicron_uuid_kinds::BlueprintUuid;
 use omicron_uuid_kinds::CollectionUuid;
+use omicron_uuid_kinds::DatasetUuid;
+use omicron_uuid_kinds::InstanceUuid;
+use omicron_uuid_kinds::OmicronZoneUuid;
 use omicron_uuid_kinds::SledUuid;
 use omicron_uuid_kinds::SupportBundleUuid;
 use omicron_uuid_kinds::WebhookDeliveryUuid;
+use omicron_uuid_kinds::ZpoolUuid;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tufaceous_artifact::ArtifactHash;
@@ -89,6 +97,10 @@ pub struct AbandonedVmmReaperStatus {
     pub sled_reservations_deleted: usize,
     pub vmms_deleted: usize,
     pub vmms_already_deleted: usize,
+    /// VMMs that are abandoned according to the database, but which their
+    /// sled-agent reports are still present. These are not deleted.
+    #[serde(default)]
+    pub vmms_still_on_sled: usize,
     pub errors: Vec<String>,
 }
 
@@ -153,6 +165,14 @@ pub struct InstanceReincarnationStatus {
     pub errors: Vec<String>,
     /// Errors that occurred while restarting individual instances.
     pub restart_errors: Vec<(ReincarnatableInstance, String)>,
+    /// If `true`, this activation started as many instance-start sagas as it
+    /// may in a single activation, so any other instances in need of
+    /// reincarnation were left for the next activation.
+    pub pass_limit_reached: bool,
+    /// Projects in which this activation started as many instance-start sagas
+    /// as it may for a single project. Any other instances in those projects
+    /// that need reincarnation were left for the next activation.
+    pub projects_at_limit: BTreeSet<Uuid>,
 }
 
 impl InstanceReincarnationStatus {
@@ -608,3 +628,154 @@ pub struct EreporterStatus {
     pub requests: usize,
     pub errors: Vec<String>,
 }
+
+/// The status of a `clickhouse_backup` background task activation
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+pub struct ClickhouseBackupStatus {
+    /// If `true`, then backups have been explicitly disabled by the config
+    /// file.
+    pub disabled: bool,
+    /// The single-node ClickHouse zone that was backed up, if any
+    pub zone_id: Option<OmicronZoneUuid>,
+    /// Name of the backup taken during this activation, if any
+    pub backup_created: Option<String>,
+    /// Names of old backups deleted to stay within the retention limit
+    pub backups_deleted: Vec<String>,
+    /// Names of the backups still present, oldest first
+    pub backups_retained: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// The status of a `dataset_quota_tuner` background task activation
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+pub struct DatasetQuotaTunerStatus {
+    /// If `true`, then quota proposals have been explicitly disabled by the
+    /// config file.
+    pub disabled: bool,
+    /// The target blueprint whose dataset quotas were considered, if any
+    pub blueprint_id: Option<BlueprintUuid>,
+    /// The inventory collection whose dataset usage was observed, if any
+    pub inventory_collection_id: Option<CollectionUuid>,
+    /// Number of in-service datasets with usage-based quotas whose usage was
+    /// found in inventory
+    pub datasets_observed: usize,
+    /// Quotas proposed for the next blueprint, keyed by dataset ID
+    pub proposed_quotas: BTreeMap<DatasetUuid, ByteCount>,
+    pub errors: Vec<String>,
+}
+
+/// The status of a `snapshot_scheduler` background task activation
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+pub struct SnapshotSchedulerStatus {
+    /// Number of snapshot schedules that were due to take snapshots
+    pub schedules_run: usize,
+    /// Snapshot create sagas started during this activation
+    pub snapshots_requested: Vec<SnapshotScheduleAction>,
+    /// Snapshot delete sagas started during this activation to stay within
+    /// each schedule's retention count
+    pub snapshots_pruned: Vec<SnapshotScheduleAction>,
+    pub errors: Vec<String>,
+}
+
+/// A snapshot of a disk created or deleted on behalf of a snapshot schedule
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct SnapshotScheduleAction {
+    pub schedule_id: Uuid,
+    pub disk_id: Uuid,
+    pub snapshot_name: String,
+}
+
+/// The status of a `zpool_usage_trends` background task activation
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+pub struct ZpoolUsageTrendsStatus {
+    /// If `true`, then trend analysis has been explicitly disabled by the
+    /// config file.
+    pub disabled: bool,
+    /// The inventory collection whose zpool usage was sampled, if any
+    pub inventory_collection_id: Option<CollectionUuid>,
+    /// Number of new usage samples recorded from the inventory collection
+    pub samples_recorded: usize,
+    /// Number of usage samples deleted for being past the retention period
+    pub samples_pruned: usize,
+    /// Number of zpools whose usage trend was analyzed
+    pub zpools_analyzed: usize,
+    /// Zpools that are nearly full, or are projected to be nearly full soon
+    pub warnings: Vec<ZpoolCapacityWarning>,
+    /// Alerts published for zpools in `warnings` during this activation
+    pub alerts_published: Vec<AlertUuid>,
+    pub errors: Vec<String>,
+}
+
+/// A zpool that is nearly full, or is projected to be nearly full soon
+///
+/// This is also the payload of `storage.zpool.capacity` alerts.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ZpoolCapacityWarning {
+    pub zpool_id: ZpoolUuid,
+    pub sled_id: SledUuid,
+    pub total_size: ByteCount,
+    pub allocated: ByteCount,
+    /// Fragmentation of the zpool's free space in whole percent, if known
+    pub fragmentation_percent: Option<u8>,
+    /// The percentage of `total_size` considered nearly full
+    pub threshold_percent: u8,
+    /// Whole days until the zpool's allocated space is projected to reach
+    /// `threshold_percent` of its size, based on the trend of recent samples
+    ///
+    /// This is 0 if the zpool has already reached the threshold, and `None`
+    /// if its allocated space isn't growing.
+    pub days_until_threshold: Option<u32>,
+}
+
+/// The status of a `zpool_scrub_alerts` background task activation
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+pub struct ZpoolScrubAlertsStatus {
+    /// If `true`, then scrub alerts have been explicitly disabled by the
+    /// config file.
+    pub disabled: bool,
+    /// The inventory collection whose zpool scrubs were checked, if any
+    pub inventory_collection_id: Option<CollectionUuid>,
+    /// Number of zpools whose most recent scrub was checked
+    pub zpools_checked: usize,
+    /// Zpools whose most recent scrub has finished, but found errors
+    pub scrubs_with_errors: Vec<ZpoolScrubErrors>,
+    /// Alerts published for scrubs in `scrubs_with_errors` during this
+    /// activation
+    pub alerts_published: Vec<AlertUuid>,
+    pub errors: Vec<String>,
+}
+
+/// A finished scrub of a zpool that found errors
+///
+/// This is also the payload of `storage.zpool.scrub_errors` alerts.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ZpoolScrubErrors {
+    pub zpool_id: ZpoolUuid,
+    pub sled_id: SledUuid,
+    /// When the scrub finished
+    pub time_finished: DateTime<Utc>,
+    /// Number of errors the scrub found
+    pub errors: u64,
+}
+
+/// The status of a `rolling_sled_reboot` background task activation
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+pub struct RollingSledRebootStatus {
+    /// If `true`, then rolling sled reboots have been explicitly disabled by
+    /// the config file.
+    pub disabled: bool,
+    /// The rolling reboot that was driven forward, if one is running
+    pub reboot_id: Option<Uuid>,
+    /// The step each sled that changed steps during this activation moved to
+    pub sleds_advanced: BTreeMap<SledUuid, RollingSledRebootStep>,
+    /// Instances for which a migration saga was started
+    pub migrations_started: Vec<InstanceUuid>,
+    /// Instances that were stopped because they couldn't be migrated
+    pub instances_stopped: Vec<InstanceUuid>,
+    /// Previously-stopped instances that were started again
+    pub instances_restarted: Vec<InstanceUuid>,
+    /// The state the reboot finished in, if it finished during this
+    /// activation
+    pub finished: Option<RollingSledRebootState>,
+    pub errors: Vec<String>,
+}
@@ -8,6 +8,8 @@ use crate::deployment::Blueprint;
 use crate::external_api::params::PhysicalDiskKind;
 use crate::external_api::shared::Baseboard;
 use crate::external_api::shared::IpRange;
+use crate::external_api::shared::ServiceProcessorType;
+use crate::external_api::shared::UpdateComponentProgress;
 use nexus_sled_agent_shared::inventory::{SledCpuFamily, SledRole};
 use nexus_sled_agent_shared::recovery_silo::RecoverySiloConfig;
 use omicron_common::api::external::ByteCount;
@@ -21,13 +23,16 @@ use omicron_common::api::internal::shared::RackNetworkConfig;
 use omicron_common::api::internal::shared::SourceNatConfig;
 use omicron_uuid_kinds::DatasetUuid;
 use omicron_uuid_kinds::PhysicalDiskUuid;
+use omicron_uuid_kinds::SledUuid;
 use omicron_uuid_kinds::ZpoolUuid;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::net::SocketAddrV6;
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Sent by a sled agent to Nexus to inform about resources
@@ -215,3 +220,89 @@ pub struct InstanceMigrateRequest {
     /// The ID of the sled to which to migrate the target instance.
     pub dst_sled_id: Uuid,
 }
+
+/// Parameters used to enable the break-glass operator account.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct BreakGlassEnableRequest {
+    /// Hash of the password the operator will log in with
+    pub password_hash: omicron_passwords::NewPasswordHash,
+    /// How long the account may be used for, in seconds
+    pub ttl_secs: u32,
+    /// Who is enabling the account, recorded for auditing
+    pub enabled_by: String,
+}
+
+/// Parameters used to disable the break-glass operator account.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct BreakGlassDisableRequest {
+    /// Who is disabling the account, recorded for auditing
+    pub disabled_by: String,
+}
+
+/// Progress of technician port updates, reported by wicketd.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct TechnicianPortUpdateReport {
+    pub updates: Vec<TechnicianPortUpdateProgress>,
+}
+
+/// Progress of an update of a single service processor's components.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct TechnicianPortUpdateProgress {
+    /// ID of the wicketd update execution driving this update
+    pub id: Uuid,
+    pub sp_type: ServiceProcessorType,
+    pub sp_slot: u16,
+    pub components: Vec<UpdateComponentProgress>,
+}
+
+/// Parameters for checking the reference counts of read-only Crucible
+/// resources
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct VolumeReferenceCheckParams {
+    /// If true, add missing volume resource usage records and remove stale
+    /// ones. Otherwise, inconsistencies are only reported.
+    #[serde(default)]
+    pub repair: bool,
+}
+
+/// Minimum severity of messages written to the Nexus log
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+/// Runtime configuration changes to apply to a running Nexus
+///
+/// These take effect immediately but are not persisted: they're lost when
+/// Nexus restarts. Settings that are not specified are left unchanged.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct RuntimeConfigUpdate {
+    /// New minimum severity of messages written to the log
+    #[serde(default)]
+    pub log_level: Option<LogLevel>,
+    /// New periods for background tasks, by task name
+    ///
+    /// Periods must be non-zero.
+    #[serde(default)]
+    pub bgtask_periods: BTreeMap<String, Duration>,
+}
+
+/// Parameters for rebooting a set of sleds, a batch at a time
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RollingSledRebootRequest {
+    /// The sleds to reboot, in the order in which to reboot them
+    pub sled_ids: Vec<SledUuid>,
+    /// The most sleds that may be out of service at once
+    ///
+    /// By default, sleds are rebooted one at a time.
+    #[serde(default)]
+    pub batch_size: Option<u16>,
+}
@@ -2,8 +2,18 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::deployment::Blueprint;
+use crate::deployment::BlueprintZoneImageSource;
 use crate::deployment::PendingMgsUpdate;
 use crate::deployment::TargetReleaseDescription;
+use crate::external_api::views::ComponentVersion;
+use crate::external_api::views::ComponentVersionMatrix;
+use crate::external_api::views::ComponentVersions;
+use crate::external_api::views::SledComponentVersions;
+use crate::external_api::views::SledPolicy;
+use crate::external_api::views::SledState;
+use crate::external_api::views::VersionedComponent;
+use crate::internal_api::params::LogLevel;
 use crate::inventory::BaseboardId;
 use crate::inventory::CabooseWhich;
 use crate::inventory::Collection;
@@ -22,14 +32,22 @@ use nexus_sled_agent_shared::inventory::BootPartitionDetails;
 use nexus_sled_agent_shared::inventory::ConfigReconcilerInventoryResult;
 use nexus_sled_agent_shared::inventory::OmicronZoneImageSource;
 use nexus_sled_agent_shared::inventory::OmicronZoneType;
+use omicron_common::api::external::AffinityPolicy;
+use omicron_common::api::external::ByteCount;
 use omicron_common::api::external::MacAddr;
+use omicron_common::api::external::Name;
 use omicron_common::api::external::ObjectStream;
 use omicron_common::api::external::TufArtifactMeta;
 use omicron_common::api::external::Vni;
 use omicron_common::disk::M2Slot;
 use omicron_common::snake_case_result;
 use omicron_common::snake_case_result::SnakeCaseResult;
+use omicron_uuid_kinds::DatasetUuid;
 use omicron_uuid_kinds::DemoSagaUuid;
+use omicron_uuid_kinds::GenericUuid;
+use omicron_uuid_kinds::InstanceUuid;
+use omicron_uuid_kinds::SiloUserUuid;
+use omicron_uuid_kinds::VolumeUuid;
 use omicron_uuid_kinds::{OmicronZoneUuid, SledUuid};
 use schemars::JsonSchema;
 use semver::Version;
@@ -40,6 +58,7 @@ use std::collections::VecDeque;
 use std::fmt::Display;
 use std::net::IpAddr;
 use std::net::Ipv6Addr;
+use std::net::SocketAddrV6;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
@@ -697,6 +716,9 @@ pub struct MgsDrivenUpdateStatus {
     pub rot: RotStatus,
     pub sp: SpStatus,
     pub host_os_phase_1: HostPhase1Status,
+    /// If the SP couldn't be reached during the latest inventory collection,
+    /// when the information here was actually collected
+    pub stale_since: Option<DateTime<Utc>>,
 }
 
 impl MgsDrivenUpdateStatus {
@@ -776,6 +798,7 @@ impl MgsDrivenUpdateStatusBuilder<'_> {
                 slot1_version: self.version_for_caboose(CabooseWhich::SpSlot1),
             },
             host_os_phase_1,
+            stale_since: self.inventory.sp_stale_since(self.baseboard_id),
         }
     }
 
@@ -967,6 +990,165 @@ impl UpdateStatus {
     }
 }
 
+impl UpdateStatus {
+    /// Assembles the per-sled component version matrix shown by the external
+    /// API
+    ///
+    /// Current versions come from this status (and so from `inventory`).
+    /// Target versions of control plane zones are the images `blueprint` asks
+    /// for; every other component is updated to the target release `new`.
+    /// (Without a target release, those components aren't being moved
+    /// anywhere, so their target is whatever they're running now.)
+    pub fn version_matrix(
+        &self,
+        old: &TargetReleaseDescription,
+        new: &TargetReleaseDescription,
+        inventory: &Collection,
+        blueprint: &Blueprint,
+    ) -> ComponentVersionMatrix {
+        let target_release =
+            new.tuf_repo().map(|repo| repo.repo.system_version.clone());
+        let release_target = |current: &ComponentVersion| match &target_release
+        {
+            Some(version) => {
+                ComponentVersion::Release { version: version.clone() }
+            }
+            None => current.clone(),
+        };
+
+        let sleds = blueprint
+            .sleds
+            .iter()
+            .filter(|(_, sled)| sled.state == SledState::Active)
+            .map(|(&sled_id, sled_config)| {
+                let baseboard_id = inventory
+                    .sled_agents
+                    .get(&sled_id)
+                    .and_then(|sa| sa.baseboard_id.as_deref());
+                let mgs_driven = baseboard_id.and_then(|baseboard_id| {
+                    self.mgs_driven.get(baseboard_id.to_string().as_str())
+                });
+                let sled_status = self.sleds.get(&sled_id);
+
+                let (sp, rot, rot_bootloader) = match mgs_driven {
+                    Some(status) => (
+                        // The SP always runs the image in slot 0.
+                        status.sp.slot0_version.clone(),
+                        match status.rot.active_slot {
+                            Some(RotSlot::A) => {
+                                status.rot.slot_a_version.clone()
+                            }
+                            Some(RotSlot::B) => {
+                                status.rot.slot_b_version.clone()
+                            }
+                            None => TufRepoVersion::Unknown,
+                        },
+                        status.rot_bootloader.stage0_version.clone(),
+                    ),
+                    None => (
+                        TufRepoVersion::Unknown,
+                        TufRepoVersion::Unknown,
+                        TufRepoVersion::Unknown,
+                    ),
+                };
+                let host_os = match sled_status.map(|s| &s.host_phase_2) {
+                    Some(HostPhase2Status {
+                        boot_disk: Ok(M2Slot::A),
+                        slot_a_version,
+                        ..
+                    }) => slot_a_version.clone(),
+                    Some(HostPhase2Status {
+                        boot_disk: Ok(M2Slot::B),
+                        slot_b_version,
+                        ..
+                    }) => slot_b_version.clone(),
+                    Some(HostPhase2Status {
+                        boot_disk: Err(message), ..
+                    }) => TufRepoVersion::Error(message.clone()),
+                    None => TufRepoVersion::Unknown,
+                };
+
+                let mut components = Vec::new();
+                for (component, current) in [
+                    (VersionedComponent::Sp, sp),
+                    (VersionedComponent::Rot, rot),
+                    (VersionedComponent::RotBootloader, rot_bootloader),
+                    (VersionedComponent::HostOs, host_os),
+                ] {
+                    let current_version = ComponentVersion::from(current);
+                    components.push(ComponentVersions {
+                        component,
+                        target_version: release_target(&current_version),
+                        current_version,
+                    });
+                }
+
+                for zone in sled_config
+                    .zones
+                    .iter()
+                    .filter(|zone| zone.disposition.should_be_running())
+                {
+                    // A zone that's not in inventory hasn't been started yet.
+                    let current = sled_status
+                        .and_then(|status| status.zones.get(&zone.id))
+                        .map_or(TufRepoVersion::Unknown, |status| {
+                            status.version.clone()
+                        });
+                    let target = match &zone.image_source {
+                        BlueprintZoneImageSource::InstallDataset => {
+                            TufRepoVersion::InstallDataset
+                        }
+                        BlueprintZoneImageSource::Artifact { hash, .. } => {
+                            TufRepoVersion::for_artifact(old, new, *hash)
+                        }
+                    };
+                    components.push(ComponentVersions {
+                        component: VersionedComponent::Zone {
+                            zone_id: zone.id.into_untyped_uuid(),
+                            kind: zone
+                                .zone_type
+                                .kind()
+                                .report_str()
+                                .to_string(),
+                        },
+                        current_version: current.into(),
+                        target_version: target.into(),
+                    });
+                }
+
+                SledComponentVersions {
+                    sled_id: sled_id.into_untyped_uuid(),
+                    serial: baseboard_id
+                        .map(|baseboard_id| baseboard_id.serial_number.clone()),
+                    components,
+                }
+            })
+            .collect();
+
+        ComponentVersionMatrix {
+            target_release,
+            blueprint_id: blueprint.id,
+            time_collected: inventory.time_done,
+            sleds,
+        }
+    }
+}
+
+impl From<TufRepoVersion> for ComponentVersion {
+    fn from(version: TufRepoVersion) -> Self {
+        match version {
+            TufRepoVersion::Unknown => ComponentVersion::Unknown,
+            TufRepoVersion::InstallDataset => ComponentVersion::InstallDataset,
+            TufRepoVersion::Version(version) => {
+                ComponentVersion::Release { version }
+            }
+            TufRepoVersion::Error(message) => {
+                ComponentVersion::Error { message }
+            }
+        }
+    }
+}
+
 /// Describes whether Nexus is quiescing or quiesced and what, if anything, is
 /// blocking the quiesce process
 ///
@@ -1193,3 +1375,245 @@ mod test {
         assert_eq!(deserialized, status);
     }
 }
+
+/// A period during which the break-glass operator account may be used
+///
+/// The break-glass account is a local user in the default silo that is granted
+/// read-only access to the fleet.  It is enabled from the technician port when
+/// the usual means of logging in are unavailable, and stops working once it
+/// expires or is disabled.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct BreakGlassAccount {
+    pub id: Uuid,
+    /// the silo to log into
+    pub silo_name: Name,
+    /// the name to log in with
+    pub username: String,
+    /// the user that logs in with this account
+    pub silo_user_id: SiloUserUuid,
+    pub time_created: DateTime<Utc>,
+    /// who enabled the account
+    pub enabled_by: String,
+    /// when the account stops being usable
+    pub time_expires: DateTime<Utc>,
+    /// when the account was disabled, if it has been
+    pub time_disabled: Option<DateTime<Utc>>,
+    /// who disabled the account, if it has been
+    pub disabled_by: Option<String>,
+}
+
+/// A read-only Crucible resource, which may be used by many volumes
+#[derive(
+    Clone,
+    Debug,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VolumeReadOnlyResource {
+    ReadOnlyRegion {
+        region_id: Uuid,
+    },
+    RegionSnapshot {
+        dataset_id: DatasetUuid,
+        region_id: Uuid,
+        snapshot_id: Uuid,
+    },
+}
+
+/// Results of checking the reference counts of read-only Crucible resources
+///
+/// Each read-only resource referenced by a volume is expected to have exactly
+/// one volume resource usage record for that volume, and those records are what
+/// determine when the resource can be freed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct VolumeReferenceCheckReport {
+    /// whether fixable inconsistencies were repaired, rather than only
+    /// reported
+    pub repair: bool,
+    /// number of volumes (that haven't been deleted) that were checked
+    pub volumes_checked: usize,
+    /// number of volume resource usage records that were checked
+    pub usage_records_checked: usize,
+    /// resources referenced by a volume that has no usage record for them;
+    /// these resources could be freed while still in use
+    pub missing_usage: Vec<VolumeReferenceInconsistency>,
+    /// usage records that don't correspond to a reference from a volume (e.g.,
+    /// because the volume has been deleted); these resources are leaked
+    pub stale_usage: Vec<VolumeReferenceInconsistency>,
+    /// read-only targets referenced by a volume for which no resource remains
+    /// (or for which the resource is being deleted); these can't be repaired
+    pub freed_while_referenced: Vec<VolumeFreedTarget>,
+    /// errors encountered while checking or repairing
+    pub errors: Vec<String>,
+}
+
+/// A volume's reference to a read-only resource that doesn't match its
+/// recorded usage
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct VolumeReferenceInconsistency {
+    pub volume_id: VolumeUuid,
+    pub resource: VolumeReadOnlyResource,
+    /// whether this inconsistency was repaired
+    pub repaired: bool,
+}
+
+/// A read-only target referenced by a volume that no longer has a
+/// corresponding resource
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct VolumeFreedTarget {
+    pub volume_id: VolumeUuid,
+    pub target: SocketAddrV6,
+}
+
+/// Runtime configuration of a running Nexus
+///
+/// This reflects any changes applied at runtime, which are lost when Nexus
+/// restarts.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RuntimeConfig {
+    /// minimum severity of messages written to the log
+    pub log_level: LogLevel,
+    /// current period of each background task, by task name
+    pub bgtask_periods: BTreeMap<String, Duration>,
+}
+
+/// Explanation of where an instance of a given shape could be placed
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct InstancePlacementExplanation {
+    /// instance whose affinity and anti-affinity groups were considered, if
+    /// any
+    pub instance_id: Option<InstanceUuid>,
+    /// hardware threads the instance needs
+    pub hardware_threads: u32,
+    /// reservoir memory the instance needs
+    pub reservoir_ram: ByteCount,
+    /// each commissioned sled, and why (if at all) the instance can't be
+    /// placed on it
+    pub sleds: Vec<SledPlacementExplanation>,
+}
+
+/// Whether an instance could be placed on one sled, and if not, why not
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct SledPlacementExplanation {
+    pub sled_id: SledUuid,
+    /// policy of the instance's affinity groups that include an instance on
+    /// this sled, if any
+    pub affinity_policy: Option<AffinityPolicy>,
+    /// policy of the instance's anti-affinity groups that include an instance
+    /// on this sled, if any
+    ///
+    /// As with placement itself, this is only reported for sleds with room
+    /// for the instance.
+    pub anti_affinity_policy: Option<AffinityPolicy>,
+    /// reasons the instance can't be placed on this sled; if empty, it can
+    pub rejections: Vec<SledPlacementRejection>,
+}
+
+/// A reason an instance can't be placed on a sled
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum SledPlacementRejection {
+    /// the sled's policy doesn't allow new instances
+    Policy { policy: SledPolicy },
+    /// the sled isn't active
+    State { state: SledState },
+    /// the sled doesn't have room for the instance; each field is how much
+    /// more of that resource would be needed
+    Capacity {
+        hardware_threads: u64,
+        rss_ram: ByteCount,
+        reservoir_ram: ByteCount,
+    },
+    /// the instance is in an anti-affinity group with the "fail" policy that
+    /// includes an instance on this sled
+    AntiAffinity,
+    /// the instance is in affinity groups with the "fail" policy that require
+    /// it to be placed on other sleds (or on more than one sled)
+    Affinity { required_sleds: Vec<SledUuid> },
+}
+
+/// A rolling reboot of a set of sleds, a batch at a time
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct RollingSledReboot {
+    pub id: Uuid,
+    pub time_created: DateTime<Utc>,
+    /// when the reboot completed, failed, or was cancelled
+    pub time_finished: Option<DateTime<Utc>>,
+    /// the most sleds that may be out of service at once
+    pub batch_size: u16,
+    pub state: RollingSledRebootState,
+    /// when an operator asked for the reboot to be cancelled, if they did
+    pub time_cancel_requested: Option<DateTime<Utc>>,
+    /// the sleds being rebooted, in the order in which they're rebooted
+    pub sleds: Vec<RollingSledRebootSled>,
+}
+
+/// The overall state of a rolling reboot of sleds
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RollingSledRebootState {
+    /// sleds are still being rebooted
+    Running,
+    /// every sled was rebooted and rejoined the control plane
+    Completed,
+    /// a sled could not be rebooted, so no further sleds were
+    Failed,
+    /// the reboot was cancelled before every sled was rebooted
+    Cancelled,
+}
+
+/// How far a rolling reboot has gotten with one sled
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct RollingSledRebootSled {
+    pub sled_id: SledUuid,
+    pub step: RollingSledRebootStep,
+    /// when the sled moved to its current step
+    pub time_step_changed: DateTime<Utc>,
+    /// when the sled's SP was asked to reset it, if it has been
+    pub time_reset: Option<DateTime<Utc>>,
+    /// why the sled could not be rebooted, if it couldn't
+    pub error: Option<String>,
+    /// instances that were stopped, rather than migrated, to reboot this sled
+    pub stopped_instances: Vec<RollingSledRebootInstance>,
+}
+
+/// A step of rebooting one sled as part of a rolling reboot
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RollingSledRebootStep {
+    /// the sled hasn't been started on
+    Pending,
+    /// the sled is quiesced, and its instances are being migrated to other
+    /// sleds (or stopped, if they can't be)
+    Draining,
+    /// the sled is about to be reset via its SP
+    Rebooting,
+    /// the sled has been reset, and is expected to report its zones running
+    /// again
+    Rejoining,
+    /// the sled was rebooted and is back in service
+    Done,
+    /// the sled could not be rebooted
+    Failed,
+}
+
+/// An instance that a rolling reboot stopped, rather than migrated, so that
+/// it could reboot the sled it was running on
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct RollingSledRebootInstance {
+    pub instance_id: InstanceUuid,
+    pub time_stopped: DateTime<Utc>,
+    /// when the instance was started again once the sled was back, if it has
+    /// been
+    pub time_restarted: Option<DateTime<Utc>>,
+}
@@ -28,6 +28,8 @@ use nexus_sled_agent_shared::inventory::ConfigReconcilerInventoryStatus;
 use nexus_sled_agent_shared::inventory::InventoryDataset;
 use nexus_sled_agent_shared::inventory::InventoryDisk;
 use nexus_sled_agent_shared::inventory::InventoryZpool;
+use nexus_sled_agent_shared::inventory::InventoryZpoolHealth;
+use nexus_sled_agent_shared::inventory::InventoryZpoolScrub;
 use nexus_sled_agent_shared::inventory::OmicronSledConfig;
 use nexus_sled_agent_shared::inventory::OmicronZoneConfig;
 use nexus_sled_agent_shared::inventory::SledCpuFamily;
@@ -211,6 +213,22 @@ impl Collection {
             .and_then(|by_bb| by_bb.get(baseboard_id))
     }
 
+    /// If the service processor information for `baseboard_id` was carried
+    /// over from an earlier collection because the SP (or MGS) couldn't be
+    /// reached during this one, returns when that information was actually
+    /// collected
+    ///
+    /// This applies to everything we learn about a baseboard via its SP: the
+    /// SP state itself, its RoT state, cabooses, RoT pages, and host phase 1
+    /// information.
+    pub fn sp_stale_since(
+        &self,
+        baseboard_id: &BaseboardId,
+    ) -> Option<DateTime<Utc>> {
+        let sp = self.sps.get(baseboard_id)?;
+        (sp.time_collected < self.time_started).then_some(sp.time_collected)
+    }
+
     pub fn rot_state_for(
         &self,
         baseboard_id: &BaseboardId,
@@ -264,6 +282,26 @@ impl Collection {
             })
     }
 
+    /// Return the time synchronization status reported by the NTP zone on
+    /// sled `sled_id`, if there is one
+    pub fn sled_timesync(&self, sled_id: SledUuid) -> Option<&TimeSync> {
+        let sled_agent = self.sled_agents.get(&sled_id)?;
+        // Timesync status is collected from the NTP zones in either the sled's
+        // ledger or its last reconciliation, so look in both.
+        let ledgered = sled_agent
+            .ledgered_sled_config
+            .iter()
+            .flat_map(|config| config.zones.iter());
+        let reconciled = sled_agent
+            .last_reconciliation
+            .iter()
+            .flat_map(|r| r.last_reconciled_config.zones.iter());
+        ledgered
+            .chain(reconciled)
+            .filter(|zone| zone.zone_type.is_ntp())
+            .find_map(|zone| self.ntp_timesync.get(&zone.id))
+    }
+
     /// Iterate over the sled ids of sleds identified as Scrimlets
     pub fn scrimlets(&self) -> impl Iterator<Item = SledUuid> + '_ {
         self.sled_agents.iter().filter_map(|sa| {
@@ -399,6 +437,12 @@ pub struct CabooseFound {
     Clone, Debug, Ord, Eq, PartialOrd, PartialEq, Deserialize, Serialize,
 )]
 pub struct ServiceProcessor {
+    /// when this information was collected
+    ///
+    /// If this is earlier than the collection's `time_started`, the SP
+    /// couldn't be reached during the collection and this information (along
+    /// with everything else found via this SP) was carried over from an
+    /// earlier one.  See [`Collection::sp_stale_since()`].
     pub time_collected: DateTime<Utc>,
     pub source: String,
 
@@ -625,11 +669,28 @@ pub struct Zpool {
     pub time_collected: DateTime<Utc>,
     pub id: ZpoolUuid,
     pub total_size: ByteCount,
+    /// Space allocated within this zpool, if known
+    pub allocated: Option<ByteCount>,
+    /// Fragmentation of this zpool's free space in whole percent, if known
+    pub fragmentation_percent: Option<u8>,
+    /// Progress of the most recent scrub of this zpool, if known
+    pub scrub: Option<InventoryZpoolScrub>,
+    /// Health, error counters, and most recent resilver of this zpool, if
+    /// known
+    pub health: Option<InventoryZpoolHealth>,
 }
 
 impl Zpool {
     pub fn new(time_collected: DateTime<Utc>, pool: InventoryZpool) -> Zpool {
-        Zpool { time_collected, id: pool.id, total_size: pool.total_size }
+        Zpool {
+            time_collected,
+            id: pool.id,
+            total_size: pool.total_size,
+            allocated: pool.allocated,
+            fragmentation_percent: pool.fragmentation_percent,
+            scrub: pool.scrub,
+            health: pool.health,
+        }
     }
 }
 
@@ -726,6 +787,12 @@ pub struct TimeSync {
 
     /// Whether or not the service claims time is synchronized
     pub synced: bool,
+
+    /// The offset between the NTP clock and the sled's system clock, in
+    /// microseconds
+    ///
+    /// This is `None` for collections made before it was recorded.
+    pub correction_usec: Option<i64>,
 }
 
 impl IdOrdItem for TimeSync {
@@ -21,6 +21,8 @@ use nexus_sled_agent_shared::inventory::{
     BootImageHeader, BootPartitionContents, BootPartitionDetails,
     ConfigReconcilerInventory, ConfigReconcilerInventoryResult,
     ConfigReconcilerInventoryStatus, HostPhase2DesiredContents,
+    InventoryZpoolHealth, InventoryZpoolHealthState, InventoryZpoolResilver,
+    InventoryZpoolResilverState, InventoryZpoolScrub, InventoryZpoolScrubState,
     OmicronSledConfig, OmicronZoneImageSource, OrphanedDataset,
     RemoveMupdateOverrideBootSuccessInventory,
 };
@@ -421,6 +423,13 @@ fn display_devices(
                 .to_rfc3339_opts(SecondsFormat::Millis, /* use_z */ true),
             sp.source
         )?;
+        if collection.sp_stale_since(baseboard_id).is_some() {
+            writeln!(
+                f,
+                "    STALE: SP could not be reached during this collection; \
+                 information is from an earlier one"
+            )?;
+        }
 
         if sp.sp_type == SpType::Sled {
             #[derive(Tabled)]
@@ -669,9 +678,35 @@ fn display_sleds(
             writeln!(indented, "zpools")?;
         }
         for zpool in zpools {
-            let Zpool { id, total_size, .. } = zpool;
+            let Zpool {
+                id,
+                total_size,
+                allocated,
+                fragmentation_percent,
+                scrub,
+                health,
+                ..
+            } = zpool;
             let mut indent2 = IndentWriter::new("  ", &mut indented);
             writeln!(indent2, "{id}: total size: {total_size}")?;
+            let mut indent3 = IndentWriter::new("  ", &mut indent2);
+            if let Some(allocated) = allocated {
+                let fragmentation = fragmentation_percent
+                    .map_or_else(|| "unknown".to_string(), |f| format!("{f}%"));
+                writeln!(
+                    indent3,
+                    "allocated: {allocated}, fragmentation: {fragmentation}"
+                )?;
+            }
+            if let Some(scrub) = scrub {
+                writeln!(indent3, "{}", display_zpool_scrub(scrub))?;
+            }
+            if let Some(health) = health {
+                writeln!(indent3, "{}", display_zpool_health(health))?;
+                if let Some(resilver) = &health.resilver {
+                    writeln!(indent3, "{}", display_zpool_resilver(resilver))?;
+                }
+            }
         }
 
         if !datasets.is_empty() {
@@ -901,6 +936,99 @@ fn display_sleds(
     Ok(())
 }
 
+fn display_zpool_scrub(scrub: &InventoryZpoolScrub) -> String {
+    let InventoryZpoolScrub {
+        state,
+        start_time,
+        end_time,
+        percent_done,
+        errors,
+    } = scrub;
+    let time = |t: &Option<chrono::DateTime<chrono::Utc>>| match t {
+        Some(t) => {
+            t.to_rfc3339_opts(SecondsFormat::Secs, /* use_z */ true)
+        }
+        None => "(unknown)".to_string(),
+    };
+    let percent = |p: &Option<u8>| match p {
+        Some(p) => format!("{p}%"),
+        None => "(unknown)".to_string(),
+    };
+    match state {
+        InventoryZpoolScrubState::InProgress => format!(
+            "scrub: in progress since {}, {} done",
+            time(start_time),
+            percent(percent_done),
+        ),
+        InventoryZpoolScrubState::Paused => format!(
+            "scrub: paused (started {}), {} done",
+            time(start_time),
+            percent(percent_done),
+        ),
+        InventoryZpoolScrubState::Finished => format!(
+            "scrub: finished at {} with {} errors",
+            time(end_time),
+            option_impl_display(errors),
+        ),
+        InventoryZpoolScrubState::Canceled => {
+            format!("scrub: canceled at {}", time(end_time))
+        }
+    }
+}
+
+fn display_zpool_health(health: &InventoryZpoolHealth) -> String {
+    let InventoryZpoolHealth {
+        state,
+        read_errors,
+        write_errors,
+        checksum_errors,
+        resilver: _,
+    } = health;
+    let state = match state {
+        InventoryZpoolHealthState::Online => "online",
+        InventoryZpoolHealthState::Degraded => "degraded",
+        InventoryZpoolHealthState::Faulted => "faulted",
+        InventoryZpoolHealthState::Offline => "offline",
+        InventoryZpoolHealthState::Removed => "removed",
+        InventoryZpoolHealthState::Unavailable => "unavailable",
+    };
+    format!(
+        "health: {state}, errors: {read_errors} read, {write_errors} write, \
+         {checksum_errors} checksum"
+    )
+}
+
+fn display_zpool_resilver(resilver: &InventoryZpoolResilver) -> String {
+    let InventoryZpoolResilver {
+        state,
+        start_time,
+        end_time,
+        percent_done,
+        errors,
+    } = resilver;
+    let time = |t: &Option<chrono::DateTime<chrono::Utc>>| match t {
+        Some(t) => {
+            t.to_rfc3339_opts(SecondsFormat::Secs, /* use_z */ true)
+        }
+        None => "(unknown)".to_string(),
+    };
+    match state {
+        InventoryZpoolResilverState::InProgress => format!(
+            "resilver: in progress since {}, {} done",
+            time(start_time),
+            match percent_done {
+                Some(p) => format!("{p}%"),
+                None => "(unknown)".to_string(),
+            },
+        ),
+        InventoryZpoolResilverState::Finished => format!(
+            "resilver: finished at {} with {} errors",
+            time(end_time),
+            option_impl_display(errors),
+        ),
+    }
+}
+
 fn display_ntp_status(
     ntp_timesync: &IdOrdMap<TimeSync>,
     f: &mut dyn fmt::Write,
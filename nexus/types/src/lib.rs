@@ -30,6 +30,7 @@
 //! relatively minor offense, so it's the way we leave things for now.
 
 pub mod authn;
+pub mod clock;
 pub mod deployment;
 pub mod external_api;
 pub mod identity;
@@ -20,6 +20,9 @@ pub fn default_silo_name() -> &'static Name {
     &DEFAULT_SILO_NAME
 }
 
+/// The name of the break-glass operator user, which lives in the default silo.
+pub const BREAK_GLASS_USER_NAME: &str = "break-glass";
+
 /// The ID of the built-in internal silo.
 pub static INTERNAL_SILO_ID: uuid::Uuid =
     uuid::Uuid::from_u128(0x001de000_5110_4000_8000_000000000001);
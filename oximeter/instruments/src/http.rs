@@ -10,9 +10,10 @@ use dropshot::{
     HttpResponse, HttpResponseError, RequestContext, ServerContext,
 };
 use futures::Future;
-use http::StatusCode;
+use http::{Method, StatusCode};
 use oximeter::{
     MetricsError, Producer, Sample, histogram::Histogram, histogram::Record,
+    types::Cumulative,
 };
 use std::collections::HashMap;
 use std::hash::{DefaultHasher, Hash as _, Hasher};
@@ -20,9 +21,46 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 oximeter::use_timeseries!("http-service.toml");
+pub use http_service::EndpointErrorCount;
+pub use http_service::EndpointLatencyHistogram;
 pub use http_service::HttpService;
 pub use http_service::RequestLatencyHistogram;
 
+/// Return the class of an HTTP status code, e.g., `"4xx"` for a 404.
+pub fn status_class(status_code: StatusCode) -> &'static str {
+    if status_code.is_informational() {
+        "1xx"
+    } else if status_code.is_success() {
+        "2xx"
+    } else if status_code.is_redirection() {
+        "3xx"
+    } else if status_code.is_client_error() {
+        "4xx"
+    } else if status_code.is_server_error() {
+        "5xx"
+    } else {
+        // `StatusCode` can represent codes up to 999, which don't belong to
+        // any of the classes defined by RFC 9110.
+        "unknown"
+    }
+}
+
+/// Key identifying the per-endpoint timeseries for a request
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct EndpointKey {
+    operation_id: String,
+    http_method: Method,
+    status_class: &'static str,
+}
+
+/// Per-endpoint timeseries tracked for each [`EndpointKey`]
+#[derive(Clone, Debug)]
+struct EndpointMetrics {
+    latency: EndpointLatencyHistogram,
+    /// Only present for keys whose status class denotes an error.
+    errors: Option<EndpointErrorCount>,
+}
+
 impl RequestLatencyHistogram {
     /// Build a new `RequestLatencyHistogram` with a specified histogram.
     ///
@@ -95,6 +133,13 @@ pub struct LatencyTracker {
     /// `RequestLatencyHistogram` when handling a request that we already have
     /// one for. Instead, we use this key to get the existing entry.
     latencies: Arc<Mutex<HashMap<u64, RequestLatencyHistogram>>>,
+    /// Latency histograms and error counts for each endpoint, HTTP method, and
+    /// class of response status.
+    ///
+    /// These are coarser than `latencies` in the status code, which keeps the
+    /// number of timeseries small enough for SLO-style monitoring and
+    /// alerting, but also distinguish requests by HTTP method.
+    endpoints: Arc<Mutex<HashMap<EndpointKey, EndpointMetrics>>>,
     /// The histogram used to track each request.
     ///
     /// We store it here to clone as we see new requests.
@@ -109,6 +154,7 @@ impl LatencyTracker {
         Self {
             service,
             latencies: Arc::new(Mutex::new(HashMap::new())),
+            endpoints: Arc::new(Mutex::new(HashMap::new())),
             histogram,
         }
     }
@@ -150,6 +196,54 @@ impl LatencyTracker {
         entry.datum.sample(latency.as_nanos() as _).map_err(MetricsError::from)
     }
 
+    /// Update (or create) the per-endpoint timeseries in response to a new
+    /// request.
+    ///
+    /// This records `latency` in the endpoint's latency histogram for the
+    /// class of `status_code` and, if that class is a client or server error,
+    /// increments the endpoint's error count.
+    pub fn update_endpoint(
+        &self,
+        operation_id: &str,
+        http_method: &Method,
+        status_code: StatusCode,
+        latency: Duration,
+    ) -> Result<(), MetricsError> {
+        let status_class = status_class(status_code);
+        let key = EndpointKey {
+            operation_id: operation_id.to_string(),
+            http_method: http_method.clone(),
+            status_class,
+        };
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let entry = endpoints.entry(key).or_insert_with(|| {
+            let is_error =
+                status_code.is_client_error() || status_code.is_server_error();
+            EndpointMetrics {
+                latency: EndpointLatencyHistogram {
+                    operation_id: operation_id.to_string().into(),
+                    http_method: http_method.to_string().into(),
+                    status_class: status_class.into(),
+                    datum: self.histogram.clone(),
+                },
+                errors: is_error.then(|| EndpointErrorCount {
+                    operation_id: operation_id.to_string().into(),
+                    http_method: http_method.to_string().into(),
+                    status_class: status_class.into(),
+                    datum: Cumulative::new(0),
+                }),
+            }
+        });
+        if let Some(errors) = &mut entry.errors {
+            errors.datum.increment();
+        }
+        entry
+            .latency
+            .datum
+            .sample(latency.as_nanos() as _)
+            .map_err(MetricsError::from)
+    }
+
     /// Instrument the given Dropshot endpoint handler function.
     ///
     /// This method is intended as a semi-convenient way to instrument the handler for a `dropshot`
@@ -176,15 +270,20 @@ impl LatencyTracker {
             Ok(response) => response.status_code(),
             Err(ref e) => e.status_code().as_status(),
         };
-        if let Err(e) =
-            self.update(&context.endpoint.operation_id, status_code, latency)
-        {
+        let operation_id = &context.endpoint.operation_id;
+        let method = context.request.method();
+        let result_update =
+            self.update(operation_id, status_code, latency).and_then(|()| {
+                self.update_endpoint(operation_id, method, status_code, latency)
+            });
+        if let Err(e) = result_update {
             slog::error!(
                 &context.log,
                 "error instrumenting dropshot handler";
                 "error" => ?e,
                 "status_code" => status_code.as_u16(),
-                "operation_id" => &context.endpoint.operation_id,
+                "method" => %method,
+                "operation_id" => operation_id,
                 "remote_addr" => context.request.remote_addr(),
                 "latency" => ?latency,
             );
@@ -206,11 +305,20 @@ impl Producer for LatencyTracker {
         #[allow(clippy::needless_collect)]
         let latencies: Vec<_> =
             self.latencies.lock().unwrap().values().cloned().collect();
+        #[allow(clippy::needless_collect)]
+        let endpoints: Vec<_> =
+            self.endpoints.lock().unwrap().values().cloned().collect();
         let service = self.service.clone();
-        let samples = latencies
+        let mut samples = latencies
             .into_iter()
             .map(|latency| Sample::new(&service, &latency))
             .collect::<Result<Vec<_>, _>>()?;
+        for endpoint in endpoints {
+            samples.push(Sample::new(&service, &endpoint.latency)?);
+            if let Some(errors) = &endpoint.errors {
+                samples.push(Sample::new(&service, errors)?);
+            }
+        }
         Ok(Box::new(samples.into_iter()))
     }
 }
@@ -247,4 +355,81 @@ mod tests {
             assert_eq!(bins[1].count, 1);
         }
     }
+
+    #[test]
+    fn test_latency_tracker_endpoints() {
+        let service =
+            HttpService { name: "my-service".into(), id: ID.parse().unwrap() };
+        let hist = Histogram::new(&[100, 1000]).unwrap();
+        let tracker = LatencyTracker::new(service, hist);
+        let operation_id = "some_operation_id";
+        let latency = Duration::from_nanos(200);
+
+        // Two distinct 4xx codes share the same endpoint timeseries.
+        for status_code in [
+            StatusCode::OK,
+            StatusCode::NOT_FOUND,
+            StatusCode::BAD_REQUEST,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            tracker
+                .update_endpoint(
+                    operation_id,
+                    &Method::GET,
+                    status_code,
+                    latency,
+                )
+                .unwrap();
+        }
+        tracker
+            .update_endpoint(
+                operation_id,
+                &Method::POST,
+                StatusCode::OK,
+                latency,
+            )
+            .unwrap();
+
+        let endpoints = tracker.endpoints.lock().unwrap();
+        assert_eq!(endpoints.len(), 4);
+        let get = |method: Method, status_class| {
+            &endpoints[&EndpointKey {
+                operation_id: operation_id.to_string(),
+                http_method: method,
+                status_class,
+            }]
+        };
+
+        let ok = get(Method::GET, "2xx");
+        assert_eq!(ok.latency.datum.n_samples(), 1);
+        assert!(ok.errors.is_none());
+
+        let client_errors = get(Method::GET, "4xx");
+        assert_eq!(client_errors.latency.datum.n_samples(), 2);
+        assert_eq!(client_errors.errors.as_ref().unwrap().datum.value(), 2);
+
+        let server_errors = get(Method::GET, "5xx");
+        assert_eq!(server_errors.latency.datum.n_samples(), 1);
+        assert_eq!(server_errors.errors.as_ref().unwrap().datum.value(), 1);
+
+        let post = get(Method::POST, "2xx");
+        assert_eq!(post.latency.http_method, "POST");
+        assert!(post.errors.is_none());
+        drop(endpoints);
+
+        // One sample for each endpoint latency histogram, plus one for each
+        // error count.
+        let mut tracker = tracker;
+        assert_eq!(tracker.produce().unwrap().count(), 6);
+    }
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(status_class(StatusCode::CONTINUE), "1xx");
+        assert_eq!(status_class(StatusCode::NO_CONTENT), "2xx");
+        assert_eq!(status_class(StatusCode::FOUND), "3xx");
+        assert_eq!(status_class(StatusCode::FORBIDDEN), "4xx");
+        assert_eq!(status_class(StatusCode::INTERNAL_SERVER_ERROR), "5xx");
+        assert_eq!(status_class(StatusCode::from_u16(600).unwrap()), "unknown");
+    }
 }
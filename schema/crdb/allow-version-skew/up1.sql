@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.reconfigurator_chicken_switches
+    ADD COLUMN IF NOT EXISTS allow_version_skew BOOL NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.reconfigurator_chicken_switches
+    ALTER COLUMN allow_version_skew DROP DEFAULT;
@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.blueprint
+    ADD COLUMN IF NOT EXISTS scope_sled_ids UUID[];
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS omicron.public.bp_planning_report (
+    blueprint_id UUID PRIMARY KEY,
+    report JSONB NOT NULL
+);
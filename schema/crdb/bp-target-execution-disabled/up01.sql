@@ -0,0 +1,3 @@
+ALTER TABLE omicron.public.bp_target
+    ADD COLUMN IF NOT EXISTS execution_disabled_reason TEXT,
+    ADD COLUMN IF NOT EXISTS time_execution_disabled TIMESTAMPTZ;
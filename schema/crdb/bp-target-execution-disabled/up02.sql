@@ -0,0 +1,5 @@
+ALTER TABLE omicron.public.bp_target
+    ADD CONSTRAINT IF NOT EXISTS execution_disabled_reason_and_time CHECK (
+        (execution_disabled_reason IS NULL)
+            = (time_execution_disabled IS NULL)
+    );
@@ -0,0 +1 @@
+ALTER TYPE omicron.public.bp_zone_disposition ADD VALUE IF NOT EXISTS 'cordoned' AFTER 'expunged';
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS omicron.public.break_glass_account (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    silo_user_id UUID NOT NULL,
+    enabled_by TEXT NOT NULL,
+    time_expires TIMESTAMPTZ NOT NULL,
+    time_disabled TIMESTAMPTZ,
+    disabled_by TEXT,
+
+    CONSTRAINT disabled_by_iff_disabled CHECK (
+        (time_disabled IS NULL) = (disabled_by IS NULL)
+    )
+);
@@ -0,0 +1,3 @@
+CREATE UNIQUE INDEX IF NOT EXISTS lookup_enabled_break_glass_account
+    ON omicron.public.break_glass_account (silo_user_id)
+    WHERE time_disabled IS NULL;
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS lookup_break_glass_account_by_silo_user
+    ON omicron.public.break_glass_account (silo_user_id);
@@ -0,0 +1,4 @@
+CREATE INDEX IF NOT EXISTS lookup_disk_by_pantry_address ON omicron.public.disk (
+    pantry_address
+) WHERE
+    time_deleted IS NULL AND pantry_address IS NOT NULL;
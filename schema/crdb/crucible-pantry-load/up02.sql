@@ -0,0 +1,5 @@
+CREATE INDEX IF NOT EXISTS lookup_snapshot_export_by_pantry_address
+    ON omicron.public.snapshot_export (
+        pantry_address
+    ) WHERE
+        time_deleted IS NULL AND pantry_address IS NOT NULL;
@@ -180,6 +180,14 @@ CREATE TYPE IF NOT EXISTS omicron.public.sled_state AS ENUM (
     -- "expunged".
     'active',
 
+    -- The sled is active, but no new resources are placed on it while it's
+    -- prepared for a disruptive operation such as a host OS update.
+    -- Resources already on the sled are left alone.
+    --
+    -- A sled can only enter this state while its policy is 'in_service' or
+    -- 'no_provision'.
+    'quiesced',
+
     -- The sled no longer has resources allocated on it, now or in the future.
     --
     -- This is a terminal state. This state is only valid if the sled policy is
@@ -274,6 +282,101 @@ CREATE INDEX IF NOT EXISTS lookup_sled_by_policy_and_state ON omicron.public.sle
     sled_state
 );
 
+-- The overall state of a rolling reboot of sleds.
+CREATE TYPE IF NOT EXISTS omicron.public.rolling_sled_reboot_state AS ENUM (
+    -- Sleds are still being rebooted.
+    'running',
+    -- Every sled was rebooted and rejoined the control plane.
+    'completed',
+    -- A sled could not be rebooted, so no further sleds were.
+    'failed',
+    -- An operator cancelled the reboot before every sled was rebooted.
+    'cancelled'
+);
+
+-- An operator-requested reboot of a set of sleds, a batch at a time.
+CREATE TABLE IF NOT EXISTS omicron.public.rolling_sled_reboot (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_finished TIMESTAMPTZ,
+
+    -- The most sleds that may be out of service at once.
+    batch_size INT4 CHECK (batch_size > 0) NOT NULL,
+
+    state omicron.public.rolling_sled_reboot_state NOT NULL,
+
+    -- Set when an operator asks for the reboot to be cancelled. Sleds that
+    -- are already rebooting are seen through before it stops.
+    time_cancel_requested TIMESTAMPTZ,
+
+    CONSTRAINT finished_iff_not_running CHECK (
+        (state = 'running') = (time_finished IS NULL)
+    )
+);
+
+-- Only one rolling reboot may run at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS one_running_rolling_sled_reboot
+ON omicron.public.rolling_sled_reboot (
+    state
+) WHERE state = 'running';
+
+-- How far a rolling reboot has gotten with one of its sleds.
+CREATE TYPE IF NOT EXISTS omicron.public.rolling_sled_reboot_step AS ENUM (
+    -- The sled hasn't been started on.
+    'pending',
+    -- The sled is quiesced, and its instances are being migrated away or
+    -- stopped.
+    'draining',
+    -- The sled is about to be reset via its SP.
+    'rebooting',
+    -- The sled has been reset, and we're waiting for it to report its zones
+    -- running again.
+    'rejoining',
+    -- The sled was rebooted and is back in service.
+    'done',
+    -- The sled could not be rebooted.
+    'failed'
+);
+
+CREATE TABLE IF NOT EXISTS omicron.public.rolling_sled_reboot_sled (
+    reboot_id UUID NOT NULL,
+    sled_id UUID NOT NULL,
+
+    -- The order in which sleds are rebooted.
+    position INT4 NOT NULL,
+
+    step omicron.public.rolling_sled_reboot_step NOT NULL,
+    time_step_changed TIMESTAMPTZ NOT NULL,
+
+    -- When the sled's SP was asked to reset it.
+    time_reset TIMESTAMPTZ,
+
+    -- Why the sled could not be rebooted, if it couldn't.
+    error TEXT,
+
+    PRIMARY KEY (reboot_id, sled_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS lookup_rolling_sled_reboot_sled_by_position
+ON omicron.public.rolling_sled_reboot_sled (
+    reboot_id,
+    position
+);
+
+-- Instances stopped by a rolling reboot because they could not be migrated
+-- off a sled, so that they can be started again once the sled is back.
+CREATE TABLE IF NOT EXISTS omicron.public.rolling_sled_reboot_instance (
+    reboot_id UUID NOT NULL,
+    instance_id UUID NOT NULL,
+
+    -- The sled the instance was running on when it was stopped.
+    sled_id UUID NOT NULL,
+    time_stopped TIMESTAMPTZ NOT NULL,
+    time_restarted TIMESTAMPTZ,
+
+    PRIMARY KEY (reboot_id, instance_id)
+);
+
 -- Accounting for VMMs using resources on a sled
 CREATE TABLE IF NOT EXISTS omicron.public.sled_resource_vmm (
     -- Should match the UUID of the corresponding VMM
@@ -360,6 +463,27 @@ CREATE UNIQUE INDEX IF NOT EXISTS lookup_subnet_allocation_by_rack_and_sled ON o
     sled_id
 );
 
+-- Leases held by a Nexus while blueprint execution is sending mutating
+-- requests to a sled-agent, so that several Nexus instances executing the same
+-- blueprint don't interleave conflicting requests to one sled.
+--
+-- A lease is only meaningful until `time_expires`; after that, any Nexus may
+-- take it over. This keeps a Nexus that dies while holding a lease from
+-- blocking execution on that sled forever.
+CREATE TABLE IF NOT EXISTS omicron.public.sled_execution_lease (
+    -- The sled whose sled-agent is being configured
+    -- (foreign key into `sled` table)
+    sled_id UUID PRIMARY KEY,
+
+    -- The Nexus holding the lease
+    nexus_id UUID NOT NULL,
+
+    time_acquired TIMESTAMPTZ NOT NULL,
+    time_expires TIMESTAMPTZ NOT NULL,
+
+    CONSTRAINT expires_after_acquired CHECK (time_expires > time_acquired)
+);
+
 /*
  * Switches
  */
@@ -625,6 +749,29 @@ CREATE INDEX IF NOT EXISTS lookup_zpool_by_disk on omicron.public.zpool (
     id
 ) WHERE physical_disk_id IS NOT NULL AND time_deleted IS NULL;
 
+/*
+ * History of the space used within each zpool, as reported by inventory.
+ *
+ * Inventory collections are pruned aggressively, so this keeps a longer
+ * record from which capacity trends can be computed. Old samples are pruned
+ * by the `zpool_usage_trends` background task.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.zpool_usage_sample (
+    zpool_id UUID NOT NULL,
+    /* When the inventory collection observed this usage */
+    time_sampled TIMESTAMPTZ NOT NULL,
+    sled_id UUID NOT NULL,
+    total_size INT8 NOT NULL,
+    allocated INT8 NOT NULL,
+    /* Fragmentation of the zpool's free space in whole percent, if known */
+    fragmentation_percent INT2,
+
+    PRIMARY KEY (zpool_id, time_sampled)
+);
+
+CREATE INDEX IF NOT EXISTS lookup_zpool_usage_sample_by_time
+    ON omicron.public.zpool_usage_sample (time_sampled);
+
 -- TODO-cleanup If modifying this enum, please remove 'update'; see
 -- https://github.com/oxidecomputer/omicron/issues/8268.
 CREATE TYPE IF NOT EXISTS omicron.public.dataset_kind AS ENUM (
@@ -1067,6 +1214,23 @@ CREATE TABLE IF NOT EXISTS omicron.public.instance_ssh_key (
     PRIMARY KEY (instance_id, ssh_key_id)
 );
 
+/*
+ * User-defined key/value tags attached to an instance. Entries are removed
+ * when the instance is destroyed.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.instance_tag (
+    instance_id UUID NOT NULL,
+    key STRING(63) NOT NULL,
+    value STRING(255) NOT NULL,
+    PRIMARY KEY (instance_id, key)
+);
+
+-- Used to filter instance lists by tag
+CREATE INDEX IF NOT EXISTS lookup_instance_tag_by_key_value ON omicron.public.instance_tag (
+    key,
+    value
+);
+
 CREATE TABLE IF NOT EXISTS omicron.public.silo_quotas (
     silo_id UUID PRIMARY KEY,
     time_created TIMESTAMPTZ NOT NULL,
@@ -1102,6 +1266,38 @@ WHERE
 AND
     s.time_deleted IS NULL;
 
+/*
+ * Optional per-project limits on provisioned resources
+ *
+ * Projects without a row here are limited only by their silo's quotas.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.project_quotas (
+    project_id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    cpus INT8 NOT NULL,
+    memory_bytes INT8 NOT NULL,
+    storage_bytes INT8 NOT NULL
+);
+
+/*
+ * Per-project policy for assigning ephemeral IPs to new instances
+ *
+ * Projects without a row here never assign ephemeral IPs automatically and
+ * let instance creators request them freely.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.project_ephemeral_ip_policy (
+    project_id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    /* Whether instances get an ephemeral IP if they don't ask for one */
+    auto_assign BOOL NOT NULL,
+    /* Pool for automatically-assigned IPs; NULL means the silo default */
+    ip_pool_id UUID,
+    /* Whether instance creators may request their own ephemeral IP */
+    allow_override BOOL NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS omicron.public.silo_auth_settings (
     silo_id UUID PRIMARY KEY,
     time_created TIMESTAMPTZ NOT NULL,
@@ -1110,6 +1306,26 @@ CREATE TABLE IF NOT EXISTS omicron.public.silo_auth_settings (
     -- null means no max: users can tokens that never expire
     device_token_max_ttl_seconds INT8 CHECK (device_token_max_ttl_seconds > 0)
 );
+
+/*
+ * Instance auto-restart policies, which may be set on both projects and
+ * their instances.
+ */
+CREATE TYPE IF NOT EXISTS omicron.public.instance_auto_restart AS ENUM (
+    /*
+     * The instance should not, under any circumstances, be automatically
+     * rebooted by the control plane.
+     */
+    'never',
+    /*
+     * If this instance is running and unexpectedly fails (e.g. due to a host
+     * software crash or unexpected host reboot), the control plane will make a
+     * best-effort attempt to restart it. The control plane may choose not to
+     * restart the instance to preserve the overall availability of the system.
+     */
+     'best_effort'
+);
+
 /*
  * Projects
  */
@@ -1128,7 +1344,14 @@ CREATE TABLE IF NOT EXISTS omicron.public.project (
     rcgen INT NOT NULL,
 
     /* Which silo this project belongs to */
-    silo_id UUID NOT NULL /* foreign key into "silo" table */
+    silo_id UUID NOT NULL, /* foreign key into "silo" table */
+
+    /*
+     * The auto-restart policy and cooldown used by instances in this project
+     * which don't set their own. If NULL, the fleet-wide default is used.
+     */
+    default_auto_restart_policy omicron.public.instance_auto_restart,
+    default_auto_restart_cooldown INTERVAL
 );
 
 CREATE UNIQUE INDEX IF NOT EXISTS lookup_project_by_silo ON omicron.public.project (
@@ -1181,21 +1404,6 @@ CREATE TYPE IF NOT EXISTS omicron.public.vmm_state AS ENUM (
     'saga_unwound'
 );
 
-CREATE TYPE IF NOT EXISTS omicron.public.instance_auto_restart AS ENUM (
-    /*
-     * The instance should not, under any circumstances, be automatically
-     * rebooted by the control plane.
-     */
-    'never',
-    /*
-     * If this instance is running and unexpectedly fails (e.g. due to a host
-     * software crash or unexpected host reboot), the control plane will make a
-     * best-effort attempt to restart it. The control plane may choose not to
-     * restart the instance to preserve the overall availability of the system.
-     */
-     'best_effort'
-);
-
 /*
  * Represents the *desired* state of an instance, as requested by the user.
 */
@@ -1217,6 +1425,20 @@ CREATE TYPE IF NOT EXISTS omicron.public.instance_intended_state AS ENUM (
     'destroyed'
 );
 
+/*
+ * What happens to an instance's sled resource reservation while it is stopped.
+ */
+CREATE TYPE IF NOT EXISTS omicron.public.instance_stopped_reservation_policy AS ENUM (
+    /* The reservation is released when the instance stops. */
+    'release',
+
+    /*
+     * The reservation is kept while the instance is stopped, so it can be
+     * started again on the same sled.
+     */
+    'retain'
+);
+
 /*
  * TODO consider how we want to manage multiple sagas operating on the same
  * Instance -- e.g., reboot concurrent with destroy or concurrent reboots or the
@@ -1291,15 +1513,6 @@ CREATE TABLE IF NOT EXISTS omicron.public.instance (
      */
      auto_restart_cooldown INTERVAL,
 
-    /*
-     * Which disk, if any, is the one this instance should be directed to boot
-     * from. With a boot device selected, guest OSes cannot configure their
-     * boot policy for future boots, so also permit NULL to indicate a guest
-     * does not want our policy, and instead should be permitted control over
-     * its boot-time fates.
-     */
-    boot_disk_id UUID,
-
     /*
      * The intended state of the instance, as requested by the user.
      *
@@ -1308,6 +1521,25 @@ CREATE TABLE IF NOT EXISTS omicron.public.instance (
      */
     intended_state omicron.public.instance_intended_state NOT NULL,
 
+    /*
+     * The disks this instance should be directed to boot from, in order of
+     * preference: if the guest fails to boot from the first disk, it falls
+     * back to the second, and so on. With boot devices selected, guest OSes
+     * cannot configure their boot policy for future boots, so also permit an
+     * empty list to indicate a guest does not want our policy, and instead
+     * should be permitted control over its boot-time fates.
+     */
+    boot_order UUID[] NOT NULL DEFAULT ARRAY[],
+
+    /*
+     * Whether the instance's sled resource reservation is kept while it is
+     * stopped. Retained reservations remain in `sled_resource_vmm`, keyed by
+     * the ID of the (deleted) VMM that last ran the instance.
+     */
+    stopped_reservation_policy
+        omicron.public.instance_stopped_reservation_policy
+        NOT NULL DEFAULT 'release',
+
     CONSTRAINT vmm_iff_active_propolis CHECK (
         ((state = 'vmm') AND (active_propolis_id IS NOT NULL)) OR
         ((state != 'vmm') AND (active_propolis_id IS NULL))
@@ -1329,6 +1561,43 @@ ON
 WHERE
     time_deleted IS NULL;
 
+/*
+ * Why the control plane automatically restarted an instance.
+ */
+CREATE TYPE IF NOT EXISTS omicron.public.instance_auto_restart_reason AS ENUM (
+    /* The instance had failed. */
+    'failed',
+    /* A previous attempt to start the instance had failed. */
+    'start_failed'
+);
+
+/*
+ * A record of each time the control plane automatically restarted an
+ * instance. Entries are removed when the instance is destroyed.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.instance_auto_restart_event (
+    id UUID PRIMARY KEY,
+    instance_id UUID NOT NULL,
+    time_restarted TIMESTAMPTZ NOT NULL,
+    reason omicron.public.instance_auto_restart_reason NOT NULL,
+    /*
+     * The VMM created to run the restarted instance. Each instance-start saga
+     * creates exactly one VMM, so this makes recording an event idempotent.
+     */
+    propolis_id UUID NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS lookup_instance_auto_restart_event_by_propolis
+ON omicron.public.instance_auto_restart_event (
+    propolis_id
+);
+
+CREATE INDEX IF NOT EXISTS lookup_instance_auto_restart_event_by_instance
+ON omicron.public.instance_auto_restart_event (
+    instance_id,
+    id
+);
+
 /*
  * A special view of an instance provided to operators for insights into what's running
  * on a sled.
@@ -1419,7 +1688,10 @@ CREATE TABLE IF NOT EXISTS omicron.public.disk (
     origin_snapshot UUID,
     origin_image UUID,
 
-    pantry_address TEXT
+    pantry_address TEXT,
+
+    /* If set, the disk cannot be deleted until this is cleared */
+    delete_protected BOOL NOT NULL DEFAULT FALSE
 );
 
 CREATE UNIQUE INDEX IF NOT EXISTS lookup_disk_by_project ON omicron.public.disk (
@@ -1444,6 +1716,21 @@ CREATE UNIQUE INDEX IF NOT EXISTS lookup_disk_by_volume_id ON omicron.public.dis
 ) WHERE
     time_deleted IS NULL;
 
+CREATE INDEX IF NOT EXISTS lookup_disk_by_pantry_address ON omicron.public.disk (
+    pantry_address
+) WHERE
+    time_deleted IS NULL AND pantry_address IS NOT NULL;
+
+/*
+ * Lifecycle state of an image, which controls whether new disks can be created
+ * from it
+ */
+CREATE TYPE IF NOT EXISTS omicron.public.image_state AS ENUM (
+    'active',
+    'deprecated',
+    'blocked'
+);
+
 CREATE TABLE IF NOT EXISTS omicron.public.image (
     /* Identity metadata (resource) */
     id UUID PRIMARY KEY,
@@ -1464,7 +1751,9 @@ CREATE TABLE IF NOT EXISTS omicron.public.image (
     version STRING(64) NOT NULL,
     digest TEXT,
     block_size omicron.public.block_size NOT NULL,
-    size_bytes INT NOT NULL
+    size_bytes INT NOT NULL,
+
+    state omicron.public.image_state NOT NULL DEFAULT 'active'
 );
 
 CREATE VIEW IF NOT EXISTS omicron.public.project_image AS
@@ -1483,7 +1772,8 @@ SELECT
     version,
     digest,
     block_size,
-    size_bytes
+    size_bytes,
+    state
 FROM
     omicron.public.image
 WHERE
@@ -1504,7 +1794,8 @@ SELECT
     version,
     digest,
     block_size,
-    size_bytes
+    size_bytes,
+    state
 FROM
     omicron.public.image
 WHERE
@@ -1527,6 +1818,44 @@ CREATE UNIQUE INDEX IF NOT EXISTS lookup_image_by_silo_and_project on omicron.pu
     time_deleted is NULL AND
     project_id is NOT NULL;
 
+/* Indexes for listing silo images by creation time or size */
+CREATE INDEX IF NOT EXISTS lookup_silo_image_by_time_created
+    ON omicron.public.image (
+        silo_id,
+        time_created,
+        id
+    ) WHERE
+        time_deleted IS NULL AND
+        project_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS lookup_silo_image_by_size
+    ON omicron.public.image (
+        silo_id,
+        size_bytes,
+        id
+    ) WHERE
+        time_deleted IS NULL AND
+        project_id IS NULL;
+
+/* Indexes for listing project images by creation time or size */
+CREATE INDEX IF NOT EXISTS lookup_project_image_by_time_created
+    ON omicron.public.image (
+        project_id,
+        time_created,
+        id
+    ) WHERE
+        time_deleted IS NULL AND
+        project_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS lookup_project_image_by_size
+    ON omicron.public.image (
+        project_id,
+        size_bytes,
+        id
+    ) WHERE
+        time_deleted IS NULL AND
+        project_id IS NOT NULL;
+
 CREATE TYPE IF NOT EXISTS omicron.public.snapshot_state AS ENUM (
   'creating',
   'ready',
@@ -1561,7 +1890,13 @@ CREATE TABLE IF NOT EXISTS omicron.public.snapshot (
     block_size omicron.public.block_size NOT NULL,
 
     /* Disk configuration (from the time the snapshot was taken) */
-    size_bytes INT NOT NULL
+    size_bytes INT NOT NULL,
+
+    /* If set, the snapshot cannot be deleted until this is cleared */
+    delete_protected BOOL NOT NULL DEFAULT FALSE,
+
+    /* The snapshot schedule that created this snapshot, if any */
+    snapshot_schedule_id UUID
 );
 
 CREATE UNIQUE INDEX IF NOT EXISTS lookup_snapshot_by_project
@@ -1577,6 +1912,164 @@ CREATE INDEX IF NOT EXISTS lookup_snapshot_by_destination_volume_id
 CREATE INDEX IF NOT EXISTS lookup_snapshot_by_volume_id
     ON omicron.public.snapshot ( volume_id );
 
+/*
+ * Used to find the snapshots created by a schedule when pruning them down to
+ * the schedule's retention count.
+ */
+CREATE INDEX IF NOT EXISTS lookup_snapshot_by_snapshot_schedule
+    ON omicron.public.snapshot (
+        snapshot_schedule_id,
+        disk_id,
+        time_created
+    ) WHERE
+        snapshot_schedule_id IS NOT NULL AND time_deleted IS NULL;
+
+/*
+ * A policy for periodically taking snapshots of a disk, or of every disk in a
+ * project, and for pruning the oldest of those snapshots.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.snapshot_schedule (
+    /* Identity metadata (resource) */
+    id UUID PRIMARY KEY,
+    name STRING(63) NOT NULL,
+    description STRING(512) NOT NULL,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    /* Indicates that the object has been deleted */
+    time_deleted TIMESTAMPTZ,
+
+    /* Every snapshot schedule is in exactly one Project at a time. */
+    project_id UUID NOT NULL,
+
+    /*
+     * The disk to snapshot. If NULL, every disk in the project is snapshotted.
+     */
+    disk_id UUID,
+
+    /* How often to take snapshots */
+    interval_secs INT8 NOT NULL CHECK (interval_secs > 0),
+
+    /* How many snapshots to keep for each disk */
+    retain_count INT8 NOT NULL CHECK (retain_count > 0),
+
+    /* When the schedule last took snapshots, if it ever has */
+    time_last_run TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS lookup_snapshot_schedule_by_project
+    ON omicron.public.snapshot_schedule (
+        project_id,
+        name
+    ) WHERE
+        time_deleted IS NULL;
+
+/*
+ * A read-only copy of a snapshot's volume, attached to a Pantry so that its
+ * contents can be downloaded.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.snapshot_export (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    /* Indicates that the object has been deleted */
+    time_deleted TIMESTAMPTZ,
+
+    /* Every snapshot export is in exactly one Project at a time. */
+    project_id UUID NOT NULL,
+
+    /* The snapshot being exported */
+    snapshot_id UUID NOT NULL,
+
+    /* The instance whose boot disk was exported, if any */
+    instance_id UUID,
+
+    /* The copy of the snapshot's volume that the Pantry reads from */
+    volume_id UUID NOT NULL,
+
+    /* The Pantry the volume is attached to, once it has been attached */
+    pantry_address TEXT,
+
+    block_size omicron.public.block_size NOT NULL,
+    size_bytes INT NOT NULL,
+
+    /* Metadata describing the exported image */
+    manifest JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS lookup_snapshot_export_by_project
+    ON omicron.public.snapshot_export (
+        project_id,
+        id
+    ) WHERE
+        time_deleted IS NULL;
+
+CREATE INDEX IF NOT EXISTS lookup_snapshot_export_by_pantry_address
+    ON omicron.public.snapshot_export (
+        pantry_address
+    ) WHERE
+        time_deleted IS NULL AND pantry_address IS NOT NULL;
+
+CREATE TYPE IF NOT EXISTS omicron.public.operation_kind AS ENUM (
+  'disk_clone',
+  'snapshot_export',
+  'instance_export'
+);
+
+CREATE TYPE IF NOT EXISTS omicron.public.operation_state AS ENUM (
+  'running',
+  'succeeded',
+  'failed'
+);
+
+/*
+ * A slow request made through the external API, which clients can poll for
+ * completion rather than waiting on the request itself.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.operation (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    /* When the operation succeeded or failed */
+    time_completed TIMESTAMPTZ,
+
+    /* Every operation is in exactly one Project. */
+    project_id UUID NOT NULL,
+
+    /* The Nexus carrying out the operation */
+    nexus_id UUID NOT NULL,
+
+    kind omicron.public.operation_kind NOT NULL,
+    state omicron.public.operation_state NOT NULL,
+
+    steps_completed INT8 NOT NULL,
+    steps_total INT8 NOT NULL,
+
+    /* The resource produced by the operation, once it has succeeded */
+    result_id UUID,
+
+    /* Why the operation failed, if it did */
+    error_message TEXT,
+
+    CONSTRAINT time_completed_iff_done CHECK (
+        (state = 'running') = (time_completed IS NULL)
+    ),
+    CONSTRAINT steps_completed_in_range CHECK (
+        steps_completed >= 0 AND steps_completed <= steps_total
+    )
+);
+
+CREATE INDEX IF NOT EXISTS lookup_operation_by_project
+    ON omicron.public.operation (
+        project_id,
+        id
+    );
+
+CREATE INDEX IF NOT EXISTS lookup_running_operation_by_nexus
+    ON omicron.public.operation (
+        nexus_id
+    ) WHERE
+        state = 'running';
+
 /*
  * Oximeter collector servers.
  */
@@ -1701,7 +2194,12 @@ CREATE TABLE IF NOT EXISTS omicron.public.vpc_subnet (
     ipv4_block INET NOT NULL,
     ipv6_block INET NOT NULL,
     /* nullable FK to the `vpc_router` table. */
-    custom_router_id UUID
+    custom_router_id UUID,
+    /*
+     * Additional IPv4 blocks from which addresses in this subnet may be
+     * allocated, added after creation when `ipv4_block` is exhausted.
+     */
+    ipv4_secondary_blocks INET[] NOT NULL DEFAULT ARRAY[]
 );
 
 /* Subnet and network interface names are unique per VPC, not project */
@@ -1876,6 +2374,32 @@ CREATE UNIQUE INDEX IF NOT EXISTS network_interface_parent_id_slot_key ON omicro
 WHERE
     time_deleted IS NULL;
 
+/*
+ * Private IP addresses held in a VPC Subnet independently of any network
+ * interface.
+ *
+ * Reserved addresses are skipped when automatically allocating addresses for
+ * new interfaces. An interface that explicitly requests a reserved address is
+ * still given it; that's how a reservation is bound to an instance.
+ */
+CREATE TABLE IF NOT EXISTS omicron.public.vpc_subnet_ip_reservation (
+    id UUID PRIMARY KEY,
+    description STRING(512) NOT NULL,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_deleted TIMESTAMPTZ,
+    vpc_id UUID NOT NULL,
+    subnet_id UUID NOT NULL,
+    ip INET NOT NULL
+);
+
+/* Ensure we do not reserve the same address twice within a subnet */
+CREATE UNIQUE INDEX IF NOT EXISTS vpc_subnet_ip_reservation_subnet_id_ip_key
+ON omicron.public.vpc_subnet_ip_reservation (
+    subnet_id,
+    ip
+) WHERE
+    time_deleted IS NULL;
+
 CREATE TYPE IF NOT EXISTS omicron.public.vpc_firewall_rule_status AS ENUM (
     'disabled',
     'enabled'
@@ -2093,14 +2617,14 @@ CREATE UNIQUE INDEX IF NOT EXISTS lookup_pool_by_name ON omicron.public.ip_pool
     time_deleted IS NULL;
 
 -- The order here is most-specific first, and it matters because we use this
--- fact to select the most specific default in the case where there is both a
--- silo default and a fleet default. If we were to add a project type, it should
--- be added before silo.
+-- fact to select the most specific link in the case where a pool is linked to
+-- both a project and its silo.
 CREATE TYPE IF NOT EXISTS omicron.public.ip_pool_resource_type AS ENUM (
+    'project',
     'silo'
 );
 
--- join table associating IP pools with resources like fleet or silo
+-- join table associating IP pools with resources like projects or silos
 CREATE TABLE IF NOT EXISTS omicron.public.ip_pool_resource (
     ip_pool_id UUID NOT NULL,
     resource_type omicron.public.ip_pool_resource_type NOT NULL,
@@ -2245,6 +2769,12 @@ CREATE TABLE IF NOT EXISTS omicron.public.external_ip (
 
     is_probe BOOL NOT NULL DEFAULT false,
 
+    /*
+     * When this IP finished attaching to its parent.  This is NULL while the
+     * IP isn't attached to anything.
+     */
+    time_attached TIMESTAMPTZ,
+
     /* The name must be non-NULL iff this is a floating IP. */
     CONSTRAINT null_fip_name CHECK (
         (kind != 'floating' AND name IS NULL) OR
@@ -2479,6 +3009,37 @@ CREATE INDEX IF NOT EXISTS lookup_console_by_silo_user
 CREATE UNIQUE INDEX IF NOT EXISTS console_session_token_unique
 	ON omicron.public.console_session (token);
 
+-- A break-glass operator account, enabled from the technician port when the
+-- usual (e.g., external IdP) means of logging in are broken.  Each row records
+-- one period during which the account could be used.  Rows are never deleted so
+-- that they serve as an audit trail.
+CREATE TABLE IF NOT EXISTS omicron.public.break_glass_account (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    -- the local user (in the default silo) that logs in with this account
+    silo_user_id UUID NOT NULL,
+    -- operator-provided description of who enabled the account
+    enabled_by TEXT NOT NULL,
+    -- the account cannot be used after this time
+    time_expires TIMESTAMPTZ NOT NULL,
+    -- set when the account is disabled, explicitly or by enabling it again
+    time_disabled TIMESTAMPTZ,
+    disabled_by TEXT,
+
+    CONSTRAINT disabled_by_iff_disabled CHECK (
+        (time_disabled IS NULL) = (disabled_by IS NULL)
+    )
+);
+
+-- A user may only have one enabled account at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS lookup_enabled_break_glass_account
+    ON omicron.public.break_glass_account (silo_user_id)
+    WHERE time_disabled IS NULL;
+
+-- Used to check whether a user logging in is a break-glass user.
+CREATE INDEX IF NOT EXISTS lookup_break_glass_account_by_silo_user
+    ON omicron.public.break_glass_account (silo_user_id);
+
 /*******************************************************************/
 
 -- Describes a single uploaded TUF repo.
@@ -3962,6 +4523,27 @@ CREATE TABLE IF NOT EXISTS omicron.public.inv_nvme_disk_firmware (
     PRIMARY KEY (inv_collection_id, sled_id, slot)
 );
 
+CREATE TYPE IF NOT EXISTS omicron.public.inv_zpool_scrub_state AS ENUM (
+    'in_progress',
+    'paused',
+    'finished',
+    'canceled'
+);
+
+CREATE TYPE IF NOT EXISTS omicron.public.inv_zpool_health_state AS ENUM (
+    'online',
+    'degraded',
+    'faulted',
+    'offline',
+    'removed',
+    'unavailable'
+);
+
+CREATE TYPE IF NOT EXISTS omicron.public.inv_zpool_resilver_state AS ENUM (
+    'in_progress',
+    'finished'
+);
+
 CREATE TABLE IF NOT EXISTS omicron.public.inv_zpool (
     -- where this observation came from
     -- (foreign key into `inv_collection` table)
@@ -3974,6 +4556,37 @@ CREATE TABLE IF NOT EXISTS omicron.public.inv_zpool (
     sled_id UUID NOT NULL,
     total_size INT NOT NULL,
 
+    -- Progress of the most recent scrub of the zpool. `scrub_state` is NULL
+    -- if the sled-agent did not report a scrub (in which case the other
+    -- `scrub_*` columns are also NULL).
+    scrub_state omicron.public.inv_zpool_scrub_state,
+    scrub_start_time TIMESTAMPTZ,
+    scrub_end_time TIMESTAMPTZ,
+    scrub_percent_done INT2,
+    scrub_errors INT8,
+
+    -- Space allocated within the zpool, and the fragmentation of its free
+    -- space in whole percent. These are NULL if not reported by the
+    -- sled-agent.
+    allocated INT8,
+    fragmentation_percent INT2,
+
+    -- Health and error counters of the zpool. These are all NULL if the
+    -- sled-agent did not report the zpool's health.
+    health_state omicron.public.inv_zpool_health_state,
+    read_errors INT8,
+    write_errors INT8,
+    checksum_errors INT8,
+
+    -- Progress of the most recent resilver of the zpool. `resilver_state` is
+    -- NULL if the sled-agent did not report a resilver (in which case the
+    -- other `resilver_*` columns are also NULL).
+    resilver_state omicron.public.inv_zpool_resilver_state,
+    resilver_start_time TIMESTAMPTZ,
+    resilver_end_time TIMESTAMPTZ,
+    resilver_percent_done INT2,
+    resilver_errors INT8,
+
     -- PK consisting of:
     -- - Which collection this was
     -- - The sled reporting the disk
@@ -4397,6 +5010,9 @@ CREATE TABLE IF NOT EXISTS omicron.public.inv_ntp_timesync (
     inv_collection_id UUID NOT NULL,
     zone_id UUID NOT NULL,
     synced BOOL NOT NULL,
+    -- offset between the NTP clock and the sled's system clock, in
+    -- microseconds (NULL for collections made before this was recorded)
+    correction_usec INT8,
 
     PRIMARY KEY (inv_collection_id, zone_id)
 );
@@ -4428,7 +5044,23 @@ CREATE TABLE IF NOT EXISTS omicron.public.reconfigurator_chicken_switches (
     time_modified TIMESTAMPTZ NOT NULL,
 
     -- Whether to add zones while the system has detected a mupdate override.
-    add_zones_with_mupdate_override BOOL NOT NULL
+    add_zones_with_mupdate_override BOOL NOT NULL,
+
+    -- Whether to update Nexus even if that would exceed the supported version
+    -- skew between Nexus and sled host OS / SP software.
+    allow_version_skew BOOL NOT NULL,
+
+    -- The number of Nexus zones to run, if overriding the built-in policy.
+    target_nexus_zone_count INT2
+        CHECK (target_nexus_zone_count IS NULL OR target_nexus_zone_count > 0),
+
+    -- The number of internal DNS zones to run, if overriding the built-in
+    -- policy.
+    target_internal_dns_zone_count INT2
+        CHECK (
+            target_internal_dns_zone_count IS NULL
+            OR target_internal_dns_zone_count > 0
+        )
 );
 
 /*
@@ -4461,7 +5093,8 @@ CREATE TABLE IF NOT EXISTS omicron.public.reconfigurator_chicken_switches (
 
 CREATE TYPE IF NOT EXISTS omicron.public.bp_zone_disposition AS ENUM (
     'in_service',
-    'expunged'
+    'expunged',
+    'cordoned'
 );
 
 CREATE TYPE IF NOT EXISTS omicron.public.bp_dataset_disposition AS ENUM (
@@ -4527,7 +5160,12 @@ CREATE TABLE IF NOT EXISTS omicron.public.blueprint (
     target_release_minimum_generation INT8 NOT NULL,
 
     -- The generation of the active group of Nexus instances
-    nexus_generation INT8 NOT NULL
+    nexus_generation INT8 NOT NULL,
+
+    -- The sleds this blueprint is scoped to, or NULL if it covers the whole
+    -- fleet. Sleds outside the scope must be carried over unchanged from the
+    -- parent blueprint.
+    scope_sled_ids UUID[]
 );
 
 -- table describing both the current and historical target blueprints of the
@@ -4551,7 +5189,19 @@ CREATE TABLE IF NOT EXISTS omicron.public.bp_target (
     enabled BOOL NOT NULL,
 
     -- Timestamp for when this blueprint was made the current target
-    time_made_target TIMESTAMPTZ NOT NULL
+    time_made_target TIMESTAMPTZ NOT NULL,
+
+    -- If non-NULL, an operator has disabled execution of this blueprint, for
+    -- the given reason, at the given time. Unlike `enabled`, this is carried
+    -- over each time a new blueprint is made the target, so that the planner
+    -- can't undo it.
+    execution_disabled_reason TEXT,
+    time_execution_disabled TIMESTAMPTZ,
+
+    CONSTRAINT execution_disabled_reason_and_time CHECK (
+        (execution_disabled_reason IS NULL)
+            = (time_execution_disabled IS NULL)
+    )
 );
 
 -- metadata associated with a single sled in a blueprint
@@ -4848,6 +5498,15 @@ CREATE TABLE IF NOT EXISTS omicron.public.bp_oximeter_read_policy (
     oximeter_read_mode omicron.public.oximeter_read_mode NOT NULL
 );
 
+-- The report from the planning run that produced a blueprint.
+CREATE TABLE IF NOT EXISTS omicron.public.bp_planning_report (
+    -- Foreign key into the `blueprint` table
+    blueprint_id UUID PRIMARY KEY,
+
+    -- The serialized `PlanningReport`.
+    report JSONB NOT NULL
+);
+
 -- Blueprint information related to pending RoT bootloader upgrades.
 CREATE TABLE IF NOT EXISTS omicron.public.bp_pending_mgs_update_rot_bootloader (
     -- Foreign key into the `blueprint` table
@@ -4942,6 +5601,53 @@ CREATE TABLE IF NOT EXISTS omicron.public.bp_pending_mgs_update_host_phase_1 (
     PRIMARY KEY(blueprint_id, hw_baseboard_id)
 );
 
+/*
+ * Progress of updates driven from the technician port (wicket), as reported
+ * to Nexus by wicketd. These updates happen outside of Reconfigurator, so this
+ * is the only way for remote operators to see them.
+ */
+CREATE TYPE IF NOT EXISTS omicron.public.update_component_kind AS ENUM (
+    'rot_bootloader',
+    'rot',
+    'sp',
+    'host'
+);
+
+CREATE TYPE IF NOT EXISTS omicron.public.update_component_state AS ENUM (
+    'waiting',
+    'updating',
+    'updated',
+    'skipped',
+    'failed',
+    'aborted'
+);
+
+-- One row per update of a single service processor's components. The ID is
+-- the ID of the wicketd update execution.
+CREATE TABLE IF NOT EXISTS omicron.public.technician_port_update (
+    id UUID PRIMARY KEY,
+    sp_type omicron.public.sp_type NOT NULL,
+    sp_slot INT4 NOT NULL,
+    -- when wicketd first reported progress for this update
+    time_started TIMESTAMPTZ NOT NULL,
+    -- when wicketd most recently reported progress for this update
+    time_reported TIMESTAMPTZ NOT NULL
+);
+
+-- Most recently reported progress of each component within an update
+CREATE TABLE IF NOT EXISTS omicron.public.technician_port_update_component (
+    -- foreign key into `technician_port_update`
+    update_id UUID NOT NULL,
+    component omicron.public.update_component_kind NOT NULL,
+    state omicron.public.update_component_state NOT NULL,
+    steps_completed INT8 NOT NULL,
+    total_steps INT8 NOT NULL,
+    current_step TEXT,
+    message TEXT,
+
+    PRIMARY KEY (update_id, component)
+);
+
 -- Mapping of Omicron zone ID to CockroachDB node ID. This isn't directly used
 -- by the blueprint tables above, but is used by the more general Reconfigurator
 -- system along with them (e.g., to decommission expunged CRDB nodes).
@@ -6022,7 +6728,11 @@ CREATE TYPE IF NOT EXISTS omicron.public.alert_class AS ENUM (
     'test.foo.bar',
     'test.foo.baz',
     'test.quux.bar',
-    'test.quux.bar.baz'
+    'test.quux.bar.baz',
+    -- A zpool is full, or is projected to be full soon.
+    'storage.zpool.capacity',
+    -- A scrub of a zpool found errors.
+    'storage.zpool.scrub_errors'
     -- Add new alert classes here!
 );
 
@@ -6602,7 +7312,7 @@ INSERT INTO omicron.public.db_metadata (
     version,
     target_version
 ) VALUES
-    (TRUE, NOW(), NOW(), '186.0.0', NULL)
+    (TRUE, NOW(), NOW(), '221.0.0', NULL)
 ON CONFLICT DO NOTHING;
 
 COMMIT;
@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.disk
+ADD COLUMN IF NOT EXISTS delete_protected BOOL NOT NULL DEFAULT FALSE;
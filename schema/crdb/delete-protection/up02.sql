@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.snapshot
+ADD COLUMN IF NOT EXISTS delete_protected BOOL NOT NULL DEFAULT FALSE;
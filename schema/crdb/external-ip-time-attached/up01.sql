@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.external_ip
+    ADD COLUMN IF NOT EXISTS time_attached TIMESTAMPTZ;
@@ -0,0 +1,7 @@
+SET LOCAL disallow_full_table_scans = off;
+
+-- We don't know when existing addresses were attached.  The last time they
+-- were modified is the best approximation we have.
+UPDATE omicron.public.external_ip
+    SET time_attached = time_modified
+    WHERE state = 'attached' AND time_attached IS NULL;
@@ -0,0 +1,8 @@
+CREATE INDEX IF NOT EXISTS lookup_silo_image_by_time_created
+    ON omicron.public.image (
+        silo_id,
+        time_created,
+        id
+    ) WHERE
+        time_deleted IS NULL AND
+        project_id IS NULL;
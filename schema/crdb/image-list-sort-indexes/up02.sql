@@ -0,0 +1,8 @@
+CREATE INDEX IF NOT EXISTS lookup_silo_image_by_size
+    ON omicron.public.image (
+        silo_id,
+        size_bytes,
+        id
+    ) WHERE
+        time_deleted IS NULL AND
+        project_id IS NULL;
@@ -0,0 +1,8 @@
+CREATE INDEX IF NOT EXISTS lookup_project_image_by_size
+    ON omicron.public.image (
+        project_id,
+        size_bytes,
+        id
+    ) WHERE
+        time_deleted IS NULL AND
+        project_id IS NOT NULL;
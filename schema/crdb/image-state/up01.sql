@@ -0,0 +1,5 @@
+CREATE TYPE IF NOT EXISTS omicron.public.image_state AS ENUM (
+    'active',
+    'deprecated',
+    'blocked'
+);
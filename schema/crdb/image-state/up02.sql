@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.image
+ADD COLUMN IF NOT EXISTS state omicron.public.image_state NOT NULL DEFAULT 'active';
@@ -0,0 +1 @@
+DROP VIEW IF EXISTS omicron.public.project_image;
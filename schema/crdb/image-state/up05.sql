@@ -0,0 +1 @@
+DROP VIEW IF EXISTS omicron.public.silo_image;
@@ -0,0 +1,21 @@
+CREATE VIEW IF NOT EXISTS omicron.public.silo_image AS
+SELECT
+    id,
+    name,
+    description,
+    time_created,
+    time_modified,
+    time_deleted,
+    silo_id,
+    volume_id,
+    url,
+    os,
+    version,
+    digest,
+    block_size,
+    size_bytes,
+    state
+FROM
+    omicron.public.image
+WHERE
+    project_id IS NULL;
@@ -0,0 +1,4 @@
+CREATE TYPE IF NOT EXISTS omicron.public.instance_auto_restart_reason AS ENUM (
+    'failed',
+    'start_failed'
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS omicron.public.instance_auto_restart_event (
+    id UUID PRIMARY KEY,
+    instance_id UUID NOT NULL,
+    time_restarted TIMESTAMPTZ NOT NULL,
+    reason omicron.public.instance_auto_restart_reason NOT NULL,
+    propolis_id UUID NOT NULL
+);
@@ -0,0 +1,4 @@
+CREATE UNIQUE INDEX IF NOT EXISTS lookup_instance_auto_restart_event_by_propolis
+ON omicron.public.instance_auto_restart_event (
+    propolis_id
+);
@@ -0,0 +1,5 @@
+CREATE INDEX IF NOT EXISTS lookup_instance_auto_restart_event_by_instance
+ON omicron.public.instance_auto_restart_event (
+    instance_id,
+    id
+);
@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.instance
+ADD COLUMN IF NOT EXISTS boot_order UUID[] NOT NULL DEFAULT ARRAY[];
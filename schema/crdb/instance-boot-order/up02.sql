@@ -0,0 +1,7 @@
+SET LOCAL disallow_full_table_scans = off;
+
+-- Carry each instance's existing boot disk over as the only entry in its boot
+-- order.
+UPDATE omicron.public.instance
+SET boot_order = ARRAY[boot_disk_id]
+WHERE boot_disk_id IS NOT NULL AND boot_order = ARRAY[];
@@ -0,0 +1 @@
+ALTER TABLE omicron.public.instance DROP COLUMN IF EXISTS boot_disk_id;
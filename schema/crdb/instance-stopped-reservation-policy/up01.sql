@@ -0,0 +1,4 @@
+CREATE TYPE IF NOT EXISTS omicron.public.instance_stopped_reservation_policy AS ENUM (
+    'release',
+    'retain'
+);
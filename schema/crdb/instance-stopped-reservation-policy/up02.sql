@@ -0,0 +1,3 @@
+ALTER TABLE omicron.public.instance
+ADD COLUMN IF NOT EXISTS stopped_reservation_policy
+    omicron.public.instance_stopped_reservation_policy NOT NULL DEFAULT 'release';
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS omicron.public.instance_tag (
+    instance_id UUID NOT NULL,
+    key STRING(63) NOT NULL,
+    value STRING(255) NOT NULL,
+    PRIMARY KEY (instance_id, key)
+);
@@ -0,0 +1,4 @@
+CREATE INDEX IF NOT EXISTS lookup_instance_tag_by_key_value ON omicron.public.instance_tag (
+    key,
+    value
+);
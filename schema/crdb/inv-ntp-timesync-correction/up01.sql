@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.inv_ntp_timesync
+    ADD COLUMN IF NOT EXISTS correction_usec INT8;
@@ -0,0 +1,8 @@
+CREATE TYPE IF NOT EXISTS omicron.public.inv_zpool_health_state AS ENUM (
+    'online',
+    'degraded',
+    'faulted',
+    'offline',
+    'removed',
+    'unavailable'
+);
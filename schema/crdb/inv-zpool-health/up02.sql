@@ -0,0 +1,4 @@
+CREATE TYPE IF NOT EXISTS omicron.public.inv_zpool_resilver_state AS ENUM (
+    'in_progress',
+    'finished'
+);
@@ -0,0 +1,10 @@
+ALTER TABLE omicron.public.inv_zpool
+    ADD COLUMN IF NOT EXISTS health_state omicron.public.inv_zpool_health_state,
+    ADD COLUMN IF NOT EXISTS read_errors INT8,
+    ADD COLUMN IF NOT EXISTS write_errors INT8,
+    ADD COLUMN IF NOT EXISTS checksum_errors INT8,
+    ADD COLUMN IF NOT EXISTS resilver_state omicron.public.inv_zpool_resilver_state,
+    ADD COLUMN IF NOT EXISTS resilver_start_time TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS resilver_end_time TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS resilver_percent_done INT2,
+    ADD COLUMN IF NOT EXISTS resilver_errors INT8;
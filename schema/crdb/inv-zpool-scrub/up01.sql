@@ -0,0 +1,6 @@
+CREATE TYPE IF NOT EXISTS omicron.public.inv_zpool_scrub_state AS ENUM (
+    'in_progress',
+    'paused',
+    'finished',
+    'canceled'
+);
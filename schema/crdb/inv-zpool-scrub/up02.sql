@@ -0,0 +1,6 @@
+ALTER TABLE omicron.public.inv_zpool
+    ADD COLUMN IF NOT EXISTS scrub_state omicron.public.inv_zpool_scrub_state,
+    ADD COLUMN IF NOT EXISTS scrub_start_time TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS scrub_end_time TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS scrub_percent_done INT2,
+    ADD COLUMN IF NOT EXISTS scrub_errors INT8;
@@ -0,0 +1 @@
+ALTER TYPE omicron.public.ip_pool_resource_type ADD VALUE IF NOT EXISTS 'project' BEFORE 'silo';
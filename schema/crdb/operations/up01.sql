@@ -0,0 +1,5 @@
+CREATE TYPE IF NOT EXISTS omicron.public.operation_kind AS ENUM (
+  'disk_clone',
+  'snapshot_export',
+  'instance_export'
+);
@@ -0,0 +1,5 @@
+CREATE TYPE IF NOT EXISTS omicron.public.operation_state AS ENUM (
+  'running',
+  'succeeded',
+  'failed'
+);
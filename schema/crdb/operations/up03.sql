@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS omicron.public.operation (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    time_completed TIMESTAMPTZ,
+    project_id UUID NOT NULL,
+    nexus_id UUID NOT NULL,
+    kind omicron.public.operation_kind NOT NULL,
+    state omicron.public.operation_state NOT NULL,
+    steps_completed INT8 NOT NULL,
+    steps_total INT8 NOT NULL,
+    result_id UUID,
+    error_message TEXT,
+
+    CONSTRAINT time_completed_iff_done CHECK (
+        (state = 'running') = (time_completed IS NULL)
+    ),
+    CONSTRAINT steps_completed_in_range CHECK (
+        steps_completed >= 0 AND steps_completed <= steps_total
+    )
+);
@@ -0,0 +1,5 @@
+CREATE INDEX IF NOT EXISTS lookup_operation_by_project
+    ON omicron.public.operation (
+        project_id,
+        id
+    );
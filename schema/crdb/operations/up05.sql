@@ -0,0 +1,5 @@
+CREATE INDEX IF NOT EXISTS lookup_running_operation_by_nexus
+    ON omicron.public.operation (
+        nexus_id
+    ) WHERE
+        state = 'running';
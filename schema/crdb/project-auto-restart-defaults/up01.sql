@@ -0,0 +1,3 @@
+ALTER TABLE omicron.public.project
+    ADD COLUMN IF NOT EXISTS default_auto_restart_policy omicron.public.instance_auto_restart,
+    ADD COLUMN IF NOT EXISTS default_auto_restart_cooldown INTERVAL;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS omicron.public.project_ephemeral_ip_policy (
+    project_id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    auto_assign BOOL NOT NULL,
+    ip_pool_id UUID,
+    allow_override BOOL NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TYPE IF NOT EXISTS omicron.public.rolling_sled_reboot_state AS ENUM (
+    'running',
+    'completed',
+    'failed',
+    'cancelled'
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS omicron.public.rolling_sled_reboot (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_finished TIMESTAMPTZ,
+    batch_size INT4 CHECK (batch_size > 0) NOT NULL,
+    state omicron.public.rolling_sled_reboot_state NOT NULL,
+    time_cancel_requested TIMESTAMPTZ,
+    CONSTRAINT finished_iff_not_running CHECK (
+        (state = 'running') = (time_finished IS NULL)
+    )
+);
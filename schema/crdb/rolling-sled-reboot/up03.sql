@@ -0,0 +1,4 @@
+CREATE UNIQUE INDEX IF NOT EXISTS one_running_rolling_sled_reboot
+ON omicron.public.rolling_sled_reboot (
+    state
+) WHERE state = 'running';
@@ -0,0 +1,8 @@
+CREATE TYPE IF NOT EXISTS omicron.public.rolling_sled_reboot_step AS ENUM (
+    'pending',
+    'draining',
+    'rebooting',
+    'rejoining',
+    'done',
+    'failed'
+);
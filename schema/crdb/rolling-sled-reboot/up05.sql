@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS omicron.public.rolling_sled_reboot_sled (
+    reboot_id UUID NOT NULL,
+    sled_id UUID NOT NULL,
+    position INT4 NOT NULL,
+    step omicron.public.rolling_sled_reboot_step NOT NULL,
+    time_step_changed TIMESTAMPTZ NOT NULL,
+    time_reset TIMESTAMPTZ,
+    error TEXT,
+    PRIMARY KEY (reboot_id, sled_id)
+);
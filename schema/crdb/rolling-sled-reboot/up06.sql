@@ -0,0 +1,5 @@
+CREATE UNIQUE INDEX IF NOT EXISTS lookup_rolling_sled_reboot_sled_by_position
+ON omicron.public.rolling_sled_reboot_sled (
+    reboot_id,
+    position
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS omicron.public.rolling_sled_reboot_instance (
+    reboot_id UUID NOT NULL,
+    instance_id UUID NOT NULL,
+    sled_id UUID NOT NULL,
+    time_stopped TIMESTAMPTZ NOT NULL,
+    time_restarted TIMESTAMPTZ,
+    PRIMARY KEY (reboot_id, instance_id)
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS omicron.public.sled_execution_lease (
+    sled_id UUID PRIMARY KEY,
+    nexus_id UUID NOT NULL,
+    time_acquired TIMESTAMPTZ NOT NULL,
+    time_expires TIMESTAMPTZ NOT NULL,
+
+    CONSTRAINT expires_after_acquired CHECK (time_expires > time_acquired)
+);
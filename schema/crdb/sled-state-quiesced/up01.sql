@@ -0,0 +1 @@
+ALTER TYPE omicron.public.sled_state ADD VALUE IF NOT EXISTS 'quiesced' AFTER 'active';
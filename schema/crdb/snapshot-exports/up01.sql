@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS omicron.public.snapshot_export (
+    id UUID PRIMARY KEY,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_modified TIMESTAMPTZ NOT NULL,
+    time_deleted TIMESTAMPTZ,
+    project_id UUID NOT NULL,
+    snapshot_id UUID NOT NULL,
+    instance_id UUID,
+    volume_id UUID NOT NULL,
+    pantry_address TEXT,
+    block_size omicron.public.block_size NOT NULL,
+    size_bytes INT NOT NULL,
+    manifest JSONB NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE INDEX IF NOT EXISTS lookup_snapshot_export_by_project
+    ON omicron.public.snapshot_export (
+        project_id,
+        id
+    ) WHERE
+        time_deleted IS NULL;
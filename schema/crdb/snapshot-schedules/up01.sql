@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.snapshot
+ADD COLUMN IF NOT EXISTS snapshot_schedule_id UUID;
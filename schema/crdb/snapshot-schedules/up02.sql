@@ -0,0 +1,7 @@
+CREATE INDEX IF NOT EXISTS lookup_snapshot_by_snapshot_schedule
+    ON omicron.public.snapshot (
+        snapshot_schedule_id,
+        disk_id,
+        time_created
+    ) WHERE
+        snapshot_schedule_id IS NOT NULL AND time_deleted IS NULL;
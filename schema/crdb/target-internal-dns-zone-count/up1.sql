@@ -0,0 +1,6 @@
+ALTER TABLE omicron.public.reconfigurator_chicken_switches
+    ADD COLUMN IF NOT EXISTS target_internal_dns_zone_count INT2
+        CHECK (
+            target_internal_dns_zone_count IS NULL
+            OR target_internal_dns_zone_count > 0
+        );
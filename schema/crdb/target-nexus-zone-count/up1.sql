@@ -0,0 +1,3 @@
+ALTER TABLE omicron.public.reconfigurator_chicken_switches
+    ADD COLUMN IF NOT EXISTS target_nexus_zone_count INT2
+        CHECK (target_nexus_zone_count IS NULL OR target_nexus_zone_count > 0);
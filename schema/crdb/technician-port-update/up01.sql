@@ -0,0 +1,6 @@
+CREATE TYPE IF NOT EXISTS omicron.public.update_component_kind AS ENUM (
+    'rot_bootloader',
+    'rot',
+    'sp',
+    'host'
+);
@@ -0,0 +1,8 @@
+CREATE TYPE IF NOT EXISTS omicron.public.update_component_state AS ENUM (
+    'waiting',
+    'updating',
+    'updated',
+    'skipped',
+    'failed',
+    'aborted'
+);
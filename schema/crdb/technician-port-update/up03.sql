@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS omicron.public.technician_port_update (
+    id UUID PRIMARY KEY,
+    sp_type omicron.public.sp_type NOT NULL,
+    sp_slot INT4 NOT NULL,
+    time_started TIMESTAMPTZ NOT NULL,
+    time_reported TIMESTAMPTZ NOT NULL
+);
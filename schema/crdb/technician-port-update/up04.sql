@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS omicron.public.technician_port_update_component (
+    update_id UUID NOT NULL,
+    component omicron.public.update_component_kind NOT NULL,
+    state omicron.public.update_component_state NOT NULL,
+    steps_completed INT8 NOT NULL,
+    total_steps INT8 NOT NULL,
+    current_step TEXT,
+    message TEXT,
+
+    PRIMARY KEY (update_id, component)
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS omicron.public.vpc_subnet_ip_reservation (
+    id UUID PRIMARY KEY,
+    description STRING(512) NOT NULL,
+    time_created TIMESTAMPTZ NOT NULL,
+    time_deleted TIMESTAMPTZ,
+    vpc_id UUID NOT NULL,
+    subnet_id UUID NOT NULL,
+    ip INET NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE UNIQUE INDEX IF NOT EXISTS vpc_subnet_ip_reservation_subnet_id_ip_key
+ON omicron.public.vpc_subnet_ip_reservation (
+    subnet_id,
+    ip
+) WHERE
+    time_deleted IS NULL;
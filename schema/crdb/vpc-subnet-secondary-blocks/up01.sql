@@ -0,0 +1,2 @@
+ALTER TABLE omicron.public.vpc_subnet
+ADD COLUMN IF NOT EXISTS ipv4_secondary_blocks INET[] NOT NULL DEFAULT ARRAY[];
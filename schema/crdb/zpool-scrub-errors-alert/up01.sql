@@ -0,0 +1,2 @@
+ALTER TYPE omicron.public.alert_class
+    ADD VALUE IF NOT EXISTS 'storage.zpool.scrub_errors' AFTER 'storage.zpool.capacity';
@@ -0,0 +1,3 @@
+ALTER TABLE omicron.public.inv_zpool
+    ADD COLUMN IF NOT EXISTS allocated INT8,
+    ADD COLUMN IF NOT EXISTS fragmentation_percent INT2;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS omicron.public.zpool_usage_sample (
+    zpool_id UUID NOT NULL,
+    time_sampled TIMESTAMPTZ NOT NULL,
+    sled_id UUID NOT NULL,
+    total_size INT8 NOT NULL,
+    allocated INT8 NOT NULL,
+    fragmentation_percent INT2,
+
+    PRIMARY KEY (zpool_id, time_sampled)
+);
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS lookup_zpool_usage_sample_by_time
+    ON omicron.public.zpool_usage_sample (time_sampled);
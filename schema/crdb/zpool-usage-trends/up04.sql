@@ -0,0 +1,2 @@
+ALTER TYPE omicron.public.alert_class
+    ADD VALUE IF NOT EXISTS 'storage.zpool.capacity' AFTER 'test.quux.bar.baz';
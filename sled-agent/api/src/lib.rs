@@ -13,7 +13,8 @@ use dropshot::{
     StreamingBody, TypedBody,
 };
 use nexus_sled_agent_shared::inventory::{
-    Inventory, OmicronSledConfig, SledRole,
+    Inventory, InventoryChanges, InventoryKnownDigests, OmicronSledConfig,
+    SledRole, v1, v4, v6,
 };
 use omicron_common::{
     api::external::Generation,
@@ -66,6 +67,12 @@ api_versions!([
     // |  example for the next person.
     // v
     // (next_int, IDENT),
+    (9, ADD_INVENTORY_CHANGES),
+    (8, ADD_IDEMPOTENCY_KEYS),
+    (7, ADD_ZPOOL_HEALTH),
+    (6, ADD_ZPOOL_USAGE),
+    (5, ADD_SUPPORT_KSTAT_INFO),
+    (4, ADD_ZPOOL_SCRUB_STATUS),
     (3, ADD_SWITCH_ZONE_OPERATOR_POLICY),
     (2, REMOVE_DESTROY_ORPHANED_DATASETS_CHICKEN_SWITCH),
     (1, INITIAL),
@@ -323,9 +330,23 @@ pub trait SledAgentApi {
     #[endpoint {
         method = PUT,
         path = "/omicron-config",
+        operation_id = "omicron_config_put",
+        versions = ..VERSION_ADD_IDEMPOTENCY_KEYS,
+    }]
+    async fn omicron_config_put_v1(
+        rqctx: RequestContext<Self::Context>,
+        body: TypedBody<OmicronSledConfig>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
+
+    #[endpoint {
+        method = PUT,
+        path = "/omicron-config",
+        operation_id = "omicron_config_put",
+        versions = VERSION_ADD_IDEMPOTENCY_KEYS..,
     }]
     async fn omicron_config_put(
         rqctx: RequestContext<Self::Context>,
+        headers: Header<IdempotencyKeyHeaders>,
         body: TypedBody<OmicronSledConfig>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 
@@ -340,9 +361,24 @@ pub trait SledAgentApi {
     #[endpoint {
         method = PUT,
         path = "/vmms/{propolis_id}",
+        operation_id = "vmm_register",
+        versions = ..VERSION_ADD_IDEMPOTENCY_KEYS,
+    }]
+    async fn vmm_register_v1(
+        rqctx: RequestContext<Self::Context>,
+        path_params: Path<VmmPathParam>,
+        body: TypedBody<InstanceEnsureBody>,
+    ) -> Result<HttpResponseOk<SledVmmState>, HttpError>;
+
+    #[endpoint {
+        method = PUT,
+        path = "/vmms/{propolis_id}",
+        operation_id = "vmm_register",
+        versions = VERSION_ADD_IDEMPOTENCY_KEYS..,
     }]
     async fn vmm_register(
         rqctx: RequestContext<Self::Context>,
+        headers: Header<IdempotencyKeyHeaders>,
         path_params: Path<VmmPathParam>,
         body: TypedBody<InstanceEnsureBody>,
     ) -> Result<HttpResponseOk<SledVmmState>, HttpError>;
@@ -551,11 +587,61 @@ pub trait SledAgentApi {
     #[endpoint {
         method = GET,
         path = "/inventory",
+        operation_id = "inventory",
+        versions = ..VERSION_ADD_ZPOOL_SCRUB_STATUS,
+    }]
+    async fn inventory_v1(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v1::Inventory>, HttpError>;
+
+    /// Fetch basic information about this sled
+    #[endpoint {
+        method = GET,
+        path = "/inventory",
+        operation_id = "inventory",
+        versions =
+            VERSION_ADD_ZPOOL_SCRUB_STATUS..VERSION_ADD_ZPOOL_USAGE,
+    }]
+    async fn inventory_v4(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v4::Inventory>, HttpError>;
+
+    /// Fetch basic information about this sled
+    #[endpoint {
+        method = GET,
+        path = "/inventory",
+        operation_id = "inventory",
+        versions = VERSION_ADD_ZPOOL_USAGE..VERSION_ADD_ZPOOL_HEALTH,
+    }]
+    async fn inventory_v6(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v6::Inventory>, HttpError>;
+
+    /// Fetch basic information about this sled
+    #[endpoint {
+        method = GET,
+        path = "/inventory",
+        operation_id = "inventory",
+        versions = VERSION_ADD_ZPOOL_HEALTH..,
     }]
     async fn inventory(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<Inventory>, HttpError>;
 
+    /// Fetch the parts of this sled's inventory that have changed
+    ///
+    /// Sections whose digests match the ones provided are omitted from the
+    /// response.
+    #[endpoint {
+        method = GET,
+        path = "/inventory/changes",
+        versions = VERSION_ADD_INVENTORY_CHANGES..,
+    }]
+    async fn inventory_changes(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<InventoryKnownDigests>,
+    ) -> Result<HttpResponseOk<InventoryChanges>, HttpError>;
+
     /// Fetch sled identifiers
     #[endpoint {
         method = GET,
@@ -675,6 +761,15 @@ pub trait SledAgentApi {
         request_context: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<SledDiagnosticsQueryOutput>, HttpError>;
 
+    #[endpoint {
+        method = GET,
+        path = "/support/kstat-info",
+        versions = VERSION_ADD_SUPPORT_KSTAT_INFO..,
+    }]
+    async fn support_kstat_info(
+        request_context: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<Vec<SledDiagnosticsQueryOutput>>, HttpError>;
+
     #[endpoint {
         method = GET,
         path = "/support/health-check",
@@ -892,6 +987,18 @@ pub struct RangeRequestHeaders {
     pub range: Option<String>,
 }
 
+/// Headers for requests that can be safely retried
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct IdempotencyKeyHeaders {
+    /// A key chosen by the client to identify this request, e.g., a UUID
+    ///
+    /// If a request with the same key has already completed successfully, the
+    /// sled agent returns the response to that request instead of applying the
+    /// request again. Reusing a key for a different request is an error.
+    #[serde(rename = "idempotency-key")]
+    pub idempotency_key: Option<String>,
+}
+
 /// Path parameters for sled-diagnostics log requests used by support bundles
 /// (sled agent API)
 #[derive(Deserialize, JsonSchema)]
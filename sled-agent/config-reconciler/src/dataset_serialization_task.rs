@@ -13,6 +13,9 @@
 use crate::CurrentlyManagedZpools;
 use crate::InventoryError;
 use camino::Utf8PathBuf;
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
 use debug_ignore::DebugIgnore;
 use futures::StreamExt;
 use id_map::IdMap;
@@ -20,22 +23,28 @@ use id_map::IdMappable;
 use iddqd::IdOrdMap;
 use illumos_utils::zfs;
 use illumos_utils::zfs::CanMount;
+use illumos_utils::zfs::CreateSnapshotError;
 use illumos_utils::zfs::DatasetEnsureArgs;
 use illumos_utils::zfs::DatasetProperties;
 use illumos_utils::zfs::DestroyDatasetError;
+use illumos_utils::zfs::ListSnapshotsOfError;
 use illumos_utils::zfs::Mountpoint;
+use illumos_utils::zfs::SnapshotWithProperties;
 use illumos_utils::zfs::WhichDatasets;
 use illumos_utils::zfs::Zfs;
+use key_manager::StorageKeyRequester;
 use nexus_sled_agent_shared::inventory::InventoryDataset;
 use nexus_sled_agent_shared::inventory::OrphanedDataset;
 use omicron_common::disk::DatasetConfig;
 use omicron_common::disk::DatasetKind;
 use omicron_common::disk::DatasetName;
+use omicron_common::disk::DiskIdentity;
 use omicron_common::disk::SharedDatasetConfig;
 use omicron_common::zpool_name::ZpoolName;
 use omicron_uuid_kinds::DatasetUuid;
 use sled_storage::config::MountConfig;
 use sled_storage::dataset::CRYPT_DATASET;
+use sled_storage::dataset::DatasetError;
 use sled_storage::dataset::ZONE_DATASET;
 use sled_storage::manager::NestedDatasetConfig;
 use sled_storage::manager::NestedDatasetListOptions;
@@ -53,6 +62,16 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::oneshot;
 
+/// Name of the snapshot taken of an orphaned dataset before it's destroyed
+const SAFETY_SNAPSHOT_NAME: &str = "oxide-orphaned";
+
+/// Snapshot property recording when an orphaned dataset may be destroyed
+const SAFETY_SNAPSHOT_RETAIN_UNTIL: &str = "oxide:retain-until";
+
+/// How long orphaned datasets are kept (along with their safety snapshot)
+/// before being destroyed
+const SAFETY_SNAPSHOT_RETENTION: TimeDelta = TimeDelta::days(1);
+
 #[derive(Debug, thiserror::Error)]
 pub enum DatasetTaskError {
     #[error("dataset task busy; cannot service new requests")]
@@ -160,6 +179,19 @@ pub enum NestedDatasetListError {
     },
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionKeyRotationError {
+    #[error("failed to rotate encryption key on zpool {zpool}")]
+    RotateFailed {
+        zpool: ZpoolName,
+        #[source]
+        err: DatasetError,
+    },
+    #[cfg(test)]
+    #[error("test error: {0}")]
+    TestError(&'static str),
+}
+
 #[derive(Debug)]
 pub(crate) struct DatasetEnsureResult {
     pub(crate) config: DatasetConfig,
@@ -188,9 +220,14 @@ impl DatasetTaskHandle {
 
     pub fn spawn_dataset_task(
         mount_config: Arc<MountConfig>,
+        key_requester: StorageKeyRequester,
         base_log: &Logger,
     ) -> Self {
-        Self::spawn_with_zfs_impl(mount_config, base_log, RealZfs)
+        Self::spawn_with_zfs_impl(
+            mount_config,
+            base_log,
+            RealZfs { key_requester },
+        )
     }
 
     fn spawn_with_zfs_impl<T: ZfsImpl>(
@@ -246,10 +283,15 @@ impl DatasetTaskHandle {
     ///   kind that we ought to destroy? See
     ///   `reason_to_skip_orphaned_dataset_destruction` for kinds we refuse to
     ///   destroy.
+    /// * Has the dataset been kept long enough for its data to be recovered,
+    ///   in case it was orphaned by mistake? The first time we find an
+    ///   orphan, we take a safety snapshot of it and keep it for a day before
+    ///   destroying it (and the snapshot).
     ///
     /// The returned map lists includes the orphaned datasets we found but did
     /// not destroy, either because of one of the checks above or because we
-    /// attempted destruction but it failed.
+    /// attempted destruction but it failed. The reason recorded for datasets
+    /// that are being retained names their safety snapshot.
     pub async fn datasets_destroy_orphans(
         &self,
         datasets: IdMap<DatasetConfig>,
@@ -327,6 +369,24 @@ impl DatasetTaskHandle {
         .await
     }
 
+    /// Rotate the key of the encryption root on each U.2 in `disks` (keyed by
+    /// zpool) to the key for `new_epoch`.
+    pub async fn encryption_keys_rotate(
+        &self,
+        disks: BTreeMap<ZpoolName, DiskIdentity>,
+        new_epoch: u64,
+    ) -> Result<
+        BTreeMap<ZpoolName, Result<(), EncryptionKeyRotationError>>,
+        DatasetTaskError,
+    > {
+        self.try_send_request(|tx| DatasetTaskRequest::EncryptionKeysRotate {
+            disks,
+            new_epoch,
+            tx,
+        })
+        .await
+    }
+
     async fn try_send_request<T, F>(
         &self,
         make_request: F,
@@ -418,7 +478,55 @@ impl DatasetTask {
                     self.nested_dataset_list(dataset, options, zfs).await,
                 );
             }
+            DatasetTaskRequest::EncryptionKeysRotate {
+                disks,
+                new_epoch,
+                tx,
+            } => {
+                _ = tx.0.send(
+                    self.encryption_keys_rotate(disks, new_epoch, zfs).await,
+                );
+            }
+        }
+    }
+
+    async fn encryption_keys_rotate<T: ZfsImpl>(
+        &self,
+        disks: BTreeMap<ZpoolName, DiskIdentity>,
+        new_epoch: u64,
+        zfs: &T,
+    ) -> BTreeMap<ZpoolName, Result<(), EncryptionKeyRotationError>> {
+        let mut results = BTreeMap::new();
+        for (zpool, disk_identity) in disks {
+            let result = zfs
+                .rotate_encryption_root_key(
+                    &self.log,
+                    &self.mount_config,
+                    &zpool,
+                    &disk_identity,
+                    new_epoch,
+                )
+                .await;
+            match &result {
+                Ok(()) => {
+                    info!(
+                        self.log, "rotated encryption key";
+                        "zpool" => %zpool,
+                        "epoch" => new_epoch,
+                    );
+                }
+                Err(err) => {
+                    warn!(
+                        self.log, "failed to rotate encryption key";
+                        "zpool" => %zpool,
+                        "epoch" => new_epoch,
+                        InlineErrorChain::new(err),
+                    );
+                }
+            }
+            results.insert(zpool, result);
         }
+        results
     }
 
     async fn inventory<T: ZfsImpl>(
@@ -549,6 +657,23 @@ impl DatasetTask {
                 continue;
             }
 
+            // Is this dataset still being kept around in case it was orphaned
+            // by mistake?
+            if let Some(reason) = self
+                .reason_to_retain_orphaned_dataset(&dataset_full_name, zfs)
+                .await
+            {
+                orphaned_datasets.insert_overwrite(OrphanedDataset {
+                    name: dataset,
+                    reason,
+                    id: properties.id,
+                    mounted: properties.mounted,
+                    available: properties.avail,
+                    used: properties.used,
+                });
+                continue;
+            }
+
             // Try to destroy this dataset. If we fail, record the reason this
             // dataset is left orphaned.
             let maybe_reason =
@@ -587,6 +712,100 @@ impl DatasetTask {
         Ok(orphaned_datasets)
     }
 
+    // Datasets are orphaned when they're removed from our config, which may be
+    // because of an erroneous blueprint. Before destroying one, we snapshot it
+    // and then keep it for `SAFETY_SNAPSHOT_RETENTION`, so that its data can be
+    // recovered in the meantime. (A snapshot can't outlive its dataset, so the
+    // dataset itself has to be kept, too.)
+    //
+    // Returns `None` once the retention window has passed and the dataset may
+    // be destroyed (along with the snapshot), or `Some(reason)` while it's
+    // being retained, including when we couldn't take the snapshot.
+    async fn reason_to_retain_orphaned_dataset<T: ZfsImpl>(
+        &self,
+        dataset_full_name: &str,
+        zfs: &T,
+    ) -> Option<String> {
+        let snapshot = format!("{dataset_full_name}@{SAFETY_SNAPSHOT_NAME}");
+
+        let existing = match zfs
+            .list_snapshots_of(
+                dataset_full_name,
+                &[SAFETY_SNAPSHOT_RETAIN_UNTIL],
+            )
+            .await
+        {
+            Ok(snapshots) => snapshots
+                .into_iter()
+                .find(|s| s.snapshot.snap_name == SAFETY_SNAPSHOT_NAME),
+            Err(err) => {
+                return Some(format!(
+                    "failed to look for safety snapshot: {}",
+                    InlineErrorChain::new(&err),
+                ));
+            }
+        };
+
+        let retain_until = match existing {
+            Some(existing) => {
+                match existing
+                    .parse_value::<DateTime<Utc>>(SAFETY_SNAPSHOT_RETAIN_UNTIL)
+                {
+                    Ok(Some(retain_until)) => retain_until,
+                    // We always set this property when taking the snapshot;
+                    // leave anything else alone for an operator to look at.
+                    Ok(None) | Err(_) => {
+                        return Some(format!(
+                            "safety snapshot {snapshot} has no valid \
+                             {SAFETY_SNAPSHOT_RETAIN_UNTIL} property",
+                        ));
+                    }
+                }
+            }
+            None => {
+                let retain_until = Utc::now() + SAFETY_SNAPSHOT_RETENTION;
+                if let Err(err) = zfs
+                    .create_snapshot(
+                        dataset_full_name,
+                        SAFETY_SNAPSHOT_NAME,
+                        &[(
+                            SAFETY_SNAPSHOT_RETAIN_UNTIL,
+                            &retain_until.to_rfc3339(),
+                        )],
+                    )
+                    .await
+                {
+                    warn!(
+                        self.log,
+                        "failed to take safety snapshot of orphaned dataset";
+                        "dataset" => dataset_full_name,
+                        InlineErrorChain::new(&err),
+                    );
+                    return Some(format!(
+                        "failed to take safety snapshot: {}",
+                        InlineErrorChain::new(&err),
+                    ));
+                }
+                info!(
+                    self.log,
+                    "took safety snapshot of orphaned dataset";
+                    "snapshot" => &snapshot,
+                    "retain_until" => %retain_until,
+                );
+                retain_until
+            }
+        };
+
+        if Utc::now() < retain_until {
+            Some(format!(
+                "retained for recovery until {} (safety snapshot {snapshot})",
+                retain_until.to_rfc3339(),
+            ))
+        } else {
+            None
+        }
+    }
+
     async fn datasets_ensure<T: ZfsImpl>(
         &mut self,
         config: IdMap<DatasetConfig>,
@@ -1143,6 +1362,15 @@ enum DatasetTaskRequest {
             >,
         >,
     },
+    EncryptionKeysRotate {
+        disks: BTreeMap<ZpoolName, DiskIdentity>,
+        new_epoch: u64,
+        tx: DebugIgnore<
+            oneshot::Sender<
+                BTreeMap<ZpoolName, Result<(), EncryptionKeyRotationError>>,
+            >,
+        >,
+    },
 }
 
 #[derive(Debug)]
@@ -1217,9 +1445,35 @@ trait ZfsImpl: Send + Sync + 'static {
         datasets: &[String],
         which: WhichDatasets,
     ) -> impl Future<Output = anyhow::Result<Vec<DatasetProperties>>> + Send;
+
+    fn list_snapshots_of(
+        &self,
+        filesystem: &str,
+        properties: &[&str],
+    ) -> impl Future<
+        Output = Result<Vec<SnapshotWithProperties>, ListSnapshotsOfError>,
+    > + Send;
+
+    fn create_snapshot(
+        &self,
+        filesystem: &str,
+        snap_name: &str,
+        properties: &[(&str, &str)],
+    ) -> impl Future<Output = Result<(), CreateSnapshotError>> + Send;
+
+    fn rotate_encryption_root_key(
+        &self,
+        log: &Logger,
+        mount_config: &MountConfig,
+        zpool: &ZpoolName,
+        disk_identity: &DiskIdentity,
+        new_epoch: u64,
+    ) -> impl Future<Output = Result<(), EncryptionKeyRotationError>> + Send;
 }
 
-struct RealZfs;
+struct RealZfs {
+    key_requester: StorageKeyRequester,
+}
 
 impl ZfsImpl for RealZfs {
     async fn ensure_dataset(
@@ -1262,6 +1516,46 @@ impl ZfsImpl for RealZfs {
     ) -> anyhow::Result<Vec<DatasetProperties>> {
         Zfs::get_dataset_properties(datasets, which).await
     }
+
+    async fn list_snapshots_of(
+        &self,
+        filesystem: &str,
+        properties: &[&str],
+    ) -> Result<Vec<SnapshotWithProperties>, ListSnapshotsOfError> {
+        Zfs::list_snapshots_of(filesystem, properties).await
+    }
+
+    async fn create_snapshot(
+        &self,
+        filesystem: &str,
+        snap_name: &str,
+        properties: &[(&str, &str)],
+    ) -> Result<(), CreateSnapshotError> {
+        Zfs::create_snapshot(filesystem, snap_name, properties).await
+    }
+
+    async fn rotate_encryption_root_key(
+        &self,
+        log: &Logger,
+        mount_config: &MountConfig,
+        zpool: &ZpoolName,
+        disk_identity: &DiskIdentity,
+        new_epoch: u64,
+    ) -> Result<(), EncryptionKeyRotationError> {
+        sled_storage::dataset::rotate_encryption_root_key(
+            log,
+            mount_config,
+            zpool,
+            disk_identity,
+            &self.key_requester,
+            new_epoch,
+        )
+        .await
+        .map_err(|err| EncryptionKeyRotationError::RotateFailed {
+            zpool: *zpool,
+            err,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1303,6 +1597,12 @@ mod tests {
         // Map of reasons to return an error for any attempts to
         // `ensure_dataset()` for the given dataset name.
         ensure_should_fail: BTreeMap<String, &'static str>,
+        // Current encryption epoch of each zpool's encryption root; zpools
+        // that have never been rotated are at epoch 0.
+        encryption_epochs: BTreeMap<ZpoolName, u64>,
+        // Snapshots that have been created, keyed by dataset name and then
+        // snapshot name, with the properties they were created with.
+        snapshots: BTreeMap<String, BTreeMap<String, BTreeMap<String, String>>>,
     }
 
     impl ZfsImpl for InMemoryZfs {
@@ -1398,9 +1698,10 @@ mod tests {
                 });
             }
 
-            // ...and any children.
+            // ...and any children, along with all their snapshots.
             let prefix = format!("{name}/");
             state.datasets.retain(|k, _| !k.starts_with(&prefix));
+            state.snapshots.retain(|k, _| k != name && !k.starts_with(&prefix));
 
             Ok(())
         }
@@ -1449,6 +1750,75 @@ mod tests {
                 .map(|(_, props)| props.clone())
                 .collect())
         }
+
+        async fn list_snapshots_of(
+            &self,
+            filesystem: &str,
+            properties: &[&str],
+        ) -> Result<Vec<SnapshotWithProperties>, ListSnapshotsOfError> {
+            let state = self.inner.lock().unwrap();
+            let Some(snapshots) = state.snapshots.get(filesystem) else {
+                return Ok(Vec::new());
+            };
+            Ok(snapshots
+                .iter()
+                .map(|(snap_name, props)| {
+                    SnapshotWithProperties::new(
+                        zfs::Snapshot {
+                            filesystem: filesystem.to_string(),
+                            snap_name: snap_name.clone(),
+                        },
+                        props
+                            .iter()
+                            .filter(|(name, _)| {
+                                properties.contains(&name.as_str())
+                            })
+                            .map(|(name, value)| (name.clone(), value.clone()))
+                            .collect(),
+                    )
+                })
+                .collect())
+        }
+
+        async fn create_snapshot(
+            &self,
+            filesystem: &str,
+            snap_name: &str,
+            properties: &[(&str, &str)],
+        ) -> Result<(), CreateSnapshotError> {
+            let mut state = self.inner.lock().unwrap();
+            assert!(
+                state.datasets.contains_key(filesystem),
+                "snapshotting nonexistent dataset {filesystem}"
+            );
+            state.snapshots.entry(filesystem.to_string()).or_default().insert(
+                snap_name.to_string(),
+                properties
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            );
+            Ok(())
+        }
+
+        async fn rotate_encryption_root_key(
+            &self,
+            _log: &Logger,
+            _mount_config: &MountConfig,
+            zpool: &ZpoolName,
+            _disk_identity: &DiskIdentity,
+            new_epoch: u64,
+        ) -> Result<(), EncryptionKeyRotationError> {
+            let mut state = self.inner.lock().unwrap();
+            let epoch = state.encryption_epochs.entry(*zpool).or_default();
+            if *epoch > new_epoch {
+                return Err(EncryptionKeyRotationError::TestError(
+                    "epoch regression",
+                ));
+            }
+            *epoch = new_epoch;
+            Ok(())
+        }
     }
 
     #[derive(Debug, Arbitrary, PartialEq, Eq, PartialOrd, Ord)]
@@ -2044,7 +2414,55 @@ mod tests {
             zfs.clone(),
         );
 
-        // Destroy all orphans.
+        // Destroy all orphans. The first pass only takes safety snapshots of
+        // them; they aren't destroyed until those expire.
+        let orphans = task_handle
+            .datasets_destroy_orphans(
+                dataset_configs.clone(),
+                Arc::clone(&currently_managed_zpools),
+            )
+            .await
+            .expect("no task error")
+            .expect("no zfs error");
+        for dataset in &datasets_not_in_config {
+            if reason_to_skip_orphaned_dataset_destruction(dataset.kind())
+                .is_some()
+            {
+                continue;
+            }
+            let orphan = orphans.get(dataset).expect("orphan was retained");
+            assert!(
+                orphan.reason.starts_with("retained for recovery until"),
+                "unexpected reason: {}",
+                orphan.reason,
+            );
+        }
+
+        // Running again before the snapshots expire changes nothing.
+        let orphans_again = task_handle
+            .datasets_destroy_orphans(
+                dataset_configs.clone(),
+                Arc::clone(&currently_managed_zpools),
+            )
+            .await
+            .expect("no task error")
+            .expect("no zfs error");
+        assert_eq!(orphans, orphans_again);
+
+        // Expire all the safety snapshots, then try again.
+        {
+            let mut zfs = zfs.inner.lock().unwrap();
+            let expired = (Utc::now() - TimeDelta::seconds(1)).to_rfc3339();
+            for snapshots in zfs.snapshots.values_mut() {
+                let props = snapshots
+                    .get_mut(SAFETY_SNAPSHOT_NAME)
+                    .expect("only safety snapshots are taken");
+                props.insert(
+                    SAFETY_SNAPSHOT_RETAIN_UNTIL.to_string(),
+                    expired.clone(),
+                );
+            }
+        }
         let orphans = task_handle
             .datasets_destroy_orphans(dataset_configs, currently_managed_zpools)
             .await
@@ -2098,6 +2516,10 @@ mod tests {
                         !zfs.datasets.contains_key(&name),
                         "dataset should have been destroyed: {name}"
                     );
+                    assert!(
+                        !zfs.snapshots.contains_key(&name),
+                        "safety snapshot should have been destroyed: {name}"
+                    );
                 }
             }
         }
@@ -2301,6 +2723,72 @@ mod tests {
 
         logctx.cleanup_successful();
     }
+
+    #[test]
+    fn encryption_keys_rotated_on_all_requested_zpools() {
+        let logctx = dev::test_setup_log(
+            "encryption_keys_rotated_on_all_requested_zpools",
+        );
+        let zfs = InMemoryZfs::default();
+
+        let disks = (0..3)
+            .map(|i| {
+                (
+                    ZpoolName::new_external(ZpoolUuid::new_v4()),
+                    DiskIdentity {
+                        vendor: "test-vendor".to_string(),
+                        model: "test-model".to_string(),
+                        serial: format!("test-serial-{i}"),
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        with_test_runtime(async {
+            let task_handle = DatasetTaskHandle::spawn_with_zfs_impl(
+                nonexistent_mount_config(),
+                &logctx.log,
+                zfs.clone(),
+            );
+
+            // Every zpool we asked for should be rotated.
+            let results = task_handle
+                .encryption_keys_rotate(disks.clone(), 2)
+                .await
+                .expect("no task error");
+            assert_eq!(
+                results.keys().collect::<Vec<_>>(),
+                disks.keys().collect::<Vec<_>>()
+            );
+            for (zpool, result) in results {
+                assert_matches!(result, Ok(()), "bad result for {zpool}");
+            }
+            assert_eq!(
+                zfs.inner.lock().unwrap().encryption_epochs,
+                disks.keys().map(|zpool| (*zpool, 2)).collect()
+            );
+
+            // Failing to rotate one zpool doesn't prevent rotating the others.
+            let (stale_zpool, _) = disks.first_key_value().unwrap();
+            zfs.inner.lock().unwrap().encryption_epochs.insert(*stale_zpool, 4);
+            let results = task_handle
+                .encryption_keys_rotate(disks.clone(), 3)
+                .await
+                .expect("no task error");
+            for (zpool, result) in results {
+                if zpool == *stale_zpool {
+                    assert_matches!(
+                        result,
+                        Err(EncryptionKeyRotationError::TestError(_))
+                    );
+                } else {
+                    assert_matches!(result, Ok(()), "bad result for {zpool}");
+                }
+            }
+        });
+
+        logctx.cleanup_successful();
+    }
 }
 
 // On illumos, run a set of tests that use the `RealZfs` implementation on
@@ -2554,6 +3042,7 @@ mod illumos_tests {
         let zpool = harness.add_zpool(ZpoolKind::External).await;
         let task_handle = DatasetTaskHandle::spawn_dataset_task(
             Arc::new(harness.mount_config.clone()),
+            harness.key_requester.clone(),
             &logctx.log,
         );
 
@@ -2629,6 +3118,7 @@ mod illumos_tests {
         let zpool = harness.add_zpool(ZpoolKind::External).await;
         let task_handle = DatasetTaskHandle::spawn_dataset_task(
             Arc::new(harness.mount_config.clone()),
+            harness.key_requester.clone(),
             &logctx.log,
         );
 
@@ -2693,6 +3183,7 @@ mod illumos_tests {
         let zpool = harness.add_zpool(ZpoolKind::External).await;
         let task_handle = DatasetTaskHandle::spawn_dataset_task(
             Arc::new(harness.mount_config.clone()),
+            harness.key_requester.clone(),
             &logctx.log,
         );
 
@@ -2785,6 +3276,7 @@ mod illumos_tests {
         };
         let task_handle = DatasetTaskHandle::spawn_dataset_task(
             Arc::new(harness.mount_config.clone()),
+            harness.key_requester.clone(),
             &logctx.log,
         );
 
@@ -2833,6 +3325,7 @@ mod illumos_tests {
         let zpool = harness.add_zpool(ZpoolKind::External).await;
         let task_handle = DatasetTaskHandle::spawn_dataset_task(
             Arc::new(harness.mount_config.clone()),
+            harness.key_requester.clone(),
             &logctx.log,
         );
 
@@ -85,6 +85,28 @@
 //! In the event of filename collisions (i.e. several instances of a service's
 //! rotated log files having the same modified time to the second), the
 //! number is incremented by 1 until no conflict remains.
+//!
+//! logadm(8) only runs periodically, so a particularly chatty service can
+//! fill its zone's root filesystem between rotations. To guard against this,
+//! during the same 5-minute pass, any *live* SMF log that has grown past 64
+//! MiB, or that is non-empty and hasn't been rotated by us in the last 24
+//! hours, is copied into the DUMP_DATASET (using the same naming scheme as
+//! above) and then truncated in place:
+//! ```text
+//!     /pool/int/*/crypt/zone/oxz_bar/root/var/svc/log/baz.log
+//!         -> /pool/ext/*/crypt/debug/oxz_bar/baz.log.34784217
+//! ```
+//!
+//! SMF opens log files in append mode, so the service continues writing at
+//! the start of the truncated file; any lines written between the copy and
+//! the truncation are lost, as with `logadm -c`. Live logs are not rotated
+//! while the chosen DUMP_DATASET is over 80% of its quota, leaving it to the
+//! dataset selection and cleanup described above to free up space first.
+//!
+//! The DUMP_DATASET is created with ZFS compression enabled, so archived logs
+//! are stored compressed while keeping their original names. Because of
+//! that, they're found by zone bundle collection (and `oxlog`) alongside the
+//! logs still present in the zone.
 
 use async_trait::async_trait;
 use camino::Utf8Path;
@@ -108,8 +130,9 @@ use slog::info;
 use slog::o;
 use slog::trace;
 use slog::warn;
+use std::collections::HashMap;
 use std::collections::HashSet;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, SystemTimeError, UNIX_EPOCH};
@@ -125,6 +148,13 @@ const DATASET_USAGE_PERCENT_CLEANUP: u64 = 80;
 
 const ARCHIVAL_INTERVAL: Duration = Duration::from_secs(300);
 
+// Live SMF logs over this size are rotated into the debug dataset without
+// waiting for logadm.
+const LOG_ROTATION_SIZE_BYTES: u64 = 64 * 1024 * 1024;
+// Non-empty live SMF logs we haven't rotated in this long are rotated into the
+// debug dataset regardless of size.
+const LOG_ROTATION_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
 // we sure are passing a lot of Utf8PathBufs around, let's be careful about it
 #[derive(AsRef, Clone, Debug, Eq, From, Hash, Ord, PartialEq, PartialOrd)]
 struct DumpSlicePath(Utf8PathBuf);
@@ -208,6 +238,10 @@ struct DumpSetupWorker {
 
     savecored_slices: HashSet<DumpSlicePath>,
 
+    // When we last rotated each live log file (or first saw it, if we never
+    // have), for time-based rotation.
+    live_log_rotation_times: HashMap<PathBuf, SystemTime>,
+
     log: Logger,
     rx: Receiver<DumpSetupCmd>,
     coredumpadm_invoker: Box<dyn CoreDumpAdmInvoker + Send + Sync>,
@@ -551,6 +585,7 @@ impl DumpSetupWorker {
             known_debug_dirs: vec![],
             known_core_dirs: vec![],
             savecored_slices: Default::default(),
+            live_log_rotation_times: Default::default(),
             log,
             rx,
             coredumpadm_invoker,
@@ -921,7 +956,7 @@ impl DumpSetupWorker {
         }
     }
 
-    async fn archive_files(&self) -> tokio::io::Result<()> {
+    async fn archive_files(&mut self) -> tokio::io::Result<()> {
         if let Some(debug_dir) = &self.chosen_debug_dir {
             if self.known_core_dirs.is_empty() {
                 info!(self.log, "No core dump locations yet known.");
@@ -994,12 +1029,14 @@ impl DumpSetupWorker {
         Ok(())
     }
 
-    async fn archive_logs(&self) -> Result<(), ArchiveLogsError> {
+    async fn archive_logs(&mut self) -> Result<(), ArchiveLogsError> {
         let debug_dir = self
             .chosen_debug_dir
-            .as_ref()
+            .clone()
             .ok_or(ArchiveLogsError::NoDebugDirYet)?;
         let oxz_zones = self.zone_invoker.get_zones().await?;
+        let now = SystemTime::now();
+        let mut live_logs = HashSet::new();
         for zone in oxz_zones {
             let logdir = if zone.global() {
                 PathBuf::from("/var/svc/log")
@@ -1007,11 +1044,163 @@ impl DumpSetupWorker {
                 zone.path().join("root/var/svc/log")
             };
             let zone_name = zone.name();
-            self.archive_logs_inner(debug_dir, logdir, zone_name).await?;
+            self.rotate_live_logs(
+                &debug_dir,
+                &logdir,
+                zone_name,
+                now,
+                &mut live_logs,
+            )
+            .await?;
+            self.archive_logs_inner(&debug_dir, logdir, zone_name).await?;
+        }
+        // forget about the logs of zones that no longer exist
+        self.live_log_rotation_times.retain(|path, _| live_logs.contains(path));
+        Ok(())
+    }
+
+    // Copy-and-truncate any live log in `logdir` that is over
+    // LOG_ROTATION_SIZE_BYTES, or that we haven't rotated within
+    // LOG_ROTATION_MAX_AGE, into the debug dataset. Every live log found is
+    // added to `live_logs`.
+    async fn rotate_live_logs(
+        &mut self,
+        debug_dir: &DebugDataset,
+        logdir: &Path,
+        zone_name: &str,
+        now: SystemTime,
+        live_logs: &mut HashSet<PathBuf>,
+    ) -> Result<(), ArchiveLogsError> {
+        let pattern = logdir
+            .join("*.log")
+            .to_str()
+            .ok_or_else(|| ArchiveLogsError::Utf8(zone_name.to_string()))?
+            .to_string();
+        let mut to_rotate = Vec::new();
+        for entry in glob::glob(&pattern)?.flatten() {
+            let Ok(meta) = tokio::fs::metadata(&entry).await else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            live_logs.insert(entry.clone());
+            let last_rotated = *self
+                .live_log_rotation_times
+                .entry(entry.clone())
+                .or_insert(now);
+            let too_big = meta.len() >= LOG_ROTATION_SIZE_BYTES;
+            let too_old = meta.len() > 0
+                && now
+                    .duration_since(last_rotated)
+                    .is_ok_and(|age| age >= LOG_ROTATION_MAX_AGE);
+            if too_big || too_old {
+                to_rotate.push(entry);
+            }
+        }
+        if to_rotate.is_empty() {
+            return Ok(());
+        }
+
+        // don't push a debug dataset that's already nearing its quota any
+        // further; reevaluate_choices will choose another or clean this one up.
+        match self
+            .zfs_invoker
+            .below_thresh(debug_dir.as_ref(), DATASET_USAGE_PERCENT_CLEANUP)
+        {
+            Ok((true, _)) => {}
+            Ok((false, _)) => {
+                warn!(
+                    self.log,
+                    "Debug dir {debug_dir:?} is over usage threshold, not rotating live logs from {zone_name} zone"
+                );
+                return Ok(());
+            }
+            Err(err) => {
+                error!(
+                    self.log,
+                    "Could not query zfs properties of debug dir {debug_dir:?}, not rotating live logs from {zone_name} zone: {err:?}"
+                );
+                return Ok(());
+            }
+        }
+
+        let dest_dir = debug_dir.as_ref().join(zone_name).into_std_path_buf();
+        tokio::fs::create_dir_all(&dest_dir).await?;
+        let count = to_rotate.len();
+        info!(
+            self.log,
+            "Rotating {count} live log files from {zone_name} zone"
+        );
+        for entry in to_rotate {
+            let dest = Self::archive_dest(
+                &dest_dir,
+                entry.file_name().unwrap(),
+                &entry,
+            );
+            if let Err(err) = Self::copy_sync_and_truncate(&entry, dest).await {
+                warn!(self.log, "Failed to rotate {entry:?}: {err:?}");
+            } else {
+                self.live_log_rotation_times.insert(entry, now);
+            }
         }
         Ok(())
     }
 
+    // Like copy_sync_and_remove, but leaves `source` in place (empty) for
+    // its writer to continue appending to.
+    async fn copy_sync_and_truncate(
+        source: impl AsRef<Path>,
+        dest: impl AsRef<Path>,
+    ) -> tokio::io::Result<()> {
+        let source = source.as_ref();
+        let dest = dest.as_ref();
+        let mut dest_f = tokio::fs::File::create(&dest).await?;
+        let mut src_f = tokio::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&source)
+            .await?;
+
+        tokio::io::copy(&mut src_f, &mut dest_f).await?;
+
+        dest_f.sync_all().await?;
+
+        src_f.set_len(0).await?;
+        Ok(())
+    }
+
+    // Choose a path in `dest_dir` at which to archive the log file at
+    // `source`, named `{base_name}.{n}`.
+    fn archive_dest(
+        dest_dir: &Path,
+        base_name: &OsStr,
+        source: &Path,
+    ) -> PathBuf {
+        // as we archive them, logadm will keep resetting to .log.0,
+        // so we need to maintain our own numbering in the dest dataset.
+        // we'll use the modified date of the rotated log file, or try
+        // falling back to the time of archival if that fails, and
+        // falling back to counting up from 0 if *that* somehow fails.
+        let mut n = source
+            .metadata()
+            .and_then(|m| m.modified())
+            .unwrap_or_else(|_| SystemTime::now())
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        loop {
+            let mut name = base_name.to_os_string();
+            name.push(format!(".{n}"));
+            let dest = dest_dir.join(name);
+            if dest.exists() {
+                n += 1;
+            } else {
+                return dest;
+            }
+        }
+    }
+
     async fn archive_logs_inner(
         &self,
         debug_dir: &DebugDataset,
@@ -1039,28 +1228,9 @@ impl DumpSetupWorker {
             );
         }
         for entry in rotated_log_files {
-            let src_name = entry.file_name().unwrap();
-            // as we archive them, logadm will keep resetting to .log.0,
-            // so we need to maintain our own numbering in the dest dataset.
-            // we'll use the modified date of the rotated log file, or try
-            // falling back to the time of archival if that fails, and
-            // falling back to counting up from 0 if *that* somehow fails.
-            let mut n = entry
-                .metadata()
-                .and_then(|m| m.modified())
-                .unwrap_or_else(|_| SystemTime::now())
-                .duration_since(UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0);
-            let mut dest;
-            loop {
-                dest = dest_dir.join(src_name).with_extension(format!("{n}"));
-                if dest.exists() {
-                    n += 1;
-                } else {
-                    break;
-                }
-            }
+            // strip the logadm-assigned number, e.g. foo.log.0 -> foo.log
+            let base_name = entry.file_stem().unwrap();
+            let dest = Self::archive_dest(&dest_dir, base_name, &entry);
             if let Err(err) = Self::copy_sync_and_remove(&entry, dest).await {
                 warn!(self.log, "Failed to archive {entry:?}: {err:?}");
             }
@@ -1731,6 +1901,99 @@ mod tests {
         logctx.cleanup_successful();
     }
 
+    #[tokio::test]
+    async fn test_rotates_oversized_and_stale_live_logs() {
+        let logctx = omicron_test_utils::dev::test_setup_log(
+            "test_rotates_oversized_and_stale_live_logs",
+        );
+
+        let tempdir = Utf8TempDir::new().unwrap();
+        let debug_dir = tempdir.path().join(DUMP_DATASET);
+        let zone_logs = tempdir.path().join("root/var/svc/log");
+
+        let tempdir_path = tempdir.path().as_str().to_string();
+        let zone = Zone::from_str(&format!(
+            "1:myzone:running:{tempdir_path}::ipkg:shared"
+        ))
+        .unwrap();
+
+        const MOUNTED_EXTERNAL: &str =
+            "oxp_446f6e74-4469-6557-6f6e-646572696e67";
+        let mut worker = DumpSetupWorker::new(
+            Box::<FakeCoreDumpAdm>::default(),
+            Box::new(FakeZfs {
+                zpool_props: [
+                    (
+                        MOUNTED_EXTERNAL.to_string(),
+                        [
+                            ("mounted", Ok("yes".to_string())),
+                            ("mountpoint", Ok(tempdir_path)),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    (
+                        debug_dir.to_string(),
+                        [
+                            (ZFS_PROP_USED, Ok("0".to_string())),
+                            (ZFS_PROP_AVAILABLE, Ok("1024".to_string())),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+            Box::new(FakeZone { zones: vec![zone.clone()] }),
+            logctx.log.clone(),
+            tokio::sync::mpsc::channel(1).1,
+        );
+
+        tokio::fs::create_dir_all(&debug_dir).await.unwrap();
+        let now = SystemTime::now();
+        let big_log = zone_logs.join("big.log");
+        let stale_log = zone_logs.join("stale.log");
+        let fresh_log = zone_logs.join("fresh.log");
+        create_test_file(&big_log, LOG_ROTATION_SIZE_BYTES, now);
+        create_test_file(&stale_log, 1, now);
+        create_test_file(&fresh_log, 1, now);
+
+        let mounted_debug_zpool = DebugZpool {
+            mount_config: Arc::new(MountConfig::default()),
+            name: ZpoolName::from_str(MOUNTED_EXTERNAL).unwrap(),
+        };
+        worker.update_disk_loadout(vec![], vec![mounted_debug_zpool], vec![]);
+        worker.reevaluate_choices().await;
+
+        // pretend we last rotated one of the small logs long ago
+        worker.live_log_rotation_times.insert(
+            stale_log.clone().into_std_path_buf(),
+            now - LOG_ROTATION_MAX_AGE,
+        );
+        worker.archive_files().await.unwrap();
+
+        let archived = |name: &str| {
+            let pattern = debug_dir.join(zone.name()).join(format!("{name}.*"));
+            glob::glob(pattern.as_str()).unwrap().flatten().collect::<Vec<_>>()
+        };
+        let big_archived = archived("big.log");
+        assert_eq!(big_archived.len(), 1);
+        assert_eq!(
+            big_archived[0].metadata().unwrap().len(),
+            LOG_ROTATION_SIZE_BYTES
+        );
+        assert_eq!(archived("stale.log").len(), 1);
+        assert!(archived("fresh.log").is_empty());
+
+        // rotated logs are left in place, empty, for SMF to keep appending to
+        assert_eq!(big_log.metadata().unwrap().len(), 0);
+        assert_eq!(stale_log.metadata().unwrap().len(), 0);
+        assert_eq!(fresh_log.metadata().unwrap().len(), 1);
+
+        logctx.cleanup_successful();
+    }
+
     fn create_test_file(path: &Utf8Path, size: u64, time: SystemTime) {
         if let Some(parent) = path.parent() {
             if !parent.exists() {
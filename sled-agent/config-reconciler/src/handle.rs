@@ -4,6 +4,7 @@
 
 use camino::Utf8PathBuf;
 use illumos_utils::zpool::PathInPool;
+use illumos_utils::zpool::ZpoolName;
 use key_manager::StorageKeyRequester;
 use nexus_sled_agent_shared::inventory::ConfigReconcilerInventory;
 use nexus_sled_agent_shared::inventory::ConfigReconcilerInventoryStatus;
@@ -12,6 +13,7 @@ use nexus_sled_agent_shared::inventory::InventoryDisk;
 use nexus_sled_agent_shared::inventory::InventoryZpool;
 use nexus_sled_agent_shared::inventory::OmicronSledConfig;
 use omicron_common::disk::DatasetName;
+use omicron_common::disk::DiskVariant;
 use sled_agent_api::ArtifactConfig;
 use sled_storage::config::MountConfig;
 use sled_storage::disk::Disk;
@@ -19,6 +21,7 @@ use sled_storage::manager::NestedDatasetConfig;
 use sled_storage::manager::NestedDatasetListOptions;
 use sled_storage::manager::NestedDatasetLocation;
 use slog::Logger;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::OnceLock;
@@ -27,8 +30,6 @@ use tokio::sync::watch;
 #[cfg(feature = "testing")]
 use camino_tempfile::Utf8TempDir;
 #[cfg(feature = "testing")]
-use illumos_utils::zpool::ZpoolName;
-#[cfg(feature = "testing")]
 use illumos_utils::zpool::ZpoolOrRamdisk;
 #[cfg(feature = "testing")]
 use sled_storage::dataset::U2_DEBUG_DATASET;
@@ -36,6 +37,7 @@ use sled_storage::dataset::U2_DEBUG_DATASET;
 use sled_storage::dataset::ZONE_DATASET;
 
 use crate::DatasetTaskError;
+use crate::EncryptionKeyRotationError;
 use crate::InternalDisksWithBootDisk;
 use crate::LedgerArtifactConfigError;
 use crate::LedgerNewConfigError;
@@ -59,6 +61,8 @@ use crate::reconciler_task;
 use crate::reconciler_task::CurrentlyManagedZpools;
 use crate::reconciler_task::CurrentlyManagedZpoolsReceiver;
 use crate::reconciler_task::ReconcilerResult;
+use crate::zpool_scrub_task;
+use crate::zpool_scrub_task::ZpoolScrubPolicy;
 
 #[derive(Debug, thiserror::Error)]
 pub enum InventoryError {
@@ -97,6 +101,7 @@ pub struct ConfigReconcilerHandle {
     dataset_task: DatasetTaskHandle,
     reconciler_result_rx: watch::Receiver<ReconcilerResult>,
     currently_managed_zpools_rx: CurrentlyManagedZpoolsReceiver,
+    external_disks_rx: watch::Receiver<HashSet<Disk>>,
 
     // Empty until `spawn_reconciliation_task()` is called.
     ledger_task: OnceLock<LedgerTaskHandle>,
@@ -116,6 +121,7 @@ impl ConfigReconcilerHandle {
         mount_config: MountConfig,
         key_requester: StorageKeyRequester,
         time_sync_config: TimeSyncConfig,
+        scrub_policy: ZpoolScrubPolicy,
         base_log: &Logger,
     ) -> (Self, ConfigReconcilerSpawnToken) {
         let mount_config = Arc::new(mount_config);
@@ -134,7 +140,7 @@ impl ConfigReconcilerHandle {
             watch::channel(HashSet::new());
         dump_setup_task::spawn(
             internal_disks_rx.clone(),
-            external_disks_rx,
+            external_disks_rx.clone(),
             Arc::clone(&mount_config),
             base_log,
         );
@@ -146,9 +152,18 @@ impl ConfigReconcilerHandle {
         let currently_managed_zpools_rx =
             CurrentlyManagedZpoolsReceiver::new(currently_managed_zpools_rx);
 
+        // Spawn the task that schedules zpool scrubs.
+        zpool_scrub_task::spawn(
+            scrub_policy,
+            internal_disks_rx.clone(),
+            currently_managed_zpools_rx.clone(),
+            base_log,
+        );
+
         // Spawn the task that serializes dataset operations.
         let dataset_task = DatasetTaskHandle::spawn_dataset_task(
             Arc::clone(&mount_config),
+            key_requester.clone(),
             base_log,
         );
 
@@ -160,6 +175,7 @@ impl ConfigReconcilerHandle {
                 ledger_task: OnceLock::new(),
                 reconciler_result_rx,
                 currently_managed_zpools_rx,
+                external_disks_rx,
             },
             // Stash the dependencies the reconciler task will need in
             // `spawn_reconciliation_task()` inside this token that the caller
@@ -319,6 +335,27 @@ impl ConfigReconcilerHandle {
         self.dataset_task.nested_dataset_list(dataset, options).await
     }
 
+    /// Rotate the encryption keys of all managed U.2s to the key for
+    /// `new_epoch`.
+    ///
+    /// Returns the result for each U.2, keyed by its zpool.
+    pub async fn rotate_u2_encryption_keys(
+        &self,
+        new_epoch: u64,
+    ) -> Result<
+        BTreeMap<ZpoolName, Result<(), EncryptionKeyRotationError>>,
+        DatasetTaskError,
+    > {
+        let disks = self
+            .external_disks_rx
+            .borrow()
+            .iter()
+            .filter(|disk| disk.variant() == DiskVariant::U2)
+            .map(|disk| (*disk.zpool_name(), disk.identity().clone()))
+            .collect();
+        self.dataset_task.encryption_keys_rotate(disks, new_epoch).await
+    }
+
     /// Write a new sled config to the ledger.
     pub async fn set_sled_config(
         &self,
@@ -381,7 +418,7 @@ impl ConfigReconcilerHandle {
 
         let datasets = self
             .dataset_task
-            .inventory(zpools.iter().map(|&(name, _)| name).collect())
+            .inventory(zpools.iter().map(|(name, _)| *name).collect())
             .await??;
 
         let (reconciler_status, last_reconciliation) =
@@ -389,13 +426,7 @@ impl ConfigReconcilerHandle {
 
         Ok(ReconcilerInventory {
             disks: self.raw_disks_tx.to_inventory(),
-            zpools: zpools
-                .into_iter()
-                .map(|(name, total_size)| InventoryZpool {
-                    id: name.id(),
-                    total_size,
-                })
-                .collect(),
+            zpools: zpools.into_iter().map(|(_, zpool)| zpool).collect(),
             datasets,
             ledgered_sled_config,
             reconciler_status,
@@ -29,6 +29,7 @@ use sled_storage::dataset::CONFIG_DATASET;
 use sled_storage::dataset::INSTALL_DATASET;
 use sled_storage::dataset::M2_ARTIFACT_DATASET;
 use sled_storage::dataset::M2_DEBUG_DATASET;
+use sled_storage::dataset::M2_ZONE_IMAGE_CACHE_DATASET;
 use sled_storage::disk::Disk;
 use sled_storage::disk::DiskError;
 use sled_storage::disk::RawDisk;
@@ -373,6 +374,14 @@ impl InternalDisks {
         self.all_datasets(M2_ARTIFACT_DATASET)
     }
 
+    /// Returns all `M2_ZONE_IMAGE_CACHE_DATASET` paths within available M.2
+    /// disks.
+    pub fn all_zone_image_cache_datasets(
+        &self,
+    ) -> impl ExactSizeIterator<Item = Utf8PathBuf> + '_ {
+        self.all_datasets(M2_ZONE_IMAGE_CACHE_DATASET)
+    }
+
     /// Return the directories for storing zone service bundles.
     pub fn all_zone_bundle_directories(
         &self,
@@ -44,6 +44,9 @@
 //!   it are available via methods like
 //!   [`ConfigReconcilerHandle::timesync_status()`] and
 //!   [`ConfigReconcilerHandle::inventory()`].
+//! * A task that scrubs internal and managed external zpools on a rotating
+//!   schedule (in the `zpool_scrub_task` module of this crate), as directed by
+//!   the [`ZpoolScrubPolicy`] passed to [`ConfigReconcilerHandle::new()`].
 
 mod dataset_serialization_task;
 mod disks_common;
@@ -57,8 +60,10 @@ mod mupdate_override;
 mod raw_disks;
 mod reconciler_task;
 mod sled_agent_facilities;
+mod zpool_scrub_task;
 
 pub use dataset_serialization_task::DatasetTaskError;
+pub use dataset_serialization_task::EncryptionKeyRotationError;
 pub use dataset_serialization_task::NestedDatasetDestroyError;
 pub use dataset_serialization_task::NestedDatasetEnsureError;
 pub use dataset_serialization_task::NestedDatasetListError;
@@ -84,6 +89,7 @@ pub use reconciler_task::TimeSyncError;
 pub use reconciler_task::TimeSyncStatus;
 pub use sled_agent_facilities::SledAgentArtifactStore;
 pub use sled_agent_facilities::SledAgentFacilities;
+pub use zpool_scrub_task::ZpoolScrubPolicy;
 
 #[cfg(any(test, feature = "testing"))]
 pub use internal_disks::InternalDiskDetails;
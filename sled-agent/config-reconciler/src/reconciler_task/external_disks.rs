@@ -14,6 +14,9 @@ use illumos_utils::zpool::Zpool;
 use illumos_utils::zpool::ZpoolName;
 use key_manager::StorageKeyRequester;
 use nexus_sled_agent_shared::inventory::ConfigReconcilerInventoryResult;
+use nexus_sled_agent_shared::inventory::InventoryZpool;
+use nexus_sled_agent_shared::inventory::InventoryZpoolHealth;
+use nexus_sled_agent_shared::inventory::InventoryZpoolScrub;
 use omicron_common::api::external::ByteCount;
 use omicron_common::disk::DiskManagementError;
 use omicron_common::disk::DiskVariant;
@@ -165,53 +168,105 @@ impl CurrentlyManagedZpoolsReceiver {
         }
     }
 
-    // This returns a tuple that can be converted into an `InventoryZpool`. It
-    // doesn't return an `InventoryZpool` directly because the latter only
-    // contains the zpool's ID, not the full name, and our caller wants the
+    // This returns each zpool's full name alongside its `InventoryZpool`,
+    // because the latter only contains the zpool's ID and our caller wants the
     // names too.
     pub(crate) async fn to_inventory(
         &self,
         log: &Logger,
-    ) -> Vec<(ZpoolName, ByteCount)> {
+    ) -> Vec<(ZpoolName, InventoryZpool)> {
         let current_zpools = self.current();
 
         let zpool_futs =
             current_zpools.0.iter().map(|&zpool_name| async move {
-                let info_result =
-                    Zpool::get_info(&zpool_name.to_string()).await;
+                let (info_result, scrub_result, health_result) = future::join3(
+                    Zpool::get_info(&zpool_name.to_string()),
+                    Zpool::scrub_status(&zpool_name),
+                    Zpool::health_status(&zpool_name),
+                )
+                .await;
 
-                (zpool_name, info_result)
+                (zpool_name, info_result, scrub_result, health_result)
             });
 
         future::join_all(zpool_futs)
             .await
             .into_iter()
-            .filter_map(|(zpool_name, info_result)| {
-                let info = match info_result {
-                    Ok(info) => info,
-                    Err(err) => {
-                        warn!(
-                            log, "Failed to access zpool info";
-                            "zpool" => %zpool_name,
-                            InlineErrorChain::new(&err),
-                        );
-                        return None;
-                    }
-                };
-                let total_size = match ByteCount::try_from(info.size()) {
-                    Ok(n) => n,
-                    Err(err) => {
-                        warn!(
-                            log, "Failed to parse zpool size";
-                            "zpool" => %zpool_name,
-                            "raw_size" => info.size(),
-                            InlineErrorChain::new(&err),
-                        );
-                        return None;
-                    }
-                };
-                Some((zpool_name, total_size))
-            })
+            .filter_map(
+                |(zpool_name, info_result, scrub_result, health_result)| {
+                    let info = match info_result {
+                        Ok(info) => info,
+                        Err(err) => {
+                            warn!(
+                                log, "Failed to access zpool info";
+                                "zpool" => %zpool_name,
+                                InlineErrorChain::new(&err),
+                            );
+                            return None;
+                        }
+                    };
+                    let total_size = match ByteCount::try_from(info.size()) {
+                        Ok(n) => n,
+                        Err(err) => {
+                            warn!(
+                                log, "Failed to parse zpool size";
+                                "zpool" => %zpool_name,
+                                "raw_size" => info.size(),
+                                InlineErrorChain::new(&err),
+                            );
+                            return None;
+                        }
+                    };
+                    // Failing to convert the allocated space isn't fatal; we
+                    // still want to report the zpool itself.
+                    let allocated = match ByteCount::try_from(info.allocated())
+                    {
+                        Ok(n) => Some(n),
+                        Err(err) => {
+                            warn!(
+                                log, "Failed to parse zpool allocated space";
+                                "zpool" => %zpool_name,
+                                "raw_allocated" => info.allocated(),
+                                InlineErrorChain::new(&err),
+                            );
+                            None
+                        }
+                    };
+                    // Likewise, failing to determine the scrub status or health
+                    // isn't fatal.
+                    let scrub = match scrub_result {
+                        Ok(scrub) => scrub.map(InventoryZpoolScrub::from),
+                        Err(err) => {
+                            warn!(
+                                log, "Failed to get zpool scrub status";
+                                "zpool" => %zpool_name,
+                                InlineErrorChain::new(&err),
+                            );
+                            None
+                        }
+                    };
+                    let health = match health_result {
+                        Ok(health) => Some(InventoryZpoolHealth::from(health)),
+                        Err(err) => {
+                            warn!(
+                                log, "Failed to get zpool health";
+                                "zpool" => %zpool_name,
+                                InlineErrorChain::new(&err),
+                            );
+                            None
+                        }
+                    };
+                    let zpool = InventoryZpool {
+                        id: zpool_name.id(),
+                        total_size,
+                        allocated,
+                        fragmentation_percent: info.fragmentation(),
+                        scrub,
+                        health,
+                    };
+                    Some((zpool_name, zpool))
+                },
+            )
             .collect()
     }
 }
@@ -0,0 +1,414 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Long-running tokio task responsible for scrubbing this sled's zpools on a
+//! rotating schedule.
+//!
+//! Each zpool is scrubbed once per [`ZpoolScrubPolicy::interval_days`],
+//! measured from the end of its previous scrub. Zpools that have never been
+//! scrubbed are first scrubbed at an offset into the interval derived from
+//! their ID, so that the zpools of a sled (and of a rack) don't all come due
+//! at once. At most [`ZpoolScrubPolicy::max_concurrent`] scrubs run on the
+//! sled at a time; scrubs beyond that limit (e.g., started by an operator) are
+//! paused, and paused scrubs are resumed ahead of starting new ones.
+//!
+//! Scrub results are reported through inventory, from which Nexus raises
+//! alerts for scrubs that found errors.
+
+use crate::InternalDisksReceiver;
+use crate::reconciler_task::CurrentlyManagedZpoolsReceiver;
+use chrono::DateTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use futures::future;
+use illumos_utils::zpool::Zpool;
+use illumos_utils::zpool::ZpoolName;
+use illumos_utils::zpool::ZpoolScrubState;
+use illumos_utils::zpool::ZpoolScrubStatus;
+use omicron_uuid_kinds::GenericUuid;
+use serde::Deserialize;
+use serde::Serialize;
+use slog::Logger;
+use slog::info;
+use slog::warn;
+use slog_error_chain::InlineErrorChain;
+use std::time::Duration;
+
+/// Policy controlling how often this sled's zpools are scrubbed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ZpoolScrubPolicy {
+    /// Whether to scrub zpools at all
+    pub enabled: bool,
+    /// How long (in days) after the end of one scrub of a zpool the next
+    /// scrub is due
+    pub interval_days: u32,
+    /// The most scrubs to run on the sled at once
+    pub max_concurrent: usize,
+    /// How often (in seconds) to check whether scrubs are due
+    pub check_period_secs: u64,
+}
+
+impl Default for ZpoolScrubPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_days: 30,
+            max_concurrent: 1,
+            check_period_secs: 3600,
+        }
+    }
+}
+
+impl ZpoolScrubPolicy {
+    fn interval(&self) -> TimeDelta {
+        TimeDelta::days(i64::from(self.interval_days))
+    }
+
+    /// Returns when `zpool`, whose most recent scrub is described by
+    /// `status`, is next due to be scrubbed.
+    ///
+    /// `epoch` is the start of the schedule for zpools that have never been
+    /// scrubbed (or whose most recent scrub doesn't say when it ended).
+    fn due_time(
+        &self,
+        zpool: &ZpoolName,
+        status: Option<&ZpoolScrubStatus>,
+        epoch: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        match status.and_then(|s| s.end_time) {
+            Some(end_time) => end_time + self.interval(),
+            None => {
+                let interval_secs =
+                    u128::from(self.interval_days.max(1)) * 86400;
+                let offset_secs =
+                    zpool.id().as_untyped_uuid().as_u128() % interval_secs;
+                // The offset is less than the interval, which fits in an i64.
+                epoch + TimeDelta::seconds(offset_secs as i64)
+            }
+        }
+    }
+
+    /// Decides which scrubs to start, resume, or pause, given the current
+    /// scrub status of each of the sled's zpools.
+    fn plan(
+        &self,
+        zpools: &[(ZpoolName, Option<ZpoolScrubStatus>)],
+        epoch: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> ScrubPlan {
+        let mut plan = ScrubPlan::default();
+
+        // Keep the scrubs that have made the most progress running, and pause
+        // the rest.
+        let mut running: Vec<_> = zpools
+            .iter()
+            .filter_map(|(zpool, status)| {
+                let status = status.as_ref()?;
+                (status.state == ZpoolScrubState::InProgress)
+                    .then(|| (*zpool, status.percent_done.unwrap_or(0)))
+            })
+            .collect();
+        running.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        if running.len() > self.max_concurrent {
+            plan.pause =
+                running.drain(self.max_concurrent..).map(|(z, _)| z).collect();
+        }
+
+        // Paused scrubs are resumed (oldest first) before any new scrubs are
+        // started, so that they get to finish.
+        let mut paused: Vec<_> = zpools
+            .iter()
+            .filter(|(zpool, status)| {
+                status.as_ref().is_some_and(|status| {
+                    status.state == ZpoolScrubState::Paused
+                }) && !plan.pause.contains(zpool)
+            })
+            .map(|(zpool, status)| {
+                (status.as_ref().and_then(|s| s.start_time), *zpool)
+            })
+            .collect();
+        paused.sort();
+
+        let mut due: Vec<_> = zpools
+            .iter()
+            .filter(|(_, status)| {
+                !status.as_ref().is_some_and(|status| {
+                    matches!(
+                        status.state,
+                        ZpoolScrubState::InProgress | ZpoolScrubState::Paused
+                    )
+                })
+            })
+            .filter_map(|(zpool, status)| {
+                let due_time = self.due_time(zpool, status.as_ref(), epoch);
+                (due_time <= now).then_some((due_time, *zpool))
+            })
+            .collect();
+        due.sort();
+
+        let slots = self.max_concurrent.saturating_sub(running.len());
+        let mut candidates = paused
+            .into_iter()
+            .map(|(_, zpool)| zpool)
+            .chain(due.into_iter().map(|(_, zpool)| zpool));
+        plan.start = candidates.by_ref().take(slots).collect();
+        plan.waiting = candidates.count();
+        plan
+    }
+}
+
+/// Actions to take on this sled's zpools
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ScrubPlan {
+    /// zpools on which to start (or resume) a scrub
+    start: Vec<ZpoolName>,
+    /// zpools whose scrub should be paused to stay within the concurrency
+    /// limit
+    pause: Vec<ZpoolName>,
+    /// number of zpools that are due for a scrub but have to wait for others
+    /// to finish
+    waiting: usize,
+}
+
+pub(crate) fn spawn(
+    policy: ZpoolScrubPolicy,
+    internal_disks_rx: InternalDisksReceiver,
+    currently_managed_zpools_rx: CurrentlyManagedZpoolsReceiver,
+    base_log: &Logger,
+) {
+    let log = base_log.new(slog::o!("component" => "ZpoolScrubTask"));
+    if !policy.enabled {
+        info!(log, "zpool scrubs disabled by config");
+        return;
+    }
+    tokio::spawn(
+        ZpoolScrubTask {
+            policy,
+            internal_disks_rx,
+            currently_managed_zpools_rx,
+            epoch: Utc::now(),
+            log,
+        }
+        .run(),
+    );
+}
+
+struct ZpoolScrubTask {
+    policy: ZpoolScrubPolicy,
+
+    // Sources of the zpools to scrub: both internal and managed external
+    // zpools are scrubbed.
+    internal_disks_rx: InternalDisksReceiver,
+    currently_managed_zpools_rx: CurrentlyManagedZpoolsReceiver,
+
+    // When this task started, which is the start of the schedule for zpools
+    // that have never been scrubbed.
+    epoch: DateTime<Utc>,
+
+    log: Logger,
+}
+
+impl ZpoolScrubTask {
+    async fn run(self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            self.policy.check_period_secs.max(1),
+        ));
+        interval
+            .set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.check_scrubs().await;
+        }
+    }
+
+    async fn check_scrubs(&self) {
+        let internal_disks = self.internal_disks_rx.current();
+        let zpools: Vec<ZpoolName> = internal_disks
+            .boot_disk_zpool_id()
+            .into_iter()
+            .chain(internal_disks.non_boot_disk_zpool_ids())
+            .map(ZpoolName::Internal)
+            .chain(self.currently_managed_zpools_rx.current().iter())
+            .collect();
+
+        let statuses =
+            future::join_all(zpools.into_iter().map(|zpool| async move {
+                (zpool, Zpool::scrub_status(&zpool).await)
+            }))
+            .await;
+
+        // Leave zpools whose status we can't determine alone; they might be
+        // scrubbing, and we'd rather not exceed the concurrency limit.
+        let zpools: Vec<_> = statuses
+            .into_iter()
+            .filter_map(|(zpool, result)| match result {
+                Ok(status) => Some((zpool, status)),
+                Err(err) => {
+                    warn!(
+                        self.log, "Failed to get zpool scrub status";
+                        "zpool" => %zpool,
+                        InlineErrorChain::new(&err),
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        let plan = self.policy.plan(&zpools, self.epoch, Utc::now());
+        for zpool in &plan.pause {
+            info!(
+                self.log, "Pausing zpool scrub to stay within limit";
+                "zpool" => %zpool,
+                "max_concurrent" => self.policy.max_concurrent,
+            );
+            if let Err(err) = Zpool::pause_scrub(zpool).await {
+                warn!(
+                    self.log, "Failed to pause zpool scrub";
+                    "zpool" => %zpool,
+                    InlineErrorChain::new(&err),
+                );
+            }
+        }
+        for zpool in &plan.start {
+            info!(self.log, "Starting zpool scrub"; "zpool" => %zpool);
+            if let Err(err) = Zpool::start_scrub(zpool).await {
+                warn!(
+                    self.log, "Failed to start zpool scrub";
+                    "zpool" => %zpool,
+                    InlineErrorChain::new(&err),
+                );
+            }
+        }
+        if plan.waiting > 0 {
+            info!(
+                self.log, "Zpools waiting for a scrub";
+                "waiting" => plan.waiting,
+                "max_concurrent" => self.policy.max_concurrent,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use omicron_uuid_kinds::ExternalZpoolUuid;
+
+    const POLICY: ZpoolScrubPolicy = ZpoolScrubPolicy {
+        enabled: true,
+        interval_days: 30,
+        max_concurrent: 1,
+        check_period_secs: 3600,
+    };
+
+    fn day(n: i64) -> DateTime<Utc> {
+        DateTime::UNIX_EPOCH + TimeDelta::days(n)
+    }
+
+    fn status(
+        state: ZpoolScrubState,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        percent_done: Option<u8>,
+    ) -> Option<ZpoolScrubStatus> {
+        Some(ZpoolScrubStatus {
+            state,
+            start_time,
+            end_time,
+            percent_done,
+            errors: None,
+        })
+    }
+
+    fn finished(end_day: i64) -> Option<ZpoolScrubStatus> {
+        status(ZpoolScrubState::Finished, None, Some(day(end_day)), None)
+    }
+
+    fn zpools(n: usize) -> Vec<ZpoolName> {
+        let mut zpools: Vec<_> = (0..n)
+            .map(|_| ZpoolName::External(ExternalZpoolUuid::new_v4()))
+            .collect();
+        zpools.sort();
+        zpools
+    }
+
+    #[test]
+    fn test_due_time_staggered() {
+        // Zpools that have never been scrubbed are spread over the interval.
+        let epoch = day(100);
+        for zpool in zpools(32) {
+            let due = POLICY.due_time(&zpool, None, epoch);
+            assert!(due >= epoch && due < epoch + POLICY.interval());
+            // The offset is stable.
+            assert_eq!(due, POLICY.due_time(&zpool, None, epoch));
+        }
+
+        // Zpools that have been scrubbed are due an interval later.
+        let zpool = zpools(1)[0];
+        assert_eq!(
+            POLICY.due_time(&zpool, finished(10).as_ref(), epoch),
+            day(40)
+        );
+    }
+
+    #[test]
+    fn test_plan_rotates_by_due_time() {
+        let z = zpools(3);
+        let pools = vec![
+            (z[0], finished(5)),
+            (z[1], finished(1)),
+            (z[2], finished(20)),
+        ];
+
+        // Nothing is due until 30 days after the oldest scrub.
+        assert_eq!(POLICY.plan(&pools, day(0), day(30)), ScrubPlan::default());
+
+        // The most overdue zpool goes first, and the rest wait.
+        assert_eq!(
+            POLICY.plan(&pools, day(0), day(40)),
+            ScrubPlan { start: vec![z[1]], pause: vec![], waiting: 1 }
+        );
+
+        // With more concurrency, both due zpools start.
+        let policy = ZpoolScrubPolicy { max_concurrent: 4, ..POLICY };
+        assert_eq!(
+            policy.plan(&pools, day(0), day(40)),
+            ScrubPlan { start: vec![z[1], z[0]], pause: vec![], waiting: 0 }
+        );
+    }
+
+    #[test]
+    fn test_plan_concurrency_limit() {
+        let z = zpools(4);
+        let running = |pct| {
+            status(ZpoolScrubState::InProgress, Some(day(50)), None, Some(pct))
+        };
+        let pools = vec![
+            (z[0], running(10)),
+            (z[1], running(80)),
+            (z[2], status(ZpoolScrubState::Paused, Some(day(40)), None, None)),
+            (z[3], None),
+        ];
+
+        // Over the limit: the scrub that's furthest along keeps running, and
+        // nothing else starts.
+        assert_eq!(
+            POLICY.plan(&pools, day(0), day(60)),
+            ScrubPlan { start: vec![], pause: vec![z[0]], waiting: 2 }
+        );
+
+        // Paused scrubs are resumed before new ones are started.
+        let policy = ZpoolScrubPolicy { max_concurrent: 3, ..POLICY };
+        assert_eq!(
+            policy.plan(&pools, day(0), day(60)),
+            ScrubPlan { start: vec![z[2]], pause: vec![], waiting: 1 }
+        );
+        let policy = ZpoolScrubPolicy { max_concurrent: 4, ..POLICY };
+        assert_eq!(
+            policy.plan(&pools, day(0), day(60)),
+            ScrubPlan { start: vec![z[2], z[3]], pause: vec![], waiting: 0 }
+        );
+    }
+}
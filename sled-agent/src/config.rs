@@ -14,6 +14,7 @@ use illumos_utils::dladm::FindPhysicalLinkError;
 use illumos_utils::dladm::PhysicalLink;
 use omicron_common::vlan::VlanID;
 use serde::Deserialize;
+use sled_agent_config_reconciler::ZpoolScrubPolicy;
 use sled_hardware::UnparsedDisk;
 use sled_hardware::is_gimlet;
 use sprockets_tls::keys::SprocketsConfig;
@@ -105,6 +106,10 @@ pub struct Config {
     #[serde(default)]
     pub updates: ConfigUpdates,
 
+    /// Policy for scrubbing this sled's zpools
+    #[serde(default)]
+    pub zpool_scrub: ZpoolScrubPolicy,
+
     /// When running on a scrimlet, tfportd in the switch zone will create links
     /// when it boots, and maghemite in the switch zone is configured to use
     /// those in transit mode in order to transit prefix announcements to sleds.
@@ -17,7 +17,8 @@ use dropshot::{
     Query, RequestContext, StreamingBody, TypedBody,
 };
 use nexus_sled_agent_shared::inventory::{
-    Inventory, OmicronSledConfig, SledRole,
+    Inventory, InventoryChanges, InventoryKnownDigests, OmicronSledConfig,
+    SledRole, v1, v4, v6,
 };
 use omicron_common::api::external::Error;
 use omicron_common::api::internal::nexus::{DiskRuntimeState, SledVmmState};
@@ -471,7 +472,7 @@ impl SledAgentApi for SledAgentImpl {
         sa.zones_list().await.map(HttpResponseOk).map_err(HttpError::from)
     }
 
-    async fn omicron_config_put(
+    async fn omicron_config_put_v1(
         rqctx: RequestContext<Self::Context>,
         body: TypedBody<OmicronSledConfig>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
@@ -481,6 +482,28 @@ impl SledAgentApi for SledAgentImpl {
         Ok(HttpResponseUpdatedNoContent())
     }
 
+    async fn omicron_config_put(
+        rqctx: RequestContext<Self::Context>,
+        headers: Header<IdempotencyKeyHeaders>,
+        body: TypedBody<OmicronSledConfig>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let sa = rqctx.context();
+        let key = headers.into_inner().idempotency_key;
+        let body_args = body.into_inner();
+        sa.idempotency_cache()
+            .run(
+                key.as_deref(),
+                "omicron_config_put",
+                body_args,
+                move |config| async move {
+                    sa.set_omicron_config(config).await??;
+                    Ok::<_, HttpError>(())
+                },
+            )
+            .await??;
+        Ok(HttpResponseUpdatedNoContent())
+    }
+
     async fn sled_role_get(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<SledRole>, HttpError> {
@@ -488,7 +511,7 @@ impl SledAgentApi for SledAgentImpl {
         Ok(HttpResponseOk(sa.get_role()))
     }
 
-    async fn vmm_register(
+    async fn vmm_register_v1(
         rqctx: RequestContext<Self::Context>,
         path_params: Path<VmmPathParam>,
         body: TypedBody<InstanceEnsureBody>,
@@ -501,6 +524,30 @@ impl SledAgentApi for SledAgentImpl {
         ))
     }
 
+    async fn vmm_register(
+        rqctx: RequestContext<Self::Context>,
+        headers: Header<IdempotencyKeyHeaders>,
+        path_params: Path<VmmPathParam>,
+        body: TypedBody<InstanceEnsureBody>,
+    ) -> Result<HttpResponseOk<SledVmmState>, HttpError> {
+        let sa = rqctx.context();
+        let propolis_id = path_params.into_inner().propolis_id;
+        let key = headers.into_inner().idempotency_key;
+        let body_args = body.into_inner();
+        let state = sa
+            .idempotency_cache()
+            .run(
+                key.as_deref(),
+                "vmm_register",
+                (propolis_id, body_args),
+                move |(propolis_id, instance)| async move {
+                    sa.instance_ensure_registered(propolis_id, instance).await
+                },
+            )
+            .await??;
+        Ok(HttpResponseOk(state))
+    }
+
     async fn vmm_unregister(
         rqctx: RequestContext<Self::Context>,
         path_params: Path<VmmPathParam>,
@@ -805,6 +852,30 @@ impl SledAgentApi for SledAgentImpl {
         Ok(HttpResponseUpdatedNoContent())
     }
 
+    async fn inventory_v1(
+        request_context: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v1::Inventory>, HttpError> {
+        let HttpResponseOk(inventory) =
+            Self::inventory(request_context).await?;
+        Ok(HttpResponseOk(inventory.into()))
+    }
+
+    async fn inventory_v4(
+        request_context: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v4::Inventory>, HttpError> {
+        let HttpResponseOk(inventory) =
+            Self::inventory(request_context).await?;
+        Ok(HttpResponseOk(inventory.into()))
+    }
+
+    async fn inventory_v6(
+        request_context: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v6::Inventory>, HttpError> {
+        let HttpResponseOk(inventory) =
+            Self::inventory(request_context).await?;
+        Ok(HttpResponseOk(inventory.into()))
+    }
+
     async fn inventory(
         request_context: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<Inventory>, HttpError> {
@@ -812,6 +883,15 @@ impl SledAgentApi for SledAgentImpl {
         Ok(HttpResponseOk(sa.inventory().await?))
     }
 
+    async fn inventory_changes(
+        request_context: RequestContext<Self::Context>,
+        query_params: Query<InventoryKnownDigests>,
+    ) -> Result<HttpResponseOk<InventoryChanges>, HttpError> {
+        let HttpResponseOk(inventory) =
+            Self::inventory(request_context).await?;
+        Ok(HttpResponseOk(inventory.changes_since(&query_params.into_inner())))
+    }
+
     async fn sled_identifiers(
         request_context: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<SledIdentifiers>, HttpError> {
@@ -960,6 +1040,20 @@ impl SledAgentApi for SledAgentImpl {
         Ok(HttpResponseOk(res.get_output()))
     }
 
+    async fn support_kstat_info(
+        request_context: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<Vec<SledDiagnosticsQueryOutput>>, HttpError>
+    {
+        let sa = request_context.context();
+        Ok(HttpResponseOk(
+            sa.support_kstat_info()
+                .await
+                .into_iter()
+                .map(|cmd| cmd.get_output())
+                .collect::<Vec<_>>(),
+        ))
+    }
+
     async fn support_health_check(
         request_context: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<Vec<SledDiagnosticsQueryOutput>>, HttpError>
@@ -0,0 +1,396 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Deduplication of retried requests that carry an idempotency key
+//!
+//! Nexus attaches an idempotency key to requests that ensure zones, datasets,
+//! or VMMs. If such a request times out, Nexus can't tell whether the sled
+//! agent applied it, so it retries with the same key. The [`IdempotencyCache`]
+//! remembers the response to each keyed request that completed successfully,
+//! and replays it for retries instead of applying the request again.
+//!
+//! The cache is bounded: only the most recent [`MAX_RECORDS`] keys are
+//! remembered. It's persisted to the M.2 config datasets so that it survives a
+//! sled agent restart, which is exactly when a client is most likely to
+//! retry.
+
+use camino::Utf8PathBuf;
+use dropshot::ClientErrorStatusCode;
+use dropshot::HttpError;
+use omicron_common::api::external::Generation;
+use omicron_common::ledger::Ledger;
+use omicron_common::ledger::Ledgerable;
+use serde::Deserialize;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use slog::Logger;
+use slog::warn;
+use slog_error_chain::InlineErrorChain;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::future::Future;
+use tokio::sync::Mutex;
+
+/// The file name of the ledger within each config dataset.
+pub(crate) const IDEMPOTENCY_LEDGER_FILENAME: &str = "idempotency-keys.json";
+
+/// The number of completed requests remembered by the cache.
+///
+/// Retries follow their original request closely, so this only needs to be
+/// large enough to cover the requests a sled sees in a few minutes.
+const MAX_RECORDS: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdempotencyError {
+    #[error(
+        "idempotency key {key:?} was already used for a different \
+         {operation} request"
+    )]
+    KeyReused { key: String, operation: &'static str },
+
+    #[error("a request with idempotency key {key:?} is already in progress")]
+    InProgress { key: String },
+
+    #[error("failed to serialize {what} for idempotency key {key:?}")]
+    Serialize {
+        key: String,
+        what: &'static str,
+        #[source]
+        err: serde_json::Error,
+    },
+
+    #[error("failed to replay response for idempotency key {key:?}")]
+    Replay {
+        key: String,
+        #[source]
+        err: serde_json::Error,
+    },
+}
+
+impl From<IdempotencyError> for HttpError {
+    fn from(err: IdempotencyError) -> Self {
+        let message = InlineErrorChain::new(&err).to_string();
+        match err {
+            IdempotencyError::KeyReused { .. }
+            | IdempotencyError::InProgress { .. } => {
+                HttpError::for_client_error(
+                    None,
+                    ClientErrorStatusCode::CONFLICT,
+                    message,
+                )
+            }
+            IdempotencyError::Serialize { .. }
+            | IdempotencyError::Replay { .. } => {
+                HttpError::for_internal_error(message)
+            }
+        }
+    }
+}
+
+/// One successfully completed request.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct IdempotencyRecord {
+    key: String,
+    operation: String,
+    /// SHA-256 digest of the serialized request, used to detect a key being
+    /// reused for a different request.
+    request_digest: String,
+    /// The serialized response, replayed for retries.
+    response: serde_json::Value,
+}
+
+/// The persisted contents of the cache, oldest record first.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct IdempotencyRecords {
+    generation: Generation,
+    records: VecDeque<IdempotencyRecord>,
+}
+
+impl Ledgerable for IdempotencyRecords {
+    fn is_newer_than(&self, other: &Self) -> bool {
+        self.generation > other.generation
+    }
+
+    fn generation_bump(&mut self) {
+        self.generation = self.generation.next();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    records: IdempotencyRecords,
+    in_progress: BTreeSet<String>,
+}
+
+/// Remembers the responses to recent requests that carried an idempotency key.
+///
+/// See the module-level documentation for details.
+#[derive(Debug)]
+pub struct IdempotencyCache {
+    log: Logger,
+    ledger_paths: Vec<Utf8PathBuf>,
+    inner: Mutex<Inner>,
+}
+
+impl IdempotencyCache {
+    /// Loads the cache from the ledger at `ledger_paths`, starting with an
+    /// empty cache if there isn't one.
+    ///
+    /// If `ledger_paths` is empty, the cache is only kept in memory.
+    pub async fn load(log: &Logger, ledger_paths: Vec<Utf8PathBuf>) -> Self {
+        let log = log.new(slog::o!("component" => "IdempotencyCache"));
+        let records = if ledger_paths.is_empty() {
+            IdempotencyRecords::default()
+        } else {
+            Ledger::<IdempotencyRecords>::new(&log, ledger_paths.clone())
+                .await
+                .map(Ledger::into_inner)
+                .unwrap_or_default()
+        };
+        Self {
+            log,
+            ledger_paths,
+            inner: Mutex::new(Inner { records, in_progress: BTreeSet::new() }),
+        }
+    }
+
+    /// Applies `request` (a request to perform `operation`) by calling
+    /// `apply`, unless a request with the same idempotency key has already
+    /// completed successfully, in which case its response is returned instead.
+    ///
+    /// Requests without a key are always applied. Only successful responses
+    /// are remembered, so a request that failed can be retried with the same
+    /// key.
+    pub async fn run<Req, T, E, F, Fut>(
+        &self,
+        key: Option<&str>,
+        operation: &'static str,
+        request: Req,
+        apply: F,
+    ) -> Result<Result<T, E>, IdempotencyError>
+    where
+        Req: Serialize,
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(Req) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let Some(key) = key else {
+            return Ok(apply(request).await);
+        };
+
+        let request_digest = serde_json::to_vec(&request)
+            .map(|bytes| hex::encode(Sha256::digest(&bytes)))
+            .map_err(|err| IdempotencyError::Serialize {
+                key: key.to_owned(),
+                what: "request",
+                err,
+            })?;
+
+        {
+            let mut inner = self.inner.lock().await;
+            if let Some(record) =
+                inner.records.records.iter().find(|r| r.key == key)
+            {
+                if record.operation != operation
+                    || record.request_digest != request_digest
+                {
+                    return Err(IdempotencyError::KeyReused {
+                        key: key.to_owned(),
+                        operation,
+                    });
+                }
+                let response = serde_json::from_value(record.response.clone())
+                    .map_err(|err| IdempotencyError::Replay {
+                        key: key.to_owned(),
+                        err,
+                    })?;
+                return Ok(Ok(response));
+            }
+            if !inner.in_progress.insert(key.to_owned()) {
+                return Err(IdempotencyError::InProgress {
+                    key: key.to_owned(),
+                });
+            }
+        }
+
+        let result = apply(request).await;
+
+        let mut inner = self.inner.lock().await;
+        inner.in_progress.remove(key);
+        let Ok(response) = &result else {
+            return Ok(result);
+        };
+        let response = serde_json::to_value(response).map_err(|err| {
+            IdempotencyError::Serialize {
+                key: key.to_owned(),
+                what: "response",
+                err,
+            }
+        })?;
+        let records = &mut inner.records.records;
+        records.push_back(IdempotencyRecord {
+            key: key.to_owned(),
+            operation: operation.to_owned(),
+            request_digest,
+            response,
+        });
+        while records.len() > MAX_RECORDS {
+            records.pop_front();
+        }
+        self.persist(&mut inner.records).await;
+
+        Ok(result)
+    }
+
+    async fn persist(&self, records: &mut IdempotencyRecords) {
+        if self.ledger_paths.is_empty() {
+            return;
+        }
+
+        // The request has already been applied, so failing to persist its
+        // record only means that a retry after a sled agent restart would be
+        // applied again.  Don't fail the request over it.
+        let mut ledger = Ledger::new_with(
+            &self.log,
+            self.ledger_paths.clone(),
+            records.clone(),
+        );
+        match ledger.commit().await {
+            Ok(()) => *records = ledger.into_inner(),
+            Err(err) => warn!(
+                self.log,
+                "failed to persist idempotency keys";
+                InlineErrorChain::new(&err),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use omicron_test_utils::dev::test_setup_log;
+    use std::cell::Cell;
+
+    async fn apply_counted(
+        count: &Cell<usize>,
+        response: u32,
+    ) -> Result<u32, &'static str> {
+        count.set(count.get() + 1);
+        Ok(response)
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_cache_replays_responses() {
+        let logctx = test_setup_log("test_idempotency_cache_replays_responses");
+        let cache = IdempotencyCache::load(&logctx.log, Vec::new()).await;
+        let count = Cell::new(0);
+
+        // Requests without a key are always applied.
+        for _ in 0..2 {
+            let rv = cache
+                .run(None, "op", "request", |_| apply_counted(&count, 1))
+                .await
+                .expect("no idempotency error");
+            assert_eq!(rv, Ok(1));
+        }
+        assert_eq!(count.get(), 2);
+
+        // A retry with the same key gets the original response without the
+        // request being applied again.
+        for _ in 0..2 {
+            let rv = cache
+                .run(Some("key1"), "op", "request", |_| {
+                    apply_counted(&count, 3)
+                })
+                .await
+                .expect("no idempotency error");
+            assert_eq!(rv, Ok(3));
+        }
+        assert_eq!(count.get(), 3);
+
+        // Reusing the key for a different request or operation is an error.
+        let err = cache
+            .run(Some("key1"), "op", "other request", |_| {
+                apply_counted(&count, 4)
+            })
+            .await
+            .expect_err("key reused");
+        assert!(matches!(err, IdempotencyError::KeyReused { .. }), "{err}");
+        let err = cache
+            .run(Some("key1"), "other op", "request", |_| {
+                apply_counted(&count, 4)
+            })
+            .await
+            .expect_err("key reused");
+        assert!(matches!(err, IdempotencyError::KeyReused { .. }), "{err}");
+        assert_eq!(count.get(), 3);
+
+        // Failed requests aren't remembered.
+        let rv = cache
+            .run(Some("key2"), "op", "request", |_| async {
+                Err::<u32, _>("failed")
+            })
+            .await
+            .expect("no idempotency error");
+        assert_eq!(rv, Err("failed"));
+        let rv = cache
+            .run(Some("key2"), "op", "request", |_| apply_counted(&count, 5))
+            .await
+            .expect("no idempotency error");
+        assert_eq!(rv, Ok(5));
+        assert_eq!(count.get(), 4);
+
+        // Only the most recent keys are remembered.
+        for i in 0..MAX_RECORDS {
+            let key = format!("filler{i}");
+            cache
+                .run(Some(&key), "op", "request", |_| apply_counted(&count, 6))
+                .await
+                .expect("no idempotency error")
+                .expect("request succeeded");
+        }
+        let rv = cache
+            .run(Some("key1"), "op", "other request", |_| {
+                apply_counted(&count, 7)
+            })
+            .await
+            .expect("no idempotency error");
+        assert_eq!(rv, Ok(7));
+
+        logctx.cleanup_successful();
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_cache_persists() {
+        let logctx = test_setup_log("test_idempotency_cache_persists");
+        let tempdir = camino_tempfile::tempdir().unwrap();
+        let ledger_paths = vec![
+            tempdir.path().join(IDEMPOTENCY_LEDGER_FILENAME),
+            tempdir.path().join(format!("2-{IDEMPOTENCY_LEDGER_FILENAME}")),
+        ];
+        let count = Cell::new(0);
+
+        let cache =
+            IdempotencyCache::load(&logctx.log, ledger_paths.clone()).await;
+        let rv = cache
+            .run(Some("key"), "op", "request", |_| apply_counted(&count, 1))
+            .await
+            .expect("no idempotency error");
+        assert_eq!(rv, Ok(1));
+        drop(cache);
+
+        // A new cache (as after a sled agent restart) still knows the key.
+        let cache = IdempotencyCache::load(&logctx.log, ledger_paths).await;
+        let rv = cache
+            .run(Some("key"), "op", "request", |_| apply_counted(&count, 2))
+            .await
+            .expect("no idempotency error");
+        assert_eq!(rv, Ok(1));
+        assert_eq!(count.get(), 1);
+
+        logctx.cleanup_successful();
+    }
+}
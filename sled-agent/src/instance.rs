@@ -20,6 +20,7 @@ use illumos_utils::link::VnicAllocator;
 use illumos_utils::opte::{DhcpCfg, PortCreateParams, PortManager};
 use illumos_utils::running_zone::{RunningZone, ZoneBuilderFactory};
 use illumos_utils::zone::PROPOLIS_ZONE_PREFIX;
+use illumos_utils::zone::ZoneResourceCaps;
 use illumos_utils::zpool::ZpoolOrRamdisk;
 use omicron_common::api::internal::nexus::{SledVmmState, VmmRuntimeState};
 use omicron_common::api::internal::shared::{
@@ -53,6 +54,14 @@ use uuid::Uuid;
 // The depth of the request queue for the instance.
 const QUEUE_SIZE: usize = 32;
 
+// CPU time a Propolis zone may use beyond its guest's vCPUs, in CPUs, for
+// device emulation and Propolis's own housekeeping.
+const PROPOLIS_ZONE_CPU_OVERHEAD: f64 = 1.0;
+
+// Memory a Propolis zone may use beyond its guest's memory, for Propolis's own
+// heap, device buffers, and the other processes running in the zone.
+const PROPOLIS_ZONE_MEMORY_OVERHEAD_BYTES: u64 = 1 << 30;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Failed to wait for service: {0}")]
@@ -135,6 +144,9 @@ pub enum Error {
 
     #[error("Instance is terminating")]
     Terminating,
+
+    #[error("Failed to apply coalesced put-state request: {0}")]
+    CoalescedPutStateFailed(String),
 }
 
 type PropolisClientError =
@@ -195,6 +207,25 @@ pub fn propolis_zone_name(id: &PropolisUuid) -> String {
     format!("{}{}", PROPOLIS_ZONE_PREFIX, id)
 }
 
+/// Return the resource caps for the zone of a Propolis running a VM with the
+/// provided spec.
+///
+/// The caps leave room for the guest's vCPUs and memory, plus some overhead
+/// for Propolis itself. They exist to keep a runaway VMM from starving the
+/// rest of the sled, not to hold Propolis to a tight budget.
+fn propolis_zone_resource_caps(spec: &VmmSpec) -> ZoneResourceCaps {
+    let board = &spec.0.board;
+    ZoneResourceCaps {
+        ncpus: Some(f64::from(board.cpus) + PROPOLIS_ZONE_CPU_OVERHEAD),
+        memory_bytes: Some(
+            board
+                .memory_mb
+                .saturating_mul(1 << 20)
+                .saturating_add(PROPOLIS_ZONE_MEMORY_OVERHEAD_BYTES),
+        ),
+    }
+}
+
 // State associated with a running instance.
 struct RunningState {
     // Connection to Propolis.
@@ -286,6 +317,29 @@ impl InstanceRequest {
     }
 }
 
+/// A put-state request that has been queued to an instance's runner, but that
+/// the runner has not yet started to apply.
+///
+/// When Nexus's view of an instance flaps, it may send several requests to
+/// change the instance's state in quick succession. Rather than queuing each
+/// of these and driving Propolis through every intermediate state, requests
+/// that arrive while another is still queued are coalesced into it: the
+/// queued request's target is replaced with the newest one, and the callers
+/// of the superseded requests receive the outcome of applying the newest
+/// target.
+#[derive(Debug)]
+struct PendingPutState {
+    /// The most recently requested target state.
+    state: VmmStateRequested,
+    /// Callers whose requests were coalesced into the queued request, other
+    /// than the caller who sent it.
+    waiters: Vec<oneshot::Sender<Result<VmmPutStateResponse, ManagerError>>>,
+}
+
+/// The put-state request (if any) that is queued to an instance's runner,
+/// shared between the [`Instance`] handle and its [`InstanceRunner`].
+type PendingPutStateSlot = Arc<std::sync::Mutex<Option<PendingPutState>>>;
+
 /// Identifies the component that's responsible for updating a specific VMM's
 /// state.
 ///
@@ -492,6 +546,9 @@ struct InstanceRunner {
     // Request channel on which most instance requests are made.
     rx: mpsc::Receiver<InstanceRequest>,
 
+    // The queued put-state request into which newer requests are coalesced.
+    pending_put_state: PendingPutStateSlot,
+
     // Request channel on which monitor requests are made.
     tx_monitor: mpsc::Sender<InstanceMonitorMessage>,
     rx_monitor: mpsc::Receiver<InstanceMonitorMessage>,
@@ -668,6 +725,17 @@ impl InstanceRunner {
                                     .map_err(|_| Error::FailedSendClientClosed)
                             },
                             PutState { state, tx } => {
+                                let (state, waiters) =
+                                    self.take_pending_put_state(state);
+                                if !waiters.is_empty() {
+                                    info!(
+                                        self.log,
+                                        "coalesced put-state requests";
+                                        "state" => ?state,
+                                        "superseded" => waiters.len(),
+                                    );
+                                }
+
                                 // If we're going to stop the instance, start
                                 // the timeout after which we will forcefully
                                 // terminate the VMM.
@@ -683,7 +751,23 @@ impl InstanceRunner {
                                     }
                                 }
 
-                                tx.send(self.put_state(state).await
+                                let result = self.put_state(state).await;
+                                for waiter in waiters {
+                                    // Superseded callers may well have given
+                                    // up waiting; that's fine.
+                                    let _ = waiter.send(match &result {
+                                        Ok(r) => Ok(VmmPutStateResponse {
+                                            updated_runtime: Some(r.clone()),
+                                        }),
+                                        Err(e) => Err(
+                                            Error::CoalescedPutStateFailed(
+                                                e.to_string(),
+                                            )
+                                            .into(),
+                                        ),
+                                    });
+                                }
+                                tx.send(result
                                     .map(|r| VmmPutStateResponse { updated_runtime: Some(r) })
                                     .map_err(|e| e.into()))
                                     .map_err(|_| Error::FailedSendClientClosed)
@@ -791,7 +875,11 @@ impl InstanceRunner {
                 CurrentState { tx } => {
                     tx.send(Ok(self.current_state())).map_err(|_| ())
                 }
-                PutState { tx, .. } => {
+                PutState { state, tx } => {
+                    let (_, waiters) = self.take_pending_put_state(state);
+                    for waiter in waiters {
+                        let _ = waiter.send(Err(Error::Terminating.into()));
+                    }
                     tx.send(Err(Error::Terminating.into())).map_err(|_| ())
                 }
                 IssueSnapshotRequest { tx, .. } => {
@@ -819,6 +907,23 @@ impl InstanceRunner {
         }
     }
 
+    /// Takes the queued put-state request's latest target state, along with
+    /// the callers whose requests were coalesced into it, after receiving a
+    /// `PutState` request for `state` from the request channel.
+    fn take_pending_put_state(
+        &self,
+        state: VmmStateRequested,
+    ) -> (
+        VmmStateRequested,
+        Vec<oneshot::Sender<Result<VmmPutStateResponse, ManagerError>>>,
+    ) {
+        match self.pending_put_state.lock().unwrap().take() {
+            Some(PendingPutState { state, waiters }) => (state, waiters),
+            // Requests sent directly over the channel aren't coalesced.
+            None => (state, Vec::new()),
+        }
+    }
+
     /// Yields this instance's ID.
     fn instance_id(&self) -> InstanceUuid {
         InstanceUuid::from_untyped_uuid(self.properties.id)
@@ -1497,6 +1602,10 @@ pub struct Instance {
     /// over all other requests to the instance.
     terminate_tx: mpsc::Sender<TerminateRequest>,
 
+    /// The put-state request, if any, that has been sent over `tx` but that
+    /// the runner has not yet started to apply. See [`PendingPutState`].
+    pending_put_state: PendingPutStateSlot,
+
     /// This is reference-counted so that the `Instance` struct may be cloned.
     #[allow(dead_code)]
     runner_handle: Arc<tokio::task::JoinHandle<()>>,
@@ -1618,10 +1727,13 @@ impl Instance {
             sled_serial: sled_identifiers.serial,
         };
 
+        let pending_put_state = PendingPutStateSlot::default();
+
         let runner = InstanceRunner {
             log: log.new(o!("instance_id" => id.to_string())),
             should_terminate: false,
             rx,
+            pending_put_state: pending_put_state.clone(),
             tx_monitor,
             rx_monitor,
             monitor_handle: None,
@@ -1660,6 +1772,7 @@ impl Instance {
             tx,
             runner_handle: Arc::new(runner_handle),
             terminate_tx,
+            pending_put_state,
         })
     }
 
@@ -1696,13 +1809,29 @@ impl Instance {
     /// instance begins to stop when Propolis has just begun to handle a prior
     /// request to reboot, the instance's state may proceed from Stopping to
     /// Rebooting to Running to Stopping to Stopped.
+    ///
+    /// If an earlier put-state request is still queued, this request is
+    /// coalesced into it (see [`PendingPutState`]): only the newest requested
+    /// state is applied, and `tx` receives the result of applying it.
     pub fn put_state(
         &self,
         tx: oneshot::Sender<Result<VmmPutStateResponse, ManagerError>>,
         state: VmmStateRequested,
     ) -> Result<(), Error> {
+        // Hold the lock while sending so that the runner can't take the
+        // pending request before it has been recorded.
+        let mut pending = self.pending_put_state.lock().unwrap();
+        if let Some(pending) = pending.as_mut() {
+            pending.state = state;
+            pending.waiters.push(tx);
+            return Ok(());
+        }
+
         self.tx
             .try_send(InstanceRequest::PutState { state, tx })
+            .map(|()| {
+                *pending = Some(PendingPutState { state, waiters: Vec::new() });
+            })
             .or_else(InstanceRequest::fail_try_send)
     }
 
@@ -2020,6 +2149,9 @@ impl InstanceRunner {
             .with_opte_ports(opte_ports)
             .with_links(vec![])
             .with_limit_priv(vec![])
+            .with_resource_caps(propolis_zone_resource_caps(
+                &self.propolis_spec,
+            ))
             .install()
             .await?;
 
@@ -3027,6 +3159,7 @@ mod tests {
             initial_state: InstanceInitialState,
             services: InstanceManagerServices,
             cmd_rx: mpsc::Receiver<InstanceRequest>,
+            pending_put_state: PendingPutStateSlot,
             monitor_tx: mpsc::Sender<InstanceMonitorMessage>,
             monitor_rx: mpsc::Receiver<InstanceMonitorMessage>,
         ) -> Self {
@@ -3075,6 +3208,7 @@ mod tests {
                 log: log.new(o!("component" => "TestInstanceRunner")),
                 should_terminate: false,
                 rx: cmd_rx,
+                pending_put_state,
                 tx_monitor: monitor_tx,
                 rx_monitor: monitor_rx,
                 monitor_handle: None,
@@ -3112,6 +3246,7 @@ mod tests {
         terminate_tx: mpsc::Sender<TerminateRequest>,
         monitor_tx: mpsc::Sender<InstanceMonitorMessage>,
         cmd_tx: mpsc::Sender<InstanceRequest>,
+        pending_put_state: PendingPutStateSlot,
         remove_rx: mpsc::UnboundedReceiver<
             crate::instance_manager::InstanceDeregisterRequest,
         >,
@@ -3153,6 +3288,7 @@ mod tests {
             let (terminate_tx, terminate_rx) = mpsc::channel(1);
             let (monitor_tx, monitor_rx) = mpsc::channel(1);
             let (cmd_tx, cmd_rx) = mpsc::channel(QUEUE_SIZE);
+            let pending_put_state = PendingPutStateSlot::default();
             let (remove_tx, remove_rx) = mpsc::unbounded_channel();
             let ticket = InstanceTicket::new(propolis_id, remove_tx);
 
@@ -3162,6 +3298,7 @@ mod tests {
                 initial_state,
                 services,
                 cmd_rx,
+                pending_put_state.clone(),
                 monitor_tx.clone(),
                 monitor_rx,
             );
@@ -3176,6 +3313,7 @@ mod tests {
                 terminate_tx,
                 monitor_tx,
                 cmd_tx,
+                pending_put_state,
                 remove_rx,
                 _nexus_server,
                 _dns_server,
@@ -3196,6 +3334,7 @@ mod tests {
             terminate_tx,
             monitor_tx,
             cmd_tx,
+            pending_put_state: _,
             mut remove_rx,
             _nexus_server,
             _dns_server,
@@ -3268,6 +3407,7 @@ mod tests {
             terminate_tx: _tt,
             monitor_tx,
             cmd_tx: _ct,
+            pending_put_state: _,
             mut remove_rx,
             _nexus_server,
             _dns_server,
@@ -3295,4 +3435,78 @@ mod tests {
         assert_eq!(state.vmm_state.state, VmmState::Failed);
         logctx.cleanup_successful();
     }
+
+    #[tokio::test]
+    async fn test_put_state_requests_coalesced() {
+        let logctx = omicron_test_utils::dev::test_setup_log(
+            "test_put_state_requests_coalesced",
+        );
+        let log = logctx.log.new(o!(FileKv));
+
+        let TestInstanceRunner {
+            runner_task,
+            state_rx: _sr,
+            terminate_tx,
+            monitor_tx: _mt,
+            cmd_tx,
+            pending_put_state,
+            remove_rx: _rr,
+            _nexus_server,
+            _dns_server,
+        } = TestInstanceRunner::new(&log).await;
+
+        // Queue a request to reboot the instance, and coalesce a request to
+        // stop it into that request (as `Instance::put_state` would if the
+        // second request arrived before the runner picked up the first).
+        let (coalesced_tx, coalesced_rx) = oneshot::channel();
+        let (queued_tx, queued_rx) = oneshot::channel();
+        {
+            let mut pending = pending_put_state.lock().unwrap();
+            *pending = Some(PendingPutState {
+                state: VmmStateRequested::Stopped,
+                waiters: vec![coalesced_tx],
+            });
+            cmd_tx
+                .try_send(InstanceRequest::PutState {
+                    state: VmmStateRequested::Reboot,
+                    tx: queued_tx,
+                })
+                .unwrap();
+        }
+
+        // Only the newest state should have been applied. Rebooting an
+        // instance with no Propolis zone fails, but stopping it succeeds and
+        // destroys the VMM, and both callers should be told as much.
+        for rx in [queued_rx, coalesced_rx] {
+            let resp = rx
+                .await
+                .expect("runner should respond")
+                .expect("stop request should succeed");
+            let state = resp.updated_runtime.expect("should have a state");
+            assert_eq!(state.vmm_state.state, VmmState::Destroyed);
+        }
+        assert!(pending_put_state.lock().unwrap().is_none());
+
+        drop(cmd_tx);
+        drop(terminate_tx);
+        let _ = runner_task.await;
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_propolis_zone_resource_caps() {
+        let propolis_addr =
+            SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 1, 0, 0));
+        let mut spec = fake_instance_initial_state(propolis_addr).vmm_spec;
+        spec.0.board.cpus = 4;
+        spec.0.board.memory_mb = 8192;
+
+        let caps = propolis_zone_resource_caps(&spec);
+        assert_eq!(caps.ncpus, Some(4.0 + PROPOLIS_ZONE_CPU_OVERHEAD));
+        assert_eq!(
+            caps.memory_bytes,
+            Some((8192 << 20) + PROPOLIS_ZONE_MEMORY_OVERHEAD_BYTES)
+        );
+    }
 }
@@ -22,6 +22,7 @@ pub mod config;
 mod ddm_reconciler;
 pub(crate) mod hardware_monitor;
 mod http_entrypoints;
+mod idempotency;
 mod instance;
 mod instance_manager;
 mod long_running_tasks;
@@ -38,6 +39,7 @@ mod swap_device;
 mod updates;
 mod vmm_reservoir;
 mod zone_bundle;
+mod zone_image_cache;
 
 #[cfg(test)]
 mod fakes;
@@ -89,6 +89,7 @@ pub async fn spawn_all_longrunning_tasks(
             MountConfig::default(),
             storage_key_requester,
             time_sync_config,
+            config.zpool_scrub,
             log,
         );
 
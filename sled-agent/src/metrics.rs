@@ -8,6 +8,8 @@ use illumos_utils::running_zone::RunningZone;
 use omicron_common::api::internal::nexus::ProducerEndpoint;
 use omicron_common::api::internal::nexus::ProducerKind;
 use omicron_common::api::internal::shared::SledIdentifiers;
+use oximeter::Producer;
+use oximeter::types::ProducerRegistry;
 use oximeter_instruments::kstat::CollectionDetails;
 use oximeter_instruments::kstat::Error as KstatError;
 use oximeter_instruments::kstat::KstatSampler;
@@ -352,6 +354,8 @@ pub struct MetricsManager {
     tx: mpsc::Sender<Message>,
     /// The background task itself.
     _task: tokio::task::JoinHandle<()>,
+    /// The registry of the producer server, for other producers to join.
+    registry: ProducerRegistry,
 }
 
 impl MetricsManager {
@@ -367,6 +371,7 @@ impl MetricsManager {
             .registry()
             .register_producer(sampler.clone())
             .expect("actually infallible");
+        let registry = server.registry().clone();
         let (tx, rx) = mpsc::channel(QUEUE_SIZE);
         let task_log = log.new(o!("component" => "metrics-task"));
         let _task = tokio::task::spawn(metrics_task(
@@ -376,7 +381,12 @@ impl MetricsManager {
             task_log,
             rx,
         ));
-        Ok(Self { tx, _task })
+        Ok(Self { tx, _task, registry })
+    }
+
+    /// Publish the samples of `producer` alongside the sled's other metrics.
+    pub fn register_producer<P: Producer>(&self, producer: P) {
+        self.registry.register_producer(producer).expect("actually infallible");
     }
 
     /// Return a queue that can be used to send requests to the metrics task.
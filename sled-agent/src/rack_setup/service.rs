@@ -95,9 +95,9 @@ use nexus_sled_agent_shared::inventory::{
 };
 use nexus_types::deployment::{
     Blueprint, BlueprintDatasetConfig, BlueprintDatasetDisposition,
-    BlueprintHostPhase2DesiredSlots, BlueprintSledConfig, BlueprintZoneType,
-    CockroachDbPreserveDowngrade, OximeterReadMode, PendingMgsUpdates,
-    PlanningReport, blueprint_zone_type,
+    BlueprintHostPhase2DesiredSlots, BlueprintScope, BlueprintSledConfig,
+    BlueprintZoneType, CockroachDbPreserveDowngrade, OximeterReadMode,
+    PendingMgsUpdates, PlanningReport, blueprint_zone_type,
 };
 use nexus_types::external_api::views::SledState;
 use ntp_admin_client::{
@@ -384,7 +384,8 @@ impl ServiceInner {
                 "datasets" => ?sled_config.datasets,
                 "zones" => ?sled_config.zones,
             );
-            let Err(error) = client.omicron_config_put(&sled_config).await
+            let Err(error) =
+                client.omicron_config_put(None, &sled_config).await
             else {
                 return Ok(());
             };
@@ -1623,6 +1624,7 @@ pub(crate) fn build_initial_blueprint_from_sled_configs(
     Ok(Blueprint {
         id,
         sleds: blueprint_sleds,
+        scope: BlueprintScope::Fleet,
         pending_mgs_updates: PendingMgsUpdates::new(),
         parent_blueprint_id: None,
         internal_dns_version,
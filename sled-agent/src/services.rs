@@ -33,6 +33,7 @@ use crate::config::SidecarRevision;
 use crate::ddm_reconciler::DdmReconciler;
 use crate::metrics::MetricsRequestQueue;
 use crate::profile::*;
+use crate::zone_image_cache::ZoneImageCache;
 use anyhow::anyhow;
 use camino::{Utf8Path, Utf8PathBuf};
 use clickhouse_admin_types::CLICKHOUSE_KEEPER_CONFIG_DIR;
@@ -582,6 +583,7 @@ struct SledAgentInfo {
     rack_id: Uuid,
     rack_network_config: Option<RackNetworkConfig>,
     metrics_queue: MetricsRequestQueue,
+    zone_image_cache: ZoneImageCache,
 }
 
 #[derive(Clone)]
@@ -783,6 +785,7 @@ impl ServiceManager {
         rack_id: Uuid,
         rack_network_config: Option<RackNetworkConfig>,
         metrics_queue: MetricsRequestQueue,
+        zone_image_cache: ZoneImageCache,
     ) -> Result<(), Error> {
         info!(
             &self.inner.log, "sled agent started";
@@ -801,6 +804,7 @@ impl ServiceManager {
                 rack_id,
                 rack_network_config,
                 metrics_queue: metrics_queue.clone(),
+                zone_image_cache,
             })
             .map_err(|_| "already set".to_string())
             .expect("Sled Agent should only start once");
@@ -1377,9 +1381,21 @@ impl ServiceManager {
         // ramdisk zones like the probe zone construct the file source
         // directly.)
 
+        //
+        // Omicron zones installed from the artifact store are also copied into
+        // the sled's zone image cache (if the sled agent has started), so that
+        // reinstalling them doesn't depend on the artifact store still having
+        // the image.
         let file_source = match &request {
             ZoneArgs::Omicron(prepared_zone) => {
-                prepared_zone.file_source().file_source.clone()
+                match self.inner.sled_info.get() {
+                    Some(info) => {
+                        info.zone_image_cache
+                            .prepare(prepared_zone.file_source())
+                            .await
+                    }
+                    None => prepared_zone.file_source().file_source.clone(),
+                }
             }
             ZoneArgs::Switch(_) => ramdisk_file_source(zone_type_str),
         };
@@ -1935,6 +1951,7 @@ impl ServiceManager {
                 RunningZone::boot(installed_zone).await?
             }
             OmicronZoneConfig {
+                id: zone_id,
                 zone_type:
                     OmicronZoneType::ExternalDns {
                         http_address,
@@ -1967,6 +1984,7 @@ impl ServiceManager {
                     SocketAddr::new(nic.ip, dns_address.port()).to_string();
 
                 let external_dns_config = PropertyGroupBuilder::new("config")
+                    .add_property("zone_id", "astring", zone_id.to_string())
                     .add_property(
                         "http_address",
                         "astring",
@@ -2175,6 +2193,7 @@ impl ServiceManager {
                 RunningZone::boot(installed_zone).await?
             }
             OmicronZoneConfig {
+                id: zone_id,
                 zone_type:
                     OmicronZoneType::InternalDns {
                         http_address,
@@ -2236,6 +2255,7 @@ impl ServiceManager {
                     .add_internal_dns_subnet(Ipv6Subnet::new(*gz_address));
 
                 let internal_dns_config = PropertyGroupBuilder::new("config")
+                    .add_property("zone_id", "astring", zone_id.to_string())
                     .add_property(
                         "http_address",
                         "astring",
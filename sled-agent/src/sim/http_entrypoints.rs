@@ -25,8 +25,13 @@ use dropshot::StreamingBody;
 use dropshot::TypedBody;
 use dropshot::endpoint;
 use nexus_sled_agent_shared::inventory::Inventory;
+use nexus_sled_agent_shared::inventory::InventoryChanges;
+use nexus_sled_agent_shared::inventory::InventoryKnownDigests;
 use nexus_sled_agent_shared::inventory::OmicronSledConfig;
 use nexus_sled_agent_shared::inventory::SledRole;
+use nexus_sled_agent_shared::inventory::v1;
+use nexus_sled_agent_shared::inventory::v4;
+use nexus_sled_agent_shared::inventory::v6;
 use omicron_common::api::internal::nexus::DiskRuntimeState;
 use omicron_common::api::internal::nexus::SledVmmState;
 use omicron_common::api::internal::shared::ExternalIpGatewayMap;
@@ -81,7 +86,7 @@ enum SledAgentSimImpl {}
 impl SledAgentApi for SledAgentSimImpl {
     type Context = Arc<SledAgent>;
 
-    async fn vmm_register(
+    async fn vmm_register_v1(
         rqctx: RequestContext<Self::Context>,
         path_params: Path<VmmPathParam>,
         body: TypedBody<InstanceEnsureBody>,
@@ -92,6 +97,30 @@ impl SledAgentApi for SledAgentSimImpl {
         Ok(HttpResponseOk(sa.instance_register(propolis_id, body_args).await?))
     }
 
+    async fn vmm_register(
+        rqctx: RequestContext<Self::Context>,
+        headers: Header<IdempotencyKeyHeaders>,
+        path_params: Path<VmmPathParam>,
+        body: TypedBody<InstanceEnsureBody>,
+    ) -> Result<HttpResponseOk<SledVmmState>, HttpError> {
+        let sa = rqctx.context();
+        let propolis_id = path_params.into_inner().propolis_id;
+        let key = headers.into_inner().idempotency_key;
+        let body_args = body.into_inner();
+        let state = sa
+            .idempotency_cache
+            .run(
+                key.as_deref(),
+                "vmm_register",
+                (propolis_id, body_args),
+                move |(propolis_id, instance)| async move {
+                    sa.instance_register(propolis_id, instance).await
+                },
+            )
+            .await??;
+        Ok(HttpResponseOk(state))
+    }
+
     async fn vmm_unregister(
         rqctx: RequestContext<Self::Context>,
         path_params: Path<VmmPathParam>,
@@ -321,6 +350,27 @@ impl SledAgentApi for SledAgentSimImpl {
     }
 
     /// Fetch basic information about this sled
+    async fn inventory_v1(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v1::Inventory>, HttpError> {
+        let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+        Ok(HttpResponseOk(inventory.into()))
+    }
+
+    async fn inventory_v4(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v4::Inventory>, HttpError> {
+        let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+        Ok(HttpResponseOk(inventory.into()))
+    }
+
+    async fn inventory_v6(
+        rqctx: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<v6::Inventory>, HttpError> {
+        let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+        Ok(HttpResponseOk(inventory.into()))
+    }
+
     async fn inventory(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<Inventory>, HttpError> {
@@ -332,7 +382,15 @@ impl SledAgentApi for SledAgentSimImpl {
         ))
     }
 
-    async fn omicron_config_put(
+    async fn inventory_changes(
+        rqctx: RequestContext<Self::Context>,
+        query_params: Query<InventoryKnownDigests>,
+    ) -> Result<HttpResponseOk<InventoryChanges>, HttpError> {
+        let HttpResponseOk(inventory) = Self::inventory(rqctx).await?;
+        Ok(HttpResponseOk(inventory.changes_since(&query_params.into_inner())))
+    }
+
+    async fn omicron_config_put_v1(
         rqctx: RequestContext<Self::Context>,
         body: TypedBody<OmicronSledConfig>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
@@ -342,6 +400,25 @@ impl SledAgentApi for SledAgentSimImpl {
         Ok(HttpResponseUpdatedNoContent())
     }
 
+    async fn omicron_config_put(
+        rqctx: RequestContext<Self::Context>,
+        headers: Header<IdempotencyKeyHeaders>,
+        body: TypedBody<OmicronSledConfig>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let sa = rqctx.context();
+        let key = headers.into_inner().idempotency_key;
+        let body_args = body.into_inner();
+        sa.idempotency_cache
+            .run(
+                key.as_deref(),
+                "omicron_config_put",
+                body_args,
+                move |config| async move { sa.set_omicron_config(config) },
+            )
+            .await??;
+        Ok(HttpResponseUpdatedNoContent())
+    }
+
     async fn sled_add(
         _rqctx: RequestContext<Self::Context>,
         _body: TypedBody<AddSledRequest>,
@@ -756,6 +833,13 @@ impl SledAgentApi for SledAgentSimImpl {
         method_unimplemented()
     }
 
+    async fn support_kstat_info(
+        _request_context: RequestContext<Self::Context>,
+    ) -> Result<HttpResponseOk<Vec<SledDiagnosticsQueryOutput>>, HttpError>
+    {
+        method_unimplemented()
+    }
+
     async fn support_health_check(
         _request_context: RequestContext<Self::Context>,
     ) -> Result<HttpResponseOk<Vec<SledDiagnosticsQueryOutput>>, HttpError>
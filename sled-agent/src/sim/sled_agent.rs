@@ -12,6 +12,7 @@ use super::instance::{self, SimInstance};
 use super::storage::CrucibleData;
 use super::storage::Storage;
 use crate::artifact_store::ArtifactStore;
+use crate::idempotency::IdempotencyCache;
 use crate::nexus::NexusClient;
 use crate::sim::SimulatedUpstairs;
 use crate::sim::simulatable::Simulatable;
@@ -106,6 +107,9 @@ pub struct SledAgent {
     pub bootstore_network_config: Mutex<EarlyNetworkConfig>,
     pub(super) repo_depot:
         dropshot::HttpServer<ArtifactStore<SimArtifactStorage>>,
+    /// responses to recent requests that carried an idempotency key (not
+    /// persisted across restarts of the simulated sled agent)
+    pub(super) idempotency_cache: IdempotencyCache,
     pub log: Logger,
 }
 
@@ -160,6 +164,8 @@ impl SledAgent {
                 .await
                 .start(&log, &config.dropshot);
 
+        let idempotency_cache = IdempotencyCache::load(&log, Vec::new()).await;
+
         Arc::new(SledAgent {
             id,
             ip: config.dropshot.bind_address.ip(),
@@ -189,6 +195,7 @@ impl SledAgent {
             }),
             instance_ensure_state_error: Mutex::new(None),
             repo_depot,
+            idempotency_cache,
             log,
             bootstore_network_config,
         })
@@ -782,6 +789,10 @@ impl SledAgent {
                     Ok(InventoryZpool {
                         id: *id,
                         total_size: ByteCount::try_from(zpool.total_size())?,
+                        allocated: None,
+                        fragmentation_percent: None,
+                        scrub: None,
+                        health: None,
                     })
                 })
                 .collect::<Result<Vec<_>, anyhow::Error>>()?,
@@ -2068,17 +2068,17 @@ impl Pantry {
             .map_err(|e| HttpError::for_internal_error(e.to_string()))
     }
 
-    pub fn bulk_write(
+    /// Returns the block size and total size of a volume attached to this
+    /// Pantry
+    fn volume_geometry(
         &self,
         volume_id: String,
-        offset: u64,
-        data: Vec<u8>,
-    ) -> Result<(), HttpError> {
+    ) -> Result<(u64, u64), HttpError> {
         let vcr = self.entry(volume_id)?;
 
         // Currently, Nexus will only make volumes where the first subvolume is
         // a Region. This will change in the future!
-        let (region_block_size, region_size) = match vcr {
+        match vcr {
             VolumeConstructionRequest::Volume { sub_volumes, .. } => {
                 match sub_volumes[0] {
                     VolumeConstructionRequest::Region {
@@ -2086,12 +2086,12 @@ impl Pantry {
                         blocks_per_extent,
                         extent_count,
                         ..
-                    } => (
+                    } => Ok((
                         block_size,
                         block_size
                             * blocks_per_extent
                             * u64::from(extent_count),
-                    ),
+                    )),
 
                     _ => {
                         panic!("unexpected Volume layout");
@@ -2102,7 +2102,17 @@ impl Pantry {
             _ => {
                 panic!("unexpected Volume layout");
             }
-        };
+        }
+    }
+
+    pub fn bulk_write(
+        &self,
+        volume_id: String,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> Result<(), HttpError> {
+        let (region_block_size, region_size) =
+            self.volume_geometry(volume_id)?;
 
         if (offset % region_block_size) != 0 {
             return Err(HttpError::for_bad_request(
@@ -2128,6 +2138,43 @@ impl Pantry {
         Ok(())
     }
 
+    /// Read data from a volume at a specified offset
+    ///
+    /// The simulated Pantry doesn't store written data, so this always
+    /// returns zeroes.
+    pub fn bulk_read(
+        &self,
+        volume_id: String,
+        offset: u64,
+        size: usize,
+    ) -> Result<Vec<u8>, HttpError> {
+        let (region_block_size, region_size) =
+            self.volume_geometry(volume_id)?;
+
+        if (offset % region_block_size) != 0 {
+            return Err(HttpError::for_bad_request(
+                None,
+                "offset not multiple of block size!".to_string(),
+            ));
+        }
+
+        if (size as u64 % region_block_size) != 0 {
+            return Err(HttpError::for_bad_request(
+                None,
+                "size not multiple of block size!".to_string(),
+            ));
+        }
+
+        if (offset + size as u64) > region_size {
+            return Err(HttpError::for_bad_request(
+                None,
+                "offset + size off end of region!".to_string(),
+            ));
+        }
+
+        Ok(vec![0; size])
+    }
+
     pub fn scrub(&self, volume_id: String) -> Result<String, HttpError> {
         self.entry(volume_id)?;
 
@@ -9,6 +9,7 @@ use crate::bootstrap::config::BOOTSTRAP_AGENT_RACK_INIT_PORT;
 use crate::bootstrap::early_networking::EarlyNetworkSetupError;
 use crate::config::Config;
 use crate::hardware_monitor::HardwareMonitorHandle;
+use crate::idempotency::{IDEMPOTENCY_LEDGER_FILENAME, IdempotencyCache};
 use crate::instance_manager::InstanceManager;
 use crate::long_running_tasks::LongRunningTaskHandles;
 use crate::metrics::{MetricsManager, MetricsRequestQueue};
@@ -22,6 +23,7 @@ use crate::support_bundle::storage::SupportBundleManager;
 use crate::vmm_reservoir::{ReservoirMode, VmmReservoirManager};
 use crate::zone_bundle::BundleError;
 use crate::zone_bundle::{self, ZoneBundler};
+use crate::zone_image_cache::ZoneImageCache;
 use anyhow::anyhow;
 use bootstore::schemes::v0 as bootstore;
 use camino::Utf8PathBuf;
@@ -374,6 +376,9 @@ struct SledAgentInner {
 
     // Component of Sled Agent responsible for managing the artifact store.
     repo_depot: dropshot::HttpServer<Arc<ArtifactStore<InternalDisksReceiver>>>,
+
+    // Responses to recent requests that carried an idempotency key.
+    idempotency_cache: IdempotencyCache,
 }
 
 impl SledAgentInner {
@@ -605,6 +610,14 @@ impl SledAgent {
             .await,
         );
 
+        let zone_image_cache = ZoneImageCache::new(
+            &log,
+            config_reconciler.internal_disks_rx().clone(),
+        );
+        metrics_manager.register_producer(
+            zone_image_cache.producer(&svc_config.sled_identifiers),
+        );
+
         // Start reconciling against our ledgered sled config.
         config_reconciler.spawn_reconciliation_task(
             ReconcilerFacilities {
@@ -625,6 +638,7 @@ impl SledAgent {
                 request.body.rack_id,
                 rack_network_config.clone(),
                 metrics_manager.request_queue(),
+                zone_image_cache,
             )
             .await?;
 
@@ -661,6 +675,15 @@ impl SledAgent {
         let currently_managed_zpools_rx =
             config_reconciler.currently_managed_zpools_rx().clone();
 
+        let idempotency_ledger_paths = config_reconciler
+            .internal_disks_rx()
+            .current()
+            .all_config_datasets()
+            .map(|p| p.join(IDEMPOTENCY_LEDGER_FILENAME))
+            .collect();
+        let idempotency_cache =
+            IdempotencyCache::load(&parent_log, idempotency_ledger_paths).await;
+
         let sled_agent = SledAgent {
             inner: Arc::new(SledAgentInner {
                 id: request.body.id,
@@ -681,6 +704,7 @@ impl SledAgent {
                     .clone(),
                 _metrics_manager: metrics_manager,
                 repo_depot,
+                idempotency_cache,
             }),
             log: log.clone(),
             sprockets: config.sprockets.clone(),
@@ -736,6 +760,10 @@ impl SledAgent {
         &self.inner.hardware_monitor
     }
 
+    pub(crate) fn idempotency_cache(&self) -> &IdempotencyCache {
+        &self.inner.idempotency_cache
+    }
+
     /// Trigger a request to Nexus informing it that the current sled exists,
     /// with information about the existing set of hardware.
     pub(crate) async fn notify_nexus_about_self(&self, log: &Logger) {
@@ -1184,6 +1212,12 @@ impl SledAgent {
         sled_diagnostics::zpool_info().await
     }
 
+    pub(crate) async fn support_kstat_info(
+        &self,
+    ) -> Vec<Result<SledDiagnosticsCmdOutput, SledDiagnosticsCmdError>> {
+        sled_diagnostics::kstat_info().await
+    }
+
     pub(crate) async fn support_health_check(
         &self,
     ) -> Vec<Result<SledDiagnosticsCmdOutput, SledDiagnosticsCmdError>> {
@@ -21,7 +21,7 @@ use illumos_utils::zfs::DestroySnapshotError;
 use illumos_utils::zfs::EnsureDatasetError;
 use illumos_utils::zfs::GetValueError;
 use illumos_utils::zfs::ListDatasetsError;
-use illumos_utils::zfs::ListSnapshotsError;
+use illumos_utils::zfs::ListSnapshotsOfError;
 use illumos_utils::zfs::SetValueError;
 use illumos_utils::zfs::Snapshot;
 use illumos_utils::zfs::ZFS;
@@ -67,12 +67,41 @@ const ZONE_BUNDLE_ZFS_PROPERTY_VALUE: &'static str = "true";
 // This deletes any snapshots matching the names we expect to create ourselves
 // during bundling.
 #[cfg(not(test))]
-async fn initialize_zfs_resources(log: &Logger) -> Result<(), BundleError> {
+async fn initialize_zfs_resources(
+    log: &Logger,
+    available_datasets_rx: &AvailableDatasetsReceiver,
+) -> Result<(), BundleError> {
+    // Bundling snapshots the root filesystem of the zone, which is a child of
+    // a zone dataset, and the debug datasets holding archived logs. Only look
+    // at the snapshots of those filesystems, rather than every snapshot on the
+    // system.
+    let mut filesystems = Vec::new();
+    for zone_dataset in available_datasets_rx.all_mounted_zone_root_datasets() {
+        let parent = Zfs::get_dataset_name(zone_dataset.path.as_str()).await?;
+        for child in Zfs::list_datasets(&parent).await? {
+            filesystems.push(format!("{parent}/{child}"));
+        }
+    }
+    for debug_dataset in available_datasets_rx.all_mounted_debug_datasets() {
+        filesystems
+            .push(Zfs::get_dataset_name(debug_dataset.path.as_str()).await?);
+    }
+
     let mut zb_snapshots = Vec::new();
-    for snap in Zfs::list_snapshots().await.unwrap().into_iter() {
+    let mut snapshots = Vec::new();
+    for filesystem in &filesystems {
+        snapshots.extend(
+            Zfs::list_snapshots_of(
+                filesystem,
+                &[ZONE_BUNDLE_ZFS_PROPERTY_NAME],
+            )
+            .await?,
+        );
+    }
+    for snap in snapshots.into_iter() {
         // Check for snapshots named how we expect to create them.
-        if snap.snap_name != ZONE_ROOT_SNAPSHOT_NAME
-            || !snap.snap_name.starts_with(ARCHIVE_SNAPSHOT_PREFIX)
+        if snap.snapshot.snap_name != ZONE_ROOT_SNAPSHOT_NAME
+            || !snap.snapshot.snap_name.starts_with(ARCHIVE_SNAPSHOT_PREFIX)
         {
             continue;
         }
@@ -82,14 +111,8 @@ async fn initialize_zfs_resources(log: &Logger) -> Result<(), BundleError> {
         // If we find a dataset that matches our names, but which _does not_
         // have such a property (or has in invalid property), we'll log it
         // but avoid deleting the snapshot.
-        let name = snap.to_string();
-        let Ok([value]) = Zfs::get_values(
-            &name,
-            &[ZONE_BUNDLE_ZFS_PROPERTY_NAME],
-            Some(illumos_utils::zfs::PropertySource::Local),
-        )
-        .await
-        else {
+        let name = snap.snapshot.to_string();
+        let Some(value) = snap.value(ZONE_BUNDLE_ZFS_PROPERTY_NAME) else {
             warn!(
                 log,
                 "Found a ZFS snapshot with a name reserved for zone \
@@ -113,7 +136,7 @@ async fn initialize_zfs_resources(log: &Logger) -> Result<(), BundleError> {
             );
             continue;
         }
-        zb_snapshots.push(snap);
+        zb_snapshots.push(snap.snapshot);
     }
     for snapshot in zb_snapshots {
         Zfs::destroy_snapshot(&snapshot.filesystem, &snapshot.snap_name)
@@ -252,7 +275,7 @@ impl ZoneBundler {
         // might be useful for making ZFS datasets on top of a test-only
         // temporary directory.
         #[cfg(not(test))]
-        initialize_zfs_resources(&log)
+        initialize_zfs_resources(&log, &available_datasets_rx)
             .await
             .expect("Failed to initialize existing ZFS resources");
         let notify_cleanup = Arc::new(Notify::new());
@@ -593,7 +616,7 @@ pub enum BundleError {
     DestroySnapshot(#[from] DestroySnapshotError),
 
     #[error("Failed to list ZFS snapshots")]
-    ListSnapshot(#[from] ListSnapshotsError),
+    ListSnapshot(#[from] ListSnapshotsOfError),
 
     #[error("Failed to ensure ZFS dataset")]
     EnsureDataset(#[from] EnsureDatasetError),
@@ -0,0 +1,560 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Sled-local cache of Omicron zone images
+//!
+//! Zone images delivered by Nexus live in the artifact store only as long as
+//! they're part of Nexus's artifact configuration; once an update moves on,
+//! the artifact store deletes them. Reinstalling a zone from one of those
+//! images (for instance, a zone that is expunged and re-added on the same
+//! image, or a rollback to a previous release) then requires Nexus to
+//! replicate the whole image to the sled again.
+//!
+//! The [`ZoneImageCache`] keeps its own copy of each zone image installed from
+//! the artifact store, named by its SHA-256 hash, in the zone image cache
+//! dataset on each M.2. Zone installation searches the cache before the
+//! artifact store. When adding an image would take a cache dataset over
+//! [`MAX_CACHE_BYTES`], the least recently installed images are evicted.
+
+use atomicwrites::AtomicFile;
+use atomicwrites::OverwriteBehavior;
+use camino::Utf8Path;
+use camino::Utf8PathBuf;
+use chrono::DateTime;
+use chrono::Utc;
+use omicron_common::api::internal::shared::SledIdentifiers;
+use oximeter::MetricsError;
+use oximeter::Producer;
+use oximeter::Sample;
+use oximeter::types::Cumulative;
+use sha2::{Digest, Sha256};
+use sled_agent_config_reconciler::InternalDisksReceiver;
+use sled_agent_types::zone_images::OmicronZoneFileSource;
+use sled_agent_types::zone_images::OmicronZoneImageLocation;
+use sled_agent_types::zone_images::ZoneImageFileSource;
+use sled_storage::dataset::ZONE_IMAGE_CACHE_DATASET_QUOTA;
+use slog::Logger;
+use slog::info;
+use slog::warn;
+use slog_error_chain::InlineErrorChain;
+use std::fs::File;
+use std::fs::FileTimes;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+use tufaceous_artifact::ArtifactHash;
+
+oximeter::use_timeseries!("sled-zone-image-cache.toml");
+use self::sled_zone_image_cache::Evictions;
+use self::sled_zone_image_cache::Hits;
+use self::sled_zone_image_cache::Misses;
+use self::sled_zone_image_cache::SledZoneImageCache;
+
+/// The number of bytes of zone images kept in each cache dataset.
+///
+/// This leaves some of the dataset's quota free for filesystem overhead.
+const MAX_CACHE_BYTES: u64 = ZONE_IMAGE_CACHE_DATASET_QUOTA.to_bytes() / 10 * 9;
+
+/// The directory within each cache dataset used for partially-copied images.
+const TEMP_DIR: &str = "tmp";
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error("zone image {hash} not found in any of {search_paths:?}")]
+    SourceNotFound { hash: ArtifactHash, search_paths: Vec<Utf8PathBuf> },
+
+    #[error("failed to {verb} {path}")]
+    File {
+        verb: &'static str,
+        path: Utf8PathBuf,
+        #[source]
+        err: io::Error,
+    },
+
+    #[error(
+        "hash mismatch copying zone image: expected {expected}, got {actual}"
+    )]
+    HashMismatch { expected: ArtifactHash, actual: ArtifactHash },
+
+    #[error("failed to copy zone image {hash} into any cache dataset")]
+    NoCopies {
+        hash: ArtifactHash,
+        #[source]
+        last_error: Option<Box<Error>>,
+    },
+
+    #[error("zone image cache task panicked")]
+    Join(#[source] tokio::task::JoinError),
+}
+
+/// Whether a zone image was found in the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Lookup {
+    Hit,
+    Miss,
+}
+
+/// Cache counters, shared with the metrics producer.
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+/// Caches zone images installed from the artifact store.
+///
+/// See the module-level documentation for details. This is cheap to clone;
+/// all clones share the same cache and counters.
+#[derive(Clone, Debug)]
+pub(crate) struct ZoneImageCache {
+    log: Logger,
+    internal_disks_rx: InternalDisksReceiver,
+    counters: Arc<Counters>,
+    /// Serializes copies into and evictions from the cache datasets.
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl ZoneImageCache {
+    pub(crate) fn new(
+        log: &Logger,
+        internal_disks_rx: InternalDisksReceiver,
+    ) -> Self {
+        Self {
+            log: log.new(slog::o!("component" => "ZoneImageCache")),
+            internal_disks_rx,
+            counters: Arc::default(),
+            lock: Arc::default(),
+        }
+    }
+
+    /// Returns an oximeter producer reporting this cache's hit, miss, and
+    /// eviction counts for the sled described by `identifiers`.
+    pub(crate) fn producer(
+        &self,
+        identifiers: &SledIdentifiers,
+    ) -> ZoneImageCacheProducer {
+        ZoneImageCacheProducer {
+            target: SledZoneImageCache {
+                rack_id: identifiers.rack_id,
+                sled_id: identifiers.sled_id,
+                sled_model: identifiers.model.clone().into(),
+                sled_revision: identifiers.revision,
+                sled_serial: identifiers.serial.clone().into(),
+            },
+            start_time: Utc::now(),
+            counters: Arc::clone(&self.counters),
+        }
+    }
+
+    /// Ensures that the zone image described by `file_source` is in the cache,
+    /// and returns a file source that searches the cache first.
+    ///
+    /// Only images from the artifact store are cached; other file sources are
+    /// returned unchanged. Caching is best-effort: if the image can't be added
+    /// to the cache, the zone is installed from its original location.
+    pub(crate) async fn prepare(
+        &self,
+        file_source: &OmicronZoneFileSource,
+    ) -> ZoneImageFileSource {
+        let OmicronZoneImageLocation::Artifact { hash: Ok(hash) } =
+            file_source.location
+        else {
+            return file_source.file_source.clone();
+        };
+        let cache_dirs: Vec<_> = self
+            .internal_disks_rx
+            .current()
+            .all_zone_image_cache_datasets()
+            .collect();
+        if cache_dirs.is_empty() {
+            return file_source.file_source.clone();
+        }
+
+        let _guard = self.lock.lock().await;
+        let result = tokio::task::spawn_blocking({
+            let log = self.log.clone();
+            let cache_dirs = cache_dirs.clone();
+            let search_paths = file_source.file_source.search_paths.clone();
+            let counters = Arc::clone(&self.counters);
+            move || {
+                ensure_cached(&log, &cache_dirs, hash, &search_paths, &counters)
+            }
+        })
+        .await
+        .map_err(Error::Join)
+        .and_then(|r| r);
+
+        match result {
+            Ok(lookup) => {
+                let counter = match lookup {
+                    Lookup::Hit => &self.counters.hits,
+                    Lookup::Miss => &self.counters.misses,
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+                info!(
+                    self.log,
+                    "zone image cached";
+                    "hash" => %hash,
+                    "lookup" => ?lookup,
+                );
+            }
+            Err(err) => {
+                warn!(
+                    self.log,
+                    "failed to cache zone image; \
+                     installing from its original location";
+                    "hash" => %hash,
+                    InlineErrorChain::new(&err),
+                );
+                return file_source.file_source.clone();
+            }
+        }
+
+        let mut search_paths = cache_dirs;
+        search_paths.extend(file_source.file_source.search_paths.clone());
+        ZoneImageFileSource {
+            file_name: file_source.file_source.file_name.clone(),
+            search_paths,
+        }
+    }
+}
+
+/// Ensures that the image `hash` is in at least one of `cache_dirs`, copying
+/// it from the first of `search_paths` that contains it if not.
+fn ensure_cached(
+    log: &Logger,
+    cache_dirs: &[Utf8PathBuf],
+    hash: ArtifactHash,
+    search_paths: &[Utf8PathBuf],
+    counters: &Counters,
+) -> Result<Lookup, Error> {
+    let file_name = hash.to_string();
+
+    let mut hit = false;
+    for dir in cache_dirs {
+        let path = dir.join(&file_name);
+        if path.exists() {
+            // Mark the image as recently used, so that it's evicted last.
+            if let Err(err) = touch(&path) {
+                warn!(
+                    log,
+                    "failed to update cached zone image modification time";
+                    InlineErrorChain::new(&err),
+                );
+            }
+            hit = true;
+        }
+    }
+    if hit {
+        return Ok(Lookup::Hit);
+    }
+
+    let source = search_paths
+        .iter()
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.exists())
+        .ok_or_else(|| Error::SourceNotFound {
+            hash,
+            search_paths: search_paths.to_vec(),
+        })?;
+    let size = source
+        .metadata()
+        .map_err(|err| Error::File { verb: "stat", path: source.clone(), err })?
+        .len();
+
+    let mut copies = 0;
+    let mut last_error = None;
+    for dir in cache_dirs {
+        let result = evict(log, dir, size, counters)
+            .and_then(|()| copy_verified(dir, &source, hash));
+        match result {
+            Ok(()) => copies += 1,
+            Err(err) => {
+                warn!(
+                    log,
+                    "failed to copy zone image into cache dataset";
+                    "dataset" => %dir,
+                    InlineErrorChain::new(&err),
+                );
+                last_error = Some(Box::new(err));
+            }
+        }
+    }
+    if copies == 0 {
+        return Err(Error::NoCopies { hash, last_error });
+    }
+    Ok(Lookup::Miss)
+}
+
+/// Removes the least recently used images from `dir` until an image of `size`
+/// bytes fits within [`MAX_CACHE_BYTES`].
+fn evict(
+    log: &Logger,
+    dir: &Utf8Path,
+    size: u64,
+    counters: &Counters,
+) -> Result<(), Error> {
+    let file_err = |verb, path: &Utf8Path| {
+        let path = path.to_owned();
+        move |err| Error::File { verb, path, err }
+    };
+
+    let mut entries = Vec::new();
+    let mut total = 0;
+    for entry in dir.read_dir_utf8().map_err(file_err("read", dir))? {
+        let entry = entry.map_err(file_err("read", dir))?;
+        // Skip anything that isn't a cached image, such as the temporary
+        // directory.
+        if entry.file_name().parse::<ArtifactHash>().is_err() {
+            continue;
+        }
+        let metadata =
+            entry.metadata().map_err(file_err("stat", entry.path()))?;
+        let modified =
+            metadata.modified().map_err(file_err("stat", entry.path()))?;
+        total += metadata.len();
+        entries.push((modified, metadata.len(), entry.into_path()));
+    }
+
+    entries.sort();
+    let mut entries = entries.into_iter();
+    while total + size > MAX_CACHE_BYTES {
+        let Some((_, len, path)) = entries.next() else {
+            break;
+        };
+        std::fs::remove_file(&path).map_err(file_err("remove", &path))?;
+        info!(log, "evicted zone image from cache"; "path" => %path);
+        counters.evictions.fetch_add(1, Ordering::Relaxed);
+        total -= len;
+    }
+    Ok(())
+}
+
+/// Copies `source` into `dir`, checking that its contents match `hash`.
+fn copy_verified(
+    dir: &Utf8Path,
+    source: &Utf8Path,
+    hash: ArtifactHash,
+) -> Result<(), Error> {
+    let temp_dir = dir.join(TEMP_DIR);
+    std::fs::create_dir_all(&temp_dir).map_err(|err| Error::File {
+        verb: "create",
+        path: temp_dir.clone(),
+        err,
+    })?;
+
+    let path = dir.join(hash.to_string());
+    let mut reader = File::open(source).map_err(|err| Error::File {
+        verb: "open",
+        path: source.to_owned(),
+        err,
+    })?;
+    AtomicFile::new_with_tmpdir(
+        &path,
+        OverwriteBehavior::AllowOverwrite,
+        temp_dir,
+    )
+    .write(|file| {
+        let mut writer = HashingWriter { inner: file, hasher: Sha256::new() };
+        io::copy(&mut reader, &mut writer).map_err(|err| Error::File {
+            verb: "copy",
+            path: source.to_owned(),
+            err,
+        })?;
+        let actual = ArtifactHash(writer.hasher.finalize().into());
+        if actual == hash {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch { expected: hash, actual })
+        }
+    })
+    .map_err(|err| match err {
+        atomicwrites::Error::Internal(err) => Error::File {
+            verb: "create or persist temporary file for",
+            path,
+            err,
+        },
+        atomicwrites::Error::User(err) => err,
+    })
+}
+
+fn touch(path: &Utf8Path) -> Result<(), Error> {
+    File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| {
+            file.set_times(FileTimes::new().set_modified(SystemTime::now()))
+        })
+        .map_err(|err| Error::File {
+            verb: "update modification time of",
+            path: path.to_owned(),
+            err,
+        })
+}
+
+/// A writer that hashes everything written through it.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: io::Write> io::Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Produces samples of a [`ZoneImageCache`]'s counters.
+#[derive(Debug)]
+pub(crate) struct ZoneImageCacheProducer {
+    target: SledZoneImageCache,
+    start_time: DateTime<Utc>,
+    counters: Arc<Counters>,
+}
+
+impl Producer for ZoneImageCacheProducer {
+    fn produce(
+        &mut self,
+    ) -> Result<Box<dyn Iterator<Item = Sample> + 'static>, MetricsError> {
+        let cumulative = |counter: &AtomicU64| {
+            Cumulative::with_start_time(
+                self.start_time,
+                counter.load(Ordering::Relaxed),
+            )
+        };
+        let samples = vec![
+            Sample::new(
+                &self.target,
+                &Hits { datum: cumulative(&self.counters.hits) },
+            )?,
+            Sample::new(
+                &self.target,
+                &Misses { datum: cumulative(&self.counters.misses) },
+            )?,
+            Sample::new(
+                &self.target,
+                &Evictions { datum: cumulative(&self.counters.evictions) },
+            )?,
+        ];
+        Ok(Box::new(samples.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use omicron_test_utils::dev::test_setup_log;
+
+    fn hash_of(contents: &[u8]) -> ArtifactHash {
+        ArtifactHash(Sha256::digest(contents).into())
+    }
+
+    #[test]
+    fn test_zone_image_cache_hit_and_miss() {
+        let logctx = test_setup_log("test_zone_image_cache_hit_and_miss");
+        let source_dir = camino_tempfile::tempdir().unwrap();
+        let cache_dirs = vec![
+            camino_tempfile::tempdir().unwrap(),
+            camino_tempfile::tempdir().unwrap(),
+        ];
+        let cache_paths: Vec<_> =
+            cache_dirs.iter().map(|d| d.path().to_owned()).collect();
+        let search_paths = vec![source_dir.path().to_owned()];
+        let counters = Counters::default();
+
+        let contents = b"zone image";
+        let hash = hash_of(contents);
+        std::fs::write(source_dir.path().join(hash.to_string()), contents)
+            .unwrap();
+
+        // The first lookup copies the image into every cache dataset.
+        let lookup = ensure_cached(
+            &logctx.log,
+            &cache_paths,
+            hash,
+            &search_paths,
+            &counters,
+        )
+        .expect("cached image");
+        assert_eq!(lookup, Lookup::Miss);
+        for dir in &cache_paths {
+            assert_eq!(
+                std::fs::read(dir.join(hash.to_string())).unwrap(),
+                contents
+            );
+        }
+
+        // Once cached, the image no longer needs to be in the artifact store.
+        std::fs::remove_file(source_dir.path().join(hash.to_string())).unwrap();
+        let lookup = ensure_cached(
+            &logctx.log,
+            &cache_paths,
+            hash,
+            &search_paths,
+            &counters,
+        )
+        .expect("cached image");
+        assert_eq!(lookup, Lookup::Hit);
+
+        // An image whose contents don't match its hash isn't cached.
+        let bogus = hash_of(b"other zone image");
+        std::fs::write(source_dir.path().join(bogus.to_string()), contents)
+            .unwrap();
+        let err = ensure_cached(
+            &logctx.log,
+            &cache_paths,
+            bogus,
+            &search_paths,
+            &counters,
+        )
+        .expect_err("hash mismatch");
+        assert!(matches!(err, Error::NoCopies { .. }), "{err}");
+        for dir in &cache_paths {
+            assert!(!dir.join(bogus.to_string()).exists());
+        }
+
+        logctx.cleanup_successful();
+    }
+
+    #[test]
+    fn test_zone_image_cache_evicts_least_recently_used() {
+        let logctx =
+            test_setup_log("test_zone_image_cache_evicts_least_recently_used");
+        let cache_dir = camino_tempfile::tempdir().unwrap();
+        let counters = Counters::default();
+
+        // Fill the cache with two images, the first of which was used least
+        // recently.
+        let half = usize::try_from(MAX_CACHE_BYTES / 2).unwrap();
+        let old = cache_dir.path().join(hash_of(b"old").to_string());
+        let new = cache_dir.path().join(hash_of(b"new").to_string());
+        for path in [&old, &new] {
+            File::create(path).unwrap().set_len(half as u64).unwrap();
+        }
+        File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+
+        // Making room for another image evicts only the older one.
+        evict(&logctx.log, cache_dir.path(), 1, &counters)
+            .expect("evicted images");
+        assert!(!old.exists());
+        assert!(new.exists());
+        assert_eq!(counters.evictions.load(Ordering::Relaxed), 1);
+
+        logctx.cleanup_successful();
+    }
+}
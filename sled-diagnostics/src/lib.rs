@@ -187,6 +187,18 @@ pub async fn zpool_info()
     execute_command_with_timeout(zpool_status(), DEFAULT_TIMEOUT).await
 }
 
+/// Retrieve a selection of `kstat` output for the system.
+pub async fn kstat_info()
+-> Vec<Result<SledDiagnosticsCmdOutput, SledDiagnosticsCmdError>> {
+    kstat_selectors()
+        .map(|c| async move {
+            execute_command_with_timeout(c, DEFAULT_TIMEOUT).await
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<Result<_, _>>>()
+        .await
+}
+
 pub async fn health_check()
 -> Vec<Result<SledDiagnosticsCmdOutput, SledDiagnosticsCmdError>> {
     [
@@ -369,6 +369,24 @@ pub fn zfs_list() -> Command {
     cmd
 }
 
+/// The kstats collected into a support bundle, as `module:instance:name`
+/// selectors understood by `kstat -p`.
+const KSTAT_SELECTORS: &[&str] = &[
+    "unix:0:system_pages",
+    "unix:0:vminfo",
+    "zfs:0:arcstats",
+    "cpu::sys",
+    "link:0:",
+];
+
+pub fn kstat_selectors() -> impl Iterator<Item = Command> {
+    KSTAT_SELECTORS.iter().map(|selector| {
+        let mut cmd = std::process::Command::new(PFEXEC);
+        cmd.env_clear().arg(KSTAT).arg("-p").arg(selector);
+        cmd
+    })
+}
+
 pub fn zpool_status() -> Command {
     let mut cmd = std::process::Command::new(PFEXEC);
     cmd.env_clear().arg(ZPOOL).arg("status");
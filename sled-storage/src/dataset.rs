@@ -32,6 +32,7 @@ pub const CONFIG_DATASET: &'static str = "config";
 pub const M2_DEBUG_DATASET: &'static str = "debug";
 pub const M2_BACKING_DATASET: &'static str = "backing";
 pub const M2_ARTIFACT_DATASET: &'static str = "update";
+pub const M2_ZONE_IMAGE_CACHE_DATASET: &'static str = "zone-image-cache";
 
 pub const DEBUG_DATASET_QUOTA: ByteCount =
     if cfg!(any(test, feature = "testing")) {
@@ -52,6 +53,10 @@ pub const DUMP_DATASET_COMPRESSION: CompressionAlgorithm =
 // sizes as of Oct 2024, it would be capable of storing about 10 distinct system
 // versions.
 pub const ARTIFACT_DATASET_QUOTA: ByteCount = ByteCount::from_gibibytes_u32(20);
+// Zone images are a few hundred MiB at most, so this holds the zones of a
+// couple of system versions.
+pub const ZONE_IMAGE_CACHE_DATASET_QUOTA: ByteCount =
+    ByteCount::from_gibibytes_u32(10);
 
 // U.2 datasets live under the encrypted dataset and inherit encryption
 pub const ZONE_DATASET: &'static str = "crypt/zone";
@@ -71,7 +76,7 @@ pub const U2_EXPECTED_DATASETS: [ExpectedDataset; U2_EXPECTED_DATASET_COUNT] = [
         .compression(DUMP_DATASET_COMPRESSION),
 ];
 
-const M2_EXPECTED_DATASET_COUNT: usize = 7;
+const M2_EXPECTED_DATASET_COUNT: usize = 8;
 const M2_EXPECTED_DATASETS: [ExpectedDataset; M2_EXPECTED_DATASET_COUNT] = [
     // Stores software images.
     //
@@ -99,6 +104,10 @@ const M2_EXPECTED_DATASETS: [ExpectedDataset; M2_EXPECTED_DATASET_COUNT] = [
     // Stores software artifacts (zones, OS images, Hubris images, etc.)
     // extracted from TUF repos by Nexus.
     ExpectedDataset::new(M2_ARTIFACT_DATASET).quota(ARTIFACT_DATASET_QUOTA),
+    // Caches zone images installed from the artifact store, so that they
+    // outlive the artifact store's copies.
+    ExpectedDataset::new(M2_ZONE_IMAGE_CACHE_DATASET)
+        .quota(ZONE_IMAGE_CACHE_DATASET_QUOTA),
 ];
 
 // Helper type for describing expected datasets and their optional quota.
@@ -177,6 +186,15 @@ pub enum DatasetError {
     #[error("Failed to make datasets encrypted")]
     EncryptionMigration(#[from] DatasetEncryptionMigrationError),
 
+    #[error(
+        "Refusing to rotate key of '{dataset}' from epoch {current} \
+         back to epoch {requested}"
+    )]
+    EpochRegression { dataset: String, current: u64, requested: u64 },
+
+    #[error(transparent)]
+    RotateKey(#[from] zfs::RotateKeyError),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -368,6 +386,79 @@ pub(crate) async fn ensure_zpool_has_datasets(
     Ok(())
 }
 
+/// Rotates the key of the encryption root on the U.2 `zpool_name` to the key
+/// for `new_epoch`.
+///
+/// This is a no-op if the encryption root is already at `new_epoch`, and
+/// fails if it's at a later epoch.
+pub async fn rotate_encryption_root_key(
+    log: &Logger,
+    mount_config: &MountConfig,
+    zpool_name: &ZpoolName,
+    disk_identity: &DiskIdentity,
+    key_requester: &StorageKeyRequester,
+    new_epoch: u64,
+) -> Result<(), DatasetError> {
+    let name = format!("{}/{}", zpool_name, CRYPT_DATASET);
+    let current_epoch = Zfs::get_oxide_value(&name, "epoch")
+        .await
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .ok_or_else(|| DatasetError::CannotParseEpochProperty(name.clone()))?;
+    if current_epoch == new_epoch {
+        debug!(
+            log, "Encryption root already at requested epoch";
+            "name" => &name, "epoch" => new_epoch,
+        );
+        return Ok(());
+    }
+    if current_epoch > new_epoch {
+        return Err(DatasetError::EpochRegression {
+            dataset: name,
+            current: current_epoch,
+            requested: new_epoch,
+        });
+    }
+
+    info!(log, "Retrieving key"; "epoch"=>%new_epoch, "disk_id"=>?disk_identity);
+    let key = key_requester.get_key(new_epoch, disk_identity.clone()).await?;
+    info!(log, "Got key"; "epoch"=>%new_epoch, "disk_id"=>?disk_identity);
+
+    let keypath = Keypath::new(disk_identity, &mount_config.root);
+    let mut keyfile =
+        KeyFile::create(keypath.clone(), key.expose_secret(), log)
+            .await
+            .map_err(|error| DatasetError::IoError {
+                path: keypath.0.clone(),
+                error,
+            })?;
+
+    info!(
+        log,
+        "Rotating key of encrypted filesystem: {} from epoch {} to {}",
+        name,
+        current_epoch,
+        new_epoch,
+    );
+    let result =
+        Zfs::rotate_key(&name, &keypath, new_epoch).await.inspect_err(|err| {
+            warn!(
+                log,
+                "Failed to rotate key of encrypted root filesystem";
+                "name" => &name,
+                "err" => InlineErrorChain::new(&err),
+            );
+        });
+
+    keyfile.zero_and_unlink().await.map_err(|error| DatasetError::IoError {
+        path: keyfile.path().0.clone(),
+        error,
+    })?;
+
+    result?;
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum DatasetEncryptionMigrationError {
     #[error(transparent)]
@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Support for the break-glass operator account via wicketd.
+
+use super::rack_setup::read_and_hash_password;
+use crate::wicketd::create_wicketd_client;
+use anyhow::Context;
+use anyhow::Result;
+use clap::Subcommand;
+use slog::Logger;
+use std::net::SocketAddrV6;
+use std::time::Duration;
+use wicketd_client::types::NewPasswordHash;
+use wicketd_client::types::PostBreakGlassDisableParams;
+use wicketd_client::types::PostBreakGlassEnableParams;
+
+// Enabling the account goes through wicketd to Nexus, so allow a bit longer
+// than requests that wicketd handles itself.
+const WICKETD_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum BreakGlassArgs {
+    /// Enable the break-glass operator account
+    ///
+    /// The account can log in with the password entered here and has
+    /// read-only access to the fleet. It stops working once it expires or is
+    /// disabled.
+    Enable {
+        /// Who is enabling the account (recorded for auditing)
+        #[clap(long)]
+        enabled_by: String,
+
+        /// How long the account may be used for (e.g., "30m", "4h")
+        #[clap(
+            long,
+            value_parser = humantime::parse_duration,
+            default_value = "4h",
+        )]
+        ttl: Duration,
+    },
+
+    /// Disable the break-glass operator account and end its sessions
+    Disable {
+        /// Who is disabling the account (recorded for auditing)
+        #[clap(long)]
+        disabled_by: String,
+    },
+}
+
+impl BreakGlassArgs {
+    pub(crate) async fn exec(
+        self,
+        log: Logger,
+        wicketd_addr: SocketAddrV6,
+    ) -> Result<()> {
+        let client = create_wicketd_client(&log, wicketd_addr, WICKETD_TIMEOUT);
+
+        match self {
+            Self::Enable { enabled_by, ttl } => {
+                let ttl_secs = u32::try_from(ttl.as_secs())
+                    .context("break-glass account lifetime is too long")?;
+                let hash =
+                    read_and_hash_password(&log, "break-glass operator")?;
+                let password_hash = NewPasswordHash(hash.to_string());
+
+                slog::info!(log, "enabling break-glass account...");
+                let response = client
+                    .post_break_glass_enable(&PostBreakGlassEnableParams {
+                        enabled_by,
+                        password_hash,
+                        ttl_secs,
+                    })
+                    .await
+                    .context("failed to enable break-glass account")?
+                    .into_inner();
+                println!(
+                    "break-glass account enabled: log in to silo {:?} as \
+                     user {:?} (expires {})",
+                    response.silo_name,
+                    response.username,
+                    response.time_expires,
+                );
+            }
+            Self::Disable { disabled_by } => {
+                slog::info!(log, "disabling break-glass account...");
+                client
+                    .post_break_glass_disable(&PostBreakGlassDisableParams {
+                        disabled_by,
+                    })
+                    .await
+                    .context("failed to disable break-glass account")?;
+                println!("break-glass account disabled");
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -10,7 +10,8 @@ use anyhow::Result;
 use clap::{Args, ColorChoice, Parser, Subcommand};
 
 use super::{
-    inventory::InventoryArgs, preflight::PreflightArgs, rack_setup::SetupArgs,
+    break_glass::BreakGlassArgs, inventory::InventoryArgs,
+    preflight::PreflightArgs, rack_setup::SetupArgs,
     rack_update::RackUpdateArgs, upload::UploadArgs,
 };
 
@@ -52,6 +53,9 @@ impl ShellApp {
             ShellCommand::Inventory(args) => {
                 args.exec(log, wicketd_addr, output).await
             }
+            ShellCommand::BreakGlass(args) => {
+                args.exec(log, wicketd_addr).await
+            }
         }
     }
 }
@@ -107,4 +111,9 @@ enum ShellCommand {
     /// Enumerate rack components
     #[command(subcommand)]
     Inventory(InventoryArgs),
+
+    /// Manage the break-glass operator account, for use when operators cannot
+    /// otherwise log in.
+    #[command(subcommand)]
+    BreakGlass(BreakGlassArgs),
 }
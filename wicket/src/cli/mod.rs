@@ -10,6 +10,7 @@
 //! commands and use cases must be done via the CLI, and this module contains
 //! support for that.
 
+mod break_glass;
 mod command;
 mod inventory;
 mod preflight;
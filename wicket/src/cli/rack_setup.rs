@@ -134,7 +134,10 @@ impl SetupArgs {
                 slog::info!(log, "configuration reset");
             }
             SetupArgs::SetPassword => {
-                let hash = read_and_hash_password(&log)?;
+                let hash = read_and_hash_password(
+                    &log,
+                    "recovery user of recovery silo",
+                )?;
                 let hash = NewPasswordHash(hash.to_string());
 
                 slog::info!(log, "uploading password hash to wicketd...");
@@ -243,17 +246,21 @@ impl SetupArgs {
     }
 }
 
-fn read_and_hash_password(log: &Logger) -> Result<PasswordHashString> {
-    let pass1 = rpassword::prompt_password(
-        "Password for recovery user of recovery silo: ",
-    )
-    .context("failed to read password (do you need to use `ssh -t`?)")?;
+/// Prompts twice for the password of the user described by `whose`, and
+/// returns its hash.
+pub(super) fn read_and_hash_password(
+    log: &Logger,
+    whose: &str,
+) -> Result<PasswordHashString> {
+    let pass1 = rpassword::prompt_password(format!("Password for {whose}: "))
+        .context(
+        "failed to read password (do you need to use `ssh -t`?)",
+    )?;
     let pass1 = Zeroizing::new(pass1);
 
-    let pass2 = rpassword::prompt_password(
-        "Confirm password for recovery user of recovery silo: ",
-    )
-    .context("failed to read password confirmation")?;
+    let pass2 =
+        rpassword::prompt_password(format!("Confirm password for {whose}: "))
+            .context("failed to read password confirmation")?;
     let pass2 = Zeroizing::new(pass2);
 
     if pass1 != pass2 {
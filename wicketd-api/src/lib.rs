@@ -3,6 +3,8 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use bootstrap_agent_client::types::RackOperationStatus;
+use chrono::DateTime;
+use chrono::Utc;
 use dropshot::HttpError;
 use dropshot::HttpResponseOk;
 use dropshot::HttpResponseUpdatedNoContent;
@@ -337,6 +339,31 @@ pub trait WicketdApi {
     async fn post_reload_config(
         rqctx: RequestContext<Self::Context>,
     ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
+
+    /// Enable the break-glass operator account.
+    ///
+    /// This is for use when operators cannot otherwise log in to the rack (for
+    /// example, because the external identity provider is broken). The request
+    /// is forwarded to Nexus, which grants the account read-only access to the
+    /// fleet until it expires or is disabled, and records who enabled it.
+    #[endpoint {
+        method = POST,
+        path = "/break-glass/enable",
+    }]
+    async fn post_break_glass_enable(
+        rqctx: RequestContext<Self::Context>,
+        body: TypedBody<PostBreakGlassEnableParams>,
+    ) -> Result<HttpResponseOk<PostBreakGlassEnableResponse>, HttpError>;
+
+    /// Disable the break-glass operator account.
+    #[endpoint {
+        method = POST,
+        path = "/break-glass/disable",
+    }]
+    async fn post_break_glass_disable(
+        rqctx: RequestContext<Self::Context>,
+        body: TypedBody<PostBreakGlassDisableParams>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError>;
 }
 
 #[derive(
@@ -443,6 +470,31 @@ pub struct PutRssRecoveryUserPasswordHash {
     pub hash: omicron_passwords::NewPasswordHash,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PostBreakGlassEnableParams {
+    pub password_hash: omicron_passwords::NewPasswordHash,
+    /// How long the account may be used for, in seconds.
+    pub ttl_secs: u32,
+    /// Who is enabling the account, for the audit trail kept by Nexus.
+    pub enabled_by: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PostBreakGlassEnableResponse {
+    /// The silo to log into.
+    pub silo_name: String,
+    /// The name to log in with.
+    pub username: String,
+    /// When the account stops being usable.
+    pub time_expires: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PostBreakGlassDisableParams {
+    /// Who is disabling the account, for the audit trail kept by Nexus.
+    pub disabled_by: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct GetInventoryParams {
     /// Refresh the state of these SPs from MGS prior to returning (instead of
@@ -14,8 +14,10 @@ use anyhow::Result;
 use anyhow::anyhow;
 use anyhow::bail;
 use internal_dns_resolver::Resolver;
+use internal_dns_types::names::ServiceName;
 use sled_hardware_types::Baseboard;
 use slog::info;
+use slog::o;
 use std::net::Ipv6Addr;
 use std::net::SocketAddrV6;
 use std::sync::Arc;
@@ -44,6 +46,12 @@ pub struct ServerContext {
 }
 
 impl ServerContext {
+    /// Returns a client for the internal API of a Nexus instance found via
+    /// internal DNS.
+    pub(crate) async fn nexus_client(&self) -> Result<nexus_client::Client> {
+        nexus_client_from_resolver(&self.internal_dns_resolver, &self.log).await
+    }
+
     pub(crate) fn bootstrap_agent_addr(&self) -> Result<SocketAddrV6> {
         // Port on which the bootstrap agent dropshot server within sled-agent
         // is listening.
@@ -117,3 +125,22 @@ impl ServerContext {
         }
     }
 }
+
+/// Returns a client for the internal API of a Nexus instance found via
+/// `resolver`, if we have one.
+pub(crate) async fn nexus_client_from_resolver(
+    resolver: &Mutex<Option<Resolver>>,
+    log: &slog::Logger,
+) -> Result<nexus_client::Client> {
+    let Some(resolver) = resolver.lock().unwrap().clone() else {
+        bail!("no internal DNS resolver available (rack subnet unknown?)");
+    };
+    let nexus_addr = resolver
+        .lookup_socket_v6(ServiceName::Nexus)
+        .await
+        .map_err(|err| anyhow!("failed to look up Nexus address: {err}"))?;
+    Ok(nexus_client::Client::new(
+        &format!("http://{nexus_addr}"),
+        log.new(o!("component" => "NexusClient")),
+    ))
+}
@@ -914,6 +914,70 @@ impl WicketdApi for WicketdApiImpl {
 
         Ok(HttpResponseUpdatedNoContent())
     }
+
+    async fn post_break_glass_enable(
+        rqctx: RequestContext<ServerContext>,
+        body: TypedBody<PostBreakGlassEnableParams>,
+    ) -> Result<HttpResponseOk<PostBreakGlassEnableResponse>, HttpError> {
+        let ctx = rqctx.context();
+        let params = body.into_inner();
+
+        let client = ctx
+            .nexus_client()
+            .await
+            .map_err(|err| HttpError::for_unavail(None, format!("{err:#}")))?;
+
+        slog::warn!(
+            ctx.log,
+            "enabling break-glass account";
+            "enabled_by" => &params.enabled_by,
+            "ttl_secs" => params.ttl_secs,
+        );
+        let account = client
+            .break_glass_enable(&nexus_client::types::BreakGlassEnableRequest {
+                enabled_by: params.enabled_by,
+                password_hash: params.password_hash,
+                ttl_secs: params.ttl_secs,
+            })
+            .await
+            .map_err(http_error_from_nexus_client_error)?
+            .into_inner();
+
+        Ok(HttpResponseOk(PostBreakGlassEnableResponse {
+            silo_name: account.silo_name.to_string(),
+            username: account.username,
+            time_expires: account.time_expires,
+        }))
+    }
+
+    async fn post_break_glass_disable(
+        rqctx: RequestContext<ServerContext>,
+        body: TypedBody<PostBreakGlassDisableParams>,
+    ) -> Result<HttpResponseUpdatedNoContent, HttpError> {
+        let ctx = rqctx.context();
+        let params = body.into_inner();
+
+        let client = ctx
+            .nexus_client()
+            .await
+            .map_err(|err| HttpError::for_unavail(None, format!("{err:#}")))?;
+
+        slog::warn!(
+            ctx.log,
+            "disabling break-glass account";
+            "disabled_by" => &params.disabled_by,
+        );
+        client
+            .break_glass_disable(
+                &nexus_client::types::BreakGlassDisableRequest {
+                    disabled_by: params.disabled_by,
+                },
+            )
+            .await
+            .map_err(http_error_from_nexus_client_error)?;
+
+        Ok(HttpResponseUpdatedNoContent())
+    }
 }
 
 // Get the current inventory or return a 503 Unavailable.
@@ -976,3 +1040,24 @@ fn http_error_from_client_error(
         headers: None,
     }
 }
+
+fn http_error_from_nexus_client_error(
+    err: nexus_client::Error<nexus_client::types::Error>,
+) -> HttpError {
+    // Errors without a status code generally mean we couldn't get a response
+    // from Nexus at all.
+    let status_code = err
+        .status()
+        .map(|status| status.try_into().expect("status code must be an error"))
+        .unwrap_or(dropshot::ErrorStatusCode::SERVICE_UNAVAILABLE);
+
+    let message = format!("request to Nexus failed: {err}");
+
+    HttpError {
+        status_code,
+        error_code: None,
+        external_message: message.clone(),
+        internal_message: message,
+        headers: None,
+    }
+}
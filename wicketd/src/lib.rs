@@ -10,6 +10,7 @@ mod helpers;
 mod http_entrypoints;
 mod installinator_progress;
 pub mod mgs;
+mod nexus_progress;
 mod nexus_proxy;
 mod preflight_check;
 mod rss_config;
@@ -30,6 +31,7 @@ pub use installinator_progress::{IprUpdateTracker, RunningUpdateState};
 use internal_dns_resolver::Resolver;
 use mgs::make_mgs_client;
 pub(crate) use mgs::{MgsHandle, MgsManager};
+use nexus_progress::NexusProgressReporter;
 use nexus_proxy::NexusTcpProxy;
 use omicron_common::FileKv;
 use omicron_common::address::{AZ_PREFIX, Ipv6Subnet};
@@ -176,6 +178,15 @@ impl Server {
             .transpose()?;
 
         let internal_dns_resolver = Arc::new(Mutex::new(internal_dns_resolver));
+        tokio::spawn(
+            NexusProgressReporter::new(
+                update_tracker.clone(),
+                Arc::clone(&internal_dns_resolver),
+                &log,
+            )
+            .run(),
+        );
+
         let nexus_tcp_proxy = NexusTcpProxy::start(
             args.nexus_proxy_address,
             Arc::clone(&internal_dns_resolver),
@@ -0,0 +1,258 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Reporting the progress of wicketd-driven updates to Nexus.
+//!
+//! Updates started from the technician port don't go through the control
+//! plane's update system, so without this, remote operators have no way to
+//! see that they're happening (or that they failed). While any updates are
+//! tracked, we periodically summarize each SP's update into per-component
+//! progress and send that to Nexus.
+//!
+//! Reporting is best-effort: Nexus may well be unavailable while we're
+//! updating the rack, and we never want that to interfere with the update
+//! itself.
+
+use crate::context::nexus_client_from_resolver;
+use crate::update_tracker::UpdateTracker;
+use display_error_chain::DisplayErrorChain;
+use internal_dns_resolver::Resolver;
+use nexus_client::types::ServiceProcessorType;
+use nexus_client::types::TechnicianPortUpdateProgress;
+use nexus_client::types::TechnicianPortUpdateReport;
+use nexus_client::types::UpdateComponentKind;
+use nexus_client::types::UpdateComponentProgress;
+use nexus_client::types::UpdateComponentState;
+use slog::Logger;
+use slog::debug;
+use slog::o;
+use slog::warn;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use update_engine::StepStatus;
+use update_engine::events::StepInfo;
+use wicket_common::inventory::SpIdentifier;
+use wicket_common::inventory::SpType;
+use wicket_common::update_events::EventBuffer;
+use wicket_common::update_events::UpdateComponent;
+use wicket_common::update_events::WicketdEngineSpec;
+
+/// How often we send progress to Nexus while updates are tracked
+const REPORT_INTERVAL: Duration = Duration::from_secs(15);
+
+pub(crate) struct NexusProgressReporter {
+    update_tracker: Arc<UpdateTracker>,
+    internal_dns_resolver: Arc<Mutex<Option<Resolver>>>,
+    log: Logger,
+}
+
+impl NexusProgressReporter {
+    pub(crate) fn new(
+        update_tracker: Arc<UpdateTracker>,
+        internal_dns_resolver: Arc<Mutex<Option<Resolver>>>,
+        log: &Logger,
+    ) -> Self {
+        let log = log.new(o!("component" => "NexusProgressReporter"));
+        Self { update_tracker, internal_dns_resolver, log }
+    }
+
+    pub(crate) async fn run(self) {
+        let mut interval = tokio::time::interval(REPORT_INTERVAL);
+        interval
+            .set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        // The last report Nexus accepted; we don't resend a report unless
+        // something has changed since.
+        let mut last_reported = None;
+        loop {
+            interval.tick().await;
+
+            let updates = self.update_tracker.nexus_progress().await;
+            if updates.is_empty() || last_reported.as_ref() == Some(&updates) {
+                continue;
+            }
+
+            let client = match nexus_client_from_resolver(
+                &self.internal_dns_resolver,
+                &self.log,
+            )
+            .await
+            {
+                Ok(client) => client,
+                Err(err) => {
+                    debug!(
+                        self.log,
+                        "not reporting update progress: Nexus unavailable";
+                        "err" => #%err,
+                    );
+                    continue;
+                }
+            };
+
+            let report =
+                TechnicianPortUpdateReport { updates: updates.clone() };
+            match client.technician_port_update_report(&report).await {
+                Ok(_) => {
+                    debug!(
+                        self.log, "reported update progress to Nexus";
+                        "updates" => updates.len(),
+                    );
+                    last_reported = Some(updates);
+                }
+                Err(err) => {
+                    warn!(
+                        self.log, "failed to report update progress to Nexus";
+                        "err" => %DisplayErrorChain::new(&err),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Summarizes the update of a single SP into the per-component progress we
+/// report to Nexus.
+///
+/// Returns `None` if the update hasn't started yet.
+pub(crate) fn summarize_update(
+    sp: SpIdentifier,
+    buffer: &EventBuffer,
+) -> Option<TechnicianPortUpdateProgress> {
+    let execution_id = buffer.root_execution_id()?;
+
+    let mut components: BTreeMap<UpdateComponent, ComponentSummary> =
+        BTreeMap::new();
+    for (_, data) in buffer.steps().as_slice() {
+        // Nested steps belong to the same component as their parent, so we
+        // only need to look at top-level steps.
+        if data.nest_level() != 0 {
+            continue;
+        }
+        let Ok(info) = StepInfo::<WicketdEngineSpec>::from_generic(
+            data.step_info().clone(),
+        ) else {
+            continue;
+        };
+        components
+            .entry(info.component)
+            .or_insert_with(|| ComponentSummary::new(&info))
+            .add_step(&info, data.step_status());
+    }
+
+    Some(TechnicianPortUpdateProgress {
+        id: execution_id.0,
+        sp_type: match sp.type_ {
+            SpType::Sled => ServiceProcessorType::Sled,
+            SpType::Switch => ServiceProcessorType::Switch,
+            SpType::Power => ServiceProcessorType::Power,
+        },
+        sp_slot: sp.slot,
+        components: components
+            .into_iter()
+            .map(|(component, summary)| summary.into_progress(component))
+            .collect(),
+    })
+}
+
+struct ComponentSummary {
+    steps_completed: u32,
+    total_steps: u32,
+    running: Option<String>,
+    failed: Option<String>,
+    aborted: Option<String>,
+    last_step_skipped: bool,
+}
+
+impl ComponentSummary {
+    fn new(info: &StepInfo<WicketdEngineSpec>) -> Self {
+        Self {
+            steps_completed: 0,
+            total_steps: info.total_component_steps.try_into().unwrap_or(0),
+            running: None,
+            failed: None,
+            aborted: None,
+            last_step_skipped: false,
+        }
+    }
+
+    fn add_step(
+        &mut self,
+        info: &StepInfo<WicketdEngineSpec>,
+        status: &StepStatus<WicketdEngineSpec>,
+    ) {
+        match status {
+            StepStatus::NotStarted | StepStatus::WillNotBeRun { .. } => {}
+            StepStatus::Running { .. } => {
+                self.running = Some(info.description.to_string());
+            }
+            StepStatus::Completed { reason } => {
+                self.steps_completed += 1;
+                if info.is_last_step_in_component() {
+                    self.last_step_skipped = reason
+                        .step_completed_info()
+                        .is_some_and(|info| info.outcome.is_skipped());
+                }
+            }
+            StepStatus::Failed { reason } => {
+                self.failed = Some(match reason.step_failed_info() {
+                    Some(failure) => {
+                        format!("{}: {}", info.description, failure.message)
+                    }
+                    None => info.description.to_string(),
+                });
+            }
+            StepStatus::Aborted { reason, .. } => {
+                self.aborted = Some(match reason.step_aborted_info() {
+                    Some(abort) => {
+                        format!("{}: {}", info.description, abort.message)
+                    }
+                    None => info.description.to_string(),
+                });
+            }
+        }
+    }
+
+    fn into_progress(
+        self,
+        component: UpdateComponent,
+    ) -> UpdateComponentProgress {
+        let (state, current_step, message) = if let Some(message) = self.failed
+        {
+            (UpdateComponentState::Failed, None, Some(message))
+        } else if let Some(message) = self.aborted {
+            (UpdateComponentState::Aborted, None, Some(message))
+        } else if self.running.is_some() {
+            (UpdateComponentState::Updating, self.running, None)
+        } else if self.steps_completed == self.total_steps {
+            if self.last_step_skipped {
+                (UpdateComponentState::Skipped, None, None)
+            } else {
+                (UpdateComponentState::Updated, None, None)
+            }
+        } else if self.steps_completed > 0 {
+            // Between two steps of this component.
+            (UpdateComponentState::Updating, None, None)
+        } else {
+            (UpdateComponentState::Waiting, None, None)
+        };
+
+        UpdateComponentProgress {
+            component: match component {
+                UpdateComponent::RotBootloader => {
+                    UpdateComponentKind::RotBootloader
+                }
+                UpdateComponent::Rot => UpdateComponentKind::Rot,
+                UpdateComponent::Sp => UpdateComponentKind::Sp,
+                UpdateComponent::Host => UpdateComponentKind::Host,
+            },
+            state,
+            steps_completed: self.steps_completed,
+            total_steps: self.total_steps,
+            current_step,
+            message,
+        }
+    }
+}
@@ -404,6 +404,22 @@ impl UpdateTracker {
         }
     }
 
+    /// Summarizes the progress of each tracked update, for reporting to
+    /// Nexus.
+    pub(crate) async fn nexus_progress(
+        &self,
+    ) -> Vec<nexus_client::types::TechnicianPortUpdateProgress> {
+        let update_data = self.sp_update_data.lock().await;
+        update_data
+            .sp_update_data
+            .iter()
+            .filter_map(|(sp, update_data)| {
+                let event_buffer = update_data.event_buffer.lock().unwrap();
+                crate::nexus_progress::summarize_update(*sp, &event_buffer)
+            })
+            .collect()
+    }
+
     pub(crate) async fn event_report(&self, sp: SpIdentifier) -> EventReport {
         let mut update_data = self.sp_update_data.lock().await;
         match update_data.sp_update_data.entry(sp) {